@@ -0,0 +1,364 @@
+//! Instance Activity Timeline
+//!
+//! Records lifecycle events per instance (started, stopped, crashed,
+//! restarted, version changed, config edited, seeded) so "what changed last
+//! night?" has an answer. Events live in `activity/<instance-id>.jsonl` in
+//! the app directory, one JSON object per line, oldest first, capped at
+//! `MAX_EVENTS` per instance.
+//!
+//! Recording is best-effort: a timeline that can't be written never fails the
+//! action it describes.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::config::{get_app_dir, Instance};
+use crate::events::{self, Event};
+
+/// Events kept per instance
+const MAX_EVENTS: usize = 500;
+
+/// A start this soon after a stop or crash is recorded as a restart
+const RESTART_WINDOW: TimeDelta = TimeDelta::seconds(15);
+
+/// What happened to an instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Started,
+    Stopped,
+    /// The process exited without being stopped
+    Crashed,
+    Restarted,
+    VersionChanged,
+    ConfigEdited,
+    /// The database seed ran
+    Seeded,
+    SeedFailed,
+}
+
+impl ActivityKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ActivityKind::Started => "Started",
+            ActivityKind::Stopped => "Stopped",
+            ActivityKind::Crashed => "Crashed",
+            ActivityKind::Restarted => "Restarted",
+            ActivityKind::VersionChanged => "Version changed",
+            ActivityKind::ConfigEdited => "Config edited",
+            ActivityKind::Seeded => "Seeded",
+            ActivityKind::SeedFailed => "Seed failed",
+        }
+    }
+}
+
+/// One entry in an instance's timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub at: DateTime<Utc>,
+    pub kind: ActivityKind,
+    /// E.g. the version started, "8.0.36 -> 8.4.2", or the edited settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+fn activity_dir() -> Result<PathBuf, String> {
+    Ok(get_app_dir()?.join("activity"))
+}
+
+fn activity_path(id: &Uuid) -> Result<PathBuf, String> {
+    Ok(activity_dir()?.join(format!("{}.jsonl", id)))
+}
+
+fn load_from(path: &Path) -> Vec<ActivityEvent> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn write_to(path: &Path, events: &[ActivityEvent]) -> Result<(), String> {
+    let mut content = String::new();
+    for event in events {
+        let line = serde_json::to_string(event)
+            .map_err(|e| format!("Failed to serialize activity: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write activity: {}", e))
+}
+
+/// Add an event to a timeline file
+///
+/// Merges events that describe one thing: a quick stop/start becomes a
+/// restart, and a crash already on record isn't recorded twice.
+fn record_in(path: &Path, event: ActivityEvent) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create activity directory: {}", e))?;
+    }
+
+    let mut events = load_from(path);
+    let last = events.last().cloned();
+    let recent = |e: &ActivityEvent| event.at - e.at <= RESTART_WINDOW;
+
+    match (last, event.kind) {
+        (Some(last), ActivityKind::Crashed) if last.kind == ActivityKind::Crashed => {
+            return Ok(());
+        }
+        (Some(last), ActivityKind::Started)
+            if last.kind == ActivityKind::Stopped && recent(&last) =>
+        {
+            events.pop();
+            events.push(ActivityEvent {
+                kind: ActivityKind::Restarted,
+                ..event
+            });
+        }
+        (Some(last), ActivityKind::Started)
+            if last.kind == ActivityKind::Crashed && recent(&last) =>
+        {
+            events.push(ActivityEvent {
+                kind: ActivityKind::Restarted,
+                ..event
+            });
+        }
+        _ => {
+            // Appending is enough until the cap is reached
+            if events.len() < MAX_EVENTS {
+                let line = serde_json::to_string(&event)
+                    .map_err(|e| format!("Failed to serialize activity: {}", e))?;
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| format!("Failed to open activity: {}", e))?;
+                return writeln!(file, "{}", line)
+                    .map_err(|e| format!("Failed to write activity: {}", e));
+            }
+            events.push(event);
+        }
+    }
+
+    let excess = events.len().saturating_sub(MAX_EVENTS);
+    events.drain(..excess);
+    write_to(path, &events)
+}
+
+/// Record an event for an instance now
+///
+/// The event is also published to the event stream.
+pub fn record(id: &Uuid, kind: ActivityKind, detail: Option<String>) {
+    events::publish(Event::Instance {
+        instance_id: *id,
+        kind,
+        detail: detail.clone(),
+    });
+    let event = ActivityEvent {
+        at: Utc::now(),
+        kind,
+        detail,
+    };
+    if let Ok(path) = activity_path(id) {
+        if let Err(e) = record_in(&path, event) {
+            eprintln!("Failed to record instance activity: {}", e);
+        }
+    }
+}
+
+/// Describe the changes between two versions of an instance
+fn changes(before: &Instance, after: &Instance) -> Vec<(ActivityKind, String)> {
+    let mut changes = Vec::new();
+
+    if before.version != after.version {
+        changes.push((
+            ActivityKind::VersionChanged,
+            format!("{} -> {}", before.version, after.version),
+        ));
+    }
+
+    let mut edited = Vec::new();
+    if before.port != after.port {
+        edited.push(format!("port {} -> {}", before.port, after.port));
+    }
+    if before.config != after.config {
+        let empty = serde_json::Map::new();
+        let old = before.config.as_object().unwrap_or(&empty);
+        let new = after.config.as_object().unwrap_or(&empty);
+        let mut keys: Vec<&String> = old
+            .keys()
+            .chain(new.keys())
+            .filter(|key| old.get(*key) != new.get(*key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        if keys.is_empty() {
+            edited.push("config".to_string());
+        } else {
+            edited.extend(keys.into_iter().cloned());
+        }
+    }
+    if before.extra_args != after.extra_args {
+        edited.push("start arguments".to_string());
+    }
+    if before.seed != after.seed {
+        edited.push("seed".to_string());
+    }
+    if !edited.is_empty() {
+        changes.push((ActivityKind::ConfigEdited, edited.join(", ")));
+    }
+
+    changes
+}
+
+/// Record version and config changes made to an instance
+pub fn record_changes(before: &Instance, after: &Instance) {
+    for (kind, detail) in changes(before, after) {
+        record(&after.id, kind, Some(detail));
+    }
+}
+
+/// An instance's timeline, newest first (at most `limit` events)
+pub fn load(id: &Uuid, limit: usize) -> Result<Vec<ActivityEvent>, String> {
+    let mut events = load_from(&activity_path(id)?);
+    events.reverse();
+    events.truncate(limit);
+    Ok(events)
+}
+
+/// Delete an instance's timeline
+pub fn delete(id: &Uuid) {
+    if let Ok(path) = activity_path(id) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    fn event(at: DateTime<Utc>, kind: ActivityKind) -> ActivityEvent {
+        ActivityEvent {
+            at,
+            kind,
+            detail: None,
+        }
+    }
+
+    fn kinds(path: &Path) -> Vec<ActivityKind> {
+        load_from(path).into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn test_quick_stop_start_is_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.jsonl");
+        let t = Utc::now();
+
+        record_in(&path, event(t, ActivityKind::Started)).unwrap();
+        record_in(&path, event(t + TimeDelta::hours(1), ActivityKind::Stopped)).unwrap();
+        record_in(
+            &path,
+            event(
+                t + TimeDelta::hours(1) + TimeDelta::seconds(1),
+                ActivityKind::Started,
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            kinds(&path),
+            vec![ActivityKind::Started, ActivityKind::Restarted]
+        );
+
+        // A start long after a stop stays a start
+        record_in(&path, event(t + TimeDelta::hours(2), ActivityKind::Stopped)).unwrap();
+        record_in(&path, event(t + TimeDelta::hours(3), ActivityKind::Started)).unwrap();
+        assert_eq!(
+            kinds(&path),
+            vec![
+                ActivityKind::Started,
+                ActivityKind::Restarted,
+                ActivityKind::Stopped,
+                ActivityKind::Started
+            ]
+        );
+    }
+
+    #[test]
+    fn test_crash_is_recorded_once_then_restarted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.jsonl");
+        let t = Utc::now();
+
+        record_in(&path, event(t, ActivityKind::Crashed)).unwrap();
+        record_in(
+            &path,
+            event(t + TimeDelta::seconds(1), ActivityKind::Crashed),
+        )
+        .unwrap();
+        record_in(
+            &path,
+            event(t + TimeDelta::seconds(2), ActivityKind::Started),
+        )
+        .unwrap();
+        assert_eq!(
+            kinds(&path),
+            vec![ActivityKind::Crashed, ActivityKind::Restarted]
+        );
+    }
+
+    #[test]
+    fn test_timeline_is_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.jsonl");
+        let t = Utc::now();
+
+        for i in 0..(MAX_EVENTS as i64 + 5) {
+            record_in(
+                &path,
+                event(t + TimeDelta::minutes(i), ActivityKind::ConfigEdited),
+            )
+            .unwrap();
+        }
+        let events = load_from(&path);
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events[0].at, t + TimeDelta::minutes(5));
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = InstanceBuilder::new()
+            .version("8.0.36")
+            .config(serde_json::json!({ "max_connections": 100, "sql_mode": "" }))
+            .build();
+        let mut after = before.clone();
+        assert!(changes(&before, &after).is_empty());
+
+        after.version = "8.4.2".to_string();
+        after.port += 1;
+        after.config = serde_json::json!({ "max_connections": 200, "sql_mode": "" });
+        let changes = changes(&before, &after);
+        assert_eq!(
+            changes[0],
+            (ActivityKind::VersionChanged, "8.0.36 -> 8.4.2".to_string())
+        );
+        assert_eq!(changes[1].0, ActivityKind::ConfigEdited);
+        assert_eq!(
+            changes[1].1,
+            format!("port {} -> {}, max_connections", before.port, after.port)
+        );
+
+        let mut with_args = before.clone();
+        with_args.extra_args = vec!["--max_allowed_packet=64M".to_string()];
+        assert_eq!(
+            super::changes(&before, &with_args),
+            vec![(ActivityKind::ConfigEdited, "start arguments".to_string())]
+        );
+    }
+}
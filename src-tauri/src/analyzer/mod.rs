@@ -6,18 +6,23 @@
 pub mod detector;
 pub mod parsers;
 pub mod types;
+pub mod wordpress;
 
 pub use detector::{detect_project_type, get_document_root};
 pub use parsers::{
-    extract_cache_config, extract_database_config, extract_mail_config, extract_php_version,
-    extract_search_config, parse_composer_json, parse_env_file, parse_wp_config, update_env_value,
+    apply_env_edits, extract_cache_config, extract_database_config, extract_mail_config,
+    extract_php_version, extract_search_config, parse_composer_json, parse_env_file,
+    parse_wp_config, update_env_value,
 };
 pub use types::{
-    CacheConfig, ComposerInfo, DatabaseConfig, IssueSeverity, MailConfig, ProjectInfo,
-    ProjectIssue, ProjectType, SearchConfig,
+    CacheConfig, ComposerInfo, DatabaseConfig, EnvEdit, EnvFixResult, IssueSeverity, MailConfig,
+    ProjectInfo, ProjectIssue, ProjectType, SearchConfig,
 };
+pub use wordpress::{WpFix, WpFixAction};
 
 use crate::config::{Config, Instance, ServiceType};
+use crate::mail_routing;
+use crate::services::mailpit::MailpitService;
 use std::path::Path;
 
 /// Analyze a project directory
@@ -171,7 +176,7 @@ pub fn analyze_with_burd_config(path: &Path, config: &Config) -> Result<ProjectI
 
     // Check mail configuration
     if let Some(ref mail) = mail {
-        check_mail_config(&mut info, mail, config);
+        check_mail_config(&mut info, mail, path, config);
     }
 
     // Check search configuration
@@ -185,9 +190,35 @@ pub fn analyze_with_burd_config(path: &Path, config: &Config) -> Result<ProjectI
     // Check document root configuration
     check_document_root_config(&mut info, path, config);
 
+    // Check WordPress cache and search plugin configuration
+    check_wordpress_plugins(&mut info, path, config);
+
     Ok(info)
 }
 
+/// Apply the .env edits attached to `issues` in one write
+///
+/// Issues without edits are skipped. The previous .env is kept as
+/// `.env.backup`; when there's nothing to apply, .env isn't touched.
+pub fn apply_env_fixes(path: &Path, issues: &[ProjectIssue]) -> Result<EnvFixResult, String> {
+    let edits: Vec<EnvEdit> = issues
+        .iter()
+        .flat_map(|issue| issue.env_edits.iter().cloned())
+        .collect();
+    if edits.is_empty() {
+        return Ok(EnvFixResult {
+            applied: edits,
+            backup: None,
+        });
+    }
+
+    let backup = apply_env_edits(path, &edits)?;
+    Ok(EnvFixResult {
+        applied: edits,
+        backup: Some(backup),
+    })
+}
+
 /// Check database configuration against Burd instances
 fn check_database_config(info: &mut ProjectInfo, db: &DatabaseConfig, config: &Config) {
     // Find Burd database instances
@@ -228,20 +259,33 @@ fn check_database_config(info: &mut ProjectInfo, db: &DatabaseConfig, config: &C
         };
 
         if let Some(inst) = suggested_instance {
-            info.add_issue(
-                ProjectIssue::warning(
-                    "database",
-                    format!(
-                        "Database port {} doesn't match Burd's {:?} on port {}",
-                        db.port, inst.service_type, inst.port
-                    ),
-                )
-                .with_suggestion(format!("Update DB_PORT to {} in .env", inst.port)),
-            );
+            let mut issue = ProjectIssue::warning(
+                "database",
+                format!(
+                    "Database port {} doesn't match Burd's {:?} on port {}",
+                    db.port, inst.service_type, inst.port
+                ),
+            )
+            .with_suggestion(format!("Update DB_PORT to {} in .env", inst.port));
+            // Bedrock prefers DATABASE_URL, which DB_PORT doesn't override
+            if !uses_database_url(info) {
+                issue = issue.with_env_edit("DB_PORT", inst.port.to_string());
+            }
+            info.add_issue(issue);
         }
     }
 }
 
+/// Whether the project's database settings come from DATABASE_URL
+fn uses_database_url(info: &ProjectInfo) -> bool {
+    info.project_type == ProjectType::Bedrock
+        && info
+            .env_file
+            .as_deref()
+            .and_then(parse_env_file)
+            .is_some_and(|env| env.contains_key("DATABASE_URL"))
+}
+
 /// Check cache configuration against Burd instances
 fn check_cache_config(info: &mut ProjectInfo, cache: &CacheConfig, config: &Config) {
     if !cache.is_redis() {
@@ -266,7 +310,8 @@ fn check_cache_config(info: &mut ProjectInfo, cache: &CacheConfig, config: &Conf
                                 port, inst.port
                             ),
                         )
-                        .with_suggestion(format!("Update REDIS_PORT to {} in .env", inst.port)),
+                        .with_suggestion(format!("Update REDIS_PORT to {} in .env", inst.port))
+                        .with_env_edit("REDIS_PORT", inst.port.to_string()),
                     );
                 }
             }
@@ -281,25 +326,40 @@ fn check_cache_config(info: &mut ProjectInfo, cache: &CacheConfig, config: &Conf
 }
 
 /// Check mail configuration against Burd instances
-fn check_mail_config(info: &mut ProjectInfo, mail: &MailConfig, config: &Config) {
+fn check_mail_config(info: &mut ProjectInfo, mail: &MailConfig, path: &Path, config: &Config) {
+    // A routed project gets the route's complete MAIL_* settings
+    if let Some(destination) = mail_routing::routed_destination(config, path) {
+        let env = parse_env_file(&path.join(".env")).unwrap_or_default();
+        let changes = destination.env_changes(&env);
+        if !changes.is_empty() {
+            let keys: Vec<&str> = changes.iter().map(|c| c.key).collect();
+            let mut issue = ProjectIssue::warning(
+                "mail",
+                format!(
+                    "Mail for this project is routed to {}, but .env doesn't match",
+                    destination.describe()
+                ),
+            )
+            .with_suggestion(format!("Update {} in .env", keys.join(", ")));
+            for change in changes {
+                issue = issue.with_env_edit(change.key, change.suggested);
+            }
+            info.add_issue(issue);
+        }
+        return;
+    }
+
     if mail.mailer != "smtp" {
         return; // Only check SMTP config
     }
 
-    // Find Burd Mailpit instance
-    let mailpit_instance = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit);
+    // Find the Burd Mailpit instance this project should send mail to
+    let mailpit_instance = MailpitService::for_project(config, path);
 
     match mailpit_instance {
         Some(inst) => {
             // Mailpit SMTP is typically on port 1025, web UI on inst.port
-            let smtp_port = inst
-                .config
-                .get("smtp_port")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(1025) as u16;
+            let smtp_port = MailpitService::smtp_port(inst);
 
             if mail.port != smtp_port {
                 info.add_issue(
@@ -313,7 +373,9 @@ fn check_mail_config(info: &mut ProjectInfo, mail: &MailConfig, config: &Config)
                     .with_suggestion(format!(
                         "Update MAIL_HOST=127.0.0.1 and MAIL_PORT={} in .env",
                         smtp_port
-                    )),
+                    ))
+                    .with_env_edit("MAIL_HOST", "127.0.0.1")
+                    .with_env_edit("MAIL_PORT", smtp_port.to_string()),
                 );
             }
         }
@@ -417,13 +479,14 @@ fn check_app_url_config(info: &mut ProjectInfo, path: &Path, config: &Config) {
 
     if let Some(domain) = domain {
         let expected_url = if domain.ssl_enabled {
-            format!("https://{}.{}", domain.subdomain, config.tld)
+            format!("https://{}", domain.full_domain(&config.tld))
         } else if config.proxy_installed {
-            format!("http://{}.{}", domain.subdomain, config.tld)
+            format!("http://{}", domain.full_domain(&config.tld))
         } else {
             format!(
-                "http://{}.{}:{}",
-                domain.subdomain, config.tld, config.proxy_port
+                "http://{}:{}",
+                domain.full_domain(&config.tld),
+                config.proxy_port
             )
         };
 
@@ -443,7 +506,8 @@ fn check_app_url_config(info: &mut ProjectInfo, path: &Path, config: &Config) {
                 .with_suggestion(format!(
                     "Update {} to {} in .env",
                     url_var_name, expected_url
-                )),
+                ))
+                .with_env_edit(url_var_name, expected_url),
             );
         }
     } else {
@@ -510,6 +574,53 @@ fn check_document_root_config(info: &mut ProjectInfo, path: &Path, config: &Conf
     }
 }
 
+/// Check WordPress cache and search plugins against Burd instances
+fn check_wordpress_plugins(info: &mut ProjectInfo, path: &Path, config: &Config) {
+    if !matches!(
+        info.project_type,
+        ProjectType::WordPress | ProjectType::Bedrock
+    ) {
+        return;
+    }
+
+    let fixes = wordpress::plan_fixes(&info.project_type, path, config);
+    for (category, plugin) in [
+        ("cache", "Redis object cache"),
+        ("search", "Meilisearch plugin"),
+    ] {
+        let count = fixes.iter().filter(|f| f.category == category).count();
+        if count > 0 {
+            info.add_issue(
+                ProjectIssue::warning(
+                    category,
+                    format!(
+                        "{} isn't configured for Burd ({} change(s) needed)",
+                        plugin, count
+                    ),
+                )
+                .with_suggestion("Run 'burd env fix' to configure it"),
+            );
+        }
+    }
+
+    let has_redis = config
+        .instances
+        .iter()
+        .any(|i| i.service_type == ServiceType::Redis);
+    if has_redis && !wordpress::has_redis_plugin(&info.project_type, path) {
+        info.add_issue(
+            ProjectIssue::info(
+                "cache",
+                "Burd has Redis but the Redis Object Cache plugin isn't installed",
+            )
+            .with_suggestion(format!(
+                "Run 'burd wp plugin install {} --activate', then 'burd env fix'",
+                wordpress::REDIS_PLUGIN
+            )),
+        );
+    }
+}
+
 /// Find a database instance in Burd config that matches the connection type
 pub fn find_matching_db_instance<'a>(
     config: &'a Config,
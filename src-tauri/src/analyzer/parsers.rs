@@ -3,11 +3,11 @@
 //! Parsers for various config file formats used by PHP projects.
 
 use super::types::{
-    CacheConfig, ComposerInfo, DatabaseConfig, MailConfig, ProjectType, SearchConfig,
+    CacheConfig, ComposerInfo, DatabaseConfig, EnvEdit, MailConfig, ProjectType, SearchConfig,
 };
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Parse a .env file into a HashMap
 ///
@@ -487,6 +487,40 @@ pub fn update_env_value(path: &Path, key: &str, value: &str) -> Result<(), Strin
     Ok(())
 }
 
+/// Apply several edits to a project's .env in a single write
+///
+/// The previous file is copied to `.env.backup` (which Laravel's default
+/// .gitignore already covers), and the new content replaces .env through a
+/// rename, so a failure never leaves it half-written. Returns the backup's
+/// path.
+pub fn apply_env_edits(project: &Path, edits: &[EnvEdit]) -> Result<PathBuf, String> {
+    let env_path = project.join(".env");
+    let content =
+        fs::read_to_string(&env_path).map_err(|e| format!("Failed to read .env file: {}", e))?;
+
+    let mut new_content = edits.iter().fold(content.clone(), |acc, edit| {
+        update_env_content(&acc, &edit.key, &edit.value)
+    });
+    if content.ends_with('\n') && !new_content.ends_with('\n') {
+        new_content.push('\n');
+    }
+
+    // fs::copy keeps the permissions, which matter for a file holding secrets
+    let backup_path = project.join(".env.backup");
+    fs::copy(&env_path, &backup_path).map_err(|e| format!("Failed to back up .env file: {}", e))?;
+
+    let temp_path = project.join(".env.burd-tmp");
+    fs::copy(&env_path, &temp_path)
+        .and_then(|_| fs::write(&temp_path, new_content))
+        .and_then(|_| fs::rename(&temp_path, &env_path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to write .env file: {}", e)
+        })?;
+
+    Ok(backup_path)
+}
+
 /// Update a key-value pair in .env content
 fn update_env_content(content: &str, key: &str, value: &str) -> String {
     let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
@@ -593,4 +627,30 @@ define('DB_HOST', 'localhost:3307');
         let updated = update_env_content(content, "NEW_KEY", "value");
         assert!(updated.contains("NEW_KEY=value"));
     }
+
+    #[test]
+    fn test_apply_env_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = "APP_NAME=MyApp\nDB_PORT=3306\nREDIS_PORT=6379\n";
+        fs::write(dir.path().join(".env"), original).unwrap();
+
+        let edits = vec![
+            EnvEdit {
+                key: "DB_PORT".to_string(),
+                value: "3330".to_string(),
+            },
+            EnvEdit {
+                key: "MAIL_PORT".to_string(),
+                value: "1025".to_string(),
+            },
+        ];
+        let backup = apply_env_edits(dir.path(), &edits).unwrap();
+
+        assert_eq!(fs::read_to_string(backup).unwrap(), original);
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".env")).unwrap(),
+            "APP_NAME=MyApp\nDB_PORT=3330\nREDIS_PORT=6379\nMAIL_PORT=1025\n"
+        );
+        assert!(!dir.path().join(".env.burd-tmp").exists());
+    }
 }
@@ -2,6 +2,7 @@
 //!
 //! Data structures for representing analyzed project information.
 
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::path::PathBuf;
 
@@ -197,7 +198,8 @@ impl SearchConfig {
 }
 
 /// Issue severity level
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IssueSeverity {
     /// Critical issue that will prevent the app from working
     Error,
@@ -217,8 +219,15 @@ impl fmt::Display for IssueSeverity {
     }
 }
 
+/// A .env value that resolves an issue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvEdit {
+    pub key: String,
+    pub value: String,
+}
+
 /// A detected issue or suggestion for the project
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectIssue {
     /// Severity of the issue
     pub severity: IssueSeverity,
@@ -228,6 +237,9 @@ pub struct ProjectIssue {
     pub message: String,
     /// Suggested fix (if any)
     pub suggestion: Option<String>,
+    /// .env changes that fix the issue, if it can be fixed automatically
+    #[serde(default)]
+    pub env_edits: Vec<EnvEdit>,
 }
 
 impl ProjectIssue {
@@ -238,6 +250,7 @@ impl ProjectIssue {
             category: category.into(),
             message: message.into(),
             suggestion: None,
+            env_edits: Vec::new(),
         }
     }
 
@@ -248,6 +261,7 @@ impl ProjectIssue {
             category: category.into(),
             message: message.into(),
             suggestion: None,
+            env_edits: Vec::new(),
         }
     }
 
@@ -258,6 +272,7 @@ impl ProjectIssue {
             category: category.into(),
             message: message.into(),
             suggestion: None,
+            env_edits: Vec::new(),
         }
     }
 
@@ -266,6 +281,24 @@ impl ProjectIssue {
         self.suggestion = Some(suggestion.into());
         self
     }
+
+    /// Add a .env change that fixes this issue
+    pub fn with_env_edit(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env_edits.push(EnvEdit {
+            key: key.into(),
+            value: value.into(),
+        });
+        self
+    }
+}
+
+/// Outcome of applying issues' .env edits
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvFixResult {
+    /// Edits written to .env, in order
+    pub applied: Vec<EnvEdit>,
+    /// Copy of the previous .env (None when nothing was written)
+    pub backup: Option<PathBuf>,
 }
 
 /// Complete analyzed project information
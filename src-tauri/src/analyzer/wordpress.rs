@@ -0,0 +1,561 @@
+//! WordPress cache and search plugin configuration
+//!
+//! WordPress plugins don't pick Burd's services up from .env the way Laravel
+//! does. The Redis Object Cache plugin reads `WP_REDIS_*` constants and only
+//! takes over once its `object-cache.php` drop-in sits in the content
+//! directory; Meilisearch plugins read `MEILISEARCH_*` constants. This plans
+//! (and applies) those changes against Burd's Redis and Meilisearch instances:
+//!
+//! - WordPress: `define()` calls in wp-config.php
+//! - Bedrock: .env keys, plus `Config::define()` lines in
+//!   config/application.php so they become constants
+//! - both: copying the plugin's drop-in into the content directory
+//!
+//! Only services the project has opted into (plugin installed or settings
+//! already present) get fixes, so sites that don't use Redis are left alone.
+
+use super::parsers::{parse_env_file, update_env_value};
+use super::types::ProjectType;
+use crate::config::{Config, Instance, ServiceType};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// wordpress.org slug of the Redis Object Cache plugin
+pub const REDIS_PLUGIN: &str = "redis-cache";
+
+/// A change that points a WordPress plugin at a Burd service
+#[derive(Debug, Clone, PartialEq)]
+pub enum WpFixAction {
+    /// Set a constant in wp-config.php to a PHP literal
+    Constant { name: String, literal: String },
+    /// Set a key in Bedrock's .env
+    EnvValue { key: String, value: String },
+    /// Add `Config::define('NAME', env('NAME'))` to Bedrock's config/application.php
+    BedrockDefine { name: String },
+    /// Copy a plugin's drop-in into the content directory
+    DropIn { source: PathBuf, target: PathBuf },
+}
+
+/// A proposed WordPress plugin configuration fix
+#[derive(Debug, Clone, PartialEq)]
+pub struct WpFix {
+    /// Category (cache, search)
+    pub category: String,
+    /// What is changed, e.g. `WP_REDIS_PORT in wp-config.php`
+    pub target: String,
+    /// Current value, if set
+    pub current: Option<String>,
+    /// Value after the fix
+    pub suggested: String,
+    /// Reason for the fix
+    pub reason: String,
+    pub action: WpFixAction,
+}
+
+/// A setting a plugin reads, and the PHP literal it should have
+struct Setting {
+    name: &'static str,
+    value: String,
+    literal: String,
+}
+
+impl Setting {
+    fn string(name: &'static str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        let literal = php_string(&value);
+        Self {
+            name,
+            value,
+            literal,
+        }
+    }
+
+    fn int(name: &'static str, value: u16) -> Self {
+        Self {
+            name,
+            value: value.to_string(),
+            literal: value.to_string(),
+        }
+    }
+}
+
+/// A single-quoted PHP string literal
+pub fn php_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Where a project's WordPress configuration lives
+struct Layout {
+    root: PathBuf,
+    bedrock: bool,
+}
+
+impl Layout {
+    fn new(project_type: &ProjectType, path: &Path) -> Option<Self> {
+        let bedrock = match project_type {
+            ProjectType::WordPress => false,
+            ProjectType::Bedrock => true,
+            _ => return None,
+        };
+        Some(Self {
+            root: path.to_path_buf(),
+            bedrock,
+        })
+    }
+
+    /// wp-content, or Bedrock's web/app
+    fn content_dir(&self) -> PathBuf {
+        if self.bedrock {
+            self.root.join("web").join("app")
+        } else {
+            self.root.join("wp-content")
+        }
+    }
+
+    fn wp_config(&self) -> PathBuf {
+        self.root.join("wp-config.php")
+    }
+
+    fn application_config(&self) -> PathBuf {
+        self.root.join("config").join("application.php")
+    }
+
+    /// Names of the installed plugin directories
+    fn plugins(&self) -> Vec<String> {
+        fs::read_dir(self.content_dir().join("plugins"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Current values of the settings, from wp-config.php or Bedrock's .env
+struct CurrentValues {
+    values: HashMap<String, String>,
+    /// config/application.php, for Bedrock
+    application: Option<String>,
+}
+
+impl CurrentValues {
+    fn load(layout: &Layout) -> Self {
+        if layout.bedrock {
+            return Self {
+                values: parse_env_file(&layout.root.join(".env")).unwrap_or_default(),
+                application: fs::read_to_string(layout.application_config()).ok(),
+            };
+        }
+
+        let content = fs::read_to_string(layout.wp_config()).unwrap_or_default();
+        let values = define_pattern(r"\w+")
+            .captures_iter(&content)
+            .map(|cap| (cap[1].to_string(), unquote(&cap[2]).to_string()))
+            .collect();
+        Self {
+            values,
+            application: None,
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&String> {
+        self.values.get(name)
+    }
+
+    fn any_set(&self, names: &[&str]) -> bool {
+        names.iter().any(|n| self.values.contains_key(*n))
+    }
+}
+
+/// `define('NAME', value);` with the name and the raw value captured
+fn define_pattern(name: &str) -> regex::Regex {
+    regex::Regex::new(&format!(
+        r#"define\s*\(\s*['"]({})['"]\s*,\s*(.+?)\s*\)\s*;"#,
+        name
+    ))
+    .expect("valid define pattern")
+}
+
+/// Strip the quotes from a PHP string literal
+fn unquote(literal: &str) -> &str {
+    let literal = literal.trim();
+    ['\'', '"']
+        .iter()
+        .find_map(|q| literal.strip_prefix(*q).and_then(|l| l.strip_suffix(*q)))
+        .unwrap_or(literal)
+}
+
+fn redis_settings(instance: &Instance) -> Vec<Setting> {
+    vec![
+        Setting::string("WP_REDIS_HOST", "127.0.0.1"),
+        Setting::int("WP_REDIS_PORT", instance.port),
+    ]
+}
+
+fn meilisearch_settings(instance: &Instance) -> Vec<Setting> {
+    let mut settings = vec![Setting::string(
+        "MEILISEARCH_HOST",
+        format!("http://127.0.0.1:{}", instance.port),
+    )];
+    if let Some(key) = instance.get_master_key() {
+        settings.push(Setting::string("MEILISEARCH_KEY", key));
+    }
+    settings
+}
+
+/// Fixes that make `settings` match, in wp-config.php or Bedrock's .env and application.php
+fn setting_fixes(
+    layout: &Layout,
+    current: &CurrentValues,
+    category: &str,
+    settings: &[Setting],
+    reason: &str,
+) -> Vec<WpFix> {
+    let mut fixes = Vec::new();
+    for setting in settings {
+        let value = current.get(setting.name);
+        if value != Some(&setting.value) {
+            let (target, action) = if layout.bedrock {
+                (
+                    format!("{} in .env", setting.name),
+                    WpFixAction::EnvValue {
+                        key: setting.name.to_string(),
+                        value: setting.value.clone(),
+                    },
+                )
+            } else {
+                (
+                    format!("{} in wp-config.php", setting.name),
+                    WpFixAction::Constant {
+                        name: setting.name.to_string(),
+                        literal: setting.literal.clone(),
+                    },
+                )
+            };
+            fixes.push(WpFix {
+                category: category.to_string(),
+                target,
+                current: value.cloned(),
+                suggested: setting.value.clone(),
+                reason: reason.to_string(),
+                action,
+            });
+        }
+
+        // Bedrock only turns .env keys into constants it's told about
+        if let Some(application) = &current.application {
+            if !has_bedrock_define(application, setting.name) {
+                fixes.push(WpFix {
+                    category: category.to_string(),
+                    target: "config/application.php".to_string(),
+                    current: None,
+                    suggested: bedrock_define(setting.name),
+                    reason: format!("Bedrock doesn't pass {} to WordPress yet", setting.name),
+                    action: WpFixAction::BedrockDefine {
+                        name: setting.name.to_string(),
+                    },
+                });
+            }
+        }
+    }
+    fixes
+}
+
+/// Plan fixes that point WordPress cache and search plugins at Burd's instances
+///
+/// Returns nothing for projects that aren't WordPress or Bedrock.
+pub fn plan_fixes(project_type: &ProjectType, path: &Path, config: &Config) -> Vec<WpFix> {
+    let Some(layout) = Layout::new(project_type, path) else {
+        return Vec::new();
+    };
+    let current = CurrentValues::load(&layout);
+    let plugins = layout.plugins();
+    let mut fixes = Vec::new();
+
+    let redis = config
+        .instances
+        .iter()
+        .find(|i| i.service_type == ServiceType::Redis);
+    if let Some(redis) = redis {
+        let has_plugin = plugins.iter().any(|p| p == REDIS_PLUGIN);
+        if has_plugin || current.any_set(&["WP_REDIS_HOST", "WP_REDIS_PORT"]) {
+            fixes.extend(setting_fixes(
+                &layout,
+                &current,
+                "cache",
+                &redis_settings(redis),
+                &format!("Burd's Redis is running on port {}", redis.port),
+            ));
+        }
+
+        let source = layout
+            .content_dir()
+            .join("plugins")
+            .join(REDIS_PLUGIN)
+            .join("includes")
+            .join("object-cache.php");
+        let target = layout.content_dir().join("object-cache.php");
+        if has_plugin && source.exists() && !target.exists() {
+            fixes.push(WpFix {
+                category: "cache".to_string(),
+                target: "object-cache.php drop-in".to_string(),
+                current: None,
+                suggested: format!(
+                    "copy of {}",
+                    source.strip_prefix(path).unwrap_or(&source).display()
+                ),
+                reason: "Redis Object Cache only caches once its drop-in is installed".to_string(),
+                action: WpFixAction::DropIn { source, target },
+            });
+        }
+    }
+
+    let meilisearch = config
+        .instances
+        .iter()
+        .find(|i| i.service_type == ServiceType::Meilisearch);
+    if let Some(meilisearch) = meilisearch {
+        let has_plugin = plugins.iter().any(|p| p.contains("meilisearch"));
+        if has_plugin || current.any_set(&["MEILISEARCH_HOST", "MEILISEARCH_KEY"]) {
+            fixes.extend(setting_fixes(
+                &layout,
+                &current,
+                "search",
+                &meilisearch_settings(meilisearch),
+                &format!("Burd's Meilisearch is running on port {}", meilisearch.port),
+            ));
+        }
+    }
+
+    fixes
+}
+
+/// Whether the Redis Object Cache plugin is installed in a WordPress or Bedrock project
+pub fn has_redis_plugin(project_type: &ProjectType, path: &Path) -> bool {
+    Layout::new(project_type, path)
+        .map(|layout| layout.plugins().iter().any(|p| p == REDIS_PLUGIN))
+        .unwrap_or(false)
+}
+
+impl WpFix {
+    /// Apply the fix to the project at `path`
+    pub fn apply(&self, path: &Path) -> Result<(), String> {
+        self.action.apply(path)
+    }
+}
+
+impl WpFixAction {
+    /// Apply the change to the project at `path`
+    pub fn apply(&self, path: &Path) -> Result<(), String> {
+        match self {
+            WpFixAction::Constant { name, literal } => {
+                let wp_config = path.join("wp-config.php");
+                let content = fs::read_to_string(&wp_config)
+                    .map_err(|e| format!("Failed to read wp-config.php: {}", e))?;
+                let content = set_constant(&content, name, literal)?;
+                fs::write(&wp_config, content)
+                    .map_err(|e| format!("Failed to write wp-config.php: {}", e))
+            }
+            WpFixAction::EnvValue { key, value } => update_env_value(path, key, value),
+            WpFixAction::BedrockDefine { name } => {
+                let application = path.join("config").join("application.php");
+                let content = fs::read_to_string(&application)
+                    .map_err(|e| format!("Failed to read config/application.php: {}", e))?;
+                let content = add_bedrock_define(&content, name)?;
+                fs::write(&application, content)
+                    .map_err(|e| format!("Failed to write config/application.php: {}", e))
+            }
+            WpFixAction::DropIn { source, target } => fs::copy(source, target)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to install object-cache.php: {}", e)),
+        }
+    }
+}
+
+/// Insert `line` before the first line matching `is_anchor`
+fn insert_before(content: &str, line: &str, is_anchor: impl Fn(&str) -> bool) -> Option<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let index = lines.iter().position(|l| is_anchor(l))?;
+    lines.insert(index, line);
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+/// Set a constant in wp-config.php content, replacing its value or adding it
+/// above the "stop editing" comment (or the wp-settings.php require)
+fn set_constant(content: &str, name: &str, literal: &str) -> Result<String, String> {
+    if let Some(cap) = define_pattern(&regex::escape(name)).captures(content) {
+        let value = cap.get(2).expect("value group");
+        return Ok(format!(
+            "{}{}{}",
+            &content[..value.start()],
+            literal,
+            &content[value.end()..]
+        ));
+    }
+
+    let define = format!("define( '{}', {} );", name, literal);
+    insert_before(content, &define, |l| {
+        l.to_lowercase().contains("stop editing")
+    })
+    .or_else(|| insert_before(content, &define, |l| l.contains("wp-settings.php")))
+    .ok_or_else(|| format!("Couldn't find where to add {} in wp-config.php", name))
+}
+
+fn bedrock_define(name: &str) -> String {
+    format!("Config::define('{}', env('{}'));", name, name)
+}
+
+fn has_bedrock_define(content: &str, name: &str) -> bool {
+    regex::Regex::new(&format!(
+        r#"Config::define\s*\(\s*['"]{}['"]"#,
+        regex::escape(name)
+    ))
+    .map(|re| re.is_match(content))
+    .unwrap_or(false)
+}
+
+/// Add a `Config::define` for an .env key above `Config::apply()`
+fn add_bedrock_define(content: &str, name: &str) -> Result<String, String> {
+    if has_bedrock_define(content, name) {
+        return Ok(content.to_string());
+    }
+    insert_before(content, &bedrock_define(name), |l| {
+        l.trim_start().starts_with("Config::apply(")
+    })
+    .ok_or_else(|| "Couldn't find Config::apply() in config/application.php".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+    use serde_json::json;
+
+    const WP_CONFIG: &str = "<?php\n\
+        define( 'DB_NAME', 'shop' );\n\
+        define( 'WP_REDIS_PORT', 6379 );\n\
+        \n\
+        /* That's all, stop editing! Happy publishing. */\n\
+        require_once ABSPATH . 'wp-settings.php';\n";
+
+    fn config() -> Config {
+        ConfigBuilder::new()
+            .instance(
+                InstanceBuilder::new()
+                    .service_type(ServiceType::Redis)
+                    .port(6380)
+                    .build(),
+            )
+            .instance(
+                InstanceBuilder::new()
+                    .service_type(ServiceType::Meilisearch)
+                    .port(7701)
+                    .config(json!({ "master_key": "secret" }))
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn test_set_constant() {
+        let replaced = set_constant(WP_CONFIG, "WP_REDIS_PORT", "6380").unwrap();
+        assert!(replaced.contains("define( 'WP_REDIS_PORT', 6380 );"));
+        assert!(!replaced.contains("6379"));
+
+        let added = set_constant(WP_CONFIG, "WP_REDIS_HOST", "'127.0.0.1'").unwrap();
+        assert!(
+            added.contains("define( 'WP_REDIS_HOST', '127.0.0.1' );\n/* That's all, stop editing!")
+        );
+        assert!(added.ends_with("wp-settings.php';\n"));
+
+        assert!(set_constant("<?php\n", "WP_REDIS_HOST", "'127.0.0.1'").is_err());
+    }
+
+    #[test]
+    fn test_add_bedrock_define() {
+        let content = "<?php\nConfig::define('WP_ENV', env('WP_ENV'));\nConfig::apply();\n";
+        let added = add_bedrock_define(content, "WP_REDIS_HOST").unwrap();
+        assert_eq!(
+            added,
+            "<?php\nConfig::define('WP_ENV', env('WP_ENV'));\n\
+             Config::define('WP_REDIS_HOST', env('WP_REDIS_HOST'));\nConfig::apply();\n"
+        );
+        assert_eq!(add_bedrock_define(&added, "WP_REDIS_HOST").unwrap(), added);
+    }
+
+    #[test]
+    fn test_plan_and_apply_wordpress_fixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let plugin = root.join("wp-content/plugins/redis-cache/includes");
+        fs::create_dir_all(&plugin).unwrap();
+        fs::write(plugin.join("object-cache.php"), "<?php // drop-in").unwrap();
+        fs::write(root.join("wp-config.php"), WP_CONFIG).unwrap();
+
+        let fixes = plan_fixes(&ProjectType::WordPress, root, &config());
+        let targets: Vec<&str> = fixes.iter().map(|f| f.target.as_str()).collect();
+        // Meilisearch isn't used by the site, so it's left alone
+        assert_eq!(
+            targets,
+            vec![
+                "WP_REDIS_HOST in wp-config.php",
+                "WP_REDIS_PORT in wp-config.php",
+                "object-cache.php drop-in",
+            ]
+        );
+        assert_eq!(fixes[1].current.as_deref(), Some("6379"));
+
+        for fix in &fixes {
+            fix.apply(root).unwrap();
+        }
+        assert!(root.join("wp-content/object-cache.php").exists());
+        assert!(plan_fixes(&ProjectType::WordPress, root, &config()).is_empty());
+        assert!(plan_fixes(&ProjectType::Laravel { version: None }, root, &config()).is_empty());
+    }
+
+    #[test]
+    fn test_plan_bedrock_fixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("web/app/plugins/wp-meilisearch")).unwrap();
+        fs::create_dir_all(root.join("config")).unwrap();
+        fs::write(
+            root.join("config/application.php"),
+            "<?php\nConfig::define('MEILISEARCH_HOST', env('MEILISEARCH_HOST'));\nConfig::apply();\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join(".env"),
+            "MEILISEARCH_HOST=http://127.0.0.1:7701\n",
+        )
+        .unwrap();
+
+        let fixes = plan_fixes(&ProjectType::Bedrock, root, &config());
+        assert_eq!(
+            fixes.iter().map(|f| &f.action).collect::<Vec<_>>(),
+            vec![
+                &WpFixAction::EnvValue {
+                    key: "MEILISEARCH_KEY".to_string(),
+                    value: "secret".to_string(),
+                },
+                &WpFixAction::BedrockDefine {
+                    name: "MEILISEARCH_KEY".to_string(),
+                },
+            ]
+        );
+
+        for fix in &fixes {
+            fix.apply(root).unwrap();
+        }
+        assert!(plan_fixes(&ProjectType::Bedrock, root, &config()).is_empty());
+    }
+}
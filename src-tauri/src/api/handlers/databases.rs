@@ -1,18 +1,24 @@
 //! Database API handlers
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::api::{
     state::ApiState,
-    types::{ApiResponse, CreateDatabaseRequest},
+    types::{
+        ApiResponse, CreateDatabaseRequest, CreateDatabaseUserRequest, DatabaseUserResponse,
+        UpdateDatabaseUserRequest,
+    },
 };
 use crate::config::ServiceType;
-use crate::db_manager::{create_manager_for_instance, sanitize_db_name};
+use crate::db_manager::{
+    create_manager_for_instance, generate_password, is_database_service, sanitize_db_name,
+    validate_db_user, DatabaseManager, DatabaseUser, DbPrivileges,
+};
 
 /// Database info response
 #[derive(Debug, Serialize)]
@@ -47,9 +53,7 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<Databas
             .instances
             .into_iter()
             .filter(|i| {
-                (i.service_type == ServiceType::MariaDB
-                    || i.service_type == ServiceType::PostgreSQL)
-                    && process_manager.get_status(i).running
+                is_database_service(i.service_type) && process_manager.get_status(i).running
             })
             .collect::<Vec<_>>()
     };
@@ -62,11 +66,7 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<Databas
             Err(_) => continue,
         };
 
-        let service_type = match instance.service_type {
-            ServiceType::MariaDB => "MariaDB",
-            ServiceType::PostgreSQL => "PostgreSQL",
-            _ => continue,
-        };
+        let service_type = instance.service_type.display_name();
 
         if let Ok(databases) = manager.list_databases() {
             for db in databases {
@@ -129,21 +129,20 @@ pub async fn create(
                 None => return Json(ApiResponse::err("Instance not found")),
             }
         } else {
-            // Find first running database instance
+            // Find first running database instance, preferring SQL engines
             match config
                 .instances
                 .iter()
-                .find(|i| {
-                    (i.service_type == ServiceType::MariaDB
-                        || i.service_type == ServiceType::PostgreSQL)
-                        && process_manager.get_status(i).running
+                .filter(|i| {
+                    is_database_service(i.service_type) && process_manager.get_status(i).running
                 })
+                .min_by_key(|i| i.service_type == ServiceType::MongoDB)
                 .cloned()
             {
                 Some(i) => i,
                 None => {
                     return Json(ApiResponse::err(
-                        "No running database instance found. Please start a MariaDB or PostgreSQL instance first.",
+                        "No running database instance found. Please start a MariaDB, PostgreSQL or MongoDB instance first.",
                     ))
                 }
             }
@@ -173,11 +172,7 @@ pub async fn create(
         return Json(ApiResponse::err(e));
     }
 
-    let service_type = match instance.service_type {
-        ServiceType::MariaDB => "MariaDB",
-        ServiceType::PostgreSQL => "PostgreSQL",
-        _ => "Unknown",
-    };
+    let service_type = instance.service_type.display_name();
 
     Json(ApiResponse::ok(DatabaseInfo {
         name: db_name,
@@ -220,9 +215,7 @@ pub async fn drop(
             .instances
             .into_iter()
             .filter(|i| {
-                (i.service_type == ServiceType::MariaDB
-                    || i.service_type == ServiceType::PostgreSQL)
-                    && process_manager.get_status(i).running
+                is_database_service(i.service_type) && process_manager.get_status(i).running
             })
             .collect::<Vec<_>>()
     };
@@ -252,3 +245,185 @@ pub async fn drop(
         db_name
     )))
 }
+
+/// Query parameters for removing a user from a database
+#[derive(Debug, Deserialize)]
+pub struct RemoveUserQuery {
+    /// Also drop the user itself, not just its access to this database
+    #[serde(default)]
+    pub drop_user: bool,
+}
+
+/// Find the running database instance holding a database
+///
+/// Returns the sanitized database name and a manager for its instance.
+fn find_database(
+    state: &ApiState,
+    name: &str,
+) -> Result<(String, Box<dyn DatabaseManager>), String> {
+    let db_name = sanitize_db_name(name)?;
+    let instances = {
+        let config_store = state
+            .inner
+            .config_store
+            .lock()
+            .map_err(|_| "Failed to acquire config lock")?;
+        let process_manager = state
+            .inner
+            .process_manager
+            .lock()
+            .map_err(|_| "Failed to acquire process manager lock")?;
+        let config = config_store
+            .load()
+            .map_err(|e| format!("Failed to load config: {}", e))?;
+
+        config
+            .instances
+            .into_iter()
+            .filter(|i| {
+                is_database_service(i.service_type) && process_manager.get_status(i).running
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for instance in instances {
+        let Ok(manager) = create_manager_for_instance(&instance) else {
+            continue;
+        };
+        if let Ok(true) = manager.database_exists(&db_name) {
+            return Ok((db_name, manager));
+        }
+    }
+
+    Err(format!(
+        "Database '{}' not found in any running database instance",
+        db_name
+    ))
+}
+
+fn privileges_for(read_only: bool) -> DbPrivileges {
+    if read_only {
+        DbPrivileges::ReadOnly
+    } else {
+        DbPrivileges::All
+    }
+}
+
+/// Create the user unless it exists, then grant it access to the database
+fn grant_user(
+    manager: &dyn DatabaseManager,
+    db_name: String,
+    req: CreateDatabaseUserRequest,
+) -> Result<DatabaseUserResponse, String> {
+    let username = validate_db_user(&req.username)?;
+
+    let mut generated = None;
+    if !manager.user_exists(&username)? {
+        let password = match req.password {
+            Some(password) => password,
+            None => generated.insert(generate_password()).clone(),
+        };
+        manager.create_user(&username, &password)?;
+    } else if let Some(password) = &req.password {
+        manager.set_user_password(&username, password)?;
+    }
+
+    let privileges = privileges_for(req.read_only);
+    manager.grant_privileges(&username, &db_name, privileges)?;
+
+    Ok(DatabaseUserResponse {
+        username,
+        database: db_name,
+        privileges: Some(privileges),
+        password: generated,
+    })
+}
+
+/// Apply a password and/or privilege change to a user
+fn change_user(
+    manager: &dyn DatabaseManager,
+    db_name: String,
+    user: &str,
+    req: UpdateDatabaseUserRequest,
+) -> Result<DatabaseUserResponse, String> {
+    let username = validate_db_user(user)?;
+
+    if let Some(password) = &req.password {
+        manager.set_user_password(&username, password)?;
+    }
+    if let Some(read_only) = req.read_only {
+        manager.grant_privileges(&username, &db_name, privileges_for(read_only))?;
+    }
+
+    let privileges = manager
+        .list_users(Some(&db_name))?
+        .into_iter()
+        .find(|u| u.name == username)
+        .and_then(|u| u.privileges);
+
+    Ok(DatabaseUserResponse {
+        username,
+        database: db_name,
+        privileges,
+        password: None,
+    })
+}
+
+/// GET /databases/:name/users - List users with access to a database
+pub async fn list_users(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Json<ApiResponse<Vec<DatabaseUser>>> {
+    let result = find_database(&state, &name).and_then(|(db, m)| m.list_users(Some(&db)));
+    match result {
+        Ok(users) => Json(ApiResponse::ok(users)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /databases/:name/users - Create a user if needed and grant it access
+pub async fn add_user(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<CreateDatabaseUserRequest>,
+) -> Json<ApiResponse<DatabaseUserResponse>> {
+    let result = find_database(&state, &name).and_then(|(db, m)| grant_user(m.as_ref(), db, req));
+    match result {
+        Ok(user) => Json(ApiResponse::ok(user)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// PUT /databases/:name/users/:user - Change a user's password or privileges
+pub async fn update_user(
+    State(state): State<ApiState>,
+    Path((name, user)): Path<(String, String)>,
+    Json(req): Json<UpdateDatabaseUserRequest>,
+) -> Json<ApiResponse<DatabaseUserResponse>> {
+    let result =
+        find_database(&state, &name).and_then(|(db, m)| change_user(m.as_ref(), db, &user, req));
+    match result {
+        Ok(user) => Json(ApiResponse::ok(user)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// DELETE /databases/:name/users/:user - Revoke a user's access (or drop it)
+pub async fn remove_user(
+    State(state): State<ApiState>,
+    Path((name, user)): Path<(String, String)>,
+    Query(query): Query<RemoveUserQuery>,
+) -> Json<ApiResponse<()>> {
+    let result = find_database(&state, &name).and_then(|(db, m)| {
+        let username = validate_db_user(&user)?;
+        if query.drop_user {
+            m.drop_user(&username)
+        } else {
+            m.revoke_privileges(&username, &db)
+        }
+    });
+    match result {
+        Ok(()) => Json(ApiResponse::success()),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
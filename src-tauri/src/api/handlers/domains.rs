@@ -1,17 +1,21 @@
 //! Domain API handlers
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::api::{
     state::ApiState,
     types::{ApiResponse, CreateDomainRequest, ToggleSslRequest, UpdateDomainRequest},
 };
+use crate::client_certs;
+use crate::commands::apply_custom_directives;
 use crate::config::DomainTarget;
+use crate::site_stats::{self, DomainStats, DEFAULT_WINDOW_MINUTES};
+use crate::traffic::{self, RequestRecord, MAX_REQUESTS_PER_DOMAIN};
 
 /// Domain response
 #[derive(Debug, Serialize)]
@@ -22,6 +26,10 @@ pub struct DomainInfo {
     pub target_type: String,
     pub target_value: String,
     pub ssl_enabled: bool,
+    pub force_https: bool,
+    pub hsts: bool,
+    pub require_client_cert: bool,
+    pub custom_directives: Option<String>,
 }
 
 /// GET /domains - List all domains
@@ -55,6 +63,10 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<DomainI
                     target_type,
                     target_value,
                     ssl_enabled: d.ssl_enabled,
+                    force_https: d.force_https,
+                    hsts: d.hsts,
+                    require_client_cert: d.require_client_cert,
+                    custom_directives: d.custom_directives.clone(),
                 }
             })
             .collect();
@@ -201,6 +213,10 @@ pub async fn create(
         target_type,
         target_value,
         ssl_enabled: domain.ssl_enabled,
+        force_https: domain.force_https,
+        hsts: domain.hsts,
+        require_client_cert: domain.require_client_cert,
+        custom_directives: domain.custom_directives.clone(),
     }))
 }
 
@@ -270,6 +286,42 @@ pub async fn update(
             Err(e) => return Json(ApiResponse::err(e)),
         };
 
+        if req.require_client_cert == Some(true) {
+            if !updated.ssl_enabled {
+                return Json(ApiResponse::err(
+                    "Enable SSL for this domain before requiring a client certificate",
+                ));
+            }
+            if let Err(e) = client_certs::ensure(&uuid.to_string(), &updated.full_domain(&tld)) {
+                return Json(ApiResponse::err(e));
+            }
+        }
+
+        let updated = if req.force_https.is_some()
+            || req.hsts.is_some()
+            || req.require_client_cert.is_some()
+        {
+            match config_store.update_domain_https_options(
+                uuid,
+                req.force_https,
+                req.hsts,
+                req.require_client_cert,
+            ) {
+                Ok(d) => d,
+                Err(e) => return Json(ApiResponse::err(e)),
+            }
+        } else {
+            updated
+        };
+
+        let updated = match &req.custom_directives {
+            Some(directives) => match apply_custom_directives(&config_store, uuid, directives) {
+                Ok(d) => d,
+                Err(e) => return Json(ApiResponse::err(e)),
+            },
+            None => updated,
+        };
+
         let instance_port = if let DomainTarget::Instance(instance_id) = &updated.target {
             config.instances.iter().find(|i| &i.id == instance_id).map(|i| i.port)
         } else {
@@ -315,6 +367,10 @@ pub async fn update(
         target_type,
         target_value,
         ssl_enabled: updated.ssl_enabled,
+        force_https: updated.force_https,
+        hsts: updated.hsts,
+        require_client_cert: updated.require_client_cert,
+        custom_directives: updated.custom_directives.clone(),
     }))
 }
 
@@ -440,5 +496,104 @@ pub async fn toggle_ssl(
         target_type,
         target_value,
         ssl_enabled: domain.ssl_enabled,
+        force_https: domain.force_https,
+        hsts: domain.hsts,
+        require_client_cert: domain.require_client_cert,
+        custom_directives: domain.custom_directives.clone(),
     }))
 }
+
+/// Query parameters for the stats endpoints
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// Length of the recent window in minutes (default 15)
+    #[serde(default)]
+    pub minutes: Option<u32>,
+}
+
+/// GET /domains/stats - Request counts and response times of every domain
+pub async fn stats(
+    State(state): State<ApiState>,
+    Query(query): Query<StatsQuery>,
+) -> Json<ApiResponse<Vec<DomainStats>>> {
+    let config = match state.inner.config_store.lock() {
+        Ok(cs) => cs.load(),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    let config = match config {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+    };
+
+    let minutes = query.minutes.unwrap_or(DEFAULT_WINDOW_MINUTES).max(1);
+    let stats = site_stats::all_domain_stats(&config, minutes);
+    Json(ApiResponse::ok(stats))
+}
+
+/// GET /domains/{id}/stats - Request counts and response times of a domain
+pub async fn domain_stats(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<StatsQuery>,
+) -> Json<ApiResponse<DomainStats>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid domain ID")),
+    };
+
+    let config = match state.inner.config_store.lock() {
+        Ok(cs) => cs.load(),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    let config = match config {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+    };
+    let Some(domain) = config.domains.iter().find(|d| d.id == uuid) else {
+        return Json(ApiResponse::err(format!("Domain {} not found", id)));
+    };
+
+    let minutes = query.minutes.unwrap_or(DEFAULT_WINDOW_MINUTES).max(1);
+    let stats = site_stats::domain_stats(&domain.full_domain(&config.tld), minutes);
+    Json(ApiResponse::ok(stats))
+}
+
+/// Query parameters for the requests endpoint
+#[derive(Debug, Deserialize)]
+pub struct RequestsQuery {
+    /// Most recent requests to return (default and at most 200)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// GET /domains/{id}/requests - Most recent requests served for a domain, newest first
+///
+/// New requests are streamed by `GET /events?types=request&domain=<domain>`.
+pub async fn requests(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<RequestsQuery>,
+) -> Json<ApiResponse<Vec<RequestRecord>>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid domain ID")),
+    };
+
+    let config = match state.inner.config_store.lock() {
+        Ok(cs) => cs.load(),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    let config = match config {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+    };
+    let Some(domain) = config.domains.iter().find(|d| d.id == uuid) else {
+        return Json(ApiResponse::err(format!("Domain {} not found", id)));
+    };
+
+    let limit = query.limit.unwrap_or(MAX_REQUESTS_PER_DOMAIN);
+    match traffic::recent_requests(Some(&domain.full_domain(&config.tld)), limit) {
+        Ok(requests) => Json(ApiResponse::ok(requests)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
@@ -0,0 +1,35 @@
+//! .env sync API handlers
+
+use axum::{extract::State, Json};
+use serde::Deserialize;
+
+use crate::api::{state::ApiState, types::ApiResponse};
+use crate::env_sync::{self, EnvChange};
+
+#[derive(Deserialize)]
+pub struct EnvSyncRequest {
+    /// Changes as suggested in the `env_sync` field of PUT /instances/:id
+    pub changes: Vec<EnvChange>,
+}
+
+/// POST /env/sync - Apply suggested .env updates to linked projects
+pub async fn sync(
+    State(state): State<ApiState>,
+    Json(req): Json<EnvSyncRequest>,
+) -> Json<ApiResponse<usize>> {
+    let config = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        match config_store.load() {
+            Ok(c) => c,
+            Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+        }
+    };
+
+    match env_sync::apply(&config, &req.changes) {
+        Ok(applied) => Json(ApiResponse::ok(applied)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
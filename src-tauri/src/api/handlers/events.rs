@@ -0,0 +1,52 @@
+//! Event stream API handler
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    response::Response,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::events::{self, EventFilter};
+
+/// GET /events - Stream events over a WebSocket
+///
+/// Each message is a JSON event with a `type` of instance, download, health,
+/// log or request. `?types=health,log` limits the types, `?instance=<id>`
+/// limits instance events to one instance and `?domain=shop.test` limits
+/// requests to one domain. When the client falls behind, skipped
+/// events are reported as `{"type": "lagged", "skipped": n}`.
+pub async fn stream(ws: WebSocketUpgrade, Query(filter): Query<EventFilter>) -> Response {
+    ws.on_upgrade(move |socket| forward(socket, filter))
+}
+
+async fn forward(mut socket: WebSocket, filter: EventFilter) {
+    let mut receiver = events::subscribe();
+    loop {
+        tokio::select! {
+            received = receiver.recv() => {
+                let text = match received {
+                    Ok(event) if filter.matches(&event) => match serde_json::to_string(&event) {
+                        Ok(text) => text,
+                        Err(_) => continue,
+                    },
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        serde_json::json!({ "type": "lagged", "skipped": skipped }).to_string()
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                // Pings are answered by axum; anything else from the client is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Health API handler
+//!
+//! One URL for uptime monitors and scripts: 200 when every subsystem is
+//! fine, 503 with the failing checks otherwise.
+
+use axum::{extract::State, http::StatusCode, Json};
+use std::sync::atomic::Ordering;
+
+use crate::api::{
+    state::ApiState,
+    types::{HealthCheckResult, HealthResponse, InstancesHealth},
+};
+use crate::config::Instance;
+use crate::helper_client::HelperClient;
+use crate::proxy_watchdog::{self, ProxyHealth};
+use crate::readiness;
+
+/// Running instances, or the error that kept them from being listed
+fn running_instances(state: &ApiState) -> Result<(usize, Vec<Instance>), String> {
+    let config = state
+        .inner
+        .config_store
+        .lock()
+        .map_err(|_| "Failed to acquire config lock")?
+        .load()
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    let process_manager = state
+        .inner
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock")?;
+
+    let total = config.instances.len();
+    let running = config
+        .instances
+        .into_iter()
+        .filter(|i| process_manager.get_status(i).running)
+        .collect();
+    Ok((total, running))
+}
+
+async fn check_instances(state: &ApiState) -> InstancesHealth {
+    let (total, running) = match running_instances(state) {
+        Ok(instances) => instances,
+        Err(e) => {
+            return InstancesHealth {
+                ok: false,
+                total: 0,
+                running: 0,
+                unhealthy: vec![e],
+            }
+        }
+    };
+
+    let checks = running
+        .iter()
+        .map(|i| readiness::is_healthy(i.port, i.service_type));
+    let unhealthy: Vec<String> = futures_util::future::join_all(checks)
+        .await
+        .into_iter()
+        .zip(&running)
+        .filter(|(healthy, _)| !healthy)
+        .map(|(_, instance)| instance.name.clone())
+        .collect();
+
+    InstancesHealth {
+        ok: unhealthy.is_empty(),
+        total,
+        running: running.len(),
+        unhealthy,
+    }
+}
+
+fn check_dns(state: &ApiState) -> HealthCheckResult {
+    match state.inner.dns_server.lock() {
+        Ok(dns) if dns.is_running() => HealthCheckResult::new(true, "running"),
+        Ok(_) => HealthCheckResult::new(false, "not running"),
+        Err(_) => HealthCheckResult::new(false, "Failed to acquire DNS server lock"),
+    }
+}
+
+/// The watchdog's last result, checking now if it hasn't run yet
+async fn check_proxy(state: &ApiState) -> HealthCheckResult {
+    let mut health = ProxyHealth::from_u8(state.inner.proxy_healthy.load(Ordering::Relaxed));
+    if health == ProxyHealth::Unknown {
+        health = proxy_watchdog::check_now(&state.inner).await;
+    }
+    HealthCheckResult::new(health == ProxyHealth::Healthy, health.label())
+}
+
+/// The helper is optional; it only fails the check when installed but unreachable
+async fn check_helper() -> HealthCheckResult {
+    if !HelperClient::is_installed() {
+        return HealthCheckResult::new(true, "not installed");
+    }
+    match tokio::task::spawn_blocking(HelperClient::is_running).await {
+        Ok(true) => HealthCheckResult::new(true, "running"),
+        _ => HealthCheckResult::new(false, "installed but not responding"),
+    }
+}
+
+/// GET /health - Health of every Burd subsystem (503 when any check fails)
+pub async fn get_health(State(state): State<ApiState>) -> (StatusCode, Json<HealthResponse>) {
+    let dns = check_dns(&state);
+    let (proxy, helper, instances) =
+        tokio::join!(check_proxy(&state), check_helper(), check_instances(&state));
+
+    let healthy = dns.ok && proxy.ok && helper.ok && instances.ok;
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(HealthResponse {
+            status: if healthy { "ok" } else { "degraded" },
+            api: HealthCheckResult::new(true, "running"),
+            dns,
+            proxy,
+            helper,
+            instances,
+        }),
+    )
+}
@@ -1,20 +1,32 @@
 //! Instance API handlers
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::activity::{self, ActivityEvent};
 use crate::api::{
     state::ApiState,
     types::{ApiResponse, CreateInstanceRequest, UpdateInstanceRequest},
 };
-use crate::commands::{generate_env_for_service, parse_service_type};
+use crate::commands::{generate_env_for_service, parse_service_type, EnvFormat};
+use crate::config::{Instance, SeedSettings, ServiceType};
+use crate::env_sync::{self, EnvChange};
+use crate::metrics::InstanceMetrics;
+use crate::port_allocator;
 use crate::process::ProcessManager;
+use crate::readiness;
+use crate::seed::{self, SeedResult};
 use crate::service_config::ServiceRegistry;
-use crate::services::{get_service, HealthCheck};
+use crate::services::centrifugo::{CentrifugoService, CentrifugoSetup};
+use crate::services::get_service;
+use crate::services::keycloak::{KeycloakProvision, KeycloakService};
+use crate::services::mailpit::MailpitService;
+use crate::services::php_ini::{self, PhpIniOverrides, XdebugMode};
+use crate::services::soketi::{SoketiService, SoketiSetup};
 
 /// Instance with health status (API response type)
 #[derive(Debug, Serialize)]
@@ -23,6 +35,8 @@ pub struct InstanceWithHealth {
     pub name: String,
     pub port: u16,
     pub service_type: String,
+    /// Service ID, e.g. "mariadb" (`service_type` is the display name)
+    pub service_id: String,
     pub version: String,
     pub running: bool,
     pub pid: Option<u32>,
@@ -31,34 +45,47 @@ pub struct InstanceWithHealth {
     pub domain: String,
     pub domain_enabled: bool,
     pub process_manager: String,
+    /// ID of the stack it belongs to
+    pub stack_id: Option<String>,
+    pub tags: Vec<String>,
+    /// Started when Burd or the daemon launches
+    pub auto_start: bool,
+    /// Restarted when it crashes or fails its health check
+    pub restart_on_failure: bool,
+    /// Arguments appended to the service's start arguments
+    pub extra_args: Vec<String>,
+    /// Database seed, for MariaDB and PostgreSQL instances
+    pub seed: Option<SeedSettings>,
+    /// Suggested .env updates for linked projects (only after an update)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env_sync: Vec<EnvChange>,
 }
 
-/// Check health for a service
-async fn check_health_for_service(port: u16, service_type: crate::config::ServiceType) -> bool {
-    let service = get_service(service_type);
-    match service.health_check() {
-        HealthCheck::Http { path } => {
-            let url = format!("http://127.0.0.1:{}{}", port, path);
-            reqwest::get(&url)
-                .await
-                .map(|r| r.status().is_success())
-                .unwrap_or(false)
-        }
-        HealthCheck::Tcp => {
-            use std::net::TcpStream;
-            use std::time::Duration;
-            // Parse the socket address safely
-            let addr = match format!("127.0.0.1:{}", port).parse() {
-                Ok(addr) => addr,
-                Err(_) => return false, // Invalid port number
-            };
-            TcpStream::connect_timeout(&addr, Duration::from_secs(2)).is_ok()
-        }
-    }
+/// Query parameters for the list endpoint
+#[derive(Debug, Deserialize)]
+pub struct ListInstancesQuery {
+    /// Comma-separated tags; only instances carrying all of them are returned
+    #[serde(default)]
+    pub tag: Option<String>,
 }
 
-/// GET /instances - List all instances
-pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<InstanceWithHealth>>> {
+/// Split a comma-separated tag filter into individual tags
+fn parse_tag_filter(filter: Option<&str>) -> Vec<String> {
+    filter
+        .unwrap_or("")
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// GET /instances - List all instances (optionally filtered by ?tag=a,b)
+pub async fn list(
+    State(state): State<ApiState>,
+    Query(query): Query<ListInstancesQuery>,
+) -> Json<ApiResponse<Vec<InstanceWithHealth>>> {
+    let tags = parse_tag_filter(query.tag.as_deref());
+
     // Collect instance data while holding lock
     let instances_data = {
         let config_store = match state.inner.config_store.lock() {
@@ -79,6 +106,7 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<Instanc
         let instances: Vec<_> = config
             .instances
             .into_iter()
+            .filter(|instance| instance.has_tags(&tags))
             .map(|instance| {
                 let status = process_manager.get_status(&instance);
                 (instance, status.running, status.pid, tld.clone())
@@ -92,7 +120,7 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<Instanc
     let mut results = Vec::new();
     for (instance, running, pid, tld) in instances_data {
         let healthy = if running {
-            Some(check_health_for_service(instance.port, instance.service_type).await)
+            Some(readiness::is_healthy(instance.port, instance.service_type).await)
         } else {
             None
         };
@@ -100,17 +128,18 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<Instanc
         let service = get_service(instance.service_type);
         let has_config = !instance.config.is_null() && instance.config != serde_json::json!({});
         // Only show domain if instance has explicit custom domain set
-        let domain = if instance.domain.is_some() {
-            instance.full_domain(&tld)
-        } else {
-            String::new()
-        };
+        let domain = instance
+            .domain
+            .as_ref()
+            .map(|custom| format!("{}.{}", custom, tld))
+            .unwrap_or_default();
         let domain_enabled = instance.domain_enabled;
         results.push(InstanceWithHealth {
             id: instance.id.to_string(),
             name: instance.name,
             port: instance.port,
             service_type: service.display_name().to_string(),
+            service_id: instance.service_type.as_str().to_string(),
             version: instance.version,
             running,
             pid,
@@ -119,6 +148,13 @@ pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<Instanc
             domain,
             domain_enabled,
             process_manager: "binary".to_string(),
+            stack_id: instance.stack_id.map(|id| id.to_string()),
+            tags: instance.tags,
+            auto_start: instance.auto_start,
+            restart_on_failure: instance.restart_on_failure,
+            extra_args: instance.extra_args,
+            seed: instance.seed,
+            env_sync: Vec::new(),
         });
     }
 
@@ -161,7 +197,7 @@ pub async fn get(
 
     let (instance, running, pid, tld) = instance_data;
     let healthy = if running {
-        Some(check_health_for_service(instance.port, instance.service_type).await)
+        Some(readiness::is_healthy(instance.port, instance.service_type).await)
     } else {
         None
     };
@@ -170,16 +206,17 @@ pub async fn get(
     let has_config = !instance.config.is_null() && instance.config != serde_json::json!({});
 
     // Only show domain if instance has explicit custom domain set
-    let domain = if instance.domain.is_some() {
-        instance.full_domain(&tld)
-    } else {
-        String::new()
-    };
+    let domain = instance
+        .domain
+        .as_ref()
+        .map(|custom| format!("{}.{}", custom, tld))
+        .unwrap_or_default();
     Json(ApiResponse::ok(InstanceWithHealth {
         id: instance.id.to_string(),
         name: instance.name.clone(),
         port: instance.port,
         service_type: service.display_name().to_string(),
+        service_id: instance.service_type.as_str().to_string(),
         version: instance.version.clone(),
         running,
         pid,
@@ -188,6 +225,13 @@ pub async fn get(
         domain,
         domain_enabled: instance.domain_enabled,
         process_manager: "binary".to_string(),
+        stack_id: instance.stack_id.map(|id| id.to_string()),
+        tags: instance.tags,
+        auto_start: instance.auto_start,
+        restart_on_failure: instance.restart_on_failure,
+        extra_args: instance.extra_args,
+        seed: instance.seed,
+        env_sync: Vec::new(),
     }))
 }
 
@@ -196,8 +240,8 @@ pub async fn create(
     State(state): State<ApiState>,
     Json(req): Json<CreateInstanceRequest>,
 ) -> Json<ApiResponse<InstanceWithHealth>> {
-    // Validate port
-    if req.port < 1024 {
+    // Validate port if one was given (otherwise it's assigned automatically)
+    if matches!(req.port, Some(port) if port < 1024) {
         return Json(ApiResponse::err("Port must be at least 1024"));
     }
 
@@ -207,10 +251,20 @@ pub async fn create(
         Err(e) => return Json(ApiResponse::err(e)),
     };
 
-    // Validate version
-    if req.version.is_empty() {
-        return Json(ApiResponse::err("Version is required"));
-    }
+    // Resolve the version (falls back to the pinned or newest installed one)
+    let version = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        let resolved = config_store
+            .load()
+            .and_then(|config| config.resolve_version(svc_type, &req.version));
+        match resolved {
+            Ok(v) => v,
+            Err(e) => return Json(ApiResponse::err(e)),
+        }
+    };
 
     // Check version is installed
     {
@@ -224,10 +278,10 @@ pub async fn create(
             Err(e) => return Json(ApiResponse::err(e)),
         };
 
-        if !installed.contains(&req.version) {
+        if !installed.contains(&version) {
             return Json(ApiResponse::err(format!(
                 "Version {} is not installed for {}",
-                req.version, req.service_type
+                version, req.service_type
             )));
         }
     }
@@ -237,7 +291,7 @@ pub async fn create(
     let service_def = registry.get_service(&req.service_type.to_lowercase());
     let _auto_create_domain = service_def.map(|s| s.auto_create_domain).unwrap_or(false);
 
-    let service_config = req.config.unwrap_or_else(|| serde_json::json!({}));
+    let mut service_config = req.config.unwrap_or_else(|| serde_json::json!({}));
 
     let result = {
         let config_store = match state.inner.config_store.lock() {
@@ -268,11 +322,21 @@ pub async fn create(
         }
 
         let tld = config.tld.clone();
+        let port =
+            match port_allocator::resolve_port(&config, svc_type, req.port, req.auto_assign_port) {
+                Ok(port) => port,
+                Err(e) => return Json(ApiResponse::err(e)),
+            };
+        if svc_type == ServiceType::Mailpit {
+            if let Err(e) = MailpitService::assign_smtp_port(&config, &mut service_config) {
+                return Json(ApiResponse::err(e));
+            }
+        }
         let instance = match config_store.create_instance(
             req.name,
-            req.port,
+            port,
             svc_type,
-            req.version,
+            version,
             service_config,
             req.custom_domain.clone(),
         ) {
@@ -290,17 +354,18 @@ pub async fn create(
     let service = get_service(instance.service_type);
     let has_config = !instance.config.is_null() && instance.config != serde_json::json!({});
     // Only show domain if instance has explicit custom domain set
-    let domain = if instance.domain.is_some() {
-        instance.full_domain(&tld)
-    } else {
-        String::new()
-    };
+    let domain = instance
+        .domain
+        .as_ref()
+        .map(|custom| format!("{}.{}", custom, tld))
+        .unwrap_or_default();
 
     Json(ApiResponse::ok(InstanceWithHealth {
         id: instance.id.to_string(),
         name: instance.name,
         port: instance.port,
         service_type: service.display_name().to_string(),
+        service_id: instance.service_type.as_str().to_string(),
         version: instance.version,
         running: false,
         pid: None,
@@ -309,6 +374,13 @@ pub async fn create(
         domain,
         domain_enabled: instance.domain_enabled,
         process_manager: "binary".to_string(),
+        stack_id: instance.stack_id.map(|id| id.to_string()),
+        tags: instance.tags,
+        auto_start: instance.auto_start,
+        restart_on_failure: instance.restart_on_failure,
+        extra_args: instance.extra_args,
+        seed: instance.seed,
+        env_sync: Vec::new(),
     }))
 }
 
@@ -329,6 +401,11 @@ pub async fn update(
             Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
         };
 
+        let before = match config_store.get_instance(uuid) {
+            Ok(i) => i,
+            Err(e) => return Json(ApiResponse::err(e)),
+        };
+
         let instance = match config_store.update_instance(
             uuid,
             req.name,
@@ -342,6 +419,38 @@ pub async fn update(
             Err(e) => return Json(ApiResponse::err(e)),
         };
 
+        let instance = match req.auto_start {
+            Some(auto_start) => match config_store.update_instance_auto_start(uuid, auto_start) {
+                Ok(i) => i,
+                Err(e) => return Json(ApiResponse::err(e)),
+            },
+            None => instance,
+        };
+
+        let instance = match req.restart_on_failure {
+            Some(restart) => match config_store.update_instance_restart_on_failure(uuid, restart) {
+                Ok(i) => i,
+                Err(e) => return Json(ApiResponse::err(e)),
+            },
+            None => instance,
+        };
+
+        let instance = match req.tags {
+            Some(tags) => match config_store.update_instance_tags(uuid, &tags) {
+                Ok(i) => i,
+                Err(e) => return Json(ApiResponse::err(e)),
+            },
+            None => instance,
+        };
+
+        let instance = match req.extra_args {
+            Some(args) => match config_store.update_instance_extra_args(uuid, &args) {
+                Ok(i) => i,
+                Err(e) => return Json(ApiResponse::err(e)),
+            },
+            None => instance,
+        };
+
         let config = match config_store.load() {
             Ok(c) => c,
             Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
@@ -353,29 +462,33 @@ pub async fn update(
         };
 
         let status = process_manager.get_status(&instance);
-        (instance, status.running, status.pid, config.tld)
+        (before, instance, status.running, status.pid, config)
     };
 
-    let (instance, running, pid, tld) = result;
+    let (before, instance, running, pid, config) = result;
+    activity::record_changes(&before, &instance);
+    let tld = config.tld.clone();
+    let env_sync = env_sync::plan_instance_change(&config, &before, &instance, &tld);
     let healthy = if running {
-        Some(check_health_for_service(instance.port, instance.service_type).await)
+        Some(readiness::is_healthy(instance.port, instance.service_type).await)
     } else {
         None
     };
 
     let service = get_service(instance.service_type);
     let has_config = !instance.config.is_null() && instance.config != serde_json::json!({});
-    let domain = if instance.domain.is_some() {
-        instance.full_domain(&tld)
-    } else {
-        String::new()
-    };
+    let domain = instance
+        .domain
+        .as_ref()
+        .map(|custom| format!("{}.{}", custom, tld))
+        .unwrap_or_default();
 
     Json(ApiResponse::ok(InstanceWithHealth {
         id: instance.id.to_string(),
         name: instance.name,
         port: instance.port,
         service_type: service.display_name().to_string(),
+        service_id: instance.service_type.as_str().to_string(),
         version: instance.version,
         running,
         pid,
@@ -384,13 +497,31 @@ pub async fn update(
         domain,
         domain_enabled: instance.domain_enabled,
         process_manager: "binary".to_string(),
+        stack_id: instance.stack_id.map(|id| id.to_string()),
+        tags: instance.tags,
+        auto_start: instance.auto_start,
+        restart_on_failure: instance.restart_on_failure,
+        extra_args: instance.extra_args,
+        seed: instance.seed,
+        env_sync,
     }))
 }
 
-/// POST /instances/:id/start - Start an instance
+/// Query parameters for the start endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct StartInstanceQuery {
+    /// Respond only once the service passes its health check
+    #[serde(default)]
+    pub wait: bool,
+    /// Seconds to wait for readiness (default 30)
+    pub timeout: Option<u64>,
+}
+
+/// POST /instances/:id/start - Start an instance (?wait=true&timeout=30 waits until healthy)
 pub async fn start(
     State(state): State<ApiState>,
     Path(id): Path<String>,
+    Query(query): Query<StartInstanceQuery>,
 ) -> Json<ApiResponse<u32>> {
     let uuid = match Uuid::parse_str(&id) {
         Ok(u) => u,
@@ -446,7 +577,7 @@ pub async fn start(
             .filter(|d| d.routes_to_instance(&uuid))
             .any(|d| d.ssl_enabled);
 
-        let pid = match process_manager.start(&instance, Some(&tld), ssl_enabled) {
+        let pid = match process_manager.start(&instance, Some(&config), ssl_enabled) {
             Ok(p) => p,
             Err(e) => return Json(ApiResponse::err(e)),
         };
@@ -459,10 +590,10 @@ pub async fn start(
             .cloned()
             .collect();
 
-        (pid, instance.port, domains, tld)
+        (pid, instance.port, instance.service_type, domains, tld)
     };
 
-    let (pid, port, domains, tld) = result;
+    let (pid, port, service_type, domains, tld) = result;
 
     // Register proxy routes
     {
@@ -477,6 +608,16 @@ pub async fn start(
         }
     }
 
+    if query.wait {
+        let timeout = query
+            .timeout
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(readiness::DEFAULT_READY_TIMEOUT);
+        if let Err(e) = readiness::wait_until_healthy(port, service_type, timeout).await {
+            return Json(ApiResponse::err(e));
+        }
+    }
+
     Json(ApiResponse::ok(pid))
 }
 
@@ -487,7 +628,7 @@ pub async fn stop(State(state): State<ApiState>, Path(id): Path<String>) -> Json
         Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
     };
 
-    // Get domains before stopping
+    // Get domains before stopping; wake-on-access instances keep theirs routed
     let domains = {
         let config_store = match state.inner.config_store.lock() {
             Ok(cs) => cs,
@@ -498,11 +639,15 @@ pub async fn stop(State(state): State<ApiState>, Path(id): Path<String>) -> Json
             Ok(c) => c,
             Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
         };
+        let wake_on_access = config
+            .instances
+            .iter()
+            .any(|i| i.id == uuid && i.wake_on_access);
 
         config
             .domains
             .iter()
-            .filter(|d| d.routes_to_instance(&uuid))
+            .filter(|d| !wake_on_access && d.routes_to_instance(&uuid))
             .cloned()
             .collect::<Vec<_>>()
     };
@@ -554,7 +699,12 @@ pub async fn restart(
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 
     // Start again
-    let start_result = start(State(state.clone()), Path(id)).await;
+    let start_result = start(
+        State(state.clone()),
+        Path(id),
+        Query(StartInstanceQuery::default()),
+    )
+    .await;
     if let Json(ApiResponse {
         success: false,
         error: Some(e),
@@ -567,6 +717,131 @@ pub async fn restart(
     Json(ApiResponse::success())
 }
 
+/// Request body for bulk lifecycle actions
+#[derive(Debug, Deserialize)]
+pub struct BulkActionRequest {
+    /// "start", "stop", or "restart"
+    pub action: String,
+    /// Instances carrying all of these tags are affected
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only instances of this stack (ID or name) are affected
+    #[serde(default)]
+    pub stack: Option<String>,
+}
+
+/// Outcome of a bulk action for one instance
+#[derive(Debug, Serialize)]
+pub struct BulkActionResult {
+    pub id: String,
+    pub name: String,
+    pub success: bool,
+    /// True when the instance was already in the requested state
+    pub skipped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// POST /instances/bulk - Start, stop, or restart every instance matching tags
+/// and/or belonging to a stack
+pub async fn bulk(
+    State(state): State<ApiState>,
+    Json(req): Json<BulkActionRequest>,
+) -> Json<ApiResponse<Vec<BulkActionResult>>> {
+    if !matches!(req.action.as_str(), "start" | "stop" | "restart") {
+        return Json(ApiResponse::err(format!(
+            "Unknown action '{}'. Use start, stop, or restart",
+            req.action
+        )));
+    }
+    let tags: Vec<String> = req
+        .tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let stack = req
+        .stack
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    if tags.is_empty() && stack.is_none() {
+        return Json(ApiResponse::err("At least one tag or a stack is required"));
+    }
+
+    let targets: Vec<(String, String, bool)> = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        let process_manager = match state.inner.process_manager.lock() {
+            Ok(pm) => pm,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire process manager lock")),
+        };
+        let config = match config_store.load() {
+            Ok(c) => c,
+            Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+        };
+        let stack_id = match stack {
+            Some(stack) => match config
+                .stacks
+                .iter()
+                .find(|s| s.id.to_string() == stack || s.name.eq_ignore_ascii_case(stack))
+            {
+                Some(s) => Some(s.id),
+                None => return Json(ApiResponse::err(format!("Stack '{}' not found", stack))),
+            },
+            None => None,
+        };
+
+        config
+            .instances
+            .iter()
+            .filter(|i| i.has_tags(&tags))
+            .filter(|i| stack_id.is_none() || i.stack_id == stack_id)
+            .map(|i| {
+                let running = process_manager.get_status(i).running;
+                (i.id.to_string(), i.name.clone(), running)
+            })
+            .collect()
+    };
+
+    let mut results = Vec::new();
+    for (id, name, running) in targets {
+        let skipped = match req.action.as_str() {
+            "start" => running,
+            "stop" => !running,
+            _ => false,
+        };
+
+        let error = if skipped {
+            None
+        } else {
+            let (handler_state, path) = (State(state.clone()), Path(id.clone()));
+            match req.action.as_str() {
+                "start" => {
+                    start(handler_state, path, Query(StartInstanceQuery::default()))
+                        .await
+                        .0
+                        .error
+                }
+                "stop" => stop(handler_state, path).await.0.error,
+                _ => restart(handler_state, path).await.0.error,
+            }
+        };
+
+        results.push(BulkActionResult {
+            id,
+            name,
+            success: error.is_none(),
+            skipped,
+            error,
+        });
+    }
+
+    Json(ApiResponse::ok(results))
+}
+
 /// DELETE /instances/:id - Delete an instance
 pub async fn remove(
     State(state): State<ApiState>,
@@ -617,6 +892,7 @@ pub async fn remove(
             return Json(ApiResponse::err(e));
         }
     }
+    activity::delete(&uuid);
 
     // Unregister proxy routes
     {
@@ -648,16 +924,93 @@ pub async fn logs(
     }
 }
 
+/// Query parameters for the activity endpoint
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    /// Most recent events to return (default 50)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// GET /instances/:id/activity - Get an instance's lifecycle events, newest first
+pub async fn activity(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<ActivityQuery>,
+) -> Json<ApiResponse<Vec<ActivityEvent>>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        if let Err(e) = config_store.get_instance(uuid) {
+            return Json(ApiResponse::err(e));
+        }
+    }
+
+    match activity::load(&uuid, query.limit.unwrap_or(50)) {
+        Ok(events) => Json(ApiResponse::ok(events)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// GET /instances/:id/metrics - CPU, memory and disk usage with recent history
+pub async fn metrics(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<InstanceMetrics>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let instance = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        match config_store.get_instance(uuid) {
+            Ok(i) => i,
+            Err(e) => return Json(ApiResponse::err(e)),
+        }
+    };
+
+    let process_manager = match state.inner.process_manager.lock() {
+        Ok(pm) => pm,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire process manager lock")),
+    };
+    Json(ApiResponse::ok(process_manager.get_metrics(&instance)))
+}
+
+/// Query parameters for the env endpoint
+#[derive(Debug, Deserialize)]
+pub struct EnvQuery {
+    /// Snippet flavour: laravel (default), symfony, django, rails, node
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
 /// GET /instances/:id/env - Get instance environment variables
 pub async fn env(
     State(state): State<ApiState>,
     Path(id): Path<String>,
+    Query(query): Query<EnvQuery>,
 ) -> Json<ApiResponse<String>> {
     let uuid = match Uuid::parse_str(&id) {
         Ok(u) => u,
         Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
     };
 
+    let format = match EnvFormat::parse_optional(query.format.as_deref()) {
+        Ok(f) => f,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
     let result = {
         let config_store = match state.inner.config_store.lock() {
             Ok(cs) => cs,
@@ -670,8 +1023,261 @@ pub async fn env(
         };
 
         // Generate environment variables based on service type
-        generate_env_for_service(&instance)
+        generate_env_for_service(&instance, format)
     };
 
     Json(ApiResponse::ok(result))
 }
+
+/// POST /instances/{id}/centrifugo/setup - Apply the Laravel broadcasting preset
+pub async fn centrifugo_setup(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<CentrifugoSetup>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let config_store = match state.inner.config_store.lock() {
+        Ok(cs) => cs,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+
+    match CentrifugoService::setup(&config_store, uuid) {
+        Ok(setup) => Json(ApiResponse::ok(setup)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /instances/{id}/soketi/setup - Apply the Laravel broadcasting preset
+pub async fn soketi_setup(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<SoketiSetup>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let config_store = match state.inner.config_store.lock() {
+        Ok(cs) => cs,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+
+    match SoketiService::setup(&config_store, uuid) {
+        Ok(setup) => Json(ApiResponse::ok(setup)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /instances/{id}/keycloak/provision - Create the realm, client and test user
+pub async fn keycloak_provision(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<KeycloakProvision>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let instance = match state.inner.config_store.lock() {
+        Ok(cs) => KeycloakService::find_instance(&cs, uuid),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    let instance = match instance {
+        Ok(i) => i,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
+    let result = tokio::task::spawn_blocking(move || KeycloakService::provision(&instance))
+        .await
+        .unwrap_or_else(|e| Err(format!("Task error: {}", e)));
+    match result {
+        Ok(provision) => Json(ApiResponse::ok(provision)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// PUT /instances/{id}/seed - Set the database seed (null clears it)
+pub async fn set_seed(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(seed): Json<Option<SeedSettings>>,
+) -> Json<ApiResponse<Option<SeedSettings>>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let config_store = match state.inner.config_store.lock() {
+        Ok(cs) => cs,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+
+    let before = match config_store.get_instance(uuid) {
+        Ok(i) => i,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+    match config_store.update_instance_seed(uuid, seed) {
+        Ok(instance) => {
+            activity::record_changes(&before, &instance);
+            Json(ApiResponse::ok(instance.seed))
+        }
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /instances/{id}/seed/run - Reset the database with its seed now
+pub async fn run_seed(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<SeedResult>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let (instance, running) = {
+        let instance = match state.inner.config_store.lock() {
+            Ok(cs) => cs.get_instance(uuid),
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        let instance = match instance {
+            Ok(i) => i,
+            Err(e) => return Json(ApiResponse::err(e)),
+        };
+        let running = match state.inner.process_manager.lock() {
+            Ok(pm) => pm.get_status(&instance).running,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire process manager lock")),
+        };
+        (instance, running)
+    };
+
+    let result = tokio::task::spawn_blocking(move || seed::run_seed(&instance, running))
+        .await
+        .unwrap_or_else(|e| Err(format!("Task error: {}", e)));
+    match result {
+        Ok(result) => Json(ApiResponse::ok(result)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// Request body for setting an instance's Xdebug mode
+#[derive(Debug, Deserialize)]
+pub struct SetXdebugRequest {
+    /// "off", "debug" (or "on"), or "profile"
+    pub mode: String,
+}
+
+/// Xdebug mode of a FrankenPHP instance
+#[derive(Debug, Serialize)]
+pub struct XdebugStatus {
+    pub mode: XdebugMode,
+    /// Port the IDE listens on in debug mode
+    pub client_port: u16,
+    /// Where profiles are written in profile mode
+    pub output_dir: Option<String>,
+    /// Whether the instance was restarted to apply the mode
+    pub restarted: bool,
+}
+
+fn xdebug_status(instance: &Instance, restarted: bool) -> Result<XdebugStatus, String> {
+    Ok(XdebugStatus {
+        mode: XdebugMode::from_instance(instance)?,
+        client_port: php_ini::XDEBUG_CLIENT_PORT,
+        output_dir: php_ini::xdebug_output_dir(instance)
+            .ok()
+            .map(|dir| dir.to_string_lossy().to_string()),
+        restarted,
+    })
+}
+
+/// GET /instances/{id}/xdebug - Get the Xdebug mode of a FrankenPHP instance
+pub async fn get_xdebug(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<XdebugStatus>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+
+    let instance = match state.inner.config_store.lock() {
+        Ok(cs) => cs.get_instance(uuid),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    match instance.and_then(|i| xdebug_status(&i, false)) {
+        Ok(status) => Json(ApiResponse::ok(status)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// PUT /instances/{id}/xdebug - Set the Xdebug mode, restarting the instance if it's running
+pub async fn set_xdebug(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<SetXdebugRequest>,
+) -> Json<ApiResponse<XdebugStatus>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid instance ID")),
+    };
+    let mode = match XdebugMode::parse(&request.mode) {
+        Ok(m) => m,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
+    let updated = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+        let before = match config_store.get_instance(uuid) {
+            Ok(i) => i,
+            Err(e) => return Json(ApiResponse::err(e)),
+        };
+        if before.service_type != ServiceType::FrankenPHP {
+            return Json(ApiResponse::err(format!(
+                "Xdebug is only available for PHP instances, not {}",
+                before.service_type.display_name()
+            )));
+        }
+
+        let config = mode.apply_to(&before.config);
+        let candidate = Instance {
+            config: config.clone(),
+            ..before.clone()
+        };
+        if let Err(e) = PhpIniOverrides::from_instance(&candidate) {
+            return Json(ApiResponse::err(e));
+        }
+        match config_store.update_instance_config(uuid, config) {
+            Ok(instance) => {
+                activity::record_changes(&before, &instance);
+                instance
+            }
+            Err(e) => return Json(ApiResponse::err(e)),
+        }
+    };
+
+    let running = match state.inner.process_manager.lock() {
+        Ok(pm) => pm.get_status(&updated).running,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire process manager lock")),
+    };
+    if running {
+        if let Json(ApiResponse {
+            success: false,
+            error: Some(e),
+            ..
+        }) = restart(State(state.clone()), Path(id)).await
+        {
+            return Json(ApiResponse::err(e));
+        }
+    }
+
+    match xdebug_status(&updated, running) {
+        Ok(status) => Json(ApiResponse::ok(status)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
@@ -5,8 +5,9 @@
 //! the desktop IPC layer.
 
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -17,7 +18,7 @@ use std::sync::Arc;
 use crate::api::{state::ApiState, types::ApiResponse};
 use crate::commands::mail::{MailMessageDetail, MailMessageList, SmtpConfig};
 use crate::commands::AppState;
-use crate::config::ServiceType;
+use crate::services::mailpit::MailpitService;
 
 static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
     reqwest::Client::builder()
@@ -31,46 +32,36 @@ struct MailpitPorts {
     smtp_port: u16,
 }
 
-/// Locate the (single) Mailpit instance and confirm it's running.
+/// Locate the Mailpit instance to use and confirm it's running.
 ///
-/// Returns an error string suitable for a 503 response when Mailpit is either
-/// not configured at all or configured but not running — the caller can't do
-/// anything useful either way.
-fn get_mailpit_ports(state: &Arc<AppState>) -> Result<MailpitPorts, String> {
+/// With an `instance_id` only that Mailpit is considered; otherwise the first
+/// running Mailpit wins. Returns an error string suitable for a 503 response
+/// when Mailpit is either not configured at all or configured but not
+/// running — the caller can't do anything useful either way.
+fn get_mailpit_ports(
+    state: &Arc<AppState>,
+    instance_id: Option<&str>,
+) -> Result<MailpitPorts, String> {
     let config_store = state
         .config_store
         .lock()
         .map_err(|_| "Failed to lock config")?;
     let config = config_store.load().map_err(|e| e.to_string())?;
 
-    let mailpit = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit)
-        .ok_or("No Mailpit instance configured")?;
+    let candidates = MailpitService::candidates(&config, instance_id)?;
 
     let process_manager = state
         .process_manager
         .lock()
         .map_err(|_| "Failed to lock process manager")?;
-    if !process_manager.is_running(&mailpit.id) {
-        return Err("Mailpit is not running".to_string());
-    }
-
-    // `smtp_port` is historically stored as a string; accept either shape.
-    let smtp_port = mailpit
-        .config
-        .get("smtp_port")
-        .and_then(|v| {
-            v.as_str()
-                .and_then(|s| s.parse::<u16>().ok())
-                .or_else(|| v.as_u64().map(|n| n as u16))
-        })
-        .unwrap_or(1025);
+    let mailpit = candidates
+        .into_iter()
+        .find(|i| process_manager.is_running(&i.id))
+        .ok_or("Mailpit is not running")?;
 
     Ok(MailpitPorts {
         http_port: mailpit.port,
-        smtp_port,
+        smtp_port: MailpitService::smtp_port(mailpit),
     })
 }
 
@@ -90,8 +81,17 @@ fn upstream_err(msg: impl Into<String>) -> Response {
         .into_response()
 }
 
+/// Selects a specific Mailpit instance; the first running one is used if omitted
+#[derive(Deserialize)]
+pub struct MailpitQuery {
+    #[serde(default)]
+    pub instance_id: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct ListQuery {
+    #[serde(default)]
+    pub instance_id: Option<String>,
     #[serde(default)]
     pub start: Option<u32>,
     #[serde(default)]
@@ -100,6 +100,17 @@ pub struct ListQuery {
     pub search: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub instance_id: Option<String>,
+    pub query: String,
+    #[serde(default)]
+    pub start: Option<u32>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
 #[derive(Deserialize)]
 pub struct MarkReadRequest {
     pub ids: Vec<String>,
@@ -111,9 +122,14 @@ pub struct UnreadCount {
     pub unread: u32,
 }
 
+#[derive(Serialize)]
+pub struct HtmlSource {
+    pub html: String,
+}
+
 /// GET /mail/config - SMTP + HTTP ports for Mailpit
-pub async fn config(State(state): State<ApiState>) -> Response {
-    match get_mailpit_ports(&state.inner) {
+pub async fn config(State(state): State<ApiState>, Query(q): Query<MailpitQuery>) -> Response {
+    match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => Json(ApiResponse::ok(SmtpConfig {
             host: "127.0.0.1".to_string(),
             port: p.smtp_port,
@@ -126,7 +142,7 @@ pub async fn config(State(state): State<ApiState>) -> Response {
 
 /// GET /mail - list captured messages (with optional search/pagination)
 pub async fn list(State(state): State<ApiState>, Query(q): Query<ListQuery>) -> Response {
-    let port = match get_mailpit_ports(&state.inner) {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => p.http_port,
         Err(e) => return unavailable(e),
     };
@@ -165,8 +181,12 @@ pub async fn list(State(state): State<ApiState>, Query(q): Query<ListQuery>) ->
 }
 
 /// GET /mail/:id - single message detail
-pub async fn get(State(state): State<ApiState>, Path(id): Path<String>) -> Response {
-    let port = match get_mailpit_ports(&state.inner) {
+pub async fn get(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(q): Query<MailpitQuery>,
+) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => p.http_port,
         Err(e) => return unavailable(e),
     };
@@ -186,8 +206,12 @@ pub async fn get(State(state): State<ApiState>, Path(id): Path<String>) -> Respo
 }
 
 /// DELETE /mail/:id - delete a single message
-pub async fn delete_one(State(state): State<ApiState>, Path(id): Path<String>) -> Response {
-    let port = match get_mailpit_ports(&state.inner) {
+pub async fn delete_one(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(q): Query<MailpitQuery>,
+) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => p.http_port,
         Err(e) => return unavailable(e),
     };
@@ -215,8 +239,8 @@ pub async fn delete_one(State(state): State<ApiState>, Path(id): Path<String>) -
 }
 
 /// DELETE /mail - delete all messages
-pub async fn delete_all(State(state): State<ApiState>) -> Response {
-    let port = match get_mailpit_ports(&state.inner) {
+pub async fn delete_all(State(state): State<ApiState>, Query(q): Query<MailpitQuery>) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => p.http_port,
         Err(e) => return unavailable(e),
     };
@@ -235,9 +259,10 @@ pub async fn delete_all(State(state): State<ApiState>) -> Response {
 /// PUT /mail/read - mark a set of messages read/unread
 pub async fn mark_read(
     State(state): State<ApiState>,
+    Query(q): Query<MailpitQuery>,
     Json(req): Json<MarkReadRequest>,
 ) -> Response {
-    let port = match get_mailpit_ports(&state.inner) {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => p.http_port,
         Err(e) => return unavailable(e),
     };
@@ -270,8 +295,11 @@ pub async fn mark_read(
 }
 
 /// GET /mail/unread-count
-pub async fn unread_count(State(state): State<ApiState>) -> Response {
-    let port = match get_mailpit_ports(&state.inner) {
+pub async fn unread_count(
+    State(state): State<ApiState>,
+    Query(q): Query<MailpitQuery>,
+) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
         Ok(p) => p.http_port,
         Err(e) => return unavailable(e),
     };
@@ -289,3 +317,129 @@ pub async fn unread_count(State(state): State<ApiState>) -> Response {
         Err(e) => upstream_err(format!("Failed to parse response: {}", e)),
     }
 }
+
+/// GET /mail/search - full-text search using Mailpit's search syntax
+pub async fn search(State(state): State<ApiState>, Query(q): Query<SearchQuery>) -> Response {
+    if q.query.trim().is_empty() {
+        return Json(ApiResponse::<()>::err("Search query is required")).into_response();
+    }
+    list(
+        State(state),
+        Query(ListQuery {
+            instance_id: q.instance_id,
+            start: q.start,
+            limit: q.limit,
+            search: Some(q.query),
+        }),
+    )
+    .await
+}
+
+/// Pass a file Mailpit serves through unchanged, keeping its content type
+async fn passthrough(url: &str, what: &str, disposition: Option<String>) -> Response {
+    let resp = match HTTP_CLIENT.get(url).send().await {
+        Ok(r) => r,
+        Err(e) => return upstream_err(format!("Failed to fetch {}: {}", what, e)),
+    };
+    if !resp.status().is_success() {
+        return upstream_err(format!("Mailpit API error: {}", resp.status()));
+    }
+
+    let content_type = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .cloned()
+        .unwrap_or(header::HeaderValue::from_static("application/octet-stream"));
+    let disposition = disposition.or_else(|| {
+        resp.headers()
+            .get(header::CONTENT_DISPOSITION)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    });
+
+    let bytes = match resp.bytes().await {
+        Ok(b) => b,
+        Err(e) => return upstream_err(format!("Failed to read {}: {}", what, e)),
+    };
+
+    let mut builder = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type);
+    if let Some(disposition) = disposition {
+        builder = builder.header(header::CONTENT_DISPOSITION, disposition);
+    }
+    builder.body(Body::from(bytes)).unwrap()
+}
+
+/// GET /mail/messages/:id/raw - the message source as an .eml download
+pub async fn raw(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(q): Query<MailpitQuery>,
+) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
+        Ok(p) => p.http_port,
+        Err(e) => return unavailable(e),
+    };
+
+    let url = format!("http://127.0.0.1:{}/api/v1/message/{}/raw", port, id);
+    let mut resp = passthrough(
+        &url,
+        "message source",
+        Some(format!("attachment; filename=\"{}.eml\"", id)),
+    )
+    .await;
+    if resp.status().is_success() {
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("message/rfc822"),
+        );
+    }
+    resp
+}
+
+/// GET /mail/messages/:id/html - the HTML part as sent
+pub async fn html(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(q): Query<MailpitQuery>,
+) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
+        Ok(p) => p.http_port,
+        Err(e) => return unavailable(e),
+    };
+
+    let url = format!("http://127.0.0.1:{}/api/v1/message/{}", port, id);
+    let resp = match HTTP_CLIENT.get(&url).send().await {
+        Ok(r) => r,
+        Err(e) => return upstream_err(format!("Failed to fetch email: {}", e)),
+    };
+    if !resp.status().is_success() {
+        return upstream_err(format!("Mailpit API error: {}", resp.status()));
+    }
+    match resp.json::<MailMessageDetail>().await {
+        Ok(data) if data.html.is_empty() => {
+            Json(ApiResponse::<()>::err("This message has no HTML part")).into_response()
+        }
+        Ok(data) => Json(ApiResponse::ok(HtmlSource { html: data.html })).into_response(),
+        Err(e) => upstream_err(format!("Failed to parse response: {}", e)),
+    }
+}
+
+/// GET /mail/messages/:id/attachments/:part_id - download an attachment
+pub async fn attachment(
+    State(state): State<ApiState>,
+    Path((id, part_id)): Path<(String, String)>,
+    Query(q): Query<MailpitQuery>,
+) -> Response {
+    let port = match get_mailpit_ports(&state.inner, q.instance_id.as_deref()) {
+        Ok(p) => p.http_port,
+        Err(e) => return unavailable(e),
+    };
+
+    let url = format!(
+        "http://127.0.0.1:{}/api/v1/message/{}/part/{}",
+        port, id, part_id
+    );
+    passthrough(&url, "attachment", None).await
+}
@@ -0,0 +1,148 @@
+//! Object storage (MinIO) API handlers
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::{state::ApiState, types::ApiResponse};
+use crate::minio_manager::{AccessKey, BucketInfo, BucketPolicy, MinioManager, DEFAULT_KEY_HOURS};
+
+#[derive(Deserialize)]
+pub struct CreateBucketRequest {
+    pub name: String,
+    /// `private` (default), `public-read`, or `public`
+    #[serde(default)]
+    pub policy: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SetPolicyRequest {
+    pub policy: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateKeyRequest {
+    /// Limit the key to one bucket
+    #[serde(default)]
+    pub bucket: Option<String>,
+    #[serde(default)]
+    pub hours: Option<u32>,
+}
+
+/// Resolve a running MinIO instance by ID
+fn get_manager(state: &ApiState, id: &str) -> Result<MinioManager, String> {
+    let uuid = Uuid::parse_str(id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = state
+        .inner
+        .config_store
+        .lock()
+        .map_err(|_| "Failed to acquire config lock")?;
+    let process_manager = state
+        .inner
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock")?;
+    let config = config_store
+        .load()
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+
+    let instance = config
+        .instances
+        .iter()
+        .find(|i| i.id == uuid)
+        .ok_or("Instance not found")?;
+    let manager = MinioManager::for_instance(instance)?;
+
+    if !process_manager.get_status(instance).running {
+        return Err("Instance is not running".to_string());
+    }
+
+    Ok(manager)
+}
+
+/// Run a manager call off the async runtime (the S3 client blocks)
+async fn with_manager<T: Send + 'static>(
+    state: &ApiState,
+    id: &str,
+    f: impl FnOnce(MinioManager) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let manager = get_manager(state, id)?;
+    tokio::task::spawn_blocking(move || f(manager))
+        .await
+        .unwrap_or_else(|e| Err(format!("Task error: {}", e)))
+}
+
+/// GET /minio/:id/buckets - List buckets
+pub async fn list_buckets(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<Vec<BucketInfo>>> {
+    match with_manager(&state, &id, |m| m.list_buckets()).await {
+        Ok(buckets) => Json(ApiResponse::ok(buckets)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /minio/:id/buckets - Create a bucket
+pub async fn create_bucket(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateBucketRequest>,
+) -> Json<ApiResponse<()>> {
+    let policy = match BucketPolicy::parse(req.policy.as_deref().unwrap_or_default()) {
+        Ok(policy) => policy,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+    match with_manager(&state, &id, move |m| m.create_bucket(&req.name, policy)).await {
+        Ok(()) => Json(ApiResponse::ok(())),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// DELETE /minio/:id/buckets/:bucket - Delete an empty bucket
+pub async fn delete_bucket(
+    State(state): State<ApiState>,
+    Path((id, bucket)): Path<(String, String)>,
+) -> Json<ApiResponse<()>> {
+    match with_manager(&state, &id, move |m| m.delete_bucket(&bucket)).await {
+        Ok(()) => Json(ApiResponse::ok(())),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// PUT /minio/:id/buckets/:bucket/policy - Apply an anonymous access preset
+pub async fn set_policy(
+    State(state): State<ApiState>,
+    Path((id, bucket)): Path<(String, String)>,
+    Json(req): Json<SetPolicyRequest>,
+) -> Json<ApiResponse<()>> {
+    let policy = match BucketPolicy::parse(&req.policy) {
+        Ok(policy) => policy,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+    match with_manager(&state, &id, move |m| m.set_policy(&bucket, policy)).await {
+        Ok(()) => Json(ApiResponse::ok(())),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /minio/:id/keys - Generate temporary access keys
+pub async fn create_key(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateKeyRequest>,
+) -> Json<ApiResponse<AccessKey>> {
+    let hours = req.hours.unwrap_or(DEFAULT_KEY_HOURS);
+    let result = with_manager(&state, &id, move |m| {
+        m.create_access_key(req.bucket.as_deref(), hours)
+    })
+    .await;
+    match result {
+        Ok(key) => Json(ApiResponse::ok(key)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
@@ -2,7 +2,15 @@
 
 pub mod databases;
 pub mod domains;
+pub mod env;
+pub mod events;
+pub mod health;
 pub mod instances;
 pub mod mail;
+pub mod minio;
+pub mod queues;
 pub mod services;
+pub mod snapshots;
+pub mod stacks;
 pub mod status;
+pub mod wake;
@@ -0,0 +1,136 @@
+//! Queue (Beanstalkd) API handlers
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::{state::ApiState, types::ApiResponse};
+use crate::queue_manager::{BeanstalkdManager, JobInfo, PeekState, TubeStats, DEFAULT_KICK_BOUND};
+
+#[derive(Deserialize)]
+pub struct PeekQuery {
+    #[serde(default)]
+    pub state: Option<PeekState>,
+}
+
+#[derive(Deserialize)]
+pub struct KickTubeRequest {
+    #[serde(default)]
+    pub bound: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct KickResult {
+    pub kicked: u64,
+}
+
+/// Resolve a running Beanstalkd instance by ID
+fn get_manager(state: &ApiState, id: &str) -> Result<BeanstalkdManager, String> {
+    let uuid = Uuid::parse_str(id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = state
+        .inner
+        .config_store
+        .lock()
+        .map_err(|_| "Failed to acquire config lock")?;
+    let process_manager = state
+        .inner
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock")?;
+    let config = config_store
+        .load()
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+
+    let instance = config
+        .instances
+        .iter()
+        .find(|i| i.id == uuid)
+        .ok_or("Instance not found")?;
+    let manager = BeanstalkdManager::for_instance(instance)?;
+
+    if !process_manager.get_status(instance).running {
+        return Err("Instance is not running".to_string());
+    }
+
+    Ok(manager)
+}
+
+/// GET /queues/:id/tubes - List tubes with job counts
+pub async fn list_tubes(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<Vec<TubeStats>>> {
+    let result = get_manager(&state, &id).and_then(|m| m.list_tubes());
+    match result {
+        Ok(tubes) => Json(ApiResponse::ok(tubes)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// GET /queues/:id/tubes/:tube/peek?state=ready|delayed|buried - Peek at the next job
+pub async fn peek(
+    State(state): State<ApiState>,
+    Path((id, tube)): Path<(String, String)>,
+    Query(q): Query<PeekQuery>,
+) -> Json<ApiResponse<Option<JobInfo>>> {
+    let peek_state = q.state.unwrap_or(PeekState::Ready);
+    let result = get_manager(&state, &id).and_then(|m| m.peek(&tube, peek_state));
+    match result {
+        Ok(job) => Json(ApiResponse::ok(job)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /queues/:id/tubes/:tube/kick - Kick buried (or delayed) jobs in a tube
+pub async fn kick_tube(
+    State(state): State<ApiState>,
+    Path((id, tube)): Path<(String, String)>,
+    Json(req): Json<KickTubeRequest>,
+) -> Json<ApiResponse<KickResult>> {
+    let bound = req.bound.unwrap_or(DEFAULT_KICK_BOUND);
+    let result = get_manager(&state, &id).and_then(|m| m.kick_tube(&tube, bound));
+    match result {
+        Ok(kicked) => Json(ApiResponse::ok(KickResult { kicked })),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// GET /queues/:id/jobs/:job_id - Get a job with its stats
+pub async fn get_job(
+    State(state): State<ApiState>,
+    Path((id, job_id)): Path<(String, u64)>,
+) -> Json<ApiResponse<JobInfo>> {
+    let result = get_manager(&state, &id).and_then(|m| m.get_job(job_id));
+    match result {
+        Ok(job) => Json(ApiResponse::ok(job)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /queues/:id/jobs/:job_id/bury - Bury a ready or delayed job
+pub async fn bury_job(
+    State(state): State<ApiState>,
+    Path((id, job_id)): Path<(String, u64)>,
+) -> Json<ApiResponse<()>> {
+    let result = get_manager(&state, &id).and_then(|m| m.bury_job(job_id));
+    match result {
+        Ok(()) => Json(ApiResponse::success()),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /queues/:id/jobs/:job_id/kick - Kick a buried or delayed job
+pub async fn kick_job(
+    State(state): State<ApiState>,
+    Path((id, job_id)): Path<(String, u64)>,
+) -> Json<ApiResponse<()>> {
+    let result = get_manager(&state, &id).and_then(|m| m.kick_job(job_id));
+    match result {
+        Ok(()) => Json(ApiResponse::success()),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
@@ -1,13 +1,14 @@
 //! Service API handlers
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::api::{state::ApiState, types::ApiResponse};
 use crate::commands::parse_service_type;
+use crate::port_allocator;
 use crate::service_config::ServiceRegistry;
 
 /// Service info response
@@ -20,6 +21,8 @@ pub struct ServiceInfo {
     pub max_instances: Option<usize>,
     pub internal: bool,
     pub auto_create_domain: bool,
+    /// Loaded from a user service definition
+    pub user_defined: bool,
 }
 
 /// Service versions response
@@ -29,6 +32,21 @@ pub struct ServiceVersions {
     pub installed: Vec<String>,
 }
 
+/// Installed binary response
+#[derive(Debug, Serialize)]
+pub struct InstalledBinary {
+    pub service_type: String,
+    pub version: String,
+    pub path: String,
+}
+
+/// Suggested port response
+#[derive(Debug, Serialize)]
+pub struct SuggestedPort {
+    pub service_type: String,
+    pub port: u16,
+}
+
 /// GET /services - List all available service types
 pub async fn list(State(_state): State<ApiState>) -> Json<ApiResponse<Vec<ServiceInfo>>> {
     let registry = ServiceRegistry::load();
@@ -45,6 +63,7 @@ pub async fn list(State(_state): State<ApiState>) -> Json<ApiResponse<Vec<Servic
             max_instances: config.max_instances,
             internal: config.internal,
             auto_create_domain: config.auto_create_domain,
+            user_defined: config.definition_path.is_some(),
         })
         .collect();
 
@@ -79,3 +98,129 @@ pub async fn get_versions(
         installed,
     }))
 }
+
+/// Query parameters for the suggest-port endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct SuggestPortQuery {
+    /// Port to return if it's free
+    pub preferred: Option<u16>,
+}
+
+/// GET /services/:service_type/suggest-port - Suggest a free port for a new instance
+///
+/// Returns `?preferred=<port>` when it's free.
+pub async fn suggest_port(
+    State(state): State<ApiState>,
+    Path(service_type): Path<String>,
+    Query(query): Query<SuggestPortQuery>,
+) -> Json<ApiResponse<SuggestedPort>> {
+    let svc_type = match parse_service_type(&service_type) {
+        Ok(t) => t,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
+    let config = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+
+        match config_store.load() {
+            Ok(c) => c,
+            Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+        }
+    };
+
+    match port_allocator::resolve_port(&config, svc_type, query.preferred, true) {
+        Ok(port) => Json(ApiResponse::ok(SuggestedPort { service_type, port })),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// Request body for the install endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct InstallRequest {
+    /// Version to install; the newest on the service's release channel when omitted
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// POST /services/:service_type/install - Download and install a service binary
+///
+/// Responds once the binary is installed. Progress is published as `download`
+/// events on `/events` in the meantime.
+pub async fn install(
+    State(state): State<ApiState>,
+    Path(service_type): Path<String>,
+    Json(req): Json<InstallRequest>,
+) -> Json<ApiResponse<InstalledBinary>> {
+    let svc_type = match parse_service_type(&service_type) {
+        Ok(t) => t,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
+    let (channel, arch_override) = {
+        let config_store = match state.inner.config_store.lock() {
+            Ok(cs) => cs,
+            Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+        };
+
+        match config_store.load() {
+            Ok(c) => (
+                c.release_channel(svc_type),
+                c.arch_overrides.get(&svc_type).copied(),
+            ),
+            Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+        }
+    };
+
+    let binary_manager = match state.inner.binary_manager.lock() {
+        Ok(bm) => bm.clone(),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire binary manager lock")),
+    };
+
+    let version = match req.version {
+        Some(version) => version,
+        None => match binary_manager
+            .get_available_versions(svc_type, channel)
+            .await
+        {
+            Ok(versions) => {
+                let latest = versions.iter().position(|v| v.is_latest).unwrap_or(0);
+                match versions.into_iter().nth(latest) {
+                    Some(latest) => latest.version,
+                    None => {
+                        return Json(ApiResponse::err(format!(
+                            "No versions of {} are available",
+                            service_type
+                        )))
+                    }
+                }
+            }
+            Err(e) => return Json(ApiResponse::err(e)),
+        },
+    };
+
+    let binary_info = match binary_manager
+        .download(svc_type, &version, arch_override, None)
+        .await
+    {
+        Ok(info) => info,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+    let installed = InstalledBinary {
+        service_type,
+        version: binary_info.version.clone(),
+        path: binary_info.path.clone(),
+    };
+
+    let config_store = match state.inner.config_store.lock() {
+        Ok(cs) => cs,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    if let Err(e) = config_store.update_binary_info(svc_type, binary_info) {
+        return Json(ApiResponse::err(e));
+    }
+
+    Json(ApiResponse::ok(installed))
+}
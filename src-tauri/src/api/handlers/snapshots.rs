@@ -0,0 +1,116 @@
+//! Snapshot API handlers
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::{state::ApiState, types::ApiResponse};
+use crate::config::Instance;
+use crate::snapshot::{self, SnapshotInfo};
+
+#[derive(Deserialize)]
+pub struct CreateSnapshotRequest {
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Resolve an instance with its running state and the snapshot retention
+fn resolve_instance(state: &ApiState, id: &str) -> Result<(Instance, bool, usize), String> {
+    let uuid = Uuid::parse_str(id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = state
+        .inner
+        .config_store
+        .lock()
+        .map_err(|_| "Failed to acquire config lock")?;
+    let process_manager = state
+        .inner
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock")?;
+    let config = config_store
+        .load()
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+
+    let instance = config
+        .instances
+        .into_iter()
+        .find(|i| i.id == uuid)
+        .ok_or("Instance not found")?;
+    let running = process_manager.get_status(&instance).running;
+
+    Ok((instance, running, config.snapshot_retention))
+}
+
+/// GET /instances/:id/snapshots - List snapshots, newest first
+pub async fn list(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<Vec<SnapshotInfo>>> {
+    let result =
+        resolve_instance(&state, &id).and_then(|(i, _, _)| snapshot::list_snapshots(&i.id));
+    match result {
+        Ok(snapshots) => Json(ApiResponse::ok(snapshots)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /instances/:id/snapshots - Create a snapshot
+pub async fn create(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(req): Json<CreateSnapshotRequest>,
+) -> Json<ApiResponse<SnapshotInfo>> {
+    let (instance, running, retention) = match resolve_instance(&state, &id) {
+        Ok(resolved) => resolved,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+    let label = req.label;
+
+    let result = tokio::task::spawn_blocking(move || {
+        snapshot::create_snapshot(&instance, running, label, retention)
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Task error: {}", e)));
+    match result {
+        Ok(info) => Json(ApiResponse::ok(info)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /instances/:id/snapshots/:snapshot_id/restore - Restore a snapshot
+pub async fn restore(
+    State(state): State<ApiState>,
+    Path((id, snapshot_id)): Path<(String, String)>,
+) -> Json<ApiResponse<SnapshotInfo>> {
+    let (instance, running, _) = match resolve_instance(&state, &id) {
+        Ok(resolved) => resolved,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
+    let result = tokio::task::spawn_blocking(move || {
+        snapshot::restore_snapshot(&instance, &snapshot_id, running)
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Task error: {}", e)));
+    match result {
+        Ok(info) => Json(ApiResponse::ok(info)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// DELETE /instances/:id/snapshots/:snapshot_id - Delete a snapshot
+pub async fn remove(
+    State(state): State<ApiState>,
+    Path((id, snapshot_id)): Path<(String, String)>,
+) -> Json<ApiResponse<()>> {
+    let result = resolve_instance(&state, &id)
+        .and_then(|(i, _, _)| snapshot::delete_snapshot(&i.id, &snapshot_id));
+    match result {
+        Ok(()) => Json(ApiResponse::success()),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
@@ -0,0 +1,214 @@
+//! Stack API handlers
+//!
+//! Stacks come with their instance counts and the combined resource usage of
+//! their running instances. `POST /stacks/{id}/start|stop|restart` acts on a
+//! stack as a unit, in dependency order: databases and other backing
+//! services start before the apps that use them and stop after them.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use super::instances::{self, StartInstanceQuery};
+use crate::api::{state::ApiState, types::ApiResponse};
+use crate::autostart;
+use crate::commands::{StackActionResult, StackInfo};
+
+/// GET /stacks - List stacks with their resource usage
+pub async fn list(State(state): State<ApiState>) -> Json<ApiResponse<Vec<StackInfo>>> {
+    let config = match state.inner.config_store.lock() {
+        Ok(cs) => cs.load(),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    let config = match config {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+    };
+    let process_manager = match state.inner.process_manager.lock() {
+        Ok(pm) => pm,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire process manager lock")),
+    };
+
+    let stacks = config
+        .stacks
+        .iter()
+        .map(|stack| StackInfo::new(stack, &config, &process_manager))
+        .collect();
+    Json(ApiResponse::ok(stacks))
+}
+
+/// GET /stacks/{id} - Get a stack with its resource usage
+pub async fn get(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<StackInfo>> {
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(u) => u,
+        Err(_) => return Json(ApiResponse::err("Invalid stack ID")),
+    };
+
+    let config = match state.inner.config_store.lock() {
+        Ok(cs) => cs.load(),
+        Err(_) => return Json(ApiResponse::err("Failed to acquire config lock")),
+    };
+    let config = match config {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::err(format!("Failed to load config: {}", e))),
+    };
+    let Some(stack) = config.stacks.iter().find(|s| s.id == uuid) else {
+        return Json(ApiResponse::err(format!("Stack {} not found", id)));
+    };
+    let process_manager = match state.inner.process_manager.lock() {
+        Ok(pm) => pm,
+        Err(_) => return Json(ApiResponse::err("Failed to acquire process manager lock")),
+    };
+
+    let info = StackInfo::new(stack, &config, &process_manager);
+    Json(ApiResponse::ok(info))
+}
+
+/// A stack member and whether it's running
+struct Member {
+    id: String,
+    name: String,
+    running: bool,
+    /// Later members need it healthy before they start
+    wait: bool,
+}
+
+/// Members of a stack (ID or name) in start order
+fn stack_members(state: &ApiState, stack: &str) -> Result<Vec<Member>, String> {
+    let config = state
+        .inner
+        .config_store
+        .lock()
+        .map_err(|_| "Failed to acquire config lock")?
+        .load()
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    let stack_id = config
+        .stacks
+        .iter()
+        .find(|s| s.id.to_string() == stack || s.name.eq_ignore_ascii_case(stack))
+        .map(|s| s.id)
+        .ok_or_else(|| format!("Stack '{}' not found", stack))?;
+    let process_manager = state
+        .inner
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock")?;
+
+    let members = autostart::stack_members(&config.instances, stack_id);
+    Ok(members
+        .iter()
+        .map(|i| Member {
+            id: i.id.to_string(),
+            name: i.name.clone(),
+            running: process_manager.get_status(i).running,
+            wait: autostart::starts_before_others(i, &members),
+        })
+        .collect())
+}
+
+/// Start the members that aren't running, each dependency healthy before
+/// the members that need it
+async fn start_members(state: &ApiState, members: &[Member]) -> Vec<StackActionResult> {
+    let mut results = Vec::new();
+    for member in members {
+        let error = if member.running {
+            None
+        } else {
+            let query = StartInstanceQuery {
+                wait: member.wait,
+                timeout: None,
+            };
+            instances::start(State(state.clone()), Path(member.id.clone()), Query(query))
+                .await
+                .0
+                .error
+        };
+        results.push(StackActionResult {
+            id: member.id.clone(),
+            name: member.name.clone(),
+            success: error.is_none(),
+            skipped: member.running,
+            error,
+        });
+    }
+    results
+}
+
+/// Stop the running members, apps before the services they depend on
+async fn stop_members(state: &ApiState, members: &[Member]) -> Vec<StackActionResult> {
+    let mut results = Vec::new();
+    for member in members.iter().rev() {
+        let error = if member.running {
+            instances::stop(State(state.clone()), Path(member.id.clone()))
+                .await
+                .0
+                .error
+        } else {
+            None
+        };
+        results.push(StackActionResult {
+            id: member.id.clone(),
+            name: member.name.clone(),
+            success: error.is_none(),
+            skipped: !member.running,
+            error,
+        });
+    }
+    results.reverse();
+    results
+}
+
+/// POST /stacks/{id}/start - Start every instance of a stack in dependency order
+pub async fn start(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<Vec<StackActionResult>>> {
+    match stack_members(&state, &id) {
+        Ok(members) => Json(ApiResponse::ok(start_members(&state, &members).await)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /stacks/{id}/stop - Stop every instance of a stack, apps first
+pub async fn stop(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<Vec<StackActionResult>>> {
+    match stack_members(&state, &id) {
+        Ok(members) => Json(ApiResponse::ok(stop_members(&state, &members).await)),
+        Err(e) => Json(ApiResponse::err(e)),
+    }
+}
+
+/// POST /stacks/{id}/restart - Stop a stack, then start it in dependency order
+pub async fn restart(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Json<ApiResponse<Vec<StackActionResult>>> {
+    let mut members = match stack_members(&state, &id) {
+        Ok(members) => members,
+        Err(e) => return Json(ApiResponse::err(e)),
+    };
+
+    let stopped = stop_members(&state, &members).await;
+    // Members that failed to stop are left running and reported as such
+    for (member, stop) in members.iter_mut().zip(&stopped) {
+        member.running = stop.error.is_some();
+    }
+    let started = start_members(&state, &members).await;
+
+    let results = started
+        .into_iter()
+        .zip(stopped)
+        .map(|(start, stop)| match stop.error {
+            Some(_) => stop,
+            None => start,
+        })
+        .collect();
+    Json(ApiResponse::ok(results))
+}
@@ -6,7 +6,7 @@ use crate::api::{
     state::ApiState,
     types::{ApiResponse, StatusResponse},
 };
-use crate::launchd;
+use crate::platform;
 
 /// GET /status - Get overall system status
 pub async fn get_status(State(state): State<ApiState>) -> Json<ApiResponse<StatusResponse>> {
@@ -47,7 +47,7 @@ pub async fn get_status(State(state): State<ApiState>) -> Json<ApiResponse<Statu
     };
 
     // Check if proxy daemon is installed
-    let proxy_installed = launchd::is_installed();
+    let proxy_installed = platform::proxy_daemon().is_installed();
 
     Json(ApiResponse::ok(StatusResponse {
         app_running: true,
@@ -0,0 +1,52 @@
+//! Wake-on-access API handler
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+};
+
+use crate::api::state::ApiState;
+
+/// ANY /wake/{domain_id} - Start a stopped wake-on-access instance
+///
+/// Caddy sends the 502s of wake-on-access sites here, keeping the visitor's
+/// Host header, and serves the page this returns.
+pub async fn wake(
+    State(state): State<ApiState>,
+    Path(domain_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let domain = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.split(':').next())
+        .unwrap_or_default()
+        .to_string();
+
+    let app_state = state.inner.clone();
+    let outcome =
+        tokio::task::spawn_blocking(move || crate::wake::wake_domain(&app_state, &domain_id))
+            .await
+            .ok();
+
+    match outcome.and_then(|outcome| outcome.page(&domain)) {
+        Some((status, html)) => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+            let mut builder = Response::builder()
+                .status(status)
+                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+                .header(header::CACHE_CONTROL, "no-store");
+            if status == StatusCode::SERVICE_UNAVAILABLE {
+                builder = builder.header(header::RETRY_AFTER, "2");
+            }
+            builder.body(Body::from(html)).unwrap()
+        }
+        None => Response::builder()
+            .status(StatusCode::BAD_GATEWAY)
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(Body::from("Service unavailable"))
+            .unwrap(),
+    }
+}
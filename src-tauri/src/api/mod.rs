@@ -2,34 +2,65 @@
 //!
 //! Provides a REST API on localhost:19840 for programmatic control.
 //! Used by the MCP CLI to expose Burd functionality to Claude and other AI agents.
+//! An opt-in remote mode (see `remote`) also exposes it to the LAN behind a token.
+//! Callers are rate limited and request bodies are capped (see `rate_limit`).
+//! `/events` streams instance, download, health and log events over a WebSocket.
+//! `/health` summarizes every subsystem for uptime monitors (503 when degraded).
 
 pub mod handlers;
+pub mod rate_limit;
+pub mod remote;
 pub mod state;
 pub mod types;
 
 use axum::{
-    routing::{delete, get, post, put},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{any, delete, get, post, put},
     Router,
 };
+use std::future::IntoFuture;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
 use crate::commands::AppState;
+use crate::config::ConfigStore;
+use rate_limit::RateLimiter;
+use remote::AccessPolicy;
 use state::ApiState;
 
 /// Default port for the API server
 pub const API_PORT: u16 = 19840;
 
 /// Create the API router with all routes
-pub fn create_router(app_state: Arc<AppState>) -> Router {
+pub fn create_router(app_state: Arc<AppState>, policy: AccessPolicy) -> Router {
     let api_state = ApiState::new(app_state);
+    let policy = Arc::new(policy);
+    let limiter = Arc::new(RateLimiter::new(policy.requires_token()));
+
+    // Caddy calls it for site visitors, who have no token, so it gets its own auth
+    let wake = Router::new()
+        .route("/wake/{domain_id}", any(handlers::wake::wake))
+        .layer(DefaultBodyLimit::max(rate_limit::MAX_BODY_BYTES))
+        .layer(middleware::from_fn_with_state(
+            limiter.clone(),
+            rate_limit::enforce,
+        ))
+        .layer(middleware::from_fn_with_state(
+            policy.clone(),
+            remote::enforce_wake,
+        ));
 
     Router::new()
         // Status
         .route("/status", get(handlers::status::get_status))
+        .route("/health", get(handlers::health::get_health))
+        // Event stream (WebSocket)
+        .route("/events", get(handlers::events::stream))
         // Instances
         .route("/instances", get(handlers::instances::list))
         .route("/instances", post(handlers::instances::create))
+        .route("/instances/bulk", post(handlers::instances::bulk))
         .route("/instances/{id}", get(handlers::instances::get))
         .route("/instances/{id}", put(handlers::instances::update))
         .route("/instances/{id}", delete(handlers::instances::remove))
@@ -40,17 +71,75 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
             post(handlers::instances::restart),
         )
         .route("/instances/{id}/logs", get(handlers::instances::logs))
+        .route(
+            "/instances/{id}/activity",
+            get(handlers::instances::activity),
+        )
+        .route("/instances/{id}/metrics", get(handlers::instances::metrics))
         .route("/instances/{id}/env", get(handlers::instances::env))
+        .route(
+            "/instances/{id}/centrifugo/setup",
+            post(handlers::instances::centrifugo_setup),
+        )
+        .route(
+            "/instances/{id}/soketi/setup",
+            post(handlers::instances::soketi_setup),
+        )
+        .route(
+            "/instances/{id}/keycloak/provision",
+            post(handlers::instances::keycloak_provision),
+        )
+        .route("/instances/{id}/seed", put(handlers::instances::set_seed))
+        .route(
+            "/instances/{id}/seed/run",
+            post(handlers::instances::run_seed),
+        )
+        .route(
+            "/instances/{id}/xdebug",
+            get(handlers::instances::get_xdebug).put(handlers::instances::set_xdebug),
+        )
+        // Stacks
+        .route("/stacks", get(handlers::stacks::list))
+        .route("/stacks/{id}", get(handlers::stacks::get))
+        .route("/stacks/{id}/start", post(handlers::stacks::start))
+        .route("/stacks/{id}/stop", post(handlers::stacks::stop))
+        .route("/stacks/{id}/restart", post(handlers::stacks::restart))
+        // Snapshots
+        .route(
+            "/instances/{id}/snapshots",
+            get(handlers::snapshots::list).post(handlers::snapshots::create),
+        )
+        .route(
+            "/instances/{id}/snapshots/{snapshot_id}",
+            delete(handlers::snapshots::remove),
+        )
+        .route(
+            "/instances/{id}/snapshots/{snapshot_id}/restore",
+            post(handlers::snapshots::restore),
+        )
         // Domains
         .route("/domains", get(handlers::domains::list))
         .route("/domains", post(handlers::domains::create))
+        .route("/domains/stats", get(handlers::domains::stats))
         .route("/domains/{id}", put(handlers::domains::update))
         .route("/domains/{id}", delete(handlers::domains::remove))
         .route("/domains/{id}/ssl", post(handlers::domains::toggle_ssl))
+        .route("/domains/{id}/stats", get(handlers::domains::domain_stats))
+        .route("/domains/{id}/requests", get(handlers::domains::requests))
+        // .env sync
+        .route("/env/sync", post(handlers::env::sync))
         // Databases
         .route("/databases", get(handlers::databases::list))
         .route("/databases", post(handlers::databases::create))
         .route("/databases/{name}", delete(handlers::databases::drop))
+        .route(
+            "/databases/{name}/users",
+            get(handlers::databases::list_users).post(handlers::databases::add_user),
+        )
+        .route(
+            "/databases/{name}/users/{user}",
+            put(handlers::databases::update_user).delete(handlers::databases::remove_user),
+        )
         // Mail (Mailpit)
         .route("/mail/config", get(handlers::mail::config))
         .route("/mail/unread-count", get(handlers::mail::unread_count))
@@ -59,30 +148,176 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
         .route("/mail/messages/read", post(handlers::mail::mark_read))
         .route("/mail/messages/{id}", get(handlers::mail::get))
         .route("/mail/messages/{id}", delete(handlers::mail::delete_one))
+        .route("/mail/messages/{id}/raw", get(handlers::mail::raw))
+        .route("/mail/messages/{id}/html", get(handlers::mail::html))
+        .route(
+            "/mail/messages/{id}/attachments/{part_id}",
+            get(handlers::mail::attachment),
+        )
+        .route("/mail/search", get(handlers::mail::search))
+        // Object storage (MinIO)
+        .route(
+            "/minio/{id}/buckets",
+            get(handlers::minio::list_buckets).post(handlers::minio::create_bucket),
+        )
+        .route(
+            "/minio/{id}/buckets/{bucket}",
+            delete(handlers::minio::delete_bucket),
+        )
+        .route(
+            "/minio/{id}/buckets/{bucket}/policy",
+            put(handlers::minio::set_policy),
+        )
+        .route("/minio/{id}/keys", post(handlers::minio::create_key))
+        // Queues (Beanstalkd)
+        .route("/queues/{id}/tubes", get(handlers::queues::list_tubes))
+        .route(
+            "/queues/{id}/tubes/{tube}/peek",
+            get(handlers::queues::peek),
+        )
+        .route(
+            "/queues/{id}/tubes/{tube}/kick",
+            post(handlers::queues::kick_tube),
+        )
+        .route("/queues/{id}/jobs/{job_id}", get(handlers::queues::get_job))
+        .route(
+            "/queues/{id}/jobs/{job_id}/bury",
+            post(handlers::queues::bury_job),
+        )
+        .route(
+            "/queues/{id}/jobs/{job_id}/kick",
+            post(handlers::queues::kick_job),
+        )
         // Services
         .route("/services", get(handlers::services::list))
         .route(
             "/services/{service_type}/versions",
             get(handlers::services::get_versions),
         )
+        .route(
+            "/services/{service_type}/suggest-port",
+            get(handlers::services::suggest_port),
+        )
+        .route(
+            "/services/{service_type}/install",
+            post(handlers::services::install),
+        )
+        // MCP over HTTP (SSE transport)
+        .merge(crate::mcp::http::router())
+        .layer(DefaultBodyLimit::max(rate_limit::MAX_BODY_BYTES))
+        .layer(middleware::from_fn_with_state(
+            limiter.clone(),
+            rate_limit::enforce,
+        ))
+        // Outermost, so unauthorized requests are rejected before being counted
+        .layer(middleware::from_fn_with_state(
+            policy.clone(),
+            remote::enforce,
+        ))
+        .merge(wake)
         .with_state(api_state)
 }
 
 /// Start the API server on localhost:19840
+///
+/// In remote mode it also listens on the configured bind address.
 pub async fn start_server(app_state: Arc<AppState>) -> Result<(), String> {
-    let router = create_router(app_state);
-    let addr = SocketAddr::from(([127, 0, 0, 1], API_PORT));
+    let config_store = ConfigStore::new()?;
+    let settings = config_store.load()?.api_remote;
+    let addrs = remote::listen_addrs(&settings, API_PORT)?;
+    let router = create_router(app_state.clone(), AccessPolicy::from_settings(&settings));
 
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .map_err(|e| format!("Failed to bind API server to {}: {}", addr, e))?;
+    // Feed health and log events to /events subscribers
+    tokio::spawn(crate::events::watch((*app_state).clone()));
+
+    // Publish config changes, whichever process makes them
+    if let Err(e) = crate::events::watch_config(config_store.path()) {
+        eprintln!("Config changes won't be published: {}", e);
+    }
 
-    println!("MCP API server listening on http://{}", addr);
+    let mut servers = Vec::new();
+    for addr in addrs {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("Failed to bind API server to {}: {}", addr, e))?;
 
-    // Run server (this will block until shutdown)
-    axum::serve(listener, router)
+        println!("MCP API server listening on http://{}", addr);
+        // Peer addresses let `/wake` tell Caddy from remote callers
+        let service = router
+            .clone()
+            .into_make_service_with_connect_info::<SocketAddr>();
+        servers.push(axum::serve(listener, service).into_future());
+    }
+    if settings.enabled {
+        println!("Remote API access enabled; requests require the API token");
+    }
+
+    // Run servers (this will block until shutdown)
+    futures_util::future::try_join_all(servers)
         .await
         .map_err(|e| format!("API server error: {}", e))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ApiRemoteSettings, Config};
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{header, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    fn remote_router() -> Router {
+        let config_path = std::env::temp_dir().join("burd-api-wake-test.json");
+        let config = Config::default();
+        let state = Arc::new(AppState::new(ConfigStore::at(config_path), &config));
+        let settings = ApiRemoteSettings {
+            enabled: true,
+            token: Some("secret-token".to_string()),
+            ..ApiRemoteSettings::default()
+        };
+        create_router(state, AccessPolicy::from_settings(&settings))
+    }
+
+    fn wake_from(peer: [u8; 4], authorization: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder().uri("/wake/not-a-domain");
+        if let Some(value) = authorization {
+            request = request.header(header::AUTHORIZATION, value);
+        }
+        let mut request = request.body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((peer, 50000))));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_wake_requires_token_from_remote_callers() {
+        let router = remote_router();
+
+        let response = router
+            .clone()
+            .oneshot(wake_from([192, 168, 1, 20], None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Caddy calls from loopback; an unknown domain gets the error page
+        let response = router
+            .clone()
+            .oneshot(wake_from([127, 0, 0, 1], None))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router
+            .oneshot(wake_from([192, 168, 1, 20], Some("Bearer secret-token")))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
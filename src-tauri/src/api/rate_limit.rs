@@ -0,0 +1,279 @@
+//! Rate limiting for the HTTP API
+//!
+//! Each caller (its bearer token in remote mode, otherwise the local client)
+//! gets token buckets: a generous one for ordinary requests and a tight one for
+//! requests that spawn or kill processes, so a looping agent can't restart
+//! instances back to back. Over the limit, the API answers 429 with
+//! `Retry-After`.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::types::ApiResponse;
+
+/// Largest request body accepted
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Buckets kept before idle ones are dropped
+const MAX_TRACKED_BUCKETS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Class {
+    General,
+    /// Start, stop, restart, bulk actions, snapshot create/restore, seeding,
+    /// Xdebug changes, .env sync and service installs
+    Lifecycle,
+}
+
+impl Class {
+    /// (burst, refill per second)
+    fn limit(self) -> (f64, f64) {
+        match self {
+            // 600 per minute
+            Class::General => (120.0, 10.0),
+            // 30 per minute
+            Class::Lifecycle => (10.0, 0.5),
+        }
+    }
+
+    fn of(method: &Method, path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        let lifecycle = match *method {
+            Method::POST => {
+                path == "/instances/bulk"
+                    || path == "/env/sync"
+                    || path.ends_with("/start")
+                    || path.ends_with("/stop")
+                    || path.ends_with("/restart")
+                    || path.ends_with("/restore")
+                    || path.ends_with("/snapshots")
+                    || path.ends_with("/seed/run")
+                    || (path.starts_with("/services/") && path.ends_with("/install"))
+            }
+            // Changing the Xdebug mode restarts FrankenPHP
+            Method::PUT => path.ends_with("/xdebug"),
+            _ => false,
+        };
+        if lifecycle {
+            Class::Lifecycle
+        } else {
+            Class::General
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-caller token buckets
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Key buckets by bearer token; only meaningful once tokens are checked
+    per_token: bool,
+    buckets: Mutex<HashMap<(String, Class), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_token: bool) -> Self {
+        Self {
+            per_token,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn take(&self, caller: &str, class: Class, now: Instant) -> Result<(), Duration> {
+        let (burst, per_second) = class.limit();
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Ok(());
+        };
+
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            // Buckets that have refilled carry no state worth keeping
+            buckets.retain(|(_, class), bucket| {
+                let (burst, per_second) = class.limit();
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + elapsed * per_second < burst
+            });
+        }
+
+        let bucket = buckets
+            .entry((caller.to_string(), class))
+            .or_insert(Bucket {
+                tokens: burst,
+                updated: now,
+            });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
+
+/// Middleware enforcing the per-caller limits
+pub async fn enforce(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // CORS preflights from allowed origins are answered by `remote::enforce` first
+    let caller = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .filter(|_| limiter.per_token)
+        .unwrap_or("local")
+        .to_string();
+    let class = Class::of(request.method(), request.uri().path());
+
+    match limiter.take(&caller, class, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after = (wait.as_secs_f64().ceil() as u64).max(1);
+            let message = match class {
+                Class::General => "Too many requests",
+                Class::Lifecycle => "Too many start/stop/restart requests",
+            };
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ApiResponse::<()>::err(format!(
+                    "{}; retry in {}s",
+                    message, retry_after
+                ))),
+            )
+                .into_response();
+            if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn drain(limiter: &RateLimiter, caller: &str, class: Class, now: Instant) {
+        let (burst, _) = class.limit();
+        for _ in 0..burst as usize {
+            assert!(limiter.take(caller, class, now).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(false);
+        let start = Instant::now();
+        drain(&limiter, "local", Class::General, start);
+
+        let wait = limiter.take("local", Class::General, start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(100));
+
+        // 10 per second come back, never more than the burst
+        let later = start + Duration::from_secs(1);
+        for _ in 0..10 {
+            assert!(limiter.take("local", Class::General, later).is_ok());
+        }
+        assert!(limiter.take("local", Class::General, later).is_err());
+
+        let much_later = later + Duration::from_secs(3600);
+        drain(&limiter, "local", Class::General, much_later);
+        assert!(limiter.take("local", Class::General, much_later).is_err());
+    }
+
+    #[test]
+    fn test_classes_have_separate_limits() {
+        assert_eq!(
+            Class::of(&Method::POST, "/instances/abc/start"),
+            Class::Lifecycle
+        );
+        assert_eq!(
+            Class::of(&Method::POST, "/instances/bulk/"),
+            Class::Lifecycle
+        );
+        assert_eq!(
+            Class::of(&Method::POST, "/instances/abc/snapshots"),
+            Class::Lifecycle
+        );
+        assert_eq!(
+            Class::of(&Method::GET, "/instances/abc/snapshots"),
+            Class::General
+        );
+        assert_eq!(
+            Class::of(&Method::POST, "/instances/abc/seed/run"),
+            Class::Lifecycle
+        );
+        assert_eq!(
+            Class::of(&Method::PUT, "/instances/abc/xdebug"),
+            Class::Lifecycle
+        );
+        assert_eq!(Class::of(&Method::POST, "/env/sync"), Class::Lifecycle);
+        assert_eq!(
+            Class::of(&Method::POST, "/services/mysql/install"),
+            Class::Lifecycle
+        );
+        assert_eq!(
+            Class::of(&Method::GET, "/instances/abc/xdebug"),
+            Class::General
+        );
+        assert_eq!(
+            Class::of(&Method::PUT, "/instances/abc/seed"),
+            Class::General
+        );
+        assert_eq!(Class::of(&Method::POST, "/instances"), Class::General);
+
+        let limiter = RateLimiter::new(true);
+        let now = Instant::now();
+        drain(&limiter, "Bearer a", Class::Lifecycle, now);
+        assert!(limiter.take("Bearer a", Class::Lifecycle, now).is_err());
+
+        // Other classes and other callers keep their own buckets
+        assert!(limiter.take("Bearer a", Class::General, now).is_ok());
+        assert!(limiter.take("Bearer b", Class::Lifecycle, now).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_bucket_answers_429() {
+        let router = Router::new()
+            .route("/instances/{id}/restart", post(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(RateLimiter::new(false)),
+                enforce,
+            ));
+        let restart = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/instances/abc/restart")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..10 {
+            let response = router.clone().oneshot(restart()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = router.clone().oneshot(restart()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+    }
+}
@@ -0,0 +1,327 @@
+//! Remote access mode for the HTTP API
+//!
+//! Off by default: the API listens on 127.0.0.1 only and trusts every caller.
+//! With `ApiRemoteSettings::enabled`, it also listens on the configured bind
+//! address and every request needs `Authorization: Bearer <token>` — including
+//! requests from loopback, since the Caddy proxy (used for HTTPS) connects
+//! from there too. Browsers on an allowed origin get CORS headers.
+//!
+//! `/wake` is the exception: Caddy calls it from loopback on behalf of site
+//! visitors, who have no token, so only callers elsewhere must present one.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use super::types::ApiResponse;
+use crate::config::ApiRemoteSettings;
+
+/// Methods and headers browsers may use on allowed origins
+const ALLOWED_METHODS: &str = "GET, POST, PUT, DELETE, OPTIONS";
+const ALLOWED_HEADERS: &str = "authorization, content-type";
+
+/// Generate a new API token
+pub fn generate_token() -> String {
+    use rand::distr::Alphanumeric;
+    use rand::Rng;
+
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect()
+}
+
+/// Normalize an origin to `scheme://host[:port]`
+fn normalize_origin(origin: &str) -> Result<String, String> {
+    let origin = origin.trim();
+    if origin == "*" {
+        return Err("Wildcard origins aren't allowed; list each origin".to_string());
+    }
+    let url = url::Url::parse(origin).map_err(|e| format!("Invalid origin '{}': {}", origin, e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Origin '{}' must use http or https", origin));
+    }
+    if url.path() != "/" || url.query().is_some() || url.fragment().is_some() {
+        return Err(format!(
+            "Origin '{}' must not include a path (e.g. https://dashboard.example.com)",
+            origin
+        ));
+    }
+    Ok(url.origin().ascii_serialization())
+}
+
+/// Validate and normalize settings before they are saved
+///
+/// Enabling remote mode without a token generates one.
+pub fn validate(mut settings: ApiRemoteSettings) -> Result<ApiRemoteSettings, String> {
+    let bind = settings.bind_address.trim();
+    bind.parse::<IpAddr>()
+        .map_err(|_| format!("Invalid bind address '{}'", bind))?;
+    settings.bind_address = bind.to_string();
+
+    let mut origins = Vec::new();
+    for origin in &settings.allowed_origins {
+        let origin = normalize_origin(origin)?;
+        if !origins.contains(&origin) {
+            origins.push(origin);
+        }
+    }
+    settings.allowed_origins = origins;
+
+    if settings.enabled && settings.token.as_deref().is_none_or(str::is_empty) {
+        settings.token = Some(generate_token());
+    }
+    Ok(settings)
+}
+
+/// Addresses the API listens on
+///
+/// Loopback is always included so the CLI and MCP server keep working.
+pub fn listen_addrs(settings: &ApiRemoteSettings, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let loopback = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    if !settings.enabled {
+        return Ok(vec![loopback]);
+    }
+    if settings.token.as_deref().is_none_or(str::is_empty) {
+        return Err(
+            "Remote API access is enabled but no token is set; run 'burd api remote' again"
+                .to_string(),
+        );
+    }
+
+    let ip: IpAddr = settings
+        .bind_address
+        .parse()
+        .map_err(|_| format!("Invalid API bind address '{}'", settings.bind_address))?;
+    let addr = SocketAddr::new(ip, port);
+    if ip.is_loopback() {
+        Ok(vec![addr])
+    } else if ip.is_unspecified() {
+        // 0.0.0.0 already covers loopback
+        Ok(vec![addr])
+    } else {
+        Ok(vec![loopback, addr])
+    }
+}
+
+/// Who may call the API, fixed when the server starts
+#[derive(Debug, Clone, Default)]
+pub struct AccessPolicy {
+    /// Required bearer token (remote mode only)
+    token: Option<String>,
+    allowed_origins: Vec<String>,
+}
+
+impl AccessPolicy {
+    pub fn from_settings(settings: &ApiRemoteSettings) -> Self {
+        Self {
+            token: settings.token.clone().filter(|_| settings.enabled),
+            allowed_origins: settings.allowed_origins.clone(),
+        }
+    }
+
+    /// Whether every request must carry the token
+    pub fn requires_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    fn origin_allowed(&self, origin: &str) -> bool {
+        self.allowed_origins.iter().any(|o| o == origin)
+    }
+
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()))
+    }
+}
+
+/// Compare without stopping at the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn add_cors_headers(response: &mut Response, origin: &HeaderValue) {
+    let headers = response.headers_mut();
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static(ALLOWED_METHODS),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static(ALLOWED_HEADERS),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from_static("600"),
+    );
+    headers.append(header::VARY, HeaderValue::from_static("Origin"));
+}
+
+fn reject(status: StatusCode, message: &str) -> Response {
+    (status, Json(ApiResponse::<()>::err(message))).into_response()
+}
+
+/// Middleware applying CORS and, in remote mode, token auth
+pub async fn enforce(
+    State(policy): State<Arc<AccessPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    let allowed_origin = origin
+        .as_ref()
+        .filter(|o| o.to_str().is_ok_and(|o| policy.origin_allowed(o)))
+        .cloned();
+
+    // A page on another origin must be listed explicitly in remote mode
+    if origin.is_some() && allowed_origin.is_none() && policy.token.is_some() {
+        return reject(StatusCode::FORBIDDEN, "Origin not allowed");
+    }
+
+    // Preflight requests carry no credentials
+    if request.method() == Method::OPTIONS {
+        if let Some(origin) = &allowed_origin {
+            let mut response = StatusCode::NO_CONTENT.into_response();
+            add_cors_headers(&mut response, origin);
+            return response;
+        }
+    }
+
+    let mut response = if policy.authorized(request.headers()) {
+        next.run(request).await
+    } else {
+        reject(StatusCode::UNAUTHORIZED, "Missing or invalid API token")
+    };
+    if let Some(origin) = &allowed_origin {
+        add_cors_headers(&mut response, origin);
+    }
+    response
+}
+
+/// Middleware for `/wake`: token auth for every caller but loopback
+///
+/// A request without a known peer address (served without connect info)
+/// counts as remote.
+pub async fn enforce_wake(
+    State(policy): State<Arc<AccessPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let loopback = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .is_some_and(|ConnectInfo(peer)| peer.ip().is_loopback());
+
+    if loopback || policy.authorized(request.headers()) {
+        next.run(request).await
+    } else {
+        reject(StatusCode::UNAUTHORIZED, "Missing or invalid API token")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::ConnectInfo, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "secret-token";
+
+    fn router(settings: &ApiRemoteSettings) -> Router {
+        Router::new()
+            .route("/status", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(AccessPolicy::from_settings(settings)),
+                enforce,
+            ))
+    }
+
+    fn remote_settings() -> ApiRemoteSettings {
+        ApiRemoteSettings {
+            enabled: true,
+            token: Some(TOKEN.to_string()),
+            ..ApiRemoteSettings::default()
+        }
+    }
+
+    async fn status(router: Router, peer: [u8; 4], authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/status");
+        if let Some(value) = authorization {
+            request = request.header(header::AUTHORIZATION, value);
+        }
+        let mut request = request.body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((peer, 50000))));
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_local_mode_trusts_loopback_callers() {
+        // Without remote mode the API only listens on loopback, so no token is needed
+        let settings = ApiRemoteSettings {
+            token: Some(TOKEN.to_string()),
+            ..ApiRemoteSettings::default()
+        };
+        assert!(!AccessPolicy::from_settings(&settings).requires_token());
+        assert_eq!(
+            status(router(&settings), [127, 0, 0, 1], None).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remote_mode_rejects_missing_token() {
+        let settings = remote_settings();
+        assert_eq!(
+            status(router(&settings), [192, 168, 1, 20], None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Caddy forwards HTTPS requests from loopback, so loopback needs it too
+        assert_eq!(
+            status(router(&settings), [127, 0, 0, 1], None).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remote_mode_rejects_wrong_token() {
+        let settings = remote_settings();
+        for authorization in ["Bearer wrong-token", "Bearer secret-toke", TOKEN] {
+            assert_eq!(
+                status(router(&settings), [192, 168, 1, 20], Some(authorization)).await,
+                StatusCode::UNAUTHORIZED,
+                "{}",
+                authorization
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_mode_accepts_token_from_remote_peer() {
+        let authorization = format!("Bearer {}", TOKEN);
+        assert_eq!(
+            status(
+                router(&remote_settings()),
+                [192, 168, 1, 20],
+                Some(&authorization)
+            )
+            .await,
+            StatusCode::OK
+        );
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::db_manager::DbPrivileges;
+
 /// Standard API response wrapper
 #[derive(Serialize)]
 pub struct ApiResponse<T: Serialize> {
@@ -46,13 +48,20 @@ impl ApiResponse<()> {
 #[derive(Deserialize)]
 pub struct CreateInstanceRequest {
     pub name: String,
-    pub port: u16,
+    /// Port to use; assigned automatically when omitted
+    #[serde(default)]
+    pub port: Option<u16>,
     pub service_type: String,
+    /// Version to use; the default (pinned or newest installed) when omitted
+    #[serde(default)]
     pub version: String,
     #[serde(default)]
     pub config: Option<serde_json::Value>,
     #[serde(default)]
     pub custom_domain: Option<String>,
+    /// Pick a free port instead of failing when `port` is already in use
+    #[serde(default)]
+    pub auto_assign_port: bool,
 }
 
 /// Update instance request
@@ -71,6 +80,18 @@ pub struct UpdateInstanceRequest {
     pub domain_enabled: Option<bool>,
     #[serde(default)]
     pub config: Option<serde_json::Value>,
+    /// Start the instance when the app or the headless daemon launches
+    #[serde(default)]
+    pub auto_start: Option<bool>,
+    /// Restart the instance when it crashes or fails its health check
+    #[serde(default)]
+    pub restart_on_failure: Option<bool>,
+    /// Replaces the instance's tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Replaces the arguments appended to the service's start arguments
+    #[serde(default)]
+    pub extra_args: Option<Vec<String>>,
 }
 
 /// Helper to deserialize a field that can be absent, null, or a value
@@ -105,6 +126,18 @@ pub struct UpdateDomainRequest {
     pub target_type: Option<String>,
     #[serde(default)]
     pub target_value: Option<String>,
+    /// Redirect HTTP to HTTPS (only applies when SSL is enabled)
+    #[serde(default)]
+    pub force_https: Option<bool>,
+    /// Send a Strict-Transport-Security header (only applies when SSL is enabled)
+    #[serde(default)]
+    pub hsts: Option<bool>,
+    /// Require a Burd-issued client certificate (only applies when SSL is enabled)
+    #[serde(default)]
+    pub require_client_cert: Option<bool>,
+    /// Raw Caddyfile directives for the site; an empty string clears them
+    #[serde(default)]
+    pub custom_directives: Option<String>,
 }
 
 /// Toggle SSL request
@@ -122,6 +155,27 @@ pub struct CreateDatabaseRequest {
     pub instance_id: Option<String>,
 }
 
+/// Create (if needed) a database user and grant it access to a database
+#[derive(Deserialize)]
+pub struct CreateDatabaseUserRequest {
+    pub username: String,
+    /// Password for a new user; generated (and returned) when omitted
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Grant read access only
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Change a database user's password and/or its privileges on the database
+#[derive(Deserialize)]
+pub struct UpdateDatabaseUserRequest {
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub read_only: Option<bool>,
+}
+
 /// Status response
 #[derive(Serialize)]
 pub struct StatusResponse {
@@ -133,6 +187,44 @@ pub struct StatusResponse {
     pub running_instances: usize,
 }
 
+/// Result of one subsystem check in the health report
+#[derive(Serialize)]
+pub struct HealthCheckResult {
+    pub ok: bool,
+    pub detail: String,
+}
+
+impl HealthCheckResult {
+    pub fn new(ok: bool, detail: impl Into<String>) -> Self {
+        Self {
+            ok,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Running instances and those failing their health check
+#[derive(Serialize)]
+pub struct InstancesHealth {
+    pub ok: bool,
+    pub total: usize,
+    pub running: usize,
+    /// Names of running instances that fail their health check
+    pub unhealthy: Vec<String>,
+}
+
+/// Health report of every Burd subsystem
+#[derive(Serialize)]
+pub struct HealthResponse {
+    /// `ok` when every check passes, `degraded` otherwise
+    pub status: &'static str,
+    pub api: HealthCheckResult,
+    pub dns: HealthCheckResult,
+    pub proxy: HealthCheckResult,
+    pub helper: HealthCheckResult,
+    pub instances: InstancesHealth,
+}
+
 /// Instance response (simplified for API)
 #[derive(Serialize)]
 pub struct InstanceResponse {
@@ -175,6 +267,17 @@ pub struct ServiceVersionsResponse {
     pub installed: Vec<String>,
 }
 
+/// Database user response
+#[derive(Serialize)]
+pub struct DatabaseUserResponse {
+    pub username: String,
+    pub database: String,
+    pub privileges: Option<DbPrivileges>,
+    /// Set only when Burd generated the password
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
 /// Database info response
 #[derive(Serialize)]
 pub struct DatabaseResponse {
@@ -3,38 +3,152 @@
 //! Shared by the MCP server and the `burd` CLI. The daemon binds to
 //! 127.0.0.1:19840 and returns `{ "success": bool, "data"|"error": … }`
 //! envelopes; `handle_response` unwraps that envelope for callers.
+//! When remote API access is on, every request carries the API token.
+//!
+//! `connect_remote` points every client in the process at another machine's
+//! Burd instead (the CLI's `--host`), sending that machine's token.
 
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use serde_json::Value;
+use std::sync::OnceLock;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+
+use crate::api::API_PORT;
+use crate::config::{ConfigStore, RemoteHost};
 
 const API_BASE: &str = "http://127.0.0.1:19840";
 
+/// The other machine's Burd this process talks to, once connected
+static REMOTE: OnceLock<RemoteHost> = OnceLock::new();
+
+/// Talk to another machine's Burd for the rest of the process
+///
+/// `host` is the name of a saved remote host or an API address; `token`
+/// replaces the saved token.
+pub fn connect_remote(host: &str, token: Option<String>) -> Result<&'static RemoteHost, String> {
+    let saved = ConfigStore::new()
+        .and_then(|store| store.load())
+        .ok()
+        .and_then(|config| {
+            config
+                .remote_hosts
+                .into_iter()
+                .find(|h| h.name.eq_ignore_ascii_case(host))
+        });
+    let mut remote = match saved {
+        Some(saved) => saved,
+        None => RemoteHost {
+            name: host.to_string(),
+            url: normalize_url(host).map_err(|e| {
+                format!(
+                    "'{}' is neither a saved remote host nor an address: {}",
+                    host, e
+                )
+            })?,
+            token: None,
+        },
+    };
+    if token.is_some() {
+        remote.token = token;
+    }
+
+    REMOTE
+        .set(remote)
+        .map_err(|_| "Already connected to a remote Burd".to_string())?;
+    Ok(remote_host().expect("remote host was just set"))
+}
+
+/// The other machine's Burd this process talks to, if any
+pub fn remote_host() -> Option<&'static RemoteHost> {
+    REMOTE.get()
+}
+
+/// Normalize an API address to `scheme://host:port`
+///
+/// A bare host name gets `http://` and Burd's API port.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    let with_scheme = if url.contains("://") {
+        url.to_string()
+    } else if url.contains(':') {
+        format!("http://{}", url)
+    } else {
+        format!("http://{}:{}", url, API_PORT)
+    };
+
+    let parsed = url::Url::parse(&with_scheme).map_err(|e| format!("Invalid address: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Address must use http or https".to_string());
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err("Address has no host".to_string());
+    }
+    if parsed.path() != "/" || parsed.query().is_some() {
+        return Err("Address must not include a path".to_string());
+    }
+    Ok(parsed.origin().ascii_serialization())
+}
+
 pub struct BurdApiClient {
+    base_url: String,
     client: reqwest::blocking::Client,
     probe_client: reqwest::blocking::Client,
 }
 
 impl BurdApiClient {
     pub fn new() -> Self {
+        Self::with_timeout(std::time::Duration::from_secs(30))
+    }
+
+    /// Client whose requests wait up to `timeout`, for calls that block on
+    /// long-running work such as binary downloads
+    pub fn with_timeout(timeout: std::time::Duration) -> Self {
+        match remote_host() {
+            Some(remote) => Self::build(&remote.url, remote.token.as_deref(), timeout),
+            None => Self::build(API_BASE, local_token().as_deref(), timeout),
+        }
+    }
+
+    /// Client for another machine's Burd, regardless of `connect_remote`
+    pub fn for_host(host: &RemoteHost) -> Self {
+        Self::build(
+            &host.url,
+            host.token.as_deref(),
+            std::time::Duration::from_secs(30),
+        )
+    }
+
+    fn build(base_url: &str, token: Option<&str>, timeout: std::time::Duration) -> Self {
+        let headers = bearer_headers(token);
         Self {
+            base_url: base_url.to_string(),
             client: reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
+                .timeout(timeout)
+                .default_headers(headers.clone())
                 .build()
                 .expect("Failed to create HTTP client"),
             // Separate short-timeout client so `is_available()` fails fast
-            // (≤500ms) when the daemon is down — CLI lifecycle commands
-            // shouldn't hang on the 30s main-client timeout.
+            // (≤500ms locally, 3s for another machine) when the daemon is
+            // down — CLI lifecycle commands shouldn't hang on the 30s
+            // main-client timeout.
             probe_client: reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_millis(500))
+                .timeout(if base_url == API_BASE {
+                    std::time::Duration::from_millis(500)
+                } else {
+                    std::time::Duration::from_secs(3)
+                })
+                .default_headers(headers)
                 .build()
                 .expect("Failed to create probe HTTP client"),
         }
     }
 
     /// Check if the Burd daemon is reachable. Uses the short-timeout probe
-    /// client to fail fast (≤500ms).
+    /// client to fail fast.
     pub fn is_available(&self) -> bool {
         self.probe_client
-            .get(format!("{}/status", API_BASE))
+            .get(format!("{}/status", self.base_url))
             .send()
             .map(|r| r.status().is_success())
             .unwrap_or(false)
@@ -43,7 +157,7 @@ impl BurdApiClient {
     pub fn get(&self, path: &str) -> Result<String, String> {
         let response = self
             .client
-            .get(format!("{}{}", API_BASE, path))
+            .get(format!("{}{}", self.base_url, path))
             .send()
             .map_err(|e| format!("Request failed: {}", e))?;
 
@@ -53,7 +167,7 @@ impl BurdApiClient {
     pub fn post(&self, path: &str, body: &Value) -> Result<String, String> {
         let response = self
             .client
-            .post(format!("{}{}", API_BASE, path))
+            .post(format!("{}{}", self.base_url, path))
             .json(body)
             .send()
             .map_err(|e| format!("Request failed: {}", e))?;
@@ -64,7 +178,7 @@ impl BurdApiClient {
     pub fn put(&self, path: &str, body: &Value) -> Result<String, String> {
         let response = self
             .client
-            .put(format!("{}{}", API_BASE, path))
+            .put(format!("{}{}", self.base_url, path))
             .json(body)
             .send()
             .map_err(|e| format!("Request failed: {}", e))?;
@@ -75,7 +189,7 @@ impl BurdApiClient {
     pub fn delete(&self, path: &str) -> Result<String, String> {
         let response = self
             .client
-            .delete(format!("{}{}", API_BASE, path))
+            .delete(format!("{}{}", self.base_url, path))
             .send()
             .map_err(|e| format!("Request failed: {}", e))?;
 
@@ -112,8 +226,70 @@ impl BurdApiClient {
     }
 }
 
+/// WebSocket request for the daemon's `/events` stream
+///
+/// `query` filters it, e.g. `?types=download`.
+pub fn events_request(query: &str) -> Result<Request, String> {
+    let (base_url, token) = match remote_host() {
+        Some(remote) => (remote.url.as_str(), remote.token.clone()),
+        None => (API_BASE, local_token()),
+    };
+    let mut request = format!("{}/events{}", base_url.replacen("http", "ws", 1), query)
+        .into_client_request()
+        .map_err(|e| format!("Invalid events URL: {}", e))?;
+    request
+        .headers_mut()
+        .extend(bearer_headers(token.as_deref()));
+    Ok(request)
+}
+
+/// This machine's API token, required by the daemon in remote mode
+fn local_token() -> Option<String> {
+    ConfigStore::new()
+        .and_then(|store| store.load())
+        .ok()
+        .filter(|config| config.api_remote.enabled)
+        .and_then(|config| config.api_remote.token)
+}
+
+/// Bearer token header for `token`
+fn bearer_headers(token: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(mut value) =
+        token.and_then(|t| HeaderValue::from_str(&format!("Bearer {}", t)).ok())
+    {
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    headers
+}
+
 impl Default for BurdApiClient {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("mac-mini.local").unwrap(),
+            "http://mac-mini.local:19840"
+        );
+        assert_eq!(
+            normalize_url("192.168.1.20:8080").unwrap(),
+            "http://192.168.1.20:8080"
+        );
+        assert_eq!(
+            normalize_url("https://burd.office.example.com/").unwrap(),
+            "https://burd.office.example.com"
+        );
+        assert!(normalize_url("ftp://mac-mini.local").is_err());
+        assert!(normalize_url("http://mac-mini.local/api").is_err());
+        assert!(normalize_url("").is_err());
+    }
+}
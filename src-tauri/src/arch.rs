@@ -0,0 +1,252 @@
+//! CPU architecture of service binaries
+//!
+//! Some services only publish x86_64 macOS builds, which run on Apple
+//! Silicon through Rosetta 2. Downloads prefer the native asset, fall back
+//! to x86_64 when a service has no arm64 build, and honor a per-service
+//! override (`Config::arch_overrides`) for builds that are missing or broken
+//! on one architecture. The architecture of each installed binary is read
+//! from its Mach-O (or ELF) header and recorded in `BinaryInfo`.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Where Rosetta 2 installs its runtime
+const ROSETTA_RUNTIME: &str = "/Library/Apple/usr/share/rosetta/rosetta";
+
+/// A CPU architecture Burd downloads binaries for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Arch {
+    Arm64,
+    X86_64,
+}
+
+impl Arch {
+    /// Architecture Burd itself runs on
+    pub fn host() -> Self {
+        if cfg!(target_arch = "aarch64") {
+            Arch::Arm64
+        } else {
+            Arch::X86_64
+        }
+    }
+
+    /// Parse user input (`arm64`/`aarch64`, `x86_64`/`x64`/`amd64`/`intel`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "arm64" | "aarch64" | "apple" => Some(Arch::Arm64),
+            "x86_64" | "x64" | "amd64" | "intel" => Some(Arch::X86_64),
+            _ => None,
+        }
+    }
+
+    /// Suffix of the platform keys in `services.json` (`darwin-arm64`)
+    pub fn platform_suffix(&self) -> &'static str {
+        match self {
+            Arch::Arm64 => "arm64",
+            Arch::X86_64 => "x64",
+        }
+    }
+
+    /// Name passed to trait-based services' `download_method`
+    pub fn target_name(&self) -> &'static str {
+        match self {
+            Arch::Arm64 => "aarch64",
+            Arch::X86_64 => "x86_64",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Arch::Arm64 => "arm64",
+            Arch::X86_64 => "x86_64",
+        }
+    }
+
+    /// Whether this architecture runs translated by Rosetta on this machine
+    pub fn needs_rosetta(&self) -> bool {
+        cfg!(target_os = "macos") && *self == Arch::X86_64 && Arch::host() == Arch::Arm64
+    }
+}
+
+/// Architecture of an installed binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryArch {
+    Arm64,
+    X86_64,
+    /// Fat binary containing both slices
+    Universal,
+}
+
+impl BinaryArch {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinaryArch::Arm64 => "arm64",
+            BinaryArch::X86_64 => "x86_64",
+            BinaryArch::Universal => "universal",
+        }
+    }
+
+    /// Whether the binary only has an x86_64 slice and runs under Rosetta here
+    pub fn needs_rosetta(&self) -> bool {
+        *self == BinaryArch::X86_64 && Arch::X86_64.needs_rosetta()
+    }
+}
+
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const EM_X86_64: u16 = 0x3e;
+const EM_AARCH64: u16 = 0xb7;
+
+fn cpu_arch(cputype: u32) -> Option<Arch> {
+    match cputype {
+        CPU_TYPE_ARM64 => Some(Arch::Arm64),
+        CPU_TYPE_X86_64 => Some(Arch::X86_64),
+        _ => None,
+    }
+}
+
+/// Determine a binary's architecture from its header bytes
+///
+/// Returns `None` for scripts, 32-bit binaries, and anything unrecognized.
+pub fn detect_from_header(header: &[u8]) -> Option<BinaryArch> {
+    let word =
+        |offset: usize| -> Option<[u8; 4]> { header.get(offset..offset + 4)?.try_into().ok() };
+
+    if header.starts_with(&ELF_MAGIC) {
+        let machine = u16::from_le_bytes(header.get(18..20)?.try_into().ok()?);
+        return match machine {
+            EM_X86_64 => Some(BinaryArch::X86_64),
+            EM_AARCH64 => Some(BinaryArch::Arm64),
+            _ => None,
+        };
+    }
+
+    let magic = word(0)?;
+    if u32::from_le_bytes(magic) == MH_MAGIC_64 {
+        return match cpu_arch(u32::from_le_bytes(word(4)?))? {
+            Arch::Arm64 => Some(BinaryArch::Arm64),
+            Arch::X86_64 => Some(BinaryArch::X86_64),
+        };
+    }
+
+    // Fat headers are big-endian: nfat_arch entries of 20 (or 32) bytes
+    let entry_size = match u32::from_be_bytes(magic) {
+        FAT_MAGIC => 20,
+        FAT_MAGIC_64 => 32,
+        _ => return None,
+    };
+    let count = u32::from_be_bytes(word(4)?) as usize;
+    let (mut arm64, mut x86_64) = (false, false);
+    for i in 0..count.min(16) {
+        match cpu_arch(u32::from_be_bytes(word(8 + i * entry_size)?)) {
+            Some(Arch::Arm64) => arm64 = true,
+            Some(Arch::X86_64) => x86_64 = true,
+            None => {}
+        }
+    }
+    match (arm64, x86_64) {
+        (true, true) => Some(BinaryArch::Universal),
+        (true, false) => Some(BinaryArch::Arm64),
+        (false, true) => Some(BinaryArch::X86_64),
+        (false, false) => None,
+    }
+}
+
+/// Determine the architecture of the binary at `path` (symlinks followed)
+pub fn detect(path: &Path) -> Option<BinaryArch> {
+    let mut header = Vec::with_capacity(512);
+    File::open(path)
+        .ok()?
+        .take(512)
+        .read_to_end(&mut header)
+        .ok()?;
+    detect_from_header(&header)
+}
+
+/// Whether Rosetta 2 is installed
+pub fn rosetta_installed() -> bool {
+    Path::new(ROSETTA_RUNTIME).exists()
+}
+
+/// Error for an x86_64 binary that can't run because Rosetta is missing
+pub fn require_rosetta(name: &str, arch: Option<BinaryArch>) -> Result<(), String> {
+    if arch.is_some_and(|a| a.needs_rosetta()) && !rosetta_installed() {
+        return Err(format!(
+            "{} is an x86_64 binary and needs Rosetta 2 on Apple Silicon.\n\
+             Install it with: softwareupdate --install-rosetta --agree-to-license",
+            name
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mach_o(cputype: u32) -> Vec<u8> {
+        let mut header = MH_MAGIC_64.to_le_bytes().to_vec();
+        header.extend(cputype.to_le_bytes());
+        header.extend([0; 24]);
+        header
+    }
+
+    fn fat(cputypes: &[u32]) -> Vec<u8> {
+        let mut header = FAT_MAGIC.to_be_bytes().to_vec();
+        header.extend((cputypes.len() as u32).to_be_bytes());
+        for cputype in cputypes {
+            header.extend(cputype.to_be_bytes());
+            header.extend([0; 16]);
+        }
+        header
+    }
+
+    #[test]
+    fn test_detect_mach_o() {
+        assert_eq!(
+            detect_from_header(&mach_o(CPU_TYPE_ARM64)),
+            Some(BinaryArch::Arm64)
+        );
+        assert_eq!(
+            detect_from_header(&mach_o(CPU_TYPE_X86_64)),
+            Some(BinaryArch::X86_64)
+        );
+    }
+
+    #[test]
+    fn test_detect_fat_binaries() {
+        assert_eq!(
+            detect_from_header(&fat(&[CPU_TYPE_X86_64, CPU_TYPE_ARM64])),
+            Some(BinaryArch::Universal)
+        );
+        assert_eq!(
+            detect_from_header(&fat(&[CPU_TYPE_X86_64])),
+            Some(BinaryArch::X86_64)
+        );
+    }
+
+    #[test]
+    fn test_detect_elf_and_scripts() {
+        let mut elf = vec![0x7f, b'E', b'L', b'F'];
+        elf.extend([0; 14]);
+        elf.extend(EM_AARCH64.to_le_bytes());
+        assert_eq!(detect_from_header(&elf), Some(BinaryArch::Arm64));
+        assert_eq!(detect_from_header(b"#!/bin/sh\nexec foo"), None);
+        assert_eq!(detect_from_header(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_arch() {
+        assert_eq!(Arch::parse("aarch64"), Some(Arch::Arm64));
+        assert_eq!(Arch::parse("X64"), Some(Arch::X86_64));
+        assert_eq!(Arch::parse("ppc"), None);
+    }
+}
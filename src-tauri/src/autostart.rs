@@ -0,0 +1,283 @@
+//! Instance auto-start
+//!
+//! Starts the instances marked auto-start when the app or the headless
+//! daemon launches. Instances start in waves: backing services (databases,
+//! caches, queues, search, mail) first, then the app servers that connect to
+//! them, then tunnels and the proxy. Before the next wave starts, instances
+//! whose stack has auto-start members in a later wave must pass their health
+//! check, so an app never comes up before its database accepts connections.
+//!
+//! Starting or stopping a whole stack uses the same order (reversed when
+//! stopping), see [`stack_members`].
+
+use crate::commands::AppState;
+use crate::config::{Config, Instance, ServiceType};
+use crate::readiness;
+use uuid::Uuid;
+
+/// Position of a service type in the start order (lower starts first)
+fn start_tier(service_type: ServiceType) -> u8 {
+    match service_type {
+        ServiceType::Meilisearch
+        | ServiceType::MongoDB
+        | ServiceType::Typesense
+        | ServiceType::MinIO
+        | ServiceType::MariaDB
+        | ServiceType::MySQL
+        | ServiceType::PostgreSQL
+        | ServiceType::Redis
+        | ServiceType::Valkey
+        | ServiceType::Mailpit
+        | ServiceType::Beanstalkd
+        | ServiceType::RabbitMQ
+        | ServiceType::OpenSearch
+        | ServiceType::Memcached
+        | ServiceType::Centrifugo
+        | ServiceType::Soketi
+        | ServiceType::Keycloak
+        | ServiceType::Custom(_) => 0,
+        ServiceType::FrankenPHP
+        | ServiceType::FrankenPhpPark
+        | ServiceType::Gitea
+        | ServiceType::Bun => 1,
+        ServiceType::Frpc | ServiceType::Caddy => 2,
+    }
+}
+
+/// Auto-start instances grouped into waves, in start order
+///
+/// Instances keep their configured order within a wave.
+fn start_waves(instances: &[Instance]) -> Vec<Vec<&Instance>> {
+    let mut flagged: Vec<&Instance> = instances.iter().filter(|i| i.auto_start).collect();
+    flagged.sort_by_key(|i| start_tier(i.service_type));
+
+    let mut waves: Vec<Vec<&Instance>> = Vec::new();
+    for instance in flagged {
+        match waves.last_mut() {
+            Some(wave) if start_tier(wave[0].service_type) == start_tier(instance.service_type) => {
+                wave.push(instance)
+            }
+            _ => waves.push(vec![instance]),
+        }
+    }
+    waves
+}
+
+/// Whether an auto-start instance of the same stack starts in a later wave
+fn has_dependents(instance: &Instance, instances: &[Instance]) -> bool {
+    let Some(stack_id) = instance.stack_id else {
+        return false;
+    };
+    let tier = start_tier(instance.service_type);
+    instances.iter().any(|other| {
+        other.auto_start
+            && other.stack_id == Some(stack_id)
+            && start_tier(other.service_type) > tier
+    })
+}
+
+/// Members of a stack in start order; stop them in reverse
+///
+/// Instances keep their configured order within a tier.
+pub fn stack_members(instances: &[Instance], stack_id: Uuid) -> Vec<&Instance> {
+    let mut members: Vec<&Instance> = instances
+        .iter()
+        .filter(|i| i.stack_id == Some(stack_id))
+        .collect();
+    members.sort_by_key(|i| start_tier(i.service_type));
+    members
+}
+
+/// Whether other stack members start after `instance`, so it must pass its
+/// health check first
+pub fn starts_before_others(instance: &Instance, members: &[&Instance]) -> bool {
+    let tier = start_tier(instance.service_type);
+    members
+        .iter()
+        .any(|other| start_tier(other.service_type) > tier)
+}
+
+/// Start one instance after checking its version is installed
+pub fn start_instance(state: &AppState, config: &Config, id: Uuid) -> Result<u32, String> {
+    let instance = config
+        .instances
+        .iter()
+        .find(|i| i.id == id)
+        .ok_or_else(|| format!("Instance {} not found", id))?;
+
+    let version_exists = config
+        .binaries
+        .get(&instance.service_type)
+        .map(|versions| versions.contains_key(&instance.version))
+        .unwrap_or(false);
+    if !version_exists {
+        return Err(format!(
+            "Version {} is not installed for {}",
+            instance.version,
+            instance.service_type.display_name()
+        ));
+    }
+
+    let ssl_enabled = config
+        .domains
+        .iter()
+        .filter(|d| d.routes_to_instance(&id))
+        .any(|d| d.ssl_enabled);
+
+    let process_manager = state
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock")?;
+    process_manager.start(instance, Some(config), ssl_enabled)
+}
+
+/// Start every auto-start instance that isn't running, in dependency order
+///
+/// Failures are logged and don't hold back later waves. Returns the IDs of
+/// the instances that were started.
+pub async fn start_all(state: AppState) -> Vec<Uuid> {
+    let config = match state.config_store.lock().map(|store| store.load()) {
+        Ok(Ok(config)) => config,
+        _ => {
+            eprintln!("Failed to load config for auto-start");
+            return Vec::new();
+        }
+    };
+
+    let mut started = Vec::new();
+    for wave in start_waves(&config.instances) {
+        let ids: Vec<Uuid> = wave.iter().map(|i| i.id).collect();
+        let wave_state = state.clone();
+        let wave_config = config.clone();
+        let results = tokio::task::spawn_blocking(move || {
+            ids.into_iter()
+                .filter_map(|id| {
+                    let instance = wave_config.instances.iter().find(|i| i.id == id)?;
+                    let running = wave_state
+                        .process_manager
+                        .lock()
+                        .map(|pm| pm.get_status(instance).running)
+                        .unwrap_or(true);
+                    if running {
+                        return None;
+                    }
+                    Some((id, start_instance(&wave_state, &wave_config, id)))
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        for (id, result) in results {
+            let Some(instance) = config.instances.iter().find(|i| i.id == id) else {
+                continue;
+            };
+            match result {
+                Ok(pid) => {
+                    println!("Started '{}' (PID {})", instance.name, pid);
+                    started.push(id);
+                }
+                Err(e) => {
+                    eprintln!("Failed to start '{}': {}", instance.name, e);
+                    continue;
+                }
+            }
+
+            if has_dependents(instance, &config.instances) {
+                if let Err(e) = readiness::wait_until_healthy(
+                    instance.port,
+                    instance.service_type,
+                    readiness::DEFAULT_READY_TIMEOUT,
+                )
+                .await
+                {
+                    eprintln!("Auto-start: {}", e);
+                }
+            }
+        }
+    }
+
+    started
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    fn instance(name: &str, service_type: ServiceType, stack_id: Option<Uuid>) -> Instance {
+        let builder = InstanceBuilder::new()
+            .name(name)
+            .service_type(service_type)
+            .auto_start(true);
+        match stack_id {
+            Some(stack_id) => builder.stack_id(stack_id).build(),
+            None => builder.build(),
+        }
+    }
+
+    fn names(waves: &[Vec<&Instance>]) -> Vec<Vec<String>> {
+        waves
+            .iter()
+            .map(|wave| wave.iter().map(|i| i.name.clone()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_start_waves_order_backing_services_first() {
+        let mut instances = vec![
+            instance("app", ServiceType::FrankenPHP, None),
+            instance("tunnel", ServiceType::Frpc, None),
+            instance("db", ServiceType::MariaDB, None),
+            instance("cache", ServiceType::Redis, None),
+        ];
+        instances.push(
+            InstanceBuilder::new()
+                .name("manual")
+                .service_type(ServiceType::PostgreSQL)
+                .build(),
+        );
+
+        assert_eq!(
+            names(&start_waves(&instances)),
+            vec![vec!["db", "cache"], vec!["app"], vec!["tunnel"]]
+        );
+    }
+
+    #[test]
+    fn test_has_dependents_only_within_stack() {
+        let stack = Some(Uuid::new_v4());
+        let instances = vec![
+            instance("db", ServiceType::MySQL, stack),
+            instance("app", ServiceType::FrankenPHP, stack),
+            instance("other-db", ServiceType::PostgreSQL, None),
+            instance("search", ServiceType::Meilisearch, Some(Uuid::new_v4())),
+        ];
+
+        assert!(has_dependents(&instances[0], &instances));
+        assert!(!has_dependents(&instances[1], &instances));
+        assert!(!has_dependents(&instances[2], &instances));
+        assert!(!has_dependents(&instances[3], &instances));
+    }
+
+    #[test]
+    fn test_stack_members_in_start_order() {
+        let stack = Uuid::new_v4();
+        let mut instances = vec![
+            instance("app", ServiceType::FrankenPHP, Some(stack)),
+            instance("db", ServiceType::MariaDB, Some(stack)),
+            instance("other-db", ServiceType::PostgreSQL, None),
+            instance("cache", ServiceType::Redis, Some(stack)),
+        ];
+        // Auto-start doesn't matter for stack actions
+        instances[1].auto_start = false;
+
+        let members = stack_members(&instances, stack);
+        let names: Vec<&str> = members.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["db", "cache", "app"]);
+
+        assert!(starts_before_others(members[0], &members));
+        assert!(!starts_before_others(members[2], &members));
+        assert!(!starts_before_others(members[0], &members[..2]));
+    }
+}
@@ -0,0 +1,860 @@
+//! Scheduled Backups
+//!
+//! Periodically copies the config and the data of selected instances into a
+//! user-chosen folder (iCloud Drive, an external disk, ...), every N hours or
+//! on a cron schedule. Each backup is a `burd-backup-<timestamp>/` directory
+//! holding:
+//!
+//! - `config.json`: the Burd config
+//! - `instances/<name>-<id>/`: per selected instance, depending on its state
+//!   (or `<name>-<id>.tar.gz` with compression on):
+//!   - running MariaDB/PostgreSQL: one SQL dump per database
+//!   - running services with snapshot support (e.g. MongoDB): the snapshot payload
+//!   - stopped instances: a copy of the data directory
+//! - `backup.json`: what was captured for each instance, and what was skipped
+//!
+//! Running instances without a consistent way to capture them are skipped
+//! rather than copied mid-write. A backup is written under a `.partial` name
+//! and renamed once complete, so an interrupted run never counts. After each
+//! backup, the oldest ones beyond the retention are removed.
+//!
+//! A run holds `.burd-backup.lock` in the folder, so the app, the daemon and
+//! the CLI never write or prune backups there at the same time.
+//!
+//! An instance can be restored from any backup that captured it.
+
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::binary::copy_dir_contents;
+use crate::commands::AppState;
+use crate::config::{get_app_dir, get_instance_dir, BackupSettings, Config, Instance, ServiceType};
+use crate::cron::CronSchedule;
+use crate::db_manager;
+use crate::error::LockExt;
+use crate::lock;
+use crate::snapshot::{self, path_size};
+
+/// Directory name prefix of every backup
+pub const BACKUP_PREFIX: &str = "burd-backup-";
+
+/// Suffix of a backup that is still being written
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Held in the backup folder while a backup is written
+const LOCK_FILE: &str = ".burd-backup.lock";
+
+/// Metadata file inside each backup
+const MANIFEST_FILE: &str = "backup.json";
+
+/// Suffix of a compressed instance directory
+const ARCHIVE_SUFFIX: &str = ".tar.gz";
+
+/// Where compressed data is unpacked while restoring, in the app directory
+const RESTORE_STAGING_DIR: &str = "backup-restore";
+
+/// Outcome of the last scheduled or manual run, in the app directory
+const STATUS_FILE: &str = "backup-status.json";
+
+/// Delay between checks whether a backup is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Wait after a failed run before trying again
+const RETRY_AFTER_FAILURE: TimeDelta = TimeDelta::hours(1);
+
+/// Longest allowed interval between backups (30 days)
+const MAX_INTERVAL_HOURS: u32 = 24 * 30;
+
+/// How an instance's data was captured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMethod {
+    /// One SQL file per database
+    SqlDump,
+    /// The service's snapshot format (dump, archive, RDB file, mirror)
+    Snapshot,
+    /// A copy of the stopped instance's data directory
+    DataCopy,
+    /// Not captured; see the note
+    Skipped,
+}
+
+/// One instance in a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceBackup {
+    pub instance_id: Uuid,
+    pub name: String,
+    pub service_type: ServiceType,
+    pub method: CaptureMethod,
+    /// Why the instance was skipped
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Whether the data is stored as a .tar.gz archive
+    #[serde(default)]
+    pub compressed: bool,
+}
+
+/// Backup metadata (stored as `backup.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    pub instances: Vec<InstanceBackup>,
+    /// Where the backup lives (filled in when listing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// Outcome of the last backup run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupStatus {
+    pub last_attempt: Option<DateTime<Utc>>,
+    /// Set when the last run failed
+    pub last_error: Option<String>,
+}
+
+fn status_path() -> Result<PathBuf, String> {
+    Ok(get_app_dir()?.join(STATUS_FILE))
+}
+
+/// Load the outcome of the last run
+pub fn load_status() -> BackupStatus {
+    status_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_status(status: &BackupStatus) {
+    if let (Ok(path), Ok(json)) = (status_path(), serde_json::to_string_pretty(status)) {
+        let _ = fs::write(path, json);
+    }
+}
+
+/// Expand a leading `~/` in a user-entered folder
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// The configured backup folder, which must exist
+///
+/// A missing folder usually means the external disk isn't connected, so it
+/// is never created here.
+pub fn destination_dir(settings: &BackupSettings) -> Result<PathBuf, String> {
+    let dest = settings
+        .destination
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .ok_or_else(|| "No backup folder is set".to_string())?;
+    let path = expand_home(dest);
+    if !path.is_dir() {
+        return Err(format!(
+            "Backup folder {} doesn't exist (is the disk connected?)",
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Validate and normalize settings before they are saved
+pub fn validate_settings(mut settings: BackupSettings) -> Result<BackupSettings, String> {
+    if settings.interval_hours == 0 || settings.interval_hours > MAX_INTERVAL_HOURS {
+        return Err(format!(
+            "Backup interval must be between 1 and {} hours",
+            MAX_INTERVAL_HOURS
+        ));
+    }
+    settings.destination = settings
+        .destination
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+    settings.schedule = settings
+        .schedule
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| !s.is_empty());
+    if let Some(schedule) = &settings.schedule {
+        CronSchedule::parse(schedule).map_err(|e| format!("Invalid schedule: {}", e))?;
+    }
+    if settings.enabled {
+        destination_dir(&settings)?;
+    }
+    Ok(settings)
+}
+
+fn read_manifest(dir: &Path) -> Result<BackupInfo, String> {
+    let content = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to read backup metadata: {}", e))?;
+    let mut info: BackupInfo =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid backup metadata: {}", e))?;
+    info.path = Some(dir.to_path_buf());
+    Ok(info)
+}
+
+/// List the complete backups in a folder, newest first
+pub fn list_backups_in(dest: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = fs::read_dir(dest) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(BACKUP_PREFIX) && !name.ends_with(PARTIAL_SUFFIX)
+        })
+        .filter_map(|e| read_manifest(&e.path()).ok())
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    backups
+}
+
+/// List the backups in the configured folder, newest first
+pub fn list_backups(settings: &BackupSettings) -> Result<Vec<BackupInfo>, String> {
+    Ok(list_backups_in(&destination_dir(settings)?))
+}
+
+/// Delete the oldest backups beyond `keep` (0 keeps all); returns removed IDs
+pub fn prune_backups(dest: &Path, keep: usize) -> Result<Vec<String>, String> {
+    if keep == 0 {
+        return Ok(Vec::new());
+    }
+    let mut removed = Vec::new();
+    for backup in list_backups_in(dest).into_iter().skip(keep) {
+        if let Some(path) = &backup.path {
+            fs::remove_dir_all(path)
+                .map_err(|e| format!("Failed to remove backup {}: {}", backup.id, e))?;
+            removed.push(backup.id);
+        }
+    }
+    Ok(removed)
+}
+
+/// Whether a scheduled backup should run now
+///
+/// With a cron schedule, a backup is due once a scheduled time has passed
+/// since the latest one; otherwise once the interval has.
+pub fn is_due(
+    settings: &BackupSettings,
+    latest: Option<DateTime<Utc>>,
+    status: &BackupStatus,
+    now: DateTime<Utc>,
+) -> bool {
+    if !settings.enabled || settings.destination.is_none() {
+        return false;
+    }
+    if let (Some(attempt), Some(_)) = (status.last_attempt, &status.last_error) {
+        if now - attempt < RETRY_AFTER_FAILURE {
+            return false;
+        }
+    }
+    let Some(latest) = latest else {
+        return true;
+    };
+    match settings
+        .schedule
+        .as_deref()
+        .map(CronSchedule::parse)
+        .and_then(Result::ok)
+    {
+        Some(schedule) => schedule
+            .next_after(latest.with_timezone(&Local).naive_local())
+            .is_some_and(|next| next <= now.with_timezone(&Local).naive_local()),
+        None => now - latest >= TimeDelta::hours(i64::from(settings.interval_hours)),
+    }
+}
+
+/// Directory name for an instance inside a backup
+fn instance_dir_name(name: &str, id: &Uuid) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let id = id.to_string();
+    format!("{}-{}", name, &id[..8])
+}
+
+/// `<dir>.tar.gz` next to a directory
+fn archive_path(dir: &Path) -> PathBuf {
+    let mut path = dir.as_os_str().to_owned();
+    path.push(ARCHIVE_SUFFIX);
+    PathBuf::from(path)
+}
+
+/// Pack a directory into `<dir>.tar.gz`, then remove the directory
+fn compress_dir(dir: &Path) -> Result<(), String> {
+    let file = fs::File::create(archive_path(dir))
+        .map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder
+        .append_dir_all(".", dir)
+        .and_then(|_| builder.into_inner())
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to compress {}: {}", dir.display(), e))?;
+    fs::remove_dir_all(dir).map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))
+}
+
+/// Unpack a `.tar.gz` archive into `dest`
+fn extract_archive(archive: &Path, dest: &Path) -> Result<(), String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+    tar::Archive::new(GzDecoder::new(file))
+        .unpack(dest)
+        .map_err(|e| format!("Failed to extract {}: {}", archive.display(), e))
+}
+
+/// Dump every database of a running MariaDB/PostgreSQL instance
+fn dump_databases(instance: &Instance, dir: &Path) -> Result<(), String> {
+    let manager = db_manager::create_manager_for_instance(instance)?;
+    for database in manager.list_databases()? {
+        manager.export_sql(&database.name, &dir.join(format!("{}.sql", database.name)))?;
+    }
+    Ok(())
+}
+
+/// Capture one instance's data into `dir`
+fn capture_instance(
+    instance: &Instance,
+    running: bool,
+    dir: &Path,
+) -> Result<CaptureMethod, String> {
+    if !running {
+        copy_dir_contents(&get_instance_dir(&instance.id)?, dir)?;
+        return Ok(CaptureMethod::DataCopy);
+    }
+    match instance.service_type {
+        ServiceType::MariaDB | ServiceType::PostgreSQL => {
+            dump_databases(instance, dir)?;
+            Ok(CaptureMethod::SqlDump)
+        }
+        service_type if snapshot::supports_snapshots(service_type) => {
+            snapshot::write_payload(instance, true, dir)?;
+            Ok(CaptureMethod::Snapshot)
+        }
+        _ => Err("Running; stop it during backups to include its data".to_string()),
+    }
+}
+
+/// Lock the backup folder, failing when another run (in any process) has it
+///
+/// Dropping the file releases the lock.
+fn lock_destination(dest: &Path) -> Result<File, String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dest.join(LOCK_FILE))
+        .map_err(|e| format!("Failed to open backup lock: {}", e))?;
+    // SAFETY: flock only reads the descriptor, which `file` keeps open
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+            return Err("A backup is already running".to_string());
+        }
+        return Err(format!("Failed to lock backup folder: {}", err));
+    }
+    Ok(file)
+}
+
+/// Write a backup into `dest`, then prune old ones
+///
+/// `running` holds the IDs of running instances.
+pub fn run_backup_in(
+    dest: &Path,
+    config: &Config,
+    running: &HashSet<Uuid>,
+    now: DateTime<Utc>,
+) -> Result<BackupInfo, String> {
+    let _lock = lock_destination(dest)?;
+
+    let id = now.format("%Y%m%d-%H%M%S").to_string();
+    let final_dir = dest.join(format!("{}{}", BACKUP_PREFIX, id));
+    if final_dir.exists() {
+        return Err(format!("Backup {} already exists", id));
+    }
+
+    // Leftovers of interrupted runs; no other run is writing while we hold
+    // the lock
+    if let Ok(entries) = fs::read_dir(dest) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(BACKUP_PREFIX) && name.ends_with(PARTIAL_SUFFIX) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    let dir = dest.join(format!("{}{}{}", BACKUP_PREFIX, id, PARTIAL_SUFFIX));
+    let result = write_backup(&dir, &id, config, running, now);
+    let mut info = match result {
+        Ok(info) => info,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+
+    fs::rename(&dir, &final_dir).map_err(|e| format!("Failed to finish backup: {}", e))?;
+    info.path = Some(final_dir);
+    prune_backups(dest, config.backup.retention)?;
+
+    Ok(info)
+}
+
+fn write_backup(
+    dir: &Path,
+    id: &str,
+    config: &Config,
+    running: &HashSet<Uuid>,
+    now: DateTime<Utc>,
+) -> Result<BackupInfo, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    // The config holds passwords and tokens
+    let config_path = dir.join("config.json");
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(&config_path, json).map_err(|e| format!("Failed to write config backup: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&config_path, fs::Permissions::from_mode(0o600));
+    }
+
+    let mut instances = Vec::new();
+    for instance in config
+        .instances
+        .iter()
+        .filter(|i| config.backup.instance_ids.contains(&i.id))
+    {
+        let instance_dir = dir
+            .join("instances")
+            .join(instance_dir_name(&instance.name, &instance.id));
+        fs::create_dir_all(&instance_dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+        let (method, note) =
+            match capture_instance(instance, running.contains(&instance.id), &instance_dir) {
+                Ok(method) => (method, None),
+                Err(e) => {
+                    let _ = fs::remove_dir_all(&instance_dir);
+                    (CaptureMethod::Skipped, Some(e))
+                }
+            };
+        let compressed = config.backup.compress && method != CaptureMethod::Skipped;
+        if compressed {
+            compress_dir(&instance_dir)?;
+        }
+        instances.push(InstanceBackup {
+            instance_id: instance.id,
+            name: instance.name.clone(),
+            service_type: instance.service_type,
+            method,
+            note,
+            compressed,
+        });
+    }
+
+    let mut info = BackupInfo {
+        id: id.to_string(),
+        created_at: now,
+        size_bytes: 0,
+        instances,
+        path: None,
+    };
+    info.size_bytes = path_size(dir);
+    let json = serde_json::to_string_pretty(&info)
+        .map_err(|e| format!("Failed to serialize backup metadata: {}", e))?;
+    fs::write(dir.join(MANIFEST_FILE), json)
+        .map_err(|e| format!("Failed to write backup metadata: {}", e))?;
+
+    Ok(info)
+}
+
+/// Run a backup into the configured folder and record the outcome
+pub fn run_backup(config: &Config, running: &HashSet<Uuid>) -> Result<BackupInfo, String> {
+    let now = Utc::now();
+    let result =
+        destination_dir(&config.backup).and_then(|dest| run_backup_in(&dest, config, running, now));
+
+    save_status(&BackupStatus {
+        last_attempt: Some(now),
+        last_error: result.as_ref().err().cloned(),
+    });
+    result
+}
+
+/// Find a backup in the configured folder by ID
+pub fn find_backup(settings: &BackupSettings, id: &str) -> Result<BackupInfo, String> {
+    list_backups(settings)?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or_else(|| format!("Backup '{}' not found", id))
+}
+
+/// Import every SQL dump in `dir`, replacing the databases they are named after
+fn restore_databases(instance: &Instance, dir: &Path) -> Result<(), String> {
+    let manager = db_manager::create_manager_for_instance(instance)?;
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut dumps: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "sql"))
+        .collect();
+    dumps.sort();
+
+    for dump in dumps {
+        let Some(database) = dump.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if manager.database_exists(database)? {
+            manager.drop_database(database)?;
+        }
+        manager.create_database(database)?;
+        manager.import_sql(database, &dump)?;
+    }
+    Ok(())
+}
+
+/// Restore captured data from `dir` into an instance
+fn restore_from(
+    instance: &Instance,
+    method: CaptureMethod,
+    dir: &Path,
+    running: bool,
+) -> Result<(), String> {
+    match method {
+        CaptureMethod::SqlDump => {
+            if !running {
+                return Err(format!(
+                    "Start '{}' to restore its databases",
+                    instance.name
+                ));
+            }
+            restore_databases(instance, dir)
+        }
+        CaptureMethod::Snapshot => snapshot::restore_payload(instance, dir, running),
+        CaptureMethod::DataCopy => {
+            if running {
+                return Err(format!(
+                    "Stop '{}' before restoring its data",
+                    instance.name
+                ));
+            }
+            let data_dir = get_instance_dir(&instance.id)?;
+            if data_dir.exists() {
+                fs::remove_dir_all(&data_dir)
+                    .map_err(|e| format!("Failed to clear data directory: {}", e))?;
+            }
+            fs::create_dir_all(&data_dir)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+            copy_dir_contents(dir, &data_dir)
+        }
+        CaptureMethod::Skipped => Err("Nothing was captured".to_string()),
+    }
+}
+
+/// Restore one instance's data from a backup
+///
+/// SQL dumps replace the databases they contain on the running server (each
+/// is dropped and recreated), snapshot payloads are restored like snapshots,
+/// and data directory copies replace the data of the stopped instance.
+pub fn restore_backup(
+    settings: &BackupSettings,
+    backup_id: &str,
+    instance: &Instance,
+    running: bool,
+) -> Result<InstanceBackup, String> {
+    let info = find_backup(settings, backup_id)?;
+    let entry = info
+        .instances
+        .iter()
+        .find(|i| i.instance_id == instance.id)
+        .cloned()
+        .ok_or_else(|| format!("Backup '{}' doesn't include '{}'", backup_id, instance.name))?;
+    if entry.method == CaptureMethod::Skipped {
+        return Err(format!(
+            "'{}' was skipped in backup '{}': {}",
+            instance.name,
+            backup_id,
+            entry.note.as_deref().unwrap_or("no data")
+        ));
+    }
+
+    let source = info
+        .path
+        .ok_or_else(|| format!("Backup '{}' not found", backup_id))?
+        .join("instances")
+        .join(instance_dir_name(&entry.name, &entry.instance_id));
+    if !entry.compressed {
+        restore_from(instance, entry.method, &source, running)?;
+        return Ok(entry);
+    }
+
+    let staging = get_app_dir()?.join(RESTORE_STAGING_DIR);
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create restore directory: {}", e))?;
+    let result = extract_archive(&archive_path(&source), &staging)
+        .and_then(|_| restore_from(instance, entry.method, &staging, running));
+    let _ = fs::remove_dir_all(&staging);
+    result.map(|_| entry)
+}
+
+/// The config and which of the selected instances are running
+pub fn current_state(state: &AppState) -> Result<(Config, HashSet<Uuid>), String> {
+    let config = lock!(state.config_store)?.load()?;
+    let process_manager = lock!(state.process_manager)?;
+    let running = config
+        .instances
+        .iter()
+        .filter(|i| config.backup.instance_ids.contains(&i.id))
+        .filter(|i| process_manager.get_status(i).running)
+        .map(|i| i.id)
+        .collect();
+    Ok((config, running))
+}
+
+/// Run a backup if one is due
+fn run_if_due(state: &AppState) {
+    let Ok((config, running)) = current_state(state) else {
+        return;
+    };
+    let latest = list_backups(&config.backup)
+        .ok()
+        .and_then(|backups| backups.first().map(|b| b.created_at));
+    if !is_due(&config.backup, latest, &load_status(), Utc::now()) {
+        return;
+    }
+
+    match run_backup(&config, &running) {
+        Ok(info) => eprintln!("Backup {} written ({} bytes)", info.id, info.size_bytes),
+        Err(e) => eprintln!("Scheduled backup failed: {}", e),
+    }
+}
+
+/// Run scheduled backups forever
+pub async fn run(state: AppState) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let state = state.clone();
+        let _ = tokio::task::spawn_blocking(move || run_if_due(&state)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+
+    fn settings(enabled: bool) -> BackupSettings {
+        BackupSettings {
+            enabled,
+            destination: Some("/Volumes/Backup".to_string()),
+            interval_hours: 24,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let ok = BackupStatus::default();
+
+        assert!(!is_due(&settings(false), None, &ok, now));
+        assert!(is_due(&settings(true), None, &ok, now));
+        assert!(!is_due(
+            &settings(true),
+            Some(now - TimeDelta::hours(23)),
+            &ok,
+            now
+        ));
+        assert!(is_due(
+            &settings(true),
+            Some(now - TimeDelta::hours(24)),
+            &ok,
+            now
+        ));
+
+        // A recent failure waits before retrying
+        let failed = BackupStatus {
+            last_attempt: Some(now - TimeDelta::minutes(10)),
+            last_error: Some("disk not connected".to_string()),
+        };
+        assert!(!is_due(&settings(true), None, &failed, now));
+        let failed_long_ago = BackupStatus {
+            last_attempt: Some(now - TimeDelta::hours(2)),
+            ..failed
+        };
+        assert!(is_due(&settings(true), None, &failed_long_ago, now));
+    }
+
+    #[test]
+    fn test_is_due_with_schedule() {
+        let mut nightly = settings(true);
+        nightly.schedule = Some("30 2 * * *".to_string());
+        let ok = BackupStatus::default();
+        let local = |s: &str| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        let latest = Some(local("2026-10-16 02:30"));
+        assert!(!is_due(&nightly, latest, &ok, local("2026-10-17 02:29")));
+        assert!(is_due(&nightly, latest, &ok, local("2026-10-17 02:31")));
+        // The interval no longer applies
+        assert!(!is_due(&nightly, latest, &ok, local("2026-10-17 02:00")));
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let mut invalid = settings(false);
+        invalid.interval_hours = 0;
+        assert!(validate_settings(invalid).is_err());
+
+        // Enabling needs an existing folder
+        let mut missing = settings(true);
+        missing.destination = Some("/nonexistent/burd-backups".to_string());
+        assert!(validate_settings(missing).is_err());
+
+        let mut bad_schedule = settings(false);
+        bad_schedule.schedule = Some("every night".to_string());
+        assert!(validate_settings(bad_schedule).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut valid = settings(true);
+        valid.destination = Some(format!("  {}  ", dir.path().display()));
+        valid.schedule = Some(" 30  2 * * * ".to_string());
+        let valid = validate_settings(valid).unwrap();
+        assert_eq!(
+            valid.destination.as_deref(),
+            Some(dir.path().to_str().unwrap())
+        );
+        assert_eq!(valid.schedule.as_deref(), Some("30 2 * * *"));
+
+        let mut blank = settings(false);
+        blank.schedule = Some("  ".to_string());
+        assert_eq!(validate_settings(blank).unwrap().schedule, None);
+    }
+
+    #[test]
+    fn test_run_backup_in_writes_and_prunes() {
+        let dest = tempfile::tempdir().unwrap();
+        let redis = InstanceBuilder::new()
+            .name("cache")
+            .port(1)
+            .service_type(ServiceType::Redis)
+            .build();
+        let mut config = ConfigBuilder::new().instance(redis.clone()).build();
+        config.backup = BackupSettings {
+            retention: 2,
+            instance_ids: vec![redis.id],
+            ..settings(true)
+        };
+        let running = HashSet::from([redis.id]);
+
+        let start = Utc::now();
+        for hour in 0..3 {
+            let info = run_backup_in(
+                dest.path(),
+                &config,
+                &running,
+                start + TimeDelta::hours(hour),
+            )
+            .unwrap();
+            assert!(info.path.unwrap().join("config.json").exists());
+        }
+
+        let backups = list_backups_in(dest.path());
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].created_at, start + TimeDelta::hours(2));
+
+        // Redis isn't reachable here, so it is skipped with a reason
+        let cache = &backups[0].instances[0];
+        assert_eq!(cache.method, CaptureMethod::Skipped);
+        assert!(cache.note.is_some());
+        assert!(!dest
+            .path()
+            .read_dir()
+            .unwrap()
+            .flatten()
+            .any(|e| e.file_name().to_string_lossy().ends_with(PARTIAL_SUFFIX)));
+    }
+
+    #[test]
+    fn test_run_backup_in_skips_locked_folder() {
+        let dest = tempfile::tempdir().unwrap();
+        let config = ConfigBuilder::new().build();
+        let partial = dest.path().join(format!(
+            "{}20240101-000000{}",
+            BACKUP_PREFIX, PARTIAL_SUFFIX
+        ));
+        fs::create_dir(&partial).unwrap();
+
+        // Another run is writing that partial backup
+        let lock = lock_destination(dest.path()).unwrap();
+        let err = run_backup_in(dest.path(), &config, &HashSet::new(), Utc::now()).unwrap_err();
+        assert_eq!(err, "A backup is already running");
+        assert!(partial.exists());
+
+        // Once it's gone, its leftover is cleaned up
+        drop(lock);
+        run_backup_in(dest.path(), &config, &HashSet::new(), Utc::now()).unwrap();
+        assert!(!partial.exists());
+        assert_eq!(list_backups_in(dest.path()).len(), 1);
+    }
+
+    #[test]
+    fn test_compress_and_extract() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("db-1234abcd");
+        fs::create_dir_all(data.join("nested")).unwrap();
+        fs::write(data.join("app.sql"), "CREATE TABLE t (id INT);").unwrap();
+        fs::write(data.join("nested/file"), "x").unwrap();
+
+        compress_dir(&data).unwrap();
+        assert!(!data.exists());
+        assert!(dir.path().join("db-1234abcd.tar.gz").is_file());
+
+        let dest = dir.path().join("restored");
+        extract_archive(&archive_path(&data), &dest).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.join("app.sql")).unwrap(),
+            "CREATE TABLE t (id INT);"
+        );
+        assert_eq!(fs::read_to_string(dest.join("nested/file")).unwrap(), "x");
+    }
+
+    #[test]
+    fn test_instance_dir_name() {
+        let instance = InstanceBuilder::new().name("My DB/prod").build();
+        let name = instance_dir_name(&instance.name, &instance.id);
+        assert!(name.starts_with("My-DB-prod-"));
+        assert_eq!(name.len(), "My-DB-prod-".len() + 8);
+    }
+}
@@ -13,9 +13,16 @@
 //!   burd parked    List all parked directories
 //!   burd refresh   Refresh parked directories (check for new/removed projects)
 //!   burd status    Show park status for current directory
+//!   burd instances List instances (filter with --tag)
 //!   burd share     Share a site via tunnel
+//!   burd stats     Request counts and response times per domain
 //!   burd db        Database management (list, create, drop, import, export, shell)
 //!   burd env       Environment management (check, fix, show)
+//!   burd queue     Beanstalkd queue inspection (inspect, kick, bury)
+//!   burd wp        Run wp-cli in the current WordPress/Bedrock project
+//!   burd composer  Run the project's Composer version with its PVM PHP
+//!   burd install   Download a service binary with a progress bar
+//!   burd verify    Verify installed binaries and repair what can be fixed
 
 use burd_lib::cli;
 use clap::{Parser, Subcommand};
@@ -28,6 +35,17 @@ use clap::{Parser, Subcommand};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Control another machine's Burd: a name saved with `burd remote add`
+    /// or an API address such as 192.168.1.20 (default: $BURD_HOST).
+    /// Works with instances, start, stop, restart, logs, update, xdebug,
+    /// install and mcp.
+    #[arg(long, global = true)]
+    host: Option<String>,
+
+    /// API token for --host, overriding the saved one (default: $BURD_TOKEN)
+    #[arg(long, global = true)]
+    token: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -59,7 +77,12 @@ enum Commands {
     ///
     /// All subdirectories will automatically become domains.
     /// Requires FrankenPHP Park instance to be created in the Burd app first.
-    Park,
+    Park {
+        /// Levels to search for projects: with 2, folders that aren't
+        /// projects (e.g. clients/) are searched for nested ones (1-3)
+        #[arg(long, default_value_t = 1)]
+        depth: u8,
+    },
 
     /// Unpark (forget) the current directory
     ///
@@ -114,27 +137,58 @@ enum Commands {
     /// Shows all directories linked via 'burd link' or 'burd init'.
     Links,
 
+    /// List instances
+    ///
+    /// Examples:
+    ///   burd instances                          # All instances
+    ///   burd instances --tag client:acme        # Only instances tagged client:acme
+    Instances {
+        /// Only list instances carrying this tag (repeatable; all must match)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+
     /// Start an instance
     ///
     /// Starts the named instance, or the instance tied to the current
-    /// directory when NAME is omitted.
+    /// directory when NAME is omitted. With `--tag`, starts every instance
+    /// carrying all given tags.
     Start {
         /// Instance name or domain (optional)
         name: Option<String>,
+        /// Act on instances carrying this tag (repeatable; all must match)
+        #[arg(long = "tag", value_name = "TAG", conflicts_with = "name")]
+        tags: Vec<String>,
+        /// Return only once the service accepts connections (up to 25s)
+        #[arg(long, conflicts_with = "tags")]
+        wait: bool,
     },
 
     /// Stop an instance
     Stop {
         /// Instance name or domain (optional)
         name: Option<String>,
+        /// Act on instances carrying this tag (repeatable; all must match)
+        #[arg(long = "tag", value_name = "TAG", conflicts_with = "name")]
+        tags: Vec<String>,
     },
 
     /// Restart an instance
     Restart {
         /// Instance name or domain (optional)
         name: Option<String>,
+        /// Act on instances carrying this tag (repeatable; all must match)
+        #[arg(long = "tag", value_name = "TAG", conflicts_with = "name")]
+        tags: Vec<String>,
     },
 
+    /// Start or stop every instance of a stack
+    ///
+    /// Databases, caches and other backing services start before the apps
+    /// that use them, and stop after them.
+    #[command(subcommand)]
+    Stack(StackCommands),
+
     /// Show recent logs for an instance
     ///
     /// Resolves NAME the same way as start/stop/restart (name, UUID, subdomain,
@@ -151,6 +205,36 @@ enum Commands {
         follow: bool,
     },
 
+    /// Show an instance's activity timeline
+    ///
+    /// Starts, stops, crashes, restarts, version changes and config edits,
+    /// newest first. Resolves NAME the same way as `burd logs`.
+    History {
+        /// Instance name or domain (optional)
+        name: Option<String>,
+        /// Number of events to show (default: 20)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Turn Xdebug on or off for a PHP instance
+    ///
+    /// MODE is `on` (step debugging; the IDE listens on port 9003), `off`,
+    /// `profile` (cachegrind profiles of requests carrying XDEBUG_TRIGGER),
+    /// or `status`. A running instance is restarted to apply it. Resolves
+    /// NAME the same way as `burd start`.
+    ///
+    /// Examples:
+    ///   burd xdebug on            # Debug the current directory's site
+    ///   burd xdebug profile shop  # Profile shop.burd
+    ///   burd xdebug off
+    Xdebug {
+        /// on, off, profile, or status
+        mode: String,
+        /// Instance name or domain (optional)
+        name: Option<String>,
+    },
+
     /// Update instance settings
     ///
     /// Currently supports `--php-version`, `--port`, and `--name`. Mirrors the
@@ -167,17 +251,67 @@ enum Commands {
         /// Rename the instance
         #[arg(long = "name", value_name = "NEW_NAME")]
         new_name: Option<String>,
+        /// Start and supervise the instance from `burd daemon`
+        #[arg(long, value_name = "BOOL")]
+        auto_start: Option<bool>,
+        /// Restart the instance, with backoff, when it crashes or fails its
+        /// health check
+        #[arg(long, value_name = "BOOL")]
+        restart_on_failure: Option<bool>,
+        /// Replace the instance's tags (comma-separated; "" clears them)
+        #[arg(long, value_name = "TAGS", value_delimiter = ',')]
+        tags: Option<Vec<String>>,
+        /// Extra argument appended to the service's start command (repeatable;
+        /// replaces the current ones)
+        #[arg(long = "arg", value_name = "ARG", allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Remove all extra start arguments
+        #[arg(long, conflicts_with = "args")]
+        clear_args: bool,
     },
 
-    /// List installed versions of Burd services
+    /// List installed versions of Burd services, or pin a default version
+    ///
+    /// A pinned version is used by `burd init`, `burd link`, `burd new`, the
+    /// API, and stack templates whenever no version is given. `--arch` makes
+    /// future downloads of a service use one architecture, for services whose
+    /// native Apple Silicon build is missing or broken. `--channel` opts a
+    /// service into release candidates or betas when picking a download.
     ///
     /// Examples:
     ///   burd versions                   # All services + installed versions
     ///   burd versions --service frankenphp
+    ///   burd versions --service mariadb --pin 10.11
+    ///   burd versions --service mariadb --unpin
+    ///   burd versions --service mailpit --arch x86_64
+    ///   burd versions --service mailpit --arch native
+    ///   burd versions --service meilisearch --channel rc
     Versions {
         /// Restrict output to a single service type (e.g. frankenphp, mariadb)
         #[arg(long)]
         service: Option<String>,
+        /// Pin the default version for --service (exact or prefix, e.g. 10.11)
+        #[arg(
+            long,
+            value_name = "VERSION",
+            requires = "service",
+            conflicts_with = "unpin"
+        )]
+        pin: Option<String>,
+        /// Remove the pinned default version for --service
+        #[arg(long, requires = "service")]
+        unpin: bool,
+        /// Architecture to download for --service (arm64, x86_64, or native)
+        #[arg(long, value_name = "ARCH", requires = "service")]
+        arch: Option<String>,
+        /// Releases offered for --service (stable, rc, or beta)
+        #[arg(
+            long,
+            value_name = "CHANNEL",
+            requires = "service",
+            conflicts_with = "arch"
+        )]
+        channel: Option<String>,
     },
 
     /// Enable HTTPS for a domain
@@ -205,6 +339,34 @@ enum Commands {
         name: Option<String>,
     },
 
+    /// Client certificates (mutual TLS)
+    ///
+    /// Requires visitors of an HTTPS domain to present a client certificate
+    /// issued by Burd, and issues or shows that certificate.
+    ///
+    /// Examples:
+    ///   burd client-cert require admin   # Require a certificate for admin.burd
+    ///   burd client-cert show admin      # Show the certificate files and password
+    #[command(subcommand)]
+    ClientCert(ClientCertCommands),
+
+    /// Show request counts and response times per domain
+    ///
+    /// Computed from the sites' access logs for the last N minutes and the
+    /// N minutes before, so a site that suddenly got slow stands out.
+    ///
+    /// Examples:
+    ///   burd stats                  # All domains
+    ///   burd stats myapp            # Details for myapp.burd
+    ///   burd stats myapp --minutes 60
+    Stats {
+        /// Domain name (optional, defaults to every domain)
+        name: Option<String>,
+        /// Length of each window in minutes
+        #[arg(long, default_value_t = 15)]
+        minutes: u32,
+    },
+
     /// Open a site in the default browser
     ///
     /// Opens the specified domain or current directory's domain in browser.
@@ -248,13 +410,31 @@ enum Commands {
 
     /// Create a new project from template
     ///
-    /// Scaffolds a new Laravel, WordPress, or Bedrock project.
+    /// Scaffolds a new Laravel, Symfony, Statamic, WordPress, or Bedrock
+    /// project, sets up its database, Redis and Mailpit, writes their
+    /// settings to the project, and links <name>.<tld>.
     New {
-        /// Project type (laravel, wordpress, bedrock)
+        /// Project type (laravel, symfony, statamic, wordpress, bedrock)
         template: String,
 
         /// Project name (will be used as directory name)
         name: String,
+
+        /// Database engine (mariadb | postgres); defaults to MariaDB
+        #[arg(long, value_enum)]
+        db: Option<DbEngineArg>,
+        /// Only scaffold the project; don't set up services
+        #[arg(long)]
+        no_services: bool,
+        /// Don't create the instance and domain for the site
+        #[arg(long)]
+        no_link: bool,
+        /// Skip enabling SSL on the new domain
+        #[arg(long)]
+        no_ssl: bool,
+        /// Don't auto-start the site after linking it
+        #[arg(long)]
+        no_start: bool,
     },
 
     /// Full interactive project setup wizard
@@ -276,6 +456,75 @@ enum Commands {
     /// - Cache and mail setup
     Doctor,
 
+    /// Terminal dashboard for instances
+    ///
+    /// Lists instances with their health and ports, tails the selected
+    /// instance's logs, and starts/stops/restarts it with s/x/r. Needs the
+    /// Burd app or daemon running.
+    Tui,
+
+    /// Download and install a service binary
+    ///
+    /// Runs in the Burd app or daemon and shows a progress bar while it
+    /// downloads.
+    ///
+    /// Examples:
+    ///   burd install meilisearch          # Newest on the release channel
+    ///   burd install redis 7.2.4
+    Install {
+        /// Service type (e.g. meilisearch, mariadb, redis)
+        service: String,
+        /// Version to install (default: newest on the service's release channel)
+        version: Option<String>,
+    },
+
+    /// Verify installed service binaries
+    ///
+    /// Re-checks recorded hashes, executable bits, the macOS quarantine
+    /// attribute, and Homebrew symlinks broken by `brew upgrade`.
+    ///
+    /// Examples:
+    ///   burd verify                     # Report problems
+    ///   burd verify --repair            # Fix what can be fixed in place
+    ///   burd verify --service mariadb --repair
+    Verify {
+        /// Only check one service type (e.g. mariadb, frankenphp)
+        #[arg(long)]
+        service: Option<String>,
+        /// Repair permissions, quarantine attributes, and broken Homebrew links
+        #[arg(long)]
+        repair: bool,
+    },
+
+    /// Headless daemon
+    ///
+    /// Runs the API server, DNS, proxy route sync, and instance supervision
+    /// without the GUI. Install it as a login service to have everything
+    /// working after a reboot without opening the app.
+    #[command(subcommand)]
+    Daemon(DaemonCommands),
+
+    /// DNS diagnostics
+    ///
+    /// Checks each hop of `.<tld>` resolution and reports which one is broken.
+    #[command(subcommand)]
+    Dns(DnsCommands),
+
+    /// HTTPS root CA
+    ///
+    /// Shows where Caddy's root CA is trusted (keychain, Firefox), trusts it,
+    /// and exports it as PEM files for curl, Node and Python or as a setup
+    /// bundle for phones and other computers.
+    #[command(subcommand)]
+    Ca(CaCommands),
+
+    /// HTTP API access
+    ///
+    /// Lets a browser dashboard or another machine on the network control
+    /// Burd through the HTTP API, with a token required on every request.
+    #[command(subcommand)]
+    Api(ApiCommands),
+
     /// Update the burd CLI to the latest version
     ///
     /// Checks for updates and installs if available.
@@ -285,6 +534,25 @@ enum Commands {
         check: bool,
     },
 
+    /// Roll back the config to before an app upgrade
+    ///
+    /// Burd snapshots the config and a manifest of the installed binaries
+    /// when it starts with a new version. This restores the newest such
+    /// snapshot (or the given one), saving the current config first.
+    ///
+    /// Examples:
+    ///   burd rollback-env --list         # Show the snapshots
+    ///   burd rollback-env                # Undo the last upgrade
+    ///   burd rollback-env 20261018-093012-123
+    RollbackEnv {
+        /// Snapshot ID (default: the newest taken on an upgrade)
+        #[arg(conflicts_with = "list")]
+        id: Option<String>,
+        /// List the snapshots instead
+        #[arg(short, long)]
+        list: bool,
+    },
+
     /// Share a site via tunnel
     ///
     /// Exposes a local site to the internet via frpc tunnel.
@@ -293,6 +561,9 @@ enum Commands {
         /// Custom subdomain for the tunnel (optional, random if not specified)
         #[arg(short, long)]
         subdomain: Option<String>,
+        /// Response headers to add: no-framing, csp-report-only, noindex (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        headers: Vec<String>,
     },
 
     /// Database management commands
@@ -303,10 +574,70 @@ enum Commands {
 
     /// Environment file management
     ///
-    /// Check and fix .env files against Burd services.
+    /// Check and fix .env files against Burd services, and keep project
+    /// secrets in the keychain instead of .env.
     #[command(subcommand)]
     Env(EnvCommands),
 
+    /// Queue inspection commands
+    ///
+    /// Inspect Burd's Beanstalkd instances: list tubes and job counts,
+    /// peek at jobs, and bury or kick stuck jobs.
+    #[command(subcommand)]
+    Queue(QueueCommands),
+
+    /// Object storage commands
+    ///
+    /// Manage the buckets of Burd's MinIO instances: list, create, and
+    /// delete buckets, set anonymous access, and generate access keys.
+    #[command(subcommand)]
+    S3(S3Commands),
+
+    /// Laravel queue workers
+    ///
+    /// Run `php artisan queue:work` or Horizon for a Laravel project, with
+    /// the output in a log under Burd's logs directory. Worker settings are
+    /// saved and shown in the app.
+    #[command(subcommand)]
+    Worker(WorkerCommands),
+
+    /// Snapshot commands
+    ///
+    /// Snapshot and restore Meilisearch, MongoDB, Redis/Valkey, and MinIO
+    /// instances. SQL databases use `burd db export` / `burd db import`, and
+    /// are snapshotted automatically before they're dropped or overwritten.
+    #[command(subcommand)]
+    Snapshot(SnapshotCommands),
+
+    /// Backup commands
+    ///
+    /// Scheduled backups of the config and selected instances' data are set
+    /// up in Settings; these show their state and run one on demand.
+    #[command(subcommand)]
+    Backup(BackupCommands),
+
+    /// Config export and import
+    ///
+    /// Moves a setup to another Mac: instances, domains, stacks, parked
+    /// directories and tunnels, with the installed versions and the user
+    /// service definitions. Instance data isn't included.
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Service definitions
+    ///
+    /// Adds services that don't ship with Burd from a definition file in
+    /// the same JSON shape as Burd's built-in services.
+    #[command(subcommand)]
+    Service(ServiceCommands),
+
+    /// Saved remote hosts for --host
+    ///
+    /// Another Mac running Burd with remote access on (`burd api remote`),
+    /// e.g. a Mac mini serving the team's databases.
+    #[command(subcommand)]
+    Remote(RemoteCommands),
+
     /// Run MCP server for AI agent integration
     ///
     /// Starts an MCP (Model Context Protocol) server that communicates via stdio.
@@ -390,6 +721,56 @@ enum Commands {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+
+    /// Run wp-cli in the current WordPress or Bedrock project
+    ///
+    /// Uses the PHP of the FrankenPHP instance serving the project and puts
+    /// the database instance's client tools on PATH, so `wp db` commands
+    /// work without a system PHP or MySQL. wp-cli is downloaded on first use.
+    ///
+    /// Examples:
+    ///   burd wp install                     # Download or update wp-cli
+    ///   burd wp plugin list
+    ///   burd wp db import dump.sql
+    ///   burd wp db export backup.sql
+    ///   burd wp search-replace https://example.com   # Replace with the Burd URL
+    ///   burd wp search-replace old.test new.test
+    ///
+    /// Help is disabled on this subcommand so `-h`-style flags reach wp-cli;
+    /// use `burd wp help` for wp-cli's help.
+    #[command(name = "wp", disable_help_flag = true, disable_version_flag = true)]
+    Wp {
+        /// wp-cli command and arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Run Composer in the current project
+    ///
+    /// Runs the Composer version pinned in the project's `.composer-version`
+    /// (else the default) with the PHP selected by its `.php-version` (else
+    /// PVM's default), which is also first on PATH for `@php` scripts.
+    /// Composer is downloaded on first use.
+    ///
+    /// Examples:
+    ///   burd composer install
+    ///   burd composer require laravel/horizon
+    ///   burd composer use 2.2               # Pin a version in this project
+    ///   burd composer use stable --global   # Set the default version
+    ///   burd composer versions              # List installed versions
+    ///
+    /// Help is disabled on this subcommand so `-h`-style flags reach
+    /// Composer; use `burd composer help` for Composer's help.
+    #[command(
+        name = "composer",
+        disable_help_flag = true,
+        disable_version_flag = true
+    )]
+    Composer {
+        /// Composer command and arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
 }
 
 /// Environment subcommands
@@ -398,18 +779,465 @@ enum EnvCommands {
     /// Check .env against Burd services
     ///
     /// Compares your project's .env file with running Burd services
-    /// and reports any mismatches.
+    /// and reports any mismatches. For WordPress and Bedrock, also checks
+    /// the Redis Object Cache and Meilisearch plugin settings.
     Check,
 
     /// Fix .env issues interactively
     ///
-    /// Prompts for each issue found and offers to fix it.
-    Fix,
+    /// Prompts for each issue found and offers to fix it. With --apply-all,
+    /// applies every fix without asking, writing .env once and keeping the
+    /// previous version as .env.backup.
+    Fix {
+        /// Apply all fixes without prompting
+        #[arg(long)]
+        apply_all: bool,
+    },
 
     /// Show relevant .env values
     ///
     /// Displays database, cache, mail, and search settings.
     Show,
+
+    /// Store project secrets in the keychain
+    ///
+    /// Secrets are injected into the project's FrankenPHP or Bun instance,
+    /// its queue workers, `burd composer` and `burd wp` at runtime, and never
+    /// written to .env. Pass KEY without a value to be prompted for it.
+    Set {
+        /// KEY=VALUE pairs, or KEY to enter the value at a prompt
+        #[arg(required = true)]
+        assignments: Vec<String>,
+    },
+
+    /// Remove project secrets
+    Unset {
+        /// Secret names
+        #[arg(required = true)]
+        keys: Vec<String>,
+    },
+
+    /// List the current project's secrets
+    Secrets {
+        /// Print the values instead of masking them
+        #[arg(long)]
+        reveal: bool,
+    },
+
+    /// Route the project's mail to another Mailpit or an SMTP relay
+    ///
+    /// Without options, shows where the project's mail goes. After changing
+    /// the route, `burd env fix` writes the matching MAIL_* settings to .env.
+    /// Keep a relay's password in the vault with `burd env set MAIL_PASSWORD`.
+    Mail {
+        /// Send mail to this Mailpit instance (by name)
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["smtp", "reset"])]
+        mailpit: Option<String>,
+
+        /// Relay mail through this SMTP server (port defaults to 587)
+        #[arg(long, value_name = "HOST:PORT", conflicts_with = "reset")]
+        smtp: Option<String>,
+
+        /// SMTP username for the relay
+        #[arg(long, requires = "smtp")]
+        username: Option<String>,
+
+        /// Relay encryption: tls, ssl or none
+        #[arg(long, value_name = "MODE", requires = "smtp")]
+        encryption: Option<String>,
+
+        /// Remove the route and use the stack's Mailpit again
+        #[arg(long)]
+        reset: bool,
+    },
+}
+
+/// Queue subcommands
+#[derive(Subcommand)]
+enum QueueCommands {
+    /// List tubes with job counts, or peek at the jobs of one tube
+    Inspect {
+        /// Tube to inspect (shows the next ready, delayed, and buried job)
+        tube: Option<String>,
+
+        /// Use a specific Beanstalkd instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Kick buried or delayed jobs back into the ready queue
+    Kick {
+        /// Tube to kick jobs in
+        tube: Option<String>,
+
+        /// Kick a single job by ID instead of a whole tube
+        #[arg(long, value_name = "ID", conflicts_with = "tube")]
+        job: Option<u64>,
+
+        /// Maximum number of jobs to kick from the tube (default: 100)
+        #[arg(long)]
+        bound: Option<u32>,
+
+        /// Use a specific Beanstalkd instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Bury a ready or delayed job so workers stop picking it up
+    Bury {
+        /// Job ID
+        job: u64,
+
+        /// Use a specific Beanstalkd instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+}
+
+/// S3 subcommands
+#[derive(Subcommand)]
+enum S3Commands {
+    /// List buckets
+    List {
+        /// Use a specific MinIO instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Create a bucket
+    Create {
+        /// Bucket name
+        name: String,
+
+        /// Anonymous access: private (default), public-read, or public
+        #[arg(long)]
+        policy: Option<String>,
+
+        /// Use a specific MinIO instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Delete an empty bucket
+    Delete {
+        /// Bucket name
+        name: String,
+
+        /// Use a specific MinIO instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Set a bucket's anonymous access
+    Policy {
+        /// Bucket name
+        name: String,
+
+        /// private, public-read, or public
+        policy: String,
+
+        /// Use a specific MinIO instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Generate temporary access keys, printed as .env lines
+    Key {
+        /// Limit the key to one bucket
+        #[arg(long)]
+        bucket: Option<String>,
+
+        /// Hours until the key expires (default: 24, at most 168)
+        #[arg(long)]
+        hours: Option<u32>,
+
+        /// Use a specific MinIO instance by name
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+}
+
+/// Worker subcommands
+#[derive(Subcommand)]
+enum WorkerCommands {
+    /// Start the worker of a Laravel project
+    ///
+    /// Options left out keep the worker's saved settings (one queue:work
+    /// process on the default queue for a new worker).
+    ///
+    /// Examples:
+    ///   burd worker start                          # Current directory
+    ///   burd worker start --concurrency 3 --queue high,default
+    ///   burd worker start ~/Sites/shop --mode horizon
+    Start {
+        /// Project directory (default: current directory)
+        path: Option<String>,
+
+        /// queue (php artisan queue:work) or horizon
+        #[arg(long)]
+        mode: Option<String>,
+
+        /// Number of queue:work processes (1-16)
+        #[arg(long, short = 'c')]
+        concurrency: Option<u8>,
+
+        /// Queues to work, comma-separated in priority order ("" for the default)
+        #[arg(long)]
+        queue: Option<String>,
+
+        /// Queue connection, when not the project's default ("" to reset)
+        #[arg(long)]
+        connection: Option<String>,
+    },
+
+    /// Stop the worker of a Laravel project
+    Stop {
+        /// Project directory (default: current directory)
+        path: Option<String>,
+
+        /// Stop every worker
+        #[arg(long, conflicts_with = "path")]
+        all: bool,
+    },
+
+    /// List workers and their processes
+    Status,
+}
+
+/// Daemon subcommands
+#[derive(Subcommand)]
+enum DaemonCommands {
+    /// Run the daemon in the foreground (used by the login service)
+    Run,
+
+    /// Install the login service so the daemon starts at login
+    Install,
+
+    /// Stop the daemon and remove the login service
+    Uninstall,
+
+    /// Show whether the daemon is installed and running
+    Status,
+}
+
+/// DNS subcommands
+#[derive(Subcommand)]
+enum DnsCommands {
+    /// Test resolution hop by hop: resolver file, DNS port, local server, system resolver
+    Test {
+        /// Domain to resolve (default: the first configured domain)
+        domain: Option<String>,
+    },
+}
+
+/// Client certificate subcommands
+#[derive(Subcommand)]
+enum ClientCertCommands {
+    /// Require a client certificate, issuing one if the domain has none
+    Require {
+        /// Domain name (optional, defaults to current directory's domain)
+        name: Option<String>,
+    },
+    /// Stop requiring a client certificate (the certificate is kept)
+    Disable {
+        /// Domain name (optional, defaults to current directory's domain)
+        name: Option<String>,
+    },
+    /// Issue a new certificate; previously issued ones stop working
+    Reissue {
+        /// Domain name (optional, defaults to current directory's domain)
+        name: Option<String>,
+    },
+    /// Show the certificate files and the PKCS#12 password
+    Show {
+        /// Domain name (optional, defaults to current directory's domain)
+        name: Option<String>,
+    },
+}
+
+/// Root CA subcommands
+#[derive(Subcommand)]
+enum CaCommands {
+    /// Show whether the keychain and each Firefox profile trust the CA
+    Status,
+    /// Trust the CA in the keychain and every Firefox profile
+    Trust,
+    /// Write the CA, a combined CA bundle and setup instructions
+    Export {
+        /// Output directory (default: ~/Library/Application Support/Burd/ca)
+        dir: Option<String>,
+    },
+    /// Write the setup bundle other devices on the network use to trust the
+    /// CA and reach your domains, served at http://<lan-ip>/burd-setup/
+    Bundle,
+}
+
+/// HTTP API subcommands
+#[derive(Subcommand)]
+enum ApiCommands {
+    /// Show whether remote access is on, the allowed origins and the token
+    Status,
+    /// Accept connections from other machines (generates a token if needed)
+    Remote {
+        /// Address to listen on (default: 0.0.0.0, all interfaces)
+        #[arg(long)]
+        bind: Option<String>,
+        /// Browser origin allowed to call the API, e.g. https://dash.example.com
+        /// (repeatable; replaces the current list)
+        #[arg(long = "origin")]
+        origins: Vec<String>,
+    },
+    /// Only accept connections from this machine
+    Local,
+    /// Replace the API token; the old one stops working
+    RotateToken,
+}
+
+/// Backup subcommands
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// Show the schedule, folder, retention and the last run
+    Status,
+    /// List the backups in the backup folder
+    List,
+    /// Back up the config and selected instances now
+    Run,
+    /// Restore an instance from a backup (SQL databases must be running;
+    /// copied data directories must be stopped)
+    Restore {
+        /// Backup ID (see `burd backup list`)
+        id: String,
+
+        /// Instance name
+        instance: String,
+    },
+}
+
+/// Config subcommands
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Export the config to a .tar.gz archive
+    Export {
+        /// Archive to write (default: burd-config-<timestamp>.tar.gz)
+        path: Option<std::path::PathBuf>,
+    },
+    /// Import an exported config, skipping what already exists
+    Import {
+        /// Archive written by `burd config export`
+        path: std::path::PathBuf,
+
+        /// Show what would be imported without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Remote host subcommands
+#[derive(Subcommand)]
+enum RemoteCommands {
+    /// Save a remote host under a name
+    Add {
+        /// Name to use with --host, e.g. office
+        name: String,
+        /// API address, e.g. 192.168.1.20 or http://mini.local:19840
+        url: String,
+        /// Token shown by `burd api status` on that machine
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// List the saved remote hosts and whether they're reachable
+    List,
+    /// Forget a saved remote host
+    Remove {
+        /// Name of the host
+        name: String,
+    },
+}
+
+/// Service definition subcommands
+#[derive(Subcommand)]
+enum ServiceCommands {
+    /// Validate a service definition and install it, with its init hook
+    ///
+    /// The file name is the service ID (e.g. clickhouse.json). Burd loads
+    /// the definition the next time it starts.
+    AddDefinition {
+        /// Definition file
+        path: std::path::PathBuf,
+
+        /// Replace an installed definition with the same ID
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Stack subcommands
+#[derive(Subcommand)]
+enum StackCommands {
+    /// Start every instance of a stack in dependency order
+    Up {
+        /// Stack name or ID (default: the current directory's stack)
+        name: Option<String>,
+    },
+
+    /// Stop every instance of a stack, apps first
+    Down {
+        /// Stack name or ID (default: the current directory's stack)
+        name: Option<String>,
+    },
+
+    /// Stop a stack, then start it again in dependency order
+    Restart {
+        /// Stack name or ID (default: the current directory's stack)
+        name: Option<String>,
+    },
+}
+
+/// Snapshot subcommands
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// List snapshots (of one instance, or all that support snapshots)
+    List {
+        /// Instance name
+        instance: Option<String>,
+    },
+
+    /// Create a snapshot of an instance
+    Create {
+        /// Instance name
+        instance: String,
+
+        /// Optional label to recognize the snapshot later
+        #[arg(long)]
+        label: Option<String>,
+    },
+
+    /// Restore a snapshot (MongoDB must be running; other services must be stopped)
+    Restore {
+        /// Instance name
+        instance: String,
+
+        /// Snapshot ID (see `burd snapshot list`)
+        id: String,
+    },
+
+    /// Delete a snapshot
+    Delete {
+        /// Instance name
+        instance: String,
+
+        /// Snapshot ID (see `burd snapshot list`)
+        id: String,
+    },
+
+    /// Show or set how long databases' safety snapshots are kept
+    ///
+    /// A database is snapshotted before `burd db drop`, `burd db import` and
+    /// destructive `burd wp` commands such as search-replace.
+    Safety {
+        /// Hours to keep them (0 turns them off)
+        hours: Option<u32>,
+    },
 }
 
 /// Engine selector for `burd db create`
@@ -417,6 +1245,7 @@ enum EnvCommands {
 enum DbEngineArg {
     Mariadb,
     Postgres,
+    Mongodb,
 }
 
 impl From<DbEngineArg> for burd_lib::db_manager::DbType {
@@ -424,6 +1253,7 @@ impl From<DbEngineArg> for burd_lib::db_manager::DbType {
         match v {
             DbEngineArg::Mariadb => burd_lib::db_manager::DbType::MariaDB,
             DbEngineArg::Postgres => burd_lib::db_manager::DbType::PostgreSQL,
+            DbEngineArg::Mongodb => burd_lib::db_manager::DbType::MongoDB,
         }
     }
 }
@@ -439,7 +1269,7 @@ enum DbCommands {
         /// Database name
         name: String,
 
-        /// Engine to use when multiple are configured (mariadb | postgres)
+        /// Engine to use when multiple are configured (mariadb | postgres | mongodb)
         #[arg(long, value_enum)]
         engine: Option<DbEngineArg>,
 
@@ -457,6 +1287,10 @@ enum DbCommands {
         #[arg(short, long)]
         force: bool,
 
+        /// Don't snapshot the database before dropping it
+        #[arg(long)]
+        no_snapshot: bool,
+
         /// Restrict search to a specific engine
         #[arg(long, value_enum)]
         engine: Option<DbEngineArg>,
@@ -474,6 +1308,10 @@ enum DbCommands {
         /// Path to SQL file
         file: String,
 
+        /// Don't snapshot an existing database before importing into it
+        #[arg(long)]
+        no_snapshot: bool,
+
         /// Engine to use when the database doesn't exist yet
         #[arg(long, value_enum)]
         engine: Option<DbEngineArg>,
@@ -514,11 +1352,160 @@ enum DbCommands {
         #[arg(long, value_name = "NAME")]
         instance: Option<String>,
     },
+
+    /// Reset a database with its instance's seed (SQL file and/or command)
+    Seed {
+        /// Burd instance whose seed to run (needed when several have one)
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Manage MariaDB/MySQL users and their access to databases
+    #[command(subcommand)]
+    User(DbUserCommands),
+}
+
+/// Database user subcommands
+#[derive(Subcommand)]
+enum DbUserCommands {
+    /// List users, or those with access to a database
+    List {
+        /// Only users with access to this database
+        database: Option<String>,
+
+        /// Burd instance to use
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Create a user
+    Create {
+        /// User name
+        user: String,
+
+        /// Password (generated and printed when omitted)
+        #[arg(long)]
+        password: Option<String>,
+
+        /// Grant the user access to this database
+        #[arg(long)]
+        database: Option<String>,
+
+        /// Grant read access only
+        #[arg(long, requires = "database")]
+        read_only: bool,
+
+        /// Burd instance to use
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Drop a user
+    Drop {
+        /// User name
+        user: String,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+
+        /// Burd instance to use
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Grant a user access to a database (replaces its current access there)
+    Grant {
+        /// User name
+        user: String,
+
+        /// Database name
+        database: String,
+
+        /// Grant read access only
+        #[arg(long)]
+        read_only: bool,
+
+        /// Burd instance to use
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Revoke a user's access to a database
+    Revoke {
+        /// User name
+        user: String,
+
+        /// Database name
+        database: String,
+
+        /// Burd instance to use
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+
+    /// Change a user's password
+    Password {
+        /// User name
+        user: String,
+
+        /// New password (generated and printed when omitted)
+        #[arg(long)]
+        password: Option<String>,
+
+        /// Burd instance to use
+        #[arg(long, value_name = "NAME")]
+        instance: Option<String>,
+    },
+}
+
+/// Commands that run entirely through the API and so can target --host
+fn supports_host(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Instances { .. }
+            | Commands::Start { .. }
+            | Commands::Stop { .. }
+            | Commands::Restart { .. }
+            | Commands::Stack(_)
+            | Commands::Logs { .. }
+            | Commands::Update { .. }
+            | Commands::Xdebug { .. }
+            | Commands::Install { .. }
+            | Commands::Mcp
+    )
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    let host = cli
+        .host
+        .or_else(|| std::env::var("BURD_HOST").ok())
+        .filter(|h| !h.is_empty());
+    if let Some(host) = host {
+        let token = cli.token.or_else(|| std::env::var("BURD_TOKEN").ok());
+        let connected = if supports_host(&cli.command) {
+            cli::remote::connect(&host, token)
+        } else {
+            Err(
+                "This command only works on the machine running Burd; drop --host (or unset BURD_HOST)."
+                    .to_string(),
+            )
+        };
+        if let Err(e) = connected {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // Keep the previous version's config restorable (`burd rollback-env`)
+    cli::snapshot_on_startup();
+
     let result = match cli.command {
         Commands::Analyze => cli::run_analyze(),
         Commands::Init {
@@ -530,7 +1517,7 @@ fn main() {
             no_start,
             public_dir,
         }),
-        Commands::Park => cli::run_park(),
+        Commands::Park { depth } => cli::run_park(depth),
         Commands::Forget => cli::run_forget(),
         Commands::Parked => cli::run_parked(),
         Commands::Refresh => cli::run_refresh(),
@@ -540,41 +1527,131 @@ fn main() {
             no_ssl,
             no_start,
         } => cli::run_link_with(name, cli::LinkOptions { no_ssl, no_start }),
-        Commands::Start { name } => cli::run_start(name),
-        Commands::Stop { name } => cli::run_stop(name),
-        Commands::Restart { name } => cli::run_restart(name),
+        Commands::Instances { tags } => cli::run_instances_list(tags),
+        Commands::Start { name, tags, wait } if tags.is_empty() => cli::run_start(name, wait),
+        Commands::Start { tags, .. } => cli::run_tagged("start", tags),
+        Commands::Stop { name, tags } if tags.is_empty() => cli::run_stop(name),
+        Commands::Stop { tags, .. } => cli::run_tagged("stop", tags),
+        Commands::Restart { name, tags } if tags.is_empty() => cli::run_restart(name),
+        Commands::Restart { tags, .. } => cli::run_tagged("restart", tags),
+        Commands::Stack(stack_cmd) => match stack_cmd {
+            StackCommands::Up { name } => cli::run_stack_up(name),
+            StackCommands::Down { name } => cli::run_stack_down(name),
+            StackCommands::Restart { name } => cli::run_stack_restart(name),
+        },
         Commands::Logs {
             name,
             lines,
             follow,
         } => cli::run_logs(name, cli::LogsOptions { lines, follow }),
+        Commands::History { name, limit } => cli::run_history(name, limit),
+        Commands::Xdebug { mode, name } => cli::run_xdebug(&mode, name),
         Commands::Update {
             name,
             php_version,
             port,
             new_name,
+            auto_start,
+            restart_on_failure,
+            tags,
+            args,
+            clear_args,
         } => cli::run_update(
             name,
             cli::UpdateOptions {
                 php_version,
                 port,
                 new_name,
+                auto_start,
+                restart_on_failure,
+                tags: tags.map(|t| t.into_iter().filter(|t| !t.trim().is_empty()).collect()),
+                extra_args: if clear_args {
+                    Some(Vec::new())
+                } else if args.is_empty() {
+                    None
+                } else {
+                    Some(args)
+                },
             },
         ),
-        Commands::Versions { service } => cli::run_service_versions(service),
+        Commands::Versions {
+            service,
+            pin,
+            unpin,
+            arch,
+            channel,
+        } => match (service, pin, arch, channel) {
+            (Some(service), _, _, Some(channel)) => cli::run_set_channel(&service, &channel),
+            (Some(service), _, Some(arch), None) => cli::run_set_arch(&service, &arch),
+            (Some(service), Some(version), None, None) => {
+                cli::run_pin_version(&service, Some(version))
+            }
+            (Some(service), None, None, None) if unpin => cli::run_pin_version(&service, None),
+            (service, _, _, _) => cli::run_service_versions(service),
+        },
         Commands::Unlink => cli::run_unlink(),
         Commands::Links => cli::run_links(),
         Commands::Secure { name } => cli::run_secure(name),
         Commands::Unsecure { name } => cli::run_unsecure(name),
+        Commands::ClientCert(cert_cmd) => match cert_cmd {
+            ClientCertCommands::Require { name } => cli::run_client_cert_require(name),
+            ClientCertCommands::Disable { name } => cli::run_client_cert_disable(name),
+            ClientCertCommands::Reissue { name } => cli::run_client_cert_reissue(name),
+            ClientCertCommands::Show { name } => cli::run_client_cert_show(name),
+        },
+        Commands::Stats { name, minutes } => cli::run_stats(name, minutes),
         Commands::Open { name } => cli::run_open(name),
         Commands::Proxy { name, port } => cli::run_proxy(name, port),
         Commands::Unproxy { name } => cli::run_unproxy(name),
         Commands::Proxies => cli::run_proxies(),
-        Commands::New { template, name } => cli::run_new(&template, &name),
+        Commands::New {
+            template,
+            name,
+            db,
+            no_services,
+            no_link,
+            no_ssl,
+            no_start,
+        } => cli::run_new_with(
+            &template,
+            &name,
+            cli::NewOptions {
+                database: db.map(Into::into),
+                no_services,
+                no_link,
+                no_ssl,
+                no_start,
+            },
+        ),
         Commands::Setup => cli::run_setup(),
         Commands::Doctor => cli::run_doctor(),
+        Commands::Tui => cli::run_tui(),
+        Commands::Install { service, version } => cli::run_install(&service, version),
+        Commands::Verify { service, repair } => cli::run_verify(service, repair),
+        Commands::Daemon(daemon_cmd) => match daemon_cmd {
+            DaemonCommands::Run => cli::run_daemon(),
+            DaemonCommands::Install => cli::run_daemon_install(),
+            DaemonCommands::Uninstall => cli::run_daemon_uninstall(),
+            DaemonCommands::Status => cli::run_daemon_status(),
+        },
+        Commands::Dns(dns_cmd) => match dns_cmd {
+            DnsCommands::Test { domain } => cli::run_dns_test(domain.as_deref()),
+        },
+        Commands::Ca(ca_cmd) => match ca_cmd {
+            CaCommands::Status => cli::run_ca_status(),
+            CaCommands::Trust => cli::run_ca_trust(),
+            CaCommands::Export { dir } => cli::run_ca_export(dir),
+            CaCommands::Bundle => cli::run_ca_bundle(),
+        },
+        Commands::Api(api_cmd) => match api_cmd {
+            ApiCommands::Status => cli::run_api_status(),
+            ApiCommands::Remote { bind, origins } => cli::run_api_remote(bind, origins),
+            ApiCommands::Local => cli::run_api_local(),
+            ApiCommands::RotateToken => cli::run_api_rotate_token(),
+        },
         Commands::Upgrade { check } => cli::run_upgrade(check),
-        Commands::Share { subdomain } => cli::run_share(subdomain),
+        Commands::RollbackEnv { id, list } => cli::run_rollback_env(id, list),
+        Commands::Share { subdomain, headers } => cli::run_share(subdomain, headers),
         Commands::Db(db_cmd) => match db_cmd {
             DbCommands::List => cli::run_db_list(),
             DbCommands::Create {
@@ -585,15 +1662,29 @@ fn main() {
             DbCommands::Drop {
                 name,
                 force,
+                no_snapshot,
                 engine,
                 instance,
-            } => cli::run_db_drop(&name, force, engine.map(Into::into), instance.as_deref()),
+            } => cli::run_db_drop(
+                &name,
+                force,
+                no_snapshot,
+                engine.map(Into::into),
+                instance.as_deref(),
+            ),
             DbCommands::Import {
                 name,
                 file,
+                no_snapshot,
                 engine,
                 instance,
-            } => cli::run_db_import(&name, &file, engine.map(Into::into), instance.as_deref()),
+            } => cli::run_db_import(
+                &name,
+                &file,
+                no_snapshot,
+                engine.map(Into::into),
+                instance.as_deref(),
+            ),
             DbCommands::Export {
                 name,
                 output,
@@ -610,11 +1701,132 @@ fn main() {
                 engine,
                 instance,
             } => cli::run_db_shell(name.as_deref(), engine.map(Into::into), instance.as_deref()),
+            DbCommands::Seed { instance, force } => cli::run_db_seed(instance.as_deref(), force),
+            DbCommands::User(user_cmd) => match user_cmd {
+                DbUserCommands::List { database, instance } => {
+                    cli::run_db_user_list(database.as_deref(), instance.as_deref())
+                }
+                DbUserCommands::Create {
+                    user,
+                    password,
+                    database,
+                    read_only,
+                    instance,
+                } => cli::run_db_user_create(
+                    &user,
+                    password.as_deref(),
+                    database.as_deref(),
+                    read_only,
+                    instance.as_deref(),
+                ),
+                DbUserCommands::Drop {
+                    user,
+                    force,
+                    instance,
+                } => cli::run_db_user_drop(&user, force, instance.as_deref()),
+                DbUserCommands::Grant {
+                    user,
+                    database,
+                    read_only,
+                    instance,
+                } => cli::run_db_user_grant(&user, &database, read_only, instance.as_deref()),
+                DbUserCommands::Revoke {
+                    user,
+                    database,
+                    instance,
+                } => cli::run_db_user_revoke(&user, &database, instance.as_deref()),
+                DbUserCommands::Password {
+                    user,
+                    password,
+                    instance,
+                } => cli::run_db_user_password(&user, password.as_deref(), instance.as_deref()),
+            },
         },
         Commands::Env(env_cmd) => match env_cmd {
             EnvCommands::Check => cli::run_env_check(),
-            EnvCommands::Fix => cli::run_env_fix(),
+            EnvCommands::Fix { apply_all } => cli::run_env_fix(apply_all),
             EnvCommands::Show => cli::run_env_show(),
+            EnvCommands::Set { assignments } => cli::run_env_set(assignments),
+            EnvCommands::Unset { keys } => cli::run_env_unset(keys),
+            EnvCommands::Secrets { reveal } => cli::run_env_secrets(reveal),
+            EnvCommands::Mail {
+                mailpit,
+                smtp,
+                username,
+                encryption,
+                reset,
+            } => cli::run_env_mail(mailpit, smtp, username, encryption, reset),
+        },
+        Commands::Queue(queue_cmd) => match queue_cmd {
+            QueueCommands::Inspect { tube, instance } => {
+                cli::run_queue_inspect(tube.as_deref(), instance.as_deref())
+            }
+            QueueCommands::Kick {
+                tube,
+                job,
+                bound,
+                instance,
+            } => cli::run_queue_kick(tube.as_deref(), job, bound, instance.as_deref()),
+            QueueCommands::Bury { job, instance } => cli::run_queue_bury(job, instance.as_deref()),
+        },
+        Commands::S3(s3_cmd) => match s3_cmd {
+            S3Commands::List { instance } => cli::run_s3_list(instance.as_deref()),
+            S3Commands::Create {
+                name,
+                policy,
+                instance,
+            } => cli::run_s3_create(&name, policy.as_deref(), instance.as_deref()),
+            S3Commands::Delete { name, instance } => cli::run_s3_delete(&name, instance.as_deref()),
+            S3Commands::Policy {
+                name,
+                policy,
+                instance,
+            } => cli::run_s3_policy(&name, &policy, instance.as_deref()),
+            S3Commands::Key {
+                bucket,
+                hours,
+                instance,
+            } => cli::run_s3_key(bucket.as_deref(), hours, instance.as_deref()),
+        },
+        Commands::Worker(worker_cmd) => match worker_cmd {
+            WorkerCommands::Start {
+                path,
+                mode,
+                concurrency,
+                queue,
+                connection,
+            } => cli::run_worker_start(path, mode, concurrency, queue, connection),
+            WorkerCommands::Stop { path, all } => cli::run_worker_stop(path, all),
+            WorkerCommands::Status => cli::run_worker_status(),
+        },
+        Commands::Snapshot(snapshot_cmd) => match snapshot_cmd {
+            SnapshotCommands::List { instance } => cli::run_snapshot_list(instance.as_deref()),
+            SnapshotCommands::Create { instance, label } => {
+                cli::run_snapshot_create(&instance, label.as_deref())
+            }
+            SnapshotCommands::Restore { instance, id } => cli::run_snapshot_restore(&instance, &id),
+            SnapshotCommands::Delete { instance, id } => cli::run_snapshot_delete(&instance, &id),
+            SnapshotCommands::Safety { hours } => cli::run_snapshot_safety(hours),
+        },
+        Commands::Config(config_cmd) => match config_cmd {
+            ConfigCommands::Export { path } => cli::run_config_export(path),
+            ConfigCommands::Import { path, dry_run } => cli::run_config_import(&path, dry_run),
+        },
+        Commands::Service(service_cmd) => match service_cmd {
+            ServiceCommands::AddDefinition { path, force } => {
+                cli::run_add_service_definition(&path, force)
+            }
+        },
+        Commands::Remote(remote_cmd) => match remote_cmd {
+            RemoteCommands::Add { name, url, token } => cli::run_remote_add(&name, &url, token),
+            RemoteCommands::List => cli::run_remote_list(),
+            RemoteCommands::Remove { name } => cli::run_remote_remove(&name),
+        },
+        Commands::Backup(backup_cmd) => match backup_cmd {
+            BackupCommands::Status => cli::run_backup_status(),
+            BackupCommands::List => cli::run_backup_list(),
+            BackupCommands::Run => cli::run_backup_run(),
+            BackupCommands::Restore { id, instance } => cli::run_backup_restore(&id, &instance),
         },
         Commands::Mcp => cli::run_mcp(),
         Commands::Mysql { tool, args } => {
@@ -631,6 +1843,21 @@ fn main() {
                 cli::run_postgres(&tool, args)
             }
         }
+        Commands::Wp { args } => {
+            if args.len() == 1 && args[0] == "install" {
+                cli::run_wp_install()
+            } else {
+                cli::run_wp(args)
+            }
+        }
+        Commands::Composer { args } => match args.first().map(String::as_str) {
+            Some("use") if args.len() >= 2 => {
+                let global = args[2..].iter().any(|a| a == "--global" || a == "-g");
+                cli::run_composer_use(&args[1], global)
+            }
+            Some("versions") if args.len() == 1 => cli::run_composer_versions(),
+            _ => cli::run_composer(args),
+        },
     };
 
     if let Err(e) = result {
@@ -3,11 +3,14 @@
 //! Downloads, installs, and manages service binaries (Caddy, FrankenPHP, etc.).
 //! Handles version management, download progress reporting, and binary verification.
 
+use crate::arch::{self, Arch};
 use crate::config::{
-    get_bin_dir, get_binary_name, get_binary_path, get_service_bin_dir, get_versioned_binary_dir,
-    BinaryInfo, ConfigStore, ServiceType,
+    compare_versions, get_bin_dir, get_binary_name, get_binary_path, get_service_bin_dir,
+    get_versioned_binary_dir, BinaryInfo, ConfigStore, ReleaseChannel, ServiceType,
 };
-use crate::service_config::{get_current_platform, DownloadConfig, ServiceRegistry, VersionConfig};
+use crate::events::{self, Event};
+use crate::github;
+use crate::service_config::{platform_key, DownloadConfig, ServiceRegistry, VersionConfig};
 use crate::services::{get_service, DownloadMethod, VersionSource};
 use chrono::Utc;
 use flate2::read::GzDecoder;
@@ -35,6 +38,28 @@ pub struct VersionInfo {
     pub version: String,
     pub is_latest: bool,
     pub label: Option<String>,
+    /// Stable, release candidate, or beta
+    pub channel: ReleaseChannel,
+}
+
+/// Number of versions offered from GitHub releases
+const MAX_GITHUB_VERSIONS: usize = 20;
+
+impl VersionInfo {
+    /// Versions offered on `channel`, in the given order, newest marked latest
+    fn offered(versions: Vec<(String, ReleaseChannel)>, channel: ReleaseChannel) -> Vec<Self> {
+        versions
+            .into_iter()
+            .filter(|(_, c)| channel.includes(*c))
+            .enumerate()
+            .map(|(i, (version, channel))| VersionInfo {
+                version,
+                is_latest: i == 0,
+                label: None,
+                channel,
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,7 +68,7 @@ struct GitHubAsset {
     browser_download_url: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DownloadProgress {
     pub service_type: String,
     pub downloaded: u64,
@@ -53,6 +78,15 @@ pub struct DownloadProgress {
     pub phase: String,
 }
 
+/// Report download progress to the event stream, and to the GUI when the
+/// download was started from it
+fn report_progress(app: Option<&AppHandle>, progress: DownloadProgress) {
+    events::publish(Event::Download(progress.clone()));
+    if let Some(app) = app {
+        let _ = app.emit("download-progress", progress);
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct BinaryStatus {
     pub service_type: String,
@@ -61,16 +95,8 @@ pub struct BinaryStatus {
     pub path: Option<String>,
 }
 
-/// Verify the SHA256 checksum of a downloaded file
-///
-/// # Arguments
-/// * `file_path` - Path to the file to verify
-/// * `expected_checksum` - Expected SHA256 checksum as a hex string
-///
-/// # Returns
-/// * `Ok(())` if checksum matches
-/// * `Err(String)` with error message if verification fails
-fn verify_checksum(file_path: &Path, expected_checksum: &str) -> Result<(), String> {
+/// Compute the SHA256 of a file as a lowercase hex string
+pub(crate) fn file_sha256(file_path: &Path) -> Result<String, String> {
     // Read the file
     let mut file = File::open(file_path)
         .map_err(|e| format!("Failed to open file for checksum verification: {}", e))?;
@@ -88,8 +114,20 @@ fn verify_checksum(file_path: &Path, expected_checksum: &str) -> Result<(), Stri
         hasher.update(&buffer[..bytes_read]);
     }
 
-    let computed_hash = hasher.finalize();
-    let computed_hex = format!("{:x}", computed_hash);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Verify the SHA256 checksum of a downloaded file
+///
+/// # Arguments
+/// * `file_path` - Path to the file to verify
+/// * `expected_checksum` - Expected SHA256 checksum as a hex string
+///
+/// # Returns
+/// * `Ok(())` if checksum matches
+/// * `Err(String)` with error message if verification fails
+fn verify_checksum(file_path: &Path, expected_checksum: &str) -> Result<(), String> {
+    let computed_hex = file_sha256(file_path)?;
 
     // Compare checksums (case-insensitive)
     if computed_hex.eq_ignore_ascii_case(expected_checksum) {
@@ -191,8 +229,8 @@ impl BinaryManager {
             }
         }
 
-        // Sort versions (newest first, simple string sort works for semver)
-        versions.sort_by(|a, b| b.cmp(a));
+        // Newest first
+        versions.sort_by(|a, b| compare_versions(b, a));
 
         Ok(versions)
     }
@@ -208,16 +246,19 @@ impl BinaryManager {
     }
 
     /// Fetch available versions for a service
+    ///
+    /// Prereleases are only offered when `channel` includes them.
     pub async fn get_available_versions(
         &self,
         service_type: ServiceType,
+        channel: ReleaseChannel,
     ) -> Result<Vec<VersionInfo>, String> {
         let service_id = service_type.as_str();
         let registry = ServiceRegistry::load();
 
         // Try JSON config first
         if let Some(service_config) = registry.get_service(service_id) {
-            return self.get_versions_from_config(service_config).await;
+            return self.get_versions_from_config(service_config, channel).await;
         }
 
         // Fallback to trait-based service
@@ -225,16 +266,16 @@ impl BinaryManager {
         let version_source = service.version_source();
 
         match version_source {
-            VersionSource::GitHubReleases(api_url) => self.fetch_github_versions(api_url).await,
-            VersionSource::Static(versions) => Ok(versions
-                .into_iter()
-                .enumerate()
-                .map(|(i, v)| VersionInfo {
-                    version: v.to_string(),
-                    is_latest: i == 0,
-                    label: None,
-                })
-                .collect()),
+            VersionSource::GitHubReleases(api_url) => {
+                self.fetch_github_versions(api_url, channel).await
+            }
+            VersionSource::Static(versions) => Ok(VersionInfo::offered(
+                versions
+                    .into_iter()
+                    .map(|v| (v.to_string(), ReleaseChannel::of_version(v)))
+                    .collect(),
+                channel,
+            )),
         }
     }
 
@@ -242,21 +283,21 @@ impl BinaryManager {
     async fn get_versions_from_config(
         &self,
         config: &crate::service_config::ServiceConfig,
+        channel: ReleaseChannel,
     ) -> Result<Vec<VersionInfo>, String> {
         let mut versions = match &config.versions {
             VersionConfig::GithubReleases { github_repo } => {
                 let api_url = format!("https://api.github.com/repos/{}/releases", github_repo);
-                self.fetch_github_versions(&api_url).await?
+                self.fetch_github_versions(&api_url, channel).await?
             }
-            VersionConfig::Static { versions } => versions
-                .iter()
-                .enumerate()
-                .map(|(i, v)| VersionInfo {
-                    version: v.clone(),
-                    is_latest: i == 0,
-                    label: None,
-                })
-                .collect(),
+            // Static lists are kept in the order services.json gives them
+            VersionConfig::Static { versions } => VersionInfo::offered(
+                versions
+                    .iter()
+                    .map(|v| (v.clone(), ReleaseChannel::of_version(v)))
+                    .collect(),
+                channel,
+            ),
         };
 
         // Apply version labels from config
@@ -274,30 +315,33 @@ impl BinaryManager {
         Ok(versions)
     }
 
-    /// Fetch versions from GitHub releases API
-    async fn fetch_github_versions(&self, api_url: &str) -> Result<Vec<VersionInfo>, String> {
-        let releases: Vec<GitHubRelease> = self
-            .client
-            .get(api_url)
-            .header("User-Agent", "Burd-App")
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch releases: {}", e))?
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse releases: {}", e))?;
+    /// Fetch versions from GitHub releases API, newest first
+    async fn fetch_github_versions(
+        &self,
+        api_url: &str,
+        channel: ReleaseChannel,
+    ) -> Result<Vec<VersionInfo>, String> {
+        let body = github::get_cached(&self.client, api_url).await?;
+        let releases: Vec<GitHubRelease> =
+            serde_json::from_str(&body).map_err(|e| format!("Failed to parse releases: {}", e))?;
 
-        let versions: Vec<VersionInfo> = releases
+        // GitHub lists releases by creation date, so a patch for an older
+        // line can come before a newer minor release
+        let mut releases: Vec<(String, ReleaseChannel)> = releases
             .into_iter()
-            .filter(|r| !r.prerelease)
-            .take(20)
-            .enumerate()
-            .map(|(i, r)| VersionInfo {
-                version: r.tag_name,
-                is_latest: i == 0,
-                label: None,
+            .map(|r| {
+                let channel = match ReleaseChannel::of_version(&r.tag_name) {
+                    // Marked as a prerelease without saying which kind
+                    ReleaseChannel::Stable if r.prerelease => ReleaseChannel::Beta,
+                    channel => channel,
+                };
+                (r.tag_name, channel)
             })
             .collect();
+        releases.sort_by(|(a, _), (b, _)| compare_versions(b, a));
+
+        let mut versions = VersionInfo::offered(releases, channel);
+        versions.truncate(MAX_GITHUB_VERSIONS);
 
         if versions.is_empty() {
             Err("No releases found".to_string())
@@ -306,15 +350,27 @@ impl BinaryManager {
         }
     }
 
+    /// Fetch a single release (by tag) from the GitHub releases API
+    async fn fetch_github_release(&self, release_url: &str) -> Result<GitHubRelease, String> {
+        let body = github::get_cached(&self.client, release_url).await?;
+        serde_json::from_str(&body).map_err(|e| format!("Failed to parse release info: {}", e))
+    }
+
+    /// Download a version, preferring a native build
+    ///
+    /// `arch_override` forces one architecture (no Rosetta fallback).
+    /// Downloads started through the HTTP API have no `app` and report
+    /// progress on the event stream only.
     pub async fn download(
         &self,
         service_type: ServiceType,
         version: &str,
-        app: AppHandle,
+        arch_override: Option<Arch>,
+        app: Option<AppHandle>,
     ) -> Result<BinaryInfo, String> {
         let service_id = service_type.as_str();
         let registry = ServiceRegistry::load();
-        let platform = get_current_platform();
+        let target_arch = arch_override.unwrap_or_else(Arch::host);
 
         // Create base bin directory
         let bin_dir = get_bin_dir()?;
@@ -324,10 +380,14 @@ impl BinaryManager {
         // Try to get download info from JSON config
         let (download_url, final_version, is_archive, binary_name, checksum) =
             if let Some(service_config) = registry.get_service(service_id) {
-                let platform_config = service_config
-                    .platforms
-                    .get(&platform)
-                    .ok_or_else(|| format!("No download available for platform: {}", platform))?;
+                let (_, platform_config) = service_config
+                    .platform_for(target_arch, arch_override.is_some())
+                    .ok_or_else(|| {
+                        format!(
+                            "No download available for platform: {}",
+                            platform_key(target_arch)
+                        )
+                    })?;
 
                 let binary_name = platform_config
                     .binary_name
@@ -344,16 +404,7 @@ impl BinaryManager {
                                 "https://api.github.com/repos/{}/releases/tags/{}",
                                 github_repo, version
                             );
-                            let release: GitHubRelease = self
-                                .client
-                                .get(&release_url)
-                                .header("User-Agent", "Burd-App")
-                                .send()
-                                .await
-                                .map_err(|e| format!("Failed to fetch release info: {}", e))?
-                                .json()
-                                .await
-                                .map_err(|e| format!("Failed to parse release info: {}", e))?;
+                            let release = self.fetch_github_release(&release_url).await?;
 
                             let asset = release
                                 .assets
@@ -413,29 +464,27 @@ impl BinaryManager {
                                 formula,
                                 &binary_name,
                                 &bin_dir,
-                                &app,
+                                app.as_ref(),
                             )
                             .await;
                     }
                     DownloadConfig::Npm { package } => {
-                        // NPM packages are installed per-instance, not downloaded as binaries
-                        // Return a "virtual" BinaryInfo that marks this version as available
-                        return Ok(BinaryInfo {
-                            version: version.to_string(),
-                            path: format!("npm:{}", package),
-                            downloaded_at: chrono::Utc::now(),
-                        });
+                        // Handle npm installation - returns early
+                        return self
+                            .install_npm_package(
+                                service_type,
+                                package,
+                                version,
+                                &binary_name,
+                                app.as_ref(),
+                            )
+                            .await;
                     }
                 }
             } else {
                 // Fallback to trait-based service
                 let service = get_service(service_type);
-                let arch = if cfg!(target_arch = "aarch64") {
-                    "aarch64"
-                } else {
-                    "x86_64"
-                };
-                let download_method = service.download_method(version, arch);
+                let download_method = service.download_method(version, target_arch.target_name());
 
                 match download_method {
                     DownloadMethod::GitHubRelease {
@@ -444,16 +493,7 @@ impl BinaryManager {
                         checksum,
                     } => {
                         let release_url = format!("{}{}", api_url, version);
-                        let release: GitHubRelease = self
-                            .client
-                            .get(&release_url)
-                            .header("User-Agent", "Burd-App")
-                            .send()
-                            .await
-                            .map_err(|e| format!("Failed to fetch release info: {}", e))?
-                            .json()
-                            .await
-                            .map_err(|e| format!("Failed to parse release info: {}", e))?;
+                        let release = self.fetch_github_release(&release_url).await?;
 
                         let asset = release
                             .assets
@@ -558,6 +598,7 @@ impl BinaryManager {
             .map_err(|e| format!("Failed to create download file: {}", e))?;
 
         let mut downloaded: u64 = 0;
+        let mut published_percent = None;
         let mut stream = response.bytes_stream();
 
         while let Some(chunk) = stream.next().await {
@@ -579,7 +620,15 @@ impl BinaryManager {
                 phase: "downloading".to_string(),
             };
 
-            let _ = app.emit("download-progress", progress);
+            // The event stream gets whole-percent steps rather than every chunk
+            let percent = progress.percentage as u32;
+            if published_percent != Some(percent) {
+                published_percent = Some(percent);
+                events::publish(Event::Download(progress.clone()));
+            }
+            if let Some(app) = &app {
+                let _ = app.emit("download-progress", progress);
+            }
         }
 
         drop(file); // Close file before extraction
@@ -591,8 +640,8 @@ impl BinaryManager {
 
         // Emit extracting phase
         if is_archive {
-            let _ = app.emit(
-                "download-progress",
+            report_progress(
+                app.as_ref(),
                 DownloadProgress {
                     service_type: service_type.as_str().to_string(),
                     downloaded: total_size,
@@ -632,11 +681,13 @@ impl BinaryManager {
         // Make executable and remove quarantine attribute (macOS Gatekeeper)
         make_binary_executable(&binary_path)?;
 
-        // Return binary info
+        // Return binary info, recording the hash for later re-verification
         let binary_info = BinaryInfo {
             version: final_version,
             path: binary_path.to_string_lossy().to_string(),
             downloaded_at: Utc::now(),
+            sha256: Some(file_sha256(&binary_path)?),
+            arch: arch::detect(&binary_path),
         };
 
         Ok(binary_info)
@@ -649,13 +700,13 @@ impl BinaryManager {
         formula: &str,
         binary_name: &str,
         bin_dir: &std::path::Path,
-        app: &AppHandle,
+        app: Option<&AppHandle>,
     ) -> Result<BinaryInfo, String> {
         use std::process::Command;
 
         // Emit initial progress
-        let _ = app.emit(
-            "download-progress",
+        report_progress(
+            app,
             DownloadProgress {
                 service_type: service_type.as_str().to_string(),
                 downloaded: 0,
@@ -683,8 +734,8 @@ impl BinaryManager {
 
         if !list_output.status.success() {
             // Install the formula
-            let _ = app.emit(
-                "download-progress",
+            report_progress(
+                app,
                 DownloadProgress {
                     service_type: service_type.as_str().to_string(),
                     downloaded: 0,
@@ -740,10 +791,13 @@ impl BinaryManager {
             .map(|e| e.file_name().to_string_lossy().to_string())
             .ok_or_else(|| format!("No version found in Cellar: {}", cellar))?;
 
-        // Find the binary in prefix/bin/
-        let source_binary = std::path::PathBuf::from(&prefix)
-            .join("bin")
-            .join(binary_name);
+        // Find the binary in prefix/bin/ (servers like rabbitmq-server live in sbin/)
+        let prefix_dir = std::path::PathBuf::from(&prefix);
+        let source_binary = ["bin", "sbin"]
+            .iter()
+            .map(|dir| prefix_dir.join(dir).join(binary_name))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| prefix_dir.join("bin").join(binary_name));
 
         if !source_binary.exists() {
             return Err(format!(
@@ -799,8 +853,8 @@ impl BinaryManager {
         }
 
         // Emit completion
-        let _ = app.emit(
-            "download-progress",
+        report_progress(
+            app,
             DownloadProgress {
                 service_type: service_type.as_str().to_string(),
                 downloaded: 100,
@@ -810,10 +864,137 @@ impl BinaryManager {
             },
         );
 
+        // No hash: the link target is managed (and upgraded) by Homebrew
         Ok(BinaryInfo {
             version,
             path: dest_binary.to_string_lossy().to_string(),
             downloaded_at: Utc::now(),
+            sha256: None,
+            arch: arch::detect(&dest_binary),
+        })
+    }
+
+    /// Install an npm package into its version directory
+    ///
+    /// Runs `npm install` with the Node from `nvm::find_node` and writes an
+    /// executable launcher named after the binary that runs the package's
+    /// bin script with that Node, so the process supervisor can start it
+    /// like any other binary.
+    async fn install_npm_package(
+        &self,
+        service_type: ServiceType,
+        package: &str,
+        version: &str,
+        binary_name: &str,
+        app: Option<&AppHandle>,
+    ) -> Result<BinaryInfo, String> {
+        use std::process::Command;
+
+        report_progress(
+            app,
+            DownloadProgress {
+                service_type: service_type.as_str().to_string(),
+                downloaded: 0,
+                total: 0,
+                percentage: -1.0, // Indeterminate
+                phase: "installing".to_string(),
+            },
+        );
+
+        let node = crate::nvm::find_node().ok_or_else(|| {
+            format!(
+                "Node.js is required to install {}. Install it from the Node section or with Homebrew",
+                service_type.display_name()
+            )
+        })?;
+        let node_dir = node.parent().ok_or("Invalid Node.js path")?;
+        let npm = node_dir.join("npm");
+        if !npm.exists() {
+            return Err(format!("npm not found next to {}", node.display()));
+        }
+
+        // Install into a staging directory first: "latest" only resolves to
+        // a version once the package is installed
+        let service_dir = get_service_bin_dir(service_type)?;
+        let staging_dir = service_dir.join(".npm-install");
+        let _ = fs::remove_dir_all(&staging_dir);
+        fs::create_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to create install directory: {}", e))?;
+
+        // npm's own scripts start with `#!/usr/bin/env node`
+        let path = match std::env::var("PATH") {
+            Ok(path) => format!("{}:{}", node_dir.display(), path),
+            Err(_) => node_dir.display().to_string(),
+        };
+        let install_output = Command::new(&npm)
+            .arg("install")
+            .arg("--prefix")
+            .arg(&staging_dir)
+            .args(["--omit=dev", "--no-audit", "--no-fund"])
+            .arg(format!("{}@{}", package, version))
+            .env("PATH", path)
+            .env_remove("npm_config_prefix")
+            .output()
+            .map_err(|e| format!("Failed to run npm: {}", e))?;
+
+        if !install_output.status.success() {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(format!(
+                "npm install failed: {}",
+                String::from_utf8_lossy(&install_output.stderr)
+            ));
+        }
+
+        let manifest_path = staging_dir
+            .join("node_modules")
+            .join(package)
+            .join("package.json");
+        let manifest: serde_json::Value = fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .ok_or_else(|| format!("Failed to read {}", manifest_path.display()))?;
+        let installed_version = manifest["version"]
+            .as_str()
+            .ok_or_else(|| format!("No version in {}", manifest_path.display()))?
+            .to_string();
+
+        let version_dir = get_versioned_binary_dir(service_type, &installed_version)?;
+        let _ = fs::remove_dir_all(&version_dir);
+        fs::rename(&staging_dir, &version_dir)
+            .map_err(|e| format!("Failed to move installed package: {}", e))?;
+
+        let launcher = version_dir.join(binary_name);
+        let script = format!(
+            "#!/bin/sh\nexec \"{}\" \"{}\" \"$@\"\n",
+            node.display(),
+            version_dir
+                .join("node_modules")
+                .join(".bin")
+                .join(binary_name)
+                .display()
+        );
+        fs::write(&launcher, script).map_err(|e| format!("Failed to write launcher: {}", e))?;
+        fs::set_permissions(&launcher, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to set permissions: {}", e))?;
+
+        report_progress(
+            app,
+            DownloadProgress {
+                service_type: service_type.as_str().to_string(),
+                downloaded: 100,
+                total: 100,
+                percentage: 100.0,
+                phase: "complete".to_string(),
+            },
+        );
+
+        // No hash: the launcher is generated and the package tree is npm's
+        Ok(BinaryInfo {
+            version: installed_version,
+            path: launcher.to_string_lossy().to_string(),
+            downloaded_at: Utc::now(),
+            sha256: None,
+            arch: None,
         })
     }
 
@@ -1262,7 +1443,7 @@ fn find_package_root(dir: &Path, binary_name: &str) -> Option<PathBuf> {
 }
 
 /// Copy contents of a directory recursively
-fn copy_dir_contents(src: &Path, dest: &Path) -> Result<(), String> {
+pub(crate) fn copy_dir_contents(src: &Path, dest: &Path) -> Result<(), String> {
     if let Ok(entries) = fs::read_dir(src) {
         for entry in entries.flatten() {
             let src_path = entry.path();
@@ -0,0 +1,394 @@
+//! Binary integrity verification
+//!
+//! Re-checks installed service binaries and repairs what can be fixed in
+//! place:
+//!
+//! 1. The binary (or the Homebrew symlink pointing at it) still exists
+//! 2. Homebrew symlinks still resolve; `brew upgrade` and `brew cleanup`
+//!    remove the Cellar directory an old link may point into, so dangling
+//!    links are re-pointed at the formula's current prefix
+//! 3. The executable bits are set
+//! 4. The macOS quarantine attribute is gone (Gatekeeper blocks the binary
+//!    and its bundled libraries otherwise)
+//! 5. The SHA256 matches the one recorded at install
+//! 6. x86_64-only binaries on Apple Silicon have Rosetta 2 to run under
+//!
+//! A hash mismatch can't be repaired in place; that version has to be
+//! downloaded again.
+
+use crate::arch::{self, BinaryArch};
+use crate::binary::file_sha256;
+use crate::config::{BinaryInfo, Config, ServiceType};
+use crate::service_config::{DownloadConfig, ServiceRegistry};
+use serde::Serialize;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Kind of problem found with an installed binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Missing,
+    BrokenSymlink,
+    NotExecutable,
+    Quarantined,
+    HashMismatch,
+    RosettaMissing,
+}
+
+impl IssueKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IssueKind::Missing => "Missing",
+            IssueKind::BrokenSymlink => "Broken symlink",
+            IssueKind::NotExecutable => "Not executable",
+            IssueKind::Quarantined => "Quarantined",
+            IssueKind::HashMismatch => "Hash mismatch",
+            IssueKind::RosettaMissing => "Rosetta missing",
+        }
+    }
+}
+
+/// A problem found with an installed binary
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryIssue {
+    pub kind: IssueKind,
+    pub detail: String,
+    /// Whether the problem was fixed during this run
+    pub repaired: bool,
+    /// What to do when it wasn't (or can't be) repaired
+    pub hint: Option<String>,
+}
+
+/// Verification result for one installed version
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryCheck {
+    pub service_type: ServiceType,
+    pub version: String,
+    pub path: String,
+    /// Whether a hash was recorded at install and still matches
+    pub hash_verified: bool,
+    /// Architecture read from the binary's header
+    pub arch: Option<BinaryArch>,
+    pub issues: Vec<BinaryIssue>,
+}
+
+impl BinaryCheck {
+    /// Whether the binary is usable (no issues, or all repaired)
+    pub fn healthy(&self) -> bool {
+        self.issues.iter().all(|i| i.repaired)
+    }
+
+    fn issue(&mut self, kind: IssueKind, detail: String, repaired: bool, hint: Option<String>) {
+        self.issues.push(BinaryIssue {
+            kind,
+            detail,
+            repaired,
+            hint,
+        });
+    }
+}
+
+/// Verify every installed binary, optionally limited to one service type
+///
+/// With `repair`, broken Homebrew links, missing executable bits, and
+/// quarantine attributes are fixed.
+pub fn verify_binaries(
+    config: &Config,
+    service_type: Option<ServiceType>,
+    repair: bool,
+) -> Vec<BinaryCheck> {
+    let mut installed: Vec<(ServiceType, &String, &BinaryInfo)> = config
+        .binaries
+        .iter()
+        .filter(|(st, _)| service_type.is_none_or(|wanted| wanted == **st))
+        .flat_map(|(st, versions)| versions.iter().map(move |(v, info)| (*st, v, info)))
+        // Placeholder entries of per-instance npm packages have nothing on disk to check
+        .filter(|(_, _, info)| !info.path.starts_with("npm:"))
+        .collect();
+    installed.sort_by(|a, b| (a.0.as_str(), a.1).cmp(&(b.0.as_str(), b.1)));
+
+    installed
+        .into_iter()
+        .map(|(st, version, info)| verify_binary(st, version, info, repair))
+        .collect()
+}
+
+/// Verify (and optionally repair) one installed version
+pub fn verify_binary(
+    service_type: ServiceType,
+    version: &str,
+    info: &BinaryInfo,
+    repair: bool,
+) -> BinaryCheck {
+    let path = PathBuf::from(&info.path);
+    let redownload = format!(
+        "Delete {} {} in the Burd app and download it again.",
+        service_type.display_name(),
+        version
+    );
+    let mut check = BinaryCheck {
+        service_type,
+        version: version.to_string(),
+        path: info.path.clone(),
+        hash_verified: false,
+        arch: None,
+        issues: Vec::new(),
+    };
+
+    let Ok(link_meta) = fs::symlink_metadata(&path) else {
+        check.issue(
+            IssueKind::Missing,
+            format!("{} does not exist", path.display()),
+            false,
+            Some(redownload),
+        );
+        return check;
+    };
+
+    let is_symlink = link_meta.file_type().is_symlink();
+    if is_symlink && !path.exists() {
+        let target = fs::read_link(&path)
+            .map(|t| t.display().to_string())
+            .unwrap_or_default();
+        let formula = homebrew_formula(service_type);
+        let relinked = match &formula {
+            Some(formula) if repair => Some(relink_homebrew(&path, formula)),
+            _ => None,
+        };
+        match relinked {
+            Some(Ok(new_target)) => check.issue(
+                IssueKind::BrokenSymlink,
+                format!(
+                    "pointed to missing {} (re-linked to {})",
+                    target,
+                    new_target.display()
+                ),
+                true,
+                None,
+            ),
+            relinked => {
+                let hint = match (formula, relinked) {
+                    (Some(formula), Some(Err(e))) => {
+                        format!("{}. Try `brew reinstall {}`.", e, formula)
+                    }
+                    (Some(_), _) => "Run `burd verify --repair` to re-link it.".to_string(),
+                    (None, _) => redownload,
+                };
+                check.issue(
+                    IssueKind::BrokenSymlink,
+                    format!("points to missing {}", target),
+                    false,
+                    Some(hint),
+                );
+                return check;
+            }
+        }
+    }
+
+    if let Ok(meta) = fs::metadata(&path) {
+        if meta.permissions().mode() & 0o111 == 0 {
+            // Homebrew owns link targets; only fix files Burd installed
+            let repaired = repair && !is_symlink && set_executable(&path).is_ok();
+            let hint = if is_symlink {
+                homebrew_formula(service_type).map(|f| format!("Try `brew reinstall {}`.", f))
+            } else if !repaired {
+                Some("Run `burd verify --repair` to fix permissions.".to_string())
+            } else {
+                None
+            };
+            check.issue(
+                IssueKind::NotExecutable,
+                format!("mode {:o}", meta.permissions().mode() & 0o777),
+                repaired,
+                hint,
+            );
+        }
+    }
+
+    if !is_symlink && is_quarantined(&path) {
+        let dir = path.parent().unwrap_or(&path);
+        let repaired = repair && clear_quarantine(dir).is_ok();
+        let hint = (!repaired).then(|| {
+            "Run `burd verify --repair` to clear it, or allow it in System Settings > \
+             Privacy & Security."
+                .to_string()
+        });
+        check.issue(
+            IssueKind::Quarantined,
+            "com.apple.quarantine attribute is set".to_string(),
+            repaired,
+            hint,
+        );
+    }
+
+    check.arch = arch::detect(&path);
+    if check.arch.is_some_and(|a| a.needs_rosetta()) && !arch::rosetta_installed() {
+        check.issue(
+            IssueKind::RosettaMissing,
+            "x86_64 binary on Apple Silicon".to_string(),
+            false,
+            Some(
+                "Install Rosetta: softwareupdate --install-rosetta --agree-to-license".to_string(),
+            ),
+        );
+    }
+
+    if let (Some(expected), false) = (&info.sha256, is_symlink) {
+        match file_sha256(&path) {
+            Ok(actual) if actual.eq_ignore_ascii_case(expected) => check.hash_verified = true,
+            Ok(actual) => check.issue(
+                IssueKind::HashMismatch,
+                format!("expected {}, found {}", expected, actual),
+                false,
+                Some(redownload),
+            ),
+            Err(e) => check.issue(IssueKind::HashMismatch, e, false, Some(redownload)),
+        }
+    }
+
+    check
+}
+
+/// Homebrew formula a service is installed from on this platform, if any
+fn homebrew_formula(service_type: ServiceType) -> Option<String> {
+    let service = ServiceRegistry::load().get_service(service_type.as_str())?;
+    match &service.current_platform()?.download {
+        DownloadConfig::Homebrew { formula } => Some(formula.clone()),
+        _ => None,
+    }
+}
+
+/// Point a dangling link at the binary in the formula's current prefix
+fn relink_homebrew(link: &Path, formula: &str) -> Result<PathBuf, String> {
+    let output = Command::new("brew")
+        .args(["--prefix", formula])
+        .output()
+        .map_err(|_| "Homebrew is not installed".to_string())?;
+    if !output.status.success() {
+        return Err(format!("Formula '{}' is not installed", formula));
+    }
+
+    let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let binary_name = link.file_name().ok_or("Invalid binary path")?;
+    let target = Path::new(&prefix).join("bin").join(binary_name);
+    if !target.exists() {
+        return Err(format!("{} not found", target.display()));
+    }
+
+    fs::remove_file(link).map_err(|e| format!("Failed to remove broken link: {}", e))?;
+    std::os::unix::fs::symlink(&target, link)
+        .map_err(|e| format!("Failed to create symlink: {}", e))?;
+    Ok(target)
+}
+
+fn set_executable(path: &Path) -> Result<(), String> {
+    let mut perms = fs::metadata(path)
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms)
+        .map_err(|e| format!("Failed to set executable permission: {}", e))
+}
+
+/// Whether Gatekeeper's quarantine attribute is set on a file
+fn is_quarantined(path: &Path) -> bool {
+    cfg!(target_os = "macos")
+        && Command::new("xattr")
+            .args(["-p", "com.apple.quarantine"])
+            .arg(path)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+}
+
+/// Clear the quarantine attribute from a version directory (binary and
+/// bundled libraries)
+fn clear_quarantine(dir: &Path) -> Result<(), String> {
+    let status = Command::new("xattr")
+        .args(["-dr", "com.apple.quarantine"])
+        .arg(dir)
+        .status()
+        .map_err(|e| format!("Failed to run xattr: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("xattr failed to clear the quarantine attribute".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn install(dir: &Path, contents: &[u8], mode: u32) -> BinaryInfo {
+        let path = dir.join("meilisearch");
+        fs::write(&path, contents).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        BinaryInfo {
+            version: "1.6.0".to_string(),
+            path: path.to_string_lossy().to_string(),
+            downloaded_at: Utc::now(),
+            sha256: Some(file_sha256(&path).unwrap()),
+            arch: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_healthy_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = install(dir.path(), b"binary", 0o755);
+
+        let check = verify_binary(ServiceType::Meilisearch, "1.6.0", &info, false);
+        assert!(check.issues.is_empty());
+        assert!(check.hash_verified);
+        assert!(check.healthy());
+    }
+
+    #[test]
+    fn test_repair_executable_bits() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = install(dir.path(), b"binary", 0o644);
+
+        let check = verify_binary(ServiceType::Meilisearch, "1.6.0", &info, false);
+        assert_eq!(check.issues[0].kind, IssueKind::NotExecutable);
+        assert!(!check.healthy());
+
+        let check = verify_binary(ServiceType::Meilisearch, "1.6.0", &info, true);
+        assert!(check.healthy());
+        let mode = fs::metadata(&info.path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_hash_mismatch_is_not_repaired() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = install(dir.path(), b"binary", 0o755);
+        fs::write(&info.path, b"tampered").unwrap();
+
+        let check = verify_binary(ServiceType::Meilisearch, "1.6.0", &info, true);
+        assert_eq!(check.issues.len(), 1);
+        assert_eq!(check.issues[0].kind, IssueKind::HashMismatch);
+        assert!(!check.healthy());
+    }
+
+    #[test]
+    fn test_missing_and_dangling_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut info = install(dir.path(), b"binary", 0o755);
+        info.path = dir.path().join("gone").to_string_lossy().to_string();
+        let check = verify_binary(ServiceType::Meilisearch, "1.6.0", &info, false);
+        assert_eq!(check.issues[0].kind, IssueKind::Missing);
+
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(dir.path().join("Cellar/old/bin/x"), &link).unwrap();
+        info.path = link.to_string_lossy().to_string();
+        let check = verify_binary(ServiceType::Meilisearch, "1.6.0", &info, false);
+        assert_eq!(check.issues.len(), 1);
+        assert_eq!(check.issues[0].kind, IssueKind::BrokenSymlink);
+        assert!(check.issues[0].detail.contains("Cellar/old"));
+    }
+}
@@ -3,12 +3,17 @@
 //! This module manages the Caddy web server for HTTPS reverse proxy.
 //! Caddy provides automatic local HTTPS with its built-in CA.
 
-use crate::config::get_app_dir;
+use crate::api::API_PORT;
+use crate::client_certs;
+use crate::config::{get_app_dir, ConfigStore, Domain, DomainTarget};
+use crate::device_setup;
+use crate::platform;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
+use uuid::Uuid;
 
 /// Get the data directory for Burd app
 fn get_data_dir() -> Result<PathBuf, String> {
@@ -36,6 +41,13 @@ pub fn get_logs_dir() -> PathBuf {
         .join("Library/Logs/Burd")
 }
 
+/// Get the path to a site's JSON access log
+pub fn get_site_log_path(domain: &str) -> PathBuf {
+    get_logs_dir()
+        .join("sites")
+        .join(format!("{}.json", domain))
+}
+
 /// Get the path where we install Caddy binary for the daemon (in user space)
 pub fn get_caddy_daemon_bin() -> PathBuf {
     get_data_dir()
@@ -62,10 +74,25 @@ pub struct RouteEntry {
     /// Whether SSL/HTTPS is enabled for this route
     #[serde(default)]
     pub ssl_enabled: bool,
+    /// Redirect HTTP to HTTPS (only with SSL)
+    #[serde(default)]
+    pub force_https: bool,
+    /// Send Strict-Transport-Security over HTTPS (only with SSL)
+    #[serde(default)]
+    pub hsts: bool,
+    /// CA certificate that client certificates must chain to (only with SSL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca: Option<String>,
+    /// Raw Caddyfile directives added to every block that serves the site
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_directives: Option<String>,
+    /// Send 502s to the API's wake endpoint, which starts the stopped instance
+    #[serde(default)]
+    pub wake: bool,
 }
 
 /// Common CSS styles for error pages
-const ERROR_PAGE_STYLES: &str = r#"*{margin:0;padding:0;box-sizing:border-box}
+pub(crate) const ERROR_PAGE_STYLES: &str = r#"*{margin:0;padding:0;box-sizing:border-box}
 body{font-family:-apple-system,BlinkMacSystemFont,"Segoe UI",Roboto,sans-serif;min-height:100vh;display:flex;align-items:center;justify-content:center;background:#f5f5f7;color:#1d1d1f}
 .container{text-align:center;padding:40px;max-width:500px}
 h1{font-size:120px;font-weight:700;color:#d1d1d6;line-height:1}
@@ -175,6 +202,11 @@ impl RouteEntry {
             route_type: RouteType::ReverseProxy { port },
             instance_id,
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            client_ca: None,
+            custom_directives: None,
+            wake: false,
         }
     }
 
@@ -191,8 +223,38 @@ impl RouteEntry {
             route_type: RouteType::FileServer { path, browse },
             instance_id,
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            client_ca: None,
+            custom_directives: None,
+            wake: false,
         }
     }
+
+    /// Set the HTTP→HTTPS redirect and HSTS options
+    pub fn with_https_options(mut self, force_https: bool, hsts: bool) -> Self {
+        self.force_https = force_https;
+        self.hsts = hsts;
+        self
+    }
+
+    /// Require client certificates issued by `client_ca` (a PEM file path)
+    pub fn with_client_auth(mut self, client_ca: Option<String>) -> Self {
+        self.client_ca = client_ca;
+        self
+    }
+
+    /// Add raw Caddyfile directives to the site
+    pub fn with_custom_directives(mut self, custom_directives: Option<String>) -> Self {
+        self.custom_directives = custom_directives;
+        self
+    }
+
+    /// Start the instance behind the site when it's down (see `wake`)
+    pub fn with_wake(mut self, wake: bool) -> Self {
+        self.wake = wake;
+        self
+    }
 }
 
 /// Routes configuration (for Caddyfile generation)
@@ -258,7 +320,14 @@ pub fn get_caddy_version() -> Result<String, String> {
 }
 
 /// Generate main Caddyfile content (global config + import + catch-all)
-pub fn generate_main_caddyfile(tld: &str) -> String {
+///
+/// The catch-all covers the primary TLD and every extra one.
+pub fn generate_main_caddyfile(tld: &str, extra_tlds: &[String]) -> String {
+    let catch_all = std::iter::once(tld)
+        .chain(extra_tlds.iter().map(String::as_str).filter(|t| *t != tld))
+        .map(|t| format!("http://*.{}", t))
+        .collect::<Vec<_>>()
+        .join(", ");
     format!(
         r#"{{
     # Disable admin API in daemon mode for security
@@ -283,20 +352,194 @@ pub fn generate_main_caddyfile(tld: &str) -> String {
 # Import all domain configurations
 import domains/*.caddy
 
-# Default catch-all for *.{tld} (must be last) - HTTP only
+# Default catch-all for every Burd TLD (must be last) - HTTP only
 # Note: No HTTPS catch-all to avoid generating a wildcard certificate
-http://*.{tld} {{
+{catch_all} {{
     handle /_burd/health {{
         respond "burd-ok" 200
     }}
     respond "No service configured for this domain" 404
 }}
+
+# Device setup bundle for other machines reaching this one by IP address
+# (written by `burd ca bundle`)
+:80 {{
+    redir /burd-setup {setup_path} 308
+    handle_path {setup_path}* {{
+        root * "{setup_dir}"
+        @profile path *.mobileconfig
+        header @profile Content-Type application/x-apple-aspen-config
+        file_server
+    }}
+    respond "Not found" 404
+}}
 "#,
-        tld = tld,
-        logs_dir = get_logs_dir().display()
+        catch_all = catch_all,
+        logs_dir = get_logs_dir().display(),
+        setup_path = device_setup::SETUP_URL_PATH,
+        setup_dir = device_setup::get_setup_dir().display()
     )
 }
 
+/// Indent user-supplied directives to sit inside a site block
+fn indent_directives(directives: &str) -> String {
+    directives
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                "\n".to_string()
+            } else {
+                format!("    {}\n", line.trim_end())
+            }
+        })
+        .collect()
+}
+
+/// Clean up custom directives entered by a user
+///
+/// Blank input clears them. Unbalanced braces are rejected up front, since
+/// a stray `}` would close the site block and leak into the rest of the
+/// Caddyfile.
+pub fn normalize_custom_directives(raw: &str) -> Result<Option<String>, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    let mut depth: i32 = 0;
+    for c in trimmed.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth < 0 {
+                    return Err("Custom directives have an unmatched '}'".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err("Custom directives have an unclosed '{'".to_string());
+    }
+
+    Ok(Some(trimmed.to_string()))
+}
+
+/// Check a site's generated config with `caddy validate`
+///
+/// The site is validated on its own from a temporary file, so a broken
+/// snippet is caught before the running proxy reloads. Skipped when Caddy
+/// isn't installed, as there is no proxy to break.
+pub fn validate_domain_config(route: &RouteEntry) -> Result<(), String> {
+    let daemon_bin = get_caddy_daemon_bin();
+    let binary = if daemon_bin.exists() {
+        daemon_bin
+    } else {
+        match get_caddy_binary_path() {
+            Ok(binary) => binary,
+            Err(_) => return Ok(()),
+        }
+    };
+
+    let path = std::env::temp_dir().join(format!("burd-validate-{}.caddy", Uuid::new_v4()));
+    fs::write(&path, generate_domain_config(route))
+        .map_err(|e| format!("Failed to write config for validation: {}", e))?;
+
+    let output = Command::new(&binary)
+        .args(["validate", "--adapter", "caddyfile", "--config"])
+        .arg(&path)
+        .env("XDG_DATA_HOME", platform::caddy_data_dir())
+        .output();
+    let _ = fs::remove_file(&path);
+
+    let output = output.map_err(|e| format!("Failed to run caddy validate: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("unknown error")
+        .trim();
+    Err(format!("Caddy rejected the configuration: {}", reason))
+}
+
+/// max-age sent with the Strict-Transport-Security header (one year)
+const HSTS_MAX_AGE: u32 = 31_536_000;
+
+/// Wrap a site's directives in an HTTP block and, with SSL, an HTTPS block
+///
+/// `directives` renders the site body for a scheme ("http" or "https"). When
+/// HTTPS is forced, the HTTP block only redirects; with HSTS enabled, the
+/// HTTPS block also sends a Strict-Transport-Security header. A route that
+/// requires client certificates always redirects HTTP, since plain HTTP
+/// would skip the certificate check. Both blocks write access logs to the
+/// site's own file so the log viewer can show them per site. Custom
+/// directives go into every block that serves the site, ahead of the
+/// generated ones.
+fn render_site_blocks(
+    route: &RouteEntry,
+    header: &str,
+    directives: impl Fn(&str) -> String,
+) -> String {
+    let client_auth = route.client_ca.as_ref().filter(|_| route.ssl_enabled);
+    let log = format!(
+        "    log {{\n        output file \"{}\" {{\n            mode 0644\n            roll_size 10mb\n            roll_keep 2\n        }}\n        format json\n    }}\n",
+        get_site_log_path(&route.domain).display()
+    );
+    let custom = route
+        .custom_directives
+        .as_deref()
+        .map(indent_directives)
+        .unwrap_or_default();
+    let http_directives = if route.ssl_enabled && (route.force_https || client_auth.is_some()) {
+        "    redir https://{host}{uri} 308\n".to_string()
+    } else {
+        format!("{}{}", custom, directives("http"))
+    };
+
+    let mut config = format!(
+        "{header}\nhttp://{domain} {{\n{log}{http_directives}}}\n",
+        header = header,
+        domain = route.domain,
+        log = log,
+        http_directives = http_directives
+    );
+
+    if route.ssl_enabled {
+        let hsts = if route.hsts {
+            format!(
+                "    header Strict-Transport-Security \"max-age={}\"\n",
+                HSTS_MAX_AGE
+            )
+        } else {
+            String::new()
+        };
+        let tls = match client_auth {
+            Some(ca) => format!(
+                "    tls internal {{\n        client_auth {{\n            mode require_and_verify\n            trust_pool file \"{}\"\n        }}\n    }}\n",
+                ca
+            ),
+            None => "    tls internal\n".to_string(),
+        };
+        config.push_str(&format!(
+            "\nhttps://{domain} {{\n{tls}{log}{hsts}{custom}{https_directives}}}\n",
+            domain = route.domain,
+            tls = tls,
+            log = log,
+            hsts = hsts,
+            custom = custom,
+            https_directives = directives("https")
+        ));
+    }
+
+    config
+}
+
 /// Generate content for a single domain config file
 /// When ssl_enabled is true, generates both HTTP and HTTPS blocks
 pub fn generate_domain_config(route: &RouteEntry) -> String {
@@ -307,107 +550,61 @@ pub fn generate_domain_config(route: &RouteEntry) -> String {
             let error_503 = get_503_error_html(&route.domain, *port).replace('`', "\\`");
             let error_504 = get_504_error_html(&route.domain, *port).replace('`', "\\`");
 
-            if route.ssl_enabled {
-                // Generate both HTTP and HTTPS blocks
+            // A stopped wake-on-access instance is started by the API, which
+            // answers with a page that reloads until the site is up
+            let on_502 = if route.wake {
                 format!(
-                    r#"# Route: {instance_id}
-http://{domain} {{
-    reverse_proxy localhost:{port} {{
-        header_up X-Forwarded-Proto http
-        header_up X-Forwarded-Port 80
-    }}
-    handle_errors {{
-        @502 expression `{{http.error.status_code}} == 502`
-        @503 expression `{{http.error.status_code}} == 503`
-        @504 expression `{{http.error.status_code}} == 504`
-        header @502 Content-Type text/html
-        header @503 Content-Type text/html
-        header @504 Content-Type text/html
-        respond @502 `{error_502}` 502
-        respond @503 `{error_503}` 503
-        respond @504 `{error_504}` 504
-    }}
-}}
-
-https://{domain} {{
-    tls internal
-    reverse_proxy localhost:{port} {{
-        header_up X-Forwarded-Proto https
-        header_up X-Forwarded-Port 443
-    }}
-    handle_errors {{
-        @502 expression `{{http.error.status_code}} == 502`
-        @503 expression `{{http.error.status_code}} == 503`
-        @504 expression `{{http.error.status_code}} == 504`
-        header @502 Content-Type text/html
-        header @503 Content-Type text/html
-        header @504 Content-Type text/html
-        respond @502 `{error_502}` 502
-        respond @503 `{error_503}` 503
-        respond @504 `{error_504}` 504
-    }}
-}}
-"#,
-                    domain = route.domain,
-                    port = port,
-                    instance_id = route.instance_id,
-                    error_502 = error_502,
-                    error_503 = error_503,
-                    error_504 = error_504
+                    "        reverse_proxy @502 localhost:{api_port} {{\n            rewrite /wake/{id}\n        }}\n",
+                    api_port = API_PORT,
+                    id = route.instance_id
                 )
             } else {
-                // HTTP only
                 format!(
-                    r#"# Route: {instance_id}
-http://{domain} {{
-    reverse_proxy localhost:{port} {{
-        header_up X-Forwarded-Proto http
-        header_up X-Forwarded-Port 80
+                    "        header @502 Content-Type text/html\n        respond @502 `{}` 502\n",
+                    error_502
+                )
+            };
+
+            render_site_blocks(
+                route,
+                &format!("# Route: {}", route.instance_id),
+                |scheme| {
+                    let forwarded_port = if scheme == "https" { 443 } else { 80 };
+                    format!(
+                        r#"    reverse_proxy localhost:{port} {{
+        header_up X-Forwarded-Proto {scheme}
+        header_up X-Forwarded-Port {forwarded_port}
     }}
     handle_errors {{
         @502 expression `{{http.error.status_code}} == 502`
         @503 expression `{{http.error.status_code}} == 503`
         @504 expression `{{http.error.status_code}} == 504`
-        header @502 Content-Type text/html
-        header @503 Content-Type text/html
+{on_502}        header @503 Content-Type text/html
         header @504 Content-Type text/html
-        respond @502 `{error_502}` 502
         respond @503 `{error_503}` 503
         respond @504 `{error_504}` 504
     }}
-}}
 "#,
-                    domain = route.domain,
-                    port = port,
-                    instance_id = route.instance_id,
-                    error_502 = error_502,
-                    error_503 = error_503,
-                    error_504 = error_504
-                )
-            }
+                        port = port,
+                        scheme = scheme,
+                        forwarded_port = forwarded_port,
+                        on_502 = on_502,
+                        error_503 = error_503,
+                        error_504 = error_504
+                    )
+                },
+            )
         }
         RouteType::FileServer { path, browse } => {
             let browse_directive = if *browse { "\n        browse" } else { "" };
             let error_404 = get_404_error_html(&route.domain).replace('`', "\\`");
 
-            if route.ssl_enabled {
-                // Generate both HTTP and HTTPS blocks
-                format!(
-                    r#"# Route: {instance_id} (Static Files)
-http://{domain} {{
-    root * "{path}"
-    file_server {{{browse_directive}
-    }}
-    handle_errors {{
-        @404 expression `{{http.error.status_code}} == 404`
-        header @404 Content-Type text/html
-        respond @404 `{error_404}` 404
-    }}
-}}
-
-https://{domain} {{
-    tls internal
-    root * "{path}"
+            render_site_blocks(
+                route,
+                &format!("# Route: {} (Static Files)", route.instance_id),
+                |_| {
+                    format!(
+                        r#"    root * "{path}"
     file_server {{{browse_directive}
     }}
     handle_errors {{
@@ -415,36 +612,13 @@ https://{domain} {{
         header @404 Content-Type text/html
         respond @404 `{error_404}` 404
     }}
-}}
 "#,
-                    domain = route.domain,
-                    path = path,
-                    browse_directive = browse_directive,
-                    instance_id = route.instance_id,
-                    error_404 = error_404
-                )
-            } else {
-                // HTTP only
-                format!(
-                    r#"# Route: {instance_id} (Static Files)
-http://{domain} {{
-    root * "{path}"
-    file_server {{{browse_directive}
-    }}
-    handle_errors {{
-        @404 expression `{{http.error.status_code}} == 404`
-        header @404 Content-Type text/html
-        respond @404 `{error_404}` 404
-    }}
-}}
-"#,
-                    domain = route.domain,
-                    path = path,
-                    browse_directive = browse_directive,
-                    instance_id = route.instance_id,
-                    error_404 = error_404
-                )
-            }
+                        path = path,
+                        browse_directive = browse_directive,
+                        error_404 = error_404
+                    )
+                },
+            )
         }
     }
 }
@@ -482,12 +656,22 @@ fn delete_file(path: &PathBuf) -> Result<(), String> {
 
 /// Write the main Caddyfile (global config + import + catch-all)
 pub fn write_main_caddyfile(tld: &str) -> Result<(), String> {
-    let content = generate_main_caddyfile(tld);
+    let extra_tlds = ConfigStore::new()
+        .and_then(|store| store.load())
+        .map(|config| config.extra_tlds)
+        .unwrap_or_default();
+    let content = generate_main_caddyfile(tld, &extra_tlds);
     write_file(&get_caddyfile_path(), &content)
 }
 
 /// Write a single domain config file
 pub fn write_domain_file(route: &RouteEntry) -> Result<(), String> {
+    // Make sure the site's log directory exists before Caddy loads the config
+    if let Some(log_dir) = get_site_log_path(&route.domain).parent() {
+        fs::create_dir_all(log_dir)
+            .map_err(|e| format!("Failed to create site log directory: {}", e))?;
+    }
+
     let content = generate_domain_config(route);
     let path = get_domain_filepath(&route.domain);
     write_file(&path, &content)?;
@@ -501,6 +685,55 @@ pub fn write_domain_config_raw(path: &PathBuf, content: &str) -> Result<(), Stri
     write_file(path, content)
 }
 
+/// Client CA Caddy should verify visitors against, if the domain requires
+/// client certificates
+pub fn client_ca_for(domain: &Domain) -> Option<String> {
+    domain.require_client_cert.then(|| {
+        client_certs::ca_cert_path(&domain.id.to_string())
+            .to_string_lossy()
+            .to_string()
+    })
+}
+
+/// Apply each domain's HTTPS options (redirect, HSTS, client certificates),
+/// custom directives and its instance's wake-on-access from the config
+///
+/// Routes are registered without them, so they are looked up by domain name
+/// when the files are written. Routes without a matching domain are unchanged.
+fn apply_domain_https_options(tld: &str, routes: &[RouteEntry]) -> Vec<RouteEntry> {
+    let config = ConfigStore::new()
+        .and_then(|store| store.load())
+        .unwrap_or_default();
+
+    routes
+        .iter()
+        .cloned()
+        .map(|route| {
+            let domain = config
+                .domains
+                .iter()
+                .find(|d| d.full_domain(tld) == route.domain);
+            match domain {
+                Some(d) => {
+                    let wake = match &d.target {
+                        DomainTarget::Instance(id) => config
+                            .instances
+                            .iter()
+                            .any(|i| &i.id == id && i.wake_on_access),
+                        _ => false,
+                    };
+                    route
+                        .with_https_options(d.force_https, d.hsts)
+                        .with_client_auth(client_ca_for(d))
+                        .with_custom_directives(d.custom_directives.clone())
+                        .with_wake(wake)
+                }
+                None => route,
+            }
+        })
+        .collect()
+}
+
 /// Write all domain files and the main Caddyfile
 /// This replaces the old write_caddyfile function for full sync
 pub fn write_caddyfile(tld: &str, routes: &[RouteEntry]) -> Result<(), String> {
@@ -508,8 +741,8 @@ pub fn write_caddyfile(tld: &str, routes: &[RouteEntry]) -> Result<(), String> {
     write_main_caddyfile(tld)?;
 
     // Write each domain file
-    for route in routes {
-        write_domain_file(route)?;
+    for route in apply_domain_https_options(tld, routes) {
+        write_domain_file(&route)?;
     }
 
     // Clean up orphaned domain files (files that exist but aren't in routes)
@@ -636,7 +869,7 @@ mod tests {
 
     #[test]
     fn test_generate_main_caddyfile() {
-        let caddyfile = generate_main_caddyfile("burd");
+        let caddyfile = generate_main_caddyfile("burd", &[]);
 
         assert!(caddyfile.contains("admin off"));
         assert!(caddyfile.contains("Burd CA Self Signed CN"));
@@ -644,6 +877,10 @@ mod tests {
         assert!(caddyfile.contains("http://*.burd"));
         // Note: No HTTPS catch-all to avoid generating a wildcard certificate
         assert!(!caddyfile.contains("https://*.burd"));
+        // Device setup bundle is reachable by IP on port 80
+        assert!(caddyfile.contains(":80 {"));
+        assert!(caddyfile.contains("handle_path /burd-setup/*"));
+        assert!(caddyfile.contains("application/x-apple-aspen-config"));
         // Import should come before catch-all
         let import_pos = caddyfile.find("import domains").unwrap();
         let catchall_pos = caddyfile.find("*.burd").unwrap();
@@ -653,6 +890,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_main_caddyfile_catches_every_tld() {
+        let extra = vec!["test".to_string(), "burd".to_string()];
+        let caddyfile = generate_main_caddyfile("burd", &extra);
+        assert!(caddyfile.contains("http://*.burd, http://*.test {"));
+    }
+
     #[test]
     fn test_generate_domain_config_reverse_proxy_no_ssl() {
         let route =
@@ -674,6 +918,19 @@ mod tests {
         assert!(config.contains("Gateway Timeout"));
     }
 
+    #[test]
+    fn test_generate_domain_config_wake() {
+        let route =
+            RouteEntry::reverse_proxy("api.burd".to_string(), 7700, "test-1".to_string(), false)
+                .with_wake(true);
+        let config = generate_domain_config(&route);
+
+        assert!(config
+            .contains("reverse_proxy @502 localhost:19840 {\n            rewrite /wake/test-1\n"));
+        assert!(!config.contains("Service Not Running"));
+        assert!(config.contains("respond @503"));
+    }
+
     #[test]
     fn test_generate_domain_config_reverse_proxy_with_ssl() {
         let route =
@@ -686,12 +943,105 @@ mod tests {
         assert!(config.contains("localhost:7700"));
         // Custom error pages for both HTTP and HTTPS (2 handle_errors blocks)
         assert_eq!(config.matches("handle_errors").count(), 2);
+        // Both blocks log requests to the site's access log
+        let site_log = format!(
+            "output file \"{}\"",
+            get_site_log_path("api.burd").display()
+        );
+        assert_eq!(config.matches(&site_log).count(), 2);
         // Each block has 502, 503, 504 error handling
         assert!(config.contains("@502"));
         assert!(config.contains("@503"));
         assert!(config.contains("@504"));
     }
 
+    #[test]
+    fn test_generate_domain_config_https_options() {
+        let route =
+            RouteEntry::reverse_proxy("api.burd".to_string(), 7700, "test-1".to_string(), true)
+                .with_https_options(true, true);
+        let config = generate_domain_config(&route);
+
+        let (http_block, https_block) = config.split_once("https://api.burd").unwrap();
+        assert!(http_block.contains("redir https://{host}{uri} 308"));
+        assert!(!http_block.contains("reverse_proxy"));
+        assert!(https_block.contains("Strict-Transport-Security"));
+        assert!(https_block.contains("reverse_proxy localhost:7700"));
+
+        // Options have no effect without SSL
+        let route =
+            RouteEntry::reverse_proxy("api.burd".to_string(), 7700, "test-1".to_string(), false)
+                .with_https_options(true, true);
+        let config = generate_domain_config(&route);
+        assert!(!config.contains("redir"));
+        assert!(!config.contains("Strict-Transport-Security"));
+    }
+
+    #[test]
+    fn test_generate_domain_config_custom_directives() {
+        let directives = "header X-Robots-Tag noindex\nbasic_auth {\n    admin $2a$14$hash\n}";
+        let route =
+            RouteEntry::reverse_proxy("api.burd".to_string(), 7700, "test-1".to_string(), true)
+                .with_custom_directives(Some(directives.to_string()));
+        let config = generate_domain_config(&route);
+
+        // Both blocks serve the site, indented inside the site block
+        assert_eq!(
+            config.matches("    header X-Robots-Tag noindex\n").count(),
+            2
+        );
+        assert_eq!(config.matches("        admin $2a$14$hash\n").count(), 2);
+        let (_, https_block) = config.split_once("https://api.burd").unwrap();
+        assert!(
+            https_block.find("basic_auth").unwrap() < https_block.find("reverse_proxy").unwrap()
+        );
+
+        // A redirect-only HTTP block doesn't get them
+        let route = route.with_https_options(true, false);
+        let config = generate_domain_config(&route);
+        let (http_block, https_block) = config.split_once("https://api.burd").unwrap();
+        assert!(!http_block.contains("basic_auth"));
+        assert!(https_block.contains("basic_auth"));
+    }
+
+    #[test]
+    fn test_normalize_custom_directives() {
+        assert_eq!(normalize_custom_directives("  \n ").unwrap(), None);
+        assert_eq!(
+            normalize_custom_directives("\nredir /old /new 301\n").unwrap(),
+            Some("redir /old /new 301".to_string())
+        );
+        assert_eq!(
+            normalize_custom_directives("header {\n    -Server\n}").unwrap(),
+            Some("header {\n    -Server\n}".to_string())
+        );
+        assert!(normalize_custom_directives("header -Server\n}\nhttp://evil.burd {").is_err());
+        assert!(normalize_custom_directives("basic_auth {").is_err());
+    }
+
+    #[test]
+    fn test_generate_domain_config_client_auth() {
+        let route =
+            RouteEntry::reverse_proxy("admin.burd".to_string(), 7700, "test-1".to_string(), true)
+                .with_client_auth(Some("/certs/admin.burd/client-ca.crt".to_string()));
+        let config = generate_domain_config(&route);
+
+        let (http_block, https_block) = config.split_once("https://admin.burd").unwrap();
+        // Plain HTTP would bypass the certificate check
+        assert!(http_block.contains("redir https://{host}{uri} 308"));
+        assert!(!http_block.contains("reverse_proxy"));
+        assert!(https_block.contains("mode require_and_verify"));
+        assert!(https_block.contains("trust_pool file \"/certs/admin.burd/client-ca.crt\""));
+
+        // No effect without SSL
+        let route =
+            RouteEntry::reverse_proxy("admin.burd".to_string(), 7700, "test-1".to_string(), false)
+                .with_client_auth(Some("/certs/admin.burd/client-ca.crt".to_string()));
+        let config = generate_domain_config(&route);
+        assert!(!config.contains("client_auth"));
+        assert!(config.contains("reverse_proxy localhost:7700"));
+    }
+
     #[test]
     fn test_generate_domain_config_file_server() {
         let route = RouteEntry::file_server(
@@ -0,0 +1,284 @@
+//! Certificate expiry monitor
+//!
+//! Caddy issues every HTTPS site a certificate from its local CA and renews
+//! it on its own, but only while it's running and healthy: a Mac that slept
+//! through the renewal window, or a daemon stuck on a bad Caddyfile, leaves
+//! sites with certificates that are about to lapse. Every hour the monitor
+//! reads the issued certificates and restarts the proxy daemon when one is
+//! within `cert_monitor.renew_days` of expiry, so Caddy renews it on startup.
+//!
+//! Caddy's local certificates are short-lived (12 hours by default), so a
+//! certificate never counts as expiring before a third of its lifetime is
+//! left, the point at which Caddy should already have renewed it.
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::commands::{restart_proxy_for_certs, AppState};
+use crate::config::CertMonitorSettings;
+use crate::helper_client::HelperClient;
+use crate::platform;
+
+/// Delay between certificate checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Shortest time between two restarts, so a certificate Caddy can't renew
+/// doesn't bounce the proxy every hour
+const RESTART_COOLDOWN: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Shortest renewal window that can be configured
+pub const MIN_RENEW_DAYS: u32 = 1;
+
+/// Longest renewal window that can be configured
+pub const MAX_RENEW_DAYS: u32 = 90;
+
+/// Expiry of one issued site certificate
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateStatus {
+    pub domain: String,
+    pub path: String,
+    /// `None` when the certificate couldn't be read
+    pub not_before: Option<DateTime<Utc>>,
+    pub not_after: Option<DateTime<Utc>>,
+    /// Within the renewal window (or already expired)
+    pub expiring: bool,
+    pub expired: bool,
+}
+
+/// Directory holding the certificates Caddy issued from its local CA
+pub fn certificates_dir() -> PathBuf {
+    platform::caddy_data_dir().join("caddy/certificates/local")
+}
+
+/// Check the renewal window
+pub fn validate_settings(settings: CertMonitorSettings) -> Result<CertMonitorSettings, String> {
+    if !(MIN_RENEW_DAYS..=MAX_RENEW_DAYS).contains(&settings.renew_days) {
+        return Err(format!(
+            "Renewal window must be between {} and {} days",
+            MIN_RENEW_DAYS, MAX_RENEW_DAYS
+        ));
+    }
+    Ok(settings)
+}
+
+/// Parse an openssl date like `Nov 11 08:46:28 2035 GMT`
+fn parse_openssl_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%b %d %H:%M:%S %Y GMT")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Parse the `notBefore=`/`notAfter=` lines of `openssl x509 -dates`
+fn parse_dates(output: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut not_before = None;
+    let mut not_after = None;
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix("notBefore=") {
+            not_before = parse_openssl_date(value);
+        } else if let Some(value) = line.strip_prefix("notAfter=") {
+            not_after = parse_openssl_date(value);
+        }
+    }
+    Some((not_before?, not_after?))
+}
+
+/// Read a certificate's validity period with openssl
+fn read_dates(path: &Path) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let output = Command::new("openssl")
+        .args(["x509", "-noout", "-dates", "-in"])
+        .arg(path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    parse_dates(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Whether a certificate is within the renewal window
+fn is_expiring(
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+    now: DateTime<Utc>,
+    renew_days: u32,
+) -> bool {
+    let window = TimeDelta::days(i64::from(renew_days)).min((not_after - not_before) / 3);
+    not_after - now <= window
+}
+
+/// Domain a certificate directory is named after (`wildcard_.x.test` is `*.x.test`)
+fn domain_for_dir(name: &str) -> String {
+    match name.strip_prefix("wildcard_") {
+        Some(rest) => format!("*{}", rest),
+        None => name.to_string(),
+    }
+}
+
+/// Expiry of every certificate in `dir`, sorted by domain
+fn statuses_in(dir: &Path, renew_days: u32, now: DateTime<Utc>) -> Vec<CertificateStatus> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut statuses: Vec<CertificateStatus> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path().join(format!("{}.crt", name));
+            if !path.exists() {
+                return None;
+            }
+            let dates = read_dates(&path);
+            Some(CertificateStatus {
+                domain: domain_for_dir(&name),
+                path: path.to_string_lossy().to_string(),
+                not_before: dates.map(|(from, _)| from),
+                not_after: dates.map(|(_, until)| until),
+                expiring: dates
+                    .is_some_and(|(from, until)| is_expiring(from, until, now, renew_days)),
+                expired: dates.is_some_and(|(_, until)| until <= now),
+            })
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.domain.cmp(&b.domain));
+    statuses
+}
+
+/// Expiry of every certificate Caddy has issued
+pub fn certificates_status(renew_days: u32) -> Vec<CertificateStatus> {
+    statuses_in(&certificates_dir(), renew_days, Utc::now())
+}
+
+/// Watch certificates forever, passing the domains to `on_restart` whenever
+/// the proxy is restarted to renew them
+///
+/// Only restarts while the privileged helper is running, so a background
+/// task doesn't pop up a password prompt.
+pub async fn run(state: AppState, on_restart: impl Fn(&[String]) + Send + 'static) {
+    let mut last_restart: Option<Instant> = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let settings = match state.config_store.lock().map(|store| store.load()) {
+            Ok(Ok(config)) => config.cert_monitor,
+            _ => continue,
+        };
+        if !settings.enabled || !platform::proxy_daemon().is_installed() {
+            continue;
+        }
+        if last_restart.is_some_and(|at| at.elapsed() < RESTART_COOLDOWN) {
+            continue;
+        }
+
+        let Ok(expiring) = tokio::task::spawn_blocking(move || {
+            certificates_status(settings.renew_days)
+                .into_iter()
+                .filter(|c| c.expiring)
+                .map(|c| c.domain)
+                .collect::<Vec<_>>()
+        })
+        .await
+        else {
+            continue;
+        };
+        if expiring.is_empty() {
+            continue;
+        }
+
+        let result = tokio::task::spawn_blocking(|| {
+            if !HelperClient::is_running() {
+                return Err("the privileged helper isn't running".to_string());
+            }
+            restart_proxy_for_certs()
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("Task error: {}", e)));
+
+        last_restart = Some(Instant::now());
+        match result {
+            Ok(()) => {
+                eprintln!(
+                    "Restarted the proxy to renew expiring certificates: {}",
+                    expiring.join(", ")
+                );
+                on_restart(&expiring);
+            }
+            Err(e) => eprintln!(
+                "Certificates for {} are expiring and the proxy could not be restarted: {}",
+                expiring.join(", "),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_dates() {
+        let output = "notBefore=Jan  5 08:46:28 2026 GMT\nnotAfter=Nov 11 20:46:28 2026 GMT\n";
+        let (not_before, not_after) = parse_dates(output).unwrap();
+        assert_eq!(not_before, at("2026-01-05T08:46:28Z"));
+        assert_eq!(not_after, at("2026-11-11T20:46:28Z"));
+
+        assert!(parse_dates("notAfter=Nov 11 20:46:28 2026 GMT").is_none());
+        assert!(parse_dates("unable to load certificate").is_none());
+    }
+
+    #[test]
+    fn test_is_expiring() {
+        let from = at("2026-01-01T00:00:00Z");
+        let until = at("2026-04-01T00:00:00Z");
+        assert!(!is_expiring(from, until, at("2026-03-01T00:00:00Z"), 7));
+        assert!(is_expiring(from, until, at("2026-03-26T00:00:00Z"), 7));
+        assert!(is_expiring(from, until, at("2026-05-01T00:00:00Z"), 7));
+
+        // A 12-hour certificate only counts once 4 hours are left
+        let from = at("2026-01-01T00:00:00Z");
+        let until = at("2026-01-01T12:00:00Z");
+        assert!(!is_expiring(from, until, at("2026-01-01T06:00:00Z"), 7));
+        assert!(is_expiring(from, until, at("2026-01-01T09:00:00Z"), 7));
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let settings = |renew_days| CertMonitorSettings {
+            enabled: true,
+            renew_days,
+        };
+        assert!(validate_settings(settings(7)).is_ok());
+        assert!(validate_settings(settings(0)).is_err());
+        assert!(validate_settings(settings(MAX_RENEW_DAYS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_statuses_in_lists_certificate_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["shop.test", "wildcard_.api.test"] {
+            let cert_dir = dir.path().join(name);
+            fs::create_dir_all(&cert_dir).unwrap();
+            fs::write(cert_dir.join(format!("{}.crt", name)), "not a certificate").unwrap();
+        }
+        // Directories without a certificate are skipped
+        fs::create_dir_all(dir.path().join("empty.test")).unwrap();
+
+        let statuses = statuses_in(dir.path(), 7, Utc::now());
+        let domains: Vec<&str> = statuses.iter().map(|s| s.domain.as_str()).collect();
+        assert_eq!(domains, vec!["*.api.test", "shop.test"]);
+        assert!(statuses
+            .iter()
+            .all(|s| s.not_after.is_none() && !s.expiring));
+
+        assert!(statuses_in(&dir.path().join("missing"), 7, Utc::now()).is_empty());
+    }
+}
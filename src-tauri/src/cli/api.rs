@@ -0,0 +1,106 @@
+//! HTTP API CLI commands
+//!
+//! Shows and changes whether the HTTP API accepts remote connections, and
+//! manages the token remote callers must send.
+
+use crate::api::{remote, API_PORT};
+use crate::config::{ApiRemoteSettings, ConfigStore};
+
+fn print_restart_note() {
+    println!("Restart Burd (or 'burd daemon run') for the change to take effect.");
+    println!();
+}
+
+fn print_settings(settings: &ApiRemoteSettings) {
+    println!();
+    println!("HTTP API");
+    println!("{}", "-".repeat(40));
+    if settings.enabled {
+        println!(
+            "  [OK] Remote access: on (http://{}:{})",
+            settings.bind_address, API_PORT
+        );
+    } else {
+        println!("  [--] Remote access: off (http://127.0.0.1:{})", API_PORT);
+    }
+    if settings.allowed_origins.is_empty() {
+        println!("  Allowed origins: none");
+    } else {
+        println!("  Allowed origins:");
+        for origin in &settings.allowed_origins {
+            println!("    {}", origin);
+        }
+    }
+    if let Some(token) = settings.token.as_ref().filter(|_| settings.enabled) {
+        println!("  Token: {}", token);
+        println!();
+        println!("Send it with every request:");
+        println!(
+            "  curl -H 'Authorization: Bearer {}' http://<this-machine>:{}/status",
+            token, API_PORT
+        );
+    }
+    println!();
+}
+
+/// Show the HTTP API access settings
+pub fn run_api_status() -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    print_settings(&config.api_remote);
+    Ok(())
+}
+
+/// Allow remote connections to the HTTP API
+///
+/// Keeps the current bind address and origins unless new ones are given.
+pub fn run_api_remote(bind: Option<String>, origins: Vec<String>) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
+    let mut settings = config_store.load()?.api_remote;
+    settings.enabled = true;
+    if let Some(bind) = bind {
+        settings.bind_address = bind;
+    }
+    if !origins.is_empty() {
+        settings.allowed_origins = origins;
+    }
+
+    let settings = remote::validate(settings)?;
+    config_store.update_api_remote(settings.clone())?;
+
+    print_settings(&settings);
+    print_restart_note();
+    Ok(())
+}
+
+/// Restrict the HTTP API to this machine again
+pub fn run_api_local() -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
+    let mut settings = config_store.load()?.api_remote;
+    if !settings.enabled {
+        println!("Remote API access is already off");
+        return Ok(());
+    }
+
+    settings.enabled = false;
+    config_store.update_api_remote(settings)?;
+
+    println!("Remote API access turned off");
+    print_restart_note();
+    Ok(())
+}
+
+/// Replace the API token, invalidating the old one
+pub fn run_api_rotate_token() -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
+    let mut settings = config_store.load()?.api_remote;
+    settings.token = Some(remote::generate_token());
+    config_store.update_api_remote(settings.clone())?;
+
+    println!("Generated a new API token. The old token no longer works after a restart.");
+    print_settings(&settings);
+    print_restart_note();
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! Backup CLI commands
+//!
+//! Shows the scheduled backup settings and history, runs a backup on demand
+//! and restores instances from a backup. The schedule itself runs in the app
+//! and the daemon.
+
+use std::collections::HashSet;
+
+use super::snapshot::format_size;
+use crate::backup::{self, BackupInfo, CaptureMethod};
+use crate::config::ConfigStore;
+use crate::process::ProcessManager;
+
+fn format_time(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn print_backup(info: &BackupInfo) {
+    println!(
+        "  {:<24} {:<20} {:>10}",
+        info.id,
+        format_time(info.created_at),
+        format_size(info.size_bytes)
+    );
+    for instance in &info.instances {
+        let method = match instance.method {
+            CaptureMethod::SqlDump => "SQL dump",
+            CaptureMethod::Snapshot => "snapshot",
+            CaptureMethod::DataCopy => "data copy",
+            CaptureMethod::Skipped => "skipped",
+        };
+        match &instance.note {
+            Some(note) => println!("    {} ({}): {}", instance.name, method, note),
+            None => println!("    {} ({})", instance.name, method),
+        }
+    }
+}
+
+/// Show the backup settings and the outcome of the last run
+pub fn run_backup_status() -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let settings = &config.backup;
+    let status = backup::load_status();
+
+    println!();
+    println!("Backups");
+    println!("{}", "-".repeat(40));
+    if !settings.enabled {
+        println!("  [--] Scheduled: off");
+    } else if let Some(schedule) = &settings.schedule {
+        println!("  [OK] Scheduled: cron '{}' (local time)", schedule);
+    } else {
+        println!(
+            "  [OK] Scheduled: every {} hour(s)",
+            settings.interval_hours
+        );
+    }
+    println!(
+        "  Folder: {}",
+        settings.destination.as_deref().unwrap_or("not set")
+    );
+    if settings.retention == 0 {
+        println!("  Keep: all backups");
+    } else {
+        println!("  Keep: newest {}", settings.retention);
+    }
+    if settings.compress {
+        println!("  Compression: on (.tar.gz)");
+    }
+
+    let names: Vec<&str> = config
+        .instances
+        .iter()
+        .filter(|i| settings.instance_ids.contains(&i.id))
+        .map(|i| i.name.as_str())
+        .collect();
+    if names.is_empty() {
+        println!("  Instances: none (config only)");
+    } else {
+        println!("  Instances: {}", names.join(", "));
+    }
+
+    match (status.last_attempt, &status.last_error) {
+        (Some(attempt), Some(error)) => {
+            println!(
+                "  [ERR] Last run {} failed: {}",
+                format_time(attempt),
+                error
+            )
+        }
+        (Some(attempt), None) => println!("  [OK] Last run: {}", format_time(attempt)),
+        (None, _) => println!("  Last run: never"),
+    }
+    println!();
+    Ok(())
+}
+
+/// List the backups in the configured folder, newest first
+pub fn run_backup_list() -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let backups = backup::list_backups(&config.backup)?;
+
+    println!();
+    if backups.is_empty() {
+        println!("No backups yet. Run 'burd backup run' to create one.");
+    } else {
+        println!("  {:<24} {:<20} {:>10}", "ID", "CREATED", "SIZE");
+        for info in &backups {
+            print_backup(info);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Back up the config and selected instances now
+pub fn run_backup_run() -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let process_manager = ProcessManager::new();
+    let running: HashSet<_> = config
+        .instances
+        .iter()
+        .filter(|i| config.backup.instance_ids.contains(&i.id))
+        .filter(|i| process_manager.get_status(i).running)
+        .map(|i| i.id)
+        .collect();
+
+    println!("Backing up...");
+    let info = backup::run_backup(&config, &running)?;
+
+    println!("Created backup:");
+    print_backup(&info);
+    if let Some(path) = &info.path {
+        println!("Saved to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Restore an instance's data from a backup
+pub fn run_backup_restore(backup_id: &str, instance_name: &str) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let instance = config
+        .instances
+        .iter()
+        .find(|i| i.name.eq_ignore_ascii_case(instance_name))
+        .ok_or_else(|| format!("No instance named '{}'", instance_name))?;
+    let running = ProcessManager::new().get_status(instance).running;
+
+    println!("Restoring '{}' from backup {}...", instance.name, backup_id);
+    let restored = backup::restore_backup(&config.backup, backup_id, instance, running)?;
+
+    match restored.method {
+        CaptureMethod::SqlDump => println!("Restored the databases of '{}'.", instance.name),
+        _ => println!("Restored the data of '{}'.", instance.name),
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Root CA CLI commands
+//!
+//! Shows where Caddy's root CA is trusted, trusts it in the keychain and
+//! Firefox, exports it as PEM files for curl, Node and other tools, and
+//! writes the setup bundle for other devices on the network.
+
+use crate::commands::{
+    auto_trust_ca_if_needed, export_ca_bundle, get_ca_trust_status, write_device_bundle,
+};
+use crate::config::ConfigStore;
+use crate::trust_stores::FirefoxTrust;
+
+fn firefox_label(trust: FirefoxTrust) -> (&'static str, &'static str) {
+    match trust {
+        FirefoxTrust::Imported => ("[OK]", "CA imported"),
+        FirefoxTrust::KeychainRoots => ("[OK]", "uses keychain roots"),
+        FirefoxTrust::NotTrusted => ("[WARN]", "not trusted"),
+    }
+}
+
+/// Show where the root CA is trusted
+pub fn run_ca_status() -> Result<(), String> {
+    let status = get_ca_trust_status()?;
+
+    println!();
+    println!("Root CA");
+    println!("{}", "-".repeat(40));
+    if !status.ca_exists {
+        println!("  [--] Not generated yet");
+        println!("      Enable HTTPS on a domain and open it once to generate it.");
+        println!();
+        return Ok(());
+    }
+    println!("  Path:    {}", status.ca_path);
+    if let Some(name) = &status.cert_name {
+        println!("  Name:    {}", name);
+    }
+    if let Some(expiry) = &status.cert_expiry {
+        println!("  Expires: {}", expiry);
+    }
+    println!();
+
+    if status.is_trusted {
+        println!("  [OK] Keychain");
+    } else {
+        println!("  [WARN] Keychain: not trusted");
+    }
+    if status.firefox_profiles.is_empty() {
+        println!("  [--] Firefox: no profiles found");
+    }
+    for profile in &status.firefox_profiles {
+        let (symbol, label) = firefox_label(profile.trust);
+        println!("  {} Firefox ({}): {}", symbol, profile.name, label);
+    }
+
+    let untrusted = !status.is_trusted
+        || status
+            .firefox_profiles
+            .iter()
+            .any(|p| p.trust == FirefoxTrust::NotTrusted);
+    if untrusted {
+        println!();
+        println!("Run 'burd ca trust' to trust it everywhere.");
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Trust the root CA in the keychain and in every Firefox profile
+pub fn run_ca_trust() -> Result<(), String> {
+    if !get_ca_trust_status()?.ca_exists {
+        return Err(
+            "The root CA hasn't been generated yet. Enable HTTPS on a domain and open it once."
+                .to_string(),
+        );
+    }
+
+    auto_trust_ca_if_needed()?;
+    run_ca_status()
+}
+
+/// Export the root CA as PEM files with setup instructions
+pub fn run_ca_export(dir: Option<String>) -> Result<(), String> {
+    let export = export_ca_bundle(dir)?;
+
+    println!();
+    println!("Exported the root CA:");
+    println!("  {}", export.ca_pem.display());
+    if let Some(bundle) = &export.bundle_pem {
+        println!("  {}", bundle.display());
+    }
+    println!();
+    if let Ok(readme) = std::fs::read_to_string(&export.readme) {
+        print!("{}", readme);
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Write the device setup bundle and show where other devices can open it
+pub fn run_ca_bundle() -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let bundle = write_device_bundle(&config)?;
+
+    println!();
+    println!("Device setup bundle written to {}", bundle.dir.display());
+    for file in &bundle.files {
+        println!("  {}", file);
+    }
+    println!();
+    if bundle.lan_ip.is_some() {
+        println!("On the other device, open:");
+        println!("  {}", bundle.url);
+    } else {
+        println!("[WARN] Couldn't detect this Mac's network address.");
+        println!("       Open http://<this Mac's IP>/burd-setup/ on the other device.");
+    }
+    println!();
+    println!(
+        "The page explains how to trust the CA and point .{} domains at this Mac.",
+        config.tld
+    );
+    println!();
+
+    Ok(())
+}
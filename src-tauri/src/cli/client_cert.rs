@@ -0,0 +1,117 @@
+//! Client certificate CLI commands
+//!
+//! Require a client certificate (mutual TLS) for a domain, and issue or show
+//! the certificate visitors need to present.
+
+use super::secure::{find_domain, regenerate_caddyfile};
+use crate::client_certs::{self, ClientCert};
+use crate::config::ConfigStore;
+
+fn print_cert(cert: &ClientCert, full_domain: &str) {
+    println!("  Certificate: {}", cert.cert.display());
+    println!("  Key:         {}", cert.key.display());
+    println!("  PKCS#12:     {}", cert.p12.display());
+    println!("  Password:    {}", cert.p12_password);
+    println!();
+    println!("Import the PKCS#12 file into your browser or keychain, or use curl:");
+    println!(
+        "  curl --cert {} --key {} https://{}",
+        cert.cert.display(),
+        cert.key.display(),
+        full_domain
+    );
+    println!();
+}
+
+/// Require a client certificate for a domain, issuing one if needed
+pub fn run_client_cert_require(name: Option<String>) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let config = config_store.load()?;
+    let domain = find_domain(&config, name)?;
+    let full_domain = domain.full_domain(&config.tld);
+
+    if !domain.ssl_enabled {
+        return Err(format!(
+            "SSL is disabled for {}. Run 'burd secure {}' first.",
+            full_domain, domain.subdomain
+        ));
+    }
+
+    let cert = client_certs::ensure(&domain.id.to_string(), &full_domain)?;
+    config_store.update_domain_https_options(domain.id, None, None, Some(true))?;
+    regenerate_caddyfile(&config_store)?;
+
+    println!();
+    println!("{} now requires a client certificate.", full_domain);
+    println!();
+    print_cert(&cert, &full_domain);
+
+    Ok(())
+}
+
+/// Stop requiring a client certificate for a domain
+pub fn run_client_cert_disable(name: Option<String>) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let config = config_store.load()?;
+    let domain = find_domain(&config, name)?;
+    let full_domain = domain.full_domain(&config.tld);
+
+    if !domain.require_client_cert {
+        println!("{} doesn't require a client certificate", full_domain);
+        return Ok(());
+    }
+
+    config_store.update_domain_https_options(domain.id, None, None, Some(false))?;
+    regenerate_caddyfile(&config_store)?;
+
+    println!("{} no longer requires a client certificate", full_domain);
+
+    Ok(())
+}
+
+/// Issue a new client certificate, invalidating the previous ones
+pub fn run_client_cert_reissue(name: Option<String>) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let config = config_store.load()?;
+    let domain = find_domain(&config, name)?;
+    let full_domain = domain.full_domain(&config.tld);
+
+    let cert = client_certs::issue(&domain.id.to_string(), &full_domain)?;
+    regenerate_caddyfile(&config_store)?;
+
+    println!();
+    println!(
+        "Issued a new client certificate for {}. Earlier certificates no longer work.",
+        full_domain
+    );
+    println!();
+    print_cert(&cert, &full_domain);
+
+    Ok(())
+}
+
+/// Show a domain's client certificate
+pub fn run_client_cert_show(name: Option<String>) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let domain = find_domain(&config, name)?;
+    let full_domain = domain.full_domain(&config.tld);
+
+    println!();
+    if domain.require_client_cert {
+        println!("[OK] {} requires a client certificate", full_domain);
+    } else {
+        println!("[--] {} doesn't require a client certificate", full_domain);
+    }
+    println!();
+
+    match client_certs::load(&domain.id.to_string()) {
+        Some(cert) => print_cert(&cert, &full_domain),
+        None => {
+            println!("No client certificate issued yet.");
+            println!("Run 'burd client-cert require {}'.", domain.subdomain);
+            println!();
+        }
+    }
+
+    Ok(())
+}
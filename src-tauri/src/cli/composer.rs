@@ -0,0 +1,116 @@
+//! Composer passthrough
+//!
+//! Runs the project's pinned Composer version with the project's PHP from
+//! PVM, downloading either on first use. The project's vault secrets are
+//! set for scripts Composer runs.
+
+use crate::config::ConfigStore;
+use crate::pvm::composer;
+use crate::vault;
+use std::env;
+use std::path::PathBuf;
+
+fn current_dir() -> Result<PathBuf, String> {
+    env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))
+}
+
+/// Download a Composer version unless it's already installed
+fn ensure_installed(version: &str) -> Result<(), String> {
+    if composer::is_installed(version) {
+        return Ok(());
+    }
+    println!("Downloading Composer {}...", version);
+    composer::download_version(version)?;
+    Ok(())
+}
+
+/// Run Composer with `args` in the project containing the current directory
+pub fn run_composer(args: Vec<String>) -> Result<(), String> {
+    let current_dir = current_dir()?;
+    let root = composer::find_project_root(&current_dir);
+
+    let version = match composer::selected_version(root.as_deref()) {
+        Some(version) => version,
+        None => {
+            let version = composer::resolve_version("stable")?;
+            println!("Composer is not installed yet; downloading it first.");
+            ensure_installed(&version)?;
+            composer::set_default_version(&version)?;
+            version
+        }
+    };
+    ensure_installed(&version)?;
+
+    let dir = root.unwrap_or(current_dir);
+    let mut cmd = composer::command(&dir, &version, &args)?;
+    if let Ok(config) = ConfigStore::new().and_then(|store| store.load()) {
+        vault::inject(&mut cmd, &config.vault, &dir);
+    }
+    let status = cmd
+        .status()
+        .map_err(|e| format!("Failed to execute Composer: {}", e))?;
+
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
+    Ok(())
+}
+
+/// Select a Composer version (a version or `stable`, `2.2`, `preview`)
+///
+/// Pins it in the current project, or sets the default outside a project
+/// or with `global`.
+pub fn run_composer_use(spec: &str, global: bool) -> Result<(), String> {
+    let version = composer::resolve_version(spec)?;
+    ensure_installed(&version)?;
+
+    let root = composer::find_project_root(&current_dir()?).filter(|_| !global);
+    match root {
+        Some(root) => {
+            composer::pin_version(&root, &version)?;
+            println!(
+                "Pinned Composer {} in {}",
+                version,
+                root.join(composer::PIN_FILE).display()
+            );
+        }
+        None => {
+            composer::set_default_version(&version)?;
+            println!("Default Composer version set to {}", version);
+        }
+    }
+    Ok(())
+}
+
+/// List installed Composer versions and the current project's selection
+pub fn run_composer_versions() -> Result<(), String> {
+    let installed = composer::list_installed_versions()?;
+    if installed.is_empty() {
+        println!("No Composer versions installed. Run: burd composer use stable");
+        return Ok(());
+    }
+
+    let root = composer::find_project_root(&current_dir()?);
+    let pinned = root.as_deref().and_then(composer::pinned_version);
+
+    for v in &installed {
+        let mut notes = Vec::new();
+        if v.is_default {
+            notes.push("default");
+        }
+        if pinned.as_deref() == Some(v.version.as_str()) {
+            notes.push("pinned here");
+        }
+        if notes.is_empty() {
+            println!("  {}", v.version);
+        } else {
+            println!("  {} ({})", v.version, notes.join(", "));
+        }
+    }
+
+    if let Some(pinned) = pinned.filter(|p| !composer::is_installed(p)) {
+        println!("  {} (pinned here, not installed)", pinned);
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Config export/import CLI commands
+//!
+//! Moves a Burd setup to another Mac: `burd config export` writes instances,
+//! domains, stacks, parked directories and tunnels to an archive, and
+//! `burd config import` merges one into this Mac's config.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::ConfigStore;
+use crate::config_export;
+
+fn print_list(label: &str, items: &[String]) {
+    if !items.is_empty() {
+        println!("  {:<20} {}", format!("{}:", label), items.join(", "));
+    }
+}
+
+/// Export the config to `path` (or `burd-config-<timestamp>.tar.gz` here)
+pub fn run_config_export(path: Option<PathBuf>) -> Result<(), String> {
+    let path = path.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "burd-config-{}.tar.gz",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    let config = ConfigStore::new()?.load()?;
+    let export = config_export::export_archive(&config, &path)?;
+
+    println!("✓ Exported the config to {}", path.display());
+    println!(
+        "  Instances: {}, domains: {}, stacks: {}, parked directories: {}, tunnels: {}",
+        export.instances.len(),
+        export.domains.len(),
+        export.stacks.len(),
+        export.parked_directories.len(),
+        export.tunnels.len()
+    );
+    if !export.service_definitions.is_empty() {
+        println!(
+            "  Service definitions: {}",
+            export.service_definitions.join(", ")
+        );
+    }
+    println!("Instance data isn't included; use `burd backup` or `burd db export` for that.");
+    Ok(())
+}
+
+/// Import an exported config, or with `dry_run` show what would be imported
+pub fn run_config_import(path: &Path, dry_run: bool) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
+    let mut config = config_store.load()?;
+    let result = config_export::import_archive(&mut config, path, dry_run)?;
+    if !dry_run {
+        config_store.save(&config)?;
+    }
+
+    println!();
+    if dry_run {
+        println!("Would import from {}:", path.display());
+    } else {
+        println!("✓ Imported {}", path.display());
+    }
+    print_list("Instances", &result.instances);
+    print_list("Domains", &result.domains);
+    print_list("Stacks", &result.stacks);
+    print_list("Parked directories", &result.parked_directories);
+    print_list("frp servers", &result.frp_servers);
+    print_list("Tunnels", &result.tunnels);
+    print_list("Service definitions", &result.service_definitions);
+
+    if !result.skipped.is_empty() {
+        println!();
+        println!("Skipped:");
+        for skipped in &result.skipped {
+            println!("  - {}", skipped);
+        }
+    }
+    if !result.notes.is_empty() {
+        println!();
+        for note in &result.notes {
+            println!("  ! {}", note);
+        }
+    }
+    if !result.missing_versions.is_empty() {
+        println!();
+        println!("Not installed on this Mac:");
+        for missing in &result.missing_versions {
+            println!(
+                "  burd install {} {}",
+                missing.service_type.as_str(),
+                missing.version
+            );
+        }
+    }
+
+    if !dry_run && (!result.domains.is_empty() || !result.service_definitions.is_empty()) {
+        println!();
+        println!("Restart Burd (or `burd daemon`) to load the imported domains and services.");
+    }
+    println!();
+    Ok(())
+}
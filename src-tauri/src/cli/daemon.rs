@@ -0,0 +1,74 @@
+//! Daemon CLI commands
+//!
+//! Run Burd headless (API, DNS, proxy sync, supervision) and manage the
+//! login service that starts it (a LaunchAgent on macOS, a systemd user unit
+//! on Linux).
+
+use crate::config::ConfigStore;
+use crate::daemon;
+
+/// Run the daemon in the foreground (what the login service invokes)
+pub fn run_daemon() -> Result<(), String> {
+    daemon::run()
+}
+
+/// Install the login service so the daemon starts at login
+pub fn run_daemon_install() -> Result<(), String> {
+    daemon::install()?;
+
+    println!(
+        "Installed the daemon service at {}",
+        daemon::service_path()?.display()
+    );
+    println!("The daemon starts now and at every login.");
+    println!("Logs: {}", daemon::log_path().display());
+
+    let config = ConfigStore::new()?.load()?;
+    if !config.instances.iter().any(|i| i.auto_start) {
+        println!();
+        println!("No instances are set to auto-start. Enable one with:");
+        println!("  burd update <name> --auto-start true");
+    }
+    Ok(())
+}
+
+/// Remove the login service and stop the daemon
+pub fn run_daemon_uninstall() -> Result<(), String> {
+    daemon::uninstall()?;
+    println!("Removed the daemon service.");
+    Ok(())
+}
+
+/// Show whether the daemon is installed and running
+pub fn run_daemon_status() -> Result<(), String> {
+    let status = daemon::get_status();
+
+    println!();
+    println!("Burd Daemon");
+    println!("-----------");
+    println!(
+        "  Installed:  {}",
+        if status.installed { "yes" } else { "no" }
+    );
+    match status.pid {
+        Some(pid) => println!("  Running:    yes (PID {})", pid),
+        None => println!("  Running:    no"),
+    }
+    println!("  Logs:       {}", daemon::log_path().display());
+
+    let config = ConfigStore::new()?.load()?;
+    let auto_start: Vec<&str> = config
+        .instances
+        .iter()
+        .filter(|i| i.auto_start)
+        .map(|i| i.name.as_str())
+        .collect();
+    if auto_start.is_empty() {
+        println!("  Auto-start: none");
+    } else {
+        println!("  Auto-start: {}", auto_start.join(", "));
+    }
+    println!();
+
+    Ok(())
+}
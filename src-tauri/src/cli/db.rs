@@ -2,8 +2,14 @@
 //!
 //! Commands for managing databases from the command line.
 
-use crate::config::{ConfigStore, Instance, ServiceType};
-use crate::db_manager::{create_manager_for_instance, find_all_db_instances, sanitize_db_name, DbType};
+use crate::cli::lifecycle::ensure_ready;
+use crate::cli::snapshot::take_safety_snapshot;
+use crate::config::{Config, ConfigStore, Instance};
+use crate::db_manager::{
+    create_manager_for_instance, find_all_db_instances, find_managed_db_instances,
+    generate_password, sanitize_db_name, validate_db_user, DatabaseManager, DbPrivileges, DbType,
+};
+use crate::seed;
 use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::mpsc;
@@ -16,12 +22,12 @@ pub fn run_db_list() -> Result<(), String> {
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
 
-    let db_instances = find_all_db_instances(&config);
+    let db_instances = find_managed_db_instances(&config);
 
     if db_instances.is_empty() {
         println!("No database instances configured in Burd.");
         println!();
-        println!("Create a MariaDB or PostgreSQL instance in the Burd app first.");
+        println!("Create a MariaDB, PostgreSQL or MongoDB instance in the Burd app first.");
         return Ok(());
     }
 
@@ -133,10 +139,7 @@ fn select_db_instance<'a>(
             });
     }
 
-    let matches_engine = |inst: &Instance, e: DbType| match e {
-        DbType::MariaDB => inst.service_type == ServiceType::MariaDB,
-        DbType::PostgreSQL => inst.service_type == ServiceType::PostgreSQL,
-    };
+    let matches_engine = |inst: &Instance, e: DbType| inst.service_type == e.service_type();
 
     if let Some(e) = engine {
         return instances
@@ -156,16 +159,13 @@ fn select_db_instance<'a>(
             });
     }
 
-    let has_maria = instances
-        .iter()
-        .any(|i| i.service_type == ServiceType::MariaDB);
-    let has_pg = instances
+    let multiple_engines = instances
         .iter()
-        .any(|i| i.service_type == ServiceType::PostgreSQL);
+        .any(|i| i.service_type != instances[0].service_type);
 
-    if has_maria && has_pg {
+    if multiple_engines {
         return Err(
-            "Multiple database engines available. Pass --engine mariadb|postgres or --instance <name>.".to_string(),
+            "Multiple database engines available. Pass --engine mariadb|postgres|mongodb or --instance <name>.".to_string(),
         );
     }
 
@@ -177,11 +177,7 @@ fn select_db_instance<'a>(
     if announce_auto && instances.len() > 1 {
         eprintln!(
             "Using {} instance '{}' (auto-selected)",
-            match picked.service_type {
-                ServiceType::MariaDB => "MariaDB",
-                ServiceType::PostgreSQL => "PostgreSQL",
-                _ => "database",
-            },
+            picked.service_type.display_name(),
             picked.name
         );
     }
@@ -197,10 +193,7 @@ fn find_instance_with_database<'a>(
     engine: Option<DbType>,
     instance_name: Option<&str>,
 ) -> Result<Option<&'a Instance>, String> {
-    let matches_engine = |inst: &Instance, e: DbType| match e {
-        DbType::MariaDB => inst.service_type == ServiceType::MariaDB,
-        DbType::PostgreSQL => inst.service_type == ServiceType::PostgreSQL,
-    };
+    let matches_engine = |inst: &Instance, e: DbType| inst.service_type == e.service_type();
 
     for inst in instances {
         if let Some(name) = instance_name {
@@ -235,15 +228,16 @@ pub fn run_db_create(
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
 
-    let db_instances = find_all_db_instances(&config);
+    let db_instances = find_managed_db_instances(&config);
 
     if db_instances.is_empty() {
         return Err("No database instances configured in Burd.\n\
-             Create a MariaDB or PostgreSQL instance in the Burd app first."
+             Create a MariaDB, PostgreSQL or MongoDB instance in the Burd app first."
             .to_string());
     }
 
     let instance = select_db_instance(&db_instances, engine, instance_name, true)?;
+    ensure_ready(instance)?;
     let manager = create_manager_for_instance(instance)?;
 
     // Check if database already exists
@@ -259,10 +253,26 @@ pub fn run_db_create(
     Ok(())
 }
 
-/// Drop a database
+/// Snapshot a database about to be dropped or overwritten, unless skipped
+fn snapshot_before(
+    config: &Config,
+    instance: &Instance,
+    database: &str,
+    reason: &str,
+    no_snapshot: bool,
+) -> Result<(), String> {
+    if no_snapshot {
+        return Ok(());
+    }
+    take_safety_snapshot(config, instance, database, reason)
+        .map_err(|e| format!("{}\nPass --no-snapshot to go ahead without one.", e))
+}
+
+/// Drop a database, snapshotting it first unless `no_snapshot`
 pub fn run_db_drop(
     name: &str,
     force: bool,
+    no_snapshot: bool,
     engine: Option<DbType>,
     instance_name: Option<&str>,
 ) -> Result<(), String> {
@@ -271,7 +281,7 @@ pub fn run_db_drop(
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
 
-    let db_instances = find_all_db_instances(&config);
+    let db_instances = find_managed_db_instances(&config);
 
     if db_instances.is_empty() {
         return Err("No database instances configured in Burd.".to_string());
@@ -308,6 +318,7 @@ pub fn run_db_drop(
         }
     }
 
+    snapshot_before(&config, instance, &sanitized, "before db drop", no_snapshot)?;
     println!("Dropping database '{}'...", sanitized);
     manager.drop_database(&sanitized)?;
     println!("Database '{}' dropped successfully.", sanitized);
@@ -315,10 +326,12 @@ pub fn run_db_drop(
     Ok(())
 }
 
-/// Import SQL file into database
+/// Import SQL file into database, snapshotting an existing one first unless
+/// `no_snapshot`
 pub fn run_db_import(
     name: &str,
     sql_file: &str,
+    no_snapshot: bool,
     engine: Option<DbType>,
     instance_name: Option<&str>,
 ) -> Result<(), String> {
@@ -332,7 +345,7 @@ pub fn run_db_import(
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
 
-    let db_instances = find_all_db_instances(&config);
+    let db_instances = find_managed_db_instances(&config);
 
     if db_instances.is_empty() {
         return Err("No database instances configured in Burd.".to_string());
@@ -344,7 +357,10 @@ pub fn run_db_import(
         engine,
         instance_name,
     )? {
-        Some(i) => i,
+        Some(i) => {
+            snapshot_before(&config, i, &sanitized, "before db import", no_snapshot)?;
+            i
+        }
         None => {
             // Database doesn't exist - offer to create it
             print!("Database '{}' doesn't exist. Create it? [Y/n] ", sanitized);
@@ -361,6 +377,7 @@ pub fn run_db_import(
             }
 
             let target = select_db_instance(&db_instances, engine, instance_name, true)?;
+            ensure_ready(target)?;
             let manager = create_manager_for_instance(target)?;
             println!("Creating database '{}'...", sanitized);
             manager.create_database(&sanitized)?;
@@ -389,7 +406,7 @@ pub fn run_db_export(
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
 
-    let db_instances = find_all_db_instances(&config);
+    let db_instances = find_managed_db_instances(&config);
 
     if db_instances.is_empty() {
         return Err("No database instances configured in Burd.".to_string());
@@ -410,7 +427,7 @@ pub fn run_db_export(
     // Determine output path
     let output_path = match output_file {
         Some(f) => PathBuf::from(f),
-        None => PathBuf::from(format!("{}.sql", sanitized)),
+        None => PathBuf::from(format!("{}.{}", sanitized, manager.dump_extension())),
     };
 
     // Check if file exists
@@ -448,7 +465,7 @@ pub fn run_db_shell(
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
 
-    let db_instances = find_all_db_instances(&config);
+    let db_instances = find_managed_db_instances(&config);
 
     if db_instances.is_empty() {
         return Err("No database instances configured in Burd.".to_string());
@@ -461,7 +478,9 @@ pub fn run_db_shell(
             None => return Err(format!("Database '{}' not found.", sanitized)),
         }
     } else {
-        select_db_instance(&db_instances, engine, instance_name, true)?
+        let instance = select_db_instance(&db_instances, engine, instance_name, true)?;
+        ensure_ready(instance)?;
+        instance
     };
 
     let manager = create_manager_for_instance(instance)?;
@@ -487,3 +506,272 @@ pub fn run_db_shell(
 
     Ok(())
 }
+
+/// Reset a database with its instance's seed (see `burd db seed`)
+///
+/// Uses the one instance with a seed configured unless `instance_name` picks
+/// another.
+pub fn run_db_seed(instance_name: Option<&str>, force: bool) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+
+    let seeded: Vec<&Instance> = config
+        .instances
+        .iter()
+        .filter(|i| i.seed.is_some())
+        .collect();
+    let instance = match instance_name {
+        Some(name) => config
+            .instances
+            .iter()
+            .find(|i| i.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("No instance named '{}'", name))?,
+        None => match seeded.as_slice() {
+            [instance] => *instance,
+            [] => return Err("No instance has a seed configured.".to_string()),
+            _ => {
+                return Err(format!(
+                    "Multiple instances have a seed. Pass --instance <name>: {}",
+                    seeded
+                        .iter()
+                        .map(|i| i.name.clone())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+        },
+    };
+    let seed = instance
+        .seed
+        .as_ref()
+        .ok_or_else(|| format!("'{}' has no seed configured", instance.name))?;
+
+    if !force {
+        print!(
+            "Reset database '{}' on '{}' with its seed? Its current data will be lost. [y/N] ",
+            seed.database, instance.name
+        );
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| format!("Failed to read input: {}", e))?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    ensure_ready(instance)?;
+    println!("Seeding '{}'...", seed.database);
+    let result = seed::run_seed(instance, true)?;
+    if let Some(output) = result.output.filter(|o| !o.is_empty()) {
+        println!("{}", output);
+    }
+    println!(
+        "Database '{}' seeded in {:.1}s.",
+        result.database,
+        result.duration_ms as f64 / 1000.0
+    );
+
+    Ok(())
+}
+
+/// Manager for user commands: the MariaDB instance holding `database` when
+/// given, otherwise the (single or `--instance`) MariaDB instance
+fn user_manager(
+    database: Option<&str>,
+    instance_name: Option<&str>,
+) -> Result<Box<dyn DatabaseManager>, String> {
+    let config = ConfigStore::new()?.load()?;
+    let db_instances = find_all_db_instances(&config);
+
+    if db_instances.is_empty() {
+        return Err("No database instances configured in Burd.".to_string());
+    }
+
+    let instance = match database {
+        Some(db_name) => {
+            let sanitized = sanitize_db_name(db_name)?;
+            find_instance_with_database(
+                &db_instances,
+                &sanitized,
+                Some(DbType::MariaDB),
+                instance_name,
+            )?
+            .ok_or_else(|| format!("Database '{}' not found.", sanitized))?
+        }
+        None => {
+            let instance =
+                select_db_instance(&db_instances, Some(DbType::MariaDB), instance_name, true)?;
+            ensure_ready(instance)?;
+            instance
+        }
+    };
+
+    create_manager_for_instance(instance)
+}
+
+fn privileges_label(privileges: Option<DbPrivileges>) -> &'static str {
+    match privileges {
+        Some(DbPrivileges::All) => "all privileges",
+        Some(DbPrivileges::ReadOnly) => "read-only",
+        None => "",
+    }
+}
+
+fn privileges_for(read_only: bool) -> DbPrivileges {
+    if read_only {
+        DbPrivileges::ReadOnly
+    } else {
+        DbPrivileges::All
+    }
+}
+
+/// List users, or those with access to a database
+pub fn run_db_user_list(database: Option<&str>, instance_name: Option<&str>) -> Result<(), String> {
+    let manager = user_manager(database, instance_name)?;
+    let sanitized = database.map(sanitize_db_name).transpose()?;
+    let users = manager.list_users(sanitized.as_deref())?;
+
+    println!();
+    match &sanitized {
+        Some(db) => println!("Users with access to '{}'", db),
+        None => println!("Users ({})", manager.connection_info()),
+    }
+    println!("{}", "-".repeat(40));
+    if users.is_empty() {
+        println!("  (no users)");
+    }
+    for user in &users {
+        println!("  {:<32} {}", user.name, privileges_label(user.privileges));
+    }
+    println!();
+    Ok(())
+}
+
+/// Create a user, optionally granting it access to a database
+pub fn run_db_user_create(
+    username: &str,
+    password: Option<&str>,
+    database: Option<&str>,
+    read_only: bool,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let username = validate_db_user(username)?;
+    let manager = user_manager(database, instance_name)?;
+
+    let generated = password.is_none();
+    let password = password.map(String::from).unwrap_or_else(generate_password);
+    manager.create_user(&username, &password)?;
+    println!("User '{}' created.", username);
+
+    if let Some(db_name) = database {
+        let sanitized = sanitize_db_name(db_name)?;
+        let privileges = privileges_for(read_only);
+        manager.grant_privileges(&username, &sanitized, privileges)?;
+        println!(
+            "Granted {} on '{}'.",
+            privileges_label(Some(privileges)),
+            sanitized
+        );
+    }
+
+    if generated {
+        println!();
+        println!("Password: {}", password);
+    }
+    Ok(())
+}
+
+/// Drop a user
+pub fn run_db_user_drop(
+    username: &str,
+    force: bool,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let username = validate_db_user(username)?;
+    let manager = user_manager(None, instance_name)?;
+
+    if !manager.user_exists(&username)? {
+        return Err(format!("User '{}' not found.", username));
+    }
+
+    if !force {
+        print!("Are you sure you want to drop user '{}'? [y/N] ", username);
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .map_err(|e| format!("Failed to read input: {}", e))?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    manager.drop_user(&username)?;
+    println!("User '{}' dropped.", username);
+    Ok(())
+}
+
+/// Grant a user access to a database, replacing what it had there
+pub fn run_db_user_grant(
+    username: &str,
+    database: &str,
+    read_only: bool,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let username = validate_db_user(username)?;
+    let sanitized = sanitize_db_name(database)?;
+    let manager = user_manager(Some(&sanitized), instance_name)?;
+
+    let privileges = privileges_for(read_only);
+    manager.grant_privileges(&username, &sanitized, privileges)?;
+    println!(
+        "Granted '{}' {} on '{}'.",
+        username,
+        privileges_label(Some(privileges)),
+        sanitized
+    );
+    Ok(())
+}
+
+/// Revoke a user's access to a database
+pub fn run_db_user_revoke(
+    username: &str,
+    database: &str,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let username = validate_db_user(username)?;
+    let sanitized = sanitize_db_name(database)?;
+    let manager = user_manager(Some(&sanitized), instance_name)?;
+
+    manager.revoke_privileges(&username, &sanitized)?;
+    println!("Revoked access of '{}' to '{}'.", username, sanitized);
+    Ok(())
+}
+
+/// Change a user's password (generates one when none is given)
+pub fn run_db_user_password(
+    username: &str,
+    password: Option<&str>,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let username = validate_db_user(username)?;
+    let manager = user_manager(None, instance_name)?;
+
+    let generated = password.is_none();
+    let password = password.map(String::from).unwrap_or_else(generate_password);
+    manager.set_user_password(&username, &password)?;
+    println!("Password of '{}' changed.", username);
+
+    if generated {
+        println!();
+        println!("Password: {}", password);
+    }
+    Ok(())
+}
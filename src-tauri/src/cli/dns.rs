@@ -0,0 +1,48 @@
+//! DNS CLI commands
+//!
+//! Self-test for `.<tld>` resolution that pinpoints the broken hop.
+
+use crate::config::ConfigStore;
+use crate::dns_check::{self, CheckStatus, DnsSelfTest};
+
+/// Print each hop check with its hint
+pub(crate) fn print_checks(report: &DnsSelfTest) {
+    for check in &report.checks {
+        let symbol = match check.status {
+            CheckStatus::Pass => "[OK]",
+            CheckStatus::Fail => "[ERR]",
+            CheckStatus::Skipped => "[--]",
+        };
+        println!("  {} {}: {}", symbol, check.hop.label(), check.detail);
+        if let Some(hint) = &check.hint {
+            println!("      {}", hint);
+        }
+    }
+}
+
+/// Test DNS resolution for a domain (default: the first configured domain)
+pub fn run_dns_test(domain: Option<&str>) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let domain = domain
+        .map(String::from)
+        .unwrap_or_else(|| dns_check::default_test_domain(&config));
+
+    println!();
+    println!(
+        "DNS self-test for {} (.{} on port {})",
+        domain, config.tld, config.dns_port
+    );
+    println!("{}", "-".repeat(40));
+
+    let report = dns_check::run_self_test(&config.tld, config.dns_port, &domain);
+    print_checks(&report);
+
+    println!();
+    if report.passed() {
+        println!("Resolution works end to end.");
+    } else if let Some(hop) = report.broken_hop {
+        println!("Broken hop: {}", hop.label());
+    }
+
+    Ok(())
+}
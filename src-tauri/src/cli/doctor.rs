@@ -8,8 +8,12 @@ use crate::analyzer::{
     parse_env_file, ProjectType,
 };
 use crate::api_client::BurdApiClient;
+use crate::binary_verify;
 use crate::config::{ConfigStore, ServiceType};
 use crate::db_manager::{create_manager_for_instance, find_all_db_instances};
+use crate::dns_check;
+use crate::domain::DomainManager;
+use crate::services::mailpit::MailpitService;
 use std::collections::HashMap;
 use std::env;
 use std::net::TcpStream;
@@ -86,6 +90,8 @@ impl Status {
 ///
 /// Checks:
 /// - Burd service instances (running, ports available)
+/// - DNS resolution, hop by hop
+/// - Installed binaries (hashes, permissions, quarantine, Homebrew links)
 /// - Current project configuration vs Burd services
 /// - Database connectivity and existence
 /// - Cache configuration
@@ -223,7 +229,43 @@ pub fn run_doctor() -> Result<(), String> {
         println!("      Install proxy in Burd app for HTTPS support.");
     }
 
-    // === Section 3: Current Project ===
+    // === Section 3: DNS ===
+    println!();
+    println!("DNS");
+    println!("---");
+
+    let dns_domain = dns_check::default_test_domain(&config);
+    let dns_report = dns_check::run_self_test(&config.tld, config.dns_port, &dns_domain);
+    super::dns::print_checks(&dns_report);
+    if let Some(hop) = dns_report.broken_hop {
+        println!(
+            "      Resolution of {} breaks at: {}",
+            dns_domain,
+            hop.label()
+        );
+    }
+
+    // === Section 4: Binaries ===
+    println!();
+    println!("Binaries");
+    println!("--------");
+
+    let checks = binary_verify::verify_binaries(&config, None, false);
+    let broken: Vec<_> = checks.iter().filter(|c| !c.healthy()).collect();
+    if broken.is_empty() {
+        println!(
+            "  {} {} installed binaries intact",
+            Status::Ok.symbol(),
+            checks.len()
+        );
+    } else {
+        for check in &broken {
+            super::verify::print_check(check);
+        }
+        println!("      Run `burd verify --repair` to fix what can be fixed in place.");
+    }
+
+    // === Section 5: Current Project ===
     println!();
     println!("Current Project");
     println!("---------------");
@@ -254,7 +296,7 @@ pub fn run_doctor() -> Result<(), String> {
                 });
 
                 if is_linked {
-                    let subdomain = slug::slugify(&project.name);
+                    let subdomain = DomainManager::slugify_with(&project.name, &config.slugs);
                     println!(
                         "  {} Linked to {}.{}",
                         Status::Ok.symbol(),
@@ -531,18 +573,11 @@ fn check_project_mail(
         .unwrap_or("log");
 
     if mail_mailer == "smtp" {
-        let mailpit_instance = config
-            .instances
-            .iter()
-            .find(|i| i.service_type == ServiceType::Mailpit);
+        let mailpit_instance = MailpitService::for_project(config, project_dir);
 
         match mailpit_instance {
             Some(instance) => {
-                let smtp_port = instance
-                    .config
-                    .get("smtp_port")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(1025) as u16;
+                let smtp_port = MailpitService::smtp_port(instance);
 
                 if let Some(mail) = mail_config {
                     if mail.host == "127.0.0.1" && mail.port == smtp_port {
@@ -1,16 +1,22 @@
 //! Environment CLI commands
 //!
-//! Commands for checking and fixing .env files against Burd services.
+//! Commands for checking and fixing .env files against Burd services, plus
+//! the cache and search plugin settings WordPress keeps outside .env, and
+//! for the project's secrets vault (`burd env set|unset|secrets`).
 
 use crate::analyzer::{
-    analyze_with_burd_config, extract_cache_config, extract_database_config, extract_mail_config,
-    parse_env_file, update_env_value, ProjectType,
+    analyze_with_burd_config, apply_env_fixes, extract_cache_config, extract_database_config,
+    extract_mail_config, parse_env_file, update_env_value, wordpress, ProjectIssue, ProjectType,
+    WpFix,
 };
 use crate::config::{ConfigStore, DomainTarget, ServiceType};
+use crate::mail_routing::{self, MailRouteTarget, SmtpRelay};
+use crate::services::mailpit::MailpitService;
+use crate::vault;
 use std::collections::HashMap;
 use std::env;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// An issue found in the .env file
 #[derive(Debug, Clone)]
@@ -38,7 +44,7 @@ pub fn run_env_check() -> Result<(), String> {
     // Analyze the project
     let project = analyze_with_burd_config(&current_dir, &config)?;
 
-    if !project.project_type.uses_env_file() {
+    if !project.project_type.uses_env_file() && !project.project_type.uses_wp_config() {
         println!(
             "This project type ({}) doesn't use .env files.",
             project.project_type
@@ -46,21 +52,16 @@ pub fn run_env_check() -> Result<(), String> {
         return Ok(());
     }
 
-    let env_path = current_dir.join(".env");
-    if !env_path.exists() {
-        return Err("No .env file found in current directory.".to_string());
-    }
+    let issues = load_env_issues(&project.project_type, &config, &current_dir)?;
+    let wp_fixes = wordpress::plan_fixes(&project.project_type, &current_dir, &config);
 
-    let env_vars = parse_env_file(&env_path).ok_or("Failed to parse .env file")?;
-    let issues = check_env_against_burd(&project.project_type, &env_vars, &config, &current_dir)?;
-
-    print_env_check_results(&issues);
+    print_env_check_results(&issues, &wp_fixes);
 
     Ok(())
 }
 
-/// Interactive fix for .env issues
-pub fn run_env_fix() -> Result<(), String> {
+/// Fix .env issues, interactively or all at once with `apply_all`
+pub fn run_env_fix(apply_all: bool) -> Result<(), String> {
     let current_dir =
         env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
 
@@ -70,7 +71,7 @@ pub fn run_env_fix() -> Result<(), String> {
     // Analyze the project
     let project = analyze_with_burd_config(&current_dir, &config)?;
 
-    if !project.project_type.uses_env_file() {
+    if !project.project_type.uses_env_file() && !project.project_type.uses_wp_config() {
         println!(
             "This project type ({}) doesn't use .env files.",
             project.project_type
@@ -79,20 +80,21 @@ pub fn run_env_fix() -> Result<(), String> {
     }
 
     let env_path = current_dir.join(".env");
-    if !env_path.exists() {
-        return Err("No .env file found in current directory.".to_string());
-    }
-
-    let env_vars = parse_env_file(&env_path).ok_or("Failed to parse .env file")?;
-    let issues = check_env_against_burd(&project.project_type, &env_vars, &config, &current_dir)?;
+    let issues = load_env_issues(&project.project_type, &config, &current_dir)?;
+    let wp_fixes = wordpress::plan_fixes(&project.project_type, &current_dir, &config);
+    let total = issues.len() + wp_fixes.len();
 
-    if issues.is_empty() {
-        println!("No issues found. Your .env file is configured correctly for Burd services.");
+    if total == 0 {
+        println!("No issues found. Your project is configured correctly for Burd services.");
         return Ok(());
     }
 
+    if apply_all {
+        return apply_all_fixes(&current_dir, &issues, &wp_fixes);
+    }
+
     println!();
-    println!("Found {} issue(s) in .env file:", issues.len());
+    println!("Found {} issue(s):", total);
     println!();
 
     let mut fixed_count = 0;
@@ -110,15 +112,7 @@ pub fn run_env_fix() -> Result<(), String> {
         println!("  Reason:    {}", issue.reason);
         println!();
 
-        print!("Apply this fix? [y/N] ");
-        io::stdout().flush().unwrap();
-
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .map_err(|e| format!("Failed to read input: {}", e))?;
-
-        if input.trim().eq_ignore_ascii_case("y") {
+        if confirm_fix()? {
             update_env_value(&env_path, &issue.key, &issue.suggested)?;
             println!("  Updated {}", issue.key);
             fixed_count += 1;
@@ -128,12 +122,91 @@ pub fn run_env_fix() -> Result<(), String> {
         println!();
     }
 
+    for fix in &wp_fixes {
+        println!("[{}] {}", fix.category, fix.target);
+        println!(
+            "  Current:   {}",
+            fix.current
+                .as_deref()
+                .map(|c| mask_sensitive(&fix.target, c))
+                .unwrap_or_else(|| "(not set)".to_string())
+        );
+        println!(
+            "  Suggested: {}",
+            mask_sensitive(&fix.target, &fix.suggested)
+        );
+        println!("  Reason:    {}", fix.reason);
+        println!();
+
+        if confirm_fix()? {
+            fix.apply(&current_dir)?;
+            println!("  Updated {}", fix.target);
+            fixed_count += 1;
+        } else {
+            println!("  Skipped");
+        }
+        println!();
+    }
+
     println!();
-    println!("Fixed {} of {} issue(s).", fixed_count, issues.len());
+    println!("Fixed {} of {} issue(s).", fixed_count, total);
 
     Ok(())
 }
 
+/// Apply every fix without prompting
+///
+/// The .env changes are written in one go, with the previous file kept as
+/// .env.backup.
+fn apply_all_fixes(
+    current_dir: &Path,
+    issues: &[EnvIssue],
+    wp_fixes: &[WpFix],
+) -> Result<(), String> {
+    let project_issues: Vec<ProjectIssue> = issues
+        .iter()
+        .filter(|issue| issue.current != issue.suggested)
+        .map(|issue| {
+            ProjectIssue::warning(&issue.category, &issue.reason)
+                .with_env_edit(&issue.key, &issue.suggested)
+        })
+        .collect();
+
+    let result = apply_env_fixes(current_dir, &project_issues)?;
+    for edit in &result.applied {
+        println!(
+            "Updated {} = {}",
+            edit.key,
+            mask_sensitive(&edit.key, &edit.value)
+        );
+    }
+    if let Some(backup) = &result.backup {
+        println!("Previous .env saved to {}", backup.display());
+    }
+
+    for fix in wp_fixes {
+        fix.apply(current_dir)?;
+        println!("Updated {}", fix.target);
+    }
+
+    println!();
+    println!("Fixed {} issue(s).", result.applied.len() + wp_fixes.len());
+    Ok(())
+}
+
+/// Ask whether to apply the fix just shown
+fn confirm_fix() -> Result<bool, String> {
+    print!("Apply this fix? [y/N] ");
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
 /// Show relevant .env values
 pub fn run_env_show() -> Result<(), String> {
     let current_dir =
@@ -214,6 +287,27 @@ pub fn run_env_show() -> Result<(), String> {
     Ok(())
 }
 
+/// Load the project's .env and check it against Burd services
+///
+/// Plain WordPress has no .env, so it has no .env issues.
+fn load_env_issues(
+    project_type: &ProjectType,
+    config: &crate::config::Config,
+    current_dir: &Path,
+) -> Result<Vec<EnvIssue>, String> {
+    if !project_type.uses_env_file() {
+        return Ok(Vec::new());
+    }
+
+    let env_path = current_dir.join(".env");
+    if !env_path.exists() {
+        return Err("No .env file found in current directory.".to_string());
+    }
+
+    let env_vars = parse_env_file(&env_path).ok_or("Failed to parse .env file")?;
+    check_env_against_burd(project_type, &env_vars, config, current_dir)
+}
+
 /// Check environment variables against Burd services
 fn check_env_against_burd(
     project_type: &ProjectType,
@@ -238,7 +332,7 @@ fn check_env_against_burd(
     // Check mail configuration (Laravel only)
     if matches!(project_type, ProjectType::Laravel { .. }) {
         if let Some(mail_config) = extract_mail_config(project_type, env_vars) {
-            check_mail_env(&mail_config, env_vars, config, &mut issues);
+            check_mail_env(&mail_config, env_vars, config, current_dir, &mut issues);
         }
     }
 
@@ -275,7 +369,7 @@ fn check_site_url_env(
     let Some(domain) = domain else { return };
 
     let scheme = if domain.ssl_enabled { "https" } else { "http" };
-    let expected = format!("{}://{}.{}", scheme, domain.subdomain, config.tld);
+    let expected = format!("{}://{}", scheme, domain.full_domain(&config.tld));
 
     let keys: &[&str] = match project_type {
         ProjectType::Laravel { .. } => &["APP_URL"],
@@ -416,26 +510,36 @@ fn check_cache_env(
 /// Check mail environment variables
 fn check_mail_env(
     mail_config: &crate::analyzer::MailConfig,
-    _env_vars: &HashMap<String, String>,
+    env_vars: &HashMap<String, String>,
     config: &crate::config::Config,
+    current_dir: &Path,
     issues: &mut Vec<EnvIssue>,
 ) {
-    // Find Mailpit instance
-    let mailpit_instance = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit);
+    // A routed project gets the route's complete MAIL_* settings
+    if let Some(destination) = mail_routing::routed_destination(config, current_dir) {
+        for change in destination.env_changes(env_vars) {
+            issues.push(EnvIssue {
+                key: change.key.to_string(),
+                current: change.current,
+                suggested: change.suggested,
+                reason: format!(
+                    "Mail for this project is routed to {}",
+                    destination.describe()
+                ),
+                category: "mail".to_string(),
+            });
+        }
+        return;
+    }
+
+    // Find the Mailpit instance this project should send mail to
+    let mailpit_instance = MailpitService::for_project(config, current_dir);
 
     let Some(instance) = mailpit_instance else {
         return;
     };
 
-    // Mailpit SMTP port is typically 1025
-    let smtp_port = instance
-        .config
-        .get("smtp_port")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(1025) as u16;
+    let smtp_port = MailpitService::smtp_port(instance);
 
     // Check if using SMTP. If Mailpit is configured but the app is logging to
     // file or dropping mail to the array driver, the user probably set that
@@ -484,16 +588,16 @@ fn check_mail_env(
 }
 
 /// Print environment check results
-fn print_env_check_results(issues: &[EnvIssue]) {
+fn print_env_check_results(issues: &[EnvIssue], wp_fixes: &[WpFix]) {
     println!();
 
-    if issues.is_empty() {
-        println!("No issues found. Your .env file is configured correctly for Burd services.");
+    if issues.is_empty() && wp_fixes.is_empty() {
+        println!("No issues found. Your project is configured correctly for Burd services.");
         println!();
         return;
     }
 
-    println!("Found {} issue(s):", issues.len());
+    println!("Found {} issue(s):", issues.len() + wp_fixes.len());
     println!();
 
     // Group by category
@@ -511,7 +615,24 @@ fn print_env_check_results(issues: &[EnvIssue]) {
         println!();
     }
 
-    println!("Run 'burd env fix' to fix these issues interactively.");
+    for fix in wp_fixes {
+        println!("[{}]", fix.category);
+        println!(
+            "  {}: {} -> {}",
+            fix.target,
+            fix.current
+                .as_deref()
+                .map(|c| mask_sensitive(&fix.target, c))
+                .unwrap_or_else(|| "(not set)".to_string()),
+            mask_sensitive(&fix.target, &fix.suggested)
+        );
+        println!("    {}", fix.reason);
+        println!();
+    }
+
+    println!(
+        "Run 'burd env fix' to fix these issues interactively, or 'burd env fix --apply-all'."
+    );
     println!();
 }
 
@@ -546,3 +667,181 @@ fn mask_sensitive(key: &str, value: &str) -> String {
         value.to_string()
     }
 }
+
+// ============================================================================
+// Secrets vault
+// ============================================================================
+
+/// Root of the project containing the current directory
+fn vault_project() -> Result<PathBuf, String> {
+    let current_dir =
+        env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    Ok(vault::project_root(&current_dir))
+}
+
+/// Read a secret's value from stdin (works when piped, too)
+fn prompt_value(key: &str) -> Result<String, String> {
+    print!("Value for {}: ", key);
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Store secrets for the current project (`KEY=VALUE`, or `KEY` to be
+/// asked for the value so it stays out of shell history)
+pub fn run_env_set(assignments: Vec<String>) -> Result<(), String> {
+    let project = vault_project()?;
+    let store = ConfigStore::new()?;
+    let dotenv = parse_env_file(&project.join(".env")).unwrap_or_default();
+
+    // Ask for values before taking the config lock
+    let mut secrets = Vec::new();
+    for assignment in &assignments {
+        secrets.push(if assignment.contains('=') {
+            vault::parse_assignment(assignment)?
+        } else {
+            vault::validate_key(assignment)?;
+            (assignment.clone(), prompt_value(assignment)?)
+        });
+    }
+
+    let _lock = store.lock_file()?;
+    let mut vault_projects = store.load()?.vault;
+    for (key, value) in &secrets {
+        vault::set(&mut vault_projects, &project, key, value)?;
+        println!("✓ Stored {} in the vault", key);
+        if dotenv.contains_key(key) {
+            println!(
+                "  {} is also in .env; the vault value takes precedence at runtime.",
+                key
+            );
+        }
+    }
+
+    store.update_vault(vault_projects)?;
+    println!("Project: {}", project.display());
+    println!("Restart the project's instance to apply.");
+    Ok(())
+}
+
+/// Remove secrets of the current project
+pub fn run_env_unset(keys: Vec<String>) -> Result<(), String> {
+    let project = vault_project()?;
+    let store = ConfigStore::new()?;
+    let _lock = store.lock_file()?;
+    let mut vault_projects = store.load()?.vault;
+
+    for key in &keys {
+        if vault::unset(&mut vault_projects, &project, key) {
+            println!("✓ Removed {} from the vault", key);
+        } else {
+            println!("• {} is not in the vault", key);
+        }
+    }
+
+    store.update_vault(vault_projects)
+}
+
+/// List the current project's secrets; values only with `reveal`
+pub fn run_env_secrets(reveal: bool) -> Result<(), String> {
+    let current_dir =
+        env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let config = ConfigStore::new()?.load()?;
+
+    let Some(project) = vault::find_project(&config.vault, &current_dir) else {
+        println!("No secrets for this project. Add one with:");
+        println!("  burd env set KEY=VALUE");
+        return Ok(());
+    };
+
+    println!();
+    println!("Secrets for {}", project.path);
+    println!("{}", "-".repeat(12 + project.path.len()));
+    for key in &project.keys {
+        let value = if reveal {
+            vault::get(Path::new(&project.path), key).unwrap_or_else(|e| format!("({})", e))
+        } else {
+            "********".to_string()
+        };
+        println!("  {} = {}", key, value);
+    }
+    println!();
+    Ok(())
+}
+
+/// Show or change where the current project's mail goes
+///
+/// With `mailpit` or `smtp` the project gets a route; `reset` removes it so
+/// the stack's Mailpit is used again. The .env is updated by `burd env fix`.
+pub fn run_env_mail(
+    mailpit: Option<String>,
+    smtp: Option<String>,
+    username: Option<String>,
+    encryption: Option<String>,
+    reset: bool,
+) -> Result<(), String> {
+    let project = vault_project()?;
+    let store = ConfigStore::new()?;
+    let config_lock = store.lock_file()?;
+    let config = store.load()?;
+    let mut routes = config.mail_routes.clone();
+
+    let target = if let Some(name) = mailpit {
+        let instance = MailpitService::instances(&config)
+            .into_iter()
+            .find(|i| i.name == name || i.id.to_string() == name)
+            .ok_or_else(|| format!("No Mailpit instance named '{}'.", name))?;
+        Some(MailRouteTarget::Mailpit {
+            instance_id: instance.id,
+        })
+    } else if let Some(address) = smtp {
+        let (host, port) = mail_routing::parse_host_port(&address)?;
+        let relay = SmtpRelay::new(&host, port, username.as_deref(), encryption.as_deref())?;
+        Some(MailRouteTarget::Smtp(relay))
+    } else {
+        None
+    };
+
+    if let Some(target) = target {
+        mail_routing::set_route(&mut routes, &project, target);
+        store.update_mail_routes(routes)?;
+    } else if reset {
+        let before = routes.len();
+        let root = project.to_string_lossy();
+        routes.retain(|r| r.project != root);
+        if routes.len() == before {
+            println!("This project has no mail route.");
+            return Ok(());
+        }
+        store.update_mail_routes(routes)?;
+        println!("✓ Removed the mail route");
+    }
+    drop(config_lock);
+
+    let config = store.load()?;
+    println!();
+    println!("Project: {}", project.display());
+    match mail_routing::routed_destination(&config, &project) {
+        Some(destination) => {
+            println!("Mail goes to {}", destination.describe());
+            if matches!(destination, mail_routing::MailDestination::Smtp(_)) {
+                println!("  Keep the relay password in the vault: burd env set MAIL_PASSWORD");
+            }
+        }
+        None => match MailpitService::for_project(&config, &project) {
+            Some(instance) => println!(
+                "Mail goes to the stack's Mailpit '{}' (SMTP port {})",
+                instance.name,
+                MailpitService::smtp_port(instance)
+            ),
+            None => println!("No Mailpit instance; mail isn't captured by Burd."),
+        },
+    }
+    println!();
+    println!("Run 'burd env fix' to apply the MAIL_* settings to .env.");
+    Ok(())
+}
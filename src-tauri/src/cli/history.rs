@@ -0,0 +1,33 @@
+//! `burd history [NAME] [--limit N]` — an instance's activity timeline.
+//!
+//! Reads the timeline straight from the app directory, so it works whether
+//! or not the app is running.
+
+use crate::activity;
+use crate::cli::lifecycle::resolve_instance;
+use crate::config::ConfigStore;
+
+pub fn run_history(name: Option<String>, limit: usize) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let instance = resolve_instance(&config, name.as_deref())?;
+    let events = activity::load(&instance.id, limit)?;
+
+    println!();
+    println!("Activity for {}", instance.name);
+    println!("{}", "-".repeat(40));
+    if events.is_empty() {
+        println!("  No activity recorded yet.");
+    }
+    for event in &events {
+        let time = event
+            .at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S");
+        match &event.detail {
+            Some(detail) => println!("  {}  {:<16} {}", time, event.kind.label(), detail),
+            None => println!("  {}  {}", time, event.kind.label()),
+        }
+    }
+    println!();
+    Ok(())
+}
@@ -7,7 +7,9 @@
 use crate::analyzer::{detect_project_type, get_document_root};
 use crate::api_client::BurdApiClient;
 use crate::caddy;
-use crate::config::{ConfigStore, Domain, Instance, ServiceType};
+use crate::config::{Config, ConfigStore, Domain, Instance, ServiceType};
+use crate::domain::DomainManager;
+use crate::port_allocator;
 use chrono::Utc;
 use std::env;
 use std::path::{Path, PathBuf};
@@ -42,21 +44,27 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
         ));
     }
 
-    let project_name = current_dir
+    init_project(&current_dir, opts)
+}
+
+/// Create the FrankenPHP instance and domain for `project_dir` (also used by
+/// `burd new`)
+pub(crate) fn init_project(project_dir: &Path, opts: InitOptions) -> Result<(), String> {
+    let project_name = project_dir
         .file_name()
         .and_then(|n| n.to_str())
         .ok_or_else(|| "Could not determine project name from directory".to_string())?
         .to_string();
 
     // Resolve doc root: explicit override wins, else framework detection.
-    let project_type = detect_project_type(&current_dir);
+    let project_type = detect_project_type(project_dir);
     let doc_root_path: PathBuf = match opts.public_dir.as_ref() {
-        Some(p) => resolve_override_dir(&current_dir, p)?,
-        None => get_document_root(&current_dir, &project_type),
+        Some(p) => resolve_override_dir(project_dir, p)?,
+        None => get_document_root(project_dir, &project_type),
     };
     let document_root = doc_root_path.to_string_lossy().to_string();
 
-    if doc_root_path != current_dir {
+    if doc_root_path != project_dir {
         println!(
             "Detected {} project — document root: {}",
             project_type, document_root
@@ -66,9 +74,10 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
     }
 
     let config_store = ConfigStore::new()?;
+    let config_lock = config_store.lock_file()?;
     let mut config = config_store.load()?;
 
-    let subdomain = slug::slugify(&project_name);
+    let subdomain = DomainManager::slugify_with(&project_name, &config.slugs);
 
     if config.domains.iter().any(|d| d.subdomain == subdomain) {
         return Err(format!(
@@ -77,24 +86,9 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
         ));
     }
 
-    let mut port = ServiceType::FrankenPHP.default_port();
-    while config.instances.iter().any(|i| i.port == port) {
-        if port == u16::MAX {
-            return Err("No available ports found".to_string());
-        }
-        port += 1;
-    }
+    let port = port_allocator::suggest_port(&config, ServiceType::FrankenPHP)?;
 
-    let version = config
-        .binaries
-        .get(&ServiceType::FrankenPHP)
-        .and_then(|versions| versions.keys().next())
-        .ok_or_else(|| {
-            "No FrankenPHP versions installed.\n\
-             Please download FrankenPHP in the Burd app first."
-                .to_string()
-        })?
-        .clone();
+    let version = config.default_version(ServiceType::FrankenPHP)?;
 
     let instance = Instance {
         id: Uuid::new_v4(),
@@ -107,10 +101,15 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
         }),
         master_key: None,
         auto_start: false,
+        restart_on_failure: false,
+        wake_on_access: false,
         created_at: Utc::now(),
         domain: Some(subdomain.clone()),
         domain_enabled: true,
         stack_id: None,
+        tags: Vec::new(),
+        extra_args: Vec::new(),
+        seed: None,
     };
 
     let instance_dir = crate::config::get_instance_dir(&instance.id)?;
@@ -125,6 +124,7 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
     config.domains.push(domain);
 
     config_store.save(&config)?;
+    drop(config_lock);
 
     // Previously `burd init` wrote the domain to config but never generated
     // the Caddy domain file, so Caddy had no route until the daemon next
@@ -141,15 +141,7 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
         eprintln!("Warning: failed to write Caddy domain file for {}: {}", full_domain, e);
     }
 
-    let scheme = if ssl_enabled { "https" } else { "http" };
-    let url = if config.proxy_installed {
-        format!("{}://{}.{}", scheme, subdomain, config.tld)
-    } else {
-        format!(
-            "{}://{}.{}:{}",
-            scheme, subdomain, config.tld, config.proxy_port
-        )
-    };
+    let url = site_url(&config, &subdomain, ssl_enabled);
 
     println!();
     println!(
@@ -166,7 +158,7 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
     // Seed `.env` from `.env.example` so first-run `php artisan migrate` etc.
     // don't die on a missing file. Skipped when `.env` already exists — we
     // never overwrite user config.
-    seed_env_from_example(&current_dir);
+    seed_env_from_example(project_dir);
 
     if opts.no_start {
         println!();
@@ -206,6 +198,20 @@ pub fn run_init_with(opts: InitOptions) -> Result<(), String> {
     Ok(())
 }
 
+/// URL a linked site is reachable at, through the proxy port until the
+/// proxy is installed on 80/443
+pub(crate) fn site_url(config: &Config, subdomain: &str, ssl: bool) -> String {
+    let scheme = if ssl { "https" } else { "http" };
+    if config.proxy_installed {
+        format!("{}://{}.{}", scheme, subdomain, config.tld)
+    } else {
+        format!(
+            "{}://{}.{}:{}",
+            scheme, subdomain, config.tld, config.proxy_port
+        )
+    }
+}
+
 /// Copy `.env.example` to `.env` on a fresh checkout. Intentionally silent on
 /// success (init output is already noisy) and non-fatal on failure — we don't
 /// want a locked filesystem or unreadable example file to block instance setup.
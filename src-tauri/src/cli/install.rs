@@ -0,0 +1,110 @@
+//! `burd install <service> [version]` — download a service binary.
+//!
+//! The download runs in the app or daemon (`POST /services/:type/install`),
+//! which publishes `download` events on the `/events` WebSocket; those drive
+//! the progress bar. Without the stream the install still runs, just
+//! without progress.
+
+use crate::api_client::{events_request, BurdApiClient};
+use crate::binary::DownloadProgress;
+use crate::commands::parse_service_type;
+use futures_util::StreamExt;
+use std::io::Write;
+use std::time::Duration;
+use tokio_tungstenite::connect_async;
+
+/// How long to wait for the download and install to finish
+const INSTALL_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Width of the progress bar, in characters
+const BAR_WIDTH: usize = 30;
+
+pub fn run_install(service: &str, version: Option<String>) -> Result<(), String> {
+    let service_type = parse_service_type(service)?;
+
+    if !BurdApiClient::new().is_available() {
+        return Err(
+            "Burd app isn't running. Open Burd or run `burd setup`, then try again.".to_string(),
+        );
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to start runtime: {}", e))?;
+    let installed = runtime.block_on(install(service_type.as_str(), version))?;
+
+    let installed: serde_json::Value = serde_json::from_str(&installed)
+        .map_err(|e| format!("Unexpected response from Burd: {}", e))?;
+    println!(
+        "Installed {} {}.",
+        service_type.display_name(),
+        installed
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+    );
+    Ok(())
+}
+
+/// Start the install and show progress until it finishes
+async fn install(service: &'static str, version: Option<String>) -> Result<String, String> {
+    // Subscribe first so the earliest progress isn't missed
+    let progress = match connect_async(events_request("?types=download")?).await {
+        Ok((stream, _)) => Some(tokio::spawn(async move {
+            let (_, mut read) = stream.split();
+            while let Some(Ok(message)) = read.next().await {
+                let Ok(progress) =
+                    serde_json::from_str::<DownloadProgress>(message.to_text().unwrap_or_default())
+                else {
+                    continue;
+                };
+                if progress.service_type == service {
+                    render(&progress);
+                }
+            }
+        })),
+        Err(_) => {
+            println!("Installing {} (progress unavailable)...", service);
+            None
+        }
+    };
+
+    let path = format!("/services/{}/install", service);
+    let body = serde_json::json!({ "version": version });
+    let result = tokio::task::spawn_blocking(move || {
+        BurdApiClient::with_timeout(INSTALL_TIMEOUT).post(&path, &body)
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Task error: {}", e)));
+
+    if let Some(progress) = progress {
+        progress.abort();
+        // Clear the progress line
+        print!("\r{:width$}\r", "", width = BAR_WIDTH + 40);
+        let _ = std::io::stdout().flush();
+    }
+    result
+}
+
+/// Draw the progress line, overwriting the previous one
+fn render(progress: &DownloadProgress) {
+    let line = if progress.percentage < 0.0 || progress.total == 0 {
+        // Indeterminate, e.g. a Homebrew install
+        format!("{}...", progress.phase)
+    } else {
+        let filled = ((progress.percentage / 100.0) * BAR_WIDTH as f64).round() as usize;
+        let filled = filled.min(BAR_WIDTH);
+        format!(
+            "{:<11} [{}{}] {:>3.0}%  {:.1}/{:.1} MB",
+            progress.phase,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            progress.percentage,
+            progress.downloaded as f64 / 1_048_576.0,
+            progress.total as f64 / 1_048_576.0
+        )
+    };
+    print!("\r{:width$}", line, width = BAR_WIDTH + 40);
+    let _ = std::io::stdout().flush();
+}
@@ -0,0 +1,81 @@
+//! `burd instances [--tag TAG]...` — list instances, optionally filtered by tag.
+//!
+//! Reads the config directly, so it works whether or not the app is running.
+//! With `--host`, asks the other machine's API instead.
+
+use crate::api_client;
+use crate::cli::remote;
+use crate::config::ConfigStore;
+use crate::process::ProcessManager;
+
+/// List instances carrying all of `tags` (every instance when empty)
+pub fn run_instances_list(tags: Vec<String>) -> Result<(), String> {
+    if api_client::remote_host().is_some() {
+        let rows = remote::list_instances(&tags)?
+            .into_iter()
+            .map(|i| Row {
+                name: i.name,
+                service: i.service_type,
+                port: i.port,
+                running: i.running,
+                tags: i.tags,
+            })
+            .collect();
+        return print_instances(rows, &tags);
+    }
+
+    let config = ConfigStore::new()?.load()?;
+    let process_manager = ProcessManager::new();
+    let rows = config
+        .instances
+        .iter()
+        .filter(|i| i.has_tags(&tags))
+        .map(|i| Row {
+            name: i.name.clone(),
+            service: i.service_type.display_name().to_string(),
+            port: i.port,
+            running: process_manager.get_status(i).running,
+            tags: i.tags.clone(),
+        })
+        .collect();
+    print_instances(rows, &tags)
+}
+
+/// One line of the instance table
+struct Row {
+    name: String,
+    service: String,
+    port: u16,
+    running: bool,
+    tags: Vec<String>,
+}
+
+fn print_instances(rows: Vec<Row>, tags: &[String]) -> Result<(), String> {
+    if rows.is_empty() {
+        if tags.is_empty() {
+            println!("No instances.");
+        } else {
+            println!("No instances tagged {}.", tags.join(", "));
+        }
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  {:<24} {:<12} {:>6}  {:<8} TAGS",
+        "NAME", "SERVICE", "PORT", "STATUS"
+    );
+    for row in rows {
+        println!(
+            "  {:<24} {:<12} {:>6}  {:<8} {}",
+            row.name,
+            row.service,
+            row.port,
+            if row.running { "running" } else { "stopped" },
+            row.tags.join(", ")
+        );
+    }
+    println!();
+
+    Ok(())
+}
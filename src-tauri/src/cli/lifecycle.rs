@@ -6,23 +6,132 @@
 //!
 //! When NAME is omitted, resolves the instance tied to the current directory
 //! (document_root match — same strategy used by `burd secure`, `burd open`, etc.).
+//!
+//! `--tag TAG` (repeatable) acts on every instance carrying all given tags
+//! instead, via POST /instances/bulk.
+//!
+//! `burd start --wait` returns only once the service passes its health check.
+//!
+//! With `--host`, the instances are another machine's and NAME is required.
 
-use crate::api_client::BurdApiClient;
-use crate::config::{ConfigStore, DomainTarget, Instance};
+use crate::api_client::{self, BurdApiClient};
+use crate::cli::remote;
+use crate::config::{DomainTarget, Instance};
+use crate::process::ProcessManager;
+use crate::readiness;
 use std::env;
 use std::path::Path;
 use uuid::Uuid;
 
-pub fn run_start(name: Option<String>) -> Result<(), String> {
-    dispatch(name, Action::Start)
+/// Seconds the API waits for readiness; below the API client's 30s timeout
+const READY_TIMEOUT_SECS: u64 = 25;
+
+pub fn run_start(name: Option<String>, wait: bool) -> Result<(), String> {
+    dispatch(name, Action::Start, wait)
 }
 
 pub fn run_stop(name: Option<String>) -> Result<(), String> {
-    dispatch(name, Action::Stop)
+    dispatch(name, Action::Stop, false)
 }
 
 pub fn run_restart(name: Option<String>) -> Result<(), String> {
-    dispatch(name, Action::Restart)
+    dispatch(name, Action::Restart, false)
+}
+
+/// API path that starts an instance, optionally waiting until it's healthy
+fn start_path(id: Uuid, wait: bool) -> String {
+    if wait {
+        format!(
+            "/instances/{}/start?wait=true&timeout={}",
+            id, READY_TIMEOUT_SECS
+        )
+    } else {
+        format!("/instances/{}/start", id)
+    }
+}
+
+/// Make sure `instance` accepts connections before it's used directly
+///
+/// A stopped instance is started through the app and awaited; a running one
+/// (possibly started moments ago) is awaited on its port.
+pub(crate) fn ensure_ready(instance: &Instance) -> Result<(), String> {
+    if ProcessManager::new().is_running(&instance.id) {
+        return readiness::wait_until_listening(
+            instance.port,
+            instance.service_type,
+            readiness::DEFAULT_READY_TIMEOUT,
+        );
+    }
+
+    let client = BurdApiClient::new();
+    if !client.is_available() {
+        return Err(format!(
+            "'{}' is stopped and the Burd app isn't running. Open Burd, then try again.",
+            instance.name
+        ));
+    }
+    eprintln!("Starting '{}'...", instance.name);
+    client
+        .post(&start_path(instance.id, true), &serde_json::json!({}))
+        .map(|_| ())
+}
+
+/// Start, stop, or restart every instance carrying all of `tags`
+pub fn run_tagged(action: &str, tags: Vec<String>) -> Result<(), String> {
+    let action = match action {
+        "start" => Action::Start,
+        "stop" => Action::Stop,
+        "restart" => Action::Restart,
+        other => return Err(format!("Unknown action '{}'", other)),
+    };
+
+    let client = BurdApiClient::new();
+    if !client.is_available() {
+        return Err(
+            "Burd app isn't running. Open Burd or run `burd setup`, then try again.".to_string(),
+        );
+    }
+
+    let body = serde_json::json!({ "action": action.verb(), "tags": tags });
+    let response = client.post("/instances/bulk", &body)?;
+    let results: Vec<serde_json::Value> = serde_json::from_str(&response)
+        .map_err(|e| format!("Failed to parse bulk response: {}", e))?;
+
+    if results.is_empty() {
+        println!("No instances tagged {}.", tags.join(", "));
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for result in &results {
+        let name = result.get("name").and_then(|v| v.as_str()).unwrap_or("?");
+        let skipped = result.get("skipped").and_then(|v| v.as_bool()) == Some(true);
+        match result.get("error").and_then(|v| v.as_str()) {
+            Some(error) => {
+                failed += 1;
+                println!("✗ '{}': {}", name, error);
+            }
+            None if skipped => println!(
+                "• '{}' is already {}",
+                name,
+                match action {
+                    Action::Start | Action::Restart => "running",
+                    Action::Stop => "stopped",
+                }
+            ),
+            None => println!("✓ {} '{}'", capitalize(action.past()), name),
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} instance(s) failed to {}",
+            failed,
+            results.len(),
+            action.verb()
+        ));
+    }
+    Ok(())
 }
 
 #[derive(Copy, Clone)]
@@ -49,9 +158,8 @@ impl Action {
     }
 }
 
-fn dispatch(name: Option<String>, action: Action) -> Result<(), String> {
-    let config_store = ConfigStore::new()?;
-    let config = config_store.load()?;
+fn dispatch(name: Option<String>, action: Action, wait: bool) -> Result<(), String> {
+    let config = remote::load_config()?;
 
     let instance = resolve_instance(&config, name.as_deref())?;
 
@@ -63,10 +171,29 @@ fn dispatch(name: Option<String>, action: Action) -> Result<(), String> {
         );
     }
 
-    let path = format!("/instances/{}/{}", instance.id, action.verb());
+    let path = match action {
+        Action::Start => start_path(instance.id, wait),
+        _ => format!("/instances/{}/{}", instance.id, action.verb()),
+    };
     match client.post(&path, &serde_json::json!({})) {
         Ok(_) => {
-            println!("✓ {} '{}'", capitalize(action.past()), instance.name);
+            let ready = if wait { " and ready" } else { "" };
+            println!(
+                "✓ {} '{}'{}",
+                capitalize(action.past()),
+                instance.name,
+                ready
+            );
+            Ok(())
+        }
+        Err(msg) if wait && is_idempotent_noop(action, &msg) => {
+            // Another machine's ports may not be reachable from here
+            if api_client::remote_host().is_some() {
+                println!("• '{}' is already running.", instance.name);
+                return Ok(());
+            }
+            ensure_ready(&instance)?;
+            println!("• '{}' is already running and ready.", instance.name);
             Ok(())
         }
         // Repeat start/stop against an already-{running,stopped} instance is
@@ -123,6 +250,13 @@ pub(crate) fn resolve_instance(
         return Err(format!("No instance matches '{}'.", raw));
     }
 
+    if let Some(host) = api_client::remote_host() {
+        return Err(format!(
+            "Pass an instance name; directories here don't map to instances on {}.",
+            host.name
+        ));
+    }
+
     // No name — resolve from the current directory.
     let cwd = env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
     let cwd_str = cwd.to_string_lossy().to_string();
@@ -10,6 +10,9 @@ use crate::api_client::BurdApiClient;
 use crate::caddy;
 use crate::config::{ConfigStore, Domain, Instance, ServiceType};
 use crate::db_manager::{create_manager_for_instance, find_all_db_instances, sanitize_db_name};
+use crate::domain::DomainManager;
+use crate::port_allocator;
+use crate::services::mailpit::MailpitService;
 use chrono::Utc;
 use std::collections::HashMap;
 use std::env;
@@ -70,7 +73,7 @@ pub fn run_link_with(name: Option<String>, opts: LinkOptions) -> Result<(), Stri
             let stripped = n.strip_suffix(&tld_suffix).unwrap_or(&n);
             slug::slugify(stripped)
         }
-        None => slug::slugify(&project_name),
+        None => DomainManager::slugify_with(&project_name, &config.slugs),
     };
 
     if is_js {
@@ -79,6 +82,7 @@ pub fn run_link_with(name: Option<String>, opts: LinkOptions) -> Result<(), Stri
         println!("Linking directory: {}", document_root);
     }
 
+    let config_lock = config_store.lock_file()?;
     let mut config = config_store.load()?;
     let project_root = current_dir.to_string_lossy().to_string();
 
@@ -133,28 +137,11 @@ pub fn run_link_with(name: Option<String>, opts: LinkOptions) -> Result<(), Stri
         ServiceType::FrankenPHP
     };
 
-    // Find an available port (start from default port for the service)
-    let mut port = service_type.default_port();
-    while config.instances.iter().any(|i| i.port == port) {
-        if port == u16::MAX {
-            return Err("No available ports found".to_string());
-        }
-        port += 1;
-    }
+    // Find an available port (prefers the default port for the service)
+    let port = port_allocator::suggest_port(&config, service_type)?;
 
-    // Get installed version for the appropriate service
-    let version = config
-        .binaries
-        .get(&service_type)
-        .and_then(|versions| versions.keys().next())
-        .ok_or_else(|| {
-            if is_js {
-                "No Bun versions installed.\nPlease download Bun in the Burd app first.".to_string()
-            } else {
-                "No FrankenPHP versions installed.\nPlease download FrankenPHP in the Burd app first.".to_string()
-            }
-        })?
-        .clone();
+    // Use the default (pinned or newest installed) version for the service
+    let version = config.default_version(service_type)?;
 
     // Build instance config based on project type
     let instance_config = if is_js {
@@ -178,10 +165,15 @@ pub fn run_link_with(name: Option<String>, opts: LinkOptions) -> Result<(), Stri
         config: instance_config,
         master_key: None,
         auto_start: false,
+        restart_on_failure: false,
+        wake_on_access: false,
         created_at: Utc::now(),
         domain: Some(subdomain.clone()),
         domain_enabled: true,
         stack_id: None,
+        tags: Vec::new(),
+        extra_args: Vec::new(),
+        seed: None,
     };
 
     // Create instance data directory
@@ -199,6 +191,7 @@ pub fn run_link_with(name: Option<String>, opts: LinkOptions) -> Result<(), Stri
 
     // Save config
     config_store.save(&config)?;
+    drop(config_lock);
 
     // Without this, `burd link` leaves Caddy with no route for the new
     // subdomain — the daemon only writes Caddy files when domains are
@@ -396,7 +389,13 @@ fn offer_env_fixes(
         None => return Ok(()),
     };
 
-    let issues = collect_env_issues(&project.project_type, &env_vars, config, subdomain);
+    let issues = collect_env_issues(
+        project_dir,
+        &project.project_type,
+        &env_vars,
+        config,
+        subdomain,
+    );
 
     if issues.is_empty() {
         return Ok(());
@@ -429,6 +428,7 @@ fn offer_env_fixes(
 
 /// Collect .env issues that need fixing
 fn collect_env_issues(
+    project_dir: &Path,
     project_type: &ProjectType,
     env_vars: &HashMap<String, String>,
     config: &crate::config::Config,
@@ -539,17 +539,10 @@ fn collect_env_issues(
     // Check Mail configuration (Laravel)
     if matches!(project_type, ProjectType::Laravel { .. }) {
         if let Some(mail_config) = extract_mail_config(project_type, env_vars) {
-            let mailpit_instance = config
-                .instances
-                .iter()
-                .find(|i| i.service_type == ServiceType::Mailpit);
+            let mailpit_instance = MailpitService::for_project(config, project_dir);
 
             if let Some(instance) = mailpit_instance {
-                let smtp_port = instance
-                    .config
-                    .get("smtp_port")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(1025) as u16;
+                let smtp_port = MailpitService::smtp_port(instance);
 
                 if mail_config.mailer == "smtp" && mail_config.port != smtp_port {
                     issues.push((
@@ -588,6 +581,7 @@ pub fn run_unlink() -> Result<(), String> {
     let document_root = current_dir.to_string_lossy().to_string();
 
     let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
     let mut config = config_store.load()?;
 
     // Find instance with matching document_root, working_directory, OR where current dir is parent
@@ -637,7 +631,7 @@ pub fn run_unlink() -> Result<(), String> {
 
     if let Some(idx) = domain_idx {
         let domain = config.domains.remove(idx);
-        let full_domain = format!("{}.{}", domain.subdomain, config.tld);
+        let full_domain = domain.full_domain(&config.tld);
 
         // Delete Caddy domain file
         if let Err(e) = caddy::delete_domain_file(&full_domain) {
@@ -715,7 +709,7 @@ pub fn run_links() -> Result<(), String> {
             .find(|d| d.routes_to_instance(&instance.id));
 
         let domain_str = domain
-            .map(|d| format!("{}.{}", d.subdomain, config.tld))
+            .map(|d| d.full_domain(&config.tld))
             .unwrap_or_else(|| "no domain".to_string());
 
         let ssl_status = domain
@@ -6,7 +6,7 @@
 
 use crate::api_client::BurdApiClient;
 use crate::cli::lifecycle::resolve_instance;
-use crate::config::ConfigStore;
+use crate::cli::remote;
 use std::thread;
 use std::time::Duration;
 
@@ -25,8 +25,7 @@ impl Default for LogsOptions {
 }
 
 pub fn run_logs(name: Option<String>, opts: LogsOptions) -> Result<(), String> {
-    let config_store = ConfigStore::new()?;
-    let config = config_store.load()?;
+    let config = remote::load_config()?;
 
     let instance = resolve_instance(&config, name.as_deref())?;
 
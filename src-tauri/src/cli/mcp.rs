@@ -7,7 +7,8 @@ use crate::mcp::server;
 /// Run the MCP server
 ///
 /// This starts an MCP server that communicates via stdio (JSON-RPC).
-/// It requires the Burd desktop application to be running.
+/// It requires the Burd desktop application to be running. Clients that
+/// speak HTTP can connect to http://127.0.0.1:19840/mcp/sse instead.
 pub fn run_mcp() -> Result<(), String> {
     server::run_server()
 }
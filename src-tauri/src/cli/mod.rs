@@ -3,10 +3,21 @@
 //! Provides commands for managing Burd instances from the terminal.
 
 pub mod analyze;
+pub mod api;
+pub mod backup;
+pub mod ca;
+pub mod client_cert;
+pub mod composer;
+pub mod config_export;
+pub mod daemon;
 pub mod db;
+pub mod dns;
 pub mod doctor;
 pub mod env;
+pub mod history;
 pub mod init;
+pub mod install;
+pub mod instances;
 pub mod lifecycle;
 pub mod link;
 pub mod logs;
@@ -17,31 +28,81 @@ pub mod open;
 pub mod park;
 pub mod postgres;
 pub mod proxy;
+pub mod queue;
+pub mod remote;
+pub mod rollback;
+pub mod s3;
 pub mod secure;
 pub mod services;
 pub mod setup;
 pub mod share;
+pub mod snapshot;
+pub mod stack;
+pub mod stats;
+pub mod tui;
 pub mod update_instance;
 pub mod upgrade;
+pub mod verify;
+pub mod worker;
+pub mod wp;
+pub mod xdebug;
 
 pub use analyze::run_analyze;
-pub use db::{run_db_create, run_db_drop, run_db_export, run_db_import, run_db_list, run_db_shell};
+pub use api::{run_api_local, run_api_remote, run_api_rotate_token, run_api_status};
+pub use backup::{run_backup_list, run_backup_restore, run_backup_run, run_backup_status};
+pub use ca::{run_ca_bundle, run_ca_export, run_ca_status, run_ca_trust};
+pub use client_cert::{
+    run_client_cert_disable, run_client_cert_reissue, run_client_cert_require, run_client_cert_show,
+};
+pub use composer::{run_composer, run_composer_use, run_composer_versions};
+pub use config_export::{run_config_export, run_config_import};
+pub use daemon::{run_daemon, run_daemon_install, run_daemon_status, run_daemon_uninstall};
+pub use db::{
+    run_db_create, run_db_drop, run_db_export, run_db_import, run_db_list, run_db_seed,
+    run_db_shell, run_db_user_create, run_db_user_drop, run_db_user_grant, run_db_user_list,
+    run_db_user_password, run_db_user_revoke,
+};
+pub use dns::run_dns_test;
 pub use doctor::run_doctor;
-pub use env::{run_env_check, run_env_fix, run_env_show};
+pub use env::{
+    run_env_check, run_env_fix, run_env_mail, run_env_secrets, run_env_set, run_env_show,
+    run_env_unset,
+};
+pub use history::run_history;
 pub use init::{run_init, run_init_with, InitOptions};
-pub use lifecycle::{run_restart, run_start, run_stop};
+pub use install::run_install;
+pub use instances::run_instances_list;
+pub use lifecycle::{run_restart, run_start, run_stop, run_tagged};
 pub use link::{run_link, run_link_with, run_links, run_unlink, LinkOptions};
 pub use logs::{run_logs, LogsOptions};
-pub use services::{run_service_versions, run_services_list};
+pub use services::{
+    run_add_service_definition, run_pin_version, run_service_versions, run_services_list,
+    run_set_arch, run_set_channel,
+};
 pub use update_instance::{run_update, UpdateOptions};
 pub use mcp::run_mcp;
 pub use mysql::{list_mysql_tools, run_mysql};
-pub use new::run_new;
+pub use new::{run_new, run_new_with, NewOptions};
 pub use open::run_open;
 pub use park::{run_forget, run_park, run_parked, run_refresh, run_status};
 pub use postgres::{list_postgres_tools, run_postgres};
 pub use proxy::{run_proxies, run_proxy, run_unproxy};
+pub use queue::{run_queue_bury, run_queue_inspect, run_queue_kick};
+pub use remote::{run_remote_add, run_remote_list, run_remote_remove};
+pub use rollback::{run_rollback_env, snapshot_on_startup};
+pub use s3::{run_s3_create, run_s3_delete, run_s3_key, run_s3_list, run_s3_policy};
 pub use secure::{run_secure, run_unsecure};
 pub use setup::run_setup;
 pub use share::run_share;
+pub use snapshot::{
+    run_snapshot_create, run_snapshot_delete, run_snapshot_list, run_snapshot_restore,
+    run_snapshot_safety,
+};
+pub use stack::{run_stack_down, run_stack_restart, run_stack_up};
+pub use stats::run_stats;
+pub use tui::run_tui;
 pub use upgrade::run_upgrade;
+pub use verify::run_verify;
+pub use worker::{run_worker_start, run_worker_status, run_worker_stop};
+pub use wp::{run_wp, run_wp_install};
+pub use xdebug::run_xdebug;
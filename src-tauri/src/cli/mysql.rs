@@ -25,23 +25,8 @@ pub fn run_mysql(tool: &str, args: Vec<String>) -> Result<(), String> {
                 .to_string()
         })?;
 
-    // Get the version - use instance version or find installed version
-    let version = if instance.version.is_empty() {
-        // Find any installed version for this service type
-        config
-            .binaries
-            .get(&instance.service_type)
-            .and_then(|versions| versions.keys().next())
-            .cloned()
-            .ok_or_else(|| {
-                format!(
-                    "No {} binary installed. Download one in the Burd app first.",
-                    instance.service_type.display_name()
-                )
-            })?
-    } else {
-        instance.version.clone()
-    };
+    // Get the version - use instance version or the default installed version
+    let version = config.resolve_version(instance.service_type, &instance.version)?;
 
     // Get the versioned binary directory
     let bin_dir = get_versioned_binary_dir(instance.service_type, &version)?;
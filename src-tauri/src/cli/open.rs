@@ -40,14 +40,15 @@ pub fn run_open(name: Option<String>) -> Result<(), String> {
         })?;
 
     // Build URL based on SSL status and proxy installation
+    let full_domain = domain.full_domain(&config.tld);
     let url = if config.proxy_installed {
         if domain.ssl_enabled {
-            format!("https://{}.{}", subdomain, config.tld)
+            format!("https://{}", full_domain)
         } else {
-            format!("http://{}.{}", subdomain, config.tld)
+            format!("http://{}", full_domain)
         }
     } else {
-        format!("http://{}.{}:{}", subdomain, config.tld, config.proxy_port)
+        format!("http://{}:{}", full_domain, config.proxy_port)
     };
 
     // Open in default browser using macOS `open` command
@@ -5,15 +5,18 @@
 use crate::caddy;
 use crate::cli::init::is_initialized;
 use crate::config::ConfigStore;
-use crate::park::{self, generate_subdomain};
+use crate::park::{self, generate_subdomain, GitInfo};
 use std::env;
 use std::path::Path;
 
 /// Park the current directory
 ///
 /// Adds the current directory to the list of parked directories.
-/// All subdirectories will automatically become domains.
-pub fn run_park() -> Result<(), String> {
+/// All subdirectories will automatically become domains; with a `depth`
+/// above 1, folders that aren't projects are searched for nested ones.
+pub fn run_park(depth: u8) -> Result<(), String> {
+    let depth = park::validate_depth(depth)?;
+
     let current_dir =
         env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
 
@@ -39,10 +42,10 @@ pub fn run_park() -> Result<(), String> {
     }
 
     // Create the parked directory
-    let _parked_dir = config_store.create_parked_directory(path.clone(), true)?;
+    let _parked_dir = config_store.create_parked_directory(path.clone(), true, depth)?;
 
     // Scan for projects
-    let projects = park::scan_directory(Path::new(&path)).unwrap_or_default();
+    let projects = park::scan_directory(Path::new(&path), depth).unwrap_or_default();
 
     let config = config_store.load()?;
 
@@ -55,7 +58,7 @@ pub fn run_park() -> Result<(), String> {
     } else {
         println!("Found {} project(s):", projects.len());
         for project in &projects {
-            let subdomain = generate_subdomain(&project.name);
+            let subdomain = generate_subdomain(&project.name, &config.slugs);
             let domain = format!("{}.{}", subdomain, config.tld);
             let type_label = match project.project_type.as_str() {
                 "php-laravel" => "Laravel",
@@ -97,7 +100,7 @@ pub fn run_forget() -> Result<(), String> {
 
     // Delete the .caddy files for each domain
     for domain in &domains {
-        let full_domain = format!("{}.{}", domain.subdomain, config.tld);
+        let full_domain = domain.full_domain(&config.tld);
         if let Err(e) = caddy::delete_domain_file(&full_domain) {
             eprintln!(
                 "Warning: Failed to delete domain file for {}: {}",
@@ -125,6 +128,27 @@ pub fn run_forget() -> Result<(), String> {
     Ok(())
 }
 
+/// One-line git summary, e.g. "main* ↑2 · 3d ago"
+fn describe_git(git: &GitInfo) -> String {
+    let mut summary = git.branch.clone().unwrap_or_else(|| "detached".to_string());
+    if git.dirty {
+        summary.push('*');
+    }
+    if git.ahead > 0 {
+        summary.push_str(&format!(" ↑{}", git.ahead));
+    }
+    if git.behind > 0 {
+        summary.push_str(&format!(" ↓{}", git.behind));
+    }
+    if let Some(at) = git.last_commit_at {
+        summary.push_str(&format!(
+            " · {} ago",
+            park::format_commit_age(at, chrono::Utc::now())
+        ));
+    }
+    summary
+}
+
 /// List all parked directories
 pub fn run_parked() -> Result<(), String> {
     let config_store = ConfigStore::new()?;
@@ -151,19 +175,31 @@ pub fn run_parked() -> Result<(), String> {
     println!();
 
     for dir in parked_dirs {
-        let projects = park::scan_directory(Path::new(&dir.path)).unwrap_or_default();
+        let projects = park::scan_directory(Path::new(&dir.path), dir.depth).unwrap_or_default();
         let ssl_status = if dir.ssl_enabled { "SSL" } else { "HTTP" };
 
-        println!(
-            "  {} ({} projects, {})",
-            dir.path,
-            projects.len(),
-            ssl_status
-        );
+        if dir.depth > 1 {
+            println!(
+                "  {} ({} projects, {}, depth {})",
+                dir.path,
+                projects.len(),
+                ssl_status,
+                dir.depth
+            );
+        } else {
+            println!(
+                "  {} ({} projects, {})",
+                dir.path,
+                projects.len(),
+                ssl_status
+            );
+        }
 
         // Show projects
-        for project in projects {
-            let subdomain = generate_subdomain(&project.name);
+        let paths: Vec<&Path> = projects.iter().map(|p| p.path.as_path()).collect();
+        let git_infos = park::git_infos(&paths);
+        for (project, git) in projects.iter().zip(git_infos) {
+            let subdomain = generate_subdomain(&project.name, &config.slugs);
             let domain = format!("{}.{}", subdomain, config.tld);
             let type_label = match project.project_type.as_str() {
                 "php-laravel" => "Laravel",
@@ -171,7 +207,13 @@ pub fn run_parked() -> Result<(), String> {
                 "static" => "Static",
                 _ => "Unknown",
             };
-            println!("    {} -> {} ({})", project.name, domain, type_label);
+            match git.map(|g| describe_git(&g)) {
+                Some(git) => println!(
+                    "    {} -> {} ({})  {}",
+                    project.name, domain, type_label, git
+                ),
+                None => println!("    {} -> {} ({})", project.name, domain, type_label),
+            }
         }
         println!();
     }
@@ -206,7 +248,7 @@ pub fn run_refresh() -> Result<(), String> {
 
     for dir in parked_dirs {
         // For CLI, we just report what would be synced
-        let projects = park::scan_directory(Path::new(&dir.path)).unwrap_or_default();
+        let projects = park::scan_directory(Path::new(&dir.path), dir.depth).unwrap_or_default();
 
         // Check for new projects
         let existing_domains: std::collections::HashSet<String> = config
@@ -218,7 +260,7 @@ pub fn run_refresh() -> Result<(), String> {
 
         let discovered: std::collections::HashSet<String> = projects
             .iter()
-            .map(|p| generate_subdomain(&p.name))
+            .map(|p| generate_subdomain(&p.name, &config.slugs))
             .collect();
 
         let new_count = discovered.difference(&existing_domains).count();
@@ -290,7 +332,7 @@ pub fn run_status() -> Result<(), String> {
 
     // Check if this directory is parked
     if let Some(parked_dir) = config_store.find_parked_directory_by_path(&path)? {
-        let projects = park::scan_directory(Path::new(&path)).unwrap_or_default();
+        let projects = park::scan_directory(Path::new(&path), parked_dir.depth).unwrap_or_default();
         let ssl_status = if parked_dir.ssl_enabled {
             "SSL enabled"
         } else {
@@ -305,7 +347,7 @@ pub fn run_status() -> Result<(), String> {
         if !projects.is_empty() {
             println!("Projects:");
             for project in projects {
-                let subdomain = generate_subdomain(&project.name);
+                let subdomain = generate_subdomain(&project.name, &config.slugs);
                 let domain = format!("{}.{}", subdomain, config.tld);
                 let type_label = match project.project_type.as_str() {
                     "php-laravel" => "Laravel",
@@ -323,17 +365,23 @@ pub fn run_status() -> Result<(), String> {
     // Check if current directory is inside a parked directory
     for parked_dir in config_store.list_parked_directories()? {
         if path.starts_with(&parked_dir.path) && path != parked_dir.path {
-            // We're inside a parked directory - this might be a project
-            let project_name = current_dir
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown");
+            // We're inside a parked directory - find the project it belongs to
+            let (project_name, project_type) =
+                match park::find_project_containing(&parked_dir, &current_dir) {
+                    Some(project) => (project.name, project.project_type),
+                    None => (
+                        current_dir
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        park::detect_project_type(&current_dir),
+                    ),
+                };
 
-            let subdomain = generate_subdomain(project_name);
+            let subdomain = generate_subdomain(&project_name, &config.slugs);
             let domain = format!("{}.{}", subdomain, config.tld);
 
-            // Detect project type
-            let project_type = park::detect_project_type(&current_dir);
             let type_label = match project_type.as_str() {
                 "php-laravel" => "Laravel",
                 "php" => "PHP",
@@ -25,20 +25,8 @@ pub fn run_postgres(tool: &str, args: Vec<String>) -> Result<(), String> {
                 .to_string()
         })?;
 
-    // Get the version - use instance version or find installed version
-    let version = if instance.version.is_empty() {
-        // Find any installed version for PostgreSQL
-        config
-            .binaries
-            .get(&ServiceType::PostgreSQL)
-            .and_then(|versions| versions.keys().next())
-            .cloned()
-            .ok_or_else(|| {
-                "No PostgreSQL binary installed. Download one in the Burd app first.".to_string()
-            })?
-    } else {
-        instance.version.clone()
-    };
+    // Get the version - use instance version or the default installed version
+    let version = config.resolve_version(ServiceType::PostgreSQL, &instance.version)?;
 
     // Get the versioned binary directory
     let bin_dir = get_versioned_binary_dir(ServiceType::PostgreSQL, &version)?;
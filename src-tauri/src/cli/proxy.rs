@@ -136,7 +136,7 @@ pub fn run_proxies() -> Result<(), String> {
     println!();
 
     for (domain, port) in proxy_domains {
-        let full_domain = format!("{}.{}", domain.subdomain, config.tld);
+        let full_domain = domain.full_domain(&config.tld);
         let ssl_status = if domain.ssl_enabled { "SSL" } else { "HTTP" };
 
         println!("  {} -> localhost:{} ({})", full_domain, port, ssl_status);
@@ -155,7 +155,7 @@ fn regenerate_caddyfile(config_store: &ConfigStore) -> Result<(), String> {
         .domains
         .iter()
         .filter_map(|domain| {
-            let full_domain = format!("{}.{}", domain.subdomain, config.tld);
+            let full_domain = domain.full_domain(&config.tld);
             match &domain.target {
                 DomainTarget::Instance(inst_id) => {
                     let instance = config.instances.iter().find(|i| i.id == *inst_id)?;
@@ -0,0 +1,152 @@
+//! Queue CLI commands
+//!
+//! Commands for inspecting Beanstalkd queues from the command line.
+
+use crate::config::{ConfigStore, Instance};
+use crate::queue_manager::{
+    find_beanstalkd_instances, BeanstalkdManager, JobInfo, PeekState, DEFAULT_KICK_BOUND,
+};
+
+/// Maximum number of characters of a job body shown by `burd queue inspect`
+const BODY_PREVIEW_LEN: usize = 200;
+
+/// Find the Beanstalkd instance to use (by name, or the first one configured)
+fn resolve_instance(instance_name: Option<&str>) -> Result<Instance, String> {
+    let config = ConfigStore::new()?.load()?;
+    let instances = find_beanstalkd_instances(&config);
+
+    if instances.is_empty() {
+        return Err("No Beanstalkd instance configured in Burd.\n\
+             Create a Beanstalkd instance in the Burd app first."
+            .to_string());
+    }
+
+    match instance_name {
+        Some(name) => instances
+            .into_iter()
+            .find(|i| i.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| format!("No Beanstalkd instance named '{}'", name)),
+        None => Ok(instances[0].clone()),
+    }
+}
+
+/// Shorten a job body for display
+fn preview(body: &str) -> String {
+    let body = body.replace(['\r', '\n'], " ");
+    if body.chars().count() > BODY_PREVIEW_LEN {
+        let truncated: String = body.chars().take(BODY_PREVIEW_LEN).collect();
+        format!("{}...", truncated)
+    } else {
+        body
+    }
+}
+
+fn print_job(label: &str, job: &JobInfo) {
+    println!("  {} job #{}", label, job.id);
+    println!(
+        "    priority {}, age {}s, ttr {}s, reserves {}, timeouts {}, buries {}, kicks {}",
+        job.priority, job.age, job.ttr, job.reserves, job.timeouts, job.buries, job.kicks
+    );
+    if job.delay > 0 {
+        println!("    delay {}s, {}s left", job.delay, job.time_left);
+    }
+    println!("    {}", preview(&job.body));
+}
+
+/// Show tubes with job counts, or the next ready/delayed/buried job of one tube
+pub fn run_queue_inspect(tube: Option<&str>, instance_name: Option<&str>) -> Result<(), String> {
+    let instance = resolve_instance(instance_name)?;
+    let manager = BeanstalkdManager::for_instance(&instance)?;
+
+    println!();
+    println!("{} ({})", instance.name, manager.connection_info());
+    println!("{}", "-".repeat(40));
+
+    let tubes = manager.list_tubes()?;
+    let tubes: Vec<_> = tubes
+        .into_iter()
+        .filter(|t| tube.is_none_or(|name| t.name == name))
+        .collect();
+
+    if tubes.is_empty() {
+        match tube {
+            Some(name) => println!(
+                "  Tube '{}' does not exist (it has no jobs or watchers).",
+                name
+            ),
+            None => println!("  No tubes."),
+        }
+        println!();
+        return Ok(());
+    }
+
+    println!(
+        "  {:<24} {:>7} {:>7} {:>8} {:>7} {:>7} {:>8}",
+        "TUBE", "READY", "URGENT", "RESERVED", "DELAYED", "BURIED", "WAITING"
+    );
+    for t in &tubes {
+        println!(
+            "  {:<24} {:>7} {:>7} {:>8} {:>7} {:>7} {:>8}{}",
+            t.name,
+            t.ready,
+            t.urgent,
+            t.reserved,
+            t.delayed,
+            t.buried,
+            t.waiting,
+            if t.paused { "  (paused)" } else { "" }
+        );
+    }
+
+    if let Some(name) = tube {
+        println!();
+        for state in PeekState::ALL {
+            match manager.peek(name, state)? {
+                Some(job) => print_job(&format!("Next {}", state.as_str()), &job),
+                None => println!("  No {} jobs.", state.as_str()),
+            }
+        }
+    } else if tubes.iter().any(|t| t.buried > 0) {
+        println!();
+        println!("  Buried jobs found. Inspect with: burd queue inspect <tube>");
+    }
+
+    println!();
+    Ok(())
+}
+
+/// Kick a single job, or up to `bound` buried/delayed jobs of a tube
+pub fn run_queue_kick(
+    tube: Option<&str>,
+    job: Option<u64>,
+    bound: Option<u32>,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let instance = resolve_instance(instance_name)?;
+    let manager = BeanstalkdManager::for_instance(&instance)?;
+
+    match (job, tube) {
+        (Some(id), _) => {
+            manager.kick_job(id)?;
+            println!("Kicked job #{}.", id);
+        }
+        (None, Some(tube)) => {
+            let kicked = manager.kick_tube(tube, bound.unwrap_or(DEFAULT_KICK_BOUND))?;
+            println!("Kicked {} job(s) in tube '{}'.", kicked, tube);
+        }
+        (None, None) => return Err("Specify a tube or --job <id> to kick.".to_string()),
+    }
+
+    Ok(())
+}
+
+/// Bury a ready or delayed job
+pub fn run_queue_bury(job: u64, instance_name: Option<&str>) -> Result<(), String> {
+    let instance = resolve_instance(instance_name)?;
+    let manager = BeanstalkdManager::for_instance(&instance)?;
+
+    manager.bury_job(job)?;
+    println!("Buried job #{}.", job);
+    Ok(())
+}
@@ -0,0 +1,236 @@
+//! Remote host CLI commands
+//!
+//! Saves other machines' Burd APIs (e.g. an office Mac mini serving the
+//! team's databases) under a name for `--host`, and lets instance commands
+//! work against them. On the remote machine, `burd api remote` turns on
+//! remote access and shows the token.
+//!
+//! With `--host`, the commands that go through the API (instances, start,
+//! stop, restart, logs, update, xdebug, install, stack, mcp) talk to that
+//! machine instead.
+//! They see its instances, domains and stacks as the API lists them.
+
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api_client::{self, BurdApiClient};
+use crate::config::{Config, ConfigStore, Domain, Instance, RemoteHost, ServiceType, Stack};
+
+/// An instance as `GET /instances` lists it
+#[derive(Debug, Deserialize)]
+pub(crate) struct RemoteInstance {
+    pub id: Uuid,
+    pub name: String,
+    pub port: u16,
+    /// Display name
+    pub service_type: String,
+    pub service_id: String,
+    pub version: String,
+    pub running: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub domain_enabled: bool,
+    #[serde(default)]
+    pub auto_start: bool,
+    #[serde(default)]
+    pub restart_on_failure: bool,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    #[serde(default)]
+    pub stack_id: Option<Uuid>,
+}
+
+/// A stack as `GET /stacks` lists it
+#[derive(Debug, Deserialize)]
+struct RemoteStack {
+    id: Uuid,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// A domain as `GET /domains` lists it
+#[derive(Debug, Deserialize)]
+struct RemoteDomain {
+    id: Uuid,
+    subdomain: String,
+    target_type: String,
+    target_value: String,
+    ssl_enabled: bool,
+}
+
+/// Connect the CLI to `host` (a saved name or an API address)
+pub fn connect(host: &str, token: Option<String>) -> Result<(), String> {
+    let remote = api_client::connect_remote(host, token)?;
+    if !BurdApiClient::new().is_available() {
+        return Err(format!(
+            "Can't reach Burd at {}. Check that it's running there with remote access on (`burd api remote`) and that the token is right.",
+            remote.url
+        ));
+    }
+    Ok(())
+}
+
+/// List the instances of the connected remote host carrying all of `tags`
+pub(crate) fn list_instances(tags: &[String]) -> Result<Vec<RemoteInstance>, String> {
+    let path = if tags.is_empty() {
+        "/instances".to_string()
+    } else {
+        format!("/instances?tag={}", urlencoding::encode(&tags.join(",")))
+    };
+    let body = BurdApiClient::new().get(&path)?;
+    serde_json::from_str(&body).map_err(|e| format!("Unexpected instance list: {}", e))
+}
+
+/// The config of the Burd the CLI controls
+///
+/// This machine's, or, with `--host`, the instances, domains and stacks the
+/// remote host's API lists. Remote instances carry no settings or paths.
+pub(crate) fn load_config() -> Result<Config, String> {
+    if api_client::remote_host().is_none() {
+        return ConfigStore::new()?.load();
+    }
+
+    let client = BurdApiClient::new();
+    let status: serde_json::Value = serde_json::from_str(&client.get("/status")?)
+        .map_err(|e| format!("Unexpected status: {}", e))?;
+    let domains: Vec<RemoteDomain> = serde_json::from_str(&client.get("/domains")?)
+        .map_err(|e| format!("Unexpected domain list: {}", e))?;
+    let stacks: Vec<RemoteStack> = serde_json::from_str(&client.get("/stacks")?)
+        .map_err(|e| format!("Unexpected stack list: {}", e))?;
+
+    let mut config = Config::default();
+    if let Some(tld) = status.get("tld").and_then(|v| v.as_str()) {
+        config.tld = tld.to_string();
+    }
+    config.instances = list_instances(&[])?
+        .into_iter()
+        .map(|remote| Instance {
+            id: remote.id,
+            name: remote.name,
+            port: remote.port,
+            service_type: ServiceType::from_id(&remote.service_id),
+            version: remote.version,
+            config: serde_json::Value::Null,
+            master_key: None,
+            auto_start: remote.auto_start,
+            restart_on_failure: remote.restart_on_failure,
+            wake_on_access: false,
+            created_at: Utc::now(),
+            domain: None,
+            domain_enabled: remote.domain_enabled,
+            stack_id: remote.stack_id,
+            tags: remote.tags,
+            extra_args: remote.extra_args,
+            seed: None,
+        })
+        .collect();
+    config.domains = domains
+        .into_iter()
+        .filter(|d| d.target_type == "instance")
+        .filter_map(|d| {
+            let instance_id = Uuid::parse_str(&d.target_value).ok()?;
+            let mut domain = Domain::for_instance(d.subdomain, instance_id, d.ssl_enabled);
+            domain.id = d.id;
+            Some(domain)
+        })
+        .collect();
+    config.stacks = stacks
+        .into_iter()
+        .map(|remote| Stack {
+            id: remote.id,
+            name: remote.name,
+            description: remote.description,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+        .collect();
+    Ok(config)
+}
+
+/// Save a remote host under `name`, checking that it answers
+pub fn run_remote_add(name: &str, url: &str, token: Option<String>) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err("Give the host a name without spaces, e.g. `office`".to_string());
+    }
+    let host = RemoteHost {
+        name: name.to_string(),
+        url: api_client::normalize_url(url)?,
+        token: token.filter(|t| !t.is_empty()),
+    };
+
+    // Every request to a remote-enabled API needs the token, /status included,
+    // so an answer other than a connection failure means a wrong token.
+    let reachable = match BurdApiClient::for_host(&host).get("/status") {
+        Ok(_) => true,
+        Err(e) if e.starts_with("Request failed") => false,
+        Err(e) => {
+            return Err(format!(
+                "{} answered but refused the request ({}). Check the token from `burd api status` there.",
+                host.url, e
+            ))
+        }
+    };
+
+    let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
+    let mut hosts = config_store.load()?.remote_hosts;
+    let replaced = hosts.iter().any(|h| h.name.eq_ignore_ascii_case(name));
+    hosts.retain(|h| !h.name.eq_ignore_ascii_case(name));
+    hosts.push(host.clone());
+    config_store.update_remote_hosts(hosts)?;
+
+    println!(
+        "✓ {} remote host '{}' ({})",
+        if replaced { "Updated" } else { "Added" },
+        host.name,
+        host.url
+    );
+    if !reachable {
+        println!("  It isn't reachable right now; saved anyway.");
+    }
+    println!("Use it with `burd --host {} instances`.", host.name);
+    Ok(())
+}
+
+/// Forget a saved remote host
+pub fn run_remote_remove(name: &str) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    let _lock = config_store.lock_file()?;
+    let mut hosts = config_store.load()?.remote_hosts;
+    let before = hosts.len();
+    hosts.retain(|h| !h.name.eq_ignore_ascii_case(name));
+    if hosts.len() == before {
+        return Err(format!("No remote host named '{}'", name));
+    }
+    config_store.update_remote_hosts(hosts)?;
+    println!("Removed remote host '{}'", name);
+    Ok(())
+}
+
+/// List the saved remote hosts and whether they answer
+pub fn run_remote_list() -> Result<(), String> {
+    let hosts = ConfigStore::new()?.load()?.remote_hosts;
+    if hosts.is_empty() {
+        println!(
+            "No remote hosts. Add one with `burd remote add <name> <address> --token <token>`."
+        );
+        return Ok(());
+    }
+
+    println!();
+    println!("  {:<16} {:<40} STATUS", "NAME", "ADDRESS");
+    for host in &hosts {
+        let status = if BurdApiClient::for_host(host).is_available() {
+            "reachable"
+        } else {
+            "unreachable"
+        };
+        println!("  {:<16} {:<40} {}", host.name, host.url, status);
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Environment rollback CLI command
+//!
+//! Lists the config snapshots Burd takes when it starts with a new version,
+//! and puts one back to undo an upgrade.
+
+use crate::config::ConfigStore;
+use crate::env_snapshot::{self, EnvSnapshot, SnapshotKind};
+
+fn format_time(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn print_snapshot(snapshot: &EnvSnapshot) {
+    println!(
+        "  {:<26} {:<20} {:<10} {}",
+        snapshot.id,
+        format_time(snapshot.created_at),
+        snapshot.burd_version.as_deref().unwrap_or("unknown"),
+        snapshot.reason
+    );
+}
+
+/// Snapshot the config if the CLI is the first to run after an upgrade
+pub fn snapshot_on_startup() {
+    match ConfigStore::new() {
+        Ok(store) => env_snapshot::snapshot_on_startup(&store),
+        Err(e) => eprintln!("Failed to snapshot the environment before upgrading: {}", e),
+    }
+}
+
+/// List environment snapshots, or roll back to one
+///
+/// Without an ID, rolls back to the newest snapshot taken on an upgrade.
+pub fn run_rollback_env(id: Option<String>, list: bool) -> Result<(), String> {
+    let store = ConfigStore::new()?;
+
+    if list {
+        let snapshots = env_snapshot::list(&store);
+        println!();
+        if snapshots.is_empty() {
+            println!(
+                "No environment snapshots yet. One is taken when Burd starts after an upgrade."
+            );
+        } else {
+            println!("  {:<26} {:<20} {:<10} REASON", "ID", "CREATED", "VERSION");
+            for snapshot in &snapshots {
+                print_snapshot(snapshot);
+            }
+        }
+        println!();
+        return Ok(());
+    }
+
+    let current_version = env!("CARGO_PKG_VERSION");
+    let rollback = env_snapshot::rollback(&store, id.as_deref(), current_version)?;
+    let restored = &rollback.restored;
+
+    println!();
+    match (&restored.burd_version, restored.kind) {
+        (Some(version), SnapshotKind::Upgrade) => {
+            println!(
+                "✓ Restored the config of Burd {} ({})",
+                version, restored.id
+            )
+        }
+        _ => println!("✓ Restored the config from snapshot {}", restored.id),
+    }
+    println!(
+        "  The replaced config was saved; undo with: burd rollback-env {}",
+        rollback.replaced.id
+    );
+
+    if !rollback.missing_binaries.is_empty() {
+        println!();
+        println!("These binaries of the restored config are no longer installed:");
+        for binary in &rollback.missing_binaries {
+            println!(
+                "  {} {}  (burd install {} {})",
+                binary.service, binary.version, binary.service, binary.version
+            );
+        }
+    }
+
+    println!();
+    match &restored.burd_version {
+        Some(version) if version != current_version => println!(
+            "Reinstall Burd {} and restart it to finish the rollback.",
+            version
+        ),
+        _ => println!("Restart Burd to use the restored config."),
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! S3 CLI commands
+//!
+//! Commands for managing the buckets of MinIO instances from the command line.
+
+use crate::config::{ConfigStore, Instance};
+use crate::minio_manager::{find_minio_instances, BucketPolicy, MinioManager, DEFAULT_KEY_HOURS};
+
+/// Find the MinIO instance to use (by name, or the first one configured)
+fn resolve_instance(instance_name: Option<&str>) -> Result<Instance, String> {
+    let config = ConfigStore::new()?.load()?;
+    let instances = find_minio_instances(&config);
+
+    if instances.is_empty() {
+        return Err("No MinIO instance configured in Burd.\n\
+             Create a MinIO instance in the Burd app first."
+            .to_string());
+    }
+
+    match instance_name {
+        Some(name) => instances
+            .into_iter()
+            .find(|i| i.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| format!("No MinIO instance named '{}'", name)),
+        None => Ok(instances[0].clone()),
+    }
+}
+
+fn manager(instance_name: Option<&str>) -> Result<MinioManager, String> {
+    MinioManager::for_instance(&resolve_instance(instance_name)?)
+}
+
+/// List the buckets of a MinIO instance
+pub fn run_s3_list(instance_name: Option<&str>) -> Result<(), String> {
+    let instance = resolve_instance(instance_name)?;
+    let manager = MinioManager::for_instance(&instance)?;
+    let buckets = manager.list_buckets()?;
+
+    println!();
+    println!("{} ({})", instance.name, manager.connection_info());
+    println!("{}", "-".repeat(40));
+
+    if buckets.is_empty() {
+        println!("  No buckets. Create one with: burd s3 create <name>");
+    } else {
+        println!("  {:<40} CREATED", "BUCKET");
+        for bucket in &buckets {
+            println!("  {:<40} {}", bucket.name, bucket.created);
+        }
+    }
+
+    println!();
+    Ok(())
+}
+
+/// Create a bucket
+pub fn run_s3_create(
+    name: &str,
+    policy: Option<&str>,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let policy = BucketPolicy::parse(policy.unwrap_or_default())?;
+    manager(instance_name)?.create_bucket(name, policy)?;
+    println!("Created bucket '{}' ({}).", name, policy.as_str());
+    Ok(())
+}
+
+/// Delete an empty bucket
+pub fn run_s3_delete(name: &str, instance_name: Option<&str>) -> Result<(), String> {
+    manager(instance_name)?.delete_bucket(name)?;
+    println!("Deleted bucket '{}'.", name);
+    Ok(())
+}
+
+/// Apply an anonymous access preset to a bucket
+pub fn run_s3_policy(name: &str, policy: &str, instance_name: Option<&str>) -> Result<(), String> {
+    let policy = BucketPolicy::parse(policy)?;
+    manager(instance_name)?.set_policy(name, policy)?;
+    println!("Bucket '{}' is now {}.", name, policy.as_str());
+    Ok(())
+}
+
+/// Generate temporary access keys, printed as .env lines
+pub fn run_s3_key(
+    bucket: Option<&str>,
+    hours: Option<u32>,
+    instance_name: Option<&str>,
+) -> Result<(), String> {
+    let key =
+        manager(instance_name)?.create_access_key(bucket, hours.unwrap_or(DEFAULT_KEY_HOURS))?;
+
+    match &key.bucket {
+        Some(bucket) => println!(
+            "# Limited to bucket '{}', expires {}",
+            bucket, key.expires_at
+        ),
+        None => println!("# Full access, expires {}", key.expires_at),
+    }
+    println!("AWS_ACCESS_KEY_ID={}", key.access_key);
+    println!("AWS_SECRET_ACCESS_KEY={}", key.secret_key);
+    println!("AWS_SESSION_TOKEN={}", key.session_token);
+    Ok(())
+}
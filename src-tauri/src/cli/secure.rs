@@ -3,7 +3,7 @@
 //! Commands for enabling/disabling HTTPS for domains from the command line.
 
 use crate::caddy;
-use crate::config::ConfigStore;
+use crate::config::{Config, ConfigStore, Domain};
 use std::env;
 
 /// Enable HTTPS for a domain
@@ -24,32 +24,8 @@ pub fn run_unsecure(name: Option<String>) -> Result<(), String> {
 fn set_domain_ssl(name: Option<String>, ssl_enabled: bool) -> Result<(), String> {
     let config_store = ConfigStore::new()?;
     let config = config_store.load()?;
-
-    // Determine subdomain to look for
-    let subdomain = match name {
-        Some(n) => {
-            // Strip TLD suffix if present (e.g., "hello.burd" -> "hello")
-            let tld_suffix = format!(".{}", config.tld);
-            let stripped = n.strip_suffix(&tld_suffix).unwrap_or(&n);
-            slug::slugify(stripped)
-        }
-        None => {
-            // Find domain for current directory
-            find_current_directory_subdomain(&config)?
-        }
-    };
-
-    // Find the domain
-    let domain = config
-        .domains
-        .iter()
-        .find(|d| d.subdomain == subdomain)
-        .ok_or_else(|| {
-            format!(
-                "Domain '{}.{}' not found.\nUse 'burd links' to see available domains.",
-                subdomain, config.tld
-            )
-        })?;
+    let domain = find_domain(&config, name)?;
+    let subdomain = &domain.subdomain;
 
     // Check if already in desired state
     if domain.ssl_enabled == ssl_enabled {
@@ -80,8 +56,37 @@ fn set_domain_ssl(name: Option<String>, ssl_enabled: bool) -> Result<(), String>
     Ok(())
 }
 
+/// Find a domain by name, or the current directory's domain
+pub(super) fn find_domain(config: &Config, name: Option<String>) -> Result<Domain, String> {
+    // Determine subdomain to look for
+    let subdomain = match name {
+        Some(n) => {
+            // Strip TLD suffix if present (e.g., "hello.burd" -> "hello")
+            let tld_suffix = format!(".{}", config.tld);
+            let stripped = n.strip_suffix(&tld_suffix).unwrap_or(&n);
+            slug::slugify(stripped)
+        }
+        None => {
+            // Find domain for current directory
+            find_current_directory_subdomain(config)?
+        }
+    };
+
+    config
+        .domains
+        .iter()
+        .find(|d| d.subdomain == subdomain)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Domain '{}.{}' not found.\nUse 'burd links' to see available domains.",
+                subdomain, config.tld
+            )
+        })
+}
+
 /// Find the subdomain for the current directory
-fn find_current_directory_subdomain(config: &crate::config::Config) -> Result<String, String> {
+fn find_current_directory_subdomain(config: &Config) -> Result<String, String> {
     let current_dir =
         env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
     let document_root = current_dir.to_string_lossy().to_string();
@@ -114,7 +119,7 @@ fn find_current_directory_subdomain(config: &crate::config::Config) -> Result<St
 }
 
 /// Regenerate Caddyfile with current config
-fn regenerate_caddyfile(config_store: &ConfigStore) -> Result<(), String> {
+pub(super) fn regenerate_caddyfile(config_store: &ConfigStore) -> Result<(), String> {
     let config = config_store.load()?;
 
     // Build routes from domains
@@ -122,7 +127,7 @@ fn regenerate_caddyfile(config_store: &ConfigStore) -> Result<(), String> {
         .domains
         .iter()
         .filter_map(|domain| {
-            let full_domain = format!("{}.{}", domain.subdomain, config.tld);
+            let full_domain = domain.full_domain(&config.tld);
             match &domain.target {
                 crate::config::DomainTarget::Instance(inst_id) => {
                     let instance = config.instances.iter().find(|i| i.id == *inst_id)?;
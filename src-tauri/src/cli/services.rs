@@ -1,8 +1,18 @@
 //! `burd services [versions [TYPE]]` — list services and installed versions.
 //!
 //! Mirrors the MCP `list_services` and `get_service_versions` tools.
+//! `burd versions --service TYPE --pin VERSION` sets the default version used
+//! when creating instances; `--arch ARCH` picks the architecture downloaded
+//! and `--channel CHANNEL` whether prereleases are offered.
+//! `burd service add-definition FILE` installs a user service definition.
 
 use crate::api_client::BurdApiClient;
+use crate::arch::Arch;
+use crate::commands::parse_service_type;
+use crate::config::{ConfigStore, ReleaseChannel};
+use crate::service_definitions;
+use crate::validation;
+use std::path::Path;
 
 fn client() -> Result<BurdApiClient, String> {
     let client = BurdApiClient::new();
@@ -55,3 +65,100 @@ pub fn run_service_versions(service_type: Option<String>) -> Result<(), String>
     }
     Ok(())
 }
+
+/// Pin the default version for `service_type`, or unpin it when `version` is
+/// `None`. A pin may be a prefix (`10.11`) matching any installed patch release.
+pub fn run_pin_version(service_type: &str, version: Option<String>) -> Result<(), String> {
+    let svc_type = parse_service_type(service_type)?;
+    if let Some(version) = &version {
+        validation::validate_version(version).map_err(|e| format!("Invalid version: {}", e))?;
+    }
+
+    let store = ConfigStore::new()?;
+    store.set_default_service_version(svc_type, version.clone())?;
+
+    let name = svc_type.display_name();
+    match version {
+        Some(version) => {
+            println!("Pinned {} to {}.", name, version);
+            if let Err(e) = store.load()?.default_version(svc_type) {
+                println!("Warning: {}", e);
+            }
+        }
+        None => println!(
+            "Unpinned {}; new instances use the newest installed version.",
+            name
+        ),
+    }
+    Ok(())
+}
+
+/// Download `service_type` for one architecture from now on, or go back to
+/// the host's own architecture with `native`. Installed versions are kept;
+/// delete and re-download them to switch.
+pub fn run_set_arch(service_type: &str, arch: &str) -> Result<(), String> {
+    let svc_type = parse_service_type(service_type)?;
+    let arch = match arch {
+        "native" => None,
+        other => Some(Arch::parse(other).ok_or_else(|| {
+            format!(
+                "Unknown architecture '{}'. Use arm64, x86_64, or native.",
+                other
+            )
+        })?),
+    };
+
+    ConfigStore::new()?.set_arch_override(svc_type, arch)?;
+
+    let name = svc_type.display_name();
+    match arch {
+        Some(arch) => {
+            println!("{} downloads now use the {} build.", name, arch.label());
+            if arch.needs_rosetta() {
+                println!("It will run under Rosetta on this Mac.");
+            }
+        }
+        None => println!("{} downloads now use the native build.", name),
+    }
+    println!("Re-download installed versions to switch them.");
+    Ok(())
+}
+
+/// Offer only stable releases of `service_type`, or also release candidates
+/// (`rc`) or every prerelease (`beta`), when picking a version to download.
+pub fn run_set_channel(service_type: &str, channel: &str) -> Result<(), String> {
+    let svc_type = parse_service_type(service_type)?;
+    let channel = ReleaseChannel::parse(channel)?;
+
+    ConfigStore::new()?.set_release_channel(svc_type, channel)?;
+
+    let name = svc_type.display_name();
+    match channel {
+        ReleaseChannel::Stable => println!("{} now offers stable releases only.", name),
+        ReleaseChannel::Rc => println!("{} now offers release candidates too.", name),
+        ReleaseChannel::Beta => println!("{} now offers all prereleases, including betas.", name),
+    }
+    Ok(())
+}
+
+/// Validate a service definition and copy it (and its init hook) into the
+/// definitions directory. Works without the app; the app and the daemon load
+/// it when they next start.
+pub fn run_add_service_definition(path: &Path, force: bool) -> Result<(), String> {
+    let (id, config) = service_definitions::install(path, force)?;
+
+    println!("✓ Added {} ({})", config.display_name, id);
+    if let Some(definition) = &config.definition_path {
+        println!("  Definition: {}", definition.display());
+    }
+    if let Some(hook) = config
+        .hooks
+        .init
+        .as_deref()
+        .and_then(|h| config.hook_path(h))
+    {
+        println!("  Init hook:  {}", hook.display());
+    }
+    println!("Restart Burd (or `burd daemon`) to load it.");
+    Ok(())
+}
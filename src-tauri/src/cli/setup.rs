@@ -12,8 +12,12 @@ use crate::analyzer::{
     analyze_project, extract_cache_config, extract_database_config, extract_mail_config,
     parse_env_file, update_env_value, ProjectType,
 };
+use crate::cli::lifecycle::ensure_ready;
 use crate::config::{ConfigStore, Domain, Instance, ServiceType};
 use crate::db_manager::{create_manager_for_instance, find_all_db_instances, sanitize_db_name};
+use crate::domain::DomainManager;
+use crate::port_allocator;
+use crate::services::mailpit::MailpitService;
 use chrono::Utc;
 use std::env;
 use std::io::{self, Write};
@@ -141,28 +145,25 @@ pub fn run_setup() -> Result<(), String> {
 
     // Show access info
     let config = config_store.load()?;
-    let subdomain = slug::slugify(&project_name);
+    let subdomain = DomainManager::slugify_with(&project_name, &config.slugs);
 
     if let Some(domain) = config.domains.iter().find(|d| d.subdomain == subdomain) {
         println!();
         println!("Access your project:");
         let url = if config.proxy_installed {
-            format!("https://{}.{}", domain.subdomain, config.tld)
+            format!("https://{}", domain.full_domain(&config.tld))
         } else {
             format!(
-                "http://{}.{}:{}",
-                domain.subdomain, config.tld, config.proxy_port
+                "http://{}:{}",
+                domain.full_domain(&config.tld),
+                config.proxy_port
             )
         };
         println!("  URL: {}", url);
     }
 
     // Show Mailpit if configured
-    if let Some(mailpit) = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit)
-    {
+    if let Some(mailpit) = MailpitService::for_project(&config, &current_dir) {
         let web_port = mailpit
             .config
             .get("web_port")
@@ -183,9 +184,9 @@ fn setup_frankenphp_instance(
     project_name: &str,
     config_store: &ConfigStore,
 ) -> Result<bool, String> {
-    let mut config = config_store.load()?;
+    let config = config_store.load()?;
     let document_root = project_dir.to_string_lossy().to_string();
-    let subdomain = slug::slugify(project_name);
+    let subdomain = DomainManager::slugify_with(project_name, &config.slugs);
 
     // Check if already linked
     let existing = config.instances.iter().find(|i| {
@@ -223,23 +224,19 @@ fn setup_frankenphp_instance(
         return Ok(false);
     }
 
+    // Reload under the config lock; the prompt may have waited a while
+    let _lock = config_store.lock_file()?;
+    let mut config = config_store.load()?;
+    if config.domains.iter().any(|d| d.subdomain == subdomain) {
+        println!("Domain {}.{} already exists.", subdomain, config.tld);
+        return Ok(false);
+    }
+
     // Find available port
-    let mut port = ServiceType::FrankenPHP.default_port();
-    while config.instances.iter().any(|i| i.port == port) {
-        port += 1;
-    }
-
-    // Get installed FrankenPHP version
-    let version = config
-        .binaries
-        .get(&ServiceType::FrankenPHP)
-        .and_then(|versions| versions.keys().next())
-        .ok_or_else(|| {
-            "No FrankenPHP versions installed.\n\
-             Please download FrankenPHP in the Burd app first."
-                .to_string()
-        })?
-        .clone();
+    let port = port_allocator::suggest_port(&config, ServiceType::FrankenPHP)?;
+
+    // Use the default (pinned or newest installed) FrankenPHP version
+    let version = config.default_version(ServiceType::FrankenPHP)?;
 
     // Create instance
     let instance = Instance {
@@ -253,10 +250,15 @@ fn setup_frankenphp_instance(
         }),
         master_key: None,
         auto_start: false,
+        restart_on_failure: false,
+        wake_on_access: false,
         created_at: Utc::now(),
         domain: Some(subdomain.clone()),
         domain_enabled: true,
         stack_id: None,
+        tags: Vec::new(),
+        extra_args: Vec::new(),
+        seed: None,
     };
 
     // Create instance directory
@@ -318,6 +320,7 @@ fn setup_database(
         });
 
     let db_instance = db_instances[0];
+    ensure_ready(db_instance)?;
     let manager = create_manager_for_instance(db_instance)?;
 
     // Check if exists
@@ -525,11 +528,8 @@ fn setup_mail(
         return Ok(false);
     }
 
-    // Find Mailpit instance
-    let mailpit_instance = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit);
+    // Find the Mailpit instance this project should send mail to
+    let mailpit_instance = MailpitService::for_project(config, project_dir);
 
     let mailpit_instance = match mailpit_instance {
         Some(i) => i,
@@ -539,11 +539,7 @@ fn setup_mail(
         }
     };
 
-    let smtp_port = mailpit_instance
-        .config
-        .get("smtp_port")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(1025) as u16;
+    let smtp_port = MailpitService::smtp_port(mailpit_instance);
 
     let env_vars = match parse_env_file(&env_path) {
         Some(vars) => vars,
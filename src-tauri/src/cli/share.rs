@@ -3,14 +3,20 @@
 //! Exposes local sites to the internet via frpc tunnel.
 
 use crate::config::{ConfigStore, ServiceType};
-use crate::tunnel::{generate_random_subdomain, FrpcManager, SubdomainConfig, TunnelTarget};
+use crate::park;
+use crate::tunnel::{
+    generate_random_subdomain, FrpcManager, HeaderPreset, SubdomainConfig, TunnelTarget,
+};
 use std::env;
 
 /// Share a site via frpc tunnel
 ///
-/// Exposes a local site to the internet.
-/// Requires frpc to be installed, running, and connected.
-pub fn run_share(subdomain: Option<String>) -> Result<(), String> {
+/// Exposes a local site to the internet, with the given response header
+/// presets on new tunnels. Requires frpc to be installed, running, and
+/// connected.
+pub fn run_share(subdomain: Option<String>, headers: Vec<String>) -> Result<(), String> {
+    let header_presets = HeaderPreset::parse_all(&headers)?;
+
     let current_dir =
         env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
 
@@ -177,6 +183,11 @@ pub fn run_share(subdomain: Option<String>) -> Result<(), String> {
         "http".to_string(),
         false, // auto_start
     )?;
+    let tunnel = if header_presets.is_empty() {
+        tunnel
+    } else {
+        config_store.update_tunnel_header_presets(tunnel.id, header_presets)?
+    };
 
     // Get the public URL
     let public_url = tunnel.get_public_url(server);
@@ -192,6 +203,10 @@ pub fn run_share(subdomain: Option<String>) -> Result<(), String> {
     println!("Sharing '{}' (port {})", target_name, target_port);
     println!();
     println!("  Public URL: {}", public_url);
+    for preset in &tunnel.header_presets {
+        let (name, value) = preset.header();
+        println!("  Header:     {}: {}", name, value);
+    }
     println!();
     println!("Note: This tunnel will persist until removed in the Burd app.");
 
@@ -228,12 +243,16 @@ fn find_parked_project(
 
     for parked_dir in &config.parked_directories {
         if current_path.starts_with(&parked_dir.path) && current_path.as_ref() != parked_dir.path {
-            // We're inside a parked directory - this is a project
-            let project_name = current_dir
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
+            // We're inside a parked directory - find the project it belongs to
+            let project_name = park::find_project_containing(parked_dir, current_dir)
+                .map(|project| project.name)
+                .unwrap_or_else(|| {
+                    current_dir
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("unknown")
+                        .to_string()
+                });
 
             // Find the FrankenPHP Park instance to get its port
             let park_instance = config
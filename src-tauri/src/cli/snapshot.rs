@@ -0,0 +1,216 @@
+//! Snapshot CLI commands
+//!
+//! Commands for snapshotting and restoring non-SQL services (Meilisearch,
+//! MongoDB, Redis/Valkey, MinIO) from the command line, and for restoring the
+//! safety snapshots taken before a database is dropped or overwritten.
+
+use crate::config::{Config, ConfigStore, Instance};
+use crate::db_manager::is_database_service;
+use crate::process::ProcessManager;
+use crate::snapshot::{self, SnapshotInfo};
+
+/// Whether an instance can have snapshots, safety snapshots included
+fn has_snapshots(instance: &Instance) -> bool {
+    snapshot::supports_snapshots(instance.service_type)
+        || is_database_service(instance.service_type)
+}
+
+/// Find an instance that supports snapshots by name
+fn resolve_instance(name: &str) -> Result<(Instance, usize), String> {
+    let config = ConfigStore::new()?.load()?;
+    let instance = config
+        .instances
+        .iter()
+        .find(|i| i.name.eq_ignore_ascii_case(name))
+        .cloned()
+        .ok_or_else(|| format!("No instance named '{}'", name))?;
+
+    if !has_snapshots(&instance) {
+        return Err(format!(
+            "Snapshots are not supported for {} instances.\n\
+             Supported: Meilisearch, MongoDB, Redis, Valkey, MinIO. Use `burd db export` for SQL databases.",
+            instance.service_type.display_name()
+        ));
+    }
+
+    Ok((instance, config.snapshot_retention))
+}
+
+fn is_running(instance: &Instance) -> bool {
+    ProcessManager::new().get_status(instance).running
+}
+
+/// Format a byte count for display
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn print_snapshots(snapshots: &[SnapshotInfo]) {
+    if snapshots.is_empty() {
+        println!("  No snapshots.");
+        return;
+    }
+
+    println!("  {:<22} {:<20} {:>10}  LABEL", "ID", "CREATED", "SIZE");
+    for s in snapshots {
+        let label = match (&s.database, &s.expires_at) {
+            (Some(database), Some(expires)) => format!(
+                "{}: {} (until {})",
+                database,
+                s.label.as_deref().unwrap_or(""),
+                expires.with_timezone(&chrono::Local).format("%H:%M")
+            ),
+            _ => s.label.clone().unwrap_or_default(),
+        };
+        println!(
+            "  {:<22} {:<20} {:>10}  {}",
+            s.id,
+            s.created_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            format_size(s.size_bytes),
+            label
+        );
+    }
+}
+
+/// List snapshots of one instance, or of every instance that supports them
+pub fn run_snapshot_list(instance_name: Option<&str>) -> Result<(), String> {
+    let instances = match instance_name {
+        Some(name) => vec![resolve_instance(name)?.0],
+        None => ConfigStore::new()?
+            .load()?
+            .instances
+            .into_iter()
+            .filter(has_snapshots)
+            .collect(),
+    };
+
+    if instances.is_empty() {
+        println!("No instances support snapshots (Meilisearch, MongoDB, Redis, Valkey, MinIO).");
+        return Ok(());
+    }
+
+    for instance in &instances {
+        println!();
+        println!(
+            "{} ({})",
+            instance.name,
+            instance.service_type.display_name()
+        );
+        println!("{}", "-".repeat(40));
+        print_snapshots(&snapshot::list_snapshots(&instance.id)?);
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Create a snapshot of an instance
+pub fn run_snapshot_create(instance_name: &str, label: Option<&str>) -> Result<(), String> {
+    let (instance, retention) = resolve_instance(instance_name)?;
+    if !snapshot::supports_snapshots(instance.service_type) {
+        return Err(format!(
+            "Snapshots of {} are taken before `burd db drop` and `burd db import`. Use `burd db export` for a copy.",
+            instance.service_type.display_name()
+        ));
+    }
+
+    println!("Creating snapshot of '{}'...", instance.name);
+    let info = snapshot::create_snapshot(
+        &instance,
+        is_running(&instance),
+        label.map(String::from),
+        retention,
+    )?;
+
+    println!(
+        "Created snapshot {} ({}).",
+        info.id,
+        format_size(info.size_bytes)
+    );
+    if retention > 0 {
+        println!("Keeping the newest {} snapshot(s) per instance.", retention);
+    }
+    Ok(())
+}
+
+/// Restore a snapshot into its instance
+pub fn run_snapshot_restore(instance_name: &str, snapshot_id: &str) -> Result<(), String> {
+    let (instance, _) = resolve_instance(instance_name)?;
+
+    println!(
+        "Restoring snapshot {} into '{}'...",
+        snapshot_id, instance.name
+    );
+    let info = snapshot::restore_snapshot(&instance, snapshot_id, is_running(&instance))?;
+
+    match &info.database {
+        Some(database) => println!("Restored '{}' from snapshot {}.", database, info.id),
+        None => println!("Restored snapshot {}.", info.id),
+    }
+    if info.kind == snapshot::SnapshotKind::MeilisearchDump {
+        println!("The dump is imported the next time the instance starts.");
+    }
+    Ok(())
+}
+
+/// Delete a snapshot
+pub fn run_snapshot_delete(instance_name: &str, snapshot_id: &str) -> Result<(), String> {
+    let (instance, _) = resolve_instance(instance_name)?;
+
+    snapshot::delete_snapshot(&instance.id, snapshot_id)?;
+    println!("Deleted snapshot {}.", snapshot_id);
+    Ok(())
+}
+
+/// Snapshot `database` before it's dropped or overwritten, unless the config
+/// turns safety snapshots off
+///
+/// `reason` labels the snapshot, e.g. "before db drop".
+pub(crate) fn take_safety_snapshot(
+    config: &Config,
+    instance: &Instance,
+    database: &str,
+    reason: &str,
+) -> Result<(), String> {
+    let hours = config.safety_snapshot_hours;
+    if hours == 0 {
+        return Ok(());
+    }
+
+    let info = snapshot::create_safety_snapshot(instance, database, reason, hours)?;
+    println!(
+        "Snapshot {} of '{}' kept for {}h. Undo with: burd snapshot restore {} {}",
+        info.id, database, hours, instance.name, info.id
+    );
+    Ok(())
+}
+
+/// Show or set how many hours safety snapshots are kept (0 turns them off)
+pub fn run_snapshot_safety(hours: Option<u32>) -> Result<(), String> {
+    let config_store = ConfigStore::new()?;
+    if let Some(hours) = hours {
+        config_store.update_safety_snapshot_hours(hours)?;
+    }
+
+    match config_store.load()?.safety_snapshot_hours {
+        0 => println!("Safety snapshots are off."),
+        hours => println!(
+            "Databases are snapshotted before `burd db drop`, `burd db import` and destructive `burd wp` commands, and kept for {}h.",
+            hours
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! `burd stack up|down|restart [NAME]` — start or stop a whole stack.
+//!
+//! Instances start in dependency order (databases, caches and other backing
+//! services before the apps that use them) and stop in reverse. A failing
+//! instance doesn't hold back the others; every outcome is listed and the
+//! command fails when any instance did.
+//!
+//! NAME is a stack name or ID. When omitted, the stack of the instance tied
+//! to the current directory is used.
+
+use crate::api_client::BurdApiClient;
+use crate::cli::lifecycle::resolve_instance;
+use crate::cli::remote;
+use serde::Deserialize;
+
+/// Outcome for one instance, as returned by the API
+#[derive(Deserialize)]
+struct ActionResult {
+    name: String,
+    skipped: bool,
+    error: Option<String>,
+}
+
+pub fn run_stack_up(name: Option<String>) -> Result<(), String> {
+    run(name, "start")
+}
+
+pub fn run_stack_down(name: Option<String>) -> Result<(), String> {
+    run(name, "stop")
+}
+
+pub fn run_stack_restart(name: Option<String>) -> Result<(), String> {
+    run(name, "restart")
+}
+
+/// Stack named on the command line, or the current directory's
+fn resolve_stack(name: Option<String>) -> Result<(String, String), String> {
+    let config = remote::load_config()?;
+
+    let stack = match name {
+        Some(name) => config
+            .stacks
+            .iter()
+            .find(|s| s.id.to_string() == name || s.name.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("Stack '{}' not found", name))?,
+        None => {
+            let instance = resolve_instance(&config, None)?;
+            let stack_id = instance.stack_id.ok_or_else(|| {
+                format!(
+                    "'{}' isn't part of a stack. Pass a stack name.",
+                    instance.name
+                )
+            })?;
+            config
+                .stacks
+                .iter()
+                .find(|s| s.id == stack_id)
+                .ok_or_else(|| format!("Stack {} not found", stack_id))?
+        }
+    };
+    Ok((stack.id.to_string(), stack.name.clone()))
+}
+
+fn run(name: Option<String>, action: &str) -> Result<(), String> {
+    let (id, stack_name) = resolve_stack(name)?;
+
+    let client = BurdApiClient::new();
+    if !client.is_available() {
+        return Err(
+            "Burd app isn't running. Open Burd or run `burd setup`, then try again.".to_string(),
+        );
+    }
+
+    let response = client.post(
+        &format!("/stacks/{}/{}", id, action),
+        &serde_json::json!({}),
+    )?;
+    let results: Vec<ActionResult> = serde_json::from_str(&response)
+        .map_err(|e| format!("Failed to parse stack response: {}", e))?;
+
+    if results.is_empty() {
+        println!("Stack '{}' has no instances.", stack_name);
+        return Ok(());
+    }
+
+    let (done, state) = match action {
+        "start" => ("Started", "running"),
+        "stop" => ("Stopped", "stopped"),
+        _ => ("Restarted", "running"),
+    };
+    let mut failed = 0;
+    for result in &results {
+        match &result.error {
+            Some(error) => {
+                failed += 1;
+                println!("✗ '{}': {}", result.name, error);
+            }
+            None if result.skipped => println!("• '{}' is already {}", result.name, state),
+            None => println!("✓ {} '{}'", done, result.name),
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} instance(s) in '{}' failed to {}",
+            failed,
+            results.len(),
+            stack_name,
+            action
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! `burd stats [DOMAIN] [--minutes N]` — per-site request counts and
+//! response times.
+//!
+//! Reads the sites' access logs directly, so it works whether or not the app
+//! is running.
+
+use crate::cli::secure::find_domain;
+use crate::config::ConfigStore;
+use crate::site_stats::{self, DomainStats, LatencyStats};
+
+fn format_ms(ms: f64) -> String {
+    if ms >= 1000.0 {
+        format!("{:.2}s", ms / 1000.0)
+    } else {
+        format!("{:.0}ms", ms)
+    }
+}
+
+fn print_window(label: &str, stats: &LatencyStats) {
+    if stats.requests == 0 {
+        println!("  {:<10} no requests", label);
+        return;
+    }
+    println!(
+        "  {:<10} {} requests, {} errors  avg {}  p50 {}  p95 {}  max {}",
+        label,
+        stats.requests,
+        stats.errors,
+        format_ms(stats.avg_ms),
+        format_ms(stats.p50_ms),
+        format_ms(stats.p95_ms),
+        format_ms(stats.max_ms)
+    );
+}
+
+/// Median change against the previous window, e.g. "3.1x slower"
+fn describe_slowdown(stats: &DomainStats) -> Option<String> {
+    let slowdown = stats.slowdown?;
+    if stats.slower {
+        Some(format!("{:.1}x slower", slowdown))
+    } else if slowdown <= 1.0 / 1.5 {
+        Some(format!("{:.1}x faster", 1.0 / slowdown))
+    } else {
+        None
+    }
+}
+
+fn print_domain(stats: &DomainStats) {
+    println!();
+    println!("Response times for {}", stats.domain);
+    println!("{}", "-".repeat(40));
+    print_window(&format!("last {}m", stats.window_minutes), &stats.recent);
+    print_window(
+        &format!("{}m before", stats.window_minutes),
+        &stats.previous,
+    );
+    if let Some(change) = describe_slowdown(stats) {
+        println!();
+        println!(
+            "  Median is {} than the {} minutes before",
+            change, stats.window_minutes
+        );
+    }
+    if let Some(at) = stats.last_request_at {
+        println!();
+        println!(
+            "  Last request: {}",
+            at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")
+        );
+    }
+    println!();
+}
+
+fn print_all(stats: &[DomainStats], minutes: u32) {
+    println!();
+    println!("Response times, last {} minutes", minutes);
+    println!("{}", "-".repeat(40));
+    if stats.is_empty() {
+        println!("  No domains configured.");
+    }
+    println!(
+        "  {:<28} {:>8} {:>6} {:>8} {:>8}",
+        "DOMAIN", "REQUESTS", "5XX", "P50", "P95"
+    );
+    for s in stats {
+        if s.recent.requests == 0 {
+            println!("  {:<28} {:>8}", s.domain, 0);
+            continue;
+        }
+        let change = describe_slowdown(s)
+            .map(|c| format!("  {}", c))
+            .unwrap_or_default();
+        println!(
+            "  {:<28} {:>8} {:>6} {:>8} {:>8}{}",
+            s.domain,
+            s.recent.requests,
+            s.recent.errors,
+            format_ms(s.recent.p50_ms),
+            format_ms(s.recent.p95_ms),
+            change
+        );
+    }
+    println!();
+}
+
+/// Show response times of a domain, or of every domain without one
+pub fn run_stats(name: Option<String>, minutes: u32) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let minutes = minutes.max(1);
+
+    match name {
+        Some(name) => {
+            let domain = find_domain(&config, Some(name))?;
+            let stats = site_stats::domain_stats(&domain.full_domain(&config.tld), minutes);
+            print_domain(&stats);
+        }
+        None => print_all(&site_stats::all_domain_stats(&config, minutes), minutes),
+    }
+    Ok(())
+}
@@ -0,0 +1,304 @@
+//! `burd tui` — terminal dashboard for instances.
+//!
+//! Everything goes through the daemon's HTTP API, the same as the other
+//! lifecycle commands: `/instances` is polled for state and health, and the
+//! selected instance's `/instances/:id/logs` buffer is tailed below the
+//! table. Start/stop/restart are sent from keybindings; the calls block the
+//! (single-threaded) UI until the daemon answers, with a status line drawn
+//! first so the wait is visible.
+
+use crate::api_client::BurdApiClient;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// How often instance state and the log tail are refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to wait for a key before checking whether a refresh is due
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The fields of `/instances` entries the dashboard shows
+#[derive(Debug, Deserialize)]
+struct InstanceRow {
+    id: String,
+    name: String,
+    service_type: String,
+    version: String,
+    port: u16,
+    running: bool,
+    healthy: Option<bool>,
+    pid: Option<u32>,
+    #[serde(default)]
+    domain: String,
+}
+
+impl InstanceRow {
+    fn status(&self) -> (&'static str, Color) {
+        match (self.running, self.healthy) {
+            (false, _) => ("stopped", Color::DarkGray),
+            (true, Some(false)) => ("unhealthy", Color::Yellow),
+            (true, Some(true)) => ("healthy", Color::Green),
+            (true, None) => ("running", Color::Green),
+        }
+    }
+}
+
+struct App {
+    client: BurdApiClient,
+    instances: Vec<InstanceRow>,
+    table: TableState,
+    logs: String,
+    /// Result of the last action, or why the last refresh failed
+    message: Option<(String, Color)>,
+    last_refresh: Option<Instant>,
+}
+
+impl App {
+    fn new() -> Self {
+        Self {
+            client: BurdApiClient::new(),
+            instances: Vec::new(),
+            table: TableState::default().with_selected(Some(0)),
+            logs: String::new(),
+            message: None,
+            last_refresh: None,
+        }
+    }
+
+    fn selected(&self) -> Option<&InstanceRow> {
+        self.table.selected().and_then(|i| self.instances.get(i))
+    }
+
+    /// Reload instances and the selected instance's logs
+    fn refresh(&mut self) {
+        self.last_refresh = Some(Instant::now());
+
+        let instances = self.client.get("/instances").and_then(|body| {
+            serde_json::from_str::<Vec<InstanceRow>>(&body)
+                .map_err(|e| format!("Unexpected response from Burd: {}", e))
+        });
+        match instances {
+            Ok(instances) => {
+                // Keep the selection on the same instance across reorders
+                let selected_id = self.selected().map(|i| i.id.clone());
+                self.instances = instances;
+                let index = selected_id
+                    .and_then(|id| self.instances.iter().position(|i| i.id == id))
+                    .unwrap_or(0)
+                    .min(self.instances.len().saturating_sub(1));
+                self.table.select(Some(index));
+                if matches!(&self.message, Some((_, Color::Red))) {
+                    self.message = None;
+                }
+            }
+            Err(e) => {
+                self.message = Some((format!("Lost connection to Burd: {}", e), Color::Red));
+                return;
+            }
+        }
+
+        self.refresh_logs();
+    }
+
+    fn refresh_logs(&mut self) {
+        self.logs = match self.selected() {
+            Some(instance) => self
+                .client
+                .get(&format!("/instances/{}/logs", instance.id))
+                .map(|body| serde_json::from_str::<String>(&body).unwrap_or(body))
+                .unwrap_or_else(|e| format!("Failed to load logs: {}", e)),
+            None => String::new(),
+        };
+    }
+
+    fn refresh_due(&self) -> bool {
+        self.last_refresh
+            .is_none_or(|last| last.elapsed() >= REFRESH_INTERVAL)
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.instances.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let last = self.instances.len() as isize - 1;
+        self.table
+            .select(Some((current + offset).clamp(0, last) as usize));
+        self.refresh_logs();
+    }
+
+    /// Send start/stop/restart for the selected instance
+    fn lifecycle(&mut self, terminal: &mut DefaultTerminal, action: &str) -> Result<(), String> {
+        let Some(instance) = self.selected() else {
+            return Ok(());
+        };
+        let (id, name) = (instance.id.clone(), instance.name.clone());
+
+        let verb = match action {
+            "start" => "Starting",
+            "stop" => "Stopping",
+            _ => "Restarting",
+        };
+        self.message = Some((format!("{} {}...", verb, name), Color::Cyan));
+        terminal
+            .draw(|frame| self.draw(frame))
+            .map_err(|e| format!("Failed to draw: {}", e))?;
+
+        let path = format!("/instances/{}/{}", id, action);
+        self.message = Some(match self.client.post(&path, &serde_json::json!({})) {
+            Ok(_) => (format!("{}: {} sent", name, action), Color::Green),
+            Err(e) => (format!("Failed to {} {}: {}", action, name, e), Color::Red),
+        });
+        self.refresh();
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, table, logs, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(self.instances.len().clamp(1, 12) as u16 + 3),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let running = self.instances.iter().filter(|i| i.running).count();
+        frame.render_widget(
+            Line::from(vec![
+                Span::from(" Burd ").bold().reversed(),
+                Span::from(format!(
+                    " {} instances, {} running",
+                    self.instances.len(),
+                    running
+                )),
+            ]),
+            header,
+        );
+
+        self.draw_table(frame, table);
+        self.draw_logs(frame, logs);
+
+        let footer_line = match &self.message {
+            Some((message, color)) => Line::from(message.as_str()).fg(*color),
+            None => {
+                Line::from(" ↑/↓ select  s start  x stop  r restart  u refresh  q quit").dark_gray()
+            }
+        };
+        frame.render_widget(footer_line, footer);
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.instances.iter().map(|instance| {
+            let (status, color) = instance.status();
+            Row::new(vec![
+                Cell::from(instance.name.as_str()),
+                Cell::from(instance.service_type.as_str()),
+                Cell::from(instance.version.as_str()),
+                Cell::from(instance.port.to_string()),
+                Cell::from(status).fg(color),
+                Cell::from(instance.pid.map(|p| p.to_string()).unwrap_or_default()),
+                Cell::from(instance.domain.as_str()),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Min(20),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Name", "Service", "Version", "Port", "Status", "PID", "Domain",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title(" Instances "));
+
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_logs(&self, frame: &mut Frame, area: Rect) {
+        let title = match self.selected() {
+            Some(instance) => format!(" Logs: {} ", instance.name),
+            None => " Logs ".to_string(),
+        };
+        // Only the lines that fit, newest at the bottom
+        let height = area.height.saturating_sub(2) as usize;
+        let lines: Vec<&str> = self.logs.lines().collect();
+        let tail = lines[lines.len().saturating_sub(height)..]
+            .iter()
+            .map(|line| Line::from(*line))
+            .collect::<Vec<_>>();
+
+        frame.render_widget(
+            Paragraph::new(tail).block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+}
+
+/// Run the dashboard until `q`, Esc or Ctrl-C
+pub fn run_tui() -> Result<(), String> {
+    let mut app = App::new();
+    if !app.client.is_available() {
+        return Err(
+            "Burd app isn't running. Open Burd or run `burd setup`, then try again.".to_string(),
+        );
+    }
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), String> {
+    loop {
+        if app.refresh_due() {
+            app.refresh();
+        }
+        terminal
+            .draw(|frame| app.draw(frame))
+            .map_err(|e| format!("Failed to draw: {}", e))?;
+
+        if !event::poll(POLL_INTERVAL).map_err(|e| format!("Failed to read input: {}", e))? {
+            continue;
+        }
+        let Event::Key(key) = event::read().map_err(|e| format!("Failed to read input: {}", e))?
+        else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => app.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => app.select(1),
+            KeyCode::Char('s') => app.lifecycle(terminal, "start")?,
+            KeyCode::Char('x') => app.lifecycle(terminal, "stop")?,
+            KeyCode::Char('r') => app.lifecycle(terminal, "restart")?,
+            KeyCode::Char('u') => {
+                app.message = None;
+                app.refresh();
+            }
+            _ => {}
+        }
+    }
+}
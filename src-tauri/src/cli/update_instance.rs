@@ -1,29 +1,50 @@
-//! `burd update [NAME] --php-version VER [--port N] [--name NEW]`
+//! `burd update [NAME] --php-version VER [--port N] [--name NEW] [--auto-start BOOL]
+//! [--restart-on-failure BOOL] [--tags A,B] [--arg ARG]... [--clear-args]`
 //!
 //! Mirrors the MCP `update_instance` tool. Today only `--php-version` is
 //! wired because that's the documented CLI gap; the PUT /instances/:id
 //! endpoint accepts more fields, which can be added later as flags.
+//!
+//! When the change leaves linked projects' `.env` files pointing at the old
+//! port, the stale keys are listed with an offer to update them (not with
+//! `--host`, since the projects live on the other machine).
 
-use crate::api_client::BurdApiClient;
+use crate::api_client::{self, BurdApiClient};
 use crate::cli::lifecycle::resolve_instance;
+use crate::cli::remote;
 use crate::config::{ConfigStore, ServiceType};
+use crate::env_sync::{self, EnvChange};
 use serde_json::{json, Map, Value};
+use std::io::{self, Write};
 
 pub struct UpdateOptions {
     pub php_version: Option<String>,
     pub port: Option<u16>,
     pub new_name: Option<String>,
+    pub auto_start: Option<bool>,
+    pub restart_on_failure: Option<bool>,
+    /// Replaces all tags; an empty list clears them
+    pub tags: Option<Vec<String>>,
+    /// Replaces the extra start arguments; an empty list clears them
+    pub extra_args: Option<Vec<String>>,
 }
 
 pub fn run_update(name: Option<String>, opts: UpdateOptions) -> Result<(), String> {
-    if opts.php_version.is_none() && opts.port.is_none() && opts.new_name.is_none() {
+    if opts.php_version.is_none()
+        && opts.port.is_none()
+        && opts.new_name.is_none()
+        && opts.auto_start.is_none()
+        && opts.restart_on_failure.is_none()
+        && opts.tags.is_none()
+        && opts.extra_args.is_none()
+    {
         return Err(
-            "Nothing to update. Pass at least one of: --php-version, --port, --name.".to_string(),
+            "Nothing to update. Pass at least one of: --php-version, --port, --name, --auto-start, --restart-on-failure, --tags, --arg, --clear-args."
+                .to_string(),
         );
     }
 
-    let config_store = ConfigStore::new()?;
-    let config = config_store.load()?;
+    let config = remote::load_config()?;
 
     let instance = resolve_instance(&config, name.as_deref())?;
 
@@ -51,8 +72,20 @@ pub fn run_update(name: Option<String>, opts: UpdateOptions) -> Result<(), Strin
     if let Some(n) = opts.new_name.as_ref() {
         body.insert("name".to_string(), json!(n));
     }
+    if let Some(a) = opts.auto_start {
+        body.insert("auto_start".to_string(), json!(a));
+    }
+    if let Some(r) = opts.restart_on_failure {
+        body.insert("restart_on_failure".to_string(), json!(r));
+    }
+    if let Some(t) = opts.tags.as_ref() {
+        body.insert("tags".to_string(), json!(t));
+    }
+    if let Some(a) = opts.extra_args.as_ref() {
+        body.insert("extra_args".to_string(), json!(a));
+    }
 
-    client.put(&format!("/instances/{}", instance.id), &Value::Object(body))?;
+    let response = client.put(&format!("/instances/{}", instance.id), &Value::Object(body))?;
 
     println!("✓ Updated '{}'", instance.name);
     if let Some(v) = opts.php_version {
@@ -64,5 +97,67 @@ pub fn run_update(name: Option<String>, opts: UpdateOptions) -> Result<(), Strin
     if let Some(n) = opts.new_name {
         println!("  name → {}", n);
     }
+    if let Some(a) = opts.auto_start {
+        println!("  auto-start → {}", a);
+    }
+    if let Some(r) = opts.restart_on_failure {
+        println!("  restart on failure → {}", r);
+    }
+    if let Some(t) = opts.tags {
+        if t.is_empty() {
+            println!("  tags → (none)");
+        } else {
+            println!("  tags → {}", t.join(", "));
+        }
+    }
+    if let Some(a) = opts.extra_args {
+        if a.is_empty() {
+            println!("  start arguments → (none)");
+        } else {
+            println!("  start arguments → {}", a.join(" "));
+        }
+        println!("  Restart the instance to apply.");
+    }
+
+    let env_sync: Vec<EnvChange> = serde_json::from_str::<Value>(&response)
+        .ok()
+        .and_then(|v| v.get("env_sync").cloned())
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    if !env_sync.is_empty() && api_client::remote_host().is_none() {
+        offer_env_sync(&env_sync)?;
+    }
+    Ok(())
+}
+
+/// Show .env values that still point at the old port/domain and offer to fix them
+fn offer_env_sync(changes: &[EnvChange]) -> Result<(), String> {
+    println!();
+    println!("Linked projects still reference the old value:");
+    for change in changes {
+        println!(
+            "  {}: {} = {} -> {}",
+            change.env_file().display(),
+            change.key,
+            change.current,
+            change.suggested
+        );
+    }
+    println!();
+
+    print!("Update these .env values? [y/N] ");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+
+    if input.trim().eq_ignore_ascii_case("y") {
+        let config = ConfigStore::new()?.load()?;
+        let applied = env_sync::apply(&config, changes)?;
+        println!("Updated {} .env value(s).", applied);
+    } else {
+        println!("Skipped. Run `burd env check` in a project to review later.");
+    }
     Ok(())
 }
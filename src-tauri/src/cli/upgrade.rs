@@ -234,41 +234,32 @@ fn fetch_from_github(client: &reqwest::blocking::Client) -> Result<UpdateManifes
     // Find CLI binaries in assets
     let mut platforms = std::collections::HashMap::new();
 
-    for asset in &release.assets {
-        if asset.name.starts_with("burd-darwin-aarch64") && !asset.name.ends_with(".sha256") {
-            // Try to find the sha256 file
-            let sha256 = release
-                .assets
-                .iter()
-                .find(|a| a.name == format!("{}.sha256", asset.name))
-                .map(|a| fetch_sha256(client, &a.browser_download_url))
-                .transpose()?
-                .unwrap_or_default();
-
-            platforms.insert(
-                "darwin-aarch64".to_string(),
-                PlatformInfo {
-                    url: asset.browser_download_url.clone(),
-                    sha256,
-                },
-            );
-        } else if asset.name.starts_with("burd-darwin-x64") && !asset.name.ends_with(".sha256") {
-            let sha256 = release
-                .assets
-                .iter()
-                .find(|a| a.name == format!("{}.sha256", asset.name))
-                .map(|a| fetch_sha256(client, &a.browser_download_url))
-                .transpose()?
-                .unwrap_or_default();
-
-            platforms.insert(
-                "darwin-x64".to_string(),
-                PlatformInfo {
-                    url: asset.browser_download_url.clone(),
-                    sha256,
-                },
-            );
-        }
+    for key in ["darwin-aarch64", "darwin-x64", "linux-aarch64", "linux-x64"] {
+        let prefix = format!("burd-{}", key);
+        let Some(asset) = release
+            .assets
+            .iter()
+            .find(|a| a.name.starts_with(&prefix) && !a.name.ends_with(".sha256"))
+        else {
+            continue;
+        };
+
+        // Try to find the sha256 file
+        let sha256 = release
+            .assets
+            .iter()
+            .find(|a| a.name == format!("{}.sha256", asset.name))
+            .map(|a| fetch_sha256(client, &a.browser_download_url))
+            .transpose()?
+            .unwrap_or_default();
+
+        platforms.insert(
+            key.to_string(),
+            PlatformInfo {
+                url: asset.browser_download_url.clone(),
+                sha256,
+            },
+        );
     }
 
     if platforms.is_empty() {
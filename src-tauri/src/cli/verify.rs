@@ -0,0 +1,90 @@
+//! Verify CLI command
+//!
+//! Re-checks installed service binaries (recorded hashes, executable bits,
+//! quarantine attributes, Homebrew symlinks) and repairs what it can.
+
+use crate::binary_verify::{self, BinaryCheck};
+use crate::commands::parse_service_type;
+use crate::config::ConfigStore;
+
+/// Print one binary's result with its issues and hints
+pub(crate) fn print_check(check: &BinaryCheck) {
+    let symbol = match (check.healthy(), check.issues.is_empty()) {
+        (false, _) => "[ERR]",
+        (true, true) => "[OK]",
+        (true, false) => "[FIXED]",
+    };
+    let hash = if check.hash_verified {
+        "hash verified"
+    } else {
+        "no recorded hash"
+    };
+    let arch = match check.arch {
+        Some(arch) if arch.needs_rosetta() => format!(", {} (Rosetta)", arch.label()),
+        Some(arch) => format!(", {}", arch.label()),
+        None => String::new(),
+    };
+    println!(
+        "  {} {} {} - {}{}",
+        symbol,
+        check.service_type.display_name(),
+        check.version,
+        hash,
+        arch
+    );
+    for issue in &check.issues {
+        let suffix = if issue.repaired { " (repaired)" } else { "" };
+        println!("      {}: {}{}", issue.kind.label(), issue.detail, suffix);
+        if let (Some(hint), false) = (&issue.hint, issue.repaired) {
+            println!("      {}", hint);
+        }
+    }
+}
+
+/// Verify installed binaries, optionally for one service, optionally repairing
+pub fn run_verify(service: Option<String>, repair: bool) -> Result<(), String> {
+    let service_type = service.as_deref().map(parse_service_type).transpose()?;
+    let config = ConfigStore::new()?.load()?;
+
+    println!();
+    println!(
+        "{} installed binaries",
+        if repair {
+            "Verifying and repairing"
+        } else {
+            "Verifying"
+        }
+    );
+    println!("{}", "-".repeat(40));
+
+    let checks = binary_verify::verify_binaries(&config, service_type, repair);
+    if checks.is_empty() {
+        println!("  No binaries installed.");
+        return Ok(());
+    }
+    for check in &checks {
+        print_check(check);
+    }
+
+    let broken = checks.iter().filter(|c| !c.healthy()).count();
+    let repaired = checks
+        .iter()
+        .flat_map(|c| &c.issues)
+        .filter(|i| i.repaired)
+        .count();
+
+    println!();
+    match (broken, repaired) {
+        (0, 0) => println!("All {} binaries are intact.", checks.len()),
+        (0, n) => println!("Repaired {} issue(s); all binaries are intact now.", n),
+        (n, _) if !repair => {
+            println!(
+                "{} binary(ies) need attention. Run `burd verify --repair`.",
+                n
+            )
+        }
+        (n, _) => println!("{} binary(ies) could not be repaired; see hints above.", n),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! `burd worker start|stop|status` — Laravel queue workers.
+//!
+//! PATH defaults to the current directory; a project's `public/` directory
+//! resolves to the project. Settings passed to `start` are saved with the
+//! worker and reused by later starts, here and in the app.
+
+use crate::config::ConfigStore;
+use crate::process::ProcessManager;
+use crate::workers::{self, WorkerMode, WorkerOptions};
+use std::env;
+use std::path::PathBuf;
+
+/// The Laravel project at PATH, or the current directory's
+fn resolve_project(path: Option<String>) -> Result<PathBuf, String> {
+    let dir = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?
+        }
+    };
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| format!("Cannot access {}: {}", dir.display(), e))?;
+    workers::project_root(&dir)
+}
+
+pub fn run_worker_start(
+    path: Option<String>,
+    mode: Option<String>,
+    concurrency: Option<u8>,
+    queue: Option<String>,
+    connection: Option<String>,
+) -> Result<(), String> {
+    let project = resolve_project(path)?;
+    let options = WorkerOptions {
+        mode: mode.as_deref().map(WorkerMode::parse).transpose()?,
+        concurrency,
+        queue,
+        connection,
+    };
+
+    let config_store = ConfigStore::new()?;
+    let process_manager = ProcessManager::new();
+
+    let config_lock = config_store.lock_file()?;
+    let mut saved = config_store.load()?.workers;
+    let worker = workers::upsert_worker(&mut saved, &project, options)?;
+    if process_manager.get_worker_status(&worker).running {
+        return Err(format!(
+            "The worker for '{}' is already running. Run `burd worker stop` first to apply new settings.",
+            worker.name()
+        ));
+    }
+    config_store.update_workers(saved)?;
+    drop(config_lock);
+
+    let pids = process_manager.start_worker(&worker)?;
+    println!(
+        "✓ Started {} for '{}' ({} process{})",
+        match worker.mode {
+            WorkerMode::Queue => "queue:work",
+            WorkerMode::Horizon => "Horizon",
+        },
+        worker.name(),
+        pids.len(),
+        if pids.len() == 1 { "" } else { "es" }
+    );
+    if let Ok(log_path) = ProcessManager::get_worker_log_path(&worker.id) {
+        println!("  Log: {}", log_path.display());
+    }
+    Ok(())
+}
+
+pub fn run_worker_stop(path: Option<String>, all: bool) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let process_manager = ProcessManager::new();
+
+    let targets = if all {
+        config.workers
+    } else {
+        let project = resolve_project(path)?;
+        let worker = workers::find_worker(&config.workers, &project)
+            .ok_or_else(|| format!("No worker for {}", project.display()))?;
+        vec![worker.clone()]
+    };
+
+    for worker in &targets {
+        match process_manager.stop_worker(&worker.id)? {
+            0 if !all => println!("• The worker for '{}' isn't running", worker.name()),
+            0 => {}
+            stopped => println!(
+                "✓ Stopped the worker for '{}' ({} process{})",
+                worker.name(),
+                stopped,
+                if stopped == 1 { "" } else { "es" }
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub fn run_worker_status() -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let process_manager = ProcessManager::new();
+
+    if config.workers.is_empty() {
+        println!("No queue workers yet.");
+        println!();
+        println!("Start one in a Laravel project with: burd worker start");
+        return Ok(());
+    }
+
+    println!("Queue Workers:");
+    println!();
+    for worker in &config.workers {
+        let status = process_manager.get_worker_status(worker);
+        let command = match worker.mode {
+            WorkerMode::Queue => format!(
+                "queue:work ×{}{}",
+                worker.concurrency,
+                worker
+                    .queue
+                    .as_ref()
+                    .map(|q| format!(" --queue={}", q))
+                    .unwrap_or_default()
+            ),
+            WorkerMode::Horizon => "horizon".to_string(),
+        };
+        let state = if status.running {
+            format!(
+                "running (PID {})",
+                status
+                    .pids
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        } else {
+            "stopped".to_string()
+        };
+        println!("  {} — {}, {}", worker.name(), command, state);
+        println!("    {}", worker.project_path);
+    }
+    Ok(())
+}
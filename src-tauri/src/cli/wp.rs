@@ -0,0 +1,75 @@
+//! wp-cli passthrough
+//!
+//! Runs wp-cli in the current WordPress/Bedrock project with the project's
+//! PHP and database instance, plus the project's vault secrets. Commands that
+//! overwrite the database, like search-replace, snapshot it first.
+
+use crate::cli::snapshot::take_safety_snapshot;
+use crate::config::ConfigStore;
+use crate::db_manager::is_database_service;
+use crate::process::ProcessManager;
+use crate::vault;
+use crate::wp_cli;
+use std::env;
+
+/// Download or update wp-cli
+pub fn run_wp_install() -> Result<(), String> {
+    let updating = wp_cli::is_installed();
+    println!(
+        "{} wp-cli...",
+        if updating { "Updating" } else { "Downloading" }
+    );
+
+    let path = wp_cli::install()?;
+    println!("Installed wp-cli at {}", path.display());
+    Ok(())
+}
+
+/// Run wp-cli with `args` in the project containing the current directory
+///
+/// `burd wp search-replace <old>` replaces `<old>` with the project's Burd URL.
+pub fn run_wp(mut args: Vec<String>) -> Result<(), String> {
+    let current_dir =
+        env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let config = ConfigStore::new()?.load()?;
+    let project = wp_cli::resolve_project(&config, &current_dir)?;
+
+    if !wp_cli::is_installed() {
+        println!("wp-cli is not installed yet; downloading it first.");
+        run_wp_install()?;
+    }
+
+    let positional: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).collect();
+    if positional.len() == 2 && positional[0] == "search-replace" {
+        let url = project.url.clone().ok_or_else(|| {
+            "This project has no Burd domain. Pass the replacement explicitly: \
+             burd wp search-replace <old> <new>"
+                .to_string()
+        })?;
+        println!("Replacing {} with {}", positional[1], url);
+        args.push(url);
+    }
+
+    if wp_cli::overwrites_database(&args) {
+        if let (Some(instance), Some(database)) = (&project.db_instance, &project.database) {
+            if is_database_service(instance.service_type)
+                && ProcessManager::new().get_status(instance).running
+            {
+                let reason = format!("before wp {}", args.join(" "));
+                take_safety_snapshot(&config, instance, database, &reason)?;
+            }
+        }
+    }
+
+    let mut cmd = wp_cli::command(&project, &args)?;
+    vault::inject(&mut cmd, &config.vault, &project.root);
+    let status = cmd
+        .status()
+        .map_err(|e| format!("Failed to execute wp-cli: {}", e))?;
+
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
+    Ok(())
+}
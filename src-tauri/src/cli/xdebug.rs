@@ -0,0 +1,92 @@
+//! `burd xdebug on|off|profile|status [NAME]` — Xdebug for a PHP instance.
+//!
+//! Resolves NAME the same way as `burd start`. Changing the mode goes through
+//! PUT /instances/{id}/xdebug, which rewrites the instance's php.ini
+//! directives and restarts it when it's running.
+
+use serde::Deserialize;
+
+use crate::api_client::BurdApiClient;
+use crate::cli::lifecycle::resolve_instance;
+use crate::cli::remote;
+use crate::config::ServiceType;
+use crate::services::php_ini::XdebugMode;
+
+/// Xdebug status as the API returns it
+#[derive(Debug, Deserialize)]
+struct XdebugStatus {
+    mode: XdebugMode,
+    client_port: u16,
+    output_dir: Option<String>,
+    restarted: bool,
+}
+
+pub fn run_xdebug(mode: &str, name: Option<String>) -> Result<(), String> {
+    let mode = match mode {
+        "status" => None,
+        other => Some(XdebugMode::parse(other)?),
+    };
+
+    let config = remote::load_config()?;
+    let instance = resolve_instance(&config, name.as_deref())?;
+    if instance.service_type != ServiceType::FrankenPHP {
+        return Err(format!(
+            "'{}' is a {} instance; Xdebug is only available for PHP instances.",
+            instance.name,
+            instance.service_type.display_name()
+        ));
+    }
+
+    let client = BurdApiClient::new();
+    if !client.is_available() {
+        return Err(
+            "Burd app isn't running. Open Burd or run `burd setup`, then try again.".to_string(),
+        );
+    }
+
+    let path = format!("/instances/{}/xdebug", instance.id);
+    let response = match mode {
+        Some(mode) => client.put(&path, &serde_json::json!({ "mode": mode.as_str() }))?,
+        None => client.get(&path)?,
+    };
+    let status: XdebugStatus =
+        serde_json::from_str(&response).map_err(|e| format!("Unexpected Xdebug status: {}", e))?;
+
+    if mode.is_some() {
+        println!(
+            "✓ Xdebug {} for '{}'{}",
+            match status.mode {
+                XdebugMode::Off => "off",
+                XdebugMode::Debug => "on",
+                XdebugMode::Profile => "profiling",
+            },
+            instance.name,
+            if status.restarted { " (restarted)" } else { "" }
+        );
+    } else {
+        println!("Xdebug for '{}': {}", instance.name, status.mode.as_str());
+    }
+
+    match status.mode {
+        XdebugMode::Debug => println!(
+            "  Listen for connections in your IDE on port {}.",
+            status.client_port
+        ),
+        XdebugMode::Profile => {
+            println!(
+                "  Add XDEBUG_TRIGGER=1 to a request's query string or cookies to profile it."
+            );
+            if let Some(dir) = &status.output_dir {
+                println!("  Profiles are written to {}", dir);
+            }
+        }
+        XdebugMode::Off => {}
+    }
+    if mode.is_some() && !status.restarted && status.mode != XdebugMode::Off {
+        println!(
+            "  '{}' is stopped; it applies on the next start.",
+            instance.name
+        );
+    }
+    Ok(())
+}
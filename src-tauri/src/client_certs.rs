@@ -0,0 +1,297 @@
+//! Client certificates (mutual TLS)
+//!
+//! A domain can require visitors to present a client certificate before
+//! Caddy lets them through. Each such domain gets its own small CA, so a
+//! certificate issued for one domain never opens another. Burd issues one
+//! client certificate from that CA and exports it as PEM files and as a
+//! password-protected PKCS#12 file for browsers and the keychain.
+//!
+//! Certificates are generated with the `openssl` command line tool. Issuing
+//! again replaces the CA, which invalidates every certificate issued before.
+
+use crate::config::get_app_dir;
+use serde::Serialize;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const CA_CERT_FILE: &str = "client-ca.crt";
+const CA_KEY_FILE: &str = "client-ca.key";
+const CERT_FILE: &str = "client.crt";
+const KEY_FILE: &str = "client.key";
+const P12_FILE: &str = "client.p12";
+const PASSWORD_FILE: &str = "client.p12.password";
+
+/// Validity of the per-domain CA
+const CA_DAYS: u32 = 3650;
+
+/// Validity of issued client certificates (Apple's limit for leaf certificates)
+const CERT_DAYS: u32 = 825;
+
+/// An issued client certificate and where its files are
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientCert {
+    pub dir: PathBuf,
+    /// Client certificate (PEM), e.g. for `curl --cert`
+    pub cert: PathBuf,
+    /// Client private key (PEM), e.g. for `curl --key`
+    pub key: PathBuf,
+    /// Certificate and key bundled for browsers and the keychain
+    pub p12: PathBuf,
+    pub p12_password: String,
+}
+
+/// Directory holding every domain's client certificates
+pub fn get_client_certs_dir() -> PathBuf {
+    get_app_dir()
+        .unwrap_or_else(|_| PathBuf::from("/tmp"))
+        .join("client-certs")
+}
+
+/// Certificates are kept per domain ID, so renaming a domain keeps them
+fn domain_dir(domain_id: &str) -> PathBuf {
+    get_client_certs_dir().join(domain_id)
+}
+
+/// CA certificate Caddy trusts for a domain's client certificates
+pub fn ca_cert_path(domain_id: &str) -> PathBuf {
+    domain_dir(domain_id).join(CA_CERT_FILE)
+}
+
+/// Run openssl, returning its error output on failure
+fn openssl(args: &[&str]) -> Result<(), String> {
+    let output = Command::new("openssl")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run openssl: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "openssl {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn restrict(path: &Path) -> Result<(), String> {
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to set permissions on {}: {}", path.display(), e))
+}
+
+fn generate_password() -> String {
+    use rand::distr::Alphanumeric;
+    use rand::Rng;
+
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+fn client_cert_in(dir: &Path, p12_password: String) -> ClientCert {
+    ClientCert {
+        dir: dir.to_path_buf(),
+        cert: dir.join(CERT_FILE),
+        key: dir.join(KEY_FILE),
+        p12: dir.join(P12_FILE),
+        p12_password,
+    }
+}
+
+/// Create a fresh CA and client certificate for `domain` in `dir`
+fn issue_in(dir: &Path, domain: &str) -> Result<ClientCert, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let ca_cert = path_str(&dir.join(CA_CERT_FILE));
+    let ca_key = path_str(&dir.join(CA_KEY_FILE));
+    let csr = dir.join("client.csr");
+    let ext = dir.join("client.ext");
+    let cert = client_cert_in(dir, generate_password());
+    let (cert_path, key_path, p12_path) = (
+        path_str(&cert.cert),
+        path_str(&cert.key),
+        path_str(&cert.p12),
+    );
+
+    openssl(&[
+        "req",
+        "-x509",
+        "-newkey",
+        "rsa:2048",
+        "-nodes",
+        "-keyout",
+        &ca_key,
+        "-out",
+        &ca_cert,
+        "-days",
+        &CA_DAYS.to_string(),
+        "-subj",
+        &format!("/O=Burd/CN=Burd Client CA ({})", domain),
+        "-addext",
+        "basicConstraints=critical,CA:TRUE",
+        "-addext",
+        "keyUsage=critical,keyCertSign,cRLSign",
+    ])?;
+    openssl(&[
+        "req",
+        "-newkey",
+        "rsa:2048",
+        "-nodes",
+        "-keyout",
+        &key_path,
+        "-out",
+        &path_str(&csr),
+        "-subj",
+        &format!("/O=Burd/CN=Burd client for {}", domain),
+    ])?;
+    fs::write(
+        &ext,
+        "basicConstraints=CA:FALSE\nkeyUsage=critical,digitalSignature,keyEncipherment\nextendedKeyUsage=clientAuth\n",
+    )
+    .map_err(|e| format!("Failed to write {}: {}", ext.display(), e))?;
+    let signed = openssl(&[
+        "x509",
+        "-req",
+        "-in",
+        &path_str(&csr),
+        "-CA",
+        &ca_cert,
+        "-CAkey",
+        &ca_key,
+        "-CAcreateserial",
+        "-out",
+        &cert_path,
+        "-days",
+        &CERT_DAYS.to_string(),
+        "-extfile",
+        &path_str(&ext),
+    ]);
+    let _ = fs::remove_file(&csr);
+    let _ = fs::remove_file(&ext);
+    signed?;
+
+    // The keychain can't read OpenSSL 3's default PKCS#12 encryption, so ask
+    // for the legacy algorithms; LibreSSL (macOS's openssl) uses them anyway
+    // and doesn't know the flag.
+    let password_arg = format!("pass:{}", cert.p12_password);
+    let p12_args = [
+        "pkcs12",
+        "-export",
+        "-inkey",
+        &key_path,
+        "-in",
+        &cert_path,
+        "-certfile",
+        &ca_cert,
+        "-name",
+        domain,
+        "-out",
+        &p12_path,
+        "-passout",
+        &password_arg,
+    ];
+    if openssl(&[&p12_args[..], &["-legacy"]].concat()).is_err() {
+        openssl(&p12_args)?;
+    }
+
+    let password_file = dir.join(PASSWORD_FILE);
+    fs::write(&password_file, &cert.p12_password)
+        .map_err(|e| format!("Failed to write {}: {}", password_file.display(), e))?;
+    for private in [
+        dir.join(CA_KEY_FILE),
+        cert.key.clone(),
+        cert.p12.clone(),
+        password_file,
+    ] {
+        restrict(&private)?;
+    }
+
+    Ok(cert)
+}
+
+fn load_in(dir: &Path) -> Option<ClientCert> {
+    if !dir.join(CA_CERT_FILE).exists() || !dir.join(CERT_FILE).exists() {
+        return None;
+    }
+    let password = fs::read_to_string(dir.join(PASSWORD_FILE)).ok()?;
+    Some(client_cert_in(dir, password.trim().to_string()))
+}
+
+/// Issue a new client certificate for a domain (`domain` is the full name,
+/// e.g. "admin.burd", used in the certificate subjects)
+///
+/// Replaces the domain's CA, so certificates issued earlier stop working.
+pub fn issue(domain_id: &str, domain: &str) -> Result<ClientCert, String> {
+    let dir = domain_dir(domain_id);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove old client certificates: {}", e))?;
+    }
+    issue_in(&dir, domain)
+}
+
+/// The domain's current client certificate, if one was issued
+pub fn load(domain_id: &str) -> Option<ClientCert> {
+    load_in(&domain_dir(domain_id))
+}
+
+/// The domain's client certificate, issuing one if there is none yet
+pub fn ensure(domain_id: &str, domain: &str) -> Result<ClientCert, String> {
+    match load(domain_id) {
+        Some(cert) => Ok(cert),
+        None => issue(domain_id, domain),
+    }
+}
+
+/// Delete a domain's CA and client certificate
+pub fn remove(domain_id: &str) -> Result<(), String> {
+    let dir = domain_dir(domain_id);
+    if !dir.exists() {
+        return Ok(());
+    }
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_issue_client_cert() {
+        let temp = TempDir::new().unwrap();
+        let cert = issue_in(temp.path(), "admin.burd").unwrap();
+
+        assert_eq!(cert.p12_password.len(), 16);
+        assert!(cert.p12.exists());
+        let mode = fs::metadata(&cert.key).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // The client certificate chains to the domain's CA
+        let verify = Command::new("openssl")
+            .args(["verify", "-purpose", "sslclient", "-CAfile"])
+            .arg(temp.path().join(CA_CERT_FILE))
+            .arg(&cert.cert)
+            .output()
+            .unwrap();
+        assert!(verify.status.success());
+
+        let loaded = load_in(temp.path()).unwrap();
+        assert_eq!(loaded.p12_password, cert.p12_password);
+        assert_eq!(loaded.cert, cert.cert);
+    }
+
+    #[test]
+    fn test_load_without_certificate() {
+        let temp = TempDir::new().unwrap();
+        assert!(load_in(temp.path()).is_none());
+    }
+}
@@ -0,0 +1,200 @@
+//! Scheduled backup commands
+//!
+//! Settings, history, manual runs and restores of the config and data
+//! backups, and exporting the config to move it to another Mac.
+
+use crate::backup::{self, BackupInfo, BackupStatus, InstanceBackup};
+use crate::client_certs;
+use crate::commands::auto_trust_ca_if_needed;
+use crate::config::{BackupSettings, Domain, ServiceType};
+use crate::config_export::{self, ConfigExport, ConfigImportResult};
+use crate::error::LockExt;
+use crate::lock;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+use uuid::Uuid;
+
+use super::AppState;
+
+/// An instance that can be included in backups
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInstanceOption {
+    pub id: String,
+    pub name: String,
+    pub service_type: ServiceType,
+    pub selected: bool,
+}
+
+/// Everything the backup settings card shows
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupOverview {
+    pub settings: BackupSettings,
+    pub status: BackupStatus,
+    pub backups: Vec<BackupInfo>,
+    /// Why the backup list couldn't be read (e.g. the disk isn't connected)
+    pub destination_error: Option<String>,
+    pub instances: Vec<BackupInstanceOption>,
+}
+
+/// Get the backup settings, last outcome and existing backups
+#[tauri::command]
+pub fn get_backup_overview(state: State<'_, AppState>) -> Result<BackupOverview, String> {
+    let config = lock!(state.config_store)?.load()?;
+
+    let (backups, destination_error) = if config.backup.destination.is_some() {
+        match backup::list_backups(&config.backup) {
+            Ok(backups) => (backups, None),
+            Err(e) => (Vec::new(), Some(e)),
+        }
+    } else {
+        (Vec::new(), None)
+    };
+
+    let instances = config
+        .instances
+        .iter()
+        .map(|i| BackupInstanceOption {
+            id: i.id.to_string(),
+            name: i.name.clone(),
+            service_type: i.service_type,
+            selected: config.backup.instance_ids.contains(&i.id),
+        })
+        .collect();
+
+    Ok(BackupOverview {
+        settings: config.backup,
+        status: backup::load_status(),
+        backups,
+        destination_error,
+        instances,
+    })
+}
+
+/// List the backups in the configured folder, newest first
+#[tauri::command]
+pub fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>, String> {
+    let config = lock!(state.config_store)?.load()?;
+    backup::list_backups(&config.backup)
+}
+
+/// Update the backup schedule, folder, retention and instance selection
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn update_backup_settings(
+    enabled: bool,
+    destination: Option<String>,
+    interval_hours: u32,
+    schedule: Option<String>,
+    retention: usize,
+    instance_ids: Vec<String>,
+    compress: bool,
+    state: State<'_, AppState>,
+) -> Result<BackupSettings, String> {
+    let instance_ids = instance_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id).map_err(|_| format!("Invalid instance ID: {}", id)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let settings = backup::validate_settings(BackupSettings {
+        enabled,
+        destination,
+        interval_hours,
+        schedule,
+        retention,
+        instance_ids,
+        compress,
+    })?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_backup_settings(settings.clone())?;
+    Ok(settings)
+}
+
+/// Run a backup now, regardless of the schedule
+#[tauri::command]
+pub async fn run_backup_now(state: State<'_, AppState>) -> Result<BackupInfo, String> {
+    let (config, running) = backup::current_state(&state)?;
+
+    tokio::task::spawn_blocking(move || backup::run_backup(&config, &running))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Restore an instance's data from a backup
+#[tauri::command]
+pub async fn restore_backup(
+    backup_id: String,
+    instance_id: String,
+    state: State<'_, AppState>,
+) -> Result<InstanceBackup, String> {
+    let uuid = Uuid::parse_str(&instance_id).map_err(|_| "Invalid instance ID")?;
+    let config = lock!(state.config_store)?.load()?;
+    let instance = config
+        .instances
+        .into_iter()
+        .find(|i| i.id == uuid)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let running = lock!(state.process_manager)?.get_status(&instance).running;
+
+    tokio::task::spawn_blocking(move || {
+        backup::restore_backup(&config.backup, &backup_id, &instance, running)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Export instances, domains, stacks, parked directories and tunnels to a
+/// `.tar.gz` archive at `path`
+#[tauri::command]
+pub fn export_config(path: String, state: State<'_, AppState>) -> Result<ConfigExport, String> {
+    let config = lock!(state.config_store)?.load()?;
+    config_export::export_archive(&config, Path::new(&path))
+}
+
+/// Import an exported config archive and register the imported domains
+///
+/// With `dry_run`, reports what would be imported without changing anything.
+#[tauri::command]
+pub async fn import_config(
+    path: String,
+    dry_run: bool,
+    state: State<'_, AppState>,
+) -> Result<ConfigImportResult, String> {
+    let (result, config) = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let mut config = config_store.load()?;
+        let result = config_export::import_archive(&mut config, Path::new(&path), dry_run)?;
+        if !dry_run {
+            config_store.save(&config)?;
+        }
+        (result, config)
+    };
+    if dry_run {
+        return Ok(result);
+    }
+
+    let imported: Vec<&Domain> = config
+        .domains
+        .iter()
+        .filter(|d| result.domains.contains(&d.subdomain))
+        .collect();
+    for domain in &imported {
+        if domain.require_client_cert {
+            client_certs::ensure(&domain.id.to_string(), &domain.full_domain(&config.tld))?;
+        }
+    }
+
+    {
+        let proxy = state.proxy_server.lock().await;
+        for domain in &imported {
+            proxy.register_domain(domain, &config)?;
+        }
+    }
+    if imported.iter().any(|d| d.ssl_enabled) {
+        let _ = auto_trust_ca_if_needed();
+    }
+
+    Ok(result)
+}
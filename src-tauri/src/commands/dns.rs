@@ -2,11 +2,16 @@
 //!
 //! Handles DNS server, resolver, and network status commands.
 
+use crate::config::{DnsRecord, DnsRecordType};
+use crate::dns::DnsQuery;
+use crate::dns_check::{self, DnsSelfTest};
+use crate::emulation::{self, Emulation, EmulationStatus};
 use crate::error::LockExt;
 use crate::lock; // Shared macro from error.rs
-use crate::resolver;
+use crate::platform;
 use serde::Serialize;
 use tauri::State;
+use uuid::Uuid;
 
 use super::AppState;
 
@@ -71,7 +76,7 @@ pub async fn get_network_status(state: State<'_, AppState>) -> Result<NetworkSta
         (proxy.is_running(), proxy.port(), route_infos)
     };
 
-    let resolver_installed = resolver::is_installed(&tld);
+    let resolver_installed = platform::dns_resolver().is_installed(&tld);
 
     Ok(NetworkStatus {
         dns_running,
@@ -90,57 +95,91 @@ pub struct ResolverStatus {
     pub installed: bool,
     pub port: Option<u16>,
     pub tld: String,
+    /// Extra TLDs without a resolver file
+    pub missing_tlds: Vec<String>,
 }
 
 #[tauri::command]
 pub fn get_resolver_status(state: State<'_, AppState>) -> Result<ResolverStatus, String> {
-    // Read TLD from config so it reflects recent changes
-    let tld = {
+    // Read TLDs from config so they reflect recent changes
+    let (tld, extra_tlds) = {
         let config_store = lock!(state.config_store)?;
-        config_store.load()?.tld.clone()
+        let config = config_store.load()?;
+        (config.tld, config.extra_tlds)
     };
 
-    let installed = resolver::is_installed(&tld);
-    let port = resolver::get_current_config(&tld).map(|c| c.port);
+    let installed = platform::dns_resolver().is_installed(&tld);
+    let port = platform::dns_resolver()
+        .current_config(&tld)
+        .map(|c| c.port);
+    let missing_tlds = extra_tlds
+        .into_iter()
+        .filter(|t| !platform::dns_resolver().is_installed(t))
+        .collect();
 
     Ok(ResolverStatus {
         installed,
         port,
         tld,
+        missing_tlds,
     })
 }
 
-/// Install the macOS resolver file (requires admin privileges)
+/// Install the macOS resolver files for every TLD (requires admin privileges)
 #[tauri::command]
 pub fn install_resolver(state: State<'_, AppState>) -> Result<(), String> {
     // Read from config so it reflects recent changes
-    let (dns_port, tld) = {
+    let (dns_port, tlds) = {
         let config_store = lock!(state.config_store)?;
         let config = config_store.load()?;
-        (config.dns_port, config.tld.clone())
+        (config.dns_port, config.tlds())
     };
 
-    resolver::install(&tld, dns_port)?;
-    resolver::flush_dns_cache()?;
+    for tld in &tlds {
+        platform::dns_resolver().install(tld, dns_port)?;
+    }
+    platform::dns_resolver().flush_cache()?;
 
     Ok(())
 }
 
-/// Uninstall the macOS resolver file (requires admin privileges)
+/// Uninstall the macOS resolver files for every TLD (requires admin privileges)
 #[tauri::command]
 pub fn uninstall_resolver(state: State<'_, AppState>) -> Result<(), String> {
-    // Read TLD from config so it reflects recent changes
-    let tld = {
+    // Read TLDs from config so they reflect recent changes
+    let tlds = {
         let config_store = lock!(state.config_store)?;
-        config_store.load()?.tld.clone()
+        config_store.load()?.tlds()
     };
 
-    resolver::uninstall(&tld)?;
-    resolver::flush_dns_cache()?;
+    for tld in &tlds {
+        platform::dns_resolver().uninstall(tld)?;
+    }
+    platform::dns_resolver().flush_cache()?;
 
     Ok(())
 }
 
+/// Check each hop of `.<tld>` resolution and report the first broken one
+///
+/// Tests `domain` if given, otherwise the first configured domain.
+#[tauri::command]
+pub async fn run_dns_self_test(
+    domain: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<DnsSelfTest, String> {
+    let (tld, dns_port, domain) = {
+        let config_store = lock!(state.config_store)?;
+        let config = config_store.load()?;
+        let domain = domain.unwrap_or_else(|| dns_check::default_test_domain(&config));
+        (config.tld, config.dns_port, domain)
+    };
+
+    tokio::task::spawn_blocking(move || dns_check::run_self_test(&tld, dns_port, &domain))
+        .await
+        .map_err(|e| format!("Task error: {}", e))
+}
+
 // ============================================================================
 // DNS Server Commands
 // ============================================================================
@@ -167,3 +206,196 @@ pub fn restart_dns_server(state: State<'_, AppState>) -> Result<(), String> {
     dns.stop();
     dns.start()
 }
+
+/// The DNS query log
+#[derive(Debug, Serialize)]
+pub struct DnsQueryLog {
+    pub enabled: bool,
+    /// Newest first
+    pub queries: Vec<DnsQuery>,
+}
+
+/// Get the DNS queries answered while the query log was on, optionally only
+/// those for names containing `filter`
+#[tauri::command]
+pub fn get_dns_queries(
+    filter: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<DnsQueryLog, String> {
+    let dns = lock!(state.dns_server)?;
+    let filter = filter
+        .map(|f| f.trim().trim_end_matches('.').to_lowercase())
+        .filter(|f| !f.is_empty());
+    let queries = dns
+        .recent_queries()
+        .into_iter()
+        .filter(|q| filter.as_ref().is_none_or(|f| q.name.contains(f.as_str())))
+        .collect();
+    Ok(DnsQueryLog {
+        enabled: dns.query_log_enabled(),
+        queries,
+    })
+}
+
+/// Turn the DNS query log on or off
+#[tauri::command]
+pub fn set_dns_query_log(enabled: bool, state: State<'_, AppState>) -> Result<(), String> {
+    lock!(state.dns_server)?.set_query_log(enabled);
+    Ok(())
+}
+
+/// Forget the logged DNS queries
+#[tauri::command]
+pub fn clear_dns_queries(state: State<'_, AppState>) -> Result<(), String> {
+    lock!(state.dns_server)?.clear_queries();
+    Ok(())
+}
+
+// ============================================================================
+// DNS Record Commands
+// ============================================================================
+
+/// List the custom DNS records
+#[tauri::command]
+pub fn list_dns_records(state: State<'_, AppState>) -> Result<Vec<DnsRecord>, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.dns_records)
+}
+
+/// Add an A, AAAA or CNAME record under the TLD
+///
+/// The DNS server answers with it right away.
+#[tauri::command]
+pub fn add_dns_record(
+    name: String,
+    record_type: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<DnsRecord, String> {
+    let record_type = DnsRecordType::parse(&record_type)?;
+    let (record, records) = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let config = config_store.load()?;
+        let record = DnsRecord::new(&name, record_type, &value, &config.tld)?;
+        record.check_conflicts(&config.dns_records)?;
+
+        let mut records = config.dns_records;
+        records.push(record.clone());
+        config_store.update_dns_records(records.clone())?;
+        (record, records)
+    };
+
+    lock!(state.dns_server)?.set_records(records);
+    Ok(record)
+}
+
+/// Remove a custom DNS record
+#[tauri::command]
+pub fn remove_dns_record(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid record ID")?;
+
+    let records = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let mut records = config_store.load()?.dns_records;
+        let before = records.len();
+        records.retain(|r| r.id != uuid);
+        if records.len() == before {
+            return Err("DNS record not found".to_string());
+        }
+        config_store.update_dns_records(records.clone())?;
+        records
+    };
+
+    lock!(state.dns_server)?.set_records(records);
+    Ok(())
+}
+
+// ============================================================================
+// SaaS Emulation Commands
+// ============================================================================
+
+/// List the SaaS emulation presets and which are enabled
+#[tauri::command]
+pub fn list_emulations(state: State<'_, AppState>) -> Result<Vec<EmulationStatus>, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(emulation::statuses(&config_store.load()?))
+}
+
+/// Point a preset's hosts at an instance (default: the first of its service)
+///
+/// Installs a resolver file per host, which asks for the admin password
+/// unless the helper is installed.
+#[tauri::command]
+pub async fn enable_emulation(
+    preset: String,
+    instance_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<EmulationStatus, String> {
+    let preset = emulation::find_preset(&preset)?;
+    let instance_id = instance_id
+        .map(|id| Uuid::parse_str(&id).map_err(|_| "Invalid instance ID".to_string()))
+        .transpose()?;
+
+    let config = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let config = config_store.load()?;
+        let instance_id = emulation::pick_instance(&config, preset, instance_id)?;
+
+        let mut emulations = config.emulations;
+        emulations.retain(|e| e.preset != preset.id);
+        emulations.push(Emulation {
+            preset: preset.id.to_string(),
+            instance_id,
+        });
+        config_store.update_emulations(emulations)?;
+        config_store.load()?
+    };
+
+    lock!(state.dns_server)?.set_emulated_hosts(emulation::emulated_hosts(&config));
+    {
+        let proxy = state.proxy_server.lock().await;
+        for route in emulation::proxy_routes(&config) {
+            proxy.register_route(&route.host, route.port, &route.route_id, true)?;
+        }
+    }
+
+    let dns_port = config.dns_port;
+    tokio::task::spawn_blocking(move || emulation::install_resolvers(preset, dns_port))
+        .await
+        .map_err(|e| format!("Task error: {}", e))??;
+
+    emulation::statuses(&config)
+        .into_iter()
+        .find(|s| s.preset.id == preset.id)
+        .ok_or_else(|| "Emulation not found".to_string())
+}
+
+/// Stop emulating a preset's hosts and remove their resolver files
+#[tauri::command]
+pub async fn disable_emulation(preset: String, state: State<'_, AppState>) -> Result<(), String> {
+    let preset = emulation::find_preset(&preset)?;
+
+    let config = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let mut emulations = config_store.load()?.emulations;
+        emulations.retain(|e| e.preset != preset.id);
+        config_store.update_emulations(emulations)?;
+        config_store.load()?
+    };
+
+    lock!(state.dns_server)?.set_emulated_hosts(emulation::emulated_hosts(&config));
+    if preset.proxied {
+        let proxy = state.proxy_server.lock().await;
+        for host in preset.hosts {
+            proxy.unregister_route(host)?;
+        }
+    }
+
+    tokio::task::spawn_blocking(move || emulation::uninstall_resolvers(preset))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
@@ -3,16 +3,23 @@
 //! Handles domain routing configuration for instances, ports, and static files.
 
 use crate::caddy;
-use crate::commands::auto_trust_ca_if_needed;
-use crate::config::{DomainSource, DomainTarget};
+use crate::client_certs::{self, ClientCert};
+use crate::commands::{auto_trust_ca_if_needed, suggest_env_sync};
+use crate::config::{
+    Config, ConfigStore, Domain, DomainConflictResolution, DomainImportPreview, DomainImportResult,
+    DomainSource, DomainTarget,
+};
+use crate::domain_export;
+use crate::env_sync;
 use crate::error::LockExt;
-use crate::launchd;
 use crate::lock; // Shared macro from error.rs
 use crate::park;
+use crate::platform;
+use crate::traffic::{self, RequestRecord, MAX_REQUESTS_PER_DOMAIN};
 use crate::validation;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
 use super::AppState;
@@ -34,9 +41,16 @@ pub struct DomainInfo {
     pub static_path: Option<String>, // path for static file server
     pub static_browse: Option<bool>, // directory listing enabled
     pub ssl_enabled: bool,           // whether SSL/HTTPS is enabled
+    pub force_https: bool,           // redirect HTTP to HTTPS (with SSL)
+    pub hsts: bool,                  // send Strict-Transport-Security (with SSL)
+    pub require_client_cert: bool,   // require a Burd-issued client certificate (with SSL)
     pub created_at: String,
     pub source: String,               // "manual", "parked", or "isolated"
     pub project_type: Option<String>, // For parked: "Laravel", "WordPress", etc.
+    /// Raw Caddyfile directives added to the site
+    pub custom_directives: Option<String>,
+    /// Extra TLD the domain uses instead of the primary one
+    pub tld: Option<String>,
 }
 
 /// Create domain target - instance, port, or static files
@@ -81,14 +95,15 @@ pub async fn set_instance_domain(
     id: String,
     domain: Option<String>,
     enabled: bool,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
 
     // Get old instance info
-    let old_instance = {
+    let (old_instance, slugs) = {
         let config_store = lock!(state.config_store)?;
-        config_store.get_instance(uuid)?
+        (config_store.get_instance(uuid)?, config_store.load()?.slugs)
     };
 
     // Check if process is running
@@ -101,22 +116,28 @@ pub async fn set_instance_domain(
     if is_running && old_instance.domain_enabled {
         let proxy = state.proxy_server.lock().await;
         let tld = proxy.tld();
-        let _ = proxy.unregister_route(&old_instance.full_domain(tld));
+        let _ = proxy.unregister_route(&old_instance.full_domain(tld, &slugs));
     }
 
     // Update config
-    let new_instance = {
+    let (new_instance, config) = {
         let config_store = lock!(state.config_store)?;
-        config_store.update_instance_domain(uuid, domain, enabled)?
+        let instance = config_store.update_instance_domain(uuid, domain, enabled)?;
+        (instance, config_store.load()?)
     };
 
+    suggest_env_sync(
+        &app,
+        env_sync::plan_instance_change(&config, &old_instance, &new_instance, &config.tld),
+    );
+
     // Register new route if running and enabled
     // Note: Instance domain routing uses SSL enabled by default
     if is_running && new_instance.domain_enabled {
         let proxy = state.proxy_server.lock().await;
         let tld = proxy.tld();
         proxy.register_route(
-            &new_instance.full_domain(tld),
+            &new_instance.full_domain(tld, &config.slugs),
             new_instance.port,
             &new_instance.id.to_string(),
             true, // SSL enabled by default for legacy instance domain routing
@@ -184,12 +205,13 @@ pub fn list_domains(state: State<'_, AppState>) -> Result<Vec<DomainInfo>, Strin
                         .find(|pd| pd.id == *parked_dir_id)
                         .and_then(|pd| {
                             // Scan directory to find the project matching this subdomain
-                            park::scan_directory(Path::new(&pd.path))
+                            park::scan_directory(Path::new(&pd.path), pd.depth)
                                 .ok()
                                 .and_then(|projects| {
-                                    projects
-                                        .into_iter()
-                                        .find(|p| park::generate_subdomain(&p.name) == d.subdomain)
+                                    projects.into_iter().find(|p| {
+                                        park::generate_subdomain(&p.name, &config.slugs)
+                                            == d.subdomain
+                                    })
                                 })
                                 .map(|p| p.project_type.as_str().to_string())
                         });
@@ -202,6 +224,7 @@ pub fn list_domains(state: State<'_, AppState>) -> Result<Vec<DomainInfo>, Strin
                 id: d.id.to_string(),
                 subdomain: d.subdomain.clone(),
                 full_domain: d.full_domain(&tld),
+                tld: d.tld.clone(),
                 target_type,
                 target_value,
                 target_name,
@@ -209,6 +232,10 @@ pub fn list_domains(state: State<'_, AppState>) -> Result<Vec<DomainInfo>, Strin
                 static_path,
                 static_browse,
                 ssl_enabled: d.ssl_enabled,
+                force_https: d.force_https,
+                hsts: d.hsts,
+                require_client_cert: d.require_client_cert,
+                custom_directives: d.custom_directives.clone(),
                 created_at: d.created_at.to_rfc3339(),
                 source,
                 project_type,
@@ -335,6 +362,7 @@ pub async fn create_domain(
         id: domain.id.to_string(),
         subdomain: domain.subdomain.clone(),
         full_domain: domain.full_domain(&tld),
+        tld: domain.tld.clone(),
         target_type,
         target_value,
         target_name,
@@ -342,6 +370,10 @@ pub async fn create_domain(
         static_path,
         static_browse,
         ssl_enabled: domain.ssl_enabled,
+        force_https: domain.force_https,
+        hsts: domain.hsts,
+        require_client_cert: domain.require_client_cert,
+        custom_directives: domain.custom_directives.clone(),
         created_at: domain.created_at.to_rfc3339(),
         source: "manual".to_string(), // Newly created domains are always manual
         project_type: None,
@@ -353,6 +385,7 @@ pub async fn create_domain(
 pub async fn update_domain(
     id: String,
     request: UpdateDomainRequest,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<DomainInfo, String> {
     let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
@@ -421,13 +454,22 @@ pub async fn update_domain(
     };
 
     // Update domain and get TLD + instances in one lock acquisition
-    let (domain, tld, instances) = {
+    let (domain, tld, instances, config) = {
         let config_store = lock!(state.config_store)?;
         let domain = config_store.update_domain(domain_id, request.subdomain, new_target)?;
         let config = config_store.load()?;
-        (domain, config.tld.clone(), config.instances.clone())
+        (domain, config.tld.clone(), config.instances.clone(), config)
     };
 
+    suggest_env_sync(
+        &app,
+        env_sync::plan_host_change(
+            &config,
+            &old_domain.full_domain(&tld),
+            &domain.full_domain(&tld),
+        ),
+    );
+
     // Get target port using cached instances
     let target_port = domain.get_target_port(&instances);
 
@@ -494,6 +536,7 @@ pub async fn update_domain(
         id: domain.id.to_string(),
         subdomain: domain.subdomain,
         full_domain,
+        tld: domain.tld.clone(),
         target_type,
         target_value,
         target_name,
@@ -501,6 +544,10 @@ pub async fn update_domain(
         static_path,
         static_browse,
         ssl_enabled: domain.ssl_enabled,
+        force_https: domain.force_https,
+        hsts: domain.hsts,
+        require_client_cert: domain.require_client_cert,
+        custom_directives: domain.custom_directives.clone(),
         created_at: domain.created_at.to_rfc3339(),
         source: "manual".to_string(), // Updated domains are treated as manual
         project_type: None,
@@ -527,7 +574,10 @@ pub async fn delete_domain(id: String, state: State<'_, AppState>) -> Result<(),
 
     // Delete domain
     let config_store = lock!(state.config_store)?;
-    config_store.delete_domain(domain_id)
+    config_store.delete_domain(domain_id)?;
+
+    // Its client certificates can't be used for anything else
+    client_certs::remove(&domain_id.to_string())
 }
 
 /// Reinitialize SSL certificate for a specific domain
@@ -539,8 +589,8 @@ pub async fn reinit_domain_ssl(id: String, _state: State<'_, AppState>) -> Resul
 
     // With Caddy, certificates are auto-managed
     // Just restart the daemon to force a config reload
-    if launchd::is_installed() {
-        tokio::task::spawn_blocking(launchd::restart)
+    if platform::proxy_daemon().is_installed() {
+        tokio::task::spawn_blocking(|| platform::proxy_daemon().restart())
             .await
             .map_err(|e| format!("Task error: {}", e))??;
     }
@@ -645,6 +695,7 @@ pub async fn update_domain_ssl(
         id: domain.id.to_string(),
         subdomain: domain.subdomain,
         full_domain,
+        tld: domain.tld.clone(),
         target_type,
         target_value,
         target_name,
@@ -652,44 +703,63 @@ pub async fn update_domain_ssl(
         static_path,
         static_browse,
         ssl_enabled: domain.ssl_enabled,
+        force_https: domain.force_https,
+        hsts: domain.hsts,
+        require_client_cert: domain.require_client_cert,
+        custom_directives: domain.custom_directives.clone(),
         created_at: domain.created_at.to_rfc3339(),
         source: "manual".to_string(), // SSL toggle is for manual domains
         project_type: None,
     })
 }
 
-/// Update the Caddy configuration for a specific domain with custom content
+/// Move a domain to one of the configured TLDs
+///
+/// `tld` is one of the extra TLDs, or `None` for the primary one. Returns the
+/// domain's new full name.
 #[tauri::command]
-pub fn update_domain_config(
+pub async fn set_domain_tld(
     id: String,
-    config: String,
+    tld: Option<String>,
+    app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<String, String> {
     let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+    let tld = tld
+        .map(|t| t.trim().trim_start_matches('.').to_lowercase())
+        .filter(|t| !t.is_empty());
 
-    let config_store = lock!(state.config_store)?;
-    let domain = config_store.get_domain(domain_id)?;
-    let app_config = config_store.load()?;
-    let full_domain = domain.full_domain(&app_config.tld);
+    let (old_domain, domain, config) = {
+        let config_store = lock!(state.config_store)?;
+        let old_domain = config_store.get_domain(domain_id)?;
+        let domain = config_store.set_domain_tld(domain_id, tld)?;
+        (old_domain, domain, config_store.load()?)
+    };
+    let old_full_domain = old_domain.full_domain(&config.tld);
+    let full_domain = domain.full_domain(&config.tld);
+    if old_full_domain == full_domain {
+        return Ok(full_domain);
+    }
 
-    // Write the custom config to the domain's .caddy file
-    let filepath = caddy::get_domain_filepath(&full_domain);
-    caddy::write_domain_config_raw(&filepath, &config)?;
+    {
+        let proxy = state.proxy_server.lock().await;
+        let _ = proxy.unregister_route(&old_full_domain);
+        proxy.register_domain(&domain, &config)?;
+    }
 
-    Ok(())
-}
+    suggest_env_sync(
+        &app,
+        env_sync::plan_host_change(&config, &old_full_domain, &full_domain),
+    );
 
-/// Get the Caddy configuration for a specific domain
-#[tauri::command]
-pub fn get_domain_config(id: String, state: State<'_, AppState>) -> Result<String, String> {
-    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+    Ok(full_domain)
+}
 
-    let config_store = lock!(state.config_store)?;
-    let config = config_store.load()?;
-    let domain = config_store.get_domain(domain_id)?;
+/// Build the Caddy route for a domain, including its HTTPS options and
+/// custom directives
+fn domain_route(config: &Config, domain: &Domain) -> Result<caddy::RouteEntry, String> {
     let full_domain = domain.full_domain(&config.tld);
 
-    // Build the RouteEntry for this domain
     let route = match &domain.target {
         DomainTarget::Instance(instance_id) => {
             let instance = config
@@ -719,10 +789,185 @@ pub fn get_domain_config(id: String, state: State<'_, AppState>) -> Result<Strin
         ),
     };
 
+    Ok(route
+        .with_https_options(domain.force_https, domain.hsts)
+        .with_client_auth(caddy::client_ca_for(domain))
+        .with_custom_directives(domain.custom_directives.clone()))
+}
+
+/// Update the Caddy configuration for a specific domain
+///
+/// `force_https` / `hsts` change the domain's HTTPS options and regenerate its
+/// config file; `config` replaces the file with custom content.
+#[tauri::command]
+pub fn update_domain_config(
+    id: String,
+    config: Option<String>,
+    force_https: Option<bool>,
+    hsts: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+    if config.is_none() && force_https.is_none() && hsts.is_none() {
+        return Err("Nothing to update".to_string());
+    }
+
+    let config_store = lock!(state.config_store)?;
+
+    if force_https.is_some() || hsts.is_some() {
+        let domain =
+            config_store.update_domain_https_options(domain_id, force_https, hsts, None)?;
+        let app_config = config_store.load()?;
+        caddy::write_domain_file(&domain_route(&app_config, &domain)?)?;
+    }
+
+    if let Some(content) = config {
+        let domain = config_store.get_domain(domain_id)?;
+        let app_config = config_store.load()?;
+        let full_domain = domain.full_domain(&app_config.tld);
+
+        // Write the custom config to the domain's .caddy file
+        let filepath = caddy::get_domain_filepath(&full_domain);
+        caddy::write_domain_config_raw(&filepath, &content)?;
+    }
+
+    Ok(())
+}
+
+/// Validate and save a domain's custom Caddyfile directives
+///
+/// Blank directives clear them. The regenerated site config is checked with
+/// `caddy validate` before anything is saved, so a broken snippet never
+/// reaches the running proxy.
+pub(crate) fn apply_custom_directives(
+    config_store: &ConfigStore,
+    domain_id: Uuid,
+    directives: &str,
+) -> Result<Domain, String> {
+    let custom_directives = caddy::normalize_custom_directives(directives)?;
+    let app_config = config_store.load()?;
+    let domain = config_store.get_domain(domain_id)?;
+
+    let route =
+        domain_route(&app_config, &domain)?.with_custom_directives(custom_directives.clone());
+    caddy::validate_domain_config(&route)?;
+
+    let domain = config_store.update_domain_custom_directives(domain_id, custom_directives)?;
+    caddy::write_domain_file(&route)?;
+
+    Ok(domain)
+}
+
+/// Set a domain's custom Caddyfile directives (headers, redirects, basic auth)
+#[tauri::command]
+pub fn set_domain_custom_directives(
+    id: String,
+    directives: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    apply_custom_directives(&config_store, domain_id, &directives)?;
+
+    Ok(())
+}
+
+/// Require (or stop requiring) a client certificate for a domain
+///
+/// Enabling needs SSL on the domain and issues its client certificate if it
+/// has none yet, which is returned. Disabling keeps the certificate so it
+/// works again when the requirement is turned back on.
+#[tauri::command]
+pub fn set_domain_client_auth(
+    id: String,
+    required: bool,
+    state: State<'_, AppState>,
+) -> Result<Option<ClientCert>, String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let app_config = config_store.load()?;
+    let domain = config_store.get_domain(domain_id)?;
+
+    let cert = if required {
+        if !domain.ssl_enabled {
+            return Err("Enable SSL for this domain before requiring a client certificate".into());
+        }
+        let full_domain = domain.full_domain(&app_config.tld);
+        Some(client_certs::ensure(&domain_id.to_string(), &full_domain)?)
+    } else {
+        None
+    };
+
+    let domain = config_store.update_domain_https_options(domain_id, None, None, Some(required))?;
+    caddy::write_domain_file(&domain_route(&app_config, &domain)?)?;
+
+    Ok(cert)
+}
+
+/// Get a domain's client certificate, if one was issued
+#[tauri::command]
+pub fn get_client_cert(id: String) -> Result<Option<ClientCert>, String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+    Ok(client_certs::load(&domain_id.to_string()))
+}
+
+/// Issue a new client certificate for a domain
+///
+/// The domain gets a new CA, so every certificate issued before stops working.
+#[tauri::command]
+pub fn reissue_client_cert(id: String, state: State<'_, AppState>) -> Result<ClientCert, String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let app_config = config_store.load()?;
+    let domain = config_store.get_domain(domain_id)?;
+
+    let cert = client_certs::issue(&domain_id.to_string(), &domain.full_domain(&app_config.tld))?;
+
+    // Rewriting the domain file makes Caddy reload and pick up the new CA
+    caddy::write_domain_file(&domain_route(&app_config, &domain)?)?;
+
+    Ok(cert)
+}
+
+/// Get the Caddy configuration for a specific domain
+#[tauri::command]
+pub fn get_domain_config(id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let config = config_store.load()?;
+    let domain = config_store.get_domain(domain_id)?;
+    let route = domain_route(&config, &domain)?;
+
     // Generate and return the Caddy config
     Ok(caddy::generate_domain_config(&route))
 }
 
+/// Get the most recent requests served for a domain, newest first
+#[tauri::command]
+pub fn get_domain_requests(
+    id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RequestRecord>, String> {
+    let domain_id = Uuid::parse_str(&id).map_err(|_| "Invalid domain ID")?;
+
+    let config = lock!(state.config_store)?.load()?;
+    let domain = config
+        .domains
+        .iter()
+        .find(|d| d.id == domain_id)
+        .ok_or_else(|| format!("Domain {} not found", id))?;
+
+    traffic::recent_requests(
+        Some(&domain.full_domain(&config.tld)),
+        limit.unwrap_or(MAX_REQUESTS_PER_DOMAIN),
+    )
+}
+
 /// Reorder domains in the config (for drag-and-drop)
 #[tauri::command]
 pub async fn reorder_domains(
@@ -738,3 +983,78 @@ pub async fn reorder_domains(
     config_store.reorder_domains(domain_uuids)?;
     Ok(())
 }
+
+// ============================================================================
+// Export / Import Commands
+// ============================================================================
+
+/// Export domains as shareable JSON (all but parked domains when no IDs are given)
+#[tauri::command]
+pub fn export_domains(
+    domain_ids: Vec<String>,
+    created_by: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let ids = domain_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id).map_err(|_| format!("Invalid domain ID: {}", id)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let config = lock!(state.config_store)?.load()?;
+    let export = domain_export::export_domains(&config, &ids, created_by)?;
+
+    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize domains: {}", e))
+}
+
+/// Preview a domain import - validates and detects conflicts
+#[tauri::command]
+pub fn preview_domain_import(
+    config_json: String,
+    state: State<'_, AppState>,
+) -> Result<DomainImportPreview, String> {
+    let import = domain_export::parse_export(&config_json)?;
+    let config = lock!(state.config_store)?.load()?;
+    Ok(domain_export::preview_import(&config, import))
+}
+
+/// Import domains (after preview and conflict resolution) and register their routes
+#[tauri::command]
+pub async fn import_domains(
+    config_json: String,
+    conflict_resolutions: Vec<DomainConflictResolution>,
+    state: State<'_, AppState>,
+) -> Result<DomainImportResult, String> {
+    let import = domain_export::parse_export(&config_json)?;
+
+    let (result, config) = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let mut config = config_store.load()?;
+        let result = domain_export::apply_import(&mut config, &import, &conflict_resolutions)?;
+        config_store.save(&config)?;
+        (result, config)
+    };
+
+    let imported: Vec<&Domain> = config
+        .domains
+        .iter()
+        .filter(|d| result.domains_created.contains(&d.id))
+        .collect();
+    for domain in &imported {
+        if domain.require_client_cert {
+            client_certs::ensure(&domain.id.to_string(), &domain.full_domain(&config.tld))?;
+        }
+    }
+
+    {
+        let proxy = state.proxy_server.lock().await;
+        for domain in &imported {
+            proxy.register_domain(domain, &config)?;
+        }
+    }
+    if imported.iter().any(|d| d.ssl_enabled) {
+        let _ = auto_trust_ca_if_needed();
+    }
+
+    Ok(result)
+}
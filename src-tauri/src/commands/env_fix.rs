@@ -0,0 +1,35 @@
+//! .env fix commands
+//!
+//! Tauri commands for checking a linked project's .env against Burd services
+//! and applying the fixes the analyzer suggests, the GUI side of
+//! `burd env check` and `burd env fix --apply-all`.
+
+use crate::analyzer::{self, EnvFixResult, ProjectIssue};
+use crate::error::LockExt;
+use crate::lock; // Shared macro from error.rs
+use std::path::Path;
+use tauri::State;
+
+use super::AppState;
+
+/// Analyze a project against Burd's instances and domains
+///
+/// Issues carrying `env_edits` can be passed to `apply_env_fixes`.
+#[tauri::command]
+pub fn check_project_env(
+    project_path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectIssue>, String> {
+    let config = lock!(state.config_store)?.load()?;
+    let info = analyzer::analyze_with_burd_config(Path::new(&project_path), &config)?;
+    Ok(info.issues)
+}
+
+/// Apply the .env edits of the given issues in one write, backing up .env
+#[tauri::command]
+pub fn apply_env_fixes(
+    project_path: String,
+    issues: Vec<ProjectIssue>,
+) -> Result<EnvFixResult, String> {
+    analyzer::apply_env_fixes(Path::new(&project_path), &issues)
+}
@@ -3,18 +3,38 @@
 //! Handles CRUD operations for service instances, lifecycle management,
 //! health checks, logs, configuration, and environment variables.
 
-use crate::config::{Domain, Instance, ServiceType};
+use crate::activity::{self, ActivityEvent};
+use crate::client_certs;
+use crate::config::{
+    Domain, Instance, InstanceImportPreview, InstanceImportResult, SeedSettings, ServiceType,
+    TunnelTarget,
+};
+use crate::env_sync::{self, EnvChange};
 use crate::error::LockExt;
+use crate::instance_export;
 use crate::lock; // Shared macro from error.rs
+use crate::metrics::InstanceMetrics;
+use crate::port_allocator;
 use crate::process::ProcessManager;
+use crate::readiness;
+use crate::seed::{self, SeedResult};
 use crate::service_config::ServiceRegistry;
-use crate::services::{get_service, HealthCheck};
+use crate::services::centrifugo::{CentrifugoService, CentrifugoSetup};
+use crate::services::db_locale::DbLocaleSettings;
+use crate::services::keycloak::{KeycloakProvision, KeycloakService};
+use crate::services::mailpit::MailpitService;
+use crate::services::mysql_config::MySqlServerSettings;
+use crate::services::php_ini::PhpIniOverrides;
+use crate::services::soketi::{SoketiService, SoketiSetup};
+use crate::start_diagnosis::StartDiagnosis;
 use crate::validation;
 use futures_util::future;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
+use super::env_formats::{self, EnvFormat};
+use super::tunnels::reload_frpc_if_running;
 use super::AppState;
 
 // ============================================================================
@@ -37,6 +57,17 @@ pub struct InstanceWithHealth {
     pub process_manager: String,
     pub stack_id: Option<String>,
     pub mapped_domains: Vec<String>,
+    pub tags: Vec<String>,
+    /// Started when Burd or the daemon launches
+    pub auto_start: bool,
+    /// Restarted when it crashes or fails its health check
+    pub restart_on_failure: bool,
+    /// Started when one of its domains is visited while stopped
+    pub wake_on_access: bool,
+    /// Arguments appended to the service's start arguments
+    pub extra_args: Vec<String>,
+    /// Database seed, for MariaDB and PostgreSQL instances
+    pub seed: Option<SeedSettings>,
 }
 
 /// Instance configuration response
@@ -59,6 +90,43 @@ pub struct InstanceInfo {
     pub running: bool,
     pub pid: Option<u32>,
     pub categories: Vec<InfoCategory>,
+    /// Recent lifecycle events, newest first
+    pub activity: Vec<ActivityEvent>,
+}
+
+/// Result of moving an instance to another port
+#[derive(Debug, Serialize)]
+pub struct PortChange {
+    pub old_port: u16,
+    pub new_port: u16,
+    /// Domains now routed to the new port
+    pub domains: Vec<String>,
+    /// Tunnels now forwarding to the new port
+    pub tunnels: Vec<String>,
+    /// Whether the instance was restarted on the new port
+    pub restarted: bool,
+    /// Connection settings for the new port
+    pub env: String,
+    /// .env updates suggested for linked projects
+    pub env_sync: Vec<EnvChange>,
+}
+
+/// Create instance request payload
+#[derive(Debug, Deserialize)]
+pub struct CreateInstanceRequest {
+    pub name: String,
+    /// Port to use; assigned automatically when omitted
+    #[serde(default)]
+    pub port: Option<u16>,
+    pub service_type: String,
+    pub version: String,
+    #[serde(default)]
+    pub config: Option<serde_json::Value>,
+    #[serde(default)]
+    pub custom_domain: Option<String>,
+    /// Pick a free port instead of failing when `port` is already in use
+    #[serde(default)]
+    pub auto_assign_port: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -74,33 +142,6 @@ pub struct InfoItem {
     pub copyable: bool,
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
-
-async fn check_health_for_service(port: u16, service_type: ServiceType) -> bool {
-    let service = get_service(service_type);
-    match service.health_check() {
-        HealthCheck::Http { path } => {
-            let url = format!("http://127.0.0.1:{}{}", port, path);
-            reqwest::get(&url)
-                .await
-                .map(|r| r.status().is_success())
-                .unwrap_or(false)
-        }
-        HealthCheck::Tcp => {
-            use std::net::TcpStream;
-            use std::time::Duration;
-            // Parse the socket address safely
-            let addr = match format!("127.0.0.1:{}", port).parse() {
-                Ok(addr) => addr,
-                Err(_) => return false, // Invalid port number
-            };
-            TcpStream::connect_timeout(&addr, Duration::from_secs(2)).is_ok()
-        }
-    }
-}
-
 // ============================================================================
 // Instance CRUD Commands
 // ============================================================================
@@ -141,7 +182,7 @@ pub async fn list_instances(state: State<'_, AppState>) -> Result<Vec<InstanceWi
             async move {
                 // Perform health check asynchronously
                 let healthy = if running {
-                    Some(check_health_for_service(instance.port, instance.service_type).await)
+                    Some(readiness::is_healthy(instance.port, instance.service_type).await)
                 } else {
                     None
                 };
@@ -185,6 +226,12 @@ pub async fn list_instances(state: State<'_, AppState>) -> Result<Vec<InstanceWi
                     process_manager: "binary".to_string(),
                     stack_id: instance.stack_id.map(|id| id.to_string()),
                     mapped_domains,
+                    tags: instance.tags,
+                    auto_start: instance.auto_start,
+                    restart_on_failure: instance.restart_on_failure,
+                    wake_on_access: instance.wake_on_access,
+                    extra_args: instance.extra_args,
+                    seed: instance.seed,
                 }
             }
         })
@@ -198,21 +245,28 @@ pub async fn list_instances(state: State<'_, AppState>) -> Result<Vec<InstanceWi
 
 #[tauri::command]
 pub fn create_instance(
-    name: String,
-    port: u16,
-    service_type: String,
-    version: String,
-    config: Option<serde_json::Value>,
-    custom_domain: Option<String>,
+    request: CreateInstanceRequest,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<InstanceWithHealth, String> {
+    let CreateInstanceRequest {
+        name,
+        port,
+        service_type,
+        version,
+        config,
+        custom_domain,
+        auto_assign_port,
+    } = request;
+
     // Validate instance name
     validation::validate_instance_name(&name)
         .map_err(|e| format!("Invalid instance name: {}", e))?;
 
-    // Validate port
-    validation::validate_port(port).map_err(|e| format!("Invalid port: {}", e))?;
+    // Validate port if one was given (otherwise it's assigned automatically)
+    if let Some(port) = port {
+        validation::validate_port(port).map_err(|e| format!("Invalid port: {}", e))?;
+    }
 
     // Validate version
     validation::validate_version(&version).map_err(|e| format!("Invalid version: {}", e))?;
@@ -235,7 +289,7 @@ pub fn create_instance(
     let service_def = registry.get_service(&service_type.to_lowercase());
     let _auto_create_domain = service_def.map(|s| s.auto_create_domain).unwrap_or(false);
 
-    let service_config = config.unwrap_or_else(|| serde_json::json!({}));
+    let mut service_config = config.unwrap_or_else(|| serde_json::json!({}));
     let config_store = lock!(state.config_store)?;
     let app_config = config_store.load()?;
 
@@ -256,6 +310,11 @@ pub fn create_instance(
         }
     }
     let tld = app_config.tld.clone();
+    // A port another process holds would only fail once the instance starts
+    let port = port_allocator::resolve_port(&app_config, svc_type, port, auto_assign_port)?;
+    if svc_type == ServiceType::Mailpit {
+        MailpitService::assign_smtp_port(&app_config, &mut service_config)?;
+    }
     let instance = config_store.create_instance(
         name,
         port,
@@ -316,6 +375,12 @@ pub fn create_instance(
         process_manager: "binary".to_string(),
         stack_id: instance.stack_id.map(|id| id.to_string()),
         mapped_domains,
+        tags: instance.tags,
+        auto_start: instance.auto_start,
+        restart_on_failure: instance.restart_on_failure,
+        wake_on_access: instance.wake_on_access,
+        extra_args: instance.extra_args,
+        seed: instance.seed,
     })
 }
 
@@ -332,6 +397,7 @@ pub fn rename_instance(
         .map_err(|e| format!("Invalid instance name: {}", e))?;
 
     let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
     let mut config = config_store.load()?;
 
     let instance = config
@@ -346,13 +412,160 @@ pub fn rename_instance(
     Ok(())
 }
 
+/// Replace an instance's tags (e.g. "client:acme", "env:test")
+#[tauri::command]
+pub fn set_instance_tags(
+    id: String,
+    tags: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.update_instance_tags(uuid, &tags)?.tags)
+}
+
+/// Replace the arguments appended to an instance's start command
+///
+/// Takes effect the next time the instance starts.
+#[tauri::command]
+pub fn set_instance_extra_args(
+    id: String,
+    extra_args: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let before = config_store.get_instance(uuid)?;
+    let updated = config_store.update_instance_extra_args(uuid, &extra_args)?;
+    activity::record_changes(&before, &updated);
+
+    Ok(updated.extra_args)
+}
+
+/// Set or clear a database instance's seed
+///
+/// The seed runs on its schedule (if any) and through `run_instance_seed`.
+#[tauri::command]
+pub fn set_instance_seed(
+    id: String,
+    seed: Option<SeedSettings>,
+    state: State<'_, AppState>,
+) -> Result<Option<SeedSettings>, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let before = config_store.get_instance(uuid)?;
+    let updated = config_store.update_instance_seed(uuid, seed)?;
+    activity::record_changes(&before, &updated);
+
+    Ok(updated.seed)
+}
+
+/// Reset an instance's database with its seed now
+///
+/// The instance must be running. Drops and reimports the database when the
+/// seed has an SQL file.
+#[tauri::command]
+pub async fn run_instance_seed(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<SeedResult, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let instance = lock!(state.config_store)?.get_instance(uuid)?;
+    let running = lock!(state.process_manager)?.get_status(&instance).running;
+
+    tokio::task::spawn_blocking(move || seed::run_seed(&instance, running))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Set whether an instance starts when the app or the headless daemon launches
+///
+/// The daemon also restarts auto-start instances that crash.
+#[tauri::command]
+pub fn set_instance_auto_start(
+    id: String,
+    auto_start: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_instance_auto_start(uuid, auto_start)?;
+
+    Ok(())
+}
+
+/// Set whether an instance is restarted when it crashes or fails its health check
+#[tauri::command]
+pub fn set_instance_restart_on_failure(
+    id: String,
+    restart_on_failure: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_instance_restart_on_failure(uuid, restart_on_failure)?;
+
+    Ok(())
+}
+
+/// Set whether an instance is started when one of its domains is visited
+///
+/// A stopped instance's domains are routed while this is on, and dropped
+/// when it's turned off.
+#[tauri::command]
+pub async fn set_instance_wake_on_access(
+    id: String,
+    wake_on_access: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let (config, running) = {
+        let config_store = lock!(state.config_store)?;
+        config_store.update_instance_wake_on_access(uuid, wake_on_access)?;
+        let running = lock!(state.process_manager)?.is_running(&uuid);
+        (config_store.load()?, running)
+    };
+
+    let proxy = state.proxy_server.lock().await;
+    if running {
+        // Routes stay; only Caddy's handling of a down backend changes
+        return proxy.sync_to_daemon();
+    }
+    for domain in config
+        .domains
+        .iter()
+        .filter(|d| d.routes_to_instance(&uuid))
+    {
+        if wake_on_access {
+            proxy.register_domain(domain, &config)?;
+        } else {
+            let _ = proxy.unregister_route(&domain.full_domain(&config.tld));
+        }
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Instance Lifecycle Commands
 // ============================================================================
 
+/// Start an instance
+///
+/// With `wait`, returns only once the service passes its health check (or
+/// fails after `timeout_secs`, 30 by default), for callers that use the
+/// service right away.
 #[tauri::command]
 pub async fn start_instance(
     id: String,
+    wait: Option<bool>,
+    timeout_secs: Option<u64>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<u32, String> {
@@ -398,7 +611,7 @@ pub async fn start_instance(
         // Check if any domain has SSL enabled
         let ssl_enabled = domains.iter().any(|d| d.ssl_enabled);
 
-        let pid = process_manager.start(&instance, Some(&tld), ssl_enabled)?;
+        let pid = process_manager.start(&instance, Some(&config), ssl_enabled)?;
 
         (instance, pid, tld, domains)
     };
@@ -417,6 +630,14 @@ pub async fn start_instance(
     }
 
     let _ = app.emit("instances-changed", ());
+
+    if wait.unwrap_or(false) {
+        let timeout = timeout_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(readiness::DEFAULT_READY_TIMEOUT);
+        readiness::wait_until_healthy(instance.port, instance.service_type, timeout).await?;
+    }
+
     Ok(pid)
 }
 
@@ -428,16 +649,21 @@ pub async fn stop_instance(
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
 
-    // Get domains before stopping
+    // Get domains before stopping; wake-on-access instances keep theirs
+    // routed so a visit can start them again
     let domains = {
         let config_store = lock!(state.config_store)?;
         let config = config_store.load()?;
+        let wake_on_access = config
+            .instances
+            .iter()
+            .any(|i| i.id == uuid && i.wake_on_access);
 
         // Get domains that route to this instance
         let domains: Vec<Domain> = config
             .domains
             .iter()
-            .filter(|d| d.routes_to_instance(&uuid))
+            .filter(|d| !wake_on_access && d.routes_to_instance(&uuid))
             .cloned()
             .collect();
 
@@ -479,7 +705,7 @@ pub async fn restart_instance(
     // Small delay between stop and start
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
     // Restart by calling start_instance logic
-    start_instance(id.clone(), state.clone(), app.clone()).await?;
+    start_instance(id.clone(), None, None, state.clone(), app.clone()).await?;
 
     let _ = app.emit("instances-changed", ());
     Ok(())
@@ -509,15 +735,16 @@ pub async fn delete_instance(
     };
 
     // Get domains that route to this instance and unregister their routes
-    let domains_to_remove = {
+    let (domains_to_remove, slugs) = {
         let config_store = lock!(state.config_store)?;
         let config = config_store.load()?;
-        config
+        let domains = config
             .domains
             .iter()
             .filter(|d| d.routes_to_instance(&uuid))
             .cloned()
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>();
+        (domains, config.slugs)
     };
 
     // Unregister proxy routes for all domains pointing to this instance
@@ -529,7 +756,7 @@ pub async fn delete_instance(
         }
         // Also unregister the legacy instance domain route if enabled
         if instance.domain_enabled {
-            let _ = proxy.unregister_route(&instance.full_domain(tld));
+            let _ = proxy.unregister_route(&instance.full_domain(tld, &slugs));
         }
     }
 
@@ -537,6 +764,9 @@ pub async fn delete_instance(
     let config_store = lock!(state.config_store)?;
     config_store.delete_domains_for_instance(uuid)?;
     let result = config_store.delete_instance(uuid);
+    if result.is_ok() {
+        activity::delete(&uuid);
+    }
     let _ = app.emit("instances-changed", ());
     result
 }
@@ -548,7 +778,22 @@ pub async fn delete_instance(
 #[tauri::command]
 pub async fn check_instance_health(port: u16, service_type: String) -> Result<bool, String> {
     let svc_type = super::parse_service_type(&service_type)?;
-    Ok(check_health_for_service(port, svc_type).await)
+    Ok(readiness::is_healthy(port, svc_type).await)
+}
+
+/// Suggest a free port for a new instance of the given service type
+///
+/// Returns `preferred` when it's free, so the form can keep a port the user typed.
+#[tauri::command]
+pub fn suggest_port(
+    service_type: String,
+    preferred: Option<u16>,
+    state: State<'_, AppState>,
+) -> Result<u16, String> {
+    let svc_type = super::parse_service_type(&service_type)?;
+    let config_store = lock!(state.config_store)?;
+    let config = config_store.load()?;
+    port_allocator::resolve_port(&config, svc_type, preferred, true)
 }
 
 /// Check if a port has something listening via TCP connect
@@ -569,6 +814,19 @@ pub fn get_instance_logs(id: String) -> Result<String, String> {
     ProcessManager::read_logs(&uuid)
 }
 
+/// Explain why a stopped instance failed to start, from its last log
+#[tauri::command]
+pub fn get_start_diagnosis(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<StartDiagnosis>, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let instance = lock!(state.config_store)?.get_instance(uuid)?;
+    let process_manager = lock!(state.process_manager)?;
+    process_manager.diagnose(&instance)
+}
+
 // ============================================================================
 // Instance Config Commands
 // ============================================================================
@@ -602,7 +860,35 @@ pub fn update_instance_config(
     let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
 
     let config_store = lock!(state.config_store)?;
-    config_store.update_instance_config(uuid, config)?;
+
+    // Reject invalid my.cnf, php.ini and timezone settings now rather than on the next start
+    let instance = config_store.get_instance(uuid)?;
+    let candidate = Instance {
+        config: config.clone(),
+        ..instance.clone()
+    };
+    match instance.service_type {
+        ServiceType::MariaDB | ServiceType::MySQL => {
+            MySqlServerSettings::from_instance(&candidate)?;
+            DbLocaleSettings::from_instance(&candidate)?;
+        }
+        ServiceType::PostgreSQL => {
+            DbLocaleSettings::from_instance(&candidate)?;
+        }
+        ServiceType::FrankenPHP | ServiceType::FrankenPhpPark => {
+            PhpIniOverrides::from_instance(&candidate)?;
+        }
+        _ => {}
+    }
+
+    let updated = config_store.update_instance_config(uuid, config)?;
+    activity::record_changes(&instance, &updated);
+
+    // The park Caddyfile is watched, so its overrides apply without a restart
+    if updated.service_type == ServiceType::FrankenPhpPark {
+        let tld = config_store.load()?.tld;
+        crate::park::regenerate_park_caddyfile(&config_store, &updated, &tld)?;
+    }
 
     Ok(())
 }
@@ -633,26 +919,202 @@ pub fn change_instance_version(
 
     // Update the instance version
     drop(binary_manager); // Release lock before calling config_store again
-    config_store.update_instance_version(uuid, new_version)?;
+    let updated = config_store.update_instance_version(uuid, new_version)?;
+    activity::record_changes(&instance, &updated);
 
     Ok(())
 }
 
+/// Move an instance to another port
+///
+/// Picks a free port when `port` is omitted. A running instance is restarted
+/// on the new port; if it won't start there, the old port is restored and it
+/// starts on that again. Domains and tunnels targeting the instance follow it,
+/// and linked projects get .env suggestions.
+#[tauri::command]
+pub async fn change_instance_port(
+    id: String,
+    port: Option<u16>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<PortChange, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let (before, updated, restarted, config) = {
+        let config_store = lock!(state.config_store)?;
+        let process_manager = lock!(state.process_manager)?;
+
+        let config = config_store.load()?;
+        let before = config_store.get_instance(uuid)?;
+        let new_port = port_allocator::resolve_port_change(&config, uuid, port)?;
+
+        let running = process_manager.is_running(&uuid);
+        let ssl_enabled = config
+            .domains
+            .iter()
+            .filter(|d| d.routes_to_instance(&uuid))
+            .any(|d| d.ssl_enabled);
+        if running {
+            process_manager.stop(&uuid)?;
+        }
+
+        let updated =
+            config_store.update_instance(uuid, None, Some(new_port), None, None, None, None)?;
+        if running {
+            if let Err(e) = process_manager.start(&updated, Some(&config), ssl_enabled) {
+                config_store.update_instance(
+                    uuid,
+                    None,
+                    Some(before.port),
+                    None,
+                    None,
+                    None,
+                    None,
+                )?;
+                let _ = process_manager.start(&before, Some(&config), ssl_enabled);
+                return Err(format!(
+                    "'{}' failed to start on port {}: {}. It stays on port {}.",
+                    before.name, new_port, e, before.port
+                ));
+            }
+        }
+
+        (before, updated, running, config_store.load()?)
+    };
+
+    // Domains and tunnels point at the instance, so they only need the new port
+    let domains: Vec<Domain> = config
+        .domains
+        .iter()
+        .filter(|d| d.routes_to_instance(&uuid))
+        .cloned()
+        .collect();
+    {
+        let proxy = state.proxy_server.lock().await;
+        for domain in &domains {
+            let _ = proxy.register_domain(domain, &config);
+        }
+    }
+    let tunnels: Vec<String> = config
+        .tunnels
+        .iter()
+        .filter(|t| matches!(t.target, TunnelTarget::Instance(id) if id == uuid))
+        .map(|t| t.name.clone())
+        .collect();
+    if !tunnels.is_empty() {
+        let _ = reload_frpc_if_running(&state);
+    }
+
+    activity::record_changes(&before, &updated);
+    let env_sync = env_sync::plan_instance_change(&config, &before, &updated, &config.tld);
+    suggest_env_sync(&app, env_sync.clone());
+    let _ = app.emit("instances-changed", ());
+
+    Ok(PortChange {
+        old_port: before.port,
+        new_port: updated.port,
+        domains: domains.iter().map(|d| d.full_domain(&config.tld)).collect(),
+        tunnels,
+        restarted,
+        env: generate_env_for_service(&updated, EnvFormat::default()),
+        env_sync,
+    })
+}
+
 // ============================================================================
 // Instance ENV Commands
 // ============================================================================
 
 /// Get environment variables for connecting to an instance
+///
+/// `format` selects the framework flavour (laravel, symfony, django, rails, node);
+/// omitted means Laravel.
 #[tauri::command]
-pub fn get_instance_env(id: String, state: State<'_, AppState>) -> Result<String, String> {
+pub fn get_instance_env(
+    id: String,
+    format: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
     let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+    let format = EnvFormat::parse_optional(format.as_deref())?;
     let config_store = lock!(state.config_store)?;
     let instance = config_store.get_instance(uuid)?;
 
-    Ok(generate_env_for_service(&instance))
+    Ok(generate_env_for_service(&instance, format))
+}
+
+/// Generate the Centrifugo config and Laravel broadcasting .env block
+///
+/// Saves the preset to the instance config; a running instance picks it up
+/// on restart.
+#[tauri::command]
+pub fn setup_centrifugo(id: String, state: State<'_, AppState>) -> Result<CentrifugoSetup, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+    let config_store = lock!(state.config_store)?;
+    CentrifugoService::setup(&config_store, uuid)
+}
+
+/// Generate Soketi app credentials and the Laravel broadcasting .env block
+///
+/// Saves the preset to the instance config; a running instance picks it up
+/// on restart.
+#[tauri::command]
+pub fn setup_soketi(id: String, state: State<'_, AppState>) -> Result<SoketiSetup, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+    let config_store = lock!(state.config_store)?;
+    SoketiService::setup(&config_store, uuid)
+}
+
+/// Create a Keycloak instance's realm, OIDC client and test user where missing
+///
+/// The instance has to be running. Returns what was done and the Laravel
+/// Socialite .env block.
+#[tauri::command]
+pub async fn provision_keycloak(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<KeycloakProvision, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+    let instance = {
+        let config_store = lock!(state.config_store)?;
+        KeycloakService::find_instance(&config_store, uuid)?
+    };
+    tokio::task::spawn_blocking(move || KeycloakService::provision(&instance))
+        .await
+        .map_err(|e| format!("Provisioning failed: {}", e))?
+}
+
+/// Offer .env updates for linked projects to the frontend
+///
+/// Emits `env-sync-suggested`; the frontend asks for confirmation and calls
+/// `apply_env_sync` with the accepted changes.
+pub(crate) fn suggest_env_sync(app: &AppHandle, changes: Vec<EnvChange>) {
+    if !changes.is_empty() {
+        let _ = app.emit("env-sync-suggested", changes);
+    }
+}
+
+/// Apply suggested .env updates, returning how many keys changed
+#[tauri::command]
+pub fn apply_env_sync(
+    changes: Vec<EnvChange>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let config = {
+        let config_store = lock!(state.config_store)?;
+        config_store.load()?
+    };
+    env_sync::apply(&config, &changes)
 }
 
-pub fn generate_env_for_service(instance: &Instance) -> String {
+/// Generate connection settings for an instance in the requested format.
+///
+/// Services without a framework-specific snippet fall back to the Laravel block.
+pub fn generate_env_for_service(instance: &Instance, format: EnvFormat) -> String {
+    if let Some(snippet) = env_formats::generate_for_format(instance, format) {
+        return snippet;
+    }
+
     match instance.service_type {
         ServiceType::Redis => generate_redis_env(instance),
         ServiceType::Valkey => generate_valkey_env(instance),
@@ -662,6 +1124,8 @@ pub fn generate_env_for_service(instance: &Instance) -> String {
         ServiceType::MinIO => generate_minio_env(instance),
         ServiceType::MongoDB => generate_mongodb_env(instance),
         ServiceType::Beanstalkd => generate_beanstalkd_env(instance),
+        ServiceType::RabbitMQ => generate_rabbitmq_env(instance),
+        ServiceType::OpenSearch => generate_opensearch_env(instance),
         ServiceType::PostgreSQL => generate_postgresql_env(instance),
         ServiceType::MariaDB => generate_mariadb_env(instance),
         ServiceType::MySQL => generate_mysql_env(instance),
@@ -673,8 +1137,11 @@ pub fn generate_env_for_service(instance: &Instance) -> String {
         ServiceType::Frpc => "# frpc is a tunneling service - no ENV needed".to_string(),
         ServiceType::Caddy => "# Caddy is an internal service - no ENV needed".to_string(),
         ServiceType::Centrifugo => generate_centrifugo_env(instance),
+        ServiceType::Soketi => generate_soketi_env(instance),
+        ServiceType::Keycloak => KeycloakService::laravel_env(instance),
         ServiceType::Gitea => generate_gitea_env(instance),
         ServiceType::Bun => generate_bun_env(instance),
+        ServiceType::Custom(id) => generate_custom_env(id, instance),
     }
 }
 
@@ -772,6 +1239,20 @@ fn generate_memcached_env(instance: &Instance) -> String {
     )
 }
 
+/// Host and port under the service ID, e.g. `CLICKHOUSE_HOST` for clickhouse
+fn generate_custom_env(id: &str, instance: &Instance) -> String {
+    let prefix = id.to_uppercase().replace('-', "_");
+    format!(
+        "# {}\n\
+         {}_HOST=127.0.0.1\n\
+         {}_PORT={}\n",
+        instance.service_type.display_name(),
+        prefix,
+        prefix,
+        instance.port
+    )
+}
+
 fn generate_mailpit_env(instance: &Instance) -> String {
     let smtp_port = instance
         .config
@@ -794,33 +1275,39 @@ fn generate_mailpit_env(instance: &Instance) -> String {
 }
 
 fn generate_minio_env(instance: &Instance) -> String {
-    let root_user = instance
-        .config
-        .get("root_user")
-        .and_then(|v| v.as_str())
-        .unwrap_or("minioadmin");
-    let root_password = instance
-        .config
-        .get("root_password")
-        .and_then(|v| v.as_str())
-        .unwrap_or("minioadmin");
+    use crate::services::minio::MinIOService;
+
+    let (root_user, root_password) = MinIOService::credentials(instance);
+    // Point at the first seeded bucket so the generated env works as-is
+    let bucket = MinIOService::bucket_seeds(instance)
+        .ok()
+        .and_then(|seeds| seeds.into_iter().next())
+        .map(|seed| seed.name)
+        .unwrap_or_else(|| "your-bucket".to_string());
 
     format!(
         "# Laravel (S3 driver)\n\
          AWS_ACCESS_KEY_ID={}\n\
          AWS_SECRET_ACCESS_KEY={}\n\
          AWS_DEFAULT_REGION=us-east-1\n\
-         AWS_BUCKET=your-bucket\n\
+         AWS_BUCKET={}\n\
          AWS_ENDPOINT=http://127.0.0.1:{}\n\
          AWS_USE_PATH_STYLE_ENDPOINT=true\n\
          \n\
          # WordPress (S3 Offload Media)\n\
-         S3_UPLOADS_BUCKET=your-bucket\n\
+         S3_UPLOADS_BUCKET={}\n\
          S3_UPLOADS_REGION=us-east-1\n\
          S3_UPLOADS_KEY={}\n\
          S3_UPLOADS_SECRET={}\n\
          S3_UPLOADS_ENDPOINT=http://127.0.0.1:{}\n",
-        root_user, root_password, instance.port, root_user, root_password, instance.port
+        root_user,
+        root_password,
+        bucket,
+        instance.port,
+        bucket,
+        root_user,
+        root_password,
+        instance.port
     )
 }
 
@@ -848,6 +1335,53 @@ fn generate_beanstalkd_env(instance: &Instance) -> String {
     )
 }
 
+fn generate_rabbitmq_env(instance: &Instance) -> String {
+    use crate::services::rabbitmq::RabbitMQService;
+
+    let (user, password) = RabbitMQService::credentials(instance);
+
+    format!(
+        "# Laravel (vladimir-yuldashev/laravel-queue-rabbitmq)\n\
+         QUEUE_CONNECTION=rabbitmq\n\
+         RABBITMQ_HOST=127.0.0.1\n\
+         RABBITMQ_PORT={}\n\
+         RABBITMQ_USER={}\n\
+         RABBITMQ_PASSWORD={}\n\
+         RABBITMQ_VHOST={}\n\
+         RABBITMQ_QUEUE=default\n\
+         \n\
+         # Connection URI\n\
+         RABBITMQ_URL={}\n\
+         \n\
+         # Management UI: http://127.0.0.1:{}\n",
+        instance.port,
+        user,
+        password,
+        RabbitMQService::vhost(instance),
+        RabbitMQService::amqp_url(instance),
+        RabbitMQService::management_port(instance)
+    )
+}
+
+fn generate_opensearch_env(instance: &Instance) -> String {
+    format!(
+        "# Laravel Scout (babenkoivan/elastic-scout-driver)\n\
+         SCOUT_DRIVER=elastic\n\
+         ELASTIC_HOST=127.0.0.1:{}\n\
+         \n\
+         # Laravel Explorer (jeroen-g/explorer) and matchish/laravel-scout-elasticsearch\n\
+         ELASTICSEARCH_HOST=127.0.0.1\n\
+         ELASTICSEARCH_PORT={}\n\
+         ELASTICSEARCH_SCHEME=http\n\
+         \n\
+         # OpenSearch clients (opensearch-project/opensearch-php)\n\
+         OPENSEARCH_HOST={}\n",
+        instance.port,
+        instance.port,
+        crate::services::opensearch::OpenSearchService::url(instance)
+    )
+}
+
 fn generate_postgresql_env(instance: &Instance) -> String {
     format!(
         "# Laravel\n\
@@ -940,28 +1474,11 @@ fn generate_frankenphp_env(instance: &Instance) -> String {
 }
 
 fn generate_centrifugo_env(instance: &Instance) -> String {
-    let api_key = instance
-        .config
-        .get("api_key")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    let token_secret = instance
-        .config
-        .get("token_hmac_secret")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
+    CentrifugoService::laravel_env(instance, None)
+}
 
-    format!(
-        "# Centrifugo\n\
-         # WebSocket: ws://127.0.0.1:{}/connection/websocket\n\
-         # Admin UI: http://127.0.0.1:{}/\n\
-         \n\
-         CENTRIFUGO_URL=http://127.0.0.1:{}\n\
-         CENTRIFUGO_API_KEY={}\n\
-         CENTRIFUGO_TOKEN_HMAC_SECRET={}\n",
-        instance.port, instance.port, instance.port, api_key, token_secret
-    )
+fn generate_soketi_env(instance: &Instance) -> String {
+    SoketiService::laravel_env(instance, None)
 }
 
 /// Reorder instances in the config (for drag-and-drop)
@@ -1008,6 +1525,84 @@ fn generate_bun_env(instance: &Instance) -> String {
     )
 }
 
+// ============================================================================
+// Instance Export / Import Commands
+// ============================================================================
+
+/// Export an instance and its domains as shareable JSON
+///
+/// Passwords and keys are left out unless `include_secrets` is set.
+#[tauri::command]
+pub fn export_instance(
+    id: String,
+    include_secrets: bool,
+    created_by: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+    let config = lock!(state.config_store)?.load()?;
+    let export = instance_export::export_instance(&config, uuid, include_secrets, created_by)?;
+
+    serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize instance: {}", e))
+}
+
+/// Preview an instance import - validates and detects conflicts
+#[tauri::command]
+pub fn preview_instance_import(
+    config_json: String,
+    state: State<'_, AppState>,
+) -> Result<InstanceImportPreview, String> {
+    let import = instance_export::parse_export(&config_json)?;
+    let config = lock!(state.config_store)?.load()?;
+    instance_export::preview_import(&config, import)
+}
+
+/// Import an instance (after preview) and register its domains
+///
+/// `name` and `port` replace the exported ones, e.g. when the name is taken.
+#[tauri::command]
+pub async fn import_instance(
+    config_json: String,
+    name: Option<String>,
+    port: Option<u16>,
+    state: State<'_, AppState>,
+) -> Result<InstanceImportResult, String> {
+    let import = instance_export::parse_export(&config_json)?;
+
+    let (result, config) = {
+        let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
+        let mut config = config_store.load()?;
+        let result = instance_export::apply_import(&mut config, import, name, port)?;
+        config_store.save(&config)?;
+        (result, config)
+    };
+
+    let imported: Vec<&Domain> = config
+        .domains
+        .iter()
+        .filter(|d| result.domains_created.contains(&d.id))
+        .collect();
+    for domain in &imported {
+        if domain.require_client_cert {
+            client_certs::ensure(&domain.id.to_string(), &domain.full_domain(&config.tld))?;
+        }
+    }
+
+    {
+        let proxy = state.proxy_server.lock().await;
+        for domain in &imported {
+            proxy.register_domain(domain, &config)?;
+        }
+    }
+    if imported.iter().any(|d| d.ssl_enabled) {
+        let _ = super::auto_trust_ca_if_needed();
+    }
+
+    Ok(result)
+}
+
 // ============================================================================
 // Instance Info Command
 // ============================================================================
@@ -1020,27 +1615,46 @@ pub async fn get_instance_info(
 ) -> Result<InstanceInfo, String> {
     let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
 
-    let (instance, running, pid, tld) = {
+    let (instance, running, pid, domain) = {
         let config_store = lock!(state.config_store)?;
         let process_manager = lock!(state.process_manager)?;
 
         let config = config_store.load()?;
         let instance = config_store.get_instance(uuid)?;
         let status = process_manager.get_status(&instance);
+        let domain = instance.full_domain(&config.tld, &config.slugs);
 
-        (instance, status.running, status.pid, config.tld.clone())
+        (instance, status.running, status.pid, domain)
     };
 
-    Ok(generate_info_for_service(&instance, running, pid, &tld))
+    let activity = activity::load(&uuid, 50).unwrap_or_default();
+
+    Ok(generate_info_for_service(
+        &instance, running, pid, &domain, activity,
+    ))
+}
+
+/// Get CPU, memory and disk usage of an instance, with recent history
+#[tauri::command]
+pub async fn get_instance_metrics(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<InstanceMetrics, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid instance ID")?;
+
+    let instance = lock!(state.config_store)?.get_instance(uuid)?;
+    let process_manager = lock!(state.process_manager)?;
+    Ok(process_manager.get_metrics(&instance))
 }
 
 fn generate_info_for_service(
     instance: &Instance,
     running: bool,
     pid: Option<u32>,
-    tld: &str,
+    domain: &str,
+    activity: Vec<ActivityEvent>,
 ) -> InstanceInfo {
-    let mut categories = vec![create_basic_info_category(instance, running, pid, tld)];
+    let mut categories = vec![create_basic_info_category(instance, running, pid, domain)];
 
     // Add service-specific categories
     match instance.service_type {
@@ -1056,8 +1670,12 @@ fn generate_info_for_service(
         ServiceType::Typesense => categories.push(create_typesense_category(instance)),
         ServiceType::MinIO => categories.push(create_minio_category(instance)),
         ServiceType::Mailpit => categories.push(create_mailpit_category(instance)),
+        ServiceType::RabbitMQ => categories.push(create_rabbitmq_category(instance)),
+        ServiceType::OpenSearch => categories.push(create_opensearch_category(instance)),
         ServiceType::Gitea => categories.push(create_gitea_category(instance)),
         ServiceType::Centrifugo => categories.push(create_centrifugo_category(instance)),
+        ServiceType::Soketi => categories.push(create_soketi_category(instance)),
+        ServiceType::Keycloak => categories.push(create_keycloak_category(instance)),
         _ => {}
     }
 
@@ -1070,6 +1688,7 @@ fn generate_info_for_service(
         running,
         pid,
         categories,
+        activity,
     }
 }
 
@@ -1077,7 +1696,7 @@ fn create_basic_info_category(
     instance: &Instance,
     running: bool,
     pid: Option<u32>,
-    tld: &str,
+    domain: &str,
 ) -> InfoCategory {
     let mut items = vec![
         InfoItem {
@@ -1122,7 +1741,15 @@ fn create_basic_info_category(
     if instance.domain_enabled {
         items.push(InfoItem {
             label: "Domain".to_string(),
-            value: instance.full_domain(tld),
+            value: domain.to_string(),
+            copyable: true,
+        });
+    }
+
+    if !instance.extra_args.is_empty() {
+        items.push(InfoItem {
+            label: "Extra Arguments".to_string(),
+            value: instance.extra_args_line(),
             copyable: true,
         });
     }
@@ -1495,6 +2122,75 @@ fn create_mailpit_category(instance: &Instance) -> InfoCategory {
     }
 }
 
+fn create_rabbitmq_category(instance: &Instance) -> InfoCategory {
+    use crate::services::rabbitmq::RabbitMQService;
+
+    let (user, password) = RabbitMQService::credentials(instance);
+
+    let items = vec![
+        InfoItem {
+            label: "Management UI".to_string(),
+            value: format!(
+                "http://127.0.0.1:{}",
+                RabbitMQService::management_port(instance)
+            ),
+            copyable: true,
+        },
+        InfoItem {
+            label: "AMQP URL".to_string(),
+            value: RabbitMQService::amqp_url(instance),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Username".to_string(),
+            value: user,
+            copyable: true,
+        },
+        InfoItem {
+            label: "Password".to_string(),
+            value: password,
+            copyable: true,
+        },
+        InfoItem {
+            label: "Virtual Host".to_string(),
+            value: RabbitMQService::vhost(instance),
+            copyable: true,
+        },
+    ];
+
+    InfoCategory {
+        title: "RabbitMQ Configuration".to_string(),
+        items,
+    }
+}
+
+fn create_opensearch_category(instance: &Instance) -> InfoCategory {
+    use crate::services::opensearch::OpenSearchService;
+
+    let items = vec![
+        InfoItem {
+            label: "REST Endpoint".to_string(),
+            value: OpenSearchService::url(instance),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Transport Port".to_string(),
+            value: OpenSearchService::transport_port(instance).to_string(),
+            copyable: true,
+        },
+        InfoItem {
+            label: "JVM Heap".to_string(),
+            value: OpenSearchService::heap_size(instance),
+            copyable: false,
+        },
+    ];
+
+    InfoCategory {
+        title: "OpenSearch Configuration".to_string(),
+        items,
+    }
+}
+
 fn create_gitea_category(instance: &Instance) -> InfoCategory {
     let items = vec![InfoItem {
         label: "Web UI".to_string(),
@@ -1555,3 +2251,78 @@ fn create_centrifugo_category(instance: &Instance) -> InfoCategory {
         items,
     }
 }
+
+fn create_soketi_category(instance: &Instance) -> InfoCategory {
+    let mut items = vec![InfoItem {
+        label: "WebSocket".to_string(),
+        value: format!("ws://127.0.0.1:{}", instance.port),
+        copyable: true,
+    }];
+
+    for (key, label) in [
+        ("app_id", "App ID"),
+        ("app_key", "App Key"),
+        ("app_secret", "App Secret"),
+    ] {
+        if let Some(value) = instance
+            .config
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+        {
+            items.push(InfoItem {
+                label: label.to_string(),
+                value: value.to_string(),
+                copyable: true,
+            });
+        }
+    }
+
+    InfoCategory {
+        title: "Soketi Configuration".to_string(),
+        items,
+    }
+}
+
+fn create_keycloak_category(instance: &Instance) -> InfoCategory {
+    let (admin_user, admin_password) = KeycloakService::admin_credentials(instance);
+    let (test_user, test_password) = KeycloakService::test_user(instance);
+
+    let items = vec![
+        InfoItem {
+            label: "Admin Console".to_string(),
+            value: format!("{}/admin", KeycloakService::url(instance)),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Admin".to_string(),
+            value: format!("{} / {}", admin_user, admin_password),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Issuer".to_string(),
+            value: KeycloakService::issuer(instance),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Client ID".to_string(),
+            value: KeycloakService::client_id(instance).to_string(),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Client Secret".to_string(),
+            value: KeycloakService::client_secret(instance),
+            copyable: true,
+        },
+        InfoItem {
+            label: "Test User".to_string(),
+            value: format!("{} / {}", test_user, test_password),
+            copyable: true,
+        },
+    ];
+
+    InfoCategory {
+        title: "Keycloak Configuration".to_string(),
+        items,
+    }
+}
@@ -1,13 +1,15 @@
 //! Log Commands
 //!
 //! Tauri commands for log aggregation and streaming.
-//! Supports Caddy proxy logs and per-instance process logs.
+//! Supports Caddy proxy logs (including the daemon's own output and
+//! per-site access logs), the helper and Burd daemon output, and
+//! per-instance process logs, and the disk use of Burd's own logs.
 
 use crate::error::LockExt;
 use crate::lock;
 use crate::logs::{
-    get_caddy_log_path, get_instance_log_path, get_last_lines, get_log_sources_with_instances,
-    parse_caddy_json, parse_plain_text, read_new_lines, LogEntry, LogFileState, LogSourceInfo,
+    self, get_last_lines, get_log_sources_with_instances, log_files_for_sources, read_new_lines,
+    site_domains, LogEntry, LogFile, LogFileState, LogSourceInfo, LogUsage, PruneResult,
 };
 use std::time::Duration;
 use tauri::ipc::Channel;
@@ -15,12 +17,26 @@ use tauri::State;
 
 use super::AppState;
 
-/// Get available log sources (Caddy + per-service-type from instances)
+/// Resolve source IDs (all when empty) to log files using the current config
+fn resolve_log_files(sources: &[String], state: &AppState) -> Result<Vec<LogFile>, String> {
+    let config_store = lock!(state.config_store)?;
+    let config = config_store.load()?;
+    Ok(log_files_for_sources(
+        sources,
+        &config.instances,
+        &site_domains(&config),
+    ))
+}
+
+/// Get available log sources (Caddy, its sites, and per-service-type from instances)
 #[tauri::command]
 pub fn get_available_log_sources(state: State<'_, AppState>) -> Result<Vec<LogSourceInfo>, String> {
     let config_store = lock!(state.config_store)?;
     let config = config_store.load()?;
-    Ok(get_log_sources_with_instances(&config.instances))
+    Ok(get_log_sources_with_instances(
+        &config.instances,
+        &site_domains(&config),
+    ))
 }
 
 /// Get recent logs from specified sources
@@ -33,95 +49,12 @@ pub async fn get_recent_logs(
     let limit = limit.unwrap_or(500);
     let mut all_logs: Vec<LogEntry> = Vec::new();
 
-    // Load instances for non-caddy sources
-    let instances = {
-        let config_store = lock!(state.config_store)?;
-        let config = config_store.load()?;
-        config.instances.clone()
-    };
-
-    let sources_empty = sources.is_empty();
-
-    for source in &sources {
-        if source == "caddy" {
-            let path = get_caddy_log_path();
-            if path.exists() {
-                if let Ok(lines) = get_last_lines(path.to_str().unwrap_or(""), limit) {
-                    for line in lines {
-                        if let Some(entry) = parse_caddy_json(&line) {
-                            all_logs.push(entry);
-                        }
-                    }
-                }
-            }
-        } else {
-            // Read logs from all instances of this service type
-            for instance in &instances {
-                let svc_type = instance.service_type.as_str();
-                if svc_type == source {
-                    if let Ok(log_path) = get_instance_log_path(&instance.id.to_string()) {
-                        if log_path.exists() {
-                            if let Ok(lines) =
-                                get_last_lines(log_path.to_str().unwrap_or(""), limit)
-                            {
-                                for line in lines {
-                                    let trimmed = line.trim();
-                                    if !trimmed.is_empty() {
-                                        let mut entry = parse_plain_text(
-                                            trimmed,
-                                            svc_type,
-                                            Some(&instance.id.to_string()),
-                                        );
-                                        entry.domain =
-                                            Some(instance.name.clone());
-                                        all_logs.push(entry);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // If no sources specified, load from all
-    if sources_empty {
-        // Caddy
-        let path = get_caddy_log_path();
-        if path.exists() {
-            if let Ok(lines) = get_last_lines(path.to_str().unwrap_or(""), limit) {
-                for line in lines {
-                    if let Some(entry) = parse_caddy_json(&line) {
-                        all_logs.push(entry);
-                    }
-                }
-            }
+    for file in resolve_log_files(&sources, &state)? {
+        if !file.path.exists() {
+            continue;
         }
-        // All instances
-        for instance in &instances {
-            let svc_type = instance.service_type.as_str();
-            if svc_type == "caddy" {
-                continue;
-            }
-            if let Ok(log_path) = get_instance_log_path(&instance.id.to_string()) {
-                if log_path.exists() {
-                    if let Ok(lines) = get_last_lines(log_path.to_str().unwrap_or(""), limit) {
-                        for line in lines {
-                            let trimmed = line.trim();
-                            if !trimmed.is_empty() {
-                                let mut entry = parse_plain_text(
-                                    trimmed,
-                                    svc_type,
-                                    Some(&instance.id.to_string()),
-                                );
-                                entry.domain = Some(instance.name.clone());
-                                all_logs.push(entry);
-                            }
-                        }
-                    }
-                }
-            }
+        if let Ok(lines) = get_last_lines(&file.path.to_string_lossy(), limit) {
+            all_logs.extend(lines.iter().filter_map(|line| file.parse(line)));
         }
     }
 
@@ -142,91 +75,26 @@ pub async fn stream_logs(
     on_log: Channel<LogEntry>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    // Load instances for non-caddy sources
-    let instances = {
-        let config_store = lock!(state.config_store)?;
-        let config = config_store.load()?;
-        config.instances.clone()
-    };
-
-    let stream_all = sources.is_empty();
-    let stream_caddy = stream_all || sources.contains(&"caddy".to_string());
+    let files = resolve_log_files(&sources, &state)?;
 
+    // Start at the end of each file (only new logs)
     let mut file_state = LogFileState::new();
-
-    // Initialize Caddy log position at end of file (only new logs)
-    if stream_caddy {
-        let caddy_path = get_caddy_log_path();
-        if caddy_path.exists() {
-            if let Ok(metadata) = std::fs::metadata(&caddy_path) {
-                file_state.set_position(caddy_path.to_str().unwrap_or(""), metadata.len());
-            }
-        }
-    }
-
-    // Initialize instance log positions at end of file
-    for instance in &instances {
-        let svc_type = instance.service_type.as_str();
-        if svc_type == "caddy" {
-            continue;
-        }
-        if stream_all || sources.contains(&svc_type.to_string()) {
-            if let Ok(log_path) = get_instance_log_path(&instance.id.to_string()) {
-                if log_path.exists() {
-                    if let Ok(metadata) = std::fs::metadata(&log_path) {
-                        file_state
-                            .set_position(log_path.to_str().unwrap_or(""), metadata.len());
-                    }
-                }
-            }
+    for file in &files {
+        if let Ok(metadata) = std::fs::metadata(&file.path) {
+            file_state.set_position(&file.path.to_string_lossy(), metadata.len());
         }
     }
 
     // Poll for new logs every 100ms
     loop {
-        // Stream Caddy logs
-        if stream_caddy {
-            let path = get_caddy_log_path();
-            if path.exists() {
-                let path_str = path.to_str().unwrap_or("");
-                if let Ok(lines) = read_new_lines(path_str, &mut file_state) {
-                    for line in lines {
-                        if let Some(entry) = parse_caddy_json(&line) {
-                            if on_log.send(entry).is_err() {
-                                return Ok(());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Stream instance logs
-        for instance in &instances {
-            let svc_type = instance.service_type.as_str();
-            if svc_type == "caddy" {
+        for file in &files {
+            if !file.path.exists() {
                 continue;
             }
-            if stream_all || sources.contains(&svc_type.to_string()) {
-                if let Ok(log_path) = get_instance_log_path(&instance.id.to_string()) {
-                    if log_path.exists() {
-                        let path_str = log_path.to_str().unwrap_or("");
-                        if let Ok(lines) = read_new_lines(path_str, &mut file_state) {
-                            for line in lines {
-                                let trimmed = line.trim();
-                                if !trimmed.is_empty() {
-                                    let mut entry = parse_plain_text(
-                                        trimmed,
-                                        svc_type,
-                                        Some(&instance.id.to_string()),
-                                    );
-                                    entry.domain = Some(instance.name.clone());
-                                    if on_log.send(entry).is_err() {
-                                        return Ok(());
-                                    }
-                                }
-                            }
-                        }
+            if let Ok(lines) = read_new_lines(&file.path.to_string_lossy(), &mut file_state) {
+                for entry in lines.iter().filter_map(|line| file.parse(line)) {
+                    if on_log.send(entry).is_err() {
+                        return Ok(());
                     }
                 }
             }
@@ -240,42 +108,33 @@ pub async fn stream_logs(
 /// Clear log file (for testing/maintenance)
 #[tauri::command]
 pub async fn clear_logs(source: String, state: State<'_, AppState>) -> Result<(), String> {
-    match source.as_str() {
-        "caddy" => {
-            let path = get_caddy_log_path();
-            if path.exists() {
-                std::fs::write(&path, "")
-                    .map_err(|e| format!("Failed to clear caddy logs: {}", e))?;
-            }
-            Ok(())
-        }
-        _ => {
-            // Clear logs for all instances of this service type
-            let instances = {
-                let config_store = lock!(state.config_store)?;
-                let config = config_store.load()?;
-                config.instances.clone()
-            };
-
-            let mut cleared = false;
-            for instance in &instances {
-                if instance.service_type.as_str() == source {
-                    if let Ok(log_path) = get_instance_log_path(&instance.id.to_string()) {
-                        if log_path.exists() {
-                            std::fs::write(&log_path, "").map_err(|e| {
-                                format!("Failed to clear logs for {}: {}", instance.name, e)
-                            })?;
-                            cleared = true;
-                        }
-                    }
-                }
-            }
+    let files = resolve_log_files(std::slice::from_ref(&source), &state)?;
+    if files.is_empty() {
+        return Err(format!("No log files found for source: {}", source));
+    }
 
-            if cleared {
-                Ok(())
-            } else {
-                Err(format!("No log files found for source: {}", source))
-            }
-        }
+    for file in files.iter().filter(|f| f.path.exists()) {
+        std::fs::write(&file.path, "").map_err(|e| {
+            format!(
+                "Failed to clear logs for {}: {}",
+                file.label.as_deref().unwrap_or(&file.source),
+                e
+            )
+        })?;
     }
+    Ok(())
+}
+
+/// Disk use of the instance and worker logs
+#[tauri::command]
+pub fn get_log_usage(state: State<'_, AppState>) -> Result<LogUsage, String> {
+    let config = lock!(state.config_store)?.load()?;
+    logs::get_log_usage(&config)
+}
+
+/// Remove rotated logs and the logs of deleted instances and workers
+#[tauri::command]
+pub fn prune_logs(state: State<'_, AppState>) -> Result<PruneResult, String> {
+    let config = lock!(state.config_store)?.load()?;
+    logs::prune_logs(&config)
 }
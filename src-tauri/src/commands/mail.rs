@@ -1,5 +1,5 @@
 use crate::commands::AppState;
-use crate::config::ServiceType;
+use crate::services::mailpit::MailpitService;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -179,56 +179,48 @@ pub struct SmtpConfig {
 }
 
 // ============================================================================
-// Helper: get Mailpit instance port
+// Helper: get Mailpit instance ports
 // ============================================================================
 
-fn get_mailpit_port(state: &State<'_, AppState>) -> Result<u16, String> {
+struct MailpitPorts {
+    http_port: u16,
+    smtp_port: u16,
+}
+
+/// Resolve the Mailpit instance to talk to and confirm it's running
+///
+/// With an `instance_id` that specific Mailpit is used; otherwise the first
+/// running Mailpit is picked, so single-Mailpit setups behave as before.
+fn get_mailpit_ports(
+    state: &State<'_, AppState>,
+    instance_id: Option<&str>,
+) -> Result<MailpitPorts, String> {
     let config_store = state
         .config_store
         .lock()
         .map_err(|_| "Failed to lock config")?;
     let config = config_store.load().map_err(|e| e.to_string())?;
 
-    // Find Mailpit instance
-    let mailpit = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit)
-        .ok_or("No Mailpit instance found")?;
+    let candidates = MailpitService::candidates(&config, instance_id)?;
 
     // Check if running via ProcessManager
     let process_manager = state
         .process_manager
         .lock()
         .map_err(|_| "Failed to lock process manager")?;
-    if !process_manager.is_running(&mailpit.id) {
-        return Err("Mailpit is not running".to_string());
-    }
-
-    Ok(mailpit.port)
+    let mailpit = candidates
+        .into_iter()
+        .find(|i| process_manager.is_running(&i.id))
+        .ok_or("Mailpit is not running")?;
+
+    Ok(MailpitPorts {
+        http_port: mailpit.port,
+        smtp_port: MailpitService::smtp_port(mailpit),
+    })
 }
 
-fn get_mailpit_smtp_port(state: &State<'_, AppState>) -> Result<u16, String> {
-    let config_store = state
-        .config_store
-        .lock()
-        .map_err(|_| "Failed to lock config")?;
-    let config = config_store.load().map_err(|e| e.to_string())?;
-
-    let mailpit = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit)
-        .ok_or("No Mailpit instance found")?;
-
-    let smtp_port = mailpit
-        .config
-        .get("smtp_port")
-        .and_then(|v| v.as_str())
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(1025);
-
-    Ok(smtp_port)
+fn get_mailpit_port(state: &State<'_, AppState>, instance_id: Option<&str>) -> Result<u16, String> {
+    get_mailpit_ports(state, instance_id).map(|p| p.http_port)
 }
 
 // ============================================================================
@@ -236,14 +228,16 @@ fn get_mailpit_smtp_port(state: &State<'_, AppState>) -> Result<u16, String> {
 // ============================================================================
 
 #[tauri::command]
-pub async fn get_mailpit_config(state: State<'_, AppState>) -> Result<SmtpConfig, String> {
-    let http_port = get_mailpit_port(&state)?;
-    let smtp_port = get_mailpit_smtp_port(&state)?;
+pub async fn get_mailpit_config(
+    state: State<'_, AppState>,
+    instance_id: Option<String>,
+) -> Result<SmtpConfig, String> {
+    let ports = get_mailpit_ports(&state, instance_id.as_deref())?;
 
     Ok(SmtpConfig {
         host: "127.0.0.1".to_string(),
-        port: smtp_port,
-        http_port,
+        port: ports.smtp_port,
+        http_port: ports.http_port,
     })
 }
 
@@ -253,8 +247,9 @@ pub async fn list_emails(
     start: Option<u32>,
     limit: Option<u32>,
     search: Option<String>,
+    instance_id: Option<String>,
 ) -> Result<MailMessageList, String> {
-    let port = get_mailpit_port(&state)?;
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
 
     let client = &*HTTP_CLIENT;
 
@@ -307,8 +302,9 @@ pub async fn list_emails(
 pub async fn get_email(
     state: State<'_, AppState>,
     message_id: String,
+    instance_id: Option<String>,
 ) -> Result<MailMessageDetail, String> {
-    let port = get_mailpit_port(&state)?;
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
 
     let client = &*HTTP_CLIENT;
     let url = format!("http://127.0.0.1:{}/api/v1/message/{}", port, message_id);
@@ -338,8 +334,9 @@ pub async fn get_email(
 pub async fn delete_emails(
     state: State<'_, AppState>,
     message_ids: Vec<String>,
+    instance_id: Option<String>,
 ) -> Result<(), String> {
-    let port = get_mailpit_port(&state)?;
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
 
     let client = &*HTTP_CLIENT;
     let url = format!("http://127.0.0.1:{}/api/v1/messages", port);
@@ -365,8 +362,11 @@ pub async fn delete_emails(
 }
 
 #[tauri::command]
-pub async fn delete_all_emails(state: State<'_, AppState>) -> Result<(), String> {
-    let port = get_mailpit_port(&state)?;
+pub async fn delete_all_emails(
+    state: State<'_, AppState>,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
 
     let client = &*HTTP_CLIENT;
     let url = format!("http://127.0.0.1:{}/api/v1/messages", port);
@@ -389,8 +389,9 @@ pub async fn mark_emails_read(
     state: State<'_, AppState>,
     message_ids: Vec<String>,
     read: bool,
+    instance_id: Option<String>,
 ) -> Result<(), String> {
-    let port = get_mailpit_port(&state)?;
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
 
     let client = &*HTTP_CLIENT;
     let url = format!("http://127.0.0.1:{}/api/v1/messages", port);
@@ -421,8 +422,11 @@ pub async fn mark_emails_read(
 }
 
 #[tauri::command]
-pub async fn get_unread_count(state: State<'_, AppState>) -> Result<u32, String> {
-    let port = get_mailpit_port(&state)?;
+pub async fn get_unread_count(
+    state: State<'_, AppState>,
+    instance_id: Option<String>,
+) -> Result<u32, String> {
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
 
     let client = &*HTTP_CLIENT;
     let url = format!("http://127.0.0.1:{}/api/v1/messages?limit=0", port);
@@ -444,3 +448,93 @@ pub async fn get_unread_count(state: State<'_, AppState>) -> Result<u32, String>
 
     Ok(result.unread)
 }
+
+/// Full-text search through Mailpit's search API
+///
+/// `query` uses Mailpit's search syntax, e.g. `to:dev@example.com` or
+/// `subject:"Reset your password" has:attachment`.
+#[tauri::command]
+pub async fn search_emails(
+    state: State<'_, AppState>,
+    query: String,
+    start: Option<u32>,
+    limit: Option<u32>,
+    instance_id: Option<String>,
+) -> Result<MailMessageList, String> {
+    if query.trim().is_empty() {
+        return Err("Search query is required".to_string());
+    }
+    list_emails(state, start, limit, Some(query), instance_id).await
+}
+
+/// GET a Mailpit endpoint that returns a file rather than JSON
+async fn fetch_bytes(url: &str, what: &str) -> Result<Vec<u8>, String> {
+    let response = HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", what, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Mailpit API error: {}", response.status()));
+    }
+
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read {}: {}", what, e))
+}
+
+fn write_export(path: &str, bytes: &[u8]) -> Result<(), String> {
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Save one of a message's attachments to `path`
+#[tauri::command]
+pub async fn save_email_attachment(
+    state: State<'_, AppState>,
+    message_id: String,
+    part_id: String,
+    path: String,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
+    let url = format!(
+        "http://127.0.0.1:{}/api/v1/message/{}/part/{}",
+        port, message_id, part_id
+    );
+    let bytes = fetch_bytes(&url, "attachment").await?;
+    write_export(&path, &bytes)
+}
+
+/// Save a message's raw source to `path` as an .eml file
+#[tauri::command]
+pub async fn export_email_eml(
+    state: State<'_, AppState>,
+    message_id: String,
+    path: String,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    let port = get_mailpit_port(&state, instance_id.as_deref())?;
+    let url = format!(
+        "http://127.0.0.1:{}/api/v1/message/{}/raw",
+        port, message_id
+    );
+    let bytes = fetch_bytes(&url, "message source").await?;
+    write_export(&path, &bytes)
+}
+
+/// The HTML part of a message as sent, for inspecting template markup
+#[tauri::command]
+pub async fn get_email_html_source(
+    state: State<'_, AppState>,
+    message_id: String,
+    instance_id: Option<String>,
+) -> Result<String, String> {
+    let email = get_email(state, message_id, instance_id).await?;
+    if email.html.is_empty() {
+        return Err("This message has no HTML part".to_string());
+    }
+    Ok(email.html)
+}
@@ -0,0 +1,115 @@
+//! Mail route commands
+//!
+//! Tauri commands for sending a linked project's mail to another Mailpit
+//! instance or an SMTP relay. The project's .env is updated through the
+//! .env fixes, like `burd env fix` does.
+
+use crate::config::{Config, MailRoute};
+use crate::env_sync;
+use crate::error::LockExt;
+use crate::lock; // Shared macro from error.rs
+use crate::mail_routing::{self, MailRouteTarget};
+use crate::services::mailpit::MailpitService;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+use uuid::Uuid;
+
+use super::AppState;
+
+/// A route with where it currently sends mail
+#[derive(Debug, Serialize)]
+pub struct MailRouteInfo {
+    #[serde(flatten)]
+    pub route: MailRoute,
+    /// None when the route's Mailpit instance was removed
+    pub destination: Option<String>,
+}
+
+/// A Mailpit instance a route can point at
+#[derive(Debug, Serialize)]
+pub struct MailpitOption {
+    pub id: Uuid,
+    pub name: String,
+    pub smtp_port: u16,
+}
+
+/// Mail routes for the frontend
+#[derive(Debug, Serialize)]
+pub struct MailRoutesOverview {
+    pub routes: Vec<MailRouteInfo>,
+    /// Linked projects routes can be added for
+    pub linked_projects: Vec<String>,
+    pub mailpits: Vec<MailpitOption>,
+}
+
+fn overview(config: &Config) -> MailRoutesOverview {
+    MailRoutesOverview {
+        routes: config
+            .mail_routes
+            .iter()
+            .map(|route| MailRouteInfo {
+                route: route.clone(),
+                destination: mail_routing::routed_destination(config, Path::new(&route.project))
+                    .map(|d| d.describe()),
+            })
+            .collect(),
+        linked_projects: env_sync::linked_projects(config)
+            .into_iter()
+            .map(|project| project.to_string_lossy().to_string())
+            .collect(),
+        mailpits: MailpitService::instances(config)
+            .into_iter()
+            .map(|instance| MailpitOption {
+                id: instance.id,
+                name: instance.name.clone(),
+                smtp_port: MailpitService::smtp_port(instance),
+            })
+            .collect(),
+    }
+}
+
+/// List mail routes and the linked projects to offer
+#[tauri::command]
+pub fn list_mail_routes(state: State<'_, AppState>) -> Result<MailRoutesOverview, String> {
+    let config = lock!(state.config_store)?.load()?;
+    Ok(overview(&config))
+}
+
+/// Route the mail of the project containing `project_path`, replacing its
+/// previous route
+#[tauri::command]
+pub async fn set_mail_route(
+    project_path: String,
+    target: MailRouteTarget,
+    state: State<'_, AppState>,
+) -> Result<MailRoutesOverview, String> {
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut config = config_store.load()?;
+
+    let target = mail_routing::validate_target(&config, target)?;
+    mail_routing::set_route(&mut config.mail_routes, Path::new(&project_path), target);
+    config_store.update_mail_routes(config.mail_routes.clone())?;
+    Ok(overview(&config))
+}
+
+/// Remove a mail route, so the project uses its stack's Mailpit again
+#[tauri::command]
+pub async fn remove_mail_route(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<MailRoutesOverview, String> {
+    let id = Uuid::parse_str(&id).map_err(|_| format!("Invalid route ID: {}", id))?;
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut config = config_store.load()?;
+
+    let before = config.mail_routes.len();
+    config.mail_routes.retain(|r| r.id != id);
+    if config.mail_routes.len() == before {
+        return Err("Mail route not found".to_string());
+    }
+    config_store.update_mail_routes(config.mail_routes.clone())?;
+    Ok(overview(&config))
+}
@@ -1,18 +1,26 @@
 // Sub-modules
+mod backup;
 mod dns;
 mod domains;
+mod env_fix;
+mod env_formats;
 mod instances;
 mod logs;
 pub mod mail;
+mod mail_routes;
 mod node;
 mod park;
 mod php;
 mod proxy;
+mod redis;
 mod services;
+mod snapshots;
 mod stacks;
 mod system;
 mod tinker;
 mod tunnels;
+mod vault;
+mod workers;
 
 // Re-export tunnel commands
 pub use tunnels::{
@@ -30,24 +38,41 @@ pub use node::{
 
 // Re-export PHP/PVM commands
 pub use php::{
-    configure_php_shell_integration, delete_php_version, download_php_version,
-    fix_php_shell_integration, get_current_php, get_php_shell_integration_status, get_pvm_status,
-    list_installed_php_versions, list_remote_php_versions, remove_php_shell_integration,
-    set_default_php_version,
+    configure_php_shell_integration, delete_composer_version, delete_php_version,
+    download_composer_version, download_php_version, fix_php_shell_integration, get_current_php,
+    get_php_extensions, get_php_shell_integration_status, get_pvm_status,
+    install_php_extension_bundle, list_installed_composer_versions, list_installed_php_versions,
+    list_php_extension_bundles, list_remote_composer_versions, list_remote_php_versions,
+    remove_php_shell_integration, set_default_composer_version, set_default_php_version,
 };
 
 // Re-export instance commands
+pub(crate) use instances::suggest_env_sync;
 pub use instances::{
-    change_instance_version, check_instance_health, check_port_status, create_instance, delete_instance,
-    generate_env_for_service, get_instance_config, get_instance_env, get_instance_info,
-    get_instance_logs, list_instances, rename_instance, reorder_instances, restart_instance,
-    start_instance, stop_instance, update_instance_config,
+    apply_env_sync, change_instance_port, change_instance_version, check_instance_health,
+    check_port_status, create_instance, delete_instance, export_instance, generate_env_for_service,
+    get_instance_config, get_instance_env, get_instance_info, get_instance_logs,
+    get_instance_metrics, get_start_diagnosis, import_instance, list_instances,
+    preview_instance_import, provision_keycloak, rename_instance, reorder_instances,
+    restart_instance, run_instance_seed, set_instance_auto_start, set_instance_extra_args,
+    set_instance_restart_on_failure, set_instance_seed, set_instance_tags,
+    set_instance_wake_on_access, setup_centrifugo, setup_soketi, start_instance, stop_instance,
+    suggest_port, update_instance_config,
 };
 
+// Re-export env snippet formats
+pub use env_formats::EnvFormat;
+
+// Re-export .env fix commands
+pub use env_fix::{apply_env_fixes, check_project_env};
+
 // Re-export domain commands
+pub(crate) use domains::apply_custom_directives;
 pub use domains::{
-    create_domain, delete_domain, get_domain_config, list_domains, reinit_domain_ssl,
-    reorder_domains, set_instance_domain, update_domain, update_domain_config, update_domain_ssl,
+    create_domain, delete_domain, export_domains, get_client_cert, get_domain_config,
+    get_domain_requests, import_domains, list_domains, preview_domain_import, reinit_domain_ssl,
+    reissue_client_cert, reorder_domains, set_domain_client_auth, set_domain_custom_directives,
+    set_domain_tld, set_instance_domain, update_domain, update_domain_config, update_domain_ssl,
 };
 
 // Re-export service commands
@@ -58,28 +83,39 @@ pub use services::{
 
 // Re-export DNS/network commands
 pub use dns::{
-    get_network_status, get_resolver_status, install_resolver, restart_dns_server,
-    start_dns_server, stop_dns_server, uninstall_resolver,
+    add_dns_record, clear_dns_queries, disable_emulation, enable_emulation, get_dns_queries,
+    get_network_status, get_resolver_status, install_resolver, list_dns_records, list_emulations,
+    remove_dns_record, restart_dns_server, run_dns_self_test, set_dns_query_log, start_dns_server,
+    stop_dns_server, uninstall_resolver,
 };
 
 // Re-export proxy commands
 pub use proxy::{
-    auto_trust_ca_if_needed, check_proxy_health, check_health_sync, disable_proxy,
-    get_ca_trust_status, get_proxy_config, get_proxy_port_conflicts, get_proxy_status,
-    restart_proxy_daemon, restart_proxy_for_certs, setup_proxy, start_proxy_daemon,
-    trust_caddy_ca, untrust_caddy_ca,
+    auto_trust_ca_if_needed, check_proxy_health, disable_proxy, export_ca_bundle,
+    export_device_bundle, get_ca_trust_status, get_certificates_status, get_proxy_config,
+    get_proxy_port_conflicts, get_proxy_status, restart_proxy_daemon, restart_proxy_for_certs,
+    setup_proxy, start_proxy_daemon, trust_ca_in_firefox, trust_caddy_ca, untrust_caddy_ca,
+    write_device_bundle,
 };
 
 // Re-export system commands (settings, CLI, helper)
 pub use system::{
-    get_cli_status, get_helper_status, get_settings, install_cli, install_helper,
-    open_keychain_access, uninstall_cli, uninstall_helper, update_tld,
+    add_remote_host, add_tld, clear_release_cache, get_api_remote_settings,
+    get_cert_monitor_settings, get_cli_status, get_github_settings, get_helper_status,
+    get_idle_stop_settings, get_log_retention_settings, get_remote_host_status, get_settings,
+    get_slug_settings, install_cli, install_helper, list_remote_hosts, open_keychain_access,
+    preview_slug, remote_instance_action, remove_remote_host, remove_tld, rotate_api_token,
+    set_default_service_version, set_release_channel, uninstall_cli, uninstall_helper,
+    update_api_remote_settings, update_cert_monitor_settings, update_github_settings,
+    update_idle_stop_settings, update_log_retention_settings, update_port_range,
+    update_slug_settings, update_tld,
 };
 
 // Re-export mail commands (Mailpit)
 pub use mail::{
-    delete_all_emails, delete_emails, get_email, get_mailpit_config, get_unread_count, list_emails,
-    mark_emails_read,
+    delete_all_emails, delete_emails, export_email_eml, get_email, get_email_html_source,
+    get_mailpit_config, get_unread_count, list_emails, mark_emails_read, save_email_attachment,
+    search_emails,
 };
 
 // Re-export tinker commands (PHP Console)
@@ -88,26 +124,56 @@ pub use tinker::{
     get_tinker_php_info, list_tinker_projects,
 };
 
+// Re-export secrets vault commands
+pub use vault::{list_vault, set_vault_secret, unset_vault_secret};
+
+// Re-export mail route commands
+pub use mail_routes::{list_mail_routes, remove_mail_route, set_mail_route};
+
+// Re-export queue worker commands
+pub use workers::{
+    get_worker_logs, list_worker_projects, list_workers, remove_worker, start_worker, stop_worker,
+};
+
 // Re-export log commands
-pub use logs::{clear_logs, get_available_log_sources, get_recent_logs, stream_logs};
+pub use logs::{
+    clear_logs, get_available_log_sources, get_log_usage, get_recent_logs, prune_logs, stream_logs,
+};
 
 // Re-export park commands
 pub use park::{
     get_parked_projects, is_park_enabled, list_parked_directories, park_directory,
     refresh_all_parked_directories, refresh_parked_directory, unpark_directory,
-    update_parked_directory_ssl,
+    update_parked_directory_depth, update_parked_directory_ssl,
+};
+
+// Re-export snapshot commands
+pub use snapshots::{
+    create_snapshot, delete_snapshot, list_snapshots, restore_snapshot,
+    update_safety_snapshot_hours, update_snapshot_retention,
+};
+
+// Re-export Redis key browser commands
+pub use redis::{delete_redis_keys, flush_redis, list_redis_databases, list_redis_keys};
+
+// Re-export backup commands
+pub use backup::{
+    export_config, get_backup_overview, import_config, list_backups, restore_backup,
+    run_backup_now, update_backup_settings,
 };
 
 // Re-export stack commands
 pub use stacks::{
-    add_instances_to_stack, create_stack, delete_stack, export_stack, get_stack, import_stack,
-    list_stacks, move_instance_to_stack, preview_stack_import, remove_instances_from_stack,
-    update_stack,
+    add_instances_to_stack, create_redis_topology, create_stack, delete_stack, export_stack,
+    get_stack, import_stack, list_stacks, move_instance_to_stack, preview_stack_import,
+    remove_instances_from_stack, restart_stack, start_stack, stop_stack, update_stack,
+    StackActionResult, StackInfo,
 };
 
 use crate::binary::BinaryManager;
-use crate::config::ConfigStore;
+use crate::config::{Config, ConfigStore};
 use crate::dns::DnsServer;
+use crate::emulation;
 use crate::process::ProcessManager;
 use crate::proxy::ProxyServer;
 use std::sync::atomic::AtomicU8;
@@ -124,6 +190,45 @@ pub struct AppState {
     pub binary_manager: Arc<Mutex<BinaryManager>>,
     pub dns_server: Arc<Mutex<DnsServer>>,
     pub proxy_server: Arc<AsyncMutex<ProxyServer>>,
-    /// Cached proxy health: 0 = unknown, 1 = healthy, 2 = unhealthy
+    /// Last proxy watchdog result (a `ProxyHealth` stored as u8)
     pub proxy_healthy: Arc<AtomicU8>,
 }
+
+impl AppState {
+    /// Build the shared state and start the DNS server
+    ///
+    /// Used by both the GUI and the headless daemon.
+    pub fn new(config_store: ConfigStore, config: &Config) -> Self {
+        // Initialize DNS server with TLD
+        let mut dns_server = DnsServer::new(config.dns_port, config.tlds());
+        dns_server.set_records(config.dns_records.clone());
+        dns_server.set_emulated_hosts(emulation::emulated_hosts(config));
+        let _ = dns_server.start();
+
+        // Initialize proxy server
+        let proxy_server = ProxyServer::new(config.proxy_port, config.tld.clone());
+
+        // Drop PID files left behind by instances that died with the last session
+        let process_manager = ProcessManager::new();
+        process_manager.clean_stale_pid_files();
+
+        let state = Self {
+            config_store: Arc::new(Mutex::new(config_store)),
+            process_manager: Arc::new(Mutex::new(process_manager)),
+            binary_manager: Arc::new(Mutex::new(BinaryManager::new())),
+            dns_server: Arc::new(Mutex::new(dns_server)),
+            proxy_server: Arc::new(AsyncMutex::new(proxy_server)),
+            proxy_healthy: Arc::new(AtomicU8::new(0)),
+        };
+
+        // Start wake-on-access instances when the proxy can't reach them
+        if let Ok(proxy) = state.proxy_server.try_lock() {
+            let waker_state = state.clone();
+            proxy.set_waker(Arc::new(move |id| {
+                crate::wake::wake_domain(&waker_state, id)
+            }));
+        }
+
+        state
+    }
+}
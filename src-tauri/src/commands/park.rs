@@ -3,13 +3,13 @@
 //! Tauri commands for managing parked directories and projects.
 
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, State};
 use uuid::Uuid;
 
 use crate::commands::AppState;
 use crate::config::ParkedDirectory;
-use crate::park::{self, DiscoveredProject, SyncResult};
+use crate::park::{self, DiscoveredProject, GitInfo, SyncResult};
 use crate::park_watcher::ParkWatcherState;
 use crate::validation;
 
@@ -31,6 +31,7 @@ pub struct ParkedDirectoryInfo {
     pub id: String,
     pub path: String,
     pub ssl_enabled: bool,
+    pub depth: u8,
     pub project_count: usize,
     pub conflicts: Vec<String>,
     pub created_at: String,
@@ -42,6 +43,7 @@ impl From<ParkedDirectory> for ParkedDirectoryInfo {
             id: pd.id.to_string(),
             path: pd.path,
             ssl_enabled: pd.ssl_enabled,
+            depth: pd.depth,
             project_count: 0, // Will be populated separately
             conflicts: Vec::new(),
             created_at: pd.created_at.to_rfc3339(),
@@ -59,11 +61,17 @@ pub struct ParkedProjectInfo {
     pub status: String, // "active", "conflict", "isolated", "error"
     pub isolated: bool,
     pub instance_id: Option<String>,
+    /// Branch, uncommitted changes and last commit (git repositories only)
+    pub git: Option<GitInfo>,
 }
 
 impl ParkedProjectInfo {
-    fn from_discovered(project: &DiscoveredProject, tld: &str, status: &str) -> Self {
-        let subdomain = park::generate_subdomain(&project.name);
+    fn from_discovered(
+        project: &DiscoveredProject,
+        subdomain: &str,
+        tld: &str,
+        status: &str,
+    ) -> Self {
         Self {
             name: project.name.clone(),
             path: project.path.to_string_lossy().to_string(),
@@ -73,6 +81,7 @@ impl ParkedProjectInfo {
             status: status.to_string(),
             isolated: false,
             instance_id: None,
+            git: None,
         }
     }
 }
@@ -120,14 +129,15 @@ pub async fn list_parked_directories(
 
     let mut result = Vec::new();
     for pd in parked_dirs {
-        let projects = park::scan_directory(std::path::Path::new(&pd.path)).unwrap_or_default();
+        let projects =
+            park::scan_directory(std::path::Path::new(&pd.path), pd.depth).unwrap_or_default();
         let project_count = projects.len();
 
         // Find conflicts
         let conflicts: Vec<String> = projects
             .iter()
             .filter_map(|p| {
-                let subdomain = park::generate_subdomain(&p.name);
+                let subdomain = park::generate_subdomain(&p.name, &config.slugs);
                 let is_conflict = config
                     .domains
                     .iter()
@@ -144,6 +154,7 @@ pub async fn list_parked_directories(
             id: pd.id.to_string(),
             path: pd.path,
             ssl_enabled: pd.ssl_enabled,
+            depth: pd.depth,
             project_count,
             conflicts,
             created_at: pd.created_at.to_rfc3339(),
@@ -154,10 +165,14 @@ pub async fn list_parked_directories(
 }
 
 /// Park a directory
+///
+/// `depth` is how many levels below the directory to look for projects
+/// (default 1, immediate subdirectories only).
 #[tauri::command]
 pub async fn park_directory(
     path: String,
     ssl_enabled: bool,
+    depth: Option<u8>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
     watcher_state: State<'_, ParkWatcherState>,
@@ -165,6 +180,7 @@ pub async fn park_directory(
     // Validate the directory path to prevent path traversal attacks
     validation::validate_directory_path(&path)
         .map_err(|e| format!("Invalid directory path: {}", e))?;
+    let depth = park::validate_depth(depth.unwrap_or(1))?;
 
     // Phase 1: Verify park is enabled and create parked directory (config_store only)
     let (parked_dir, tld) = {
@@ -175,7 +191,7 @@ pub async fn park_directory(
                     .to_string(),
             );
         }
-        let parked_dir = config_store.create_parked_directory(path.clone(), ssl_enabled)?;
+        let parked_dir = config_store.create_parked_directory(path.clone(), ssl_enabled, depth)?;
         let config = config_store.load()?;
         (parked_dir, config.tld.clone())
     };
@@ -191,15 +207,20 @@ pub async fn park_directory(
     };
 
     // Phase 4: Start file system watcher for this directory
-    let _ =
-        watcher_state.start_watching(parked_dir.id, PathBuf::from(&parked_dir.path), app_handle);
+    let _ = watcher_state.start_watching(
+        parked_dir.id,
+        PathBuf::from(&parked_dir.path),
+        parked_dir.depth,
+        app_handle,
+    );
 
     // Return info
-    let projects = park::scan_directory(std::path::Path::new(&path)).unwrap_or_default();
+    let projects = park::scan_directory(std::path::Path::new(&path), depth).unwrap_or_default();
     Ok(ParkedDirectoryInfo {
         id: parked_dir.id.to_string(),
         path: parked_dir.path,
         ssl_enabled: parked_dir.ssl_enabled,
+        depth: parked_dir.depth,
         project_count: projects.len(),
         conflicts: sync_result.conflicts,
         created_at: parked_dir.created_at.to_rfc3339(),
@@ -248,10 +269,15 @@ pub async fn unpark_directory(
 }
 
 /// Refresh/rescan a parked directory
+///
+/// Also re-watches the directory, so folders added for nested projects are
+/// watched too.
 #[tauri::command]
 pub async fn refresh_parked_directory(
     id: String,
+    app_handle: AppHandle,
     state: State<'_, AppState>,
+    watcher_state: State<'_, ParkWatcherState>,
 ) -> Result<SyncResultInfo, String> {
     let uuid = Uuid::parse_str(&id).map_err(|e| format!("Invalid UUID: {}", e))?;
 
@@ -273,6 +299,14 @@ pub async fn refresh_parked_directory(
         park::sync_parked_domains(&parked_dir, &config_store, &proxy, &tld)?
     };
 
+    // Phase 4: Re-watch to pick up new folders
+    let _ = watcher_state.start_watching(
+        parked_dir.id,
+        PathBuf::from(&parked_dir.path),
+        parked_dir.depth,
+        app_handle,
+    );
+
     Ok(result.into())
 }
 
@@ -314,15 +348,34 @@ pub async fn get_parked_projects(
 ) -> Result<Vec<ParkedProjectInfo>, String> {
     let uuid = Uuid::parse_str(&id).map_err(|e| format!("Invalid UUID: {}", e))?;
 
+    let mut result = parked_projects(uuid, &state)?;
+
+    // Git runs once per project, so only after the config lock is released
+    let paths: Vec<PathBuf> = result.iter().map(|p| PathBuf::from(&p.path)).collect();
+    let infos = tokio::task::spawn_blocking(move || {
+        let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        park::git_infos(&paths)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?;
+    for (project, git) in result.iter_mut().zip(infos) {
+        project.git = git;
+    }
+
+    Ok(result)
+}
+
+/// Projects in a parked directory with their domain status
+fn parked_projects(uuid: Uuid, state: &AppState) -> Result<Vec<ParkedProjectInfo>, String> {
     let config_store = lock!(state.config_store)?;
     let config = config_store.load()?;
 
     let parked_dir = config_store.get_parked_directory(uuid)?;
-    let projects = park::scan_directory(std::path::Path::new(&parked_dir.path))?;
+    let projects = park::scan_directory(Path::new(&parked_dir.path), parked_dir.depth)?;
 
     let mut result = Vec::new();
     for project in projects {
-        let subdomain = park::generate_subdomain(&project.name);
+        let subdomain = park::generate_subdomain(&project.name, &config.slugs);
 
         // Determine status
         let status = if let Some(domain) = config.domains.iter().find(|d| d.subdomain == subdomain)
@@ -343,7 +396,8 @@ pub async fn get_parked_projects(
             }
         };
 
-        let mut info = ParkedProjectInfo::from_discovered(&project, &config.tld, status);
+        let mut info =
+            ParkedProjectInfo::from_discovered(&project, &subdomain, &config.tld, status);
 
         // Check if isolated
         if let Some(domain) = config
@@ -382,14 +436,70 @@ pub async fn update_parked_directory_ssl(
         let _ = config_store.update_domain_ssl(domain.id, ssl_enabled);
     }
 
-    let projects = park::scan_directory(std::path::Path::new(&parked_dir.path)).unwrap_or_default();
+    let projects = park::scan_directory(std::path::Path::new(&parked_dir.path), parked_dir.depth)
+        .unwrap_or_default();
 
     Ok(ParkedDirectoryInfo {
         id: parked_dir.id.to_string(),
         path: parked_dir.path,
         ssl_enabled: parked_dir.ssl_enabled,
+        depth: parked_dir.depth,
         project_count: projects.len(),
         conflicts: Vec::new(),
         created_at: parked_dir.created_at.to_rfc3339(),
     })
 }
+
+/// Update how deep a parked directory is searched for projects
+///
+/// Re-syncs its domains (projects may appear or disappear) and re-watches it.
+#[tauri::command]
+pub async fn update_parked_directory_depth(
+    id: String,
+    depth: u8,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    watcher_state: State<'_, ParkWatcherState>,
+) -> Result<ParkedDirectoryInfo, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|e| format!("Invalid UUID: {}", e))?;
+    let depth = park::validate_depth(depth)?;
+
+    // Phase 1: Save the new depth (config_store only)
+    let (parked_dir, tld) = {
+        let config_store = lock!(state.config_store)?;
+        let parked_dir = config_store.update_parked_directory_depth(uuid, depth)?;
+        let config = config_store.load()?;
+        (parked_dir, config.tld.clone())
+    };
+    // config_store lock released here
+
+    // Phase 2: Acquire proxy lock (async)
+    let proxy = state.proxy_server.lock().await;
+
+    // Phase 3: Re-acquire config_store and sync
+    let sync_result = {
+        let config_store = lock!(state.config_store)?;
+        park::sync_parked_domains(&parked_dir, &config_store, &proxy, &tld)?
+    };
+
+    // Phase 4: Re-watch at the new depth
+    let _ = watcher_state.start_watching(
+        parked_dir.id,
+        PathBuf::from(&parked_dir.path),
+        parked_dir.depth,
+        app_handle,
+    );
+
+    let projects = park::scan_directory(std::path::Path::new(&parked_dir.path), parked_dir.depth)
+        .unwrap_or_default();
+
+    Ok(ParkedDirectoryInfo {
+        id: parked_dir.id.to_string(),
+        path: parked_dir.path,
+        ssl_enabled: parked_dir.ssl_enabled,
+        depth: parked_dir.depth,
+        project_count: projects.len(),
+        conflicts: sync_result.conflicts,
+        created_at: parked_dir.created_at.to_rfc3339(),
+    })
+}
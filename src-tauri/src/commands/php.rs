@@ -1,7 +1,10 @@
 //! PHP related commands
 //!
-//! Handles PVM (PHP Version Manager) commands for managing PHP versions.
+//! Handles PVM (PHP Version Manager) commands for managing PHP versions,
+//! and the Composer versions that run with them.
 
+use crate::pvm::composer::{self, ComposerVersion, RemoteComposerVersion};
+use crate::pvm::extensions::{self, ExtensionBundle, ExtensionBundleInfo, PhpExtensions};
 use crate::pvm::{
     self, CurrentPHP, PHPVersion, PvmStatus, RemotePHPVersion, ShellIntegrationStatus,
 };
@@ -58,6 +61,31 @@ pub async fn set_default_php_version(version: String) -> Result<(), String> {
         .map_err(|e| format!("Task error: {}", e))?
 }
 
+/// List the extensions of an installed PHP version and the bundle it came from
+#[tauri::command]
+pub async fn get_php_extensions(version: String) -> Result<PhpExtensions, String> {
+    tokio::task::spawn_blocking(move || extensions::get_extensions(&version))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// List the static-php.dev extension bundles and the versions they cover
+#[tauri::command]
+pub async fn list_php_extension_bundles() -> Result<Vec<ExtensionBundleInfo>, String> {
+    extensions::list_bundles().await
+}
+
+/// Switch an installed PHP version to the build from another extension bundle
+#[tauri::command]
+pub async fn install_php_extension_bundle(
+    version: String,
+    bundle: String,
+    app: tauri::AppHandle,
+) -> Result<PhpExtensions, String> {
+    let bundle = ExtensionBundle::parse(&bundle)?;
+    extensions::install_bundle(&version, bundle, &app).await
+}
+
 /// Get PHP shell integration status
 #[tauri::command]
 pub fn get_php_shell_integration_status() -> ShellIntegrationStatus {
@@ -87,3 +115,55 @@ pub async fn fix_php_shell_integration() -> Result<(), String> {
         .await
         .map_err(|e| format!("Task error: {}", e))?
 }
+
+// ============================================================================
+// Composer Commands
+// ============================================================================
+
+/// List installed Composer versions
+#[tauri::command]
+pub async fn list_installed_composer_versions() -> Result<Vec<ComposerVersion>, String> {
+    tokio::task::spawn_blocking(composer::list_installed_versions)
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// List the Composer release each channel points to
+#[tauri::command]
+pub async fn list_remote_composer_versions() -> Result<Vec<RemoteComposerVersion>, String> {
+    tokio::task::spawn_blocking(composer::list_remote_versions)
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Download a Composer version
+///
+/// The first version downloaded becomes the default.
+#[tauri::command]
+pub async fn download_composer_version(version: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        composer::download_version(&version)?;
+        if composer::get_default_version().is_none() {
+            composer::set_default_version(&version)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Delete a Composer version
+#[tauri::command]
+pub async fn delete_composer_version(version: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || composer::delete_version(&version))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Set the default Composer version
+#[tauri::command]
+pub async fn set_default_composer_version(version: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || composer::set_default_version(&version))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
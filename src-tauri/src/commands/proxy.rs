@@ -4,12 +4,16 @@
 
 use crate::binary::BinaryManager;
 use crate::caddy;
-use crate::config::ServiceType;
+use crate::cert_monitor::{self, CertificateStatus};
+use crate::config::{Config, ReleaseChannel, ServiceType};
 use crate::constants::PROXY_PLIST_PATH;
+use crate::device_setup::{self, DeviceBundle};
 use crate::error::LockExt;
 use crate::helper_client::{HelperClient, HelperRequest};
-use crate::launchd;
 use crate::lock; // Shared macro from error.rs
+use crate::platform;
+use crate::proxy_watchdog::{self, ProxyHealth};
+use crate::trust_stores::{self, CaExport, FirefoxProfile, FirefoxTrust};
 use serde::Serialize;
 use std::path::PathBuf;
 use tauri::{AppHandle, State};
@@ -29,47 +33,25 @@ pub struct ProxyStatus {
     pub caddy_installed: bool,
     /// None if daemon not installed/running, Some(true) if Burd's Caddy responds, Some(false) if port hijacked
     pub proxy_healthy: Option<bool>,
-}
-
-/// Check if Burd's Caddy is the one actually listening on port 80.
-/// Returns None if daemon not installed/running, Some(true) if healthy, Some(false) if port hijacked.
-pub fn check_health_sync() -> Option<bool> {
-    let daemon_status = launchd::get_status();
-    if !daemon_status.installed || !daemon_status.running {
-        return None;
-    }
-
-    // Make a quick HTTP request to the health endpoint on 127.0.0.1:80
-    let client = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(2))
-        .build()
-        .ok()?;
-
-    match client
-        .get("http://127.0.0.1/_burd/health")
-        .header("Host", "health-check.burd")
-        .send()
-    {
-        Ok(resp) => {
-            let body = resp.text().unwrap_or_default();
-            Some(body.trim() == "burd-ok")
-        }
-        Err(_) => Some(false),
-    }
+    /// Last watchdog result for whichever proxy is active (Caddy daemon or fallback)
+    pub health: ProxyHealth,
 }
 
 /// Get the status of the privileged proxy daemon (Caddy)
 #[tauri::command]
 pub fn get_proxy_status(state: State<'_, AppState>) -> Result<ProxyStatus, String> {
-    let daemon_status = launchd::get_status();
-
-    // Read cached health from AppState if available
+    let daemon_status = platform::proxy_daemon().status();
+
+    // Read the watchdog's cached result
+    let health = ProxyHealth::from_u8(
+        state
+            .proxy_healthy
+            .load(std::sync::atomic::Ordering::Relaxed),
+    );
     let proxy_healthy = if daemon_status.installed && daemon_status.running {
-        let cached = state.proxy_healthy.load(std::sync::atomic::Ordering::Relaxed);
-        // 0 = unknown/not-checked, 1 = healthy, 2 = unhealthy
-        match cached {
-            1 => Some(true),
-            2 => Some(false),
+        match health {
+            ProxyHealth::Healthy => Some(true),
+            ProxyHealth::Hijacked => Some(false),
             _ => None,
         }
     } else {
@@ -82,17 +64,14 @@ pub fn get_proxy_status(state: State<'_, AppState>) -> Result<ProxyStatus, Strin
         daemon_pid: daemon_status.pid,
         caddy_installed: caddy::is_caddy_installed(),
         proxy_healthy,
+        health,
     })
 }
 
-/// Manually check proxy health (for frontend polling)
+/// Check proxy health right now instead of waiting for the watchdog
 #[tauri::command]
-pub async fn check_proxy_health() -> Result<Option<bool>, String> {
-    let result =
-        tokio::task::spawn_blocking(check_health_sync)
-            .await
-            .map_err(|e| format!("Task error: {}", e))?;
-    Ok(result)
+pub async fn check_proxy_health(state: State<'_, AppState>) -> Result<ProxyHealth, String> {
+    Ok(proxy_watchdog::check_now(&state).await)
 }
 
 /// A process holding a port that Burd's reverse proxy needs
@@ -170,7 +149,7 @@ fn list_port_listeners(port: u16) -> Vec<(u32, String, Option<String>)> {
 #[tauri::command]
 pub async fn get_proxy_port_conflicts() -> Result<Vec<PortConflict>, String> {
     tokio::task::spawn_blocking(|| {
-        let burd_pid = launchd::get_status().pid;
+        let burd_pid = platform::proxy_daemon().status().pid;
 
         let mut conflicts = Vec::new();
         for port in [80u16, 443u16] {
@@ -206,7 +185,7 @@ pub async fn setup_proxy(app: AppHandle, state: State<'_, AppState>) -> Result<(
     if !caddy::is_caddy_installed() {
         // Get the latest Caddy version
         let versions = BinaryManager::new()
-            .get_available_versions(ServiceType::Caddy)
+            .get_available_versions(ServiceType::Caddy, ReleaseChannel::Stable)
             .await?;
 
         let version = versions
@@ -225,7 +204,7 @@ pub async fn setup_proxy(app: AppHandle, state: State<'_, AppState>) -> Result<(
         };
 
         let binary_info = binary_manager
-            .download(ServiceType::Caddy, &version, app)
+            .download(ServiceType::Caddy, &version, None, Some(app))
             .await?;
 
         // Update config with the binary info
@@ -267,7 +246,7 @@ pub async fn setup_proxy(app: AppHandle, state: State<'_, AppState>) -> Result<(
         .map_err(|e| format!("Task error: {}", e))??;
 
     // Install and start launchd daemon (this will start Caddy)
-    tokio::task::spawn_blocking(launchd::install)
+    tokio::task::spawn_blocking(|| platform::proxy_daemon().install())
         .await
         .map_err(|e| format!("Task error: {}", e))??;
 
@@ -303,7 +282,7 @@ pub async fn setup_proxy(app: AppHandle, state: State<'_, AppState>) -> Result<(
 #[tauri::command]
 pub async fn disable_proxy(state: State<'_, AppState>) -> Result<(), String> {
     // Uninstall launchd plist (run in blocking task for osascript)
-    tokio::task::spawn_blocking(launchd::uninstall)
+    tokio::task::spawn_blocking(|| platform::proxy_daemon().uninstall())
         .await
         .map_err(|e| format!("Task error: {}", e))??;
 
@@ -317,7 +296,7 @@ pub async fn disable_proxy(state: State<'_, AppState>) -> Result<(), String> {
 /// Start the privileged proxy daemon
 #[tauri::command]
 pub fn start_proxy_daemon() -> Result<(), String> {
-    launchd::start()?;
+    platform::proxy_daemon().start()?;
     // Give Caddy time to start and fix permissions
     std::thread::sleep(std::time::Duration::from_millis(300));
     let _ = fix_caddy_data_permissions();
@@ -327,7 +306,7 @@ pub fn start_proxy_daemon() -> Result<(), String> {
 /// Restart the privileged proxy daemon
 #[tauri::command]
 pub fn restart_proxy_daemon() -> Result<(), String> {
-    launchd::restart()?;
+    platform::proxy_daemon().restart()?;
     // Give Caddy time to restart and fix permissions
     std::thread::sleep(std::time::Duration::from_millis(300));
     let _ = fix_caddy_data_permissions();
@@ -339,8 +318,8 @@ pub fn restart_proxy_daemon() -> Result<(), String> {
 pub fn restart_proxy_for_certs() -> Result<(), String> {
     // With Caddy, we don't need to regenerate certificates
     // Just restart the daemon if it's installed
-    if launchd::is_installed() {
-        launchd::restart()?;
+    if platform::proxy_daemon().is_installed() {
+        platform::proxy_daemon().restart()?;
         // Give Caddy time to restart and fix permissions
         std::thread::sleep(std::time::Duration::from_millis(300));
         let _ = fix_caddy_data_permissions();
@@ -348,6 +327,20 @@ pub fn restart_proxy_for_certs() -> Result<(), String> {
     Ok(())
 }
 
+/// Expiry of every certificate Caddy has issued for a site
+#[tauri::command]
+pub async fn get_certificates_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<CertificateStatus>, String> {
+    let renew_days = {
+        let config_store = lock!(state.config_store)?;
+        config_store.load()?.cert_monitor.renew_days
+    };
+    tokio::task::spawn_blocking(move || cert_monitor::certificates_status(renew_days))
+        .await
+        .map_err(|e| format!("Task error: {}", e))
+}
+
 /// Proxy configuration info for debugging
 #[derive(Debug, Serialize)]
 pub struct ProxyConfigInfo {
@@ -378,7 +371,7 @@ pub fn get_proxy_config(state: State<'_, AppState>) -> Result<ProxyConfigInfo, S
     let plist_content = std::fs::read_to_string(PROXY_PLIST_PATH).ok();
 
     // Get daemon status
-    let daemon_status = launchd::get_status();
+    let daemon_status = platform::proxy_daemon().status();
 
     // Get Caddy version
     let caddy_version = caddy::get_caddy_version().ok();
@@ -413,12 +406,14 @@ pub struct CATrustStatus {
     pub cert_name: Option<String>,
     /// Certificate expiration date (e.g., "Nov 11 08:46:28 2035 GMT")
     pub cert_expiry: Option<String>,
+    /// Firefox profiles, which keep their own trust store
+    pub firefox_profiles: Vec<FirefoxProfile>,
 }
 
 /// Get the path to Caddy's root CA certificate
 fn get_caddy_ca_path() -> PathBuf {
     // When running as daemon with XDG_DATA_HOME set to user space, Caddy stores PKI here
-    launchd::get_caddy_data_dir().join("caddy/pki/authorities/local/root.crt")
+    platform::caddy_data_dir().join("caddy/pki/authorities/local/root.crt")
 }
 
 /// Parse certificate metadata using openssl (for user-accessible paths only)
@@ -529,6 +524,7 @@ pub fn get_ca_trust_status_internal() -> Result<CATrustStatus, String> {
         ca_path: ca_path_str,
         cert_name,
         cert_expiry,
+        firefox_profiles: trust_stores::find_firefox_profiles(),
     })
 }
 
@@ -570,9 +566,17 @@ pub fn trust_caddy_ca_internal() -> Result<(), String> {
 }
 
 /// Auto-trust CA if it exists but is not yet trusted
+/// Also imports it into Firefox profiles that don't trust it yet
 /// Returns Ok(true) if CA was trusted, Ok(false) if no action needed, Err on failure
 pub fn auto_trust_ca_if_needed() -> Result<bool, String> {
     if let Ok(status) = get_ca_trust_status_internal() {
+        let firefox_untrusted = status
+            .firefox_profiles
+            .iter()
+            .any(|p| p.trust == FirefoxTrust::NotTrusted);
+        if status.ca_exists && firefox_untrusted {
+            let _ = trust_stores::trust_in_firefox(&get_caddy_ca_path());
+        }
         if status.ca_exists && !status.is_trusted {
             trust_caddy_ca_internal()?;
             return Ok(true);
@@ -588,7 +592,7 @@ pub fn fix_caddy_data_permissions() -> Result<(), String> {
         return Err("Helper is not running".to_string());
     }
 
-    let caddy_data_path = launchd::get_caddy_data_dir();
+    let caddy_data_path = platform::caddy_data_dir();
     let path_str = caddy_data_path.to_string_lossy().to_string();
 
     let response =
@@ -650,3 +654,59 @@ pub async fn untrust_caddy_ca() -> Result<(), String> {
         }
     }
 }
+
+/// Make Firefox trust Caddy's root CA
+///
+/// Firefox keeps its own trust store per profile; returns the profiles with
+/// their updated status.
+#[tauri::command]
+pub async fn trust_ca_in_firefox() -> Result<Vec<FirefoxProfile>, String> {
+    let ca_path = get_caddy_ca_path();
+    if !ca_path.exists() {
+        return Err("CA certificate not found".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || trust_stores::trust_in_firefox(&ca_path))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Export Caddy's root CA as PEM files with setup instructions for curl,
+/// Node and other tools that don't read the keychain
+#[tauri::command]
+pub fn export_ca_bundle(dir: Option<String>) -> Result<CaExport, String> {
+    let dir = match dir {
+        Some(dir) => PathBuf::from(dir),
+        None => trust_stores::default_export_dir()?,
+    };
+    trust_stores::export_pem_files(&get_caddy_ca_path(), &dir)
+}
+
+/// Write the setup bundle other devices use to trust the root CA and reach
+/// this Mac's domains (served at `http://<lan-ip>/burd-setup/`)
+pub fn write_device_bundle(config: &Config) -> Result<DeviceBundle, String> {
+    let ca_path = get_caddy_ca_path();
+    if !ca_path.exists() {
+        return Err(
+            "The root CA hasn't been generated yet. Enable HTTPS on a domain and open it once."
+                .to_string(),
+        );
+    }
+
+    let bundle = device_setup::write_bundle(&ca_path, config)?;
+
+    // Caddyfiles written by older versions don't serve the bundle yet
+    if platform::proxy_daemon().is_installed() {
+        caddy::write_main_caddyfile(&config.tld)?;
+    }
+
+    Ok(bundle)
+}
+
+/// Export the device setup bundle (CA certificate, configuration profile,
+/// hosts entries and instructions) for phones and other computers
+#[tauri::command]
+pub fn export_device_bundle(state: State<'_, AppState>) -> Result<DeviceBundle, String> {
+    let config = lock!(state.config_store)?.load()?;
+    write_device_bundle(&config)
+}
@@ -0,0 +1,97 @@
+//! Redis key browser commands
+//!
+//! Lists, inspects, and deletes the keys of running Redis/Valkey instances.
+
+use crate::error::LockExt;
+use crate::lock;
+use crate::redis_manager::{DatabaseInfo, KeyScan, RedisManager, DEFAULT_KEY_LIMIT};
+use tauri::State;
+use uuid::Uuid;
+
+use super::AppState;
+
+/// Create a manager for a running Redis or Valkey instance
+fn get_manager(instance_id: &str, state: &AppState) -> Result<RedisManager, String> {
+    let uuid = Uuid::parse_str(instance_id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let config = config_store.load()?;
+    let instance = config
+        .instances
+        .iter()
+        .find(|i| i.id == uuid)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let manager = RedisManager::for_instance(instance)?;
+
+    let process_manager = lock!(state.process_manager)?;
+    if !process_manager.get_status(instance).running {
+        return Err(format!("{} is not running", instance.name));
+    }
+
+    Ok(manager)
+}
+
+/// Run a manager call on a blocking thread
+async fn run<T: Send + 'static>(
+    instance_id: &str,
+    state: &AppState,
+    f: impl FnOnce(RedisManager) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let manager = get_manager(instance_id, state)?;
+    tokio::task::spawn_blocking(move || f(manager))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// List the databases of an instance with their key counts
+#[tauri::command]
+pub async fn list_redis_databases(
+    instance_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<DatabaseInfo>, String> {
+    run(&instance_id, &state, |m| m.list_databases()).await
+}
+
+/// List keys matching a glob-style pattern (all keys by default)
+#[tauri::command]
+pub async fn list_redis_keys(
+    instance_id: String,
+    db: u32,
+    pattern: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<KeyScan, String> {
+    run(&instance_id, &state, move |m| {
+        m.list_keys(
+            db,
+            pattern.as_deref().unwrap_or_default(),
+            limit.unwrap_or(DEFAULT_KEY_LIMIT),
+        )
+    })
+    .await
+}
+
+/// Delete keys, returning how many existed
+#[tauri::command]
+pub async fn delete_redis_keys(
+    instance_id: String,
+    db: u32,
+    keys: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<u64, String> {
+    run(&instance_id, &state, move |m| m.delete_keys(db, &keys)).await
+}
+
+/// Delete every key of one database, or of all databases when `db` is `None`
+#[tauri::command]
+pub async fn flush_redis(
+    instance_id: String,
+    db: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    run(&instance_id, &state, move |m| match db {
+        Some(db) => m.flush_database(db),
+        None => m.flush_all(),
+    })
+    .await
+}
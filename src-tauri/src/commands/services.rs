@@ -3,7 +3,7 @@
 //! Handles binary downloads, version management, and service registry.
 
 use crate::binary::{BinaryStatus, VersionInfo};
-use crate::config::ServiceType;
+use crate::config::{ReleaseChannel, ServiceType};
 use crate::error::LockExt;
 use crate::lock; // Shared macro from error.rs
 use crate::service_config::{ServiceInfo, ServiceRegistry};
@@ -26,12 +26,19 @@ pub fn parse_service_type(s: &str) -> Result<ServiceType, String> {
         "valkey" => Ok(ServiceType::Valkey),
         "mailpit" => Ok(ServiceType::Mailpit),
         "beanstalkd" => Ok(ServiceType::Beanstalkd),
+        "rabbitmq" => Ok(ServiceType::RabbitMQ),
+        "opensearch" => Ok(ServiceType::OpenSearch),
         "memcached" => Ok(ServiceType::Memcached),
         "frpc" => Ok(ServiceType::Frpc),
         "caddy" => Ok(ServiceType::Caddy),
         "centrifugo" => Ok(ServiceType::Centrifugo),
+        "soketi" => Ok(ServiceType::Soketi),
+        "keycloak" => Ok(ServiceType::Keycloak),
         "gitea" => Ok(ServiceType::Gitea),
         "bun" => Ok(ServiceType::Bun),
+        id if ServiceRegistry::load().definition_ids().contains(&id) => {
+            Ok(ServiceType::from_id(id))
+        }
         _ => Err(format!("Unknown service type: {}", s)),
     }
 }
@@ -61,12 +68,21 @@ pub fn get_all_binary_statuses(state: State<'_, AppState>) -> Result<Vec<BinaryS
     binary_manager.get_all_statuses_sync(&config_store)
 }
 
+/// Get downloadable versions, from the service's release channel unless one is given
 #[tauri::command]
 pub async fn get_available_versions(
     service_type: String,
+    channel: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Vec<VersionInfo>, String> {
     let svc_type = parse_service_type(&service_type)?;
+    let channel = match channel {
+        Some(channel) => ReleaseChannel::parse(&channel)?,
+        None => {
+            let config_store = lock!(state.config_store)?;
+            config_store.load()?.release_channel(svc_type)
+        }
+    };
 
     let binary_manager = {
         state
@@ -76,7 +92,9 @@ pub async fn get_available_versions(
             .clone()
     };
 
-    binary_manager.get_available_versions(svc_type).await
+    binary_manager
+        .get_available_versions(svc_type, channel)
+        .await
 }
 
 /// Get installed versions for a service type
@@ -134,6 +152,10 @@ pub async fn download_binary(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let svc_type = parse_service_type(&service_type)?;
+    let arch_override = {
+        let config_store = lock!(state.config_store)?;
+        config_store.load()?.arch_overrides.get(&svc_type).copied()
+    };
 
     // Get binary manager and config store, perform the download
     let binary_manager = {
@@ -144,7 +166,9 @@ pub async fn download_binary(
             .clone()
     };
 
-    let binary_info = binary_manager.download(svc_type, &version, app).await?;
+    let binary_info = binary_manager
+        .download(svc_type, &version, arch_override, Some(app))
+        .await?;
 
     // Update config with the binary info
     let config_store = lock!(state.config_store)?;
@@ -0,0 +1,93 @@
+//! Snapshot related commands
+//!
+//! Handles snapshots of non-SQL services (Meilisearch, MongoDB, Redis/Valkey, MinIO).
+
+use crate::config::Instance;
+use crate::error::LockExt;
+use crate::lock;
+use crate::snapshot::{self, SnapshotInfo};
+use tauri::State;
+use uuid::Uuid;
+
+use super::AppState;
+
+/// Look up an instance with its running state and the snapshot retention
+fn resolve_instance(id: &str, state: &AppState) -> Result<(Instance, bool, usize), String> {
+    let uuid = Uuid::parse_str(id).map_err(|_| "Invalid instance ID")?;
+
+    let config_store = lock!(state.config_store)?;
+    let config = config_store.load()?;
+    let instance = config
+        .instances
+        .into_iter()
+        .find(|i| i.id == uuid)
+        .ok_or_else(|| format!("Instance {} not found", id))?;
+
+    let process_manager = lock!(state.process_manager)?;
+    let running = process_manager.get_status(&instance).running;
+
+    Ok((instance, running, config.snapshot_retention))
+}
+
+/// List snapshots of an instance, newest first
+#[tauri::command]
+pub fn list_snapshots(instance_id: String) -> Result<Vec<SnapshotInfo>, String> {
+    let uuid = Uuid::parse_str(&instance_id).map_err(|_| "Invalid instance ID")?;
+    snapshot::list_snapshots(&uuid)
+}
+
+/// Create a snapshot of an instance
+#[tauri::command]
+pub async fn create_snapshot(
+    instance_id: String,
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SnapshotInfo, String> {
+    let (instance, running, retention) = resolve_instance(&instance_id, &state)?;
+
+    tokio::task::spawn_blocking(move || {
+        snapshot::create_snapshot(&instance, running, label, retention)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Restore a snapshot into its instance
+#[tauri::command]
+pub async fn restore_snapshot(
+    instance_id: String,
+    snapshot_id: String,
+    state: State<'_, AppState>,
+) -> Result<SnapshotInfo, String> {
+    let (instance, running, _) = resolve_instance(&instance_id, &state)?;
+
+    tokio::task::spawn_blocking(move || {
+        snapshot::restore_snapshot(&instance, &snapshot_id, running)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Delete a snapshot
+#[tauri::command]
+pub fn delete_snapshot(instance_id: String, snapshot_id: String) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&instance_id).map_err(|_| "Invalid instance ID")?;
+    snapshot::delete_snapshot(&uuid, &snapshot_id)
+}
+
+/// Update the number of snapshots kept per instance (0 keeps all)
+#[tauri::command]
+pub fn update_snapshot_retention(
+    retention: usize,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let config_store = lock!(state.config_store)?;
+    config_store.update_snapshot_retention(retention)
+}
+
+/// Update how many hours the CLI's safety snapshots are kept (0 turns them off)
+#[tauri::command]
+pub fn update_safety_snapshot_hours(hours: u32, state: State<'_, AppState>) -> Result<(), String> {
+    let config_store = lock!(state.config_store)?;
+    config_store.update_safety_snapshot_hours(hours)
+}
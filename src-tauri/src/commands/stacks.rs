@@ -2,16 +2,21 @@
 //!
 //! Handles stack management for grouping instances and team sharing.
 
+use crate::autostart;
 use crate::config::{
-    ConflictResolution, Domain, DomainTarget, ImportConflict, ImportResult, Instance,
-    MissingVersion, Stack, StackDomain, StackExport, StackImportPreview, StackRequirements,
-    StackService,
+    is_default_alias, Config, ConflictResolution, Domain, DomainTarget, ImportConflict,
+    ImportResult, Instance, MissingVersion, Stack, StackDomain, StackExport, StackImportPreview,
+    StackRequirements, StackService,
 };
 use crate::error::LockExt;
+use crate::instance_export::strip_secrets;
 use crate::lock;
+use crate::metrics::UsageRollup;
+use crate::process::ProcessManager;
+use crate::services::redis_topology::{self, TopologyRequest};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
 use super::AppState;
@@ -27,10 +32,50 @@ pub struct StackInfo {
     pub name: String,
     pub description: Option<String>,
     pub instance_count: usize,
+    pub running_count: usize,
+    /// Combined CPU, memory, and disk usage of the running instances
+    pub usage: UsageRollup,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl StackInfo {
+    /// Stack with its instance counts and current resource usage
+    pub(crate) fn new(stack: &Stack, config: &Config, process_manager: &ProcessManager) -> Self {
+        let instances: Vec<&Instance> = config
+            .instances
+            .iter()
+            .filter(|i| i.stack_id == Some(stack.id))
+            .collect();
+        let running_count = instances
+            .iter()
+            .filter(|i| process_manager.get_status(i).running)
+            .count();
+
+        StackInfo {
+            id: stack.id.to_string(),
+            name: stack.name.clone(),
+            description: stack.description.clone(),
+            instance_count: instances.len(),
+            running_count,
+            usage: process_manager.usage_of(instances),
+            created_at: stack.created_at.to_rfc3339(),
+            updated_at: stack.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Outcome of a stack action for one instance
+#[derive(Debug, Clone, Serialize)]
+pub struct StackActionResult {
+    pub id: String,
+    pub name: String,
+    pub success: bool,
+    /// True when the instance was already in the requested state
+    pub skipped: bool,
+    pub error: Option<String>,
+}
+
 /// Create stack request payload
 #[derive(Debug, Deserialize)]
 pub struct CreateStackRequest {
@@ -40,6 +85,19 @@ pub struct CreateStackRequest {
     pub instance_ids: Vec<String>,
 }
 
+/// Create Redis/Valkey replication topology request payload
+#[derive(Debug, Deserialize)]
+pub struct CreateRedisTopologyRequest {
+    pub name: String,
+    pub service_type: String,
+    pub version: String,
+    pub replicas: u8,
+    #[serde(default)]
+    pub sentinels: u8,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
 /// Update stack request payload
 #[derive(Debug, Deserialize)]
 pub struct UpdateStackRequest {
@@ -65,42 +123,24 @@ fn default_true() -> bool {
 // Stack CRUD Commands
 // ============================================================================
 
-/// List all stacks with their instance counts
+/// List all stacks with their instance counts and resource usage
 #[tauri::command]
 pub async fn list_stacks(state: State<'_, AppState>) -> Result<Vec<StackInfo>, String> {
-    let config_store = lock!(state.config_store)?;
-    let config = config_store.load()?;
+    let config = lock!(state.config_store)?.load()?;
+    let process_manager = lock!(state.process_manager)?;
 
-    let stacks = config
+    Ok(config
         .stacks
         .iter()
-        .map(|stack| {
-            let instance_count = config
-                .instances
-                .iter()
-                .filter(|i| i.stack_id == Some(stack.id))
-                .count();
-
-            StackInfo {
-                id: stack.id.to_string(),
-                name: stack.name.clone(),
-                description: stack.description.clone(),
-                instance_count,
-                created_at: stack.created_at.to_rfc3339(),
-                updated_at: stack.updated_at.to_rfc3339(),
-            }
-        })
-        .collect();
-
-    Ok(stacks)
+        .map(|stack| StackInfo::new(stack, &config, &process_manager))
+        .collect())
 }
 
 /// Get a specific stack
 #[tauri::command]
 pub async fn get_stack(id: String, state: State<'_, AppState>) -> Result<StackInfo, String> {
     let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid stack ID")?;
-    let config_store = lock!(state.config_store)?;
-    let config = config_store.load()?;
+    let config = lock!(state.config_store)?.load()?;
 
     let stack = config
         .stacks
@@ -108,20 +148,8 @@ pub async fn get_stack(id: String, state: State<'_, AppState>) -> Result<StackIn
         .find(|s| s.id == uuid)
         .ok_or_else(|| format!("Stack {} not found", id))?;
 
-    let instance_count = config
-        .instances
-        .iter()
-        .filter(|i| i.stack_id == Some(uuid))
-        .count();
-
-    Ok(StackInfo {
-        id: stack.id.to_string(),
-        name: stack.name.clone(),
-        description: stack.description.clone(),
-        instance_count,
-        created_at: stack.created_at.to_rfc3339(),
-        updated_at: stack.updated_at.to_rfc3339(),
-    })
+    let process_manager = lock!(state.process_manager)?;
+    Ok(StackInfo::new(stack, &config, &process_manager))
 }
 
 /// Create a new stack from selected instances
@@ -137,17 +165,48 @@ pub async fn create_stack(
         .collect::<Result<Vec<_>, _>>()?;
 
     let config_store = lock!(state.config_store)?;
-    let stack =
-        config_store.create_stack(request.name, request.description, instance_ids.clone())?;
-
-    Ok(StackInfo {
-        id: stack.id.to_string(),
-        name: stack.name,
-        description: stack.description,
-        instance_count: instance_ids.len(),
-        created_at: stack.created_at.to_rfc3339(),
-        updated_at: stack.updated_at.to_rfc3339(),
-    })
+    let stack = config_store.create_stack(request.name, request.description, instance_ids)?;
+    let config = config_store.load()?;
+
+    let process_manager = lock!(state.process_manager)?;
+    Ok(StackInfo::new(&stack, &config, &process_manager))
+}
+
+/// Create a Redis/Valkey master with replicas (and optional Sentinels) as a new stack
+#[tauri::command]
+pub async fn create_redis_topology(
+    request: CreateRedisTopologyRequest,
+    state: State<'_, AppState>,
+) -> Result<StackInfo, String> {
+    let service_type = super::parse_service_type(&request.service_type)?;
+
+    let binary_manager = lock!(state.binary_manager)?;
+    let installed_versions = binary_manager.get_installed_versions_sync(service_type)?;
+    if !installed_versions.contains(&request.version) {
+        return Err(format!(
+            "Version {} is not installed for {}",
+            request.version,
+            service_type.display_name()
+        ));
+    }
+    drop(binary_manager);
+
+    let config_store = lock!(state.config_store)?;
+    let (stack, _) = redis_topology::create_topology(
+        &config_store,
+        &TopologyRequest {
+            name: request.name,
+            service_type,
+            version: request.version,
+            replicas: request.replicas,
+            sentinels: request.sentinels,
+            password: request.password,
+        },
+    )?;
+    let config = config_store.load()?;
+
+    let process_manager = lock!(state.process_manager)?;
+    Ok(StackInfo::new(&stack, &config, &process_manager))
 }
 
 /// Update a stack's name and/or description
@@ -161,16 +220,10 @@ pub async fn update_stack(
     let config_store = lock!(state.config_store)?;
 
     let stack = config_store.update_stack(uuid, request.name, request.description)?;
-    let instances = config_store.get_instances_in_stack(uuid)?;
-
-    Ok(StackInfo {
-        id: stack.id.to_string(),
-        name: stack.name,
-        description: stack.description,
-        instance_count: instances.len(),
-        created_at: stack.created_at.to_rfc3339(),
-        updated_at: stack.updated_at.to_rfc3339(),
-    })
+    let config = config_store.load()?;
+
+    let process_manager = lock!(state.process_manager)?;
+    Ok(StackInfo::new(&stack, &config, &process_manager))
 }
 
 /// Delete a stack
@@ -242,34 +295,153 @@ pub async fn move_instance_to_stack(
 }
 
 // ============================================================================
-// Export Commands
+// Stack Lifecycle Commands
 // ============================================================================
 
-/// Fields that should be stripped from config as secrets
-const SECRET_FIELDS: &[&str] = &["password", "master_key", "api_key", "token", "secret"];
-
-/// Strip secret fields from a config value
-fn strip_secrets(config: &serde_json::Value) -> serde_json::Value {
-    match config {
-        serde_json::Value::Object(map) => {
-            let mut new_map = serde_json::Map::new();
-            for (key, value) in map {
-                let key_lower = key.to_lowercase();
-                if SECRET_FIELDS.iter().any(|s| key_lower.contains(s)) {
-                    // Skip secret fields
-                    continue;
-                }
-                new_map.insert(key.clone(), strip_secrets(value));
-            }
-            serde_json::Value::Object(new_map)
-        }
-        serde_json::Value::Array(arr) => {
-            serde_json::Value::Array(arr.iter().map(strip_secrets).collect())
-        }
-        other => other.clone(),
+/// A stack member and whether it's running
+struct Member {
+    id: Uuid,
+    name: String,
+    running: bool,
+    /// Later members need it healthy before they start
+    wait: bool,
+}
+
+/// Members of a stack in start order
+fn stack_members(id: &str, state: &AppState) -> Result<Vec<Member>, String> {
+    let uuid = Uuid::parse_str(id).map_err(|_| "Invalid stack ID")?;
+    let config = lock!(state.config_store)?.load()?;
+    if !config.stacks.iter().any(|s| s.id == uuid) {
+        return Err(format!("Stack {} not found", id));
+    }
+    let process_manager = lock!(state.process_manager)?;
+
+    let members = autostart::stack_members(&config.instances, uuid);
+    Ok(members
+        .iter()
+        .map(|i| Member {
+            id: i.id,
+            name: i.name.clone(),
+            running: process_manager.get_status(i).running,
+            wait: autostart::starts_before_others(i, &members),
+        })
+        .collect())
+}
+
+/// Start the members that aren't running, waiting for each dependency to
+/// pass its health check before the members that need it
+async fn start_members(
+    members: &[Member],
+    state: &State<'_, AppState>,
+    app: &AppHandle,
+) -> Vec<StackActionResult> {
+    let mut results = Vec::new();
+    for member in members {
+        let error = if member.running {
+            None
+        } else {
+            super::start_instance(
+                member.id.to_string(),
+                Some(member.wait),
+                None,
+                state.clone(),
+                app.clone(),
+            )
+            .await
+            .err()
+        };
+        results.push(StackActionResult {
+            id: member.id.to_string(),
+            name: member.name.clone(),
+            success: error.is_none(),
+            skipped: member.running,
+            error,
+        });
+    }
+    results
+}
+
+/// Stop the running members, apps before the services they depend on
+async fn stop_members(
+    members: &[Member],
+    state: &State<'_, AppState>,
+    app: &AppHandle,
+) -> Vec<StackActionResult> {
+    let mut results = Vec::new();
+    for member in members.iter().rev() {
+        let error = if member.running {
+            super::stop_instance(member.id.to_string(), state.clone(), app.clone())
+                .await
+                .err()
+        } else {
+            None
+        };
+        results.push(StackActionResult {
+            id: member.id.to_string(),
+            name: member.name.clone(),
+            success: error.is_none(),
+            skipped: !member.running,
+            error,
+        });
+    }
+    results.reverse();
+    results
+}
+
+/// Start every instance of a stack, databases and other backing services
+/// first
+///
+/// A failing instance doesn't stop the others; every outcome is returned.
+#[tauri::command]
+pub async fn start_stack(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<StackActionResult>, String> {
+    let members = stack_members(&id, &state)?;
+    Ok(start_members(&members, &state, &app).await)
+}
+
+/// Stop every instance of a stack, apps first
+#[tauri::command]
+pub async fn stop_stack(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<StackActionResult>, String> {
+    let members = stack_members(&id, &state)?;
+    Ok(stop_members(&members, &state, &app).await)
+}
+
+/// Stop every instance of a stack, then start them all in dependency order
+#[tauri::command]
+pub async fn restart_stack(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<StackActionResult>, String> {
+    let mut members = stack_members(&id, &state)?;
+    let stopped = stop_members(&members, &state, &app).await;
+    // Members that failed to stop are left running and reported as such
+    for (member, stop) in members.iter_mut().zip(&stopped) {
+        member.running = stop.error.is_some();
     }
+    let started = start_members(&members, &state, &app).await;
+
+    Ok(started
+        .into_iter()
+        .zip(stopped)
+        .map(|(start, stop)| match stop.error {
+            Some(_) => stop,
+            None => start,
+        })
+        .collect())
 }
 
+// ============================================================================
+// Export Commands
+// ============================================================================
+
 /// Export a stack to JSON format for sharing
 #[tauri::command]
 pub async fn export_stack(
@@ -305,6 +477,7 @@ pub async fn export_stack(
             name: instance.name.clone(),
             port: instance.port,
             auto_start: instance.auto_start,
+            extra_args: instance.extra_args.clone(),
             config: strip_secrets(&instance.config),
         })
         .collect();
@@ -360,6 +533,27 @@ pub async fn export_stack(
 // Import Commands
 // ============================================================================
 
+/// Replace `default` (or omitted) service versions in a stack template with
+/// the configured default version
+///
+/// A pinned version that isn't installed yet is kept so the preview reports
+/// it as missing.
+fn resolve_template_versions(config: &Config, import: &mut StackExport) -> Result<(), String> {
+    for service in &mut import.services {
+        if !is_default_alias(&service.version) {
+            continue;
+        }
+        service.version = match config.default_version(service.service_type) {
+            Ok(version) => version,
+            Err(e) => config
+                .pinned_version(service.service_type)
+                .map(String::from)
+                .ok_or(e)?,
+        };
+    }
+    Ok(())
+}
+
 /// Preview a stack import - validates and detects conflicts
 #[tauri::command]
 pub async fn preview_stack_import(
@@ -367,11 +561,12 @@ pub async fn preview_stack_import(
     state: State<'_, AppState>,
 ) -> Result<StackImportPreview, String> {
     // Parse the import config
-    let import: StackExport =
+    let mut import: StackExport =
         serde_json::from_str(&config_json).map_err(|e| format!("Invalid stack config: {}", e))?;
 
     let config_store = lock!(state.config_store)?;
     let config = config_store.load()?;
+    resolve_template_versions(&config, &mut import)?;
 
     // Check if stack with this ID already exists
     let existing_stack = config.stacks.iter().find(|s| s.id == import.id).cloned();
@@ -441,11 +636,17 @@ pub async fn import_stack(
     state: State<'_, AppState>,
 ) -> Result<ImportResult, String> {
     // Parse the import config
-    let import: StackExport =
+    let mut import: StackExport =
         serde_json::from_str(&config_json).map_err(|e| format!("Invalid stack config: {}", e))?;
 
     let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
     let mut config = config_store.load()?;
+    resolve_template_versions(&config, &mut import)?;
+    for service in &import.services {
+        crate::validation::validate_extra_args(&service.extra_args)
+            .map_err(|e| format!("Service '{}': {}", service.name, e))?;
+    }
 
     // Build resolution maps
     let mut port_reassignments: std::collections::HashMap<String, u16> =
@@ -552,6 +753,7 @@ pub async fn import_stack(
             instance.version = service.version.clone();
             instance.config = service.config.clone();
             instance.auto_start = service.auto_start;
+            instance.extra_args = service.extra_args.clone();
             instances_updated.push(instance.id);
             ref_to_instance.insert(service.ref_id.clone(), instance.id);
         } else {
@@ -565,10 +767,16 @@ pub async fn import_stack(
                 config: service.config.clone(),
                 master_key: None,
                 auto_start: service.auto_start,
+                restart_on_failure: false,
+                wake_on_access: false,
                 created_at: Utc::now(),
                 domain: None,
                 domain_enabled: true,
                 stack_id: Some(stack.id),
+                tags: Vec::new(),
+                extra_args: service.extra_args.clone(),
+                // Seeds point at local files and directories, so they aren't shared
+                seed: None,
             };
             instances_created.push(instance.id);
             ref_to_instance.insert(service.ref_id.clone(), instance.id);
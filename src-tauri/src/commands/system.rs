@@ -2,14 +2,28 @@
 //!
 //! Handles settings, CLI, and helper tool management.
 
-use crate::config::DomainTarget;
+use crate::api::{self, remote};
+use crate::api_client::{self, BurdApiClient};
+use crate::cert_monitor;
+use crate::commands::suggest_env_sync;
+use crate::config::{
+    ApiRemoteSettings, CertMonitorSettings, GitHubSettings, IdleStopSettings, LogRetentionSettings,
+    PortRange, ReleaseChannel, RemoteHost, SlugSettings,
+};
 use crate::constants::CLI_INSTALL_PATH;
+use crate::domain::{self, DomainManager};
+use crate::env_sync;
 use crate::error::LockExt;
+use crate::github::{self, ReleaseCache};
 use crate::helper_client::HelperClient;
-use crate::launchd;
+use crate::idle;
 use crate::lock; // Shared macro from error.rs
+use crate::logs;
+use crate::platform;
+use crate::tld_migration::{self, TldMigration};
 use crate::validation;
 use serde::Serialize;
+use std::collections::HashMap;
 use tauri::{AppHandle, Manager, State};
 
 use super::AppState;
@@ -22,8 +36,17 @@ use super::AppState;
 #[derive(Debug, Serialize)]
 pub struct AppSettings {
     pub tld: String,
+    /// TLDs domains can use besides the primary one
+    pub extra_tlds: Vec<String>,
     pub dns_port: u16,
     pub proxy_port: u16,
+    pub port_range: PortRange,
+    pub snapshot_retention: usize,
+    pub safety_snapshot_hours: u32,
+    /// Pinned default version per service id (e.g. "mariadb": "10.11")
+    pub default_versions: HashMap<String, String>,
+    /// Release channel per service id, for services not on stable
+    pub release_channels: HashMap<String, ReleaseChannel>,
 }
 
 /// Get current application settings
@@ -34,107 +57,579 @@ pub fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
 
     Ok(AppSettings {
         tld: config.tld,
+        extra_tlds: config.extra_tlds,
         dns_port: config.dns_port,
         proxy_port: config.proxy_port,
+        port_range: config.port_range,
+        snapshot_retention: config.snapshot_retention,
+        safety_snapshot_hours: config.safety_snapshot_hours,
+        default_versions: config
+            .default_versions
+            .iter()
+            .map(|(service_type, version)| (service_type.as_str().to_string(), version.clone()))
+            .collect(),
+        release_channels: config
+            .release_channels
+            .iter()
+            .map(|(service_type, channel)| (service_type.as_str().to_string(), *channel))
+            .collect(),
     })
 }
 
-/// Update the TLD setting
-/// Note: Requires app restart to take effect for DNS/proxy servers
+/// Update the TLD setting and move everything over to it
+///
+/// Moves the resolver file, DNS server and proxy routes to the new TLD and
+/// offers .env updates for linked projects. Anything that couldn't be moved
+/// is listed in the returned manual steps.
 #[tauri::command]
-pub fn update_tld(tld: String, state: State<'_, AppState>) -> Result<(), String> {
-    // Validate and normalize TLD
-    let new_tld = tld.trim().to_lowercase();
-    validation::validate_tld(&new_tld).map_err(|e| format!("Invalid TLD: {}", e))?;
+pub async fn update_tld(
+    tld: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<TldMigration, String> {
+    let new_tld = tld_migration::validate(&tld)?;
+
+    // Save the new TLD, keeping the old config for the migration
+    let (old_tld, config) = {
+        let config_store = lock!(state.config_store)?;
+        let old_tld = config_store.load()?.tld;
+        config_store.update_tld(new_tld.clone())?;
+        (old_tld, config_store.load()?)
+    };
+
+    let mut migration = TldMigration::new(&old_tld, &new_tld);
+    if old_tld == new_tld {
+        return Ok(migration);
+    }
+
+    // Move the resolver if it was installed for the old TLD
+    if platform::dns_resolver().is_installed(&old_tld) {
+        let moved = platform::dns_resolver()
+            .uninstall(&old_tld)
+            .and_then(|_| platform::dns_resolver().install(&new_tld, config.dns_port));
+        match moved {
+            Ok(()) => {
+                migration.resolver_updated = true;
+                let _ = platform::dns_resolver().flush_cache();
+            }
+            Err(e) => migration.manual_steps.push(format!(
+                "Couldn't move the resolver ({}); install it for .{} in Settings",
+                e, new_tld
+            )),
+        }
+    } else {
+        migration.manual_steps.push(format!(
+            "No resolver is installed, so .{} domains won't resolve until you install it in Settings",
+            new_tld
+        ));
+    }
+
+    let dns_result = {
+        let mut dns = lock!(state.dns_server)?;
+        dns.set_tlds(config.tlds())
+    };
+    if let Err(e) = dns_result {
+        migration.manual_steps.push(format!(
+            "The DNS server didn't restart ({}); restart it in Settings",
+            e
+        ));
+    }
+
+    // Re-point proxy routes (and the Caddyfile) at the new TLD
+    if let Err(e) = state.proxy_server.lock().await.set_tld(&new_tld) {
+        migration
+            .manual_steps
+            .push(format!("Couldn't update the proxy routes: {}", e));
+    }
+    if platform::proxy_daemon().is_installed() {
+        // Restart Caddy so it issues certificates for the new domains
+        if let Err(e) = platform::proxy_daemon().restart() {
+            migration.manual_steps.push(format!(
+                "Couldn't restart the proxy daemon ({}); restart it in Settings",
+                e
+            ));
+        }
+    }
+
+    // Instances started with a domain keep the old one until restarted
+    let stale: Vec<String> = {
+        let process_manager = lock!(state.process_manager)?;
+        config
+            .instances
+            .iter()
+            .filter(|i| i.domain_enabled && process_manager.is_running(&i.id))
+            .map(|i| i.name.clone())
+            .collect()
+    };
+    if !stale.is_empty() {
+        migration.manual_steps.push(format!(
+            "Restart {} to pick up the new domain",
+            stale.join(", ")
+        ));
+    }
+
+    migration.env_changes = env_sync::plan_tld_change(&config, &old_tld, &new_tld);
+    migration.manual_steps.extend(tld_migration::manual_steps(
+        &config,
+        &old_tld,
+        &new_tld,
+        &migration.env_changes,
+    ));
+    suggest_env_sync(&app, migration.env_changes.clone());
 
-    // Get old TLD to check if it changed
-    let old_tld = {
+    Ok(migration)
+}
+
+/// Answer for the configured TLDs and refresh the proxy's catch-all
+async fn apply_tlds(tlds: Vec<String>, state: &AppState) -> Result<Vec<String>, String> {
+    lock!(state.dns_server)?.set_tlds(tlds.clone())?;
+    // Rewrites the main Caddyfile so the catch-all covers every TLD
+    let _ = state.proxy_server.lock().await.sync_to_daemon();
+    Ok(tlds)
+}
+
+/// Add a TLD domains can use besides the primary one
+///
+/// Installs its resolver file (prompting for admin privileges unless the
+/// helper is running) and returns every configured TLD.
+#[tauri::command]
+pub async fn add_tld(tld: String, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let tld = tld_migration::validate(&tld)?;
+
+    let (dns_port, tlds) = {
         let config_store = lock!(state.config_store)?;
-        config_store.load()?.tld.clone()
+        config_store.add_tld(tld.clone())?;
+        let config = config_store.load()?;
+        (config.dns_port, config.tlds())
     };
 
-    // Update the config
-    {
+    tokio::task::spawn_blocking(move || {
+        platform::dns_resolver().install(&tld, dns_port)?;
+        platform::dns_resolver().flush_cache()
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    apply_tlds(tlds, &state).await
+}
+
+/// Remove an extra TLD no domain uses anymore, along with its resolver file
+#[tauri::command]
+pub async fn remove_tld(tld: String, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let tld = tld.trim().trim_start_matches('.').to_lowercase();
+
+    let tlds = {
         let config_store = lock!(state.config_store)?;
-        config_store.update_tld(new_tld.clone())?;
+        config_store.remove_tld(&tld)?;
+        config_store.load()?.tlds()
+    };
+
+    tokio::task::spawn_blocking(move || {
+        platform::dns_resolver().uninstall(&tld)?;
+        platform::dns_resolver().flush_cache()
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    apply_tlds(tlds, &state).await
+}
+
+/// Update the port range used for automatic port assignment
+#[tauri::command]
+pub fn update_port_range(start: u16, end: u16, state: State<'_, AppState>) -> Result<(), String> {
+    validation::validate_port(start).map_err(|e| format!("Invalid range start: {}", e))?;
+    validation::validate_port(end).map_err(|e| format!("Invalid range end: {}", e))?;
+    if start > end {
+        return Err("Port range start must not exceed end".to_string());
     }
 
-    // If TLD changed, update resolver and certificates
-    if old_tld != new_tld {
-        // Update resolver if it was installed for old TLD
-        if crate::resolver::is_installed(&old_tld) {
-            // Get DNS port from config
-            let dns_port = {
-                let config_store = lock!(state.config_store)?;
-                config_store.load()?.dns_port
-            };
+    let config_store = lock!(state.config_store)?;
+    config_store.update_port_range(PortRange { start, end })
+}
 
-            // Uninstall old resolver
-            crate::resolver::uninstall(&old_tld)?;
+/// Pin the default version for a service type (an empty or missing version unpins it)
+#[tauri::command]
+pub fn set_default_service_version(
+    service_type: String,
+    version: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let svc_type = super::parse_service_type(&service_type)?;
+    let version = version
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if let Some(version) = &version {
+        validation::validate_version(version).map_err(|e| format!("Invalid version: {}", e))?;
+    }
 
-            // Install new resolver
-            crate::resolver::install(&new_tld, dns_port)?;
+    let config_store = lock!(state.config_store)?;
+    config_store.set_default_service_version(svc_type, version)
+}
+
+/// Choose which releases (stable, rc, or beta) are offered for a service type
+#[tauri::command]
+pub fn set_release_channel(
+    service_type: String,
+    channel: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let svc_type = super::parse_service_type(&service_type)?;
+    let channel = ReleaseChannel::parse(&channel)?;
 
-            // Flush DNS cache
-            let _ = crate::resolver::flush_dns_cache();
+    let config_store = lock!(state.config_store)?;
+    config_store.set_release_channel(svc_type, channel)
+}
+
+/// GitHub API settings, without the token itself
+#[derive(Serialize)]
+pub struct GitHubSettingsInfo {
+    /// A token is saved in settings
+    pub token_configured: bool,
+    /// GITHUB_TOKEN is set in Burd's environment (used when no token is saved)
+    pub env_token: bool,
+    pub cache_ttl_minutes: u32,
+}
+
+impl From<GitHubSettings> for GitHubSettingsInfo {
+    fn from(settings: GitHubSettings) -> Self {
+        Self {
+            token_configured: settings.token.is_some(),
+            env_token: std::env::var(github::TOKEN_ENV_VAR).is_ok_and(|t| !t.trim().is_empty()),
+            cache_ttl_minutes: settings.cache_ttl_minutes,
         }
+    }
+}
+
+/// Get GitHub API settings
+#[tauri::command]
+pub fn get_github_settings(state: State<'_, AppState>) -> Result<GitHubSettingsInfo, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.github.into())
+}
+
+/// Update GitHub API settings
+///
+/// A missing `token` keeps the saved one; an empty one removes it.
+#[tauri::command]
+pub fn update_github_settings(
+    token: Option<String>,
+    cache_ttl_minutes: u32,
+    state: State<'_, AppState>,
+) -> Result<GitHubSettingsInfo, String> {
+    let config_store = lock!(state.config_store)?;
+    let current = config_store.load()?.github;
+    let token = match token {
+        Some(token) => Some(token.trim().to_string()).filter(|t| !t.is_empty()),
+        None => current.token,
+    };
+    let settings = GitHubSettings {
+        token,
+        cache_ttl_minutes,
+    };
+
+    config_store.update_github_settings(settings.clone())?;
+    Ok(settings.into())
+}
+
+/// Get the idle auto-stop settings
+#[tauri::command]
+pub fn get_idle_stop_settings(state: State<'_, AppState>) -> Result<IdleStopSettings, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.idle_stop)
+}
+
+/// Update the idle auto-stop settings
+#[tauri::command]
+pub fn update_idle_stop_settings(
+    enabled: bool,
+    idle_minutes: u32,
+    exclude_databases: bool,
+    state: State<'_, AppState>,
+) -> Result<IdleStopSettings, String> {
+    let settings = idle::validate_settings(IdleStopSettings {
+        enabled,
+        idle_minutes,
+        exclude_databases,
+    })?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_idle_stop_settings(settings.clone())?;
+    Ok(settings)
+}
+
+/// Get the certificate expiry monitor settings
+#[tauri::command]
+pub fn get_cert_monitor_settings(
+    state: State<'_, AppState>,
+) -> Result<CertMonitorSettings, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.cert_monitor)
+}
 
-        // Regenerate certificates if daemon is installed
-        if launchd::is_installed() {
-            // Get all domains with the new TLD
-            let routes: Vec<crate::caddy::RouteEntry> = {
-                let config_store = lock!(state.config_store)?;
-                let config = config_store.load()?;
-
-                let mut routes: Vec<crate::caddy::RouteEntry> = Vec::new();
-
-                // Include all domain routes
-                for d in &config.domains {
-                    match &d.target {
-                        DomainTarget::Instance(instance_id) => {
-                            if let Some(instance) =
-                                config.instances.iter().find(|i| i.id == *instance_id)
-                            {
-                                routes.push(crate::caddy::RouteEntry::reverse_proxy(
-                                    d.full_domain(&new_tld),
-                                    instance.port,
-                                    d.id.to_string(),
-                                    d.ssl_enabled,
-                                ));
-                            }
-                        }
-                        DomainTarget::Port(port) => {
-                            routes.push(crate::caddy::RouteEntry::reverse_proxy(
-                                d.full_domain(&new_tld),
-                                *port,
-                                d.id.to_string(),
-                                d.ssl_enabled,
-                            ));
-                        }
-                        DomainTarget::StaticFiles { path, browse } => {
-                            routes.push(crate::caddy::RouteEntry::file_server(
-                                d.full_domain(&new_tld),
-                                path.clone(),
-                                *browse,
-                                d.id.to_string(),
-                                d.ssl_enabled,
-                            ));
-                        }
-                    }
-                }
-
-                routes
-            };
-
-            // Write new Caddyfile (Caddy will auto-reload)
-            crate::caddy::write_caddyfile(&new_tld, &routes)?;
-
-            // Restart daemon to ensure it picks up changes
-            launchd::restart()?;
+/// Update the certificate expiry monitor settings
+#[tauri::command]
+pub fn update_cert_monitor_settings(
+    enabled: bool,
+    renew_days: u32,
+    state: State<'_, AppState>,
+) -> Result<CertMonitorSettings, String> {
+    let settings = cert_monitor::validate_settings(CertMonitorSettings {
+        enabled,
+        renew_days,
+    })?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_cert_monitor_settings(settings.clone())?;
+    Ok(settings)
+}
+
+/// Get the log rotation settings
+#[tauri::command]
+pub fn get_log_retention_settings(
+    state: State<'_, AppState>,
+) -> Result<LogRetentionSettings, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.log_retention)
+}
+
+/// Update the log rotation settings
+#[tauri::command]
+pub fn update_log_retention_settings(
+    settings: LogRetentionSettings,
+    state: State<'_, AppState>,
+) -> Result<LogRetentionSettings, String> {
+    let settings = logs::validate_retention_settings(settings)?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_log_retention_settings(settings.clone())?;
+    Ok(settings)
+}
+
+/// Get how project and instance names become domain slugs
+#[tauri::command]
+pub fn get_slug_settings(state: State<'_, AppState>) -> Result<SlugSettings, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.slugs)
+}
+
+/// Update how project and instance names become domain slugs
+#[tauri::command]
+pub fn update_slug_settings(
+    settings: SlugSettings,
+    state: State<'_, AppState>,
+) -> Result<SlugSettings, String> {
+    let settings = domain::validate_slug_settings(settings)?;
+
+    let config_store = lock!(state.config_store)?;
+    config_store.update_slug_settings(settings.clone())?;
+    Ok(settings)
+}
+
+/// The slug `name` would get with `settings`, for previewing unsaved changes
+#[tauri::command]
+pub fn preview_slug(name: String, settings: SlugSettings) -> String {
+    DomainManager::slugify_with(&name, &settings)
+}
+
+/// Forget cached GitHub release listings, returning how many were removed
+#[tauri::command]
+pub fn clear_release_cache() -> Result<usize, String> {
+    ReleaseCache::new()?.clear()
+}
+
+/// Remote API access settings, with the token for display
+#[derive(Serialize)]
+pub struct ApiRemoteInfo {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub allowed_origins: Vec<String>,
+    pub token: Option<String>,
+    pub port: u16,
+}
+
+impl From<ApiRemoteSettings> for ApiRemoteInfo {
+    fn from(settings: ApiRemoteSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            bind_address: settings.bind_address,
+            allowed_origins: settings.allowed_origins,
+            token: settings.token,
+            port: api::API_PORT,
         }
     }
+}
 
-    Ok(())
+/// Get remote API access settings
+#[tauri::command]
+pub fn get_api_remote_settings(state: State<'_, AppState>) -> Result<ApiRemoteInfo, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.api_remote.into())
+}
+
+/// Update remote API access settings
+/// Note: Requires app restart to take effect for the API server
+#[tauri::command]
+pub fn update_api_remote_settings(
+    enabled: bool,
+    bind_address: String,
+    allowed_origins: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<ApiRemoteInfo, String> {
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let current = config_store.load()?.api_remote;
+    let settings = remote::validate(ApiRemoteSettings {
+        enabled,
+        bind_address,
+        allowed_origins,
+        token: current.token,
+    })?;
+
+    config_store.update_api_remote(settings.clone())?;
+    Ok(settings.into())
+}
+
+/// Replace the API token, invalidating the old one
+/// Note: Requires app restart to take effect for the API server
+#[tauri::command]
+pub fn rotate_api_token(state: State<'_, AppState>) -> Result<ApiRemoteInfo, String> {
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut settings = config_store.load()?.api_remote;
+    settings.token = Some(remote::generate_token());
+
+    config_store.update_api_remote(settings.clone())?;
+    Ok(settings.into())
+}
+
+// ============================================================================
+// Remote Host Commands
+// ============================================================================
+
+/// A remote host's reachability and instances (as its `GET /instances` lists them)
+#[derive(Serialize)]
+pub struct RemoteHostStatus {
+    pub reachable: bool,
+    pub error: Option<String>,
+    pub instances: Vec<serde_json::Value>,
+}
+
+fn find_remote_host(
+    config_store: &crate::config::ConfigStore,
+    name: &str,
+) -> Result<RemoteHost, String> {
+    config_store
+        .load()?
+        .remote_hosts
+        .into_iter()
+        .find(|h| h.name == name)
+        .ok_or_else(|| format!("No remote host named '{}'", name))
+}
+
+/// List the saved remote hosts
+#[tauri::command]
+pub fn list_remote_hosts(state: State<'_, AppState>) -> Result<Vec<RemoteHost>, String> {
+    let config_store = lock!(state.config_store)?;
+    Ok(config_store.load()?.remote_hosts)
+}
+
+/// Save a remote host, replacing one with the same name
+#[tauri::command]
+pub fn add_remote_host(
+    name: String,
+    url: String,
+    token: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RemoteHost>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Name is required".to_string());
+    }
+    let host = RemoteHost {
+        name,
+        url: api_client::normalize_url(&url)?,
+        token: token.filter(|t| !t.trim().is_empty()),
+    };
+
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut hosts = config_store.load()?.remote_hosts;
+    hosts.retain(|h| !h.name.eq_ignore_ascii_case(&host.name));
+    hosts.push(host);
+    config_store.update_remote_hosts(hosts.clone())?;
+    Ok(hosts)
+}
+
+/// Forget a saved remote host
+#[tauri::command]
+pub fn remove_remote_host(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<RemoteHost>, String> {
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut hosts = config_store.load()?.remote_hosts;
+    hosts.retain(|h| h.name != name);
+    config_store.update_remote_hosts(hosts.clone())?;
+    Ok(hosts)
+}
+
+/// Check a remote host and list its instances
+#[tauri::command]
+pub async fn get_remote_host_status(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<RemoteHostStatus, String> {
+    let host = {
+        let config_store = lock!(state.config_store)?;
+        find_remote_host(&config_store, &name)?
+    };
+
+    tokio::task::spawn_blocking(
+        move || match BurdApiClient::for_host(&host).get("/instances") {
+            Ok(body) => RemoteHostStatus {
+                reachable: true,
+                error: None,
+                instances: serde_json::from_str(&body).unwrap_or_default(),
+            },
+            Err(e) => RemoteHostStatus {
+                reachable: false,
+                error: Some(e),
+                instances: Vec::new(),
+            },
+        },
+    )
+    .await
+    .map_err(|e| format!("Task error: {}", e))
+}
+
+/// Start, stop or restart an instance on a remote host
+#[tauri::command]
+pub async fn remote_instance_action(
+    name: String,
+    instance_id: String,
+    action: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if !matches!(action.as_str(), "start" | "stop" | "restart") {
+        return Err(format!("Unknown action '{}'", action));
+    }
+    let instance_id = uuid::Uuid::parse_str(&instance_id).map_err(|_| "Invalid instance ID")?;
+    let host = {
+        let config_store = lock!(state.config_store)?;
+        find_remote_host(&config_store, &name)?
+    };
+
+    tokio::task::spawn_blocking(move || {
+        BurdApiClient::for_host(&host)
+            .post(
+                &format!("/instances/{}/{}", instance_id, action),
+                &serde_json::json!({}),
+            )
+            .map(|_| ())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
 }
 
 // ============================================================================
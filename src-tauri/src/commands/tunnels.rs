@@ -3,7 +3,8 @@
 //! Handles FRP server management, tunnel CRUD, and frpc process control.
 
 use crate::config::{
-    FrpServer, ServiceType, SubdomainConfig, Tunnel, TunnelState, TunnelTarget, TunnelWithState,
+    FrpServer, HeaderPreset, ServiceType, SubdomainConfig, Tunnel, TunnelState, TunnelTarget,
+    TunnelWithState,
 };
 use crate::error::LockExt;
 use crate::lock; // Shared macro from error.rs
@@ -84,6 +85,8 @@ pub struct CreateTunnelRequest {
     pub subdomain_value: Option<String>,
     pub protocol: Option<String>,
     pub auto_start: Option<bool>,
+    /// Response header presets, e.g. "noindex" (see `HeaderPreset`)
+    pub header_presets: Option<Vec<String>>,
 }
 
 /// frpc connection status response
@@ -112,6 +115,7 @@ pub struct FrpcProxyStatus {
 pub async fn list_tunnels(state: State<'_, AppState>) -> Result<Vec<TunnelWithState>, String> {
     let (tunnels, servers, instances) = {
         let config_store = lock!(state.config_store)?;
+        let _lock = config_store.lock_file()?;
         let mut config = config_store.load()?;
 
         // Migrate: Generate random subdomains for any tunnels missing them
@@ -195,7 +199,7 @@ pub async fn list_tunnels(state: State<'_, AppState>) -> Result<Vec<TunnelWithSt
 
 /// Reload frpc config if it's running (after tunnel changes)
 /// This sends SIGHUP to the frpc process to hot-reload the config
-fn reload_frpc_if_running(state: &State<'_, AppState>) -> Result<(), String> {
+pub(crate) fn reload_frpc_if_running(state: &State<'_, AppState>) -> Result<(), String> {
     let frpc_manager = FrpcManager::new()?;
     if !frpc_manager.is_running() {
         return Ok(()); // Not running, nothing to reload
@@ -282,17 +286,23 @@ pub fn create_tunnel(
 
     let protocol = request.protocol.unwrap_or_else(|| "http".to_string());
     let auto_start = request.auto_start.unwrap_or(false);
+    let header_presets = HeaderPreset::parse_all(&request.header_presets.unwrap_or_default())?;
 
     let tunnel = {
         let config_store = lock!(state.config_store)?;
-        config_store.create_tunnel(
+        let tunnel = config_store.create_tunnel(
             request.name,
             server_id,
             target,
             subdomain,
             protocol,
             auto_start,
-        )?
+        )?;
+        if header_presets.is_empty() {
+            tunnel
+        } else {
+            config_store.update_tunnel_header_presets(tunnel.id, header_presets)?
+        }
     };
 
     // Reload frpc if running to pick up the new tunnel
@@ -330,8 +340,12 @@ pub fn update_tunnel(
     subdomain_type: Option<String>,
     subdomain_value: Option<String>,
     protocol: Option<String>,
+    header_presets: Option<Vec<String>>,
 ) -> Result<Tunnel, String> {
     let tunnel_id = Uuid::parse_str(&id).map_err(|_| "Invalid tunnel ID")?;
+    let header_presets = header_presets
+        .map(|presets| HeaderPreset::parse_all(&presets))
+        .transpose()?;
 
     // Parse optional server_id
     let server_id_parsed = match server_id {
@@ -369,7 +383,7 @@ pub fn update_tunnel(
 
     let tunnel = {
         let config_store = lock!(state.config_store)?;
-        config_store.update_tunnel(
+        let tunnel = config_store.update_tunnel(
             tunnel_id,
             name,
             server_id_parsed,
@@ -377,7 +391,11 @@ pub fn update_tunnel(
             subdomain,
             protocol,
             None,
-        )?
+        )?;
+        match header_presets {
+            Some(presets) => config_store.update_tunnel_header_presets(tunnel_id, presets)?,
+            None => tunnel,
+        }
     };
 
     // Reload frpc if running to apply changes
@@ -0,0 +1,72 @@
+//! Secrets vault commands
+//!
+//! Tauri commands for per-project secrets kept in the keychain. Values are
+//! written but never read back to the frontend.
+
+use crate::config::VaultProject;
+use crate::env_sync;
+use crate::error::LockExt;
+use crate::lock; // Shared macro from error.rs
+use crate::vault;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+
+use super::AppState;
+
+/// Vault contents for the frontend
+#[derive(Debug, Serialize)]
+pub struct VaultOverview {
+    /// Projects with secrets (names only)
+    pub projects: Vec<VaultProject>,
+    /// Linked projects secrets can be added to
+    pub linked_projects: Vec<String>,
+}
+
+/// List projects with secrets and the linked projects to offer
+#[tauri::command]
+pub fn list_vault(state: State<'_, AppState>) -> Result<VaultOverview, String> {
+    let config = lock!(state.config_store)?.load()?;
+    Ok(VaultOverview {
+        linked_projects: env_sync::linked_projects(&config)
+            .into_iter()
+            .map(|project| project.to_string_lossy().to_string())
+            .collect(),
+        projects: config.vault,
+    })
+}
+
+/// Store a secret for the project containing `project_path`
+#[tauri::command]
+pub async fn set_vault_secret(
+    project_path: String,
+    key: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<VaultProject>, String> {
+    let project = vault::project_root(Path::new(&project_path));
+    let key = key.trim().to_string();
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut projects = config_store.load()?.vault;
+
+    vault::set(&mut projects, &project, &key, &value)?;
+    config_store.update_vault(projects.clone())?;
+    Ok(projects)
+}
+
+/// Remove a project's secret
+#[tauri::command]
+pub async fn unset_vault_secret(
+    project_path: String,
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<VaultProject>, String> {
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+    let mut projects = config_store.load()?.vault;
+
+    vault::unset(&mut projects, Path::new(&project_path), &key);
+    config_store.update_vault(projects.clone())?;
+    Ok(projects)
+}
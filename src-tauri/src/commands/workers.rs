@@ -0,0 +1,108 @@
+//! Queue worker commands
+//!
+//! Tauri commands for running `queue:work`/Horizon in linked Laravel projects.
+
+use crate::error::LockExt;
+use crate::lock; // Shared macro from error.rs
+use crate::process::ProcessManager;
+use crate::workers::{self, WorkerMode, WorkerOptions, WorkerStatus};
+use std::path::Path;
+use tauri::State;
+use uuid::Uuid;
+
+use super::AppState;
+
+/// List saved workers with their running processes
+#[tauri::command]
+pub fn list_workers(state: State<'_, AppState>) -> Result<Vec<WorkerStatus>, String> {
+    let config = lock!(state.config_store)?.load()?;
+    let process_manager = lock!(state.process_manager)?;
+    Ok(config
+        .workers
+        .iter()
+        .map(|worker| process_manager.get_worker_status(worker))
+        .collect())
+}
+
+/// Linked Laravel projects a worker can be started for
+#[tauri::command]
+pub fn list_worker_projects(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = lock!(state.config_store)?.load()?;
+    Ok(workers::laravel_projects(&config)
+        .into_iter()
+        .map(|project| project.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Start the worker of a Laravel project, saving any settings given
+///
+/// Settings left out keep their saved values (one `queue:work` process on
+/// the default queue for a new worker).
+#[tauri::command]
+pub async fn start_worker(
+    project_path: String,
+    mode: Option<String>,
+    concurrency: Option<u8>,
+    queue: Option<String>,
+    connection: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<WorkerStatus, String> {
+    let project = workers::project_root(Path::new(&project_path))?;
+    let options = WorkerOptions {
+        mode: mode.as_deref().map(WorkerMode::parse).transpose()?,
+        concurrency,
+        queue,
+        connection,
+    };
+
+    let config_store = lock!(state.config_store)?;
+    let process_manager = lock!(state.process_manager)?;
+
+    let config_lock = config_store.lock_file()?;
+    let mut saved = config_store.load()?.workers;
+    let worker = workers::upsert_worker(&mut saved, &project, options)?;
+    if process_manager.get_worker_status(&worker).running {
+        return Err(format!(
+            "The worker for '{}' is already running. Stop it first to apply new settings.",
+            worker.name()
+        ));
+    }
+    config_store.update_workers(saved)?;
+    drop(config_lock);
+
+    process_manager.start_worker(&worker)?;
+    Ok(process_manager.get_worker_status(&worker))
+}
+
+/// Stop a worker's processes; returns how many were running
+#[tauri::command]
+pub async fn stop_worker(id: String, state: State<'_, AppState>) -> Result<usize, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid worker ID")?;
+    let process_manager = lock!(state.process_manager)?;
+    process_manager.stop_worker(&uuid)
+}
+
+/// Stop a worker and forget its settings
+#[tauri::command]
+pub async fn remove_worker(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid worker ID")?;
+    let config_store = lock!(state.config_store)?;
+    let _lock = config_store.lock_file()?;
+
+    let mut saved = config_store.load()?.workers;
+    let before = saved.len();
+    saved.retain(|w| w.id != uuid);
+    if saved.len() == before {
+        return Err("Worker not found".to_string());
+    }
+
+    lock!(state.process_manager)?.stop_worker(&uuid)?;
+    config_store.update_workers(saved)
+}
+
+/// The last lines of a worker's log
+#[tauri::command]
+pub fn get_worker_logs(id: String) -> Result<String, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| "Invalid worker ID")?;
+    ProcessManager::read_worker_logs(&uuid)
+}
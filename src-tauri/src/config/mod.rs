@@ -1,27 +1,63 @@
 //! Configuration module
 //!
-//! Handles application configuration, data models, and path utilities.
+//! Handles application configuration, data models, path utilities, default
+//! service versions, and config template resolution.
 
 mod models;
 mod paths;
 mod store;
+pub mod template;
+mod versions;
 
 // Re-export models
 pub use models::{
+    ApiRemoteSettings,
+    BackupSettings,
     BinaryInfo,
+    CertMonitorSettings,
     Config,
     ConflictResolution,
+    DnsRecord,
+    DnsRecordType,
     Domain,
+    // Domain export types
+    DomainConflictResolution,
+    DomainExport,
+    DomainImportConflict,
+    DomainImportPreview,
+    DomainImportResult,
     DomainSource,
     DomainTarget,
+    Emulation,
+    ExportedDomain,
+    ExportedDomainTarget,
+    ExportedInstance,
+    ExportedInstanceDomain,
     // Tunnel types (re-exported from tunnel module)
     FrpServer,
+    GitHubSettings,
+    HeaderPreset,
+    IdleStopSettings,
     ImportConflict,
     ImportResult,
     Instance,
+    InstanceCandidate,
+    // Instance export types
+    InstanceExport,
+    InstanceImportConflict,
+    InstanceImportPreview,
+    InstanceImportResult,
+    LogRetentionSettings,
+    MailRoute,
     MissingVersion,
     ParkedDirectory,
+    PortRange,
+    RemoteHost,
+    SeedSettings,
     ServiceType,
+    SlugScript,
+    SlugSettings,
+    SlugTruncate,
     // Stack types
     Stack,
     StackDomain,
@@ -34,14 +70,21 @@ pub use models::{
     TunnelState,
     TunnelTarget,
     TunnelWithState,
+    VaultProject,
+    Worker,
 };
 
+// Re-export version defaults
+pub use versions::{compare_versions, is_default_alias, ReleaseChannel, DEFAULT_VERSION_ALIAS};
+
 // Re-export store
 pub use store::ConfigStore;
 
 // Re-export path utilities
+#[cfg(any(test, feature = "test-support"))]
+pub use paths::set_app_dir_override;
 pub use paths::{
     get_app_dir, get_bin_dir, get_binary_name, get_binary_path, get_instance_dir,
-    get_instances_dir, get_pids_dir, get_service_bin_dir, get_versioned_binary_dir,
-    get_versioned_binary_path,
+    get_instances_dir, get_pids_dir, get_service_bin_dir, get_snapshots_dir,
+    get_versioned_binary_dir, get_versioned_binary_path,
 };
@@ -2,14 +2,23 @@
 //!
 //! Contains Domain, Instance, ServiceType, BinaryInfo, and Config structs.
 
+use super::versions::ReleaseChannel;
+use crate::arch::{Arch, BinaryArch};
+use crate::service_config::{self, ServiceRegistry};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
+pub use crate::dns::{DnsRecord, DnsRecordType};
+pub use crate::emulation::Emulation;
+pub use crate::mail_routing::MailRoute;
+pub use crate::vault::VaultProject;
+pub use crate::workers::Worker;
+
 // Re-export tunnel types for convenience
 pub use crate::tunnel::{
-    FrpServer, SubdomainConfig, Tunnel, TunnelState, TunnelTarget, TunnelWithState,
+    FrpServer, HeaderPreset, SubdomainConfig, Tunnel, TunnelState, TunnelTarget, TunnelWithState,
 };
 
 // ============================================================================
@@ -58,11 +67,27 @@ pub struct Domain {
     pub id: Uuid,
     /// Subdomain only (e.g., "api" not "api.burd")
     pub subdomain: String,
+    /// One of the extra TLDs to use instead of the primary one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tld: Option<String>,
     /// What this domain routes to
     pub target: DomainTarget,
     /// Whether SSL (HTTPS) is enabled for this domain
     #[serde(default)]
     pub ssl_enabled: bool,
+    /// Redirect HTTP to HTTPS (only applies when SSL is enabled)
+    #[serde(default)]
+    pub force_https: bool,
+    /// Send a Strict-Transport-Security header over HTTPS (only applies when SSL is enabled)
+    #[serde(default)]
+    pub hsts: bool,
+    /// Require a client certificate issued by Burd (only applies when SSL is enabled)
+    #[serde(default)]
+    pub require_client_cert: bool,
+    /// Raw Caddyfile directives added to the domain's site blocks (headers,
+    /// redirects, basic auth, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_directives: Option<String>,
     /// Where this domain originated from (manual, parked, or isolated)
     #[serde(default)]
     pub source: DomainSource,
@@ -76,8 +101,13 @@ impl Domain {
         Self {
             id: Uuid::new_v4(),
             subdomain,
+            tld: None,
             target: DomainTarget::Instance(instance_id),
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            require_client_cert: false,
+            custom_directives: None,
             source: DomainSource::Manual,
             created_at: Utc::now(),
         }
@@ -88,8 +118,13 @@ impl Domain {
         Self {
             id: Uuid::new_v4(),
             subdomain,
+            tld: None,
             target: DomainTarget::Port(port),
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            require_client_cert: false,
+            custom_directives: None,
             source: DomainSource::Manual,
             created_at: Utc::now(),
         }
@@ -105,8 +140,13 @@ impl Domain {
         Self {
             id: Uuid::new_v4(),
             subdomain,
+            tld: None,
             target: DomainTarget::StaticFiles { path, browse },
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            require_client_cert: false,
+            custom_directives: None,
             source: DomainSource::Manual,
             created_at: Utc::now(),
         }
@@ -122,8 +162,13 @@ impl Domain {
         Self {
             id: Uuid::new_v4(),
             subdomain,
+            tld: None,
             target: DomainTarget::Port(port),
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            require_client_cert: false,
+            custom_directives: None,
             source: DomainSource::Parked { parked_dir_id },
             created_at: Utc::now(),
         }
@@ -140,8 +185,13 @@ impl Domain {
         Self {
             id: Uuid::new_v4(),
             subdomain,
+            tld: None,
             target: DomainTarget::StaticFiles { path, browse },
             ssl_enabled,
+            force_https: false,
+            hsts: false,
+            require_client_cert: false,
+            custom_directives: None,
             source: DomainSource::Parked { parked_dir_id },
             created_at: Utc::now(),
         }
@@ -169,8 +219,10 @@ impl Domain {
     }
 
     /// Get the full domain with TLD (e.g., "api.burd")
+    ///
+    /// `tld` is the primary TLD, used unless the domain has its own.
     pub fn full_domain(&self, tld: &str) -> String {
-        format!("{}.{}", self.subdomain, tld)
+        format!("{}.{}", self.subdomain, self.tld.as_deref().unwrap_or(tld))
     }
 
     /// Get the target port (resolves instance to its port if needed)
@@ -197,15 +249,15 @@ impl Domain {
 // ============================================================================
 
 /// Supported service types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "lowercase")]
+///
+/// Serialized as the service ID (`as_str`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ServiceType {
     Meilisearch,
     MongoDB,
     Typesense,
     MinIO,
     FrankenPHP,
-    #[serde(rename = "frankenphp-park")]
     FrankenPhpPark,
     MariaDB,
     MySQL,
@@ -214,12 +266,22 @@ pub enum ServiceType {
     Valkey,
     Mailpit,
     Beanstalkd,
+    RabbitMQ,
+    OpenSearch,
     Memcached,
     Frpc,
     Caddy,
     Centrifugo,
+    Soketi,
+    Keycloak,
     Gitea,
     Bun,
+    /// A service from a user service definition, by ID
+    ///
+    /// Its display name and default port come from the `ServiceRegistry`,
+    /// which only sees definitions installed by another process after a
+    /// restart.
+    Custom(&'static str),
 }
 
 impl ServiceType {
@@ -238,12 +300,20 @@ impl ServiceType {
             ServiceType::Valkey => "Valkey",
             ServiceType::Mailpit => "Mailpit",
             ServiceType::Beanstalkd => "Beanstalkd",
+            ServiceType::RabbitMQ => "RabbitMQ",
+            ServiceType::OpenSearch => "OpenSearch",
             ServiceType::Memcached => "Memcached",
             ServiceType::Frpc => "Tunnels (frpc)",
             ServiceType::Caddy => "Caddy",
             ServiceType::Centrifugo => "Centrifugo",
+            ServiceType::Soketi => "Soketi",
+            ServiceType::Keycloak => "Keycloak",
             ServiceType::Gitea => "Gitea",
             ServiceType::Bun => "Bun",
+            ServiceType::Custom(id) => ServiceRegistry::load()
+                .get_service(id)
+                .map(|service| service.display_name.as_str())
+                .unwrap_or(id),
         }
     }
 
@@ -263,12 +333,17 @@ impl ServiceType {
             ServiceType::Valkey => "valkey",
             ServiceType::Mailpit => "mailpit",
             ServiceType::Beanstalkd => "beanstalkd",
+            ServiceType::RabbitMQ => "rabbitmq",
+            ServiceType::OpenSearch => "opensearch",
             ServiceType::Memcached => "memcached",
             ServiceType::Frpc => "frpc",
             ServiceType::Caddy => "caddy",
             ServiceType::Centrifugo => "centrifugo",
+            ServiceType::Soketi => "soketi",
+            ServiceType::Keycloak => "keycloak",
             ServiceType::Gitea => "gitea",
             ServiceType::Bun => "bun",
+            ServiceType::Custom(id) => id,
         }
     }
 
@@ -287,16 +362,53 @@ impl ServiceType {
             ServiceType::Valkey => 6380,
             ServiceType::Mailpit => 8025,
             ServiceType::Beanstalkd => 11300,
+            ServiceType::RabbitMQ => 5672,
+            ServiceType::OpenSearch => 9200,
             ServiceType::Memcached => 11211,
             ServiceType::Frpc => 0, // frpc doesn't have a default port
             ServiceType::Caddy => 443,
             ServiceType::Centrifugo => 8000,
+            ServiceType::Soketi => 6001,
+            ServiceType::Keycloak => 8180,
             ServiceType::Gitea => 3000,
             ServiceType::Bun => 3000,
+            ServiceType::Custom(id) => ServiceRegistry::load()
+                .get_service(id)
+                .map(|service| service.default_port)
+                .unwrap_or(0),
         }
     }
 
+    /// Every service type, built-in ones first, then user-defined ones
     pub fn all() -> Vec<ServiceType> {
+        let mut all = Self::builtin();
+        all.extend(
+            ServiceRegistry::load()
+                .definition_ids()
+                .into_iter()
+                .map(ServiceType::Custom),
+        );
+        all
+    }
+
+    /// Look up a service type by ID; unknown IDs become `Custom`
+    ///
+    /// Instances of a service whose definition was removed keep their type,
+    /// so the config still loads.
+    pub fn from_id(id: &str) -> ServiceType {
+        Self::builtin_by_id(id)
+            .unwrap_or_else(|| ServiceType::Custom(service_config::intern_service_id(id)))
+    }
+
+    /// The built-in (or internal) service with this ID
+    pub fn builtin_by_id(id: &str) -> Option<ServiceType> {
+        Self::builtin()
+            .into_iter()
+            .chain([ServiceType::Caddy])
+            .find(|service_type| service_type.as_str() == id)
+    }
+
+    fn builtin() -> Vec<ServiceType> {
         vec![
             ServiceType::Meilisearch,
             ServiceType::MongoDB,
@@ -311,15 +423,32 @@ impl ServiceType {
             ServiceType::Valkey,
             ServiceType::Mailpit,
             ServiceType::Beanstalkd,
+            ServiceType::RabbitMQ,
+            ServiceType::OpenSearch,
             ServiceType::Memcached,
             ServiceType::Frpc,
             ServiceType::Centrifugo,
+            ServiceType::Soketi,
+            ServiceType::Keycloak,
             ServiceType::Gitea,
             ServiceType::Bun,
         ]
     }
 }
 
+impl Serialize for ServiceType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ServiceType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(ServiceType::from_id(&id))
+    }
+}
+
 // ============================================================================
 // Instance Entity
 // ============================================================================
@@ -342,6 +471,13 @@ pub struct Instance {
     pub master_key: Option<String>,
     #[serde(default)]
     pub auto_start: bool,
+    /// Restart the instance when it crashes or fails its health check
+    #[serde(default)]
+    pub restart_on_failure: bool,
+    /// Keep routing the instance's domains while it's stopped, and start it
+    /// when a request arrives
+    #[serde(default)]
+    pub wake_on_access: bool,
     pub created_at: DateTime<Utc>,
     /// Custom domain override (without TLD, e.g., "my-api" instead of "my-api.jonny")
     #[serde(default)]
@@ -352,6 +488,37 @@ pub struct Instance {
     /// Stack this instance belongs to (None = standalone)
     #[serde(default)]
     pub stack_id: Option<Uuid>,
+    /// Free-form tags for filtering and bulk actions (e.g. "client:acme")
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Arguments appended to the service's own start arguments (e.g. "--appendonly", "yes")
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    /// Seed that resets a database instance's demo data, on demand or on a schedule
+    #[serde(default)]
+    pub seed: Option<SeedSettings>,
+}
+
+/// A project-defined seed for a database instance
+///
+/// The SQL file (if any) is imported into the recreated database first, then
+/// the command (if any) runs in the project directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedSettings {
+    /// Database the seed resets
+    pub database: String,
+    /// SQL file imported into a freshly recreated database
+    #[serde(default)]
+    pub sql_file: Option<String>,
+    /// Shell command, e.g. "php artisan migrate:fresh --seed"
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Directory the command runs in
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// Cron schedule in local time (e.g. "0 3 * * *"); on demand only when unset
+    #[serde(default)]
+    pub schedule: Option<String>,
 }
 
 fn default_domain_enabled() -> bool {
@@ -395,20 +562,45 @@ impl Instance {
     }
 
     /// Generate a domain slug from the instance name
-    pub fn generate_domain_slug(&self) -> String {
-        slug::slugify(&self.name)
+    pub fn generate_domain_slug(&self, settings: &SlugSettings) -> String {
+        crate::domain::DomainManager::slugify_with(&self.name, settings)
     }
 
     /// Get the effective domain slug (custom or auto-generated)
-    pub fn effective_domain_slug(&self) -> String {
+    pub fn effective_domain_slug(&self, settings: &SlugSettings) -> String {
         self.domain
             .clone()
-            .unwrap_or_else(|| self.generate_domain_slug())
+            .unwrap_or_else(|| self.generate_domain_slug(settings))
     }
 
     /// Get the full domain with TLD (e.g., "my-api.burd")
-    pub fn full_domain(&self, tld: &str) -> String {
-        format!("{}.{}", self.effective_domain_slug(), tld)
+    pub fn full_domain(&self, tld: &str, settings: &SlugSettings) -> String {
+        format!("{}.{}", self.effective_domain_slug(settings), tld)
+    }
+
+    /// Extra start arguments as one shell-style line, quoting where needed
+    pub fn extra_args_line(&self) -> String {
+        self.extra_args
+            .iter()
+            .map(|arg| {
+                let needs_quotes = arg.is_empty()
+                    || arg
+                        .chars()
+                        .any(|c| c.is_whitespace() || matches!(c, '\'' | '"' | '\\' | '$'));
+                if needs_quotes {
+                    format!("'{}'", arg.replace('\'', "'\\''"))
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Check whether the instance carries every one of `tags` (case-insensitive)
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter()
+            .all(|tag| self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim())))
     }
 }
 
@@ -421,6 +613,12 @@ pub struct BinaryInfo {
     pub version: String,
     pub path: String,
     pub downloaded_at: DateTime<Utc>,
+    /// SHA256 of the binary recorded at install (None for Homebrew links)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Architecture read from the binary's header at install
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<BinaryArch>,
 }
 
 /// Custom deserializer to migrate from old single-version format to multi-version format
@@ -494,17 +692,26 @@ pub struct ParkedDirectory {
     /// Whether SSL (HTTPS) is enabled for parked domains from this directory
     #[serde(default)]
     pub ssl_enabled: bool,
+    /// How many levels below the directory to look for projects
+    /// (1 = immediate subdirectories only)
+    #[serde(default = "default_park_depth")]
+    pub depth: u8,
     /// When this directory was parked
     pub created_at: DateTime<Utc>,
 }
 
+fn default_park_depth() -> u8 {
+    1
+}
+
 impl ParkedDirectory {
     /// Create a new parked directory
-    pub fn new(path: String, ssl_enabled: bool) -> Self {
+    pub fn new(path: String, ssl_enabled: bool, depth: u8) -> Self {
         Self {
             id: Uuid::new_v4(),
             path,
             ssl_enabled,
+            depth,
             created_at: Utc::now(),
         }
     }
@@ -575,11 +782,16 @@ pub struct StackService {
     /// Internal reference ID for linking domains to this service
     pub ref_id: String,
     pub service_type: ServiceType,
+    /// Exact version, or `default` (or omitted) for the configured default
+    #[serde(default)]
     pub version: String,
     pub name: String,
     pub port: u16,
     #[serde(default)]
     pub auto_start: bool,
+    /// Arguments appended to the service's start arguments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
     /// Service-specific config (secrets should be stripped)
     #[serde(default)]
     pub config: serde_json::Value,
@@ -677,10 +889,531 @@ pub struct ImportResult {
     pub domains_created: Vec<Uuid>,
 }
 
+// ============================================================================
+// Domain Export Format (for sharing)
+// ============================================================================
+
+/// Export format for sharing a set of domains between team members
+///
+/// Targets refer to instances by name, so the layout can be recreated on
+/// another machine where the instances have different IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainExport {
+    /// Schema version for future format migrations
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    /// Who created/exported this config
+    #[serde(default)]
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub domains: Vec<ExportedDomain>,
+}
+
+/// A domain within a domain export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedDomain {
+    /// Subdomain only (e.g., "api" not "api.burd")
+    pub subdomain: String,
+    pub target: ExportedDomainTarget,
+    #[serde(default)]
+    pub ssl_enabled: bool,
+    #[serde(default)]
+    pub force_https: bool,
+    #[serde(default)]
+    pub hsts: bool,
+    #[serde(default)]
+    pub require_client_cert: bool,
+}
+
+/// What an exported domain routes to, without machine-specific IDs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum ExportedDomainTarget {
+    /// A Burd instance, matched by name on import
+    Instance {
+        name: String,
+        service_type: ServiceType,
+        /// The instance's port when exported, for reference
+        port: u16,
+    },
+    /// A raw port
+    Port { port: u16 },
+    /// A static file directory
+    StaticFiles { path: String, browse: bool },
+}
+
+// ============================================================================
+// Domain Import Types
+// ============================================================================
+
+/// Preview result when validating a domain import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainImportPreview {
+    pub config: DomainExport,
+    pub conflicts: Vec<DomainImportConflict>,
+}
+
+/// An instance an unmatched domain could be routed to instead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceCandidate {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Conflicts detected during a domain import
+///
+/// Domains with an unresolved conflict are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DomainImportConflict {
+    /// A domain with this subdomain already exists
+    SubdomainExists {
+        subdomain: String,
+        existing_id: Uuid,
+    },
+    /// No instance with the exported name exists
+    InstanceNotFound {
+        subdomain: String,
+        instance_name: String,
+        service_type: ServiceType,
+        /// Instances of the same service type
+        candidates: Vec<InstanceCandidate>,
+    },
+    /// The static file directory doesn't exist on this machine
+    PathNotFound { subdomain: String, path: String },
+}
+
+/// How to resolve a conflict for one imported domain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DomainConflictResolution {
+    /// Replace the existing domain with the imported one
+    ReplaceExisting { subdomain: String },
+    /// Import the domain under another subdomain
+    RenameDomain {
+        subdomain: String,
+        new_subdomain: String,
+    },
+    /// Route the domain to another instance
+    MapInstance {
+        subdomain: String,
+        instance_id: Uuid,
+    },
+    /// Serve the domain from another directory
+    ChangePath { subdomain: String, path: String },
+    /// Don't import the domain
+    Skip { subdomain: String },
+}
+
+/// Result of a successful domain import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainImportResult {
+    pub domains_created: Vec<Uuid>,
+    /// IDs of the existing domains that were replaced
+    pub domains_replaced: Vec<Uuid>,
+    /// Subdomains that were skipped
+    pub domains_skipped: Vec<String>,
+}
+
+// ============================================================================
+// Instance Export Format (for sharing)
+// ============================================================================
+
+/// Export format for sharing a single instance, lighter than a stack
+///
+/// Holds the instance definition and its domains; instance data isn't
+/// included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceExport {
+    /// Schema version for future format migrations
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    /// Who created/exported this config
+    #[serde(default)]
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Whether passwords and keys were kept in `instance.config`
+    #[serde(default)]
+    pub secrets_included: bool,
+    pub instance: ExportedInstance,
+    #[serde(default)]
+    pub domains: Vec<ExportedInstanceDomain>,
+    /// Connection settings (.env lines) when exported, for reference
+    #[serde(default)]
+    pub env: String,
+}
+
+/// The instance within an instance export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedInstance {
+    pub name: String,
+    pub service_type: ServiceType,
+    /// Exact version, or `default` (or omitted) for the configured default
+    #[serde(default)]
+    pub version: String,
+    pub port: u16,
+    #[serde(default)]
+    pub auto_start: bool,
+    #[serde(default)]
+    pub restart_on_failure: bool,
+    #[serde(default)]
+    pub wake_on_access: bool,
+    #[serde(default = "default_domain_enabled")]
+    pub domain_enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+    #[serde(default)]
+    pub config: serde_json::Value,
+}
+
+/// A domain of the instance within an instance export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedInstanceDomain {
+    /// Subdomain only (e.g., "search" not "search.burd")
+    pub subdomain: String,
+    #[serde(default)]
+    pub ssl_enabled: bool,
+    #[serde(default)]
+    pub force_https: bool,
+    #[serde(default)]
+    pub hsts: bool,
+    #[serde(default)]
+    pub require_client_cert: bool,
+}
+
+// ============================================================================
+// Instance Import Types
+// ============================================================================
+
+/// Preview result when validating an instance import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceImportPreview {
+    pub config: InstanceExport,
+    /// The version to download first, if it isn't installed
+    pub missing_version: Option<MissingVersion>,
+    pub conflicts: Vec<InstanceImportConflict>,
+}
+
+/// Conflicts detected during an instance import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum InstanceImportConflict {
+    /// An instance with this name exists; the import needs another name
+    NameExists { name: String, existing_id: Uuid },
+    /// The port is taken; the instance moves to a free port unless another is chosen
+    PortInUse { port: u16, reason: String },
+    /// A domain with this subdomain exists; the imported one is skipped
+    SubdomainExists {
+        subdomain: String,
+        existing_id: Uuid,
+    },
+}
+
+/// Result of a successful instance import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceImportResult {
+    pub instance_id: Uuid,
+    pub name: String,
+    pub port: u16,
+    pub domains_created: Vec<Uuid>,
+    /// Subdomains that were skipped
+    pub domains_skipped: Vec<String>,
+}
+
 // ============================================================================
 // Config
 // ============================================================================
 
+/// Inclusive port range used for automatic port assignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    /// Check whether a port falls within this range
+    pub fn contains(&self, port: u16) -> bool {
+        port >= self.start && port <= self.end
+    }
+}
+
+impl Default for PortRange {
+    fn default() -> Self {
+        Self {
+            start: 10000,
+            end: 19999,
+        }
+    }
+}
+
+/// Opt-in remote access to the HTTP API
+///
+/// By default the API only listens on 127.0.0.1 and needs no credentials.
+/// In remote mode it also listens on `bind_address`, every request must
+/// carry the bearer token, and browsers on `allowed_origins` may call it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiRemoteSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on in remote mode ("0.0.0.0" for every interface)
+    #[serde(default = "default_api_bind_address")]
+    pub bind_address: String,
+    /// Origins (e.g. "https://dashboard.example.com") allowed to call the API from a browser
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Bearer token required in remote mode (generated when remote mode is enabled)
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+fn default_api_bind_address() -> String {
+    "0.0.0.0".to_string()
+}
+
+impl Default for ApiRemoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_api_bind_address(),
+            allowed_origins: Vec::new(),
+            token: None,
+        }
+    }
+}
+
+/// Another machine's Burd that the CLI and app can control over its API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteHost {
+    /// Name used with `--host` (e.g. "office")
+    pub name: String,
+    /// API address (e.g. "http://mac-mini.local:19840")
+    pub url: String,
+    /// Bearer token shown by `burd api status` on that machine
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Scheduled backups of the config and selected instances' data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Folder backups are written to (e.g. in iCloud Drive or on an external disk)
+    #[serde(default)]
+    pub destination: Option<String>,
+    /// Hours between backups
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+    /// Cron schedule in local time (e.g. "30 2 * * *"); replaces the interval when set
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Backups kept in the destination (0 keeps all)
+    #[serde(default = "default_backup_retention")]
+    pub retention: usize,
+    /// Instances whose data is included (the config is always included)
+    #[serde(default)]
+    pub instance_ids: Vec<Uuid>,
+    /// Store each instance's data as a .tar.gz archive
+    #[serde(default)]
+    pub compress: bool,
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_backup_retention() -> usize {
+    7
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            destination: None,
+            interval_hours: default_backup_interval_hours(),
+            schedule: None,
+            retention: default_backup_retention(),
+            instance_ids: Vec::new(),
+            compress: false,
+        }
+    }
+}
+
+/// Stopping instances nobody has used for a while
+///
+/// Pairs with wake-on-access, which starts them again on the next visit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdleStopSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes without requests or connections before an instance is stopped
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u32,
+    /// Leave MariaDB, PostgreSQL and MongoDB instances running
+    #[serde(default = "default_exclude_databases")]
+    pub exclude_databases: bool,
+}
+
+fn default_idle_minutes() -> u32 {
+    30
+}
+
+fn default_exclude_databases() -> bool {
+    true
+}
+
+impl Default for IdleStopSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: default_idle_minutes(),
+            exclude_databases: default_exclude_databases(),
+        }
+    }
+}
+
+/// Restarting the proxy when a site's HTTPS certificate is about to expire
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertMonitorSettings {
+    #[serde(default = "default_cert_monitor_enabled")]
+    pub enabled: bool,
+    /// Days before expiry at which a certificate counts as expiring
+    #[serde(default = "default_cert_renew_days")]
+    pub renew_days: u32,
+}
+
+fn default_cert_monitor_enabled() -> bool {
+    true
+}
+
+fn default_cert_renew_days() -> u32 {
+    7
+}
+
+impl Default for CertMonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_cert_monitor_enabled(),
+            renew_days: default_cert_renew_days(),
+        }
+    }
+}
+
+/// Rotating instance and worker logs so they can't fill the disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRetentionSettings {
+    #[serde(default = "default_log_rotation_enabled")]
+    pub enabled: bool,
+    /// Size in MB at which a log is rotated
+    #[serde(default = "default_log_max_file_mb")]
+    pub max_file_mb: u64,
+    /// Rotated files kept per log, the oldest removed first
+    #[serde(default = "default_log_keep_files")]
+    pub keep_files: u32,
+    /// Size in MB all logs together may take up
+    #[serde(default = "default_log_total_cap_mb")]
+    pub total_cap_mb: u64,
+}
+
+fn default_log_rotation_enabled() -> bool {
+    true
+}
+
+fn default_log_max_file_mb() -> u64 {
+    10
+}
+
+fn default_log_keep_files() -> u32 {
+    5
+}
+
+fn default_log_total_cap_mb() -> u64 {
+    1024
+}
+
+impl Default for LogRetentionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_log_rotation_enabled(),
+            max_file_mb: default_log_max_file_mb(),
+            keep_files: default_log_keep_files(),
+            total_cap_mb: default_log_total_cap_mb(),
+        }
+    }
+}
+
+/// How letters outside ASCII in a name end up in its domain slug
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugScript {
+    /// Spell them in Latin letters ("東京" -> "dong-jing")
+    #[default]
+    Transliterate,
+    /// Keep them as an internationalized label ("東京" -> "xn--1lqs71d")
+    Punycode,
+}
+
+/// Where a slug longer than the limit is cut
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugTruncate {
+    /// At the last word that fits
+    #[default]
+    Word,
+    /// At exactly the limit
+    Cut,
+    /// At the limit, ending in a short hash of the whole slug so long names stay distinct
+    Hash,
+}
+
+/// Turning project and instance names into domain slugs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlugSettings {
+    #[serde(default)]
+    pub script: SlugScript,
+    /// Longest slug; `None` for the DNS label limit of 63
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    pub truncate: SlugTruncate,
+    /// Slugs for particular names, used as they are
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, String>,
+    /// Shell command printing the slug for the name in `$BURD_SLUG_NAME`;
+    /// when it prints nothing the rules above apply. It runs once per name
+    /// and its output is kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook: Option<String>,
+}
+
+/// Access to the GitHub API, used to list service releases
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitHubSettings {
+    /// Personal access token; raises the rate limit from 60 to 5000 requests an hour
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Minutes a cached release listing is used before asking GitHub again
+    #[serde(default = "default_release_cache_ttl_minutes")]
+    pub cache_ttl_minutes: u32,
+}
+
+fn default_release_cache_ttl_minutes() -> u32 {
+    60
+}
+
+impl Default for GitHubSettings {
+    fn default() -> Self {
+        Self {
+            token: None,
+            cache_ttl_minutes: default_release_cache_ttl_minutes(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default)]
@@ -694,6 +1427,9 @@ pub struct Config {
     /// Parked directories for automatic domain creation
     #[serde(default)]
     pub parked_directories: Vec<ParkedDirectory>,
+    /// Queue workers for linked Laravel projects
+    #[serde(default)]
+    pub workers: Vec<Worker>,
     /// Binaries per service type - supports multiple versions
     /// Structure: { ServiceType: { "version": BinaryInfo } }
     #[serde(default, deserialize_with = "deserialize_binaries")]
@@ -707,6 +1443,21 @@ pub struct Config {
     /// Custom TLD for domain routing (e.g., "burd" for .burd domains)
     #[serde(default = "default_tld")]
     pub tld: String,
+    /// More TLDs domains can use; each gets a resolver file and DNS answers
+    #[serde(default)]
+    pub extra_tlds: Vec<String>,
+    /// Extra DNS records under the TLD (e.g. api pointing at a LAN IP)
+    #[serde(default)]
+    pub dns_records: Vec<DnsRecord>,
+    /// Enabled SaaS emulation presets (e.g. s3.amazonaws.com to MinIO)
+    #[serde(default)]
+    pub emulations: Vec<Emulation>,
+    /// Projects with secrets in the vault (names only; values are in the keychain)
+    #[serde(default)]
+    pub vault: Vec<VaultProject>,
+    /// Projects whose mail goes somewhere other than their stack's Mailpit
+    #[serde(default)]
+    pub mail_routes: Vec<MailRoute>,
     /// Whether the privileged proxy daemon is installed (launchd on macOS)
     /// When true, the proxy runs on ports 80/443 via system daemon
     #[serde(default)]
@@ -717,6 +1468,49 @@ pub struct Config {
     /// Tunnel configurations
     #[serde(default)]
     pub tunnels: Vec<Tunnel>,
+    /// Range used when assigning ports to new instances automatically
+    #[serde(default)]
+    pub port_range: PortRange,
+    /// Number of snapshots kept per instance (0 keeps all)
+    #[serde(default = "default_snapshot_retention")]
+    pub snapshot_retention: usize,
+    /// Hours the CLI keeps the snapshot it takes before dropping or
+    /// overwriting a database (0 turns these snapshots off)
+    #[serde(default = "default_safety_snapshot_hours")]
+    pub safety_snapshot_hours: u32,
+    /// Preferred version per service type for new instances (exact or prefix)
+    #[serde(default)]
+    pub default_versions: HashMap<ServiceType, String>,
+    /// Release channel per service type when listing versions (stable if absent)
+    #[serde(default)]
+    pub release_channels: HashMap<ServiceType, ReleaseChannel>,
+    /// Architecture to download per service instead of the host's own
+    #[serde(default)]
+    pub arch_overrides: HashMap<ServiceType, Arch>,
+    /// Remote access to the HTTP API (off by default)
+    #[serde(default)]
+    pub api_remote: ApiRemoteSettings,
+    /// Other machines' Burd APIs this one can control
+    #[serde(default)]
+    pub remote_hosts: Vec<RemoteHost>,
+    /// Scheduled backups (off by default)
+    #[serde(default)]
+    pub backup: BackupSettings,
+    /// GitHub token and release listing cache
+    #[serde(default)]
+    pub github: GitHubSettings,
+    /// Stopping idle instances (off by default)
+    #[serde(default)]
+    pub idle_stop: IdleStopSettings,
+    /// Watching site certificates for expiry
+    #[serde(default)]
+    pub cert_monitor: CertMonitorSettings,
+    /// Rotating instance and worker logs
+    #[serde(default)]
+    pub log_retention: LogRetentionSettings,
+    /// Turning names into domain slugs
+    #[serde(default)]
+    pub slugs: SlugSettings,
 }
 
 fn default_dns_port() -> u16 {
@@ -731,6 +1525,14 @@ fn default_tld() -> String {
     crate::domain::DEFAULT_TLD.to_string()
 }
 
+fn default_snapshot_retention() -> usize {
+    crate::snapshot::DEFAULT_SNAPSHOT_RETENTION
+}
+
+fn default_safety_snapshot_hours() -> u32 {
+    crate::snapshot::DEFAULT_SAFETY_SNAPSHOT_HOURS
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -738,13 +1540,46 @@ impl Default for Config {
             domains: Vec::new(),
             stacks: Vec::new(),
             parked_directories: Vec::new(),
+            workers: Vec::new(),
             binaries: HashMap::new(),
             dns_port: default_dns_port(),
             proxy_port: default_proxy_port(),
             tld: default_tld(),
+            extra_tlds: Vec::new(),
+            dns_records: Vec::new(),
+            emulations: Vec::new(),
+            vault: Vec::new(),
+            mail_routes: Vec::new(),
             proxy_installed: false,
             frp_servers: Vec::new(),
             tunnels: Vec::new(),
+            port_range: PortRange::default(),
+            snapshot_retention: default_snapshot_retention(),
+            safety_snapshot_hours: default_safety_snapshot_hours(),
+            default_versions: HashMap::new(),
+            release_channels: HashMap::new(),
+            arch_overrides: HashMap::new(),
+            api_remote: ApiRemoteSettings::default(),
+            remote_hosts: Vec::new(),
+            backup: BackupSettings::default(),
+            github: GitHubSettings::default(),
+            idle_stop: IdleStopSettings::default(),
+            cert_monitor: CertMonitorSettings::default(),
+            log_retention: LogRetentionSettings::default(),
+            slugs: SlugSettings::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Every TLD Burd answers for, the primary one first
+    pub fn tlds(&self) -> Vec<String> {
+        let mut tlds = vec![self.tld.clone()];
+        for tld in &self.extra_tlds {
+            if !tlds.contains(tld) {
+                tlds.push(tld.clone());
+            }
         }
+        tlds
     }
 }
@@ -6,8 +6,32 @@ use std::path::PathBuf;
 use uuid::Uuid;
 
 use super::ServiceType;
+use crate::service_config::ServiceRegistry;
+
+#[cfg(any(test, feature = "test-support"))]
+thread_local! {
+    /// App directory used instead of the user's while a test environment is up
+    static APP_DIR_OVERRIDE: std::cell::RefCell<Option<PathBuf>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Point every Burd path on this thread at `dir` (`None` restores the user's
+/// data directory)
+///
+/// Used by [`crate::test_env`] so end-to-end tests never touch real data.
+/// Being per thread, it can't leak into tests running in parallel; work
+/// moved to other threads (`spawn_blocking`) doesn't see it.
+#[cfg(any(test, feature = "test-support"))]
+pub fn set_app_dir_override(dir: Option<PathBuf>) {
+    APP_DIR_OVERRIDE.with(|current| *current.borrow_mut() = dir);
+}
 
 pub fn get_app_dir() -> Result<PathBuf, String> {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(dir) = APP_DIR_OVERRIDE.with(|dir| dir.borrow().clone()) {
+        return Ok(dir);
+    }
+
     dirs::data_dir()
         .map(|p| p.join("Burd"))
         .ok_or_else(|| "Could not determine application data directory".to_string())
@@ -29,6 +53,11 @@ pub fn get_instance_dir(id: &Uuid) -> Result<PathBuf, String> {
     get_instances_dir().map(|p| p.join(id.to_string()))
 }
 
+/// Get the snapshot directory of an instance: snapshots/{instance_id}/
+pub fn get_snapshots_dir(id: &Uuid) -> Result<PathBuf, String> {
+    get_app_dir().map(|p| p.join("snapshots").join(id.to_string()))
+}
+
 /// Get the binary name for a service type
 pub fn get_binary_name(service_type: ServiceType) -> &'static str {
     match service_type {
@@ -45,12 +74,25 @@ pub fn get_binary_name(service_type: ServiceType) -> &'static str {
         ServiceType::Valkey => "valkey-server",
         ServiceType::Mailpit => "mailpit",
         ServiceType::Beanstalkd => "beanstalkd",
+        ServiceType::RabbitMQ => "rabbitmq-server",
+        ServiceType::OpenSearch => "opensearch",
         ServiceType::Memcached => "memcached",
         ServiceType::Frpc => "frpc",
         ServiceType::Caddy => "caddy",
         ServiceType::Centrifugo => "centrifugo",
+        ServiceType::Soketi => "soketi",
+        ServiceType::Keycloak => "kc.sh",
         ServiceType::Gitea => "gitea",
         ServiceType::Bun => "bun",
+        ServiceType::Custom(id) => ServiceRegistry::load()
+            .get_service(id)
+            .map(|service| {
+                service
+                    .current_platform()
+                    .and_then(|platform| platform.binary_name.as_deref())
+                    .unwrap_or(service.binary_name.as_str())
+            })
+            .unwrap_or(id),
     }
 }
 
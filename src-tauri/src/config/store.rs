@@ -3,20 +3,52 @@
 //! Handles loading, saving, and CRUD operations for the config file.
 
 use chrono::Utc;
+use std::cell::Cell;
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::marker::PhantomData;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::arch::Arch;
+
 use super::{
-    get_instance_dir, BinaryInfo, Config, Domain, DomainTarget, FrpServer, Instance,
-    ParkedDirectory, ServiceType, Stack, SubdomainConfig, Tunnel, TunnelTarget,
+    get_instance_dir, ApiRemoteSettings, BackupSettings, BinaryInfo, CertMonitorSettings, Config,
+    DnsRecord, Domain, DomainTarget, Emulation, FrpServer, GitHubSettings, HeaderPreset,
+    IdleStopSettings, Instance, LogRetentionSettings, MailRoute, ParkedDirectory, PortRange,
+    ReleaseChannel, RemoteHost, SeedSettings, ServiceType, SlugSettings, Stack, SubdomainConfig,
+    Tunnel, TunnelTarget, VaultProject, Worker,
 };
 
 pub struct ConfigStore {
     config_path: PathBuf,
 }
 
+thread_local! {
+    /// How many `ConfigLock`s this thread holds
+    static LOCK_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Exclusive lock on config.json, shared by every Burd process
+///
+/// The GUI, CLI, daemon and API all edit the config by loading it, changing
+/// it and saving it back; without the lock two of them doing that at once
+/// lose one of the changes. It's held from load to save, is re-entrant
+/// within a thread (store methods call each other), and can't be held
+/// across an `.await`. Dropping it releases the lock.
+pub struct ConfigLock {
+    /// `None` for a nested lock on a thread that already holds the file lock
+    _file: Option<File>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        LOCK_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
 impl ConfigStore {
     pub fn new() -> Result<Self, String> {
         let app_dir = super::get_app_dir()?;
@@ -28,6 +60,17 @@ impl ConfigStore {
         })
     }
 
+    /// Store for the config file at `config_path`
+    #[cfg(test)]
+    pub fn at(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    /// Path of config.json
+    pub fn path(&self) -> &Path {
+        &self.config_path
+    }
+
     pub fn load(&self) -> Result<Config, String> {
         if !self.config_path.exists() {
             return Ok(Config::default());
@@ -87,7 +130,43 @@ impl ConfigStore {
         serde_json::to_string_pretty(&raw).unwrap_or_else(|_| content.to_string())
     }
 
+    /// Lock the config file until the returned guard is dropped
+    ///
+    /// Blocks while another process (or thread) holds it. Take it before
+    /// loading a config that's going to be saved.
+    pub fn lock_file(&self) -> Result<ConfigLock, String> {
+        if LOCK_DEPTH.with(Cell::get) > 0 {
+            LOCK_DEPTH.with(|depth| depth.set(depth.get() + 1));
+            return Ok(ConfigLock {
+                _file: None,
+                _not_send: PhantomData,
+            });
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.config_path.with_extension("json.lock"))
+            .map_err(|e| format!("Failed to open config lock: {}", e))?;
+        // SAFETY: flock only reads the descriptor, which `file` keeps open
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(format!(
+                "Failed to lock config: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        LOCK_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        Ok(ConfigLock {
+            _file: Some(file),
+            _not_send: PhantomData,
+        })
+    }
+
     pub fn save(&self, config: &Config) -> Result<(), String> {
+        // Writers also share the temp file below
+        let _lock = self.lock_file()?;
+
         let content = serde_json::to_string_pretty(config)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
@@ -115,6 +194,7 @@ impl ConfigStore {
         service_config: serde_json::Value,
         custom_domain: Option<String>,
     ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Check if port is already in use by another instance
@@ -131,10 +211,15 @@ impl ConfigStore {
             config: service_config,
             master_key: None,
             auto_start: false,
+            restart_on_failure: false,
+            wake_on_access: false,
             created_at: Utc::now(),
             domain: custom_domain,
             domain_enabled: true,
             stack_id: None,
+            tags: Vec::new(),
+            extra_args: Vec::new(),
+            seed: None,
         };
 
         // Create instance data directory
@@ -170,6 +255,7 @@ impl ConfigStore {
     }
 
     pub fn delete_instance(&self, id: Uuid) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let idx = config
@@ -208,6 +294,7 @@ impl ConfigStore {
         domain_enabled: Option<bool>,
         config_update: Option<serde_json::Value>,
     ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Check port uniqueness before mutating
@@ -255,6 +342,7 @@ impl ConfigStore {
         domain: Option<String>,
         enabled: bool,
     ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let instance = config
@@ -272,12 +360,151 @@ impl ConfigStore {
         Ok(updated)
     }
 
+    /// Set whether the headless daemon starts and supervises an instance
+    pub fn update_instance_auto_start(
+        &self,
+        id: Uuid,
+        auto_start: bool,
+    ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+
+        instance.auto_start = auto_start;
+
+        let updated = instance.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
+    /// Set whether a stopped instance is started when one of its domains is visited
+    pub fn update_instance_wake_on_access(
+        &self,
+        id: Uuid,
+        wake_on_access: bool,
+    ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+
+        instance.wake_on_access = wake_on_access;
+
+        let updated = instance.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
+    /// Set whether the supervisor restarts an instance that crashes or turns unhealthy
+    pub fn update_instance_restart_on_failure(
+        &self,
+        id: Uuid,
+        restart_on_failure: bool,
+    ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+
+        instance.restart_on_failure = restart_on_failure;
+
+        let updated = instance.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
+    /// Replace an instance's tags (normalized to lowercase, de-duplicated)
+    pub fn update_instance_tags(&self, id: Uuid, tags: &[String]) -> Result<Instance, String> {
+        let tags = crate::validation::normalize_tags(tags).map_err(|e| e.to_string())?;
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+
+        instance.tags = tags;
+
+        let updated = instance.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
+    /// Replace an instance's extra start arguments
+    pub fn update_instance_extra_args(
+        &self,
+        id: Uuid,
+        args: &[String],
+    ) -> Result<Instance, String> {
+        crate::validation::validate_extra_args(args).map_err(|e| e.to_string())?;
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+
+        instance.extra_args = args.to_vec();
+
+        let updated = instance.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
+    /// Set or clear a database instance's seed
+    pub fn update_instance_seed(
+        &self,
+        id: Uuid,
+        seed: Option<SeedSettings>,
+    ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+
+        instance.seed = seed
+            .map(|seed| crate::seed::validate_settings(instance, seed))
+            .transpose()?;
+
+        let updated = instance.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
     /// Update instance configuration
     pub fn update_instance_config(
         &self,
         id: Uuid,
         new_config: serde_json::Value,
     ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let instance = config
@@ -300,6 +527,7 @@ impl ConfigStore {
         id: Uuid,
         new_version: String,
     ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let instance = config
@@ -326,6 +554,7 @@ impl ConfigStore {
         service_type: ServiceType,
         info: BinaryInfo,
     ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
         let version = info.version.clone();
         config
@@ -386,6 +615,7 @@ impl ConfigStore {
         service_type: ServiceType,
         version: &str,
     ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
         if let Some(versions) = config.binaries.get_mut(&service_type) {
             versions.remove(version);
@@ -403,13 +633,241 @@ impl ConfigStore {
 
     /// Update the TLD setting
     pub fn update_tld(&self, tld: String) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
+        // An extra TLD that becomes the primary one is no longer extra
+        config.extra_tlds.retain(|t| *t != tld);
+        for domain in &mut config.domains {
+            if domain.tld.as_ref() == Some(&tld) {
+                domain.tld = None;
+            }
+        }
         config.tld = tld;
         self.save(&config)
     }
 
+    /// Add a TLD domains can use besides the primary one
+    pub fn add_tld(&self, tld: String) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        if config.tlds().contains(&tld) {
+            return Err(format!(".{} is already configured", tld));
+        }
+        config.extra_tlds.push(tld);
+        self.save(&config)
+    }
+
+    /// Remove an extra TLD, as long as no domain uses it
+    pub fn remove_tld(&self, tld: &str) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        if tld == config.tld {
+            return Err("The primary TLD can't be removed; change it instead".to_string());
+        }
+        if !config.extra_tlds.iter().any(|t| t == tld) {
+            return Err(format!(".{} is not configured", tld));
+        }
+
+        let in_use: Vec<String> = config
+            .domains
+            .iter()
+            .filter(|d| d.tld.as_deref() == Some(tld))
+            .map(|d| d.full_domain(&config.tld))
+            .collect();
+        if !in_use.is_empty() {
+            return Err(format!(
+                ".{} is still used by {}; move them to another TLD first",
+                tld,
+                in_use.join(", ")
+            ));
+        }
+
+        config.extra_tlds.retain(|t| t != tld);
+        self.save(&config)
+    }
+
+    /// Update the port range used for automatic port assignment
+    pub fn update_port_range(&self, port_range: PortRange) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.port_range = port_range;
+        self.save(&config)
+    }
+
+    /// Update the HTTP API's remote access settings
+    pub fn update_api_remote(&self, settings: ApiRemoteSettings) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.api_remote = settings;
+        self.save(&config)
+    }
+
+    /// Replace the remote hosts this machine can control
+    pub fn update_remote_hosts(&self, hosts: Vec<RemoteHost>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.remote_hosts = hosts;
+        self.save(&config)
+    }
+
+    /// Replace the queue workers
+    pub fn update_workers(&self, workers: Vec<Worker>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.workers = workers;
+        self.save(&config)
+    }
+
+    /// Replace the custom DNS records
+    pub fn update_dns_records(&self, records: Vec<DnsRecord>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.dns_records = records;
+        self.save(&config)
+    }
+
+    /// Replace the enabled SaaS emulation presets
+    pub fn update_emulations(&self, emulations: Vec<Emulation>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.emulations = emulations;
+        self.save(&config)
+    }
+
+    /// Update the projects recorded in the secrets vault
+    pub fn update_vault(&self, vault: Vec<VaultProject>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.vault = vault;
+        self.save(&config)
+    }
+
+    /// Replace the projects' mail routes
+    pub fn update_mail_routes(&self, routes: Vec<MailRoute>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.mail_routes = routes;
+        self.save(&config)
+    }
+
+    /// Update the scheduled backup settings
+    pub fn update_backup_settings(&self, settings: BackupSettings) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.backup = settings;
+        self.save(&config)
+    }
+
+    /// Update the GitHub token and release cache settings
+    pub fn update_github_settings(&self, settings: GitHubSettings) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.github = settings;
+        self.save(&config)
+    }
+
+    /// Update the idle auto-stop settings
+    pub fn update_idle_stop_settings(&self, settings: IdleStopSettings) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.idle_stop = settings;
+        self.save(&config)
+    }
+
+    /// Update the certificate expiry monitor settings
+    pub fn update_cert_monitor_settings(
+        &self,
+        settings: CertMonitorSettings,
+    ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.cert_monitor = settings;
+        self.save(&config)
+    }
+
+    /// Update the log rotation settings
+    pub fn update_log_retention_settings(
+        &self,
+        settings: LogRetentionSettings,
+    ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.log_retention = settings;
+        self.save(&config)
+    }
+
+    /// Update how names become domain slugs
+    pub fn update_slug_settings(&self, settings: SlugSettings) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.slugs = settings;
+        self.save(&config)
+    }
+
+    /// Update the number of snapshots kept per instance
+    pub fn update_snapshot_retention(&self, retention: usize) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.snapshot_retention = retention;
+        self.save(&config)
+    }
+
+    /// Update how many hours safety snapshots are kept (0 turns them off)
+    pub fn update_safety_snapshot_hours(&self, hours: u32) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        config.safety_snapshot_hours = hours;
+        self.save(&config)
+    }
+
+    /// Pin (or with `None`, unpin) the default version for a service type
+    pub fn set_default_service_version(
+        &self,
+        service_type: ServiceType,
+        version: Option<String>,
+    ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        match version {
+            Some(version) => config.default_versions.insert(service_type, version),
+            None => config.default_versions.remove(&service_type),
+        };
+        self.save(&config)
+    }
+
+    /// Set the release channel versions are offered from for a service type
+    pub fn set_release_channel(
+        &self,
+        service_type: ServiceType,
+        channel: ReleaseChannel,
+    ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        match channel {
+            ReleaseChannel::Stable => config.release_channels.remove(&service_type),
+            channel => config.release_channels.insert(service_type, channel),
+        };
+        self.save(&config)
+    }
+
+    /// Override (or with `None`, reset) the architecture downloaded for a service type
+    pub fn set_arch_override(
+        &self,
+        service_type: ServiceType,
+        arch: Option<Arch>,
+    ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        match arch {
+            Some(arch) => config.arch_overrides.insert(service_type, arch),
+            None => config.arch_overrides.remove(&service_type),
+        };
+        self.save(&config)
+    }
+
     /// Update the proxy_installed setting
     pub fn set_proxy_installed(&self, installed: bool) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
         config.proxy_installed = installed;
         self.save(&config)
@@ -435,6 +893,17 @@ impl ConfigStore {
             .ok_or_else(|| format!("Domain {} not found", id))
     }
 
+    /// Reject subdomains reserved for Burd itself (e.g. the start page)
+    fn check_reserved_subdomain(subdomain: &str) -> Result<(), String> {
+        if crate::start_page::is_reserved_subdomain(subdomain) {
+            return Err(format!(
+                "Domain '{}' is reserved for the Burd start page",
+                subdomain
+            ));
+        }
+        Ok(())
+    }
+
     /// Create a new domain routing to an instance
     pub fn create_domain_for_instance(
         &self,
@@ -442,6 +911,7 @@ impl ConfigStore {
         instance_id: Uuid,
         ssl_enabled: bool,
     ) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate instance exists
@@ -449,7 +919,8 @@ impl ConfigStore {
             return Err(format!("Instance {} not found", instance_id));
         }
 
-        // Check for duplicate subdomain
+        // Check for reserved and duplicate subdomains
+        Self::check_reserved_subdomain(&subdomain)?;
         if config.domains.iter().any(|d| d.subdomain == subdomain) {
             return Err(format!("Domain '{}' already exists", subdomain));
         }
@@ -469,9 +940,11 @@ impl ConfigStore {
         port: u16,
         ssl_enabled: bool,
     ) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
-        // Check for duplicate subdomain
+        // Check for reserved and duplicate subdomains
+        Self::check_reserved_subdomain(&subdomain)?;
         if config.domains.iter().any(|d| d.subdomain == subdomain) {
             return Err(format!("Domain '{}' already exists", subdomain));
         }
@@ -492,9 +965,11 @@ impl ConfigStore {
         browse: bool,
         ssl_enabled: bool,
     ) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
-        // Check for duplicate subdomain
+        // Check for reserved and duplicate subdomains
+        Self::check_reserved_subdomain(&subdomain)?;
         if config.domains.iter().any(|d| d.subdomain == subdomain) {
             return Err(format!("Domain '{}' already exists", subdomain));
         }
@@ -516,6 +991,31 @@ impl ConfigStore {
         Ok(domain)
     }
 
+    /// Move a domain to another configured TLD
+    ///
+    /// `None` (or the primary TLD itself) puts it under the primary TLD.
+    pub fn set_domain_tld(&self, id: Uuid, tld: Option<String>) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        let tld = tld.filter(|t| *t != config.tld);
+        if let Some(tld) = &tld {
+            if !config.extra_tlds.contains(tld) {
+                return Err(format!(".{} is not a configured TLD", tld));
+            }
+        }
+
+        let domain = config
+            .domains
+            .iter_mut()
+            .find(|d| d.id == id)
+            .ok_or_else(|| format!("Domain {} not found", id))?;
+        domain.tld = tld;
+        let domain = domain.clone();
+
+        self.save(&config)?;
+        Ok(domain)
+    }
+
     /// Update an existing domain
     pub fn update_domain(
         &self,
@@ -523,6 +1023,7 @@ impl ConfigStore {
         subdomain: Option<String>,
         target: Option<DomainTarget>,
     ) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // First, find the domain index and current subdomain
@@ -534,8 +1035,9 @@ impl ConfigStore {
 
         let current_subdomain = config.domains[domain_idx].subdomain.clone();
 
-        // Check for duplicate subdomain if changing (before mutable borrow)
+        // Check for reserved and duplicate subdomains if changing (before mutable borrow)
         if let Some(ref new_subdomain) = subdomain {
+            Self::check_reserved_subdomain(new_subdomain)?;
             if new_subdomain != &current_subdomain
                 && config
                     .domains
@@ -571,6 +1073,7 @@ impl ConfigStore {
 
     /// Delete a domain by ID
     pub fn delete_domain(&self, id: Uuid) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let idx = config
@@ -601,6 +1104,7 @@ impl ConfigStore {
 
     /// Update SSL enabled status for a domain
     pub fn update_domain_ssl(&self, id: Uuid, ssl_enabled: bool) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let domain = config
@@ -609,6 +1113,15 @@ impl ConfigStore {
             .find(|d| d.id == id)
             .ok_or_else(|| format!("Domain {} not found", id))?;
 
+        // Without SSL, Caddy would serve the site over plain HTTP with no
+        // client certificate check
+        if !ssl_enabled && domain.require_client_cert {
+            return Err(
+                "Stop requiring a client certificate for this domain before disabling SSL"
+                    .to_string(),
+            );
+        }
+
         domain.ssl_enabled = ssl_enabled;
 
         let updated = domain.clone();
@@ -617,8 +1130,66 @@ impl ConfigStore {
         Ok(updated)
     }
 
+    /// Update a domain's HTTP→HTTPS redirect, HSTS and client certificate
+    /// options (None leaves a value unchanged)
+    pub fn update_domain_https_options(
+        &self,
+        id: Uuid,
+        force_https: Option<bool>,
+        hsts: Option<bool>,
+        require_client_cert: Option<bool>,
+    ) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let domain = config
+            .domains
+            .iter_mut()
+            .find(|d| d.id == id)
+            .ok_or_else(|| format!("Domain {} not found", id))?;
+
+        if let Some(force_https) = force_https {
+            domain.force_https = force_https;
+        }
+        if let Some(hsts) = hsts {
+            domain.hsts = hsts;
+        }
+        if let Some(require_client_cert) = require_client_cert {
+            domain.require_client_cert = require_client_cert;
+        }
+
+        let updated = domain.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
+    /// Set (or clear with None) a domain's custom Caddyfile directives
+    pub fn update_domain_custom_directives(
+        &self,
+        id: Uuid,
+        custom_directives: Option<String>,
+    ) -> Result<Domain, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let domain = config
+            .domains
+            .iter_mut()
+            .find(|d| d.id == id)
+            .ok_or_else(|| format!("Domain {} not found", id))?;
+
+        domain.custom_directives = custom_directives;
+
+        let updated = domain.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
     /// Delete all domains that route to a specific instance
     pub fn delete_domains_for_instance(&self, instance_id: Uuid) -> Result<Vec<Domain>, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let (removed, remaining): (Vec<_>, Vec<_>) = config
@@ -644,6 +1215,7 @@ impl ConfigStore {
     /// Migrate existing instance domain settings to Domain entities
     /// This is called once on startup to migrate from old format
     pub fn migrate_instance_domains(&self) -> Result<Vec<Domain>, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
         let mut migrated = Vec::new();
 
@@ -658,7 +1230,7 @@ impl ConfigStore {
                     .any(|d| matches!(&d.target, DomainTarget::Instance(id) if id == &instance.id));
 
                 if !has_domain {
-                    let subdomain = instance.effective_domain_slug();
+                    let subdomain = instance.effective_domain_slug(&config.slugs);
                     // Migrated domains default to SSL enabled
                     let domain = Domain::for_instance(subdomain, instance.id, true);
                     migrated.push(domain.clone());
@@ -701,7 +1273,9 @@ impl ConfigStore {
         &self,
         path: String,
         ssl_enabled: bool,
+        depth: u8,
     ) -> Result<ParkedDirectory, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate that the path exists and is a directory
@@ -718,7 +1292,7 @@ impl ConfigStore {
             return Err(format!("Directory '{}' is already parked", path));
         }
 
-        let parked_dir = ParkedDirectory::new(path, ssl_enabled);
+        let parked_dir = ParkedDirectory::new(path, ssl_enabled, depth);
 
         config.parked_directories.push(parked_dir.clone());
         self.save(&config)?;
@@ -728,6 +1302,7 @@ impl ConfigStore {
 
     /// Delete a parked directory by ID
     pub fn delete_parked_directory(&self, id: Uuid) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let idx = config
@@ -748,6 +1323,7 @@ impl ConfigStore {
         id: Uuid,
         ssl_enabled: bool,
     ) -> Result<ParkedDirectory, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let parked_dir = config
@@ -764,6 +1340,29 @@ impl ConfigStore {
         Ok(updated)
     }
 
+    /// Update how deep a parked directory is searched for projects
+    pub fn update_parked_directory_depth(
+        &self,
+        id: Uuid,
+        depth: u8,
+    ) -> Result<ParkedDirectory, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+
+        let parked_dir = config
+            .parked_directories
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Parked directory {} not found", id))?;
+
+        parked_dir.depth = depth;
+
+        let updated = parked_dir.clone();
+        self.save(&config)?;
+
+        Ok(updated)
+    }
+
     /// Get all domains associated with a parked directory
     pub fn get_domains_for_parked_directory(
         &self,
@@ -782,6 +1381,7 @@ impl ConfigStore {
         &self,
         parked_dir_id: Uuid,
     ) -> Result<Vec<Domain>, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let (removed, remaining): (Vec<_>, Vec<_>) = config
@@ -852,6 +1452,7 @@ impl ConfigStore {
         description: Option<String>,
         instance_ids: Vec<Uuid>,
     ) -> Result<Stack, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate all instance IDs exist
@@ -883,6 +1484,7 @@ impl ConfigStore {
         name: Option<String>,
         description: Option<Option<String>>,
     ) -> Result<Stack, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let stack = config
@@ -909,6 +1511,7 @@ impl ConfigStore {
     /// If delete_instances is true, also deletes all instances in the stack
     /// If false, instances become standalone (stack_id = None)
     pub fn delete_stack(&self, id: Uuid, delete_instances: bool) -> Result<Vec<Uuid>, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Find the stack
@@ -951,6 +1554,7 @@ impl ConfigStore {
         stack_id: Uuid,
         instance_ids: Vec<Uuid>,
     ) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate stack exists
@@ -983,6 +1587,7 @@ impl ConfigStore {
 
     /// Remove instances from their stack (move to standalone)
     pub fn remove_instances_from_stack(&self, instance_ids: Vec<Uuid>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Track which stacks were affected
@@ -1033,6 +1638,7 @@ impl ConfigStore {
 
     /// Reorder instances based on provided ID list
     pub fn reorder_instances(&self, instance_ids: Vec<Uuid>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Create a map of instance IDs to their desired position
@@ -1058,6 +1664,7 @@ impl ConfigStore {
     }
 
     pub fn reorder_domains(&self, domain_ids: Vec<Uuid>) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Create a map of domain IDs to their desired position
@@ -1088,6 +1695,7 @@ impl ConfigStore {
         instance_id: Uuid,
         new_stack_id: Option<Uuid>,
     ) -> Result<Instance, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate new stack exists if provided
@@ -1162,6 +1770,7 @@ impl ConfigStore {
         token: String,
         subdomain_host: String,
     ) -> Result<FrpServer, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // If this is the first server, make it the default
@@ -1196,6 +1805,7 @@ impl ConfigStore {
         subdomain_host: Option<String>,
         is_default: Option<bool>,
     ) -> Result<FrpServer, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Find index first to avoid borrow checker issues
@@ -1242,6 +1852,7 @@ impl ConfigStore {
 
     /// Delete an frp server
     pub fn delete_frp_server(&self, id: Uuid) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Check if any tunnels use this server
@@ -1296,6 +1907,7 @@ impl ConfigStore {
         protocol: String,
         auto_start: bool,
     ) -> Result<Tunnel, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate server exists
@@ -1326,6 +1938,7 @@ impl ConfigStore {
             subdomain,
             protocol,
             auto_start,
+            header_presets: Vec::new(),
             created_at: Utc::now(),
         };
 
@@ -1347,6 +1960,7 @@ impl ConfigStore {
         protocol: Option<String>,
         auto_start: Option<bool>,
     ) -> Result<Tunnel, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         // Validate server exists if changing
@@ -1394,8 +2008,29 @@ impl ConfigStore {
         Ok(updated)
     }
 
+    /// Replace the response header presets of a tunnel
+    pub fn update_tunnel_header_presets(
+        &self,
+        id: Uuid,
+        presets: Vec<HeaderPreset>,
+    ) -> Result<Tunnel, String> {
+        let _lock = self.lock_file()?;
+        let mut config = self.load()?;
+        let tunnel = config
+            .tunnels
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Tunnel {} not found", id))?;
+        tunnel.header_presets = presets;
+
+        let updated = tunnel.clone();
+        self.save(&config)?;
+        Ok(updated)
+    }
+
     /// Delete a tunnel
     pub fn delete_tunnel(&self, id: Uuid) -> Result<(), String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let idx = config
@@ -1422,6 +2057,7 @@ impl ConfigStore {
 
     /// Delete all tunnels that target a specific instance
     pub fn delete_tunnels_for_instance(&self, instance_id: Uuid) -> Result<Vec<Tunnel>, String> {
+        let _lock = self.lock_file()?;
         let mut config = self.load()?;
 
         let (removed, remaining): (Vec<_>, Vec<_>) = config
@@ -1435,3 +2071,65 @@ impl ConfigStore {
         Ok(removed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempConfigDir;
+
+    #[test]
+    fn test_lock_is_reentrant() {
+        let dir = TempConfigDir::new().unwrap();
+        let store = ConfigStore::at(dir.config_path.clone());
+
+        let outer = store.lock_file().unwrap();
+        // Saving takes the lock again on the same thread
+        store.add_tld("test".to_string()).unwrap();
+        drop(outer);
+
+        // Released, so another store (as in another process) can take it
+        let other = ConfigStore::at(dir.config_path.clone());
+        drop(other.lock_file().unwrap());
+        assert_eq!(store.load().unwrap().extra_tlds, vec!["test"]);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = TempConfigDir::new().unwrap();
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let path = dir.config_path.clone();
+                std::thread::spawn(move || {
+                    ConfigStore::at(path).add_tld(format!("t{}", i)).unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let config = ConfigStore::at(dir.config_path.clone()).load().unwrap();
+        assert_eq!(config.extra_tlds.len(), 8);
+    }
+
+    #[test]
+    fn test_ssl_stays_on_while_client_cert_required() {
+        let dir = TempConfigDir::new().unwrap();
+        let store = ConfigStore::at(dir.config_path.clone());
+        let domain = store
+            .create_domain_for_port("admin".to_string(), 8080, true)
+            .unwrap();
+        store
+            .update_domain_https_options(domain.id, None, None, Some(true))
+            .unwrap();
+
+        assert!(store.update_domain_ssl(domain.id, false).is_err());
+        assert!(store.get_domain(domain.id).unwrap().ssl_enabled);
+
+        store
+            .update_domain_https_options(domain.id, None, None, Some(false))
+            .unwrap();
+        let domain = store.update_domain_ssl(domain.id, false).unwrap();
+        assert!(!domain.ssl_enabled);
+    }
+}
@@ -0,0 +1,290 @@
+//! Template variables in instance config
+//!
+//! Config values may contain placeholders such as `{{instance.port}}`,
+//! `{{data_dir}}`, `{{app_dir}}`, or `{{domain}}`. They are stored as written
+//! and resolved each time the instance starts, so config copied to another
+//! instance picks up that instance's port and paths instead of stale absolute
+//! values. `services.json` config field defaults may use the same
+//! placeholders.
+
+use std::collections::BTreeMap;
+
+use super::models::{Config, Instance};
+use super::paths::{get_app_dir, get_instance_dir};
+use crate::service_config::ServiceRegistry;
+
+/// Variables available to config templates
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "instance.id",
+    "instance.name",
+    "instance.port",
+    "instance.version",
+    "data_dir",
+    "app_dir",
+    "home",
+    "tld",
+    "domain",
+];
+
+/// Values for one instance's template variables
+///
+/// A `None` value means the variable exists but has no value for this
+/// instance (e.g. `domain` when domain routing is disabled).
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    values: BTreeMap<&'static str, Option<String>>,
+}
+
+impl TemplateVars {
+    /// Build the variables for an instance; `config` enables `{{tld}}`/`{{domain}}`
+    pub fn for_instance(instance: &Instance, config: Option<&Config>) -> Result<Self, String> {
+        let data_dir = get_instance_dir(&instance.id)?;
+        let app_dir = get_app_dir()?;
+
+        let mut values = BTreeMap::new();
+        values.insert("instance.id", Some(instance.id.to_string()));
+        values.insert("instance.name", Some(instance.name.clone()));
+        values.insert("instance.port", Some(instance.port.to_string()));
+        values.insert("instance.version", Some(instance.version.clone()));
+        values.insert("data_dir", Some(data_dir.to_string_lossy().to_string()));
+        values.insert("app_dir", Some(app_dir.to_string_lossy().to_string()));
+        values.insert(
+            "home",
+            dirs::home_dir().map(|h| h.to_string_lossy().to_string()),
+        );
+        values.insert("tld", config.map(|c| c.tld.clone()));
+        values.insert(
+            "domain",
+            config
+                .filter(|_| instance.domain_enabled)
+                .map(|c| instance.full_domain(&c.tld, &c.slugs)),
+        );
+
+        Ok(Self { values })
+    }
+
+    /// Replace every `{{ variable }}` in `template`
+    ///
+    /// Unknown variables are an error so typos surface at start time rather
+    /// than as a literal `{{...}}` passed to the service. An unclosed `{{` is
+    /// left as-is.
+    pub fn render(&self, template: &str) -> Result<String, String> {
+        let mut result = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let name = rest[start + 2..start + 2 + len].trim();
+
+            let value = match self.values.get(name) {
+                Some(Some(value)) => value,
+                Some(None) => return Err(unavailable_message(name)),
+                None => {
+                    return Err(format!(
+                        "Unknown template variable '{{{{{}}}}}'. Available: {}",
+                        name,
+                        TEMPLATE_VARIABLES.join(", ")
+                    ))
+                }
+            };
+
+            result.push_str(&rest[..start]);
+            result.push_str(value);
+            rest = &rest[start + 2 + len + 2..];
+        }
+
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    /// Render every string in a JSON value, recursing into arrays and objects
+    pub fn render_value(&self, value: &serde_json::Value) -> Result<serde_json::Value, String> {
+        self.render_value_at(value, "config")
+    }
+
+    fn render_value_at(
+        &self,
+        value: &serde_json::Value,
+        path: &str,
+    ) -> Result<serde_json::Value, String> {
+        use serde_json::Value;
+
+        match value {
+            Value::String(s) => self
+                .render(s)
+                .map(Value::String)
+                .map_err(|e| format!("{}: {}", path, e)),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| self.render_value_at(item, &format!("{}[{}]", path, i)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            Value::Object(map) => {
+                let mut rendered = serde_json::Map::new();
+                for (key, item) in map {
+                    let item = self.render_value_at(item, &format!("{}.{}", path, key))?;
+                    rendered.insert(key.clone(), item);
+                }
+                Ok(Value::Object(rendered))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+fn unavailable_message(name: &str) -> String {
+    match name {
+        "domain" => "'{{domain}}' needs domain routing enabled on this instance".to_string(),
+        _ => format!("'{{{{{}}}}}' has no value for this instance", name),
+    }
+}
+
+/// Whether any string in a JSON value contains a `{{` placeholder
+pub fn has_placeholders(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::String(s) => s.contains("{{"),
+        serde_json::Value::Array(items) => items.iter().any(has_placeholders),
+        serde_json::Value::Object(map) => map.values().any(has_placeholders),
+        _ => false,
+    }
+}
+
+/// Fill unset config keys whose `services.json` default is a template
+///
+/// Plain defaults stay with the service code that already applies them;
+/// only templated defaults need the instance's values to mean anything.
+fn apply_templated_defaults(instance: &mut Instance) {
+    let Some(service) = ServiceRegistry::load().get_service(instance.service_type.as_str()) else {
+        return;
+    };
+    let Some(config) = instance.config.as_object_mut() else {
+        return;
+    };
+
+    for field in &service.config_fields {
+        if let Some(default) = field.default.as_ref().filter(|d| d.contains("{{")) {
+            config
+                .entry(field.key.clone())
+                .or_insert_with(|| serde_json::Value::String(default.clone()));
+        }
+    }
+}
+
+/// Return a copy of the instance with its config templates resolved
+pub fn resolve_instance(instance: &Instance, config: Option<&Config>) -> Result<Instance, String> {
+    let mut resolved = instance.clone();
+    apply_templated_defaults(&mut resolved);
+    if has_placeholders(&resolved.config) {
+        let vars = TemplateVars::for_instance(instance, config)?;
+        resolved.config = vars.render_value(&resolved.config)?;
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+    use serde_json::json;
+
+    fn vars_for(instance: &Instance, config: Option<&Config>) -> TemplateVars {
+        TemplateVars::for_instance(instance, config).unwrap()
+    }
+
+    #[test]
+    fn test_render_instance_variables() {
+        let instance = InstanceBuilder::new().name("api").port(8080).build();
+        let vars = vars_for(&instance, None);
+
+        assert_eq!(
+            vars.render("http://127.0.0.1:{{instance.port}}/{{ instance.name }}")
+                .unwrap(),
+            "http://127.0.0.1:8080/api"
+        );
+        assert!(vars
+            .render("{{data_dir}}/uploads")
+            .unwrap()
+            .ends_with(&format!("{}/uploads", instance.id)));
+        assert_eq!(vars.render("no placeholders").unwrap(), "no placeholders");
+        assert_eq!(vars.render("unclosed {{port").unwrap(), "unclosed {{port");
+    }
+
+    #[test]
+    fn test_render_unknown_variable_fails() {
+        let instance = InstanceBuilder::new().build();
+        let err = vars_for(&instance, None).render("{{prot}}").unwrap_err();
+        assert!(err.contains("Unknown template variable '{{prot}}'"));
+    }
+
+    #[test]
+    fn test_render_domain_requires_routing() {
+        let config = ConfigBuilder::new().tld("test").build();
+        let routed = InstanceBuilder::new()
+            .domain("shop")
+            .domain_enabled(true)
+            .build();
+        assert_eq!(
+            vars_for(&routed, Some(&config))
+                .render("https://{{domain}}")
+                .unwrap(),
+            "https://shop.test"
+        );
+
+        let unrouted = InstanceBuilder::new()
+            .domain("shop")
+            .domain_enabled(false)
+            .build();
+        assert!(vars_for(&unrouted, Some(&config))
+            .render("{{domain}}")
+            .unwrap_err()
+            .contains("domain routing"));
+    }
+
+    #[test]
+    fn test_render_domain_follows_slug_settings() {
+        let mut config = ConfigBuilder::new().tld("test").build();
+        config
+            .slugs
+            .overrides
+            .insert("Web Shop".to_string(), "store".to_string());
+        let instance = InstanceBuilder::new()
+            .name("Web Shop")
+            .domain_enabled(true)
+            .build();
+
+        assert_eq!(
+            vars_for(&instance, Some(&config))
+                .render("{{domain}}")
+                .unwrap(),
+            "store.test"
+        );
+    }
+
+    #[test]
+    fn test_resolve_instance_config() {
+        let instance = InstanceBuilder::new()
+            .port(7700)
+            .config(json!({
+                "url": "http://localhost:{{instance.port}}",
+                "args": ["--port", "{{instance.port}}"],
+                "workers": 4
+            }))
+            .build();
+
+        let resolved = resolve_instance(&instance, None).unwrap();
+        assert_eq!(resolved.config["url"], "http://localhost:7700");
+        assert_eq!(resolved.config["args"][1], "7700");
+        assert_eq!(resolved.config["workers"], 4);
+        // The stored instance keeps its templates
+        assert_eq!(instance.config["url"], "http://localhost:{{instance.port}}");
+
+        let broken = InstanceBuilder::new()
+            .config(json!({ "nested": { "path": "{{nope}}" } }))
+            .build();
+        let err = resolve_instance(&broken, None).unwrap_err();
+        assert!(err.starts_with("config.nested.path:"));
+    }
+}
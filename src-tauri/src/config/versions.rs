@@ -0,0 +1,374 @@
+//! Default service versions
+//!
+//! `Config::default_versions` pins a preferred version per service type so a
+//! team standardizes on e.g. MariaDB 10.11 instead of whatever was newest
+//! when someone clicked download. A pin matches an installed version exactly
+//! or as a prefix (`10.11` matches `10.11.9`). Without a pin the newest
+//! installed version is used.
+//!
+//! Versions are ordered semver-style: numeric components compare as numbers
+//! and a prerelease (`1.12.0-rc.1`) sorts below its final release.
+//! `Config::release_channels` opts a service into release candidates or
+//! betas when listing versions to download; stable is the default.
+
+use super::models::{Config, ServiceType};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Version placeholder in stack templates meaning "use the default version"
+pub const DEFAULT_VERSION_ALIAS: &str = "default";
+
+/// Prerelease identifiers that mark a release candidate
+const RC_MARKERS: &[&str] = &["rc"];
+
+/// Prerelease identifiers that mark an alpha, beta, or other preview build
+const BETA_MARKERS: &[&str] = &[
+    "alpha", "beta", "pre", "preview", "dev", "nightly", "canary", "snapshot",
+];
+
+/// Which releases are offered when downloading a service
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    /// Final releases only
+    #[default]
+    Stable,
+    /// Final releases and release candidates
+    Rc,
+    /// Everything, including alphas and betas
+    Beta,
+}
+
+impl ReleaseChannel {
+    /// Parse a channel name as accepted by the commands and CLI
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "rc" => Ok(Self::Rc),
+            "beta" => Ok(Self::Beta),
+            other => Err(format!(
+                "Unknown release channel '{}'. Use 'stable', 'rc', or 'beta'",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Rc => "rc",
+            Self::Beta => "beta",
+        }
+    }
+
+    /// The channel a version belongs to, judged by its prerelease suffix
+    pub fn of_version(version: &str) -> Self {
+        let pre = ParsedVersion::parse(version).pre;
+        let has_marker = |markers: &[&str]| {
+            pre.iter().any(|id| {
+                let id = id.to_lowercase();
+                markers.iter().any(|m| id.starts_with(m))
+            })
+        };
+        if has_marker(BETA_MARKERS) {
+            Self::Beta
+        } else if has_marker(RC_MARKERS) {
+            Self::Rc
+        } else {
+            Self::Stable
+        }
+    }
+
+    /// Whether versions from `channel` are offered when following this one
+    pub fn includes(&self, channel: ReleaseChannel) -> bool {
+        channel <= *self
+    }
+}
+
+/// A version string split into numeric components and prerelease identifiers
+///
+/// Leading non-digits (`v`, `RELEASE.`) and build metadata (`+build.5`) are
+/// ignored. Strings without any number (`legacy`, `latest`) parse to an empty
+/// core and sort below every real version.
+struct ParsedVersion<'a> {
+    core: Vec<u64>,
+    pre: Vec<&'a str>,
+}
+
+impl<'a> ParsedVersion<'a> {
+    fn parse(version: &'a str) -> Self {
+        let version = version.split('+').next().unwrap_or_default();
+        let version = version.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let core_end = version
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(version.len());
+        let (core, pre) = version.split_at(core_end);
+
+        Self {
+            core: core
+                .split('.')
+                .filter(|part| !part.is_empty())
+                .filter_map(|part| part.parse().ok())
+                .collect(),
+            pre: pre
+                .split(['.', '-', '_'])
+                .filter(|id| !id.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// Compare prerelease identifiers: numbers numerically and below words
+fn compare_identifiers(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// Compare version strings semver-style (`10.11.9` > `10.6.2`, `2.0.0` >
+/// `2.0.0-rc.2` > `2.0.0-rc.1`, `1.2` == `1.2.0`)
+///
+/// Falls back to comparing the raw strings so sorting is deterministic.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (pa, pb) = (ParsedVersion::parse(a), ParsedVersion::parse(b));
+
+    let len = pa.core.len().max(pb.core.len());
+    let component = |core: &[u64], i: usize| core.get(i).copied().unwrap_or(0);
+    let core = (0..len)
+        .map(|i| component(&pa.core, i).cmp(&component(&pb.core, i)))
+        .find(|o| o.is_ne())
+        .unwrap_or_else(|| (!pa.core.is_empty()).cmp(&!pb.core.is_empty()));
+
+    let pre = match (pa.pre.is_empty(), pb.pre.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => pa
+            .pre
+            .iter()
+            .zip(&pb.pre)
+            .map(|(x, y)| compare_identifiers(x, y))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| pa.pre.len().cmp(&pb.pre.len())),
+    };
+
+    core.then(pre).then_with(|| a.cmp(b))
+}
+
+/// Whether an installed version satisfies a pin
+fn matches_pin(version: &str, pin: &str) -> bool {
+    version == pin
+        || version
+            .strip_prefix(pin)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Whether a requested version asks for the default (empty or `default`)
+pub fn is_default_alias(version: &str) -> bool {
+    version.is_empty() || version == DEFAULT_VERSION_ALIAS
+}
+
+impl Config {
+    /// The pinned default version for a service type, if any
+    pub fn pinned_version(&self, service_type: ServiceType) -> Option<&str> {
+        self.default_versions
+            .get(&service_type)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// The release channel versions are offered from for a service type
+    pub fn release_channel(&self, service_type: ServiceType) -> ReleaseChannel {
+        self.release_channels
+            .get(&service_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Installed version to use when none is given
+    ///
+    /// The newest installed version matching the pin, or the newest installed
+    /// version when the service isn't pinned.
+    pub fn default_version(&self, service_type: ServiceType) -> Result<String, String> {
+        let name = service_type.display_name();
+        let installed = self.binaries.get(&service_type);
+        let pin = self.pinned_version(service_type);
+
+        let best = installed.and_then(|versions| {
+            versions
+                .keys()
+                .filter(|v| pin.map(|p| matches_pin(v, p)).unwrap_or(true))
+                .max_by(|a, b| compare_versions(a, b))
+                .cloned()
+        });
+
+        best.ok_or_else(|| match pin {
+            Some(pin) => format!(
+                "{} is pinned to version {}, which is not installed.\n\
+                 Download it in the Burd app or change the pin.",
+                name, pin
+            ),
+            None => format!(
+                "No {} versions installed.\nPlease download {} in the Burd app first.",
+                name, name
+            ),
+        })
+    }
+
+    /// Resolve a requested version, falling back to the default when it's
+    /// empty or `default`
+    pub fn resolve_version(
+        &self,
+        service_type: ServiceType,
+        version: &str,
+    ) -> Result<String, String> {
+        if is_default_alias(version) {
+            self.default_version(service_type)
+        } else {
+            Ok(version.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BinaryInfo;
+    use std::collections::HashMap;
+
+    fn config_with(versions: &[&str]) -> Config {
+        let mut config = Config::default();
+        let installed: HashMap<String, BinaryInfo> = versions
+            .iter()
+            .map(|v| {
+                let info = BinaryInfo {
+                    version: v.to_string(),
+                    path: format!("/bin/mariadb/{}", v),
+                    downloaded_at: chrono::Utc::now(),
+                    sha256: None,
+                    arch: None,
+                };
+                (v.to_string(), info)
+            })
+            .collect();
+        config.binaries.insert(ServiceType::MariaDB, installed);
+        config
+    }
+
+    #[test]
+    fn test_default_version_prefers_newest() {
+        let config = config_with(&["10.6.2", "10.11.9", "11.4.2"]);
+        assert_eq!(
+            config.default_version(ServiceType::MariaDB).unwrap(),
+            "11.4.2"
+        );
+        assert!(config.default_version(ServiceType::MySQL).is_err());
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        let mut versions = vec![
+            "v1.10.0",
+            "legacy",
+            "1.9.3",
+            "1.10.0-rc.2",
+            "1.10.0-rc.10",
+            "1.10.0-beta.1",
+            "1.2",
+            "1.2.0+build.7",
+        ];
+        versions.sort_by(|a, b| compare_versions(b, a));
+        assert_eq!(
+            versions,
+            vec![
+                "v1.10.0",
+                "1.10.0-rc.10",
+                "1.10.0-rc.2",
+                "1.10.0-beta.1",
+                "1.9.3",
+                "1.2.0+build.7",
+                "1.2",
+                "legacy",
+            ]
+        );
+        assert_eq!(
+            compare_versions(
+                "RELEASE.2024-12-18T13-15-44Z",
+                "RELEASE.2024-11-07T00-52-20Z"
+            ),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_release_channel_of_version() {
+        assert_eq!(
+            ReleaseChannel::of_version("v1.11.3"),
+            ReleaseChannel::Stable
+        );
+        assert_eq!(
+            ReleaseChannel::of_version("1.12.0-rc.1"),
+            ReleaseChannel::Rc
+        );
+        assert_eq!(ReleaseChannel::of_version("v3.0.0rc2"), ReleaseChannel::Rc);
+        assert_eq!(
+            ReleaseChannel::of_version("v1.12.0-beta.0"),
+            ReleaseChannel::Beta
+        );
+        assert_eq!(
+            ReleaseChannel::of_version("2.0.0-alpha"),
+            ReleaseChannel::Beta
+        );
+        assert_eq!(
+            ReleaseChannel::of_version("RELEASE.2024-12-18T13-15-44Z"),
+            ReleaseChannel::Stable
+        );
+
+        assert!(ReleaseChannel::Stable.includes(ReleaseChannel::Stable));
+        assert!(!ReleaseChannel::Stable.includes(ReleaseChannel::Rc));
+        assert!(ReleaseChannel::Rc.includes(ReleaseChannel::Rc));
+        assert!(!ReleaseChannel::Rc.includes(ReleaseChannel::Beta));
+        assert!(ReleaseChannel::Beta.includes(ReleaseChannel::Rc));
+        assert_eq!(ReleaseChannel::parse(" RC ").unwrap(), ReleaseChannel::Rc);
+        assert!(ReleaseChannel::parse("nightly").is_err());
+    }
+
+    #[test]
+    fn test_default_version_follows_pin() {
+        let mut config = config_with(&["10.1.0", "10.11.2", "10.11.9", "11.4.2"]);
+        config
+            .default_versions
+            .insert(ServiceType::MariaDB, "10.1".to_string());
+        assert_eq!(
+            config.default_version(ServiceType::MariaDB).unwrap(),
+            "10.1.0"
+        );
+
+        config
+            .default_versions
+            .insert(ServiceType::MariaDB, "10.11".to_string());
+        assert_eq!(
+            config
+                .resolve_version(ServiceType::MariaDB, "default")
+                .unwrap(),
+            "10.11.9"
+        );
+        assert_eq!(
+            config
+                .resolve_version(ServiceType::MariaDB, "11.4.2")
+                .unwrap(),
+            "11.4.2"
+        );
+
+        config
+            .default_versions
+            .insert(ServiceType::MariaDB, "10.4".to_string());
+        let err = config.default_version(ServiceType::MariaDB).unwrap_err();
+        assert!(err.contains("pinned to version 10.4"));
+    }
+}
@@ -0,0 +1,705 @@
+//! Config Export and Import
+//!
+//! Packs the parts of the config that describe a setup (instances, domains,
+//! stacks, parked directories, frp servers and tunnels) into a portable
+//! `.tar.gz`, so it can be recreated on another Mac. The archive holds:
+//!
+//! - `burd-config.json`: the exported config, with a manifest of the
+//!   installed service versions and the version preferences
+//! - `services/`: the user service definitions and their hooks
+//!
+//! Machine-specific settings (ports of Burd itself, the backup folder, API
+//! access, the GitHub token) and instance data are left out; scheduled
+//! backups cover the data. IDs are kept, so backups taken on the old Mac
+//! restore onto the imported instances.
+//!
+//! Imports merge into the existing config and can be run again: anything
+//! whose ID, name, subdomain or path already exists is skipped, and domains,
+//! instances and tunnels are pointed at the existing item with the same name.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::arch::Arch;
+use crate::config::{
+    get_app_dir, get_instance_dir, Config, Domain, DomainSource, DomainTarget, FrpServer, Instance,
+    ParkedDirectory, ReleaseChannel, ServiceType, Stack, Tunnel, TunnelTarget,
+};
+use crate::port_allocator;
+use crate::service_definitions;
+
+/// Current export schema version
+const SCHEMA_VERSION: u32 = 1;
+
+/// The exported config inside the archive
+const EXPORT_FILE: &str = "burd-config.json";
+
+/// User service definitions inside the archive
+const DEFINITIONS_DIR: &str = "services";
+
+/// Where an archive is unpacked while importing, in the app directory
+const IMPORT_STAGING_DIR: &str = "config-import";
+
+/// An installed service version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedVersion {
+    pub service_type: ServiceType,
+    pub version: String,
+}
+
+/// A portable copy of a Burd setup (stored as `burd-config.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigExport {
+    pub schema_version: u32,
+    /// Burd version that wrote the export
+    pub burd_version: String,
+    pub created_at: DateTime<Utc>,
+    pub tld: String,
+    #[serde(default)]
+    pub instances: Vec<Instance>,
+    #[serde(default)]
+    pub domains: Vec<Domain>,
+    #[serde(default)]
+    pub stacks: Vec<Stack>,
+    #[serde(default)]
+    pub parked_directories: Vec<ParkedDirectory>,
+    #[serde(default)]
+    pub frp_servers: Vec<FrpServer>,
+    #[serde(default)]
+    pub tunnels: Vec<Tunnel>,
+    /// Service versions installed when exporting
+    #[serde(default)]
+    pub versions: Vec<ExportedVersion>,
+    #[serde(default)]
+    pub default_versions: HashMap<ServiceType, String>,
+    #[serde(default)]
+    pub release_channels: HashMap<ServiceType, ReleaseChannel>,
+    #[serde(default)]
+    pub arch_overrides: HashMap<ServiceType, Arch>,
+    /// IDs of the user service definitions in the archive
+    #[serde(default)]
+    pub service_definitions: Vec<String>,
+}
+
+/// What an import added, and what it left out
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigImportResult {
+    /// Names of the imported instances
+    pub instances: Vec<String>,
+    /// Subdomains of the imported domains
+    pub domains: Vec<String>,
+    pub stacks: Vec<String>,
+    /// Paths of the imported parked directories
+    pub parked_directories: Vec<String>,
+    pub frp_servers: Vec<String>,
+    pub tunnels: Vec<String>,
+    /// IDs of the installed service definitions
+    pub service_definitions: Vec<String>,
+    /// What wasn't imported, and why
+    pub skipped: Vec<String>,
+    /// Changes made while importing, such as instances moved to a free port
+    pub notes: Vec<String>,
+    /// Versions installed on the exporting Mac but not here
+    pub missing_versions: Vec<ExportedVersion>,
+}
+
+/// Build the export of a config
+pub fn build_export(config: &Config) -> ConfigExport {
+    let mut versions: Vec<ExportedVersion> = config
+        .binaries
+        .iter()
+        .flat_map(|(service_type, installed)| {
+            installed.keys().map(|version| ExportedVersion {
+                service_type: *service_type,
+                version: version.clone(),
+            })
+        })
+        .collect();
+    versions.sort_by(|a, b| {
+        (a.service_type.as_str(), &a.version).cmp(&(b.service_type.as_str(), &b.version))
+    });
+
+    ConfigExport {
+        schema_version: SCHEMA_VERSION,
+        burd_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        tld: config.tld.clone(),
+        instances: config.instances.clone(),
+        domains: config.domains.clone(),
+        stacks: config.stacks.clone(),
+        parked_directories: config.parked_directories.clone(),
+        frp_servers: config.frp_servers.clone(),
+        tunnels: config.tunnels.clone(),
+        versions,
+        default_versions: config.default_versions.clone(),
+        release_channels: config.release_channels.clone(),
+        arch_overrides: config.arch_overrides.clone(),
+        service_definitions: Vec::new(),
+    }
+}
+
+/// Write the export of a config and the user service definitions to `dest`
+pub fn export_archive(config: &Config, dest: &Path) -> Result<ConfigExport, String> {
+    let definitions_dir = service_definitions::get_definitions_dir()?;
+    let mut export = build_export(config);
+    export.service_definitions = service_definitions::load_all()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    export.service_definitions.sort();
+
+    let json = serde_json::to_vec_pretty(&export)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let file = fs::File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(export.created_at.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, EXPORT_FILE, Cursor::new(json))
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+
+    if !export.service_definitions.is_empty() {
+        builder
+            .append_dir_all(DEFINITIONS_DIR, &definitions_dir)
+            .map_err(|e| format!("Failed to add service definitions: {}", e))?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+
+    Ok(export)
+}
+
+/// Parse and validate an exported config
+pub fn parse_export(json: &str) -> Result<ConfigExport, String> {
+    let export: ConfigExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid config export: {}", e))?;
+    if export.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Config export uses schema version {}; this version of Burd supports up to {}",
+            export.schema_version, SCHEMA_VERSION
+        ));
+    }
+    Ok(export)
+}
+
+/// Unpack an archive into `dest` and read its exported config
+pub fn unpack_archive(archive: &Path, dest: &Path) -> Result<ConfigExport, String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+    tar::Archive::new(GzDecoder::new(file))
+        .unpack(dest)
+        .map_err(|e| format!("Failed to extract {}: {}", archive.display(), e))?;
+
+    let json = fs::read_to_string(dest.join(EXPORT_FILE)).map_err(|_| {
+        format!(
+            "{} is not a Burd config export (no {})",
+            archive.display(),
+            EXPORT_FILE
+        )
+    })?;
+    parse_export(&json)
+}
+
+/// Import an archive into `config`
+///
+/// Service definitions are installed and instance directories created unless
+/// `dry_run` is set; the caller saves the config and registers the routes.
+pub fn import_archive(
+    config: &mut Config,
+    archive: &Path,
+    dry_run: bool,
+) -> Result<ConfigImportResult, String> {
+    let staging = get_app_dir()?.join(IMPORT_STAGING_DIR);
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+
+    let result = unpack_archive(archive, &staging).map(|export| {
+        let mut result = apply_import(config, &export);
+        import_definitions(&staging.join(DEFINITIONS_DIR), dry_run, &mut result);
+        result
+    });
+    let _ = fs::remove_dir_all(&staging);
+    let result = result?;
+
+    if !dry_run {
+        for instance in config
+            .instances
+            .iter()
+            .filter(|i| result.instances.contains(&i.name))
+        {
+            let dir = get_instance_dir(&instance.id)?;
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Install the archive's service definitions that aren't installed here
+fn import_definitions(dir: &Path, dry_run: bool, result: &mut ConfigImportResult) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let installed: Vec<String> = service_definitions::load_all()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if installed.iter().any(|installed| installed == id) {
+            result
+                .skipped
+                .push(format!("Service definition '{}': already installed", id));
+            continue;
+        }
+        let outcome = if dry_run {
+            service_definitions::read_definition(&path).map(|_| ())
+        } else {
+            service_definitions::install(&path, false).map(|_| ())
+        };
+        match outcome {
+            Ok(()) => result.service_definitions.push(id.to_string()),
+            Err(e) => result
+                .skipped
+                .push(format!("Service definition '{}': {}", id, e)),
+        }
+    }
+}
+
+/// Merge an export into `config`
+pub fn apply_import(config: &mut Config, export: &ConfigExport) -> ConfigImportResult {
+    let mut result = ConfigImportResult::default();
+
+    // Stacks: existing ones with the same name are reused
+    let mut stack_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for stack in &export.stacks {
+        if let Some(existing) = config
+            .stacks
+            .iter()
+            .find(|s| s.id == stack.id || s.name.eq_ignore_ascii_case(&stack.name))
+        {
+            stack_ids.insert(stack.id, existing.id);
+            result
+                .skipped
+                .push(format!("Stack '{}': already exists", stack.name));
+            continue;
+        }
+        stack_ids.insert(stack.id, stack.id);
+        result.stacks.push(stack.name.clone());
+        config.stacks.push(stack.clone());
+    }
+
+    // Instances: existing ones with the same name are reused, and taken
+    // ports are replaced with free ones
+    let mut instance_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for instance in &export.instances {
+        if let Some(existing) = config
+            .instances
+            .iter()
+            .find(|i| i.id == instance.id || i.name == instance.name)
+        {
+            instance_ids.insert(instance.id, existing.id);
+            result
+                .skipped
+                .push(format!("Instance '{}': already exists", instance.name));
+            continue;
+        }
+
+        let mut instance = instance.clone();
+        instance.stack_id = instance.stack_id.and_then(|id| stack_ids.get(&id).copied());
+        if port_allocator::reserved_ports(config).contains(&instance.port) {
+            match port_allocator::suggest_port(config, instance.service_type) {
+                Ok(port) => {
+                    result.notes.push(format!(
+                        "Instance '{}' moved from port {} to {}, which was free",
+                        instance.name, instance.port, port
+                    ));
+                    instance.port = port;
+                }
+                Err(e) => {
+                    result.skipped.push(format!(
+                        "Instance '{}': port {} is taken ({})",
+                        instance.name, instance.port, e
+                    ));
+                    continue;
+                }
+            }
+        }
+
+        instance_ids.insert(instance.id, instance.id);
+        result.instances.push(instance.name.clone());
+        config.instances.push(instance);
+    }
+
+    // Parked directories: only ones that exist here
+    let mut parked_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for parked in &export.parked_directories {
+        if let Some(existing) = config
+            .parked_directories
+            .iter()
+            .find(|p| p.id == parked.id || p.path == parked.path)
+        {
+            parked_ids.insert(parked.id, existing.id);
+            result
+                .skipped
+                .push(format!("Parked directory {}: already parked", parked.path));
+            continue;
+        }
+        if !Path::new(&parked.path).is_dir() {
+            result.skipped.push(format!(
+                "Parked directory {}: directory not found",
+                parked.path
+            ));
+            continue;
+        }
+        parked_ids.insert(parked.id, parked.id);
+        result.parked_directories.push(parked.path.clone());
+        config.parked_directories.push(parked.clone());
+    }
+
+    for domain in &export.domains {
+        let skip = |reason: &str| format!("Domain '{}': {}", domain.subdomain, reason);
+        if config
+            .domains
+            .iter()
+            .any(|d| d.id == domain.id || d.subdomain == domain.subdomain)
+        {
+            result.skipped.push(skip("already exists"));
+            continue;
+        }
+
+        let mut domain = domain.clone();
+        match &domain.target {
+            DomainTarget::Instance(id) => match instance_ids.get(id) {
+                Some(mapped) => domain.target = DomainTarget::Instance(*mapped),
+                None => {
+                    result.skipped.push(skip("its instance wasn't imported"));
+                    continue;
+                }
+            },
+            DomainTarget::StaticFiles { path, .. } if !Path::new(path).is_dir() => {
+                result
+                    .skipped
+                    .push(skip(&format!("directory {} not found", path)));
+                continue;
+            }
+            _ => {}
+        }
+        match domain.source {
+            DomainSource::Parked { parked_dir_id } => match parked_ids.get(&parked_dir_id) {
+                Some(mapped) => {
+                    domain.source = DomainSource::Parked {
+                        parked_dir_id: *mapped,
+                    }
+                }
+                None => {
+                    result
+                        .skipped
+                        .push(skip("its parked directory wasn't imported"));
+                    continue;
+                }
+            },
+            DomainSource::Isolated {
+                original_parked_dir_id,
+            } => {
+                domain.source = match parked_ids.get(&original_parked_dir_id) {
+                    Some(mapped) => DomainSource::Isolated {
+                        original_parked_dir_id: *mapped,
+                    },
+                    None => DomainSource::Manual,
+                };
+            }
+            _ => {}
+        }
+
+        result.domains.push(domain.subdomain.clone());
+        config.domains.push(domain);
+    }
+
+    // frp servers: existing ones for the same address are reused
+    let mut server_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for server in &export.frp_servers {
+        if let Some(existing) = config.frp_servers.iter().find(|s| {
+            s.id == server.id
+                || (s.server_addr == server.server_addr && s.server_port == server.server_port)
+        }) {
+            server_ids.insert(server.id, existing.id);
+            result
+                .skipped
+                .push(format!("frp server '{}': already exists", server.name));
+            continue;
+        }
+        let mut server = server.clone();
+        server.is_default = server.is_default && !config.frp_servers.iter().any(|s| s.is_default);
+        server_ids.insert(server.id, server.id);
+        result.frp_servers.push(server.name.clone());
+        config.frp_servers.push(server);
+    }
+
+    for tunnel in &export.tunnels {
+        let skip = |reason: &str| format!("Tunnel '{}': {}", tunnel.name, reason);
+        if config.tunnels.iter().any(|t| t.id == tunnel.id) {
+            result.skipped.push(skip("already exists"));
+            continue;
+        }
+        let Some(server_id) = server_ids.get(&tunnel.server_id) else {
+            result.skipped.push(skip("its frp server wasn't imported"));
+            continue;
+        };
+
+        let mut tunnel = tunnel.clone();
+        tunnel.server_id = *server_id;
+        if let TunnelTarget::Instance(id) = tunnel.target {
+            match instance_ids.get(&id) {
+                Some(mapped) => tunnel.target = TunnelTarget::Instance(*mapped),
+                None => {
+                    result.skipped.push(skip("its instance wasn't imported"));
+                    continue;
+                }
+            }
+        }
+
+        result.tunnels.push(tunnel.name.clone());
+        config.tunnels.push(tunnel);
+    }
+
+    // Version preferences fill in what isn't set here
+    for (service_type, version) in &export.default_versions {
+        config
+            .default_versions
+            .entry(*service_type)
+            .or_insert_with(|| version.clone());
+    }
+    for (service_type, channel) in &export.release_channels {
+        config
+            .release_channels
+            .entry(*service_type)
+            .or_insert(*channel);
+    }
+    for (service_type, arch) in &export.arch_overrides {
+        config.arch_overrides.entry(*service_type).or_insert(*arch);
+    }
+
+    result.missing_versions = export
+        .versions
+        .iter()
+        .filter(|v| {
+            !config
+                .binaries
+                .get(&v.service_type)
+                .is_some_and(|installed| installed.contains_key(&v.version))
+        })
+        .cloned()
+        .collect();
+
+    if export.tld != config.tld {
+        result.notes.push(format!(
+            "The export used .{} domains; they're now under .{} (change the TLD in Settings to keep .{})",
+            export.tld, config.tld, export.tld
+        ));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BinaryInfo, SubdomainConfig};
+    use crate::test_utils::{ConfigBuilder, DomainBuilder, InstanceBuilder, StackBuilder};
+
+    fn exported_config() -> Config {
+        let stack = StackBuilder::new().name("shop").build();
+        let db = InstanceBuilder::new()
+            .name("db")
+            .service_type(ServiceType::MariaDB)
+            .version("11.4.2")
+            .port(3306)
+            .stack_id(stack.id)
+            .build();
+        let server = FrpServer::new(
+            "tunnels".to_string(),
+            "tunnel.example.com".to_string(),
+            7000,
+            "secret".to_string(),
+            "tunnel.example.com".to_string(),
+        );
+        let tunnel = Tunnel::new(
+            "db".to_string(),
+            server.id,
+            TunnelTarget::Instance(db.id),
+            SubdomainConfig::Custom {
+                subdomain: "db".to_string(),
+            },
+        );
+
+        let mut config = ConfigBuilder::new()
+            .domain(DomainBuilder::new_instance(db.id).subdomain("db").build())
+            .domain(DomainBuilder::new_port(5173).subdomain("vite").build())
+            .domain(
+                DomainBuilder::new_static("/nonexistent/burd-docs")
+                    .subdomain("docs")
+                    .build(),
+            )
+            .instance(db)
+            .stack(stack)
+            .build();
+        config.frp_servers.push(server);
+        config.tunnels.push(tunnel);
+        config
+            .binaries
+            .entry(ServiceType::MariaDB)
+            .or_default()
+            .insert(
+                "11.4.2".to_string(),
+                BinaryInfo {
+                    version: "11.4.2".to_string(),
+                    path: "/nonexistent/mariadb".to_string(),
+                    downloaded_at: Utc::now(),
+                    sha256: None,
+                    arch: None,
+                },
+            );
+        config
+            .default_versions
+            .insert(ServiceType::MariaDB, "11.4".to_string());
+        config
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let export = build_export(&exported_config());
+        assert_eq!(
+            export.versions,
+            vec![ExportedVersion {
+                service_type: ServiceType::MariaDB,
+                version: "11.4.2".to_string(),
+            }]
+        );
+
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed = parse_export(&json).unwrap();
+        assert_eq!(parsed.instances.len(), 1);
+        assert_eq!(parsed.domains.len(), 3);
+        assert_eq!(parsed.tunnels.len(), 1);
+
+        let mut newer = export;
+        newer.schema_version = SCHEMA_VERSION + 1;
+        assert!(parse_export(&serde_json::to_string(&newer).unwrap()).is_err());
+        assert!(parse_export("{}").is_err());
+    }
+
+    #[test]
+    fn test_import_into_empty_config() {
+        let source = exported_config();
+        let export = build_export(&source);
+        let mut config = ConfigBuilder::new().build();
+
+        let result = apply_import(&mut config, &export);
+        assert_eq!(result.instances, vec!["db"]);
+        assert_eq!(result.stacks, vec!["shop"]);
+        assert_eq!(result.domains, vec!["db", "vite"]);
+        assert_eq!(result.tunnels, vec!["db"]);
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.skipped[0].contains("docs"));
+        assert_eq!(result.missing_versions, export.versions);
+        assert_eq!(config.instances[0].id, source.instances[0].id);
+        assert_eq!(config.instances[0].stack_id, Some(source.stacks[0].id));
+        assert_eq!(
+            config.default_versions.get(&ServiceType::MariaDB),
+            Some(&"11.4".to_string())
+        );
+
+        // Importing again changes nothing
+        let again = apply_import(&mut config, &export);
+        assert!(again.instances.is_empty());
+        assert!(again.domains.is_empty());
+        assert!(again.tunnels.is_empty());
+        assert_eq!(config.instances.len(), 1);
+        assert_eq!(config.domains.len(), 2);
+    }
+
+    #[test]
+    fn test_import_reuses_existing_items() {
+        let export = build_export(&exported_config());
+        let existing = InstanceBuilder::new()
+            .name("db")
+            .service_type(ServiceType::MariaDB)
+            .port(3307)
+            .build();
+        let taken = InstanceBuilder::new().name("cache").port(3306).build();
+        let mut config = ConfigBuilder::new()
+            .instance(existing.clone())
+            .instance(taken)
+            .build();
+
+        let mut export_with_new = export.clone();
+        let mut other = export.instances[0].clone();
+        other.id = Uuid::new_v4();
+        other.name = "db2".to_string();
+        export_with_new.instances.push(other);
+
+        let result = apply_import(&mut config, &export_with_new);
+        assert_eq!(result.instances, vec!["db2"]);
+        assert_eq!(result.notes.len(), 1);
+        let db2 = config.instances.iter().find(|i| i.name == "db2").unwrap();
+        assert_ne!(db2.port, 3306);
+
+        // The domain and tunnel follow the existing instance with the same name
+        let domain = config.domains.iter().find(|d| d.subdomain == "db").unwrap();
+        assert_eq!(domain.target, DomainTarget::Instance(existing.id));
+        assert_eq!(
+            config.tunnels[0].target,
+            TunnelTarget::Instance(existing.id)
+        );
+    }
+
+    #[test]
+    fn test_unpack_archive_requires_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("other.tar.gz");
+        let file = fs::File::create(&archive).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "notes.txt", Cursor::new(b"hi"))
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let dest = dir.path().join("unpacked");
+        let error = unpack_archive(&archive, &dest).unwrap_err();
+        assert!(error.contains("not a Burd config export"));
+    }
+}
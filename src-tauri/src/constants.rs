@@ -35,6 +35,9 @@ pub const HELPER_IDENTIFIER: &str = "com.burd.helper";
 /// Proxy daemon bundle identifier
 pub const PROXY_IDENTIFIER: &str = "com.burd.proxy";
 
+/// Headless daemon (user LaunchAgent) identifier
+pub const DAEMON_IDENTIFIER: &str = "com.burd.daemon";
+
 // =============================================================================
 // Derived Paths
 // =============================================================================
@@ -0,0 +1,220 @@
+//! Cron Schedules
+//!
+//! Parses the five-field cron syntax (`minute hour day-of-month month
+//! day-of-week`) used by scheduled backups, e.g. `30 2 * * *` for 02:30 every
+//! night or `0 */6 * * 1-5` for every six hours on weekdays. Fields accept
+//! `*`, numbers, ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `0-30/10`);
+//! day-of-week 0 and 7 are both Sunday. As in cron, when both day fields are
+//! restricted a day matching either one runs. Times are local wall-clock
+//! times.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+
+/// Days to search for the next run before giving up (e.g. `0 0 31 2 *`)
+const MAX_DAYS_AHEAD: u32 = 366 * 8;
+
+/// A parsed cron expression; each field is a bitmask of allowed values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+fn parse_value(value: &str, min: u32, max: u32, name: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| format!("Invalid {} '{}' (expected {}-{})", name, value, min, max))
+}
+
+/// Parse one field into a bitmask
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step '{}' in {}", step, name))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, name)?,
+                parse_value(end, min, max, name)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, name)?;
+            // `5/15` runs from 5 to the end of the range
+            (value, if step.is_some() { max } else { value })
+        };
+        if start > end {
+            return Err(format!("Invalid range '{}' in {}", range, name));
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "A schedule needs 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        };
+
+        // Sunday can be written as 0 or 7
+        let mut weekdays = parse_field(weekday, 0, 7, "weekday")?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// First matching time of day at or after `from`
+    fn first_time_from(&self, from: NaiveTime) -> Option<NaiveTime> {
+        (from.hour()..24)
+            .filter(|hour| self.hours & (1 << hour) != 0)
+            .find_map(|hour| {
+                let first_minute = if hour == from.hour() {
+                    from.minute()
+                } else {
+                    0
+                };
+                (first_minute..60)
+                    .find(|minute| self.minutes & (1 << minute) != 0)
+                    .and_then(|minute| NaiveTime::from_hms_opt(hour, minute, 0))
+            })
+    }
+
+    /// The first run strictly after `after`
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        let mut date = start.date();
+        let mut from = start.time();
+        for _ in 0..MAX_DAYS_AHEAD {
+            if self.matches_date(date) {
+                if let Some(time) = self.first_time_from(from) {
+                    return Some(date.and_time(time));
+                }
+            }
+            date = date.succ_opt()?;
+            from = NaiveTime::MIN;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(expr: &str, after: &str) -> NaiveDateTime {
+        CronSchedule::parse(expr)
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("* 5-2 * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("* * 0 * *").is_err());
+        assert!(CronSchedule::parse("0 2 * * mon").is_err());
+        assert!(CronSchedule::parse(" 30  2 * * * ").is_ok());
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2026-10-16 is a Friday
+        assert_eq!(
+            next("30 2 * * *", "2026-10-16 01:00"),
+            at("2026-10-16 02:30")
+        );
+        assert_eq!(
+            next("30 2 * * *", "2026-10-16 02:30"),
+            at("2026-10-17 02:30")
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-10-16 10:07"),
+            at("2026-10-16 10:15")
+        );
+        assert_eq!(
+            next("0 */6 * * 1-5", "2026-10-16 19:00"),
+            at("2026-10-19 00:00")
+        );
+        assert_eq!(
+            next("0 3 1,15 * *", "2026-10-16 00:00"),
+            at("2026-11-01 03:00")
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2026-10-16 00:00"),
+            at("2028-02-29 00:00")
+        );
+
+        // Sunday as 7, and either day field matching when both are set
+        assert_eq!(
+            next("0 9 * * 7", "2026-10-16 00:00"),
+            at("2026-10-18 09:00")
+        );
+        assert_eq!(
+            next("0 9 20 * 0", "2026-10-16 00:00"),
+            at("2026-10-18 09:00")
+        );
+    }
+
+    #[test]
+    fn test_impossible_schedule() {
+        let schedule = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert!(schedule.next_after(at("2026-10-16 00:00")).is_none());
+    }
+}
@@ -0,0 +1,415 @@
+//! Headless daemon mode
+//!
+//! Runs the pieces of Burd that don't need the GUI — the HTTP API, DNS
+//! server, proxy route sync, and instance supervision — so the environment
+//! works after a reboot without opening the app. Installed as a user
+//! LaunchAgent (`~/Library/LaunchAgents/com.burd.daemon.plist`) on macOS and
+//! a systemd user unit on Linux (see [`platform::user_service`]).
+
+use crate::activity::{self, ActivityKind};
+use crate::api::{self, API_PORT};
+use crate::autostart;
+use crate::backup;
+use crate::cert_monitor;
+use crate::commands::AppState;
+use crate::config::{get_app_dir, Config, ConfigStore};
+use crate::constants::DAEMON_IDENTIFIER;
+use crate::env_snapshot;
+use crate::idle;
+use crate::launchd;
+use crate::logs;
+use crate::metrics;
+use crate::platform::{self, ServiceStatus, UserService};
+use crate::process;
+use crate::proxy_watchdog;
+use crate::seed;
+use crate::traffic;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the supervisor checks auto-start instances
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Consecutive restarts before the supervisor gives up on an instance
+const MAX_RESTARTS: u32 = 5;
+
+/// Path of the LaunchAgent plist
+fn plist_path() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|h| {
+            h.join("Library/LaunchAgents")
+                .join(format!("{}.plist", DAEMON_IDENTIFIER))
+        })
+        .ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+/// Path of the daemon log file
+pub fn log_path() -> PathBuf {
+    platform::user_logs_dir().join("daemon.log")
+}
+
+/// Generate the LaunchAgent plist that runs `<exe> daemon run`
+fn generate_plist(exe: &str, log: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>daemon</string>
+        <string>run</string>
+    </array>
+
+    <key>RunAtLoad</key>
+    <true/>
+
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+
+    <key>ProcessType</key>
+    <string>Background</string>
+
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+        label = DAEMON_IDENTIFIER,
+        exe = exe,
+        log = log
+    )
+}
+
+/// The headless daemon as a user LaunchAgent
+pub struct LaunchAgent;
+
+impl UserService for LaunchAgent {
+    fn install(&self, exe: &Path, log: &Path) -> Result<(), String> {
+        let plist = plist_path()?;
+        if let Some(dir) = plist.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create LaunchAgents directory: {}", e))?;
+        }
+
+        // Reload cleanly if an older plist is already loaded
+        if plist.exists() {
+            let _ = launchctl(&["unload", "-w"], &plist);
+        }
+
+        let content = generate_plist(&exe.display().to_string(), &log.display().to_string());
+        fs::write(&plist, content).map_err(|e| format!("Failed to write plist: {}", e))?;
+
+        launchctl(&["load", "-w"], &plist)
+    }
+
+    fn uninstall(&self) -> Result<(), String> {
+        let plist = plist_path()?;
+        if !plist.exists() {
+            return Ok(()); // Already uninstalled
+        }
+
+        let _ = launchctl(&["unload", "-w"], &plist);
+        fs::remove_file(&plist).map_err(|e| format!("Failed to remove plist: {}", e))
+    }
+
+    fn status(&self) -> ServiceStatus {
+        let installed = plist_path().map(|p| p.exists()).unwrap_or(false);
+
+        let pid = if installed {
+            Command::new("launchctl")
+                .args(["list", DAEMON_IDENTIFIER])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .and_then(|o| launchd::parse_launchctl_pid(&String::from_utf8_lossy(&o.stdout)))
+        } else {
+            None
+        };
+
+        ServiceStatus {
+            installed,
+            running: pid.is_some(),
+            pid,
+        }
+    }
+
+    fn definition_path(&self) -> Result<PathBuf, String> {
+        plist_path()
+    }
+}
+
+/// Install and start the login service (no admin privileges needed)
+pub fn install() -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate the burd executable: {}", e))?;
+    let log = log_path();
+    if let Some(dir) = log.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    }
+
+    platform::user_service().install(&exe, &log)
+}
+
+/// Stop and remove the login service
+pub fn uninstall() -> Result<(), String> {
+    platform::user_service().uninstall()
+}
+
+/// Check whether the login service is installed and running
+pub fn get_status() -> ServiceStatus {
+    platform::user_service().status()
+}
+
+/// File defining the login service
+pub fn service_path() -> Result<PathBuf, String> {
+    platform::user_service().definition_path()
+}
+
+fn launchctl(args: &[&str], plist: &std::path::Path) -> Result<(), String> {
+    let output = Command::new("launchctl")
+        .args(args)
+        .arg(plist)
+        .output()
+        .map_err(|e| format!("Failed to run launchctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "launchctl {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Right to run the API server, supervisors and schedulers
+///
+/// Only one process - the app or the daemon - holds it, so nothing runs
+/// twice. It's an flock on `services.lock` in the app directory, released
+/// when the holder exits.
+pub struct ServicesLock {
+    _file: File,
+}
+
+fn open_services_lock() -> Result<File, String> {
+    let dir = get_app_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app directory: {}", e))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join("services.lock"))
+        .map_err(|e| format!("Failed to open services lock: {}", e))
+}
+
+/// Take the services lock, or `None` while another process holds it
+pub fn try_lock_services() -> Result<Option<ServicesLock>, String> {
+    let file = open_services_lock()?;
+    // SAFETY: flock only reads the descriptor, which `file` keeps open
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+            return Ok(None);
+        }
+        return Err(format!("Failed to lock services: {}", err));
+    }
+    Ok(Some(ServicesLock { _file: file }))
+}
+
+/// Take the services lock, waiting until its holder exits
+pub fn lock_services() -> Result<ServicesLock, String> {
+    let file = open_services_lock()?;
+    // SAFETY: flock only reads the descriptor, which `file` keeps open
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+        return Err(format!(
+            "Failed to lock services: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(ServicesLock { _file: file })
+}
+
+/// Run the daemon in the foreground until the API server exits
+///
+/// While the app runs the services, the daemon waits for it to quit rather
+/// than exiting, which the login service would treat as a crash and restart.
+pub fn run() -> Result<(), String> {
+    let _services = match try_lock_services()? {
+        Some(lock) => lock,
+        None => {
+            println!("The Burd app is running; the daemon takes over when it quits");
+            lock_services()?
+        }
+    };
+
+    let config_store = ConfigStore::new()?;
+    // Keep the previous version's config restorable (`burd rollback-env`)
+    env_snapshot::snapshot_on_startup(&config_store);
+    let config = config_store.load()?;
+    let state = Arc::new(AppState::new(config_store, &config));
+
+    let runtime =
+        tokio::runtime::Runtime::new().map_err(|e| format!("Failed to start runtime: {}", e))?;
+    runtime.block_on(serve(state, config))
+}
+
+async fn serve(state: Arc<AppState>, config: Config) -> Result<(), String> {
+    let dns_running = state
+        .dns_server
+        .lock()
+        .map(|dns| dns.is_running())
+        .unwrap_or(false);
+    if !dns_running {
+        eprintln!(
+            "DNS server could not bind port {}; .{} domains will not resolve",
+            config.dns_port, config.tld
+        );
+    }
+
+    // Same proxy setup as the app: fallback proxy unless Caddy is installed,
+    // then register every configured domain
+    {
+        let mut proxy = state.proxy_server.lock().await;
+        if !platform::proxy_daemon().is_installed() {
+            if let Err(e) = proxy.start().await {
+                eprintln!("Failed to start fallback proxy: {}", e);
+            }
+        }
+        proxy.register_config_routes(&config);
+    }
+
+    // Bring up auto-start instances, then keep them running
+    autostart::start_all((*state).clone()).await;
+    tokio::spawn(supervise(Arc::clone(&state)));
+    tokio::spawn(process::supervise((*state).clone(), |_| {}));
+    tokio::spawn(proxy_watchdog::run((*state).clone(), |_| {}));
+    tokio::spawn(cert_monitor::run((*state).clone(), |_| {}));
+    tokio::spawn(idle::run((*state).clone(), |_| {}));
+    tokio::spawn(logs::run((*state).clone()));
+    tokio::spawn(backup::run((*state).clone()));
+    tokio::spawn(seed::run((*state).clone()));
+    tokio::spawn(metrics::run((*state).clone()));
+    tokio::spawn(traffic::run((*state).clone()));
+
+    let api_host = if config.api_remote.enabled {
+        config.api_remote.bind_address.as_str()
+    } else {
+        "127.0.0.1"
+    };
+    println!(
+        "Burd daemon running (API on http://{}:{}, DNS on port {})",
+        api_host, API_PORT, config.dns_port
+    );
+    api::start_server(state).await
+}
+
+/// Restart auto-start instances that exit without being stopped
+///
+/// Instances flagged `restart_on_failure` are left to `process::supervise`,
+/// which also health-checks them.
+async fn supervise(state: Arc<AppState>) {
+    let mut restarts: HashMap<Uuid, u32> = HashMap::new();
+
+    loop {
+        tokio::time::sleep(SUPERVISE_INTERVAL).await;
+
+        let tick_state = Arc::clone(&state);
+        let counts = std::mem::take(&mut restarts);
+        restarts = tokio::task::spawn_blocking(move || supervise_tick(&tick_state, counts))
+            .await
+            .unwrap_or_default();
+    }
+}
+
+/// One supervisor pass; returns the updated consecutive-restart counts
+fn supervise_tick(state: &AppState, mut restarts: HashMap<Uuid, u32>) -> HashMap<Uuid, u32> {
+    let config = match state.config_store.lock().map(|store| store.load()) {
+        Ok(Ok(config)) => config,
+        _ => return restarts,
+    };
+
+    for instance in config
+        .instances
+        .iter()
+        .filter(|i| i.auto_start && !i.restart_on_failure)
+    {
+        let crashed = match state.process_manager.lock() {
+            Ok(pm) => pm.has_crashed(&instance.id),
+            Err(_) => continue,
+        };
+        if !crashed {
+            // Still running (or stopped on purpose) - reset the backoff
+            restarts.remove(&instance.id);
+            continue;
+        }
+
+        let count = restarts.entry(instance.id).or_insert(0);
+        *count += 1;
+        if *count <= MAX_RESTARTS + 1 {
+            activity::record(&instance.id, ActivityKind::Crashed, None);
+        }
+        if *count > MAX_RESTARTS {
+            if *count == MAX_RESTARTS + 1 {
+                eprintln!(
+                    "'{}' crashed {} times in a row; not restarting it again",
+                    instance.name, MAX_RESTARTS
+                );
+            }
+            continue;
+        }
+
+        match autostart::start_instance(state, &config, instance.id) {
+            Ok(pid) => println!("Restarted crashed '{}' (PID {})", instance.name, pid),
+            Err(e) => eprintln!("Failed to restart '{}': {}", instance.name, e),
+        }
+    }
+
+    restarts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_plist() {
+        let plist = generate_plist("/usr/local/bin/burd", "/tmp/daemon.log");
+        assert!(plist.contains("<string>com.burd.daemon</string>"));
+        assert!(plist.contains(
+            "<string>/usr/local/bin/burd</string>\n        <string>daemon</string>\n        <string>run</string>"
+        ));
+        assert!(plist.contains("<key>RunAtLoad</key>"));
+        assert!(plist.contains("<string>/tmp/daemon.log</string>"));
+    }
+
+    #[test]
+    fn test_services_lock_has_one_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::config::set_app_dir_override(Some(dir.path().to_path_buf()));
+
+        let held = try_lock_services().unwrap();
+        assert!(held.is_some());
+        assert!(try_lock_services().unwrap().is_none());
+
+        // Free again once the holder is gone
+        drop(held);
+        assert!(try_lock_services().unwrap().is_some());
+        crate::config::set_app_dir_override(None);
+    }
+}
@@ -2,9 +2,19 @@
 //!
 //! Provides database operations using the mysql/mariadb CLI tools.
 
-use super::{DatabaseInfo, DatabaseManager};
+use super::{quote_sql_string, DatabaseInfo, DatabaseManager, DatabaseUser, DbPrivileges};
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+
+/// Hosts each managed user is created for
+///
+/// `%` covers TCP connections; `localhost` is needed too, since an anonymous
+/// `''@'localhost'` account would otherwise match local connections first.
+const USER_HOSTS: [&str; 2] = ["localhost", "%"];
+
+/// Accounts left out of user listings
+const SYSTEM_USERS: &str = "'root', 'mysql', 'mariadb.sys', ''";
 
 /// MariaDB database manager
 pub struct MariaDbManager {
@@ -103,6 +113,76 @@ impl MariaDbManager {
 
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
+
+    /// Execute statements passed on stdin, keeping passwords off the command line
+    fn execute_script(&self, sql: &str) -> Result<String, String> {
+        let mysql = Self::find_mysql_binary();
+        let mut args = self.build_args();
+        args.push("-N".to_string());
+        args.push("-B".to_string());
+
+        let mut child = Command::new(&mysql)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to spawn mysql: {}", e))?;
+
+        if let Some(ref mut stdin) = child.stdin {
+            stdin
+                .write_all(sql.as_bytes())
+                .map_err(|e| format!("Failed to write to mysql stdin: {}", e))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to wait for mysql: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("MySQL error: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Run a statement once per host, given the `'user'@'host'` account
+    fn for_each_host(
+        &self,
+        username: &str,
+        statement: impl Fn(&str) -> String,
+    ) -> Result<(), String> {
+        let username = super::validate_db_user(username)?;
+        let script: String = USER_HOSTS
+            .iter()
+            .map(|host| {
+                let account = format!("{}@{}", quote_sql_string(&username), quote_sql_string(host));
+                format!("{};\n", statement(&account))
+            })
+            .collect();
+        self.execute_script(&script)?;
+        Ok(())
+    }
+
+    /// The user's current privileges on a database, if any
+    fn privileges_on(
+        &self,
+        username: &str,
+        database: &str,
+    ) -> Result<Option<DbPrivileges>, String> {
+        let query = format!(
+            "SELECT MAX(Insert_priv) FROM mysql.db WHERE User = {} AND Db = {}",
+            quote_sql_string(username),
+            quote_sql_string(database)
+        );
+        let output = self.execute_query(&query)?;
+        Ok(match output.trim() {
+            "Y" => Some(DbPrivileges::All),
+            "N" => Some(DbPrivileges::ReadOnly),
+            _ => None,
+        })
+    }
 }
 
 impl DatabaseManager for MariaDbManager {
@@ -178,7 +258,6 @@ impl DatabaseManager for MariaDbManager {
             .spawn()
             .map_err(|e| format!("Failed to spawn mysql: {}", e))?;
 
-        use std::io::Write;
         if let Some(ref mut stdin) = child.stdin {
             stdin
                 .write_all(sql_content.as_bytes())
@@ -244,4 +323,125 @@ impl DatabaseManager for MariaDbManager {
             format!("MariaDB at {}:{}", self.host, self.port)
         }
     }
+
+    fn list_users(&self, database: Option<&str>) -> Result<Vec<DatabaseUser>, String> {
+        let Some(database) = database else {
+            let query = format!(
+                "SELECT DISTINCT User FROM mysql.user WHERE User NOT IN ({}) ORDER BY User",
+                SYSTEM_USERS
+            );
+            let output = self.execute_query(&query)?;
+            return Ok(output
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|name| DatabaseUser {
+                    name: name.to_string(),
+                    privileges: None,
+                })
+                .collect());
+        };
+
+        let sanitized = super::sanitize_db_name(database)?;
+        let query = format!(
+            "SELECT User, MAX(Insert_priv) FROM mysql.db WHERE Db = {} AND User NOT IN ({}) \
+             GROUP BY User ORDER BY User",
+            quote_sql_string(&sanitized),
+            SYSTEM_USERS
+        );
+        let output = self.execute_query(&query)?;
+        Ok(output
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(name, insert)| DatabaseUser {
+                name: name.to_string(),
+                privileges: Some(if insert == "Y" {
+                    DbPrivileges::All
+                } else {
+                    DbPrivileges::ReadOnly
+                }),
+            })
+            .collect())
+    }
+
+    fn user_exists(&self, username: &str) -> Result<bool, String> {
+        let username = super::validate_db_user(username)?;
+        let query = format!(
+            "SELECT User FROM mysql.user WHERE User = {} LIMIT 1",
+            quote_sql_string(&username)
+        );
+        let output = self.execute_query(&query)?;
+        Ok(!output.trim().is_empty())
+    }
+
+    fn create_user(&self, username: &str, password: &str) -> Result<(), String> {
+        super::validate_db_password(password)?;
+        if self.user_exists(username)? {
+            return Err(format!("User '{}' already exists", username));
+        }
+        let password = quote_sql_string(password);
+        self.for_each_host(username, |account| {
+            format!("CREATE USER {} IDENTIFIED BY {}", account, password)
+        })
+    }
+
+    fn drop_user(&self, username: &str) -> Result<(), String> {
+        self.for_each_host(username, |account| {
+            format!("DROP USER IF EXISTS {}", account)
+        })
+    }
+
+    fn grant_privileges(
+        &self,
+        username: &str,
+        database: &str,
+        privileges: DbPrivileges,
+    ) -> Result<(), String> {
+        let sanitized = super::sanitize_db_name(database)?;
+        if !self.user_exists(username)? {
+            return Err(format!("User '{}' not found", username));
+        }
+        if !self.database_exists(&sanitized)? {
+            return Err(format!("Database '{}' not found", sanitized));
+        }
+
+        // Start from nothing so switching to read-only drops write access
+        if self.privileges_on(username, &sanitized)?.is_some() {
+            self.revoke_privileges(username, &sanitized)?;
+        }
+
+        let grant = match privileges {
+            DbPrivileges::All => "ALL PRIVILEGES",
+            DbPrivileges::ReadOnly => "SELECT, SHOW VIEW",
+        };
+        self.for_each_host(username, |account| {
+            format!("GRANT {} ON `{}`.* TO {}", grant, sanitized, account)
+        })
+    }
+
+    fn revoke_privileges(&self, username: &str, database: &str) -> Result<(), String> {
+        let sanitized = super::sanitize_db_name(database)?;
+        if self.privileges_on(username, &sanitized)?.is_none() {
+            return Err(format!(
+                "User '{}' has no privileges on '{}'",
+                username, sanitized
+            ));
+        }
+        self.for_each_host(username, |account| {
+            format!(
+                "REVOKE ALL PRIVILEGES ON `{}`.* FROM {}",
+                sanitized, account
+            )
+        })
+    }
+
+    fn set_user_password(&self, username: &str, password: &str) -> Result<(), String> {
+        super::validate_db_password(password)?;
+        if !self.user_exists(username)? {
+            return Err(format!("User '{}' not found", username));
+        }
+        let password = quote_sql_string(password);
+        self.for_each_host(username, |account| {
+            format!("ALTER USER {} IDENTIFIED BY {}", account, password)
+        })
+    }
 }
@@ -1,14 +1,18 @@
 //! Database Manager Module
 //!
-//! Provides database management operations for MariaDB and PostgreSQL.
+//! Provides database management operations for MariaDB, PostgreSQL and
+//! MongoDB.
 
 pub mod mariadb;
+pub mod mongodb;
 pub mod postgres;
 
 pub use mariadb::MariaDbManager;
+pub use mongodb::MongoManager;
 pub use postgres::PostgresManager;
 
 use crate::config::{Config, Instance, ServiceType};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 /// Database information
@@ -22,6 +26,25 @@ pub struct DatabaseInfo {
     pub tables: Option<u32>,
 }
 
+/// Privileges a user can be granted on one database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DbPrivileges {
+    /// Everything on the database (what an app needs to run migrations)
+    All,
+    /// Read the data only
+    ReadOnly,
+}
+
+/// A database user (account)
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseUser {
+    /// User name
+    pub name: String,
+    /// Privileges on the database the listing was for (if any)
+    pub privileges: Option<DbPrivileges>,
+}
+
 /// Trait for database management operations
 pub trait DatabaseManager {
     /// List all databases
@@ -42,11 +65,63 @@ pub trait DatabaseManager {
     /// Export database to SQL file
     fn export_sql(&self, database: &str, output_path: &Path) -> Result<(), String>;
 
+    /// File extension of the dumps `export_sql` writes
+    fn dump_extension(&self) -> &'static str {
+        "sql"
+    }
+
     /// Get the shell command to open interactive database shell
     fn get_shell_command(&self, database: Option<&str>) -> Vec<String>;
 
     /// Get connection info for display
     fn connection_info(&self) -> String;
+
+    /// List users, or only those with privileges on `database`
+    fn list_users(&self, _database: Option<&str>) -> Result<Vec<DatabaseUser>, String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+
+    /// Check if a user exists
+    fn user_exists(&self, _username: &str) -> Result<bool, String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+
+    /// Create a user with a password
+    fn create_user(&self, _username: &str, _password: &str) -> Result<(), String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+
+    /// Drop a user and everything granted to it
+    fn drop_user(&self, _username: &str) -> Result<(), String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+
+    /// Grant a user privileges on a database, replacing any it had there
+    fn grant_privileges(
+        &self,
+        _username: &str,
+        _database: &str,
+        _privileges: DbPrivileges,
+    ) -> Result<(), String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+
+    /// Revoke all of a user's privileges on a database
+    fn revoke_privileges(&self, _username: &str, _database: &str) -> Result<(), String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+
+    /// Change a user's password
+    fn set_user_password(&self, _username: &str, _password: &str) -> Result<(), String> {
+        Err(users_unsupported(&self.connection_info()))
+    }
+}
+
+fn users_unsupported(connection_info: &str) -> String {
+    format!(
+        "User management is only supported for MariaDB/MySQL ({})",
+        connection_info
+    )
 }
 
 /// Database type enum
@@ -54,18 +129,38 @@ pub trait DatabaseManager {
 pub enum DbType {
     MariaDB,
     PostgreSQL,
+    MongoDB,
+}
+
+impl DbType {
+    /// The service instances of this engine run
+    pub fn service_type(&self) -> ServiceType {
+        match self {
+            DbType::MariaDB => ServiceType::MariaDB,
+            DbType::PostgreSQL => ServiceType::PostgreSQL,
+            DbType::MongoDB => ServiceType::MongoDB,
+        }
+    }
+}
+
+/// Whether Burd manages the databases of a service's instances
+pub fn is_database_service(service_type: ServiceType) -> bool {
+    matches!(
+        service_type,
+        ServiceType::MariaDB | ServiceType::PostgreSQL | ServiceType::MongoDB
+    )
 }
 
 /// Find a database instance in Burd config
 pub fn find_db_instance(config: &Config, db_type: Option<DbType>) -> Option<&Instance> {
     config.instances.iter().find(|i| match db_type {
-        Some(DbType::MariaDB) => i.service_type == ServiceType::MariaDB,
-        Some(DbType::PostgreSQL) => i.service_type == ServiceType::PostgreSQL,
+        Some(db_type) => i.service_type == db_type.service_type(),
         None => i.service_type == ServiceType::MariaDB || i.service_type == ServiceType::PostgreSQL,
     })
 }
 
-/// Find all database instances in Burd config
+/// Find all SQL database instances (the ones projects get a database on) in
+/// Burd config
 pub fn find_all_db_instances(config: &Config) -> Vec<&Instance> {
     config
         .instances
@@ -76,6 +171,15 @@ pub fn find_all_db_instances(config: &Config) -> Vec<&Instance> {
         .collect()
 }
 
+/// Find every instance whose databases Burd manages, MongoDB included
+pub fn find_managed_db_instances(config: &Config) -> Vec<&Instance> {
+    config
+        .instances
+        .iter()
+        .filter(|i| is_database_service(i.service_type))
+        .collect()
+}
+
 /// Create a database manager for an instance
 pub fn create_manager_for_instance(
     instance: &Instance,
@@ -130,6 +234,11 @@ pub fn create_manager_for_instance(
                 password,
             )))
         }
+        ServiceType::MongoDB => Ok(Box::new(MongoManager::new(
+            "127.0.0.1".to_string(),
+            instance.port,
+            instance.version.clone(),
+        ))),
         _ => Err(format!(
             "Instance '{}' is not a database service",
             instance.name
@@ -165,3 +274,131 @@ pub fn sanitize_db_name(name: &str) -> Result<String, String> {
 
     Ok(sanitized)
 }
+
+/// Accounts Burd and the server rely on, which can't be managed as users
+const RESERVED_USERS: &[&str] = &["root", "mysql", "mariadb.sys", "postgres"];
+
+/// Validate a database user name
+///
+/// Unlike database names, user names are rejected rather than filtered, so a
+/// typo never creates or drops a different user.
+pub fn validate_db_user(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("User name cannot be empty".to_string());
+    }
+    if name.len() > 32 {
+        return Err("User name too long (max 32 characters)".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(
+            "User name may only contain letters, digits, underscores and hyphens".to_string(),
+        );
+    }
+    if RESERVED_USERS.contains(&name.to_ascii_lowercase().as_str()) {
+        return Err(format!("'{}' is a system user and can't be managed", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Validate a password for a database user
+pub fn validate_db_password(password: &str) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("Password cannot be empty".to_string());
+    }
+    if password.chars().any(|c| c.is_control()) {
+        return Err("Password cannot contain control characters".to_string());
+    }
+    Ok(())
+}
+
+/// Generate a random password for a new user
+pub fn generate_password() -> String {
+    use rand::distr::Alphanumeric;
+    use rand::Rng;
+
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(24)
+        .map(char::from)
+        .collect()
+}
+
+/// Quote a value as an SQL string literal
+pub fn quote_sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+
+    #[test]
+    fn test_find_managed_db_instances() {
+        let config = ConfigBuilder::new()
+            .instance(
+                InstanceBuilder::new()
+                    .name("docs")
+                    .service_type(ServiceType::MongoDB)
+                    .build(),
+            )
+            .instance(
+                InstanceBuilder::new()
+                    .name("cache")
+                    .service_type(ServiceType::Redis)
+                    .build(),
+            )
+            .instance(
+                InstanceBuilder::new()
+                    .name("db")
+                    .service_type(ServiceType::MariaDB)
+                    .build(),
+            )
+            .build();
+
+        let managed: Vec<&str> = find_managed_db_instances(&config)
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+        assert_eq!(managed, ["docs", "db"]);
+
+        // Projects only get databases on SQL instances
+        let sql: Vec<&str> = find_all_db_instances(&config)
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+        assert_eq!(sql, ["db"]);
+        assert_eq!(
+            find_db_instance(&config, Some(DbType::MongoDB)).map(|i| i.name.as_str()),
+            Some("docs")
+        );
+    }
+
+    #[test]
+    fn test_validate_db_user() {
+        assert_eq!(validate_db_user(" app_user ").unwrap(), "app_user");
+        assert!(validate_db_user("").is_err());
+        assert!(validate_db_user("app'; DROP USER root; --").is_err());
+        assert!(validate_db_user("Root").is_err());
+        assert!(validate_db_user(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_quote_sql_string() {
+        assert_eq!(quote_sql_string("secret"), "'secret'");
+        assert_eq!(quote_sql_string("it's"), "'it''s'");
+        assert_eq!(quote_sql_string("a\\'b"), "'a\\\\''b'");
+    }
+
+    #[test]
+    fn test_generate_password() {
+        let password = generate_password();
+        assert_eq!(password.len(), 24);
+        assert!(validate_db_password(&password).is_ok());
+        assert!(validate_db_password("").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! MongoDB Database Manager
+//!
+//! Provides database operations using mongosh and the MongoDB Database Tools
+//! (mongodump/mongorestore). Tools bundled with the instance's installed
+//! version are preferred over the ones on PATH.
+
+use super::{DatabaseInfo, DatabaseManager};
+use crate::config::{get_versioned_binary_dir, ServiceType};
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+/// Databases MongoDB keeps for itself
+const SYSTEM_DATABASES: [&str; 3] = ["admin", "config", "local"];
+
+/// MongoDB only keeps databases that hold a collection, so new ones get this
+/// empty one
+const PLACEHOLDER_COLLECTION: &str = "_burd";
+
+/// Prefer tools bundled with the installed version, then PATH
+pub fn find_mongo_tool(version: &str, tool: &str) -> String {
+    if let Ok(dir) = get_versioned_binary_dir(ServiceType::MongoDB, version) {
+        for candidate in [dir.join(tool), dir.join("bin").join(tool)] {
+            if candidate.exists() {
+                return candidate.to_string_lossy().to_string();
+            }
+        }
+    }
+    tool.to_string()
+}
+
+/// Where to get a missing tool
+fn install_hint(tool: &str) -> &'static str {
+    match tool {
+        "mongosh" => "Install the MongoDB Shell (brew install mongosh)",
+        _ => "Install the MongoDB Database Tools (brew install mongodb-database-tools)",
+    }
+}
+
+/// MongoDB database manager
+pub struct MongoManager {
+    host: String,
+    port: u16,
+    /// Installed MongoDB version, for finding bundled tools
+    version: String,
+}
+
+impl MongoManager {
+    /// Create a new MongoDB manager
+    pub fn new(host: String, port: u16, version: String) -> Self {
+        Self {
+            host,
+            port,
+            version,
+        }
+    }
+
+    fn uri(&self, database: Option<&str>) -> String {
+        format!(
+            "mongodb://{}:{}/{}",
+            self.host,
+            self.port,
+            database.unwrap_or("")
+        )
+    }
+
+    /// Run a tool and return its stdout
+    fn run_tool(&self, tool: &str, args: &[String]) -> Result<String, String> {
+        let output = Command::new(find_mongo_tool(&self.version, tool))
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to execute {}: {}. {}", tool, e, install_hint(tool)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{} failed: {}", tool, stderr.trim()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Evaluate JavaScript with mongosh and return what it printed
+    fn eval(&self, script: &str) -> Result<String, String> {
+        self.run_tool(
+            "mongosh",
+            &[
+                self.uri(Some("admin")),
+                "--quiet".to_string(),
+                "--eval".to_string(),
+                script.to_string(),
+            ],
+        )
+    }
+}
+
+impl DatabaseManager for MongoManager {
+    fn list_databases(&self) -> Result<Vec<DatabaseInfo>, String> {
+        let output = self.eval(
+            "print(JSON.stringify(db.adminCommand({ listDatabases: 1 }).databases.map(d => ({ \
+             name: d.name, size: d.sizeOnDisk, \
+             collections: db.getSiblingDB(d.name).getCollectionNames().length }))))",
+        )?;
+        let databases: Vec<serde_json::Value> = serde_json::from_str(output.trim())
+            .map_err(|e| format!("Unexpected mongosh output: {}", e))?;
+
+        Ok(databases
+            .iter()
+            .filter_map(|db| {
+                let name = db.get("name")?.as_str()?;
+                if SYSTEM_DATABASES.contains(&name) {
+                    return None;
+                }
+                Some(DatabaseInfo {
+                    name: name.to_string(),
+                    size: db.get("size").and_then(|s| s.as_f64()).map(|s| s as u64),
+                    tables: db
+                        .get("collections")
+                        .and_then(|c| c.as_u64())
+                        .map(|c| c as u32),
+                })
+            })
+            .collect())
+    }
+
+    fn create_database(&self, name: &str) -> Result<(), String> {
+        let sanitized = super::sanitize_db_name(name)?;
+
+        if self.database_exists(&sanitized)? {
+            return Ok(()); // Already exists
+        }
+
+        self.eval(&format!(
+            "db.getSiblingDB('{}').createCollection('{}')",
+            sanitized, PLACEHOLDER_COLLECTION
+        ))?;
+        Ok(())
+    }
+
+    fn drop_database(&self, name: &str) -> Result<(), String> {
+        let sanitized = super::sanitize_db_name(name)?;
+        self.eval(&format!("db.getSiblingDB('{}').dropDatabase()", sanitized))?;
+        Ok(())
+    }
+
+    fn database_exists(&self, name: &str) -> Result<bool, String> {
+        let sanitized = super::sanitize_db_name(name)?;
+        Ok(self.list_databases()?.iter().any(|db| db.name == sanitized))
+    }
+
+    /// Restore a `mongodump --archive` file (gzipped or not) or a dump
+    /// directory into `database`, whatever database it was dumped from
+    fn import_sql(&self, database: &str, sql_path: &Path) -> Result<(), String> {
+        let sanitized = super::sanitize_db_name(database)?;
+
+        if !sql_path.exists() {
+            return Err(format!("Dump not found: {}", sql_path.display()));
+        }
+
+        let mut args = vec![
+            format!("--uri={}", self.uri(None)),
+            "--nsFrom=$db$.$coll$".to_string(),
+            format!("--nsTo={}.$coll$", sanitized),
+        ];
+        if sql_path.is_dir() {
+            args.push(format!("--dir={}", sql_path.display()));
+        } else {
+            args.push(format!("--archive={}", sql_path.display()));
+            if is_gzipped(sql_path)? {
+                args.push("--gzip".to_string());
+            }
+        }
+
+        self.run_tool("mongorestore", &args)
+            .map(|_| ())
+            .map_err(|e| format!("Import failed: {}", e))
+    }
+
+    /// Dump `database` to a gzipped `mongodump` archive
+    fn export_sql(&self, database: &str, output_path: &Path) -> Result<(), String> {
+        let sanitized = super::sanitize_db_name(database)?;
+
+        self.run_tool(
+            "mongodump",
+            &[
+                format!("--uri={}", self.uri(None)),
+                format!("--db={}", sanitized),
+                format!("--archive={}", output_path.display()),
+                "--gzip".to_string(),
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| format!("Export failed: {}", e))
+    }
+
+    fn dump_extension(&self) -> &'static str {
+        "archive.gz"
+    }
+
+    fn get_shell_command(&self, database: Option<&str>) -> Vec<String> {
+        let database = database.and_then(|db| super::sanitize_db_name(db).ok());
+        vec![
+            find_mongo_tool(&self.version, "mongosh"),
+            self.uri(database.as_deref()),
+        ]
+    }
+
+    fn connection_info(&self) -> String {
+        format!("MongoDB at {}:{}", self.host, self.port)
+    }
+}
+
+/// Whether a file starts with the gzip magic bytes
+fn is_gzipped(path: &Path) -> Result<bool, String> {
+    let mut magic = [0u8; 2];
+    let read = std::fs::File::open(path)
+        .and_then(|mut file| file.read(&mut magic))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(read == 2 && magic == [0x1f, 0x8b])
+}
@@ -0,0 +1,294 @@
+//! Device setup bundle
+//!
+//! Other devices on the network can reach this Mac's sites through Caddy, but
+//! they have to trust the local root CA and resolve `.<tld>` domains to this
+//! Mac first. `write_bundle` puts everything they need in one folder: the CA
+//! certificate, an Apple configuration profile that installs it, hosts file
+//! entries and instructions. Caddy serves the folder to other devices at
+//! `http://<lan-ip>/burd-setup/`, and it is also reachable at
+//! `http://burd.<tld>/setup/` through the start page.
+
+use crate::config::Config;
+use crate::start_page;
+use serde::Serialize;
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// URL path Caddy serves the bundle under for requests by IP
+pub const SETUP_URL_PATH: &str = "/burd-setup/";
+
+const CA_FILE: &str = "burd-root-ca.crt";
+const PROFILE_FILE: &str = "burd.mobileconfig";
+const HOSTS_FILE: &str = "hosts.txt";
+const README_FILE: &str = "README.txt";
+
+/// A written device setup bundle
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceBundle {
+    pub dir: PathBuf,
+    /// This Mac's address on the local network, if it has one
+    pub lan_ip: Option<String>,
+    /// Where other devices open the bundle
+    pub url: String,
+    pub files: Vec<String>,
+}
+
+/// Directory the bundle is written to (inside the start page directory)
+pub fn get_setup_dir() -> PathBuf {
+    start_page::get_start_page_dir().join("setup")
+}
+
+/// This Mac's address on the local network
+///
+/// Connecting a UDP socket only picks the outgoing interface; nothing is sent.
+pub fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// Base64 body of a PEM certificate (the DER bytes, as a profile expects)
+fn pem_body(pem: &str) -> Option<String> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("-----BEGIN CERTIFICATE"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----END CERTIFICATE"))
+        .collect();
+    (!body.is_empty()).then_some(body)
+}
+
+/// Apple configuration profile installing the root CA (iOS, iPadOS, macOS)
+fn generate_profile(cert_base64: &str, tld: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>PayloadContent</key>
+    <array>
+        <dict>
+            <key>PayloadCertificateFileName</key>
+            <string>{ca_file}</string>
+            <key>PayloadContent</key>
+            <data>{cert}</data>
+            <key>PayloadDescription</key>
+            <string>Trusts HTTPS certificates for .{tld} sites served by Burd</string>
+            <key>PayloadDisplayName</key>
+            <string>Burd Local CA</string>
+            <key>PayloadIdentifier</key>
+            <string>com.burd.ca.{tld}.root</string>
+            <key>PayloadType</key>
+            <string>com.apple.security.root</string>
+            <key>PayloadUUID</key>
+            <string>{cert_uuid}</string>
+            <key>PayloadVersion</key>
+            <integer>1</integer>
+        </dict>
+    </array>
+    <key>PayloadDisplayName</key>
+    <string>Burd (.{tld} sites)</string>
+    <key>PayloadIdentifier</key>
+    <string>com.burd.ca.{tld}</string>
+    <key>PayloadRemovalDisallowed</key>
+    <false/>
+    <key>PayloadType</key>
+    <string>Configuration</string>
+    <key>PayloadUUID</key>
+    <string>{profile_uuid}</string>
+    <key>PayloadVersion</key>
+    <integer>1</integer>
+</dict>
+</plist>
+"#,
+        ca_file = CA_FILE,
+        cert = cert_base64,
+        tld = tld,
+        cert_uuid = Uuid::new_v4().to_string().to_uppercase(),
+        profile_uuid = Uuid::new_v4().to_string().to_uppercase(),
+    )
+}
+
+/// Every domain another device may want to open
+fn bundle_domains(config: &Config) -> Vec<String> {
+    let mut domains: Vec<String> = config
+        .domains
+        .iter()
+        .map(|d| d.full_domain(&config.tld))
+        .collect();
+    domains.push(start_page::start_page_domain(&config.tld));
+    domains.sort();
+    domains.dedup();
+    domains
+}
+
+fn hosts_entries(ip: &str, domains: &[String]) -> String {
+    domains
+        .iter()
+        .map(|domain| format!("{}\t{}\n", ip, domain))
+        .collect()
+}
+
+fn setup_instructions(ip: &str, tld: &str, url: &str) -> String {
+    format!(
+        "Burd device setup\n\
+         =================\n\
+         \n\
+         Sites on this Mac ({ip}) use .{tld} domains and a local certificate\n\
+         authority. To open them from another device on the same network:\n\
+         \n\
+         1. Trust the certificate\n\
+         \n\
+         iPhone / iPad: open {url}{profile} in Safari and allow the download.\n  \
+           Install it in Settings > General > VPN & Device Management, then turn\n  \
+           on full trust in Settings > General > About > Certificate Trust Settings.\n\
+         Mac: open {profile} and install it in System Settings > Privacy &\n  \
+           Security > Profiles.\n\
+         Android: Settings > Security > Encryption & credentials > Install a\n  \
+           certificate > CA certificate, then choose {ca}.\n\
+         Windows: open {ca} > Install Certificate > Local Machine > Trusted Root\n  \
+           Certification Authorities.\n\
+         Linux: copy {ca} to /usr/local/share/ca-certificates/ and run\n  \
+           sudo update-ca-certificates.\n\
+         \n\
+         2. Resolve .{tld} domains to this Mac\n\
+         \n\
+         Laptops and desktops: add the lines in {hosts} to /etc/hosts\n  \
+           (C:\\Windows\\System32\\drivers\\etc\\hosts on Windows).\n\
+         Phones and tablets can't edit their hosts file. Add the same entries as\n  \
+           local DNS records on your router instead.\n\
+         \n\
+         If this Mac's address changes, run `burd ca bundle` again.\n",
+        ip = ip,
+        tld = tld,
+        url = url,
+        profile = PROFILE_FILE,
+        ca = CA_FILE,
+        hosts = HOSTS_FILE,
+    )
+}
+
+fn generate_index(readme: &str, files: &[String]) -> String {
+    let links: String = files
+        .iter()
+        .map(|f| {
+            format!(
+                "<li><a href=\"{0}\">{0}</a></li>",
+                start_page::escape_html(f)
+            )
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>Burd device setup</title></head>\
+         <body style=\"font-family:-apple-system,sans-serif;max-width:720px;margin:0 auto;padding:24px\">\
+         <ul>{}</ul><pre style=\"white-space:pre-wrap\">{}</pre></body></html>\n",
+        links,
+        start_page::escape_html(readme)
+    )
+}
+
+fn write_file(dir: &Path, name: &str, contents: &str) -> Result<(), String> {
+    fs::write(dir.join(name), contents).map_err(|e| format!("Failed to write {}: {}", name, e))
+}
+
+/// Write the bundle to `dir` for devices reaching this Mac at `ip`
+fn write_bundle_to(
+    dir: &Path,
+    ca_pem: &str,
+    tld: &str,
+    domains: &[String],
+    ip: Option<&str>,
+) -> Result<DeviceBundle, String> {
+    let cert =
+        pem_body(ca_pem).ok_or_else(|| "The root CA is not a PEM certificate".to_string())?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let url = match ip {
+        Some(ip) => format!("http://{}{}", ip, SETUP_URL_PATH),
+        None => format!("http://{}/setup/", start_page::start_page_domain(tld)),
+    };
+    let ip_label = ip.unwrap_or("<this Mac's IP address>");
+
+    write_file(dir, CA_FILE, ca_pem)?;
+    write_file(dir, PROFILE_FILE, &generate_profile(&cert, tld))?;
+    write_file(dir, HOSTS_FILE, &hosts_entries(ip_label, domains))?;
+    let readme = setup_instructions(ip_label, tld, &url);
+    write_file(dir, README_FILE, &readme)?;
+
+    let files: Vec<String> = [CA_FILE, PROFILE_FILE, HOSTS_FILE, README_FILE]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+    write_file(dir, "index.html", &generate_index(&readme, &files))?;
+
+    Ok(DeviceBundle {
+        dir: dir.to_path_buf(),
+        lan_ip: ip.map(String::from),
+        url,
+        files,
+    })
+}
+
+/// Write the device setup bundle for the root CA at `ca_path`
+pub fn write_bundle(ca_path: &Path, config: &Config) -> Result<DeviceBundle, String> {
+    let ca_pem = fs::read_to_string(ca_path)
+        .map_err(|e| format!("Failed to read the root CA at {}: {}", ca_path.display(), e))?;
+    let ip = lan_ip().map(|ip| ip.to_string());
+    write_bundle_to(
+        &get_setup_dir(),
+        &ca_pem,
+        &config.tld,
+        &bundle_domains(config),
+        ip.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PEM: &str =
+        "-----BEGIN CERTIFICATE-----\nMIIBpDCC\nAUqgAwIB\n-----END CERTIFICATE-----\n";
+
+    #[test]
+    fn test_pem_body() {
+        assert_eq!(pem_body(PEM).as_deref(), Some("MIIBpDCCAUqgAwIB"));
+        assert!(pem_body("not a cert").is_none());
+    }
+
+    #[test]
+    fn test_profile_contains_certificate() {
+        let profile = generate_profile("MIIBpDCCAUqgAwIB", "burd");
+        assert!(profile.contains("<data>MIIBpDCCAUqgAwIB</data>"));
+        assert!(profile.contains("<string>com.apple.security.root</string>"));
+        assert!(profile.contains("<string>com.burd.ca.burd</string>"));
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let temp = TempDir::new().unwrap();
+        let domains = vec!["api.burd".to_string(), "burd.burd".to_string()];
+        let bundle =
+            write_bundle_to(temp.path(), PEM, "burd", &domains, Some("192.168.1.20")).unwrap();
+
+        assert_eq!(bundle.url, "http://192.168.1.20/burd-setup/");
+        assert_eq!(
+            fs::read_to_string(temp.path().join(HOSTS_FILE)).unwrap(),
+            "192.168.1.20\tapi.burd\n192.168.1.20\tburd.burd\n"
+        );
+        assert_eq!(fs::read_to_string(temp.path().join(CA_FILE)).unwrap(), PEM);
+        let readme = fs::read_to_string(temp.path().join(README_FILE)).unwrap();
+        assert!(readme.contains("http://192.168.1.20/burd-setup/burd.mobileconfig"));
+        assert!(temp.path().join("index.html").exists());
+
+        let bundle = write_bundle_to(temp.path(), PEM, "burd", &domains, None).unwrap();
+        assert_eq!(bundle.url, "http://burd.burd/setup/");
+    }
+}
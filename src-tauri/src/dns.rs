@@ -1,31 +1,253 @@
 //! DNS Server for resolving custom TLD domains to localhost
 //!
 //! This module provides a lightweight DNS server that resolves all queries
-//! for the configured TLD to 127.0.0.1, enabling custom local domain names.
+//! for the configured TLD to 127.0.0.1 (A) and ::1 (AAAA), enabling custom
+//! local domain names.
+//!
+//! Custom records from the config (A, AAAA or CNAME, e.g. `api` pointing at a
+//! LAN IP) replace that answer for their name. Names outside the TLD get
+//! NXDOMAIN; a name without data for the queried type gets an empty answer
+//! with the zone's SOA, so resolvers cache the negative result briefly.
+//!
+//! Emulated hosts (see [`crate::emulation`]) and their subdomains also
+//! resolve to localhost, whatever their TLD.
+//!
+//! For debugging a name that doesn't resolve, the server can keep the last
+//! queries it answered (name, type, response and answers) in a ring buffer.
+//! The query log is off until turned on and isn't persisted.
 
 use crate::domain::DEFAULT_DNS_PORT;
+use chrono::{DateTime, Utc};
 use hickory_proto::op::{MessageType, OpCode, ResponseCode};
-use hickory_proto::rr::{DNSClass, RData, Record, RecordType};
+use hickory_proto::rr::rdata::{A, AAAA, CNAME, SOA};
+use hickory_proto::rr::{DNSClass, Name, RData, Record, RecordType};
 use hickory_proto::serialize::binary::{BinDecodable, BinEncodable};
-use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
+use uuid::Uuid;
+
+/// TTL of answers
+const ANSWER_TTL: u32 = 300;
+
+/// TTL of the SOA, and so of cached negative answers
+const NEGATIVE_TTL: u32 = 60;
+
+/// CNAMEs followed within the TLD before giving up
+const MAX_CNAME_CHAIN: usize = 8;
+
+/// Queries kept in the query log, the oldest dropped first
+const QUERY_LOG_CAPACITY: usize = 500;
+
+/// Record types that can be added to the TLD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+    A,
+    Aaaa,
+    Cname,
+}
+
+impl DnsRecordType {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "A" => Ok(DnsRecordType::A),
+            "AAAA" => Ok(DnsRecordType::Aaaa),
+            "CNAME" => Ok(DnsRecordType::Cname),
+            other => Err(format!(
+                "Unsupported record type '{}'. Use A, AAAA or CNAME",
+                other
+            )),
+        }
+    }
+
+    fn record_type(&self) -> RecordType {
+        match self {
+            DnsRecordType::A => RecordType::A,
+            DnsRecordType::Aaaa => RecordType::AAAA,
+            DnsRecordType::Cname => RecordType::CNAME,
+        }
+    }
+}
+
+/// A custom record under the TLD
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsRecord {
+    pub id: Uuid,
+    /// Name below the TLD, e.g. "api" for api.burd
+    pub name: String,
+    pub record_type: DnsRecordType,
+    /// IPv4 address, IPv6 address, or CNAME target host
+    pub value: String,
+}
+
+/// Whether a host name is made of valid labels
+fn is_valid_host(name: &str) -> bool {
+    name.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+impl DnsRecord {
+    /// Validate a new record, normalizing its name and value
+    ///
+    /// The name may be given with or without the TLD. A CNAME target without
+    /// a dot is taken to be under the TLD.
+    pub fn new(
+        name: &str,
+        record_type: DnsRecordType,
+        value: &str,
+        tld: &str,
+    ) -> Result<Self, String> {
+        let name = name.trim().trim_end_matches('.').to_ascii_lowercase();
+        let name = name
+            .strip_suffix(&format!(".{}", tld))
+            .unwrap_or(&name)
+            .to_string();
+        if name.is_empty() || name == tld || !is_valid_host(&name) {
+            return Err(format!("Invalid record name '{}'", name));
+        }
+
+        let value = value.trim();
+        let value = match record_type {
+            DnsRecordType::A => value
+                .parse::<Ipv4Addr>()
+                .map_err(|_| format!("'{}' is not an IPv4 address", value))?
+                .to_string(),
+            DnsRecordType::Aaaa => value
+                .parse::<Ipv6Addr>()
+                .map_err(|_| format!("'{}' is not an IPv6 address", value))?
+                .to_string(),
+            DnsRecordType::Cname => {
+                let target = value.trim_end_matches('.').to_ascii_lowercase();
+                if !is_valid_host(&target) {
+                    return Err(format!("Invalid CNAME target '{}'", value));
+                }
+                let target = if target.contains('.') {
+                    target
+                } else {
+                    format!("{}.{}", target, tld)
+                };
+                if target == format!("{}.{}", name, tld) {
+                    return Err(format!("'{}' can't be an alias of itself", name));
+                }
+                target
+            }
+        };
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            name,
+            record_type,
+            value,
+        })
+    }
+
+    /// Check that this record can be added next to `existing`
+    ///
+    /// A name with a CNAME can't have other records.
+    pub fn check_conflicts(&self, existing: &[DnsRecord]) -> Result<(), String> {
+        for other in existing.iter().filter(|r| r.name == self.name) {
+            if other.record_type == self.record_type && other.value == self.value {
+                return Err(format!(
+                    "{} already has this {} record",
+                    self.name,
+                    self.record_type.record_type()
+                ));
+            }
+            if other.record_type == DnsRecordType::Cname || self.record_type == DnsRecordType::Cname
+            {
+                return Err(format!(
+                    "{} already has records; a CNAME can't share its name with others",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn rdata(&self) -> Option<RData> {
+        match self.record_type {
+            DnsRecordType::A => self.value.parse().ok().map(|ip| RData::A(A(ip))),
+            DnsRecordType::Aaaa => self.value.parse().ok().map(|ip| RData::AAAA(AAAA(ip))),
+            DnsRecordType::Cname => Name::from_ascii(format!("{}.", self.value))
+                .ok()
+                .map(|target| RData::CNAME(CNAME(target))),
+        }
+    }
+}
+
+/// A query answered while the query log was on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DnsQuery {
+    pub at: DateTime<Utc>,
+    /// Queried name, without the trailing dot
+    pub name: String,
+    /// Record type, e.g. "A" or "AAAA"
+    pub query_type: String,
+    /// "NOERROR", "NODATA" (the name exists but has no records of this type),
+    /// "NXDOMAIN" (not in one of our TLDs) or another response code
+    pub response: String,
+    /// Answer records, e.g. "A 127.0.0.1"
+    pub answers: Vec<String>,
+}
+
+/// Recent queries, recorded only while enabled
+#[derive(Default)]
+struct QueryLog {
+    enabled: AtomicBool,
+    queries: Mutex<VecDeque<DnsQuery>>,
+}
+
+impl QueryLog {
+    /// Record the queries a response answered, if the log is on
+    fn record(&self, response: &[u8]) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let Ok(mut queries) = self.queries.lock() else {
+            return;
+        };
+        for query in logged_queries(response, Utc::now()) {
+            if queries.len() >= QUERY_LOG_CAPACITY {
+                queries.pop_front();
+            }
+            queries.push_back(query);
+        }
+    }
+}
 
 /// DNS Server state
 pub struct DnsServer {
     port: u16,
-    tld: String,
+    /// Primary TLD first, then the extra ones
+    tlds: Vec<String>,
+    records: Arc<RwLock<Vec<DnsRecord>>>,
+    /// Hosts outside our TLDs answered with localhost
+    emulated_hosts: Arc<RwLock<Vec<String>>>,
+    query_log: Arc<QueryLog>,
     running: Arc<AtomicBool>,
     handle: Option<JoinHandle<()>>,
 }
 
 impl DnsServer {
-    pub fn new(port: u16, tld: String) -> Self {
+    pub fn new(port: u16, tlds: Vec<String>) -> Self {
         Self {
             port,
-            tld,
+            tlds,
+            records: Arc::new(RwLock::new(Vec::new())),
+            emulated_hosts: Arc::new(RwLock::new(Vec::new())),
+            query_log: Arc::new(QueryLog::default()),
             running: Arc::new(AtomicBool::new(false)),
             handle: None,
         }
@@ -48,7 +270,10 @@ impl DnsServer {
 
         self.running.store(true, Ordering::SeqCst);
         let running = Arc::clone(&self.running);
-        let tld = self.tld.clone();
+        let tlds = self.tlds.clone();
+        let records = Arc::clone(&self.records);
+        let emulated_hosts = Arc::clone(&self.emulated_hosts);
+        let query_log = Arc::clone(&self.query_log);
 
         let handle = thread::spawn(move || {
             let mut buf = [0u8; 512];
@@ -56,8 +281,15 @@ impl DnsServer {
             while running.load(Ordering::SeqCst) {
                 match socket.recv_from(&mut buf) {
                     Ok((len, src)) => {
-                        if let Some(response) = handle_dns_query(&buf[..len], &tld) {
+                        let response = match (records.read(), emulated_hosts.read()) {
+                            (Ok(records), Ok(hosts)) => {
+                                handle_dns_query(&buf[..len], &tlds, &records, &hosts)
+                            }
+                            _ => None,
+                        };
+                        if let Some(response) = response {
                             let _ = socket.send_to(&response, src);
+                            query_log.record(&response);
                         }
                     }
                     Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
@@ -73,10 +305,70 @@ impl DnsServer {
         Ok(())
     }
 
-    /// Get the TLD this server is configured for
+    /// Get the primary TLD this server is configured for
     #[allow(dead_code)]
     pub fn tld(&self) -> &str {
-        &self.tld
+        self.tlds.first().map(String::as_str).unwrap_or_default()
+    }
+
+    /// Get every TLD this server answers for
+    #[allow(dead_code)]
+    pub fn tlds(&self) -> &[String] {
+        &self.tlds
+    }
+
+    /// Answer for a new set of TLDs, restarting the server if it's running
+    pub fn set_tlds(&mut self, tlds: Vec<String>) -> Result<(), String> {
+        let was_running = self.is_running();
+        self.stop();
+        self.tlds = tlds;
+        if was_running {
+            self.start()?;
+        }
+        Ok(())
+    }
+
+    /// Answer with these custom records from now on
+    pub fn set_records(&self, records: Vec<DnsRecord>) {
+        if let Ok(mut current) = self.records.write() {
+            *current = records;
+        }
+    }
+
+    /// Answer these hosts outside our TLDs with localhost from now on
+    pub fn set_emulated_hosts(&self, hosts: Vec<String>) {
+        if let Ok(mut current) = self.emulated_hosts.write() {
+            *current = hosts;
+        }
+    }
+
+    /// Turn the query log on or off; turning it on starts an empty log
+    pub fn set_query_log(&self, enabled: bool) {
+        if enabled && !self.query_log_enabled() {
+            self.clear_queries();
+        }
+        self.query_log.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether queries are being logged
+    pub fn query_log_enabled(&self) -> bool {
+        self.query_log.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Logged queries, newest first
+    pub fn recent_queries(&self) -> Vec<DnsQuery> {
+        self.query_log
+            .queries
+            .lock()
+            .map(|queries| queries.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget the logged queries
+    pub fn clear_queries(&self) {
+        if let Ok(mut queries) = self.query_log.queries.lock() {
+            queries.clear();
+        }
     }
 
     /// Stop the DNS server
@@ -101,7 +393,10 @@ impl DnsServer {
 
 impl Default for DnsServer {
     fn default() -> Self {
-        Self::new(DEFAULT_DNS_PORT, crate::domain::DEFAULT_TLD.to_string())
+        Self::new(
+            DEFAULT_DNS_PORT,
+            vec![crate::domain::DEFAULT_TLD.to_string()],
+        )
     }
 }
 
@@ -111,8 +406,130 @@ impl Drop for DnsServer {
     }
 }
 
+/// The part of `name` below `tld` ("" for the TLD itself), if it's in the TLD
+fn relative_name(name: &Name, tld: &str) -> Option<String> {
+    let name = name.to_string().to_lowercase();
+    let name = name.trim_end_matches('.');
+    if name == tld {
+        return Some(String::new());
+    }
+    name.strip_suffix(&format!(".{}", tld)).map(str::to_string)
+}
+
+/// The TLD `name` falls under and its part below it
+///
+/// The longest TLD wins, so `app.dev.test` is in `dev.test` when both `test`
+/// and `dev.test` are configured.
+fn locate<'a>(name: &Name, tlds: &'a [String]) -> Option<(&'a str, String)> {
+    tlds.iter()
+        .filter_map(|tld| relative_name(name, tld).map(|relative| (tld.as_str(), relative)))
+        .max_by_key(|(tld, _)| tld.len())
+}
+
+fn record(name: &Name, ttl: u32, data: RData) -> Record {
+    let mut record = Record::from_rdata(name.clone(), ttl, data);
+    record.set_dns_class(DNSClass::IN);
+    record
+}
+
+/// Whether `name` is one of the emulated hosts or below one
+fn is_emulated(name: &Name, hosts: &[String]) -> bool {
+    let name = name.to_string().to_lowercase();
+    let name = name.trim_end_matches('.');
+    hosts
+        .iter()
+        .any(|host| name == host || name.ends_with(&format!(".{}", host)))
+}
+
+/// Localhost over both IPv4 and IPv6
+fn localhost_answers(name: &Name, query_type: RecordType) -> Vec<Record> {
+    let data = match query_type {
+        RecordType::A => RData::A(A(Ipv4Addr::LOCALHOST)),
+        RecordType::AAAA => RData::AAAA(AAAA(Ipv6Addr::LOCALHOST)),
+        _ => return Vec::new(),
+    };
+    vec![record(name, ANSWER_TTL, data)]
+}
+
+/// The TLD's SOA record, sent with negative answers
+fn soa_record(tld: &str) -> Option<Record> {
+    let zone = Name::from_ascii(format!("{}.", tld)).ok()?;
+    let soa = SOA::new(
+        Name::from_ascii(format!("ns.{}.", tld)).ok()?,
+        Name::from_ascii(format!("hostmaster.{}.", tld)).ok()?,
+        1,
+        3600,
+        600,
+        86400,
+        NEGATIVE_TTL,
+    );
+    Some(record(&zone, NEGATIVE_TTL, RData::SOA(soa)))
+}
+
+/// Answers for a name in `tld`, following CNAMEs that stay in our TLDs
+///
+/// Custom records apply under every TLD.
+fn answers(
+    name: &Name,
+    relative: &str,
+    query_type: RecordType,
+    tld: &str,
+    tlds: &[String],
+    records: &[DnsRecord],
+    chain: usize,
+) -> Vec<Record> {
+    if relative.is_empty() && query_type == RecordType::SOA {
+        return soa_record(tld).into_iter().collect();
+    }
+
+    let custom: Vec<&DnsRecord> = records.iter().filter(|r| r.name == relative).collect();
+    if custom.is_empty() {
+        return localhost_answers(name, query_type);
+    }
+
+    if let Some(alias) = custom
+        .iter()
+        .find(|r| r.record_type == DnsRecordType::Cname)
+    {
+        let Ok(target) = Name::from_ascii(format!("{}.", alias.value)) else {
+            return Vec::new();
+        };
+        let mut result = vec![record(
+            name,
+            ANSWER_TTL,
+            RData::CNAME(CNAME(target.clone())),
+        )];
+        if query_type != RecordType::CNAME && chain < MAX_CNAME_CHAIN {
+            if let Some((target_tld, target_relative)) = locate(&target, tlds) {
+                result.extend(answers(
+                    &target,
+                    &target_relative,
+                    query_type,
+                    target_tld,
+                    tlds,
+                    records,
+                    chain + 1,
+                ));
+            }
+        }
+        return result;
+    }
+
+    custom
+        .iter()
+        .filter(|r| r.record_type.record_type() == query_type)
+        .filter_map(|r| r.rdata())
+        .map(|data| record(name, ANSWER_TTL, data))
+        .collect()
+}
+
 /// Handle a DNS query and return a response
-fn handle_dns_query(query_data: &[u8], tld: &str) -> Option<Vec<u8>> {
+fn handle_dns_query(
+    query_data: &[u8],
+    tlds: &[String],
+    records: &[DnsRecord],
+    emulated_hosts: &[String],
+) -> Option<Vec<u8>> {
     use hickory_proto::op::Message;
 
     // Parse the incoming query
@@ -138,27 +555,33 @@ fn handle_dns_query(query_data: &[u8], tld: &str) -> Option<Vec<u8>> {
         response.add_query(query_record.clone());
 
         let name = query_record.name();
-        let name_str = name.to_string().to_lowercase();
-
-        // Check if this is a query for our TLD
-        let tld_suffix = format!(".{}.", tld);
-        let is_our_tld = name_str.ends_with(&tld_suffix) || name_str == format!("{}.", tld);
-
-        if is_our_tld && query_record.query_type() == RecordType::A {
-            // Create A record pointing to localhost
-            let mut record = Record::new();
-            record.set_name(name.clone());
-            record.set_rr_type(RecordType::A);
-            record.set_dns_class(DNSClass::IN);
-            record.set_ttl(300); // 5 minute TTL
-            record.set_data(Some(RData::A(hickory_proto::rr::rdata::A(Ipv4Addr::new(
-                127, 0, 0, 1,
-            )))));
-
-            response.add_answer(record);
-        } else if !is_our_tld {
-            // Not our TLD, return NXDOMAIN
+        if is_emulated(name, emulated_hosts) {
+            response.add_answers(localhost_answers(name, query_record.query_type()));
+            continue;
+        }
+
+        let Some((tld, relative)) = locate(name, tlds) else {
+            // Not one of our TLDs, return NXDOMAIN
             response.set_response_code(ResponseCode::NXDomain);
+            continue;
+        };
+
+        let found = answers(
+            name,
+            &relative,
+            query_record.query_type(),
+            tld,
+            tlds,
+            records,
+            0,
+        );
+        if found.is_empty() {
+            // The name exists but has no data of this type
+            if let Some(soa) = soa_record(tld) {
+                response.add_name_server(soa);
+            }
+        } else {
+            response.add_answers(found);
         }
     }
 
@@ -166,13 +589,52 @@ fn handle_dns_query(query_data: &[u8], tld: &str) -> Option<Vec<u8>> {
     response.to_bytes().ok()
 }
 
+/// The query log entries for an encoded response, one per question
+fn logged_queries(response: &[u8], at: DateTime<Utc>) -> Vec<DnsQuery> {
+    use hickory_proto::op::Message;
+
+    let Ok(message) = Message::from_bytes(response) else {
+        return Vec::new();
+    };
+    let answers: Vec<String> = message
+        .answers()
+        .iter()
+        .filter_map(|record| {
+            let data = record.data()?;
+            Some(format!("{} {}", record.record_type(), data))
+        })
+        .collect();
+    let response = match message.response_code() {
+        ResponseCode::NoError if answers.is_empty() => "NODATA".to_string(),
+        ResponseCode::NoError => "NOERROR".to_string(),
+        ResponseCode::NXDomain => "NXDOMAIN".to_string(),
+        code => format!("{:?}", code).to_uppercase(),
+    };
+
+    message
+        .queries()
+        .iter()
+        .map(|query| DnsQuery {
+            at,
+            name: query
+                .name()
+                .to_string()
+                .trim_end_matches('.')
+                .to_lowercase(),
+            query_type: query.query_type().to_string(),
+            response: response.clone(),
+            answers: answers.clone(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_dns_server_lifecycle() {
-        let mut server = DnsServer::new(15354, "test".to_string()); // Use high port for testing
+        let mut server = DnsServer::new(15354, vec!["test".to_string()]); // Use high port for testing
 
         assert!(!server.is_running());
 
@@ -183,4 +645,274 @@ mod tests {
             assert!(!server.is_running());
         }
     }
+
+    fn respond_with(
+        tlds: &[&str],
+        name: &str,
+        record_type: RecordType,
+        records: &[DnsRecord],
+        emulated_hosts: &[String],
+    ) -> hickory_proto::op::Message {
+        use hickory_proto::op::{Message, Query};
+
+        let tlds: Vec<String> = tlds.iter().map(|t| t.to_string()).collect();
+        let mut message = Message::new();
+        message.set_id(1);
+        message.add_query(Query::query(Name::from_ascii(name).unwrap(), record_type));
+        let response =
+            handle_dns_query(&message.to_bytes().unwrap(), &tlds, records, emulated_hosts).unwrap();
+        Message::from_bytes(&response).unwrap()
+    }
+
+    fn respond_in(
+        tlds: &[&str],
+        name: &str,
+        record_type: RecordType,
+        records: &[DnsRecord],
+    ) -> hickory_proto::op::Message {
+        respond_with(tlds, name, record_type, records, &[])
+    }
+
+    fn respond(
+        name: &str,
+        record_type: RecordType,
+        records: &[DnsRecord],
+    ) -> hickory_proto::op::Message {
+        respond_in(&["test"], name, record_type, records)
+    }
+
+    fn query_with(name: &str, record_type: RecordType, records: &[DnsRecord]) -> Vec<RData> {
+        respond(name, record_type, records)
+            .answers()
+            .iter()
+            .filter_map(|record| record.data().cloned())
+            .collect()
+    }
+
+    fn query(name: &str, record_type: RecordType) -> Vec<RData> {
+        query_with(name, record_type, &[])
+    }
+
+    fn custom(name: &str, record_type: DnsRecordType, value: &str) -> DnsRecord {
+        DnsRecord::new(name, record_type, value, "test").unwrap()
+    }
+
+    #[test]
+    fn test_resolves_to_loopback_on_both_stacks() {
+        assert_eq!(
+            query("shop.test.", RecordType::A),
+            vec![RData::A(hickory_proto::rr::rdata::A(Ipv4Addr::LOCALHOST))]
+        );
+        assert_eq!(
+            query("shop.test.", RecordType::AAAA),
+            vec![RData::AAAA(hickory_proto::rr::rdata::AAAA(
+                Ipv6Addr::LOCALHOST
+            ))]
+        );
+        assert!(query("shop.test.", RecordType::MX).is_empty());
+        assert!(query("example.com.", RecordType::AAAA).is_empty());
+    }
+
+    #[test]
+    fn test_negative_answers_carry_the_soa() {
+        let nodata = respond("shop.test.", RecordType::MX, &[]);
+        assert_eq!(nodata.response_code(), ResponseCode::NoError);
+        assert!(nodata.answers().is_empty());
+        assert_eq!(nodata.name_servers().len(), 1);
+        assert_eq!(nodata.name_servers()[0].record_type(), RecordType::SOA);
+        assert_eq!(nodata.name_servers()[0].ttl(), NEGATIVE_TTL);
+
+        let nxdomain = respond("example.com.", RecordType::A, &[]);
+        assert_eq!(nxdomain.response_code(), ResponseCode::NXDomain);
+        assert!(nxdomain.name_servers().is_empty());
+
+        let apex = respond("test.", RecordType::SOA, &[]);
+        assert_eq!(apex.answers().len(), 1);
+    }
+
+    #[test]
+    fn test_custom_records() {
+        let records = vec![
+            custom("api.test", DnsRecordType::A, "192.168.1.20"),
+            custom("docs", DnsRecordType::Cname, "api"),
+            custom("cdn", DnsRecordType::Cname, "cdn.example.com."),
+        ];
+
+        let lan = RData::A(A("192.168.1.20".parse().unwrap()));
+        assert_eq!(
+            query_with("API.test.", RecordType::A, &records),
+            vec![lan.clone()]
+        );
+        // A name with records no longer points at localhost
+        let aaaa = respond("api.test.", RecordType::AAAA, &records);
+        assert!(aaaa.answers().is_empty());
+        assert_eq!(aaaa.name_servers().len(), 1);
+
+        // CNAMEs within the TLD are followed
+        let api = Name::from_ascii("api.test.").unwrap();
+        assert_eq!(
+            query_with("docs.test.", RecordType::A, &records),
+            vec![RData::CNAME(CNAME(api.clone())), lan]
+        );
+        assert_eq!(
+            query_with("docs.test.", RecordType::CNAME, &records),
+            vec![RData::CNAME(CNAME(api))]
+        );
+        assert_eq!(
+            query_with("cdn.test.", RecordType::A, &records),
+            vec![RData::CNAME(CNAME(
+                Name::from_ascii("cdn.example.com.").unwrap()
+            ))]
+        );
+
+        // Other names still resolve to localhost
+        assert_eq!(
+            query_with("shop.test.", RecordType::A, &records),
+            vec![RData::A(A(Ipv4Addr::LOCALHOST))]
+        );
+    }
+
+    #[test]
+    fn test_answers_for_every_tld() {
+        let tlds = ["test", "burd", "dev.test"];
+        let records = vec![custom("api", DnsRecordType::A, "192.168.1.20")];
+
+        for name in ["shop.test.", "shop.burd.", "shop.dev.test."] {
+            let response = respond_in(&tlds, name, RecordType::A, &[]);
+            assert_eq!(response.response_code(), ResponseCode::NoError);
+            assert_eq!(response.answers().len(), 1);
+        }
+        assert_eq!(
+            respond_in(&tlds, "shop.localhost.", RecordType::A, &[]).response_code(),
+            ResponseCode::NXDomain
+        );
+
+        // Custom records apply under every TLD
+        let lan = RData::A(A("192.168.1.20".parse().unwrap()));
+        let answer = respond_in(&tlds, "api.burd.", RecordType::A, &records);
+        assert_eq!(answer.answers()[0].data(), Some(&lan));
+
+        // The longest TLD wins, so this is `api` in `dev.test`
+        let answer = respond_in(&tlds, "api.dev.test.", RecordType::A, &records);
+        assert_eq!(answer.answers()[0].data(), Some(&lan));
+
+        // Negative answers carry the SOA of the TLD that matched
+        let nodata = respond_in(&tlds, "shop.burd.", RecordType::MX, &[]);
+        assert_eq!(
+            nodata.name_servers()[0].name(),
+            &Name::from_ascii("burd.").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_emulated_hosts_resolve_to_loopback() {
+        let hosts = vec!["s3.amazonaws.com".to_string()];
+
+        for name in ["s3.amazonaws.com.", "bucket.S3.amazonaws.com."] {
+            let response = respond_with(&["test"], name, RecordType::AAAA, &[], &hosts);
+            assert_eq!(response.response_code(), ResponseCode::NoError);
+            assert_eq!(
+                response.answers()[0].data(),
+                Some(&RData::AAAA(AAAA(Ipv6Addr::LOCALHOST)))
+            );
+        }
+
+        let other = respond_with(&["test"], "sqs.amazonaws.com.", RecordType::A, &[], &hosts);
+        assert_eq!(other.response_code(), ResponseCode::NXDomain);
+    }
+
+    #[test]
+    fn test_cname_loops_end() {
+        let records = vec![
+            custom("a", DnsRecordType::Cname, "b"),
+            custom("b", DnsRecordType::Cname, "a"),
+        ];
+        assert_eq!(
+            query_with("a.test.", RecordType::A, &records).len(),
+            MAX_CNAME_CHAIN + 1
+        );
+    }
+
+    fn encoded(name: &str, record_type: RecordType, records: &[DnsRecord]) -> Vec<u8> {
+        respond(name, record_type, records).to_bytes().unwrap()
+    }
+
+    #[test]
+    fn test_logged_queries() {
+        let at = Utc::now();
+        let records = vec![custom("docs", DnsRecordType::Cname, "shop")];
+
+        let logged = logged_queries(&encoded("Docs.test.", RecordType::A, &records), at);
+        assert_eq!(
+            logged,
+            vec![DnsQuery {
+                at,
+                name: "docs.test".to_string(),
+                query_type: "A".to_string(),
+                response: "NOERROR".to_string(),
+                answers: vec!["CNAME shop.test.".to_string(), "A 127.0.0.1".to_string()],
+            }]
+        );
+
+        let nodata = logged_queries(&encoded("shop.test.", RecordType::MX, &[]), at);
+        assert_eq!(nodata[0].response, "NODATA");
+        assert!(nodata[0].answers.is_empty());
+
+        let nxdomain = logged_queries(&encoded("shop.burd.", RecordType::AAAA, &[]), at);
+        assert_eq!(nxdomain[0].response, "NXDOMAIN");
+        assert_eq!(nxdomain[0].query_type, "AAAA");
+    }
+
+    #[test]
+    fn test_query_log_is_opt_in_and_bounded() {
+        let server = DnsServer::new(0, vec!["test".to_string()]);
+        let response = encoded("shop.test.", RecordType::A, &[]);
+
+        server.query_log.record(&response);
+        assert!(server.recent_queries().is_empty());
+
+        server.set_query_log(true);
+        for _ in 0..QUERY_LOG_CAPACITY + 10 {
+            server.query_log.record(&response);
+        }
+        server
+            .query_log
+            .record(&encoded("last.test.", RecordType::A, &[]));
+        let queries = server.recent_queries();
+        assert_eq!(queries.len(), QUERY_LOG_CAPACITY);
+        assert_eq!(queries[0].name, "last.test");
+
+        // Turning it off keeps what was logged, turning it on again starts over
+        server.set_query_log(false);
+        server.query_log.record(&response);
+        assert_eq!(server.recent_queries().len(), QUERY_LOG_CAPACITY);
+        server.set_query_log(true);
+        assert!(server.recent_queries().is_empty());
+    }
+
+    #[test]
+    fn test_record_validation() {
+        let record = custom("API.test.", DnsRecordType::Cname, "Shop");
+        assert_eq!(record.name, "api");
+        assert_eq!(record.value, "shop.test");
+
+        assert!(DnsRecord::new("api", DnsRecordType::A, "::1", "test").is_err());
+        assert!(DnsRecord::new("api", DnsRecordType::Aaaa, "10.0.0.1", "test").is_err());
+        assert!(DnsRecord::new("bad name", DnsRecordType::A, "10.0.0.1", "test").is_err());
+        assert!(DnsRecord::new("test", DnsRecordType::A, "10.0.0.1", "test").is_err());
+        assert!(DnsRecord::new("api", DnsRecordType::Cname, "api", "test").is_err());
+        assert_eq!(DnsRecordType::parse("aaaa"), Ok(DnsRecordType::Aaaa));
+        assert!(DnsRecordType::parse("MX").is_err());
+
+        let existing = vec![custom("api", DnsRecordType::A, "10.0.0.1")];
+        assert!(custom("api", DnsRecordType::A, "10.0.0.2")
+            .check_conflicts(&existing)
+            .is_ok());
+        assert!(custom("api", DnsRecordType::A, "10.0.0.1")
+            .check_conflicts(&existing)
+            .is_err());
+        assert!(custom("api", DnsRecordType::Cname, "shop")
+            .check_conflicts(&existing)
+            .is_err());
+    }
 }
@@ -0,0 +1,409 @@
+//! DNS self-test
+//!
+//! Walks the chain a `.<tld>` lookup takes on macOS and reports the first
+//! hop that is broken:
+//!
+//! 1. `/etc/resolver/<tld>` exists and points at 127.0.0.1 on the DNS port
+//! 2. Something is listening on the DNS port
+//! 3. The local DNS server answers a query for the test domain with 127.0.0.1
+//! 4. The system resolver returns 127.0.0.1 for the test domain
+
+use crate::config::Config;
+use crate::platform;
+use hickory_proto::op::{Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_proto::rr::{Name, RData, RecordType};
+use hickory_proto::serialize::binary::{BinDecodable, BinEncodable};
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// How long to wait for the local DNS server to answer
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A hop in the resolution chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsHop {
+    ResolverFile,
+    Listener,
+    LocalQuery,
+    SystemResolver,
+}
+
+impl DnsHop {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DnsHop::ResolverFile => "Resolver file",
+            DnsHop::Listener => "DNS port",
+            DnsHop::LocalQuery => "Local DNS server",
+            DnsHop::SystemResolver => "System resolver",
+        }
+    }
+}
+
+/// Outcome of a single hop check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skipped,
+}
+
+/// Result of checking one hop
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsCheck {
+    pub hop: DnsHop,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl DnsCheck {
+    fn pass(hop: DnsHop, detail: String) -> Self {
+        Self {
+            hop,
+            status: CheckStatus::Pass,
+            detail,
+            hint: None,
+        }
+    }
+
+    fn fail(hop: DnsHop, detail: String, hint: impl Into<String>) -> Self {
+        Self {
+            hop,
+            status: CheckStatus::Fail,
+            detail,
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skipped(hop: DnsHop, detail: &str) -> Self {
+        Self {
+            hop,
+            status: CheckStatus::Skipped,
+            detail: detail.to_string(),
+            hint: None,
+        }
+    }
+}
+
+/// Full self-test report
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsSelfTest {
+    pub tld: String,
+    pub dns_port: u16,
+    pub domain: String,
+    pub checks: Vec<DnsCheck>,
+    /// First hop that failed, if any
+    pub broken_hop: Option<DnsHop>,
+}
+
+impl DnsSelfTest {
+    /// Whether no hop failed
+    pub fn passed(&self) -> bool {
+        self.broken_hop.is_none()
+    }
+}
+
+/// Pick the domain to test: the first configured domain, or `burd.<tld>`
+pub fn default_test_domain(config: &Config) -> String {
+    config
+        .domains
+        .first()
+        .map(|d| d.full_domain(&config.tld))
+        .unwrap_or_else(|| format!("burd.{}", config.tld))
+}
+
+/// Run every hop check for `domain` and report the first broken one
+pub fn run_self_test(tld: &str, dns_port: u16, domain: &str) -> DnsSelfTest {
+    let mut checks = vec![check_resolver_file(tld, dns_port)];
+
+    let listener = check_listener(dns_port);
+    let listening = listener.status == CheckStatus::Pass;
+    checks.push(listener);
+
+    if listening {
+        checks.push(check_local_query(dns_port, tld, domain));
+    } else {
+        checks.push(DnsCheck::skipped(
+            DnsHop::LocalQuery,
+            "Nothing is listening on the DNS port",
+        ));
+    }
+
+    checks.push(check_system_resolver(domain));
+
+    let broken_hop = checks
+        .iter()
+        .find(|c| c.status == CheckStatus::Fail)
+        .map(|c| c.hop);
+
+    DnsSelfTest {
+        tld: tld.to_string(),
+        dns_port,
+        domain: domain.to_string(),
+        checks,
+        broken_hop,
+    }
+}
+
+/// Check that the resolver file exists and points at our DNS server
+fn check_resolver_file(tld: &str, dns_port: u16) -> DnsCheck {
+    let path = platform::dns_resolver().config_path(tld);
+    let hop = DnsHop::ResolverFile;
+
+    let Some(resolver_config) = platform::dns_resolver().current_config(tld) else {
+        return DnsCheck::fail(
+            hop,
+            format!("{} does not exist", path.display()),
+            "Install the resolver from Burd's settings (requires admin privileges).",
+        );
+    };
+
+    match resolver_config.nameserver.as_deref() {
+        Some("127.0.0.1") => {}
+        Some(other) => {
+            return DnsCheck::fail(
+                hop,
+                format!(
+                    "{} points at nameserver {} instead of 127.0.0.1",
+                    path.display(),
+                    other
+                ),
+                "Reinstall the resolver from Burd's settings.",
+            )
+        }
+        None => {
+            return DnsCheck::fail(
+                hop,
+                format!("{} has no nameserver line", path.display()),
+                "Reinstall the resolver from Burd's settings.",
+            )
+        }
+    }
+
+    if resolver_config.port != dns_port {
+        return DnsCheck::fail(
+            hop,
+            format!(
+                "{} uses port {} but the DNS server is configured for port {}",
+                path.display(),
+                resolver_config.port,
+                dns_port
+            ),
+            "Reinstall the resolver from Burd's settings so both ports match.",
+        );
+    }
+
+    DnsCheck::pass(
+        hop,
+        format!("{} -> 127.0.0.1 port {}", path.display(), dns_port),
+    )
+}
+
+/// Check that something owns the DNS port
+///
+/// If we can bind the port ourselves, nothing is listening on it.
+fn check_listener(dns_port: u16) -> DnsCheck {
+    let hop = DnsHop::Listener;
+    let addr = SocketAddr::from(([127, 0, 0, 1], dns_port));
+
+    match UdpSocket::bind(addr) {
+        Ok(_) => DnsCheck::fail(
+            hop,
+            format!("Nothing is listening on UDP {}", addr),
+            "Start the Burd app (the DNS server runs inside it) or restart the DNS server.",
+        ),
+        Err(e) if e.kind() == ErrorKind::AddrInUse => {
+            DnsCheck::pass(hop, format!("UDP {} is in use", addr))
+        }
+        Err(e) => DnsCheck::fail(
+            hop,
+            format!("Could not probe UDP {}: {}", addr, e),
+            "Check that the DNS port is valid and not blocked.",
+        ),
+    }
+}
+
+/// Query the local DNS server directly, bypassing the system resolver
+fn check_local_query(dns_port: u16, tld: &str, domain: &str) -> DnsCheck {
+    let hop = DnsHop::LocalQuery;
+
+    match query_local_server(dns_port, domain) {
+        Ok(LocalAnswer::Addresses(addrs)) if addrs.contains(&Ipv4Addr::LOCALHOST) => {
+            DnsCheck::pass(hop, format!("{} -> 127.0.0.1", domain))
+        }
+        Ok(LocalAnswer::Addresses(addrs)) if addrs.is_empty() => DnsCheck::fail(
+            hop,
+            format!("The server returned no A record for {}", domain),
+            format!(
+                "The server may be serving a different TLD. Restart Burd after changing the TLD to .{}.",
+                tld
+            ),
+        ),
+        Ok(LocalAnswer::Addresses(addrs)) => DnsCheck::fail(
+            hop,
+            format!("{} resolved to {:?} instead of 127.0.0.1", domain, addrs),
+            format!(
+                "Another DNS server owns port {}. Stop it or change Burd's DNS port.",
+                dns_port
+            ),
+        ),
+        Ok(LocalAnswer::Rejected(code)) => DnsCheck::fail(
+            hop,
+            format!("The server answered {} for {}", code, domain),
+            format!(
+                "The server is not serving .{}. Restart Burd after changing the TLD.",
+                tld
+            ),
+        ),
+        Err(e) => DnsCheck::fail(
+            hop,
+            e,
+            format!(
+                "Port {} is bound but not answering DNS queries. Another process may own it.",
+                dns_port
+            ),
+        ),
+    }
+}
+
+/// Resolve the domain through the operating system
+fn check_system_resolver(domain: &str) -> DnsCheck {
+    let hop = DnsHop::SystemResolver;
+
+    match (domain, 0).to_socket_addrs() {
+        Ok(addrs) => {
+            let ips: Vec<IpAddr> = addrs.map(|a| a.ip()).collect();
+            if ips.contains(&IpAddr::V4(Ipv4Addr::LOCALHOST)) {
+                DnsCheck::pass(hop, format!("{} -> 127.0.0.1", domain))
+            } else {
+                DnsCheck::fail(
+                    hop,
+                    format!("{} resolved to {:?} instead of 127.0.0.1", domain, ips),
+                    "Another resolver (VPN, DNS profile, or /etc/hosts) is answering first.",
+                )
+            }
+        }
+        Err(e) => DnsCheck::fail(
+            hop,
+            format!("{} did not resolve: {}", domain, e),
+            "Flush the DNS cache (sudo dscacheutil -flushcache; sudo killall -HUP mDNSResponder).",
+        ),
+    }
+}
+
+/// What the local DNS server said
+#[derive(Debug)]
+enum LocalAnswer {
+    Addresses(Vec<Ipv4Addr>),
+    Rejected(ResponseCode),
+}
+
+/// Send an A query for `domain` to 127.0.0.1:`dns_port`
+fn query_local_server(dns_port: u16, domain: &str) -> Result<LocalAnswer, String> {
+    let query = build_query(domain)?;
+
+    let socket = UdpSocket::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+        .map_err(|e| format!("Failed to open UDP socket: {}", e))?;
+    socket
+        .set_read_timeout(Some(QUERY_TIMEOUT))
+        .map_err(|e| format!("Failed to set socket timeout: {}", e))?;
+    socket
+        .send_to(&query, SocketAddr::from(([127, 0, 0, 1], dns_port)))
+        .map_err(|e| format!("Failed to send query: {}", e))?;
+
+    let mut buf = [0u8; 512];
+    let len = match socket.recv(&mut buf) {
+        Ok(len) => len,
+        Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            return Err(format!("No answer within {}s", QUERY_TIMEOUT.as_secs()))
+        }
+        Err(e) => return Err(format!("Failed to read answer: {}", e)),
+    };
+
+    parse_answer(&buf[..len])
+}
+
+/// Build a recursive A query for `domain`
+fn build_query(domain: &str) -> Result<Vec<u8>, String> {
+    let name =
+        Name::from_ascii(domain).map_err(|e| format!("Invalid domain '{}': {}", domain, e))?;
+
+    let mut message = Message::new();
+    message.set_id(rand::random());
+    message.set_message_type(MessageType::Query);
+    message.set_op_code(OpCode::Query);
+    message.set_recursion_desired(true);
+    message.add_query(Query::query(name, RecordType::A));
+
+    message
+        .to_bytes()
+        .map_err(|e| format!("Failed to encode query: {}", e))
+}
+
+/// Extract the A records from a DNS response
+fn parse_answer(data: &[u8]) -> Result<LocalAnswer, String> {
+    let message = Message::from_bytes(data).map_err(|e| format!("Malformed DNS answer: {}", e))?;
+
+    if message.response_code() != ResponseCode::NoError {
+        return Ok(LocalAnswer::Rejected(message.response_code()));
+    }
+
+    let addrs = message
+        .answers()
+        .iter()
+        .filter_map(|record| match record.data() {
+            Some(RData::A(a)) => Some(a.0),
+            _ => None,
+        })
+        .collect();
+
+    Ok(LocalAnswer::Addresses(addrs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns::DnsServer;
+
+    #[test]
+    fn test_query_local_server() {
+        let mut server = DnsServer::new(15355, vec!["selftest".to_string()]);
+
+        // Start might fail if port is in use, that's ok for this test
+        if server.start().is_ok() {
+            match query_local_server(15355, "app.selftest").unwrap() {
+                LocalAnswer::Addresses(addrs) => assert_eq!(addrs, vec![Ipv4Addr::LOCALHOST]),
+                other => panic!("unexpected answer: {:?}", other),
+            }
+            assert!(matches!(
+                query_local_server(15355, "example.com").unwrap(),
+                LocalAnswer::Rejected(ResponseCode::NXDomain)
+            ));
+            assert_eq!(check_listener(15355).status, CheckStatus::Pass);
+            server.stop();
+        }
+    }
+
+    #[test]
+    fn test_broken_hop_is_first_failure() {
+        // Nothing listens on this port, so the local query is skipped
+        let report = run_self_test("burd-selftest-missing", 15356, "app.burd-selftest-missing");
+
+        assert_eq!(report.checks.len(), 4);
+        assert_eq!(report.checks[0].status, CheckStatus::Fail);
+        assert_eq!(report.broken_hop, Some(DnsHop::ResolverFile));
+        assert_eq!(report.checks[2].status, CheckStatus::Skipped);
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_build_query_rejects_invalid_domain() {
+        let long_label = "a".repeat(64);
+        assert!(build_query(&format!("{}.burd", long_label)).is_err());
+        assert!(build_query("app.burd").is_ok());
+    }
+}
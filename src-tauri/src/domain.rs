@@ -2,9 +2,21 @@
 //!
 //! Handles converting instance names to valid domain slugs and managing
 //! domain assignments to avoid conflicts.
+//!
+//! Names in other scripts are transliterated by default ("東京" ->
+//! "dong-jing") or, per [`SlugSettings`], kept as punycode IDN labels that
+//! browsers show in the original script. Slugs are cut to fit a DNS label,
+//! and users can pin the slug for a name or compute it with a hook command.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::{get_app_dir, SlugScript, SlugSettings, SlugTruncate};
 
 /// Default TLD for local development
 pub const DEFAULT_TLD: &str = "burd";
@@ -15,6 +27,15 @@ pub const DEFAULT_DNS_PORT: u16 = 5354;
 /// Default proxy server port (unprivileged)
 pub const DEFAULT_PROXY_PORT: u16 = 8080;
 
+/// Longest DNS label, and so the longest slug
+pub const MAX_SLUG_LENGTH: usize = 63;
+
+/// Shortest slug limit, leaving room for a hash suffix
+pub const MIN_SLUG_LENGTH: usize = 8;
+
+/// Prefix of punycode-encoded labels
+const IDN_PREFIX: &str = "xn--";
+
 /// Domain information for an instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainInfo {
@@ -54,21 +75,62 @@ impl DomainManager {
     /// - "Meilisearch Dev" -> "meilisearch-dev"
     /// - "Test_Server 123" -> "test-server-123"
     pub fn slugify(name: &str) -> String {
-        slug::slugify(name)
+        Self::slugify_with(name, &SlugSettings::default())
+    }
+
+    /// Generate a domain slug from a name following the user's settings
+    ///
+    /// An override for the name wins, then the hook's output, then the
+    /// script and truncation rules. A name with nothing to transliterate
+    /// (emoji, say) falls back to punycode rather than an empty slug.
+    pub fn slugify_with(name: &str, settings: &SlugSettings) -> String {
+        if let Some(slug) = settings.overrides.get(name) {
+            return slug.clone();
+        }
+
+        let max = settings
+            .max_length
+            .unwrap_or(MAX_SLUG_LENGTH)
+            .clamp(MIN_SLUG_LENGTH, MAX_SLUG_LENGTH);
+
+        if let Some(output) = settings
+            .hook
+            .as_deref()
+            .and_then(|h| slug_hook_output(h, name))
+        {
+            let slug = slug::slugify(output);
+            if !slug.is_empty() {
+                return shorten(&slug, max, settings.truncate, str::to_string);
+            }
+        }
+
+        if settings.script == SlugScript::Transliterate {
+            let slug = slug::slugify(name);
+            if !slug.is_empty() {
+                return shorten(&slug, max, settings.truncate, str::to_string);
+            }
+        }
+
+        let label = unicode_label(name);
+        if label.is_empty() {
+            // Nothing usable at all; stay stable and non-empty
+            return format!("site-{}", short_hash(name));
+        }
+        shorten(&label, max, settings.truncate, idn_label)
     }
 
     /// Generate the full domain for an instance name
     ///
     /// Example: "My API" -> "my-api.burd"
-    pub fn generate_domain(name: &str, tld: &str) -> String {
-        format!("{}.{}", Self::slugify(name), tld)
+    pub fn generate_domain(name: &str, tld: &str, settings: &SlugSettings) -> String {
+        format!("{}.{}", Self::slugify_with(name, settings), tld)
     }
 
     /// Get a unique domain, appending a number if needed to avoid conflicts
     ///
     /// Example: If "my-api" is taken, returns "my-api-2"
-    pub fn get_unique_slug(&self, name: &str) -> String {
-        let base_slug = Self::slugify(name);
+    pub fn get_unique_slug(&self, name: &str, settings: &SlugSettings) -> String {
+        let base_slug = Self::slugify_with(name, settings);
 
         if !self.registered.contains(&base_slug) {
             return base_slug;
@@ -152,8 +214,12 @@ impl DomainManager {
             }
         }
 
-        // No consecutive hyphens
-        if slug.contains("--") {
+        // No consecutive hyphens, except in punycode labels ("a-ü" is
+        // "xn--a--...")
+        if slug
+            .split('.')
+            .any(|label| !label.starts_with(IDN_PREFIX) && label.contains("--"))
+        {
             return Some("Domain cannot contain consecutive hyphens".to_string());
         }
 
@@ -167,8 +233,9 @@ impl DomainManager {
         custom_domain: Option<&str>,
         enabled: bool,
         tld: &str,
+        settings: &SlugSettings,
     ) -> DomainInfo {
-        let generated = Self::slugify(instance_name);
+        let generated = Self::slugify_with(instance_name, settings);
         let effective_slug = custom_domain.unwrap_or(&generated);
         let effective = format!("{}.{}", effective_slug, tld);
         let url = format!("http://{}:{}", effective, self.proxy_port);
@@ -194,6 +261,269 @@ impl Default for DomainManager {
     }
 }
 
+/// Check slug settings before saving, normalizing the overrides
+pub fn validate_slug_settings(mut settings: SlugSettings) -> Result<SlugSettings, String> {
+    if let Some(max) = settings.max_length {
+        if !(MIN_SLUG_LENGTH..=MAX_SLUG_LENGTH).contains(&max) {
+            return Err(format!(
+                "Maximum slug length must be between {} and {}",
+                MIN_SLUG_LENGTH, MAX_SLUG_LENGTH
+            ));
+        }
+    }
+
+    let mut overrides = std::collections::BTreeMap::new();
+    for (name, slug) in settings.overrides {
+        let slug = slug.trim().to_lowercase();
+        let valid = !slug.is_empty()
+            && slug.len() <= MAX_SLUG_LENGTH
+            && !slug.starts_with('-')
+            && !slug.ends_with('-')
+            && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(format!(
+                "Invalid slug '{}' for '{}'. Use letters, digits and hyphens, up to {} characters",
+                slug, name, MAX_SLUG_LENGTH
+            ));
+        }
+        overrides.insert(name.trim().to_string(), slug);
+    }
+    settings.overrides = overrides;
+    settings.hook = settings
+        .hook
+        .map(|hook| hook.trim().to_string())
+        .filter(|hook| !hook.is_empty());
+    Ok(settings)
+}
+
+/// Hook output by (hook, name); `None` when the hook failed
+type HookOutputs = HashMap<(String, String), Option<String>>;
+
+/// Hook outputs this process has looked up, failures included
+fn hook_outputs() -> &'static Mutex<HookOutputs> {
+    static OUTPUTS: OnceLock<Mutex<HookOutputs>> = OnceLock::new();
+    OUTPUTS.get_or_init(Default::default)
+}
+
+/// Where the hook's output for `name` is kept (cache/slug-hook/)
+fn hook_cache_path(hook: &str, name: &str) -> Option<PathBuf> {
+    let key = Sha256::digest(format!("{}\0{}", hook, name).as_bytes());
+    Some(
+        get_app_dir()
+            .ok()?
+            .join("cache")
+            .join("slug-hook")
+            .join(format!("{:x}", key)),
+    )
+}
+
+/// The slug hook's output for `name`, running it only the first time
+///
+/// Domains are computed all the time (every idle check, every template
+/// render), so the output is kept on disk: the hook runs once per name, and
+/// a domain stays put even if the hook would print something else later.
+/// Failures are only remembered for the life of the process.
+fn slug_hook_output(hook: &str, name: &str) -> Option<String> {
+    let key = (hook.to_string(), name.to_string());
+    if let Some(output) = hook_outputs().lock().ok()?.get(&key) {
+        return output.clone();
+    }
+
+    let path = hook_cache_path(hook, name);
+    let output = match path.as_ref().and_then(|p| fs::read_to_string(p).ok()) {
+        Some(cached) => Some(cached),
+        None => {
+            let output = run_slug_hook(hook, name);
+            if let (Some(output), Some(path)) = (&output, &path) {
+                if let Some(dir) = path.parent() {
+                    let _ = fs::create_dir_all(dir);
+                }
+                let _ = fs::write(path, output);
+            }
+            output
+        }
+    };
+
+    if let Ok(mut outputs) = hook_outputs().lock() {
+        outputs.insert(key, output.clone());
+    }
+    output
+}
+
+/// Run the user's slug hook for `name`; `None` when it fails or prints nothing
+fn run_slug_hook(hook: &str, name: &str) -> Option<String> {
+    let output = Command::new("sh")
+        .args(["-c", hook])
+        .env("BURD_SLUG_NAME", name)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        eprintln!(
+            "Slug hook failed for '{}': {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    let slug = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!slug.is_empty()).then_some(slug)
+}
+
+/// Lowercase `name` keeping letters of any script, other runs becoming `-`
+fn unicode_label(name: &str) -> String {
+    let mut label = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            label.push(c);
+        } else if !label.is_empty() && !label.ends_with('-') {
+            label.push('-');
+        }
+    }
+    label.trim_end_matches('-').to_string()
+}
+
+/// The ASCII form of a label: itself, or `xn--` and its punycode
+fn idn_label(label: &str) -> String {
+    if label.is_ascii() {
+        label.to_string()
+    } else {
+        format!("{}{}", IDN_PREFIX, punycode(label))
+    }
+}
+
+/// First 6 hex digits of the SHA-256 of `value`
+fn short_hash(value: &str) -> String {
+    format!("{:x}", Sha256::digest(value.as_bytes()))[..6].to_string()
+}
+
+/// Shorten `slug` until its encoded form fits in `max` characters
+///
+/// Cuts happen on the unencoded slug, so a punycode label is never split.
+fn shorten(slug: &str, max: usize, truncate: SlugTruncate, encode: fn(&str) -> String) -> String {
+    let full = encode(slug);
+    if full.len() <= max {
+        return full;
+    }
+
+    let chars: Vec<char> = slug.chars().collect();
+    let prefix = |len: usize| {
+        chars[..len]
+            .iter()
+            .collect::<String>()
+            .trim_end_matches('-')
+            .to_string()
+    };
+    let cut = || {
+        (0..chars.len())
+            .rev()
+            .map(|len| encode(&prefix(len)))
+            .find(|s| s.len() <= max)
+            .unwrap_or_default()
+    };
+
+    match truncate {
+        SlugTruncate::Cut => cut(),
+        SlugTruncate::Word => (0..chars.len())
+            .rev()
+            .filter(|&len| chars[len] == '-')
+            .map(|len| encode(&prefix(len)))
+            .find(|s| !s.is_empty() && s.len() <= max)
+            .unwrap_or_else(cut),
+        SlugTruncate::Hash => {
+            let hash = short_hash(slug);
+            (0..chars.len())
+                .rev()
+                .map(|len| match prefix(len) {
+                    p if p.is_empty() => hash.clone(),
+                    p => encode(&format!("{}-{}", p, hash)),
+                })
+                .find(|s| s.len() <= max)
+                .unwrap_or(hash)
+        }
+    }
+}
+
+/// Punycode (RFC 3492) encoding of `input`, without the `xn--` prefix
+fn punycode(input: &str) -> String {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+    const SKEW: u32 = 38;
+    const DAMP: u32 = 700;
+    const INITIAL_BIAS: u32 = 72;
+    const INITIAL_N: u32 = 128;
+
+    fn digit(d: u32) -> char {
+        match d {
+            0..=25 => (b'a' + d as u8) as char,
+            _ => (b'0' + (d - 26) as u8) as char,
+        }
+    }
+
+    fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+        let mut delta = if first { delta / DAMP } else { delta / 2 };
+        delta += delta / points;
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+    }
+
+    let code_points: Vec<u32> = input.chars().map(u32::from).collect();
+    let mut output: String = input.chars().filter(char::is_ascii).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let (mut n, mut delta, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+    let mut handled = basic;
+    while (handled as usize) < code_points.len() {
+        let next = code_points
+            .iter()
+            .copied()
+            .filter(|&c| c >= n)
+            .min()
+            .unwrap_or(n);
+        delta += (next - n) * (handled + 1);
+        n = next;
+
+        for &c in &code_points {
+            if c < n {
+                delta += 1;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = if k <= bias {
+                        T_MIN
+                    } else if k >= bias + T_MAX {
+                        T_MAX
+                    } else {
+                        k - bias
+                    };
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,10 +536,165 @@ mod tests {
         assert_eq!(DomainManager::slugify("UPPERCASE"), "uppercase");
     }
 
+    #[test]
+    fn test_slugify_non_latin() {
+        // Transliterated by default, never empty
+        assert_eq!(DomainManager::slugify("にほんご"), "nihongo");
+        assert_eq!(DomainManager::slugify("東京"), "dong-jing");
+        let fallback = DomainManager::slugify("!!!");
+        assert!(fallback.starts_with("site-") && fallback.len() == 11);
+
+        let punycode = SlugSettings {
+            script: SlugScript::Punycode,
+            ..SlugSettings::default()
+        };
+        assert_eq!(
+            DomainManager::slugify_with("東京", &punycode),
+            "xn--1lqs71d"
+        );
+        assert_eq!(
+            DomainManager::slugify_with("München", &punycode),
+            "xn--mnchen-3ya"
+        );
+        assert_eq!(DomainManager::slugify_with("My API", &punycode), "my-api");
+    }
+
+    #[test]
+    fn test_punycode_reference_vectors() {
+        assert_eq!(punycode("bücher"), "bcher-kva");
+        assert_eq!(punycode("日本語"), "wgv71a119e");
+        assert_eq!(punycode("abc"), "abc-");
+    }
+
+    #[test]
+    fn test_slugify_truncation() {
+        let name = "the quick brown fox jumps over the lazy dog";
+        let settings = |truncate| SlugSettings {
+            max_length: Some(20),
+            truncate,
+            ..SlugSettings::default()
+        };
+
+        assert_eq!(
+            DomainManager::slugify_with(name, &settings(SlugTruncate::Word)),
+            "the-quick-brown-fox"
+        );
+        assert_eq!(
+            DomainManager::slugify_with(name, &settings(SlugTruncate::Cut)),
+            "the-quick-brown-fox"
+        );
+        assert_eq!(
+            DomainManager::slugify_with(
+                "abcdefghij klmnopqrstuvwxyz",
+                &settings(SlugTruncate::Cut)
+            ),
+            "abcdefghij-klmnopqrs"
+        );
+        let hashed = DomainManager::slugify_with(name, &settings(SlugTruncate::Hash));
+        assert_eq!(hashed.len(), 20);
+        assert!(hashed.starts_with("the-quick-bro-"));
+        assert_ne!(
+            hashed,
+            DomainManager::slugify_with(&format!("{} again", name), &settings(SlugTruncate::Hash))
+        );
+
+        // Long names fit a DNS label even without settings
+        assert_eq!(
+            DomainManager::slugify(&"a".repeat(100)).len(),
+            MAX_SLUG_LENGTH
+        );
+
+        // Punycode labels are shortened before encoding, never split
+        let punycode = SlugSettings {
+            script: SlugScript::Punycode,
+            max_length: Some(12),
+            truncate: SlugTruncate::Cut,
+            ..SlugSettings::default()
+        };
+        let slug = DomainManager::slugify_with("東京都渋谷区", &punycode);
+        assert!(slug.starts_with(IDN_PREFIX) && slug.len() <= 12, "{}", slug);
+    }
+
+    #[test]
+    fn test_slugify_overrides_and_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::config::set_app_dir_override(Some(dir.path().to_path_buf()));
+        let mut settings = SlugSettings {
+            hook: Some("printf 'Hooked %s' \"$BURD_SLUG_NAME\"".to_string()),
+            ..SlugSettings::default()
+        };
+        settings
+            .overrides
+            .insert("日本語プロジェクト".to_string(), "nihongo".to_string());
+
+        assert_eq!(
+            DomainManager::slugify_with("日本語プロジェクト", &settings),
+            "nihongo"
+        );
+        assert_eq!(
+            DomainManager::slugify_with("Shop", &settings),
+            "hooked-shop"
+        );
+
+        // A hook printing nothing leaves it to the rules
+        settings.hook = Some("true".to_string());
+        assert_eq!(DomainManager::slugify_with("Shop", &settings), "shop");
+        crate::config::set_app_dir_override(None);
+    }
+
+    #[test]
+    fn test_slug_hook_runs_once_per_name() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::config::set_app_dir_override(Some(dir.path().to_path_buf()));
+        let counter = dir.path().join("runs");
+        let settings = SlugSettings {
+            hook: Some(format!(
+                "echo run >> '{}'; printf 'Shop %s' \"$(wc -l < '{}' | tr -d ' ')\"",
+                counter.display(),
+                counter.display()
+            )),
+            ..SlugSettings::default()
+        };
+
+        assert_eq!(DomainManager::slugify_with("Shop", &settings), "shop-1");
+        assert_eq!(DomainManager::slugify_with("Shop", &settings), "shop-1");
+
+        // Another process finds the output on disk
+        hook_outputs().lock().unwrap().clear();
+        assert_eq!(DomainManager::slugify_with("Shop", &settings), "shop-1");
+        assert_eq!(std::fs::read_to_string(&counter).unwrap(), "run\n");
+        crate::config::set_app_dir_override(None);
+    }
+
+    #[test]
+    fn test_validate_slug_settings() {
+        let mut settings = SlugSettings::default();
+        settings
+            .overrides
+            .insert("東京".to_string(), " Tokyo ".to_string());
+        settings.hook = Some("  ".to_string());
+        let settings = validate_slug_settings(settings).unwrap();
+        assert_eq!(settings.overrides["東京"], "tokyo");
+        assert_eq!(settings.hook, None);
+
+        let too_short = SlugSettings {
+            max_length: Some(3),
+            ..SlugSettings::default()
+        };
+        assert!(validate_slug_settings(too_short).is_err());
+
+        let mut invalid = SlugSettings::default();
+        invalid
+            .overrides
+            .insert("shop".to_string(), "my shop".to_string());
+        assert!(validate_slug_settings(invalid).is_err());
+    }
+
     #[test]
     fn test_generate_domain() {
+        let settings = SlugSettings::default();
         assert_eq!(
-            DomainManager::generate_domain("My API", "burd"),
+            DomainManager::generate_domain("My API", "burd", &settings),
             "my-api.burd"
         );
     }
@@ -217,14 +702,15 @@ mod tests {
     #[test]
     fn test_unique_slug() {
         let mut manager = DomainManager::default();
+        let settings = SlugSettings::default();
 
-        assert_eq!(manager.get_unique_slug("My API"), "my-api");
+        assert_eq!(manager.get_unique_slug("My API", &settings), "my-api");
         manager.register("my-api");
 
-        assert_eq!(manager.get_unique_slug("My API"), "my-api-2");
+        assert_eq!(manager.get_unique_slug("My API", &settings), "my-api-2");
         manager.register("my-api-2");
 
-        assert_eq!(manager.get_unique_slug("My API"), "my-api-3");
+        assert_eq!(manager.get_unique_slug("My API", &settings), "my-api-3");
     }
 
     #[test]
@@ -236,6 +722,9 @@ mod tests {
         assert!(DomainManager::validate_slug("-api").is_some());
         assert!(DomainManager::validate_slug("api-").is_some());
         assert!(DomainManager::validate_slug("my--api").is_some());
+        assert!(DomainManager::validate_slug("xn--tdaaaaaa").is_none());
+        assert!(DomainManager::validate_slug("shop.xn--tdaaaaaa").is_none());
+        assert!(DomainManager::validate_slug("xn--a--0ra").is_none());
         assert!(DomainManager::validate_slug("MY-API").is_some());
     }
 }
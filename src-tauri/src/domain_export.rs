@@ -0,0 +1,557 @@
+//! Domain Export and Import
+//!
+//! Exports a set of domains as shareable JSON so a teammate can recreate the
+//! routing layout. Targets are written without machine-specific IDs: an
+//! instance by its name, a port by its number, a static site by its directory.
+//!
+//! Imports are previewed first, like stack imports. Subdomains that already
+//! exist, instances that can't be found by name and static directories that
+//! don't exist are reported as conflicts; a domain whose conflicts aren't
+//! resolved is skipped.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::config::{
+    Config, Domain, DomainConflictResolution, DomainExport, DomainImportConflict,
+    DomainImportPreview, DomainImportResult, DomainSource, DomainTarget, ExportedDomain,
+    ExportedDomainTarget, Instance, InstanceCandidate,
+};
+use crate::start_page;
+use crate::validation;
+
+/// Current export schema version
+const SCHEMA_VERSION: u32 = 1;
+
+/// Find an instance by name, preferring an exact match
+fn find_instance<'a>(config: &'a Config, name: &str) -> Option<&'a Instance> {
+    config
+        .instances
+        .iter()
+        .find(|i| i.name == name)
+        .or_else(|| {
+            config
+                .instances
+                .iter()
+                .find(|i| i.name.eq_ignore_ascii_case(name))
+        })
+}
+
+fn validate_subdomain(subdomain: &str) -> Result<(), String> {
+    validation::validate_domain_name(subdomain)
+        .map_err(|e| format!("Invalid subdomain '{}': {}", subdomain, e))?;
+    if start_page::is_reserved_subdomain(subdomain) {
+        return Err(format!(
+            "Domain '{}' is reserved for the Burd start page",
+            subdomain
+        ));
+    }
+    Ok(())
+}
+
+fn export_domain(config: &Config, domain: &Domain) -> Result<ExportedDomain, String> {
+    let target = match &domain.target {
+        DomainTarget::Instance(id) => {
+            let instance = config
+                .instances
+                .iter()
+                .find(|i| i.id == *id)
+                .ok_or_else(|| format!("Instance for domain '{}' not found", domain.subdomain))?;
+            ExportedDomainTarget::Instance {
+                name: instance.name.clone(),
+                service_type: instance.service_type,
+                port: instance.port,
+            }
+        }
+        DomainTarget::Port(port) => ExportedDomainTarget::Port { port: *port },
+        DomainTarget::StaticFiles { path, browse } => ExportedDomainTarget::StaticFiles {
+            path: path.clone(),
+            browse: *browse,
+        },
+    };
+
+    Ok(ExportedDomain {
+        subdomain: domain.subdomain.clone(),
+        target,
+        ssl_enabled: domain.ssl_enabled,
+        force_https: domain.force_https,
+        hsts: domain.hsts,
+        require_client_cert: domain.require_client_cert,
+    })
+}
+
+/// Export domains by ID, or every domain except parked ones when `ids` is empty
+///
+/// Parked domains are left out by default since parking the same directory
+/// recreates them.
+pub fn export_domains(
+    config: &Config,
+    ids: &[Uuid],
+    created_by: Option<String>,
+) -> Result<DomainExport, String> {
+    if let Some(missing) = ids
+        .iter()
+        .find(|id| !config.domains.iter().any(|d| d.id == **id))
+    {
+        return Err(format!("Domain {} not found", missing));
+    }
+
+    let domains = config
+        .domains
+        .iter()
+        .filter(|d| {
+            if ids.is_empty() {
+                !matches!(d.source, DomainSource::Parked { .. })
+            } else {
+                ids.contains(&d.id)
+            }
+        })
+        .map(|d| export_domain(config, d))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DomainExport {
+        schema_version: SCHEMA_VERSION,
+        created_by,
+        created_at: Utc::now(),
+        domains,
+    })
+}
+
+/// Parse and validate a domain export
+pub fn parse_export(json: &str) -> Result<DomainExport, String> {
+    let export: DomainExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid domain config: {}", e))?;
+    if export.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Domain config uses schema version {}; this version of Burd supports up to {}",
+            export.schema_version, SCHEMA_VERSION
+        ));
+    }
+
+    for (index, domain) in export.domains.iter().enumerate() {
+        validate_subdomain(&domain.subdomain)?;
+        if export.domains[..index]
+            .iter()
+            .any(|d| d.subdomain == domain.subdomain)
+        {
+            return Err(format!("Domain '{}' is listed twice", domain.subdomain));
+        }
+    }
+    Ok(export)
+}
+
+/// Conflicts for one imported domain
+fn domain_conflicts(config: &Config, domain: &ExportedDomain) -> Vec<DomainImportConflict> {
+    let mut conflicts = Vec::new();
+
+    if let Some(existing) = config
+        .domains
+        .iter()
+        .find(|d| d.subdomain == domain.subdomain)
+    {
+        conflicts.push(DomainImportConflict::SubdomainExists {
+            subdomain: domain.subdomain.clone(),
+            existing_id: existing.id,
+        });
+    }
+
+    match &domain.target {
+        ExportedDomainTarget::Instance {
+            name, service_type, ..
+        } if find_instance(config, name).is_none() => {
+            conflicts.push(DomainImportConflict::InstanceNotFound {
+                subdomain: domain.subdomain.clone(),
+                instance_name: name.clone(),
+                service_type: *service_type,
+                candidates: config
+                    .instances
+                    .iter()
+                    .filter(|i| i.service_type == *service_type)
+                    .map(|i| InstanceCandidate {
+                        id: i.id,
+                        name: i.name.clone(),
+                    })
+                    .collect(),
+            });
+        }
+        ExportedDomainTarget::StaticFiles { path, .. } if !Path::new(path).is_dir() => {
+            conflicts.push(DomainImportConflict::PathNotFound {
+                subdomain: domain.subdomain.clone(),
+                path: path.clone(),
+            });
+        }
+        _ => {}
+    }
+
+    conflicts
+}
+
+/// Preview an import: the parsed config and its conflicts
+pub fn preview_import(config: &Config, import: DomainExport) -> DomainImportPreview {
+    let conflicts = import
+        .domains
+        .iter()
+        .flat_map(|d| domain_conflicts(config, d))
+        .collect();
+    DomainImportPreview {
+        config: import,
+        conflicts,
+    }
+}
+
+/// What the resolutions for one imported domain ask for
+#[derive(Default)]
+struct DomainPlan {
+    skip: bool,
+    replace: bool,
+    subdomain: Option<String>,
+    instance_id: Option<Uuid>,
+    path: Option<String>,
+}
+
+fn plans(resolutions: &[DomainConflictResolution]) -> HashMap<&str, DomainPlan> {
+    let mut plans: HashMap<&str, DomainPlan> = HashMap::new();
+    for resolution in resolutions {
+        match resolution {
+            DomainConflictResolution::ReplaceExisting { subdomain } => {
+                plans.entry(subdomain).or_default().replace = true;
+            }
+            DomainConflictResolution::RenameDomain {
+                subdomain,
+                new_subdomain,
+            } => {
+                plans.entry(subdomain).or_default().subdomain = Some(new_subdomain.clone());
+            }
+            DomainConflictResolution::MapInstance {
+                subdomain,
+                instance_id,
+            } => {
+                plans.entry(subdomain).or_default().instance_id = Some(*instance_id);
+            }
+            DomainConflictResolution::ChangePath { subdomain, path } => {
+                plans.entry(subdomain).or_default().path = Some(path.clone());
+            }
+            DomainConflictResolution::Skip { subdomain } => {
+                plans.entry(subdomain).or_default().skip = true;
+            }
+        }
+    }
+    plans
+}
+
+/// Apply an import to the config (after preview and conflict resolution)
+///
+/// Domains whose instance or directory can't be found, or whose subdomain
+/// exists without a `ReplaceExisting` resolution, are skipped. The caller
+/// saves the config and registers the new routes.
+pub fn apply_import(
+    config: &mut Config,
+    import: &DomainExport,
+    resolutions: &[DomainConflictResolution],
+) -> Result<DomainImportResult, String> {
+    let plans = plans(resolutions);
+    let no_plan = DomainPlan::default();
+
+    let mut result = DomainImportResult {
+        domains_created: Vec::new(),
+        domains_replaced: Vec::new(),
+        domains_skipped: Vec::new(),
+    };
+
+    for domain in &import.domains {
+        let plan = plans.get(domain.subdomain.as_str()).unwrap_or(&no_plan);
+        if plan.skip {
+            result.domains_skipped.push(domain.subdomain.clone());
+            continue;
+        }
+
+        let target = match &domain.target {
+            ExportedDomainTarget::Instance { name, .. } => {
+                let instance = match plan.instance_id {
+                    Some(id) => Some(
+                        config
+                            .instances
+                            .iter()
+                            .find(|i| i.id == id)
+                            .ok_or_else(|| format!("Instance {} not found", id))?,
+                    ),
+                    None => find_instance(config, name),
+                };
+                instance.map(|i| DomainTarget::Instance(i.id))
+            }
+            ExportedDomainTarget::Port { port } => Some(DomainTarget::Port(*port)),
+            ExportedDomainTarget::StaticFiles { path, browse } => {
+                let path = plan.path.clone().unwrap_or_else(|| path.clone());
+                Path::new(&path)
+                    .is_dir()
+                    .then_some(DomainTarget::StaticFiles {
+                        path,
+                        browse: *browse,
+                    })
+            }
+        };
+        let Some(target) = target else {
+            result.domains_skipped.push(domain.subdomain.clone());
+            continue;
+        };
+
+        let subdomain = plan
+            .subdomain
+            .clone()
+            .unwrap_or_else(|| domain.subdomain.clone());
+        if plan.subdomain.is_some() {
+            validate_subdomain(&subdomain)?;
+        }
+        if let Some(index) = config.domains.iter().position(|d| d.subdomain == subdomain) {
+            if plan.replace {
+                result
+                    .domains_replaced
+                    .push(config.domains.remove(index).id);
+            } else if plan.subdomain.is_some() {
+                return Err(format!("Domain '{}' already exists", subdomain));
+            } else {
+                result.domains_skipped.push(domain.subdomain.clone());
+                continue;
+            }
+        }
+
+        let new_domain = Domain {
+            id: Uuid::new_v4(),
+            subdomain,
+            tld: None,
+            target,
+            ssl_enabled: domain.ssl_enabled,
+            force_https: domain.ssl_enabled && domain.force_https,
+            hsts: domain.ssl_enabled && domain.hsts,
+            require_client_cert: domain.ssl_enabled && domain.require_client_cert,
+            custom_directives: None,
+            source: DomainSource::Manual,
+            created_at: Utc::now(),
+        };
+        result.domains_created.push(new_domain.id);
+        config.domains.push(new_domain);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServiceType;
+    use crate::test_utils::{ConfigBuilder, DomainBuilder, InstanceBuilder};
+
+    fn exported(subdomain: &str, target: ExportedDomainTarget) -> ExportedDomain {
+        ExportedDomain {
+            subdomain: subdomain.to_string(),
+            target,
+            ssl_enabled: true,
+            force_https: true,
+            hsts: false,
+            require_client_cert: false,
+        }
+    }
+
+    fn instance_target(name: &str) -> ExportedDomainTarget {
+        ExportedDomainTarget::Instance {
+            name: name.to_string(),
+            service_type: ServiceType::MariaDB,
+            port: 3306,
+        }
+    }
+
+    fn export_of(domains: Vec<ExportedDomain>) -> DomainExport {
+        DomainExport {
+            schema_version: SCHEMA_VERSION,
+            created_by: None,
+            created_at: Utc::now(),
+            domains,
+        }
+    }
+
+    #[test]
+    fn test_export_uses_instance_names() {
+        let db = InstanceBuilder::new()
+            .name("mysql")
+            .service_type(ServiceType::MariaDB)
+            .port(3306)
+            .build();
+        let parked = Domain::for_parked_port("blog".to_string(), 8000, false, Uuid::new_v4());
+        let config = ConfigBuilder::new()
+            .domain(DomainBuilder::new_instance(db.id).subdomain("db").build())
+            .domain(DomainBuilder::new_port(5173).subdomain("vite").build())
+            .domain(parked.clone())
+            .instance(db)
+            .build();
+
+        let export = export_domains(&config, &[], None).unwrap();
+        let subdomains: Vec<&str> = export
+            .domains
+            .iter()
+            .map(|d| d.subdomain.as_str())
+            .collect();
+        assert_eq!(subdomains, vec!["db", "vite"]);
+        assert_eq!(export.domains[0].target, instance_target("mysql"));
+
+        // Parked domains can be exported explicitly
+        let export = export_domains(&config, &[parked.id], None).unwrap();
+        assert_eq!(
+            export.domains[0].target,
+            ExportedDomainTarget::Port { port: 8000 }
+        );
+        assert!(export_domains(&config, &[Uuid::new_v4()], None).is_err());
+
+        // Round trip through JSON
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(parse_export(&json).unwrap().domains.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_export_validates() {
+        let json = |subdomains: &[&str]| {
+            let export = export_of(
+                subdomains
+                    .iter()
+                    .map(|s| exported(s, ExportedDomainTarget::Port { port: 3000 }))
+                    .collect(),
+            );
+            serde_json::to_string(&export).unwrap()
+        };
+
+        assert!(parse_export(&json(&["api", "web"])).is_ok());
+        assert!(parse_export(&json(&["api", "api"])).is_err());
+        assert!(parse_export(&json(&["bad name"])).is_err());
+        assert!(parse_export("{}").is_err());
+    }
+
+    #[test]
+    fn test_preview_conflicts() {
+        let db = InstanceBuilder::new()
+            .name("MySQL")
+            .service_type(ServiceType::MariaDB)
+            .build();
+        let config = ConfigBuilder::new()
+            .domain(DomainBuilder::new_port(3000).subdomain("api").build())
+            .instance(db.clone())
+            .build();
+
+        let preview = preview_import(
+            &config,
+            export_of(vec![
+                // Exists, and the instance matches case-insensitively
+                exported("api", instance_target("mysql")),
+                exported("db", instance_target("mariadb")),
+                exported(
+                    "docs",
+                    ExportedDomainTarget::StaticFiles {
+                        path: "/nonexistent/burd-docs".to_string(),
+                        browse: false,
+                    },
+                ),
+                exported("vite", ExportedDomainTarget::Port { port: 5173 }),
+            ]),
+        );
+
+        assert_eq!(preview.conflicts.len(), 3);
+        assert!(matches!(
+            &preview.conflicts[0],
+            DomainImportConflict::SubdomainExists { subdomain, .. } if subdomain == "api"
+        ));
+        match &preview.conflicts[1] {
+            DomainImportConflict::InstanceNotFound {
+                instance_name,
+                candidates,
+                ..
+            } => {
+                assert_eq!(instance_name, "mariadb");
+                assert_eq!(candidates.len(), 1);
+                assert_eq!(candidates[0].id, db.id);
+            }
+            other => panic!("unexpected conflict {:?}", other),
+        }
+        assert!(matches!(
+            &preview.conflicts[2],
+            DomainImportConflict::PathNotFound { subdomain, .. } if subdomain == "docs"
+        ));
+    }
+
+    #[test]
+    fn test_apply_import_with_resolutions() {
+        let db = InstanceBuilder::new()
+            .name("db")
+            .service_type(ServiceType::MariaDB)
+            .build();
+        let existing = DomainBuilder::new_port(3000).subdomain("api").build();
+        let taken = DomainBuilder::new_port(4000).subdomain("web").build();
+        let mut config = ConfigBuilder::new()
+            .domain(existing.clone())
+            .domain(taken)
+            .instance(db.clone())
+            .build();
+        let site = tempfile::tempdir().unwrap();
+
+        let import = export_of(vec![
+            exported("api", ExportedDomainTarget::Port { port: 8000 }),
+            exported("web", ExportedDomainTarget::Port { port: 8001 }),
+            exported("admin", instance_target("other-db")),
+            exported("mail", instance_target("missing")),
+            exported(
+                "docs",
+                ExportedDomainTarget::StaticFiles {
+                    path: "/nonexistent/burd-docs".to_string(),
+                    browse: true,
+                },
+            ),
+            exported("old", ExportedDomainTarget::Port { port: 9000 }),
+        ]);
+        let resolutions = vec![
+            DomainConflictResolution::ReplaceExisting {
+                subdomain: "api".to_string(),
+            },
+            DomainConflictResolution::RenameDomain {
+                subdomain: "web".to_string(),
+                new_subdomain: "web2".to_string(),
+            },
+            DomainConflictResolution::MapInstance {
+                subdomain: "admin".to_string(),
+                instance_id: db.id,
+            },
+            DomainConflictResolution::ChangePath {
+                subdomain: "docs".to_string(),
+                path: site.path().to_string_lossy().to_string(),
+            },
+            DomainConflictResolution::Skip {
+                subdomain: "old".to_string(),
+            },
+        ];
+
+        let result = apply_import(&mut config, &import, &resolutions).unwrap();
+        let created: Vec<&Domain> = config
+            .domains
+            .iter()
+            .filter(|d| result.domains_created.contains(&d.id))
+            .collect();
+        let subdomains: Vec<&str> = created.iter().map(|d| d.subdomain.as_str()).collect();
+        assert_eq!(subdomains, vec!["api", "web2", "admin", "docs"]);
+        assert_eq!(result.domains_replaced, vec![existing.id]);
+        assert_eq!(result.domains_skipped, vec!["mail", "old"]);
+        assert_eq!(created[0].target, DomainTarget::Port(8000));
+        assert_eq!(created[2].target, DomainTarget::Instance(db.id));
+        assert!(created[0].force_https);
+        assert_eq!(config.domains.len(), 5);
+
+        // Renaming onto an existing subdomain fails
+        let rename = vec![DomainConflictResolution::RenameDomain {
+            subdomain: "vite".to_string(),
+            new_subdomain: "api".to_string(),
+        }];
+        let import = export_of(vec![exported(
+            "vite",
+            ExportedDomainTarget::Port { port: 5173 },
+        )]);
+        assert!(apply_import(&mut config, &import, &rename).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! SaaS Emulation
+//!
+//! One-click presets that point the hard-coded hosts of popular services at
+//! a local instance, so an app talking to `s3.amazonaws.com` or
+//! `smtp.mailtrap.io` can be tested without code changes.
+//!
+//! An enabled preset makes the DNS server answer its hosts (and their
+//! subdomains) with localhost, has the system resolver send each host to our
+//! DNS server, and, for HTTP APIs, routes the hosts through the proxy to the
+//! instance. SMTP presets only need the DNS part: the app connects straight to
+//! the instance's SMTP port.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{Config, Instance, ServiceType};
+use crate::platform;
+use crate::services::mailpit::MailpitService;
+
+/// A service whose hosts can be emulated locally
+#[derive(Debug, Clone, Serialize)]
+pub struct EmulationPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Service the hosts are pointed at
+    pub service_type: ServiceType,
+    pub hosts: &'static [&'static str],
+    /// Route the hosts through the proxy (HTTP APIs)
+    pub proxied: bool,
+    /// What the app still has to configure
+    pub note: &'static str,
+}
+
+/// Available presets, in display order
+pub const PRESETS: &[EmulationPreset] = &[
+    EmulationPreset {
+        id: "aws-s3",
+        name: "Amazon S3",
+        service_type: ServiceType::MinIO,
+        hosts: &["s3.amazonaws.com", "s3.us-east-1.amazonaws.com"],
+        proxied: true,
+        note: "Use path-style requests and the MinIO instance's access keys.",
+    },
+    EmulationPreset {
+        id: "mailtrap",
+        name: "Mailtrap SMTP",
+        service_type: ServiceType::Mailpit,
+        hosts: &[
+            "smtp.mailtrap.io",
+            "sandbox.smtp.mailtrap.io",
+            "live.smtp.mailtrap.io",
+        ],
+        proxied: false,
+        note: "Set the SMTP port to Mailpit's; any credentials are accepted.",
+    },
+    EmulationPreset {
+        id: "mailgun-smtp",
+        name: "Mailgun SMTP",
+        service_type: ServiceType::Mailpit,
+        hosts: &["smtp.mailgun.org", "smtp.eu.mailgun.org"],
+        proxied: false,
+        note: "Set the SMTP port to Mailpit's; any credentials are accepted.",
+    },
+    EmulationPreset {
+        id: "sendgrid-smtp",
+        name: "SendGrid SMTP",
+        service_type: ServiceType::Mailpit,
+        hosts: &["smtp.sendgrid.net"],
+        proxied: false,
+        note: "Set the SMTP port to Mailpit's; any credentials are accepted.",
+    },
+    EmulationPreset {
+        id: "ses-smtp",
+        name: "Amazon SES SMTP",
+        service_type: ServiceType::Mailpit,
+        hosts: &[
+            "email-smtp.us-east-1.amazonaws.com",
+            "email-smtp.eu-west-1.amazonaws.com",
+        ],
+        proxied: false,
+        note: "Set the SMTP port to Mailpit's; any credentials are accepted.",
+    },
+];
+
+/// An enabled preset, stored in the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Emulation {
+    /// Preset ID, e.g. "aws-s3"
+    pub preset: String,
+    /// Instance the preset's hosts are pointed at
+    pub instance_id: Uuid,
+}
+
+/// A proxy route of an enabled preset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmulationRoute {
+    pub host: String,
+    pub port: u16,
+    /// ID the route is registered under
+    pub route_id: String,
+}
+
+/// A preset and whether and where it's enabled
+#[derive(Debug, Clone, Serialize)]
+pub struct EmulationStatus {
+    pub preset: EmulationPreset,
+    pub enabled: bool,
+    pub instance_id: Option<Uuid>,
+    pub instance_name: Option<String>,
+    /// Port the app reaches the instance on
+    pub port: Option<u16>,
+    /// Every host has its resolver file
+    pub resolver_installed: bool,
+}
+
+/// Find a preset by ID
+pub fn find_preset(id: &str) -> Result<&'static EmulationPreset, String> {
+    PRESETS
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Unknown emulation preset '{}'", id))
+}
+
+/// Port of the instance that serves the preset's protocol
+fn target_port(preset: &EmulationPreset, instance: &Instance) -> u16 {
+    match preset.service_type {
+        ServiceType::Mailpit => MailpitService::smtp_port(instance),
+        _ => instance.port,
+    }
+}
+
+/// Enabled presets whose instance still exists
+fn active(config: &Config) -> impl Iterator<Item = (&'static EmulationPreset, &Instance)> {
+    config.emulations.iter().filter_map(|emulation| {
+        let preset = find_preset(&emulation.preset).ok()?;
+        let instance = config
+            .instances
+            .iter()
+            .find(|i| i.id == emulation.instance_id)?;
+        Some((preset, instance))
+    })
+}
+
+/// Hosts the DNS server answers with localhost
+pub fn emulated_hosts(config: &Config) -> Vec<String> {
+    active(config)
+        .flat_map(|(preset, _)| preset.hosts.iter().map(|h| h.to_string()))
+        .collect()
+}
+
+/// Proxy routes of the enabled HTTP presets
+pub fn proxy_routes(config: &Config) -> Vec<EmulationRoute> {
+    active(config)
+        .filter(|(preset, _)| preset.proxied)
+        .flat_map(|(preset, instance)| {
+            preset.hosts.iter().map(move |host| EmulationRoute {
+                host: host.to_string(),
+                port: instance.port,
+                route_id: format!("emulation-{}", preset.id),
+            })
+        })
+        .collect()
+}
+
+/// Instance to point a preset at: the given one, or the first of its service
+pub fn pick_instance(
+    config: &Config,
+    preset: &EmulationPreset,
+    instance_id: Option<Uuid>,
+) -> Result<Uuid, String> {
+    let mut candidates = config
+        .instances
+        .iter()
+        .filter(|i| i.service_type == preset.service_type);
+
+    let instance = match instance_id {
+        Some(id) => candidates.find(|i| i.id == id).ok_or_else(|| {
+            format!(
+                "{} needs a {} instance",
+                preset.name,
+                preset.service_type.display_name()
+            )
+        })?,
+        None => candidates.next().ok_or_else(|| {
+            format!(
+                "Create a {} instance to emulate {}",
+                preset.service_type.display_name(),
+                preset.name
+            )
+        })?,
+    };
+    Ok(instance.id)
+}
+
+/// Every preset with its state
+pub fn statuses(config: &Config) -> Vec<EmulationStatus> {
+    PRESETS
+        .iter()
+        .map(|preset| {
+            let emulation = config.emulations.iter().find(|e| e.preset == preset.id);
+            let instance =
+                emulation.and_then(|e| config.instances.iter().find(|i| i.id == e.instance_id));
+            EmulationStatus {
+                preset: preset.clone(),
+                enabled: emulation.is_some(),
+                instance_id: emulation.map(|e| e.instance_id),
+                instance_name: instance.map(|i| i.name.clone()),
+                port: instance.map(|i| target_port(preset, i)),
+                resolver_installed: preset
+                    .hosts
+                    .iter()
+                    .all(|h| platform::dns_resolver().is_installed(h)),
+            }
+        })
+        .collect()
+}
+
+/// Install the resolver files sending the preset's hosts to our DNS server
+/// (requires admin privileges)
+pub fn install_resolvers(preset: &EmulationPreset, dns_port: u16) -> Result<(), String> {
+    for host in preset.hosts {
+        platform::dns_resolver().install(host, dns_port)?;
+    }
+    platform::dns_resolver().flush_cache()
+}
+
+/// Remove the preset's resolver files (requires admin privileges)
+pub fn uninstall_resolvers(preset: &EmulationPreset) -> Result<(), String> {
+    for host in preset.hosts {
+        platform::dns_resolver().uninstall(host)?;
+    }
+    platform::dns_resolver().flush_cache()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{test_config_with_instances, test_instance};
+
+    #[test]
+    fn test_presets_are_unique() {
+        for (i, preset) in PRESETS.iter().enumerate() {
+            assert!(PRESETS[i + 1..].iter().all(|p| p.id != preset.id));
+            assert!(!preset.hosts.is_empty());
+        }
+        assert!(find_preset("aws-s3").is_ok());
+        assert!(find_preset("nope").is_err());
+    }
+
+    #[test]
+    fn test_active_presets_need_their_instance() {
+        let s3 = test_instance("s3", 9000, ServiceType::MinIO);
+        let mail = test_instance("mail", 8025, ServiceType::Mailpit);
+        let mut config = test_config_with_instances(vec![s3.clone(), mail.clone()]);
+        config.emulations = vec![
+            Emulation {
+                preset: "aws-s3".to_string(),
+                instance_id: s3.id,
+            },
+            Emulation {
+                preset: "mailtrap".to_string(),
+                instance_id: mail.id,
+            },
+            Emulation {
+                preset: "sendgrid-smtp".to_string(),
+                instance_id: Uuid::new_v4(),
+            },
+        ];
+
+        let hosts = emulated_hosts(&config);
+        assert!(hosts.contains(&"s3.amazonaws.com".to_string()));
+        assert!(hosts.contains(&"smtp.mailtrap.io".to_string()));
+        assert!(!hosts.contains(&"smtp.sendgrid.net".to_string()));
+
+        let routes = proxy_routes(&config);
+        assert_eq!(routes.len(), 2);
+        assert!(routes
+            .iter()
+            .all(|r| r.port == 9000 && r.route_id == "emulation-aws-s3"));
+
+        let status = statuses(&config);
+        let mailtrap = status.iter().find(|s| s.preset.id == "mailtrap").unwrap();
+        assert!(mailtrap.enabled);
+        assert_eq!(mailtrap.port, Some(1025));
+    }
+
+    #[test]
+    fn test_pick_instance() {
+        let s3 = test_instance("s3", 9000, ServiceType::MinIO);
+        let mail = test_instance("mail", 8025, ServiceType::Mailpit);
+        let config = test_config_with_instances(vec![mail.clone(), s3.clone()]);
+        let preset = find_preset("aws-s3").unwrap();
+
+        assert_eq!(pick_instance(&config, preset, None), Ok(s3.id));
+        assert_eq!(pick_instance(&config, preset, Some(s3.id)), Ok(s3.id));
+        assert!(pick_instance(&config, preset, Some(mail.id)).is_err());
+
+        let empty = test_config_with_instances(vec![]);
+        assert!(pick_instance(&empty, preset, None).is_err());
+    }
+}
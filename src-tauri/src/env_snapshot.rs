@@ -0,0 +1,404 @@
+//! Environment snapshots across app upgrades
+//!
+//! When the app, `burd daemon` or the CLI starts with a different version
+//! than Burd last ran with, it first copies config.json into `env-snapshots/<id>/` in the app directory,
+//! with a `manifest.json` listing the installed service binaries, before the
+//! new version migrates or rewrites anything. `burd rollback-env` puts such a
+//! config back, so a regression in a new release can be undone by
+//! reinstalling the previous release and rolling back its environment.
+//!
+//! The version the app last ran with is kept in `app-version`, outside
+//! config.json, so restoring a config doesn't reset it. A rollback snapshots
+//! the config it replaces too, which makes it reversible. Only the newest
+//! snapshots are kept.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::ConfigStore;
+
+/// Snapshot directory, next to config.json
+const SNAPSHOTS_DIR: &str = "env-snapshots";
+
+/// Version the app last ran with, next to config.json
+const VERSION_FILE: &str = "app-version";
+
+/// The copied config inside each snapshot
+const CONFIG_FILE: &str = "config.json";
+
+/// Metadata file inside each snapshot, written last
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Snapshots kept; older ones are removed
+const KEEP_SNAPSHOTS: usize = 10;
+
+/// Why a snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotKind {
+    /// The app started with a new version
+    Upgrade,
+    /// The config was replaced by `burd rollback-env`
+    Rollback,
+}
+
+/// A service binary recorded in a snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledBinary {
+    pub service: String,
+    pub version: String,
+    pub path: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// A snapshot's manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvSnapshot {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub kind: SnapshotKind,
+    /// Burd version the config belongs to (None when the last run predates
+    /// these snapshots)
+    pub burd_version: Option<String>,
+    pub reason: String,
+    pub binaries: Vec<InstalledBinary>,
+}
+
+/// Outcome of a rollback
+#[derive(Debug, Clone)]
+pub struct Rollback {
+    pub restored: EnvSnapshot,
+    /// Snapshot of the config that was replaced
+    pub replaced: EnvSnapshot,
+    /// Binaries of the restored config that are no longer on disk
+    pub missing_binaries: Vec<InstalledBinary>,
+}
+
+fn snapshots_dir(store: &ConfigStore) -> PathBuf {
+    store.path().with_file_name(SNAPSHOTS_DIR)
+}
+
+/// Binaries listed in a config
+///
+/// Read from the raw JSON, so configs of other Burd versions (including the
+/// old one-version-per-service format) are understood.
+fn installed_binaries(config_json: &str) -> Vec<InstalledBinary> {
+    let Ok(raw) = serde_json::from_str::<serde_json::Value>(config_json) else {
+        return Vec::new();
+    };
+    let Some(services) = raw.get("binaries").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+
+    let entry = |service: &str, version: &str, info: &serde_json::Value| {
+        Some(InstalledBinary {
+            service: service.to_string(),
+            version: version.to_string(),
+            path: info.get("path")?.as_str()?.to_string(),
+            sha256: info
+                .get("sha256")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        })
+    };
+
+    let mut binaries: Vec<InstalledBinary> = services
+        .iter()
+        .flat_map(|(service, versions)| {
+            if versions.get("path").is_some() {
+                let version = versions.get("version").and_then(|v| v.as_str());
+                return entry(service, version.unwrap_or("unknown"), versions)
+                    .into_iter()
+                    .collect::<Vec<_>>();
+            }
+            versions
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(version, info)| entry(service, version, info))
+                .collect()
+        })
+        .collect();
+    binaries.sort_by(|a, b| (&a.service, &a.version).cmp(&(&b.service, &b.version)));
+    binaries
+}
+
+/// Write a snapshot of `config_json`
+fn write_snapshot(
+    store: &ConfigStore,
+    config_json: &str,
+    kind: SnapshotKind,
+    burd_version: Option<&str>,
+    reason: String,
+) -> Result<EnvSnapshot, String> {
+    let created_at = Utc::now();
+    let root = snapshots_dir(store);
+    let base = created_at.format("%Y%m%d-%H%M%S-%3f").to_string();
+    let mut id = base.clone();
+    let mut n = 1;
+    while root.join(&id).exists() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let snapshot = EnvSnapshot {
+        id,
+        created_at,
+        kind,
+        burd_version: burd_version.map(str::to_string),
+        reason,
+        binaries: installed_binaries(config_json),
+    };
+
+    let dir = root.join(&snapshot.id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+    fs::write(dir.join(CONFIG_FILE), config_json)
+        .map_err(|e| format!("Failed to write snapshot config: {}", e))?;
+    let manifest = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot manifest: {}", e))?;
+    fs::write(dir.join(MANIFEST_FILE), manifest)
+        .map_err(|e| format!("Failed to write snapshot manifest: {}", e))?;
+
+    prune(&root, KEEP_SNAPSHOTS);
+    Ok(snapshot)
+}
+
+fn read_config(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read config: {}", e))
+}
+
+fn list_in(root: &Path) -> Vec<EnvSnapshot> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    // Without a manifest the snapshot wasn't finished
+    let mut snapshots: Vec<EnvSnapshot> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join(MANIFEST_FILE)).ok())
+        .filter_map(|manifest| serde_json::from_str(&manifest).ok())
+        .collect();
+    snapshots.sort_by(|a, b| (b.created_at, &b.id).cmp(&(a.created_at, &a.id)));
+    snapshots
+}
+
+/// Remove the oldest snapshots beyond `keep`
+fn prune(root: &Path, keep: usize) {
+    for snapshot in list_in(root).into_iter().skip(keep) {
+        let _ = fs::remove_dir_all(root.join(&snapshot.id));
+    }
+}
+
+/// Snapshots, newest first
+pub fn list(store: &ConfigStore) -> Vec<EnvSnapshot> {
+    list_in(&snapshots_dir(store))
+}
+
+/// Snapshot the config if the app runs a different version than last time
+///
+/// Call before the config is loaded, so the snapshot holds the config as
+/// the previous version left it. Returns the snapshot if one was taken.
+pub fn snapshot_on_upgrade(
+    store: &ConfigStore,
+    current_version: &str,
+) -> Result<Option<EnvSnapshot>, String> {
+    let version_file = store.path().with_file_name(VERSION_FILE);
+    let last_version = fs::read_to_string(&version_file)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if last_version.as_deref() == Some(current_version) {
+        return Ok(None);
+    }
+
+    // A fresh install has nothing to snapshot
+    let snapshot = if store.path().exists() {
+        let reason = match &last_version {
+            Some(last) => format!("Upgrade from Burd {} to {}", last, current_version),
+            None => format!("First start of Burd {}", current_version),
+        };
+        let config_json = read_config(store.path())?;
+        Some(write_snapshot(
+            store,
+            &config_json,
+            SnapshotKind::Upgrade,
+            last_version.as_deref(),
+            reason,
+        )?)
+    } else {
+        None
+    };
+
+    fs::write(&version_file, current_version)
+        .map_err(|e| format!("Failed to record the app version: {}", e))?;
+    Ok(snapshot)
+}
+
+/// Snapshot the config if this is the first start of a new version
+///
+/// Shared by the app, the daemon and the CLI, since any of them may be the
+/// first to run after an upgrade. A failure is reported but doesn't stop
+/// the start.
+pub fn snapshot_on_startup(store: &ConfigStore) {
+    if let Err(e) = snapshot_on_upgrade(store, env!("CARGO_PKG_VERSION")) {
+        eprintln!("Failed to snapshot the environment before upgrading: {}", e);
+    }
+}
+
+/// Put a snapshot's config back
+///
+/// `id` defaults to the newest upgrade snapshot. The config being replaced is
+/// snapshotted first.
+pub fn rollback(
+    store: &ConfigStore,
+    id: Option<&str>,
+    current_version: &str,
+) -> Result<Rollback, String> {
+    let root = snapshots_dir(store);
+    let snapshots = list_in(&root);
+    let restored = match id {
+        Some(id) => snapshots.into_iter().find(|s| s.id == id),
+        None => snapshots
+            .into_iter()
+            .find(|s| s.kind == SnapshotKind::Upgrade),
+    }
+    .ok_or_else(|| match id {
+        Some(id) => format!("Environment snapshot '{}' not found", id),
+        None => "No environment snapshot from an upgrade yet".to_string(),
+    })?;
+    // Read before snapshotting the current config, which may prune this one
+    let restored_json = read_config(&root.join(&restored.id).join(CONFIG_FILE))?;
+
+    let _lock = store.lock_file()?;
+    let current_json = read_config(store.path())?;
+    let replaced = write_snapshot(
+        store,
+        &current_json,
+        SnapshotKind::Rollback,
+        Some(current_version),
+        format!("Before rolling back to {}", restored.id),
+    )?;
+
+    let temp_path = store.path().with_extension("json.tmp");
+    fs::write(&temp_path, &restored_json)
+        .map_err(|e| format!("Failed to write temp config: {}", e))?;
+    fs::rename(&temp_path, store.path()).map_err(|e| format!("Failed to rename config: {}", e))?;
+
+    let missing_binaries = restored
+        .binaries
+        .iter()
+        .filter(|b| !Path::new(&b.path).exists())
+        .cloned()
+        .collect();
+    Ok(Rollback {
+        restored,
+        replaced,
+        missing_binaries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(dir: &Path, config: &str) -> ConfigStore {
+        let path = dir.join("config.json");
+        fs::write(&path, config).unwrap();
+        ConfigStore::at(path)
+    }
+
+    #[test]
+    fn test_snapshot_only_on_version_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), r#"{"tld":"test"}"#);
+
+        let first = snapshot_on_upgrade(&store, "0.8.0").unwrap().unwrap();
+        assert_eq!(first.burd_version, None);
+        assert!(snapshot_on_upgrade(&store, "0.8.0").unwrap().is_none());
+
+        let upgrade = snapshot_on_upgrade(&store, "0.9.0").unwrap().unwrap();
+        assert_eq!(upgrade.burd_version.as_deref(), Some("0.8.0"));
+        assert_eq!(upgrade.kind, SnapshotKind::Upgrade);
+        assert_eq!(list(&store).len(), 2);
+        assert_eq!(list(&store)[0].id, upgrade.id);
+    }
+
+    #[test]
+    fn test_fresh_install_takes_no_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ConfigStore::at(dir.path().join("config.json"));
+
+        assert!(snapshot_on_upgrade(&store, "0.9.0").unwrap().is_none());
+        assert!(list(&store).is_empty());
+        assert!(snapshot_on_upgrade(&store, "0.9.0").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_manifest_lists_binaries() {
+        let config = r#"{
+            "binaries": {
+                "redis": {
+                    "7.2.4": { "version": "7.2.4", "path": "/bin/redis/7.2.4", "sha256": "ab" },
+                    "7.4.0": { "version": "7.4.0", "path": "/bin/redis/7.4.0" }
+                },
+                "meilisearch": { "version": "1.6.0", "path": "/bin/meilisearch" }
+            }
+        }"#;
+
+        let binaries = installed_binaries(config);
+        let listed: Vec<(&str, &str)> = binaries
+            .iter()
+            .map(|b| (b.service.as_str(), b.version.as_str()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("meilisearch", "1.6.0"),
+                ("redis", "7.2.4"),
+                ("redis", "7.4.0")
+            ]
+        );
+        assert_eq!(binaries[1].sha256.as_deref(), Some("ab"));
+    }
+
+    #[test]
+    fn test_rollback_restores_and_is_reversible() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), r#"{"tld":"old"}"#);
+        snapshot_on_upgrade(&store, "0.8.0").unwrap();
+        snapshot_on_upgrade(&store, "0.9.0").unwrap();
+        fs::write(store.path(), r#"{"tld":"new"}"#).unwrap();
+
+        let result = rollback(&store, None, "0.9.0").unwrap();
+        assert_eq!(result.restored.burd_version.as_deref(), Some("0.8.0"));
+        assert_eq!(
+            fs::read_to_string(store.path()).unwrap(),
+            r#"{"tld":"old"}"#
+        );
+        // The version file isn't part of a rollback
+        assert!(snapshot_on_upgrade(&store, "0.9.0").unwrap().is_none());
+
+        // Undo it
+        rollback(&store, Some(&result.replaced.id), "0.9.0").unwrap();
+        assert_eq!(
+            fs::read_to_string(store.path()).unwrap(),
+            r#"{"tld":"new"}"#
+        );
+    }
+
+    #[test]
+    fn test_old_snapshots_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), "{}");
+        for minor in 0..KEEP_SNAPSHOTS + 3 {
+            snapshot_on_upgrade(&store, &format!("0.{}.0", minor)).unwrap();
+        }
+
+        let snapshots = list(&store);
+        assert_eq!(snapshots.len(), KEEP_SNAPSHOTS);
+        let newest = format!("0.{}.0", KEEP_SNAPSHOTS + 1);
+        assert_eq!(snapshots[0].burd_version.as_deref(), Some(newest.as_str()));
+    }
+}
@@ -0,0 +1,426 @@
+//! .env sync after port and domain changes
+//!
+//! When an instance moves to a new port or a site gets a new domain, linked
+//! projects keep pointing at the old value until someone re-runs
+//! `burd env check`. This module finds the `.env` keys that still reference
+//! the old value and proposes (or applies) the update.
+//!
+//! Only values that point at the old port/host are touched, so projects using
+//! a different instance of the same service are left alone.
+
+use crate::analyzer::{
+    detect_project_type, extract_database_config, parse_env_file, update_env_value, ProjectType,
+};
+use crate::config::{Config, Instance, ServiceType};
+use crate::park;
+use crate::services::mailpit::MailpitService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A proposed change to one key of a project's .env file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvChange {
+    /// Project directory containing the .env file
+    pub project: PathBuf,
+    pub key: String,
+    pub current: String,
+    pub suggested: String,
+}
+
+impl EnvChange {
+    pub fn env_file(&self) -> PathBuf {
+        self.project.join(".env")
+    }
+}
+
+/// .env keys that carry a service's port (as a bare port, host:port, or URL)
+fn port_keys(service_type: ServiceType) -> &'static [&'static str] {
+    match service_type {
+        ServiceType::MariaDB | ServiceType::MySQL | ServiceType::PostgreSQL => {
+            &["DB_PORT", "DB_HOST", "DATABASE_URL"]
+        }
+        ServiceType::Redis | ServiceType::Valkey => &["REDIS_PORT", "REDIS_HOST", "REDIS_URL"],
+        ServiceType::Memcached => &["MEMCACHED_PORT", "MEMCACHED_HOST"],
+        ServiceType::Mailpit => &["MAIL_PORT", "MAIL_HOST"],
+        ServiceType::Meilisearch => &["MEILISEARCH_HOST"],
+        ServiceType::Typesense => &["TYPESENSE_PORT", "TYPESENSE_HOST"],
+        ServiceType::MinIO => &["AWS_ENDPOINT", "AWS_URL"],
+        ServiceType::Beanstalkd => &["BEANSTALKD_PORT", "BEANSTALKD_QUEUE_HOST"],
+        ServiceType::RabbitMQ => &["RABBITMQ_PORT", "RABBITMQ_URL"],
+        ServiceType::OpenSearch => &["ELASTIC_HOST", "ELASTICSEARCH_PORT", "OPENSEARCH_HOST"],
+        ServiceType::FrankenPHP | ServiceType::Bun => SITE_URL_KEYS,
+        _ => &[],
+    }
+}
+
+/// .env keys that carry a site's base URL
+const SITE_URL_KEYS: &[&str] = &["APP_URL", "ASSET_URL", "WP_HOME", "WP_SITEURL"];
+
+/// The port clients connect to (Mailpit's SMTP port rather than its UI port)
+fn client_port(instance: &Instance) -> u16 {
+    match instance.service_type {
+        ServiceType::Mailpit => MailpitService::smtp_port(instance),
+        _ => instance.port,
+    }
+}
+
+fn is_local_host(host: &str) -> bool {
+    matches!(host, "127.0.0.1" | "localhost" | "::1" | "[::1]")
+}
+
+/// Rewrite a bare port, local `host:port`, or local URL from `old` to `new`
+fn rewrite_port(value: &str, old: u16, new: u16) -> Option<String> {
+    let old_str = old.to_string();
+
+    if value == old_str {
+        return Some(new.to_string());
+    }
+
+    if !value.contains("://") {
+        let (host, port) = value.rsplit_once(':')?;
+        return (is_local_host(host) && port == old_str).then(|| format!("{}:{}", host, new));
+    }
+
+    let url = url::Url::parse(value).ok()?;
+    if url.port() != Some(old) || !url.host_str().map(is_local_host).unwrap_or(false) {
+        return None;
+    }
+
+    // Swap the port in place; re-serializing the URL would drop an empty password
+    let start = value.find("://")? + 3;
+    let end = value[start..]
+        .find(['/', '?', '#'])
+        .map_or(value.len(), |i| start + i);
+    let before_port = value[..end].strip_suffix(&format!(":{}", old))?;
+    Some(format!("{}:{}{}", before_port, new, &value[end..]))
+}
+
+/// Rewrite a URL whose host is `old_host` to use `new_host`
+fn rewrite_host(value: &str, old_host: &str, new_host: &str) -> Option<String> {
+    let mut url = url::Url::parse(value).ok()?;
+    if !url.host_str()?.eq_ignore_ascii_case(old_host) {
+        return None;
+    }
+    url.set_host(Some(new_host)).ok()?;
+    Some(preserve_trailing_slash(value, url.as_str()))
+}
+
+/// `Url` always serializes a bare origin with a trailing slash; keep the original shape
+fn preserve_trailing_slash(original: &str, rewritten: &str) -> String {
+    if !original.ends_with('/') {
+        rewritten.trim_end_matches('/').to_string()
+    } else {
+        rewritten.to_string()
+    }
+}
+
+/// Directories of projects linked to Burd that have a .env file
+///
+/// Covers instance document roots (`burd init`/`burd link`), Bun working
+/// directories, and projects inside parked directories. A document root like
+/// `public/` or `web/` resolves to the parent that holds the .env.
+pub fn linked_projects(config: &Config) -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    for instance in &config.instances {
+        for key in ["document_root", "working_directory"] {
+            if let Some(dir) = instance.config.get(key).and_then(|v| v.as_str()) {
+                candidates.push(PathBuf::from(dir));
+            }
+        }
+    }
+
+    for parked in &config.parked_directories {
+        if let Ok(projects) = park::scan_directory(Path::new(&parked.path), parked.depth) {
+            candidates.extend(projects.into_iter().map(|p| p.path));
+        }
+    }
+
+    let mut projects: Vec<PathBuf> = Vec::new();
+    for dir in candidates {
+        let project = if dir.join(".env").is_file() {
+            Some(dir)
+        } else {
+            dir.parent()
+                .filter(|p| p.join(".env").is_file())
+                .map(Path::to_path_buf)
+        };
+        if let Some(project) = project {
+            if !projects.contains(&project) {
+                projects.push(project);
+            }
+        }
+    }
+    projects
+}
+
+/// Whether a project's database connection matches the service type
+fn database_matches(
+    service_type: ServiceType,
+    project_type: &ProjectType,
+    env: &HashMap<String, String>,
+) -> bool {
+    match extract_database_config(project_type, env) {
+        Some(db) => match service_type {
+            ServiceType::PostgreSQL => db.is_postgres(),
+            _ => db.is_mysql(),
+        },
+        None => false,
+    }
+}
+
+/// Changes to one project's .env for a port move
+fn plan_port_in_project(
+    project: &Path,
+    env: &HashMap<String, String>,
+    service_type: ServiceType,
+    old: u16,
+    new: u16,
+) -> Vec<EnvChange> {
+    let keys = port_keys(service_type);
+    if keys.contains(&"DB_PORT")
+        && !database_matches(service_type, &detect_project_type(project), env)
+    {
+        return Vec::new();
+    }
+
+    keys.iter()
+        .filter_map(|key| {
+            let current = env.get(*key)?;
+            let suggested = rewrite_port(current, old, new)?;
+            Some(EnvChange {
+                project: project.to_path_buf(),
+                key: key.to_string(),
+                current: current.clone(),
+                suggested,
+            })
+        })
+        .collect()
+}
+
+/// Changes to one project's .env for a site host move
+fn plan_host_in_project(
+    project: &Path,
+    env: &HashMap<String, String>,
+    old_host: &str,
+    new_host: &str,
+) -> Vec<EnvChange> {
+    SITE_URL_KEYS
+        .iter()
+        .filter_map(|key| {
+            let current = env.get(*key)?;
+            let suggested = rewrite_host(current, old_host, new_host)?;
+            Some(EnvChange {
+                project: project.to_path_buf(),
+                key: key.to_string(),
+                current: current.clone(),
+                suggested,
+            })
+        })
+        .collect()
+}
+
+/// Plan .env updates for linked projects after an instance changed
+///
+/// Compares the client port and (with `tld`) the routed domain before and
+/// after the change.
+pub fn plan_instance_change(
+    config: &Config,
+    before: &Instance,
+    after: &Instance,
+    tld: &str,
+) -> Vec<EnvChange> {
+    let old_port = client_port(before);
+    let new_port = client_port(after);
+
+    let old_host = before
+        .domain_enabled
+        .then(|| before.full_domain(tld, &config.slugs));
+    let new_host = after
+        .domain_enabled
+        .then(|| after.full_domain(tld, &config.slugs));
+
+    let mut changes = Vec::new();
+    for project in linked_projects(config) {
+        let Some(env) = parse_env_file(&project.join(".env")) else {
+            continue;
+        };
+        if old_port != new_port {
+            changes.extend(plan_port_in_project(
+                &project,
+                &env,
+                after.service_type,
+                old_port,
+                new_port,
+            ));
+        }
+        if let (Some(old_host), Some(new_host)) = (&old_host, &new_host) {
+            if old_host != new_host {
+                changes.extend(plan_host_in_project(&project, &env, old_host, new_host));
+            }
+        }
+    }
+    changes
+}
+
+/// Plan .env updates for linked projects after a domain moved hosts
+pub fn plan_host_change(config: &Config, old_host: &str, new_host: &str) -> Vec<EnvChange> {
+    if old_host.eq_ignore_ascii_case(new_host) {
+        return Vec::new();
+    }
+
+    linked_projects(config)
+        .into_iter()
+        .filter_map(|project| {
+            let env = parse_env_file(&project.join(".env"))?;
+            Some(plan_host_in_project(&project, &env, old_host, new_host))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Plan .env updates for linked projects after the global TLD changed
+///
+/// Covers every configured domain and every instance with its own domain.
+pub fn plan_tld_change(config: &Config, old_tld: &str, new_tld: &str) -> Vec<EnvChange> {
+    if old_tld == new_tld {
+        return Vec::new();
+    }
+
+    let hosts: Vec<(String, String)> = config
+        .domains
+        .iter()
+        .map(|d| (d.full_domain(old_tld), d.full_domain(new_tld)))
+        .chain(
+            config
+                .instances
+                .iter()
+                .filter(|i| i.domain_enabled)
+                .map(|i| {
+                    (
+                        i.full_domain(old_tld, &config.slugs),
+                        i.full_domain(new_tld, &config.slugs),
+                    )
+                }),
+        )
+        .collect();
+
+    let mut changes = Vec::new();
+    for project in linked_projects(config) {
+        let Some(env) = parse_env_file(&project.join(".env")) else {
+            continue;
+        };
+        for (old_host, new_host) in &hosts {
+            for change in plan_host_in_project(&project, &env, old_host, new_host) {
+                // A domain and an instance can share a host
+                if !changes.contains(&change) {
+                    changes.push(change);
+                }
+            }
+        }
+    }
+    changes
+}
+
+/// Apply changes, returning how many keys were updated
+///
+/// Only writes to .env files of linked projects, and skips keys whose value
+/// changed since the plan was made.
+pub fn apply(config: &Config, changes: &[EnvChange]) -> Result<usize, String> {
+    let projects = linked_projects(config);
+    let mut applied = 0;
+
+    for change in changes {
+        if !projects.contains(&change.project) {
+            return Err(format!(
+                "{} is not a project linked to Burd",
+                change.project.display()
+            ));
+        }
+
+        let env_file = change.env_file();
+        let env = parse_env_file(&env_file)
+            .ok_or_else(|| format!("Failed to parse {}", env_file.display()))?;
+        if env.get(&change.key) != Some(&change.current) {
+            continue;
+        }
+
+        update_env_value(&env_file, &change.key, &change.suggested)?;
+        applied += 1;
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_port() {
+        assert_eq!(rewrite_port("3306", 3306, 3307), Some("3307".to_string()));
+        assert_eq!(
+            rewrite_port("127.0.0.1:3306", 3306, 3307),
+            Some("127.0.0.1:3307".to_string())
+        );
+        assert_eq!(
+            rewrite_port("http://127.0.0.1:7700", 7700, 7701),
+            Some("http://127.0.0.1:7701".to_string())
+        );
+        assert_eq!(
+            rewrite_port("mysql://root:@localhost:3306/app", 3306, 3307),
+            Some("mysql://root:@localhost:3307/app".to_string())
+        );
+        // Other ports and remote hosts are left alone
+        assert_eq!(rewrite_port("3308", 3306, 3307), None);
+        assert_eq!(rewrite_port("db.example.com:3306", 3306, 3307), None);
+        assert_eq!(
+            rewrite_port("http://search.example.com:7700", 7700, 7701),
+            None
+        );
+        assert_eq!(rewrite_port("127.0.0.1", 3306, 3307), None);
+    }
+
+    #[test]
+    fn test_rewrite_host() {
+        assert_eq!(
+            rewrite_host("https://shop.test", "shop.test", "store.test"),
+            Some("https://store.test".to_string())
+        );
+        assert_eq!(
+            rewrite_host("https://shop.test/", "shop.test", "store.test"),
+            Some("https://store.test/".to_string())
+        );
+        assert_eq!(
+            rewrite_host("https://shop.test/wp", "shop.test", "store.test"),
+            Some("https://store.test/wp".to_string())
+        );
+        assert_eq!(
+            rewrite_host("https://blog.test", "shop.test", "store.test"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plan_port_in_project_checks_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut env = HashMap::new();
+        env.insert("DB_CONNECTION".to_string(), "mysql".to_string());
+        env.insert("DB_HOST".to_string(), "127.0.0.1".to_string());
+        env.insert("DB_PORT".to_string(), "3306".to_string());
+        env.insert("DB_DATABASE".to_string(), "app".to_string());
+
+        // No artisan file, so this isn't a Laravel project and has no DB config
+        let changes = plan_port_in_project(dir.path(), &env, ServiceType::MariaDB, 3306, 3307);
+        assert!(changes.is_empty());
+
+        std::fs::write(dir.path().join("artisan"), "").unwrap();
+        let changes = plan_port_in_project(dir.path(), &env, ServiceType::MariaDB, 3306, 3307);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key, "DB_PORT");
+        assert_eq!(changes[0].suggested, "3307");
+
+        let changes = plan_port_in_project(dir.path(), &env, ServiceType::PostgreSQL, 3306, 3307);
+        assert!(changes.is_empty());
+    }
+}
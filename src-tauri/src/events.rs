@@ -0,0 +1,503 @@
+//! Event Stream
+//!
+//! An in-process broadcast of the things external clients want to react to:
+//! instance lifecycle changes, config changes, binary download progress, health transitions,
+//! first-start initialization progress, new instance log lines and requests served through the
+//! proxy. The HTTP API streams it over the `/events`
+//! WebSocket so dashboards and the MCP layer don't have to poll, and the GUI
+//! gets instance and config changes as Tauri events through `forward_changes`.
+//!
+//! Lifecycle and download events are published where they happen. Config
+//! changes are noticed by `watch_config`, so saves by the CLI (a separate
+//! process) are seen as well as the app's and the API's. Health and
+//! log lines have no single source, so `watch` polls running instances while
+//! anyone is subscribed. Publishing never blocks; a subscriber that falls
+//! behind skips the oldest events.
+
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, Debouncer};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::activity::ActivityKind;
+use crate::binary::DownloadProgress;
+use crate::commands::AppState;
+use crate::error::LockExt;
+use crate::lock;
+use crate::logs::{self, LogFileState};
+use crate::readiness;
+use crate::service_init::InitProgress;
+use crate::traffic::RequestRecord;
+
+/// Events buffered per subscriber before the oldest are skipped
+const CAPACITY: usize = 1024;
+
+/// How often running instances are checked while someone is subscribed
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Log lines published per instance per check; the rest are skipped
+const MAX_LOG_LINES: usize = 200;
+
+/// How long `forward_changes` waits for the rest of a burst of changes
+const FORWARD_DELAY: Duration = Duration::from_millis(250);
+
+/// Something that happened
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// An instance was started, stopped, crashed, restarted or edited
+    Instance {
+        instance_id: Uuid,
+        kind: ActivityKind,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        detail: Option<String>,
+    },
+    /// Progress of a service binary, PHP or Node download or install
+    Download(DownloadProgress),
+    /// Progress of a database's first-start initialization
+    Init(InitProgress),
+    /// An instance's running or healthy state changed
+    Health {
+        instance_id: Uuid,
+        running: bool,
+        /// None while stopped
+        healthy: Option<bool>,
+    },
+    /// A new line in an instance's log
+    Log { instance_id: Uuid, line: String },
+    /// A request served through the proxy (see [`crate::traffic`])
+    Request(RequestRecord),
+    /// config.json was saved, by any process
+    Config,
+}
+
+impl Event {
+    /// The `type` tag the event is serialized with
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Event::Instance { .. } => "instance",
+            Event::Download(_) => "download",
+            Event::Init(_) => "init",
+            Event::Health { .. } => "health",
+            Event::Log { .. } => "log",
+            Event::Request(_) => "request",
+            Event::Config => "config",
+        }
+    }
+
+    /// The instance the event is about, if any
+    pub fn instance_id(&self) -> Option<Uuid> {
+        match self {
+            Event::Instance { instance_id, .. }
+            | Event::Health { instance_id, .. }
+            | Event::Log { instance_id, .. } => Some(*instance_id),
+            Event::Init(progress) => Some(progress.instance_id),
+            Event::Download(_) | Event::Request(_) | Event::Config => None,
+        }
+    }
+
+    /// The domain the event is about, if any
+    pub fn domain(&self) -> Option<&str> {
+        match self {
+            Event::Request(request) => Some(&request.domain),
+            _ => None,
+        }
+    }
+}
+
+/// Which events a subscriber wants
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventFilter {
+    /// Comma-separated event types (instance, download, init, health, log, request, config); all if unset
+    #[serde(default)]
+    pub types: Option<String>,
+    /// Only events about this instance (download events always pass)
+    #[serde(default)]
+    pub instance: Option<Uuid>,
+    /// Only requests to this domain, e.g. `shop.test` (other events pass)
+    #[serde(default)]
+    pub domain: Option<String>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        let type_ok = self.types.as_deref().is_none_or(|types| {
+            types
+                .split(',')
+                .any(|t| t.trim().eq_ignore_ascii_case(event.kind()))
+        });
+        let instance_ok = match (self.instance, event.instance_id()) {
+            (Some(wanted), Some(id)) => wanted == id,
+            _ => true,
+        };
+        let domain_ok = match (&self.domain, event.domain()) {
+            (Some(wanted), Some(domain)) => wanted.eq_ignore_ascii_case(domain),
+            _ => true,
+        };
+        type_ok && instance_ok && domain_ok
+    }
+}
+
+static BUS: Lazy<broadcast::Sender<Event>> = Lazy::new(|| broadcast::channel(CAPACITY).0);
+
+/// Subscribers that don't keep `watch` running
+static PASSIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// Send an event to every current subscriber
+pub fn publish(event: Event) {
+    // Only fails when nobody is subscribed
+    let _ = BUS.send(event);
+}
+
+/// Receive events published from now on
+pub fn subscribe() -> broadcast::Receiver<Event> {
+    BUS.subscribe()
+}
+
+fn has_subscribers() -> bool {
+    BUS.receiver_count() > PASSIVE.load(Ordering::Relaxed)
+}
+
+/// The config watcher, kept for the life of the process
+static CONFIG_WATCHER: Lazy<Mutex<Option<Debouncer<RecommendedWatcher>>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Publish a `Config` event whenever config.json is saved
+///
+/// Saving renames a temp file over the config, so its directory is watched
+/// rather than the file. Both the API server and the GUI call this; the
+/// watcher is only started once per process.
+pub fn watch_config(config_path: &Path) -> Result<(), String> {
+    let mut watcher = lock!(CONFIG_WATCHER)?;
+    if watcher.is_some() {
+        return Ok(());
+    }
+
+    let dir = config_path
+        .parent()
+        .ok_or_else(|| "Config file has no parent directory".to_string())?;
+    let file_name = config_path.file_name().map(|name| name.to_os_string());
+
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(200),
+        move |res: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            if let Ok(events) = res {
+                if events
+                    .iter()
+                    .any(|e| e.path.file_name() == file_name.as_deref())
+                {
+                    publish(Event::Config);
+                }
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create config watcher: {}", e))?;
+
+    debouncer
+        .watcher()
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch config directory: {}", e))?;
+    *watcher = Some(debouncer);
+    Ok(())
+}
+
+/// Whether an event changes what the GUI shows: `Some(true)` for the
+/// config, `Some(false)` for instance state, `None` otherwise
+fn gui_change(event: &Event) -> Option<bool> {
+    match event {
+        Event::Config => Some(true),
+        Event::Instance { .. } => Some(false),
+        _ => None,
+    }
+}
+
+/// Call `on_change` for instance and config changes, wherever they were made
+///
+/// A burst of changes (a stack starting, several saves in a row) becomes one
+/// call, whose argument says whether the config changed. This subscription
+/// doesn't keep `watch` running.
+pub async fn forward_changes(on_change: impl Fn(bool)) {
+    PASSIVE.fetch_add(1, Ordering::Relaxed);
+    let mut receiver = subscribe();
+    loop {
+        let mut config = match receiver.recv().await {
+            Ok(event) => match gui_change(&event) {
+                Some(config) => config,
+                None => continue,
+            },
+            // Skipped events may have been anything
+            Err(RecvError::Lagged(_)) => true,
+            Err(RecvError::Closed) => break,
+        };
+
+        tokio::time::sleep(FORWARD_DELAY).await;
+        while let Ok(event) = receiver.try_recv() {
+            config |= gui_change(&event) == Some(true);
+        }
+        on_change(config);
+    }
+    PASSIVE.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Call `on_progress` for every init progress event
+///
+/// This subscription doesn't keep `watch` running.
+pub async fn forward_init(on_progress: impl Fn(InitProgress)) {
+    PASSIVE.fetch_add(1, Ordering::Relaxed);
+    let mut receiver = subscribe();
+    loop {
+        match receiver.recv().await {
+            Ok(Event::Init(progress)) => on_progress(progress),
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
+    PASSIVE.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Running and healthy state of an instance at one check
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Observed {
+    running: bool,
+    healthy: Option<bool>,
+}
+
+/// Health events for instances whose state changed since the last check
+///
+/// Instances seen for the first time are not reported; clients get the
+/// starting state from `GET /instances`.
+fn health_changes(
+    previous: &HashMap<Uuid, Observed>,
+    current: &HashMap<Uuid, Observed>,
+) -> Vec<Event> {
+    let mut changes: Vec<Event> = current
+        .iter()
+        .filter(|(id, now)| previous.get(id).is_some_and(|before| before != *now))
+        .map(|(id, now)| Event::Health {
+            instance_id: *id,
+            running: now.running,
+            healthy: now.healthy,
+        })
+        .collect();
+    changes.sort_by_key(|e| e.instance_id());
+    changes
+}
+
+/// Check every instance's state, publishing health transitions and new log lines
+async fn check(
+    state: &AppState,
+    previous: &mut HashMap<Uuid, Observed>,
+    log_state: &mut LogFileState,
+) -> Result<(), String> {
+    let config = lock!(state.config_store)?.load()?;
+    let running: Vec<(Uuid, bool)> = {
+        let process_manager = lock!(state.process_manager)?;
+        config
+            .instances
+            .iter()
+            .map(|i| (i.id, process_manager.get_status(i).running))
+            .collect()
+    };
+
+    let mut current = HashMap::new();
+    for (instance, (id, running)) in config.instances.iter().zip(running) {
+        let healthy = if running {
+            Some(readiness::is_healthy(instance.port, instance.service_type).await)
+        } else {
+            None
+        };
+        current.insert(id, Observed { running, healthy });
+
+        let Ok(path) = logs::get_instance_log_path(&id.to_string()) else {
+            continue;
+        };
+        let path = path.to_string_lossy().to_string();
+        if !previous.contains_key(&id) {
+            // Start at the end; only lines written from now on are new
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            log_state.set_position(&path, size);
+            continue;
+        }
+        let lines = logs::read_new_lines(&path, log_state).unwrap_or_default();
+        let skip = lines.len().saturating_sub(MAX_LOG_LINES);
+        for line in lines.into_iter().skip(skip) {
+            publish(Event::Log {
+                instance_id: id,
+                line,
+            });
+        }
+    }
+
+    for event in health_changes(previous, &current) {
+        publish(event);
+    }
+    *previous = current;
+    Ok(())
+}
+
+/// Watch instances for health transitions and log lines while anyone is subscribed
+pub async fn watch(state: AppState) {
+    let mut previous = HashMap::new();
+    let mut log_state = LogFileState::new();
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+        if !has_subscribers() {
+            // Start over once someone subscribes again
+            previous.clear();
+            log_state = LogFileState::new();
+            continue;
+        }
+        if let Err(e) = check(&state, &mut previous, &mut log_state).await {
+            eprintln!("Event watch failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observed(running: bool, healthy: Option<bool>) -> Observed {
+        Observed { running, healthy }
+    }
+
+    #[test]
+    fn test_health_changes_only_reports_transitions() {
+        let (stable, changed, new) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let previous = HashMap::from([
+            (stable, observed(true, Some(true))),
+            (changed, observed(true, Some(true))),
+        ]);
+        let current = HashMap::from([
+            (stable, observed(true, Some(true))),
+            (changed, observed(true, Some(false))),
+            (new, observed(false, None)),
+        ]);
+
+        assert_eq!(
+            health_changes(&previous, &current),
+            vec![Event::Health {
+                instance_id: changed,
+                running: true,
+                healthy: Some(false),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let id = Uuid::nil();
+        let event = Event::Log {
+            instance_id: id,
+            line: "ready".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "log", "instance_id": id, "line": "ready" })
+        );
+
+        let event = Event::Download(DownloadProgress {
+            service_type: "redis".to_string(),
+            downloaded: 50,
+            total: 100,
+            percentage: 50.0,
+            phase: "downloading".to_string(),
+        });
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "download");
+        assert_eq!(value["service_type"], "redis");
+        assert_eq!(value["type"], event.kind());
+    }
+
+    #[test]
+    fn test_config_event() {
+        assert_eq!(
+            serde_json::to_value(Event::Config).unwrap(),
+            serde_json::json!({ "type": "config" })
+        );
+        assert_eq!(gui_change(&Event::Config), Some(true));
+        assert_eq!(
+            gui_change(&Event::Instance {
+                instance_id: Uuid::nil(),
+                kind: ActivityKind::Started,
+                detail: None,
+            }),
+            Some(false)
+        );
+        assert_eq!(
+            gui_change(&Event::Log {
+                instance_id: Uuid::nil(),
+                line: String::new(),
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let id = Uuid::new_v4();
+        let log = Event::Log {
+            instance_id: id,
+            line: String::new(),
+        };
+        let download = Event::Download(DownloadProgress {
+            service_type: "caddy".to_string(),
+            downloaded: 0,
+            total: 0,
+            percentage: 0.0,
+            phase: "downloading".to_string(),
+        });
+
+        assert!(EventFilter::default().matches(&log));
+
+        let logs_only = EventFilter {
+            types: Some("health, LOG".to_string()),
+            instance: None,
+            domain: None,
+        };
+        assert!(logs_only.matches(&log));
+        assert!(!logs_only.matches(&download));
+
+        let other_instance = EventFilter {
+            types: None,
+            instance: Some(Uuid::new_v4()),
+            domain: None,
+        };
+        assert!(!other_instance.matches(&log));
+        assert!(other_instance.matches(&download));
+    }
+
+    #[test]
+    fn test_filter_by_domain() {
+        let request = Event::Request(RequestRecord {
+            domain: "shop.test".to_string(),
+            at: chrono::Utc::now(),
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            status: 200,
+            duration_ms: 3.0,
+            remote_ip: None,
+        });
+        assert_eq!(serde_json::to_value(&request).unwrap()["type"], "request");
+
+        let shop = EventFilter {
+            types: Some("request".to_string()),
+            instance: None,
+            domain: Some("Shop.test".to_string()),
+        };
+        assert!(shop.matches(&request));
+
+        let api = EventFilter {
+            domain: Some("api.test".to_string()),
+            ..shop
+        };
+        assert!(!api.matches(&request));
+    }
+}
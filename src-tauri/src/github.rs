@@ -0,0 +1,296 @@
+//! GitHub API access
+//!
+//! Requests are authenticated with the token from settings, or the
+//! `GITHUB_TOKEN` environment variable, when one is set. Responses are cached
+//! on disk for the configured TTL so that listing versions on a shared IP
+//! doesn't run into the anonymous rate limit (60 requests an hour). Expired
+//! entries are revalidated with their ETag, which GitHub doesn't count
+//! against the limit, and are still used when GitHub can't be reached.
+
+use crate::config::{get_app_dir, ConfigStore, GitHubSettings};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{HeaderMap, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+/// Environment variable used when no token is configured
+pub const TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+
+/// GitHub settings from the config, or the defaults if it can't be read
+fn settings() -> GitHubSettings {
+    ConfigStore::new()
+        .and_then(|store| store.load())
+        .map(|config| config.github)
+        .unwrap_or_default()
+}
+
+/// Configured token, falling back to the environment
+fn resolve_token(configured: Option<String>, env: Option<String>) -> Option<String> {
+    configured
+        .into_iter()
+        .chain(env)
+        .map(|token| token.trim().to_string())
+        .find(|token| !token.is_empty())
+}
+
+fn request(client: &Client, url: &str, token: Option<&str>) -> RequestBuilder {
+    let request = client
+        .get(url)
+        .header("User-Agent", "Burd-App")
+        .header("Accept", "application/vnd.github+json");
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// Explain a failed API response
+fn describe_error(status: StatusCode, headers: &HeaderMap, authenticated: bool) -> String {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if status == StatusCode::UNAUTHORIZED && authenticated {
+        return "GitHub rejected the token. Check it in Settings or GITHUB_TOKEN".to_string();
+    }
+
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && header("x-ratelimit-remaining") == Some("0"));
+    if !rate_limited {
+        return format!("GitHub API request failed with status {}", status);
+    }
+
+    let resets = header("x-ratelimit-reset")
+        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|at| {
+            format!(
+                " (resets at {})",
+                at.with_timezone(&chrono::Local).format("%H:%M")
+            )
+        })
+        .unwrap_or_default();
+    if authenticated {
+        format!("GitHub API rate limit reached{}", resets)
+    } else {
+        format!(
+            "GitHub API rate limit reached{}. Add a GitHub token in Settings or set {} to raise it",
+            resets, TOKEN_ENV_VAR
+        )
+    }
+}
+
+/// A cached API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    fetched_at: DateTime<Utc>,
+    #[serde(default)]
+    etag: Option<String>,
+    body: String,
+}
+
+impl CacheEntry {
+    /// Whether the entry can be used without asking GitHub (a TTL of 0 always asks)
+    fn is_fresh(&self, ttl_minutes: u32, now: DateTime<Utc>) -> bool {
+        now - self.fetched_at < Duration::minutes(i64::from(ttl_minutes))
+    }
+}
+
+/// On-disk cache of GitHub API responses, one file per URL
+pub struct ReleaseCache {
+    dir: PathBuf,
+}
+
+impl ReleaseCache {
+    /// The cache in the app data directory (cache/github/)
+    pub fn new() -> Result<Self, String> {
+        Ok(Self::at(get_app_dir()?.join("cache").join("github")))
+    }
+
+    fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let hash = Sha256::digest(url.as_bytes());
+        self.dir.join(format!("{:x}.json", hash))
+    }
+
+    fn read(&self, url: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.path(url)).ok()?;
+        serde_json::from_str::<CacheEntry>(&content)
+            .ok()
+            .filter(|entry| entry.url == url)
+    }
+
+    fn write(&self, entry: &CacheEntry) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create release cache directory: {}", e))?;
+        let content = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize cache entry: {}", e))?;
+        fs::write(self.path(&entry.url), content)
+            .map_err(|e| format!("Failed to write release cache: {}", e))
+    }
+
+    /// Remove every cached response, returning how many were removed
+    pub fn clear(&self) -> Result<usize, String> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in
+            fs::read_dir(&self.dir).map_err(|e| format!("Failed to read release cache: {}", e))?
+        {
+            let path = entry
+                .map_err(|e| format!("Failed to read release cache: {}", e))?
+                .path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// GET a GitHub API URL, answered from the cache while it is fresh
+///
+/// Returns the response body. A stale cached response is returned when
+/// GitHub is unreachable or refuses the request (e.g. rate limited).
+pub async fn get_cached(client: &Client, url: &str) -> Result<String, String> {
+    let settings = settings();
+    let token = resolve_token(settings.token, std::env::var(TOKEN_ENV_VAR).ok());
+    let cache = ReleaseCache::new().ok();
+    let cached = cache.as_ref().and_then(|cache| cache.read(url));
+
+    let now = Utc::now();
+    if let Some(entry) = &cached {
+        if entry.is_fresh(settings.cache_ttl_minutes, now) {
+            return Ok(entry.body.clone());
+        }
+    }
+
+    let mut req = request(client, url, token.as_deref());
+    if let Some(etag) = cached.as_ref().and_then(|entry| entry.etag.as_deref()) {
+        req = req.header(IF_NONE_MATCH, etag);
+    }
+
+    let fetched = match req.send().await {
+        Ok(response) if response.status() == StatusCode::NOT_MODIFIED => cached
+            .clone()
+            .map(|entry| (entry.etag, entry.body))
+            .ok_or_else(|| "GitHub returned 304 for an uncached request".to_string()),
+        Ok(response) if response.status().is_success() => {
+            let etag = response
+                .headers()
+                .get(ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string());
+            response
+                .text()
+                .await
+                .map(|body| (etag, body))
+                .map_err(|e| format!("Failed to read GitHub response: {}", e))
+        }
+        Ok(response) => Err(describe_error(
+            response.status(),
+            response.headers(),
+            token.is_some(),
+        )),
+        Err(e) => Err(format!("Failed to reach GitHub: {}", e)),
+    };
+
+    match (fetched, cached) {
+        (Ok((etag, body)), _) => {
+            if let Some(cache) = &cache {
+                let entry = CacheEntry {
+                    url: url.to_string(),
+                    fetched_at: now,
+                    etag,
+                    body: body.clone(),
+                };
+                if let Err(e) = cache.write(&entry) {
+                    eprintln!("Warning: {}", e);
+                }
+            }
+            Ok(body)
+        }
+        (Err(e), Some(stale)) => {
+            eprintln!("Warning: {}; using cached response for {}", e, url);
+            Ok(stale.body)
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, fetched_at: DateTime<Utc>) -> CacheEntry {
+        CacheEntry {
+            url: url.to_string(),
+            fetched_at,
+            etag: Some("\"abc\"".to_string()),
+            body: "[]".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_token_prefers_configured() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(resolve_token(some("cfg"), some("env")), some("cfg"));
+        assert_eq!(resolve_token(some("  "), some("env ")), some("env"));
+        assert_eq!(resolve_token(None, some("env")), some("env"));
+        assert_eq!(resolve_token(None, some("")), None);
+        assert_eq!(resolve_token(None, None), None);
+    }
+
+    #[test]
+    fn test_cache_entry_freshness() {
+        let now = Utc::now();
+        let entry = entry("https://api.github.com/x", now - Duration::minutes(30));
+        assert!(entry.is_fresh(60, now));
+        assert!(!entry.is_fresh(30, now));
+        assert!(!entry.is_fresh(0, now));
+    }
+
+    #[test]
+    fn test_cache_round_trip_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ReleaseCache::at(dir.path().join("github"));
+        let url = "https://api.github.com/repos/caddyserver/caddy/releases";
+
+        assert!(cache.read(url).is_none());
+        assert_eq!(cache.clear().unwrap(), 0);
+
+        cache.write(&entry(url, Utc::now())).unwrap();
+        let read = cache.read(url).unwrap();
+        assert_eq!(read.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(read.body, "[]");
+        assert!(cache.read("https://api.github.com/other").is_none());
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.read(url).is_none());
+    }
+
+    #[test]
+    fn test_describe_rate_limit() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+
+        let anonymous = describe_error(StatusCode::FORBIDDEN, &headers, false);
+        assert!(anonymous.starts_with("GitHub API rate limit reached"));
+        assert!(anonymous.contains(TOKEN_ENV_VAR));
+
+        let authenticated = describe_error(StatusCode::FORBIDDEN, &headers, true);
+        assert!(!authenticated.contains(TOKEN_ENV_VAR));
+
+        let other = describe_error(StatusCode::FORBIDDEN, &HeaderMap::new(), false);
+        assert!(other.contains("403"));
+    }
+}
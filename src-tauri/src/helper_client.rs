@@ -1,15 +1,18 @@
 //! Helper Client
 //!
 //! Communicates with the privileged helper tool via Unix domain socket.
+//! The helper is a macOS LaunchDaemon; on Linux the systemd units and
+//! resolver drop-ins are installed directly (see [`crate::platform`]).
 
 use crate::constants::{
     HELPER_BINARY_NAME, HELPER_INSTALL_PATH, HELPER_PLIST_PATH, HELPER_SOCKET_PATH,
     LAUNCH_DAEMONS_DIR, PRIVILEGED_HELPER_DIR, SYSTEM_LOGS_DIR,
 };
+use crate::platform::Os;
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Duration;
 
@@ -46,6 +49,15 @@ pub struct HelperResponse {
     pub message: String,
 }
 
+/// Files the helper's stdout and stderr are redirected to
+pub fn get_helper_output_paths() -> (PathBuf, PathBuf) {
+    let logs_dir = Path::new(SYSTEM_LOGS_DIR);
+    (
+        logs_dir.join("helper.log"),
+        logs_dir.join("helper.error.log"),
+    )
+}
+
 /// Helper client for communicating with the privileged helper
 pub struct HelperClient;
 
@@ -66,8 +78,16 @@ impl HelperClient {
 
     /// Install the helper (requires admin password - one time only)
     pub fn install() -> Result<(), String> {
+        if Os::host() != Os::MacOs {
+            return Err(format!(
+                "The privileged helper is only available on macOS, not {}",
+                Os::host().label()
+            ));
+        }
+
         // Get path to helper binary in app bundle or build directory
         let helper_source = Self::find_helper_binary()?;
+        let (stdout_log, stderr_log) = get_helper_output_paths();
 
         // Create the installation script
         let script = format!(
@@ -95,9 +115,9 @@ cat > '{plist_path}' << 'PLIST'
     <key>KeepAlive</key>
     <true/>
     <key>StandardOutPath</key>
-    <string>{system_logs_dir}/helper.log</string>
+    <string>{stdout_log}</string>
     <key>StandardErrorPath</key>
-    <string>{system_logs_dir}/helper.error.log</string>
+    <string>{stderr_log}</string>
 </dict>
 </plist>
 PLIST
@@ -110,7 +130,9 @@ launchctl load -w '{plist_path}'
             plist_path = HELPER_PLIST_PATH,
             privileged_helper_dir = PRIVILEGED_HELPER_DIR,
             launch_daemons_dir = LAUNCH_DAEMONS_DIR,
-            system_logs_dir = SYSTEM_LOGS_DIR
+            system_logs_dir = SYSTEM_LOGS_DIR,
+            stdout_log = stdout_log.display(),
+            stderr_log = stderr_log.display()
         );
 
         let output = Command::new("osascript")
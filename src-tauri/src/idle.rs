@@ -0,0 +1,290 @@
+//! Idle auto-stop
+//!
+//! Stops running instances nobody has used for `idle_stop.idle_minutes`, so
+//! rarely opened sites don't hold memory all day. An instance counts as used
+//! when a request reaches one of its domains, through the in-memory proxy
+//! (recorded by `touch`) or Caddy (whose per-site access logs change), and
+//! while anything holds a TCP connection to its port. Instances get the full
+//! period after Burd first sees them running.
+//!
+//! Wake-on-access instances keep their domains routed once stopped, so the
+//! next visit starts them again; other instances' routes are dropped as if
+//! they were stopped by hand.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::caddy::get_site_log_path;
+use crate::commands::AppState;
+use crate::config::{Config, IdleStopSettings, Instance};
+use crate::db_manager;
+
+/// Delay between idle checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Shortest idle period that can be configured
+pub const MIN_IDLE_MINUTES: u32 = 5;
+
+/// Longest idle period that can be configured (a week)
+pub const MAX_IDLE_MINUTES: u32 = 7 * 24 * 60;
+
+/// Last request per proxy route ID (the domain's ID) seen by the in-memory proxy
+fn requests() -> &'static Mutex<HashMap<String, DateTime<Utc>>> {
+    static REQUESTS: OnceLock<Mutex<HashMap<String, DateTime<Utc>>>> = OnceLock::new();
+    REQUESTS.get_or_init(Default::default)
+}
+
+/// Record a request for a proxy route
+pub fn touch(route_id: &str) {
+    if let Ok(mut requests) = requests().lock() {
+        requests.insert(route_id.to_string(), Utc::now());
+    }
+}
+
+/// Check the idle period
+pub fn validate_settings(settings: IdleStopSettings) -> Result<IdleStopSettings, String> {
+    if !(MIN_IDLE_MINUTES..=MAX_IDLE_MINUTES).contains(&settings.idle_minutes) {
+        return Err(format!(
+            "Idle time must be between {} and {} minutes",
+            MIN_IDLE_MINUTES, MAX_IDLE_MINUTES
+        ));
+    }
+    Ok(settings)
+}
+
+/// Whether the settings let `instance` be stopped at all
+fn is_eligible(instance: &Instance, settings: &IdleStopSettings) -> bool {
+    settings.enabled
+        && !(settings.exclude_databases && db_manager::is_database_service(instance.service_type))
+}
+
+fn is_idle(last_active: DateTime<Utc>, now: DateTime<Utc>, idle_minutes: u32) -> bool {
+    now - last_active >= TimeDelta::minutes(i64::from(idle_minutes))
+}
+
+/// When a request last reached one of the instance's domains
+pub fn last_request(config: &Config, instance: &Instance) -> Option<DateTime<Utc>> {
+    let domains = config
+        .domains
+        .iter()
+        .filter(|d| d.routes_to_instance(&instance.id));
+
+    let mut full_domains: Vec<String> = Vec::new();
+    let mut route_ids: Vec<String> = Vec::new();
+    for domain in domains {
+        full_domains.push(domain.full_domain(&config.tld));
+        route_ids.push(domain.id.to_string());
+    }
+    if instance.domain_enabled {
+        full_domains.push(instance.full_domain(&config.tld, &config.slugs));
+    }
+
+    let proxied = requests().lock().ok().and_then(|requests| {
+        route_ids
+            .iter()
+            .filter_map(|id| requests.get(id).copied())
+            .max()
+    });
+    let logged = full_domains
+        .iter()
+        .filter_map(|domain| fs::metadata(get_site_log_path(domain)).ok())
+        .filter_map(|meta| meta.modified().ok())
+        .map(DateTime::<Utc>::from)
+        .max();
+
+    proxied.max(logged)
+}
+
+/// Whether anything holds a TCP connection to a local port
+fn has_connections(port: u16) -> bool {
+    Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:ESTABLISHED", "-t"])
+        .output()
+        .is_ok_and(|output| output.status.success() && !output.stdout.is_empty())
+}
+
+/// Running instances that have been idle for the configured period
+///
+/// `active_at` holds when each running instance was last seen in use
+/// (connected to, or first seen running) and is updated here.
+fn idle_instances(
+    state: &AppState,
+    config: &Config,
+    active_at: &mut HashMap<Uuid, DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Vec<Instance> {
+    let running: Vec<&Instance> = match state.process_manager.lock() {
+        Ok(process_manager) => config
+            .instances
+            .iter()
+            .filter(|i| process_manager.is_running(&i.id))
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    active_at.retain(|id, _| running.iter().any(|i| i.id == *id));
+
+    let mut idle = Vec::new();
+    for instance in running {
+        let seen = *active_at.entry(instance.id).or_insert(now);
+        if !is_eligible(instance, &config.idle_stop) {
+            continue;
+        }
+        let last_active = last_request(config, instance).map_or(seen, |at| at.max(seen));
+        if !is_idle(last_active, now, config.idle_stop.idle_minutes) {
+            continue;
+        }
+        // Checked last, as it runs lsof
+        if has_connections(instance.port) {
+            active_at.insert(instance.id, now);
+            continue;
+        }
+        idle.push(instance.clone());
+    }
+    idle
+}
+
+async fn stop(state: &AppState, config: &Config, instance: &Instance) -> Result<(), String> {
+    let stop_state = state.clone();
+    let id = instance.id;
+    tokio::task::spawn_blocking(move || {
+        stop_state
+            .process_manager
+            .lock()
+            .map_err(|_| "Failed to acquire process manager lock".to_string())?
+            .stop(&id)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    if !instance.wake_on_access {
+        let proxy = state.proxy_server.lock().await;
+        for domain in config
+            .domains
+            .iter()
+            .filter(|d| d.routes_to_instance(&instance.id))
+        {
+            let _ = proxy.unregister_route(&domain.full_domain(&config.tld));
+        }
+    }
+    Ok(())
+}
+
+/// Stop idle instances forever, passing each one stopped to `on_stop`
+pub async fn run(state: AppState, on_stop: impl Fn(&Instance) + Send + 'static) {
+    let mut active_at: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let config = match state.config_store.lock().map(|store| store.load()) {
+            Ok(Ok(config)) => config,
+            _ => continue,
+        };
+        // Start counting afresh when it's turned back on
+        if !config.idle_stop.enabled {
+            active_at.clear();
+            continue;
+        }
+
+        let check_state = state.clone();
+        let check_config = config.clone();
+        let mut seen = std::mem::take(&mut active_at);
+        let Ok((idle, seen)) = tokio::task::spawn_blocking(move || {
+            let idle = idle_instances(&check_state, &check_config, &mut seen, Utc::now());
+            (idle, seen)
+        })
+        .await
+        else {
+            continue;
+        };
+        active_at = seen;
+
+        for instance in idle {
+            match stop(&state, &config, &instance).await {
+                Ok(()) => {
+                    eprintln!(
+                        "Stopped {} after {} idle minutes",
+                        instance.name, config.idle_stop.idle_minutes
+                    );
+                    active_at.remove(&instance.id);
+                    on_stop(&instance);
+                }
+                Err(e) => eprintln!("Failed to stop idle instance {}: {}", instance.name, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServiceType;
+    use crate::test_utils::{ConfigBuilder, DomainBuilder, InstanceBuilder};
+
+    #[test]
+    fn test_is_idle() {
+        let now = Utc::now();
+        assert!(!is_idle(now - TimeDelta::minutes(29), now, 30));
+        assert!(is_idle(now - TimeDelta::minutes(30), now, 30));
+    }
+
+    #[test]
+    fn test_is_eligible() {
+        let site = InstanceBuilder::new()
+            .service_type(ServiceType::FrankenPHP)
+            .build();
+        let database = InstanceBuilder::new()
+            .service_type(ServiceType::MariaDB)
+            .build();
+        let mut settings = IdleStopSettings {
+            enabled: true,
+            ..Default::default()
+        };
+
+        assert!(is_eligible(&site, &settings));
+        assert!(!is_eligible(&database, &settings));
+
+        settings.exclude_databases = false;
+        assert!(is_eligible(&database, &settings));
+
+        settings.enabled = false;
+        assert!(!is_eligible(&site, &settings));
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let settings = |idle_minutes| IdleStopSettings {
+            idle_minutes,
+            ..Default::default()
+        };
+        assert!(validate_settings(settings(30)).is_ok());
+        assert!(validate_settings(settings(MIN_IDLE_MINUTES - 1)).is_err());
+        assert!(validate_settings(settings(MAX_IDLE_MINUTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_last_request_from_proxy() {
+        let used = InstanceBuilder::new().domain_enabled(false).build();
+        let unused = InstanceBuilder::new().domain_enabled(false).build();
+        let domain = DomainBuilder::new_instance(used.id)
+            .subdomain(format!("idle-test-{}", used.id))
+            .build();
+        let config = ConfigBuilder::new()
+            .instance(used.clone())
+            .instance(unused.clone())
+            .domain(domain.clone())
+            .build();
+
+        assert_eq!(last_request(&config, &used), None);
+
+        let before = Utc::now();
+        touch(&domain.id.to_string());
+        assert!(last_request(&config, &used).is_some_and(|at| at >= before));
+        assert_eq!(last_request(&config, &unused), None);
+    }
+}
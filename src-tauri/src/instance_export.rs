@@ -0,0 +1,454 @@
+//! Instance Export and Import
+//!
+//! Exports one instance (service, version, config, domains, and its
+//! connection settings) as shareable JSON, so a teammate can replicate it
+//! without a whole stack. Passwords and keys are stripped unless asked for.
+//!
+//! Imports are previewed first. The instance gets a new ID; a name that's
+//! taken must be replaced, a port that's taken moves to a free one, and
+//! domains whose subdomain exists are skipped.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::commands::{generate_env_for_service, EnvFormat};
+use crate::config::{
+    is_default_alias, Config, Domain, DomainSource, DomainTarget, ExportedInstance,
+    ExportedInstanceDomain, Instance, InstanceExport, InstanceImportConflict,
+    InstanceImportPreview, InstanceImportResult, MissingVersion,
+};
+use crate::port_allocator;
+use crate::validation;
+
+/// Current export schema version
+const SCHEMA_VERSION: u32 = 1;
+
+/// Fields that should be stripped from config as secrets
+const SECRET_FIELDS: &[&str] = &["password", "master_key", "api_key", "token", "secret"];
+
+/// Strip secret fields from a config value
+pub fn strip_secrets(config: &serde_json::Value) -> serde_json::Value {
+    match config {
+        serde_json::Value::Object(map) => {
+            let mut new_map = serde_json::Map::new();
+            for (key, value) in map {
+                let key_lower = key.to_lowercase();
+                if SECRET_FIELDS.iter().any(|s| key_lower.contains(s)) {
+                    // Skip secret fields
+                    continue;
+                }
+                new_map.insert(key.clone(), strip_secrets(value));
+            }
+            serde_json::Value::Object(new_map)
+        }
+        serde_json::Value::Array(arr) => {
+            serde_json::Value::Array(arr.iter().map(strip_secrets).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// Export an instance with the domains routed to it
+pub fn export_instance(
+    config: &Config,
+    id: Uuid,
+    include_secrets: bool,
+    created_by: Option<String>,
+) -> Result<InstanceExport, String> {
+    let mut instance = config
+        .instances
+        .iter()
+        .find(|i| i.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Instance {} not found", id))?;
+    if !include_secrets {
+        instance.config = strip_secrets(&instance.config);
+    }
+
+    let domains = config
+        .domains
+        .iter()
+        .filter(|d| d.routes_to_instance(&id))
+        .map(|d| ExportedInstanceDomain {
+            subdomain: d.subdomain.clone(),
+            ssl_enabled: d.ssl_enabled,
+            force_https: d.force_https,
+            hsts: d.hsts,
+            require_client_cert: d.require_client_cert,
+        })
+        .collect();
+
+    Ok(InstanceExport {
+        schema_version: SCHEMA_VERSION,
+        created_by,
+        created_at: Utc::now(),
+        secrets_included: include_secrets,
+        env: generate_env_for_service(&instance, EnvFormat::default()),
+        instance: ExportedInstance {
+            name: instance.name,
+            service_type: instance.service_type,
+            version: instance.version,
+            port: instance.port,
+            auto_start: instance.auto_start,
+            restart_on_failure: instance.restart_on_failure,
+            wake_on_access: instance.wake_on_access,
+            domain_enabled: instance.domain_enabled,
+            tags: instance.tags,
+            extra_args: instance.extra_args,
+            config: instance.config,
+        },
+        domains,
+    })
+}
+
+/// Parse and validate an instance export
+pub fn parse_export(json: &str) -> Result<InstanceExport, String> {
+    let export: InstanceExport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid instance config: {}", e))?;
+    if export.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Instance config uses schema version {}; this version of Burd supports up to {}",
+            export.schema_version, SCHEMA_VERSION
+        ));
+    }
+
+    let instance = &export.instance;
+    validation::validate_instance_name(&instance.name)
+        .map_err(|e| format!("Invalid instance name: {}", e))?;
+    validation::validate_extra_args(&instance.extra_args)
+        .map_err(|e| format!("Invalid extra arguments: {}", e))?;
+    for domain in &export.domains {
+        validation::validate_domain_name(&domain.subdomain)
+            .map_err(|e| format!("Invalid subdomain '{}': {}", domain.subdomain, e))?;
+    }
+    Ok(export)
+}
+
+/// Replace a `default` (or omitted) version with the configured default
+///
+/// A pinned version that isn't installed yet is kept so the preview reports
+/// it as missing.
+fn resolve_version(config: &Config, import: &mut InstanceExport) -> Result<(), String> {
+    let instance = &mut import.instance;
+    if is_default_alias(&instance.version) {
+        instance.version = match config.default_version(instance.service_type) {
+            Ok(version) => version,
+            Err(e) => config
+                .pinned_version(instance.service_type)
+                .map(String::from)
+                .ok_or(e)?,
+        };
+    }
+    Ok(())
+}
+
+/// Preview an import: the parsed config, whether its version is missing, and
+/// its conflicts
+pub fn preview_import(
+    config: &Config,
+    mut import: InstanceExport,
+) -> Result<InstanceImportPreview, String> {
+    resolve_version(config, &mut import)?;
+    let instance = &import.instance;
+
+    let installed = config
+        .binaries
+        .get(&instance.service_type)
+        .is_some_and(|versions| versions.contains_key(&instance.version));
+    let missing_version = (!installed && instance.version != "system").then(|| MissingVersion {
+        service_type: instance.service_type,
+        version: instance.version.clone(),
+        download_size: None,
+    });
+
+    let mut conflicts = Vec::new();
+    if let Some(existing) = config.instances.iter().find(|i| i.name == instance.name) {
+        conflicts.push(InstanceImportConflict::NameExists {
+            name: instance.name.clone(),
+            existing_id: existing.id,
+        });
+    }
+    if let Err(reason) = port_allocator::check_port(config, instance.port) {
+        conflicts.push(InstanceImportConflict::PortInUse {
+            port: instance.port,
+            reason,
+        });
+    }
+    for domain in &import.domains {
+        if let Some(existing) = config
+            .domains
+            .iter()
+            .find(|d| d.subdomain == domain.subdomain)
+        {
+            conflicts.push(InstanceImportConflict::SubdomainExists {
+                subdomain: domain.subdomain.clone(),
+                existing_id: existing.id,
+            });
+        }
+    }
+
+    Ok(InstanceImportPreview {
+        config: import,
+        missing_version,
+        conflicts,
+    })
+}
+
+/// Add the imported instance and its domains to the config
+///
+/// `name` and `port` replace the exported ones. Without a port, a taken
+/// port moves to a free one; a chosen port must be free. The caller saves
+/// the config and registers the new routes.
+pub fn apply_import(
+    config: &mut Config,
+    mut import: InstanceExport,
+    name: Option<String>,
+    port: Option<u16>,
+) -> Result<InstanceImportResult, String> {
+    resolve_version(config, &mut import)?;
+    let exported = import.instance;
+
+    let name = name.unwrap_or(exported.name);
+    validation::validate_instance_name(&name)
+        .map_err(|e| format!("Invalid instance name: {}", e))?;
+    if config.instances.iter().any(|i| i.name == name) {
+        return Err(format!(
+            "An instance named '{}' already exists; import it under another name",
+            name
+        ));
+    }
+    let port = port_allocator::resolve_port(
+        config,
+        exported.service_type,
+        Some(port.unwrap_or(exported.port)),
+        port.is_none(),
+    )?;
+
+    let instance = Instance {
+        id: Uuid::new_v4(),
+        name: name.clone(),
+        port,
+        service_type: exported.service_type,
+        version: exported.version,
+        config: exported.config,
+        master_key: None,
+        auto_start: exported.auto_start,
+        restart_on_failure: exported.restart_on_failure,
+        wake_on_access: exported.wake_on_access,
+        created_at: Utc::now(),
+        domain: None,
+        domain_enabled: exported.domain_enabled,
+        stack_id: None,
+        tags: exported.tags,
+        extra_args: exported.extra_args,
+        // Seeds point at local files and directories, so they aren't shared
+        seed: None,
+    };
+    let instance_id = instance.id;
+    config.instances.push(instance);
+
+    let mut domains_created = Vec::new();
+    let mut domains_skipped = Vec::new();
+    for domain in import.domains {
+        if config
+            .domains
+            .iter()
+            .any(|d| d.subdomain == domain.subdomain)
+        {
+            domains_skipped.push(domain.subdomain);
+            continue;
+        }
+        let new_domain = Domain {
+            id: Uuid::new_v4(),
+            subdomain: domain.subdomain,
+            tld: None,
+            target: DomainTarget::Instance(instance_id),
+            ssl_enabled: domain.ssl_enabled,
+            force_https: domain.ssl_enabled && domain.force_https,
+            hsts: domain.ssl_enabled && domain.hsts,
+            require_client_cert: domain.ssl_enabled && domain.require_client_cert,
+            custom_directives: None,
+            source: DomainSource::Manual,
+            created_at: Utc::now(),
+        };
+        domains_created.push(new_domain.id);
+        config.domains.push(new_domain);
+    }
+
+    Ok(InstanceImportResult {
+        instance_id,
+        name,
+        port,
+        domains_created,
+        domains_skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServiceType;
+    use crate::test_utils::{ConfigBuilder, DomainBuilder, InstanceBuilder};
+
+    fn meilisearch() -> Instance {
+        Instance {
+            tags: vec!["client:acme".to_string()],
+            ..InstanceBuilder::new()
+                .name("search")
+                .service_type(ServiceType::Meilisearch)
+                .version("1.6.0")
+                .port(47700)
+                .config(serde_json::json!({ "master_key": "s3cret", "env": "development" }))
+                .build()
+        }
+    }
+
+    #[test]
+    fn test_strip_secrets() {
+        let stripped = strip_secrets(&serde_json::json!({
+            "password": "x",
+            "nested": { "api_key": "y", "keep": 1 },
+            "list": [{ "Token": "z" }]
+        }));
+        assert_eq!(
+            stripped,
+            serde_json::json!({ "nested": { "keep": 1 }, "list": [{}] })
+        );
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let instance = meilisearch();
+        let config = ConfigBuilder::new()
+            .domain(
+                DomainBuilder::new_instance(instance.id)
+                    .subdomain("search")
+                    .build(),
+            )
+            .domain(DomainBuilder::new_port(3000).subdomain("api").build())
+            .instance(instance.clone())
+            .build();
+
+        let export = export_instance(&config, instance.id, false, None).unwrap();
+        assert_eq!(export.instance.name, "search");
+        assert_eq!(export.instance.version, "1.6.0");
+        assert_eq!(export.instance.tags, vec!["client:acme".to_string()]);
+        assert_eq!(
+            export.instance.config,
+            serde_json::json!({ "env": "development" })
+        );
+        assert!(!export.secrets_included);
+        assert!(export
+            .env
+            .contains("MEILISEARCH_HOST=http://127.0.0.1:47700"));
+        assert_eq!(export.domains.len(), 1);
+        assert_eq!(export.domains[0].subdomain, "search");
+
+        let export = export_instance(&config, instance.id, true, None).unwrap();
+        assert_eq!(export.instance.config["master_key"], "s3cret");
+
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(parse_export(&json).unwrap().instance.port, 47700);
+        assert!(export_instance(&config, Uuid::new_v4(), false, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_export_validates() {
+        let config = ConfigBuilder::new().instance(meilisearch()).build();
+        let id = config.instances[0].id;
+        let mut export = export_instance(&config, id, false, None).unwrap();
+
+        export.schema_version = SCHEMA_VERSION + 1;
+        assert!(parse_export(&serde_json::to_string(&export).unwrap()).is_err());
+
+        export.schema_version = SCHEMA_VERSION;
+        export.instance.name = String::new();
+        assert!(parse_export(&serde_json::to_string(&export).unwrap()).is_err());
+        assert!(parse_export("{}").is_err());
+    }
+
+    #[test]
+    fn test_preview_conflicts() {
+        let instance = meilisearch();
+        let source = ConfigBuilder::new()
+            .domain(
+                DomainBuilder::new_instance(instance.id)
+                    .subdomain("search")
+                    .build(),
+            )
+            .instance(instance.clone())
+            .build();
+        let export = export_instance(&source, instance.id, false, None).unwrap();
+
+        // Importing into the same setup conflicts on everything
+        let preview = preview_import(&source, export.clone()).unwrap();
+        assert!(preview.missing_version.is_some());
+        assert_eq!(preview.conflicts.len(), 3);
+        assert!(matches!(
+            &preview.conflicts[0],
+            InstanceImportConflict::NameExists { existing_id, .. } if *existing_id == instance.id
+        ));
+        assert!(matches!(
+            &preview.conflicts[1],
+            InstanceImportConflict::PortInUse { port: 47700, .. }
+        ));
+        assert!(matches!(
+            &preview.conflicts[2],
+            InstanceImportConflict::SubdomainExists { subdomain, .. } if subdomain == "search"
+        ));
+    }
+
+    #[test]
+    fn test_apply_import() {
+        let instance = meilisearch();
+        let source = ConfigBuilder::new()
+            .domain(
+                DomainBuilder::new_instance(instance.id)
+                    .subdomain("search")
+                    .build(),
+            )
+            .domain(
+                DomainBuilder::new_instance(instance.id)
+                    .subdomain("meili")
+                    .build(),
+            )
+            .instance(instance.clone())
+            .build();
+        let export = export_instance(&source, instance.id, true, None).unwrap();
+
+        let mut config = ConfigBuilder::new()
+            .domain(DomainBuilder::new_port(3000).subdomain("search").build())
+            .build();
+        let result = apply_import(&mut config, export.clone(), None, Some(47701)).unwrap();
+
+        assert_ne!(result.instance_id, instance.id);
+        assert_eq!(result.port, 47701);
+        assert_eq!(result.domains_created.len(), 1);
+        assert_eq!(result.domains_skipped, vec!["search".to_string()]);
+
+        let imported = config
+            .instances
+            .iter()
+            .find(|i| i.id == result.instance_id)
+            .unwrap();
+        assert_eq!(imported.name, "search");
+        assert_eq!(imported.version, "1.6.0");
+        assert_eq!(imported.config["master_key"], "s3cret");
+        assert!(config
+            .domains
+            .iter()
+            .any(|d| d.subdomain == "meili" && d.routes_to_instance(&result.instance_id)));
+
+        // The name is now taken
+        assert!(apply_import(&mut config, export.clone(), None, Some(47702)).is_err());
+        let renamed = apply_import(
+            &mut config,
+            export,
+            Some("search-2".to_string()),
+            Some(47702),
+        )
+        .unwrap();
+        assert_eq!(renamed.name, "search-2");
+        assert_eq!(renamed.domains_created.len(), 0);
+    }
+}
@@ -1,52 +1,57 @@
 //! launchd Plist Management for Caddy Proxy
 //!
 //! This module manages the macOS launchd daemon that runs Caddy
-//! as a reverse proxy on privileged ports 80 and 443.
+//! as a reverse proxy on privileged ports 80 and 443 (the macOS
+//! [`ProxyDaemon`]).
 
 use crate::constants::{PROXY_IDENTIFIER, PROXY_PLIST_PATH};
 use crate::helper_client::{HelperClient, HelperRequest};
-use serde::Serialize;
+use crate::platform::{
+    caddy_data_dir, prepare_proxy_daemon, proxy_output_paths, user_app_dir, ProxyDaemon,
+    ServiceStatus,
+};
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
-/// Status of the launchd daemon
-#[derive(Debug, Clone, Serialize)]
-pub struct LaunchdStatus {
-    pub installed: bool,
-    pub running: bool,
-    pub pid: Option<u32>,
-}
+/// The proxy daemon as a launchd system daemon
+pub struct Launchd;
 
-/// Get the user's app directory explicitly using home_dir
-/// This ensures we always use user paths even if the code runs with elevated privileges
-fn get_user_app_dir() -> PathBuf {
-    dirs::home_dir()
-        .map(|h| h.join("Library/Application Support/Burd"))
-        .unwrap_or_else(|| PathBuf::from("/tmp/Burd"))
-}
+impl ProxyDaemon for Launchd {
+    fn install(&self) -> Result<(), String> {
+        install()
+    }
 
-/// Get the user's logs directory
-fn get_user_logs_dir() -> PathBuf {
-    dirs::home_dir()
-        .map(|h| h.join("Library/Logs/Burd"))
-        .unwrap_or_else(|| PathBuf::from("/tmp/Burd/logs"))
-}
+    fn uninstall(&self) -> Result<(), String> {
+        uninstall()
+    }
+
+    fn is_installed(&self) -> bool {
+        is_installed()
+    }
+
+    fn status(&self) -> ServiceStatus {
+        get_status()
+    }
 
-/// Get the path where Caddy stores its data (PKI, etc.) when running as daemon
-pub fn get_caddy_data_dir() -> PathBuf {
-    get_user_app_dir().join("caddy-data")
+    fn start(&self) -> Result<(), String> {
+        start()
+    }
+
+    fn restart(&self) -> Result<(), String> {
+        restart()
+    }
 }
 
 /// Generate the launchd plist content for Caddy
 /// Uses explicit user home paths to ensure daemon reads from user space
 fn generate_plist() -> String {
     // Use user-space paths explicitly (not dirs::data_dir which varies by user)
-    let user_app_dir = get_user_app_dir();
+    let user_app_dir = user_app_dir();
     let caddy_bin = user_app_dir.join("bin/caddy");
     let caddyfile = user_app_dir.join("Caddyfile");
-    let logs_dir = get_user_logs_dir();
-    let caddy_data = get_caddy_data_dir();
+    let (stdout_log, stderr_log) = proxy_output_paths();
+    let caddy_data = caddy_data_dir();
     let working_dir = user_app_dir;
 
     format!(
@@ -78,10 +83,10 @@ fn generate_plist() -> String {
     <integer>18</integer>
 
     <key>StandardOutPath</key>
-    <string>{logs_dir}/caddy.log</string>
+    <string>{stdout_log}</string>
 
     <key>StandardErrorPath</key>
-    <string>{logs_dir}/caddy.error.log</string>
+    <string>{stderr_log}</string>
 
     <key>WorkingDirectory</key>
     <string>{working_dir}</string>
@@ -97,7 +102,8 @@ fn generate_plist() -> String {
         label = PROXY_IDENTIFIER,
         caddy = caddy_bin.display(),
         caddyfile = caddyfile.display(),
-        logs_dir = logs_dir.display(),
+        stdout_log = stdout_log.display(),
+        stderr_log = stderr_log.display(),
         working_dir = working_dir.display(),
         caddy_data = caddy_data.display()
     )
@@ -109,26 +115,8 @@ fn generate_plist() -> String {
 /// Before calling this, ensure:
 /// 1. Caddy binary is installed via `caddy::install_caddy_for_daemon()`
 /// 2. Initial Caddyfile is written via `caddy::write_caddyfile()`
-pub fn install() -> Result<(), String> {
-    // Verify Caddy is installed for daemon
-    if !crate::caddy::is_daemon_caddy_installed() {
-        return Err(
-            "Caddy binary not installed. Call caddy::install_caddy_for_daemon() first.".to_string(),
-        );
-    }
-
-    // Verify Caddyfile exists (now in user space)
-    let caddyfile_path = get_user_app_dir().join("Caddyfile");
-    if !caddyfile_path.exists() {
-        return Err("Caddyfile not found. Call caddy::write_caddyfile() first.".to_string());
-    }
-
-    // Create user-space directories (no admin needed)
-    let logs_dir = get_user_logs_dir();
-    let caddy_data = get_caddy_data_dir();
-    fs::create_dir_all(&logs_dir).map_err(|e| format!("Failed to create logs directory: {}", e))?;
-    fs::create_dir_all(&caddy_data)
-        .map_err(|e| format!("Failed to create caddy data directory: {}", e))?;
+fn install() -> Result<(), String> {
+    prepare_proxy_daemon()?;
 
     let plist_content = generate_plist();
 
@@ -186,7 +174,7 @@ launchctl load -w '{}'
 /// Uninstall the launchd plist
 ///
 /// This requires admin privileges and will prompt the user.
-pub fn uninstall() -> Result<(), String> {
+fn uninstall() -> Result<(), String> {
     // Try using helper if available
     if HelperClient::is_running() {
         let response = HelperClient::send_request(HelperRequest::UninstallProxyDaemon)?;
@@ -225,16 +213,16 @@ rm -f '{}'
 }
 
 /// Check if the launchd daemon is installed
-pub fn is_installed() -> bool {
+fn is_installed() -> bool {
     PathBuf::from(PROXY_PLIST_PATH).exists()
 }
 
 /// Check if the launchd daemon is running and get its status
-pub fn get_status() -> LaunchdStatus {
+fn get_status() -> ServiceStatus {
     let installed = is_installed();
 
     if !installed {
-        return LaunchdStatus {
+        return ServiceStatus {
             installed: false,
             running: false,
             pid: None,
@@ -252,7 +240,7 @@ pub fn get_status() -> LaunchdStatus {
     // Try to get PID using lsof
     let pid = if running { get_pid_on_port(80) } else { None };
 
-    LaunchdStatus {
+    ServiceStatus {
         installed: true,
         running,
         pid,
@@ -280,8 +268,7 @@ fn get_pid_on_port(port: u16) -> Option<u32> {
 }
 
 /// Parse the PID from launchctl list output
-#[cfg(test)]
-fn parse_launchctl_pid(output: &str) -> Option<u32> {
+pub(crate) fn parse_launchctl_pid(output: &str) -> Option<u32> {
     // launchctl list output format:
     // {
     //     "PID" = 12345;
@@ -311,7 +298,7 @@ fn parse_launchctl_pid(output: &str) -> Option<u32> {
 }
 
 /// Start the launchd daemon
-pub fn start() -> Result<(), String> {
+fn start() -> Result<(), String> {
     if !is_installed() {
         return Err("Proxy daemon is not installed".to_string());
     }
@@ -350,7 +337,7 @@ pub fn start() -> Result<(), String> {
 }
 
 /// Restart the launchd daemon
-pub fn restart() -> Result<(), String> {
+fn restart() -> Result<(), String> {
     if !is_installed() {
         return Err("Proxy daemon is not installed".to_string());
     }
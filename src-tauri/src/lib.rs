@@ -5,108 +5,193 @@
 //! - Databases (MariaDB, PostgreSQL, MongoDB)
 //! - Cache services (Redis, Valkey, Memcached)
 //! - Mail testing (Mailpit)
-//! - Full-text search (Meilisearch, Typesense)
+//! - Full-text search (Meilisearch, Typesense, OpenSearch)
 //! - And more
 //!
 //! This crate provides the core functionality for both the GUI application
 //! and the CLI tool.
 
+mod activity;
 pub mod analyzer;
 pub mod api;
 pub mod api_client;
+mod arch;
+mod autostart;
+mod backup;
 mod binary;
+mod binary_verify;
 mod caddy;
+mod cert_monitor;
 pub mod cli;
+mod client_certs;
 mod commands;
 pub mod config;
+mod config_export;
 pub mod constants;
+mod cron;
+mod daemon;
 pub mod db_manager;
+mod device_setup;
 mod dns;
+mod dns_check;
 pub mod domain;
+mod domain_export;
 mod drivers;
+mod emulation;
+mod env_snapshot;
+mod env_sync;
 pub mod error;
+mod events;
+mod github;
 mod helper_client;
+mod idle;
+mod instance_export;
 mod launchd;
 pub mod lock_utils;
 mod logs;
 mod mail_notifier;
+mod mail_routing;
 pub mod mcp;
+mod metrics;
+pub mod minio_manager;
 mod nvm;
 pub mod park;
 mod park_watcher;
+mod pid_file;
+mod platform;
+mod port_allocator;
 mod process;
 mod proxy;
+mod proxy_watchdog;
 mod pvm;
+pub mod queue_manager;
+mod readiness;
+pub mod redis_manager;
 mod resolver;
+mod seed;
 pub mod service_config;
+mod service_init;
+mod service_definitions;
 mod services;
+mod site_stats;
+pub mod snapshot;
+mod start_diagnosis;
+mod start_page;
 mod tinker;
+mod tld_migration;
+mod traffic;
 mod tray;
+mod trust_stores;
 mod tunnel;
 pub mod validation;
+mod vault;
+mod wake;
+mod workers;
+mod wp_cli;
 
 // Test utilities module (only available in test builds)
 #[cfg(test)]
 pub mod test_utils;
 
-use binary::BinaryManager;
+// End-to-end test environment (tests, or the `test-support` feature)
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_env;
+
 use commands::{
+    add_dns_record,
     add_instances_to_stack,
+    add_remote_host,
+    add_tld,
+    apply_env_fixes,
+    apply_env_sync,
+    change_instance_port,
     change_instance_version,
     check_frpc_installed,
     check_instance_health,
     check_port_status,
+    check_project_env,
     check_proxy_health,
+    clear_dns_queries,
     clear_logs,
+    clear_release_cache,
     clear_tinker_history,
     configure_php_shell_integration,
     create_domain,
     create_frp_server,
     create_instance,
+    create_redis_topology,
+    create_snapshot,
     create_stack,
     create_tunnel,
     delete_all_emails,
     delete_binary_version,
+    delete_composer_version,
     delete_domain,
     delete_emails,
     delete_frp_server,
     delete_instance,
     delete_php_version,
+    delete_redis_keys,
+    delete_snapshot,
     delete_stack,
     delete_tinker_history_item,
     delete_tunnel,
+    disable_emulation,
     disable_proxy,
     download_binary,
+    download_composer_version,
     download_php_version,
+    enable_emulation,
     execute_tinker,
+    export_ca_bundle,
+    export_config,
+    export_device_bundle,
+    export_domains,
+    export_email_eml,
+    export_instance,
     export_stack,
     fix_php_shell_integration,
+    flush_redis,
     generate_server_token,
     get_all_binary_statuses,
+    get_api_remote_settings,
     // Log commands
     get_available_log_sources,
     get_available_services,
     get_available_versions,
+    get_backup_overview,
     get_binary_status,
     get_ca_trust_status,
+    get_cert_monitor_settings,
+    get_certificates_status,
     get_cli_status,
+    get_client_cert,
     get_current_php,
+    get_dns_queries,
     get_domain_config,
+    get_domain_requests,
     get_email,
+    get_email_html_source,
     get_frpc_config,
     get_frpc_connection_status,
     get_frpc_logs,
+    get_github_settings,
     get_helper_status,
+    get_idle_stop_settings,
     get_installed_versions,
     get_instance_config,
     get_instance_env,
     get_instance_info,
     get_instance_logs,
+    get_instance_metrics,
+    get_log_retention_settings,
+    get_log_usage,
     // Mail commands (Mailpit)
     get_mailpit_config,
     get_network_status,
     get_nvm_status,
     get_parked_projects,
+    get_php_extensions,
     get_php_shell_integration_status,
     get_proxy_config,
     get_proxy_port_conflicts,
@@ -114,46 +199,83 @@ use commands::{
     // PVM commands
     get_pvm_status,
     get_recent_logs,
+    get_remote_host_status,
     get_resolver_status,
     get_settings,
+    get_slug_settings,
     get_stack,
+    get_start_diagnosis,
     get_tinker_history,
     get_tinker_php_info,
     get_tunnel_status,
     get_unread_count,
+    get_worker_logs,
+    import_config,
+    import_domains,
+    import_instance,
     import_stack,
     install_cli,
     install_helper,
     install_node_version,
+    install_php_extension_bundle,
     install_resolver,
     is_nvm_installed,
     // Park commands
     is_park_enabled,
+    list_backups,
+    list_dns_records,
     list_domains,
     list_emails,
+    list_emulations,
+    list_mail_routes,
     // Tunnel commands
     list_frp_servers,
+    list_installed_composer_versions,
     list_installed_node_versions,
     list_installed_php_versions,
     list_instances,
     list_parked_directories,
+    list_php_extension_bundles,
+    // Redis key browser commands
+    list_redis_databases,
+    list_redis_keys,
+    list_remote_composer_versions,
+    list_remote_hosts,
     list_remote_node_versions,
     list_remote_php_versions,
+    // Snapshot commands
+    list_snapshots,
     // Stack commands
     list_stacks,
     // Tinker commands (PHP Console)
     list_tinker_projects,
     list_tunnels,
+    list_vault,
+    // Queue worker commands
+    list_worker_projects,
+    list_workers,
     mark_emails_read,
     move_instance_to_stack,
     open_keychain_access,
     park_directory,
+    preview_domain_import,
+    preview_instance_import,
+    preview_slug,
     preview_stack_import,
+    provision_keycloak,
+    prune_logs,
     refresh_all_parked_directories,
     refresh_parked_directory,
     reinit_domain_ssl,
+    reissue_client_cert,
+    remote_instance_action,
+    remove_dns_record,
     remove_instances_from_stack,
+    remove_mail_route,
     remove_php_shell_integration,
+    remove_remote_host,
+    remove_tld,
+    remove_worker,
     rename_instance,
     reorder_domains,
     reorder_instances,
@@ -161,18 +283,50 @@ use commands::{
     restart_instance,
     restart_proxy_daemon,
     restart_proxy_for_certs,
+    restart_stack,
+    restore_backup,
+    restore_snapshot,
+    rotate_api_token,
+    run_backup_now,
+    run_dns_self_test,
+    run_instance_seed,
+    save_email_attachment,
+    search_emails,
+    set_default_composer_version,
     set_default_node_version,
     set_default_php_version,
+    set_default_service_version,
+    set_dns_query_log,
+    set_domain_client_auth,
+    set_domain_custom_directives,
+    set_domain_tld,
+    set_instance_auto_start,
     set_instance_domain,
+    set_instance_extra_args,
+    set_instance_restart_on_failure,
+    set_instance_seed,
+    set_instance_tags,
+    set_instance_wake_on_access,
+    set_mail_route,
+    set_release_channel,
+    set_vault_secret,
+    setup_centrifugo,
     setup_proxy,
+    setup_soketi,
     start_dns_server,
     start_instance,
     start_proxy_daemon,
+    start_stack,
     start_tunnels,
+    start_worker,
     stop_dns_server,
     stop_instance,
+    stop_stack,
     stop_tunnels,
+    stop_worker,
     stream_logs,
+    suggest_port,
+    trust_ca_in_firefox,
     trust_caddy_ca,
     uninstall_cli,
     uninstall_helper,
@@ -180,65 +334,61 @@ use commands::{
     uninstall_resolver,
     unpark_directory,
     untrust_caddy_ca,
+    unset_vault_secret,
+    update_api_remote_settings,
+    update_backup_settings,
+    update_cert_monitor_settings,
     update_domain,
     update_domain_config,
     update_domain_ssl,
     update_frp_server,
+    update_github_settings,
+    update_idle_stop_settings,
     update_instance_config,
+    update_log_retention_settings,
+    update_parked_directory_depth,
     update_parked_directory_ssl,
     update_stack,
+    update_port_range,
+    update_safety_snapshot_hours,
+    update_slug_settings,
+    update_snapshot_retention,
     update_tld,
     update_tunnel,
     AppState,
 };
 use config::ConfigStore;
-use dns::DnsServer;
 use mail_notifier::MailNotifierState;
 use park_watcher::ParkWatcherState;
-use process::ProcessManager;
-use proxy::ProxyServer;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
 use tauri::{Emitter, Manager};
-use tokio::sync::Mutex as AsyncMutex;
+use tauri_plugin_notification::NotificationExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let config_store = ConfigStore::new().expect("Failed to initialize config store");
 
+    // Keep the previous version's config restorable (`burd rollback-env`)
+    env_snapshot::snapshot_on_startup(&config_store);
+
     // Domain migration disabled - domains are now created manually via UI
     // This prevents auto-recreation of domains on restart
     // let _ = config_store.migrate_instance_domains();
 
     let config = config_store.load().expect("Failed to load config");
 
-    let process_manager = ProcessManager::new();
-    let binary_manager = BinaryManager::new();
-
-    // Initialize DNS server with TLD
-    let mut dns_server = DnsServer::new(config.dns_port, config.tld.clone());
-    let _ = dns_server.start();
-
-    // Initialize proxy server
-    let proxy_server = ProxyServer::new(config.proxy_port, config.tld.clone());
-
-    let app_state = AppState {
-        config_store: Arc::new(Mutex::new(config_store)),
-        process_manager: Arc::new(Mutex::new(process_manager)),
-        binary_manager: Arc::new(Mutex::new(binary_manager)),
-        dns_server: Arc::new(Mutex::new(dns_server)),
-        proxy_server: Arc::new(AsyncMutex::new(proxy_server)),
-        proxy_healthy: Arc::new(std::sync::atomic::AtomicU8::new(0)),
-    };
+    // Publish config saves from every process, so the GUI can follow them
+    if let Err(e) = events::watch_config(config_store.path()) {
+        eprintln!("Config changes won't be published: {}", e);
+    }
 
-    // Check if privileged daemon is installed - if so, skip port 8080 proxy
-    let daemon_installed = launchd::is_installed();
+    let app_state = AppState::new(config_store, &config);
 
     // Collect parked directories for watcher initialization
-    let parked_dirs_for_watcher: Vec<(uuid::Uuid, PathBuf)> = config
+    let parked_dirs_for_watcher: Vec<(uuid::Uuid, PathBuf, u8)> = config
         .parked_directories
         .iter()
-        .map(|pd| (pd.id, PathBuf::from(&pd.path)))
+        .map(|pd| (pd.id, PathBuf::from(&pd.path), pd.depth))
         .collect();
 
     tauri::Builder::default()
@@ -267,113 +417,44 @@ pub fn run() {
                     app.handle().clone(),
                 );
             }
-            // Only start the fallback port 8080 proxy if the daemon is NOT installed
-            // When daemon is installed, it handles ports 80 and 443
-            if !daemon_installed {
-                let state = app.state::<AppState>();
-                let proxy_server = state.proxy_server.clone();
-                tauri::async_runtime::spawn(async move {
-                    let mut proxy = proxy_server.lock().await;
-                    let _ = proxy.start().await;
-                });
-            } else {
-                // Daemon is installed - sync all domains to Caddyfile on startup
-                let state = app.state::<AppState>();
-                let proxy_server = state.proxy_server.clone();
-
-                // Load config synchronously before spawning async task
-                let config = match state.config_store.lock() {
-                    Ok(store) => match store.load() {
-                        Ok(c) => c,
-                        Err(_) => return Ok(()),
-                    },
-                    Err(_) => return Ok(()),
-                };
-
-                tauri::async_runtime::spawn(async move {
-                    // Register all domains with the proxy server
-                    let proxy = proxy_server.lock().await;
-                    let tld = config.tld.clone();
-
-                    for domain in &config.domains {
-                        let full_domain = domain.full_domain(&tld);
-                        match &domain.target {
-                            config::DomainTarget::Instance(instance_id) => {
-                                // Find the instance to get its port
-                                if let Some(instance) =
-                                    config.instances.iter().find(|i| &i.id == instance_id)
-                                {
-                                    let _ = proxy.register_route(
-                                        &full_domain,
-                                        instance.port,
-                                        &domain.id.to_string(),
-                                        domain.ssl_enabled,
-                                    );
-                                }
-                            }
-                            config::DomainTarget::Port(port) => {
-                                let _ = proxy.register_route(
-                                    &full_domain,
-                                    *port,
-                                    &domain.id.to_string(),
-                                    domain.ssl_enabled,
-                                );
-                            }
-                            config::DomainTarget::StaticFiles { path, browse } => {
-                                let _ = proxy.register_static_route(
-                                    &full_domain,
-                                    path,
-                                    *browse,
-                                    &domain.id.to_string(),
-                                    domain.ssl_enabled,
-                                );
-                            }
-                        }
-                    }
-                });
-            }
-
             // Start mail notifier for Mailpit WebSocket events
             mail_notifier::start_mail_notifier(app.handle().clone());
 
-            // Start MCP API server for external control
-            let api_state = app.state::<AppState>().inner().clone();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = api::start_server(std::sync::Arc::new(api_state)).await {
-                    eprintln!("Failed to start MCP API server: {}", e);
+            // Run the API server, supervisors and schedulers unless the login
+            // daemon already does; then take over once it stops
+            match daemon::try_lock_services() {
+                Ok(Some(services)) => start_services(app.handle(), services),
+                Ok(None) => {
+                    let app_handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        match tauri::async_runtime::spawn_blocking(daemon::lock_services).await {
+                            Ok(Ok(services)) => start_services(&app_handle, services),
+                            Ok(Err(e)) => eprintln!("{}", e),
+                            Err(_) => {}
+                        }
+                    });
                 }
-            });
+                Err(e) => eprintln!("{}", e),
+            }
 
-            // Start background proxy health poller
+            // Refresh the GUI after changes, including those made through the
+            // API, MCP server or CLI
             {
-                let proxy_healthy = app.state::<AppState>().proxy_healthy.clone();
                 let app_handle = app.handle().clone();
-                tauri::async_runtime::spawn(async move {
-                    use std::sync::atomic::Ordering;
-                    loop {
-                        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-
-                        let health = tokio::task::spawn_blocking({
-                            let _ph = proxy_healthy.clone();
-                            commands::check_health_sync
-                        })
-                        .await
-                        .ok()
-                        .flatten();
-
-                        let new_val: u8 = match health {
-                            Some(true) => 1,
-                            Some(false) => 2,
-                            None => 0,
-                        };
-                        let old_val = proxy_healthy.swap(new_val, Ordering::Relaxed);
-
-                        // Emit event when health status changes
-                        if old_val != new_val {
-                            let _ = app_handle.emit("proxy-health-changed", health);
-                        }
+                tauri::async_runtime::spawn(events::forward_changes(move |config| {
+                    if config {
+                        let _ = app_handle.emit("config-changed", ());
                     }
-                });
+                    let _ = app_handle.emit("instances-changed", ());
+                }));
+            }
+
+            // Show database initialization progress on first start
+            {
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(events::forward_init(move |progress| {
+                    let _ = app_handle.emit("instance-init", progress);
+                }));
             }
 
             Ok(())
@@ -382,6 +463,16 @@ pub fn run() {
             list_instances,
             create_instance,
             rename_instance,
+            set_instance_auto_start,
+            set_instance_restart_on_failure,
+            set_instance_wake_on_access,
+            set_instance_extra_args,
+            set_instance_seed,
+            run_instance_seed,
+            set_instance_tags,
+            setup_centrifugo,
+            setup_soketi,
+            provision_keycloak,
             start_instance,
             stop_instance,
             restart_instance,
@@ -396,7 +487,9 @@ pub fn run() {
             delete_binary_version,
             check_instance_health,
             check_port_status,
+            suggest_port,
             get_instance_logs,
+            get_start_diagnosis,
             get_network_status,
             set_instance_domain,
             install_resolver,
@@ -406,14 +499,58 @@ pub fn run() {
             get_instance_config,
             update_instance_config,
             change_instance_version,
+            change_instance_port,
             get_instance_env,
             get_instance_info,
+            get_instance_metrics,
+            apply_env_sync,
+            check_project_env,
+            apply_env_fixes,
+            // Instance export commands
+            export_instance,
+            preview_instance_import,
+            import_instance,
             // DNS Server commands
             start_dns_server,
             stop_dns_server,
             restart_dns_server,
+            list_dns_records,
+            add_dns_record,
+            remove_dns_record,
+            run_dns_self_test,
+            get_dns_queries,
+            set_dns_query_log,
+            clear_dns_queries,
+            list_emulations,
+            enable_emulation,
+            disable_emulation,
             get_settings,
             update_tld,
+            add_tld,
+            remove_tld,
+            update_port_range,
+            set_default_service_version,
+            set_release_channel,
+            get_api_remote_settings,
+            update_api_remote_settings,
+            rotate_api_token,
+            list_remote_hosts,
+            add_remote_host,
+            remove_remote_host,
+            get_remote_host_status,
+            remote_instance_action,
+            get_github_settings,
+            update_github_settings,
+            clear_release_cache,
+            get_idle_stop_settings,
+            update_idle_stop_settings,
+            get_cert_monitor_settings,
+            update_cert_monitor_settings,
+            get_slug_settings,
+            update_slug_settings,
+            preview_slug,
+            get_log_retention_settings,
+            update_log_retention_settings,
             // Proxy commands (Caddy-based)
             get_proxy_status,
             setup_proxy,
@@ -421,10 +558,14 @@ pub fn run() {
             start_proxy_daemon,
             restart_proxy_daemon,
             restart_proxy_for_certs,
+            get_certificates_status,
             // CA trust commands
             get_ca_trust_status,
             trust_caddy_ca,
             untrust_caddy_ca,
+            trust_ca_in_firefox,
+            export_ca_bundle,
+            export_device_bundle,
             // Proxy health check
             check_proxy_health,
             get_proxy_port_conflicts,
@@ -436,8 +577,17 @@ pub fn run() {
             reinit_domain_ssl,
             update_domain_ssl,
             get_domain_config,
+            get_domain_requests,
             update_domain_config,
+            set_domain_client_auth,
+            set_domain_custom_directives,
+            set_domain_tld,
+            get_client_cert,
+            reissue_client_cert,
             reorder_domains,
+            export_domains,
+            preview_domain_import,
+            import_domains,
             get_proxy_config,
             // NVM commands
             get_nvm_status,
@@ -469,6 +619,15 @@ pub fn run() {
             configure_php_shell_integration,
             remove_php_shell_integration,
             fix_php_shell_integration,
+            get_php_extensions,
+            list_php_extension_bundles,
+            install_php_extension_bundle,
+            // Composer commands
+            list_installed_composer_versions,
+            list_remote_composer_versions,
+            download_composer_version,
+            delete_composer_version,
+            set_default_composer_version,
             // Tunnel commands
             list_frp_servers,
             create_frp_server,
@@ -494,6 +653,10 @@ pub fn run() {
             delete_all_emails,
             mark_emails_read,
             get_unread_count,
+            search_emails,
+            save_email_attachment,
+            export_email_eml,
+            get_email_html_source,
             // Tinker commands (PHP Console)
             list_tinker_projects,
             execute_tinker,
@@ -501,6 +664,21 @@ pub fn run() {
             clear_tinker_history,
             delete_tinker_history_item,
             get_tinker_php_info,
+            // Queue worker commands
+            list_workers,
+            list_worker_projects,
+            start_worker,
+            stop_worker,
+            remove_worker,
+            get_worker_logs,
+            // Vault commands
+            list_vault,
+            set_vault_secret,
+            unset_vault_secret,
+            // Mail route commands
+            list_mail_routes,
+            set_mail_route,
+            remove_mail_route,
             // Park commands
             is_park_enabled,
             list_parked_directories,
@@ -510,28 +688,200 @@ pub fn run() {
             refresh_all_parked_directories,
             get_parked_projects,
             update_parked_directory_ssl,
+            update_parked_directory_depth,
             // Stack commands
             list_stacks,
             get_stack,
             create_stack,
+            create_redis_topology,
             update_stack,
             delete_stack,
             add_instances_to_stack,
             remove_instances_from_stack,
             move_instance_to_stack,
+            start_stack,
+            stop_stack,
+            restart_stack,
             export_stack,
             preview_stack_import,
             import_stack,
+            // Snapshot commands
+            list_snapshots,
+            create_snapshot,
+            restore_snapshot,
+            delete_snapshot,
+            update_safety_snapshot_hours,
+            update_snapshot_retention,
+            // Redis key browser commands
+            list_redis_databases,
+            list_redis_keys,
+            delete_redis_keys,
+            flush_redis,
+            // Backup commands
+            get_backup_overview,
+            update_backup_settings,
+            run_backup_now,
+            list_backups,
+            restore_backup,
+            export_config,
+            import_config,
             // Log commands
             get_available_log_sources,
             get_recent_logs,
             stream_logs,
             clear_logs,
+            get_log_usage,
+            prune_logs,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
+/// Start what only one process may run: the proxy routes, API server,
+/// instance supervisors and schedulers
+///
+/// The services lock is kept for the app's lifetime.
+fn start_services(app: &tauri::AppHandle, services: daemon::ServicesLock) {
+    app.manage(services);
+
+    // Only start the fallback port 8080 proxy if the daemon is NOT installed
+    // When daemon is installed, it handles ports 80 and 443
+    if !platform::proxy_daemon().is_installed() {
+        let state = app.state::<AppState>();
+        let proxy_server = state.proxy_server.clone();
+        let config = state
+            .config_store
+            .lock()
+            .ok()
+            .and_then(|store| store.load().ok());
+        tauri::async_runtime::spawn(async move {
+            let mut proxy = proxy_server.lock().await;
+            let _ = proxy.start().await;
+            // Wake-on-access domains are routed even while stopped
+            if let Some(config) = config {
+                for domain in wake::wake_domains(&config) {
+                    let _ = proxy.register_domain(domain, &config);
+                }
+            }
+        });
+    } else {
+        // Daemon is installed - sync all domains to Caddyfile on startup
+        let state = app.state::<AppState>();
+        let proxy_server = state.proxy_server.clone();
+
+        // Load config synchronously before spawning async task
+        let config = state.config_store.lock().map(|store| store.load());
+        if let Ok(Ok(config)) = config {
+            tauri::async_runtime::spawn(async move {
+                // Register all domains with the proxy server
+                let proxy = proxy_server.lock().await;
+                proxy.register_config_routes(&config);
+            });
+        }
+    }
+
+    // Start MCP API server for external control
+    let api_state = app.state::<AppState>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = api::start_server(std::sync::Arc::new(api_state)).await {
+            eprintln!("Failed to start MCP API server: {}", e);
+        }
+    });
+
+    // Start the proxy watchdog; emits an event when health changes
+    {
+        let watchdog_state = app.state::<AppState>().inner().clone();
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(proxy_watchdog::run(watchdog_state, move |health| {
+            let _ = app_handle.emit("proxy-health-changed", health);
+        }));
+    }
+
+    // Restart the proxy when site certificates are about to expire
+    {
+        let cert_state = app.state::<AppState>().inner().clone();
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(cert_monitor::run(cert_state, move |domains| {
+            let _ = app_handle.emit("certificates-renewed", domains);
+        }));
+    }
+
+    // Restart failing restart-on-failure instances and tell the user
+    {
+        let supervisor_state = app.state::<AppState>().inner().clone();
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(process::supervise(supervisor_state, move |event| {
+            let _ = app_handle
+                .notification()
+                .builder()
+                .title("Burd")
+                .body(event.message())
+                .show();
+            let _ = app_handle.emit("instance-supervised", event);
+            let _ = app_handle.emit("instances-changed", ());
+        }));
+    }
+
+    // Stop instances nobody has used for a while
+    {
+        let idle_state = app.state::<AppState>().inner().clone();
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(idle::run(idle_state, move |_| {
+            let _ = app_handle.emit("instances-changed", ());
+        }));
+    }
+
+    // Start auto-start instances, backing services first
+    {
+        let autostart_state = app.state::<AppState>().inner().clone();
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let started = autostart::start_all(autostart_state.clone()).await;
+            if started.is_empty() {
+                return;
+            }
+
+            // Route their domains, as starting them from the UI would
+            let config = autostart_state
+                .config_store
+                .lock()
+                .map_err(|_| "Failed to acquire config lock".to_string())
+                .and_then(|store| store.load());
+            if let Ok(config) = config {
+                let proxy = autostart_state.proxy_server.lock().await;
+                for domain in config
+                    .domains
+                    .iter()
+                    .filter(|d| started.iter().any(|id| d.routes_to_instance(id)))
+                {
+                    let _ = proxy.register_domain(domain, &config);
+                }
+            }
+            let _ = app_handle.emit("instances-changed", ());
+        });
+    }
+
+    // Run scheduled backups
+    let backup_state = app.state::<AppState>().inner().clone();
+    tauri::async_runtime::spawn(backup::run(backup_state));
+
+    // Run scheduled database seeds
+    let seed_state = app.state::<AppState>().inner().clone();
+    tauri::async_runtime::spawn(seed::run(seed_state));
+
+    // Rotate instance and worker logs
+    let logs_state = app.state::<AppState>().inner().clone();
+    tauri::async_runtime::spawn(logs::run(logs_state));
+
+    // Sample resource usage of running instances
+    let metrics_state = app.state::<AppState>().inner().clone();
+    tauri::async_runtime::spawn(metrics::run(metrics_state));
+
+    // Record requests served through the proxy
+    let traffic_state = app.state::<AppState>().inner().clone();
+    tauri::async_runtime::spawn(traffic::run(traffic_state));
+}
+
 /// Check if Laravel Herd is running and emit a warning event to the frontend
 fn check_herd_conflict(handle: &tauri::AppHandle) {
     use std::process::Command;
@@ -220,7 +220,7 @@ mod tests {
         let config_store = ConfigStore::new().unwrap();
         let process_manager = ProcessManager::new();
         let binary_manager = BinaryManager::new();
-        let dns_server = DnsServer::new(5300, "test".to_string());
+        let dns_server = DnsServer::new(5300, vec!["test".to_string()]);
         let proxy_server = ProxyServer::new(8080, "test".to_string());
 
         AppState {
@@ -1,20 +1,47 @@
 //! Log Aggregation Module
 //!
 //! This module provides functionality for aggregating logs from multiple sources:
-//! - Caddy reverse proxy (JSON access logs)
+//! - Caddy reverse proxy (JSON logs, daemon stdout/stderr, per-site access logs)
+//! - Privileged helper and Burd daemon (launchd stdout/stderr)
 //! - FrankenPHP instances (stdout/stderr)
 //! - Application logs (Laravel logs, etc.)
+//!
+//! It also keeps Burd's own instance and worker logs in check: a log is
+//! rotated once it passes `log_retention.max_file_mb`, keeping
+//! `log_retention.keep_files` rotated copies, and all logs together are held
+//! under `log_retention.total_cap_mb`.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
-use crate::caddy::get_logs_dir;
-use crate::config::{get_app_dir, Instance};
+use crate::caddy::{get_logs_dir, get_site_log_path};
+use crate::commands::AppState;
+use crate::config::{get_app_dir, Config, Instance, LogRetentionSettings};
+use crate::daemon;
+use crate::helper_client::get_helper_output_paths;
+use crate::platform::proxy_output_paths;
+
+/// Source ID of the Caddy JSON log
+pub const CADDY_SOURCE: &str = "caddy";
+
+/// Source ID of the proxy daemon's stdout/stderr
+pub const CADDY_DAEMON_SOURCE: &str = "caddy-daemon";
+
+/// Source ID of the privileged helper's stdout/stderr
+pub const HELPER_SOURCE: &str = "helper";
+
+/// Source ID of the Burd daemon's output
+pub const DAEMON_SOURCE: &str = "burd-daemon";
+
+/// Prefix of per-site access log source IDs, followed by the full domain
+pub const CADDY_SITE_SOURCE_PREFIX: &str = "caddy-site:";
 
 /// A single log entry from any source
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,7 +101,7 @@ impl LogEntry {
 pub struct LogSourceInfo {
     pub id: String,
     pub name: String,
-    pub log_type: String, // "file", "stdout", "combined"
+    pub log_type: String, // "file", "stdout", "site", "combined"
     pub path: Option<String>,
     pub color: String,
 }
@@ -130,12 +157,15 @@ pub fn parse_caddy_json(line: &str) -> Option<LogEntry> {
     let uri = request["uri"].as_str().unwrap_or("");
     let path = Some(uri.to_string());
 
-    // Extract response details
+    // Extract response details; error logs (e.g. a 502 from an unreachable
+    // upstream) and Caddy's own access logs carry them at the top level
     let resp = &json["resp"];
-    let status = resp["status"].as_u64().map(|s| s as u16);
+    let resp_status = resp["status"].as_u64().map(|s| s as u16);
+    let status = resp_status.or_else(|| json["status"].as_u64().map(|s| s as u16));
     let latency = resp["duration"]
         .as_f64()
-        .or_else(|| resp["latency"].as_f64());
+        .or_else(|| resp["latency"].as_f64())
+        .or_else(|| json["duration"].as_f64());
     let duration_ms = latency.map(|l| l * 1000.0);
 
     // Extract request ID if present
@@ -145,22 +175,22 @@ pub fn parse_caddy_json(line: &str) -> Option<LogEntry> {
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    // Build message
-    let message = if method.is_some() && status.is_some() {
-        format!(
+    // Build message; anything but an access log keeps its own message, which
+    // explains what went wrong
+    let message = match (&method, resp_status) {
+        (Some(method), Some(status)) => format!(
             "{} {} {} {:.2}ms",
-            method.as_deref().unwrap_or("-"),
+            method,
             uri,
-            status.unwrap_or(0),
+            status,
             duration_ms.unwrap_or(0.0)
-        )
-    } else {
-        msg
+        ),
+        _ => msg,
     };
 
     Some(LogEntry {
         id: Uuid::new_v4().to_string(),
-        source: "caddy".to_string(),
+        source: CADDY_SOURCE.to_string(),
         instance_id: None,
         timestamp,
         level,
@@ -175,6 +205,15 @@ pub fn parse_caddy_json(line: &str) -> Option<LogEntry> {
     })
 }
 
+/// Whether a Caddy log entry is an access log line (not an error log line)
+///
+/// Lines without a logger name count as access log lines.
+pub fn is_access_log(entry: &LogEntry) -> bool {
+    entry.context["logger"]
+        .as_str()
+        .is_none_or(|logger| logger.starts_with("http.log.access"))
+}
+
 /// Parse a Laravel/Monolog JSON log line
 #[allow(dead_code)]
 pub fn parse_laravel_json(line: &str, instance_id: Option<&str>) -> Option<LogEntry> {
@@ -289,20 +328,41 @@ pub fn read_new_lines(path: &str, state: &mut LogFileState) -> Result<Vec<String
     Ok(lines)
 }
 
+/// Most bytes read from the end of a file when tailing it
+pub const MAX_TAIL_BYTES: u64 = 1024 * 1024;
+
 /// Get the last N lines from a file (for initial load)
+///
+/// Only the final `MAX_TAIL_BYTES` are read, so huge logs cost the same as small ones.
 pub fn get_last_lines(path: &str, count: usize) -> Result<Vec<String>, String> {
     let file = File::open(path).map_err(|e| format!("Failed to open log file {}: {}", path, e))?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?
+        .len();
+    let start_pos = file_size.saturating_sub(MAX_TAIL_BYTES);
 
-    let reader = BufReader::new(file);
-    let all_lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
+    let mut reader = BufReader::new(file);
+    reader
+        .seek(SeekFrom::Start(start_pos))
+        .map_err(|e| format!("Failed to seek: {}", e))?;
 
-    let start = if all_lines.len() > count {
-        all_lines.len() - count
-    } else {
-        0
-    };
+    let mut all_lines: Vec<String> = reader
+        .split(b'\n')
+        .map_while(Result::ok)
+        .map(|line| {
+            String::from_utf8_lossy(&line)
+                .trim_end_matches('\r')
+                .to_string()
+        })
+        .collect();
+    // The first line is likely cut off when starting mid-file
+    if start_pos > 0 && !all_lines.is_empty() {
+        all_lines.remove(0);
+    }
 
-    Ok(all_lines[start..].to_vec())
+    let start = all_lines.len().saturating_sub(count);
+    Ok(all_lines.split_off(start))
 }
 
 /// Get the path to the Caddy access log
@@ -310,10 +370,146 @@ pub fn get_caddy_log_path() -> PathBuf {
     get_logs_dir().join("caddy-access.json")
 }
 
+/// Directory holding the instance and worker logs
+pub fn logs_dir() -> Result<PathBuf, String> {
+    Ok(get_app_dir()?.join("logs"))
+}
+
 /// Get the log file path for an instance
 pub fn get_instance_log_path(instance_id: &str) -> Result<PathBuf, String> {
-    let log_dir = get_app_dir()?.join("logs");
-    Ok(log_dir.join(format!("{}.log", instance_id)))
+    Ok(logs_dir()?.join(format!("{}.log", instance_id)))
+}
+
+/// How the lines of a log file are parsed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    /// Caddy JSON, falling back to plain text for anything Caddy printed
+    /// before its logger was set up
+    Caddy,
+    /// Plain process output
+    Plain,
+}
+
+/// A log file feeding one of the sources
+#[derive(Debug, Clone)]
+pub struct LogFile {
+    pub path: PathBuf,
+    pub source: String,
+    pub format: LogFormat,
+    pub instance_id: Option<String>,
+    /// Shown as the domain of entries that don't carry one
+    pub label: Option<String>,
+}
+
+impl LogFile {
+    fn caddy(path: PathBuf, source: &str, label: Option<String>) -> Self {
+        Self {
+            path,
+            source: source.to_string(),
+            format: LogFormat::Caddy,
+            instance_id: None,
+            label,
+        }
+    }
+
+    fn plain(path: PathBuf, source: &str) -> Self {
+        Self {
+            path,
+            source: source.to_string(),
+            format: LogFormat::Plain,
+            instance_id: None,
+            label: None,
+        }
+    }
+
+    /// Parse a line from this file into an entry; blank lines are skipped
+    pub fn parse(&self, line: &str) -> Option<LogEntry> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let mut entry = match self.format {
+            LogFormat::Caddy => parse_caddy_json(trimmed)
+                .unwrap_or_else(|| parse_plain_text(trimmed, &self.source, None)),
+            LogFormat::Plain => parse_plain_text(trimmed, &self.source, None),
+        };
+        entry.source = self.source.clone();
+        entry.instance_id = self.instance_id.clone();
+        if entry.domain.is_none() {
+            entry.domain = self.label.clone();
+        }
+        Some(entry)
+    }
+}
+
+/// Full domains of every configured site, sorted
+pub fn site_domains(config: &Config) -> Vec<String> {
+    let mut domains: Vec<String> = config
+        .domains
+        .iter()
+        .map(|d| d.full_domain(&config.tld))
+        .collect();
+    domains.sort();
+    domains
+}
+
+/// Resolve source IDs to the files behind them (all sources when empty)
+///
+/// Site sources are only resolved for the given domains, so a source ID can't
+/// point outside the site log directory.
+pub fn log_files_for_sources(
+    sources: &[String],
+    instances: &[Instance],
+    site_domains: &[String],
+) -> Vec<LogFile> {
+    let wanted = |id: &str| sources.is_empty() || sources.iter().any(|s| s == id);
+    let mut files = Vec::new();
+
+    if wanted(CADDY_SOURCE) {
+        files.push(LogFile::caddy(get_caddy_log_path(), CADDY_SOURCE, None));
+    }
+    if wanted(CADDY_DAEMON_SOURCE) {
+        let (stdout_log, stderr_log) = proxy_output_paths();
+        files.push(LogFile::caddy(stdout_log, CADDY_DAEMON_SOURCE, None));
+        files.push(LogFile::caddy(stderr_log, CADDY_DAEMON_SOURCE, None));
+    }
+    if wanted(HELPER_SOURCE) {
+        let (stdout_log, stderr_log) = get_helper_output_paths();
+        files.push(LogFile::plain(stdout_log, HELPER_SOURCE));
+        files.push(LogFile::plain(stderr_log, HELPER_SOURCE));
+    }
+    if wanted(DAEMON_SOURCE) {
+        files.push(LogFile::plain(daemon::log_path(), DAEMON_SOURCE));
+    }
+    for domain in site_domains {
+        let source = format!("{}{}", CADDY_SITE_SOURCE_PREFIX, domain);
+        if wanted(&source) {
+            files.push(LogFile::caddy(
+                get_site_log_path(domain),
+                &source,
+                Some(domain.clone()),
+            ));
+        }
+    }
+
+    for instance in instances {
+        let svc_type = instance.service_type.as_str();
+        if svc_type == CADDY_SOURCE || !wanted(svc_type) {
+            continue;
+        }
+        if let Ok(path) = get_instance_log_path(&instance.id.to_string()) {
+            files.push(LogFile {
+                path,
+                source: svc_type.to_string(),
+                format: LogFormat::Plain,
+                instance_id: Some(instance.id.to_string()),
+                label: Some(instance.name.clone()),
+            });
+        }
+    }
+
+    files
 }
 
 /// Get a color for a given service type
@@ -333,9 +529,13 @@ pub fn color_for_service_type(svc_type: &str) -> &'static str {
         "typesense" => "#F97316",       // Orange
         "minio" => "#FB923C",           // Light orange
         "beanstalkd" => "#84CC16",      // Lime
+        "rabbitmq" => "#FF6600",        // RabbitMQ orange
+        "opensearch" => "#005EB8",      // OpenSearch blue
         "memcached" => "#6366F1",       // Indigo
         "frpc" => "#14B8A6",            // Teal
         "centrifugo" => "#F43F5E",      // Rose
+        "soketi" => "#8B5CF6",          // Violet
+        "keycloak" => "#008AAA",        // Keycloak teal
         "gitea" => "#22C55E",           // Green
         _ => "#8E8E93",                 // Gray
     }
@@ -358,32 +558,73 @@ pub fn display_name_for_service_type(svc_type: &str) -> &'static str {
         "typesense" => "Typesense",
         "minio" => "MinIO",
         "beanstalkd" => "Beanstalkd",
+        "rabbitmq" => "RabbitMQ",
+        "opensearch" => "OpenSearch",
         "memcached" => "Memcached",
         "frpc" => "Tunnels (frpc)",
         "centrifugo" => "Centrifugo",
+        "soketi" => "Soketi",
+        "keycloak" => "Keycloak",
         "gitea" => "Gitea",
         _ => "Unknown",
     }
 }
 
-/// Get available log sources including per-instance-type sources
-pub fn get_log_sources_with_instances(instances: &[Instance]) -> Vec<LogSourceInfo> {
+/// Get available log sources: the proxy, the launchd services, the proxy's
+/// sites, and per-instance-type sources
+pub fn get_log_sources_with_instances(
+    instances: &[Instance],
+    site_domains: &[String],
+) -> Vec<LogSourceInfo> {
+    let (_, stderr_log) = proxy_output_paths();
+    let (_, helper_stderr_log) = get_helper_output_paths();
     let mut sources = vec![
         LogSourceInfo {
-            id: "caddy".to_string(),
+            id: CADDY_SOURCE.to_string(),
             name: "Caddy (Proxy)".to_string(),
             log_type: "file".to_string(),
             path: Some(get_caddy_log_path().to_string_lossy().to_string()),
             color: "#3B82F6".to_string(),
         },
+        LogSourceInfo {
+            id: CADDY_DAEMON_SOURCE.to_string(),
+            name: "Caddy Daemon (Proxy)".to_string(),
+            log_type: "stdout".to_string(),
+            path: Some(stderr_log.to_string_lossy().to_string()),
+            color: "#1D4ED8".to_string(),
+        },
+        LogSourceInfo {
+            id: HELPER_SOURCE.to_string(),
+            name: "Privileged Helper".to_string(),
+            log_type: "stdout".to_string(),
+            path: Some(helper_stderr_log.to_string_lossy().to_string()),
+            color: "#64748B".to_string(),
+        },
+        LogSourceInfo {
+            id: DAEMON_SOURCE.to_string(),
+            name: "Burd Daemon".to_string(),
+            log_type: "stdout".to_string(),
+            path: Some(daemon::log_path().to_string_lossy().to_string()),
+            color: "#475569".to_string(),
+        },
     ];
 
+    for domain in site_domains {
+        sources.push(LogSourceInfo {
+            id: format!("{}{}", CADDY_SITE_SOURCE_PREFIX, domain),
+            name: domain.clone(),
+            log_type: "site".to_string(),
+            path: Some(get_site_log_path(domain).to_string_lossy().to_string()),
+            color: "#60A5FA".to_string(),
+        });
+    }
+
     // Collect unique service types from instances
     let mut seen_types = std::collections::HashSet::new();
     for instance in instances {
         let svc_type = instance.service_type.as_str();
         // Skip caddy instances (already included above)
-        if svc_type == "caddy" {
+        if svc_type == CADDY_SOURCE {
             continue;
         }
         if seen_types.insert(svc_type.to_string()) {
@@ -400,9 +641,319 @@ pub fn get_log_sources_with_instances(instances: &[Instance]) -> Vec<LogSourceIn
     sources
 }
 
+// ============================================================================
+// Rotation and Retention
+// ============================================================================
+
+/// Delay between log size checks
+const ROTATE_INTERVAL: Duration = Duration::from_secs(60);
+
+const MB: u64 = 1024 * 1024;
+
+/// Smallest size a log can be rotated at
+pub const MIN_LOG_FILE_MB: u64 = 1;
+
+/// Largest size a log can be rotated at
+pub const MAX_LOG_FILE_MB: u64 = 1024;
+
+/// Most rotated files that can be kept per log
+pub const MAX_KEEP_FILES: u32 = 50;
+
+/// Disk use of one log and its rotated files
+#[derive(Debug, Clone, Serialize)]
+pub struct LogUsageEntry {
+    /// File name of the log, e.g. `<instance id>.log`
+    pub name: String,
+    /// Instance name or worker project, the file name for orphaned logs
+    pub label: String,
+    /// Size of the log being written
+    pub bytes: u64,
+    pub rotated_bytes: u64,
+    pub rotated_files: usize,
+    /// Left behind by an instance or worker that no longer exists
+    pub orphaned: bool,
+}
+
+/// Disk use of the logs directory
+#[derive(Debug, Clone, Serialize)]
+pub struct LogUsage {
+    pub dir: String,
+    pub total_bytes: u64,
+    pub cap_bytes: u64,
+    /// Largest first
+    pub logs: Vec<LogUsageEntry>,
+}
+
+/// What `prune_logs` removed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneResult {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
+
+/// A file in the logs directory
+struct LogDirFile {
+    path: PathBuf,
+    /// Name of the log it belongs to
+    base: String,
+    /// Rotation index, `None` for the log being written
+    index: Option<u32>,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Check the rotation size, kept files and total cap
+pub fn validate_retention_settings(
+    settings: LogRetentionSettings,
+) -> Result<LogRetentionSettings, String> {
+    if !(MIN_LOG_FILE_MB..=MAX_LOG_FILE_MB).contains(&settings.max_file_mb) {
+        return Err(format!(
+            "Logs must be rotated at between {} and {} MB",
+            MIN_LOG_FILE_MB, MAX_LOG_FILE_MB
+        ));
+    }
+    if settings.keep_files > MAX_KEEP_FILES {
+        return Err(format!(
+            "At most {} rotated files can be kept per log",
+            MAX_KEEP_FILES
+        ));
+    }
+    if settings.total_cap_mb < settings.max_file_mb {
+        return Err("The total cap can't be smaller than the rotation size".to_string());
+    }
+    Ok(settings)
+}
+
+/// The log a file in the logs directory belongs to and its rotation index:
+/// `x.log` is `("x.log", None)`, `x.log.2` is `("x.log", Some(2))`
+fn parse_log_name(name: &str) -> Option<(&str, Option<u32>)> {
+    if name.ends_with(".log") {
+        return Some((name, None));
+    }
+    let (base, index) = name.rsplit_once('.')?;
+    if !base.ends_with(".log") {
+        return None;
+    }
+    Some((base, Some(index.parse().ok()?)))
+}
+
+/// Path of the `index`th rotated copy of a log, 1 being the newest
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+fn scan_logs_dir(dir: &Path) -> Vec<LogDirFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (base, index) = parse_log_name(&name)?;
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some(LogDirFile {
+                path: entry.path(),
+                base: base.to_string(),
+                index,
+                bytes: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            })
+        })
+        .collect()
+}
+
+fn truncate_file(path: &Path) -> io::Result<()> {
+    OpenOptions::new().write(true).open(path)?.set_len(0)
+}
+
+/// Rotate a log: `x.log.1` becomes `x.log.2` and so on, dropping the oldest,
+/// then the log is copied to `x.log.1` and emptied
+///
+/// The log is copied rather than renamed because the process writing it
+/// keeps it open. It writes in append mode, so it carries on at the start of
+/// the emptied file; lines written between the copy and the truncation are
+/// lost.
+pub fn rotate_file(path: &Path, keep_files: u32) -> io::Result<()> {
+    if keep_files > 0 {
+        let _ = fs::remove_file(rotated_path(path, keep_files));
+        for index in (1..keep_files).rev() {
+            let from = rotated_path(path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, index + 1))?;
+            }
+        }
+        fs::copy(path, rotated_path(path, 1))?;
+    }
+    truncate_file(path)
+}
+
+/// Rotate the logs in `dir` past the size limit, drop rotated files beyond
+/// `keep_files`, then bring the directory under the total cap
+///
+/// The cap removes the oldest rotated files first and only empties logs
+/// being written, largest first, when that isn't enough.
+pub fn enforce_retention_in(dir: &Path, settings: &LogRetentionSettings) {
+    let max_bytes = settings.max_file_mb * MB;
+    for file in scan_logs_dir(dir) {
+        let result = match file.index {
+            None if file.bytes > max_bytes => rotate_file(&file.path, settings.keep_files),
+            Some(index) if index > settings.keep_files => fs::remove_file(&file.path),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to rotate {}: {}", file.path.display(), e);
+        }
+    }
+
+    let cap_bytes = settings.total_cap_mb * MB;
+    let files = scan_logs_dir(dir);
+    let mut total: u64 = files.iter().map(|f| f.bytes).sum();
+    if total <= cap_bytes {
+        return;
+    }
+
+    let (mut rotated, mut current): (Vec<_>, Vec<_>) =
+        files.into_iter().partition(|f| f.index.is_some());
+    rotated.sort_by_key(|f| (f.modified, Reverse(f.index)));
+    current.sort_by_key(|f| Reverse(f.bytes));
+
+    for file in rotated {
+        if total <= cap_bytes {
+            return;
+        }
+        if fs::remove_file(&file.path).is_ok() {
+            total -= file.bytes;
+        }
+    }
+    for file in current {
+        if total <= cap_bytes {
+            return;
+        }
+        if truncate_file(&file.path).is_ok() {
+            total -= file.bytes;
+        }
+    }
+}
+
+/// Rotate and cap the instance and worker logs
+pub fn enforce_retention(settings: &LogRetentionSettings) -> Result<(), String> {
+    enforce_retention_in(&logs_dir()?, settings);
+    Ok(())
+}
+
+/// File names of the logs of existing instances and workers, with a label
+/// for each
+pub fn log_owners(config: &Config) -> HashMap<String, String> {
+    let instances = config
+        .instances
+        .iter()
+        .map(|i| (format!("{}.log", i.id), i.name.clone()));
+    let workers = config.workers.iter().map(|w| {
+        let project = Path::new(&w.project_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| w.project_path.clone());
+        (
+            format!("worker-{}.log", w.id),
+            format!("Worker: {}", project),
+        )
+    });
+    instances.chain(workers).collect()
+}
+
+/// Disk use of the logs in `dir`, grouped per log
+pub fn usage_in(dir: &Path, owners: &HashMap<String, String>, cap_bytes: u64) -> LogUsage {
+    let mut logs: HashMap<String, LogUsageEntry> = HashMap::new();
+    for file in scan_logs_dir(dir) {
+        let entry = logs
+            .entry(file.base.clone())
+            .or_insert_with(|| LogUsageEntry {
+                label: owners
+                    .get(&file.base)
+                    .cloned()
+                    .unwrap_or_else(|| file.base.clone()),
+                orphaned: !owners.contains_key(&file.base),
+                name: file.base,
+                bytes: 0,
+                rotated_bytes: 0,
+                rotated_files: 0,
+            });
+        match file.index {
+            None => entry.bytes = file.bytes,
+            Some(_) => {
+                entry.rotated_bytes += file.bytes;
+                entry.rotated_files += 1;
+            }
+        }
+    }
+
+    let mut logs: Vec<LogUsageEntry> = logs.into_values().collect();
+    logs.sort_by(|a, b| {
+        (b.bytes + b.rotated_bytes)
+            .cmp(&(a.bytes + a.rotated_bytes))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    LogUsage {
+        dir: dir.to_string_lossy().to_string(),
+        total_bytes: logs.iter().map(|l| l.bytes + l.rotated_bytes).sum(),
+        cap_bytes,
+        logs,
+    }
+}
+
+/// Remove the rotated files in `dir` and the logs of instances and workers
+/// that no longer exist
+pub fn prune_in(dir: &Path, owners: &HashMap<String, String>) -> PruneResult {
+    let mut result = PruneResult::default();
+    for file in scan_logs_dir(dir) {
+        if file.index.is_none() && owners.contains_key(&file.base) {
+            continue;
+        }
+        if fs::remove_file(&file.path).is_ok() {
+            result.files_removed += 1;
+            result.bytes_freed += file.bytes;
+        }
+    }
+    result
+}
+
+/// Disk use of the instance and worker logs
+pub fn get_log_usage(config: &Config) -> Result<LogUsage, String> {
+    Ok(usage_in(
+        &logs_dir()?,
+        &log_owners(config),
+        config.log_retention.total_cap_mb * MB,
+    ))
+}
+
+/// Remove rotated logs and the logs of deleted instances and workers
+pub fn prune_logs(config: &Config) -> Result<PruneResult, String> {
+    Ok(prune_in(&logs_dir()?, &log_owners(config)))
+}
+
+/// Rotate and cap the logs forever
+pub async fn run(state: AppState) {
+    loop {
+        let settings = match state.config_store.lock().map(|store| store.load()) {
+            Ok(Ok(config)) => Some(config.log_retention),
+            _ => None,
+        };
+        if let Some(settings) = settings.filter(|s| s.enabled) {
+            let _ = tokio::task::spawn_blocking(move || enforce_retention(&settings)).await;
+        }
+
+        tokio::time::sleep(ROTATE_INTERVAL).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ServiceType;
+    use crate::test_utils::InstanceBuilder;
 
     #[test]
     fn test_parse_caddy_json() {
@@ -416,6 +967,97 @@ mod tests {
         assert_eq!(entry.status, Some(200));
     }
 
+    #[test]
+    fn test_parse_caddy_json_error_keeps_message() {
+        let line = r#"{"level":"error","ts":1704067200.5,"logger":"http.log.error","msg":"dial tcp 127.0.0.1:8000: connect: connection refused","request":{"method":"GET","host":"api.test","uri":"/"},"duration":0.001,"status":502}"#;
+
+        let entry = parse_caddy_json(line).unwrap();
+        assert_eq!(entry.level, "ERROR");
+        assert_eq!(entry.status, Some(502));
+        assert_eq!(entry.domain, Some("api.test".to_string()));
+        assert_eq!(
+            entry.message,
+            "dial tcp 127.0.0.1:8000: connect: connection refused"
+        );
+    }
+
+    #[test]
+    fn test_log_file_parse() {
+        let file = LogFile::caddy(
+            PathBuf::from("/tmp/sites/shop.test.json"),
+            "caddy-site:shop.test",
+            Some("shop.test".to_string()),
+        );
+
+        let entry = file
+            .parse(r#"{"level":"info","ts":1704067200.0,"msg":"handled request","request":{"method":"GET","uri":"/"},"resp":{"status":200,"duration":0.01}}"#)
+            .unwrap();
+        assert_eq!(entry.source, "caddy-site:shop.test");
+        assert_eq!(entry.domain, Some("shop.test".to_string()));
+        assert_eq!(entry.status, Some(200));
+
+        // Output from before the logger is set up isn't JSON
+        let entry = file
+            .parse("Error: loading initial config: bad site")
+            .unwrap();
+        assert_eq!(entry.source, "caddy-site:shop.test");
+        assert_eq!(entry.message, "Error: loading initial config: bad site");
+
+        assert!(file.parse("   ").is_none());
+    }
+
+    #[test]
+    fn test_log_files_for_sources() {
+        let instances = vec![
+            InstanceBuilder::new()
+                .name("search")
+                .service_type(ServiceType::Meilisearch)
+                .build(),
+            InstanceBuilder::new()
+                .name("cache")
+                .service_type(ServiceType::Redis)
+                .build(),
+        ];
+        let sites = vec!["shop.test".to_string()];
+
+        let all = log_files_for_sources(&[], &instances, &sites);
+        let sources: Vec<&str> = all.iter().map(|f| f.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            vec![
+                "caddy",
+                "caddy-daemon",
+                "caddy-daemon",
+                "helper",
+                "helper",
+                "burd-daemon",
+                "caddy-site:shop.test",
+                "meilisearch",
+                "redis"
+            ]
+        );
+
+        let files = log_files_for_sources(
+            &["caddy-site:shop.test".to_string(), "redis".to_string()],
+            &instances,
+            &sites,
+        );
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, get_site_log_path("shop.test"));
+        assert_eq!(files[1].label, Some("cache".to_string()));
+        assert_eq!(files[1].format, LogFormat::Plain);
+
+        let files = log_files_for_sources(&["helper".to_string()], &instances, &sites);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, get_helper_output_paths().0);
+        assert_eq!(files[1].format, LogFormat::Plain);
+
+        // Sites that aren't configured don't resolve to a path
+        let files =
+            log_files_for_sources(&["caddy-site:../../etc".to_string()], &instances, &sites);
+        assert!(files.is_empty());
+    }
+
     #[test]
     fn test_parse_plain_text_error() {
         let line = "[ERROR] Something went wrong";
@@ -423,6 +1065,175 @@ mod tests {
         assert_eq!(entry.level, "ERROR");
     }
 
+    #[test]
+    fn test_get_last_lines_reads_only_the_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.log");
+        let line = "x".repeat(99);
+        let mut content = String::new();
+        for _ in 0..(MAX_TAIL_BYTES / 100 + 50) {
+            content.push_str(&line);
+            content.push('\n');
+        }
+        content.push_str("second to last\nlast\n");
+        std::fs::write(&path, content).unwrap();
+
+        let lines = get_last_lines(path.to_str().unwrap(), 2).unwrap();
+        assert_eq!(lines, vec!["second to last", "last"]);
+
+        let lines = get_last_lines(path.to_str().unwrap(), usize::MAX).unwrap();
+        assert!(lines.len() <= (MAX_TAIL_BYTES / 100) as usize + 2);
+        assert!(lines.iter().take(lines.len() - 2).all(|l| *l == line));
+    }
+
+    fn retention(max_file_mb: u64, keep_files: u32, total_cap_mb: u64) -> LogRetentionSettings {
+        LogRetentionSettings {
+            enabled: true,
+            max_file_mb,
+            keep_files,
+            total_cap_mb,
+        }
+    }
+
+    fn write_mb(path: &Path, mb: u64, byte: u8) {
+        fs::write(path, vec![byte; (mb * MB) as usize]).unwrap();
+    }
+
+    #[test]
+    fn test_parse_log_name() {
+        assert_eq!(parse_log_name("a.log"), Some(("a.log", None)));
+        assert_eq!(parse_log_name("a.log.3"), Some(("a.log", Some(3))));
+        assert_eq!(parse_log_name("a.log.old"), None);
+        assert_eq!(parse_log_name("a.txt.1"), None);
+        assert_eq!(parse_log_name(".DS_Store"), None);
+    }
+
+    #[test]
+    fn test_rotate_file_shifts_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("a.log");
+        for run in ["first", "second", "third"] {
+            fs::write(&log, run).unwrap();
+            rotate_file(&log, 2).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&log).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.log.1")).unwrap(),
+            "third"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.log.2")).unwrap(),
+            "second"
+        );
+        assert!(!dir.path().join("a.log.3").exists());
+    }
+
+    #[test]
+    fn test_rotate_file_keeps_appending_writer() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("a.log");
+        let mut writer = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log)
+            .unwrap();
+        writer.write_all(b"before\n").unwrap();
+
+        rotate_file(&log, 1).unwrap();
+        writer.write_all(b"after\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&log).unwrap(), "after\n");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.log.1")).unwrap(),
+            "before\n"
+        );
+    }
+
+    #[test]
+    fn test_enforce_retention_rotates_large_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mb(&dir.path().join("big.log"), 2, b'b');
+        fs::write(dir.path().join("small.log"), "ok").unwrap();
+        // Left over from a higher keep_files
+        fs::write(dir.path().join("small.log.4"), "old").unwrap();
+
+        enforce_retention_in(dir.path(), &retention(1, 3, 100));
+
+        assert_eq!(fs::metadata(dir.path().join("big.log")).unwrap().len(), 0);
+        assert_eq!(
+            fs::metadata(dir.path().join("big.log.1")).unwrap().len(),
+            2 * MB
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("small.log")).unwrap(),
+            "ok"
+        );
+        assert!(!dir.path().join("small.log.4").exists());
+    }
+
+    #[test]
+    fn test_enforce_retention_caps_total_size() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mb(&dir.path().join("a.log.2"), 1, b'2');
+        write_mb(&dir.path().join("a.log.1"), 1, b'1');
+        write_mb(&dir.path().join("a.log"), 1, b'a');
+        write_mb(&dir.path().join("b.log"), 1, b'b');
+
+        // The oldest rotated file goes first
+        enforce_retention_in(dir.path(), &retention(10, 5, 3));
+        assert!(!dir.path().join("a.log.2").exists());
+        assert!(dir.path().join("a.log.1").exists());
+
+        // Logs being written are only emptied once nothing rotated is left
+        enforce_retention_in(dir.path(), &retention(10, 5, 1));
+        assert!(!dir.path().join("a.log.1").exists());
+        let sizes: Vec<u64> = ["a.log", "b.log"]
+            .iter()
+            .map(|name| fs::metadata(dir.path().join(name)).unwrap().len())
+            .collect();
+        assert_eq!(sizes.iter().sum::<u64>(), MB);
+    }
+
+    #[test]
+    fn test_usage_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("web.log"), "12345").unwrap();
+        fs::write(dir.path().join("web.log.1"), "123").unwrap();
+        fs::write(dir.path().join("web.log.2"), "12").unwrap();
+        fs::write(dir.path().join("gone.log"), "1").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a log").unwrap();
+        let owners = HashMap::from([("web.log".to_string(), "Web".to_string())]);
+
+        let usage = usage_in(dir.path(), &owners, 100);
+        assert_eq!(usage.total_bytes, 11);
+        assert_eq!(usage.logs.len(), 2);
+        assert_eq!(usage.logs[0].label, "Web");
+        assert_eq!(usage.logs[0].bytes, 5);
+        assert_eq!(usage.logs[0].rotated_bytes, 5);
+        assert_eq!(usage.logs[0].rotated_files, 2);
+        assert!(!usage.logs[0].orphaned);
+        assert_eq!(usage.logs[1].label, "gone.log");
+        assert!(usage.logs[1].orphaned);
+
+        let result = prune_in(dir.path(), &owners);
+        assert_eq!(result.files_removed, 3);
+        assert_eq!(result.bytes_freed, 6);
+        assert_eq!(usage_in(dir.path(), &owners, 100).total_bytes, 5);
+        assert!(dir.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_validate_retention_settings() {
+        assert!(validate_retention_settings(retention(10, 5, 1024)).is_ok());
+        assert!(validate_retention_settings(retention(10, 0, 10)).is_ok());
+        assert!(validate_retention_settings(retention(0, 5, 1024)).is_err());
+        assert!(validate_retention_settings(retention(10, MAX_KEEP_FILES + 1, 1024)).is_err());
+        assert!(validate_retention_settings(retention(10, 5, 5)).is_err());
+    }
+
     #[test]
     fn test_log_entry_new() {
         let entry = LogEntry::new("test", "INFO", "Test message");
@@ -1,15 +1,18 @@
 //! Mailpit notification service
 //!
-//! Connects to Mailpit's WebSocket API to receive real-time email notifications.
+//! Connects to each running Mailpit's WebSocket API to receive real-time email notifications.
 //! Emits events to the frontend when new emails arrive.
 
 use crate::commands::AppState;
-use crate::config::ServiceType;
+use crate::services::mailpit::MailpitService;
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager, State};
 use tokio_tungstenite::connect_async;
+use uuid::Uuid;
 
 /// Payload emitted when a new email arrives
 #[derive(Debug, Clone, Serialize)]
@@ -18,6 +21,8 @@ pub struct NewEmailPayload {
     pub from_address: String,
     pub subject: String,
     pub id: String,
+    /// Mailpit instance that captured the email
+    pub instance_id: String,
 }
 
 /// Mailpit WebSocket message types
@@ -53,36 +58,87 @@ struct MailpitAddress {
 /// Shared state for mail notifier
 pub struct MailNotifierState {
     running: AtomicBool,
+    /// Mailpit instances that currently have a WebSocket listener
+    watched: Mutex<HashSet<Uuid>>,
 }
 
 impl Default for MailNotifierState {
     fn default() -> Self {
         Self {
             running: AtomicBool::new(false),
+            watched: Mutex::new(HashSet::new()),
         }
     }
 }
 
-/// Get the Mailpit HTTP port from config
-fn get_mailpit_port(state: &State<'_, AppState>) -> Option<u16> {
-    let config_store = state.config_store.lock().ok()?;
-    let config = config_store.load().ok()?;
-
-    let mailpit = config
-        .instances
-        .iter()
-        .find(|i| i.service_type == ServiceType::Mailpit)?;
+/// Get the HTTP ports of all running Mailpit instances
+fn get_running_mailpits(state: &State<'_, AppState>) -> Vec<(Uuid, u16)> {
+    let Ok(config_store) = state.config_store.lock() else {
+        return Vec::new();
+    };
+    let Ok(config) = config_store.load() else {
+        return Vec::new();
+    };
+    let Ok(process_manager) = state.process_manager.lock() else {
+        return Vec::new();
+    };
+
+    MailpitService::instances(&config)
+        .into_iter()
+        .filter(|i| process_manager.is_running(&i.id))
+        .map(|i| (i.id, i.port))
+        .collect()
+}
 
-    // Check if running
-    let process_manager = state.process_manager.lock().ok()?;
-    if !process_manager.is_running(&mailpit.id) {
-        return None;
+/// Forward new-email events from one Mailpit instance until the socket closes
+async fn watch_mailpit(app_handle: &AppHandle, instance_id: Uuid, port: u16) {
+    let ws_url = format!("ws://127.0.0.1:{}/api/events", port);
+
+    if let Ok((ws_stream, _)) = connect_async(&ws_url).await {
+        let (_, mut read) = ws_stream.split();
+
+        while let Some(msg_result) = read.next().await {
+            match msg_result {
+                Ok(msg) => {
+                    if let Ok(text) = msg.to_text() {
+                        if let Ok(MailpitEvent::New { data: email }) =
+                            serde_json::from_str::<MailpitEvent>(text)
+                        {
+                            let payload = NewEmailPayload {
+                                from_name: email
+                                    .From
+                                    .as_ref()
+                                    .map(|f| f.Name.clone())
+                                    .unwrap_or_default(),
+                                from_address: email
+                                    .From
+                                    .as_ref()
+                                    .map(|f| f.Address.clone())
+                                    .unwrap_or_else(|| "Unknown".to_string()),
+                                subject: email
+                                    .Subject
+                                    .unwrap_or_else(|| "(No subject)".to_string()),
+                                id: email.ID,
+                                instance_id: instance_id.to_string(),
+                            };
+
+                            // Emit event to frontend
+                            let _ = app_handle.emit("new-email", payload.clone());
+                        }
+                    }
+                }
+                Err(_) => {
+                    break;
+                }
+            }
+        }
     }
-
-    Some(mailpit.port)
 }
 
 /// Start the mail notifier WebSocket listener
+///
+/// Every running Mailpit instance gets its own listener; new instances are
+/// picked up on the next poll.
 pub fn start_mail_notifier(app_handle: AppHandle) {
     tauri::async_runtime::spawn(async move {
         let state = app_handle.state::<AppState>();
@@ -94,59 +150,31 @@ pub fn start_mail_notifier(app_handle: AppHandle) {
         }
 
         loop {
-            // Get Mailpit port
-            let port = match get_mailpit_port(&state) {
-                Some(p) => p,
-                None => {
-                    // Mailpit not running, wait and retry
-                    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+            for (instance_id, port) in get_running_mailpits(&state) {
+                let newly_watched = notifier_state
+                    .watched
+                    .lock()
+                    .map(|mut watched| watched.insert(instance_id))
+                    .unwrap_or(false);
+                if !newly_watched {
                     continue;
                 }
-            };
-
-            let ws_url = format!("ws://127.0.0.1:{}/api/events", port);
-
-            if let Ok((ws_stream, _)) = connect_async(&ws_url).await {
-                let (_, mut read) = ws_stream.split();
-
-                while let Some(msg_result) = read.next().await {
-                    match msg_result {
-                        Ok(msg) => {
-                            if let Ok(text) = msg.to_text() {
-                                if let Ok(MailpitEvent::New { data: email }) =
-                                    serde_json::from_str::<MailpitEvent>(text)
-                                {
-                                    let payload = NewEmailPayload {
-                                        from_name: email
-                                            .From
-                                            .as_ref()
-                                            .map(|f| f.Name.clone())
-                                            .unwrap_or_default(),
-                                        from_address: email
-                                            .From
-                                            .as_ref()
-                                            .map(|f| f.Address.clone())
-                                            .unwrap_or_else(|| "Unknown".to_string()),
-                                        subject: email
-                                            .Subject
-                                            .unwrap_or_else(|| "(No subject)".to_string()),
-                                        id: email.ID,
-                                    };
-
-                                    // Emit event to frontend
-                                    let _ = app_handle.emit("new-email", payload.clone());
-                                }
-                            }
-                        }
-                        Err(_) => {
-                            break;
-                        }
+
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    watch_mailpit(&app_handle, instance_id, port).await;
+
+                    // Wait before allowing a reconnect
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    if let Ok(mut watched) = app_handle.state::<MailNotifierState>().watched.lock()
+                    {
+                        watched.remove(&instance_id);
                     }
-                }
+                });
             }
 
-            // Wait before reconnecting
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            // Poll for Mailpit instances that started or need reconnecting
+            tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
         }
     });
 }
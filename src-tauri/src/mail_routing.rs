@@ -0,0 +1,357 @@
+//! Mail routing for linked projects
+//!
+//! A project sends mail to the Mailpit of its stack by default (see
+//! `MailpitService::for_project`). A route sends one project's mail somewhere
+//! else: another Mailpit instance, or a real SMTP server such as a staging
+//! relay. `burd env check`, `burd env fix` and the app's .env fixes then
+//! suggest the route's MAIL_* values.
+//!
+//! Routes don't store a relay password. It belongs in the project's vault
+//! (`burd env set MAIL_PASSWORD`), which overrides .env at runtime.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::config::{Config, Instance, ServiceType};
+use crate::services::mailpit::MailpitService;
+use crate::vault;
+
+/// Where a project's mail goes instead of its stack's Mailpit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailRoute {
+    pub id: Uuid,
+    /// Project root directory
+    pub project: String,
+    pub target: MailRouteTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MailRouteTarget {
+    /// A specific Mailpit instance
+    Mailpit { instance_id: Uuid },
+    /// A real SMTP server
+    Smtp(SmtpRelay),
+}
+
+/// An SMTP server mail is relayed through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmtpRelay {
+    pub host: String,
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// "tls" or "ssl"; none for a plain connection
+    #[serde(default)]
+    pub encryption: Option<String>,
+}
+
+impl SmtpRelay {
+    /// Validate a relay, normalizing its host and encryption
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: Option<&str>,
+        encryption: Option<&str>,
+    ) -> Result<Self, String> {
+        let host = host.trim().to_lowercase();
+        let valid_host = !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':'));
+        if !valid_host {
+            return Err(format!("Invalid SMTP host '{}'", host));
+        }
+        if port == 0 {
+            return Err("SMTP port must be between 1 and 65535".to_string());
+        }
+
+        let encryption = match encryption.map(|e| e.trim().to_lowercase()) {
+            None => None,
+            Some(e) if e.is_empty() || e == "none" => None,
+            Some(e) if e == "tls" || e == "ssl" => Some(e),
+            Some(e) => {
+                return Err(format!(
+                    "Unknown encryption '{}'. Use 'tls', 'ssl' or 'none'",
+                    e
+                ))
+            }
+        };
+
+        Ok(Self {
+            host,
+            port,
+            username: username
+                .map(str::trim)
+                .filter(|u| !u.is_empty())
+                .map(str::to_string),
+            encryption,
+        })
+    }
+}
+
+/// Parse "host:port" (the port defaults to 587)
+pub fn parse_host_port(value: &str) -> Result<(String, u16), String> {
+    match value.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port
+                .parse()
+                .map_err(|_| format!("Invalid SMTP port in '{}'", value))?;
+            Ok((host.to_string(), port))
+        }
+        None => Ok((value.to_string(), 587)),
+    }
+}
+
+/// Check a route target before it's saved, normalizing a relay
+pub fn validate_target(
+    config: &Config,
+    target: MailRouteTarget,
+) -> Result<MailRouteTarget, String> {
+    match target {
+        MailRouteTarget::Mailpit { instance_id } => {
+            let instance = config
+                .instances
+                .iter()
+                .find(|i| i.id == instance_id)
+                .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+            if instance.service_type != ServiceType::Mailpit {
+                return Err(format!(
+                    "Instance '{}' is not a Mailpit instance",
+                    instance.name
+                ));
+            }
+            Ok(target)
+        }
+        MailRouteTarget::Smtp(relay) => SmtpRelay::new(
+            &relay.host,
+            relay.port,
+            relay.username.as_deref(),
+            relay.encryption.as_deref(),
+        )
+        .map(MailRouteTarget::Smtp),
+    }
+}
+
+/// The route of the project `dir` belongs to
+pub fn find_route<'a>(routes: &'a [MailRoute], dir: &Path) -> Option<&'a MailRoute> {
+    let root = vault::project_root(dir);
+    routes.iter().find(|r| Path::new(&r.project) == root)
+}
+
+/// Route the project `dir` belongs to, replacing its previous route
+pub fn set_route(routes: &mut Vec<MailRoute>, dir: &Path, target: MailRouteTarget) -> MailRoute {
+    let project = vault::project_root(dir).to_string_lossy().to_string();
+    routes.retain(|r| r.project != project);
+    let route = MailRoute {
+        id: Uuid::new_v4(),
+        project,
+        target,
+    };
+    routes.push(route.clone());
+    route
+}
+
+/// Where a project's mail goes
+#[derive(Debug, Clone, Copy)]
+pub enum MailDestination<'a> {
+    Mailpit(&'a Instance),
+    Smtp(&'a SmtpRelay),
+}
+
+/// A MAIL_* value in .env that differs from the destination's
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailEnvChange {
+    pub key: &'static str,
+    pub current: String,
+    pub suggested: String,
+}
+
+/// Values Laravel's .env template uses for "not set"
+fn is_unset(value: &str) -> bool {
+    value.is_empty() || value.eq_ignore_ascii_case("null")
+}
+
+impl MailDestination<'_> {
+    pub fn describe(&self) -> String {
+        match self {
+            MailDestination::Mailpit(instance) => format!(
+                "Mailpit '{}' (SMTP port {})",
+                instance.name,
+                MailpitService::smtp_port(instance)
+            ),
+            MailDestination::Smtp(relay) => {
+                format!("SMTP relay {}:{}", relay.host, relay.port)
+            }
+        }
+    }
+
+    /// The MAIL_* settings that send a Laravel app's mail here
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let (host, port, username, encryption) = match self {
+            MailDestination::Mailpit(instance) => (
+                "127.0.0.1".to_string(),
+                MailpitService::smtp_port(instance),
+                None,
+                None,
+            ),
+            MailDestination::Smtp(relay) => (
+                relay.host.clone(),
+                relay.port,
+                relay.username.clone(),
+                relay.encryption.clone(),
+            ),
+        };
+
+        vec![
+            ("MAIL_MAILER", "smtp".to_string()),
+            ("MAIL_HOST", host),
+            ("MAIL_PORT", port.to_string()),
+            ("MAIL_USERNAME", username.unwrap_or_default()),
+            ("MAIL_ENCRYPTION", encryption.unwrap_or_default()),
+        ]
+    }
+
+    /// MAIL_* values in `env` that have to change to send mail here
+    pub fn env_changes(&self, env: &HashMap<String, String>) -> Vec<MailEnvChange> {
+        self.env()
+            .into_iter()
+            .filter_map(|(key, suggested)| {
+                let current = env.get(key).cloned().unwrap_or_default();
+                let same = current == suggested || (is_unset(&current) && is_unset(&suggested));
+                (!same).then_some(MailEnvChange {
+                    key,
+                    current,
+                    suggested,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Where a routed project's mail goes
+///
+/// `None` when the project has no route, or its Mailpit was removed; the
+/// stack's Mailpit applies then.
+pub fn routed_destination<'a>(config: &'a Config, dir: &Path) -> Option<MailDestination<'a>> {
+    match &find_route(&config.mail_routes, dir)?.target {
+        MailRouteTarget::Mailpit { instance_id } => config
+            .instances
+            .iter()
+            .find(|i| i.id == *instance_id)
+            .map(MailDestination::Mailpit),
+        MailRouteTarget::Smtp(relay) => Some(MailDestination::Smtp(relay)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_relay_validation() {
+        let relay = SmtpRelay::new(" SMTP.Staging.example ", 587, Some(""), Some("TLS")).unwrap();
+        assert_eq!(relay.host, "smtp.staging.example");
+        assert_eq!(relay.username, None);
+        assert_eq!(relay.encryption.as_deref(), Some("tls"));
+
+        assert!(SmtpRelay::new("smtp example", 587, None, None).is_err());
+        assert!(SmtpRelay::new("smtp.example", 0, None, None).is_err());
+        assert!(SmtpRelay::new("smtp.example", 25, None, Some("starttls")).is_err());
+        assert_eq!(
+            parse_host_port("smtp.example:2525").unwrap(),
+            ("smtp.example".to_string(), 2525)
+        );
+        assert_eq!(parse_host_port("smtp.example").unwrap().1, 587);
+    }
+
+    #[test]
+    fn test_route_replaces_previous_and_matches_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("composer.json"), "{}").unwrap();
+        std::fs::create_dir(dir.path().join("app")).unwrap();
+
+        let mut routes = Vec::new();
+        let relay = SmtpRelay::new("smtp.example", 587, None, None).unwrap();
+        set_route(&mut routes, dir.path(), MailRouteTarget::Smtp(relay));
+        let mailpit = MailRouteTarget::Mailpit {
+            instance_id: Uuid::new_v4(),
+        };
+        set_route(&mut routes, &dir.path().join("app"), mailpit.clone());
+
+        assert_eq!(routes.len(), 1);
+        let route = find_route(&routes, &dir.path().join("app")).unwrap();
+        assert_eq!(route.target, mailpit);
+    }
+
+    #[test]
+    fn test_routed_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+        let mailpit = InstanceBuilder::new()
+            .name("staging-mail")
+            .service_type(ServiceType::Mailpit)
+            .config(serde_json::json!({ "smtp_port": "1026" }))
+            .build();
+        let mut config = ConfigBuilder::new().instance(mailpit.clone()).build();
+        assert!(routed_destination(&config, dir.path()).is_none());
+
+        set_route(
+            &mut config.mail_routes,
+            dir.path(),
+            MailRouteTarget::Mailpit {
+                instance_id: mailpit.id,
+            },
+        );
+        let destination = routed_destination(&config, dir.path()).unwrap();
+        assert!(matches!(destination, MailDestination::Mailpit(i) if i.id == mailpit.id));
+
+        // The Mailpit was removed: fall back to the stack's
+        config.instances.clear();
+        assert!(routed_destination(&config, dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_env_changes() {
+        let relay = SmtpRelay::new("smtp.staging.example", 587, Some("app"), Some("tls")).unwrap();
+        let destination = MailDestination::Smtp(&relay);
+        let current = env(&[
+            ("MAIL_MAILER", "smtp"),
+            ("MAIL_HOST", "127.0.0.1"),
+            ("MAIL_PORT", "1025"),
+            ("MAIL_USERNAME", "null"),
+        ]);
+
+        let changes = destination.env_changes(&current);
+        let keys: Vec<&str> = changes.iter().map(|c| c.key).collect();
+        assert_eq!(
+            keys,
+            ["MAIL_HOST", "MAIL_PORT", "MAIL_USERNAME", "MAIL_ENCRYPTION"]
+        );
+        assert_eq!(changes[1].suggested, "587");
+
+        // Back to Mailpit: "null" and missing count as unset
+        let mailpit = InstanceBuilder::new()
+            .service_type(ServiceType::Mailpit)
+            .build();
+        let current = env(&[
+            ("MAIL_MAILER", "smtp"),
+            ("MAIL_HOST", "127.0.0.1"),
+            ("MAIL_PORT", "1025"),
+            ("MAIL_ENCRYPTION", "null"),
+        ]);
+        assert!(MailDestination::Mailpit(&mailpit)
+            .env_changes(&current)
+            .is_empty());
+    }
+}
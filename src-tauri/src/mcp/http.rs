@@ -0,0 +1,141 @@
+//! MCP server over HTTP (SSE transport)
+//!
+//! Served by the HTTP API so editors that only speak HTTP MCP can connect to
+//! a running Burd without spawning `burd mcp`:
+//!
+//! 1. `GET /mcp/sse` opens an event stream; the first `endpoint` event names
+//!    the URL to post to (`/mcp/messages?sessionId=…`).
+//! 2. Each JSON-RPC message is POSTed there and answered with 202; the
+//!    response arrives on the stream as a `message` event.
+//!
+//! Tools run exactly as over stdio, through the HTTP API.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use super::protocol::{JsonRpcRequest, JsonRpcResponse};
+use super::server::handle_request;
+use crate::api_client::BurdApiClient;
+
+/// Path clients connect to
+pub const SSE_PATH: &str = "/mcp/sse";
+
+/// Path clients post messages to
+const MESSAGES_PATH: &str = "/mcp/messages";
+
+/// Open SSE streams by session ID
+type Sessions = Arc<Mutex<HashMap<Uuid, mpsc::UnboundedSender<Event>>>>;
+
+/// Routes for the SSE transport, merged into the API router
+pub fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(SSE_PATH, get(connect))
+        .route(MESSAGES_PATH, post(message))
+        .with_state(Sessions::default())
+}
+
+/// Removes the session once its stream is dropped (client disconnected)
+struct SessionGuard {
+    id: Uuid,
+    sessions: Sessions,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.remove(&self.id);
+        }
+    }
+}
+
+/// GET /mcp/sse - Open a session's event stream
+async fn connect(
+    State(sessions): State<Sessions>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    let id = Uuid::new_v4();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let endpoint = format!("{}?sessionId={}", MESSAGES_PATH, id);
+    tx.send(Event::default().event("endpoint").data(endpoint))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    sessions
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .insert(id, tx);
+
+    let guard = SessionGuard {
+        id,
+        sessions: Arc::clone(&sessions),
+    };
+    let stream = stream::unfold((rx, guard), |(mut rx, guard)| async move {
+        let event = rx.recv().await?;
+        Some((Ok(event), (rx, guard)))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: Uuid,
+}
+
+/// POST /mcp/messages?sessionId=… - Handle one JSON-RPC message
+async fn message(
+    State(sessions): State<Sessions>,
+    Query(query): Query<MessageQuery>,
+    Json(body): Json<Value>,
+) -> Response {
+    let sender = sessions
+        .lock()
+        .ok()
+        .and_then(|sessions| sessions.get(&query.session_id).cloned());
+    let Some(sender) = sender else {
+        return (StatusCode::NOT_FOUND, "Unknown or closed MCP session").into_response();
+    };
+
+    tokio::spawn(async move {
+        let response = match serde_json::from_value::<JsonRpcRequest>(body) {
+            // Notifications (no id) get no response
+            Ok(request) if request.id.is_none() => return,
+            Ok(request) => {
+                let id = request.id.clone();
+                // The API client blocks, and calls back into this server
+                tokio::task::spawn_blocking(move || {
+                    let client = BurdApiClient::new();
+                    handle_request(&client, request)
+                })
+                .await
+                .unwrap_or_else(|e| {
+                    JsonRpcResponse::error(id, -32603, format!("Internal error: {}", e))
+                })
+            }
+            Err(e) => JsonRpcResponse::error(None, -32600, format!("Invalid request: {}", e)),
+        };
+        if let Ok(data) = serde_json::to_string(&response) {
+            let _ = sender.send(Event::default().event("message").data(data));
+        }
+    });
+
+    StatusCode::ACCEPTED.into_response()
+}
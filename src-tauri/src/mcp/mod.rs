@@ -1,8 +1,10 @@
 //! MCP (Model Context Protocol) module
 //!
 //! Provides MCP server functionality for external AI agent control of Burd.
-//! The MCP server communicates via stdio (JSON-RPC) and calls the Burd HTTP API.
+//! The MCP server communicates via stdio (JSON-RPC) or, served by the HTTP API,
+//! over SSE, and calls the Burd HTTP API.
 
+pub mod http;
 pub mod protocol;
 pub mod server;
 pub mod tools;
@@ -1,4 +1,6 @@
 //! MCP server implementation (stdio-based)
+//!
+//! `handle_request` is shared with the HTTP (SSE) transport in `http`.
 
 use serde_json::{json, Value};
 use std::io::{self, BufRead, Write};
@@ -148,7 +150,8 @@ pub fn run_server() -> Result<(), String> {
     Ok(())
 }
 
-fn handle_request(client: &BurdApiClient, request: JsonRpcRequest) -> JsonRpcResponse {
+/// Handle one JSON-RPC request
+pub(super) fn handle_request(client: &BurdApiClient, request: JsonRpcRequest) -> JsonRpcResponse {
     match request.method.as_str() {
         "initialize" => handle_initialize(request.id),
         "initialized" => JsonRpcResponse::success(request.id, json!({})),
@@ -227,7 +230,10 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
 
     match name {
         // Instance tools
-        "list_instances" => client.get("/instances"),
+        "list_instances" => match args.get("tag").and_then(|v| v.as_str()) {
+            Some(tag) => client.get(&format!("/instances?tag={}", tag)),
+            None => client.get("/instances"),
+        },
         "create_instance" => client.post("/instances", &args),
         "update_instance" => {
             let id = args
@@ -242,6 +248,10 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
             if let Some(v) = args.get("domain") { body.insert("domain".to_string(), v.clone()); }
             if let Some(v) = args.get("domain_enabled") { body.insert("domain_enabled".to_string(), v.clone()); }
             if let Some(v) = args.get("config") { body.insert("config".to_string(), v.clone()); }
+            if let Some(v) = args.get("auto_start") { body.insert("auto_start".to_string(), v.clone()); }
+            if let Some(v) = args.get("restart_on_failure") { body.insert("restart_on_failure".to_string(), v.clone()); }
+            if let Some(v) = args.get("tags") { body.insert("tags".to_string(), v.clone()); }
+            if let Some(v) = args.get("extra_args") { body.insert("extra_args".to_string(), v.clone()); }
             client.put(&format!("/instances/{}", id), &Value::Object(body))
         }
         "start_instance" => {
@@ -249,7 +259,13 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
                 .get("id")
                 .and_then(|v| v.as_str())
                 .ok_or("Missing 'id' parameter")?;
-            client.post(&format!("/instances/{}/start", id), &json!({}))
+            let wait = args.get("wait").and_then(|v| v.as_bool()).unwrap_or(false);
+            let path = if wait {
+                format!("/instances/{}/start?wait=true&timeout=25", id)
+            } else {
+                format!("/instances/{}/start", id)
+            };
+            client.post(&path, &json!({}))
         }
         "stop_instance" => {
             let id = args
@@ -265,6 +281,9 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
                 .ok_or("Missing 'id' parameter")?;
             client.post(&format!("/instances/{}/restart", id), &json!({}))
         }
+        "bulk_instance_action" => client.post("/instances/bulk", &args),
+        "list_stacks" => client.get("/stacks"),
+        "apply_env_sync" => client.post("/env/sync", &args),
         "delete_instance" => {
             let id = args
                 .get("id")
@@ -284,7 +303,42 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
                 .get("id")
                 .and_then(|v| v.as_str())
                 .ok_or("Missing 'id' parameter")?;
-            client.get(&format!("/instances/{}/env", id))
+            match args.get("format").and_then(|v| v.as_str()) {
+                Some(format) => client.get(&format!(
+                    "/instances/{}/env?format={}",
+                    id,
+                    urlencoding::encode(format)
+                )),
+                None => client.get(&format!("/instances/{}/env", id)),
+            }
+        }
+        "setup_centrifugo" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            client.post(&format!("/instances/{}/centrifugo/setup", id), &json!({}))
+        }
+        "setup_soketi" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            client.post(&format!("/instances/{}/soketi/setup", id), &json!({}))
+        }
+        "provision_keycloak" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            client.post(&format!("/instances/{}/keycloak/provision", id), &json!({}))
+        }
+        "run_instance_seed" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            client.post(&format!("/instances/{}/seed/run", id), &json!({}))
         }
 
         // Domain tools
@@ -311,6 +365,21 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
                 .ok_or("Missing 'id' parameter")?;
             client.post(&format!("/domains/{}/ssl", id), &args)
         }
+        "get_domain_requests" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(200);
+            client.get(&format!("/domains/{}/requests?limit={}", id, limit))
+        }
+        "get_domain_stats" => {
+            let minutes = args.get("minutes").and_then(|v| v.as_u64()).unwrap_or(15);
+            match args.get("id").and_then(|v| v.as_str()) {
+                Some(id) => client.get(&format!("/domains/{}/stats?minutes={}", id, minutes)),
+                None => client.get(&format!("/domains/stats?minutes={}", minutes)),
+            }
+        }
 
         // Database tools
         "list_databases" => client.get("/databases"),
@@ -355,9 +424,12 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
         }
 
         // Mail (Mailpit)
-        "get_mailpit_config" => client.get("/mail/config"),
+        "get_mailpit_config" => client.get(&format!("/mail/config{}", mailpit_query(&args))),
         "list_emails" => {
             let mut params = Vec::new();
+            if let Some(v) = args.get("instance_id").and_then(|v| v.as_str()) {
+                params.push(format!("instance_id={}", urlencoding::encode(v)));
+            }
             if let Some(v) = args.get("start").and_then(|v| v.as_u64()) {
                 params.push(format!("start={}", v));
             }
@@ -381,17 +453,59 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
                 .get("id")
                 .and_then(|v| v.as_str())
                 .ok_or("Missing 'id' parameter")?;
-            client.get(&format!("/mail/messages/{}", id))
+            client.get(&format!("/mail/messages/{}{}", id, mailpit_query(&args)))
+        }
+        "search_emails" => {
+            let query = args
+                .get("query")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'query' parameter")?;
+            let mut params = vec![format!("query={}", urlencoding::encode(query))];
+            if let Some(v) = args.get("instance_id").and_then(|v| v.as_str()) {
+                params.push(format!("instance_id={}", urlencoding::encode(v)));
+            }
+            if let Some(v) = args.get("start").and_then(|v| v.as_u64()) {
+                params.push(format!("start={}", v));
+            }
+            if let Some(v) = args.get("limit").and_then(|v| v.as_u64()) {
+                params.push(format!("limit={}", v));
+            }
+            client.get(&format!("/mail/search?{}", params.join("&")))
+        }
+        "get_email_html" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            client.get(&format!(
+                "/mail/messages/{}/html{}",
+                id,
+                mailpit_query(&args)
+            ))
+        }
+        "get_email_raw" => {
+            let id = args
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'id' parameter")?;
+            client.get(&format!(
+                "/mail/messages/{}/raw{}",
+                id,
+                mailpit_query(&args)
+            ))
         }
         "delete_email" => {
             let id = args
                 .get("id")
                 .and_then(|v| v.as_str())
                 .ok_or("Missing 'id' parameter")?;
-            client.delete(&format!("/mail/messages/{}", id))
+            client.delete(&format!("/mail/messages/{}{}", id, mailpit_query(&args)))
         }
-        "mark_emails_read" => client.post("/mail/messages/read", &args),
-        "get_unread_count" => client.get("/mail/unread-count"),
+        "mark_emails_read" => client.post(
+            &format!("/mail/messages/read{}", mailpit_query(&args)),
+            &args,
+        ),
+        "get_unread_count" => client.get(&format!("/mail/unread-count{}", mailpit_query(&args))),
 
         // Status
         "get_status" => client.get("/status"),
@@ -450,6 +564,14 @@ fn execute_tool(client: &BurdApiClient, name: &str, args: Option<Value>) -> Resu
     }
 }
 
+/// Build the `?instance_id=` suffix for mail endpoints (empty if not given)
+fn mailpit_query(args: &Value) -> String {
+    args.get("instance_id")
+        .and_then(|v| v.as_str())
+        .map(|id| format!("?instance_id={}", urlencoding::encode(id)))
+        .unwrap_or_default()
+}
+
 /// Execute a burd CLI command and return its output
 fn execute_cli_command(args: &[&str]) -> Result<String, String> {
     use std::process::Command;
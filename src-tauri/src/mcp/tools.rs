@@ -25,10 +25,15 @@ pub fn get_tools() -> Vec<Tool> {
         // ====================================================================
         Tool {
             name: "list_instances".to_string(),
-            description: "List all Burd service instances with their status, health, and configuration".to_string(),
+            description: "List all Burd service instances with their status, health, tags, and configuration".to_string(),
             input_schema: json!({
                 "type": "object",
-                "properties": {},
+                "properties": {
+                    "tag": {
+                        "type": "string",
+                        "description": "Only list instances carrying these tags, comma-separated (optional, e.g. 'client:acme,env:test')"
+                    }
+                },
                 "required": []
             }),
         },
@@ -44,23 +49,27 @@ pub fn get_tools() -> Vec<Tool> {
                     },
                     "port": {
                         "type": "integer",
-                        "description": "Port number (must be >= 1024)"
+                        "description": "Port number (must be >= 1024). Omit to assign a free port automatically. Rejected if another instance or process already uses it."
+                    },
+                    "auto_assign_port": {
+                        "type": "boolean",
+                        "description": "If the given port is already in use, pick a free port instead of failing (default false)"
                     },
                     "service_type": {
                         "type": "string",
-                        "description": "Service type: redis, mariadb, postgresql, frankenphp, meilisearch, typesense, mongodb, memcached, valkey, minio, mailpit, beanstalkd, centrifugo"
+                        "description": "Service type: redis, mariadb, postgresql, frankenphp, meilisearch, typesense, mongodb, memcached, valkey, minio, mailpit, beanstalkd, rabbitmq, opensearch, centrifugo, soketi, keycloak"
                     },
                     "version": {
                         "type": "string",
-                        "description": "Version to use (must be installed). Use get_service_versions to see available versions."
+                        "description": "Version to use (must be installed). Omit to use the default version: the pinned version from settings, or the newest installed one. Use get_service_versions to see available versions."
                     }
                 },
-                "required": ["name", "port", "service_type", "version"]
+                "required": ["name", "service_type"]
             }),
         },
         Tool {
             name: "update_instance".to_string(),
-            description: "Update a service instance's settings (name, port, version, domain, config). Only provide fields you want to change.".to_string(),
+            description: "Update a service instance's settings (name, port, version, domain, config). Only provide fields you want to change. If linked projects' .env files still point at the old port or domain, the response lists them under env_sync; pass those to apply_env_sync to update them.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -90,7 +99,25 @@ pub fn get_tools() -> Vec<Tool> {
                     },
                     "config": {
                         "type": "object",
-                        "description": "Service-specific config object (optional, replaces entire config)"
+                        "description": "Service-specific config object (optional, replaces entire config). String values may use {{instance.port}}, {{instance.name}}, {{data_dir}}, {{app_dir}}, {{home}}, {{tld}}, and {{domain}}, resolved when the instance starts."
+                    },
+                    "auto_start": {
+                        "type": "boolean",
+                        "description": "Start the instance when the app or the headless daemon launches (optional)"
+                    },
+                    "restart_on_failure": {
+                        "type": "boolean",
+                        "description": "Restart the instance, with backoff, when it crashes or fails its health check (optional)"
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Tags such as 'client:acme' or 'env:test' (optional, replaces all tags)"
+                    },
+                    "extra_args": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Arguments appended to the service's start command, one per item, e.g. [\"--appendonly\", \"yes\"] (optional, replaces all; applied on next start)"
                     }
                 },
                 "required": ["id"]
@@ -105,6 +132,10 @@ pub fn get_tools() -> Vec<Tool> {
                     "id": {
                         "type": "string",
                         "description": "Instance UUID (from list_instances)"
+                    },
+                    "wait": {
+                        "type": "boolean",
+                        "description": "Return only once the service accepts connections (up to 25s). Use before querying a database you just started."
                     }
                 },
                 "required": ["id"]
@@ -138,6 +169,54 @@ pub fn get_tools() -> Vec<Tool> {
                 "required": ["id"]
             }),
         },
+        Tool {
+            name: "bulk_instance_action".to_string(),
+            description: "Start, stop, or restart every instance carrying all of the given tags (e.g. everything tagged 'client:acme') and/or belonging to a stack".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["start", "stop", "restart"],
+                        "description": "Lifecycle action to apply"
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Instances must carry all of these tags"
+                    },
+                    "stack": {
+                        "type": "string",
+                        "description": "Only instances of this stack (ID or name)"
+                    }
+                },
+                "required": ["action"]
+            }),
+        },
+        Tool {
+            name: "list_stacks".to_string(),
+            description: "List stacks (groups of instances) with their instance counts and the combined CPU, memory, and disk usage of their running instances".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        },
+        Tool {
+            name: "apply_env_sync".to_string(),
+            description: "Apply .env updates suggested in the env_sync field of an update_instance response".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "changes": {
+                        "type": "array",
+                        "items": { "type": "object" },
+                        "description": "The env_sync entries to apply, as returned by update_instance"
+                    }
+                },
+                "required": ["changes"]
+            }),
+        },
         Tool {
             name: "delete_instance".to_string(),
             description: "Delete a service instance (stops it first if running)".to_string(),
@@ -175,6 +254,67 @@ pub fn get_tools() -> Vec<Tool> {
                     "id": {
                         "type": "string",
                         "description": "Instance UUID"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["laravel", "symfony", "django", "rails", "node"],
+                        "description": "Framework flavour of the snippet (default: laravel)"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "setup_centrifugo".to_string(),
+            description: "Generate a Centrifugo config (API key, token HMAC secret, allowed origins from configured domains) and return it with a matching Laravel broadcasting .env block. Restart the instance to apply.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Centrifugo instance UUID"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "setup_soketi".to_string(),
+            description: "Generate Soketi app credentials (app ID, key, secret) and return the config with a matching Laravel broadcasting .env block (PUSHER_* and VITE_PUSHER_* variables). Restart the instance to apply.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Soketi instance UUID"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "provision_keycloak".to_string(),
+            description: "Create the configured realm, OIDC client (with a fixed secret) and test user on a running Keycloak instance, skipping whatever already exists. Returns the steps taken and a Laravel Socialite .env block.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Keycloak instance UUID"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "run_instance_seed".to_string(),
+            description: "Reset a MariaDB or PostgreSQL instance's database with its configured seed: the SQL file is imported into a freshly recreated database, then the seed command (e.g. php artisan migrate:fresh --seed) runs. The instance must be running. Destroys the database's current data.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Database instance UUID"
                     }
                 },
                 "required": ["id"]
@@ -241,6 +381,18 @@ pub fn get_tools() -> Vec<Tool> {
                     "target_value": {
                         "type": "string",
                         "description": "New target value (optional)"
+                    },
+                    "force_https": {
+                        "type": "boolean",
+                        "description": "Redirect HTTP to HTTPS when SSL is enabled (optional)"
+                    },
+                    "hsts": {
+                        "type": "boolean",
+                        "description": "Send a Strict-Transport-Security header when SSL is enabled (optional)"
+                    },
+                    "require_client_cert": {
+                        "type": "boolean",
+                        "description": "Require a client certificate issued by Burd; needs SSL enabled (optional)"
                     }
                 },
                 "required": ["id"]
@@ -278,6 +430,42 @@ pub fn get_tools() -> Vec<Tool> {
                 "required": ["id", "ssl_enabled"]
             }),
         },
+        Tool {
+            name: "get_domain_requests".to_string(),
+            description: "Get the most recent requests served for a domain (method, path, status, duration), newest first. Useful for seeing what a local site was just asked and which requests failed or were slow.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Domain UUID"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Number of requests (default and at most 200)"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "get_domain_stats".to_string(),
+            description: "Get request counts, error counts and response times (avg, p50, p95, max) per domain from the sites' access logs, for the last N minutes and the N minutes before, flagging domains that got slower. Omit id for every domain.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Domain UUID (optional)"
+                    },
+                    "minutes": {
+                        "type": "integer",
+                        "description": "Length of each window in minutes (default 15)"
+                    }
+                },
+                "required": []
+            }),
+        },
 
         // ====================================================================
         // Database Tools
@@ -392,10 +580,12 @@ pub fn get_tools() -> Vec<Tool> {
         // ====================================================================
         Tool {
             name: "get_mailpit_config".to_string(),
-            description: "Get Mailpit SMTP/HTTP connection details for the running Mailpit instance. Use these in your app's .env (MAIL_HOST, MAIL_PORT).".to_string(),
+            description: "Get Mailpit SMTP/HTTP connection details for a running Mailpit instance. Use these in your app's .env (MAIL_HOST, MAIL_PORT).".to_string(),
             input_schema: json!({
                 "type": "object",
-                "properties": {},
+                "properties": {
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
+                },
                 "required": []
             }),
         },
@@ -405,6 +595,7 @@ pub fn get_tools() -> Vec<Tool> {
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" },
                     "start": { "type": "integer", "description": "Offset for pagination (default 0)" },
                     "limit": { "type": "integer", "description": "Max messages to return (default 50)" },
                     "search": { "type": "string", "description": "Optional Mailpit search query (subject/body/addr)" }
@@ -418,7 +609,46 @@ pub fn get_tools() -> Vec<Tool> {
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "id": { "type": "string", "description": "Message ID from list_emails" }
+                    "id": { "type": "string", "description": "Message ID from list_emails" },
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "search_emails".to_string(),
+            description: "Full-text search of captured emails using Mailpit's search syntax, e.g. 'to:dev@example.com' or 'subject:\"Reset your password\" has:attachment'.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Mailpit search query" },
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" },
+                    "start": { "type": "integer", "description": "Offset for pagination (default 0)" },
+                    "limit": { "type": "integer", "description": "Max messages to return (default 50)" }
+                },
+                "required": ["query"]
+            }),
+        },
+        Tool {
+            name: "get_email_html".to_string(),
+            description: "Get the HTML part of a captured email as sent, to check template markup and links.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Message ID from list_emails" },
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
+                },
+                "required": ["id"]
+            }),
+        },
+        Tool {
+            name: "get_email_raw".to_string(),
+            description: "Get the raw source (.eml) of a captured email, including all headers and MIME parts.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Message ID from list_emails" },
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
                 },
                 "required": ["id"]
             }),
@@ -429,7 +659,8 @@ pub fn get_tools() -> Vec<Tool> {
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "id": { "type": "string", "description": "Message ID to delete" }
+                    "id": { "type": "string", "description": "Message ID to delete" },
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
                 },
                 "required": ["id"]
             }),
@@ -445,7 +676,8 @@ pub fn get_tools() -> Vec<Tool> {
                         "items": { "type": "string" },
                         "description": "Message IDs to update"
                     },
-                    "read": { "type": "boolean", "description": "true = mark read, false = mark unread" }
+                    "read": { "type": "boolean", "description": "true = mark read, false = mark unread" },
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
                 },
                 "required": ["ids", "read"]
             }),
@@ -455,7 +687,9 @@ pub fn get_tools() -> Vec<Tool> {
             description: "Count of unread captured emails in Mailpit.".to_string(),
             input_schema: json!({
                 "type": "object",
-                "properties": {},
+                "properties": {
+                    "instance_id": { "type": "string", "description": "Mailpit instance ID (optional, defaults to the first running Mailpit)" }
+                },
                 "required": []
             }),
         },
@@ -0,0 +1,328 @@
+//! Instance Resource Usage
+//!
+//! Samples the CPU, memory and data directory size of every running instance
+//! and keeps a rolling history per instance, so the GUI can draw sparklines.
+//! CPU and memory come from a single `ps` call and include child processes
+//! (PostgreSQL backends, PHP workers, ...). Walking a data directory is slow,
+//! so disk usage is measured less often and carried over in between.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::Command;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::commands::AppState;
+use crate::config::get_instance_dir;
+use crate::error::LockExt;
+use crate::lock;
+use crate::snapshot::path_size;
+
+/// How often running instances are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Samples kept per instance (10 minutes)
+const HISTORY_LEN: usize = 60;
+
+/// Data directories are measured every this many samples (once a minute)
+const DISK_EVERY: u32 = 6;
+
+/// Resource usage of an instance at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricsSample {
+    pub at: DateTime<Utc>,
+    /// CPU usage of the process tree; 100 is one full core
+    pub cpu_percent: f32,
+    /// Resident memory of the process tree
+    pub memory_bytes: u64,
+    /// Size of the instance's data directory
+    pub disk_bytes: u64,
+}
+
+/// Current usage and recent history of an instance
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceMetrics {
+    pub instance_id: Uuid,
+    pub running: bool,
+    pub current: Option<MetricsSample>,
+    /// Oldest first
+    pub history: Vec<MetricsSample>,
+}
+
+/// Combined current usage of a group of instances (a stack)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct UsageRollup {
+    /// Instances that contributed a sample
+    pub sampled: usize,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub disk_bytes: u64,
+}
+
+impl UsageRollup {
+    /// Sum the latest samples of a group's running instances
+    pub fn of(samples: impl IntoIterator<Item = MetricsSample>) -> Self {
+        samples
+            .into_iter()
+            .fold(Self::default(), |total, sample| Self {
+                sampled: total.sampled + 1,
+                cpu_percent: total.cpu_percent + sample.cpu_percent,
+                memory_bytes: total.memory_bytes + sample.memory_bytes,
+                disk_bytes: total.disk_bytes + sample.disk_bytes,
+            })
+    }
+}
+
+/// Rolling sample history per running instance
+#[derive(Debug, Default)]
+pub struct MetricsHistory {
+    samples: HashMap<Uuid, VecDeque<MetricsSample>>,
+}
+
+impl MetricsHistory {
+    /// Add a sample, dropping the oldest beyond the history length
+    pub fn record(&mut self, id: Uuid, sample: MetricsSample) {
+        let history = self.samples.entry(id).or_default();
+        history.push_back(sample);
+        while history.len() > HISTORY_LEN {
+            history.pop_front();
+        }
+    }
+
+    /// Forget instances that are no longer running
+    pub fn retain(&mut self, running: &HashSet<Uuid>) {
+        self.samples.retain(|id, _| running.contains(id));
+    }
+
+    pub fn latest(&self, id: &Uuid) -> Option<MetricsSample> {
+        self.samples.get(id).and_then(|h| h.back().copied())
+    }
+
+    pub fn history(&self, id: &Uuid) -> Vec<MetricsSample> {
+        self.samples
+            .get(id)
+            .map(|h| h.iter().copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// One line of `ps -A -o pid= -o ppid= -o %cpu= -o rss=`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PsRow {
+    pid: u32,
+    ppid: u32,
+    cpu_percent: f32,
+    rss_kb: u64,
+}
+
+fn parse_ps(output: &str) -> Vec<PsRow> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(PsRow {
+                pid: fields.next()?.parse().ok()?,
+                ppid: fields.next()?.parse().ok()?,
+                cpu_percent: fields.next()?.replace(',', ".").parse().ok()?,
+                rss_kb: fields.next()?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// CPU and resident memory (bytes) of a process and all its descendants
+fn tree_usage(rows: &[PsRow], root: u32) -> Option<(f32, u64)> {
+    let root_row = rows.iter().find(|r| r.pid == root)?;
+    let mut cpu = root_row.cpu_percent;
+    let mut rss_kb = root_row.rss_kb;
+
+    let mut pending = vec![root];
+    let mut seen = HashSet::from([root]);
+    while let Some(parent) = pending.pop() {
+        for row in rows.iter().filter(|r| r.ppid == parent) {
+            if seen.insert(row.pid) {
+                cpu += row.cpu_percent;
+                rss_kb += row.rss_kb;
+                pending.push(row.pid);
+            }
+        }
+    }
+    Some((cpu, rss_kb * 1024))
+}
+
+/// Snapshot of every process on the system
+fn list_processes() -> Vec<PsRow> {
+    Command::new("ps")
+        .args([
+            "-A", "-o", "pid=", "-o", "ppid=", "-o", "%cpu=", "-o", "rss=",
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_ps(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// Sample every running instance once
+///
+/// `ps` and the data directory walk run without holding the process manager
+/// lock, so starting and stopping instances isn't held up.
+fn sample_running(state: &AppState, round: u32) -> Result<(), String> {
+    let config = lock!(state.config_store)?.load()?;
+    let (running, previous): (Vec<(Uuid, u32)>, HashMap<Uuid, MetricsSample>) = {
+        let process_manager = lock!(state.process_manager)?;
+        let running: Vec<(Uuid, u32)> = config
+            .instances
+            .iter()
+            .filter_map(|i| process_manager.get_status(i).pid.map(|pid| (i.id, pid)))
+            .collect();
+        let previous = running
+            .iter()
+            .filter_map(|(id, _)| process_manager.latest_metrics(id).map(|s| (*id, s)))
+            .collect();
+        (running, previous)
+    };
+
+    let rows = if running.is_empty() {
+        Vec::new()
+    } else {
+        list_processes()
+    };
+    let now = Utc::now();
+    let samples: Vec<(Uuid, MetricsSample)> = running
+        .iter()
+        .filter_map(|(id, pid)| {
+            let (cpu_percent, memory_bytes) = tree_usage(&rows, *pid)?;
+            let disk_bytes = match previous.get(id) {
+                Some(sample) if !round.is_multiple_of(DISK_EVERY) => sample.disk_bytes,
+                _ => get_instance_dir(id).map(|dir| path_size(&dir)).unwrap_or(0),
+            };
+            Some((
+                *id,
+                MetricsSample {
+                    at: now,
+                    cpu_percent,
+                    memory_bytes,
+                    disk_bytes,
+                },
+            ))
+        })
+        .collect();
+
+    let mut process_manager = lock!(state.process_manager)?;
+    process_manager.record_metrics(samples);
+    Ok(())
+}
+
+/// Sample running instances in the background
+pub async fn run(state: AppState) {
+    let mut round: u32 = 0;
+    loop {
+        let sample_state = state.clone();
+        let _ = tokio::task::spawn_blocking(move || sample_running(&sample_state, round)).await;
+        round = round.wrapping_add(1);
+
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu_percent: f32) -> MetricsSample {
+        MetricsSample {
+            at: Utc::now(),
+            cpu_percent,
+            memory_bytes: 0,
+            disk_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_ps() {
+        let rows = parse_ps("    1     0   0.0   9000\n  420     1  12,5  20480\nbogus line\n");
+        assert_eq!(
+            rows,
+            vec![
+                PsRow {
+                    pid: 1,
+                    ppid: 0,
+                    cpu_percent: 0.0,
+                    rss_kb: 9000
+                },
+                PsRow {
+                    pid: 420,
+                    ppid: 1,
+                    cpu_percent: 12.5,
+                    rss_kb: 20480
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_usage_includes_descendants() {
+        // 100 (postgres) -> 101, 102 -> 103; 200 is unrelated
+        let rows = parse_ps(
+            "100 1 1.5 1000\n101 100 2.0 500\n102 100 0.5 500\n103 102 1.0 100\n200 1 50.0 9999\n",
+        );
+        let (cpu, memory) = tree_usage(&rows, 100).unwrap();
+        assert!((cpu - 5.0).abs() < f32::EPSILON);
+        assert_eq!(memory, 2100 * 1024);
+
+        let (cpu, memory) = tree_usage(&rows, 102).unwrap();
+        assert!((cpu - 1.5).abs() < f32::EPSILON);
+        assert_eq!(memory, 600 * 1024);
+
+        assert!(tree_usage(&rows, 999).is_none());
+    }
+
+    #[test]
+    fn test_usage_rollup_sums_samples() {
+        let samples = [
+            MetricsSample {
+                memory_bytes: 2 << 30,
+                disk_bytes: 100,
+                ..sample(12.5)
+            },
+            MetricsSample {
+                memory_bytes: 1 << 30,
+                disk_bytes: 50,
+                ..sample(30.0)
+            },
+        ];
+
+        let usage = UsageRollup::of(samples);
+        assert_eq!(usage.sampled, 2);
+        assert!((usage.cpu_percent - 42.5).abs() < f32::EPSILON);
+        assert_eq!(usage.memory_bytes, 3 << 30);
+        assert_eq!(usage.disk_bytes, 150);
+
+        assert_eq!(UsageRollup::of([]), UsageRollup::default());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let id = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let mut history = MetricsHistory::default();
+        for i in 0..(HISTORY_LEN + 5) {
+            history.record(id, sample(i as f32));
+        }
+        history.record(other, sample(1.0));
+
+        let samples = history.history(&id);
+        assert_eq!(samples.len(), HISTORY_LEN);
+        assert_eq!(samples[0].cpu_percent, 5.0);
+        assert_eq!(
+            history.latest(&id).unwrap().cpu_percent,
+            (HISTORY_LEN + 4) as f32
+        );
+
+        history.retain(&HashSet::from([other]));
+        assert!(history.history(&id).is_empty());
+        assert_eq!(history.history(&other).len(), 1);
+    }
+}
@@ -0,0 +1,347 @@
+//! MinIO Manager Module
+//!
+//! Manages the buckets of a MinIO instance over its S3 API: lists, creates,
+//! and deletes buckets, applies the anonymous access presets, and issues
+//! temporary access keys so apps don't have to be handed the root
+//! credentials. Used by the HTTP API and `burd s3`.
+
+use crate::config::{Config, Instance, ServiceType};
+use crate::services::minio::{validate_bucket_name, S3Client};
+use serde::Serialize;
+use std::time::Duration;
+
+pub use crate::services::minio::BucketPolicy;
+
+/// Timeout for S3 requests
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default lifetime of generated access keys, in hours
+pub const DEFAULT_KEY_HOURS: u32 = 24;
+
+/// Longest lifetime of generated access keys, in hours (a week)
+pub const MAX_KEY_HOURS: u32 = 7 * 24;
+
+/// A bucket (from `ListBuckets`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BucketInfo {
+    pub name: String,
+    /// ISO 8601 timestamp
+    pub created: String,
+}
+
+/// Temporary credentials (from STS `AssumeRole`)
+///
+/// S3 clients need all three values; the session token is what makes the
+/// key pair valid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccessKey {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: String,
+    /// ISO 8601 timestamp
+    pub expires_at: String,
+    /// Bucket the key is limited to, `None` for full access
+    pub bucket: Option<String>,
+}
+
+/// Text of each `<tag>...</tag>` element, with entities decoded
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        values.push(unescape_xml(&after[..end]));
+        rest = &after[end + close.len()..];
+    }
+    values
+}
+
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    xml_values(xml, tag).into_iter().next()
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_buckets(xml: &str) -> Vec<BucketInfo> {
+    xml_values(xml, "Bucket")
+        .iter()
+        .filter_map(|bucket| {
+            Some(BucketInfo {
+                name: xml_value(bucket, "Name")?,
+                created: xml_value(bucket, "CreationDate").unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn parse_access_key(xml: &str, bucket: Option<&str>) -> Result<AccessKey, String> {
+    let get = |tag: &str| {
+        xml_value(xml, tag).ok_or_else(|| format!("MinIO returned no {} for the new key", tag))
+    };
+    Ok(AccessKey {
+        access_key: get("AccessKeyId")?,
+        secret_key: get("SecretAccessKey")?,
+        session_token: get("SessionToken")?,
+        expires_at: get("Expiration")?,
+        bucket: bucket.map(str::to_string),
+    })
+}
+
+/// Error message from an S3 error response, falling back to the status
+fn s3_error(status: reqwest::StatusCode, body: &str) -> String {
+    match xml_value(body, "Message") {
+        Some(message) => message,
+        None => format!("MinIO returned HTTP {}", status),
+    }
+}
+
+/// Policy limiting a key to one bucket and its objects
+fn bucket_only_policy(bucket: &str) -> serde_json::Value {
+    serde_json::json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Action": ["s3:*"],
+            "Resource": [
+                format!("arn:aws:s3:::{}", bucket),
+                format!("arn:aws:s3:::{}/*", bucket)
+            ]
+        }]
+    })
+}
+
+/// Check an access key lifetime
+pub fn validate_key_hours(hours: u32) -> Result<(), String> {
+    if !(1..=MAX_KEY_HOURS).contains(&hours) {
+        return Err(format!(
+            "Access keys must last between 1 and {} hours",
+            MAX_KEY_HOURS
+        ));
+    }
+    Ok(())
+}
+
+/// Manager for a single MinIO instance, authenticated with its root credentials
+pub struct MinioManager {
+    port: u16,
+    s3: S3Client,
+}
+
+impl MinioManager {
+    /// Create a manager for a MinIO instance
+    pub fn for_instance(instance: &Instance) -> Result<Self, String> {
+        if instance.service_type != ServiceType::MinIO {
+            return Err(format!(
+                "Instance '{}' is not a MinIO instance",
+                instance.name
+            ));
+        }
+        let client = reqwest::blocking::Client::builder()
+            .timeout(TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self {
+            port: instance.port,
+            s3: S3Client::new(client, instance),
+        })
+    }
+
+    /// Get connection info for display
+    pub fn connection_info(&self) -> String {
+        format!("MinIO at 127.0.0.1:{}", self.port)
+    }
+
+    fn s3_request(
+        &self,
+        method: &str,
+        bucket: &str,
+        query: &str,
+        body: Vec<u8>,
+    ) -> Result<(reqwest::StatusCode, String), String> {
+        self.s3
+            .request("s3", method, &format!("/{}", bucket), query, None, body)
+            .map_err(|e| format!("Failed to reach MinIO at port {}: {}", self.port, e))
+    }
+
+    /// List all buckets
+    pub fn list_buckets(&self) -> Result<Vec<BucketInfo>, String> {
+        let (status, body) = self.s3_request("GET", "", "", Vec::new())?;
+        if !status.is_success() {
+            return Err(s3_error(status, &body));
+        }
+        Ok(parse_buckets(&body))
+    }
+
+    /// Create a bucket with an access preset
+    pub fn create_bucket(&self, name: &str, policy: BucketPolicy) -> Result<(), String> {
+        validate_bucket_name(name)?;
+
+        let (status, body) = self.s3_request("PUT", name, "", Vec::new())?;
+        if status.as_u16() == 409 {
+            return Err(format!("Bucket '{}' already exists", name));
+        }
+        if !status.is_success() {
+            return Err(s3_error(status, &body));
+        }
+
+        if policy != BucketPolicy::Private {
+            self.set_policy(name, policy)?;
+        }
+        Ok(())
+    }
+
+    /// Delete an empty bucket
+    pub fn delete_bucket(&self, name: &str) -> Result<(), String> {
+        let (status, body) = self.s3_request("DELETE", name, "", Vec::new())?;
+        match status.as_u16() {
+            404 => Err(format!("Bucket '{}' not found", name)),
+            409 => Err(format!(
+                "Bucket '{}' is not empty. Delete its objects first",
+                name
+            )),
+            _ if !status.is_success() => Err(s3_error(status, &body)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply an anonymous access preset to a bucket
+    pub fn set_policy(&self, name: &str, policy: BucketPolicy) -> Result<(), String> {
+        let (status, body) = self.s3_request("HEAD", name, "", Vec::new())?;
+        if status.as_u16() == 404 {
+            return Err(format!("Bucket '{}' not found", name));
+        }
+        if !status.is_success() {
+            return Err(s3_error(status, &body));
+        }
+        self.s3
+            .set_policy(name, policy)
+            .map_err(|e| format!("Failed to set policy of '{}': {}", name, e))
+    }
+
+    /// Generate temporary credentials valid for `hours`
+    ///
+    /// With a bucket, the key can only access that bucket; otherwise it has
+    /// the same access as the root user.
+    pub fn create_access_key(&self, bucket: Option<&str>, hours: u32) -> Result<AccessKey, String> {
+        validate_key_hours(hours)?;
+
+        let mut form = format!(
+            "Action=AssumeRole&Version=2011-06-15&DurationSeconds={}",
+            u64::from(hours) * 3600
+        );
+        if let Some(bucket) = bucket {
+            validate_bucket_name(bucket)?;
+            form.push_str("&Policy=");
+            form.push_str(&urlencoding::encode(
+                &bucket_only_policy(bucket).to_string(),
+            ));
+        }
+
+        let (status, body) = self
+            .s3
+            .request(
+                "sts",
+                "POST",
+                "/",
+                "",
+                Some("application/x-www-form-urlencoded"),
+                form.into_bytes(),
+            )
+            .map_err(|e| format!("Failed to reach MinIO at port {}: {}", self.port, e))?;
+        if !status.is_success() {
+            return Err(s3_error(status, &body));
+        }
+        parse_access_key(&body, bucket)
+    }
+}
+
+/// Find all MinIO instances in Burd config
+pub fn find_minio_instances(config: &Config) -> Vec<&Instance> {
+    config
+        .instances
+        .iter()
+        .filter(|i| i.service_type == ServiceType::MinIO)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_buckets() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListAllMyBucketsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/"><Owner><ID>02d6176db174dc93cb1b899f7c6078f08654445fe8cf1b6ce98d8855f66bdbf4</ID><DisplayName>minio</DisplayName></Owner><Buckets><Bucket><Name>media</Name><CreationDate>2026-05-01T10:00:00.000Z</CreationDate></Bucket><Bucket><Name>uploads</Name><CreationDate>2026-05-02T11:30:00.000Z</CreationDate></Bucket></Buckets></ListAllMyBucketsResult>"#;
+
+        assert_eq!(
+            parse_buckets(xml),
+            vec![
+                BucketInfo {
+                    name: "media".to_string(),
+                    created: "2026-05-01T10:00:00.000Z".to_string(),
+                },
+                BucketInfo {
+                    name: "uploads".to_string(),
+                    created: "2026-05-02T11:30:00.000Z".to_string(),
+                },
+            ]
+        );
+        assert!(parse_buckets("<Buckets></Buckets>").is_empty());
+    }
+
+    #[test]
+    fn test_parse_access_key() {
+        let xml = r#"<AssumeRoleResponse xmlns="https://sts.amazonaws.com/doc/2011-06-15/"><AssumeRoleResult><Credentials><AccessKeyId>Y4RJU1RNFGK48LGO9I2S</AccessKeyId><SecretAccessKey>sYLRKS1Z7hSjluf6gEbb9066hnx315wHTiACPAjg</SecretAccessKey><Expiration>2026-05-02T10:00:00Z</Expiration><SessionToken>eyJhbGciOiJIUzUxMiIs&amp;more</SessionToken></Credentials></AssumeRoleResult></AssumeRoleResponse>"#;
+
+        let key = parse_access_key(xml, Some("media")).unwrap();
+        assert_eq!(key.access_key, "Y4RJU1RNFGK48LGO9I2S");
+        assert_eq!(key.secret_key, "sYLRKS1Z7hSjluf6gEbb9066hnx315wHTiACPAjg");
+        assert_eq!(key.session_token, "eyJhbGciOiJIUzUxMiIs&more");
+        assert_eq!(key.expires_at, "2026-05-02T10:00:00Z");
+        assert_eq!(key.bucket.as_deref(), Some("media"));
+
+        assert!(parse_access_key("<Credentials></Credentials>", None).is_err());
+    }
+
+    #[test]
+    fn test_s3_error() {
+        let body = "<Error><Code>BucketNotEmpty</Code><Message>The bucket you tried to delete is not empty</Message></Error>";
+        assert_eq!(
+            s3_error(reqwest::StatusCode::CONFLICT, body),
+            "The bucket you tried to delete is not empty"
+        );
+        assert_eq!(
+            s3_error(reqwest::StatusCode::FORBIDDEN, ""),
+            "MinIO returned HTTP 403 Forbidden"
+        );
+    }
+
+    #[test]
+    fn test_bucket_only_policy() {
+        let policy = bucket_only_policy("media");
+        assert_eq!(
+            policy["Statement"][0]["Resource"],
+            serde_json::json!(["arn:aws:s3:::media", "arn:aws:s3:::media/*"])
+        );
+    }
+
+    #[test]
+    fn test_validate_key_hours() {
+        assert!(validate_key_hours(DEFAULT_KEY_HOURS).is_ok());
+        assert!(validate_key_hours(0).is_err());
+        assert!(validate_key_hours(MAX_KEY_HOURS + 1).is_err());
+    }
+}
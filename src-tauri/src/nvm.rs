@@ -3,6 +3,8 @@
 //! Provides integration with NVM for managing Node.js versions.
 //! Supports version installation, switching, and listing available versions.
 
+use crate::binary::DownloadProgress;
+use crate::events::{self, Event};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::path::PathBuf;
@@ -382,8 +384,25 @@ pub fn list_remote_lts_versions() -> Result<Vec<NodeVersion>, String> {
 }
 
 /// Install a Node version
+///
+/// NVM doesn't report progress, so the event stream only gets an
+/// indeterminate "installing" event and a "complete" one.
 pub fn install_version(version: &str) -> Result<String, String> {
-    run_nvm_command(&format!("install {}", version))
+    publish_progress(-1.0, "installing");
+    let output = run_nvm_command(&format!("install {}", version))?;
+    publish_progress(100.0, "complete");
+    Ok(output)
+}
+
+/// Publish Node install progress on the API event stream
+fn publish_progress(percentage: f64, phase: &str) {
+    events::publish(Event::Download(DownloadProgress {
+        service_type: "node".to_string(),
+        downloaded: 0,
+        total: 0,
+        percentage,
+        phase: phase.to_string(),
+    }));
 }
 
 /// Uninstall a Node version
@@ -407,6 +426,31 @@ pub fn set_default_version(version: &str) -> Result<String, String> {
     run_nvm_command(&format!("alias default {}", version))
 }
 
+/// Path of the `node` binary npm-installed services run with
+///
+/// The NVM default version when NVM is installed, otherwise a Homebrew or
+/// system Node.
+pub fn find_node() -> Option<PathBuf> {
+    if is_nvm_installed() {
+        let node = run_nvm_command("which default")
+            .ok()
+            .map(|output| PathBuf::from(output.trim()))
+            .filter(|path| path.is_file());
+        if node.is_some() {
+            return node;
+        }
+    }
+
+    [
+        "/opt/homebrew/bin/node",
+        "/usr/local/bin/node",
+        "/usr/bin/node",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .find(|path| path.is_file())
+}
+
 /// Compare two version strings (e.g., "v20.18.0" vs "v18.20.5")
 fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     let parse = |s: &str| -> Vec<u32> {
@@ -6,16 +6,22 @@
 //! - Auto-create domains for discovered projects
 //! - Generate FrankenPHP Caddyfile for virtual host routing
 //! - Support custom drivers via TOML config files
+//! - Summarize each project's git state (branch, uncommitted changes, last commit)
 
 use crate::config::{
-    get_instance_dir, ConfigStore, Domain, DomainSource, Instance, ParkedDirectory,
+    get_instance_dir, ConfigStore, Domain, DomainSource, Instance, ParkedDirectory, SlugSettings,
 };
+use crate::domain::DomainManager;
 use crate::drivers::DriverLoader;
 use crate::proxy::ProxyServer;
+use crate::services::php_ini::PhpIniOverrides;
+use chrono::{DateTime, TimeZone, Utc};
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::sync::Mutex;
 
 /// Global driver loader for custom project detection (cached)
@@ -153,8 +159,54 @@ const SKIP_DIRECTORIES: &[&str] = &[
     "dist",
 ];
 
+/// Deepest level a parked directory can be searched for projects
+pub const MAX_PARK_DEPTH: u8 = 3;
+
+/// Check a parked directory's search depth
+pub fn validate_depth(depth: u8) -> Result<u8, String> {
+    if !(1..=MAX_PARK_DEPTH).contains(&depth) {
+        return Err(format!(
+            "Depth must be between 1 and {} (got {})",
+            MAX_PARK_DEPTH, depth
+        ));
+    }
+    Ok(depth)
+}
+
 /// Scan a directory for project subdirectories
-pub fn scan_directory(parked_path: &Path) -> Result<Vec<DiscoveredProject>, String> {
+///
+/// With `depth` 1 every subdirectory is a project. With a larger depth, a
+/// subdirectory that isn't a recognised project (e.g. `clients/`) is searched
+/// one level deeper, and nested projects are named by their relative path
+/// (`clients/acme`, served as `clients-acme`). A folder with no projects
+/// inside is still served as a project itself. Symlinked folders are
+/// followed; a folder reached twice (e.g. through a link loop) is listed once.
+pub fn scan_directory(parked_path: &Path, depth: u8) -> Result<Vec<DiscoveredProject>, String> {
+    Ok(scan(parked_path, depth)?.projects)
+}
+
+/// Directories whose entries decide what a parked directory contains
+///
+/// The parked directory plus every folder searched for nested projects,
+/// with symlinks resolved (a watch on a link doesn't see changes inside its
+/// target).
+pub fn watch_paths(parked_path: &Path, depth: u8) -> Result<Vec<PathBuf>, String> {
+    let mut paths =
+        vec![fs::canonicalize(parked_path).unwrap_or_else(|_| parked_path.to_path_buf())];
+    paths.extend(scan(parked_path, depth)?.containers);
+    Ok(paths)
+}
+
+#[derive(Default)]
+struct Scan {
+    projects: Vec<DiscoveredProject>,
+    /// Folders that were searched for nested projects (canonical paths)
+    containers: Vec<PathBuf>,
+    /// Canonical paths already listed or searched
+    visited: HashSet<PathBuf>,
+}
+
+fn scan(parked_path: &Path, depth: u8) -> Result<Scan, String> {
     if !parked_path.exists() {
         return Err(format!("Path '{}' does not exist", parked_path.display()));
     }
@@ -168,33 +220,61 @@ pub fn scan_directory(parked_path: &Path) -> Result<Vec<DiscoveredProject>, Stri
     let entries =
         fs::read_dir(parked_path).map_err(|e| format!("Failed to read directory: {}", e))?;
 
-    let mut projects = Vec::new();
-
     // Try to acquire driver loader for custom driver detection
     let mut driver_loader = DRIVER_LOADER
         .lock()
         .map_err(|e| format!("Failed to acquire driver loader lock: {}", e))?;
 
-    for entry in entries.flatten() {
+    let mut scan = Scan::default();
+    if let Ok(root) = fs::canonicalize(parked_path) {
+        scan.visited.insert(root);
+    }
+    scan_entries(entries, None, depth.max(1), &mut driver_loader, &mut scan);
+
+    Ok(scan)
+}
+
+fn scan_entries(
+    entries: fs::ReadDir,
+    prefix: Option<&str>,
+    depth: u8,
+    driver_loader: &mut DriverLoader,
+    scan: &mut Scan,
+) {
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
         let path = entry.path();
 
-        // Skip non-directories
+        // Skip non-directories (symlinks are followed)
         if !path.is_dir() {
             continue;
         }
 
-        let name = entry.file_name().to_string_lossy().to_string();
+        let dir_name = entry.file_name().to_string_lossy().to_string();
 
         // Skip hidden directories
-        if name.starts_with('.') {
+        if dir_name.starts_with('.') {
             continue;
         }
 
         // Skip common non-project directories
-        if SKIP_DIRECTORIES.contains(&name.as_str()) {
+        if SKIP_DIRECTORIES.contains(&dir_name.as_str()) {
+            continue;
+        }
+
+        // Skip folders already reached through another path or a link loop
+        let real_path = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !scan.visited.insert(real_path.clone()) {
             continue;
         }
 
+        let name = match prefix {
+            Some(prefix) => format!("{}/{}", prefix, dir_name),
+            None => dir_name,
+        };
+
         // Check custom drivers first, then fall back to built-in detection
         let (project_type, document_root) =
             if let Some(driver_match) = driver_loader.detect_custom(&path) {
@@ -211,15 +291,41 @@ pub fn scan_directory(parked_path: &Path) -> Result<Vec<DiscoveredProject>, Stri
                 (project_type, document_root)
             };
 
-        projects.push(DiscoveredProject {
+        // Look for projects inside folders that aren't one themselves
+        if depth > 1 && project_type == ProjectType::Unknown {
+            if let Ok(children) = fs::read_dir(&path) {
+                let found = scan.projects.len();
+                scan.containers.push(real_path);
+                scan_entries(children, Some(&name), depth - 1, driver_loader, scan);
+                if scan.projects.len() > found {
+                    continue;
+                }
+            }
+        }
+
+        scan.projects.push(DiscoveredProject {
             name,
             path,
             project_type,
             document_root,
         });
     }
+}
 
-    Ok(projects)
+/// Find the parked project a directory belongs to (the project folder itself
+/// or anything inside it)
+pub fn find_project_containing(
+    parked_dir: &ParkedDirectory,
+    dir: &Path,
+) -> Option<DiscoveredProject> {
+    let real_dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    scan_directory(Path::new(&parked_dir.path), parked_dir.depth)
+        .ok()?
+        .into_iter()
+        .find(|project| {
+            dir.starts_with(&project.path)
+                || fs::canonicalize(&project.path).is_ok_and(|real| real_dir.starts_with(real))
+        })
 }
 
 /// Detect the project type based on directory contents (Laravel Valet-style detection)
@@ -437,8 +543,8 @@ pub fn determine_document_root(path: &Path, project_type: &ProjectType) -> PathB
 }
 
 /// Generate a subdomain slug from a project name
-pub fn generate_subdomain(name: &str) -> String {
-    slug::slugify(name)
+pub fn generate_subdomain(name: &str, settings: &SlugSettings) -> String {
+    DomainManager::slugify_with(name, settings)
 }
 
 /// Sync domains for a parked directory
@@ -461,7 +567,7 @@ pub fn sync_parked_domains(
     };
 
     // Scan the parked directory
-    let discovered = scan_directory(Path::new(&parked_dir.path))?;
+    let discovered = scan_directory(Path::new(&parked_dir.path), parked_dir.depth)?;
 
     // Load current config
     let config = config_store.load()?;
@@ -481,12 +587,12 @@ pub fn sync_parked_domains(
     // Discovered subdomain names
     let discovered_names: HashSet<String> = discovered
         .iter()
-        .map(|p| generate_subdomain(&p.name))
+        .map(|p| generate_subdomain(&p.name, &config.slugs))
         .collect();
 
     // Add new projects
     for project in &discovered {
-        let subdomain = generate_subdomain(&project.name);
+        let subdomain = generate_subdomain(&project.name, &config.slugs);
 
         // Check if this subdomain already exists for this parked directory
         if current_parked_domains.contains(&subdomain) {
@@ -494,8 +600,10 @@ pub fn sync_parked_domains(
             continue;
         }
 
-        // Check for conflict with other domains
-        if all_existing_subdomains.contains(&subdomain) {
+        // Check for conflict with other domains (or the reserved start page subdomain)
+        if all_existing_subdomains.contains(&subdomain)
+            || crate::start_page::is_reserved_subdomain(&subdomain)
+        {
             result.conflicts.push(subdomain);
             continue;
         }
@@ -510,9 +618,11 @@ pub fn sync_parked_domains(
         );
 
         // Save domain and register route
+        let config_lock = config_store.lock_file()?;
         let mut config = config_store.load()?;
         config.domains.push(domain.clone());
         config_store.save(&config)?;
+        drop(config_lock);
 
         // Register with proxy - all parked domains go through FrankenPHP Park port
         let full_domain = domain.full_domain(tld);
@@ -534,6 +644,7 @@ pub fn sync_parked_domains(
 
     for subdomain in orphaned {
         // Find and remove domain
+        let config_lock = config_store.lock_file()?;
         let mut config = config_store.load()?;
         if let Some(idx) = config
             .domains
@@ -542,6 +653,7 @@ pub fn sync_parked_domains(
         {
             let domain = config.domains.remove(idx);
             config_store.save(&config)?;
+            drop(config_lock);
 
             // Unregister from proxy
             let full_domain = domain.full_domain(tld);
@@ -578,10 +690,10 @@ pub fn regenerate_park_caddyfile(
     let mut all_projects: Vec<(String, String, bool)> = Vec::new();
 
     for parked_dir in &config.parked_directories {
-        let projects = scan_directory(Path::new(&parked_dir.path))?;
+        let projects = scan_directory(Path::new(&parked_dir.path), parked_dir.depth)?;
 
         for project in projects {
-            let subdomain = generate_subdomain(&project.name);
+            let subdomain = generate_subdomain(&project.name, &config.slugs);
 
             // Check if domain exists and is not isolated
             let domain_exists = config.domains.iter().any(|d| {
@@ -599,7 +711,8 @@ pub fn regenerate_park_caddyfile(
     }
 
     // Generate Caddyfile content
-    let caddyfile = generate_caddyfile_content(&all_projects, park_instance.port);
+    let php_ini = PhpIniOverrides::from_instance(park_instance)?;
+    let caddyfile = generate_caddyfile_content(&all_projects, park_instance.port, &php_ini);
 
     // Write to instance directory
     let instance_dir = get_instance_dir(&park_instance.id)?;
@@ -615,14 +728,16 @@ pub fn regenerate_park_caddyfile(
 
 /// Generate the Caddyfile content for FrankenPHP Park
 /// Projects tuple: (domain, document_root, requires_php)
-fn generate_caddyfile_content(projects: &[(String, String, bool)], port: u16) -> String {
+fn generate_caddyfile_content(
+    projects: &[(String, String, bool)],
+    port: u16,
+    php_ini: &PhpIniOverrides,
+) -> String {
     let mut content = String::new();
 
-    // Global options
-    content.push_str("{\n");
-    content.push_str("    frankenphp\n");
-    content.push_str("    order php_server before file_server\n");
-    content.push_str("}\n\n");
+    // Global options (php.ini overrides apply to every parked site)
+    content.push_str(&php_ini.global_options());
+    content.push('\n');
 
     // Server block for the port
     content.push_str(&format!(":{} {{\n", port));
@@ -672,22 +787,207 @@ pub fn get_all_parked_projects(
     let mut all_projects = Vec::new();
 
     for parked_dir in config.parked_directories {
-        let projects = scan_directory(Path::new(&parked_dir.path))?;
+        let projects = scan_directory(Path::new(&parked_dir.path), parked_dir.depth)?;
         all_projects.push((parked_dir, projects));
     }
 
     Ok(all_projects)
 }
 
+// ============================================================================
+// Git Status
+// ============================================================================
+
+/// Git state of a project
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GitInfo {
+    /// Checked-out branch; None when the HEAD is detached
+    pub branch: Option<String>,
+    /// Tracked files with uncommitted changes (untracked files aren't counted)
+    pub changed_files: usize,
+    pub dirty: bool,
+    /// Commits not pushed to / not pulled from the upstream branch
+    pub ahead: u32,
+    pub behind: u32,
+    pub last_commit_at: Option<DateTime<Utc>>,
+}
+
+/// Parse `git status --porcelain=v2 --branch` output
+fn parse_git_status(output: &str) -> GitInfo {
+    let mut info = GitInfo::default();
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            info.branch = (head != "(detached)").then(|| head.to_string());
+        } else if let Some(counts) = line.strip_prefix("# branch.ab ") {
+            for count in counts.split_whitespace() {
+                if let Some(n) = count.strip_prefix('+') {
+                    info.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = count.strip_prefix('-') {
+                    info.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') && !line.is_empty() {
+            info.changed_files += 1;
+        }
+    }
+    info.dirty = info.changed_files > 0;
+    info
+}
+
+/// Run git in a directory, returning its output when it succeeds
+fn git(path: &Path, args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        // Don't take the index lock just to refresh stat info
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+}
+
+/// Git state of a project, or None when it isn't a git repository root
+///
+/// Projects inside another repository (a monorepo's packages) are skipped,
+/// since their state would be the parent's.
+pub fn git_info(path: &Path) -> Option<GitInfo> {
+    if !path.join(".git").exists() {
+        return None;
+    }
+
+    let status = git(
+        path,
+        &[
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "--untracked-files=no",
+        ],
+    )?;
+    let mut info = parse_git_status(&status);
+    info.last_commit_at = git(path, &["log", "-1", "--format=%ct"])
+        .and_then(|ts| ts.trim().parse::<i64>().ok())
+        .and_then(|ts| Utc.timestamp_opt(ts, 0).single());
+    Some(info)
+}
+
+/// Git state of several projects, checked in parallel
+pub fn git_infos(paths: &[&Path]) -> Vec<Option<GitInfo>> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .iter()
+            .map(|path| scope.spawn(move || git_info(path)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().ok().flatten())
+            .collect()
+    })
+}
+
+/// Compact age of a commit, e.g. "5m", "3h", "2d", "6w"
+pub fn format_commit_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let minutes = (now - at).num_minutes().max(0);
+    match minutes {
+        0..=59 => format!("{}m", minutes),
+        60..=1439 => format!("{}h", minutes / 60),
+        1440..=20159 => format!("{}d", minutes / 1440),
+        _ => format!("{}w", minutes / 10080),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_git_status() {
+        let output = "# branch.oid 1a2b3c\n\
+                      # branch.head feature/cart\n\
+                      # branch.upstream origin/feature/cart\n\
+                      # branch.ab +2 -1\n\
+                      1 .M N... 100644 100644 100644 abc abc app/Cart.php\n\
+                      1 M. N... 100644 100644 100644 abc abc routes/web.php\n";
+        let info = parse_git_status(output);
+        assert_eq!(info.branch.as_deref(), Some("feature/cart"));
+        assert_eq!(info.changed_files, 2);
+        assert!(info.dirty);
+        assert_eq!((info.ahead, info.behind), (2, 1));
+
+        let clean = parse_git_status("# branch.oid 1a2b3c\n# branch.head (detached)\n");
+        assert_eq!(clean.branch, None);
+        assert!(!clean.dirty);
+        assert_eq!((clean.ahead, clean.behind), (0, 0));
+    }
+
+    #[test]
+    fn test_format_commit_age() {
+        let now = Utc::now();
+        let ago = |minutes| now - chrono::Duration::minutes(minutes);
+        assert_eq!(format_commit_age(ago(5), now), "5m");
+        assert_eq!(format_commit_age(ago(180), now), "3h");
+        assert_eq!(format_commit_age(ago(2 * 1440), now), "2d");
+        assert_eq!(format_commit_age(ago(21 * 1440), now), "3w");
+        assert_eq!(
+            format_commit_age(now + chrono::Duration::minutes(3), now),
+            "0m"
+        );
+    }
+
+    #[test]
+    fn test_git_info_skips_non_repositories() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(git_info(dir.path()), None);
+    }
+
     #[test]
     fn test_generate_subdomain() {
-        assert_eq!(generate_subdomain("my-project"), "my-project");
-        assert_eq!(generate_subdomain("My Project"), "my-project");
-        assert_eq!(generate_subdomain("test_project"), "test-project");
+        let settings = SlugSettings::default();
+        assert_eq!(generate_subdomain("my-project", &settings), "my-project");
+        assert_eq!(generate_subdomain("My Project", &settings), "my-project");
+        assert_eq!(
+            generate_subdomain("test_project", &settings),
+            "test-project"
+        );
+    }
+
+    #[test]
+    fn test_scan_nested_and_symlinked_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        // A project, a folder of client projects, and an empty folder
+        fs::create_dir_all(root.join("blog")).unwrap();
+        fs::write(root.join("blog/index.php"), "").unwrap();
+        fs::create_dir_all(root.join("clients/acme")).unwrap();
+        fs::write(root.join("clients/acme/artisan"), "").unwrap();
+        fs::create_dir_all(root.join("scratch")).unwrap();
+
+        // A linked project, and a link back to the parked directory
+        let elsewhere = tempfile::tempdir().unwrap();
+        fs::write(elsewhere.path().join("index.html"), "").unwrap();
+        std::os::unix::fs::symlink(elsewhere.path(), root.join("docs")).unwrap();
+        std::os::unix::fs::symlink(root, root.join("clients/loop")).unwrap();
+
+        let names = |depth| -> Vec<String> {
+            scan_directory(root, depth)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.name)
+                .collect()
+        };
+        assert_eq!(names(1), vec!["blog", "clients", "docs", "scratch"]);
+        assert_eq!(names(2), vec!["blog", "clients/acme", "docs", "scratch"]);
+        assert_eq!(
+            generate_subdomain("clients/acme", &SlugSettings::default()),
+            "clients-acme"
+        );
+
+        let watched = watch_paths(root, 2).unwrap();
+        assert!(watched.contains(&fs::canonicalize(root.join("clients")).unwrap()));
+        assert!(!watched.contains(&fs::canonicalize(root.join("blog")).unwrap()));
     }
 
     #[test]
@@ -709,7 +1009,7 @@ mod tests {
                 false,
             ), // Static
         ];
-        let content = generate_caddyfile_content(&projects, 8888);
+        let content = generate_caddyfile_content(&projects, 8888, &PhpIniOverrides::default());
 
         assert!(content.contains("frankenphp"));
         assert!(content.contains(":8888"));
@@ -1,7 +1,12 @@
 //! Park Directory Watcher
 //!
 //! Watches parked directories for file system changes and triggers sync.
+//!
+//! Only the folders that decide which projects exist are watched: the parked
+//! directory and, for deeper parks, the folders searched for nested projects
+//! (symlinks resolved). A refresh re-watches, so new folders are picked up.
 
+use crate::park;
 use notify::RecursiveMode;
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
 use std::collections::HashMap;
@@ -21,7 +26,7 @@ struct WatcherHandle {
     #[allow(dead_code)]
     debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
     #[allow(dead_code)]
-    path: PathBuf,
+    paths: Vec<PathBuf>,
 }
 
 impl Default for ParkWatcherState {
@@ -42,8 +47,11 @@ impl ParkWatcherState {
         &self,
         parked_dir_id: Uuid,
         path: PathBuf,
+        depth: u8,
         app_handle: AppHandle,
     ) -> Result<(), String> {
+        let paths = park::watch_paths(&path, depth)?;
+
         let mut watchers = self
             .watchers
             .lock()
@@ -78,13 +86,15 @@ impl ParkWatcherState {
         )
         .map_err(|e| format!("Failed to create watcher: {}", e))?;
 
-        // Start watching the directory (non-recursive - only watch immediate children)
-        debouncer
-            .watcher()
-            .watch(&path, RecursiveMode::NonRecursive)
-            .map_err(|e| format!("Failed to watch directory: {}", e))?;
+        // Watch each directory non-recursively - only its immediate children matter
+        for path in &paths {
+            debouncer
+                .watcher()
+                .watch(path, RecursiveMode::NonRecursive)
+                .map_err(|e| format!("Failed to watch directory: {}", e))?;
+        }
 
-        watchers.insert(parked_dir_id, WatcherHandle { debouncer, path });
+        watchers.insert(parked_dir_id, WatcherHandle { debouncer, paths });
 
         Ok(())
     }
@@ -123,10 +133,10 @@ impl ParkWatcherState {
 /// Initialize watchers for all existing parked directories on startup
 pub fn init_watchers(
     watcher_state: &ParkWatcherState,
-    parked_directories: Vec<(Uuid, PathBuf)>,
+    parked_directories: Vec<(Uuid, PathBuf, u8)>,
     app_handle: AppHandle,
 ) {
-    for (id, path) in parked_directories {
-        let _ = watcher_state.start_watching(id, path, app_handle.clone());
+    for (id, path, depth) in parked_directories {
+        let _ = watcher_state.start_watching(id, path, depth, app_handle.clone());
     }
 }
@@ -0,0 +1,210 @@
+//! PID file records
+//!
+//! A bare PID isn't enough to tell whether an instance is still running:
+//! after a crash or reboot the OS may hand the same PID to an unrelated
+//! process, and `kill -0` happily reports it alive. Burd records the
+//! process's start time and executable name next to the PID and only treats
+//! the instance as running while all three still match.
+//!
+//! Files written by older versions contain only the PID; those are trusted
+//! as before as long as the process exists.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// What Burd knows about a process it started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PidRecord {
+    pub pid: u32,
+    /// Start time as reported by `ps -o lstart`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>,
+    /// Executable name as reported by `ps -o comm`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+/// A live process as seen by `ps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProcessInfo {
+    zombie: bool,
+    started: String,
+    command: String,
+}
+
+/// Parse one line of `ps -o stat= -o lstart= -o comm=`
+///
+/// `lstart` is always five fields (`Mon Jan  1 10:00:00 2024`); the command
+/// may contain spaces.
+fn parse_ps_line(line: &str) -> Option<ProcessInfo> {
+    let mut fields = line.split_whitespace();
+    let stat = fields.next()?;
+    let started: Vec<&str> = fields.by_ref().take(5).collect();
+    if started.len() != 5 {
+        return None;
+    }
+    let command = fields.collect::<Vec<_>>().join(" ");
+    Some(ProcessInfo {
+        zombie: stat.starts_with('Z'),
+        started: started.join(" "),
+        command,
+    })
+}
+
+/// Look up a process, `None` when no process has this PID
+fn process_info(pid: u32) -> Option<ProcessInfo> {
+    let output = Command::new("ps")
+        .args([
+            "-p",
+            &pid.to_string(),
+            "-o",
+            "stat=",
+            "-o",
+            "lstart=",
+            "-o",
+            "comm=",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ps_line(String::from_utf8_lossy(&output.stdout).lines().next()?)
+}
+
+fn file_name(command: &str) -> &str {
+    Path::new(command)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(command)
+}
+
+/// Whether two `comm` values name the same executable
+///
+/// Linux truncates `comm` to 15 characters, so a prefix match is accepted.
+fn same_command(recorded: &str, actual: &str) -> bool {
+    let (recorded, actual) = (file_name(recorded), file_name(actual));
+    !actual.is_empty() && (recorded.starts_with(actual) || actual.starts_with(recorded))
+}
+
+impl PidRecord {
+    /// Record a process Burd just spawned
+    pub fn capture(pid: u32) -> Self {
+        let info = process_info(pid);
+        Self {
+            pid,
+            started: info.as_ref().map(|i| i.started.clone()),
+            command: info.map(|i| i.command),
+        }
+    }
+
+    /// Parse a PID file, accepting the legacy bare-PID format
+    pub fn parse(contents: &str) -> Option<Self> {
+        let contents = contents.trim();
+        if let Ok(pid) = contents.parse() {
+            return Some(Self {
+                pid,
+                started: None,
+                command: None,
+            });
+        }
+        serde_json::from_str(contents).ok()
+    }
+
+    pub fn to_file_contents(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.pid.to_string())
+    }
+
+    /// Whether the recorded process is still alive (and still the same process)
+    pub fn is_alive(&self) -> bool {
+        process_info(self.pid).is_some_and(|info| self.matches(&info))
+    }
+
+    fn matches(&self, info: &ProcessInfo) -> bool {
+        if info.zombie {
+            return false;
+        }
+        let same_start = self.started.as_ref().is_none_or(|s| *s == info.started);
+        let same_command = self
+            .command
+            .as_ref()
+            .is_none_or(|c| same_command(c, &info.command));
+        same_start && same_command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(stat: &str, started: &str, command: &str) -> ProcessInfo {
+        ProcessInfo {
+            zombie: stat.starts_with('Z'),
+            started: started.to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_ps_line() {
+        let parsed =
+            parse_ps_line("Ss   Mon Jan  1 10:00:00 2024 /Users/me/Library/Burd/bin/redis server")
+                .unwrap();
+        assert_eq!(
+            parsed,
+            info(
+                "Ss",
+                "Mon Jan 1 10:00:00 2024",
+                "/Users/me/Library/Burd/bin/redis server"
+            )
+        );
+        assert!(parse_ps_line("Z Mon Jan 1").is_none());
+    }
+
+    #[test]
+    fn test_parse_legacy_and_json_records() {
+        assert_eq!(PidRecord::parse("1234\n").unwrap().pid, 1234);
+
+        let record = PidRecord {
+            pid: 42,
+            started: Some("Mon Jan 1 10:00:00 2024".to_string()),
+            command: Some("/bin/mariadbd".to_string()),
+        };
+        assert_eq!(
+            PidRecord::parse(&record.to_file_contents()).unwrap(),
+            record
+        );
+        assert!(PidRecord::parse("garbage").is_none());
+    }
+
+    #[test]
+    fn test_recycled_pid_does_not_match() {
+        let record = PidRecord {
+            pid: 42,
+            started: Some("Mon Jan 1 10:00:00 2024".to_string()),
+            command: Some("/opt/burd/bin/mariadb/11.4.2/mariadbd".to_string()),
+        };
+        assert!(record.matches(&info("Ss", "Mon Jan 1 10:00:00 2024", "mariadbd")));
+        // Same PID, started later by something else
+        assert!(!record.matches(&info("Ss", "Tue Jan 2 09:00:00 2024", "mariadbd")));
+        assert!(!record.matches(&info("Ss", "Mon Jan 1 10:00:00 2024", "Safari")));
+        assert!(!record.matches(&info("Z", "Mon Jan 1 10:00:00 2024", "mariadbd")));
+
+        let legacy = PidRecord::parse("42").unwrap();
+        assert!(legacy.matches(&info("S", "Tue Jan 2 09:00:00 2024", "anything")));
+    }
+
+    #[test]
+    fn test_current_process_is_alive() {
+        let record = PidRecord::capture(std::process::id());
+        assert!(record.started.is_some());
+        assert!(record.is_alive());
+
+        let recycled = PidRecord {
+            started: Some("Thu Jan 1 00:00:00 1970".to_string()),
+            ..record
+        };
+        assert!(!recycled.is_alive());
+    }
+}
@@ -0,0 +1,270 @@
+//! Platform Integration
+//!
+//! The OS-specific parts of Burd sit behind the traits here:
+//! - the privileged proxy daemon serving ports 80/443 ([`ProxyDaemon`])
+//! - the user service running `burd daemon run` at login ([`UserService`])
+//! - routing a TLD's DNS queries to our DNS server ([`DnsResolver`])
+//! - running a shell script as root ([`run_privileged`])
+//!
+//! macOS uses launchd and `/etc/resolver` (see [`crate::launchd`],
+//! [`crate::daemon`] and [`crate::resolver`]); Linux uses systemd units and
+//! systemd-resolved, so the core and the CLI run there too. The GUI stays
+//! macOS-first.
+
+pub mod resolved;
+pub mod systemd;
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::launchd::Launchd;
+use crate::resolver::MacResolver;
+
+/// Operating system Burd runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    MacOs,
+    Linux,
+}
+
+impl Os {
+    /// OS Burd itself was built for
+    pub fn host() -> Self {
+        if cfg!(target_os = "macos") {
+            Os::MacOs
+        } else {
+            Os::Linux
+        }
+    }
+
+    /// OS part of platform keys and most download names (`darwin-arm64`)
+    pub fn download_name(&self) -> &'static str {
+        match self {
+            Os::MacOs => "darwin",
+            Os::Linux => "linux",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Os::MacOs => "macOS",
+            Os::Linux => "Linux",
+        }
+    }
+}
+
+/// Status of a background service
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceStatus {
+    pub installed: bool,
+    pub running: bool,
+    pub pid: Option<u32>,
+}
+
+/// System service running Caddy on ports 80 and 443
+pub trait ProxyDaemon: Send + Sync {
+    /// Install and start the service (requires admin privileges)
+    ///
+    /// Before calling this, ensure Caddy is installed via
+    /// `caddy::install_caddy_for_daemon()` and the Caddyfile is written.
+    fn install(&self) -> Result<(), String>;
+    /// Stop and remove the service (requires admin privileges)
+    fn uninstall(&self) -> Result<(), String>;
+    fn is_installed(&self) -> bool;
+    fn status(&self) -> ServiceStatus;
+    fn start(&self) -> Result<(), String>;
+    fn restart(&self) -> Result<(), String>;
+}
+
+/// Per-user service starting `burd daemon run` at login
+pub trait UserService: Send + Sync {
+    /// Install and start the service running `exe daemon run`
+    fn install(&self, exe: &Path, log: &Path) -> Result<(), String>;
+    fn uninstall(&self) -> Result<(), String>;
+    fn status(&self) -> ServiceStatus;
+    /// File defining the service
+    fn definition_path(&self) -> Result<PathBuf, String>;
+}
+
+/// Routes a TLD's DNS queries to Burd's DNS server
+pub trait DnsResolver: Send + Sync {
+    /// Route `tld` to our DNS server (requires admin privileges)
+    fn install(&self, tld: &str, dns_port: u16) -> Result<(), String>;
+    /// Stop routing `tld` (requires admin privileges)
+    fn uninstall(&self, tld: &str) -> Result<(), String>;
+    fn is_installed(&self, tld: &str) -> bool;
+    /// File routing `tld`
+    fn config_path(&self, tld: &str) -> PathBuf;
+    /// Nameserver and port `tld` is routed to, when installed
+    fn current_config(&self, tld: &str) -> Option<ResolverConfig>;
+    /// Make the system pick up resolver changes
+    fn flush_cache(&self) -> Result<(), String>;
+}
+
+/// Where a TLD's queries are sent
+#[derive(Debug, Clone)]
+pub struct ResolverConfig {
+    pub nameserver: Option<String>,
+    pub port: u16,
+}
+
+/// The proxy daemon of this OS
+pub fn proxy_daemon() -> &'static dyn ProxyDaemon {
+    match Os::host() {
+        Os::MacOs => &Launchd,
+        Os::Linux => &systemd::SystemdProxy,
+    }
+}
+
+/// The login service of this OS
+pub fn user_service() -> &'static dyn UserService {
+    match Os::host() {
+        Os::MacOs => &crate::daemon::LaunchAgent,
+        Os::Linux => &systemd::SystemdUserService,
+    }
+}
+
+/// The DNS resolver integration of this OS
+pub fn dns_resolver() -> &'static dyn DnsResolver {
+    match Os::host() {
+        Os::MacOs => &MacResolver,
+        Os::Linux => &resolved::SystemdResolved,
+    }
+}
+
+// === Paths ===
+
+/// The user's app directory, resolved from the home directory
+///
+/// Stays in user space even when code runs with elevated privileges.
+pub fn user_app_dir() -> PathBuf {
+    let relative = match Os::host() {
+        Os::MacOs => "Library/Application Support/Burd",
+        Os::Linux => ".local/share/Burd",
+    };
+    dirs::home_dir()
+        .map(|h| h.join(relative))
+        .unwrap_or_else(|| PathBuf::from("/tmp/Burd"))
+}
+
+/// The user's logs directory
+pub fn user_logs_dir() -> PathBuf {
+    let relative = match Os::host() {
+        Os::MacOs => "Library/Logs/Burd",
+        Os::Linux => ".local/state/Burd/logs",
+    };
+    dirs::home_dir()
+        .map(|h| h.join(relative))
+        .unwrap_or_else(|| PathBuf::from("/tmp/Burd/logs"))
+}
+
+/// Files the proxy daemon's stdout and stderr are redirected to
+pub fn proxy_output_paths() -> (PathBuf, PathBuf) {
+    let logs_dir = user_logs_dir();
+    (logs_dir.join("caddy.log"), logs_dir.join("caddy.error.log"))
+}
+
+/// Where Caddy stores its data (PKI, etc.) when running as the daemon
+pub fn caddy_data_dir() -> PathBuf {
+    user_app_dir().join("caddy-data")
+}
+
+/// Check the daemon's Caddy binary and Caddyfile exist, and create the
+/// user-space directories it writes to (no admin needed)
+pub(crate) fn prepare_proxy_daemon() -> Result<(), String> {
+    if !crate::caddy::is_daemon_caddy_installed() {
+        return Err(
+            "Caddy binary not installed. Call caddy::install_caddy_for_daemon() first.".to_string(),
+        );
+    }
+
+    if !user_app_dir().join("Caddyfile").exists() {
+        return Err("Caddyfile not found. Call caddy::write_caddyfile() first.".to_string());
+    }
+
+    std::fs::create_dir_all(user_logs_dir())
+        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    std::fs::create_dir_all(caddy_data_dir())
+        .map_err(|e| format!("Failed to create caddy data directory: {}", e))
+}
+
+// === Privileges ===
+
+/// Whether `program` is on PATH
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Quote a value for a POSIX shell
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Run a shell script as root, asking for the password
+///
+/// macOS shows the standard admin dialog. Linux uses pkexec in a graphical
+/// session and sudo otherwise.
+pub fn run_privileged(script: &str) -> Result<(), String> {
+    let output = match Os::host() {
+        Os::MacOs => {
+            let escaped = script.replace('\\', "\\\\").replace('"', "\\\"");
+            Command::new("osascript")
+                .arg("-e")
+                .arg(format!(
+                    "do shell script \"{}\" with administrator privileges",
+                    escaped
+                ))
+                .output()
+                .map_err(|e| format!("Failed to run osascript: {}", e))?
+        }
+        Os::Linux => {
+            let graphical = std::env::var_os("DISPLAY").is_some()
+                || std::env::var_os("WAYLAND_DISPLAY").is_some();
+            let program = if graphical && on_path("pkexec") {
+                "pkexec"
+            } else {
+                "sudo"
+            };
+            Command::new(program)
+                .args(["sh", "-c", script])
+                .output()
+                .map_err(|e| format!("Failed to run {}: {}", program, e))?
+        }
+    };
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // osascript reports -128, pkexec exits 126 when the dialog is dismissed
+    if stderr.contains("User canceled")
+        || stderr.contains("(-128)")
+        || output.status.code() == Some(126)
+    {
+        return Err("User cancelled the authorization".to_string());
+    }
+    Err(stderr.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/tmp/a b"), "'/tmp/a b'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_user_dirs_stay_in_home() {
+        let home = dirs::home_dir().unwrap();
+        assert!(user_app_dir().starts_with(&home));
+        assert!(caddy_data_dir().ends_with("Burd/caddy-data"));
+        assert!(proxy_output_paths().0.ends_with("caddy.log"));
+    }
+}
@@ -0,0 +1,145 @@
+//! systemd-resolved Integration for Linux
+//!
+//! Routes a TLD to our DNS server with a drop-in per TLD in
+//! `/etc/systemd/resolved.conf.d/`, adding it to the global DNS servers with
+//! a routing-only domain (`~burd`), so queries for the TLD always reach it.
+//!
+//! A global server also takes part in lookups matching no routing domain.
+//! resolved sticks with one global server until it fails, and ours comes
+//! after any set in resolved.conf, so it normally sees those lookups only
+//! when the others are unreachable; it then answers NXDOMAIN for names
+//! outside its TLDs. Links with their own DNS servers (set by
+//! NetworkManager or networkd) keep answering those lookups either way.
+
+use super::{run_privileged, shell_quote, DnsResolver, ResolverConfig};
+use crate::domain::DEFAULT_DNS_PORT;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Directory of systemd-resolved drop-ins
+const DROP_IN_DIR: &str = "/etc/systemd/resolved.conf.d";
+
+/// Drop-in content routing `tld` to our DNS server
+fn drop_in_content(tld: &str, dns_port: u16) -> String {
+    format!(
+        "# Burd DNS resolver for .{tld} domains\n\
+         # This file was created by the Burd application\n\
+         [Resolve]\n\
+         DNS=127.0.0.1:{port}\n\
+         Domains=~{tld}\n",
+        tld = tld,
+        port = dns_port
+    )
+}
+
+/// Parse the nameserver and port from a drop-in's `DNS=` line
+fn parse_drop_in(content: &str) -> ResolverConfig {
+    let server = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("DNS="))
+        .and_then(|servers| servers.split_whitespace().next());
+
+    let (nameserver, port) = match server.and_then(|s| s.rsplit_once(':')) {
+        Some((ip, port)) => (Some(ip.to_string()), port.parse().ok()),
+        None => (server.map(String::from), None),
+    };
+
+    ResolverConfig {
+        nameserver,
+        port: port.unwrap_or(DEFAULT_DNS_PORT),
+    }
+}
+
+/// systemd-resolved drop-ins
+pub struct SystemdResolved;
+
+impl DnsResolver for SystemdResolved {
+    fn install(&self, tld: &str, dns_port: u16) -> Result<(), String> {
+        let path = self.config_path(tld);
+        let content = drop_in_content(tld, dns_port);
+        if fs::read_to_string(&path).ok().as_deref() == Some(content.as_str()) {
+            return Ok(()); // Already correctly configured
+        }
+
+        run_privileged(&format!(
+            "mkdir -p {dir} && printf '%s' {content} > {path} && systemctl restart systemd-resolved",
+            dir = DROP_IN_DIR,
+            content = shell_quote(&content),
+            path = shell_quote(&path.to_string_lossy())
+        ))
+        .map_err(|e| format!("Failed to install resolver: {}", e))
+    }
+
+    fn uninstall(&self, tld: &str) -> Result<(), String> {
+        if !self.is_installed(tld) {
+            return Ok(()); // Already uninstalled
+        }
+
+        run_privileged(&format!(
+            "rm -f {} && systemctl restart systemd-resolved",
+            shell_quote(&self.config_path(tld).to_string_lossy())
+        ))
+        .map_err(|e| format!("Failed to uninstall resolver: {}", e))
+    }
+
+    fn is_installed(&self, tld: &str) -> bool {
+        self.config_path(tld).exists()
+    }
+
+    fn config_path(&self, tld: &str) -> PathBuf {
+        PathBuf::from(DROP_IN_DIR).join(format!("burd-{}.conf", tld))
+    }
+
+    fn current_config(&self, tld: &str) -> Option<ResolverConfig> {
+        fs::read_to_string(self.config_path(tld))
+            .ok()
+            .map(|content| parse_drop_in(&content))
+    }
+
+    fn flush_cache(&self) -> Result<(), String> {
+        Command::new("resolvectl")
+            .arg("flush-caches")
+            .output()
+            .map_err(|e| format!("Failed to flush DNS cache: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_in_round_trip() {
+        let content = drop_in_content("burd", 5354);
+        assert!(content.contains("Domains=~burd"));
+
+        let config = parse_drop_in(&content);
+        assert_eq!(config.nameserver.as_deref(), Some("127.0.0.1"));
+        assert_eq!(config.port, 5354);
+
+        let without_port = parse_drop_in("[Resolve]\nDNS=127.0.0.1\n");
+        assert_eq!(without_port.port, DEFAULT_DNS_PORT);
+    }
+
+    #[test]
+    fn test_drop_in_routes_only_the_tld() {
+        let content = drop_in_content("test", 5354);
+        let domains: Vec<&str> = content
+            .lines()
+            .filter_map(|line| line.strip_prefix("Domains="))
+            .collect();
+        // Routing-only: no search domain, and never the catch-all "~."
+        assert_eq!(domains, vec!["~test"]);
+        assert_eq!(content.matches("DNS=").count(), 1);
+    }
+
+    #[test]
+    fn test_config_path() {
+        assert_eq!(
+            SystemdResolved.config_path("test").to_string_lossy(),
+            "/etc/systemd/resolved.conf.d/burd-test.conf"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! systemd Units for Linux
+//!
+//! The proxy daemon is a system unit running Caddy as the user with
+//! `CAP_NET_BIND_SERVICE`, so it can bind ports 80 and 443 while its PKI
+//! stays user-owned. The headless daemon is a user unit started at login.
+
+use super::{
+    caddy_data_dir, prepare_proxy_daemon, proxy_output_paths, run_privileged, shell_quote,
+    user_app_dir, ProxyDaemon, ServiceStatus, UserService,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the proxy daemon's system unit
+const PROXY_UNIT: &str = "burd-proxy.service";
+
+/// Where system units are installed
+const SYSTEM_UNIT_DIR: &str = "/etc/systemd/system";
+
+/// Name of the headless daemon's user unit
+const DAEMON_UNIT: &str = "burd-daemon.service";
+
+fn proxy_unit_path() -> PathBuf {
+    Path::new(SYSTEM_UNIT_DIR).join(PROXY_UNIT)
+}
+
+/// Generate the proxy daemon's unit, running Caddy as `user`
+fn generate_proxy_unit(user: &str) -> String {
+    let app_dir = user_app_dir();
+    let (stdout_log, stderr_log) = proxy_output_paths();
+
+    format!(
+        "[Unit]
+Description=Burd proxy (Caddy on ports 80 and 443)
+After=network.target
+
+[Service]
+User={user}
+ExecStart={caddy} run --config {caddyfile} --adapter caddyfile --watch
+WorkingDirectory={working_dir}
+Environment=XDG_DATA_HOME={caddy_data}
+AmbientCapabilities=CAP_NET_BIND_SERVICE
+UMask=0022
+Restart=always
+StandardOutput=append:{stdout_log}
+StandardError=append:{stderr_log}
+
+[Install]
+WantedBy=multi-user.target
+",
+        user = user,
+        caddy = app_dir.join("bin/caddy").display(),
+        caddyfile = app_dir.join("Caddyfile").display(),
+        working_dir = app_dir.display(),
+        caddy_data = caddy_data_dir().display(),
+        stdout_log = stdout_log.display(),
+        stderr_log = stderr_log.display()
+    )
+}
+
+/// Generate the headless daemon's user unit
+fn generate_daemon_unit(exe: &Path, log: &Path) -> String {
+    format!(
+        "[Unit]
+Description=Burd daemon (API, DNS, proxy sync and instance supervision)
+
+[Service]
+ExecStart={exe} daemon run
+Restart=on-failure
+StandardOutput=append:{log}
+StandardError=append:{log}
+
+[Install]
+WantedBy=default.target
+",
+        exe = exe.display(),
+        log = log.display()
+    )
+}
+
+/// Parse `systemctl show -p MainPID --value` output (0 when not running)
+fn parse_main_pid(output: &str) -> Option<u32> {
+    output.trim().parse().ok().filter(|pid| *pid > 0)
+}
+
+/// Main PID of a unit, if it's running
+fn main_pid(user: bool, unit: &str) -> Option<u32> {
+    let mut cmd = Command::new("systemctl");
+    if user {
+        cmd.arg("--user");
+    }
+    let output = cmd
+        .args(["show", "-p", "MainPID", "--value", unit])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    parse_main_pid(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn systemctl_user(args: &[&str]) -> Result<(), String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "systemctl --user {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// The proxy daemon as a system unit
+pub struct SystemdProxy;
+
+impl ProxyDaemon for SystemdProxy {
+    fn install(&self) -> Result<(), String> {
+        prepare_proxy_daemon()?;
+
+        let user = std::env::var("USER").map_err(|_| "Could not determine the current user")?;
+        let temp_unit = std::env::temp_dir().join(PROXY_UNIT);
+        fs::write(&temp_unit, generate_proxy_unit(&user))
+            .map_err(|e| format!("Failed to write temp unit: {}", e))?;
+
+        let result = run_privileged(&format!(
+            "install -m 644 {} {} && systemctl daemon-reload && systemctl enable --now {}",
+            shell_quote(&temp_unit.to_string_lossy()),
+            shell_quote(&proxy_unit_path().to_string_lossy()),
+            PROXY_UNIT
+        ));
+        let _ = fs::remove_file(&temp_unit);
+
+        result.map_err(|e| format!("Failed to install the proxy unit: {}", e))
+    }
+
+    fn uninstall(&self) -> Result<(), String> {
+        run_privileged(&format!(
+            "systemctl disable --now {unit} 2>/dev/null || true; rm -f {path}; systemctl daemon-reload",
+            unit = PROXY_UNIT,
+            path = shell_quote(&proxy_unit_path().to_string_lossy())
+        ))
+        .map_err(|e| format!("Failed to uninstall the proxy unit: {}", e))
+    }
+
+    fn is_installed(&self) -> bool {
+        proxy_unit_path().exists()
+    }
+
+    fn status(&self) -> ServiceStatus {
+        let installed = self.is_installed();
+        let pid = if installed {
+            main_pid(false, PROXY_UNIT)
+        } else {
+            None
+        };
+        ServiceStatus {
+            installed,
+            running: pid.is_some(),
+            pid,
+        }
+    }
+
+    fn start(&self) -> Result<(), String> {
+        if !self.is_installed() {
+            return Err("Proxy daemon is not installed".to_string());
+        }
+        run_privileged(&format!("systemctl start {}", PROXY_UNIT))
+            .map_err(|e| format!("Failed to start daemon: {}", e))
+    }
+
+    fn restart(&self) -> Result<(), String> {
+        if !self.is_installed() {
+            return Err("Proxy daemon is not installed".to_string());
+        }
+        run_privileged(&format!("systemctl restart {}", PROXY_UNIT))
+            .map_err(|e| format!("Failed to restart daemon: {}", e))
+    }
+}
+
+/// The headless daemon as a user unit
+pub struct SystemdUserService;
+
+impl UserService for SystemdUserService {
+    fn install(&self, exe: &Path, log: &Path) -> Result<(), String> {
+        let unit = self.definition_path()?;
+        if let Some(dir) = unit.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create systemd user directory: {}", e))?;
+        }
+
+        fs::write(&unit, generate_daemon_unit(exe, log))
+            .map_err(|e| format!("Failed to write unit: {}", e))?;
+
+        systemctl_user(&["daemon-reload"])?;
+        // Restart so an updated unit applies when it was already running
+        systemctl_user(&["enable", DAEMON_UNIT])?;
+        systemctl_user(&["restart", DAEMON_UNIT])
+    }
+
+    fn uninstall(&self) -> Result<(), String> {
+        let unit = self.definition_path()?;
+        if !unit.exists() {
+            return Ok(()); // Already uninstalled
+        }
+
+        let _ = systemctl_user(&["disable", "--now", DAEMON_UNIT]);
+        fs::remove_file(&unit).map_err(|e| format!("Failed to remove unit: {}", e))?;
+        systemctl_user(&["daemon-reload"])
+    }
+
+    fn status(&self) -> ServiceStatus {
+        let installed = self.definition_path().map(|p| p.exists()).unwrap_or(false);
+        let pid = if installed {
+            main_pid(true, DAEMON_UNIT)
+        } else {
+            None
+        };
+        ServiceStatus {
+            installed,
+            running: pid.is_some(),
+            pid,
+        }
+    }
+
+    fn definition_path(&self) -> Result<PathBuf, String> {
+        dirs::config_dir()
+            .map(|c| c.join("systemd/user").join(DAEMON_UNIT))
+            .ok_or_else(|| "Could not determine config directory".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_unit_generation() {
+        let unit = generate_proxy_unit("dev");
+        assert!(unit.contains("User=dev"));
+        assert!(unit.contains("run --config"));
+        assert!(unit.contains("Caddyfile --adapter caddyfile --watch"));
+        assert!(unit.contains("AmbientCapabilities=CAP_NET_BIND_SERVICE"));
+        assert!(unit.contains("Environment=XDG_DATA_HOME="));
+        assert!(unit.contains("Burd/caddy-data"));
+        assert!(unit.contains("WantedBy=multi-user.target"));
+    }
+
+    #[test]
+    fn test_daemon_unit_generation() {
+        let unit = generate_daemon_unit(Path::new("/usr/bin/burd"), Path::new("/tmp/daemon.log"));
+        assert!(unit.contains("ExecStart=/usr/bin/burd daemon run"));
+        assert!(unit.contains("StandardOutput=append:/tmp/daemon.log"));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn test_parse_main_pid() {
+        assert_eq!(parse_main_pid("4242\n"), Some(4242));
+        assert_eq!(parse_main_pid("0\n"), None);
+        assert_eq!(parse_main_pid(""), None);
+    }
+}
@@ -0,0 +1,390 @@
+//! Automatic port assignment
+//!
+//! Picks a port for new instances so callers don't have to. The service's
+//! default port is preferred when it's free; otherwise the next free port in
+//! the configured range is used. Ports used by other instances, ports Burd
+//! itself listens on, and ports already bound by other processes are skipped.
+//!
+//! Explicitly requested ports go through the same checks, so a port held by
+//! Docker or another app is caught at creation instead of failing at start.
+//! Moving an existing instance to a new port does too.
+
+use crate::api::API_PORT;
+use crate::config::{Config, ServiceType};
+use crate::services::mailpit::MailpitService;
+use crate::validation;
+use std::collections::{HashMap, HashSet};
+use std::net::{SocketAddr, TcpListener};
+use uuid::Uuid;
+
+/// Check whether a port can be bound on localhost
+pub fn is_port_available(port: u16) -> bool {
+    TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).is_ok()
+}
+
+/// Collect ports that must never be assigned to a new instance
+///
+/// Includes Burd's own listeners and every port known to belong to an
+/// instance (its main port plus any `*_port` entries in its config, such as
+/// Mailpit's SMTP port).
+pub fn reserved_ports(config: &Config) -> HashSet<u16> {
+    reserved_port_owners(config).into_keys().collect()
+}
+
+/// Reserved ports and a description of what holds each one
+fn reserved_port_owners(config: &Config) -> HashMap<u16, String> {
+    let mut owners = HashMap::new();
+    // Instances are recorded first so a misconfigured Burd port still names them
+    for instance in &config.instances {
+        let owner = format!("instance '{}'", instance.name);
+        owners.entry(instance.port).or_insert_with(|| owner.clone());
+
+        if let Some(settings) = instance.config.as_object() {
+            for (key, value) in settings {
+                if !key.ends_with("_port") {
+                    continue;
+                }
+                let port = value
+                    .as_u64()
+                    .or_else(|| value.as_str().and_then(|s| s.parse().ok()));
+                if let Some(port) = port.and_then(|p| u16::try_from(p).ok()) {
+                    owners
+                        .entry(port)
+                        .or_insert_with(|| format!("{} ({})", owner, key));
+                }
+            }
+        }
+
+        // Mailpit listens for SMTP on 1025 even when smtp_port isn't set
+        if instance.service_type == ServiceType::Mailpit {
+            owners
+                .entry(MailpitService::smtp_port(instance))
+                .or_insert_with(|| format!("{} (SMTP)", owner));
+        }
+    }
+
+    for (port, owner) in [
+        (API_PORT, "Burd's API server"),
+        (config.dns_port, "Burd's DNS server"),
+        (config.proxy_port, "Burd's proxy"),
+    ] {
+        owners.entry(port).or_insert_with(|| owner.to_string());
+    }
+
+    owners
+}
+
+/// Use `requested` if it's free, otherwise reject it or (with `auto_assign`)
+/// pick a free port instead; suggests one when nothing was requested
+pub fn resolve_port(
+    config: &Config,
+    service_type: ServiceType,
+    requested: Option<u16>,
+    auto_assign: bool,
+) -> Result<u16, String> {
+    resolve_port_with(
+        config,
+        service_type,
+        requested,
+        auto_assign,
+        is_port_available,
+    )
+}
+
+/// Suggest a port for a new instance of the given service type
+pub fn suggest_port(config: &Config, service_type: ServiceType) -> Result<u16, String> {
+    suggest_port_with(config, service_type, is_port_available)
+}
+
+/// Check `requested`, or pick a free port, for moving an existing instance
+pub fn resolve_port_change(
+    config: &Config,
+    instance_id: Uuid,
+    requested: Option<u16>,
+) -> Result<u16, String> {
+    resolve_port_change_with(config, instance_id, requested, is_port_available)
+}
+
+/// Check that a port is free for a new instance
+pub fn check_port(config: &Config, port: u16) -> Result<(), String> {
+    check_port_with(config, port, is_port_available)
+}
+
+/// Find the first free port at or above `start` (used for secondary ports)
+pub fn next_free_port(config: &Config, start: u16) -> Result<u16, String> {
+    next_free_port_with(config, start, is_port_available)
+}
+
+/// Find the next free port using a custom availability check (used by tests)
+fn next_free_port_with(
+    config: &Config,
+    start: u16,
+    is_available: impl Fn(u16) -> bool,
+) -> Result<u16, String> {
+    let reserved = reserved_ports(config);
+    (start..=u16::MAX)
+        .find(|port| {
+            validation::validate_port(*port).is_ok()
+                && !reserved.contains(port)
+                && is_available(*port)
+        })
+        .ok_or_else(|| format!("No free port available above {}", start))
+}
+
+/// Check that a port can be used for a new instance
+///
+/// Fails when the port is reserved by Burd or another instance, or already
+/// bound by another process.
+fn check_port_with(
+    config: &Config,
+    port: u16,
+    is_available: impl Fn(u16) -> bool,
+) -> Result<(), String> {
+    validation::validate_port(port).map_err(|e| format!("Invalid port: {}", e))?;
+    if let Some(owner) = reserved_port_owners(config).get(&port) {
+        return Err(format!("Port {} is already used by {}", port, owner));
+    }
+    if !is_available(port) {
+        return Err(format!(
+            "Port {} is already in use by another process (such as a Docker container)",
+            port
+        ));
+    }
+    Ok(())
+}
+
+/// Resolve a port using a custom availability check (used by tests)
+fn resolve_port_with(
+    config: &Config,
+    service_type: ServiceType,
+    requested: Option<u16>,
+    auto_assign: bool,
+    is_available: impl Fn(u16) -> bool + Copy,
+) -> Result<u16, String> {
+    let Some(port) = requested else {
+        return suggest_port_with(config, service_type, is_available);
+    };
+    let Err(conflict) = check_port_with(config, port, is_available) else {
+        return Ok(port);
+    };
+
+    match suggest_port_with(config, service_type, is_available) {
+        Ok(free) if auto_assign => Ok(free),
+        Ok(free) => Err(format!("{}. Port {} is free.", conflict, free)),
+        Err(_) => Err(conflict),
+    }
+}
+
+/// Resolve a port change using a custom availability check (used by tests)
+///
+/// The instance's current port is released for the checks, since the instance
+/// itself may hold it, but never picked. Its secondary ports stay reserved.
+fn resolve_port_change_with(
+    config: &Config,
+    instance_id: Uuid,
+    requested: Option<u16>,
+    is_available: impl Fn(u16) -> bool + Copy,
+) -> Result<u16, String> {
+    let mut config = config.clone();
+    let instance = config
+        .instances
+        .iter_mut()
+        .find(|i| i.id == instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let current = instance.port;
+    if requested == Some(current) {
+        return Err(format!("'{}' already uses port {}", instance.name, current));
+    }
+    let service_type = instance.service_type;
+    instance.port = 0;
+
+    resolve_port_with(&config, service_type, requested, false, move |port| {
+        port != current && is_available(port)
+    })
+}
+
+/// Suggest a port using a custom availability check (used by tests)
+fn suggest_port_with(
+    config: &Config,
+    service_type: ServiceType,
+    is_available: impl Fn(u16) -> bool,
+) -> Result<u16, String> {
+    let range = config.port_range;
+    if range.start > range.end {
+        return Err(format!(
+            "Invalid port range {}-{}: start must not exceed end",
+            range.start, range.end
+        ));
+    }
+
+    let reserved = reserved_ports(config);
+    let is_candidate = |port: u16| {
+        validation::validate_port(port).is_ok() && !reserved.contains(&port) && is_available(port)
+    };
+
+    // Prefer the service's well-known port so connection strings stay familiar
+    let default_port = service_type.default_port();
+    if default_port != 0 && is_candidate(default_port) {
+        return Ok(default_port);
+    }
+
+    (range.start..=range.end)
+        .find(|port| is_candidate(*port))
+        .ok_or_else(|| {
+            format!(
+                "No free port available in range {}-{}",
+                range.start, range.end
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PortRange;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+    use serde_json::json;
+
+    #[test]
+    fn test_prefers_default_port() {
+        let config = ConfigBuilder::new().build();
+        let port = suggest_port_with(&config, ServiceType::Redis, |_| true).unwrap();
+        assert_eq!(port, 6379);
+    }
+
+    #[test]
+    fn test_falls_back_to_range_when_default_taken() {
+        let mut config = ConfigBuilder::new()
+            .instances(vec![InstanceBuilder::new().port(6379).build()])
+            .build();
+        config.port_range = PortRange {
+            start: 20000,
+            end: 20010,
+        };
+
+        let port = suggest_port_with(&config, ServiceType::Redis, |_| true).unwrap();
+        assert_eq!(port, 20000);
+    }
+
+    #[test]
+    fn test_skips_reserved_and_occupied_ports() {
+        let mut config = ConfigBuilder::new()
+            .instances(vec![
+                InstanceBuilder::new()
+                    .port(8025)
+                    .config(json!({ "smtp_port": 20000 }))
+                    .build(),
+                InstanceBuilder::new().port(20001).build(),
+            ])
+            .build();
+        config.port_range = PortRange {
+            start: 20000,
+            end: 20010,
+        };
+
+        // 20002 is bound by some other process
+        let port = suggest_port_with(&config, ServiceType::Mailpit, |p| p != 20002).unwrap();
+        assert_eq!(port, 20003);
+    }
+
+    #[test]
+    fn test_next_free_port_skips_implicit_mailpit_smtp() {
+        let config = ConfigBuilder::new()
+            .instances(vec![InstanceBuilder::new()
+                .service_type(ServiceType::Mailpit)
+                .port(8025)
+                .build()])
+            .build();
+
+        // The existing Mailpit holds 1025 without declaring it
+        let port = next_free_port_with(&config, 1025, |_| true).unwrap();
+        assert_eq!(port, 1026);
+    }
+
+    #[test]
+    fn test_check_port_names_the_holder() {
+        let config = ConfigBuilder::new()
+            .instances(vec![InstanceBuilder::new()
+                .name("Shop DB")
+                .port(3307)
+                .build()])
+            .build();
+
+        let err = check_port_with(&config, 3307, |_| true).unwrap_err();
+        assert!(err.contains("instance 'Shop DB'"), "{}", err);
+        let err = check_port_with(&config, config.proxy_port, |_| true).unwrap_err();
+        assert!(err.contains("Burd's proxy"), "{}", err);
+        let err = check_port_with(&config, 5432, |p| p != 5432).unwrap_err();
+        assert!(err.contains("another process"), "{}", err);
+        assert!(check_port_with(&config, 5432, |_| true).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_port() {
+        let mut config = ConfigBuilder::new().build();
+        config.port_range = PortRange {
+            start: 20000,
+            end: 20010,
+        };
+        // Docker holds Postgres' default port
+        let docker = |p: u16| p != 5432;
+
+        let resolve = |requested, auto_assign| {
+            resolve_port_with(
+                &config,
+                ServiceType::PostgreSQL,
+                requested,
+                auto_assign,
+                docker,
+            )
+        };
+        assert_eq!(resolve(None, false), Ok(20000));
+        assert_eq!(resolve(Some(5433), false), Ok(5433));
+        assert_eq!(resolve(Some(5432), true), Ok(20000));
+
+        let err = resolve(Some(5432), false).unwrap_err();
+        assert!(err.contains("another process"), "{}", err);
+        assert!(err.ends_with("Port 20000 is free."), "{}", err);
+    }
+
+    #[test]
+    fn test_resolve_port_change() {
+        let mut config = ConfigBuilder::new()
+            .instances(vec![
+                InstanceBuilder::new()
+                    .name("cache")
+                    .service_type(ServiceType::Redis)
+                    .port(6379)
+                    .build(),
+                InstanceBuilder::new().name("queue").port(20000).build(),
+            ])
+            .build();
+        config.port_range = PortRange {
+            start: 20000,
+            end: 20010,
+        };
+        let id = config.instances[0].id;
+        let change = |requested| resolve_port_change_with(&config, id, requested, |_| true);
+
+        // The current port is never picked, even though the default is free
+        assert_eq!(change(None), Ok(20001));
+        assert_eq!(change(Some(6380)), Ok(6380));
+
+        let err = change(Some(6379)).unwrap_err();
+        assert!(err.contains("already uses port 6379"), "{}", err);
+        let err = change(Some(20000)).unwrap_err();
+        assert!(err.contains("instance 'queue'"), "{}", err);
+        assert!(resolve_port_change_with(&config, Uuid::new_v4(), None, |_| true).is_err());
+    }
+
+    #[test]
+    fn test_exhausted_range() {
+        let mut config = ConfigBuilder::new().build();
+        config.port_range = PortRange {
+            start: 20000,
+            end: 20001,
+        };
+
+        assert!(suggest_port_with(&config, ServiceType::Frpc, |_| false).is_err());
+    }
+}
@@ -1,24 +1,68 @@
 //! Process Manager
 //!
 //! Manages the lifecycle of service instances (start, stop, restart).
-//! Handles PID tracking, process health checks, and inter-process communication.
-
+//! Handles PID tracking, process health checks, and inter-process communication,
+//! and holds the resource usage history sampled by `metrics`. The supervisor
+//! at the bottom restarts `restart_on_failure` instances that crash or fail
+//! their health check.
+
+use crate::activity::{self, ActivityKind};
+use crate::arch;
+use crate::autostart;
+use crate::commands::AppState;
 use crate::config::{
     get_app_dir, get_binary_path, get_instance_dir, get_pids_dir, get_versioned_binary_path,
-    Instance, ServiceType, SubdomainConfig,
+    template, Config, Instance, ServiceType, SubdomainConfig,
 };
+use crate::logs;
+use crate::metrics::{InstanceMetrics, MetricsHistory, MetricsSample, UsageRollup};
+use crate::pid_file::PidRecord;
+use crate::readiness;
+use crate::service_init;
 use crate::services::get_service;
+use crate::start_diagnosis::{self, StartDiagnosis};
 use crate::tunnel::{
     generate_frpc_config, get_frpc_binary_path, get_frpc_config_path, get_frpc_log_path,
     get_frpc_pid_path, get_tunnels_dir, FrpcAdminConfig,
 };
+use crate::vault;
+use crate::workers::{Worker, WorkerStatus};
 use serde::Serialize;
-use std::fs::{self, File};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// How long a new process is watched for an early exit
+const STARTUP_WINDOW: Duration = Duration::from_secs(3);
+
+/// Delay between supervisor checks of `restart_on_failure` instances
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Consecutive failed health checks before a running instance is restarted,
+/// so a single slow response doesn't bounce it
+const FAILURES_BEFORE_RESTART: u32 = 2;
+
+/// Wait after the first restart; doubles with every further attempt
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(10);
+
+/// Longest wait between restarts
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// Restarts attempted before giving up until the instance is healthy again
+const MAX_RESTART_ATTEMPTS: u32 = 6;
+
+/// How long queue workers get to finish their current job when stopped
+const WORKER_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether something accepts TCP connections on a local port
+fn is_port_open(port: u16) -> bool {
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok()
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct InstanceStatus {
     pub id: Uuid,
@@ -31,7 +75,9 @@ pub struct InstanceStatus {
     pub healthy: Option<bool>,
 }
 
-pub struct ProcessManager;
+pub struct ProcessManager {
+    metrics: MetricsHistory,
+}
 
 impl Default for ProcessManager {
     fn default() -> Self {
@@ -41,7 +87,9 @@ impl Default for ProcessManager {
 
 impl ProcessManager {
     pub fn new() -> Self {
-        Self
+        Self {
+            metrics: MetricsHistory::default(),
+        }
     }
 
     fn get_pid_file(&self, id: &Uuid) -> Result<PathBuf, String> {
@@ -63,35 +111,61 @@ impl ProcessManager {
     }
 
     pub fn read_logs(id: &Uuid) -> Result<String, String> {
-        let log_path = Self::get_log_path(id)?;
+        Self::read_log_tail(&Self::get_log_path(id)?)
+    }
+
+    fn read_log_tail(log_path: &Path) -> Result<String, String> {
         if !log_path.exists() {
             return Ok("No logs available yet.".to_string());
         }
-        let content =
-            fs::read_to_string(&log_path).map_err(|e| format!("Failed to read log file: {}", e))?;
-        // Return last 100 lines
-        let lines: Vec<&str> = content.lines().collect();
-        let start = if lines.len() > 100 {
-            lines.len() - 100
-        } else {
-            0
-        };
-        Ok(lines[start..].join("\n"))
+        // Return last 100 lines, without reading the whole file
+        let lines = logs::get_last_lines(&log_path.to_string_lossy(), 100)?;
+        Ok(lines.join("\n"))
+    }
+
+    /// Diagnose a failed start from the instance's log file
+    fn diagnose_log(
+        service_name: &str,
+        port: u16,
+        data_dir: &Path,
+        log_path: &Path,
+    ) -> StartDiagnosis {
+        let output = fs::read_to_string(log_path).unwrap_or_default();
+        start_diagnosis::diagnose(service_name, port, data_dir, &output, Some(log_path))
+    }
+
+    /// Explain why a stopped instance failed to start (or died), from its last log
+    ///
+    /// Returns `None` while the instance is running or before it has a log.
+    pub fn diagnose(&self, instance: &Instance) -> Result<Option<StartDiagnosis>, String> {
+        let log_path = Self::get_log_path(&instance.id)?;
+        if self.is_running(&instance.id) || !log_path.exists() {
+            return Ok(None);
+        }
+        let data_dir = get_instance_dir(&instance.id)?;
+        Ok(Some(Self::diagnose_log(
+            get_service(instance.service_type).display_name(),
+            instance.port,
+            &data_dir,
+            &log_path,
+        )))
     }
 
-    fn read_pid(&self, id: &Uuid) -> Option<u32> {
+    fn read_pid(&self, id: &Uuid) -> Option<PidRecord> {
         let pid_file = self.get_pid_file(id).ok()?;
         if !pid_file.exists() {
             return None;
         }
         fs::read_to_string(&pid_file)
             .ok()
-            .and_then(|s| s.trim().parse().ok())
+            .and_then(|s| PidRecord::parse(&s))
     }
 
+    /// Record a spawned process along with its start time and name, so a
+    /// recycled PID isn't mistaken for the instance later
     fn write_pid(&self, id: &Uuid, pid: u32) -> Result<(), String> {
         let pid_file = self.get_pid_file(id)?;
-        fs::write(&pid_file, pid.to_string())
+        fs::write(&pid_file, PidRecord::capture(pid).to_file_contents())
             .map_err(|e| format!("Failed to write PID file: {}", e))
     }
 
@@ -103,30 +177,58 @@ impl ProcessManager {
         Ok(())
     }
 
-    fn is_process_running(&self, pid: u32) -> bool {
-        // Use kill -0 to check if process exists
-        Command::new("kill")
-            .args(["-0", &pid.to_string()])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|s| s.success())
-            .unwrap_or(false)
+    pub fn is_running(&self, id: &Uuid) -> bool {
+        self.read_pid(id).is_some_and(|record| record.is_alive())
     }
 
-    pub fn is_running(&self, id: &Uuid) -> bool {
-        self.read_pid(id)
-            .map(|pid| self.is_process_running(pid))
-            .unwrap_or(false)
+    /// Remove PID files whose process is gone or whose PID now belongs to
+    /// another process. Returns the number of files removed.
+    ///
+    /// Run at boot, so instances that died with the machine (or with Burd)
+    /// aren't reported as running.
+    pub fn clean_stale_pid_files(&self) -> usize {
+        let Ok(entries) = get_pids_dir().and_then(|dir| {
+            fs::read_dir(dir).map_err(|e| format!("Failed to read pids directory: {}", e))
+        }) else {
+            return 0;
+        };
+
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pid"))
+            .filter(|path| {
+                !fs::read_to_string(path)
+                    .ok()
+                    .and_then(|s| PidRecord::parse(&s))
+                    .is_some_and(|record| record.is_alive())
+            })
+            .filter(|path| fs::remove_file(path).is_ok())
+            .count()
     }
 
-    /// Start an instance with optional TLD for domain resolution
-    /// If TLD is provided and domain_enabled is true, the full domain will be passed to the service
+    /// Start an instance with optional config for domain resolution
+    /// If config is provided and domain_enabled is true, the full domain will be passed to the service
     /// If ssl_enabled is true, HTTPS=on env var will be set for PHP services
     pub fn start(
         &self,
         instance: &Instance,
-        tld: Option<&str>,
+        config: Option<&Config>,
+        ssl_enabled: bool,
+    ) -> Result<u32, String> {
+        let pid = self.spawn(instance, config, ssl_enabled)?;
+        activity::record(
+            &instance.id,
+            ActivityKind::Started,
+            Some(format!("version {}", instance.version)),
+        );
+        Ok(pid)
+    }
+
+    fn spawn(
+        &self,
+        instance: &Instance,
+        config: Option<&Config>,
         ssl_enabled: bool,
     ) -> Result<u32, String> {
         // Check if already running
@@ -135,19 +237,17 @@ impl ProcessManager {
         }
 
         // Check if port is already in use
-        {
-            use std::net::TcpStream;
-            use std::time::Duration;
-            if let Ok(addr) = format!("127.0.0.1:{}", instance.port).parse() {
-                if TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok() {
-                    return Err(format!(
-                        "Port {} is already in use. Choose a different port or stop the process using it.",
-                        instance.port
-                    ));
-                }
-            }
+        if is_port_open(instance.port) {
+            return Err(format!(
+                "Port {} is already in use. Choose a different port or stop the process using it.",
+                instance.port
+            ));
         }
 
+        // Resolve {{...}} placeholders in the config; the stored instance keeps them
+        let resolved = template::resolve_instance(instance, config)?;
+        let instance = &resolved;
+
         // Handle frpc specially - it needs to generate tunnel config
         if instance.service_type == ServiceType::Frpc {
             return self.start_frpc(instance);
@@ -191,6 +291,10 @@ impl ProcessManager {
             ));
         }
 
+        // x86_64-only builds run through Rosetta on Apple Silicon
+        let binary_arch = arch::detect(&binary_path);
+        arch::require_rosetta(service.display_name(), binary_arch)?;
+
         let data_dir = get_instance_dir(&instance.id)?;
         fs::create_dir_all(&data_dir)
             .map_err(|e| format!("Failed to create data directory: {}", e))?;
@@ -204,70 +308,100 @@ impl ProcessManager {
             // and call it after initialization below
         }
 
-        // Run initialization if needed (first start)
-        if service.needs_init() {
-            let init_marker = data_dir.join(".initialized");
-            if !init_marker.exists() {
-                if let Some((init_cmd, init_args)) = service.init_command(&data_dir) {
-                    // Get init binary path
-                    let init_binary = if instance.service_type == ServiceType::MariaDB {
-                        use crate::services::mariadb::MariaDBService;
-                        MariaDBService::get_install_db_path()?
-                    } else if instance.service_type == ServiceType::PostgreSQL {
-                        use crate::services::postgresql::PostgreSQLService;
-                        PostgreSQLService::get_initdb_path()?
-                    } else if init_cmd.starts_with('/') {
-                        PathBuf::from(&init_cmd)
-                    } else {
-                        // Assume it's relative to the binary directory (versioned or flat)
-                        binary_path
-                            .parent()
-                            .map(|p| p.join(&init_cmd))
-                            .unwrap_or_else(|| PathBuf::from(&init_cmd))
-                    };
-
-                    let mut cmd = Command::new(&init_binary);
-                    cmd.args(&init_args).current_dir(&data_dir);
-
-                    // PostgreSQL needs TZ=GMT, PGSHAREDIR, and PKGLIBDIR
-                    if instance.service_type == ServiceType::PostgreSQL {
-                        use crate::services::postgresql::PostgreSQLService;
-                        cmd.env("TZ", "GMT");
-                        if let Ok(basedir) = PostgreSQLService::get_basedir() {
-                            let share_dir = basedir.join("share");
-                            let lib_dir = basedir.join("lib");
-                            cmd.env("PGSHAREDIR", share_dir.to_string_lossy().to_string());
-                            cmd.env("PKGLIBDIR", lib_dir.to_string_lossy().to_string());
-                        }
+        // Run initialization if needed (first start), undoing a half-finished one
+        if service.needs_init() && service_init::is_needed(instance, &data_dir) {
+            if let Some((init_cmd, init_args)) = service.init_command(&data_dir) {
+                // Get init binary path
+                let init_binary = if instance.service_type == ServiceType::MariaDB {
+                    use crate::services::mariadb::MariaDBService;
+                    MariaDBService::get_install_db_path()?
+                } else if instance.service_type == ServiceType::PostgreSQL {
+                    use crate::services::postgresql::PostgreSQLService;
+                    PostgreSQLService::get_initdb_path()?
+                } else if init_cmd.starts_with('/') {
+                    PathBuf::from(&init_cmd)
+                } else {
+                    // Assume it's relative to the binary directory (versioned or flat)
+                    binary_path
+                        .parent()
+                        .map(|p| p.join(&init_cmd))
+                        .unwrap_or_else(|| PathBuf::from(&init_cmd))
+                };
+
+                let mut cmd = Command::new(&init_binary);
+                cmd.args(&init_args).current_dir(&data_dir);
+
+                // PostgreSQL needs TZ=GMT, PGSHAREDIR, and PKGLIBDIR
+                if instance.service_type == ServiceType::PostgreSQL {
+                    use crate::services::postgresql::PostgreSQLService;
+                    cmd.env("TZ", "GMT");
+                    if let Ok(basedir) = PostgreSQLService::get_basedir() {
+                        let share_dir = basedir.join("share");
+                        let lib_dir = basedir.join("lib");
+                        cmd.env("PGSHAREDIR", share_dir.to_string_lossy().to_string());
+                        cmd.env("PKGLIBDIR", lib_dir.to_string_lossy().to_string());
                     }
+                }
 
-                    let output = cmd
-                        .output()
-                        .map_err(|e| format!("Failed to run init command: {}", e))?;
+                let log_path = Self::get_log_path(&instance.id)?;
+                service_init::run(
+                    instance,
+                    service.display_name(),
+                    &data_dir,
+                    service.init_probe(),
+                    cmd,
+                    Some(&log_path),
+                    || {
+                        // Generate PostgreSQL config after initdb creates the data directory
+                        if instance.service_type == ServiceType::PostgreSQL {
+                            use crate::services::postgresql::PostgreSQLService;
+                            PostgreSQLService::generate_config(instance, &data_dir)?;
+                        }
+                        Ok(())
+                    },
+                )?;
+            }
+        }
 
-                    if !output.status.success() {
-                        return Err(format!(
-                            "Init command failed: {}",
-                            String::from_utf8_lossy(&output.stderr)
-                        ));
-                    }
+        // MySQL refuses to initialize a non-empty data directory, so its
+        // config is generated after initialization (and refreshed every start)
+        if instance.service_type == ServiceType::MySQL {
+            use crate::services::mysql::MySQLService;
+            MySQLService::generate_config(instance, &data_dir)?;
+        }
 
-                    // Generate PostgreSQL config after initdb creates the data directory
-                    if instance.service_type == ServiceType::PostgreSQL {
-                        use crate::services::postgresql::PostgreSQLService;
-                        PostgreSQLService::generate_config(instance, &data_dir)?;
-                    }
+        // Sentinel instances run from a generated sentinel.conf
+        if matches!(
+            instance.service_type,
+            ServiceType::Redis | ServiceType::Valkey
+        ) {
+            use crate::services::key_value_service;
+            key_value_service::generate_sentinel_config(instance, &data_dir)?;
+        }
 
-                    // Mark as initialized
-                    fs::write(&init_marker, "").ok();
-                }
-            }
+        // Centrifugo reads its keys and allowed origins from a generated config
+        if instance.service_type == ServiceType::Centrifugo {
+            use crate::services::centrifugo::CentrifugoService;
+            CentrifugoService::generate_config(instance, &data_dir)?;
         }
 
-        // Create log file for output
+        // Soketi reads its app credentials from a generated config
+        if instance.service_type == ServiceType::Soketi {
+            use crate::services::soketi::SoketiService;
+            SoketiService::generate_config(instance, &data_dir)?;
+        }
+
+        // Create log file for output, in append mode so the log can be
+        // rotated while the instance runs
         let log_path = Self::get_log_path(&instance.id)?;
-        let log_file =
-            File::create(&log_path).map_err(|e| format!("Failed to create log file: {}", e))?;
+        let log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .map_err(|e| format!("Failed to create log file: {}", e))?;
+        log_file
+            .set_len(0)
+            .map_err(|e| format!("Failed to clear log file: {}", e))?;
         let log_file_err = log_file
             .try_clone()
             .map_err(|e| format!("Failed to clone log file handle: {}", e))?;
@@ -290,6 +424,20 @@ impl ProcessManager {
         )
         .ok();
         writeln!(debug_log, "Binary path: {:?}", binary_path).ok();
+        if let Some(binary_arch) = binary_arch {
+            let rosetta = if binary_arch.needs_rosetta() {
+                " (running under Rosetta; slower than a native build)"
+            } else {
+                ""
+            };
+            writeln!(
+                debug_log,
+                "Architecture: {}{}",
+                binary_arch.label(),
+                rosetta
+            )
+            .ok();
+        }
         writeln!(debug_log, "Data dir: {:?}", data_dir).ok();
         writeln!(debug_log, "Port: {}", instance.port).ok();
         let effective_working_dir = if instance.service_type == ServiceType::Bun {
@@ -298,11 +446,15 @@ impl ProcessManager {
             data_dir.to_string_lossy().to_string()
         };
         writeln!(debug_log, "Working dir: {}", effective_working_dir).ok();
+        if !instance.extra_args.is_empty() {
+            writeln!(debug_log, "Extra args: {}", instance.extra_args_line()).ok();
+        }
         writeln!(debug_log, "========================").ok();
         debug_log.flush().ok();
 
-        // Get service-specific start arguments
-        let args = service.start_args(instance, &data_dir);
+        // Get service-specific start arguments, then the instance's own
+        let mut args = service.start_args(instance, &data_dir);
+        args.extend(instance.extra_args.iter().cloned());
 
         let mut cmd = Command::new(&binary_path);
 
@@ -335,9 +487,9 @@ impl ProcessManager {
             .stdout(Stdio::from(log_file))
             .stderr(Stdio::from(log_file_err));
 
-        // Calculate full domain if TLD is provided and domain routing is enabled
+        // Calculate full domain if config is provided and domain routing is enabled
         let full_domain = if instance.domain_enabled {
-            tld.map(|t| instance.full_domain(t))
+            config.map(|c| instance.full_domain(&c.tld, &c.slugs))
         } else {
             None
         };
@@ -347,6 +499,18 @@ impl ProcessManager {
             cmd.env(key, value);
         }
 
+        // Secrets of the project the instance serves, from the vault
+        let project_dir = match instance.service_type {
+            ServiceType::FrankenPHP => instance.config.get("document_root"),
+            ServiceType::Bun => instance.config.get("working_directory"),
+            _ => None,
+        };
+        if let Some(dir) = project_dir.and_then(|v| v.as_str()) {
+            if let Ok(config) = crate::config::ConfigStore::new().and_then(|store| store.load()) {
+                vault::inject(&mut cmd, &config.vault, Path::new(dir));
+            }
+        }
+
         // Set HTTPS=on for PHP services when SSL is enabled
         // This allows Laravel/PHP to detect HTTPS without TrustProxies configuration
         if ssl_enabled
@@ -358,26 +522,41 @@ impl ProcessManager {
             cmd.env("HTTPS", "on");
         }
 
-        let child: Child = cmd
+        let mut child: Child = cmd
             .spawn()
             .map_err(|e| format!("Failed to start {}: {}", service.display_name(), e))?;
 
         let pid = child.id();
         self.write_pid(&instance.id, pid)?;
 
+        // Watch the startup window; a listening port means it came up.
+        // try_wait reaps an early exit, which `kill -0` can't tell from a zombie.
+        let deadline = std::time::Instant::now() + STARTUP_WINDOW;
+        loop {
+            std::thread::sleep(Duration::from_millis(100));
+            if !matches!(child.try_wait(), Ok(None)) {
+                self.remove_pid(&instance.id)?;
+                let diagnosis =
+                    Self::diagnose_log(service.display_name(), instance.port, &data_dir, &log_path);
+                return Err(diagnosis.to_string());
+            }
+            if is_port_open(instance.port) || std::time::Instant::now() >= deadline {
+                break;
+            }
+        }
+
         // Forget the child to prevent it from becoming a zombie when dropped
         // The process will run independently and we track it via PID file
         std::mem::forget(child);
 
-        // Wait briefly and verify it started
-        std::thread::sleep(Duration::from_millis(500));
-
-        if !self.is_process_running(pid) {
-            self.remove_pid(&instance.id)?;
-            return Err(format!(
-                "{} process exited immediately. Check port availability.",
-                service.display_name()
-            ));
+        // Buckets can only be created once the server answers
+        if instance.service_type == ServiceType::MinIO {
+            use crate::services::minio::MinIOService;
+            MinIOService::seed_buckets_in_background(instance.clone(), log_path);
+        } else if instance.service_type == ServiceType::Keycloak {
+            // Realm import needs the admin API, which comes up after the port opens
+            use crate::services::keycloak::KeycloakService;
+            KeycloakService::provision_in_background(instance.clone(), log_path);
         }
 
         Ok(pid)
@@ -396,6 +575,7 @@ impl ProcessManager {
         // Load app config to get tunnels, servers, and instances
         let config_store = crate::config::ConfigStore::new()
             .map_err(|e| format!("Failed to load config: {}", e))?;
+        let config_lock = config_store.lock_file()?;
         let mut app_config = config_store.load()?;
 
         // Need at least one server configured
@@ -428,6 +608,7 @@ impl ProcessManager {
         if needs_save {
             let _ = config_store.save(&app_config);
         }
+        drop(config_lock);
 
         // Build admin config from instance settings
         let admin_user = instance
@@ -514,7 +695,7 @@ impl ProcessManager {
         // Wait briefly and verify it started
         std::thread::sleep(Duration::from_millis(500));
 
-        if !self.is_process_running(pid) {
+        if !self.is_running(&instance.id) {
             self.remove_pid(&instance.id)?;
             return Err("frpc process exited immediately. Check the logs for details.".to_string());
         }
@@ -523,36 +704,44 @@ impl ProcessManager {
     }
 
     pub fn stop(&self, id: &Uuid) -> Result<(), String> {
-        let pid = self
+        let record = self
             .read_pid(id)
             .ok_or_else(|| "Instance is not running (no PID file)".to_string())?;
 
-        if !self.is_process_running(pid) {
+        // Never signal a process that merely inherited the PID
+        if !record.is_alive() {
             self.remove_pid(id)?;
+            activity::record(id, ActivityKind::Crashed, None);
             return Ok(());
         }
 
         // Try graceful shutdown (SIGTERM)
         let _ = Command::new("kill")
-            .args(["-TERM", &pid.to_string()])
+            .args(["-TERM", &record.pid.to_string()])
             .status();
 
         // Wait up to 5 seconds for graceful shutdown
         for _ in 0..50 {
             std::thread::sleep(Duration::from_millis(100));
-            if !self.is_process_running(pid) {
+            if !record.is_alive() {
                 self.remove_pid(id)?;
+                activity::record(id, ActivityKind::Stopped, None);
                 return Ok(());
             }
         }
 
         // Force kill (SIGKILL)
         let _ = Command::new("kill")
-            .args(["-KILL", &pid.to_string()])
+            .args(["-KILL", &record.pid.to_string()])
             .status();
 
         std::thread::sleep(Duration::from_millis(200));
         self.remove_pid(id)?;
+        activity::record(
+            id,
+            ActivityKind::Stopped,
+            Some("killed after 5s".to_string()),
+        );
 
         Ok(())
     }
@@ -560,12 +749,17 @@ impl ProcessManager {
     pub fn get_status(&self, instance: &Instance) -> InstanceStatus {
         let service = get_service(instance.service_type);
 
-        let pid = self.read_pid(&instance.id);
-        let running = pid.map(|p| self.is_process_running(p)).unwrap_or(false);
-
-        // Clean up stale PID file
-        if !running && pid.is_some() {
-            let _ = self.remove_pid(&instance.id);
+        let record = self.read_pid(&instance.id);
+        let running = record.as_ref().is_some_and(|r| r.is_alive());
+
+        // Clean up stale PID file; the process died without being stopped
+        if !running && record.is_some() && self.remove_pid(&instance.id).is_ok() {
+            let detail = self
+                .diagnose(instance)
+                .ok()
+                .flatten()
+                .and_then(|diagnosis| diagnosis.detail);
+            activity::record(&instance.id, ActivityKind::Crashed, detail);
         }
 
         InstanceStatus {
@@ -575,8 +769,606 @@ impl ProcessManager {
             service_type: service.display_name().to_string(),
             version: instance.version.clone(),
             running,
-            pid: if running { pid } else { None },
+            pid: record.filter(|_| running).map(|r| r.pid),
             healthy: None, // Will be filled by health check
         }
     }
+
+    /// Check whether an instance exited without being stopped
+    ///
+    /// `stop` removes the PID file, so a PID file pointing at a dead process
+    /// means the process died on its own.
+    pub fn has_crashed(&self, id: &Uuid) -> bool {
+        self.read_pid(id).is_some_and(|record| !record.is_alive())
+    }
+
+    pub fn get_worker_log_path(id: &Uuid) -> Result<PathBuf, String> {
+        Ok(Self::get_log_dir()?.join(format!("worker-{}.log", id)))
+    }
+
+    pub fn read_worker_logs(id: &Uuid) -> Result<String, String> {
+        Self::read_log_tail(&Self::get_worker_log_path(id)?)
+    }
+
+    fn get_worker_pid_file(&self, id: &Uuid, index: u8) -> Result<PathBuf, String> {
+        let pids_dir = get_pids_dir()?;
+        fs::create_dir_all(&pids_dir)
+            .map_err(|e| format!("Failed to create pids directory: {}", e))?;
+        Ok(pids_dir.join(format!("worker-{}-{}.pid", id, index)))
+    }
+
+    /// A worker's PID files and the processes they record
+    fn read_worker_pids(&self, id: &Uuid) -> Vec<(PathBuf, Option<PidRecord>)> {
+        let prefix = format!("worker-{}-", id);
+        let Ok(entries) = get_pids_dir().and_then(|dir| {
+            fs::read_dir(dir).map_err(|e| format!("Failed to read pids directory: {}", e))
+        }) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "pid")
+                    && path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(&prefix))
+            })
+            .map(|path| {
+                let record = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|s| PidRecord::parse(&s));
+                (path, record)
+            })
+            .collect()
+    }
+
+    /// PIDs of a worker's live processes, dropping PID files of dead ones
+    fn worker_pids(&self, id: &Uuid) -> Vec<u32> {
+        let mut pids: Vec<u32> = self
+            .read_worker_pids(id)
+            .into_iter()
+            .filter_map(|(path, record)| match record {
+                Some(record) if record.is_alive() => Some(record.pid),
+                _ => {
+                    let _ = fs::remove_file(&path);
+                    None
+                }
+            })
+            .collect();
+        pids.sort_unstable();
+        pids
+    }
+
+    /// Start a queue worker's processes with Burd's default PHP
+    ///
+    /// Every process appends its output to the worker's log. Returns the PIDs.
+    pub fn start_worker(&self, worker: &Worker) -> Result<Vec<u32>, String> {
+        if !self.worker_pids(&worker.id).is_empty() {
+            return Err(format!(
+                "The worker for '{}' is already running",
+                worker.name()
+            ));
+        }
+
+        let project = Path::new(&worker.project_path);
+        if !project.join("artisan").is_file() {
+            return Err(format!(
+                "{} is not a Laravel project (no artisan file found)",
+                worker.project_path
+            ));
+        }
+        let php = crate::tinker::get_php_binary()?;
+        let args = worker.artisan_args();
+
+        let log_path = Self::get_worker_log_path(&worker.id)?;
+        let log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .map_err(|e| format!("Failed to open worker log: {}", e))?;
+        {
+            use std::io::Write;
+            let mut header = &log_file;
+            writeln!(header, "=== Burd Worker ===").ok();
+            writeln!(header, "Project: {}", worker.project_path).ok();
+            writeln!(header, "Mode: {}", worker.mode.as_str()).ok();
+            writeln!(header, "Command: {:?} {}", php, args.join(" ")).ok();
+            writeln!(header, "Processes: {}", worker.process_count()).ok();
+            writeln!(header, "===================").ok();
+        }
+
+        // Read the project's secrets once for all processes
+        let secrets = crate::config::ConfigStore::new()
+            .and_then(|store| store.load())
+            .map(|config| vault::secrets_for(&config.vault, project))
+            .unwrap_or_default();
+
+        let mut children = Vec::new();
+        for index in 0..worker.process_count() {
+            let spawned = log_file.try_clone().and_then(|stdout| {
+                let stderr = stdout.try_clone()?;
+                Command::new(&php)
+                    .args(&args)
+                    .envs(secrets.iter().cloned())
+                    .current_dir(project)
+                    .stdout(Stdio::from(stdout))
+                    .stderr(Stdio::from(stderr))
+                    .spawn()
+            });
+            let child = match spawned {
+                Ok(child) => child,
+                Err(e) => {
+                    let _ = self.stop_worker(&worker.id);
+                    return Err(format!("Failed to start worker: {}", e));
+                }
+            };
+            fs::write(
+                self.get_worker_pid_file(&worker.id, index)?,
+                PidRecord::capture(child.id()).to_file_contents(),
+            )
+            .map_err(|e| format!("Failed to write PID file: {}", e))?;
+            children.push(child);
+        }
+
+        // A missing database or queue extension makes artisan exit right away
+        std::thread::sleep(Duration::from_secs(1));
+        if children
+            .iter_mut()
+            .any(|child| !matches!(child.try_wait(), Ok(None)))
+        {
+            let _ = self.stop_worker(&worker.id);
+            let output = logs::get_last_lines(&log_path.to_string_lossy(), 10)
+                .map(|lines| lines.join("\n"))
+                .unwrap_or_default();
+            return Err(format!(
+                "The worker exited right after starting:\n{}",
+                output
+            ));
+        }
+
+        // Tracked through the PID files from here on, like instances
+        let pids = children.iter().map(Child::id).collect();
+        children.into_iter().for_each(std::mem::forget);
+        Ok(pids)
+    }
+
+    /// Stop a worker's processes and return how many were running
+    ///
+    /// SIGTERM lets `queue:work` and Horizon finish the job at hand; whatever
+    /// is still running after `WORKER_STOP_TIMEOUT` is killed.
+    pub fn stop_worker(&self, id: &Uuid) -> Result<usize, String> {
+        let entries = self.read_worker_pids(id);
+        let running: Vec<&PidRecord> = entries
+            .iter()
+            .filter_map(|(_, record)| record.as_ref())
+            .filter(|record| record.is_alive())
+            .collect();
+
+        for record in &running {
+            let _ = Command::new("kill")
+                .args(["-TERM", &record.pid.to_string()])
+                .status();
+        }
+
+        let deadline = Instant::now() + WORKER_STOP_TIMEOUT;
+        while running.iter().any(|record| record.is_alive()) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        for record in running.iter().filter(|record| record.is_alive()) {
+            let _ = Command::new("kill")
+                .args(["-KILL", &record.pid.to_string()])
+                .status();
+        }
+
+        for (path, _) in &entries {
+            fs::remove_file(path).map_err(|e| format!("Failed to remove PID file: {}", e))?;
+        }
+        Ok(running.len())
+    }
+
+    pub fn get_worker_status(&self, worker: &Worker) -> WorkerStatus {
+        let pids = self.worker_pids(&worker.id);
+        WorkerStatus {
+            worker: worker.clone(),
+            name: worker.name(),
+            running: !pids.is_empty(),
+            pids,
+        }
+    }
+
+    /// Store a round of resource usage samples, one per running instance
+    ///
+    /// Instances missing from the round have stopped and lose their history.
+    pub fn record_metrics(&mut self, samples: Vec<(Uuid, MetricsSample)>) {
+        let running: HashSet<Uuid> = samples.iter().map(|(id, _)| *id).collect();
+        self.metrics.retain(&running);
+        for (id, sample) in samples {
+            self.metrics.record(id, sample);
+        }
+    }
+
+    /// The most recent resource usage sample of an instance
+    pub fn latest_metrics(&self, id: &Uuid) -> Option<MetricsSample> {
+        self.metrics.latest(id)
+    }
+
+    /// Combined current resource usage of the running instances among `instances`
+    pub fn usage_of<'a>(&self, instances: impl IntoIterator<Item = &'a Instance>) -> UsageRollup {
+        UsageRollup::of(
+            instances
+                .into_iter()
+                .filter(|i| self.get_status(i).running)
+                .filter_map(|i| self.latest_metrics(&i.id)),
+        )
+    }
+
+    /// Current resource usage and recent history of an instance
+    pub fn get_metrics(&self, instance: &Instance) -> InstanceMetrics {
+        let running = self.get_status(instance).running;
+        let history = if running {
+            self.metrics.history(&instance.id)
+        } else {
+            Vec::new()
+        };
+        InstanceMetrics {
+            instance_id: instance.id,
+            running,
+            current: history.last().copied(),
+            history,
+        }
+    }
+}
+
+/// How a supervised instance failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    /// The process exited without being stopped
+    Crashed,
+    /// The process is alive but fails its health check
+    Unhealthy,
+}
+
+/// What the supervisor did about a failing instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SupervisorAction {
+    Restarted {
+        attempt: u32,
+    },
+    RestartFailed {
+        attempt: u32,
+        error: String,
+    },
+    /// Out of attempts; nothing more happens until the instance recovers
+    GaveUp,
+}
+
+/// A restart (or the decision to stop restarting) by the supervisor
+#[derive(Debug, Clone, Serialize)]
+pub struct SupervisorEvent {
+    pub instance_id: Uuid,
+    pub instance_name: String,
+    pub failure: Failure,
+    #[serde(flatten)]
+    pub action: SupervisorAction,
+}
+
+impl SupervisorEvent {
+    /// One-line summary for notifications and logs
+    pub fn message(&self) -> String {
+        let failure = match self.failure {
+            Failure::Crashed => "crashed",
+            Failure::Unhealthy => "failed its health check",
+        };
+        match &self.action {
+            SupervisorAction::Restarted { attempt } => format!(
+                "{} {} and was restarted (attempt {}/{})",
+                self.instance_name, failure, attempt, MAX_RESTART_ATTEMPTS
+            ),
+            SupervisorAction::RestartFailed { attempt, error } => format!(
+                "{} {} and could not be restarted (attempt {}/{}): {}",
+                self.instance_name, failure, attempt, MAX_RESTART_ATTEMPTS, error
+            ),
+            SupervisorAction::GaveUp => format!(
+                "{} {} again; gave up after {} restarts",
+                self.instance_name, failure, MAX_RESTART_ATTEMPTS
+            ),
+        }
+    }
+}
+
+/// What to do about a failed check
+#[derive(Debug, PartialEq, Eq)]
+enum RestartDecision {
+    Wait,
+    Restart { attempt: u32 },
+    GiveUp,
+}
+
+/// Restart bookkeeping for one supervised instance
+#[derive(Debug, Default)]
+struct RestartBackoff {
+    /// Consecutive failed checks since the last restart
+    failures: u32,
+    /// Restarts since the instance was last healthy
+    attempts: u32,
+    /// No restart before this moment
+    not_before: Option<Instant>,
+}
+
+impl RestartBackoff {
+    /// Wait after restart number `attempts + 1`
+    fn delay(attempts: u32) -> Duration {
+        RESTART_BACKOFF_BASE
+            .saturating_mul(1 << attempts.min(16))
+            .min(RESTART_BACKOFF_MAX)
+    }
+
+    /// The instance passed its health check
+    ///
+    /// A restart only counts as a recovery once the instance stays healthy
+    /// through its backoff window; a service that crashes again right after
+    /// coming up keeps backing off.
+    fn healthy(&mut self, now: Instant) {
+        self.failures = 0;
+        if !matches!(self.not_before, Some(t) if now < t) {
+            self.attempts = 0;
+            self.not_before = None;
+        }
+    }
+
+    /// The instance failed a check; decides whether to restart it now
+    fn failed(&mut self, failure: Failure, now: Instant) -> RestartDecision {
+        self.failures += 1;
+
+        let threshold = match failure {
+            Failure::Crashed => 1,
+            Failure::Unhealthy => FAILURES_BEFORE_RESTART,
+        };
+        if self.failures < threshold || matches!(self.not_before, Some(t) if now < t) {
+            return RestartDecision::Wait;
+        }
+        if self.attempts >= MAX_RESTART_ATTEMPTS {
+            // Report giving up once, then stay quiet
+            if self.attempts == MAX_RESTART_ATTEMPTS {
+                self.attempts += 1;
+                return RestartDecision::GiveUp;
+            }
+            return RestartDecision::Wait;
+        }
+
+        self.failures = 0;
+        self.not_before = Some(now + Self::delay(self.attempts));
+        self.attempts += 1;
+        RestartDecision::Restart {
+            attempt: self.attempts,
+        }
+    }
+}
+
+/// Whether an instance that isn't running last exited on its own
+///
+/// `get_status` records a crash when it clears a dead process's PID file, so
+/// a crash noticed by the UI first is still seen here.
+fn crashed(id: &Uuid) -> bool {
+    activity::load(id, 1)
+        .ok()
+        .and_then(|events| events.into_iter().next())
+        .is_some_and(|event| event.kind == ActivityKind::Crashed)
+}
+
+/// Check a supervised instance; `None` when it's healthy or stopped on purpose
+async fn check(state: &AppState, instance: &Instance) -> Option<Failure> {
+    let running = state
+        .process_manager
+        .lock()
+        .map(|pm| pm.get_status(instance).running)
+        .ok()?;
+
+    if !running {
+        return crashed(&instance.id).then_some(Failure::Crashed);
+    }
+    if readiness::is_healthy(instance.port, instance.service_type).await {
+        None
+    } else {
+        Some(Failure::Unhealthy)
+    }
+}
+
+/// Stop a wedged instance (a crashed one is already gone) and start it again
+fn restart(state: &AppState, id: Uuid, failure: Failure) -> Result<u32, String> {
+    let config = state
+        .config_store
+        .lock()
+        .map_err(|_| "Failed to acquire config lock".to_string())?
+        .load()?;
+
+    if failure == Failure::Unhealthy {
+        state
+            .process_manager
+            .lock()
+            .map_err(|_| "Failed to acquire process manager lock")?
+            .stop(&id)?;
+    }
+    autostart::start_instance(state, &config, id)
+}
+
+/// Append a note from Burd to an instance's log
+fn note_in_log(id: &Uuid, message: &str) {
+    use std::io::Write;
+    let Ok(path) = ProcessManager::get_log_path(id) else {
+        return;
+    };
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "=== Burd: {} ===", message);
+    }
+}
+
+/// Supervise `restart_on_failure` instances forever
+///
+/// Crashed instances are restarted at once and unhealthy ones after
+/// `FAILURES_BEFORE_RESTART` failed checks, backing off exponentially between
+/// attempts. Every restart is logged and passed to `on_event`.
+pub async fn supervise(state: AppState, on_event: impl Fn(&SupervisorEvent) + Send + 'static) {
+    let mut backoffs: HashMap<Uuid, RestartBackoff> = HashMap::new();
+
+    loop {
+        tokio::time::sleep(SUPERVISE_INTERVAL).await;
+
+        let config = match state.config_store.lock().map(|store| store.load()) {
+            Ok(Ok(config)) => config,
+            _ => continue,
+        };
+        let supervised: Vec<Instance> = config
+            .instances
+            .into_iter()
+            .filter(|i| i.restart_on_failure)
+            .collect();
+        backoffs.retain(|id, _| supervised.iter().any(|i| i.id == *id));
+
+        for instance in supervised {
+            let backoff = backoffs.entry(instance.id).or_default();
+            let now = Instant::now();
+
+            let Some(failure) = check(&state, &instance).await else {
+                backoff.healthy(now);
+                continue;
+            };
+
+            let action = match backoff.failed(failure, now) {
+                RestartDecision::Wait => continue,
+                RestartDecision::GiveUp => SupervisorAction::GaveUp,
+                RestartDecision::Restart { attempt } => {
+                    let restart_state = state.clone();
+                    let id = instance.id;
+                    match tokio::task::spawn_blocking(move || restart(&restart_state, id, failure))
+                        .await
+                        .map_err(|e| format!("Task error: {}", e))
+                        .and_then(|result| result)
+                    {
+                        Ok(_) => SupervisorAction::Restarted { attempt },
+                        Err(error) => SupervisorAction::RestartFailed { attempt, error },
+                    }
+                }
+            };
+
+            let event = SupervisorEvent {
+                instance_id: instance.id,
+                instance_name: instance.name.clone(),
+                failure,
+                action,
+            };
+            let message = event.message();
+            eprintln!("{}", message);
+            note_in_log(&instance.id, &message);
+            on_event(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(RestartBackoff::delay(0), RESTART_BACKOFF_BASE);
+        assert_eq!(RestartBackoff::delay(1), RESTART_BACKOFF_BASE * 2);
+        assert_eq!(RestartBackoff::delay(2), RESTART_BACKOFF_BASE * 4);
+        assert_eq!(RestartBackoff::delay(10), RESTART_BACKOFF_MAX);
+        assert_eq!(RestartBackoff::delay(u32::MAX), RESTART_BACKOFF_MAX);
+    }
+
+    #[test]
+    fn test_crash_restarts_with_backoff() {
+        let mut backoff = RestartBackoff::default();
+        let start = Instant::now();
+
+        assert_eq!(
+            backoff.failed(Failure::Crashed, start),
+            RestartDecision::Restart { attempt: 1 }
+        );
+
+        // Crashed again before the first backoff window passed
+        let soon = start + RESTART_BACKOFF_BASE / 2;
+        assert_eq!(
+            backoff.failed(Failure::Crashed, soon),
+            RestartDecision::Wait
+        );
+
+        let later = start + RESTART_BACKOFF_BASE;
+        assert_eq!(
+            backoff.failed(Failure::Crashed, later),
+            RestartDecision::Restart { attempt: 2 }
+        );
+
+        // The second window is twice as long
+        let next = later + RESTART_BACKOFF_BASE;
+        assert_eq!(
+            backoff.failed(Failure::Crashed, next),
+            RestartDecision::Wait
+        );
+        assert_eq!(
+            backoff.failed(Failure::Crashed, later + RESTART_BACKOFF_BASE * 2),
+            RestartDecision::Restart { attempt: 3 }
+        );
+    }
+
+    #[test]
+    fn test_unhealthy_needs_consecutive_failures() {
+        let mut backoff = RestartBackoff::default();
+        let now = Instant::now();
+
+        assert_eq!(
+            backoff.failed(Failure::Unhealthy, now),
+            RestartDecision::Wait
+        );
+        backoff.healthy(now);
+        assert_eq!(
+            backoff.failed(Failure::Unhealthy, now),
+            RestartDecision::Wait
+        );
+        assert_eq!(
+            backoff.failed(Failure::Unhealthy, now),
+            RestartDecision::Restart { attempt: 1 }
+        );
+    }
+
+    #[test]
+    fn test_gives_up_until_healthy() {
+        let mut backoff = RestartBackoff::default();
+        let mut now = Instant::now();
+
+        for attempt in 1..=MAX_RESTART_ATTEMPTS {
+            assert_eq!(
+                backoff.failed(Failure::Crashed, now),
+                RestartDecision::Restart { attempt }
+            );
+            now += RESTART_BACKOFF_MAX;
+        }
+        assert_eq!(
+            backoff.failed(Failure::Crashed, now),
+            RestartDecision::GiveUp
+        );
+        assert_eq!(backoff.failed(Failure::Crashed, now), RestartDecision::Wait);
+
+        // Healthy inside the backoff window doesn't count as recovered
+        let mut backoff = RestartBackoff::default();
+        let start = Instant::now();
+        backoff.failed(Failure::Crashed, start);
+        backoff.healthy(start + RESTART_BACKOFF_BASE / 2);
+        assert_eq!(backoff.attempts, 1);
+
+        // Staying healthy through it does
+        backoff.healthy(start + RESTART_BACKOFF_BASE);
+        assert_eq!(backoff.attempts, 0);
+        assert_eq!(
+            backoff.failed(Failure::Crashed, start + RESTART_BACKOFF_BASE),
+            RestartDecision::Restart { attempt: 1 }
+        );
+    }
 }
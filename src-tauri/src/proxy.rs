@@ -1,14 +1,19 @@
 //! HTTP Reverse Proxy for routing custom TLD domains to service ports
 //!
 //! This module provides an HTTP reverse proxy that routes requests based on
-//! the Host header to the appropriate backend service port.
+//! the Host header to the appropriate backend service port. It listens on
+//! both 127.0.0.1 and ::1, matching the A and AAAA answers from the DNS server.
 //!
 //! When the privileged proxy daemon (Caddy) is installed, this module syncs
 //! routes to a Caddyfile that Caddy watches for changes.
 
 use crate::caddy;
+use crate::config::{Config, Domain, DomainTarget};
 use crate::domain::DEFAULT_PROXY_PORT;
-use crate::launchd;
+use crate::emulation;
+use crate::platform;
+use crate::start_page;
+use crate::wake::WakeOutcome;
 use axum::{
     body::Body,
     extract::State,
@@ -21,12 +26,15 @@ use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::client::legacy::Client;
 use hyper_util::rt::TokioExecutor;
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::{Arc, RwLock};
-use tokio::sync::oneshot;
+use tokio::sync::watch;
 
 type HttpClient = Client<HttpConnector, Body>;
 
+/// Called with a route's instance ID when its backend can't be reached
+pub type Waker = Arc<dyn Fn(&str) -> WakeOutcome + Send + Sync>;
+
 /// Type of route for the proxy
 #[derive(Debug, Clone)]
 pub enum ProxyRouteType {
@@ -63,16 +71,22 @@ struct ProxyState {
     routes: Arc<RwLock<HashMap<String, RouteEntry>>>,
     /// HTTP client for proxying requests
     client: HttpClient,
-    /// The TLD to look for (e.g., "burd")
-    tld: String,
+    /// The TLD to look for (e.g., "burd"), shared so a TLD change applies
+    /// without restarting the server
+    tld: Arc<RwLock<String>>,
+    /// Starts wake-on-access instances, when set
+    waker: Arc<RwLock<Option<Waker>>>,
 }
 
 /// Reverse proxy server
 pub struct ProxyServer {
     port: u16,
     tld: String,
+    /// The running handler's copy of `tld`
+    served_tld: Arc<RwLock<String>>,
     routes: Arc<RwLock<HashMap<String, RouteEntry>>>,
-    shutdown_tx: Option<oneshot::Sender<()>>,
+    waker: Arc<RwLock<Option<Waker>>>,
+    shutdown_tx: Option<watch::Sender<bool>>,
     running: bool,
 }
 
@@ -80,8 +94,10 @@ impl ProxyServer {
     pub fn new(port: u16, tld: String) -> Self {
         Self {
             port,
+            served_tld: Arc::new(RwLock::new(tld.clone())),
             tld,
             routes: Arc::new(RwLock::new(HashMap::new())),
+            waker: Arc::new(RwLock::new(None)),
             shutdown_tx: None,
             running: false,
         }
@@ -92,54 +108,113 @@ impl ProxyServer {
         &self.tld
     }
 
+    /// Move the routes under the primary TLD to a new one
+    ///
+    /// Routes are keyed by subdomain under the primary TLD and by full domain
+    /// under the extra ones, so routes are re-keyed for the new primary TLD.
+    pub fn set_tld(&mut self, tld: &str) -> Result<(), String> {
+        {
+            let mut routes = self
+                .routes
+                .write()
+                .map_err(|_| "Failed to acquire routes lock")?;
+            let old_suffix = format!(".{}", self.tld);
+            let new_suffix = format!(".{}", tld);
+            *routes = routes
+                .drain()
+                .map(|(key, mut route)| {
+                    if route.domain.ends_with(&old_suffix) {
+                        route.domain = format!("{}.{}", key, tld);
+                        (key, route)
+                    } else {
+                        let key = route
+                            .domain
+                            .strip_suffix(&new_suffix)
+                            .unwrap_or(&route.domain)
+                            .to_string();
+                        (key, route)
+                    }
+                })
+                .collect();
+        }
+        *self
+            .served_tld
+            .write()
+            .map_err(|_| "Failed to acquire TLD lock")? = tld.to_string();
+        self.tld = tld.to_string();
+
+        self.sync_to_daemon()
+    }
+
+    /// Set what to call when a route's backend can't be reached
+    pub fn set_waker(&self, waker: Waker) {
+        if let Ok(mut slot) = self.waker.write() {
+            *slot = Some(waker);
+        }
+    }
+
     /// Start the proxy server
     pub async fn start(&mut self) -> Result<(), String> {
         if self.running {
             return Ok(());
         }
 
-        let addr = SocketAddr::from(([127, 0, 0, 1], self.port));
-
         // Create HTTP client for proxying
         let client: HttpClient = Client::builder(TokioExecutor::new()).build_http();
 
         let state = ProxyState {
             routes: Arc::clone(&self.routes),
             client,
-            tld: self.tld.clone(),
+            tld: Arc::clone(&self.served_tld),
+            waker: Arc::clone(&self.waker),
         };
 
         let app = Router::new()
             .route("/", any(proxy_handler))
-            .route("/*path", any(proxy_handler))
+            .route("/{*path}", any(proxy_handler))
             .with_state(state);
 
-        let listener = tokio::net::TcpListener::bind(addr)
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, self.port));
+        let mut listeners = vec![tokio::net::TcpListener::bind(addr)
             .await
-            .map_err(|e| format!("Failed to bind proxy to {}: {}", addr, e))?;
+            .map_err(|e| format!("Failed to bind proxy to {}: {}", addr, e))?];
 
-        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        // Domains also resolve to ::1; IPv6 may be disabled, so it's optional
+        if let Ok(listener) =
+            tokio::net::TcpListener::bind(SocketAddr::from((Ipv6Addr::LOCALHOST, self.port))).await
+        {
+            listeners.push(listener);
+        }
 
-        // Spawn the server
-        tokio::spawn(async move {
-            axum::serve(listener, app)
-                .with_graceful_shutdown(async {
-                    let _ = shutdown_rx.await;
-                })
-                .await
-                .ok();
-        });
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+        // Spawn a server per listener
+        for listener in listeners {
+            let app = app.clone();
+            let mut shutdown_rx = shutdown_rx.clone();
+            tokio::spawn(async move {
+                axum::serve(listener, app)
+                    .with_graceful_shutdown(async move {
+                        let _ = shutdown_rx.changed().await;
+                    })
+                    .await
+                    .ok();
+            });
+        }
 
         self.shutdown_tx = Some(shutdown_tx);
         self.running = true;
 
+        // Generate the start page so it's available on the fallback proxy too
+        let _ = start_page::write_start_page(&self.tld, &self.list_routes());
+
         Ok(())
     }
 
     /// Stop the proxy server
     pub fn stop(&mut self) {
         if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(());
+            let _ = tx.send(true);
         }
         self.running = false;
     }
@@ -232,6 +307,50 @@ impl ProxyServer {
         Ok(())
     }
 
+    /// Register routes for every configured domain and emulated SaaS host
+    ///
+    /// Errors are ignored per domain so one bad entry doesn't block the rest.
+    pub fn register_config_routes(&self, config: &Config) {
+        for domain in &config.domains {
+            let _ = self.register_domain(domain, config);
+        }
+        for route in emulation::proxy_routes(config) {
+            let _ = self.register_route(&route.host, route.port, &route.route_id, true);
+        }
+    }
+
+    /// Register the route for one configured domain
+    ///
+    /// Domains targeting an instance that no longer exists are left out.
+    pub fn register_domain(&self, domain: &Domain, config: &Config) -> Result<(), String> {
+        let full_domain = domain.full_domain(&config.tld);
+        let domain_id = domain.id.to_string();
+        match &domain.target {
+            DomainTarget::Instance(instance_id) => {
+                // Find the instance to get its port
+                match config.instances.iter().find(|i| &i.id == instance_id) {
+                    Some(instance) => self.register_route(
+                        &full_domain,
+                        instance.port,
+                        &domain_id,
+                        domain.ssl_enabled,
+                    ),
+                    None => Ok(()),
+                }
+            }
+            DomainTarget::Port(port) => {
+                self.register_route(&full_domain, *port, &domain_id, domain.ssl_enabled)
+            }
+            DomainTarget::StaticFiles { path, browse } => self.register_static_route(
+                &full_domain,
+                path,
+                *browse,
+                &domain_id,
+                domain.ssl_enabled,
+            ),
+        }
+    }
+
     /// Unregister a route
     pub fn unregister_route(&self, domain: &str) -> Result<(), String> {
         let subdomain = domain
@@ -265,18 +384,25 @@ impl ProxyServer {
     /// Sync routes to the Caddyfile for the privileged proxy daemon
     ///
     /// This should be called whenever routes change so Caddy
-    /// (running on ports 80/443) can pick up the changes.
+    /// (running on ports 80/443) can pick up the changes. The start page
+    /// is regenerated here as well so it always reflects the current routes.
     pub fn sync_to_daemon(&self) -> Result<(), String> {
+        let current_routes = self.list_routes();
+        let daemon_installed = platform::proxy_daemon().is_installed();
+
+        // Regenerate the start page when something can serve it
+        // (ignore errors - config might be unavailable)
+        if self.running || daemon_installed {
+            let _ = start_page::write_start_page(&self.tld, &current_routes);
+        }
+
         // Only sync if daemon is installed
-        if !launchd::is_installed() {
+        if !daemon_installed {
             return Ok(());
         }
 
-        let routes: Vec<caddy::RouteEntry> = self
-            .routes
-            .read()
-            .map_err(|_| "Failed to read routes")?
-            .values()
+        let mut routes: Vec<caddy::RouteEntry> = current_routes
+            .iter()
             .map(|r| match &r.route_type {
                 ProxyRouteType::ReverseProxy { port } => caddy::RouteEntry::reverse_proxy(
                     r.domain.clone(),
@@ -294,6 +420,20 @@ impl ProxyServer {
             })
             .collect();
 
+        // Serve the start page unless a route already claims its subdomain
+        let start_page_domain = start_page::start_page_domain(&self.tld);
+        if !routes.iter().any(|r| r.domain == start_page_domain) {
+            routes.push(caddy::RouteEntry::file_server(
+                start_page_domain,
+                start_page::get_start_page_dir()
+                    .to_string_lossy()
+                    .to_string(),
+                false,
+                start_page::START_PAGE_ROUTE_ID.to_string(),
+                false,
+            ));
+        }
+
         // Write Caddyfile - Caddy will auto-reload when file changes
         caddy::write_caddyfile(&self.tld, &routes)?;
 
@@ -327,10 +467,29 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request<Body>) -> R
     let host_without_port = host.split(':').next().unwrap_or(&host);
 
     // Extract subdomain (part before .tld)
+    let tld_suffix = format!(
+        ".{}",
+        state.tld.read().map(|tld| tld.clone()).unwrap_or_default()
+    );
     let subdomain = host_without_port
-        .strip_suffix(&format!(".{}", state.tld))
+        .strip_suffix(&tld_suffix)
         .unwrap_or(host_without_port);
 
+    // Serve the generated start page
+    if start_page::is_reserved_subdomain(subdomain) {
+        return match tokio::fs::read_to_string(start_page::get_start_page_path()).await {
+            Ok(html) => Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "text/html; charset=utf-8")
+                .body(Body::from(html))
+                .unwrap(),
+            Err(_) => error_response(
+                StatusCode::NOT_FOUND,
+                "Start page has not been generated yet",
+            ),
+        };
+    }
+
     // Look up the route
     let route = {
         let routes = match state.routes.read() {
@@ -344,6 +503,14 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request<Body>) -> R
 
     let route = match route {
         Some(r) => r,
+        // Same answer as the Caddy catch-all, so the watchdog can tell Burd is serving
+        None if req.uri().path() == "/_burd/health" => {
+            return Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "text/plain")
+                .body(Body::from("burd-ok"))
+                .unwrap()
+        }
         None => {
             return error_response(
                 StatusCode::NOT_FOUND,
@@ -352,6 +519,8 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request<Body>) -> R
         }
     };
 
+    crate::idle::touch(&route.instance_id);
+
     // Handle based on route type
     let port = match &route.route_type {
         ProxyRouteType::ReverseProxy { port } => *port,
@@ -405,11 +574,40 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request<Body>) -> R
             let (parts, body) = resp.into_parts();
             Response::from_parts(parts, Body::new(body))
         }
-        Err(e) => error_response(
-            StatusCode::BAD_GATEWAY,
-            &format!("Failed to connect to backend service: {}", e),
-        ),
+        Err(e) => {
+            let waker = state.waker.read().ok().and_then(|w| w.clone());
+            let page = match waker {
+                Some(waker) => {
+                    let instance_id = route.instance_id.clone();
+                    tokio::task::spawn_blocking(move || waker(&instance_id))
+                        .await
+                        .ok()
+                        .and_then(|outcome| outcome.page(&route.domain))
+                }
+                None => None,
+            };
+            match page {
+                Some((status, html)) => wake_response(status, html),
+                None => error_response(
+                    StatusCode::BAD_GATEWAY,
+                    &format!("Failed to connect to backend service: {}", e),
+                ),
+            }
+        }
+    }
+}
+
+/// The page shown while a wake-on-access instance starts (or failed to)
+fn wake_response(status: u16, html: String) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut builder = Response::builder()
+        .status(status)
+        .header("content-type", "text/html; charset=utf-8")
+        .header("cache-control", "no-store");
+    if status == StatusCode::SERVICE_UNAVAILABLE {
+        builder = builder.header("retry-after", "2");
     }
+    builder.body(Body::from(html)).unwrap()
 }
 
 fn error_response(status: StatusCode, message: &str) -> Response {
@@ -0,0 +1,334 @@
+//! Proxy health watchdog
+//!
+//! The Caddy daemon (or the fallback proxy on port 8080 when the daemon isn't
+//! installed) can stop serving while its process is still alive. Every few
+//! seconds the watchdog asks the proxy's `/_burd/health` endpoint whether it
+//! is still Burd answering, publishes the result in `AppState::proxy_healthy`,
+//! and restarts a proxy that is down or wedged.
+//!
+//! A port taken over by another program is reported but never "fixed" by a
+//! restart; the user has to stop the other program.
+
+use crate::commands::AppState;
+use crate::helper_client::{HelperClient, HelperRequest};
+use crate::platform;
+use serde::Serialize;
+use std::net::{SocketAddr, TcpStream};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Delay between health checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long the health request may take before the proxy counts as wedged
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Consecutive failed checks before the proxy is restarted, so a single
+/// slow response doesn't bounce it
+const FAILURES_BEFORE_RESTART: u32 = 2;
+
+/// Restarts attempted before giving up until the proxy recovers on its own
+const MAX_RESTARTS: u32 = 3;
+
+/// What the last health check found
+///
+/// Stored as a `u8` in `AppState::proxy_healthy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum ProxyHealth {
+    /// Not checked yet
+    Unknown = 0,
+    Healthy = 1,
+    /// Another program answers on the proxy port
+    Hijacked = 2,
+    /// The port accepts connections but requests hang or fail
+    Unresponsive = 3,
+    /// Nothing is listening on the proxy port
+    Down = 4,
+    /// The watchdog restarted the proxy and is waiting for it to come back
+    Restarting = 5,
+}
+
+impl ProxyHealth {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => ProxyHealth::Healthy,
+            2 => ProxyHealth::Hijacked,
+            3 => ProxyHealth::Unresponsive,
+            4 => ProxyHealth::Down,
+            5 => ProxyHealth::Restarting,
+            _ => ProxyHealth::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProxyHealth::Unknown => "unknown",
+            ProxyHealth::Healthy => "healthy",
+            ProxyHealth::Hijacked => "port taken by another program",
+            ProxyHealth::Unresponsive => "not responding",
+            ProxyHealth::Down => "not running",
+            ProxyHealth::Restarting => "restarting",
+        }
+    }
+
+    /// Whether restarting the proxy can fix this state
+    fn needs_restart(&self) -> bool {
+        matches!(self, ProxyHealth::Unresponsive | ProxyHealth::Down)
+    }
+}
+
+/// Which proxy is expected to be serving
+#[derive(Debug, Clone)]
+enum ProxyTarget {
+    /// Caddy daemon on port 80, managed by launchd
+    Daemon { tld: String },
+    /// In-process fallback proxy
+    Fallback { port: u16, tld: String },
+}
+
+impl ProxyTarget {
+    async fn current(state: &AppState) -> Self {
+        let proxy = state.proxy_server.lock().await;
+        let tld = proxy.tld().to_string();
+        if platform::proxy_daemon().is_installed() {
+            ProxyTarget::Daemon { tld }
+        } else {
+            ProxyTarget::Fallback {
+                port: proxy.port(),
+                tld,
+            }
+        }
+    }
+
+    fn probe(&self) -> ProxyHealth {
+        match self {
+            ProxyTarget::Daemon { tld } => probe(80, tld),
+            ProxyTarget::Fallback { port, tld } => probe(*port, tld),
+        }
+    }
+}
+
+/// Check whether Burd's proxy is the one answering on `port`
+fn probe(port: u16, tld: &str) -> ProxyHealth {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    if TcpStream::connect_timeout(&addr, Duration::from_millis(500)).is_err() {
+        return ProxyHealth::Down;
+    }
+
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(_) => return ProxyHealth::Unknown,
+    };
+
+    match client
+        .get(format!("http://127.0.0.1:{}/_burd/health", port))
+        .header("Host", format!("health-check.{}", tld))
+        .send()
+    {
+        Ok(resp) => match resp.text() {
+            Ok(body) if body.trim() == "burd-ok" => ProxyHealth::Healthy,
+            Ok(_) => ProxyHealth::Hijacked,
+            Err(_) => ProxyHealth::Unresponsive,
+        },
+        Err(_) => ProxyHealth::Unresponsive,
+    }
+}
+
+async fn check(target: ProxyTarget) -> ProxyHealth {
+    tokio::task::spawn_blocking(move || target.probe())
+        .await
+        .unwrap_or(ProxyHealth::Unknown)
+}
+
+/// Check the expected proxy right now, without waiting for the next tick
+pub async fn check_now(state: &AppState) -> ProxyHealth {
+    check(ProxyTarget::current(state).await).await
+}
+
+/// Restart the proxy that failed its health check
+///
+/// The daemon is only restarted through the privileged helper; a background
+/// task must never pop up a password prompt.
+async fn restart(state: &AppState, target: &ProxyTarget) -> Result<(), String> {
+    match target {
+        ProxyTarget::Daemon { .. } => tokio::task::spawn_blocking(|| {
+            if !HelperClient::is_running() {
+                return Err("the privileged helper isn't running".to_string());
+            }
+            let response = HelperClient::send_request(HelperRequest::RestartProxyDaemon)?;
+            if response.success {
+                Ok(())
+            } else {
+                Err(response.message)
+            }
+        })
+        .await
+        .map_err(|e| format!("Task error: {}", e))?,
+        ProxyTarget::Fallback { .. } => {
+            let mut proxy = state.proxy_server.lock().await;
+            proxy.stop();
+            proxy.start().await
+        }
+    }
+}
+
+/// Restart bookkeeping across checks
+#[derive(Debug, Default)]
+struct Watchdog {
+    /// Consecutive checks that found the proxy down or wedged
+    failures: u32,
+    /// Restarts since the proxy was last healthy
+    restarts: u32,
+}
+
+impl Watchdog {
+    /// Record a check; returns true when the proxy should be restarted
+    fn should_restart(&mut self, health: ProxyHealth) -> bool {
+        if !health.needs_restart() {
+            self.failures = 0;
+            if health == ProxyHealth::Healthy {
+                self.restarts = 0;
+            }
+            return false;
+        }
+
+        self.failures += 1;
+        if self.failures < FAILURES_BEFORE_RESTART || self.restarts >= MAX_RESTARTS {
+            return false;
+        }
+        self.failures = 0;
+        self.restarts += 1;
+        true
+    }
+}
+
+/// Watch the proxy forever, calling `on_change` whenever its health changes
+pub async fn run(state: AppState, on_change: impl Fn(ProxyHealth) + Send + 'static) {
+    let mut watchdog = Watchdog::default();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let target = ProxyTarget::current(&state).await;
+        let health = check(target.clone()).await;
+        let mut reported = health;
+
+        if watchdog.should_restart(health) {
+            match restart(&state, &target).await {
+                Ok(()) => {
+                    eprintln!(
+                        "Proxy was {}; restarted it (attempt {}/{})",
+                        health.label(),
+                        watchdog.restarts,
+                        MAX_RESTARTS
+                    );
+                    reported = ProxyHealth::Restarting;
+                }
+                Err(e) => eprintln!(
+                    "Proxy is {} and could not be restarted: {}",
+                    health.label(),
+                    e
+                ),
+            }
+        }
+
+        let previous = state.proxy_healthy.swap(reported as u8, Ordering::Relaxed);
+        if ProxyHealth::from_u8(previous) != reported {
+            on_change(reported);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Answer HTTP requests with `body`, or accept them and never answer
+    fn serve(body: Option<&'static str>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut buf = [0u8; 1024];
+                    let _ = stream.read(&mut buf);
+                    match body {
+                        Some(body) => {
+                            let _ = write!(
+                                stream,
+                                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                body.len(),
+                                body
+                            );
+                        }
+                        None => std::thread::sleep(PROBE_TIMEOUT * 2),
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn test_probe_states() {
+        assert_eq!(probe(serve(Some("burd-ok")), "burd"), ProxyHealth::Healthy);
+        assert_eq!(
+            probe(serve(Some("<html>Apache</html>")), "burd"),
+            ProxyHealth::Hijacked
+        );
+        assert_eq!(probe(serve(None), "burd"), ProxyHealth::Unresponsive);
+
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        assert_eq!(probe(port, "burd"), ProxyHealth::Down);
+    }
+
+    #[test]
+    fn test_restart_policy() {
+        let mut watchdog = Watchdog::default();
+
+        // One failed check is tolerated, the second triggers a restart
+        assert!(!watchdog.should_restart(ProxyHealth::Down));
+        assert!(watchdog.should_restart(ProxyHealth::Unresponsive));
+
+        // A hijacked port is never restarted
+        assert!(!watchdog.should_restart(ProxyHealth::Hijacked));
+        assert!(!watchdog.should_restart(ProxyHealth::Hijacked));
+
+        // Gives up after MAX_RESTARTS until the proxy is healthy again
+        for _ in 1..MAX_RESTARTS {
+            assert!(!watchdog.should_restart(ProxyHealth::Down));
+            assert!(watchdog.should_restart(ProxyHealth::Down));
+        }
+        assert!(!watchdog.should_restart(ProxyHealth::Down));
+        assert!(!watchdog.should_restart(ProxyHealth::Down));
+
+        assert!(!watchdog.should_restart(ProxyHealth::Healthy));
+        assert!(!watchdog.should_restart(ProxyHealth::Down));
+        assert!(watchdog.should_restart(ProxyHealth::Down));
+    }
+
+    #[test]
+    fn test_health_round_trips_through_u8() {
+        for health in [
+            ProxyHealth::Unknown,
+            ProxyHealth::Healthy,
+            ProxyHealth::Hijacked,
+            ProxyHealth::Unresponsive,
+            ProxyHealth::Down,
+            ProxyHealth::Restarting,
+        ] {
+            assert_eq!(ProxyHealth::from_u8(health as u8), health);
+        }
+    }
+}
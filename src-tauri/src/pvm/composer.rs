@@ -0,0 +1,352 @@
+//! Composer version manager
+//!
+//! Downloads `composer.phar` releases from getcomposer.org (verified against
+//! their published SHA-256) into `bin/composer/<version>/`, with a `default`
+//! symlink like PVM's. A project pins its own version in a
+//! `.composer-version` file next to `composer.json`; `burd composer` runs the
+//! selected phar with the project's PHP from PVM (see
+//! [`super::project_php_binary`]) first on PATH, so `@php` scripts use the
+//! same binary instead of whatever PHP the shell finds.
+
+use super::{compare_versions, project_php_binary};
+use crate::config::get_bin_dir;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Release channels and the versions they point to
+const VERSIONS_URL: &str = "https://getcomposer.org/versions";
+
+/// Download location of a release (`{}` is the version)
+const DOWNLOAD_URL: &str = "https://getcomposer.org/download/{}/composer.phar";
+
+/// Project file pinning a Composer version
+pub const PIN_FILE: &str = ".composer-version";
+
+/// Channels offered for download, in display order
+const CHANNELS: [&str; 3] = ["stable", "2.2", "preview"];
+
+/// An installed Composer version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposerVersion {
+    pub version: String,
+    pub is_default: bool,
+}
+
+/// The release a getcomposer.org channel currently points to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteComposerVersion {
+    /// `stable`, `2.2` (the LTS line for PHP < 7.2.5), or `preview`
+    pub channel: String,
+    pub version: String,
+}
+
+/// One entry of a channel in the versions listing
+#[derive(Debug, Deserialize)]
+struct ChannelEntry {
+    version: String,
+}
+
+// === Directories ===
+
+/// Directory holding the downloaded Composer versions
+pub fn get_composer_dir() -> Result<PathBuf, String> {
+    get_bin_dir().map(|p| p.join("composer"))
+}
+
+/// Path of the phar of a version
+pub fn phar_path(version: &str) -> Result<PathBuf, String> {
+    get_composer_dir().map(|p| p.join(version).join("composer.phar"))
+}
+
+fn get_default_link() -> Result<PathBuf, String> {
+    get_composer_dir().map(|p| p.join("default"))
+}
+
+pub fn is_installed(version: &str) -> bool {
+    phar_path(version).map(|p| p.exists()).unwrap_or(false)
+}
+
+// === Installed Versions ===
+
+/// The default Composer version (if set)
+pub fn get_default_version() -> Option<String> {
+    let target = fs::read_link(get_default_link().ok()?).ok()?;
+    target
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(String::from)
+}
+
+/// List installed Composer versions, newest first
+pub fn list_installed_versions() -> Result<Vec<ComposerVersion>, String> {
+    let dir = get_composer_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let default_version = get_default_version();
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read Composer directory: {}", e))?;
+
+    let mut versions: Vec<ComposerVersion> = entries
+        .flatten()
+        .filter(|e| e.file_name() != "default" && e.path().join("composer.phar").exists())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .map(|version| ComposerVersion {
+            is_default: default_version.as_deref() == Some(version.as_str()),
+            version,
+        })
+        .collect();
+    versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
+
+    Ok(versions)
+}
+
+// === Remote Versions ===
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .user_agent("burd-cli")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn download(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+
+    response
+        .bytes()
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read download: {}", e))
+}
+
+/// Parse the versions listing into the offered channels
+fn parse_channels(body: &str) -> Result<Vec<RemoteComposerVersion>, String> {
+    let channels: HashMap<String, Vec<ChannelEntry>> = serde_json::from_str(body)
+        .map_err(|e| format!("Failed to parse Composer versions: {}", e))?;
+
+    Ok(CHANNELS
+        .iter()
+        .filter_map(|channel| {
+            let entry = channels.get(*channel)?.first()?;
+            Some(RemoteComposerVersion {
+                channel: channel.to_string(),
+                version: entry.version.clone(),
+            })
+        })
+        .collect())
+}
+
+/// The release each channel currently points to
+pub fn list_remote_versions() -> Result<Vec<RemoteComposerVersion>, String> {
+    let body = download(&http_client()?, VERSIONS_URL)?;
+    parse_channels(&String::from_utf8_lossy(&body))
+}
+
+/// Turn a version or channel (`latest`/`stable`, `2.2`, `preview`) into a version
+pub fn resolve_version(spec: &str) -> Result<String, String> {
+    let spec = spec.trim().trim_start_matches('v');
+    let channel = if spec == "latest" { "stable" } else { spec };
+    if !CHANNELS.contains(&channel) {
+        return Ok(spec.to_string());
+    }
+
+    list_remote_versions()?
+        .into_iter()
+        .find(|r| r.channel == channel)
+        .map(|r| r.version)
+        .ok_or_else(|| format!("No Composer release found for '{}'", spec))
+}
+
+// === Download & Install ===
+
+/// Download a version, verifying its SHA-256 checksum
+pub fn download_version(version: &str) -> Result<PathBuf, String> {
+    let client = http_client()?;
+    let url = DOWNLOAD_URL.replace("{}", version);
+
+    let bytes = download(&client, &url)?;
+    let checksum = download(&client, &format!("{}.sha256sum", url))?;
+    let expected = String::from_utf8_lossy(&checksum)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        return Err(format!(
+            "Composer {} checksum mismatch (expected {}, got {})",
+            version, expected, actual
+        ));
+    }
+
+    let path = phar_path(version)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create Composer directory: {}", e))?;
+    }
+    fs::write(&path, &bytes).map_err(|e| format!("Failed to write Composer: {}", e))?;
+
+    Ok(path)
+}
+
+// === Version Management ===
+
+/// Set the default Composer version
+pub fn set_default_version(version: &str) -> Result<(), String> {
+    if !is_installed(version) {
+        return Err(format!("Composer {} is not installed", version));
+    }
+
+    let link = get_default_link()?;
+    let _ = fs::remove_file(&link);
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(get_composer_dir()?.join(version), &link)
+            .map_err(|e| format!("Failed to create symlink: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Delete an installed Composer version
+pub fn delete_version(version: &str) -> Result<(), String> {
+    let dir = get_composer_dir()?.join(version);
+    if !dir.exists() {
+        return Err(format!("Composer {} is not installed", version));
+    }
+
+    if get_default_version().as_deref() == Some(version) {
+        let _ = fs::remove_file(get_default_link()?);
+    }
+
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete Composer {}: {}", version, e))
+}
+
+// === Projects ===
+
+/// Nearest directory at or above `start` with a `composer.json`
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join("composer.json").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Version pinned in a project's `.composer-version`
+pub fn pinned_version(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join(PIN_FILE))
+        .ok()
+        .and_then(|content| content.lines().next().map(|l| l.trim().to_string()))
+        .filter(|v| !v.is_empty())
+}
+
+/// Pin a project to a Composer version
+pub fn pin_version(root: &Path, version: &str) -> Result<(), String> {
+    fs::write(root.join(PIN_FILE), format!("{}\n", version))
+        .map_err(|e| format!("Failed to write {}: {}", PIN_FILE, e))
+}
+
+/// Version to run in a project: its pin, else the default, else the newest
+/// installed one
+pub fn selected_version(root: Option<&Path>) -> Option<String> {
+    root.and_then(pinned_version)
+        .or_else(get_default_version)
+        .or_else(|| {
+            list_installed_versions()
+                .ok()?
+                .into_iter()
+                .next()
+                .map(|v| v.version)
+        })
+}
+
+/// Build the command running a Composer version in `dir` with the
+/// project's PHP
+pub fn command(dir: &Path, version: &str, args: &[String]) -> Result<Command, String> {
+    let phar = phar_path(version)?;
+    if !phar.exists() {
+        return Err(format!(
+            "Composer {} is not installed. Run: burd composer use {}",
+            version, version
+        ));
+    }
+
+    let php = project_php_binary(dir)?;
+    let mut path: Vec<PathBuf> = php.parent().map(Path::to_path_buf).into_iter().collect();
+    if let Some(existing) = std::env::var_os("PATH") {
+        path.extend(std::env::split_paths(&existing));
+    }
+    let path = std::env::join_paths(path)
+        .map_err(|e| format!("Failed to build PATH for Composer: {}", e))?;
+
+    let mut cmd = Command::new(php);
+    cmd.arg(phar).args(args).current_dir(dir).env("PATH", path);
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_channels() {
+        let body = r#"{
+            "stable": [{"path": "/download/2.8.4/composer.phar", "version": "2.8.4", "min-php": 70205}],
+            "preview": [{"path": "/download/2.8.4/composer.phar", "version": "2.8.4", "min-php": 70205}],
+            "snapshot": [{"path": "/composer.phar", "version": "abc123", "min-php": 70205}],
+            "1": [{"path": "/download/1.10.27/composer.phar", "version": "1.10.27", "min-php": 50300}],
+            "2.2": [{"path": "/download/2.2.25/composer.phar", "version": "2.2.25", "min-php": 50300}]
+        }"#;
+
+        let channels = parse_channels(body).unwrap();
+        let pairs: Vec<(&str, &str)> = channels
+            .iter()
+            .map(|r| (r.channel.as_str(), r.version.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("stable", "2.8.4"), ("2.2", "2.2.25"), ("preview", "2.8.4")]
+        );
+
+        assert!(parse_channels("not json").is_err());
+    }
+
+    #[test]
+    fn test_pinned_version() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(pinned_version(dir.path()), None);
+
+        pin_version(dir.path(), "2.7.9").unwrap();
+        assert_eq!(pinned_version(dir.path()).as_deref(), Some("2.7.9"));
+
+        fs::write(dir.path().join(PIN_FILE), "\n").unwrap();
+        assert_eq!(pinned_version(dir.path()), None);
+    }
+
+    #[test]
+    fn test_find_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("app/Http");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_project_root(&nested), None);
+
+        fs::write(dir.path().join("composer.json"), "{}").unwrap();
+        assert_eq!(find_project_root(&nested), Some(dir.path().to_path_buf()));
+    }
+}
@@ -0,0 +1,175 @@
+//! PHP extension bundles
+//!
+//! static-php.dev publishes every PHP release in a few builds that differ only
+//! in their compiled-in extensions. PVM installs the `common` build; this
+//! module lists the others and swaps an installed version over to one, e.g.
+//! `bulk` for imagick or swoole. The chosen bundle is recorded next to the
+//! binary so it can be shown later.
+
+use super::{download_build, fetch_builds, get_version_dir, latest_per_minor, list_modules};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::AppHandle;
+
+/// Where the static-php.dev bundles are published
+const BUNDLES_BASE_URL: &str = "https://dl.static-php.dev/static-php-cli";
+
+/// File in a version directory naming the installed bundle
+const BUNDLE_MARKER: &str = ".bundle";
+
+/// A static-php.dev build flavor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtensionBundle {
+    Minimal,
+    Common,
+    Bulk,
+}
+
+impl ExtensionBundle {
+    pub const ALL: [ExtensionBundle; 3] = [
+        ExtensionBundle::Minimal,
+        ExtensionBundle::Common,
+        ExtensionBundle::Bulk,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtensionBundle::Minimal => "minimal",
+            ExtensionBundle::Common => "common",
+            ExtensionBundle::Bulk => "bulk",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ExtensionBundle::Minimal => "Core extensions only, for the smallest binary",
+            ExtensionBundle::Common => {
+                "The extensions most frameworks need (the default PVM build)"
+            }
+            ExtensionBundle::Bulk => "Common plus imagick, swoole, event, imap, protobuf and more",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|bundle| bundle.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown extension bundle '{}'. Use minimal, common or bulk",
+                    s
+                )
+            })
+    }
+
+    fn base_url(&self) -> String {
+        format!("{}/{}", BUNDLES_BASE_URL, self.as_str())
+    }
+}
+
+/// A bundle and the PHP versions it's published for
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionBundleInfo {
+    pub id: ExtensionBundle,
+    pub description: &'static str,
+    /// Latest patch release per minor version, newest first
+    pub versions: Vec<String>,
+}
+
+/// The extensions compiled into an installed PHP version
+#[derive(Debug, Clone, Serialize)]
+pub struct PhpExtensions {
+    pub version: String,
+    pub bundle: ExtensionBundle,
+    pub extensions: Vec<String>,
+}
+
+/// The bundle an installed version was built from
+///
+/// Versions installed before bundles were tracked are `common` builds.
+pub fn installed_bundle(version: &str) -> Result<ExtensionBundle, String> {
+    let marker = get_version_dir(version)?.join(BUNDLE_MARKER);
+    Ok(fs::read_to_string(marker)
+        .ok()
+        .and_then(|s| ExtensionBundle::parse(&s).ok())
+        .unwrap_or(ExtensionBundle::Common))
+}
+
+/// List the extensions of an installed PHP version
+pub fn get_extensions(version: &str) -> Result<PhpExtensions, String> {
+    let php_binary = get_version_dir(version)?.join("php");
+    if !php_binary.exists() {
+        return Err(format!("PHP {} is not installed", version));
+    }
+
+    let extensions = list_modules(&php_binary)
+        .ok_or_else(|| format!("Failed to list extensions of PHP {}", version))?;
+
+    Ok(PhpExtensions {
+        version: version.to_string(),
+        bundle: installed_bundle(version)?,
+        extensions,
+    })
+}
+
+/// List the bundles and which versions each is published for
+pub async fn list_bundles() -> Result<Vec<ExtensionBundleInfo>, String> {
+    let mut bundles = Vec::new();
+    for bundle in ExtensionBundle::ALL {
+        let versions = latest_per_minor(fetch_builds(&bundle.base_url()).await?)
+            .into_iter()
+            .map(|v| v.version)
+            .collect();
+        bundles.push(ExtensionBundleInfo {
+            id: bundle,
+            description: bundle.description(),
+            versions,
+        });
+    }
+    Ok(bundles)
+}
+
+/// Replace an installed version's binary with the build from `bundle`
+pub async fn install_bundle(
+    version: &str,
+    bundle: ExtensionBundle,
+    app_handle: &AppHandle,
+) -> Result<PhpExtensions, String> {
+    let version_dir = get_version_dir(version)?;
+    if !version_dir.join("php").exists() {
+        return Err(format!("PHP {} is not installed", version));
+    }
+
+    if installed_bundle(version)? != bundle {
+        download_build(version, &bundle.base_url(), &version_dir, app_handle)
+            .await
+            .map_err(|e| format!("{} (from the {} bundle)", e, bundle.as_str()))?;
+        fs::write(version_dir.join(BUNDLE_MARKER), bundle.as_str())
+            .map_err(|e| format!("Failed to record the installed bundle: {}", e))?;
+    }
+
+    get_extensions(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bundle() {
+        for bundle in ExtensionBundle::ALL {
+            assert_eq!(ExtensionBundle::parse(bundle.as_str()), Ok(bundle));
+        }
+        assert_eq!(ExtensionBundle::parse(" Bulk\n"), Ok(ExtensionBundle::Bulk));
+        assert!(ExtensionBundle::parse("swoole").is_err());
+    }
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!(
+            ExtensionBundle::Common.base_url(),
+            super::super::DOWNLOAD_BASE_URL
+        );
+    }
+}
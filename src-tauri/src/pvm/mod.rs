@@ -2,12 +2,19 @@
 //!
 //! Manages PHP CLI versions by downloading static binaries from static-php.dev.
 //! Similar to NVM but for PHP, with shell integration for version switching.
+//! Builds with other extension sets are handled in [`extensions`], Composer
+//! versions in [`composer`].
 
+pub mod composer;
+pub mod extensions;
+
+use crate::binary::DownloadProgress;
 use crate::config::get_app_dir;
+use crate::events::{self, Event};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tauri::{AppHandle, Emitter};
 
@@ -17,6 +24,9 @@ const DOWNLOAD_BASE_URL: &str = "https://dl.static-php.dev/static-php-cli/common
 /// Shell profile marker comment
 const SHELL_MARKER: &str = "# Added by Burd - PHP Version Manager";
 
+/// Project file pinning a PHP version (e.g. `8.3` or `8.3.15`)
+pub const PHP_VERSION_FILE: &str = ".php-version";
+
 /// Information about a PHP version
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PHPVersion {
@@ -239,8 +249,17 @@ pub fn get_burd_php() -> Option<CurrentPHP> {
     let version_str = String::from_utf8_lossy(&version_output.stdout);
     let version = parse_php_version(&version_str)?;
 
-    // Run php -m to get the list of extensions
-    let extensions = Command::new(&php_path)
+    Some(CurrentPHP {
+        version,
+        source: "Burd".to_string(),
+        path: php_path.to_string_lossy().to_string(),
+        extensions: list_modules(&php_path),
+    })
+}
+
+/// Run `php -m` to get the list of extensions
+fn list_modules(php_path: &Path) -> Option<Vec<String>> {
+    Command::new(php_path)
         .arg("-m")
         .output()
         .ok()
@@ -253,14 +272,7 @@ pub fn get_burd_php() -> Option<CurrentPHP> {
                 })
                 .map(|s| s.to_string())
                 .collect::<Vec<_>>()
-        });
-
-    Some(CurrentPHP {
-        version,
-        source: "Burd".to_string(),
-        path: php_path.to_string_lossy().to_string(),
-        extensions,
-    })
+        })
 }
 
 // === Installed Versions ===
@@ -329,6 +341,51 @@ pub fn list_installed_versions() -> Result<Vec<PHPVersion>, String> {
     Ok(versions)
 }
 
+/// Whether `version` satisfies a pin like `8.3` (any 8.3.x) or `8.3.15`
+fn matches_pin(version: &str, pin: &str) -> bool {
+    version == pin || version.starts_with(&format!("{}.", pin))
+}
+
+/// Newest installed version satisfying a pin
+pub fn find_installed_version(pin: &str) -> Option<String> {
+    list_installed_versions()
+        .ok()?
+        .into_iter()
+        .map(|v| v.version)
+        .find(|v| matches_pin(v, pin))
+}
+
+/// PHP binary for a project directory
+///
+/// The newest installed version matching the `.php-version` file in `dir`
+/// or a parent directory, otherwise the default version. Never falls back
+/// to a PHP from PATH.
+pub fn project_php_binary(dir: &Path) -> Result<PathBuf, String> {
+    let pin = dir.ancestors().find_map(|d| {
+        fs::read_to_string(d.join(PHP_VERSION_FILE))
+            .ok()
+            .and_then(|content| content.lines().next().map(|l| l.trim().to_string()))
+            .filter(|pin| !pin.is_empty())
+    });
+
+    if let Some(pin) = pin {
+        let version = find_installed_version(&pin).ok_or_else(|| {
+            format!(
+                "PHP {} is pinned in {} but not installed. Install it from the PHP section",
+                pin, PHP_VERSION_FILE
+            )
+        })?;
+        return Ok(get_version_dir(&version)?.join("php"));
+    }
+
+    let php = get_default_link()?.join("php");
+    if php.exists() {
+        Ok(php)
+    } else {
+        Err("No default PHP version set. Install one from the PHP section".to_string())
+    }
+}
+
 /// Compare two version strings (e.g., "8.4.12" vs "8.3.15")
 fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     let parse = |s: &str| -> Vec<u32> { s.split('.').filter_map(|p| p.parse().ok()).collect() };
@@ -350,8 +407,22 @@ fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
 
 /// Fetch available PHP versions from static-php.dev
 pub async fn list_remote_versions() -> Result<Vec<RemotePHPVersion>, String> {
+    let installed = list_installed_versions().unwrap_or_default();
+    let installed_versions: Vec<&str> = installed.iter().map(|v| v.version.as_str()).collect();
+
+    let mut versions = fetch_builds(DOWNLOAD_BASE_URL).await?;
+
+    // Skip already installed versions
+    versions.retain(|v| !installed_versions.contains(&v.version.as_str()));
+
+    Ok(latest_per_minor(versions))
+}
+
+/// Fetch the builds for our architecture from a static-php.dev listing,
+/// newest first
+async fn fetch_builds(base_url: &str) -> Result<Vec<RemotePHPVersion>, String> {
     let arch = get_arch_string();
-    let url = format!("{}/?format=json", DOWNLOAD_BASE_URL);
+    let url = format!("{}/?format=json", base_url);
 
     let response = reqwest::get(&url)
         .await
@@ -378,27 +449,17 @@ pub async fn list_remote_versions() -> Result<Vec<RemotePHPVersion>, String> {
     let pattern = format!(r"^php-(\d+\.\d+\.\d+)-cli-macos-{}\.tar\.gz$", arch);
     let re = Regex::new(&pattern).map_err(|e| format!("Failed to compile regex: {}", e))?;
 
-    let installed = list_installed_versions().unwrap_or_default();
-    let installed_versions: Vec<&str> = installed.iter().map(|v| v.version.as_str()).collect();
-
     let mut versions = Vec::new();
 
     for entry in entries {
         if let Some(name) = entry.get("name").and_then(|n| n.as_str()) {
             if let Some(caps) = re.captures(name) {
                 if let Some(version_match) = caps.get(1) {
-                    let version = version_match.as_str().to_string();
-
-                    // Skip already installed versions
-                    if installed_versions.contains(&version.as_str()) {
-                        continue;
-                    }
-
-                    let download_url = format!("{}/{}", DOWNLOAD_BASE_URL, name);
+                    let download_url = format!("{}/{}", base_url, name);
                     let size = entry.get("size").and_then(|s| s.as_u64());
 
                     versions.push(RemotePHPVersion {
-                        version,
+                        version: version_match.as_str().to_string(),
                         download_url,
                         size,
                     });
@@ -410,7 +471,11 @@ pub async fn list_remote_versions() -> Result<Vec<RemotePHPVersion>, String> {
     // Sort by version (newest first)
     versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
 
-    // Keep only the latest patch version per minor version
+    Ok(versions)
+}
+
+/// Keep only the latest patch version per minor version of a newest-first list
+fn latest_per_minor(mut versions: Vec<RemotePHPVersion>) -> Vec<RemotePHPVersion> {
     let mut seen_minor: Vec<String> = Vec::new();
     versions.retain(|v| {
         let minor = get_minor_version(&v.version);
@@ -421,8 +486,7 @@ pub async fn list_remote_versions() -> Result<Vec<RemotePHPVersion>, String> {
             true
         }
     });
-
-    Ok(versions)
+    versions
 }
 
 /// Get the minor version (e.g., "8.4" from "8.4.12")
@@ -446,15 +510,23 @@ fn get_arch_string() -> &'static str {
 
 // === Download & Install ===
 
+/// Publish PHP download progress on the API event stream
+fn publish_progress(downloaded: u64, total: u64, phase: &str) {
+    events::publish(Event::Download(DownloadProgress {
+        service_type: "php".to_string(),
+        downloaded,
+        total,
+        percentage: if total > 0 {
+            downloaded as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        },
+        phase: phase.to_string(),
+    }));
+}
+
 /// Download and install a PHP version
 pub async fn download_version(version: &str, app_handle: &AppHandle) -> Result<(), String> {
-    use futures_util::StreamExt;
-    use std::io::Write;
-
-    let arch = get_arch_string();
-    let filename = format!("php-{}-cli-macos-{}.tar.gz", version, arch);
-    let url = format!("{}/{}", DOWNLOAD_BASE_URL, filename);
-
     // Ensure PVM directory exists
     ensure_pvm_dir()?;
 
@@ -465,6 +537,30 @@ pub async fn download_version(version: &str, app_handle: &AppHandle) -> Result<(
         return Err(format!("PHP {} is already installed", version));
     }
 
+    // Create version directory
+    fs::create_dir_all(&version_dir)
+        .map_err(|e| format!("Failed to create version directory: {}", e))?;
+
+    download_build(version, DOWNLOAD_BASE_URL, &version_dir, app_handle).await
+}
+
+/// Download a static-php.dev build and install its binary as `version_dir/php`
+///
+/// The archive is extracted next to the binary and only moved into place once
+/// complete, so an existing binary is replaced in one step.
+async fn download_build(
+    version: &str,
+    base_url: &str,
+    version_dir: &Path,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    use futures_util::StreamExt;
+    use std::io::Write;
+
+    let arch = get_arch_string();
+    let filename = format!("php-{}-cli-macos-{}.tar.gz", version, arch);
+    let url = format!("{}/{}", base_url, filename);
+
     // Download the file
     let response = reqwest::get(&url)
         .await
@@ -489,6 +585,7 @@ pub async fn download_version(version: &str, app_handle: &AppHandle) -> Result<(
     // Download with progress
     let mut stream = response.bytes_stream();
     let mut downloaded: u64 = 0;
+    let mut published_percent = None;
 
     while let Some(chunk) = stream.next().await {
         let chunk = chunk.map_err(|e| format!("Download error: {}", e))?;
@@ -513,20 +610,29 @@ pub async fn download_version(version: &str, app_handle: &AppHandle) -> Result<(
                 "percentage": percentage,
             }),
         );
+
+        // The event stream gets whole-percent steps rather than every chunk
+        if published_percent != Some(percentage) {
+            published_percent = Some(percentage);
+            publish_progress(downloaded, total_size, "downloading");
+        }
     }
 
-    // Create version directory
-    fs::create_dir_all(&version_dir)
-        .map_err(|e| format!("Failed to create version directory: {}", e))?;
+    publish_progress(downloaded, total_size, "extracting");
+
+    // Extract the tarball into a staging directory
+    let staging_dir = version_dir.join(".download");
+    let _ = fs::remove_dir_all(&staging_dir);
+    fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
 
-    // Extract the tarball
     let tar_gz =
         fs::File::open(&temp_file).map_err(|e| format!("Failed to open temp file: {}", e))?;
     let tar = flate2::read::GzDecoder::new(tar_gz);
     let mut archive = tar::Archive::new(tar);
 
     archive
-        .unpack(&version_dir)
+        .unpack(&staging_dir)
         .map_err(|e| format!("Failed to extract archive: {}", e))?;
 
     // Clean up temp file
@@ -534,41 +640,39 @@ pub async fn download_version(version: &str, app_handle: &AppHandle) -> Result<(
 
     // The archive might have the binary in root or in a subdirectory
     // Try to find the php binary
-    let php_binary = find_php_binary(&version_dir)?;
-
-    // Move the binary to the version directory root if needed
-    let target_binary = version_dir.join("php");
-    if php_binary != target_binary {
-        fs::rename(&php_binary, &target_binary)
-            .map_err(|e| format!("Failed to move PHP binary: {}", e))?;
-    }
+    let php_binary = find_php_binary(&staging_dir)?;
 
     // Make it executable
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&target_binary)
+        let mut perms = fs::metadata(&php_binary)
             .map_err(|e| format!("Failed to get permissions: {}", e))?
             .permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&target_binary, perms)
+        fs::set_permissions(&php_binary, perms)
             .map_err(|e| format!("Failed to set permissions: {}", e))?;
     }
 
     // Remove quarantine attribute on macOS
     let _ = Command::new("xattr")
         .args(["-d", "com.apple.quarantine"])
-        .arg(&target_binary)
+        .arg(&php_binary)
         .output();
 
+    // Move the binary into place, replacing any previous build
+    let target_binary = version_dir.join("php");
+    fs::rename(&php_binary, &target_binary)
+        .map_err(|e| format!("Failed to move PHP binary: {}", e))?;
+
     // Clean up any leftover directories from extraction
-    cleanup_extraction(&version_dir)?;
+    cleanup_extraction(version_dir)?;
 
     Ok(())
 }
 
 /// Find the php binary in the extracted directory
-fn find_php_binary(dir: &PathBuf) -> Result<PathBuf, String> {
+fn find_php_binary(dir: &Path) -> Result<PathBuf, String> {
     // Check direct binary
     let direct = dir.join("php");
     if direct.exists() && direct.is_file() {
@@ -597,7 +701,7 @@ fn find_php_binary(dir: &PathBuf) -> Result<PathBuf, String> {
 }
 
 /// Clean up extraction artifacts (empty directories, etc.)
-fn cleanup_extraction(version_dir: &PathBuf) -> Result<(), String> {
+fn cleanup_extraction(version_dir: &Path) -> Result<(), String> {
     if let Ok(entries) = fs::read_dir(version_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
@@ -949,6 +1053,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_matches_pin() {
+        assert!(matches_pin("8.3.15", "8.3"));
+        assert!(matches_pin("8.3.15", "8.3.15"));
+        assert!(matches_pin("8.3.15", "8"));
+        assert!(!matches_pin("8.30.1", "8.3"));
+        assert!(!matches_pin("8.4.1", "8.3"));
+    }
+
     #[test]
     fn test_get_minor_version() {
         assert_eq!(get_minor_version("8.4.12"), "8.4");
@@ -0,0 +1,445 @@
+//! Queue Manager Module
+//!
+//! Inspects Beanstalkd instances over its plain-text protocol: lists tubes with
+//! job counts, peeks at jobs, and buries or kicks them. Used by the HTTP API
+//! and `burd queue` so stuck jobs can be diagnosed without extra tooling.
+
+use crate::config::{Config, Instance, ServiceType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// Connection and read/write timeout for Beanstalkd commands
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Default number of buried/delayed jobs kicked from a tube
+pub const DEFAULT_KICK_BOUND: u32 = 100;
+
+/// Per-tube job counts (from `stats-tube`)
+#[derive(Debug, Clone, Serialize)]
+pub struct TubeStats {
+    pub name: String,
+    pub urgent: u64,
+    pub ready: u64,
+    pub reserved: u64,
+    pub delayed: u64,
+    pub buried: u64,
+    pub total: u64,
+    pub waiting: u64,
+    pub paused: bool,
+}
+
+/// A job and its stats (from `peek*` + `stats-job`)
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: u64,
+    pub tube: String,
+    pub state: String,
+    pub priority: u32,
+    pub age: u64,
+    pub delay: u64,
+    pub ttr: u64,
+    pub time_left: u64,
+    pub reserves: u64,
+    pub timeouts: u64,
+    pub releases: u64,
+    pub buries: u64,
+    pub kicks: u64,
+    /// Job payload (lossy UTF-8)
+    pub body: String,
+}
+
+/// Which job to peek at in a tube
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeekState {
+    Ready,
+    Delayed,
+    Buried,
+}
+
+impl PeekState {
+    /// All peekable states, in display order
+    pub const ALL: [PeekState; 3] = [PeekState::Ready, PeekState::Delayed, PeekState::Buried];
+
+    /// Parse a state name
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "ready" => Ok(Self::Ready),
+            "delayed" => Ok(Self::Delayed),
+            "buried" => Ok(Self::Buried),
+            other => Err(format!(
+                "Unknown job state '{}'. Use 'ready', 'delayed', or 'buried'",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ready => "ready",
+            Self::Delayed => "delayed",
+            Self::Buried => "buried",
+        }
+    }
+
+    fn command(&self) -> &'static str {
+        match self {
+            Self::Ready => "peek-ready",
+            Self::Delayed => "peek-delayed",
+            Self::Buried => "peek-buried",
+        }
+    }
+}
+
+/// Validate a tube name before it's sent over the wire
+///
+/// Beanstalkd allows up to 200 bytes of letters, digits and `-+/;.$_()`, not
+/// starting with a hyphen. Anything else could break the line protocol.
+pub fn validate_tube_name(name: &str) -> Result<(), String> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-+/;.$_()".contains(c));
+    if name.is_empty() || name.len() > 200 || name.starts_with('-') || !valid_chars {
+        return Err(format!("Invalid tube name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Parse a simple YAML mapping as returned by `stats-*` commands
+fn parse_yaml_map(data: &str) -> HashMap<String, String> {
+    data.lines()
+        .filter(|line| *line != "---")
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Parse a simple YAML list as returned by `list-tubes`
+fn parse_yaml_list(data: &str) -> Vec<String> {
+    data.lines()
+        .filter_map(|line| line.strip_prefix("- "))
+        .map(|item| item.trim().to_string())
+        .collect()
+}
+
+fn map_u64(map: &HashMap<String, String>, key: &str) -> u64 {
+    map.get(key).and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+fn tube_stats_from_map(map: &HashMap<String, String>) -> TubeStats {
+    TubeStats {
+        name: map.get("name").cloned().unwrap_or_default(),
+        urgent: map_u64(map, "current-jobs-urgent"),
+        ready: map_u64(map, "current-jobs-ready"),
+        reserved: map_u64(map, "current-jobs-reserved"),
+        delayed: map_u64(map, "current-jobs-delayed"),
+        buried: map_u64(map, "current-jobs-buried"),
+        total: map_u64(map, "total-jobs"),
+        waiting: map_u64(map, "current-waiting"),
+        paused: map_u64(map, "pause-time-left") > 0,
+    }
+}
+
+fn job_info_from_map(id: u64, map: &HashMap<String, String>, body: &[u8]) -> JobInfo {
+    JobInfo {
+        id,
+        tube: map.get("tube").cloned().unwrap_or_default(),
+        state: map.get("state").cloned().unwrap_or_default(),
+        priority: map.get("pri").and_then(|v| v.parse().ok()).unwrap_or(0),
+        age: map_u64(map, "age"),
+        delay: map_u64(map, "delay"),
+        ttr: map_u64(map, "ttr"),
+        time_left: map_u64(map, "time-left"),
+        reserves: map_u64(map, "reserves"),
+        timeouts: map_u64(map, "timeouts"),
+        releases: map_u64(map, "releases"),
+        buries: map_u64(map, "buries"),
+        kicks: map_u64(map, "kicks"),
+        body: String::from_utf8_lossy(body).to_string(),
+    }
+}
+
+/// A single Beanstalkd connection
+struct Connection {
+    reader: BufReader<TcpStream>,
+}
+
+impl Connection {
+    fn open(port: u16) -> Result<Self, String> {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+            .map_err(|e| format!("Failed to connect to Beanstalkd on port {}: {}", port, e))?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+            .map_err(|e| format!("Failed to configure Beanstalkd connection: {}", e))?;
+
+        Ok(Self {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Send a command and return the first response line (without CRLF)
+    fn command(&mut self, command: &str) -> Result<String, String> {
+        self.reader
+            .get_mut()
+            .write_all(format!("{}\r\n", command).as_bytes())
+            .map_err(|e| format!("Failed to send '{}': {}", command, e))?;
+
+        let mut line = String::new();
+        self.reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read response to '{}': {}", command, e))?;
+        if line.is_empty() {
+            return Err("Beanstalkd closed the connection".to_string());
+        }
+
+        Ok(line.trim_end().to_string())
+    }
+
+    /// Read a `<bytes>`-long data block followed by CRLF
+    fn read_body(&mut self, bytes: usize) -> Result<Vec<u8>, String> {
+        let mut body = vec![0u8; bytes + 2];
+        self.reader
+            .read_exact(&mut body)
+            .map_err(|e| format!("Failed to read response body: {}", e))?;
+        body.truncate(bytes);
+        Ok(body)
+    }
+
+    /// Run a command that answers `OK <bytes>` with a YAML body
+    fn yaml_command(&mut self, command: &str) -> Result<Option<String>, String> {
+        let response = self.command(command)?;
+        if response == "NOT_FOUND" {
+            return Ok(None);
+        }
+        let bytes = response
+            .strip_prefix("OK ")
+            .and_then(|b| b.parse().ok())
+            .ok_or_else(|| unexpected(command, &response))?;
+        let body = self.read_body(bytes)?;
+        Ok(Some(String::from_utf8_lossy(&body).to_string()))
+    }
+
+    /// Run a peek-style command that answers `<prefix> <id> <bytes>` with a job body
+    fn job_command(
+        &mut self,
+        command: &str,
+        prefix: &str,
+    ) -> Result<Option<(u64, Vec<u8>)>, String> {
+        let response = self.command(command)?;
+        if response == "NOT_FOUND" {
+            return Ok(None);
+        }
+        let mut parts = response
+            .strip_prefix(prefix)
+            .map(|rest| rest.split_whitespace())
+            .ok_or_else(|| unexpected(command, &response))?;
+        let id = parts.next().and_then(|v| v.parse().ok());
+        let bytes = parts.next().and_then(|v| v.parse().ok());
+        let (Some(id), Some(bytes)) = (id, bytes) else {
+            return Err(unexpected(command, &response));
+        };
+        let body = self.read_body(bytes)?;
+        Ok(Some((id, body)))
+    }
+
+    fn use_tube(&mut self, tube: &str) -> Result<(), String> {
+        validate_tube_name(tube)?;
+        let command = format!("use {}", tube);
+        let response = self.command(&command)?;
+        if response != format!("USING {}", tube) {
+            return Err(unexpected(&command, &response));
+        }
+        Ok(())
+    }
+
+    fn job_stats(&mut self, id: u64) -> Result<Option<HashMap<String, String>>, String> {
+        Ok(self
+            .yaml_command(&format!("stats-job {}", id))?
+            .map(|yaml| parse_yaml_map(&yaml)))
+    }
+}
+
+fn unexpected(command: &str, response: &str) -> String {
+    format!(
+        "Unexpected Beanstalkd response to '{}': {}",
+        command, response
+    )
+}
+
+/// Manager for a single Beanstalkd instance
+pub struct BeanstalkdManager {
+    port: u16,
+}
+
+impl BeanstalkdManager {
+    pub fn new(port: u16) -> Self {
+        Self { port }
+    }
+
+    /// Create a manager for a Beanstalkd instance
+    pub fn for_instance(instance: &Instance) -> Result<Self, String> {
+        if instance.service_type != ServiceType::Beanstalkd {
+            return Err(format!(
+                "Instance '{}' is not a Beanstalkd instance",
+                instance.name
+            ));
+        }
+        Ok(Self::new(instance.port))
+    }
+
+    /// Get connection info for display
+    pub fn connection_info(&self) -> String {
+        format!("Beanstalkd at 127.0.0.1:{}", self.port)
+    }
+
+    /// List all tubes with their job counts
+    pub fn list_tubes(&self) -> Result<Vec<TubeStats>, String> {
+        let mut conn = Connection::open(self.port)?;
+        let tubes = conn
+            .yaml_command("list-tubes")?
+            .map(|yaml| parse_yaml_list(&yaml))
+            .unwrap_or_default();
+
+        let mut stats = Vec::with_capacity(tubes.len());
+        for tube in tubes {
+            // A tube can disappear between list-tubes and stats-tube
+            if let Some(yaml) = conn.yaml_command(&format!("stats-tube {}", tube))? {
+                stats.push(tube_stats_from_map(&parse_yaml_map(&yaml)));
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Peek at the next job in the given state of a tube
+    pub fn peek(&self, tube: &str, state: PeekState) -> Result<Option<JobInfo>, String> {
+        let mut conn = Connection::open(self.port)?;
+        conn.use_tube(tube)?;
+
+        let Some((id, body)) = conn.job_command(state.command(), "FOUND ")? else {
+            return Ok(None);
+        };
+        let stats = conn.job_stats(id)?.unwrap_or_default();
+        Ok(Some(job_info_from_map(id, &stats, &body)))
+    }
+
+    /// Get a job by ID
+    pub fn get_job(&self, id: u64) -> Result<JobInfo, String> {
+        let mut conn = Connection::open(self.port)?;
+        let (id, body) = conn
+            .job_command(&format!("peek {}", id), "FOUND ")?
+            .ok_or_else(|| format!("Job {} not found", id))?;
+        let stats = conn.job_stats(id)?.unwrap_or_default();
+        Ok(job_info_from_map(id, &stats, &body))
+    }
+
+    /// Bury a ready or delayed job so workers stop picking it up
+    ///
+    /// The job is reserved on this connection first (beanstalkd only buries
+    /// reserved jobs) and keeps its original priority.
+    pub fn bury_job(&self, id: u64) -> Result<(), String> {
+        let mut conn = Connection::open(self.port)?;
+        let stats = conn
+            .job_stats(id)?
+            .ok_or_else(|| format!("Job {} not found", id))?;
+        let priority = stats.get("pri").cloned().unwrap_or_else(|| "0".to_string());
+
+        match stats.get("state").map(String::as_str) {
+            Some("buried") => return Err(format!("Job {} is already buried", id)),
+            Some("reserved") => {
+                return Err(format!(
+                    "Job {} is reserved by a worker and can't be buried",
+                    id
+                ))
+            }
+            _ => {}
+        }
+
+        conn.job_command(&format!("reserve-job {}", id), "RESERVED ")?
+            .ok_or_else(|| format!("Job {} could not be reserved", id))?;
+
+        let command = format!("bury {} {}", id, priority);
+        match conn.command(&command)?.as_str() {
+            "BURIED" => Ok(()),
+            "NOT_FOUND" => Err(format!("Job {} not found", id)),
+            other => Err(unexpected(&command, other)),
+        }
+    }
+
+    /// Kick a single buried or delayed job back into the ready queue
+    pub fn kick_job(&self, id: u64) -> Result<(), String> {
+        let mut conn = Connection::open(self.port)?;
+        let command = format!("kick-job {}", id);
+        match conn.command(&command)?.as_str() {
+            "KICKED" => Ok(()),
+            "NOT_FOUND" => Err(format!("Job {} not found or not buried/delayed", id)),
+            other => Err(unexpected(&command, other)),
+        }
+    }
+
+    /// Kick up to `bound` buried (or, if none are buried, delayed) jobs in a tube
+    ///
+    /// Returns the number of jobs kicked.
+    pub fn kick_tube(&self, tube: &str, bound: u32) -> Result<u64, String> {
+        let mut conn = Connection::open(self.port)?;
+        conn.use_tube(tube)?;
+
+        let command = format!("kick {}", bound);
+        let response = conn.command(&command)?;
+        response
+            .strip_prefix("KICKED ")
+            .and_then(|count| count.parse().ok())
+            .ok_or_else(|| unexpected(&command, &response))
+    }
+}
+
+/// Find all Beanstalkd instances in Burd config
+pub fn find_beanstalkd_instances(config: &Config) -> Vec<&Instance> {
+    config
+        .instances
+        .iter()
+        .filter(|i| i.service_type == ServiceType::Beanstalkd)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tube_stats() {
+        let yaml = "---\nname: emails\ncurrent-jobs-urgent: 1\ncurrent-jobs-ready: 3\n\
+                    current-jobs-reserved: 0\ncurrent-jobs-delayed: 2\ncurrent-jobs-buried: 4\n\
+                    total-jobs: 42\ncurrent-waiting: 1\npause-time-left: 0\n";
+        let stats = tube_stats_from_map(&parse_yaml_map(yaml));
+
+        assert_eq!(stats.name, "emails");
+        assert_eq!(stats.ready, 3);
+        assert_eq!(stats.delayed, 2);
+        assert_eq!(stats.buried, 4);
+        assert_eq!(stats.total, 42);
+        assert!(!stats.paused);
+    }
+
+    #[test]
+    fn test_parse_tube_list() {
+        assert_eq!(
+            parse_yaml_list("---\n- default\n- emails\n"),
+            vec!["default".to_string(), "emails".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_validate_tube_name() {
+        assert!(validate_tube_name("default").is_ok());
+        assert!(validate_tube_name("app.emails-high_(1)").is_ok());
+        assert!(validate_tube_name("").is_err());
+        assert!(validate_tube_name("-leading").is_err());
+        assert!(validate_tube_name("bad name").is_err());
+        assert!(validate_tube_name("x\r\nkick 1000").is_err());
+    }
+}
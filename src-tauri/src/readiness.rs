@@ -0,0 +1,97 @@
+//! Instance readiness
+//!
+//! `ProcessManager::start` returns once the process is up, but a database may
+//! still be recovering or creating its system tables. Actions that depend on
+//! a freshly started instance (creating a database, starting the app that
+//! connects to it) wait here until the service's health check passes.
+
+use crate::config::ServiceType;
+use crate::services::{get_service, HealthCheck};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+/// How long to wait for an instance to become ready by default
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay between health checks while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn tcp_open(port: u16, timeout: Duration) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&addr, timeout).is_ok()
+}
+
+/// Whether the service on `port` passes its health check
+pub async fn is_healthy(port: u16, service_type: ServiceType) -> bool {
+    match get_service(service_type).health_check() {
+        HealthCheck::Http { path } => {
+            let url = format!("http://127.0.0.1:{}{}", port, path);
+            reqwest::get(&url)
+                .await
+                .map(|r| r.status().is_success())
+                .unwrap_or(false)
+        }
+        HealthCheck::Tcp => tcp_open(port, Duration::from_secs(2)),
+    }
+}
+
+/// Wait until the service on `port` passes its health check
+pub async fn wait_until_healthy(
+    port: u16,
+    service_type: ServiceType,
+    timeout: Duration,
+) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    while !is_healthy(port, service_type).await {
+        if Instant::now() >= deadline {
+            return Err(not_ready(service_type, port, timeout));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// Blocking wait until something accepts connections on `port`
+///
+/// For the CLI, which talks to databases directly without a runtime.
+pub fn wait_until_listening(
+    port: u16,
+    service_type: ServiceType,
+    timeout: Duration,
+) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    while !tcp_open(port, Duration::from_millis(500)) {
+        if Instant::now() >= deadline {
+            return Err(not_ready(service_type, port, timeout));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+fn not_ready(service_type: ServiceType, port: u16, timeout: Duration) -> String {
+    format!(
+        "{} on port {} did not become ready within {}s. Check its logs.",
+        service_type.display_name(),
+        port,
+        timeout.as_secs()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_wait_until_listening() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(wait_until_listening(port, ServiceType::Redis, Duration::from_secs(1)).is_ok());
+
+        drop(listener);
+        let err =
+            wait_until_listening(port, ServiceType::Redis, Duration::from_secs(1)).unwrap_err();
+        assert!(err.contains("did not become ready within 1s"));
+    }
+}
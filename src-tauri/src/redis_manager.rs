@@ -0,0 +1,464 @@
+//! Redis Manager Module
+//!
+//! Browses Redis and Valkey instances over RESP: lists databases and the keys
+//! matching a pattern with their types and TTLs, deletes keys, and flushes
+//! databases. Backs the key explorer in the app.
+//!
+//! Key names are shown as (lossy) UTF-8, so keys that aren't valid UTF-8 can
+//! be listed but not deleted by name.
+
+use crate::config::{Instance, ServiceType};
+use crate::services::key_value_service::KeyValueRole;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// Connection and read/write timeout for Redis commands
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default number of keys returned by one listing
+pub const DEFAULT_KEY_LIMIT: usize = 200;
+
+/// Largest number of keys returned by one listing
+pub const MAX_KEY_LIMIT: usize = 5000;
+
+/// Keys requested per `SCAN` call
+const SCAN_COUNT: usize = 500;
+
+/// Number of databases when `CONFIG GET databases` isn't available
+const DEFAULT_DATABASES: u32 = 16;
+
+/// A database with its key counts (from `INFO keyspace`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DatabaseInfo {
+    pub index: u32,
+    pub keys: u64,
+    /// Keys with a TTL
+    pub expires: u64,
+}
+
+/// A key with its type and TTL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyInfo {
+    pub name: String,
+    /// `string`, `list`, `set`, `zset`, `hash`, `stream`, ...
+    pub key_type: String,
+    /// Seconds until the key expires, `None` if it never does
+    pub ttl: Option<i64>,
+}
+
+/// Keys matching a pattern, sorted by name
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyScan {
+    pub keys: Vec<KeyInfo>,
+    /// More keys match than were returned
+    pub truncated: bool,
+}
+
+/// A RESP2 reply
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Reply {
+    Status(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+impl Reply {
+    fn into_text(self) -> Option<String> {
+        match self {
+            Reply::Status(text) => Some(text),
+            Reply::Bulk(Some(bytes)) => Some(String::from_utf8_lossy(&bytes).to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Encode a command as a RESP array of bulk strings
+fn encode_command(args: &[&str]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        command.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        command.extend_from_slice(arg.as_bytes());
+        command.extend_from_slice(b"\r\n");
+    }
+    command
+}
+
+fn read_reply(reader: &mut impl BufRead) -> Result<Reply, String> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read Redis response: {}", e))?;
+    if line.is_empty() {
+        return Err("Redis closed the connection".to_string());
+    }
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (kind, rest) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+    let length = || {
+        rest.parse::<i64>()
+            .map_err(|_| format!("Unexpected Redis response: {}", line))
+    };
+
+    match kind {
+        "+" => Ok(Reply::Status(rest.to_string())),
+        "-" => Ok(Reply::Error(rest.to_string())),
+        ":" => Ok(Reply::Integer(length()?)),
+        "$" => {
+            let Ok(len) = usize::try_from(length()?) else {
+                return Ok(Reply::Bulk(None));
+            };
+            let mut bytes = vec![0u8; len + 2];
+            reader
+                .read_exact(&mut bytes)
+                .map_err(|e| format!("Failed to read Redis response: {}", e))?;
+            bytes.truncate(len);
+            Ok(Reply::Bulk(Some(bytes)))
+        }
+        "*" => {
+            let Ok(len) = usize::try_from(length()?) else {
+                return Ok(Reply::Array(None));
+            };
+            let items = (0..len)
+                .map(|_| read_reply(reader))
+                .collect::<Result<_, _>>()?;
+            Ok(Reply::Array(Some(items)))
+        }
+        _ => Err(format!("Unexpected Redis response: {}", line)),
+    }
+}
+
+/// Parse `INFO keyspace` lines like `db0:keys=3,expires=1,avg_ttl=0`
+fn parse_keyspace(info: &str) -> Vec<DatabaseInfo> {
+    info.lines()
+        .filter_map(|line| {
+            let (db, stats) = line.trim().split_once(':')?;
+            let index = db.strip_prefix("db")?.parse().ok()?;
+            let stat = |name: &str| {
+                stats
+                    .split(',')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| *key == name)
+                    .and_then(|(_, value)| value.parse().ok())
+                    .unwrap_or(0)
+            };
+            Some(DatabaseInfo {
+                index,
+                keys: stat("keys"),
+                expires: stat("expires"),
+            })
+        })
+        .collect()
+}
+
+/// Check a key listing limit
+pub fn validate_key_limit(limit: usize) -> Result<(), String> {
+    if !(1..=MAX_KEY_LIMIT).contains(&limit) {
+        return Err(format!("Key limit must be between 1 and {}", MAX_KEY_LIMIT));
+    }
+    Ok(())
+}
+
+/// A single Redis connection, authenticated and on the requested database
+struct Connection {
+    reader: BufReader<TcpStream>,
+}
+
+impl Connection {
+    fn open(port: u16, password: Option<&str>, db: u32) -> Result<Self, String> {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+            .map_err(|e| format!("Failed to connect to Redis on port {}: {}", port, e))?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+            .map_err(|e| format!("Failed to configure Redis connection: {}", e))?;
+
+        let mut conn = Self {
+            reader: BufReader::new(stream),
+        };
+        if let Some(password) = password {
+            conn.command(&["AUTH", password])?;
+        }
+        if db != 0 {
+            conn.command(&["SELECT", &db.to_string()])?;
+        }
+        Ok(conn)
+    }
+
+    /// Send commands in one write and read their replies in order
+    fn pipeline(&mut self, commands: &[Vec<&str>]) -> Result<Vec<Reply>, String> {
+        let request: Vec<u8> = commands.iter().flat_map(|c| encode_command(c)).collect();
+        self.reader
+            .get_mut()
+            .write_all(&request)
+            .map_err(|e| format!("Failed to send Redis command: {}", e))?;
+        commands
+            .iter()
+            .map(|_| read_reply(&mut self.reader))
+            .collect()
+    }
+
+    /// Run a command, turning an error reply into `Err`
+    fn command(&mut self, args: &[&str]) -> Result<Reply, String> {
+        match self.pipeline(&[args.to_vec()])?.pop() {
+            Some(Reply::Error(error)) => Err(format!("{} failed: {}", args[0], error)),
+            Some(reply) => Ok(reply),
+            None => Err(format!("No response to {}", args[0])),
+        }
+    }
+}
+
+/// Manager for a single Redis or Valkey instance
+pub struct RedisManager {
+    port: u16,
+    password: Option<String>,
+}
+
+impl RedisManager {
+    /// Create a manager for a Redis or Valkey instance
+    pub fn for_instance(instance: &Instance) -> Result<Self, String> {
+        if !matches!(
+            instance.service_type,
+            ServiceType::Redis | ServiceType::Valkey
+        ) {
+            return Err(format!(
+                "Instance '{}' is not a Redis or Valkey instance",
+                instance.name
+            ));
+        }
+        if KeyValueRole::from_instance(instance) == KeyValueRole::Sentinel {
+            return Err(format!(
+                "Instance '{}' runs in Sentinel mode and holds no keys",
+                instance.name
+            ));
+        }
+
+        let password = instance
+            .config
+            .get("password")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .map(str::to_string);
+        Ok(Self {
+            port: instance.port,
+            password,
+        })
+    }
+
+    /// Get connection info for display
+    pub fn connection_info(&self) -> String {
+        format!("Redis at 127.0.0.1:{}", self.port)
+    }
+
+    fn connect(&self, db: u32) -> Result<Connection, String> {
+        Connection::open(self.port, self.password.as_deref(), db)
+    }
+
+    /// List every database with its key counts, empty ones included
+    pub fn list_databases(&self) -> Result<Vec<DatabaseInfo>, String> {
+        let mut conn = self.connect(0)?;
+        let info = conn
+            .command(&["INFO", "keyspace"])?
+            .into_text()
+            .unwrap_or_default();
+        let used = parse_keyspace(&info);
+
+        // CONFIG can be disabled or renamed
+        let count = match conn.command(&["CONFIG", "GET", "databases"]) {
+            Ok(Reply::Array(Some(items))) => items
+                .into_iter()
+                .nth(1)
+                .and_then(Reply::into_text)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_DATABASES),
+            _ => DEFAULT_DATABASES,
+        };
+
+        Ok((0..count)
+            .map(|index| {
+                used.iter()
+                    .find(|db| db.index == index)
+                    .cloned()
+                    .unwrap_or(DatabaseInfo {
+                        index,
+                        keys: 0,
+                        expires: 0,
+                    })
+            })
+            .collect())
+    }
+
+    /// List up to `limit` keys matching a glob-style pattern, with their types and TTLs
+    pub fn list_keys(&self, db: u32, pattern: &str, limit: usize) -> Result<KeyScan, String> {
+        validate_key_limit(limit)?;
+        let pattern = if pattern.is_empty() { "*" } else { pattern };
+        let mut conn = self.connect(db)?;
+
+        // SCAN may return a key more than once
+        let mut names = BTreeSet::new();
+        let mut cursor = "0".to_string();
+        let count = SCAN_COUNT.to_string();
+        loop {
+            let reply = conn.command(&["SCAN", &cursor, "MATCH", pattern, "COUNT", &count])?;
+            let Reply::Array(Some(mut parts)) = reply else {
+                return Err("Unexpected response to SCAN".to_string());
+            };
+            let (Some(Reply::Array(Some(batch))), Some(next)) =
+                (parts.pop(), parts.pop().and_then(Reply::into_text))
+            else {
+                return Err("Unexpected response to SCAN".to_string());
+            };
+            names.extend(batch.into_iter().filter_map(Reply::into_text));
+            cursor = next;
+            if cursor == "0" || names.len() > limit {
+                break;
+            }
+        }
+        let truncated = names.len() > limit || cursor != "0";
+        let names: Vec<String> = names.into_iter().take(limit).collect();
+
+        let commands: Vec<Vec<&str>> = names
+            .iter()
+            .flat_map(|name| [vec!["TYPE", name.as_str()], vec!["TTL", name.as_str()]])
+            .collect();
+        let replies = conn.pipeline(&commands)?;
+
+        let keys = names
+            .into_iter()
+            .zip(replies.chunks(2))
+            .filter_map(|(name, replies)| {
+                let key_type = replies[0].clone().into_text()?;
+                let ttl = match replies[1] {
+                    Reply::Integer(ttl) if ttl >= 0 => Some(ttl),
+                    _ => None,
+                };
+                // Expired or deleted since the scan
+                (key_type != "none").then_some(KeyInfo {
+                    name,
+                    key_type,
+                    ttl,
+                })
+            })
+            .collect();
+
+        Ok(KeyScan { keys, truncated })
+    }
+
+    /// Delete keys, returning how many existed
+    pub fn delete_keys(&self, db: u32, keys: &[String]) -> Result<u64, String> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let mut args = vec!["DEL"];
+        args.extend(keys.iter().map(String::as_str));
+
+        match self.connect(db)?.command(&args)? {
+            Reply::Integer(deleted) => Ok(deleted.max(0) as u64),
+            other => Err(format!("Unexpected response to DEL: {:?}", other)),
+        }
+    }
+
+    /// Delete every key of one database
+    pub fn flush_database(&self, db: u32) -> Result<(), String> {
+        self.connect(db)?.command(&["FLUSHDB"]).map(|_| ())
+    }
+
+    /// Delete every key of every database
+    pub fn flush_all(&self) -> Result<(), String> {
+        self.connect(0)?.command(&["FLUSHALL"]).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    fn parse(data: &[u8]) -> Result<Reply, String> {
+        read_reply(&mut &data[..])
+    }
+
+    #[test]
+    fn test_encode_command() {
+        assert_eq!(
+            encode_command(&["SET", "greeting", "hello world"]),
+            b"*3\r\n$3\r\nSET\r\n$8\r\ngreeting\r\n$11\r\nhello world\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_read_reply() {
+        assert_eq!(parse(b"+OK\r\n"), Ok(Reply::Status("OK".to_string())));
+        assert_eq!(
+            parse(b"-ERR unknown command\r\n"),
+            Ok(Reply::Error("ERR unknown command".to_string()))
+        );
+        assert_eq!(parse(b":-2\r\n"), Ok(Reply::Integer(-2)));
+        assert_eq!(parse(b"$-1\r\n"), Ok(Reply::Bulk(None)));
+        assert_eq!(
+            parse(b"*2\r\n$2\r\n17\r\n*2\r\n$5\r\nuser:\r\n$0\r\n\r\n"),
+            Ok(Reply::Array(Some(vec![
+                Reply::Bulk(Some(b"17".to_vec())),
+                Reply::Array(Some(vec![
+                    Reply::Bulk(Some(b"user:".to_vec())),
+                    Reply::Bulk(Some(Vec::new())),
+                ])),
+            ])))
+        );
+        assert!(parse(b"").is_err());
+        assert!(parse(b"?what\r\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_keyspace() {
+        let info =
+            "# Keyspace\r\ndb0:keys=3,expires=1,avg_ttl=0\r\ndb4:keys=12,expires=0,avg_ttl=0\r\n";
+        assert_eq!(
+            parse_keyspace(info),
+            vec![
+                DatabaseInfo {
+                    index: 0,
+                    keys: 3,
+                    expires: 1,
+                },
+                DatabaseInfo {
+                    index: 4,
+                    keys: 12,
+                    expires: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_for_instance() {
+        let redis = InstanceBuilder::new()
+            .service_type(ServiceType::Redis)
+            .config(serde_json::json!({ "password": "secret" }))
+            .build();
+        let manager = RedisManager::for_instance(&redis).unwrap();
+        assert_eq!(manager.password.as_deref(), Some("secret"));
+
+        let sentinel = InstanceBuilder::new()
+            .service_type(ServiceType::Valkey)
+            .config(serde_json::json!({ "role": "sentinel" }))
+            .build();
+        assert!(RedisManager::for_instance(&sentinel).is_err());
+
+        let mariadb = InstanceBuilder::new()
+            .service_type(ServiceType::MariaDB)
+            .build();
+        assert!(RedisManager::for_instance(&mariadb).is_err());
+    }
+
+    #[test]
+    fn test_validate_key_limit() {
+        assert!(validate_key_limit(DEFAULT_KEY_LIMIT).is_ok());
+        assert!(validate_key_limit(0).is_err());
+        assert!(validate_key_limit(MAX_KEY_LIMIT + 1).is_err());
+    }
+}
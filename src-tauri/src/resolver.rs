@@ -1,24 +1,55 @@
 //! macOS Resolver Integration
 //!
 //! Manages the `/etc/resolver/<tld>` file that tells macOS to route
-//! custom TLD domain queries to our local DNS server.
+//! custom TLD domain queries to our local DNS server (the macOS
+//! [`DnsResolver`]).
 
 use crate::domain::DEFAULT_DNS_PORT;
 use crate::helper_client::{HelperClient, HelperRequest};
+use crate::platform::{DnsResolver, ResolverConfig};
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
+/// `/etc/resolver` files
+pub struct MacResolver;
+
+impl DnsResolver for MacResolver {
+    fn install(&self, tld: &str, dns_port: u16) -> Result<(), String> {
+        install(tld, dns_port)
+    }
+
+    fn uninstall(&self, tld: &str) -> Result<(), String> {
+        uninstall(tld)
+    }
+
+    fn is_installed(&self, tld: &str) -> bool {
+        is_installed(tld)
+    }
+
+    fn config_path(&self, tld: &str) -> PathBuf {
+        resolver_file_path(tld)
+    }
+
+    fn current_config(&self, tld: &str) -> Option<ResolverConfig> {
+        get_current_config(tld)
+    }
+
+    fn flush_cache(&self) -> Result<(), String> {
+        flush_dns_cache()
+    }
+}
+
 /// Path to the resolver directory on macOS
 const RESOLVER_DIR: &str = "/etc/resolver";
 
 /// Get the path to the resolver file for a TLD
-pub fn resolver_file_path(tld: &str) -> PathBuf {
+fn resolver_file_path(tld: &str) -> PathBuf {
     PathBuf::from(RESOLVER_DIR).join(tld)
 }
 
 /// Check if a resolver file is installed for the given TLD
-pub fn is_installed(tld: &str) -> bool {
+fn is_installed(tld: &str) -> bool {
     resolver_file_path(tld).exists()
 }
 
@@ -36,7 +67,7 @@ fn resolver_content(tld: &str, dns_port: u16) -> String {
 /// Install the resolver file using osascript for privilege escalation
 ///
 /// This will prompt the user for their password via the standard macOS dialog.
-pub fn install(tld: &str, dns_port: u16) -> Result<(), String> {
+fn install(tld: &str, dns_port: u16) -> Result<(), String> {
     let path = resolver_file_path(tld);
 
     // Check if already installed with correct port
@@ -89,7 +120,7 @@ pub fn install(tld: &str, dns_port: u16) -> Result<(), String> {
 }
 
 /// Uninstall the resolver file
-pub fn uninstall(tld: &str) -> Result<(), String> {
+fn uninstall(tld: &str) -> Result<(), String> {
     if !is_installed(tld) {
         return Ok(()); // Already uninstalled
     }
@@ -129,33 +160,30 @@ pub fn uninstall(tld: &str) -> Result<(), String> {
 }
 
 /// Get the current resolver configuration if installed
-pub fn get_current_config(tld: &str) -> Option<ResolverConfig> {
+fn get_current_config(tld: &str) -> Option<ResolverConfig> {
     let content = fs::read_to_string(resolver_file_path(tld)).ok()?;
 
     let mut port = DEFAULT_DNS_PORT;
+    let mut nameserver = None;
 
     for line in content.lines() {
         let line = line.trim();
-        if line.starts_with("port ") {
+        if let Some(value) = line.strip_prefix("nameserver ") {
+            nameserver = Some(value.trim().to_string());
+        } else if line.starts_with("port ") {
             if let Ok(p) = line.strip_prefix("port ").unwrap_or("").trim().parse() {
                 port = p;
             }
         }
     }
 
-    Some(ResolverConfig { port })
-}
-
-/// Resolver configuration
-#[derive(Debug, Clone)]
-pub struct ResolverConfig {
-    pub port: u16,
+    Some(ResolverConfig { nameserver, port })
 }
 
 /// Flush the DNS cache on macOS
 ///
 /// This ensures the system picks up changes to the resolver configuration.
-pub fn flush_dns_cache() -> Result<(), String> {
+fn flush_dns_cache() -> Result<(), String> {
     // macOS uses dscacheutil and mDNSResponder
     Command::new("dscacheutil")
         .arg("-flushcache")
@@ -0,0 +1,442 @@
+//! Database Seeding
+//!
+//! Resets a database instance's demo data with a project-defined seed: an
+//! SQL file imported into a freshly recreated database, a command such as
+//! `php artisan migrate:fresh --seed` run in the project, or both (the import
+//! first). Seeds run on demand or on the instance's cron schedule, e.g.
+//! `0 3 * * *` for a demo environment that resets nightly.
+//!
+//! The command runs through `sh -c` in the project directory with `DB_HOST`,
+//! `DB_PORT` and `DB_DATABASE` (and Bedrock's `DB_NAME`) pointing at the
+//! instance. Laravel, Symfony and Bedrock let real environment variables win
+//! over .env, so a seed can't reset some other database by accident. Burd's
+//! PHP and the database client tools come first on `PATH`.
+//!
+//! Every run is recorded in the instance's activity timeline, which is also
+//! how a scheduled run that already happened (in the app or the daemon) is
+//! recognized.
+
+use chrono::{Local, NaiveDateTime};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::activity::{self, ActivityKind};
+use crate::backup::expand_home;
+use crate::commands::AppState;
+use crate::config::{get_versioned_binary_dir, Instance, SeedSettings, ServiceType};
+use crate::cron::CronSchedule;
+use crate::db_manager::{create_manager_for_instance, sanitize_db_name};
+use crate::error::LockExt;
+use crate::lock;
+use crate::tinker::get_php_binary;
+
+/// Delay between checks whether a scheduled seed is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Lines of command output kept in the result
+const OUTPUT_LINES: usize = 20;
+
+/// Activity events searched for the latest seed run
+const ACTIVITY_LOOKBACK: usize = 100;
+
+/// Instances with a seed in progress, so runs never overlap
+static RUNNING: Lazy<Mutex<HashSet<Uuid>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Outcome of a seed run
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedResult {
+    pub database: String,
+    /// Whether the SQL file was imported
+    pub imported: bool,
+    /// Last lines of the command's output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Marks a seed as running until dropped
+struct RunGuard(Uuid);
+
+impl RunGuard {
+    fn acquire(instance: &Instance) -> Result<Self, String> {
+        let mut running = RUNNING
+            .lock()
+            .map_err(|_| "Failed to acquire seed lock".to_string())?;
+        if !running.insert(instance.id) {
+            return Err(format!("'{}' is already being seeded", instance.name));
+        }
+        Ok(Self(instance.id))
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if let Ok(mut running) = RUNNING.lock() {
+            running.remove(&self.0);
+        }
+    }
+}
+
+/// Validate and normalize seed settings before they are saved
+pub fn validate_settings(instance: &Instance, seed: SeedSettings) -> Result<SeedSettings, String> {
+    if !matches!(
+        instance.service_type,
+        ServiceType::MariaDB | ServiceType::PostgreSQL
+    ) {
+        return Err(format!(
+            "Seeds are only supported for MariaDB and PostgreSQL instances, not {}",
+            instance.service_type.display_name()
+        ));
+    }
+
+    let database = seed.database.trim();
+    if sanitize_db_name(database)? != database {
+        return Err(format!("Invalid database name '{}'", database));
+    }
+
+    let clean = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let seed = SeedSettings {
+        database: database.to_string(),
+        sql_file: clean(seed.sql_file),
+        command: clean(seed.command),
+        project_dir: clean(seed.project_dir),
+        schedule: clean(seed.schedule),
+    };
+
+    if seed.sql_file.is_none() && seed.command.is_none() {
+        return Err("A seed needs an SQL file, a command, or both".to_string());
+    }
+    if let Some(file) = &seed.sql_file {
+        if !expand_home(file).is_file() {
+            return Err(format!("SQL file not found: {}", file));
+        }
+    }
+    if seed.command.is_some() {
+        let dir = seed
+            .project_dir
+            .as_deref()
+            .ok_or("A seed command needs a project directory")?;
+        if !expand_home(dir).is_dir() {
+            return Err(format!("Project directory not found: {}", dir));
+        }
+    }
+    if let Some(schedule) = &seed.schedule {
+        CronSchedule::parse(schedule).map_err(|e| format!("Invalid schedule: {}", e))?;
+    }
+
+    Ok(seed)
+}
+
+/// The last `lines` lines of some output
+fn tail(output: &str, lines: usize) -> String {
+    let all: Vec<&str> = output.trim_end().lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+/// `PATH` with Burd's PHP and the instance's client tools first
+fn command_path(instance: &Instance) -> Result<std::ffi::OsString, String> {
+    let mut path: Vec<PathBuf> = Vec::new();
+    if let Some(php_dir) = get_php_binary()
+        .ok()
+        .and_then(|p| p.parent().map(PathBuf::from))
+    {
+        path.push(php_dir);
+    }
+    let bin_dir = get_versioned_binary_dir(instance.service_type, &instance.version)?;
+    path.push(bin_dir.join("bin"));
+    path.push(bin_dir);
+    if let Some(existing) = std::env::var_os("PATH") {
+        path.extend(std::env::split_paths(&existing));
+    }
+    std::env::join_paths(path).map_err(|e| format!("Failed to build PATH for the seed: {}", e))
+}
+
+/// Run the seed command, returning the end of its output
+fn run_command(instance: &Instance, seed: &SeedSettings, command: &str) -> Result<String, String> {
+    let dir = seed
+        .project_dir
+        .as_deref()
+        .map(expand_home)
+        .ok_or("A seed command needs a project directory")?;
+
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(&dir)
+        .env("PATH", command_path(instance)?)
+        .env("DB_HOST", "127.0.0.1")
+        .env("DB_PORT", instance.port.to_string())
+        .env("DB_DATABASE", &seed.database)
+        .env("DB_NAME", &seed.database)
+        .output()
+        .map_err(|e| format!("Failed to run seed command: {}", e))?;
+
+    let text = tail(
+        &format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        OUTPUT_LINES,
+    );
+    if !output.status.success() {
+        return Err(format!(
+            "Seed command failed ({}):\n{}",
+            output.status, text
+        ));
+    }
+    Ok(text)
+}
+
+fn seed_database(instance: &Instance, seed: &SeedSettings) -> Result<SeedResult, String> {
+    let started = Instant::now();
+
+    if let Some(file) = &seed.sql_file {
+        let manager = create_manager_for_instance(instance)?;
+        if manager.database_exists(&seed.database)? {
+            manager.drop_database(&seed.database)?;
+        }
+        manager.create_database(&seed.database)?;
+        manager.import_sql(&seed.database, &expand_home(file))?;
+    }
+
+    let output = match &seed.command {
+        Some(command) => Some(run_command(instance, seed, command)?),
+        None => None,
+    };
+
+    Ok(SeedResult {
+        database: seed.database.clone(),
+        imported: seed.sql_file.is_some(),
+        output,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Run an instance's seed now and record the outcome in its timeline
+///
+/// Blocks until the import and command finish.
+pub fn run_seed(instance: &Instance, running: bool) -> Result<SeedResult, String> {
+    let seed = instance
+        .seed
+        .as_ref()
+        .ok_or_else(|| format!("'{}' has no seed configured", instance.name))?;
+    if !running {
+        return Err(format!(
+            "'{}' is not running. Start it before seeding.",
+            instance.name
+        ));
+    }
+    let _guard = RunGuard::acquire(instance)?;
+
+    let result = seed_database(instance, seed);
+    match &result {
+        Ok(_) => activity::record(
+            &instance.id,
+            ActivityKind::Seeded,
+            Some(seed.database.clone()),
+        ),
+        // The first line names the failure; the command output follows it
+        Err(e) => activity::record(
+            &instance.id,
+            ActivityKind::SeedFailed,
+            e.lines().next().map(str::to_string),
+        ),
+    }
+    result
+}
+
+/// When an instance was last seeded (or a seed failed), in local time
+fn last_seeded(instance: &Instance) -> Option<NaiveDateTime> {
+    activity::load(&instance.id, ACTIVITY_LOOKBACK)
+        .ok()?
+        .into_iter()
+        .find(|e| matches!(e.kind, ActivityKind::Seeded | ActivityKind::SeedFailed))
+        .map(|e| e.at.with_timezone(&Local).naive_local())
+}
+
+/// Whether a scheduled time passed after `since` that no run has covered yet
+fn is_due(
+    schedule: &CronSchedule,
+    since: NaiveDateTime,
+    last_seeded: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> bool {
+    let from = last_seeded.map_or(since, |last| last.max(since));
+    schedule.next_after(from).is_some_and(|next| next <= now)
+}
+
+/// Run the seeds that became due between `since` and `now`
+fn run_due(state: &AppState, since: NaiveDateTime, now: NaiveDateTime) {
+    let Ok(config) = lock!(state.config_store).and_then(|store| store.load()) else {
+        return;
+    };
+
+    for instance in &config.instances {
+        let schedule = instance
+            .seed
+            .as_ref()
+            .and_then(|s| s.schedule.as_deref())
+            .and_then(|s| CronSchedule::parse(s).ok());
+        let Some(schedule) = schedule else {
+            continue;
+        };
+        if !is_due(&schedule, since, last_seeded(instance), now) {
+            continue;
+        }
+
+        let running = match lock!(state.process_manager) {
+            Ok(process_manager) => process_manager.get_status(instance).running,
+            Err(_) => continue,
+        };
+        match run_seed(instance, running) {
+            Ok(result) => eprintln!("Seeded '{}' ({} ms)", instance.name, result.duration_ms),
+            Err(e) => eprintln!("Scheduled seed of '{}' failed: {}", instance.name, e),
+        }
+    }
+}
+
+/// Run scheduled seeds forever
+///
+/// Only schedule times passed while running count; a seed missed while Burd
+/// was closed waits for its next time rather than resetting data on launch.
+pub async fn run(state: AppState) {
+    let mut since = Local::now().naive_local();
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let now = Local::now().naive_local();
+        let state = state.clone();
+        let _ = tokio::task::spawn_blocking(move || run_due(&state, since, now)).await;
+        since = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn seed(database: &str) -> SeedSettings {
+        SeedSettings {
+            database: database.to_string(),
+            sql_file: None,
+            command: None,
+            project_dir: None,
+            schedule: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let sql = dir.path().join("demo.sql");
+        std::fs::write(&sql, "SELECT 1;").unwrap();
+        let mariadb = InstanceBuilder::new()
+            .service_type(ServiceType::MariaDB)
+            .build();
+
+        let valid = validate_settings(
+            &mariadb,
+            SeedSettings {
+                sql_file: Some(format!(" {} ", sql.display())),
+                command: Some("php artisan db:seed".to_string()),
+                project_dir: Some(dir.path().display().to_string()),
+                schedule: Some("".to_string()),
+                ..seed(" demo ")
+            },
+        )
+        .unwrap();
+        assert_eq!(valid.database, "demo");
+        assert_eq!(valid.sql_file, Some(sql.display().to_string()));
+        assert_eq!(valid.schedule, None);
+
+        let invalid = |settings: SeedSettings| validate_settings(&mariadb, settings).is_err();
+        assert!(invalid(seed("demo")));
+        assert!(invalid(SeedSettings {
+            sql_file: Some(sql.display().to_string()),
+            ..seed("demo; DROP")
+        }));
+        assert!(invalid(SeedSettings {
+            sql_file: Some(dir.path().join("missing.sql").display().to_string()),
+            ..seed("demo")
+        }));
+        assert!(invalid(SeedSettings {
+            command: Some("php artisan db:seed".to_string()),
+            ..seed("demo")
+        }));
+        assert!(invalid(SeedSettings {
+            sql_file: Some(sql.display().to_string()),
+            schedule: Some("nightly".to_string()),
+            ..seed("demo")
+        }));
+
+        let redis = InstanceBuilder::new()
+            .service_type(ServiceType::Redis)
+            .build();
+        let err = validate_settings(
+            &redis,
+            SeedSettings {
+                sql_file: Some(sql.display().to_string()),
+                ..seed("demo")
+            },
+        )
+        .unwrap_err();
+        assert!(err.contains("only supported"), "{}", err);
+    }
+
+    #[test]
+    fn test_is_due() {
+        let nightly = CronSchedule::parse("0 3 * * *").unwrap();
+
+        // 03:00 passed between the last two checks
+        assert!(is_due(
+            &nightly,
+            at("2026-10-18 02:59:40"),
+            None,
+            at("2026-10-18 03:00:10")
+        ));
+        assert!(!is_due(
+            &nightly,
+            at("2026-10-18 03:00:10"),
+            None,
+            at("2026-10-18 03:00:40")
+        ));
+
+        // The daemon already ran it
+        assert!(!is_due(
+            &nightly,
+            at("2026-10-18 02:59:40"),
+            Some(at("2026-10-18 03:00:05")),
+            at("2026-10-18 03:00:10")
+        ));
+        // A run from yesterday doesn't count
+        assert!(is_due(
+            &nightly,
+            at("2026-10-18 02:59:40"),
+            Some(at("2026-10-17 03:00:05")),
+            at("2026-10-18 03:00:10")
+        ));
+    }
+
+    #[test]
+    fn test_tail() {
+        assert_eq!(tail("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(tail("a\n", 5), "a");
+        assert_eq!(tail("", 5), "");
+    }
+}
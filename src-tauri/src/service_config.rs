@@ -1,14 +1,22 @@
 //! Service configuration loaded from services.json
 //!
 //! This module provides a centralized way to define services, their versions,
-//! and platform-specific download URLs without modifying Rust code.
+//! and platform-specific download URLs without modifying Rust code. User
+//! service definitions (see `service_definitions`) are loaded alongside.
 
+use crate::arch::Arch;
+use crate::service_definitions;
 use serde::Deserialize;
-use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, RwLock};
 
 /// Global service registry loaded from services.json
-static SERVICE_REGISTRY: OnceLock<ServiceRegistry> = OnceLock::new();
+///
+/// Replaced by `ServiceRegistry::reload`. Earlier registries are leaked rather
+/// than freed, since `&'static` references into them may still be held;
+/// definitions change rarely.
+static SERVICE_REGISTRY: RwLock<Option<&'static ServiceRegistry>> = RwLock::new(None);
 
 /// Root structure of services.json
 #[derive(Debug, Deserialize)]
@@ -48,6 +56,21 @@ pub struct ServiceConfig {
     /// Use "default" key for versions not explicitly listed
     #[serde(default)]
     pub version_labels: HashMap<String, String>,
+    /// Lifecycle hooks (user service definitions only)
+    #[serde(default)]
+    pub hooks: ServiceHooks,
+    /// Definition file of a user service; `None` for built-ins
+    #[serde(skip)]
+    pub definition_path: Option<PathBuf>,
+}
+
+/// Executables a user service definition runs during an instance's lifecycle
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServiceHooks {
+    /// Run once before the first start, in the data directory and with its
+    /// path as the argument; relative to the definition file
+    #[serde(default)]
+    pub init: Option<String>,
 }
 
 /// Health check configuration
@@ -68,6 +91,7 @@ pub struct ConfigField {
     pub field_type: String,
     #[serde(default)]
     pub required: bool,
+    /// May use config template variables such as `{{instance.port}}`
     pub default: Option<String>,
 }
 
@@ -128,12 +152,34 @@ pub enum DownloadConfig {
 }
 
 impl ServiceRegistry {
-    /// Load the service registry from the embedded JSON
+    fn build() -> &'static ServiceRegistry {
+        let json = include_str!("../services.json");
+        let mut registry: ServiceRegistry =
+            serde_json::from_str(json).expect("Failed to parse services.json");
+        for (id, config) in service_definitions::load_all() {
+            registry.services.entry(id).or_insert(config);
+        }
+        Box::leak(Box::new(registry))
+    }
+
+    /// Load the service registry from the embedded JSON and the user's
+    /// service definitions
+    ///
+    /// Loaded once per process: definitions installed by another process
+    /// (e.g. `burd service add-definition` while the app runs) are picked up
+    /// on the next start, ones installed here after `reload`.
     pub fn load() -> &'static ServiceRegistry {
-        SERVICE_REGISTRY.get_or_init(|| {
-            let json = include_str!("../services.json");
-            serde_json::from_str(json).expect("Failed to parse services.json")
-        })
+        if let Some(registry) = *SERVICE_REGISTRY.read().unwrap_or_else(|e| e.into_inner()) {
+            return registry;
+        }
+        let mut current = SERVICE_REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+        current.get_or_insert_with(Self::build)
+    }
+
+    /// Re-read the user service definitions after they changed
+    pub fn reload() {
+        let registry = Self::build();
+        *SERVICE_REGISTRY.write().unwrap_or_else(|e| e.into_inner()) = Some(registry);
     }
 
     /// Get a service configuration by ID
@@ -150,13 +196,70 @@ impl ServiceRegistry {
     pub fn all_services(&self) -> Vec<(&String, &ServiceConfig)> {
         self.services.iter().collect()
     }
+
+    /// IDs of the services loaded from user definitions, sorted
+    pub fn definition_ids(&'static self) -> Vec<&'static str> {
+        let mut ids: Vec<&'static str> = self
+            .services
+            .iter()
+            .filter(|(_, config)| config.definition_path.is_some())
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// A `'static` copy of a service ID, for `ServiceType::Custom`
+///
+/// IDs of loaded services borrow from the registry. Any other ID (an
+/// instance whose definition was removed) is leaked once.
+pub fn intern_service_id(id: &str) -> &'static str {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    if let Some((key, _)) = ServiceRegistry::load().services.get_key_value(id) {
+        return key.as_str();
+    }
+    let mut interned = INTERNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.get(id) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(id.to_string().into_boxed_str());
+    interned.insert(leaked);
+    leaked
 }
 
 impl ServiceConfig {
     /// Get the platform configuration for the current OS/arch
     pub fn current_platform(&self) -> Option<&PlatformConfig> {
-        let platform_key = get_current_platform();
-        self.platforms.get(&platform_key)
+        self.platform_for(Arch::host(), false).map(|(_, p)| p)
+    }
+
+    /// Platform configuration for an architecture
+    ///
+    /// Without `exact`, Apple Silicon falls back to the x86_64 build (run
+    /// through Rosetta) when a service publishes no arm64 build.
+    pub fn platform_for(&self, arch: Arch, exact: bool) -> Option<(Arch, &PlatformConfig)> {
+        if let Some(platform) = self.platforms.get(&platform_key(arch)) {
+            return Some((arch, platform));
+        }
+        if exact || !Arch::X86_64.needs_rosetta() || arch != Arch::Arm64 {
+            return None;
+        }
+        self.platforms
+            .get(&platform_key(Arch::X86_64))
+            .map(|p| (Arch::X86_64, p))
+    }
+
+    /// Absolute path of a hook executable
+    pub fn hook_path(&self, hook: &str) -> Option<PathBuf> {
+        self.definition_path
+            .as_ref()?
+            .parent()
+            .map(|dir| dir.join(hook))
     }
 
     /// Get the binary name for the current platform
@@ -287,6 +390,11 @@ impl ServiceConfig {
 
 /// Get the current platform identifier
 pub fn get_current_platform() -> String {
+    platform_key(Arch::host())
+}
+
+/// Platform identifier for the current OS and a given architecture
+pub fn platform_key(arch: Arch) -> String {
     let os = if cfg!(target_os = "macos") {
         "darwin"
     } else if cfg!(target_os = "windows") {
@@ -295,13 +403,7 @@ pub fn get_current_platform() -> String {
         "linux"
     };
 
-    let arch = if cfg!(target_arch = "aarch64") {
-        "arm64"
-    } else {
-        "x64"
-    };
-
-    format!("{}-{}", os, arch)
+    format!("{}-{}", os, arch.platform_suffix())
 }
 
 /// Substitute variables in a string
@@ -331,6 +433,8 @@ pub struct ServiceInfo {
     pub max_instances: Option<usize>,
     /// Process manager type: "binary"
     pub process_manager: String,
+    /// Whether this service comes from a user service definition
+    pub user_defined: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -345,12 +449,10 @@ pub struct ConfigFieldInfo {
 impl ServiceRegistry {
     /// Get service info for the frontend
     pub fn get_service_info_list(&self) -> Vec<ServiceInfo> {
-        let platform = get_current_platform();
-
         self.services
             .iter()
             .filter_map(|(id, config)| {
-                let platform_config = config.platforms.get(&platform);
+                let platform_config = config.current_platform();
                 // Skip services that require building (like Redis)
                 let requires_build = platform_config.map(|p| p.requires_build).unwrap_or(false);
 
@@ -391,6 +493,7 @@ impl ServiceRegistry {
                     auto_create_domain: config.auto_create_domain,
                     max_instances: config.max_instances,
                     process_manager,
+                    user_defined: config.definition_path.is_some(),
                 })
             })
             .collect()
@@ -408,6 +511,21 @@ mod tests {
         assert!(registry.services.contains_key("mongodb"));
     }
 
+    #[test]
+    fn test_service_type_serializes_as_id() {
+        use crate::config::ServiceType;
+
+        let json = serde_json::to_string(&ServiceType::FrankenPhpPark).unwrap();
+        assert_eq!(json, "\"frankenphp-park\"");
+        for service_type in ServiceType::all() {
+            let json = serde_json::to_string(&service_type).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ServiceType>(&json).unwrap(),
+                service_type
+            );
+        }
+    }
+
     #[test]
     fn test_platform_detection() {
         let platform = get_current_platform();
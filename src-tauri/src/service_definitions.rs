@@ -0,0 +1,420 @@
+//! User service definitions
+//!
+//! Services that don't ship with Burd can be added by dropping a definition
+//! into the `services` directory of Burd's app data: `<id>.json` holds one
+//! service in the same shape as an entry of the built-in `services.json`. An
+//! optional `init` hook, an executable relative to the definition file, runs
+//! once before an instance's first start.
+//!
+//! Definitions are loaded into the `ServiceRegistry` at launch, after the
+//! built-ins, and again when one is installed in the same process (e.g. by a
+//! config import). Invalid ones are skipped with a warning, so a broken
+//! download can't keep Burd from starting.
+
+use crate::config::{get_app_dir, ServiceType};
+use crate::service_config::{
+    DownloadConfig, HealthCheckConfig, ServiceConfig, ServiceRegistry, VersionConfig,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Field types the instance settings form knows how to show
+const FIELD_TYPES: &[&str] = &[
+    "text", "password", "number", "checkbox", "folder", "textarea",
+];
+
+/// Platforms downloads can be given for
+const PLATFORMS: &[&str] = &[
+    "darwin-arm64",
+    "darwin-x64",
+    "linux-arm64",
+    "linux-x64",
+    "windows-x64",
+];
+
+/// Directory user service definitions are loaded from
+pub fn get_definitions_dir() -> Result<PathBuf, String> {
+    get_app_dir().map(|p| p.join("services"))
+}
+
+/// Every valid definition in the definitions directory, by service ID
+///
+/// Runs while the registry initializes, so nothing here may use it.
+pub fn load_all() -> Vec<(String, ServiceConfig)> {
+    let Ok(entries) = get_definitions_dir().and_then(|dir| {
+        fs::read_dir(dir).map_err(|e| format!("Failed to read service definitions: {}", e))
+    }) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| match read_definition(&path) {
+            Ok(definition) => Some(definition),
+            Err(e) => {
+                eprintln!("Skipping service definition {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parse and validate a definition; the file name is the service ID
+pub fn read_definition(path: &Path) -> Result<(String, ServiceConfig), String> {
+    let id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| format!("Invalid definition file name: {}", path.display()))?
+        .to_string();
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut config: ServiceConfig =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid definition: {}", e))?;
+    config.definition_path = Some(path.to_path_buf());
+
+    validate(&id, &config)?;
+    Ok((id, config))
+}
+
+/// Check a definition beyond what parsing catches
+///
+/// Every problem is reported, one per line.
+pub fn validate(id: &str, config: &ServiceConfig) -> Result<(), String> {
+    let mut problems = Vec::new();
+
+    let valid_id = id.starts_with(|c: char| c.is_ascii_lowercase())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_id {
+        problems.push(format!(
+            "the service ID '{}' (the file name) must be lowercase letters, digits and dashes, starting with a letter",
+            id
+        ));
+    }
+    if ServiceType::builtin_by_id(id).is_some() {
+        problems.push(format!("'{}' is a built-in service", id));
+    }
+
+    if config.display_name.trim().is_empty() {
+        problems.push("display_name is empty".to_string());
+    }
+    if config.binary_name.is_empty() || config.binary_name.contains('/') {
+        problems.push("binary_name must be a file name".to_string());
+    }
+    if config.default_port == 0 {
+        problems.push("default_port must be a port number".to_string());
+    }
+    if let HealthCheckConfig::Http { path } = &config.health_check {
+        if !path.starts_with('/') {
+            problems.push(format!("health_check path '{}' must start with /", path));
+        }
+    }
+
+    // Config fields
+    let mut field_keys = HashSet::new();
+    for field in &config.config_fields {
+        if field.key.is_empty() || !field_keys.insert(field.key.as_str()) {
+            problems.push(format!(
+                "config field keys must be unique and non-empty ('{}')",
+                field.key
+            ));
+        }
+        if !FIELD_TYPES.contains(&field.field_type.as_str()) {
+            problems.push(format!(
+                "config field '{}' has unknown type '{}' (use {})",
+                field.key,
+                field.field_type,
+                FIELD_TYPES.join(", ")
+            ));
+        }
+    }
+
+    // Placeholders must name something `build_start_args` / `build_env_vars` fill in
+    let mut arg_vars: HashSet<&str> = field_keys.clone();
+    arg_vars.extend(["port", "data_dir"]);
+    arg_vars.extend(config.computed_values.keys().map(|k| k.as_str()));
+    let args = config
+        .start_args
+        .iter()
+        .chain(config.start_args_conditional.iter().flat_map(|c| &c.args));
+    for arg in args {
+        for name in placeholders(arg) {
+            if !arg_vars.contains(name) {
+                problems.push(format!(
+                    "start argument '{}' uses unknown {{{}}}",
+                    arg, name
+                ));
+            }
+        }
+    }
+    for cond in &config.start_args_conditional {
+        if !field_keys.contains(cond.if_config.as_str()) {
+            problems.push(format!(
+                "start_args_conditional refers to unknown config field '{}'",
+                cond.if_config
+            ));
+        }
+    }
+    for var in &config.env_vars {
+        for name in placeholders(&var.value) {
+            if !field_keys.contains(name) {
+                problems.push(format!(
+                    "env var {} uses {{{}}}, which isn't a config field",
+                    var.name, name
+                ));
+            }
+        }
+    }
+
+    // Versions and downloads
+    match &config.versions {
+        VersionConfig::Static { versions } if versions.is_empty() => {
+            problems.push("versions lists no versions".to_string());
+        }
+        VersionConfig::GithubReleases { github_repo } if github_repo.split('/').count() != 2 => {
+            problems.push(format!(
+                "github_repo '{}' must be owner/repository",
+                github_repo
+            ));
+        }
+        _ => {}
+    }
+    if config.platforms.is_empty() {
+        problems.push("platforms lists no downloads".to_string());
+    }
+    let mut platforms: Vec<_> = config.platforms.iter().collect();
+    platforms.sort_by_key(|(key, _)| key.as_str());
+    for (key, platform) in platforms {
+        if !PLATFORMS.contains(&key.as_str()) {
+            problems.push(format!(
+                "unknown platform '{}' (use {})",
+                key,
+                PLATFORMS.join(", ")
+            ));
+        }
+        match &platform.download {
+            DownloadConfig::GithubAsset { .. }
+                if !matches!(config.versions, VersionConfig::GithubReleases { .. }) =>
+            {
+                problems.push(format!(
+                    "{}: github_asset downloads need github_releases versions",
+                    key
+                ));
+            }
+            DownloadConfig::Direct { url_template, .. }
+                if !url_template.starts_with("https://")
+                    && !url_template.starts_with("http://") =>
+            {
+                problems.push(format!("{}: url_template must be an http(s) URL", key));
+            }
+            _ => {}
+        }
+    }
+
+    // Hooks stay next to the definition
+    if let Some(init) = &config.hooks.init {
+        match hook_source(config, init) {
+            Some(path) if path.is_file() => {}
+            Some(path) => problems.push(format!("init hook not found: {}", path.display())),
+            None => problems.push(format!(
+                "init hook '{}' must be a path relative to the definition",
+                init
+            )),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid definition:\n  {}", problems.join("\n  ")))
+    }
+}
+
+/// Copy a definition and its hook into the definitions directory
+///
+/// The hook is copied to `<id>/` so definitions can't overwrite each other's
+/// hooks. An installed definition with the same ID is only replaced with
+/// `replace`. Returns the service ID and the installed definition.
+pub fn install(source: &Path, replace: bool) -> Result<(String, ServiceConfig), String> {
+    let (id, config) = read_definition(source)?;
+
+    let dir = get_definitions_dir()?;
+    let target = dir.join(format!("{}.json", id));
+    if target.exists() && !replace {
+        return Err(format!(
+            "A definition for '{}' is already installed at {}. Pass --force to replace it.",
+            id,
+            target.display()
+        ));
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let content = fs::read_to_string(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let mut definition: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid definition: {}", e))?;
+
+    if let Some(init) = &config.hooks.init {
+        let hook =
+            hook_source(&config, init).ok_or_else(|| format!("Invalid init hook '{}'", init))?;
+        let file_name = hook
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("Invalid init hook '{}'", init))?;
+        let installed = format!("{}/{}", id, file_name);
+
+        let hook_dir = dir.join(&id);
+        fs::create_dir_all(&hook_dir)
+            .map_err(|e| format!("Failed to create {}: {}", hook_dir.display(), e))?;
+        fs::copy(&hook, dir.join(&installed))
+            .map_err(|e| format!("Failed to copy init hook: {}", e))?;
+        make_executable(&dir.join(&installed))?;
+
+        definition["hooks"]["init"] = Value::String(installed);
+    }
+
+    let json = serde_json::to_string_pretty(&definition)
+        .map_err(|e| format!("Failed to serialize definition: {}", e))?;
+    fs::write(&target, json).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+    let installed = read_definition(&target)?;
+    ServiceRegistry::reload();
+    Ok(installed)
+}
+
+/// The hook file a definition refers to, if the path stays inside its directory
+fn hook_source(config: &ServiceConfig, hook: &str) -> Option<PathBuf> {
+    let relative = Path::new(hook);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+    config.hook_path(hook)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+/// Names inside `{...}` in a template
+fn placeholders(template: &str) -> Vec<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITION: &str = r#"{
+        "display_name": "ClickHouse",
+        "binary_name": "clickhouse",
+        "default_port": 8123,
+        "health_check": { "type": "http", "path": "/ping" },
+        "config_fields": [
+            { "key": "password", "label": "Password", "type": "password", "default": "" }
+        ],
+        "start_args": ["server", "--", "--http_port={port}", "--path={data_dir}"],
+        "env_vars": [{ "name": "CLICKHOUSE_PASSWORD", "value": "{password}" }],
+        "versions": { "source": "static", "versions": ["24.8"] },
+        "platforms": {
+            "darwin-arm64": {
+                "download": {
+                    "type": "direct",
+                    "url_template": "https://example.com/clickhouse-{version}-arm64.tar.gz"
+                },
+                "is_archive": true
+            }
+        },
+        "hooks": { "init": "init.sh" }
+    }"#;
+
+    fn write_definition(dir: &Path, id: &str, json: &str) -> PathBuf {
+        let path = dir.join(format!("{}.json", id));
+        fs::write(&path, json).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_valid_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("init.sh"), "#!/bin/sh\n").unwrap();
+        let path = write_definition(dir.path(), "clickhouse", DEFINITION);
+
+        let (id, config) = read_definition(&path).unwrap();
+        assert_eq!(id, "clickhouse");
+        assert_eq!(config.display_name, "ClickHouse");
+        assert_eq!(
+            config.hook_path("init.sh"),
+            Some(dir.path().join("init.sh"))
+        );
+        assert_eq!(
+            config.build_start_args(9000, "/data", &serde_json::json!({})),
+            vec!["server", "--", "--http_port=9000", "--path=/data"]
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_definitions() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Missing hook
+        let path = write_definition(dir.path(), "clickhouse", DEFINITION);
+        let err = read_definition(&path).unwrap_err();
+        assert!(err.contains("init hook not found"), "{}", err);
+
+        // Built-in ID and unknown placeholder
+        fs::write(dir.path().join("init.sh"), "").unwrap();
+        let json = DEFINITION.replace("{data_dir}", "{datadir}");
+        let path = write_definition(dir.path(), "redis", &json);
+        let err = read_definition(&path).unwrap_err();
+        assert!(err.contains("'redis' is a built-in service"), "{}", err);
+        assert!(err.contains("unknown {datadir}"), "{}", err);
+
+        // Schema errors from parsing
+        let path = write_definition(dir.path(), "broken", r#"{ "display_name": "Broken" }"#);
+        assert!(read_definition(&path)
+            .unwrap_err()
+            .starts_with("Invalid definition"));
+    }
+
+    #[test]
+    fn test_hook_must_stay_next_to_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = DEFINITION.replace("\"init.sh\"", "\"../init.sh\"");
+        let path = write_definition(dir.path(), "clickhouse", &json);
+        let err = read_definition(&path).unwrap_err();
+        assert!(err.contains("must be a path relative"), "{}", err);
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(placeholders("--port={port}"), vec!["port"]);
+        assert_eq!(placeholders("{a}:{b}"), vec!["a", "b"]);
+        assert!(placeholders("plain").is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+//! Service initialization
+//!
+//! Databases create their data directory with an init command on first start
+//! (`initdb`, `mariadb-install-db`, `mysqld --initialize-insecure`). An init
+//! that fails or is interrupted part way leaves a half-populated directory
+//! the command then refuses to run in again, so the instance never starts.
+//!
+//! Before the command runs, `.initializing` records what the data directory
+//! already held (e.g. a generated my.cnf). Whatever init added is removed
+//! when it fails, or on the next start when it was interrupted. A command
+//! that exits successfully must also leave behind the files the service's
+//! init probe expects before `.initialized` is written. Every stage is
+//! published as an `init` event.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use uuid::Uuid;
+
+use crate::config::Instance;
+use crate::events::{self, Event};
+use crate::start_diagnosis;
+
+/// Written once init completed
+const INITIALIZED_MARKER: &str = ".initialized";
+
+/// Present while init runs, listing the entries that were there before
+const IN_PROGRESS_MARKER: &str = ".initializing";
+
+/// Where an instance's init is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitStage {
+    /// The init command is running
+    Started,
+    /// Files left by an interrupted init were removed
+    Recovered,
+    /// Init finished and its probe passed
+    Completed,
+    /// Init failed; what it created was removed
+    Failed,
+}
+
+/// Progress of an instance's first-start initialization
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InitProgress {
+    pub instance_id: Uuid,
+    pub service_type: String,
+    pub stage: InitStage,
+    pub message: String,
+}
+
+fn report(instance: &Instance, stage: InitStage, message: String) {
+    events::publish(Event::Init(InitProgress {
+        instance_id: instance.id,
+        service_type: instance.service_type.as_str().to_string(),
+        stage,
+        message,
+    }));
+}
+
+fn is_marker(name: &str) -> bool {
+    name == INITIALIZED_MARKER || name == IN_PROGRESS_MARKER
+}
+
+/// Names in `data_dir`, without the markers
+fn entries(data_dir: &Path) -> HashSet<String> {
+    fs::read_dir(data_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| !is_marker(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Record what `data_dir` holds before init runs
+fn begin(data_dir: &Path) -> Result<(), String> {
+    let mut existing: Vec<String> = entries(data_dir).into_iter().collect();
+    existing.sort();
+    fs::write(data_dir.join(IN_PROGRESS_MARKER), existing.join("\n"))
+        .map_err(|e| format!("Failed to mark init as started: {}", e))
+}
+
+/// Remove what an unfinished init added to `data_dir`, returning how many
+/// entries were removed
+fn rollback(data_dir: &Path) -> usize {
+    let marker = data_dir.join(IN_PROGRESS_MARKER);
+    let Ok(listing) = fs::read_to_string(&marker) else {
+        return 0;
+    };
+    let existing: HashSet<&str> = listing.lines().collect();
+
+    let mut removed = 0;
+    for name in entries(data_dir) {
+        if existing.contains(name.as_str()) {
+            continue;
+        }
+        let path = data_dir.join(&name);
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if result.is_ok() {
+            removed += 1;
+        }
+    }
+    let _ = fs::remove_file(marker);
+    removed
+}
+
+/// Files the probe expects that init didn't create
+fn missing_files(data_dir: &Path, probe: &[&str]) -> Vec<String> {
+    probe
+        .iter()
+        .filter(|path| !data_dir.join(path).exists())
+        .map(|path| path.to_string())
+        .collect()
+}
+
+/// Whether the instance still has to be initialized
+///
+/// Cleans up after an init that was interrupted (the app quit or crashed
+/// while it ran), so it can run again.
+pub fn is_needed(instance: &Instance, data_dir: &Path) -> bool {
+    if data_dir.join(IN_PROGRESS_MARKER).exists() {
+        let removed = rollback(data_dir);
+        report(
+            instance,
+            InitStage::Recovered,
+            format!(
+                "Removed {} file(s) left by an interrupted initialization of {}",
+                removed, instance.name
+            ),
+        );
+        return true;
+    }
+    !data_dir.join(INITIALIZED_MARKER).exists()
+}
+
+/// Write init output to the instance log, where the start diagnosis and the
+/// log viewer find it
+fn write_log(log_path: &Path, cmd: &Command, output: &str) {
+    if let Some(dir) = log_path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = fs::File::create(log_path) {
+        writeln!(file, "=== Burd Init ===").ok();
+        writeln!(file, "Command: {:?}", cmd).ok();
+        writeln!(file, "=================").ok();
+        file.write_all(output.as_bytes()).ok();
+    }
+}
+
+/// Fail the init: remove what it created and explain why
+fn fail(instance: &Instance, data_dir: &Path, service_name: &str, reason: String) -> String {
+    rollback(data_dir);
+    report(instance, InitStage::Failed, reason.clone());
+    format!(
+        "{} initialization failed, and the files it created were removed so it runs again \
+         on the next start. {}",
+        service_name, reason
+    )
+}
+
+/// Run an instance's init command in `data_dir`, writing its output to
+/// `log_path`
+///
+/// `probe` lists files (relative to the data directory) a successful init
+/// leaves behind. `configure` runs after the probe passes, for config that
+/// can only be written into an initialized directory; if it fails the init
+/// is undone too.
+pub fn run(
+    instance: &Instance,
+    service_name: &str,
+    data_dir: &Path,
+    probe: &[&str],
+    mut cmd: Command,
+    log_path: Option<&Path>,
+    configure: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    begin(data_dir)?;
+    report(
+        instance,
+        InitStage::Started,
+        format!("Initializing the {} data directory", service_name),
+    );
+
+    let output = match cmd.output() {
+        Ok(output) => output,
+        Err(e) => {
+            let reason = format!("Failed to run init command: {}", e);
+            return Err(fail(instance, data_dir, service_name, reason));
+        }
+    };
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if let Some(log_path) = log_path {
+        write_log(log_path, &cmd, &text);
+    }
+
+    if !output.status.success() {
+        let diagnosis = start_diagnosis::diagnose(
+            service_name,
+            instance.port,
+            data_dir,
+            &String::from_utf8_lossy(&output.stderr),
+            log_path,
+        );
+        return Err(fail(
+            instance,
+            data_dir,
+            service_name,
+            diagnosis.to_string(),
+        ));
+    }
+
+    let missing = missing_files(data_dir, probe);
+    if !missing.is_empty() {
+        let reason = format!(
+            "The init command exited without creating {}",
+            missing.join(", ")
+        );
+        return Err(fail(instance, data_dir, service_name, reason));
+    }
+
+    if let Err(e) = configure() {
+        return Err(fail(instance, data_dir, service_name, e));
+    }
+
+    fs::write(data_dir.join(INITIALIZED_MARKER), "")
+        .map_err(|e| format!("Failed to mark init as completed: {}", e))?;
+    let _ = fs::remove_file(data_dir.join(IN_PROGRESS_MARKER));
+    report(
+        instance,
+        InitStage::Completed,
+        format!("Initialized the {} data directory", service_name),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    fn sh(script: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        cmd
+    }
+
+    #[test]
+    fn test_run_marks_initialized() {
+        let dir = tempfile::tempdir().unwrap();
+        let instance = InstanceBuilder::new().build();
+        let mut cmd = sh("mkdir -p global && touch PG_VERSION global/pg_control");
+        cmd.current_dir(dir.path());
+
+        run(
+            &instance,
+            "PostgreSQL",
+            dir.path(),
+            &["PG_VERSION", "global/pg_control"],
+            cmd,
+            None,
+            || Ok(()),
+        )
+        .unwrap();
+
+        assert!(dir.path().join(INITIALIZED_MARKER).exists());
+        assert!(!dir.path().join(IN_PROGRESS_MARKER).exists());
+        assert!(!is_needed(&instance, dir.path()));
+    }
+
+    #[test]
+    fn test_failed_run_removes_what_it_created() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("my.cnf"), "[mysqld]").unwrap();
+        let instance = InstanceBuilder::new().build();
+        let mut cmd = sh("mkdir mysql && touch ibdata1 && echo 'disk full' >&2 && exit 1");
+        cmd.current_dir(dir.path());
+
+        let err = run(
+            &instance,
+            "MariaDB",
+            dir.path(),
+            &["mysql"],
+            cmd,
+            None,
+            || Ok(()),
+        )
+        .unwrap_err();
+
+        assert!(err.contains("MariaDB initialization failed"));
+        assert!(dir.path().join("my.cnf").exists());
+        assert!(!dir.path().join("mysql").exists());
+        assert!(!dir.path().join("ibdata1").exists());
+        assert!(!dir.path().join(IN_PROGRESS_MARKER).exists());
+        assert!(is_needed(&instance, dir.path()));
+    }
+
+    #[test]
+    fn test_probe_catches_incomplete_init() {
+        let dir = tempfile::tempdir().unwrap();
+        let instance = InstanceBuilder::new().build();
+        let mut cmd = sh("touch PG_VERSION");
+        cmd.current_dir(dir.path());
+
+        let err = run(
+            &instance,
+            "PostgreSQL",
+            dir.path(),
+            &["PG_VERSION", "global/pg_control"],
+            cmd,
+            None,
+            || Ok(()),
+        )
+        .unwrap_err();
+
+        assert!(err.contains("without creating global/pg_control"));
+        assert!(!dir.path().join("PG_VERSION").exists());
+        assert!(!dir.path().join(INITIALIZED_MARKER).exists());
+    }
+
+    #[test]
+    fn test_failed_configure_undoes_init() {
+        let dir = tempfile::tempdir().unwrap();
+        let instance = InstanceBuilder::new().build();
+        let mut cmd = sh("touch PG_VERSION");
+        cmd.current_dir(dir.path());
+
+        let err = run(&instance, "PostgreSQL", dir.path(), &[], cmd, None, || {
+            Err("Failed to write postgresql.conf".to_string())
+        })
+        .unwrap_err();
+
+        assert!(err.contains("postgresql.conf"));
+        assert!(!dir.path().join("PG_VERSION").exists());
+    }
+
+    #[test]
+    fn test_interrupted_init_is_cleaned_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let instance = InstanceBuilder::new().build();
+        fs::write(dir.path().join("my.cnf"), "[mysqld]").unwrap();
+        begin(dir.path()).unwrap();
+        // The app quit while init was writing
+        fs::create_dir(dir.path().join("mysql")).unwrap();
+        fs::write(dir.path().join("mysql/user.frm"), "").unwrap();
+
+        assert!(is_needed(&instance, dir.path()));
+        assert!(dir.path().join("my.cnf").exists());
+        assert!(!dir.path().join("mysql").exists());
+        assert!(!dir.path().join(IN_PROGRESS_MARKER).exists());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::config::{Instance, ServiceType};
+use crate::platform::Os;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::path::Path;
 
@@ -26,10 +27,11 @@ impl ServiceDefinition for BunService {
     }
 
     fn download_method(&self, _version: &str, arch: &str) -> DownloadMethod {
-        let asset_pattern = if arch == "aarch64" {
-            "bun-darwin-aarch64.zip"
-        } else {
-            "bun-darwin-x64.zip"
+        let asset_pattern = match (Os::host(), arch == "aarch64") {
+            (Os::MacOs, true) => "bun-darwin-aarch64.zip",
+            (Os::MacOs, false) => "bun-darwin-x64.zip",
+            (Os::Linux, true) => "bun-linux-aarch64.zip",
+            (Os::Linux, false) => "bun-linux-x64.zip",
         };
         DownloadMethod::GitHubRelease {
             api_url: "https://api.github.com/repos/oven-sh/bun/releases/tags/",
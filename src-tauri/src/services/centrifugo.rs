@@ -1,9 +1,34 @@
-use crate::config::{Instance, ServiceType};
+//! Centrifugo real-time messaging
+//!
+//! Centrifugo runs from a `config.json` rendered into its data directory
+//! from the instance config on every start, which keeps the API key and
+//! secrets off the command line. `apply_preset` fills in everything a
+//! Laravel broadcasting setup needs in one step: API key, token HMAC secret,
+//! admin credentials, and allowed origins for the configured domains.
+
+use crate::activity;
+use crate::config::{Config, ConfigStore, Instance, ServiceType};
+use crate::platform::Os;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::path::Path;
+use uuid::Uuid;
+
+/// Generated config file inside the instance data directory
+const CONFIG_FILE: &str = "config.json";
 
 pub struct CentrifugoService;
 
+/// Result of applying the Laravel broadcasting preset
+#[derive(Debug, Clone, Serialize)]
+pub struct CentrifugoSetup {
+    /// Config file Centrifugo will start with
+    pub config: Value,
+    /// Matching Laravel broadcasting .env block
+    pub env: String,
+}
+
 impl ServiceDefinition for CentrifugoService {
     fn service_type(&self) -> ServiceType {
         ServiceType::Centrifugo
@@ -28,10 +53,11 @@ impl ServiceDefinition for CentrifugoService {
     }
 
     fn download_method(&self, _version: &str, arch: &str) -> DownloadMethod {
-        let asset_pattern = if arch == "aarch64" {
-            "centrifugo_*_darwin_arm64.tar.gz"
-        } else {
-            "centrifugo_*_darwin_amd64.tar.gz"
+        let asset_pattern = match (Os::host(), arch == "aarch64") {
+            (Os::MacOs, true) => "centrifugo_*_darwin_arm64.tar.gz",
+            (Os::MacOs, false) => "centrifugo_*_darwin_amd64.tar.gz",
+            (Os::Linux, true) => "centrifugo_*_linux_arm64.tar.gz",
+            (Os::Linux, false) => "centrifugo_*_linux_amd64.tar.gz",
         };
         DownloadMethod::GitHubRelease {
             api_url: "https://api.github.com/repos/centrifugal/centrifugo/releases/tags/",
@@ -46,54 +72,268 @@ impl ServiceDefinition for CentrifugoService {
         }
     }
 
-    fn start_args(&self, instance: &Instance, _data_dir: &Path) -> Vec<String> {
-        let mut args = vec![
+    fn start_args(&self, instance: &Instance, data_dir: &Path) -> Vec<String> {
+        vec![
+            "--config".to_string(),
+            data_dir.join(CONFIG_FILE).to_string_lossy().to_string(),
             "--port".to_string(),
             instance.port.to_string(),
             "--address".to_string(),
             "127.0.0.1".to_string(),
             "--health".to_string(),
-        ];
+        ]
+    }
+}
 
-        // Add API key if configured
-        if let Some(api_key) = instance.config.get("api_key").and_then(|v| v.as_str()) {
-            if !api_key.is_empty() {
-                args.push("--api_key".to_string());
-                args.push(api_key.to_string());
-            }
+/// Read a non-empty string from instance config
+fn config_str<'a>(instance: &'a Instance, key: &str) -> Option<&'a str> {
+    instance
+        .config
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+}
+
+/// Whether the admin UI is enabled (stored as a bool or "true")
+fn admin_enabled(instance: &Instance) -> bool {
+    match instance.config.get("admin") {
+        Some(Value::Bool(enabled)) => *enabled,
+        Some(Value::String(s)) => s == "true",
+        _ => false,
+    }
+}
+
+/// Allowed origins from instance config (array or comma-separated string)
+fn configured_origins(instance: &Instance) -> Vec<String> {
+    match instance.config.get("allowed_origins") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn random_secret(len: usize) -> String {
+    use rand::distr::Alphanumeric;
+    use rand::Rng;
+
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+impl CentrifugoService {
+    /// Render the Centrifugo config file contents from instance config
+    pub fn render_config(instance: &Instance) -> Value {
+        let mut config = serde_json::Map::new();
+
+        if let Some(api_key) = config_str(instance, "api_key") {
+            config.insert("api_key".to_string(), json!(api_key));
+        }
+        if let Some(secret) = config_str(instance, "token_hmac_secret") {
+            config.insert("token_hmac_secret_key".to_string(), json!(secret));
         }
 
-        // Add token HMAC secret if configured
-        if let Some(secret) = instance
-            .config
-            .get("token_hmac_secret")
-            .and_then(|v| v.as_str())
-        {
-            if !secret.is_empty() {
-                args.push("--token_hmac_secret_key".to_string());
-                args.push(secret.to_string());
+        let origins = configured_origins(instance);
+        if !origins.is_empty() {
+            config.insert("allowed_origins".to_string(), json!(origins));
+        }
+
+        if admin_enabled(instance) {
+            config.insert("admin".to_string(), json!(true));
+            if let Some(password) = config_str(instance, "admin_password") {
+                config.insert("admin_password".to_string(), json!(password));
+            }
+            if let Some(secret) = config_str(instance, "admin_secret") {
+                config.insert("admin_secret".to_string(), json!(secret));
             }
         }
 
-        // Enable admin UI if configured
-        if let Some(admin) = instance.config.get("admin").and_then(|v| v.as_str()) {
-            if admin == "true" {
-                args.push("--admin".to_string());
+        Value::Object(config)
+    }
+
+    /// Write the config file into the data directory (refreshed every start)
+    pub fn generate_config(instance: &Instance, data_dir: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&Self::render_config(instance))
+            .map_err(|e| format!("Failed to serialize Centrifugo config: {}", e))?;
+        std::fs::write(data_dir.join(CONFIG_FILE), content)
+            .map_err(|e| format!("Failed to write Centrifugo config: {}", e))
+    }
+
+    /// Browser origins for every configured domain
+    pub fn allowed_origins(config: &Config) -> Vec<String> {
+        let mut origins: Vec<String> = config
+            .domains
+            .iter()
+            .map(|d| {
+                let scheme = if d.ssl_enabled { "https" } else { "http" };
+                format!("{}://{}", scheme, d.full_domain(&config.tld))
+            })
+            .collect();
+        origins.sort();
+        origins.dedup();
+        origins
+    }
+
+    /// Instance config with the Laravel broadcasting preset applied
+    ///
+    /// Keeps existing keys and secrets, generates any that are missing, and
+    /// replaces the allowed origins with `origins`.
+    pub fn apply_preset(instance: &Instance, origins: Vec<String>) -> Value {
+        let mut config = match &instance.config {
+            Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        for key in ["api_key", "token_hmac_secret"] {
+            if config_str(instance, key).is_none() {
+                config.insert(key.to_string(), json!(random_secret(32)));
             }
         }
 
-        // Add admin password if configured
-        if let Some(password) = instance
-            .config
-            .get("admin_password")
-            .and_then(|v| v.as_str())
-        {
-            if !password.is_empty() {
-                args.push("--admin_password".to_string());
-                args.push(password.to_string());
+        if admin_enabled(instance) {
+            if config_str(instance, "admin_password").is_none() {
+                config.insert("admin_password".to_string(), json!(random_secret(16)));
             }
+            if config_str(instance, "admin_secret").is_none() {
+                config.insert("admin_secret".to_string(), json!(random_secret(32)));
+            }
+        }
+
+        config.insert("allowed_origins".to_string(), json!(origins));
+        Value::Object(config)
+    }
+
+    /// Apply the Laravel broadcasting preset to an instance and save it
+    ///
+    /// Allowed origins come from the configured domains; the browser
+    /// WebSocket URL uses the instance's own domain when it has one. Takes
+    /// effect on the next start.
+    pub fn setup(store: &ConfigStore, id: Uuid) -> Result<CentrifugoSetup, String> {
+        let config = store.load()?;
+        let instance = store.get_instance(id)?;
+        if instance.service_type != ServiceType::Centrifugo {
+            return Err(format!("'{}' is not a Centrifugo instance", instance.name));
         }
 
-        args
+        let preset = Self::apply_preset(&instance, Self::allowed_origins(&config));
+        let before = instance;
+        let instance = store.update_instance_config(id, preset)?;
+        activity::record_changes(&before, &instance);
+
+        let websocket_url = instance.domain_enabled.then(|| {
+            let ssl_enabled = config
+                .domains
+                .iter()
+                .filter(|d| d.routes_to_instance(&id))
+                .any(|d| d.ssl_enabled);
+            format!(
+                "{}://{}/connection/websocket",
+                if ssl_enabled { "wss" } else { "ws" },
+                instance.full_domain(&config.tld, &config.slugs)
+            )
+        });
+
+        Ok(CentrifugoSetup {
+            config: Self::render_config(&instance),
+            env: Self::laravel_env(&instance, websocket_url.as_deref()),
+        })
+    }
+
+    /// Laravel broadcasting .env block (laravel-centrifugo driver + centrifuge-js)
+    ///
+    /// `websocket_url` is what the browser connects to; defaults to the
+    /// loopback address.
+    pub fn laravel_env(instance: &Instance, websocket_url: Option<&str>) -> String {
+        let websocket_url = websocket_url
+            .map(String::from)
+            .unwrap_or_else(|| format!("ws://127.0.0.1:{}/connection/websocket", instance.port));
+
+        format!(
+            "# Laravel broadcasting (denis660/laravel-centrifugo)\n\
+             BROADCAST_CONNECTION=centrifugo\n\
+             CENTRIFUGO_URL=http://127.0.0.1:{}\n\
+             CENTRIFUGO_API_KEY={}\n\
+             CENTRIFUGO_TOKEN_HMAC_SECRET_KEY={}\n\
+             \n\
+             # Frontend (centrifuge-js)\n\
+             VITE_CENTRIFUGO_URL={}\n",
+            instance.port,
+            config_str(instance, "api_key").unwrap_or(""),
+            config_str(instance, "token_hmac_secret").unwrap_or(""),
+            websocket_url
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Domain;
+    use crate::test_utils::InstanceBuilder;
+
+    #[test]
+    fn test_render_config() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::Centrifugo)
+            .config(json!({
+                "api_key": "key",
+                "token_hmac_secret": "secret",
+                "allowed_origins": "https://app.test, http://localhost:5173",
+                "admin": "false",
+                "admin_password": "ignored"
+            }))
+            .build();
+
+        assert_eq!(
+            CentrifugoService::render_config(&instance),
+            json!({
+                "api_key": "key",
+                "token_hmac_secret_key": "secret",
+                "allowed_origins": ["https://app.test", "http://localhost:5173"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_preset_keeps_existing_secrets() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::Centrifugo)
+            .config(json!({ "api_key": "existing", "admin": true }))
+            .build();
+
+        let config =
+            CentrifugoService::apply_preset(&instance, vec!["https://app.test".to_string()]);
+        assert_eq!(config["api_key"], "existing");
+        assert_eq!(config["token_hmac_secret"].as_str().unwrap().len(), 32);
+        assert_eq!(config["admin_password"].as_str().unwrap().len(), 16);
+        assert!(config["admin_secret"].is_string());
+        assert_eq!(config["allowed_origins"], json!(["https://app.test"]));
+    }
+
+    #[test]
+    fn test_allowed_origins_from_domains() {
+        let config = Config {
+            tld: "test".to_string(),
+            domains: vec![
+                Domain::for_port("shop".to_string(), 8001, true),
+                Domain::for_port("api".to_string(), 8002, false),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            CentrifugoService::allowed_origins(&config),
+            vec!["http://api.test", "https://shop.test"]
+        );
     }
 }
@@ -0,0 +1,109 @@
+//! Services from user service definitions
+//!
+//! A definition describes its binary, start arguments, environment and health
+//! check in the same JSON shape as `services.json`, so this adapts the
+//! registry entry to `ServiceDefinition` instead of hand-writing a service.
+
+use crate::config::{get_binary_name, Instance, ServiceType};
+use crate::service_config::{
+    get_current_platform, HealthCheckConfig, ServiceConfig, ServiceRegistry, VersionConfig,
+};
+use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
+use std::path::Path;
+
+pub struct CustomService {
+    id: &'static str,
+    /// `None` when the definition was removed after instances were created
+    config: Option<&'static ServiceConfig>,
+}
+
+impl CustomService {
+    pub fn new(id: &'static str) -> Self {
+        Self {
+            id,
+            config: ServiceRegistry::load().get_service(id),
+        }
+    }
+}
+
+impl ServiceDefinition for CustomService {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Custom(self.id)
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.service_type().display_name()
+    }
+
+    fn default_port(&self) -> u16 {
+        self.service_type().default_port()
+    }
+
+    fn binary_name(&self) -> &'static str {
+        get_binary_name(self.service_type())
+    }
+
+    fn version_source(&self) -> VersionSource {
+        // GitHub versions are fetched from the registry entry by BinaryManager
+        match self.config.map(|config| &config.versions) {
+            Some(VersionConfig::Static { versions }) => {
+                VersionSource::Static(versions.iter().map(|v| v.as_str()).collect())
+            }
+            _ => VersionSource::Static(Vec::new()),
+        }
+    }
+
+    fn download_method(&self, version: &str, _arch: &str) -> DownloadMethod {
+        let platform = get_current_platform();
+        DownloadMethod::Direct {
+            url: self
+                .config
+                .and_then(|config| config.get_download_url(version, &platform))
+                .unwrap_or_default(),
+            is_archive: self
+                .config
+                .and_then(|config| config.current_platform())
+                .is_some_and(|p| p.is_archive),
+            checksum: None,
+        }
+    }
+
+    fn health_check(&self) -> HealthCheck {
+        match self.config.map(|config| &config.health_check) {
+            Some(HealthCheckConfig::Http { path }) => HealthCheck::Http { path: path.clone() },
+            _ => HealthCheck::Tcp,
+        }
+    }
+
+    fn start_args(&self, instance: &Instance, data_dir: &Path) -> Vec<String> {
+        self.config
+            .map(|config| {
+                config.build_start_args(
+                    instance.port,
+                    &data_dir.to_string_lossy(),
+                    &instance.config,
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn env_vars(&self, instance: &Instance, _domain: Option<&str>) -> Vec<(String, String)> {
+        self.config
+            .map(|config| config.build_env_vars(&instance.config))
+            .unwrap_or_default()
+    }
+
+    fn needs_init(&self) -> bool {
+        self.config
+            .is_some_and(|config| config.hooks.init.is_some())
+    }
+
+    fn init_command(&self, data_dir: &Path) -> Option<(String, Vec<String>)> {
+        let config = self.config?;
+        let hook = config.hook_path(config.hooks.init.as_deref()?)?;
+        Some((
+            hook.to_string_lossy().to_string(),
+            vec![data_dir.to_string_lossy().to_string()],
+        ))
+    }
+}
@@ -0,0 +1,255 @@
+//! Per-instance timezone and locale for database services
+//!
+//! A local database follows the Mac's timezone (MariaDB, MySQL) or GMT
+//! (PostgreSQL), which makes datetime behaviour differ from CI or production.
+//! The `timezone` and `locale` instance config fields pin them instead.
+//!
+//! A timezone is either an IANA name ("Europe/Berlin", "UTC") or a fixed
+//! offset ("+02:00"). MySQL-compatible servers get a named zone through `TZ`,
+//! because `default-time-zone` only accepts names once the time zone tables
+//! are loaded, and an offset as `default-time-zone` in my.cnf. PostgreSQL gets
+//! both as `-c` start arguments, which override its postgresql.conf.
+
+use crate::config::Instance;
+use std::path::Path;
+
+/// Where named timezones are looked up
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+/// A database's timezone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbTimezone {
+    /// IANA name, e.g. "Europe/Berlin"
+    Named(String),
+    /// Fixed UTC offset, e.g. "+02:00"
+    Offset(String),
+}
+
+/// Timezone and locale settings from an instance's config
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DbLocaleSettings {
+    pub timezone: Option<DbTimezone>,
+    /// e.g. "de_DE.UTF-8"
+    pub locale: Option<String>,
+}
+
+fn config_string(instance: &Instance, key: &str) -> Option<String> {
+    let value = instance.config.get(key)?.as_str()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Parse "+HH:MM" / "-HH:MM" within the range real zones use
+fn parse_offset(value: &str) -> Option<String> {
+    let (sign, rest) = value.split_at(1);
+    if sign != "+" && sign != "-" {
+        return None;
+    }
+    let (hours, minutes) = rest.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: u8 = hours.parse().ok()?;
+    let minutes: u8 = minutes.parse().ok()?;
+    (hours <= 14 && minutes < 60).then(|| format!("{}{:02}:{:02}", sign, hours, minutes))
+}
+
+fn parse_timezone(value: &str, zoneinfo: &Path) -> Result<DbTimezone, String> {
+    if value.starts_with(['+', '-']) {
+        return parse_offset(value).map(DbTimezone::Offset).ok_or_else(|| {
+            format!(
+                "Invalid timezone offset '{}'. Use +HH:MM or -HH:MM, e.g. +02:00",
+                value
+            )
+        });
+    }
+
+    let valid_name = value.split('/').all(|part| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+    });
+    if !valid_name {
+        return Err(format!("Invalid timezone '{}'", value));
+    }
+    // Only checked where the zone database exists
+    if zoneinfo.is_dir() && !zoneinfo.join(value).is_file() {
+        return Err(format!(
+            "Unknown timezone '{}'. Use an IANA name like Europe/Berlin or UTC",
+            value
+        ));
+    }
+    Ok(DbTimezone::Named(value.to_string()))
+}
+
+fn parse_locale(value: &str) -> Result<String, String> {
+    let (name, encoding) = match value.split_once('.') {
+        Some((name, encoding)) => (name, Some(encoding)),
+        None => (value, None),
+    };
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphabetic() || c == '_')
+        && encoding.is_none_or(|e| {
+            !e.is_empty() && e.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid {
+        return Err(format!(
+            "Invalid locale '{}'. Use a name like en_US.UTF-8 or C",
+            value
+        ));
+    }
+    Ok(value.to_string())
+}
+
+impl DbLocaleSettings {
+    /// Read and validate the `timezone` and `locale` config fields
+    pub fn from_instance(instance: &Instance) -> Result<Self, String> {
+        Self::from_instance_in(instance, Path::new(ZONEINFO_DIR))
+    }
+
+    fn from_instance_in(instance: &Instance, zoneinfo: &Path) -> Result<Self, String> {
+        Ok(Self {
+            timezone: config_string(instance, "timezone")
+                .map(|tz| parse_timezone(&tz, zoneinfo))
+                .transpose()?,
+            locale: config_string(instance, "locale")
+                .map(|locale| parse_locale(&locale))
+                .transpose()?,
+        })
+    }
+
+    /// Environment for MySQL-compatible servers
+    pub fn mysql_env(&self) -> Vec<(String, String)> {
+        match &self.timezone {
+            Some(DbTimezone::Named(name)) => vec![("TZ".to_string(), name.clone())],
+            _ => Vec::new(),
+        }
+    }
+
+    /// `[mysqld]` option lines (empty if nothing is set)
+    pub fn render_mysql(&self) -> String {
+        let mut lines = Vec::new();
+
+        if let Some(DbTimezone::Offset(offset)) = &self.timezone {
+            lines.push(format!("default-time-zone='{}'", offset));
+        }
+        // lc_time_names takes the bare name (de_DE), and has no C locale
+        if let Some(locale) = &self.locale {
+            let name = locale.split('.').next().unwrap_or(locale);
+            if name != "C" && name != "POSIX" {
+                lines.push(format!("lc-time-names={}", name));
+            }
+        }
+
+        if lines.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            "\n# Timezone and locale (managed by Burd - change them in the instance settings)\n{}\n",
+            lines.join("\n")
+        )
+    }
+
+    /// `-c` arguments for postgres
+    pub fn postgres_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(timezone) = &self.timezone {
+            let value = match timezone {
+                DbTimezone::Named(name) => name.clone(),
+                // POSIX offsets count west of UTC, so the sign flips
+                DbTimezone::Offset(offset) => {
+                    let flipped = match offset.strip_prefix('+') {
+                        Some(rest) => format!("-{}", rest),
+                        None => format!("+{}", &offset[1..]),
+                    };
+                    format!("UTC{}", flipped)
+                }
+            };
+            args.extend(["-c".to_string(), format!("timezone={}", value)]);
+        }
+        if let Some(locale) = &self.locale {
+            for setting in ["lc_time", "lc_numeric", "lc_monetary"] {
+                args.extend(["-c".to_string(), format!("{}={}", setting, locale)]);
+            }
+        }
+
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServiceType;
+    use crate::test_utils::InstanceBuilder;
+    use serde_json::json;
+
+    fn parse(config: serde_json::Value) -> Result<DbLocaleSettings, String> {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::MariaDB)
+            .config(config)
+            .build();
+        let zoneinfo = tempfile::tempdir().unwrap();
+        std::fs::create_dir(zoneinfo.path().join("Europe")).unwrap();
+        std::fs::write(zoneinfo.path().join("Europe/Berlin"), "").unwrap();
+        std::fs::write(zoneinfo.path().join("UTC"), "").unwrap();
+        DbLocaleSettings::from_instance_in(&instance, zoneinfo.path())
+    }
+
+    #[test]
+    fn test_unset_changes_nothing() {
+        let settings = parse(json!({ "timezone": "", "preset": "strict" })).unwrap();
+        assert_eq!(settings, DbLocaleSettings::default());
+        assert!(settings.mysql_env().is_empty());
+        assert!(settings.render_mysql().is_empty());
+        assert!(settings.postgres_args().is_empty());
+    }
+
+    #[test]
+    fn test_named_timezone() {
+        let settings = parse(json!({ "timezone": "Europe/Berlin" })).unwrap();
+        assert_eq!(
+            settings.mysql_env(),
+            vec![("TZ".to_string(), "Europe/Berlin".to_string())]
+        );
+        assert!(settings.render_mysql().is_empty());
+        assert_eq!(settings.postgres_args(), ["-c", "timezone=Europe/Berlin"]);
+    }
+
+    #[test]
+    fn test_offset_timezone() {
+        let settings = parse(json!({ "timezone": "+02:00" })).unwrap();
+        assert!(settings.mysql_env().is_empty());
+        assert!(settings
+            .render_mysql()
+            .contains("default-time-zone='+02:00'"));
+        assert_eq!(settings.postgres_args(), ["-c", "timezone=UTC-02:00"]);
+
+        let settings = parse(json!({ "timezone": "-05:30" })).unwrap();
+        assert_eq!(settings.postgres_args(), ["-c", "timezone=UTC+05:30"]);
+    }
+
+    #[test]
+    fn test_locale() {
+        let settings = parse(json!({ "locale": "de_DE.UTF-8" })).unwrap();
+        assert!(settings.render_mysql().contains("lc-time-names=de_DE\n"));
+        assert!(settings
+            .postgres_args()
+            .contains(&"lc_time=de_DE.UTF-8".to_string()));
+
+        let settings = parse(json!({ "locale": "C" })).unwrap();
+        assert!(settings.render_mysql().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        assert!(parse(json!({ "timezone": "Mars/Olympus" })).is_err());
+        assert!(parse(json!({ "timezone": "../etc/passwd" })).is_err());
+        assert!(parse(json!({ "timezone": "+25:00" })).is_err());
+        assert!(parse(json!({ "timezone": "+2" })).is_err());
+        assert!(parse(json!({ "locale": "de_DE.UTF-8'\ninit_file=/tmp/x" })).is_err());
+        assert!(parse(json!({ "locale": ".UTF-8" })).is_err());
+    }
+}
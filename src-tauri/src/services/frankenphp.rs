@@ -1,4 +1,6 @@
 use crate::config::{get_instance_dir, Instance, ServiceType};
+use crate::platform::Os;
+use crate::services::php_ini::{self, PhpIniOverrides, XdebugMode};
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::path::Path;
 
@@ -98,10 +100,11 @@ impl ServiceDefinition for FrankenPHPService {
     }
 
     fn download_method(&self, _version: &str, arch: &str) -> DownloadMethod {
-        let asset_pattern = if arch == "aarch64" {
-            "frankenphp-mac-arm64"
-        } else {
-            "frankenphp-mac-x86_64"
+        let asset_pattern = match (Os::host(), arch == "aarch64") {
+            (Os::MacOs, true) => "frankenphp-mac-arm64",
+            (Os::MacOs, false) => "frankenphp-mac-x86_64",
+            (Os::Linux, true) => "frankenphp-linux-aarch64",
+            (Os::Linux, false) => "frankenphp-linux-x86_64",
         };
         DownloadMethod::GitHubRelease {
             api_url: "https://api.github.com/repos/dunglas/frankenphp/releases/tags/",
@@ -132,14 +135,18 @@ impl ServiceDefinition for FrankenPHPService {
 
             let (extra_directives, php_server_body) = framework_directives(instance);
 
+            // Invalid overrides are rejected when saved; never keep the site from starting
+            let global_options = PhpIniOverrides::from_instance(instance)
+                .unwrap_or_else(|e| {
+                    eprintln!("Ignoring php.ini overrides for '{}': {}", instance.name, e);
+                    PhpIniOverrides::default()
+                })
+                .global_options();
+
             // Generate Caddyfile — uses bare php_server which handles all major PHP
             // frameworks by default (try_files {path} {path}/index.php index.php)
             let caddyfile = format!(
-                r#"{{
-    frankenphp
-    order php_server before file_server
-}}
-
+                r#"{global_options}
 :{port} {{
     # Map X-Forwarded-Proto header to HTTPS env value
     map {{header.X-Forwarded-Proto}} {{https_env}} {{
@@ -158,6 +165,7 @@ impl ServiceDefinition for FrankenPHPService {
 {php_server_body}    }}
 }}
 "#,
+                global_options = global_options,
                 port = instance.port,
                 doc_root = doc_root,
                 extra_directives = extra_directives,
@@ -165,6 +173,13 @@ impl ServiceDefinition for FrankenPHPService {
             );
 
             let _ = std::fs::write(&config_file, caddyfile);
+
+            // Xdebug doesn't create its profile directory
+            if XdebugMode::from_instance(instance) == Ok(XdebugMode::Profile) {
+                if let Ok(dir) = php_ini::xdebug_output_dir(instance) {
+                    let _ = std::fs::create_dir_all(dir);
+                }
+            }
         } else {
             // Copy custom Caddyfile to the expected location
             let _ = std::fs::copy(&custom_caddyfile, &config_file);
@@ -4,6 +4,8 @@
 //! Uses a dynamically generated Caddyfile for virtual host routing.
 
 use crate::config::{get_instance_dir, Instance, ServiceType};
+use crate::platform::Os;
+use crate::services::php_ini::PhpIniOverrides;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::path::Path;
 
@@ -31,10 +33,11 @@ impl ServiceDefinition for FrankenPHPParkService {
     }
 
     fn download_method(&self, _version: &str, arch: &str) -> DownloadMethod {
-        let asset_pattern = if arch == "aarch64" {
-            "frankenphp-mac-arm64"
-        } else {
-            "frankenphp-mac-x86_64"
+        let asset_pattern = match (Os::host(), arch == "aarch64") {
+            (Os::MacOs, true) => "frankenphp-mac-arm64",
+            (Os::MacOs, false) => "frankenphp-mac-x86_64",
+            (Os::Linux, true) => "frankenphp-linux-aarch64",
+            (Os::Linux, false) => "frankenphp-linux-x86_64",
         };
         DownloadMethod::GitHubRelease {
             api_url: "https://api.github.com/repos/dunglas/frankenphp/releases/tags/",
@@ -55,12 +58,11 @@ impl ServiceDefinition for FrankenPHPParkService {
 
         // Create initial Caddyfile if it doesn't exist
         if !config_file.exists() {
+            let global_options = PhpIniOverrides::from_instance(instance)
+                .unwrap_or_default()
+                .global_options();
             let initial_caddyfile = format!(
-                r#"{{
-    frankenphp
-    order php_server before file_server
-}}
-
+                r#"{}
 :{} {{
     # Map X-Forwarded-Proto header to HTTPS env value
     map {{header.X-Forwarded-Proto}} {{https_env}} {{
@@ -73,7 +75,7 @@ impl ServiceDefinition for FrankenPHPParkService {
     }}
 }}
 "#,
-                instance.port
+                global_options, instance.port
             );
             let _ = std::fs::write(&config_file, initial_caddyfile);
         }
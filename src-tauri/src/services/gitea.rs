@@ -1,4 +1,5 @@
 use crate::config::{get_instance_dir, Instance, ServiceType};
+use crate::platform::Os;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::path::Path;
 
@@ -26,10 +27,11 @@ impl ServiceDefinition for GiteaService {
     }
 
     fn download_method(&self, _version: &str, arch: &str) -> DownloadMethod {
-        let asset_pattern = if arch == "aarch64" {
-            "darwin-10.12-arm64"
-        } else {
-            "darwin-10.12-amd64"
+        let asset_pattern = match (Os::host(), arch == "aarch64") {
+            (Os::MacOs, true) => "darwin-10.12-arm64",
+            (Os::MacOs, false) => "darwin-10.12-amd64",
+            (Os::Linux, true) => "linux-arm64",
+            (Os::Linux, false) => "linux-amd64",
         };
         DownloadMethod::GitHubRelease {
             api_url: "https://api.github.com/repos/go-gitea/gitea/releases/tags/",
@@ -6,7 +6,118 @@
 
 use crate::config::{Instance, ServiceType};
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name sentinels use for the monitored master unless configured otherwise
+pub const DEFAULT_SENTINEL_MASTER_NAME: &str = "mymaster";
+
+/// Role of a Redis-compatible instance in a replication group
+///
+/// Stored as `role` in the instance config; instances without one are
+/// standalone masters, as before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyValueRole {
+    Master,
+    /// Replicates from the master on `master_port`
+    Replica,
+    /// Runs in Sentinel mode, monitoring the master on `master_port`
+    Sentinel,
+}
+
+impl KeyValueRole {
+    /// Read the role from an instance's config
+    pub fn from_instance(instance: &Instance) -> Self {
+        match instance.config.get("role").and_then(|v| v.as_str()) {
+            Some("replica") => Self::Replica,
+            Some("sentinel") => Self::Sentinel,
+            _ => Self::Master,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Master => "master",
+            Self::Replica => "replica",
+            Self::Sentinel => "sentinel",
+        }
+    }
+}
+
+/// Read a string config value, ignoring empty strings
+fn config_str<'a>(instance: &'a Instance, key: &str) -> Option<&'a str> {
+    instance
+        .config
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+/// Port of the master a replica or sentinel points at
+fn master_port(instance: &Instance) -> Option<u16> {
+    instance.config.get("master_port").and_then(|v| {
+        v.as_str()
+            .and_then(|s| s.parse().ok())
+            .or_else(|| v.as_u64().and_then(|n| u16::try_from(n).ok()))
+    })
+}
+
+/// Path of the generated Sentinel config file
+pub fn sentinel_config_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("sentinel.conf")
+}
+
+/// Render the Sentinel config for a sentinel instance
+fn render_sentinel_config(instance: &Instance, data_dir: &Path) -> Result<String, String> {
+    let master_port = master_port(instance).ok_or_else(|| {
+        format!(
+            "Sentinel instance '{}' has no master_port configured",
+            instance.name
+        )
+    })?;
+    let master_name = config_str(instance, "master_name").unwrap_or(DEFAULT_SENTINEL_MASTER_NAME);
+    let quorum = config_str(instance, "quorum")
+        .and_then(|q| q.parse::<u8>().ok())
+        .unwrap_or(1);
+
+    let mut conf = format!(
+        "# Generated by Burd - regenerated on every start\n\
+         port {port}\n\
+         bind 127.0.0.1\n\
+         dir \"{dir}\"\n\
+         sentinel monitor {name} 127.0.0.1 {master_port} {quorum}\n\
+         sentinel down-after-milliseconds {name} 5000\n\
+         sentinel failover-timeout {name} 60000\n\
+         sentinel parallel-syncs {name} 1\n",
+        port = instance.port,
+        dir = data_dir.to_string_lossy(),
+        name = master_name,
+        master_port = master_port,
+        quorum = quorum,
+    );
+    if let Some(password) = config_str(instance, "password") {
+        conf.push_str(&format!(
+            "sentinel auth-pass {} {}\n",
+            master_name, password
+        ));
+    }
+
+    Ok(conf)
+}
+
+/// Write `sentinel.conf` for sentinel instances (no-op for other roles)
+///
+/// Sentinel rewrites its config at runtime to remember failovers, so the file
+/// is regenerated on every start to return to the configured topology.
+pub fn generate_sentinel_config(instance: &Instance, data_dir: &Path) -> Result<(), String> {
+    if KeyValueRole::from_instance(instance) != KeyValueRole::Sentinel {
+        return Ok(());
+    }
+
+    let conf = render_sentinel_config(instance, data_dir)?;
+    let path = sentinel_config_path(data_dir);
+    fs::write(&path, conf).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
 
 /// Generic configuration for Redis-compatible key-value stores
 pub struct KeyValueService {
@@ -119,7 +230,18 @@ impl ServiceDefinition for KeyValueService {
     /// - Uses instance port
     /// - Sets data directory
     /// - Optionally adds password protection via --requirepass
+    /// - Replicas follow their master via --replicaof (and --masterauth)
+    /// - Sentinels run from the generated sentinel.conf in --sentinel mode
     fn start_args(&self, instance: &Instance, data_dir: &Path) -> Vec<String> {
+        let role = KeyValueRole::from_instance(instance);
+
+        if role == KeyValueRole::Sentinel {
+            return vec![
+                sentinel_config_path(data_dir).to_string_lossy().to_string(),
+                "--sentinel".to_string(),
+            ];
+        }
+
         let mut args = vec![
             "--port".to_string(),
             instance.port.to_string(),
@@ -137,6 +259,20 @@ impl ServiceDefinition for KeyValueService {
             }
         }
 
+        if role == KeyValueRole::Replica {
+            if let Some(port) = master_port(instance) {
+                args.push("--replicaof".to_string());
+                args.push("127.0.0.1".to_string());
+                args.push(port.to_string());
+
+                // Replication groups share one password
+                if let Some(password) = config_str(instance, "password") {
+                    args.push("--masterauth".to_string());
+                    args.push(password.to_string());
+                }
+            }
+        }
+
         args
     }
 }
@@ -216,6 +352,53 @@ mod tests {
         assert!(!args.contains(&"--requirepass".to_string()));
     }
 
+    #[test]
+    fn test_start_args_replica() {
+        let service = KeyValueService::redis();
+        let instance = InstanceBuilder::new()
+            .port(6380)
+            .config(serde_json::json!({
+                "role": "replica",
+                "master_port": "6379",
+                "password": "secret123"
+            }))
+            .build();
+
+        let args = service.start_args(&instance, Path::new("/tmp/test"));
+
+        let replicaof = args.iter().position(|a| a == "--replicaof").unwrap();
+        assert_eq!(args[replicaof + 1], "127.0.0.1");
+        assert_eq!(args[replicaof + 2], "6379");
+        assert!(args.contains(&"--masterauth".to_string()));
+    }
+
+    #[test]
+    fn test_sentinel_config() {
+        let service = KeyValueService::valkey();
+        let instance = InstanceBuilder::new()
+            .port(26379)
+            .config(serde_json::json!({
+                "role": "sentinel",
+                "master_port": "6379",
+                "quorum": "2"
+            }))
+            .build();
+        let data_dir = Path::new("/tmp/test");
+
+        let args = service.start_args(&instance, data_dir);
+        assert_eq!(args, vec!["/tmp/test/sentinel.conf", "--sentinel"]);
+
+        let conf = render_sentinel_config(&instance, data_dir).unwrap();
+        assert!(conf.contains("port 26379"));
+        assert!(conf.contains("sentinel monitor mymaster 127.0.0.1 6379 2"));
+        assert!(!conf.contains("auth-pass"));
+
+        let orphan = InstanceBuilder::new()
+            .config(serde_json::json!({ "role": "sentinel" }))
+            .build();
+        assert!(render_sentinel_config(&orphan, data_dir).is_err());
+    }
+
     #[test]
     fn test_download_method_arm64() {
         let service = KeyValueService::redis();
@@ -0,0 +1,536 @@
+//! Keycloak identity provider
+//!
+//! Installed through Homebrew, which brings the OpenJDK it runs on. Every
+//! instance runs `kc.sh start-dev` with its own H2 database in its data
+//! directory, so realms and users never leak between instances.
+//!
+//! The admin account comes from the instance's `admin_user` and
+//! `admin_password`; Keycloak only creates it together with the master realm,
+//! on first start. With `auto_provision` on, the configured realm, an OIDC
+//! client and a test user are created over the Admin REST API once the
+//! server answers, so a Laravel Socialite or NextAuth app can log in right
+//! away. Provisioning only adds what's missing and never changes existing
+//! realms, clients or users.
+
+use crate::config::{ConfigStore, Instance, ServiceType};
+use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long to wait for Keycloak to answer before giving up on provisioning
+const READY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Defaults for settings the instance doesn't have
+const DEFAULT_ADMIN_USER: &str = "admin";
+const DEFAULT_ADMIN_PASSWORD: &str = "admin";
+const DEFAULT_REALM: &str = "burd";
+const DEFAULT_CLIENT_ID: &str = "laravel";
+const DEFAULT_REDIRECT_URIS: &str = "*";
+const DEFAULT_TEST_USER: &str = "test";
+const DEFAULT_TEST_PASSWORD: &str = "test";
+
+pub struct KeycloakService;
+
+/// What provisioning found and created
+#[derive(Debug, Clone, Serialize)]
+pub struct KeycloakProvision {
+    /// One line per realm, client and user: created or already there
+    pub steps: Vec<String>,
+    /// Matching Laravel Socialite .env block
+    pub env: String,
+}
+
+/// Read a non-empty string from instance config
+fn config_str<'a>(instance: &'a Instance, key: &str) -> Option<&'a str> {
+    instance
+        .config
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+impl KeycloakService {
+    /// Admin username and password
+    pub fn admin_credentials(instance: &Instance) -> (&str, &str) {
+        (
+            config_str(instance, "admin_user").unwrap_or(DEFAULT_ADMIN_USER),
+            config_str(instance, "admin_password").unwrap_or(DEFAULT_ADMIN_PASSWORD),
+        )
+    }
+
+    /// Realm created for apps
+    pub fn realm(instance: &Instance) -> &str {
+        config_str(instance, "realm").unwrap_or(DEFAULT_REALM)
+    }
+
+    /// OIDC client ID created in the realm
+    pub fn client_id(instance: &Instance) -> &str {
+        config_str(instance, "client_id").unwrap_or(DEFAULT_CLIENT_ID)
+    }
+
+    /// The client's secret; derived from the instance ID unless one is set,
+    /// so it's stable without being stored
+    pub fn client_secret(instance: &Instance) -> String {
+        config_str(instance, "client_secret")
+            .map(String::from)
+            .unwrap_or_else(|| instance.id.simple().to_string())
+    }
+
+    /// Allowed redirect URIs, comma-separated in the config
+    pub fn redirect_uris(instance: &Instance) -> Vec<String> {
+        config_str(instance, "redirect_uris")
+            .unwrap_or(DEFAULT_REDIRECT_URIS)
+            .split(',')
+            .map(|uri| uri.trim().to_string())
+            .filter(|uri| !uri.is_empty())
+            .collect()
+    }
+
+    /// Test user's username and password
+    pub fn test_user(instance: &Instance) -> (&str, &str) {
+        (
+            config_str(instance, "test_user").unwrap_or(DEFAULT_TEST_USER),
+            config_str(instance, "test_password").unwrap_or(DEFAULT_TEST_PASSWORD),
+        )
+    }
+
+    /// Whether to provision the realm on every start (stored as a bool or "true")
+    pub fn auto_provision(instance: &Instance) -> bool {
+        match instance.config.get("auto_provision") {
+            Some(Value::Bool(enabled)) => *enabled,
+            Some(Value::String(s)) => s == "true",
+            _ => true,
+        }
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:8180`
+    pub fn url(instance: &Instance) -> String {
+        format!("http://127.0.0.1:{}", instance.port)
+    }
+
+    /// OIDC issuer of the app realm
+    pub fn issuer(instance: &Instance) -> String {
+        format!("{}/realms/{}", Self::url(instance), Self::realm(instance))
+    }
+
+    /// Laravel Socialite .env block (socialiteproviders/keycloak)
+    pub fn laravel_env(instance: &Instance) -> String {
+        format!(
+            "# Laravel Socialite (socialiteproviders/keycloak)\n\
+             KEYCLOAK_BASE_URL={}\n\
+             KEYCLOAK_REALM={}\n\
+             KEYCLOAK_CLIENT_ID={}\n\
+             KEYCLOAK_CLIENT_SECRET={}\n\
+             KEYCLOAK_REDIRECT_URI=\"${{APP_URL}}/auth/keycloak/callback\"\n\
+             \n\
+             # Admin console: {}/admin (user: {})\n",
+            Self::url(instance),
+            Self::realm(instance),
+            Self::client_id(instance),
+            Self::client_secret(instance),
+            Self::url(instance),
+            Self::admin_credentials(instance).0
+        )
+    }
+
+    /// NextAuth / Auth.js .env block
+    pub fn nextauth_env(instance: &Instance) -> String {
+        format!(
+            "# Auth.js / NextAuth (Keycloak provider)\n\
+             AUTH_KEYCLOAK_ID={}\n\
+             AUTH_KEYCLOAK_SECRET={}\n\
+             AUTH_KEYCLOAK_ISSUER={}\n\
+             \n\
+             # NextAuth v4 reads KEYCLOAK_CLIENT_ID, KEYCLOAK_CLIENT_SECRET and KEYCLOAK_ISSUER\n",
+            Self::client_id(instance),
+            Self::client_secret(instance),
+            Self::issuer(instance)
+        )
+    }
+
+    /// The Keycloak instance with this ID
+    pub fn find_instance(store: &ConfigStore, id: Uuid) -> Result<Instance, String> {
+        let instance = store.get_instance(id)?;
+        if instance.service_type != ServiceType::Keycloak {
+            return Err(format!("'{}' is not a Keycloak instance", instance.name));
+        }
+        Ok(instance)
+    }
+
+    /// Create the realm, client and test user that are missing
+    ///
+    /// Keycloak has to be running.
+    pub fn provision(instance: &Instance) -> Result<KeycloakProvision, String> {
+        let admin = AdminClient::login(instance)?;
+        let steps = vec![
+            admin.ensure_realm(instance)?,
+            admin.ensure_client(instance)?,
+            admin.ensure_test_user(instance)?,
+        ];
+        Ok(KeycloakProvision {
+            steps,
+            env: Self::laravel_env(instance),
+        })
+    }
+
+    /// Provision on a background thread once the server answers
+    ///
+    /// Results are appended to the instance log, since starting shouldn't
+    /// wait on (or fail because of) provisioning.
+    pub fn provision_in_background(instance: Instance, log_path: PathBuf) {
+        if !Self::auto_provision(&instance) {
+            return;
+        }
+        std::thread::spawn(move || {
+            if let Err(e) = wait_until_ready(&instance) {
+                append_log(&log_path, &format!("Provisioning skipped: {}", e));
+                return;
+            }
+            match Self::provision(&instance) {
+                Ok(result) => {
+                    for step in result.steps {
+                        append_log(&log_path, &step);
+                    }
+                }
+                Err(e) => append_log(&log_path, &format!("Provisioning failed: {}", e)),
+            }
+        });
+    }
+}
+
+fn append_log(log_path: &Path, line: &str) {
+    if let Ok(mut file) = OpenOptions::new().append(true).open(log_path) {
+        let _ = writeln!(file, "[burd] {}", line);
+    }
+}
+
+/// Wait until the master realm answers (the first start takes a while)
+fn wait_until_ready(instance: &Instance) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}/realms/master", KeycloakService::url(instance));
+    let deadline = Instant::now() + READY_TIMEOUT;
+    while !client
+        .get(&url)
+        .send()
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+    {
+        if Instant::now() > deadline {
+            return Err("Keycloak did not become ready".to_string());
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    Ok(())
+}
+
+/// Admin REST API session, logged in to the master realm
+struct AdminClient {
+    client: Client,
+    base: String,
+    token: String,
+}
+
+impl AdminClient {
+    fn login(instance: &Instance) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let base = KeycloakService::url(instance);
+        let (user, password) = KeycloakService::admin_credentials(instance);
+
+        let response = client
+            .post(format!(
+                "{}/realms/master/protocol/openid-connect/token",
+                base
+            ))
+            .form(&[
+                ("grant_type", "password"),
+                ("client_id", "admin-cli"),
+                ("username", user),
+                ("password", password),
+            ])
+            .send()
+            .map_err(|e| format!("Failed to reach Keycloak: {}", e))?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(format!(
+                "Keycloak rejected the admin login for '{}'. The admin account is only \
+                 created on first start; update the instance's admin password to match.",
+                user
+            ));
+        }
+        let body: Value = response
+            .error_for_status()
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Keycloak admin login failed: {}", e))?;
+        let token = body["access_token"]
+            .as_str()
+            .ok_or("Keycloak returned no access token")?
+            .to_string();
+
+        Ok(Self {
+            client,
+            base,
+            token,
+        })
+    }
+
+    fn admin_url(&self, path: &str) -> String {
+        format!("{}/admin/realms{}", self.base, path)
+    }
+
+    /// GET an admin resource; None when it doesn't exist
+    fn get(&self, path: &str) -> Result<Option<Value>, String> {
+        let response = self
+            .client
+            .get(self.admin_url(path))
+            .bearer_auth(&self.token)
+            .send()
+            .map_err(|e| format!("Keycloak request failed: {}", e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response
+            .error_for_status()
+            .and_then(|r| r.json())
+            .map(Some)
+            .map_err(|e| format!("Keycloak request failed: {}", e))
+    }
+
+    fn create(&self, path: &str, body: &Value) -> Result<(), String> {
+        self.client
+            .post(self.admin_url(path))
+            .bearer_auth(&self.token)
+            .json(body)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .map(|_| ())
+            .map_err(|e| format!("Keycloak request failed: {}", e))
+    }
+
+    fn ensure_realm(&self, instance: &Instance) -> Result<String, String> {
+        let realm = KeycloakService::realm(instance);
+        if self.get(&format!("/{}", realm))?.is_some() {
+            return Ok(format!("Realm '{}' exists", realm));
+        }
+        self.create("", &json!({ "realm": realm, "enabled": true }))?;
+        Ok(format!("Created realm '{}'", realm))
+    }
+
+    fn ensure_client(&self, instance: &Instance) -> Result<String, String> {
+        let realm = KeycloakService::realm(instance);
+        let client_id = KeycloakService::client_id(instance);
+        let existing = self
+            .get(&format!("/{}/clients?clientId={}", realm, client_id))?
+            .and_then(|clients| clients.as_array().map(|c| !c.is_empty()))
+            .unwrap_or(false);
+        if existing {
+            return Ok(format!("Client '{}' exists", client_id));
+        }
+
+        self.create(
+            &format!("/{}/clients", realm),
+            &json!({
+                "clientId": client_id,
+                "protocol": "openid-connect",
+                "publicClient": false,
+                "secret": KeycloakService::client_secret(instance),
+                "redirectUris": KeycloakService::redirect_uris(instance),
+                "webOrigins": ["+"],
+                "standardFlowEnabled": true,
+                "directAccessGrantsEnabled": true
+            }),
+        )?;
+        Ok(format!("Created client '{}'", client_id))
+    }
+
+    fn ensure_test_user(&self, instance: &Instance) -> Result<String, String> {
+        let realm = KeycloakService::realm(instance);
+        let (username, password) = KeycloakService::test_user(instance);
+        let existing = self
+            .get(&format!(
+                "/{}/users?username={}&exact=true",
+                realm, username
+            ))?
+            .and_then(|users| users.as_array().map(|u| !u.is_empty()))
+            .unwrap_or(false);
+        if existing {
+            return Ok(format!("User '{}' exists", username));
+        }
+
+        // A complete profile, so the first login isn't asked to fill it in
+        self.create(
+            &format!("/{}/users", realm),
+            &json!({
+                "username": username,
+                "enabled": true,
+                "email": format!("{}@example.com", username),
+                "emailVerified": true,
+                "firstName": "Test",
+                "lastName": "User",
+                "credentials": [{
+                    "type": "password",
+                    "value": password,
+                    "temporary": false
+                }]
+            }),
+        )?;
+        Ok(format!("Created user '{}'", username))
+    }
+}
+
+impl ServiceDefinition for KeycloakService {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Keycloak
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Keycloak"
+    }
+
+    fn default_port(&self) -> u16 {
+        8180
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "kc.sh"
+    }
+
+    fn version_source(&self) -> VersionSource {
+        VersionSource::Static(vec!["latest"])
+    }
+
+    fn download_method(&self, version: &str, _arch: &str) -> DownloadMethod {
+        // services.json installs the Homebrew formula (which brings a JDK);
+        // the upstream archive needs a Java runtime of its own
+        DownloadMethod::Direct {
+            url: format!(
+                "https://github.com/keycloak/keycloak/releases/download/{}/keycloak-{}.tar.gz",
+                version, version
+            ),
+            is_archive: true,
+            checksum: None, // TODO: Add SHA256 checksums for binary verification
+        }
+    }
+
+    fn health_check(&self) -> HealthCheck {
+        HealthCheck::Http {
+            path: "/realms/master".to_string(),
+        }
+    }
+
+    fn start_args(&self, instance: &Instance, data_dir: &Path) -> Vec<String> {
+        let database = data_dir.join("keycloakdb");
+        vec![
+            "start-dev".to_string(),
+            "--http-host=127.0.0.1".to_string(),
+            format!("--http-port={}", instance.port),
+            "--db=dev-file".to_string(),
+            format!(
+                "--db-url=jdbc:h2:file:{};NON_KEYWORDS=VALUE",
+                database.to_string_lossy()
+            ),
+        ]
+    }
+
+    fn env_vars(&self, instance: &Instance, _domain: Option<&str>) -> Vec<(String, String)> {
+        let (user, password) = Self::admin_credentials(instance);
+        // Keycloak 26 reads the KC_BOOTSTRAP_ names, earlier versions the KEYCLOAK_ ones
+        vec![
+            ("KC_BOOTSTRAP_ADMIN_USERNAME".to_string(), user.to_string()),
+            (
+                "KC_BOOTSTRAP_ADMIN_PASSWORD".to_string(),
+                password.to_string(),
+            ),
+            ("KEYCLOAK_ADMIN".to_string(), user.to_string()),
+            ("KEYCLOAK_ADMIN_PASSWORD".to_string(), password.to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    fn instance(config: Value) -> Instance {
+        InstanceBuilder::new()
+            .port(8180)
+            .service_type(ServiceType::Keycloak)
+            .config(config)
+            .build()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let instance = instance(json!({}));
+        assert_eq!(
+            KeycloakService::admin_credentials(&instance),
+            ("admin", "admin")
+        );
+        assert_eq!(KeycloakService::realm(&instance), "burd");
+        assert_eq!(KeycloakService::client_id(&instance), "laravel");
+        assert_eq!(
+            KeycloakService::client_secret(&instance),
+            instance.id.simple().to_string()
+        );
+        assert_eq!(KeycloakService::redirect_uris(&instance), vec!["*"]);
+        assert!(KeycloakService::auto_provision(&instance));
+        assert_eq!(
+            KeycloakService::issuer(&instance),
+            "http://127.0.0.1:8180/realms/burd"
+        );
+    }
+
+    #[test]
+    fn test_configured_settings() {
+        let instance = instance(json!({
+            "admin_user": "root",
+            "admin_password": "secret",
+            "realm": "shop",
+            "client_id": "storefront",
+            "client_secret": "s3cret",
+            "redirect_uris": "https://shop.burd/*, http://localhost:3000/*,",
+            "auto_provision": "false"
+        }));
+        assert_eq!(
+            KeycloakService::admin_credentials(&instance),
+            ("root", "secret")
+        );
+        assert_eq!(KeycloakService::client_secret(&instance), "s3cret");
+        assert_eq!(
+            KeycloakService::redirect_uris(&instance),
+            vec!["https://shop.burd/*", "http://localhost:3000/*"]
+        );
+        assert!(!KeycloakService::auto_provision(&instance));
+
+        let env = KeycloakService::laravel_env(&instance);
+        assert!(env.contains("KEYCLOAK_REALM=shop\n"));
+        assert!(env.contains("KEYCLOAK_CLIENT_ID=storefront\n"));
+        assert!(env.contains("KEYCLOAK_CLIENT_SECRET=s3cret\n"));
+
+        let env = KeycloakService::nextauth_env(&instance);
+        assert!(env.contains("AUTH_KEYCLOAK_ISSUER=http://127.0.0.1:8180/realms/shop\n"));
+    }
+
+    #[test]
+    fn test_start_args_use_instance_database() {
+        let instance = instance(json!({}));
+        let args = KeycloakService.start_args(&instance, Path::new("/data/kc"));
+        assert_eq!(args[0], "start-dev");
+        assert!(args.contains(&"--http-port=8180".to_string()));
+        assert!(args
+            .contains(&"--db-url=jdbc:h2:file:/data/kc/keycloakdb;NON_KEYWORDS=VALUE".to_string()));
+    }
+}
@@ -1,9 +1,145 @@
-use crate::config::{Instance, ServiceType};
+//! Mailpit Service Definition
+//!
+//! Several Mailpit instances can run side by side. Projects are routed to the
+//! Mailpit in their own stack when there is one, falling back to the first
+//! Mailpit otherwise.
+
+use crate::config::{Config, Instance, ServiceType};
+use crate::platform::Os;
+use crate::port_allocator;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::path::Path;
+use uuid::Uuid;
+
+/// Default SMTP port used by Mailpit
+pub const DEFAULT_SMTP_PORT: u16 = 1025;
 
 pub struct MailpitService;
 
+impl MailpitService {
+    /// Get the SMTP port for a Mailpit instance
+    ///
+    /// `smtp_port` is historically stored as a string; accept either shape.
+    pub fn smtp_port(instance: &Instance) -> u16 {
+        instance
+            .config
+            .get("smtp_port")
+            .and_then(|v| {
+                v.as_str()
+                    .and_then(|s| s.parse::<u16>().ok())
+                    .or_else(|| v.as_u64().and_then(|n| u16::try_from(n).ok()))
+            })
+            .unwrap_or(DEFAULT_SMTP_PORT)
+    }
+
+    /// All Mailpit instances in config order
+    pub fn instances(config: &Config) -> Vec<&Instance> {
+        config
+            .instances
+            .iter()
+            .filter(|i| i.service_type == ServiceType::Mailpit)
+            .collect()
+    }
+
+    /// Resolve candidate Mailpit instances for a request
+    ///
+    /// With an explicit ID only that instance is returned; otherwise every
+    /// Mailpit is returned so the caller can pick the first running one.
+    pub fn candidates<'a>(
+        config: &'a Config,
+        instance_id: Option<&str>,
+    ) -> Result<Vec<&'a Instance>, String> {
+        match instance_id {
+            Some(id) => {
+                let uuid = Uuid::parse_str(id).map_err(|_| "Invalid instance ID")?;
+                let instance = config
+                    .instances
+                    .iter()
+                    .find(|i| i.id == uuid)
+                    .ok_or_else(|| format!("Instance {} not found", id))?;
+                if instance.service_type != ServiceType::Mailpit {
+                    return Err(format!(
+                        "Instance '{}' is not a Mailpit instance",
+                        instance.name
+                    ));
+                }
+                Ok(vec![instance])
+            }
+            None => {
+                let instances = Self::instances(config);
+                if instances.is_empty() {
+                    return Err("No Mailpit instance configured".to_string());
+                }
+                Ok(instances)
+            }
+        }
+    }
+
+    /// Pick the Mailpit instance a stack should send mail to
+    ///
+    /// Prefers a Mailpit in the same stack, then the first standalone Mailpit,
+    /// then any Mailpit at all.
+    pub fn for_stack(config: &Config, stack_id: Option<Uuid>) -> Option<&Instance> {
+        let instances = Self::instances(config);
+
+        if let Some(stack_id) = stack_id {
+            if let Some(instance) = instances.iter().find(|i| i.stack_id == Some(stack_id)) {
+                return Some(*instance);
+            }
+        }
+
+        instances
+            .iter()
+            .find(|i| i.stack_id.is_none())
+            .or_else(|| instances.first())
+            .copied()
+    }
+
+    /// Pick the Mailpit instance for a project directory
+    ///
+    /// The project is matched to the instance serving it (by document root or
+    /// working directory) and routed via that instance's stack.
+    pub fn for_project<'a>(config: &'a Config, project_dir: &Path) -> Option<&'a Instance> {
+        let project_instance = config.instances.iter().find(|i| {
+            ["document_root", "working_directory"].iter().any(|key| {
+                i.config
+                    .get(*key)
+                    .and_then(|v| v.as_str())
+                    .map(|dir| Path::new(dir).starts_with(project_dir))
+                    .unwrap_or(false)
+            })
+        });
+
+        Self::for_stack(config, project_instance.and_then(|i| i.stack_id))
+    }
+
+    /// Fill in a free SMTP port for a new Mailpit instance if none was given
+    ///
+    /// The default 1025 is kept when it's free so single-Mailpit setups are
+    /// unchanged; additional instances get the next free port above it.
+    pub fn assign_smtp_port(
+        config: &Config,
+        service_config: &mut serde_json::Value,
+    ) -> Result<(), String> {
+        let has_port = service_config
+            .get("smtp_port")
+            .is_some_and(|v| !v.as_str().is_some_and(|s| s.trim().is_empty()));
+        if has_port {
+            return Ok(());
+        }
+
+        let port = port_allocator::next_free_port(config, DEFAULT_SMTP_PORT)?;
+        if let Some(settings) = service_config.as_object_mut() {
+            settings.insert(
+                "smtp_port".to_string(),
+                serde_json::Value::String(port.to_string()),
+            );
+        }
+
+        Ok(())
+    }
+}
+
 impl ServiceDefinition for MailpitService {
     fn service_type(&self) -> ServiceType {
         ServiceType::Mailpit
@@ -26,7 +162,17 @@ impl ServiceDefinition for MailpitService {
     }
 
     fn download_method(&self, version: &str, arch: &str) -> DownloadMethod {
-        if arch == "aarch64" {
+        if Os::host() == Os::Linux {
+            let arch_suffix = if arch == "aarch64" { "arm64" } else { "amd64" };
+            DownloadMethod::Direct {
+                url: format!(
+                    "https://github.com/axllent/mailpit/releases/download/v{}/mailpit-linux-{}.tar.gz",
+                    version, arch_suffix
+                ),
+                is_archive: true,
+                checksum: None, // TODO: Add SHA256 checksums for binary verification
+            }
+        } else if arch == "aarch64" {
             DownloadMethod::Direct {
                 url: format!(
                     "https://burdbin.s3.fr-par.scw.cloud/mailpit/{}/mailpit-{}-arm64.tar.gz",
@@ -54,11 +200,7 @@ impl ServiceDefinition for MailpitService {
     }
 
     fn start_args(&self, instance: &Instance, _data_dir: &Path) -> Vec<String> {
-        let smtp_port = instance
-            .config
-            .get("smtp_port")
-            .and_then(|v| v.as_str())
-            .unwrap_or("1025");
+        let smtp_port = Self::smtp_port(instance);
 
         vec![
             "--listen".to_string(),
@@ -68,3 +210,64 @@ impl ServiceDefinition for MailpitService {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, InstanceBuilder};
+    use serde_json::json;
+
+    fn mailpit(name: &str, port: u16) -> InstanceBuilder {
+        InstanceBuilder::new()
+            .name(name)
+            .port(port)
+            .service_type(ServiceType::Mailpit)
+    }
+
+    #[test]
+    fn test_smtp_port_accepts_string_and_number() {
+        assert_eq!(MailpitService::smtp_port(&mailpit("a", 8025).build()), 1025);
+        let instance = mailpit("a", 8025)
+            .config(json!({ "smtp_port": "1026" }))
+            .build();
+        assert_eq!(MailpitService::smtp_port(&instance), 1026);
+        let instance = mailpit("a", 8025)
+            .config(json!({ "smtp_port": 1027 }))
+            .build();
+        assert_eq!(MailpitService::smtp_port(&instance), 1027);
+    }
+
+    #[test]
+    fn test_project_routed_to_stack_mailpit() {
+        let stack_id = Uuid::new_v4();
+        let config = ConfigBuilder::new()
+            .instances(vec![
+                mailpit("shared", 8025).build(),
+                mailpit("shop-mail", 8026).stack_id(stack_id).build(),
+                InstanceBuilder::new()
+                    .service_type(ServiceType::FrankenPHP)
+                    .config(json!({ "document_root": "/sites/shop/public" }))
+                    .stack_id(stack_id)
+                    .build(),
+            ])
+            .build();
+
+        let shop = MailpitService::for_project(&config, Path::new("/sites/shop")).unwrap();
+        assert_eq!(shop.name, "shop-mail");
+
+        let blog = MailpitService::for_project(&config, Path::new("/sites/blog")).unwrap();
+        assert_eq!(blog.name, "shared");
+    }
+
+    #[test]
+    fn test_candidates_rejects_non_mailpit() {
+        let other = InstanceBuilder::new().build();
+        let other_id = other.id.to_string();
+        let config = ConfigBuilder::new()
+            .instances(vec![other, mailpit("mail", 8025).build()])
+            .build();
+
+        assert!(MailpitService::candidates(&config, Some(&other_id)).is_err());
+        assert_eq!(MailpitService::candidates(&config, None).unwrap().len(), 1);
+    }
+}
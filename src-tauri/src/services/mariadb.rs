@@ -3,6 +3,8 @@
 //! Uses bundled MariaDB binary with per-instance configuration files.
 
 use crate::config::{get_service_bin_dir, Instance, ServiceType};
+use crate::services::db_locale::DbLocaleSettings;
+use crate::services::mysql_config::MySqlServerSettings;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -78,6 +80,10 @@ impl MariaDBService {
                 .map_err(|e| format!("Failed to create conf.d directory: {}", e))?;
         }
 
+        // Structured server settings from the instance config
+        let server_settings = MySqlServerSettings::from_instance(instance)?.render();
+        let locale_settings = DbLocaleSettings::from_instance(instance)?.render_mysql();
+
         let config_content = format!(
             r#"[mysqld]
 datadir="{}"
@@ -90,7 +96,7 @@ lc-messages-dir="{}"
 bind-address=127.0.0.1
 disable_log_bin
 skip-grant-tables
-
+{}{}
 # User custom configuration (files in conf.d/ survive restarts)
 !includedir {}
 "#,
@@ -101,6 +107,8 @@ skip-grant-tables
             error_log.to_string_lossy(),
             plugin_dir.to_string_lossy(),
             lc_messages_dir.to_string_lossy(),
+            server_settings,
+            locale_settings,
             conf_d.to_string_lossy(),
         );
 
@@ -154,6 +162,13 @@ impl ServiceDefinition for MariaDBService {
         HealthCheck::Tcp
     }
 
+    fn env_vars(&self, instance: &Instance, _domain: Option<&str>) -> Vec<(String, String)> {
+        // Invalid settings were rejected when saved, and fail generate_config
+        DbLocaleSettings::from_instance(instance)
+            .map(|settings| settings.mysql_env())
+            .unwrap_or_default()
+    }
+
     fn start_args(&self, _instance: &Instance, data_dir: &Path) -> Vec<String> {
         let config_path = data_dir.join("my.cnf");
         vec![format!("--defaults-file={}", config_path.to_string_lossy())]
@@ -163,6 +178,11 @@ impl ServiceDefinition for MariaDBService {
         true
     }
 
+    fn init_probe(&self) -> &'static [&'static str] {
+        // The system tables
+        &["mysql"]
+    }
+
     fn init_command(&self, data_dir: &Path) -> Option<(String, Vec<String>)> {
         let basedir = Self::get_basedir().ok()?;
         let install_db = basedir.join("bin/mariadb-install-db");
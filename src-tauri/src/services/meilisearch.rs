@@ -1,6 +1,14 @@
 use crate::config::{Instance, ServiceType};
+use crate::platform::Os;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Dump imported on the next start after a snapshot restore
+///
+/// Meilisearch writes dumps to `dumps/` in its working directory (the data dir).
+pub fn import_dump_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("dumps").join("import.dump")
+}
 
 pub struct MeilisearchService;
 
@@ -28,10 +36,11 @@ impl ServiceDefinition for MeilisearchService {
     }
 
     fn download_method(&self, _version: &str, arch: &str) -> DownloadMethod {
-        let asset_pattern = if arch == "aarch64" {
-            "meilisearch-macos-apple-silicon"
-        } else {
-            "meilisearch-macos-amd64"
+        let asset_pattern = match (Os::host(), arch == "aarch64") {
+            (Os::MacOs, true) => "meilisearch-macos-apple-silicon",
+            (Os::MacOs, false) => "meilisearch-macos-amd64",
+            (Os::Linux, true) => "meilisearch-linux-aarch64",
+            (Os::Linux, false) => "meilisearch-linux-amd64",
         };
         // Use specific version release URL
         DownloadMethod::GitHubRelease {
@@ -65,6 +74,14 @@ impl ServiceDefinition for MeilisearchService {
             }
         }
 
+        // Import a restored snapshot; ignored once the database has been rebuilt
+        let import_dump = import_dump_path(data_dir);
+        if import_dump.exists() {
+            args.push("--import-dump".to_string());
+            args.push(import_dump.to_string_lossy().to_string());
+            args.push("--ignore-dump-if-db-exists".to_string());
+        }
+
         args
     }
 }
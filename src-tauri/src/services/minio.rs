@@ -1,9 +1,427 @@
+//! MinIO object storage
+//!
+//! Besides running the server, Burd seeds the buckets listed in the
+//! instance's `buckets` config once the server is up, so the bucket named in
+//! the generated `AWS_BUCKET` exists without visiting the console. Each entry
+//! is `name` or `name:policy`, with the policy presets `private` (default),
+//! `public-read`, and `public`.
+
 use crate::config::{Instance, ServiceType};
+use crate::platform::Os;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
-use std::path::Path;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Region MinIO reports by default (used for request signing)
+const REGION: &str = "us-east-1";
+
+/// How long to wait for the server before giving up on seeding
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct MinIOService;
 
+/// Anonymous access presets for a seeded bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketPolicy {
+    /// No anonymous access
+    Private,
+    /// Anyone can download objects (e.g. public media)
+    PublicRead,
+    /// Anyone can list, download, upload, and delete
+    Public,
+}
+
+impl BucketPolicy {
+    /// Parse a preset name from instance config
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "private" | "none" => Ok(Self::Private),
+            "public-read" | "download" => Ok(Self::PublicRead),
+            "public" => Ok(Self::Public),
+            other => Err(format!(
+                "Unknown bucket policy '{}'. Use 'private', 'public-read', or 'public'",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::PublicRead => "public-read",
+            Self::Public => "public",
+        }
+    }
+
+    /// Bucket policy document, or `None` when the bucket should have no policy
+    pub(crate) fn document(&self, bucket: &str) -> Option<serde_json::Value> {
+        let bucket_arn = format!("arn:aws:s3:::{}", bucket);
+        let objects_arn = format!("arn:aws:s3:::{}/*", bucket);
+
+        let statements = match self {
+            Self::Private => return None,
+            Self::PublicRead => serde_json::json!([{
+                "Effect": "Allow",
+                "Principal": { "AWS": ["*"] },
+                "Action": ["s3:GetObject"],
+                "Resource": [objects_arn]
+            }]),
+            Self::Public => serde_json::json!([
+                {
+                    "Effect": "Allow",
+                    "Principal": { "AWS": ["*"] },
+                    "Action": ["s3:GetBucketLocation", "s3:ListBucket"],
+                    "Resource": [bucket_arn]
+                },
+                {
+                    "Effect": "Allow",
+                    "Principal": { "AWS": ["*"] },
+                    "Action": ["s3:GetObject", "s3:PutObject", "s3:DeleteObject"],
+                    "Resource": [objects_arn]
+                }
+            ]),
+        };
+
+        Some(serde_json::json!({
+            "Version": "2012-10-17",
+            "Statement": statements
+        }))
+    }
+}
+
+/// A bucket to create on start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketSeed {
+    pub name: String,
+    pub policy: BucketPolicy,
+}
+
+/// Check a name against the S3 bucket naming rules
+pub fn validate_bucket_name(name: &str) -> Result<(), String> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.');
+    let valid_ends = name
+        .chars()
+        .next()
+        .zip(name.chars().last())
+        .map(|(first, last)| first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric())
+        .unwrap_or(false);
+
+    if !(3..=63).contains(&name.len()) || !valid_chars || !valid_ends || name.contains("..") {
+        return Err(format!(
+            "Invalid bucket name '{}'. Use 3-63 lowercase letters, digits, dots, or hyphens, \
+             starting and ending with a letter or digit",
+            name
+        ));
+    }
+    Ok(())
+}
+
+impl MinIOService {
+    /// Root credentials (access key, secret key) from instance config
+    pub fn credentials(instance: &Instance) -> (String, String) {
+        let get = |key: &str| {
+            instance
+                .config
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .unwrap_or("minioadmin")
+                .to_string()
+        };
+        (get("root_user"), get("root_password"))
+    }
+
+    /// Buckets listed in the instance's `buckets` config
+    ///
+    /// Accepts a comma-separated string (as entered in the UI) or an array of
+    /// `name[:policy]` entries.
+    pub fn bucket_seeds(instance: &Instance) -> Result<Vec<BucketSeed>, String> {
+        let entries: Vec<String> = match instance.config.get("buckets") {
+            Some(serde_json::Value::String(s)) => s.split(',').map(String::from).collect(),
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect(),
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut seeds: Vec<BucketSeed> = Vec::new();
+        for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let (name, policy) = entry.split_once(':').unwrap_or((entry, ""));
+            let name = name.trim().to_string();
+            validate_bucket_name(&name)?;
+            let policy = BucketPolicy::parse(policy)?;
+
+            if !seeds.iter().any(|s| s.name == name) {
+                seeds.push(BucketSeed { name, policy });
+            }
+        }
+        Ok(seeds)
+    }
+
+    /// Seed buckets on a background thread once the server answers
+    ///
+    /// Results are appended to the instance log, since starting shouldn't
+    /// wait on (or fail because of) bucket setup.
+    pub fn seed_buckets_in_background(instance: Instance, log_path: PathBuf) {
+        let seeds = match Self::bucket_seeds(&instance) {
+            Ok(seeds) if seeds.is_empty() => return,
+            Ok(seeds) => seeds,
+            Err(e) => {
+                append_log(&log_path, &format!("Bucket seeding skipped: {}", e));
+                return;
+            }
+        };
+
+        std::thread::spawn(move || {
+            for line in seed_buckets(&instance, &seeds) {
+                append_log(&log_path, &line);
+            }
+        });
+    }
+}
+
+fn append_log(log_path: &Path, line: &str) {
+    if let Ok(mut file) = OpenOptions::new().append(true).open(log_path) {
+        let _ = writeln!(file, "[burd] {}", line);
+    }
+}
+
+/// Wait for the server, then create each bucket and apply its policy
+///
+/// Returns one log line per bucket.
+fn seed_buckets(instance: &Instance, seeds: &[BucketSeed]) -> Vec<String> {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return vec![format!("Bucket seeding failed: {}", e)],
+    };
+
+    let health_url = format!("http://127.0.0.1:{}/minio/health/live", instance.port);
+    let deadline = Instant::now() + READY_TIMEOUT;
+    while !client
+        .get(&health_url)
+        .send()
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+    {
+        if Instant::now() > deadline {
+            return vec!["Bucket seeding skipped: MinIO did not become ready".to_string()];
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    let s3 = S3Client::new(client, instance);
+    seeds
+        .iter()
+        .map(|seed| match s3.ensure_bucket(seed) {
+            Ok(()) => format!("Bucket '{}' ready ({:?})", seed.name, seed.policy),
+            Err(e) => format!("Bucket '{}' failed: {}", seed.name, e),
+        })
+        .collect()
+}
+
+/// Minimal S3 client for the local server, signing with AWS Signature V4
+pub(crate) struct S3Client {
+    client: reqwest::blocking::Client,
+    host: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3Client {
+    pub(crate) fn new(client: reqwest::blocking::Client, instance: &Instance) -> Self {
+        let (access_key, secret_key) = MinIOService::credentials(instance);
+        Self {
+            client,
+            host: format!("127.0.0.1:{}", instance.port),
+            access_key,
+            secret_key,
+        }
+    }
+
+    /// Create the bucket if missing and make its policy match the preset
+    fn ensure_bucket(&self, seed: &BucketSeed) -> Result<(), String> {
+        let status = self.send("PUT", &seed.name, "", Vec::new())?;
+        // 409 means the bucket already exists
+        if !status.is_success() && status.as_u16() != 409 {
+            return Err(format!("create returned HTTP {}", status));
+        }
+        self.set_policy(&seed.name, seed.policy)
+    }
+
+    /// Make a bucket's policy match a preset
+    pub(crate) fn set_policy(&self, bucket: &str, policy: BucketPolicy) -> Result<(), String> {
+        let status = match policy.document(bucket) {
+            Some(document) => {
+                self.send("PUT", bucket, "policy=", document.to_string().into_bytes())?
+            }
+            None => self.send("DELETE", bucket, "policy=", Vec::new())?,
+        };
+        // Deleting a policy that was never set reports 404
+        let no_policy_to_delete = policy == BucketPolicy::Private && status.as_u16() == 404;
+        if !status.is_success() && !no_policy_to_delete {
+            return Err(format!("setting policy returned HTTP {}", status));
+        }
+        Ok(())
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        bucket: &str,
+        query: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::StatusCode, String> {
+        self.request("s3", method, &format!("/{}", bucket), query, None, body)
+            .map(|(status, _)| status)
+    }
+
+    /// Send a signed request to `service` (`s3`, or `sts` for temporary
+    /// credentials), returning the status and response body
+    pub(crate) fn request(
+        &self,
+        service: &str,
+        method: &str,
+        path: &str,
+        query: &str,
+        content_type: Option<&str>,
+        body: Vec<u8>,
+    ) -> Result<(reqwest::StatusCode, String), String> {
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sign_v4(
+            &self.access_key,
+            &self.secret_key,
+            &SignedRequest {
+                service,
+                method,
+                host: &self.host,
+                path,
+                query,
+                payload: &body,
+                amz_date: &amz_date,
+            },
+        );
+
+        let mut url = format!("http://{}{}", self.host, path);
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|e| format!("Invalid method: {}", e))?;
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-content-sha256", hex_sha256(&body))
+            .header("Authorization", authorization);
+        if let Some(content_type) = content_type {
+            request = request.header("Content-Type", content_type);
+        }
+        let response = request.body(body).send().map_err(|e| e.to_string())?;
+        let status = response.status();
+        let text = response.text().map_err(|e| e.to_string())?;
+        Ok((status, text))
+    }
+}
+
+/// The parts of a request covered by the signature
+struct SignedRequest<'a> {
+    service: &'a str,
+    method: &'a str,
+    host: &'a str,
+    path: &'a str,
+    /// Already-canonical query string (e.g. `policy=`)
+    query: &'a str,
+    payload: &'a [u8],
+    /// `YYYYMMDDTHHMMSSZ`
+    amz_date: &'a str,
+}
+
+/// Build the `Authorization` header for a request (AWS Signature V4)
+fn sign_v4(access_key: &str, secret_key: &str, request: &SignedRequest) -> String {
+    let date = &request.amz_date[..8];
+    let scope = format!("{}/{}/{}/aws4_request", date, REGION, request.service);
+    let payload_hash = hex_sha256(request.payload);
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        request.method,
+        request.path,
+        request.query,
+        request.host,
+        payload_hash,
+        request.amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        request.amz_date,
+        scope,
+        hex_sha256(canonical_request.as_bytes())
+    );
+
+    let signature = hmac_sha256(
+        &signing_key(secret_key, date, request.service),
+        string_to_sign.as_bytes(),
+    );
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key,
+        scope,
+        signed_headers,
+        hex(&signature)
+    )
+}
+
+/// Derive the SigV4 signing key for a date (`YYYYMMDD`) and service
+fn signing_key(secret_key: &str, date: &str, service: &str) -> [u8; 32] {
+    let key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, REGION.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 impl ServiceDefinition for MinIOService {
     fn service_type(&self) -> ServiceType {
         ServiceType::MinIO
@@ -35,8 +453,10 @@ impl ServiceDefinition for MinIOService {
         // Direct download from MinIO CDN
         // For specific: https://dl.min.io/server/minio/release/darwin-arm64/archive/minio.RELEASE.2024-12-18T13-15-44Z
         let url = format!(
-            "https://dl.min.io/server/minio/release/darwin-{}/archive/minio.{}",
-            arch_suffix, version
+            "https://dl.min.io/server/minio/release/{}-{}/archive/minio.{}",
+            Os::host().download_name(),
+            arch_suffix,
+            version
         );
         DownloadMethod::Direct {
             url,
@@ -64,19 +484,7 @@ impl ServiceDefinition for MinIOService {
     }
 
     fn env_vars(&self, instance: &Instance, _domain: Option<&str>) -> Vec<(String, String)> {
-        let root_user = instance
-            .config
-            .get("root_user")
-            .and_then(|v| v.as_str())
-            .unwrap_or("minioadmin")
-            .to_string();
-
-        let root_password = instance
-            .config
-            .get("root_password")
-            .and_then(|v| v.as_str())
-            .unwrap_or("minioadmin")
-            .to_string();
+        let (root_user, root_password) = Self::credentials(instance);
 
         vec![
             ("MINIO_ROOT_USER".to_string(), root_user),
@@ -84,3 +492,101 @@ impl ServiceDefinition for MinIOService {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn test_bucket_seeds_from_config() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::MinIO)
+            .config(json!({ "buckets": "uploads:public-read, backups ,media:public,uploads" }))
+            .build();
+
+        let seeds = MinIOService::bucket_seeds(&instance).unwrap();
+        assert_eq!(
+            seeds,
+            vec![
+                BucketSeed {
+                    name: "uploads".to_string(),
+                    policy: BucketPolicy::PublicRead
+                },
+                BucketSeed {
+                    name: "backups".to_string(),
+                    policy: BucketPolicy::Private
+                },
+                BucketSeed {
+                    name: "media".to_string(),
+                    policy: BucketPolicy::Public
+                },
+            ]
+        );
+
+        let array = InstanceBuilder::new()
+            .config(json!({ "buckets": ["assets:download"] }))
+            .build();
+        assert_eq!(
+            MinIOService::bucket_seeds(&array).unwrap()[0].policy,
+            BucketPolicy::PublicRead
+        );
+
+        let invalid = InstanceBuilder::new()
+            .config(json!({ "buckets": "Uploads" }))
+            .build();
+        assert!(MinIOService::bucket_seeds(&invalid).is_err());
+
+        let bad_policy = InstanceBuilder::new()
+            .config(json!({ "buckets": "uploads:world" }))
+            .build();
+        assert!(MinIOService::bucket_seeds(&bad_policy).is_err());
+    }
+
+    #[test]
+    fn test_validate_bucket_name() {
+        assert!(validate_bucket_name("my-app.uploads").is_ok());
+        assert!(validate_bucket_name("ab").is_err());
+        assert!(validate_bucket_name("-uploads").is_err());
+        assert!(validate_bucket_name("my..bucket").is_err());
+        assert!(validate_bucket_name("my_bucket").is_err());
+    }
+
+    #[test]
+    fn test_policy_documents() {
+        assert!(BucketPolicy::Private.document("b").is_none());
+
+        let read = BucketPolicy::PublicRead.document("media").unwrap();
+        assert_eq!(read["Statement"][0]["Action"], json!(["s3:GetObject"]));
+        assert_eq!(
+            read["Statement"][0]["Resource"],
+            json!(["arn:aws:s3:::media/*"])
+        );
+
+        let public = BucketPolicy::Public.document("media").unwrap();
+        assert_eq!(public["Statement"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_signing_key() {
+        // Secret key from the AWS Signature V4 documentation examples
+        assert_eq!(
+            hex(&signing_key(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20130524",
+                "s3"
+            )),
+            "f117494eff5d09da21cbf7f0339559ea04fc9582d31299cb992be70a6b27c97a"
+        );
+    }
+}
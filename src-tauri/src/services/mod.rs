@@ -1,11 +1,14 @@
 pub mod beanstalkd;
 pub mod bun;
 pub mod centrifugo;
+pub mod custom;
+pub mod db_locale;
 pub mod frankenphp;
 pub mod frankenphp_park;
 pub mod frpc;
 pub mod gitea;
 pub mod key_value_service;
+pub mod keycloak;
 pub mod mailpit;
 pub mod mariadb;
 pub mod meilisearch;
@@ -13,8 +16,14 @@ pub mod memcached;
 pub mod minio;
 pub mod mongodb;
 pub mod mysql;
+pub mod mysql_config;
+pub mod opensearch;
+pub mod php_ini;
 pub mod postgresql;
+pub mod rabbitmq;
 pub mod redis;
+pub mod redis_topology;
+pub mod soketi;
 pub mod typesense;
 pub mod valkey;
 
@@ -112,6 +121,11 @@ pub trait ServiceDefinition: Send + Sync {
         None
     }
 
+    /// Files (relative to the data directory) a successful init leaves behind
+    fn init_probe(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     /// Get the process manager type for this service
     fn process_manager(&self) -> ProcessManager {
         ProcessManager::Binary
@@ -134,13 +148,18 @@ pub fn get_service(service_type: ServiceType) -> Box<dyn ServiceDefinition> {
         ServiceType::Valkey => Box::new(valkey::ValkeyService::new()),
         ServiceType::Mailpit => Box::new(mailpit::MailpitService),
         ServiceType::Beanstalkd => Box::new(beanstalkd::BeanstalkdService),
+        ServiceType::RabbitMQ => Box::new(rabbitmq::RabbitMQService),
+        ServiceType::OpenSearch => Box::new(opensearch::OpenSearchService),
         ServiceType::Memcached => Box::new(memcached::MemcachedService),
         ServiceType::Frpc => Box::new(frpc::FrpcService),
         ServiceType::Caddy => {
             panic!("Caddy is an internal service and cannot be instantiated as a user service")
         }
         ServiceType::Centrifugo => Box::new(centrifugo::CentrifugoService),
+        ServiceType::Soketi => Box::new(soketi::SoketiService),
+        ServiceType::Keycloak => Box::new(keycloak::KeycloakService),
         ServiceType::Gitea => Box::new(gitea::GiteaService),
         ServiceType::Bun => Box::new(bun::BunService),
+        ServiceType::Custom(id) => Box::new(custom::CustomService::new(id)),
     }
 }
@@ -32,6 +32,7 @@ impl ServiceDefinition for MongoDBService {
         let arch_suffix = if arch == "aarch64" { "arm64" } else { "x86_64" };
         // Direct download from MongoDB
         // Example: https://fastdl.mongodb.org/osx/mongodb-macos-arm64-8.0.4.tgz
+        // Linux builds are per distribution, so only the macOS build is offered
         let url = format!(
             "https://fastdl.mongodb.org/osx/mongodb-macos-{}-{}.tgz",
             arch_suffix, version
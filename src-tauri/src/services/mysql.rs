@@ -3,6 +3,8 @@
 //! Uses bundled MySQL binary with per-instance configuration files.
 
 use crate::config::{get_service_bin_dir, Instance, ServiceType};
+use crate::services::db_locale::DbLocaleSettings;
+use crate::services::mysql_config::MySqlServerSettings;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -38,7 +40,6 @@ impl MySQLService {
     }
 
     /// Get the basedir for a specific version
-    #[allow(dead_code)]
     pub fn get_basedir_for_version(version: &str) -> Result<PathBuf, String> {
         let service_dir = get_service_bin_dir(ServiceType::MySQL)?;
         let version_dir = service_dir.join(version);
@@ -58,7 +59,6 @@ impl MySQLService {
     }
 
     /// Generate my.cnf configuration file for an instance
-    #[allow(dead_code)]
     pub fn generate_config(instance: &Instance, data_dir: &Path) -> Result<(), String> {
         let basedir = Self::get_basedir_for_version(&instance.version)?;
         let config_path = data_dir.join("my.cnf");
@@ -73,6 +73,10 @@ impl MySQLService {
                 .map_err(|e| format!("Failed to create conf.d directory: {}", e))?;
         }
 
+        // Structured server settings from the instance config
+        let server_settings = MySqlServerSettings::from_instance(instance)?.render();
+        let locale_settings = DbLocaleSettings::from_instance(instance)?.render_mysql();
+
         let config_content = format!(
             r#"[mysqld]
 datadir="{}"
@@ -84,7 +88,7 @@ plugin-dir="{}"
 bind-address=127.0.0.1
 skip-log-bin
 mysqlx=0
-
+{}{}
 # User custom configuration (files in conf.d/ survive restarts)
 !includedir {}
 "#,
@@ -94,6 +98,8 @@ mysqlx=0
             instance.port,
             error_log.to_string_lossy(),
             plugin_dir.to_string_lossy(),
+            server_settings,
+            locale_settings,
             conf_d.to_string_lossy(),
         );
 
@@ -147,6 +153,13 @@ impl ServiceDefinition for MySQLService {
         HealthCheck::Tcp
     }
 
+    fn env_vars(&self, instance: &Instance, _domain: Option<&str>) -> Vec<(String, String)> {
+        // Invalid settings were rejected when saved, and fail generate_config
+        DbLocaleSettings::from_instance(instance)
+            .map(|settings| settings.mysql_env())
+            .unwrap_or_default()
+    }
+
     fn start_args(&self, _instance: &Instance, data_dir: &Path) -> Vec<String> {
         let config_path = data_dir.join("my.cnf");
         vec![format!("--defaults-file={}", config_path.to_string_lossy())]
@@ -156,6 +169,11 @@ impl ServiceDefinition for MySQLService {
         true
     }
 
+    fn init_probe(&self) -> &'static [&'static str] {
+        // The system tables
+        &["mysql"]
+    }
+
     fn init_command(&self, data_dir: &Path) -> Option<(String, Vec<String>)> {
         let basedir = Self::get_basedir().ok()?;
         let mysqld = basedir.join("bin/mysqld");
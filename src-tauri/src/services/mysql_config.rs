@@ -0,0 +1,228 @@
+//! Shared my.cnf server settings for MySQL-compatible services
+//!
+//! MariaDB and MySQL both regenerate `my.cnf` on every start, so hand edits
+//! are lost. Common server settings are instead stored as structured instance
+//! config (`preset`, `sql_mode`, `max_connections`, `innodb_buffer_pool_size`)
+//! and rendered into the managed file here. Files in `conf.d/` are still
+//! included afterwards for anything not covered.
+
+use crate::config::Instance;
+
+/// sql_mode used by the strict preset (matches modern MySQL defaults)
+const STRICT_SQL_MODE: &str = "ONLY_FULL_GROUP_BY,STRICT_TRANS_TABLES,NO_ZERO_IN_DATE,NO_ZERO_DATE,ERROR_FOR_DIVISION_BY_ZERO,NO_ENGINE_SUBSTITUTION";
+
+/// sql_mode used by the legacy preset (lenient, for older applications)
+const LEGACY_SQL_MODE: &str = "NO_ENGINE_SUBSTITUTION";
+
+/// Named bundles of server settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MySqlPreset {
+    /// Server defaults, nothing rendered
+    Default,
+    /// Strict SQL modes, catches invalid data early
+    Strict,
+    /// Lenient SQL modes for older apps (e.g. legacy WordPress plugins)
+    Legacy,
+    /// More connections and a larger buffer pool for heavy local workloads
+    Performance,
+}
+
+impl MySqlPreset {
+    /// Parse a preset name from instance config
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "default" => Ok(Self::Default),
+            "strict" => Ok(Self::Strict),
+            "legacy" => Ok(Self::Legacy),
+            "performance" => Ok(Self::Performance),
+            other => Err(format!(
+                "Unknown preset '{}'. Use 'default', 'strict', 'legacy', or 'performance'",
+                other
+            )),
+        }
+    }
+
+    /// Settings this preset applies before explicit overrides
+    fn settings(&self) -> MySqlServerSettings {
+        match self {
+            Self::Default => MySqlServerSettings::default(),
+            Self::Strict => MySqlServerSettings {
+                sql_mode: Some(STRICT_SQL_MODE.to_string()),
+                ..Default::default()
+            },
+            Self::Legacy => MySqlServerSettings {
+                sql_mode: Some(LEGACY_SQL_MODE.to_string()),
+                ..Default::default()
+            },
+            Self::Performance => MySqlServerSettings {
+                max_connections: Some(500),
+                innodb_buffer_pool_size: Some("1G".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Structured server settings rendered into my.cnf
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MySqlServerSettings {
+    pub sql_mode: Option<String>,
+    pub max_connections: Option<u32>,
+    pub innodb_buffer_pool_size: Option<String>,
+}
+
+/// Read a config value as a trimmed, non-empty string (numbers are accepted too)
+fn config_string(instance: &Instance, key: &str) -> Option<String> {
+    let value = instance.config.get(key)?;
+    let value = match value {
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+/// Validate a sql_mode list (comma separated mode names)
+fn validate_sql_mode(value: &str) -> Result<String, String> {
+    let mode = value.trim().to_uppercase();
+    if !mode
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ',')
+    {
+        return Err(format!("Invalid sql_mode '{}'", value));
+    }
+    Ok(mode)
+}
+
+/// Validate a size like "256M" or "1G"
+fn validate_size(value: &str) -> Result<String, String> {
+    let size = value.trim().to_uppercase();
+    let digits = size.trim_end_matches(['K', 'M', 'G']);
+    let suffix_len = size.len() - digits.len();
+    if digits.is_empty() || suffix_len > 1 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!(
+            "Invalid innodb_buffer_pool_size '{}'. Use a number with an optional K, M, or G suffix",
+            value
+        ));
+    }
+    Ok(size)
+}
+
+impl MySqlServerSettings {
+    /// Build settings from an instance's config: preset first, then explicit values
+    pub fn from_instance(instance: &Instance) -> Result<Self, String> {
+        let preset = match config_string(instance, "preset") {
+            Some(name) => MySqlPreset::parse(&name)?,
+            None => MySqlPreset::Default,
+        };
+        let mut settings = preset.settings();
+
+        if let Some(mode) = config_string(instance, "sql_mode") {
+            settings.sql_mode = Some(validate_sql_mode(&mode)?);
+        }
+
+        if let Some(value) = config_string(instance, "max_connections") {
+            let connections: u32 = value
+                .parse()
+                .ok()
+                .filter(|n| (1..=100_000).contains(n))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid max_connections '{}'. Use a number between 1 and 100000",
+                        value
+                    )
+                })?;
+            settings.max_connections = Some(connections);
+        }
+
+        if let Some(value) = config_string(instance, "innodb_buffer_pool_size") {
+            settings.innodb_buffer_pool_size = Some(validate_size(&value)?);
+        }
+
+        Ok(settings)
+    }
+
+    /// Render the settings as `[mysqld]` option lines (empty if nothing is set)
+    pub fn render(&self) -> String {
+        let mut lines = Vec::new();
+
+        if let Some(mode) = &self.sql_mode {
+            lines.push(format!("sql_mode=\"{}\"", mode));
+        }
+        if let Some(connections) = self.max_connections {
+            lines.push(format!("max_connections={}", connections));
+        }
+        if let Some(size) = &self.innodb_buffer_pool_size {
+            lines.push(format!("innodb_buffer_pool_size={}", size));
+        }
+
+        if lines.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            "\n# Server settings (managed by Burd - change them in the instance settings)\n{}\n",
+            lines.join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServiceType;
+    use crate::test_utils::InstanceBuilder;
+    use serde_json::json;
+
+    fn instance_with(config: serde_json::Value) -> Instance {
+        InstanceBuilder::new()
+            .service_type(ServiceType::MariaDB)
+            .config(config)
+            .build()
+    }
+
+    #[test]
+    fn test_no_settings_renders_nothing() {
+        let settings = MySqlServerSettings::from_instance(&instance_with(json!({}))).unwrap();
+        assert_eq!(settings, MySqlServerSettings::default());
+        assert!(settings.render().is_empty());
+    }
+
+    #[test]
+    fn test_preset_with_overrides() {
+        let instance = instance_with(json!({
+            "preset": "performance",
+            "max_connections": "250",
+            "sql_mode": "no_engine_substitution"
+        }));
+        let settings = MySqlServerSettings::from_instance(&instance).unwrap();
+
+        assert_eq!(settings.max_connections, Some(250));
+        assert_eq!(settings.innodb_buffer_pool_size.as_deref(), Some("1G"));
+        assert_eq!(settings.sql_mode.as_deref(), Some("NO_ENGINE_SUBSTITUTION"));
+
+        let rendered = settings.render();
+        assert!(rendered.contains("max_connections=250"));
+        assert!(rendered.contains("innodb_buffer_pool_size=1G"));
+        assert!(rendered.contains("sql_mode=\"NO_ENGINE_SUBSTITUTION\""));
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        assert!(
+            MySqlServerSettings::from_instance(&instance_with(json!({"preset": "turbo"}))).is_err()
+        );
+        assert!(MySqlServerSettings::from_instance(&instance_with(
+            json!({"max_connections": "0"})
+        ))
+        .is_err());
+        assert!(MySqlServerSettings::from_instance(&instance_with(
+            json!({"innodb_buffer_pool_size": "1GB"})
+        ))
+        .is_err());
+        assert!(MySqlServerSettings::from_instance(&instance_with(
+            json!({"sql_mode": "STRICT\"\ninit_file=/tmp/x"})
+        ))
+        .is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! OpenSearch search engine
+//!
+//! Installed through Homebrew, which bundles the JDK it runs on. OpenSearch
+//! speaks the Elasticsearch 7 REST API, so projects written against
+//! Elasticsearch (Laravel Scout drivers, Explorer, FOSElastica, Searchkick)
+//! can use it as is.
+//!
+//! Every instance runs as its own single-node cluster with its own data and
+//! log directories, passed as `-E` settings so Homebrew's `opensearch.yml`
+//! doesn't have to be edited. The JVM heap comes from the instance's
+//! `heap_size` setting.
+
+use crate::config::{Instance, ServiceType};
+use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
+use std::path::Path;
+
+/// Heap used when none (or an invalid one) is configured
+const DEFAULT_HEAP_SIZE: &str = "512m";
+
+/// The transport port is the HTTP port plus this (9200 -> 9300, like a default node)
+const TRANSPORT_PORT_OFFSET: u16 = 100;
+
+pub struct OpenSearchService;
+
+impl OpenSearchService {
+    /// JVM heap size, e.g. `512m` or `2g`
+    ///
+    /// Accepts a number with an optional `k`, `m` or `g` suffix (megabytes
+    /// without one); anything else falls back to the default rather than
+    /// keeping the JVM from starting.
+    pub fn heap_size(instance: &Instance) -> String {
+        instance
+            .config
+            .get("heap_size")
+            .and_then(|v| {
+                v.as_str()
+                    .map(|s| s.trim().to_lowercase())
+                    .or_else(|| v.as_u64().map(|n| n.to_string()))
+            })
+            .and_then(|s| {
+                let digits = s.strip_suffix(['k', 'm', 'g']).unwrap_or(&s);
+                let size = digits.parse::<u32>().ok().filter(|n| *n > 0)?;
+                // A bare number would be bytes to the JVM
+                Some(if digits.len() == s.len() {
+                    format!("{}m", size)
+                } else {
+                    s
+                })
+            })
+            .unwrap_or_else(|| DEFAULT_HEAP_SIZE.to_string())
+    }
+
+    /// Port nodes talk to each other on; kept off 9300 so instances don't collide
+    pub fn transport_port(instance: &Instance) -> u16 {
+        instance
+            .port
+            .checked_add(TRANSPORT_PORT_OFFSET)
+            .unwrap_or(instance.port - TRANSPORT_PORT_OFFSET)
+    }
+
+    /// REST endpoint, e.g. `http://127.0.0.1:9200`
+    pub fn url(instance: &Instance) -> String {
+        format!("http://127.0.0.1:{}", instance.port)
+    }
+
+    /// Cluster name, unique per instance so nodes never join each other
+    fn cluster_name(instance: &Instance) -> String {
+        format!("burd-{}", instance.id.simple())
+    }
+}
+
+impl ServiceDefinition for OpenSearchService {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::OpenSearch
+    }
+
+    fn display_name(&self) -> &'static str {
+        "OpenSearch"
+    }
+
+    fn default_port(&self) -> u16 {
+        9200
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "opensearch"
+    }
+
+    fn version_source(&self) -> VersionSource {
+        VersionSource::Static(vec!["latest"])
+    }
+
+    fn download_method(&self, version: &str, arch: &str) -> DownloadMethod {
+        // services.json installs the Homebrew formula (which brings a JDK);
+        // OpenSearch only publishes Linux and Windows bundles itself
+        let platform = if arch == "aarch64" {
+            "linux-arm64"
+        } else {
+            "linux-x64"
+        };
+        DownloadMethod::Direct {
+            url: format!(
+                "https://artifacts.opensearch.org/releases/bundle/opensearch/{}/opensearch-{}-{}.tar.gz",
+                version, version, platform
+            ),
+            is_archive: true,
+            checksum: None, // TODO: Add SHA256 checksums for binary verification
+        }
+    }
+
+    fn health_check(&self) -> HealthCheck {
+        HealthCheck::Http {
+            path: "/_cluster/health".to_string(),
+        }
+    }
+
+    fn start_args(&self, instance: &Instance, data_dir: &Path) -> Vec<String> {
+        let data = data_dir.join("data");
+        let logs = data_dir.join("logs");
+        let _ = std::fs::create_dir_all(&data);
+        let _ = std::fs::create_dir_all(&logs);
+
+        let setting = |key: &str, value: String| format!("-E{}={}", key, value);
+        vec![
+            setting("cluster.name", Self::cluster_name(instance)),
+            setting("node.name", instance.name.clone()),
+            setting("discovery.type", "single-node".to_string()),
+            setting("network.host", "127.0.0.1".to_string()),
+            setting("http.port", instance.port.to_string()),
+            setting("transport.port", Self::transport_port(instance).to_string()),
+            setting("path.data", data.to_string_lossy().to_string()),
+            setting("path.logs", logs.to_string_lossy().to_string()),
+        ]
+    }
+
+    fn env_vars(&self, instance: &Instance, _domain: Option<&str>) -> Vec<(String, String)> {
+        let heap = Self::heap_size(instance);
+        vec![(
+            "OPENSEARCH_JAVA_OPTS".to_string(),
+            format!("-Xms{} -Xmx{}", heap, heap),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+    use serde_json::json;
+
+    fn instance(config: serde_json::Value) -> Instance {
+        InstanceBuilder::new()
+            .port(9201)
+            .service_type(ServiceType::OpenSearch)
+            .config(config)
+            .build()
+    }
+
+    #[test]
+    fn test_heap_size() {
+        let heap = |config| OpenSearchService::heap_size(&instance(config));
+        assert_eq!(heap(json!({})), "512m");
+        assert_eq!(heap(json!({ "heap_size": "1G" })), "1g");
+        assert_eq!(heap(json!({ "heap_size": " 768m " })), "768m");
+        assert_eq!(heap(json!({ "heap_size": 256 })), "256m");
+        assert_eq!(heap(json!({ "heap_size": "1024" })), "1024m");
+        assert_eq!(heap(json!({ "heap_size": "lots" })), "512m");
+        assert_eq!(heap(json!({ "heap_size": "1gg" })), "512m");
+        assert_eq!(heap(json!({ "heap_size": "0m" })), "512m");
+        assert_eq!(heap(json!({ "heap_size": "" })), "512m");
+    }
+
+    #[test]
+    fn test_env_vars_set_heap() {
+        let instance = instance(json!({ "heap_size": "2g" }));
+        assert_eq!(
+            OpenSearchService.env_vars(&instance, None),
+            vec![(
+                "OPENSEARCH_JAVA_OPTS".to_string(),
+                "-Xms2g -Xmx2g".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_start_args_isolate_each_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = instance(json!({}));
+        let args = OpenSearchService.start_args(&first, dir.path());
+
+        assert!(args.contains(&"-Ehttp.port=9201".to_string()));
+        assert!(args.contains(&"-Etransport.port=9301".to_string()));
+        assert!(args.contains(&"-Ediscovery.type=single-node".to_string()));
+        assert!(args.contains(&format!(
+            "-Epath.data={}",
+            dir.path().join("data").display()
+        )));
+        assert!(dir.path().join("logs").is_dir());
+
+        let other = instance(json!({}));
+        assert_ne!(
+            OpenSearchService::cluster_name(&first),
+            OpenSearchService::cluster_name(&other)
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Per-instance php.ini overrides for FrankenPHP
+//!
+//! FrankenPHP embeds its own PHP, so neither the PVM CLI PHP's php.ini nor
+//! another instance's settings apply to it. Overrides come from the instance
+//! config: the memory, upload, post size and execution time fields first,
+//! then the Xdebug mode, then free-form `php_ini` lines (`directive=value`,
+//! one per line), with later values winning. They're rendered as `php_ini`
+//! options in the global `frankenphp` block of the generated Caddyfile.
+
+use crate::config::{get_instance_dir, Instance};
+use serde::{Deserialize, Serialize};
+
+/// Dedicated config fields and the directives they set
+const FIELD_DIRECTIVES: &[(&str, &str)] = &[
+    ("php_memory_limit", "memory_limit"),
+    ("php_upload_max_filesize", "upload_max_filesize"),
+    ("php_post_max_size", "post_max_size"),
+    ("php_max_execution_time", "max_execution_time"),
+];
+
+/// Port IDEs listen on for Xdebug 3 connections
+pub const XDEBUG_CLIENT_PORT: u16 = 9003;
+
+/// Xdebug mode of a FrankenPHP instance (`xdebug_mode` in its config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum XdebugMode {
+    #[default]
+    Off,
+    /// Step debugging; every request connects to the IDE
+    Debug,
+    /// Cachegrind profiles of requests carrying the XDEBUG_TRIGGER trigger
+    Profile,
+}
+
+impl XdebugMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            XdebugMode::Off => "off",
+            XdebugMode::Debug => "debug",
+            XdebugMode::Profile => "profile",
+        }
+    }
+
+    /// Parse a mode; `on` means `debug`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "off" => Ok(XdebugMode::Off),
+            "on" | "debug" => Ok(XdebugMode::Debug),
+            "profile" => Ok(XdebugMode::Profile),
+            other => Err(format!(
+                "Unknown Xdebug mode '{}'. Use off, debug or profile",
+                other
+            )),
+        }
+    }
+
+    pub fn from_instance(instance: &Instance) -> Result<Self, String> {
+        Self::parse(
+            instance
+                .config
+                .get("xdebug_mode")
+                .and_then(|v| v.as_str())
+                .unwrap_or(""),
+        )
+    }
+
+    /// Instance config with this mode set
+    pub fn apply_to(&self, config: &serde_json::Value) -> serde_json::Value {
+        let mut config = match config {
+            serde_json::Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        match self {
+            XdebugMode::Off => {
+                config.remove("xdebug_mode");
+            }
+            mode => {
+                config.insert("xdebug_mode".to_string(), mode.as_str().into());
+            }
+        }
+        serde_json::Value::Object(config)
+    }
+}
+
+/// Where an instance's profiles are written
+pub fn xdebug_output_dir(instance: &Instance) -> Result<std::path::PathBuf, String> {
+    Ok(get_instance_dir(&instance.id)?.join("xdebug"))
+}
+
+/// php.ini directives for one FrankenPHP instance, in the order they're set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PhpIniOverrides {
+    directives: Vec<(String, String)>,
+}
+
+fn is_valid_directive(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Quote a value for the Caddyfile when it isn't a plain token
+fn caddy_token(value: &str) -> String {
+    let plain = !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\\' | '{' | '}' | '#'));
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+impl PhpIniOverrides {
+    /// Build overrides from an instance's config
+    pub fn from_instance(instance: &Instance) -> Result<Self, String> {
+        let mut overrides = Self::default();
+        let config_str = |key: &str| {
+            instance
+                .config
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        for (key, directive) in FIELD_DIRECTIVES {
+            if let Some(value) = config_str(key) {
+                overrides.set(directive, value)?;
+            }
+        }
+
+        overrides.set_xdebug(instance, XdebugMode::from_instance(instance)?)?;
+
+        for line in config_str("php_ini").unwrap_or("").lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid php.ini line '{}'. Use directive=value", line))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            overrides.set(name.trim(), value)?;
+        }
+
+        Ok(overrides)
+    }
+
+    /// Load Xdebug and set its mode
+    ///
+    /// FrankenPHP has to be able to load the extension: `xdebug_extension` in
+    /// the instance config names it (a path to `xdebug.so`, or a name found in
+    /// the extension directory).
+    fn set_xdebug(&mut self, instance: &Instance, mode: XdebugMode) -> Result<(), String> {
+        if mode == XdebugMode::Off {
+            return Ok(());
+        }
+
+        let extension = instance
+            .config
+            .get("xdebug_extension")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or("xdebug");
+        self.set("zend_extension", extension)?;
+        self.set("xdebug.mode", mode.as_str())?;
+        match mode {
+            XdebugMode::Debug => {
+                self.set("xdebug.start_with_request", "yes")?;
+                self.set("xdebug.client_host", "127.0.0.1")?;
+                self.set("xdebug.client_port", &XDEBUG_CLIENT_PORT.to_string())?;
+            }
+            XdebugMode::Profile => {
+                self.set("xdebug.start_with_request", "trigger")?;
+                if let Ok(dir) = xdebug_output_dir(instance) {
+                    self.set("xdebug.output_dir", &dir.to_string_lossy())?;
+                }
+            }
+            XdebugMode::Off => {}
+        }
+        Ok(())
+    }
+
+    /// Set a directive, replacing an earlier value
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if !is_valid_directive(name) {
+            return Err(format!("Invalid php.ini directive '{}'", name));
+        }
+        if value.chars().any(char::is_control) {
+            return Err(format!("Invalid value for php.ini directive '{}'", name));
+        }
+
+        match self.directives.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.directives.push((name.to_string(), value.to_string())),
+        }
+        Ok(())
+    }
+
+    /// The directives in the order they were set
+    pub fn directives(&self) -> &[(String, String)] {
+        &self.directives
+    }
+
+    /// The Caddyfile global options block, with the directives in the `frankenphp` option
+    pub fn global_options(&self) -> String {
+        let mut block = String::from("{\n");
+        let directives = self.directives();
+        if directives.is_empty() {
+            block.push_str("    frankenphp\n");
+        } else {
+            block.push_str("    frankenphp {\n");
+            for (name, value) in directives {
+                block.push_str(&format!(
+                    "        php_ini {} {}\n",
+                    name,
+                    caddy_token(value)
+                ));
+            }
+            block.push_str("    }\n");
+        }
+        block.push_str("    order php_server before file_server\n");
+        block.push_str("}\n");
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+    use serde_json::json;
+
+    fn overrides(config: serde_json::Value) -> Result<PhpIniOverrides, String> {
+        PhpIniOverrides::from_instance(&InstanceBuilder::new().config(config).build())
+    }
+
+    fn pairs(overrides: &PhpIniOverrides) -> Vec<(&str, &str)> {
+        overrides
+            .directives()
+            .iter()
+            .map(|(n, v)| (n.as_str(), v.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fields_then_free_form_lines() {
+        let overrides = overrides(json!({
+            "document_root": "/srv/app",
+            "php_memory_limit": "256M",
+            "php_upload_max_filesize": "",
+            "php_ini": "; comment\nopcache.enable = 0\n\ndisplay_errors=On\nmemory_limit=512M\nerror_log=\"/tmp/php errors.log\""
+        }))
+        .unwrap();
+
+        assert_eq!(
+            pairs(&overrides),
+            vec![
+                ("memory_limit", "512M"),
+                ("opcache.enable", "0"),
+                ("display_errors", "On"),
+                ("error_log", "/tmp/php errors.log"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xdebug_mode() {
+        assert_eq!(XdebugMode::parse("on"), Ok(XdebugMode::Debug));
+        assert_eq!(XdebugMode::parse(" Profile "), Ok(XdebugMode::Profile));
+        assert_eq!(XdebugMode::parse(""), Ok(XdebugMode::Off));
+        assert!(XdebugMode::parse("trace").is_err());
+
+        let config = XdebugMode::Debug.apply_to(&json!({ "document_root": "/srv/app" }));
+        assert_eq!(config["xdebug_mode"], "debug");
+        assert_eq!(config["document_root"], "/srv/app");
+        assert!(XdebugMode::Off
+            .apply_to(&config)
+            .get("xdebug_mode")
+            .is_none());
+    }
+
+    #[test]
+    fn test_xdebug_directives() {
+        assert!(overrides(json!({ "xdebug_mode": "off" }))
+            .unwrap()
+            .directives()
+            .is_empty());
+
+        let debug = overrides(json!({
+            "xdebug_mode": "debug",
+            "php_ini": "xdebug.client_port=9000"
+        }))
+        .unwrap();
+        assert_eq!(
+            pairs(&debug),
+            vec![
+                ("zend_extension", "xdebug"),
+                ("xdebug.mode", "debug"),
+                ("xdebug.start_with_request", "yes"),
+                ("xdebug.client_host", "127.0.0.1"),
+                ("xdebug.client_port", "9000"),
+            ]
+        );
+
+        let profile = overrides(json!({
+            "xdebug_mode": "profile",
+            "xdebug_extension": "/opt/xdebug/xdebug.so"
+        }))
+        .unwrap();
+        let profile = pairs(&profile);
+        assert_eq!(profile[0], ("zend_extension", "/opt/xdebug/xdebug.so"));
+        assert!(profile.contains(&("xdebug.start_with_request", "trigger")));
+
+        assert!(overrides(json!({ "xdebug_mode": "trace" })).is_err());
+    }
+
+    #[test]
+    fn test_invalid_lines_are_rejected() {
+        assert!(overrides(json!({ "php_ini": "display_errors" })).is_err());
+        assert!(overrides(json!({ "php_ini": "memory limit=1G" })).is_err());
+        assert!(overrides(json!({ "php_ini": "=1" })).is_err());
+        assert!(overrides(json!({ "php_ini": "" }))
+            .unwrap()
+            .directives()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_global_options() {
+        assert_eq!(
+            PhpIniOverrides::default().global_options(),
+            "{\n    frankenphp\n    order php_server before file_server\n}\n"
+        );
+
+        let overrides = overrides(json!({
+            "php_ini": "memory_limit=1G\nerror_log=\"/tmp/php errors.log\"\nauto_prepend_file="
+        }))
+        .unwrap();
+        let block = overrides.global_options();
+        assert!(block.contains("    frankenphp {\n        php_ini memory_limit 1G\n"));
+        assert!(block.contains("        php_ini error_log \"/tmp/php errors.log\"\n"));
+        assert!(block.contains("        php_ini auto_prepend_file \"\"\n"));
+        assert!(block.ends_with("    }\n    order php_server before file_server\n}\n"));
+    }
+}
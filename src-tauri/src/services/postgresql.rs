@@ -3,6 +3,7 @@
 //! Uses bundled PostgreSQL binary with per-instance data directories.
 
 use crate::config::{get_service_bin_dir, Instance, ServiceType};
+use crate::services::db_locale::DbLocaleSettings;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -185,14 +186,24 @@ impl ServiceDefinition for PostgreSQLService {
         vars
     }
 
-    fn start_args(&self, _instance: &Instance, data_dir: &Path) -> Vec<String> {
-        vec!["-D".to_string(), data_dir.to_string_lossy().to_string()]
+    fn start_args(&self, instance: &Instance, data_dir: &Path) -> Vec<String> {
+        let mut args = vec!["-D".to_string(), data_dir.to_string_lossy().to_string()];
+        // Timezone and locale override the GMT/C defaults of postgresql.conf;
+        // invalid settings were rejected when saved
+        if let Ok(settings) = DbLocaleSettings::from_instance(instance) {
+            args.extend(settings.postgres_args());
+        }
+        args
     }
 
     fn needs_init(&self) -> bool {
         true
     }
 
+    fn init_probe(&self) -> &'static [&'static str] {
+        &["PG_VERSION", "global/pg_control"]
+    }
+
     fn init_command(&self, data_dir: &Path) -> Option<(String, Vec<String>)> {
         let basedir = Self::get_basedir().ok()?;
         let initdb = basedir.join("bin/initdb");
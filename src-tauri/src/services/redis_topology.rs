@@ -0,0 +1,236 @@
+//! Redis/Valkey replication topologies
+//!
+//! Creates a master, its replicas, and optionally Sentinels in one action and
+//! groups them in a stack, so failover behavior can be tested locally. The
+//! members are ordinary instances whose `role` / `master_port` config drives
+//! their start arguments (see `key_value_service`).
+
+use crate::config::{ConfigStore, Instance, ServiceType, Stack};
+use crate::port_allocator;
+use crate::services::key_value_service::KeyValueRole;
+use crate::validation;
+use serde_json::json;
+
+/// Maximum number of replicas in a topology
+pub const MAX_REPLICAS: u8 = 5;
+
+/// Maximum number of Sentinels in a topology
+pub const MAX_SENTINELS: u8 = 5;
+
+/// Request to create a replication topology
+#[derive(Debug, Clone)]
+pub struct TopologyRequest {
+    /// Base name; members are named `<name>-master`, `<name>-replica-1`, ...
+    pub name: String,
+    pub service_type: ServiceType,
+    pub version: String,
+    pub replicas: u8,
+    pub sentinels: u8,
+    /// Shared password for all members
+    pub password: Option<String>,
+}
+
+/// A topology member before it has a port assigned
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMember {
+    pub name: String,
+    pub role: KeyValueRole,
+}
+
+/// Sentinel quorum for a number of sentinels (a majority)
+pub fn quorum(sentinels: u8) -> u8 {
+    sentinels / 2 + 1
+}
+
+/// Validate a request and list the members to create, master first
+pub fn plan_topology(request: &TopologyRequest) -> Result<Vec<PlannedMember>, String> {
+    if !matches!(
+        request.service_type,
+        ServiceType::Redis | ServiceType::Valkey
+    ) {
+        return Err("Replication topologies are only supported for Redis and Valkey".to_string());
+    }
+    if request.replicas == 0 || request.replicas > MAX_REPLICAS {
+        return Err(format!(
+            "A topology needs between 1 and {} replicas",
+            MAX_REPLICAS
+        ));
+    }
+    if request.sentinels > MAX_SENTINELS {
+        return Err(format!(
+            "A topology can have at most {} sentinels",
+            MAX_SENTINELS
+        ));
+    }
+
+    let mut members = vec![PlannedMember {
+        name: format!("{}-master", request.name),
+        role: KeyValueRole::Master,
+    }];
+    members.extend((1..=request.replicas).map(|n| PlannedMember {
+        name: format!("{}-replica-{}", request.name, n),
+        role: KeyValueRole::Replica,
+    }));
+    members.extend((1..=request.sentinels).map(|n| PlannedMember {
+        name: format!("{}-sentinel-{}", request.name, n),
+        role: KeyValueRole::Sentinel,
+    }));
+
+    for member in &members {
+        validation::validate_instance_name(&member.name)
+            .map_err(|e| format!("Invalid instance name '{}': {}", member.name, e))?;
+    }
+
+    Ok(members)
+}
+
+/// Build the instance config for a member
+pub fn member_config(
+    role: KeyValueRole,
+    master_port: Option<u16>,
+    password: Option<&str>,
+    sentinels: u8,
+) -> serde_json::Value {
+    let mut config = json!({ "role": role.as_str() });
+
+    if let Some(port) = master_port.filter(|_| role != KeyValueRole::Master) {
+        config["master_port"] = json!(port.to_string());
+    }
+    if role == KeyValueRole::Sentinel {
+        config["quorum"] = json!(quorum(sentinels).to_string());
+    }
+    if let Some(password) = password.filter(|p| !p.is_empty()) {
+        config["password"] = json!(password);
+    }
+
+    config
+}
+
+/// Create the members one at a time and group them in a stack
+///
+/// Ports are assigned per member so each sees the ones created before it.
+/// Created instances are pushed to `created` so the caller can roll back.
+fn create_members(
+    store: &ConfigStore,
+    request: &TopologyRequest,
+    members: &[PlannedMember],
+    created: &mut Vec<Instance>,
+) -> Result<Stack, String> {
+    let mut master_port = None;
+    for member in members {
+        let config = store.load()?;
+        let port = port_allocator::suggest_port(&config, request.service_type)?;
+        let instance = store.create_instance(
+            member.name.clone(),
+            port,
+            request.service_type,
+            request.version.clone(),
+            member_config(
+                member.role,
+                master_port,
+                request.password.as_deref(),
+                request.sentinels,
+            ),
+            None,
+        )?;
+        if member.role == KeyValueRole::Master {
+            master_port = Some(port);
+        }
+        created.push(instance);
+    }
+
+    let description = format!(
+        "{} master with {} replica(s) and {} sentinel(s)",
+        request.service_type.display_name(),
+        request.replicas,
+        request.sentinels
+    );
+    store.create_stack(
+        request.name.clone(),
+        Some(description),
+        created.iter().map(|i| i.id).collect(),
+    )
+}
+
+/// Create all members of a topology and group them in a new stack
+///
+/// If any member fails, the members created so far are removed again.
+pub fn create_topology(
+    store: &ConfigStore,
+    request: &TopologyRequest,
+) -> Result<(Stack, Vec<Instance>), String> {
+    let members = plan_topology(request)?;
+
+    let mut created = Vec::with_capacity(members.len());
+    match create_members(store, request, &members, &mut created) {
+        Ok(stack) => {
+            for instance in &mut created {
+                instance.stack_id = Some(stack.id);
+            }
+            Ok((stack, created))
+        }
+        Err(e) => {
+            for instance in &created {
+                let _ = store.delete_instance(instance.id);
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(replicas: u8, sentinels: u8) -> TopologyRequest {
+        TopologyRequest {
+            name: "cache".to_string(),
+            service_type: ServiceType::Redis,
+            version: "8.4.0".to_string(),
+            replicas,
+            sentinels,
+            password: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_topology() {
+        let members = plan_topology(&request(2, 3)).unwrap();
+        let names: Vec<_> = members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "cache-master",
+                "cache-replica-1",
+                "cache-replica-2",
+                "cache-sentinel-1",
+                "cache-sentinel-2",
+                "cache-sentinel-3"
+            ]
+        );
+        assert_eq!(members[0].role, KeyValueRole::Master);
+        assert_eq!(members[5].role, KeyValueRole::Sentinel);
+    }
+
+    #[test]
+    fn test_plan_topology_rejects_invalid() {
+        assert!(plan_topology(&request(0, 0)).is_err());
+        assert!(plan_topology(&request(MAX_REPLICAS + 1, 0)).is_err());
+
+        let mut memcached = request(1, 0);
+        memcached.service_type = ServiceType::Memcached;
+        assert!(plan_topology(&memcached).is_err());
+    }
+
+    #[test]
+    fn test_member_config() {
+        let master = member_config(KeyValueRole::Master, None, Some("pw"), 3);
+        assert_eq!(master, json!({ "role": "master", "password": "pw" }));
+
+        let sentinel = member_config(KeyValueRole::Sentinel, Some(6379), None, 3);
+        assert_eq!(
+            sentinel,
+            json!({ "role": "sentinel", "master_port": "6379", "quorum": "2" })
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Soketi, a Pusher-compatible WebSocket server
+//!
+//! Soketi is an npm package: the installer puts it in the version directory
+//! with a launcher script, and Burd supervises the process the way PM2
+//! would. It runs from a `config.json` rendered into its data directory on
+//! every start, holding a single app whose ID, key, and secret come from the
+//! instance config (Soketi's own `app-id`/`app-key`/`app-secret` defaults
+//! until `apply_preset` generates real ones).
+
+use crate::activity;
+use crate::config::{ConfigStore, Instance, ServiceType};
+use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Generated config file inside the instance data directory
+const CONFIG_FILE: &str = "config.json";
+
+/// Credentials Soketi's default app uses, for instances that have none yet
+const DEFAULT_APP_ID: &str = "app-id";
+const DEFAULT_APP_KEY: &str = "app-key";
+const DEFAULT_APP_SECRET: &str = "app-secret";
+
+pub struct SoketiService;
+
+/// Result of applying the Laravel broadcasting preset
+#[derive(Debug, Clone, Serialize)]
+pub struct SoketiSetup {
+    /// Config file Soketi will start with
+    pub config: Value,
+    /// Matching Laravel broadcasting .env block
+    pub env: String,
+}
+
+impl ServiceDefinition for SoketiService {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Soketi
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Soketi"
+    }
+
+    fn default_port(&self) -> u16 {
+        6001
+    }
+
+    fn binary_name(&self) -> &'static str {
+        "soketi"
+    }
+
+    fn version_source(&self) -> VersionSource {
+        VersionSource::Static(vec!["1.6.1", "1.6.0"])
+    }
+
+    fn download_method(&self, version: &str, _arch: &str) -> DownloadMethod {
+        // services.json installs the npm package (with its dependencies and
+        // a launcher); the registry tarball alone can't run
+        DownloadMethod::Direct {
+            url: format!(
+                "https://registry.npmjs.org/@soketi/soketi/-/soketi-{}.tgz",
+                version
+            ),
+            is_archive: true,
+            checksum: None, // TODO: Add SHA256 checksums for binary verification
+        }
+    }
+
+    fn health_check(&self) -> HealthCheck {
+        HealthCheck::Http {
+            path: "/ready".to_string(),
+        }
+    }
+
+    fn start_args(&self, _instance: &Instance, data_dir: &Path) -> Vec<String> {
+        vec![
+            "start".to_string(),
+            format!("--config={}", data_dir.join(CONFIG_FILE).to_string_lossy()),
+        ]
+    }
+}
+
+/// Read a non-empty string from instance config
+fn config_str<'a>(instance: &'a Instance, key: &str) -> Option<&'a str> {
+    instance
+        .config
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+}
+
+/// Whether clients may send events to each other (stored as a bool or "true")
+fn client_messages_enabled(instance: &Instance) -> bool {
+    match instance.config.get("enable_client_messages") {
+        Some(Value::Bool(enabled)) => *enabled,
+        Some(Value::String(s)) => s == "true",
+        _ => false,
+    }
+}
+
+fn random_secret(len: usize) -> String {
+    use rand::distr::Alphanumeric;
+    use rand::Rng;
+
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+impl SoketiService {
+    /// App ID, key, and secret, falling back to Soketi's defaults
+    fn credentials(instance: &Instance) -> (&str, &str, &str) {
+        (
+            config_str(instance, "app_id").unwrap_or(DEFAULT_APP_ID),
+            config_str(instance, "app_key").unwrap_or(DEFAULT_APP_KEY),
+            config_str(instance, "app_secret").unwrap_or(DEFAULT_APP_SECRET),
+        )
+    }
+
+    /// Render the Soketi config file contents from instance config
+    pub fn render_config(instance: &Instance) -> Value {
+        let (id, key, secret) = Self::credentials(instance);
+        json!({
+            "host": "127.0.0.1",
+            "port": instance.port,
+            "appManager.array.apps": [{
+                "id": id,
+                "key": key,
+                "secret": secret,
+                "enableClientMessages": client_messages_enabled(instance)
+            }]
+        })
+    }
+
+    /// Write the config file into the data directory (refreshed every start)
+    pub fn generate_config(instance: &Instance, data_dir: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&Self::render_config(instance))
+            .map_err(|e| format!("Failed to serialize Soketi config: {}", e))?;
+        std::fs::write(data_dir.join(CONFIG_FILE), content)
+            .map_err(|e| format!("Failed to write Soketi config: {}", e))
+    }
+
+    /// Instance config with the Laravel broadcasting preset applied
+    ///
+    /// Keeps an existing app ID, key, and secret and generates any that are
+    /// missing.
+    pub fn apply_preset(instance: &Instance) -> Value {
+        let mut config = match &instance.config {
+            Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        for (key, len) in [("app_id", 8), ("app_key", 20), ("app_secret", 32)] {
+            if config_str(instance, key).is_none() {
+                config.insert(key.to_string(), json!(random_secret(len)));
+            }
+        }
+
+        Value::Object(config)
+    }
+
+    /// Apply the Laravel broadcasting preset to an instance and save it
+    ///
+    /// The browser connects through the instance's own domain when it has
+    /// one. Takes effect on the next start.
+    pub fn setup(store: &ConfigStore, id: Uuid) -> Result<SoketiSetup, String> {
+        let config = store.load()?;
+        let instance = store.get_instance(id)?;
+        if instance.service_type != ServiceType::Soketi {
+            return Err(format!("'{}' is not a Soketi instance", instance.name));
+        }
+
+        let preset = Self::apply_preset(&instance);
+        let before = instance;
+        let instance = store.update_instance_config(id, preset)?;
+        activity::record_changes(&before, &instance);
+
+        let domain = instance.domain_enabled.then(|| {
+            let ssl_enabled = config
+                .domains
+                .iter()
+                .filter(|d| d.routes_to_instance(&id))
+                .any(|d| d.ssl_enabled);
+            (
+                instance.full_domain(&config.tld, &config.slugs),
+                ssl_enabled,
+            )
+        });
+
+        Ok(SoketiSetup {
+            config: Self::render_config(&instance),
+            env: Self::laravel_env(
+                &instance,
+                domain.as_ref().map(|(d, ssl)| (d.as_str(), *ssl)),
+            ),
+        })
+    }
+
+    /// Laravel broadcasting .env block (pusher driver + laravel-echo/pusher-js)
+    ///
+    /// `domain` is the domain the browser connects through and whether it
+    /// serves HTTPS; defaults to the loopback address.
+    pub fn laravel_env(instance: &Instance, domain: Option<(&str, bool)>) -> String {
+        let (id, key, secret) = Self::credentials(instance);
+        let (browser_host, browser_port, browser_scheme) = match domain {
+            Some((domain, true)) => (domain.to_string(), 443, "https"),
+            Some((domain, false)) => (domain.to_string(), 80, "http"),
+            None => ("127.0.0.1".to_string(), instance.port, "http"),
+        };
+
+        format!(
+            "# Laravel broadcasting (pusher driver, served by Soketi)\n\
+             BROADCAST_CONNECTION=pusher\n\
+             PUSHER_APP_ID={}\n\
+             PUSHER_APP_KEY={}\n\
+             PUSHER_APP_SECRET={}\n\
+             PUSHER_HOST=127.0.0.1\n\
+             PUSHER_PORT={}\n\
+             PUSHER_SCHEME=http\n\
+             PUSHER_APP_CLUSTER=mt1\n\
+             \n\
+             # Frontend (laravel-echo + pusher-js)\n\
+             VITE_PUSHER_APP_KEY=\"${{PUSHER_APP_KEY}}\"\n\
+             VITE_PUSHER_HOST={}\n\
+             VITE_PUSHER_PORT={}\n\
+             VITE_PUSHER_SCHEME={}\n\
+             VITE_PUSHER_APP_CLUSTER=\"${{PUSHER_APP_CLUSTER}}\"\n",
+            id, key, secret, instance.port, browser_host, browser_port, browser_scheme
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    #[test]
+    fn test_render_config() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::Soketi)
+            .port(6002)
+            .config(json!({
+                "app_id": "shop",
+                "app_key": "key",
+                "app_secret": "secret",
+                "enable_client_messages": "true"
+            }))
+            .build();
+
+        assert_eq!(
+            SoketiService::render_config(&instance),
+            json!({
+                "host": "127.0.0.1",
+                "port": 6002,
+                "appManager.array.apps": [{
+                    "id": "shop",
+                    "key": "key",
+                    "secret": "secret",
+                    "enableClientMessages": true
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_config_defaults_to_soketi_app() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::Soketi)
+            .build();
+
+        let app = &SoketiService::render_config(&instance)["appManager.array.apps"][0];
+        assert_eq!(app["id"], "app-id");
+        assert_eq!(app["key"], "app-key");
+        assert_eq!(app["secret"], "app-secret");
+        assert_eq!(app["enableClientMessages"], false);
+    }
+
+    #[test]
+    fn test_apply_preset_keeps_existing_credentials() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::Soketi)
+            .config(json!({ "app_key": "existing" }))
+            .build();
+
+        let config = SoketiService::apply_preset(&instance);
+        assert_eq!(config["app_key"], "existing");
+        assert_eq!(config["app_id"].as_str().unwrap().len(), 8);
+        assert_eq!(config["app_secret"].as_str().unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_laravel_env_uses_domain_for_browser() {
+        let instance = InstanceBuilder::new()
+            .service_type(ServiceType::Soketi)
+            .port(6001)
+            .config(json!({ "app_id": "1", "app_key": "key", "app_secret": "secret" }))
+            .build();
+
+        let env = SoketiService::laravel_env(&instance, Some(("ws.test", true)));
+        assert!(env.contains("PUSHER_APP_KEY=key\n"));
+        assert!(env.contains("PUSHER_PORT=6001\n"));
+        assert!(env.contains("VITE_PUSHER_HOST=ws.test\n"));
+        assert!(env.contains("VITE_PUSHER_PORT=443\n"));
+        assert!(env.contains("VITE_PUSHER_SCHEME=https\n"));
+        assert!(env.contains("VITE_PUSHER_APP_KEY=\"${PUSHER_APP_KEY}\"\n"));
+
+        let env = SoketiService::laravel_env(&instance, None);
+        assert!(env.contains("VITE_PUSHER_HOST=127.0.0.1\n"));
+        assert!(env.contains("VITE_PUSHER_PORT=6001\n"));
+    }
+}
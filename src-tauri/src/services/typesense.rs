@@ -1,4 +1,5 @@
 use crate::config::{Instance, ServiceType};
+use crate::platform::Os;
 use crate::services::{DownloadMethod, HealthCheck, ServiceDefinition, VersionSource};
 use std::path::Path;
 
@@ -31,8 +32,11 @@ impl ServiceDefinition for TypesenseService {
         // Example: https://dl.typesense.org/releases/27.1/typesense-server-27.1-darwin-arm64.tar.gz
         let clean_version = version.trim_start_matches('v');
         let url = format!(
-            "https://dl.typesense.org/releases/{}/typesense-server-{}-darwin-{}.tar.gz",
-            clean_version, clean_version, arch_suffix
+            "https://dl.typesense.org/releases/{}/typesense-server-{}-{}-{}.tar.gz",
+            clean_version,
+            clean_version,
+            Os::host().download_name(),
+            arch_suffix
         );
         DownloadMethod::Direct {
             url,
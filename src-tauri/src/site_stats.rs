@@ -0,0 +1,243 @@
+//! Per-Site Response Times
+//!
+//! Request counts and latency percentiles per proxied domain, computed from
+//! the tail of the site's JSON access log (see [`get_site_log_path`]). Stats
+//! cover a recent window and the window before it, so a site that suddenly
+//! got slow stands out without keeping any history of our own.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::caddy::get_site_log_path;
+use crate::config::Config;
+use crate::logs::{get_last_lines, is_access_log, parse_caddy_json, site_domains};
+
+/// Window used when none is given
+pub const DEFAULT_WINDOW_MINUTES: u32 = 15;
+
+/// Requests each window needs before the two are compared
+const MIN_REQUESTS: usize = 5;
+
+/// Median slowdown from which a site counts as slower
+const SLOWER_FACTOR: f64 = 1.5;
+
+/// A request from an access log
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Request {
+    /// Unix timestamp in milliseconds
+    at: i64,
+    status: u16,
+    duration_ms: f64,
+}
+
+/// Request count and response times of a set of requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub requests: usize,
+    /// Responses with a 5xx status
+    pub errors: usize,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    fn of(requests: &[Request]) -> Self {
+        if requests.is_empty() {
+            return Self::default();
+        }
+
+        let mut durations: Vec<f64> = requests.iter().map(|r| r.duration_ms).collect();
+        durations.sort_by(f64::total_cmp);
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = (p * durations.len() as f64).ceil() as usize;
+            durations[rank.clamp(1, durations.len()) - 1]
+        };
+
+        Self {
+            requests: requests.len(),
+            errors: requests.iter().filter(|r| r.status >= 500).count(),
+            avg_ms: durations.iter().sum::<f64>() / durations.len() as f64,
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            max_ms: durations[durations.len() - 1],
+        }
+    }
+}
+
+/// Response times of a domain in the recent window and the one before it
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainStats {
+    pub domain: String,
+    pub window_minutes: u32,
+    /// Requests in the last `window_minutes`
+    pub recent: LatencyStats,
+    /// Requests in the `window_minutes` before that
+    pub previous: LatencyStats,
+    /// Recent median over previous median, when both windows saw enough requests
+    pub slowdown: Option<f64>,
+    /// The recent median is at least 1.5x the previous one
+    pub slower: bool,
+    pub last_request_at: Option<DateTime<Utc>>,
+}
+
+/// Parse an access log line into a request (error log lines are skipped)
+fn parse_request(line: &str) -> Option<Request> {
+    let entry = parse_caddy_json(line)?;
+    if !is_access_log(&entry) {
+        return None;
+    }
+
+    Some(Request {
+        at: entry.timestamp,
+        status: entry.status?,
+        duration_ms: entry.duration_ms?,
+    })
+}
+
+/// Compute a domain's stats from its access log lines
+fn stats_from_lines<'a>(
+    domain: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+    window_minutes: u32,
+    now: DateTime<Utc>,
+) -> DomainStats {
+    let window_ms = i64::from(window_minutes) * 60_000;
+    let recent_start = now.timestamp_millis() - window_ms;
+    let previous_start = recent_start - window_ms;
+
+    let requests: Vec<Request> = lines.into_iter().filter_map(parse_request).collect();
+    let (recent, previous): (Vec<Request>, Vec<Request>) = requests
+        .iter()
+        .copied()
+        .filter(|r| r.at >= previous_start)
+        .partition(|r| r.at >= recent_start);
+
+    let recent = LatencyStats::of(&recent);
+    let previous = LatencyStats::of(&previous);
+    let slowdown = (recent.requests >= MIN_REQUESTS
+        && previous.requests >= MIN_REQUESTS
+        && previous.p50_ms > 0.0)
+        .then(|| recent.p50_ms / previous.p50_ms);
+
+    DomainStats {
+        domain: domain.to_string(),
+        window_minutes,
+        recent,
+        previous,
+        slowdown,
+        slower: slowdown.is_some_and(|s| s >= SLOWER_FACTOR),
+        last_request_at: requests
+            .iter()
+            .map(|r| r.at)
+            .max()
+            .and_then(|at| Utc.timestamp_millis_opt(at).single()),
+    }
+}
+
+/// Stats of a domain (full domain, e.g. `shop.test`)
+///
+/// A site without an access log yet has empty stats.
+pub fn domain_stats(domain: &str, window_minutes: u32) -> DomainStats {
+    let path = get_site_log_path(domain);
+    let lines = get_last_lines(&path.to_string_lossy(), usize::MAX).unwrap_or_default();
+    stats_from_lines(
+        domain,
+        lines.iter().map(String::as_str),
+        window_minutes,
+        Utc::now(),
+    )
+}
+
+/// Stats of every configured site, sorted by domain
+pub fn all_domain_stats(config: &Config, window_minutes: u32) -> Vec<DomainStats> {
+    site_domains(config)
+        .iter()
+        .map(|domain| domain_stats(domain, window_minutes))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_line(at: DateTime<Utc>, status: u16, duration_secs: f64) -> String {
+        format!(
+            r#"{{"level":"info","ts":{},"logger":"http.log.access.log0","msg":"handled request","request":{{"method":"GET","host":"shop.test","uri":"/"}},"duration":{},"status":{}}}"#,
+            at.timestamp_millis() as f64 / 1000.0,
+            duration_secs,
+            status
+        )
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        let requests: Vec<Request> = (1..=20)
+            .map(|i| Request {
+                at: 0,
+                status: if i == 20 { 502 } else { 200 },
+                duration_ms: i as f64 * 10.0,
+            })
+            .collect();
+
+        let stats = LatencyStats::of(&requests);
+        assert_eq!(stats.requests, 20);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.avg_ms, 105.0);
+        assert_eq!(stats.p50_ms, 100.0);
+        assert_eq!(stats.p95_ms, 190.0);
+        assert_eq!(stats.max_ms, 200.0);
+
+        assert_eq!(LatencyStats::of(&[]), LatencyStats::default());
+    }
+
+    #[test]
+    fn test_stats_from_lines_flags_slowdown() {
+        let now = Utc::now();
+        let minutes_ago = |m: i64| now - chrono::Duration::minutes(m);
+
+        // Way outside both windows
+        let mut lines = vec![access_line(minutes_ago(90), 200, 5.0)];
+        // Previous window: 20ms
+        for _ in 0..5 {
+            lines.push(access_line(minutes_ago(20), 200, 0.02));
+        }
+        // Recent window: 80ms
+        for _ in 0..5 {
+            lines.push(access_line(minutes_ago(5), 200, 0.08));
+        }
+        // Error log lines don't count as requests
+        lines.push(format!(
+            r#"{{"level":"error","ts":{},"logger":"http.log.error","msg":"dial tcp: connection refused","duration":0.001,"status":502}}"#,
+            now.timestamp()
+        ));
+
+        let stats = stats_from_lines("shop.test", lines.iter().map(String::as_str), 15, now);
+        assert_eq!(stats.recent.requests, 5);
+        assert_eq!(stats.previous.requests, 5);
+        assert_eq!(stats.recent.p50_ms.round(), 80.0);
+        assert_eq!(stats.slowdown.map(|s| s.round()), Some(4.0));
+        assert!(stats.slower);
+        assert!(stats.last_request_at.is_some());
+    }
+
+    #[test]
+    fn test_stats_from_lines_needs_enough_requests() {
+        let now = Utc::now();
+        let lines = [
+            access_line(now - chrono::Duration::minutes(20), 200, 0.01),
+            access_line(now - chrono::Duration::minutes(1), 200, 1.0),
+        ];
+
+        let stats = stats_from_lines("shop.test", lines.iter().map(String::as_str), 15, now);
+        assert_eq!(stats.recent.requests, 1);
+        assert_eq!(stats.slowdown, None);
+        assert!(!stats.slower);
+
+        let empty = stats_from_lines("shop.test", std::iter::empty(), 15, now);
+        assert_eq!(empty.recent, LatencyStats::default());
+        assert_eq!(empty.last_request_at, None);
+    }
+}
@@ -0,0 +1,762 @@
+//! Snapshot Module
+//!
+//! Point-in-time snapshots of non-SQL services, stored per instance under
+//! `snapshots/<instance-id>/<snapshot-id>/` in the app directory:
+//!
+//! - Meilisearch: a dump created through its HTTP API
+//! - MongoDB: a gzipped `mongodump` archive
+//! - Redis/Valkey: a copy of the RDB file written by `SAVE`
+//! - MinIO: a mirror of the data directory (all buckets), taken while stopped
+//!
+//! Safety snapshots dump a single MariaDB/PostgreSQL/MongoDB database before
+//! the CLI drops or overwrites it. They expire after a few hours instead of
+//! counting toward the retention.
+//!
+//! Each snapshot keeps its metadata in `snapshot.json`. After a new snapshot is
+//! created, the oldest ones beyond the configured retention are removed.
+
+use crate::binary::copy_dir_contents;
+use crate::config::{get_instance_dir, get_snapshots_dir, Instance, ServiceType};
+use crate::db_manager::{self, mongodb::find_mongo_tool};
+use crate::services::key_value_service::KeyValueRole;
+use crate::services::meilisearch::import_dump_path;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Number of snapshots kept per instance unless configured otherwise
+pub const DEFAULT_SNAPSHOT_RETENTION: usize = 10;
+
+/// Hours safety snapshots are kept unless configured otherwise
+pub const DEFAULT_SAFETY_SNAPSHOT_HOURS: u32 = 24;
+
+/// Metadata file inside each snapshot directory
+const METADATA_FILE: &str = "snapshot.json";
+
+/// RDB file Redis/Valkey write to their data directory
+const REDIS_RDB_FILE: &str = "dump.rdb";
+
+/// Connection timeout for Redis/Valkey
+const REDIS_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Read timeout for `SAVE` (blocks until the RDB file is written)
+const REDIS_SAVE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long to wait for Meilisearch to finish a dump task
+const MEILISEARCH_DUMP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Meilisearch database entries removed before a dump is imported
+const MEILISEARCH_DB_ENTRIES: &[&str] = &[
+    "VERSION",
+    "instance-uid",
+    "auth",
+    "indexes",
+    "tasks",
+    "update_files",
+];
+
+/// How a service's data is captured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotKind {
+    MeilisearchDump,
+    MongoDump,
+    RedisRdb,
+    MinioMirror,
+    /// One database of a MariaDB/PostgreSQL/MongoDB instance (safety snapshots)
+    DatabaseDump,
+}
+
+impl SnapshotKind {
+    /// Snapshot kind for a service type, if snapshots are supported
+    pub fn for_service(service_type: ServiceType) -> Option<Self> {
+        match service_type {
+            ServiceType::Meilisearch => Some(Self::MeilisearchDump),
+            ServiceType::MongoDB => Some(Self::MongoDump),
+            ServiceType::Redis | ServiceType::Valkey => Some(Self::RedisRdb),
+            ServiceType::MinIO => Some(Self::MinioMirror),
+            _ => None,
+        }
+    }
+
+    /// File (or directory) in the snapshot holding the data
+    fn payload_name(&self) -> &'static str {
+        match self {
+            Self::MeilisearchDump => "meilisearch.dump",
+            Self::MongoDump => "mongodb.archive.gz",
+            Self::RedisRdb => REDIS_RDB_FILE,
+            Self::MinioMirror => "data",
+            Self::DatabaseDump => "database.dump",
+        }
+    }
+
+    /// Whether the instance must be running to create a snapshot (otherwise it must be stopped)
+    ///
+    /// MinIO's data directory is only consistent while the server is stopped.
+    fn create_requires_running(&self) -> bool {
+        !matches!(self, Self::MinioMirror)
+    }
+
+    /// Whether the instance must be running to restore (otherwise it must be stopped)
+    fn restore_requires_running(&self) -> bool {
+        matches!(self, Self::MongoDump | Self::DatabaseDump)
+    }
+}
+
+/// Snapshot metadata (stored as `snapshot.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub instance_id: Uuid,
+    pub instance_name: String,
+    pub service_type: ServiceType,
+    pub version: String,
+    pub kind: SnapshotKind,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Database a safety snapshot holds
+    #[serde(default)]
+    pub database: Option<String>,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+    /// When a safety snapshot is removed; other snapshots follow the retention
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Check whether a service type supports snapshots
+pub fn supports_snapshots(service_type: ServiceType) -> bool {
+    SnapshotKind::for_service(service_type).is_some()
+}
+
+/// Snapshot IDs are timestamps; reject anything that could escape the directory
+fn validate_snapshot_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid snapshot ID '{}'", id));
+    }
+    Ok(())
+}
+
+fn snapshot_dir(instance_id: &Uuid, id: &str) -> Result<PathBuf, String> {
+    validate_snapshot_id(id)?;
+    Ok(get_snapshots_dir(instance_id)?.join(id))
+}
+
+fn write_metadata(dir: &Path, info: &SnapshotInfo) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(info)
+        .map_err(|e| format!("Failed to serialize snapshot metadata: {}", e))?;
+    fs::write(dir.join(METADATA_FILE), json)
+        .map_err(|e| format!("Failed to write snapshot metadata: {}", e))
+}
+
+/// New snapshot ID from its creation time
+fn new_snapshot_id(created_at: DateTime<Utc>) -> String {
+    created_at.format("%Y%m%d-%H%M%S-%3f").to_string()
+}
+
+fn read_metadata(dir: &Path) -> Result<SnapshotInfo, String> {
+    let content = fs::read_to_string(dir.join(METADATA_FILE))
+        .map_err(|e| format!("Failed to read snapshot metadata: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid snapshot metadata: {}", e))
+}
+
+/// Total size of a file or directory in bytes
+pub(crate) fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| path_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+fn copy_file(src: &Path, dest: &Path) -> Result<(), String> {
+    fs::copy(src, dest)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {}: {}", src.display(), e))
+}
+
+/// List an instance's snapshots, newest first
+pub fn list_snapshots(instance_id: &Uuid) -> Result<Vec<SnapshotInfo>, String> {
+    let dir = get_snapshots_dir(instance_id)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read snapshot directory: {}", e))?;
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .flatten()
+        .filter_map(|entry| read_metadata(&entry.path()).ok())
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+
+    Ok(snapshots)
+}
+
+/// Get a single snapshot's metadata
+pub fn get_snapshot(instance_id: &Uuid, id: &str) -> Result<SnapshotInfo, String> {
+    read_metadata(&snapshot_dir(instance_id, id)?)
+        .map_err(|_| format!("Snapshot '{}' not found", id))
+}
+
+/// Capture an instance's data into `dir` the way a snapshot would
+///
+/// Returns the snapshot kind and the path of the written payload. Also used
+/// by scheduled backups, which keep their own copies.
+pub fn write_payload(
+    instance: &Instance,
+    running: bool,
+    dir: &Path,
+) -> Result<(SnapshotKind, PathBuf), String> {
+    let kind = SnapshotKind::for_service(instance.service_type).ok_or_else(|| {
+        format!(
+            "Snapshots are not supported for {}",
+            instance.service_type.display_name()
+        )
+    })?;
+
+    if kind.create_requires_running() && !running {
+        return Err(format!(
+            "Instance '{}' must be running to create a snapshot",
+            instance.name
+        ));
+    }
+    if !kind.create_requires_running() && running {
+        return Err(format!(
+            "Stop instance '{}' before creating a snapshot",
+            instance.name
+        ));
+    }
+    if kind == SnapshotKind::RedisRdb
+        && KeyValueRole::from_instance(instance) == KeyValueRole::Sentinel
+    {
+        return Err("Sentinel instances hold no data to snapshot".to_string());
+    }
+
+    let payload = dir.join(kind.payload_name());
+    let data_dir = get_instance_dir(&instance.id)?;
+    match kind {
+        SnapshotKind::MeilisearchDump => create_meilisearch_dump(instance, &data_dir, &payload),
+        SnapshotKind::MongoDump => run_mongo_tool(instance, "mongodump", &payload, &[]),
+        SnapshotKind::RedisRdb => save_redis_rdb(instance, &data_dir, &payload),
+        SnapshotKind::MinioMirror => fs::create_dir_all(&payload)
+            .map_err(|e| format!("Failed to create snapshot directory: {}", e))
+            .and_then(|_| copy_dir_contents(&data_dir, &payload)),
+        // `for_service` never picks it; see `create_safety_snapshot`
+        SnapshotKind::DatabaseDump => Err("Database dumps are per database".to_string()),
+    }?;
+
+    Ok((kind, payload))
+}
+
+/// Create a snapshot, then prune old ones beyond `retention` (0 keeps all)
+pub fn create_snapshot(
+    instance: &Instance,
+    running: bool,
+    label: Option<String>,
+    retention: usize,
+) -> Result<SnapshotInfo, String> {
+    let created_at = Utc::now();
+    let id = new_snapshot_id(created_at);
+    let dir = snapshot_dir(&instance.id, &id)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let (kind, payload) = match write_payload(instance, running, &dir) {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+
+    let info = SnapshotInfo {
+        id,
+        instance_id: instance.id,
+        instance_name: instance.name.clone(),
+        service_type: instance.service_type,
+        version: instance.version.clone(),
+        kind,
+        label: label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty()),
+        database: None,
+        size_bytes: path_size(&payload),
+        created_at,
+        expires_at: None,
+    };
+    write_metadata(&dir, &info)?;
+
+    prune_snapshots(&instance.id, retention)?;
+
+    Ok(info)
+}
+
+/// Dump one database before it's dropped or overwritten
+///
+/// The snapshot is labeled with `reason` (e.g. "before db drop") and removed
+/// after `hours`. The instance must be running.
+pub fn create_safety_snapshot(
+    instance: &Instance,
+    database: &str,
+    reason: &str,
+    hours: u32,
+) -> Result<SnapshotInfo, String> {
+    let manager = db_manager::create_manager_for_instance(instance)?;
+    let created_at = Utc::now();
+    let id = new_snapshot_id(created_at);
+    let dir = snapshot_dir(&instance.id, &id)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let payload = dir.join(SnapshotKind::DatabaseDump.payload_name());
+    if let Err(e) = manager.export_sql(database, &payload) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(format!("Failed to snapshot '{}': {}", database, e));
+    }
+
+    let info = SnapshotInfo {
+        id,
+        instance_id: instance.id,
+        instance_name: instance.name.clone(),
+        service_type: instance.service_type,
+        version: instance.version.clone(),
+        kind: SnapshotKind::DatabaseDump,
+        label: Some(reason.to_string()),
+        database: Some(database.to_string()),
+        size_bytes: path_size(&payload),
+        created_at,
+        expires_at: Some(created_at + chrono::Duration::hours(i64::from(hours))),
+    };
+    write_metadata(&dir, &info)?;
+
+    // 0 keeps every regular snapshot; only expired safety snapshots go
+    prune_snapshots(&instance.id, 0)?;
+
+    Ok(info)
+}
+
+/// Restore a snapshot into its instance
+///
+/// MongoDB restores into the running server (dropping collections first);
+/// the other services must be stopped so their files can be replaced.
+pub fn restore_snapshot(
+    instance: &Instance,
+    id: &str,
+    running: bool,
+) -> Result<SnapshotInfo, String> {
+    let info = get_snapshot(&instance.id, id)?;
+    if info.kind != SnapshotKind::DatabaseDump
+        && SnapshotKind::for_service(instance.service_type) != Some(info.kind)
+    {
+        return Err(format!(
+            "Snapshot '{}' cannot be restored into a {} instance",
+            id,
+            instance.service_type.display_name()
+        ));
+    }
+
+    let dir = snapshot_dir(&instance.id, id)?;
+    if !dir.join(info.kind.payload_name()).exists() {
+        return Err(format!("Snapshot '{}' has no data", id));
+    }
+    if info.kind == SnapshotKind::DatabaseDump {
+        restore_database_dump(instance, &info, &dir, running)?;
+    } else {
+        restore_payload(instance, &dir, running)?;
+    }
+
+    Ok(info)
+}
+
+/// Replace a database with the dump of a safety snapshot
+fn restore_database_dump(
+    instance: &Instance,
+    info: &SnapshotInfo,
+    dir: &Path,
+    running: bool,
+) -> Result<(), String> {
+    let database = info
+        .database
+        .as_deref()
+        .ok_or_else(|| format!("Snapshot '{}' doesn't name its database", info.id))?;
+    if !running {
+        return Err(format!(
+            "Instance '{}' must be running to restore its data",
+            instance.name
+        ));
+    }
+
+    let manager = db_manager::create_manager_for_instance(instance)?;
+    if manager.database_exists(database)? {
+        manager.drop_database(database)?;
+    }
+    manager.create_database(database)?;
+    manager.import_sql(
+        database,
+        &dir.join(SnapshotKind::DatabaseDump.payload_name()),
+    )
+}
+
+/// Restore data captured by `write_payload` into an instance
+///
+/// `dir` holds the payload (a snapshot or a scheduled backup). Same rules as
+/// `restore_snapshot` for whether the instance must be running.
+pub fn restore_payload(instance: &Instance, dir: &Path, running: bool) -> Result<(), String> {
+    let kind = SnapshotKind::for_service(instance.service_type).ok_or_else(|| {
+        format!(
+            "Snapshots are not supported for {}",
+            instance.service_type.display_name()
+        )
+    })?;
+
+    if kind.restore_requires_running() && !running {
+        return Err(format!(
+            "Instance '{}' must be running to restore its data",
+            instance.name
+        ));
+    }
+    if !kind.restore_requires_running() && running {
+        return Err(format!(
+            "Stop instance '{}' before restoring its data",
+            instance.name
+        ));
+    }
+
+    let payload = dir.join(kind.payload_name());
+    if !payload.exists() {
+        return Err(format!(
+            "No {} data in {}",
+            kind.payload_name(),
+            dir.display()
+        ));
+    }
+
+    let data_dir = get_instance_dir(&instance.id)?;
+    match kind {
+        SnapshotKind::MeilisearchDump => restore_meilisearch_dump(&data_dir, &payload)?,
+        SnapshotKind::MongoDump => run_mongo_tool(instance, "mongorestore", &payload, &["--drop"])?,
+        SnapshotKind::RedisRdb => copy_file(&payload, &data_dir.join(REDIS_RDB_FILE))?,
+        SnapshotKind::MinioMirror => {
+            if data_dir.exists() {
+                fs::remove_dir_all(&data_dir)
+                    .map_err(|e| format!("Failed to clear data directory: {}", e))?;
+            }
+            fs::create_dir_all(&data_dir)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+            copy_dir_contents(&payload, &data_dir)?;
+        }
+        SnapshotKind::DatabaseDump => return Err("Database dumps are per database".to_string()),
+    }
+
+    Ok(())
+}
+
+/// Delete a snapshot
+pub fn delete_snapshot(instance_id: &Uuid, id: &str) -> Result<(), String> {
+    let dir = snapshot_dir(instance_id, id)?;
+    if !dir.exists() {
+        return Err(format!("Snapshot '{}' not found", id));
+    }
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete snapshot: {}", e))
+}
+
+/// Snapshots to remove from `snapshots` (newest first)
+///
+/// Expired safety snapshots, then regular ones beyond the newest `keep`
+/// (0 keeps all).
+fn snapshots_to_prune(snapshots: &[SnapshotInfo], keep: usize, now: DateTime<Utc>) -> Vec<String> {
+    let expired = snapshots
+        .iter()
+        .filter(|s| s.expires_at.is_some_and(|expires| expires <= now));
+    let surplus = snapshots
+        .iter()
+        .filter(|s| s.expires_at.is_none())
+        .skip(if keep == 0 { usize::MAX } else { keep });
+    expired.chain(surplus).map(|s| s.id.clone()).collect()
+}
+
+/// Remove expired safety snapshots and the oldest regular snapshots so at
+/// most `keep` remain (0 keeps all)
+///
+/// Returns the IDs of the removed snapshots.
+pub fn prune_snapshots(instance_id: &Uuid, keep: usize) -> Result<Vec<String>, String> {
+    let removed = snapshots_to_prune(&list_snapshots(instance_id)?, keep, Utc::now());
+    for id in &removed {
+        delete_snapshot(instance_id, id)?;
+    }
+    Ok(removed)
+}
+
+// ============================================================================
+// Meilisearch
+// ============================================================================
+
+/// Create a dump through the API and move it into the snapshot
+fn create_meilisearch_dump(
+    instance: &Instance,
+    data_dir: &Path,
+    payload: &Path,
+) -> Result<(), String> {
+    let base_url = format!("http://127.0.0.1:{}", instance.port);
+    let master_key = instance.get_master_key();
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let request =
+        |builder: reqwest::blocking::RequestBuilder| -> Result<serde_json::Value, String> {
+            let builder = match &master_key {
+                Some(key) => builder.bearer_auth(key),
+                None => builder,
+            };
+            builder
+                .send()
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("Meilisearch request failed: {}", e))?
+                .json()
+                .map_err(|e| format!("Invalid Meilisearch response: {}", e))
+        };
+
+    let task = request(client.post(format!("{}/dumps", base_url)))?;
+    let task_uid = task["taskUid"]
+        .as_u64()
+        .ok_or("Meilisearch did not return a task ID")?;
+
+    let started = Instant::now();
+    let dump_uid = loop {
+        let task = request(client.get(format!("{}/tasks/{}", base_url, task_uid)))?;
+        match task["status"].as_str() {
+            Some("succeeded") => {
+                break task["details"]["dumpUid"]
+                    .as_str()
+                    .ok_or("Meilisearch did not return a dump ID")?
+                    .to_string()
+            }
+            Some("failed") | Some("canceled") => {
+                return Err(format!(
+                    "Meilisearch dump failed: {}",
+                    task["error"]["message"].as_str().unwrap_or("unknown error")
+                ))
+            }
+            _ => {}
+        }
+        if started.elapsed() > MEILISEARCH_DUMP_TIMEOUT {
+            return Err("Timed out waiting for the Meilisearch dump".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    };
+
+    let dump = data_dir.join("dumps").join(format!("{}.dump", dump_uid));
+    fs::rename(&dump, payload).map_err(|e| format!("Failed to move Meilisearch dump: {}", e))
+}
+
+/// Clear the database and stage the dump for import on the next start
+fn restore_meilisearch_dump(data_dir: &Path, payload: &Path) -> Result<(), String> {
+    for entry in MEILISEARCH_DB_ENTRIES {
+        let path = data_dir.join(entry);
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else if path.exists() {
+            fs::remove_file(&path)
+        } else {
+            continue;
+        };
+        result.map_err(|e| format!("Failed to clear {}: {}", path.display(), e))?;
+    }
+
+    let import_dump = import_dump_path(data_dir);
+    if let Some(parent) = import_dump.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create dump directory: {}", e))?;
+    }
+    copy_file(payload, &import_dump)
+}
+
+// ============================================================================
+// MongoDB
+// ============================================================================
+
+/// Run `mongodump` / `mongorestore` against an instance with a gzipped archive
+fn run_mongo_tool(
+    instance: &Instance,
+    tool: &str,
+    archive: &Path,
+    extra_args: &[&str],
+) -> Result<(), String> {
+    let output = Command::new(find_mongo_tool(&instance.version, tool))
+        .arg(format!("--uri=mongodb://127.0.0.1:{}", instance.port))
+        .arg(format!("--archive={}", archive.display()))
+        .arg("--gzip")
+        .args(extra_args)
+        .output()
+        .map_err(|e| {
+            format!(
+                "Failed to execute {}: {}. Install the MongoDB Database Tools (brew install mongodb-database-tools)",
+                tool, e
+            )
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", tool, stderr.trim()));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Redis / Valkey
+// ============================================================================
+
+/// Encode a command in RESP so arguments may contain spaces
+fn encode_redis_command(args: &[&str]) -> String {
+    let mut command = format!("*{}\r\n", args.len());
+    for arg in args {
+        command.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    command
+}
+
+fn redis_command(
+    stream: &mut TcpStream,
+    reader: &mut BufReader<TcpStream>,
+    args: &[&str],
+) -> Result<(), String> {
+    stream
+        .write_all(encode_redis_command(args).as_bytes())
+        .map_err(|e| format!("Failed to send {}: {}", args[0], e))?;
+
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read {} response: {}", args[0], e))?;
+    match line.trim_end().strip_prefix('-') {
+        Some(error) => Err(format!("{} failed: {}", args[0], error)),
+        None => Ok(()),
+    }
+}
+
+/// Write the RDB file with `SAVE` and copy it into the snapshot
+fn save_redis_rdb(instance: &Instance, data_dir: &Path, payload: &Path) -> Result<(), String> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], instance.port));
+    let mut stream = TcpStream::connect_timeout(&addr, REDIS_CONNECT_TIMEOUT)
+        .map_err(|e| format!("Failed to connect to {}: {}", addr, e))?;
+    stream
+        .set_read_timeout(Some(REDIS_SAVE_TIMEOUT))
+        .map_err(|e| format!("Failed to set timeout: {}", e))?;
+    let mut reader = BufReader::new(
+        stream
+            .try_clone()
+            .map_err(|e| format!("Failed to clone connection: {}", e))?,
+    );
+
+    if let Some(password) = instance
+        .config
+        .get("password")
+        .and_then(|v| v.as_str())
+        .filter(|p| !p.is_empty())
+    {
+        redis_command(&mut stream, &mut reader, &["AUTH", password])?;
+    }
+    redis_command(&mut stream, &mut reader, &["SAVE"])?;
+
+    copy_file(&data_dir.join(REDIS_RDB_FILE), payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::InstanceBuilder;
+
+    #[test]
+    fn test_snapshot_kind_for_service() {
+        assert_eq!(
+            SnapshotKind::for_service(ServiceType::Valkey),
+            Some(SnapshotKind::RedisRdb)
+        );
+        assert_eq!(
+            SnapshotKind::for_service(ServiceType::MinIO),
+            Some(SnapshotKind::MinioMirror)
+        );
+        assert!(!supports_snapshots(ServiceType::Memcached));
+        assert!(!supports_snapshots(ServiceType::MariaDB));
+    }
+
+    #[test]
+    fn test_validate_snapshot_id() {
+        assert!(validate_snapshot_id("20261018-120000-123").is_ok());
+        assert!(validate_snapshot_id("").is_err());
+        assert!(validate_snapshot_id("../other").is_err());
+        assert!(validate_snapshot_id("a/b").is_err());
+    }
+
+    fn info(id: &str, expires_at: Option<DateTime<Utc>>) -> SnapshotInfo {
+        SnapshotInfo {
+            id: id.to_string(),
+            instance_id: Uuid::nil(),
+            instance_name: "db".to_string(),
+            service_type: ServiceType::MariaDB,
+            version: "11.4.2".to_string(),
+            kind: SnapshotKind::DatabaseDump,
+            label: None,
+            database: None,
+            size_bytes: 0,
+            created_at: Utc::now(),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_snapshots_to_prune() {
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+        // Newest first
+        let snapshots = vec![
+            info("safety-new", Some(now + hour)),
+            info("regular-3", None),
+            info("safety-old", Some(now - hour)),
+            info("regular-2", None),
+            info("regular-1", None),
+        ];
+
+        assert_eq!(
+            snapshots_to_prune(&snapshots, 2, now),
+            vec!["safety-old", "regular-1"]
+        );
+        assert_eq!(snapshots_to_prune(&snapshots, 0, now), vec!["safety-old"]);
+        assert_eq!(
+            snapshots_to_prune(&snapshots, 1, now + hour * 2),
+            vec!["safety-new", "safety-old", "regular-2", "regular-1"]
+        );
+    }
+
+    #[test]
+    fn test_minio_snapshot_requires_stopped_instance() {
+        let instance = InstanceBuilder::new()
+            .name("s3")
+            .service_type(ServiceType::MinIO)
+            .build();
+        let dir = std::env::temp_dir().join("burd-snapshot-minio-running");
+
+        let err = write_payload(&instance, true, &dir).unwrap_err();
+        assert!(err.contains("Stop instance 's3'"));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_encode_redis_command() {
+        assert_eq!(
+            encode_redis_command(&["AUTH", "pass word"]),
+            "*2\r\n$4\r\nAUTH\r\n$9\r\npass word\r\n"
+        );
+    }
+}
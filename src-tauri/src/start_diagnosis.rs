@@ -0,0 +1,268 @@
+//! Start failure diagnostics
+//!
+//! When an instance fails to initialize or its process exits during startup,
+//! the early output is matched against known failure patterns so the user
+//! sees "port 3306 is in use" with the offending log line instead of
+//! "process exited immediately".
+
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// Log lines kept in a diagnosis
+const EXCERPT_LINES: usize = 8;
+
+/// Why an instance failed to start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCause {
+    PortInUse,
+    MissingDataDir,
+    BadConfig,
+    PermissionDenied,
+    MissingLibrary,
+    /// Exited without a recognizable error
+    Exited,
+}
+
+impl FailureCause {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FailureCause::PortInUse => "port in use",
+            FailureCause::MissingDataDir => "missing data directory",
+            FailureCause::BadConfig => "invalid configuration",
+            FailureCause::PermissionDenied => "permission denied",
+            FailureCause::MissingLibrary => "missing library",
+            FailureCause::Exited => "exited during startup",
+        }
+    }
+}
+
+/// Patterns (lowercase) identifying each cause, checked in order
+const PATTERNS: &[(FailureCause, &[&str])] = &[
+    (
+        FailureCause::PortInUse,
+        &[
+            "address already in use",
+            "eaddrinuse",
+            "bind on tcp/ip port",
+            "could not create listen socket",
+            "port is already allocated",
+        ],
+    ),
+    (
+        FailureCause::MissingLibrary,
+        &["library not loaded", "image not found", "dyld"],
+    ),
+    (
+        FailureCause::PermissionDenied,
+        &["permission denied", "operation not permitted"],
+    ),
+    (
+        FailureCause::MissingDataDir,
+        &[
+            "can't change dir to",
+            "data directory",
+            "no such file or directory",
+        ],
+    ),
+    (
+        FailureCause::BadConfig,
+        &[
+            "unknown variable",
+            "unknown option",
+            "unrecognized configuration parameter",
+            "invalid value for parameter",
+            "syntax error",
+            "parse error",
+            "error parsing",
+            "unknown directive",
+            "invalid configuration",
+            "adapting config",
+        ],
+    ),
+];
+
+/// Structured explanation of a failed start
+#[derive(Debug, Clone, Serialize)]
+pub struct StartDiagnosis {
+    pub cause: FailureCause,
+    /// One-line summary, e.g. "MariaDB failed to start: port in use"
+    pub message: String,
+    /// The log line the cause was recognized from
+    pub detail: Option<String>,
+    pub hint: String,
+    /// Last lines of startup output, oldest first
+    pub log_excerpt: Vec<String>,
+    pub log_path: Option<String>,
+}
+
+impl fmt::Display for StartDiagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(detail) = &self.detail {
+            write!(f, "\n  > {}", detail)?;
+        }
+        write!(f, "\n{}", self.hint)?;
+        if self.detail.is_none() && !self.log_excerpt.is_empty() {
+            write!(f, "\nLast output:")?;
+            for line in &self.log_excerpt {
+                write!(f, "\n  {}", line)?;
+            }
+        }
+        if let Some(path) = &self.log_path {
+            write!(f, "\nFull log: {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Output lines written by the service, without Burd's debug header
+fn output_lines(output: &str) -> Vec<&str> {
+    let mut in_header = false;
+    output
+        .lines()
+        .filter(|line| {
+            if line.starts_with("=== Burd Debug Info") {
+                in_header = true;
+                return false;
+            }
+            if in_header {
+                in_header = !line.starts_with("=====");
+                return false;
+            }
+            true
+        })
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect()
+}
+
+/// Find the first line matching a known failure pattern
+fn classify(lines: &[&str]) -> Option<(FailureCause, String)> {
+    PATTERNS.iter().find_map(|(cause, patterns)| {
+        lines
+            .iter()
+            .find(|line| {
+                let line = line.to_lowercase();
+                patterns.iter().any(|p| line.contains(p))
+            })
+            .map(|line| (*cause, line.trim().to_string()))
+    })
+}
+
+fn hint(cause: FailureCause, port: u16, data_dir: &Path) -> String {
+    match cause {
+        FailureCause::PortInUse => format!(
+            "Another process is listening on port {}. Find it with `lsof -nP -i :{}` and stop \
+             it, or change the instance's port.",
+            port, port
+        ),
+        FailureCause::MissingDataDir => format!(
+            "The data directory {} is missing or incomplete. Restore a snapshot or recreate \
+             the instance to initialize it again.",
+            data_dir.display()
+        ),
+        FailureCause::BadConfig => {
+            "Fix or remove the setting above in the instance's configuration and start it again."
+                .to_string()
+        }
+        FailureCause::PermissionDenied => format!(
+            "Check that {} is owned by your user, and run `burd verify --repair` if the binary \
+             isn't executable.",
+            data_dir.display()
+        ),
+        FailureCause::MissingLibrary => {
+            "A library the binary needs is missing. Run `burd verify --repair` or download this \
+             version again."
+                .to_string()
+        }
+        FailureCause::Exited => "Check the full log for details.".to_string(),
+    }
+}
+
+/// Diagnose a failed start from the service's early output
+pub fn diagnose(
+    service_name: &str,
+    port: u16,
+    data_dir: &Path,
+    output: &str,
+    log_path: Option<&Path>,
+) -> StartDiagnosis {
+    let lines = output_lines(output);
+    let (cause, detail) = match classify(&lines) {
+        Some((cause, line)) => (cause, Some(line)),
+        None if !data_dir.exists() => (FailureCause::MissingDataDir, None),
+        None => (FailureCause::Exited, None),
+    };
+    let start = lines.len().saturating_sub(EXCERPT_LINES);
+
+    StartDiagnosis {
+        cause,
+        message: format!("{} failed to start: {}", service_name, cause.label()),
+        detail,
+        hint: hint(cause, port, data_dir),
+        log_excerpt: lines[start..].iter().map(|l| l.to_string()).collect(),
+        log_path: log_path.map(|p| p.display().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "=== Burd Debug Info ===\nService: MariaDB\nPort: 3330\n\
+                          ========================\n";
+
+    fn diagnose_log(output: &str) -> StartDiagnosis {
+        diagnose("MariaDB", 3330, Path::new("/"), output, None)
+    }
+
+    #[test]
+    fn test_port_in_use() {
+        let log = format!(
+            "{}2024-01-01 [Note] Starting\n2024-01-01 [ERROR] Can't start server: Bind on \
+             TCP/IP port. Got error: 48: Address already in use\n",
+            HEADER
+        );
+        let diagnosis = diagnose_log(&log);
+        assert_eq!(diagnosis.cause, FailureCause::PortInUse);
+        assert!(diagnosis.detail.unwrap().contains("Bind on TCP/IP port"));
+        assert!(diagnosis.hint.contains("3330"));
+        assert_eq!(diagnosis.log_excerpt.len(), 2);
+    }
+
+    #[test]
+    fn test_bad_config_line() {
+        let log = "[ERROR] mariadbd: unknown variable 'innodb_foo=1'\n[ERROR] Aborting\n";
+        let diagnosis = diagnose_log(log);
+        assert_eq!(diagnosis.cause, FailureCause::BadConfig);
+        assert_eq!(
+            diagnosis.detail.as_deref(),
+            Some("[ERROR] mariadbd: unknown variable 'innodb_foo=1'")
+        );
+    }
+
+    #[test]
+    fn test_missing_data_dir() {
+        let diagnosis = diagnose(
+            "PostgreSQL",
+            5432,
+            Path::new("/nonexistent/burd/data"),
+            "",
+            None,
+        );
+        assert_eq!(diagnosis.cause, FailureCause::MissingDataDir);
+        assert!(diagnosis.detail.is_none());
+    }
+
+    #[test]
+    fn test_unrecognized_output_keeps_excerpt() {
+        let diagnosis = diagnose_log(&format!("{}segfault at 0x0\n", HEADER));
+        assert_eq!(diagnosis.cause, FailureCause::Exited);
+        assert_eq!(diagnosis.log_excerpt, vec!["segfault at 0x0"]);
+        assert!(diagnosis
+            .to_string()
+            .contains("Last output:\n  segfault at 0x0"));
+    }
+}
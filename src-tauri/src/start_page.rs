@@ -0,0 +1,292 @@
+//! Generated start page
+//!
+//! Renders a landing page at `burd.<tld>` listing every domain and instance
+//! with its status and a quick link, so anyone reaching this machine over
+//! LAN or a tunnel can discover what's running. The page is a static file
+//! regenerated whenever proxy routes change and served by Caddy (or by the
+//! in-memory proxy when the daemon isn't installed).
+
+use crate::config::{get_app_dir, Config, ConfigStore, DomainTarget};
+use crate::proxy::RouteEntry;
+use std::fs;
+use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Subdomain reserved for the start page (`burd.<tld>`)
+pub const START_PAGE_SUBDOMAIN: &str = "burd";
+
+/// Route identifier used for the start page in generated Caddy configs
+pub const START_PAGE_ROUTE_ID: &str = "burd-start-page";
+
+/// Common CSS styles for the start page
+const START_PAGE_STYLES: &str = r#"*{margin:0;padding:0;box-sizing:border-box}
+body{font-family:-apple-system,BlinkMacSystemFont,"Segoe UI",Roboto,sans-serif;min-height:100vh;background:#f5f5f7;color:#1d1d1f}
+.container{max-width:880px;margin:0 auto;padding:48px 24px}
+h1{font-size:32px;font-weight:700;margin-bottom:4px}
+h2{font-size:18px;font-weight:600;margin:32px 0 12px}
+.subtitle{color:#86868b;margin-bottom:8px}
+table{width:100%;border-collapse:collapse;background:#fff;border-radius:12px;overflow:hidden;border:1px solid #e5e5e5}
+th,td{text-align:left;padding:10px 14px;font-size:14px;border-bottom:1px solid #e5e5e5}
+th{color:#86868b;font-weight:500}
+tr:last-child td{border-bottom:none}
+a{color:#0071e3;text-decoration:none}
+a:hover{text-decoration:underline}
+.mono{font-family:ui-monospace,monospace}
+.status{display:inline-block;padding:2px 8px;border-radius:10px;font-size:12px;font-weight:500}
+.online{background:#d1f5d8;color:#1a7f37}
+.offline{background:#e5e5e5;color:#6e6e73}
+.empty{color:#86868b;font-size:14px}
+@media(prefers-color-scheme:dark){
+body{background:#1c1c1e;color:#f5f5f7}
+.subtitle,th,.empty{color:#98989d}
+table{background:#2c2c2e;border-color:#3a3a3c}
+th,td{border-color:#3a3a3c}
+a{color:#2997ff}
+.online{background:#1e3a26;color:#4ade80}
+.offline{background:#3a3a3c;color:#98989d}
+}"#;
+
+/// A row on the start page
+#[derive(Debug, Clone)]
+pub struct StartPageEntry {
+    /// Display name (full domain or instance name)
+    pub name: String,
+    /// Human readable description of what the entry points at
+    pub target: String,
+    /// Quick link, if the entry is reachable over HTTP
+    pub url: Option<String>,
+    /// Whether the backend is currently reachable
+    pub online: bool,
+}
+
+/// Get the directory the start page is written to
+pub fn get_start_page_dir() -> PathBuf {
+    get_app_dir()
+        .unwrap_or_else(|_| PathBuf::from("/tmp"))
+        .join("start-page")
+}
+
+/// Get the path of the generated start page
+pub fn get_start_page_path() -> PathBuf {
+    get_start_page_dir().join("index.html")
+}
+
+/// Get the full start page domain for a TLD
+pub fn start_page_domain(tld: &str) -> String {
+    format!("{}.{}", START_PAGE_SUBDOMAIN, tld)
+}
+
+/// Check whether a subdomain is reserved for the start page
+pub fn is_reserved_subdomain(subdomain: &str) -> bool {
+    subdomain == START_PAGE_SUBDOMAIN
+}
+
+/// Check whether something is listening on a local port
+fn is_port_open(port: u16) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&addr, Duration::from_millis(100)).is_ok()
+}
+
+/// Escape text for safe inclusion in HTML
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Build start page entries for domains, using registered routes for SSL info
+pub fn collect_domain_entries(config: &Config, routes: &[RouteEntry]) -> Vec<StartPageEntry> {
+    config
+        .domains
+        .iter()
+        .filter(|d| !is_reserved_subdomain(&d.subdomain))
+        .map(|d| {
+            let full_domain = d.full_domain(&config.tld);
+            let ssl_enabled = routes
+                .iter()
+                .find(|r| r.domain == full_domain)
+                .map(|r| r.ssl_enabled)
+                .unwrap_or(d.ssl_enabled);
+            let scheme = if ssl_enabled { "https" } else { "http" };
+
+            let (target, online) = match &d.target {
+                DomainTarget::Instance(id) => match config.instances.iter().find(|i| i.id == *id) {
+                    Some(instance) => (
+                        format!("{} (port {})", instance.name, instance.port),
+                        is_port_open(instance.port),
+                    ),
+                    None => ("Missing instance".to_string(), false),
+                },
+                DomainTarget::Port(port) => (format!("Port {}", port), is_port_open(*port)),
+                DomainTarget::StaticFiles { path, .. } => {
+                    (path.clone(), std::path::Path::new(path).is_dir())
+                }
+            };
+
+            StartPageEntry {
+                url: Some(format!("{}://{}", scheme, full_domain)),
+                name: full_domain,
+                target,
+                online,
+            }
+        })
+        .collect()
+}
+
+/// Build start page entries for instances
+pub fn collect_instance_entries(config: &Config) -> Vec<StartPageEntry> {
+    config
+        .instances
+        .iter()
+        .map(|instance| StartPageEntry {
+            name: instance.name.clone(),
+            target: format!(
+                "{} {} on port {}",
+                instance.service_type.display_name(),
+                instance.version,
+                instance.port
+            ),
+            url: None,
+            online: is_port_open(instance.port),
+        })
+        .collect()
+}
+
+/// Render a table of entries
+fn render_table(entries: &[StartPageEntry], empty_message: &str) -> String {
+    if entries.is_empty() {
+        return format!(r#"<p class="empty">{}</p>"#, escape_html(empty_message));
+    }
+
+    let rows: String = entries
+        .iter()
+        .map(|entry| {
+            let name = match &entry.url {
+                Some(url) => format!(
+                    r#"<a class="mono" href="{url}">{name}</a>"#,
+                    url = escape_html(url),
+                    name = escape_html(&entry.name)
+                ),
+                None => format!(r#"<span class="mono">{}</span>"#, escape_html(&entry.name)),
+            };
+            let status = if entry.online {
+                r#"<span class="status online">Online</span>"#
+            } else {
+                r#"<span class="status offline">Offline</span>"#
+            };
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                name,
+                escape_html(&entry.target),
+                status
+            )
+        })
+        .collect();
+
+    format!(
+        "<table>\n<tr><th>Name</th><th>Target</th><th>Status</th></tr>\n{}</table>",
+        rows
+    )
+}
+
+/// Generate the start page HTML
+pub fn generate_start_page_html(
+    tld: &str,
+    domains: &[StartPageEntry],
+    instances: &[StartPageEntry],
+) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>Burd - {domain}</title>
+<style>{styles}</style>
+</head>
+<body>
+<div class="container">
+<h1>Burd</h1>
+<p class="subtitle">Sites and services running on this machine</p>
+<h2>Domains</h2>
+{domains}
+<h2>Instances</h2>
+{instances}
+</div>
+</body>
+</html>"#,
+        domain = escape_html(&start_page_domain(tld)),
+        styles = START_PAGE_STYLES,
+        domains = render_table(domains, "No domains configured."),
+        instances = render_table(instances, "No instances configured."),
+    )
+}
+
+/// Regenerate the start page from the current config and registered routes
+pub fn write_start_page(tld: &str, routes: &[RouteEntry]) -> Result<(), String> {
+    let config = ConfigStore::new()?.load()?;
+    let html = generate_start_page_html(
+        tld,
+        &collect_domain_entries(&config, routes),
+        &collect_instance_entries(&config),
+    );
+
+    let dir = get_start_page_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory {:?}: {}", dir, e))?;
+
+    let path = get_start_page_path();
+    fs::write(&path, html).map_err(|e| format!("Failed to write file {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_page_lists_entries() {
+        let domains = vec![StartPageEntry {
+            name: "api.burd".to_string(),
+            target: "Port 3000".to_string(),
+            url: Some("https://api.burd".to_string()),
+            online: true,
+        }];
+        let instances = vec![StartPageEntry {
+            name: "redis".to_string(),
+            target: "Redis 7.2 on port 6379".to_string(),
+            url: None,
+            online: false,
+        }];
+
+        let html = generate_start_page_html("burd", &domains, &instances);
+        assert!(html.contains(r#"href="https://api.burd""#));
+        assert!(html.contains("Redis 7.2 on port 6379"));
+        assert!(html.contains("status online"));
+        assert!(html.contains("status offline"));
+    }
+
+    #[test]
+    fn test_start_page_empty_and_escaped() {
+        let instances = vec![StartPageEntry {
+            name: "<script>".to_string(),
+            target: "x".to_string(),
+            url: None,
+            online: false,
+        }];
+
+        let html = generate_start_page_html("burd", &[], &instances);
+        assert!(html.contains("No domains configured."));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_reserved_subdomain() {
+        assert!(is_reserved_subdomain("burd"));
+        assert!(!is_reserved_subdomain("api"));
+        assert_eq!(start_page_domain("test"), "burd.test");
+    }
+}
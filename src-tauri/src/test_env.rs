@@ -0,0 +1,448 @@
+//! Ephemeral Burd environment for end-to-end tests
+//!
+//! Unit tests cover the pieces one at a time; lifecycle flows like
+//! start → route → health need them running together. A [`TestEnv`] brings
+//! up the config store, DNS server, reverse proxy and HTTP API against a
+//! throwaway app directory, on random ports, with fake service binaries in
+//! place of downloaded ones. Dropping it stops everything it started and
+//! removes the directory.
+//!
+//! Available to the crate's own tests and, with the `test-support` feature,
+//! to tests outside it. The app directory is overridden for the creating
+//! thread only, so drive the environment from the test's thread (the default
+//! `#[tokio::test]` runtime). Fake binaries are small Python HTTP servers;
+//! tests that start them check [`fake_binaries_supported`] and skip
+//! themselves when `python3` is missing.
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::Router;
+use chrono::Utc;
+use hickory_proto::op::{Message, MessageType, Query};
+use hickory_proto::rr::{Name, RData, RecordType};
+use hickory_proto::serialize::binary::{BinDecodable, BinEncodable};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, TcpListener, UdpSocket};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::api::{self, remote::AccessPolicy};
+use crate::commands::AppState;
+use crate::config::{
+    get_versioned_binary_path, set_app_dir_override, BinaryInfo, Config, ConfigStore, ServiceType,
+};
+
+/// TLD the environment's DNS server and proxy answer for
+pub const TEST_TLD: &str = "test";
+
+/// Process-wide caches such as the service registry are shared, so
+/// environments take turns
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Fake service: answers every request with 200 and `<name>: <path>`
+///
+/// The port comes from the first argument naming a port or address
+/// (`--port 7700`, `--http-addr 127.0.0.1:7700`, `--port=7700`). The shell
+/// stays the parent so the PID Burd records keeps its command line, and
+/// passes SIGTERM on so stopping the instance frees the port.
+const FAKE_BINARY: &str = r#"#!/bin/sh
+python3 - "$@" <<'EOF' &
+import re, sys
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+NAME = "{name}"
+
+def port(args):
+    for i, arg in enumerate(args):
+        flag, _, value = arg.partition("=")
+        if "port" in flag or "addr" in flag:
+            value = value or (args[i + 1] if i + 1 < len(args) else "")
+            match = re.search(r"(\d+)$", value)
+            if match:
+                return int(match.group(1))
+    sys.exit(NAME + ": no port in arguments")
+
+class Handler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        body = ("%s: %s" % (NAME, self.path)).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "text/plain")
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    do_POST = do_PUT = do_DELETE = do_GET
+
+HTTPServer(("127.0.0.1", port(sys.argv[1:])), Handler).serve_forever()
+EOF
+child=$!
+trap 'kill $child 2>/dev/null; exit 0' TERM INT
+wait $child
+"#;
+
+/// A running Burd in a temporary app directory
+pub struct TestEnv {
+    root: PathBuf,
+    state: Arc<AppState>,
+    router: Router,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl TestEnv {
+    /// Create the app directory and start DNS on a random port
+    ///
+    /// The proxy is created on a random port but only listens once
+    /// [`start_proxy`](Self::start_proxy) is called.
+    pub fn new() -> Result<Self, String> {
+        let guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let root = std::env::temp_dir().join(format!("burd-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create test app directory: {}", e))?;
+        set_app_dir_override(Some(root.clone()));
+
+        let config = Config {
+            dns_port: free_udp_port()?,
+            proxy_port: free_port()?,
+            tld: TEST_TLD.to_string(),
+            ..Config::default()
+        };
+        let config_store = ConfigStore::new()?;
+        config_store.save(&config)?;
+
+        let state = Arc::new(AppState::new(config_store, &config));
+        let router = api::create_router(
+            state.clone(),
+            AccessPolicy::from_settings(&config.api_remote),
+        );
+
+        Ok(Self {
+            root,
+            state,
+            router,
+            _guard: guard,
+        })
+    }
+
+    /// The temporary app directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// State shared by the commands, API and proxy
+    pub fn state(&self) -> &Arc<AppState> {
+        &self.state
+    }
+
+    /// The current config
+    pub fn config(&self) -> Result<Config, String> {
+        self.state
+            .config_store
+            .lock()
+            .map_err(|_| "Failed to acquire config lock".to_string())?
+            .load()
+    }
+
+    /// Install a fake binary as `version` of `service_type`
+    ///
+    /// It's placed where downloads go and recorded in the config, so
+    /// instances of that version create and start as usual.
+    pub fn install_fake_binary(
+        &self,
+        service_type: ServiceType,
+        version: &str,
+    ) -> Result<PathBuf, String> {
+        let path = get_versioned_binary_path(service_type, version)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create binary directory: {}", e))?;
+        }
+        fs::write(&path, FAKE_BINARY.replace("{name}", service_type.as_str()))
+            .map_err(|e| format!("Failed to write fake binary: {}", e))?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make fake binary executable: {}", e))?;
+
+        self.state
+            .config_store
+            .lock()
+            .map_err(|_| "Failed to acquire config lock".to_string())?
+            .update_binary_info(
+                service_type,
+                BinaryInfo {
+                    version: version.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    downloaded_at: Utc::now(),
+                    sha256: None,
+                    arch: None,
+                },
+            )?;
+        Ok(path)
+    }
+
+    /// Start the reverse proxy on its random port
+    pub async fn start_proxy(&self) -> Result<(), String> {
+        self.state.proxy_server.lock().await.start().await
+    }
+
+    /// Call the HTTP API, returning the response's `data`
+    ///
+    /// An unsuccessful response becomes its `error`.
+    pub async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        let request = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("content-type", "application/json")
+            .body(match body {
+                Some(body) => Body::from(body.to_string()),
+                None => Body::empty(),
+            })
+            .map_err(|e| format!("Invalid request: {}", e))?;
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        let mut response: serde_json::Value = serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Invalid response from {} {}: {}", method, path, e))?;
+
+        if response["success"].as_bool() == Some(true) {
+            Ok(response["data"].take())
+        } else {
+            Err(response["error"]
+                .as_str()
+                .unwrap_or("Request failed")
+                .to_string())
+        }
+    }
+
+    /// GET `path` through the proxy for `host`, returning status and body
+    pub async fn proxy_get(&self, host: &str, path: &str) -> Result<(u16, String), String> {
+        let port = self.state.proxy_server.lock().await.port();
+        let response = reqwest::Client::new()
+            .get(format!("http://127.0.0.1:{}{}", port, path))
+            .header(reqwest::header::HOST, host)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| format!("Proxy request failed: {}", e))?;
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read proxy response: {}", e))?;
+        Ok((status, body))
+    }
+
+    /// Ask the environment's DNS server for the A records of `name`
+    pub fn resolve(&self, name: &str) -> Result<Vec<IpAddr>, String> {
+        let port = self.config()?.dns_port;
+        let name = Name::from_ascii(name).map_err(|e| format!("Invalid name: {}", e))?;
+
+        let mut query = Message::new();
+        query
+            .set_id(1)
+            .set_message_type(MessageType::Query)
+            .set_recursion_desired(true)
+            .add_query(Query::query(name, RecordType::A));
+        let query = query
+            .to_bytes()
+            .map_err(|e| format!("Failed to encode query: {}", e))?;
+
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+            .map_err(|e| format!("Failed to bind DNS client: {}", e))?;
+        socket
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .map_err(|e| format!("Failed to set DNS timeout: {}", e))?;
+        socket
+            .send_to(&query, (Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| format!("Failed to send DNS query: {}", e))?;
+
+        let mut buf = [0u8; 512];
+        let len = socket
+            .recv(&mut buf)
+            .map_err(|e| format!("No DNS answer: {}", e))?;
+        let response =
+            Message::from_bytes(&buf[..len]).map_err(|e| format!("Invalid DNS answer: {}", e))?;
+
+        Ok(response
+            .answers()
+            .iter()
+            .filter_map(|record| match record.data() {
+                Some(RData::A(a)) => Some(IpAddr::V4(a.0)),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        // Stop what the test started before its files go away
+        if let (Ok(config_store), Ok(process_manager)) = (
+            self.state.config_store.lock(),
+            self.state.process_manager.lock(),
+        ) {
+            for instance in config_store.load().map(|c| c.instances).unwrap_or_default() {
+                if process_manager.is_running(&instance.id) {
+                    let _ = process_manager.stop(&instance.id);
+                }
+            }
+        }
+        if let Ok(mut proxy) = self.state.proxy_server.try_lock() {
+            proxy.stop();
+        }
+        if let Ok(mut dns) = self.state.dns_server.lock() {
+            dns.stop();
+        }
+
+        set_app_dir_override(None);
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Whether fake binaries can run here (they need `python3` on the PATH)
+pub fn fake_binaries_supported() -> bool {
+    std::process::Command::new("python3")
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// A TCP port nothing listens on right now
+pub fn free_port() -> Result<u16, String> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to find a free port: {}", e))
+}
+
+/// A UDP port nothing is bound to right now
+fn free_udp_port() -> Result<u16, String> {
+    UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|socket| socket.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to find a free UDP port: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_start_route_health_stop() {
+        if !fake_binaries_supported() {
+            eprintln!("Skipping: python3 is needed for fake service binaries");
+            return;
+        }
+        let env = TestEnv::new().unwrap();
+        env.install_fake_binary(ServiceType::Meilisearch, "1.0.0")
+            .unwrap();
+        env.start_proxy().await.unwrap();
+
+        let port = free_port().unwrap();
+        let instance = env
+            .request(
+                "POST",
+                "/instances",
+                Some(json!({
+                    "name": "search",
+                    "service_type": "meilisearch",
+                    "version": "1.0.0",
+                    "port": port,
+                })),
+            )
+            .await
+            .unwrap();
+        let id = instance["id"].as_str().unwrap().to_string();
+        env.request(
+            "POST",
+            "/domains",
+            Some(json!({
+                "subdomain": "search",
+                "target_type": "instance",
+                "target_value": id,
+            })),
+        )
+        .await
+        .unwrap();
+
+        // Routed but nothing listening yet
+        let (status, _) = env.proxy_get("search.test", "/").await.unwrap();
+        assert_eq!(status, 502);
+
+        env.request(
+            "POST",
+            &format!("/instances/{}/start?wait=true&timeout=10", id),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            env.resolve("search.test").unwrap(),
+            vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]
+        );
+        let (status, body) = env.proxy_get("search.test", "/indexes").await.unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, "meilisearch: /indexes");
+
+        let instances = env.request("GET", "/instances", None).await.unwrap();
+        let listed = &instances.as_array().unwrap()[0];
+        assert_eq!(listed["running"], true);
+        assert_eq!(listed["healthy"], true);
+
+        env.request("POST", &format!("/instances/{}/stop", id), None)
+            .await
+            .unwrap();
+        let (status, _) = env.proxy_get("search.test", "/").await.unwrap();
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_start_requires_installed_version() {
+        let env = TestEnv::new().unwrap();
+        env.install_fake_binary(ServiceType::Meilisearch, "1.0.0")
+            .unwrap();
+
+        let instance = env
+            .request(
+                "POST",
+                "/instances",
+                Some(json!({
+                    "name": "search",
+                    "service_type": "meilisearch",
+                    "version": "1.0.0",
+                    "port": free_port().unwrap(),
+                })),
+            )
+            .await
+            .unwrap();
+        let id = instance["id"].as_str().unwrap();
+
+        fs::remove_dir_all(env.root().join("bin")).unwrap();
+        let error = env
+            .request("POST", &format!("/instances/{}/start", id), None)
+            .await
+            .unwrap_err();
+        assert!(error.contains("not found"), "{}", error);
+        let process_manager = env.state().process_manager.lock().unwrap();
+        assert!(!process_manager.is_running(&Uuid::parse_str(id).unwrap()));
+    }
+}
@@ -8,7 +8,9 @@ use std::path::PathBuf;
 use tempfile::TempDir;
 use uuid::Uuid;
 
-use crate::config::{Config, Domain, DomainTarget, Instance, ParkedDirectory, ServiceType, Stack};
+use crate::config::{
+    Config, Domain, DomainTarget, Instance, ParkedDirectory, SeedSettings, ServiceType, Stack,
+};
 
 // ============================================================================
 // Instance Builders
@@ -25,6 +27,9 @@ pub struct InstanceBuilder {
     domain: Option<String>,
     domain_enabled: bool,
     stack_id: Option<Uuid>,
+    tags: Vec<String>,
+    extra_args: Vec<String>,
+    seed: Option<SeedSettings>,
 }
 
 impl InstanceBuilder {
@@ -40,6 +45,9 @@ impl InstanceBuilder {
             domain: None,
             domain_enabled: true,
             stack_id: None,
+            tags: Vec::new(),
+            extra_args: Vec::new(),
+            seed: None,
         }
     }
 
@@ -97,6 +105,24 @@ impl InstanceBuilder {
         self
     }
 
+    /// Set the tags
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    /// Set the extra start arguments
+    pub fn extra_args(mut self, args: &[&str]) -> Self {
+        self.extra_args = args.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    /// Set the database seed
+    pub fn seed(mut self, seed: SeedSettings) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Build the instance
     pub fn build(self) -> Instance {
         Instance {
@@ -108,10 +134,15 @@ impl InstanceBuilder {
             config: self.config,
             master_key: None,
             auto_start: self.auto_start,
+            restart_on_failure: false,
+            wake_on_access: false,
             created_at: Utc::now(),
             domain: self.domain,
             domain_enabled: self.domain_enabled,
             stack_id: self.stack_id,
+            tags: self.tags,
+            extra_args: self.extra_args,
+            seed: self.seed,
         }
     }
 
@@ -385,13 +416,33 @@ impl ConfigBuilder {
             domains: self.domains,
             stacks: self.stacks,
             parked_directories: self.parked_directories,
+            workers: Vec::new(),
             binaries: std::collections::HashMap::new(),
             dns_port: self.dns_port,
             proxy_port: self.proxy_port,
             tld: self.tld,
+            extra_tlds: Vec::new(),
+            dns_records: Vec::new(),
+            emulations: Vec::new(),
+            vault: Vec::new(),
+            mail_routes: Vec::new(),
             proxy_installed: false,
             frp_servers: Vec::new(),
             tunnels: Vec::new(),
+            port_range: Default::default(),
+            snapshot_retention: crate::snapshot::DEFAULT_SNAPSHOT_RETENTION,
+            safety_snapshot_hours: crate::snapshot::DEFAULT_SAFETY_SNAPSHOT_HOURS,
+            default_versions: std::collections::HashMap::new(),
+            release_channels: std::collections::HashMap::new(),
+            arch_overrides: std::collections::HashMap::new(),
+            api_remote: Default::default(),
+            remote_hosts: Vec::new(),
+            backup: Default::default(),
+            github: Default::default(),
+            idle_stop: Default::default(),
+            cert_monitor: Default::default(),
+            log_retention: Default::default(),
+            slugs: Default::default(),
         }
     }
 }
@@ -482,6 +533,31 @@ mod tests {
         assert!(instance.auto_start);
     }
 
+    #[test]
+    fn test_instance_builder_tags() {
+        let instance = InstanceBuilder::new()
+            .tags(&["client:acme", "env:test"])
+            .build();
+
+        assert!(instance.has_tags(&["client:acme".to_string()]));
+        assert!(instance.has_tags(&["CLIENT:ACME".to_string(), "env:test".to_string()]));
+        assert!(!instance.has_tags(&["client:acme".to_string(), "env:prod".to_string()]));
+        assert!(instance.has_tags(&[]));
+    }
+
+    #[test]
+    fn test_instance_extra_args_line() {
+        let instance = InstanceBuilder::new()
+            .extra_args(&["--appendonly", "yes", "--save", "", "it's here"])
+            .build();
+
+        assert_eq!(
+            instance.extra_args_line(),
+            "--appendonly yes --save '' 'it'\\''s here'"
+        );
+        assert_eq!(InstanceBuilder::new().build().extra_args_line(), "");
+    }
+
     #[test]
     fn test_domain_builder_instance_target() {
         let instance_id = Uuid::new_v4();
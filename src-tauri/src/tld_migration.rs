@@ -0,0 +1,203 @@
+//! Moving Burd to a new TLD
+//!
+//! Changing the TLD touches more than the setting: the `/etc/resolver` file,
+//! the DNS server and proxy routes, and linked projects whose `.env` files
+//! carry the old domains. The pieces Burd can't safely change itself (URLs
+//! stored in WordPress databases, hand-written `.env` values, running
+//! instances that were started with their domain) are reported back as
+//! manual steps.
+
+use crate::analyzer::parse_env_file;
+use crate::config::{Config, DomainTarget};
+use crate::env_sync::{self, EnvChange};
+use crate::validation;
+use crate::wp_cli;
+use serde::Serialize;
+use std::path::Path;
+
+/// Public TLDs; using one would hide the real sites under it
+const PUBLIC_TLDS: &[&str] = &[
+    "com", "net", "org", "edu", "gov", "mil", "int", "io", "co", "ai", "me", "info", "biz",
+];
+
+/// TLDs on the HSTS preload list, where browsers refuse plain HTTP
+const HSTS_TLDS: &[&str] = &["app", "day", "dev", "foo", "mov", "new", "page", "zip"];
+
+/// Outcome of a TLD change
+#[derive(Debug, Clone, Serialize)]
+pub struct TldMigration {
+    pub old_tld: String,
+    pub new_tld: String,
+    /// Whether `/etc/resolver` now points the new TLD at Burd's DNS server
+    pub resolver_updated: bool,
+    /// .env updates for linked projects, offered rather than applied
+    pub env_changes: Vec<EnvChange>,
+    /// What still needs doing by hand
+    pub manual_steps: Vec<String>,
+}
+
+impl TldMigration {
+    pub fn new(old_tld: &str, new_tld: &str) -> Self {
+        Self {
+            old_tld: old_tld.to_string(),
+            new_tld: new_tld.to_string(),
+            resolver_updated: false,
+            env_changes: Vec::new(),
+            manual_steps: Vec::new(),
+        }
+    }
+}
+
+/// Normalize and validate a TLD, rejecting ones that can't work locally
+pub fn validate(tld: &str) -> Result<String, String> {
+    let tld = tld.trim().trim_start_matches('.').to_lowercase();
+    validation::validate_tld(&tld).map_err(|e| format!("Invalid TLD: {}", e))?;
+
+    if tld == "local" {
+        return Err(
+            "Invalid TLD: .local is used by Bonjour (mDNS) on macOS, so lookups would be slow or fail"
+                .to_string(),
+        );
+    }
+    if tld == "localhost" {
+        return Err(
+            "Invalid TLD: browsers resolve .localhost themselves and bypass Burd's DNS".to_string(),
+        );
+    }
+    if PUBLIC_TLDS.contains(&tld.as_str()) {
+        return Err(format!(
+            "Invalid TLD: .{} is a public TLD; Burd would hide every real .{} site",
+            tld, tld
+        ));
+    }
+
+    Ok(tld)
+}
+
+/// Steps Burd can't take itself after moving from `old_tld` to `new_tld`
+///
+/// `env_changes` are the updates already offered, so their keys aren't
+/// reported again.
+pub fn manual_steps(
+    config: &Config,
+    old_tld: &str,
+    new_tld: &str,
+    env_changes: &[EnvChange],
+) -> Vec<String> {
+    let mut steps = Vec::new();
+
+    if HSTS_TLDS.contains(&new_tld) {
+        let insecure: Vec<String> = config
+            .domains
+            .iter()
+            .filter(|d| !d.ssl_enabled)
+            .map(|d| d.full_domain(new_tld))
+            .collect();
+        if !insecure.is_empty() {
+            steps.push(format!(
+                "Browsers only load .{} over HTTPS; enable SSL for {}",
+                new_tld,
+                insecure.join(", ")
+            ));
+        }
+    }
+
+    // WordPress keeps its URLs in the database
+    let mut wordpress_roots = Vec::new();
+    for domain in &config.domains {
+        let DomainTarget::Instance(instance_id) = &domain.target else {
+            continue;
+        };
+        let Some(root) = config
+            .instances
+            .iter()
+            .find(|i| i.id == *instance_id)
+            .and_then(|i| i.config.get("document_root"))
+            .and_then(|v| v.as_str())
+            .and_then(|dr| wp_cli::find_project_root(Path::new(dr)))
+        else {
+            continue;
+        };
+        if wordpress_roots.contains(&root) {
+            continue;
+        }
+        let scheme = if domain.ssl_enabled { "https" } else { "http" };
+        steps.push(format!(
+            "Run `burd wp search-replace {}://{}` in {} to update the URLs in its database",
+            scheme,
+            domain.full_domain(old_tld),
+            root.display()
+        ));
+        wordpress_roots.push(root);
+    }
+
+    // .env values that still mention the old TLD but weren't matched to a domain
+    for project in env_sync::linked_projects(config) {
+        let Some(env) = parse_env_file(&project.join(".env")) else {
+            continue;
+        };
+        let mut keys: Vec<&String> = env
+            .iter()
+            .filter(|(key, value)| {
+                mentions_tld(value, old_tld)
+                    && !env_changes
+                        .iter()
+                        .any(|c| c.project == project && &c.key == *key)
+            })
+            .map(|(key, _)| key)
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+        keys.sort();
+        steps.push(format!(
+            "{}: {} still mention .{}",
+            project.join(".env").display(),
+            keys.iter()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            old_tld
+        ));
+    }
+
+    steps
+}
+
+/// Whether `value` contains a host under `.tld`
+fn mentions_tld(value: &str, tld: &str) -> bool {
+    let needle = format!(".{}", tld);
+    value.match_indices(&needle).any(|(index, _)| {
+        value[index + needle.len()..]
+            .chars()
+            .next()
+            .map(|c| !(c.is_ascii_alphanumeric() || c == '-' || c == '.'))
+            .unwrap_or(true)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate(" .Test "), Ok("test".to_string()));
+        assert_eq!(validate("burd"), Ok("burd".to_string()));
+        assert!(validate("local").is_err());
+        assert!(validate("localhost").is_err());
+        assert!(validate("com").is_err());
+        assert!(validate("my-tld").is_err());
+    }
+
+    #[test]
+    fn test_mentions_tld() {
+        assert!(mentions_tld("https://shop.burd", "burd"));
+        assert!(mentions_tld("https://shop.burd/wp", "burd"));
+        assert!(mentions_tld("shop.burd:8000,api.burd", "burd"));
+        assert!(mentions_tld(".burd", "burd"));
+        assert!(!mentions_tld("burd", "burd"));
+        assert!(!mentions_tld("https://shop.burdock.com", "burd"));
+        assert!(!mentions_tld("https://shop.burd.example.com", "burd"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Request Inspector
+//!
+//! Keeps the most recent requests of every proxied domain in memory, like a
+//! lightweight local Telescope. Caddy already writes each site's requests to
+//! its JSON access log; `run` tails those logs, records every new request and
+//! publishes it on the event stream. The first time a log is seen its tail
+//! fills the buffer, so requests made before Burd started still show up.
+
+use chrono::{DateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::caddy::get_site_log_path;
+use crate::commands::AppState;
+use crate::error::LockExt;
+use crate::events::{self, Event};
+use crate::lock;
+use crate::logs::{
+    get_last_lines, is_access_log, parse_caddy_json, read_new_lines, site_domains, LogFileState,
+};
+
+/// How often the access logs are checked for new requests
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Requests kept per domain
+pub const MAX_REQUESTS_PER_DOMAIN: usize = 200;
+
+/// A request served through the proxy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestRecord {
+    pub domain: String,
+    pub at: DateTime<Utc>,
+    pub method: String,
+    /// Path with query string
+    pub path: String,
+    pub status: u16,
+    pub duration_ms: f64,
+    pub remote_ip: Option<String>,
+}
+
+/// Recent requests per domain
+#[derive(Debug, Default)]
+pub struct RequestLog {
+    requests: HashMap<String, VecDeque<RequestRecord>>,
+}
+
+impl RequestLog {
+    /// Add a request, dropping the domain's oldest beyond the limit
+    pub fn record(&mut self, request: RequestRecord) {
+        let requests = self.requests.entry(request.domain.clone()).or_default();
+        requests.push_back(request);
+        while requests.len() > MAX_REQUESTS_PER_DOMAIN {
+            requests.pop_front();
+        }
+    }
+
+    /// Forget domains that no longer exist
+    pub fn retain(&mut self, domains: &HashSet<String>) {
+        self.requests.retain(|domain, _| domains.contains(domain));
+    }
+
+    /// Most recent requests of a domain, or of every domain, newest first
+    pub fn recent(&self, domain: Option<&str>, limit: usize) -> Vec<RequestRecord> {
+        let mut requests: Vec<RequestRecord> = self
+            .requests
+            .iter()
+            .filter(|(d, _)| domain.is_none_or(|wanted| wanted == d.as_str()))
+            .flat_map(|(_, requests)| requests.iter().cloned())
+            .collect();
+        requests.sort_by_key(|r| std::cmp::Reverse(r.at));
+        requests.truncate(limit);
+        requests
+    }
+}
+
+static REQUESTS: Lazy<Mutex<RequestLog>> = Lazy::new(|| Mutex::new(RequestLog::default()));
+
+/// Most recent requests of a domain (full domain), or of every domain, newest first
+pub fn recent_requests(domain: Option<&str>, limit: usize) -> Result<Vec<RequestRecord>, String> {
+    Ok(lock!(REQUESTS)?.recent(domain, limit))
+}
+
+/// Parse a site access log line (error log lines are skipped)
+fn parse_record(domain: &str, line: &str) -> Option<RequestRecord> {
+    let entry = parse_caddy_json(line)?;
+    if !is_access_log(&entry) {
+        return None;
+    }
+
+    Some(RequestRecord {
+        domain: domain.to_string(),
+        at: Utc.timestamp_millis_opt(entry.timestamp).single()?,
+        remote_ip: entry.context["request"]["remote_ip"]
+            .as_str()
+            .map(String::from),
+        method: entry.method?,
+        path: entry.path?,
+        status: entry.status?,
+        duration_ms: entry.duration_ms?,
+    })
+}
+
+/// Record the requests added to every site's access log since the last check
+///
+/// Only requests that are new since the log was first seen are published.
+fn ingest(
+    state: &AppState,
+    positions: &mut LogFileState,
+    seen: &mut HashSet<String>,
+) -> Result<(), String> {
+    let config = lock!(state.config_store)?.load()?;
+    let domains = site_domains(&config);
+
+    let mut new_requests = Vec::new();
+    let mut backfill = Vec::new();
+    for domain in &domains {
+        let path = get_site_log_path(domain).to_string_lossy().to_string();
+        let Ok(size) = std::fs::metadata(&path).map(|m| m.len()) else {
+            continue;
+        };
+
+        if seen.insert(path.clone()) {
+            let lines = get_last_lines(&path, MAX_REQUESTS_PER_DOMAIN).unwrap_or_default();
+            backfill.extend(lines.iter().filter_map(|l| parse_record(domain, l)));
+            positions.set_position(&path, size);
+            continue;
+        }
+        if size == positions.get_position(&path) {
+            continue;
+        }
+
+        let lines = read_new_lines(&path, positions).unwrap_or_default();
+        let skip = lines.len().saturating_sub(MAX_REQUESTS_PER_DOMAIN);
+        new_requests.extend(
+            lines
+                .iter()
+                .skip(skip)
+                .filter_map(|l| parse_record(domain, l)),
+        );
+    }
+
+    {
+        let mut log = lock!(REQUESTS)?;
+        log.retain(&domains.iter().cloned().collect());
+        for request in backfill.into_iter().chain(new_requests.iter().cloned()) {
+            log.record(request);
+        }
+    }
+    for request in new_requests {
+        events::publish(Event::Request(request));
+    }
+    Ok(())
+}
+
+/// Tail every site's access log forever, recording new requests
+pub async fn run(state: AppState) {
+    let mut positions = LogFileState::new();
+    let mut seen = HashSet::new();
+    loop {
+        let ingest_state = state.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let result = ingest(&ingest_state, &mut positions, &mut seen);
+            (positions, seen, result)
+        })
+        .await;
+        match result {
+            Ok((p, s, result)) => {
+                positions = p;
+                seen = s;
+                if let Err(e) = result {
+                    eprintln!("Request inspector failed: {}", e);
+                }
+            }
+            Err(_) => {
+                positions = LogFileState::new();
+                seen = HashSet::new();
+            }
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(domain: &str, at_secs: i64) -> RequestRecord {
+        RequestRecord {
+            domain: domain.to_string(),
+            at: Utc.timestamp_opt(at_secs, 0).unwrap(),
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            status: 200,
+            duration_ms: 1.0,
+            remote_ip: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let line = r#"{"level":"info","ts":1704067200.5,"logger":"http.log.access.log0","msg":"handled request","request":{"remote_ip":"127.0.0.1","method":"POST","host":"shop.test","uri":"/cart?item=1"},"duration":0.0125,"status":201}"#;
+
+        let record = parse_record("shop.test", line).unwrap();
+        assert_eq!(record.method, "POST");
+        assert_eq!(record.path, "/cart?item=1");
+        assert_eq!(record.status, 201);
+        assert_eq!(record.duration_ms, 12.5);
+        assert_eq!(record.remote_ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!(record.at.timestamp_millis(), 1704067200500);
+
+        let error = r#"{"level":"error","ts":1704067200.5,"logger":"http.log.error","msg":"dial tcp: connection refused","request":{"method":"GET","host":"shop.test","uri":"/"},"duration":0.001,"status":502}"#;
+        assert_eq!(parse_record("shop.test", error), None);
+    }
+
+    #[test]
+    fn test_request_log_caps_and_orders() {
+        let mut log = RequestLog::default();
+        for i in 0..(MAX_REQUESTS_PER_DOMAIN as i64 + 10) {
+            log.record(request("shop.test", i));
+        }
+        log.record(request("api.test", 5_000));
+
+        let shop = log.recent(Some("shop.test"), usize::MAX);
+        assert_eq!(shop.len(), MAX_REQUESTS_PER_DOMAIN);
+        assert!(shop[0].at > shop[1].at);
+
+        let all = log.recent(None, 2);
+        assert_eq!(all[0].domain, "api.test");
+        assert_eq!(all.len(), 2);
+
+        log.retain(&HashSet::from(["api.test".to_string()]));
+        assert!(log.recent(Some("shop.test"), 10).is_empty());
+    }
+}
@@ -21,6 +21,7 @@ use tauri::{AppHandle, Emitter, Listener, Manager};
 use crate::commands::AppState;
 use crate::error::LockExt;
 use crate::lock;
+use crate::proxy_watchdog::ProxyHealth;
 
 const TRAY_ID: &str = "burd-tray";
 
@@ -106,15 +107,19 @@ fn update_tooltip(tray: &TrayIcon<tauri::Wry>, app: &AppHandle) {
     let _ = tray.set_tooltip(Some(label));
 }
 
-/// Aggregate health: red if proxy poller returned false, amber if unknown,
-/// green if healthy. Instance failures roll up to amber (non-fatal).
+/// Aggregate health: red if the proxy watchdog found it down, wedged or
+/// hijacked, amber while unknown or restarting, green if healthy. Instance
+/// failures roll up to amber (non-fatal).
 fn aggregate_health(app: &AppHandle) -> Health {
     let state = app.state::<AppState>();
-    let proxy = state.proxy_healthy.load(Ordering::Relaxed);
-    match proxy {
-        1 => Health::Green,
-        2 => Health::Red,
-        _ => Health::Amber,
+    proxy_health(&state)
+}
+
+fn proxy_health(state: &AppState) -> Health {
+    match ProxyHealth::from_u8(state.proxy_healthy.load(Ordering::Relaxed)) {
+        ProxyHealth::Healthy => Health::Green,
+        ProxyHealth::Unknown | ProxyHealth::Restarting => Health::Amber,
+        ProxyHealth::Hijacked | ProxyHealth::Unresponsive | ProxyHealth::Down => Health::Red,
     }
 }
 
@@ -156,12 +161,7 @@ fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
 
 fn append_infrastructure(app: &AppHandle, menu: &Menu<tauri::Wry>) -> tauri::Result<()> {
     let state = app.state::<AppState>();
-    let proxy_code = state.proxy_healthy.load(Ordering::Relaxed);
-    let proxy_dot = match proxy_code {
-        1 => Health::Green.dot(),
-        2 => Health::Red.dot(),
-        _ => Health::Amber.dot(),
-    };
+    let proxy_dot = proxy_health(&state).dot();
     let proxy = MenuItem::with_id(
         app,
         "svc:proxy",
@@ -387,11 +387,15 @@ fn dispatch_site_action(app: &AppHandle, uuid: String, action: String) {
     tauri::async_runtime::spawn(async move {
         let state = app.state::<AppState>();
         let result: Result<(), String> = match action.as_str() {
-            "start" => {
-                crate::commands::start_instance(uuid.clone(), state.clone(), app.clone())
-                    .await
-                    .map(|_| ())
-            }
+            "start" => crate::commands::start_instance(
+                uuid.clone(),
+                None,
+                None,
+                state.clone(),
+                app.clone(),
+            )
+            .await
+            .map(|_| ()),
             "stop" => {
                 crate::commands::stop_instance(uuid.clone(), state.clone(), app.clone()).await
             }
@@ -0,0 +1,389 @@
+//! Trust stores outside the macOS keychain
+//!
+//! `trust_caddy_ca` adds the root CA to the keychain, which covers Safari,
+//! Chrome and most native tools. Firefox keeps its own certificate database
+//! per profile, and curl, Node and Python read PEM files. This module imports
+//! the CA into Firefox profiles and exports PEM files for everything else.
+
+use crate::config::get_app_dir;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Nickname the CA is stored under in Firefox's certificate database
+const NSS_NICKNAME: &str = "Burd Local CA";
+
+/// Firefox pref that makes it trust roots from the macOS keychain
+const KEYCHAIN_ROOTS_PREF: &str = "security.enterprise_roots.enabled";
+
+/// Where Homebrew's `nss` formula installs `certutil`
+const CERTUTIL_PATHS: &[&str] = &[
+    "/opt/homebrew/opt/nss/bin/certutil",
+    "/usr/local/opt/nss/bin/certutil",
+];
+
+/// How a Firefox profile trusts the Burd CA
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirefoxTrust {
+    /// The CA is in the profile's own certificate database
+    Imported,
+    /// The profile trusts roots from the keychain, so keychain trust applies
+    KeychainRoots,
+    NotTrusted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FirefoxProfile {
+    pub name: String,
+    pub path: PathBuf,
+    pub trust: FirefoxTrust,
+}
+
+/// Files written by `export_pem_files`
+#[derive(Debug, Clone, Serialize)]
+pub struct CaExport {
+    /// The Burd root CA on its own
+    pub ca_pem: PathBuf,
+    /// System roots plus the Burd CA, for tools that replace their trust
+    /// store instead of adding to it. None if the system roots couldn't be read.
+    pub bundle_pem: Option<PathBuf>,
+    /// Setup instructions for curl, Node, Python and git
+    pub readme: PathBuf,
+}
+
+fn firefox_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|p| p.join("Firefox"))
+}
+
+/// Parse Firefox's profiles.ini into (name, directory) pairs
+fn parse_profiles_ini(contents: &str, firefox_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut profiles = Vec::new();
+    let mut section: Option<(Option<String>, Option<String>, bool)> = None;
+
+    let mut finish = |section: Option<(Option<String>, Option<String>, bool)>| {
+        if let Some((name, Some(path), relative)) = section {
+            let dir = if relative {
+                firefox_dir.join(&path)
+            } else {
+                PathBuf::from(&path)
+            };
+            profiles.push((name.unwrap_or(path), dir));
+        }
+    };
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            finish(section.take());
+            if line.starts_with("[Profile") {
+                section = Some((None, None, true));
+            }
+            continue;
+        }
+        let (Some(current), Some((key, value))) = (section.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => current.0 = Some(value.trim().to_string()),
+            "Path" => current.1 = Some(value.trim().to_string()),
+            "IsRelative" => current.2 = value.trim() != "0",
+            _ => {}
+        }
+    }
+    finish(section);
+
+    profiles
+}
+
+/// Find `certutil` from Mozilla NSS (`brew install nss`)
+fn find_certutil() -> Option<PathBuf> {
+    if let Some(path) = CERTUTIL_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|p| p.exists())
+    {
+        return Some(path);
+    }
+    let output = Command::new("which").arg("certutil").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+fn nss_db(profile: &Path) -> String {
+    format!("sql:{}", profile.display())
+}
+
+/// Set a pref in user.js contents, replacing any existing value
+fn set_user_pref(contents: &str, key: &str, value: &str) -> String {
+    let prefix = format!("user_pref(\"{}\",", key);
+    let mut lines: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.trim_start().starts_with(&prefix))
+        .collect();
+    let pref = format!("user_pref(\"{}\", {});", key, value);
+    lines.push(&pref);
+    lines.join("\n") + "\n"
+}
+
+fn has_user_pref(contents: &str, key: &str, value: &str) -> bool {
+    let pref = format!("user_pref(\"{}\", {});", key, value);
+    contents.lines().any(|line| line.trim() == pref)
+}
+
+fn profile_trust(profile: &Path, certutil: Option<&Path>) -> FirefoxTrust {
+    if let Some(certutil) = certutil {
+        let listed = Command::new(certutil)
+            .args(["-L", "-d", &nss_db(profile), "-n", NSS_NICKNAME])
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if listed {
+            return FirefoxTrust::Imported;
+        }
+    }
+    let user_js = fs::read_to_string(profile.join("user.js")).unwrap_or_default();
+    if has_user_pref(&user_js, KEYCHAIN_ROOTS_PREF, "true") {
+        FirefoxTrust::KeychainRoots
+    } else {
+        FirefoxTrust::NotTrusted
+    }
+}
+
+/// List Firefox profiles and whether each trusts the Burd CA
+pub fn find_firefox_profiles() -> Vec<FirefoxProfile> {
+    let Some(dir) = firefox_dir() else {
+        return Vec::new();
+    };
+    let Ok(contents) = fs::read_to_string(dir.join("profiles.ini")) else {
+        return Vec::new();
+    };
+    let certutil = find_certutil();
+
+    parse_profiles_ini(&contents, &dir)
+        .into_iter()
+        .filter(|(_, path)| path.is_dir())
+        .map(|(name, path)| FirefoxProfile {
+            trust: profile_trust(&path, certutil.as_deref()),
+            name,
+            path,
+        })
+        .collect()
+}
+
+/// Make every Firefox profile trust the Burd CA
+///
+/// Imports the CA into each profile's certificate database when NSS
+/// `certutil` is installed. Otherwise the profile is told to trust roots from
+/// the keychain, which works once the CA is trusted there. Returns the
+/// profiles with their updated status.
+pub fn trust_in_firefox(ca_path: &Path) -> Result<Vec<FirefoxProfile>, String> {
+    let profiles = find_firefox_profiles();
+    if profiles.is_empty() {
+        return Err("No Firefox profiles found".to_string());
+    }
+    let ca = ca_path
+        .to_str()
+        .ok_or_else(|| "Invalid CA path encoding".to_string())?;
+    let certutil = find_certutil();
+
+    for profile in profiles
+        .iter()
+        .filter(|p| p.trust == FirefoxTrust::NotTrusted)
+    {
+        match &certutil {
+            Some(certutil) => {
+                let output = Command::new(certutil)
+                    .args(["-A", "-d", &nss_db(&profile.path), "-n", NSS_NICKNAME])
+                    .args(["-t", "C,,", "-i", ca])
+                    .output()
+                    .map_err(|e| format!("Failed to run certutil: {}", e))?;
+                if !output.status.success() {
+                    return Err(format!(
+                        "Failed to import the CA into Firefox profile '{}': {}",
+                        profile.name,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+            }
+            None => {
+                let user_js = profile.path.join("user.js");
+                let contents = fs::read_to_string(&user_js).unwrap_or_default();
+                fs::write(
+                    &user_js,
+                    set_user_pref(&contents, KEYCHAIN_ROOTS_PREF, "true"),
+                )
+                .map_err(|e| {
+                    format!("Failed to update Firefox profile '{}': {}", profile.name, e)
+                })?;
+            }
+        }
+    }
+
+    Ok(find_firefox_profiles())
+}
+
+/// Default directory for exported CA files
+pub fn default_export_dir() -> Result<PathBuf, String> {
+    get_app_dir().map(|p| p.join("ca"))
+}
+
+/// PEM certificates from the macOS system root store
+fn system_roots_pem() -> Option<String> {
+    let output = Command::new("security")
+        .args([
+            "find-certificate",
+            "-a",
+            "-p",
+            "/System/Library/Keychains/SystemRootCertificates.keychain",
+        ])
+        .output()
+        .ok()?;
+    let pem = String::from_utf8_lossy(&output.stdout).into_owned();
+    (output.status.success() && pem.contains("BEGIN CERTIFICATE")).then_some(pem)
+}
+
+/// Setup instructions for tools that read PEM files
+pub fn setup_instructions(ca_pem: &Path, bundle_pem: Option<&Path>) -> String {
+    let ca = ca_pem.display();
+    let bundle = bundle_pem.unwrap_or(ca_pem).display();
+    let mut text = format!(
+        "Burd root CA\n\
+         ============\n\
+         \n\
+         {ca}\n  \
+           The root certificate that signs every HTTPS domain Burd serves.\n"
+    );
+    match bundle_pem {
+        Some(_) => text.push_str(&format!(
+            "{bundle}\n  \
+               The system roots plus the Burd CA, for tools that replace their\n  \
+               trust store instead of adding to it.\n"
+        )),
+        None => text.push_str(
+            "\nThe system roots couldn't be read, so no combined bundle was written.\n\
+             The settings below that replace a tool's trust store will only trust\n\
+             Burd domains.\n",
+        ),
+    }
+    text.push_str(&format!(
+        "\n\
+         Node.js (adds to the built-in roots):\n  \
+           export NODE_EXTRA_CA_CERTS=\"{ca}\"\n\
+         \n\
+         curl:\n  \
+           curl --cacert \"{ca}\" https://myapp.burd\n  \
+           export CURL_CA_BUNDLE=\"{bundle}\"\n\
+         \n\
+         Python (requests, httpx) and other OpenSSL-based tools:\n  \
+           export REQUESTS_CA_BUNDLE=\"{bundle}\"\n  \
+           export SSL_CERT_FILE=\"{bundle}\"\n\
+         \n\
+         Git:\n  \
+           git config --global http.sslCAInfo \"{bundle}\"\n\
+         \n\
+         Add the export lines to ~/.zshrc to keep them in new shells.\n"
+    ));
+    text
+}
+
+/// Write the CA, a combined bundle and setup instructions to `dir`
+pub fn export_pem_files(ca_path: &Path, dir: &Path) -> Result<CaExport, String> {
+    let ca = fs::read_to_string(ca_path)
+        .map_err(|e| format!("Failed to read the root CA at {}: {}", ca_path.display(), e))?;
+    if !ca.contains("BEGIN CERTIFICATE") {
+        return Err(format!("{} is not a PEM certificate", ca_path.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let ca_pem = dir.join("burd-root-ca.pem");
+    fs::write(&ca_pem, &ca).map_err(|e| format!("Failed to write CA: {}", e))?;
+
+    let bundle_pem = match system_roots_pem() {
+        Some(roots) => {
+            let path = dir.join("ca-bundle.pem");
+            fs::write(&path, format!("{}\n{}", roots.trim_end(), ca))
+                .map_err(|e| format!("Failed to write CA bundle: {}", e))?;
+            Some(path)
+        }
+        None => None,
+    };
+
+    let readme = dir.join("README.txt");
+    fs::write(&readme, setup_instructions(&ca_pem, bundle_pem.as_deref()))
+        .map_err(|e| format!("Failed to write instructions: {}", e))?;
+
+    Ok(CaExport {
+        ca_pem,
+        bundle_pem,
+        readme,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_profiles_ini() {
+        let ini = "[General]\nStartWithLastProfile=1\n\n\
+                   [Profile1]\nName=work\nIsRelative=0\nPath=/Users/me/ff-work\n\n\
+                   [Profile0]\nName=default-release\nIsRelative=1\nPath=Profiles/abcd.default-release\nDefault=1\n\n\
+                   [Install4F96D1932A9F858E]\nDefault=Profiles/abcd.default-release\n";
+        let profiles = parse_profiles_ini(ini, Path::new("/ff"));
+        assert_eq!(
+            profiles,
+            vec![
+                ("work".to_string(), PathBuf::from("/Users/me/ff-work")),
+                (
+                    "default-release".to_string(),
+                    PathBuf::from("/ff/Profiles/abcd.default-release")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_user_pref_replaces_existing() {
+        let contents = "user_pref(\"browser.startup.page\", 3);\n\
+                        user_pref(\"security.enterprise_roots.enabled\", false);\n";
+        let updated = set_user_pref(contents, KEYCHAIN_ROOTS_PREF, "true");
+        assert_eq!(
+            updated,
+            "user_pref(\"browser.startup.page\", 3);\n\
+             user_pref(\"security.enterprise_roots.enabled\", true);\n"
+        );
+        assert!(has_user_pref(&updated, KEYCHAIN_ROOTS_PREF, "true"));
+        assert!(!has_user_pref(contents, KEYCHAIN_ROOTS_PREF, "true"));
+        assert_eq!(
+            set_user_pref(&updated, KEYCHAIN_ROOTS_PREF, "true"),
+            updated
+        );
+    }
+
+    #[test]
+    fn test_export_pem_files() {
+        let temp = TempDir::new().unwrap();
+        let ca_path = temp.path().join("root.crt");
+        let pem = "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n";
+        fs::write(&ca_path, pem).unwrap();
+
+        let out = temp.path().join("export");
+        let export = export_pem_files(&ca_path, &out).unwrap();
+        assert_eq!(fs::read_to_string(&export.ca_pem).unwrap(), pem);
+        if let Some(bundle) = &export.bundle_pem {
+            assert!(fs::read_to_string(bundle).unwrap().ends_with(pem));
+        }
+        let readme = fs::read_to_string(&export.readme).unwrap();
+        assert!(readme.contains(&format!(
+            "NODE_EXTRA_CA_CERTS=\"{}\"",
+            export.ca_pem.display()
+        )));
+
+        fs::write(&ca_path, "not a cert").unwrap();
+        assert!(export_pem_files(&ca_path, &out).is_err());
+    }
+}
@@ -60,6 +60,80 @@ impl FrpServer {
     }
 }
 
+/// A response header frps adds to a tunnel's responses, so half-finished
+/// client sites shared over a tunnel don't get indexed or embedded
+///
+/// Only `http` tunnels can carry them; frps passes `https` traffic through
+/// without reading it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderPreset {
+    /// `X-Frame-Options: DENY`
+    NoFraming,
+    /// A same-origin `Content-Security-Policy-Report-Only`, which reports
+    /// violations in the browser console without blocking anything
+    CspReportOnly,
+    /// `X-Robots-Tag: noindex, nofollow`
+    NoIndex,
+}
+
+impl HeaderPreset {
+    pub const ALL: [HeaderPreset; 3] = [
+        HeaderPreset::NoFraming,
+        HeaderPreset::CspReportOnly,
+        HeaderPreset::NoIndex,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderPreset::NoFraming => "no_framing",
+            HeaderPreset::CspReportOnly => "csp_report_only",
+            HeaderPreset::NoIndex => "no_index",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let normalized = s.trim().to_ascii_lowercase().replace('-', "_");
+        match normalized.as_str() {
+            "noindex" => Ok(HeaderPreset::NoIndex),
+            "csp" => Ok(HeaderPreset::CspReportOnly),
+            other => Self::ALL
+                .into_iter()
+                .find(|p| p.as_str() == other)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown header preset '{}'. Use no-framing, csp-report-only or noindex",
+                        s.trim()
+                    )
+                }),
+        }
+    }
+
+    /// Parse a list of presets, dropping duplicates
+    pub fn parse_all<S: AsRef<str>>(presets: &[S]) -> Result<Vec<Self>, String> {
+        let mut parsed = Vec::new();
+        for preset in presets {
+            let preset = Self::parse(preset.as_ref())?;
+            if !parsed.contains(&preset) {
+                parsed.push(preset);
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Header name and value
+    pub fn header(&self) -> (&'static str, &'static str) {
+        match self {
+            HeaderPreset::NoFraming => ("X-Frame-Options", "DENY"),
+            HeaderPreset::CspReportOnly => (
+                "Content-Security-Policy-Report-Only",
+                "default-src 'self'; frame-ancestors 'none'",
+            ),
+            HeaderPreset::NoIndex => ("X-Robots-Tag", "noindex, nofollow"),
+        }
+    }
+}
+
 /// Tunnel target - what to expose
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "value")]
@@ -144,6 +218,9 @@ pub struct Tunnel {
     /// Whether this tunnel auto-starts when Burd launches
     #[serde(default)]
     pub auto_start: bool,
+    /// Response headers added to the tunnel's responses (`http` tunnels only)
+    #[serde(default)]
+    pub header_presets: Vec<HeaderPreset>,
     /// When this tunnel was created
     pub created_at: DateTime<Utc>,
 }
@@ -167,6 +244,7 @@ impl Tunnel {
             subdomain,
             protocol: default_protocol(),
             auto_start: false,
+            header_presets: Vec::new(),
             created_at: Utc::now(),
         }
     }
@@ -368,6 +446,15 @@ pub fn generate_frpc_config(
         config.push_str("localIP = \"127.0.0.1\"\n");
         config.push_str(&format!("localPort = {}\n", port));
         config.push_str(&format!("subdomain = \"{}\"\n", subdomain));
+        if tunnel.protocol == "http" {
+            for preset in &tunnel.header_presets {
+                let (name, value) = preset.header();
+                config.push_str(&format!(
+                    "responseHeaders.set.\"{}\" = \"{}\"\n",
+                    name, value
+                ));
+            }
+        }
         config.push('\n');
     }
 
@@ -777,5 +864,43 @@ mod tests {
         assert!(config.contains("[[proxies]]"));
         assert!(config.contains("localPort = 8080"));
         assert!(config.contains("subdomain = \"my-api\""));
+        assert!(!config.contains("responseHeaders"));
+    }
+
+    #[test]
+    fn test_frpc_config_header_presets() {
+        let server = FrpServer::new(
+            "Test".to_string(),
+            "tunnel.example.com".to_string(),
+            7000,
+            "secret-token".to_string(),
+            "tunnel.example.com".to_string(),
+        );
+        let mut tunnel = Tunnel::new(
+            "Client Site".to_string(),
+            server.id,
+            TunnelTarget::Port(8080),
+            SubdomainConfig::Custom {
+                subdomain: "client".to_string(),
+            },
+        );
+        tunnel.header_presets =
+            HeaderPreset::parse_all(&["noindex", "no-framing", "NoIndex"]).unwrap();
+        assert_eq!(
+            tunnel.header_presets,
+            vec![HeaderPreset::NoIndex, HeaderPreset::NoFraming]
+        );
+
+        let config = generate_frpc_config(&server, std::slice::from_ref(&tunnel), &[], None);
+        assert!(config.contains("responseHeaders.set.\"X-Robots-Tag\" = \"noindex, nofollow\""));
+        assert!(config.contains("responseHeaders.set.\"X-Frame-Options\" = \"DENY\""));
+        assert!(!config.contains("Content-Security-Policy"));
+
+        // frps can't add headers to https tunnels
+        tunnel.protocol = "https".to_string();
+        let config = generate_frpc_config(&server, &[tunnel], &[], None);
+        assert!(!config.contains("responseHeaders"));
+
+        assert!(HeaderPreset::parse("hsts").is_err());
     }
 }
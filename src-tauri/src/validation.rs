@@ -425,6 +425,116 @@ pub fn validate_version(version: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+// ============================================================================
+// Tag Validation
+// ============================================================================
+
+/// Regex for instance tags (e.g. "client:acme", "env:test")
+static TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z0-9][a-z0-9._:-]*$").unwrap());
+
+/// Validate an instance tag
+///
+/// Tags must:
+/// - Be 1-64 characters long
+/// - Start with a lowercase alphanumeric character
+/// - Contain only lowercase alphanumeric characters, dots, underscores, colons, and hyphens
+///
+/// `key:value` (e.g. "client:acme") is a convention, not a requirement.
+///
+/// # Example
+/// ```
+/// use burd_lib::validation::validate_tag;
+///
+/// assert!(validate_tag("client:acme").is_ok());
+/// assert!(validate_tag("legacy").is_ok());
+/// assert!(validate_tag("Client:Acme").is_err()); // uppercase
+/// assert!(validate_tag("env test").is_err()); // contains space
+/// ```
+pub fn validate_tag(tag: &str) -> Result<(), AppError> {
+    if tag.is_empty() {
+        return Err(AppError::invalid_config("Tag cannot be empty"));
+    }
+
+    if tag.len() > 64 {
+        return Err(AppError::invalid_config(format!(
+            "Tag '{}' cannot exceed 64 characters",
+            tag
+        )));
+    }
+
+    if !TAG_REGEX.is_match(tag) {
+        return Err(AppError::invalid_config(format!(
+            "Invalid tag '{}'. Tags may contain lowercase letters, digits, '.', '_', ':' and '-', and must start with a letter or digit",
+            tag
+        )));
+    }
+
+    Ok(())
+}
+
+/// Trim, lowercase, validate, and de-duplicate a list of tags (order preserved)
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        validate_tag(&tag)?;
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
+}
+
+// ============================================================================
+// Start Argument Validation
+// ============================================================================
+
+/// Maximum number of extra start arguments per instance
+const MAX_EXTRA_ARGS: usize = 32;
+
+/// Maximum length of one extra start argument
+const MAX_EXTRA_ARG_LEN: usize = 1024;
+
+/// Validate extra start arguments for an instance
+///
+/// Arguments are passed to the service binary as-is, without a shell, so
+/// quoting isn't needed. An empty argument is allowed (e.g. Redis `--save ""`),
+/// but arguments may not contain control characters such as newlines.
+///
+/// # Example
+/// ```
+/// use burd_lib::validation::validate_extra_args;
+///
+/// assert!(validate_extra_args(&["--appendonly".to_string(), "yes".to_string()]).is_ok());
+/// assert!(validate_extra_args(&["--a\n--b".to_string()]).is_err());
+/// ```
+pub fn validate_extra_args(args: &[String]) -> Result<(), AppError> {
+    if args.len() > MAX_EXTRA_ARGS {
+        return Err(AppError::invalid_config(format!(
+            "At most {} extra arguments are allowed",
+            MAX_EXTRA_ARGS
+        )));
+    }
+
+    for arg in args {
+        if arg.len() > MAX_EXTRA_ARG_LEN {
+            return Err(AppError::invalid_config(format!(
+                "Argument '{}...' cannot exceed {} characters",
+                arg.chars().take(20).collect::<String>(),
+                MAX_EXTRA_ARG_LEN
+            )));
+        }
+        if arg.chars().any(char::is_control) {
+            return Err(AppError::invalid_config(format!(
+                "Argument '{}' cannot contain control characters",
+                arg.escape_default()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,4 +647,46 @@ mod tests {
         assert!(validate_version("invalid").is_err());
         assert!(validate_version("1.2.3.4").is_err());
     }
+
+    // Tag validation tests
+    #[test]
+    fn test_validate_tag() {
+        assert!(validate_tag("client:acme").is_ok());
+        assert!(validate_tag("env:test").is_ok());
+        assert!(validate_tag("v1.2_beta-3").is_ok());
+
+        assert!(validate_tag("").is_err());
+        assert!(validate_tag(":acme").is_err());
+        assert!(validate_tag("Client").is_err());
+        assert!(validate_tag("env test").is_err());
+        assert!(validate_tag(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_validate_extra_args() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+
+        assert!(validate_extra_args(&[]).is_ok());
+        assert!(validate_extra_args(&args(&["--appendonly", "yes", "--save", ""])).is_ok());
+        assert!(validate_extra_args(&args(&["--maxmemory 100mb"])).is_ok());
+
+        assert!(validate_extra_args(&args(&["--a\n--b"])).is_err());
+        assert!(validate_extra_args(&args(&["tab\there"])).is_err());
+        assert!(validate_extra_args(&[String::from("x").repeat(1025)]).is_err());
+        assert!(validate_extra_args(&args(&["-v"; 33])).is_err());
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = vec![
+            " Client:Acme ".to_string(),
+            "env:test".to_string(),
+            "client:acme".to_string(),
+        ];
+        assert_eq!(
+            normalize_tags(&tags).unwrap(),
+            vec!["client:acme".to_string(), "env:test".to_string()]
+        );
+        assert!(normalize_tags(&["bad tag".to_string()]).is_err());
+    }
 }
@@ -0,0 +1,383 @@
+//! Project Secrets Vault
+//!
+//! Per-project environment variables (`burd env set STRIPE_KEY=...`) that
+//! never touch the project's .env. Values are stored in the system keychain
+//! (the login Keychain on macOS, the Secret Service via `secret-tool` on
+//! Linux); the config only records which keys a project has.
+//!
+//! Secrets are injected at runtime into the FrankenPHP instance serving the
+//! project, its queue workers, and the `burd composer` and `burd wp`
+//! wrappers. Real environment variables take precedence over .env in
+//! Laravel, Symfony and most dotenv loaders, so a secret also overrides a
+//! placeholder left in .env.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::platform::Os;
+
+/// Keychain service the secrets are stored under
+const KEYCHAIN_SERVICE: &str = "com.burd.vault";
+
+/// Files marking a project's root directory
+const PROJECT_MARKERS: &[&str] = &["composer.json", "package.json", ".env"];
+
+/// A project's secret names, stored in the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultProject {
+    /// Project root directory
+    pub path: String,
+    /// Names of its secrets, sorted
+    pub keys: Vec<String>,
+}
+
+/// The project `dir` belongs to: the nearest directory with a composer.json,
+/// package.json or .env, or `dir` itself
+pub fn project_root(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|d| PROJECT_MARKERS.iter().any(|m| d.join(m).is_file()))
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// Check a secret name is a valid environment variable name
+pub fn validate_key(key: &str) -> Result<(), String> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid name '{}'. Use letters, digits and underscores, not starting with a digit",
+            key
+        ))
+    }
+}
+
+/// Split `KEY=VALUE`
+pub fn parse_assignment(assignment: &str) -> Result<(String, String), String> {
+    let (key, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| format!("Expected KEY=VALUE, got '{}'", assignment))?;
+    let key = key.trim();
+    validate_key(key)?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// The vault project `path` is inside, preferring the deepest
+///
+/// A document root like `public/` matches the project above it.
+pub fn find_project<'a>(vault: &'a [VaultProject], path: &Path) -> Option<&'a VaultProject> {
+    vault
+        .iter()
+        .filter(|p| path.starts_with(&p.path))
+        .max_by_key(|p| p.path.len())
+}
+
+/// Keychain account of a project's secret
+fn account(project: &Path, key: &str) -> String {
+    format!("{}:{}", project.display(), key)
+}
+
+fn run(cmd: &mut Command, action: &str) -> Result<String, String> {
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to {}: {}", action, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to {}: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Run `cmd` with `input` on its stdin, keeping it out of `ps`; returns
+/// what it wrote to stderr
+fn run_with_stdin(cmd: &mut Command, input: &str, action: &str) -> Result<String, String> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to {}: {}", action, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("Failed to {}: {}", action, e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to {}: {}", action, e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if !output.status.success() {
+        return Err(format!("Failed to {}: {}", action, stderr));
+    }
+    Ok(stderr)
+}
+
+/// Quote an argument for a `security -i` command line
+fn security_quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The `security -i` command storing `value` under `account`
+///
+/// `add-generic-password -w` without a value asks for it on the terminal,
+/// not on stdin, so the whole command goes to `security -i` on stdin with
+/// the value hex-encoded (`-X`).
+fn keychain_store_command(account: &str, value: &str) -> String {
+    let hex: String = value.bytes().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "add-generic-password -U -s {} -a {} -X {}\n",
+        security_quote(KEYCHAIN_SERVICE),
+        security_quote(account),
+        security_quote(&hex)
+    )
+}
+
+/// Store a secret in the keychain, replacing any previous value
+fn keychain_store(account: &str, value: &str) -> Result<(), String> {
+    match Os::host() {
+        Os::MacOs => {
+            // `find-generic-password -w` prints such values as hex
+            if value.contains(['\n', '\r']) {
+                return Err("Keychain secrets can't span several lines".to_string());
+            }
+            let action = "store the secret in the Keychain";
+            // `security -i` exits successfully even when a command fails
+            let stderr = run_with_stdin(
+                Command::new("security").arg("-i"),
+                &keychain_store_command(account, value),
+                action,
+            )?;
+            if stderr.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("Failed to {}: {}", action, stderr))
+            }
+        }
+        // secret-tool reads the value from stdin
+        Os::Linux => run_with_stdin(
+            Command::new("secret-tool")
+                .args(["store", "--label", &format!("Burd: {}", account)])
+                .args(["service", KEYCHAIN_SERVICE, "account", account]),
+            value,
+            "store the secret",
+        )
+        .map(|_| ()),
+    }
+}
+
+/// Read a secret from the keychain
+fn keychain_read(account: &str) -> Result<String, String> {
+    let value = match Os::host() {
+        Os::MacOs => run(
+            Command::new("security").args([
+                "find-generic-password",
+                "-s",
+                KEYCHAIN_SERVICE,
+                "-a",
+                account,
+                "-w",
+            ]),
+            "read the secret from the Keychain",
+        )?,
+        Os::Linux => run(
+            Command::new("secret-tool").args([
+                "lookup",
+                "service",
+                KEYCHAIN_SERVICE,
+                "account",
+                account,
+            ]),
+            "read the secret",
+        )?,
+    };
+    // `security -w` adds a trailing newline
+    Ok(value.strip_suffix('\n').unwrap_or(&value).to_string())
+}
+
+/// Remove a secret from the keychain; a missing one is fine
+fn keychain_delete(account: &str) {
+    let mut cmd = match Os::host() {
+        Os::MacOs => {
+            let mut cmd = Command::new("security");
+            cmd.args([
+                "delete-generic-password",
+                "-s",
+                KEYCHAIN_SERVICE,
+                "-a",
+                account,
+            ]);
+            cmd
+        }
+        Os::Linux => {
+            let mut cmd = Command::new("secret-tool");
+            cmd.args(["clear", "service", KEYCHAIN_SERVICE, "account", account]);
+            cmd
+        }
+    };
+    let _ = cmd.output();
+}
+
+/// Store a project's secret and record its name in `vault`
+pub fn set(
+    vault: &mut Vec<VaultProject>,
+    project: &Path,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    validate_key(key)?;
+    keychain_store(&account(project, key), value)?;
+
+    let path = project.to_string_lossy().to_string();
+    let index = match vault.iter().position(|p| p.path == path) {
+        Some(index) => index,
+        None => {
+            vault.push(VaultProject {
+                path,
+                keys: Vec::new(),
+            });
+            vault.len() - 1
+        }
+    };
+    let keys = &mut vault[index].keys;
+    if !keys.iter().any(|k| k == key) {
+        keys.push(key.to_string());
+        keys.sort();
+    }
+    Ok(())
+}
+
+/// Remove a project's secret; returns whether it had one by that name
+pub fn unset(vault: &mut Vec<VaultProject>, project: &Path, key: &str) -> bool {
+    let path = project.to_string_lossy();
+    let Some(entry) = vault.iter_mut().find(|p| p.path == path) else {
+        return false;
+    };
+    let before = entry.keys.len();
+    entry.keys.retain(|k| k != key);
+    let removed = entry.keys.len() != before;
+
+    keychain_delete(&account(project, key));
+    vault.retain(|p| !p.keys.is_empty());
+    removed
+}
+
+/// Read one secret of a project
+pub fn get(project: &Path, key: &str) -> Result<String, String> {
+    keychain_read(&account(project, key))
+}
+
+/// Secrets of the project `dir` is in, to set as environment variables
+///
+/// A secret that can't be read (e.g. removed from the keychain by hand) is
+/// skipped with a warning.
+pub fn secrets_for(vault: &[VaultProject], dir: &Path) -> Vec<(String, String)> {
+    let Some(project) = find_project(vault, dir) else {
+        return Vec::new();
+    };
+    let root = Path::new(&project.path);
+    project
+        .keys
+        .iter()
+        .filter_map(|key| match get(root, key) {
+            Ok(value) => Some((key.clone(), value)),
+            Err(e) => {
+                eprintln!("Vault: skipping {}: {}", key, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Add the secrets of the project `dir` is in to `cmd`'s environment
+pub fn inject(cmd: &mut Command, vault: &[VaultProject], dir: &Path) {
+    for (key, value) in secrets_for(vault, dir) {
+        cmd.env(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("STRIPE_KEY").is_ok());
+        assert!(validate_key("_private").is_ok());
+        assert!(validate_key("1PASSWORD").is_err());
+        assert!(validate_key("API-KEY").is_err());
+        assert!(validate_key("").is_err());
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(
+            parse_assignment("STRIPE_KEY=sk_test_a=b"),
+            Ok(("STRIPE_KEY".to_string(), "sk_test_a=b".to_string()))
+        );
+        assert_eq!(
+            parse_assignment("EMPTY="),
+            Ok(("EMPTY".to_string(), String::new()))
+        );
+        assert!(parse_assignment("NO_VALUE").is_err());
+        assert!(parse_assignment("BAD KEY=1").is_err());
+    }
+
+    #[test]
+    fn test_find_project_prefers_deepest() {
+        let vault = vec![
+            VaultProject {
+                path: "/Sites".to_string(),
+                keys: vec!["A".to_string()],
+            },
+            VaultProject {
+                path: "/Sites/shop".to_string(),
+                keys: vec!["B".to_string()],
+            },
+        ];
+
+        let shop = find_project(&vault, Path::new("/Sites/shop/public")).unwrap();
+        assert_eq!(shop.path, "/Sites/shop");
+        assert_eq!(
+            find_project(&vault, Path::new("/Sites/blog")).unwrap().path,
+            "/Sites"
+        );
+        // Only whole path components match
+        assert_eq!(
+            find_project(&vault, Path::new("/Sites/shopfront"))
+                .unwrap()
+                .path,
+            "/Sites"
+        );
+        assert!(find_project(&vault, Path::new("/tmp")).is_none());
+    }
+
+    #[test]
+    fn test_keychain_store_command() {
+        assert_eq!(
+            keychain_store_command("/Sites/my \"shop\":KEY", "a b\\"),
+            "add-generic-password -U -s \"com.burd.vault\" -a \"/Sites/my \\\"shop\\\":KEY\" -X \"6120625c\"\n"
+        );
+        assert!(keychain_store_command("/Sites/shop:KEY", "").ends_with("-X \"\"\n"));
+    }
+
+    #[test]
+    fn test_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let public = dir.path().join("public");
+        std::fs::create_dir(&public).unwrap();
+        assert_eq!(project_root(&public), public);
+
+        std::fs::write(dir.path().join("composer.json"), "{}").unwrap();
+        assert_eq!(project_root(&public), dir.path());
+    }
+}
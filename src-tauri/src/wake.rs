@@ -0,0 +1,279 @@
+//! Wake-on-access
+//!
+//! Instances flagged `wake_on_access` keep their domains routed while they're
+//! stopped, so rarely used sites don't have to run all day. A request that
+//! finds one of them down starts it in the background and gets a page that
+//! reloads until the site answers, instead of a 502.
+//!
+//! The in-memory proxy calls `wake_domain` when it can't reach a backend.
+//! Caddy sends the 502s of these sites to the API's `/wake/{domain_id}`,
+//! which does the same.
+
+use crate::caddy::ERROR_PAGE_STYLES;
+use crate::commands::AppState;
+use crate::config::{Config, Domain, DomainTarget, Instance};
+use crate::start_page::escape_html;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use uuid::Uuid;
+
+/// Seconds between reloads of the starting page
+const RELOAD_SECS: u64 = 2;
+
+/// Instances being started, and why the last start failed for instances
+/// whose visitors haven't seen the error yet
+#[derive(Default)]
+struct Wakes {
+    starting: HashSet<Uuid>,
+    failed: HashMap<Uuid, String>,
+}
+
+fn wakes() -> &'static Mutex<Wakes> {
+    static WAKES: OnceLock<Mutex<Wakes>> = OnceLock::new();
+    WAKES.get_or_init(Default::default)
+}
+
+/// What a request that couldn't reach its backend gets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WakeOutcome {
+    /// Not a wake-on-access instance; the usual error applies
+    Disabled,
+    /// The instance is starting
+    Starting { name: String },
+    /// The instance failed to start; the next request tries again
+    Failed { name: String, error: String },
+}
+
+impl WakeOutcome {
+    /// Status code and HTML for the visitor, `None` when disabled
+    pub fn page(&self, domain: &str) -> Option<(u16, String)> {
+        match self {
+            WakeOutcome::Disabled => None,
+            WakeOutcome::Starting { name } => Some((
+                503,
+                render_page(
+                    &format!("Starting {}", name),
+                    &format!(
+                        r#"<p><span class="domain">{}</span> was stopped to save resources and is starting now.</p>
+<p>This page reloads by itself once it's up.</p>"#,
+                        escape_html(domain)
+                    ),
+                    true,
+                ),
+            )),
+            WakeOutcome::Failed { name, error } => Some((
+                502,
+                render_page(
+                    &format!("{} Didn't Start", name),
+                    &format!(
+                        r#"<p>Starting the instance behind <span class="domain">{}</span> failed:</p>
+<div class="hint"><p>{}</p></div>
+<p>Reload the page to try again.</p>"#,
+                        escape_html(domain),
+                        escape_html(error)
+                    ),
+                    false,
+                ),
+            )),
+        }
+    }
+}
+
+fn render_page(title: &str, body: &str, reload: bool) -> String {
+    let refresh = if reload {
+        format!(
+            r#"<meta http-equiv="refresh" content="{}">
+"#,
+            RELOAD_SECS
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+{refresh}<title>{title}</title>
+<style>{styles}</style>
+</head>
+<body>
+<div class="container">
+<h2>{title}</h2>
+{body}
+</div>
+</body>
+</html>"#,
+        refresh = refresh,
+        title = escape_html(title),
+        styles = ERROR_PAGE_STYLES,
+        body = body
+    )
+}
+
+/// The wake-on-access instance a domain routes to
+fn wake_target<'a>(config: &'a Config, domain_id: &Uuid) -> Option<&'a Instance> {
+    let domain = config.domains.iter().find(|d| &d.id == domain_id)?;
+    match &domain.target {
+        DomainTarget::Instance(id) => config
+            .instances
+            .iter()
+            .find(|i| &i.id == id && i.wake_on_access),
+        _ => None,
+    }
+}
+
+/// Domains routed to wake-on-access instances, which stay routed while the
+/// instances are stopped
+pub fn wake_domains(config: &Config) -> impl Iterator<Item = &Domain> {
+    config.domains.iter().filter(|d| {
+        config
+            .instances
+            .iter()
+            .any(|i| i.wake_on_access && d.routes_to_instance(&i.id))
+    })
+}
+
+/// Start the instance behind a domain whose backend didn't answer
+///
+/// `domain_id` is the proxy route's ID. The start runs on its own thread, so
+/// this returns right away; until it finishes, further requests are told
+/// the instance is starting.
+pub fn wake_domain(state: &AppState, domain_id: &str) -> WakeOutcome {
+    let Ok(domain_id) = Uuid::parse_str(domain_id) else {
+        return WakeOutcome::Disabled;
+    };
+    let Some(config) = state
+        .config_store
+        .lock()
+        .ok()
+        .and_then(|store| store.load().ok())
+    else {
+        return WakeOutcome::Disabled;
+    };
+    let Some(instance) = wake_target(&config, &domain_id).cloned() else {
+        return WakeOutcome::Disabled;
+    };
+    let name = instance.name.clone();
+
+    {
+        let Ok(mut wakes) = wakes().lock() else {
+            return WakeOutcome::Disabled;
+        };
+        if let Some(error) = wakes.failed.remove(&instance.id) {
+            return WakeOutcome::Failed { name, error };
+        }
+        if !wakes.starting.insert(instance.id) {
+            return WakeOutcome::Starting { name };
+        }
+    }
+
+    let state = state.clone();
+    std::thread::spawn(move || {
+        let result = start(&state, &config, &instance);
+        if let Ok(mut wakes) = wakes().lock() {
+            wakes.starting.remove(&instance.id);
+            if let Err(error) = result {
+                wakes.failed.insert(instance.id, error);
+            }
+        }
+    });
+
+    WakeOutcome::Starting { name }
+}
+
+fn start(state: &AppState, config: &Config, instance: &Instance) -> Result<(), String> {
+    let process_manager = state
+        .process_manager
+        .lock()
+        .map_err(|_| "Failed to acquire process manager lock".to_string())?;
+
+    // Already up, just not answering yet
+    if process_manager.is_running(&instance.id) {
+        return Ok(());
+    }
+
+    let installed = config
+        .binaries
+        .get(&instance.service_type)
+        .is_some_and(|versions| versions.contains_key(&instance.version));
+    if !installed {
+        return Err(format!(
+            "Version {} is not installed for {}",
+            instance.version,
+            instance.service_type.display_name()
+        ));
+    }
+
+    let ssl_enabled = config
+        .domains
+        .iter()
+        .filter(|d| d.routes_to_instance(&instance.id))
+        .any(|d| d.ssl_enabled);
+    process_manager
+        .start(instance, Some(config), ssl_enabled)
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ConfigBuilder, DomainBuilder, InstanceBuilder};
+
+    #[test]
+    fn test_wake_target() {
+        let sleepy = Instance {
+            wake_on_access: true,
+            ..InstanceBuilder::new().name("sleepy").build()
+        };
+        let awake = InstanceBuilder::new().name("awake").build();
+        let sleepy_domain = DomainBuilder::new_instance(sleepy.id)
+            .subdomain("sleepy")
+            .build();
+        let awake_domain = DomainBuilder::new_instance(awake.id)
+            .subdomain("awake")
+            .build();
+        let config = ConfigBuilder::new()
+            .instance(sleepy.clone())
+            .instance(awake.clone())
+            .domain(sleepy_domain.clone())
+            .domain(awake_domain.clone())
+            .build();
+
+        assert_eq!(
+            wake_target(&config, &sleepy_domain.id).map(|i| i.id),
+            Some(sleepy.id)
+        );
+        assert!(wake_target(&config, &awake_domain.id).is_none());
+        assert!(wake_target(&config, &Uuid::new_v4()).is_none());
+        assert_eq!(
+            wake_domains(&config).map(|d| d.id).collect::<Vec<_>>(),
+            vec![sleepy_domain.id]
+        );
+    }
+
+    #[test]
+    fn test_pages() {
+        assert!(WakeOutcome::Disabled.page("shop.burd").is_none());
+
+        let (status, html) = WakeOutcome::Starting {
+            name: "shop".to_string(),
+        }
+        .page("shop.burd")
+        .unwrap();
+        assert_eq!(status, 503);
+        assert!(html.contains(r#"<meta http-equiv="refresh" content="2">"#));
+        assert!(html.contains("Starting shop"));
+
+        let (status, html) = WakeOutcome::Failed {
+            name: "shop".to_string(),
+            error: "Port 8000 is <in use>".to_string(),
+        }
+        .page("shop.burd")
+        .unwrap();
+        assert_eq!(status, 502);
+        assert!(!html.contains("http-equiv"));
+        assert!(html.contains("Port 8000 is &lt;in use&gt;"));
+    }
+}
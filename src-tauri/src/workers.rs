@@ -0,0 +1,364 @@
+//! Laravel queue workers
+//!
+//! A worker runs `php artisan queue:work` (or `php artisan horizon`) in a
+//! linked Laravel project. Workers are saved in the config so their settings
+//! survive restarts; `ProcessManager` spawns their processes, with one PID
+//! file per process and a single shared log.
+
+use crate::config::Config;
+use crate::env_sync;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Most `queue:work` processes one worker may run
+pub const MAX_CONCURRENCY: u8 = 16;
+
+fn default_concurrency() -> u8 {
+    1
+}
+
+/// What a worker runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkerMode {
+    /// `php artisan queue:work`, once per process
+    #[default]
+    Queue,
+    /// `php artisan horizon`, which supervises its own processes
+    Horizon,
+}
+
+impl WorkerMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkerMode::Queue => "queue",
+            WorkerMode::Horizon => "horizon",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "queue" | "queue:work" => Ok(WorkerMode::Queue),
+            "horizon" => Ok(WorkerMode::Horizon),
+            other => Err(format!(
+                "Unknown worker mode '{}'. Use queue or horizon",
+                other
+            )),
+        }
+    }
+}
+
+/// A queue worker for one Laravel project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Worker {
+    pub id: Uuid,
+    /// Project root, the directory holding `artisan`
+    pub project_path: String,
+    #[serde(default)]
+    pub mode: WorkerMode,
+    /// `queue:work` processes to run; Horizon always runs one
+    #[serde(default = "default_concurrency")]
+    pub concurrency: u8,
+    /// Queues to work, comma-separated in priority order (`--queue`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<String>,
+    /// Queue connection to work, when not the project's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Settings to change when starting a worker; `None` keeps the saved value
+#[derive(Debug, Clone, Default)]
+pub struct WorkerOptions {
+    pub mode: Option<WorkerMode>,
+    pub concurrency: Option<u8>,
+    pub queue: Option<String>,
+    pub connection: Option<String>,
+}
+
+/// Whether `name` is a usable queue or connection name
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+/// Empty means unset
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl Worker {
+    /// Project name shown in lists, the project directory's name
+    pub fn name(&self) -> String {
+        Path::new(&self.project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.project_path.clone())
+    }
+
+    /// How many processes the worker runs
+    pub fn process_count(&self) -> u8 {
+        match self.mode {
+            WorkerMode::Queue => self.concurrency,
+            WorkerMode::Horizon => 1,
+        }
+    }
+
+    /// Arguments after `php`
+    pub fn artisan_args(&self) -> Vec<String> {
+        let mut args = vec!["artisan".to_string()];
+        match self.mode {
+            WorkerMode::Queue => {
+                args.push("queue:work".to_string());
+                if let Some(connection) = &self.connection {
+                    args.push(connection.clone());
+                }
+                if let Some(queue) = &self.queue {
+                    args.push(format!("--queue={}", queue));
+                }
+            }
+            WorkerMode::Horizon => args.push("horizon".to_string()),
+        }
+        args
+    }
+
+    fn apply(&mut self, options: WorkerOptions) -> Result<(), String> {
+        if let Some(mode) = options.mode {
+            self.mode = mode;
+        }
+        if let Some(concurrency) = options.concurrency {
+            if !(1..=MAX_CONCURRENCY).contains(&concurrency) {
+                return Err(format!(
+                    "Concurrency must be between 1 and {}",
+                    MAX_CONCURRENCY
+                ));
+            }
+            self.concurrency = concurrency;
+        }
+        if let Some(queue) = options.queue {
+            let queue = non_empty(Some(queue));
+            if let Some(invalid) = queue
+                .iter()
+                .flat_map(|q| q.split(','))
+                .find(|q| !is_valid_name(q.trim()))
+            {
+                return Err(format!("Invalid queue name '{}'", invalid.trim()));
+            }
+            self.queue = queue.map(|q| q.split(',').map(str::trim).collect::<Vec<_>>().join(","));
+        }
+        if let Some(connection) = options.connection {
+            let connection = non_empty(Some(connection));
+            if let Some(invalid) = connection.as_deref().filter(|c| !is_valid_name(c)) {
+                return Err(format!("Invalid queue connection '{}'", invalid));
+            }
+            self.connection = connection;
+        }
+        Ok(())
+    }
+}
+
+/// The Laravel project `path` belongs to: `path` itself, or its parent when
+/// `path` is a document root like `public/`
+pub fn project_root(path: &Path) -> Result<PathBuf, String> {
+    [Some(path), path.parent()]
+        .into_iter()
+        .flatten()
+        .find(|dir| dir.join("artisan").is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            format!(
+                "{} is not a Laravel project (no artisan file found)",
+                path.display()
+            )
+        })
+}
+
+/// Linked projects that can run queue workers
+pub fn laravel_projects(config: &Config) -> Vec<PathBuf> {
+    env_sync::linked_projects(config)
+        .into_iter()
+        .filter(|project| project.join("artisan").is_file())
+        .collect()
+}
+
+/// The saved worker for a project
+pub fn find_worker<'a>(workers: &'a [Worker], project: &Path) -> Option<&'a Worker> {
+    workers
+        .iter()
+        .find(|w| Path::new(&w.project_path) == project)
+}
+
+/// Update the project's worker with `options`, adding one when it has none
+///
+/// Returns the worker as saved in `workers`.
+pub fn upsert_worker(
+    workers: &mut Vec<Worker>,
+    project: &Path,
+    options: WorkerOptions,
+) -> Result<Worker, String> {
+    let existing = workers
+        .iter()
+        .position(|w| Path::new(&w.project_path) == project);
+    let mut worker = match existing {
+        Some(index) => workers[index].clone(),
+        None => Worker {
+            id: Uuid::new_v4(),
+            project_path: project.to_string_lossy().to_string(),
+            mode: WorkerMode::default(),
+            concurrency: default_concurrency(),
+            queue: None,
+            connection: None,
+            created_at: Utc::now(),
+        },
+    };
+    worker.apply(options)?;
+
+    match existing {
+        Some(index) => workers[index] = worker.clone(),
+        None => workers.push(worker.clone()),
+    }
+    Ok(worker)
+}
+
+/// A worker and its live processes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    #[serde(flatten)]
+    pub worker: Worker,
+    pub name: String,
+    /// PIDs of the worker's running processes
+    pub pids: Vec<u32>,
+    pub running: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn worker(options: WorkerOptions) -> Result<Worker, String> {
+        let mut workers = Vec::new();
+        let worker = upsert_worker(&mut workers, Path::new("/srv/shop"), options);
+        assert_eq!(workers.len(), usize::from(worker.is_ok()));
+        worker
+    }
+
+    #[test]
+    fn test_artisan_args() {
+        let queue = worker(WorkerOptions {
+            concurrency: Some(3),
+            queue: Some("high, default".to_string()),
+            connection: Some("redis".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            queue.artisan_args(),
+            vec!["artisan", "queue:work", "redis", "--queue=high,default"]
+        );
+        assert_eq!(queue.process_count(), 3);
+        assert_eq!(queue.name(), "shop");
+
+        let horizon = worker(WorkerOptions {
+            mode: Some(WorkerMode::Horizon),
+            concurrency: Some(3),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(horizon.artisan_args(), vec!["artisan", "horizon"]);
+        assert_eq!(horizon.process_count(), 1);
+    }
+
+    #[test]
+    fn test_invalid_options_are_rejected() {
+        for options in [
+            WorkerOptions {
+                concurrency: Some(0),
+                ..Default::default()
+            },
+            WorkerOptions {
+                concurrency: Some(MAX_CONCURRENCY + 1),
+                ..Default::default()
+            },
+            WorkerOptions {
+                queue: Some("high,,low".to_string()),
+                ..Default::default()
+            },
+            WorkerOptions {
+                connection: Some("redis; rm -rf".to_string()),
+                ..Default::default()
+            },
+        ] {
+            assert!(worker(options).is_err());
+        }
+        assert!(WorkerMode::parse("supervisor").is_err());
+        assert_eq!(WorkerMode::parse("Horizon"), Ok(WorkerMode::Horizon));
+    }
+
+    #[test]
+    fn test_upsert_keeps_unchanged_settings() {
+        let project = Path::new("/srv/shop");
+        let mut workers = Vec::new();
+        let first = upsert_worker(
+            &mut workers,
+            project,
+            WorkerOptions {
+                concurrency: Some(4),
+                queue: Some("emails".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let second = upsert_worker(
+            &mut workers,
+            project,
+            WorkerOptions {
+                queue: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(workers.len(), 1);
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.concurrency, 4);
+        assert_eq!(second.queue, None);
+        assert_eq!(find_worker(&workers, project), Some(&second));
+
+        // A failed update leaves the saved worker alone
+        assert!(upsert_worker(
+            &mut workers,
+            project,
+            WorkerOptions {
+                concurrency: Some(0),
+                ..Default::default()
+            }
+        )
+        .is_err());
+        assert_eq!(workers[0], second);
+    }
+
+    #[test]
+    fn test_project_root() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("public")).unwrap();
+        fs::write(dir.path().join("artisan"), "").unwrap();
+
+        assert_eq!(project_root(dir.path()).unwrap(), dir.path());
+        assert_eq!(
+            project_root(&dir.path().join("public")).unwrap(),
+            dir.path()
+        );
+
+        let other = TempDir::new().unwrap();
+        assert!(project_root(other.path()).is_err());
+    }
+}
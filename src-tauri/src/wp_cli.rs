@@ -0,0 +1,344 @@
+//! wp-cli integration
+//!
+//! Downloads wp-cli into Burd's bin directory and runs it inside WordPress
+//! and Bedrock projects with the PHP that serves the project and the client
+//! tools of the project's database instance on `PATH`, so `wp db import`,
+//! `wp db export`, and `wp search-replace` work without a system PHP or MySQL.
+
+use crate::analyzer::{analyze_project, detect_project_type, ProjectType};
+use crate::config::{get_bin_dir, get_versioned_binary_dir, get_versioned_binary_path};
+use crate::config::{Config, Instance, ServiceType};
+use crate::process::ProcessManager;
+use crate::tinker::get_php_binary;
+use sha2::{Digest, Sha512};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Latest stable wp-cli phar (a `.sha512` checksum sits next to it)
+const WP_CLI_URL: &str =
+    "https://raw.githubusercontent.com/wp-cli/builds/gh-pages/phar/wp-cli.phar";
+
+/// Path of the managed wp-cli phar
+pub fn phar_path() -> Result<PathBuf, String> {
+    get_bin_dir().map(|p| p.join("wp-cli").join("wp-cli.phar"))
+}
+
+/// Check whether wp-cli has been downloaded
+pub fn is_installed() -> bool {
+    phar_path().map(|p| p.exists()).unwrap_or(false)
+}
+
+/// Download (or update) wp-cli, verifying its SHA-512 checksum
+pub fn install() -> Result<PathBuf, String> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("burd-cli")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let bytes = download(&client, WP_CLI_URL)?;
+    let checksum = download(&client, &format!("{}.sha512", WP_CLI_URL))?;
+    let expected = String::from_utf8_lossy(&checksum)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let actual = format!("{:x}", Sha512::digest(&bytes));
+    if actual != expected {
+        return Err(format!(
+            "wp-cli checksum mismatch (expected {}, got {})",
+            expected, actual
+        ));
+    }
+
+    let path = phar_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create wp-cli directory: {}", e))?;
+    }
+    fs::write(&path, &bytes).map_err(|e| format!("Failed to write wp-cli: {}", e))?;
+
+    Ok(path)
+}
+
+fn download(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+
+    response
+        .bytes()
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read download: {}", e))
+}
+
+/// A WordPress project resolved against Burd's config
+#[derive(Debug, Clone)]
+pub struct WpProject {
+    /// Project root (where wp-config.php or Bedrock's wp-cli.yml lives)
+    pub root: PathBuf,
+    /// FrankenPHP instance serving the project
+    pub php_instance: Option<Instance>,
+    /// Database instance the project connects to
+    pub db_instance: Option<Instance>,
+    /// Database the project uses (DB_NAME)
+    pub database: Option<String>,
+    /// Site URL from the domain routed to the PHP instance
+    pub url: Option<String>,
+}
+
+fn is_wordpress(project_type: &ProjectType) -> bool {
+    matches!(project_type, ProjectType::WordPress | ProjectType::Bedrock)
+}
+
+/// Walk up from `start` to the nearest WordPress or Bedrock project root
+///
+/// Bedrock's `web/` and `web/wp/` look like plain WordPress installs, so a
+/// Bedrock project up to two levels above the match wins.
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    let found = start
+        .ancestors()
+        .find(|dir| is_wordpress(&detect_project_type(dir)))?;
+
+    let root = found
+        .ancestors()
+        .take(3)
+        .find(|dir| detect_project_type(dir) == ProjectType::Bedrock)
+        .unwrap_or(found);
+    Some(root.to_path_buf())
+}
+
+/// Resolve the project containing `start` and the instances it uses
+pub fn resolve_project(config: &Config, start: &Path) -> Result<WpProject, String> {
+    let root = find_project_root(start).ok_or_else(|| {
+        format!(
+            "No WordPress or Bedrock project found at {} or its parents",
+            start.display()
+        )
+    })?;
+    let info = analyze_project(&root)?;
+
+    let php_instance = config
+        .instances
+        .iter()
+        .find(|i| {
+            matches!(
+                i.service_type,
+                ServiceType::FrankenPHP | ServiceType::FrankenPhpPark
+            ) && i
+                .config
+                .get("document_root")
+                .and_then(|v| v.as_str())
+                .map(|dr| Path::new(dr) == info.path || Path::new(dr) == info.document_root)
+                .unwrap_or(false)
+        })
+        .cloned();
+
+    // Prefer the instance on the port the project connects to, then any MariaDB/MySQL
+    let db_instance = info
+        .database
+        .as_ref()
+        .filter(|db| db.is_mysql())
+        .and_then(|db| {
+            config.instances.iter().find(|i| {
+                matches!(i.service_type, ServiceType::MariaDB | ServiceType::MySQL)
+                    && i.port == db.port
+            })
+        })
+        .or_else(|| {
+            config
+                .instances
+                .iter()
+                .find(|i| matches!(i.service_type, ServiceType::MariaDB | ServiceType::MySQL))
+        })
+        .cloned();
+
+    let url = php_instance.as_ref().and_then(|instance| {
+        config
+            .domains
+            .iter()
+            .find(|d| d.routes_to_instance(&instance.id))
+            .map(|d| site_url(&d.full_domain(&config.tld), d.ssl_enabled))
+    });
+
+    let database = info
+        .database
+        .as_ref()
+        .map(|db| db.database.clone())
+        .filter(|name| !name.is_empty());
+
+    Ok(WpProject {
+        root,
+        php_instance,
+        db_instance,
+        database,
+        url,
+    })
+}
+
+fn site_url(host: &str, ssl_enabled: bool) -> String {
+    format!("{}://{}", if ssl_enabled { "https" } else { "http" }, host)
+}
+
+/// Build the wp-cli command for a project
+///
+/// Runs the phar through the project's FrankenPHP (`frankenphp php-cli`) when
+/// it has one, falling back to Burd's default PHP. The database instance's
+/// client tools are put first on `PATH` for `wp db` commands.
+pub fn command(project: &WpProject, args: &[String]) -> Result<Command, String> {
+    let phar = phar_path()?;
+    if !phar.exists() {
+        return Err("wp-cli is not installed. Run: burd wp install".to_string());
+    }
+
+    let frankenphp = project
+        .php_instance
+        .as_ref()
+        .filter(|i| i.service_type == ServiceType::FrankenPHP)
+        .and_then(|i| get_versioned_binary_path(ServiceType::FrankenPHP, &i.version).ok())
+        .filter(|p| p.exists());
+
+    let mut cmd = match frankenphp {
+        Some(binary) => {
+            let mut cmd = Command::new(binary);
+            cmd.arg("php-cli");
+            cmd
+        }
+        None => Command::new(get_php_binary()?),
+    };
+    cmd.arg(&phar).args(args).current_dir(&project.root);
+
+    if let Some(db) = &project.db_instance {
+        let running = ProcessManager::new().get_status(db).running;
+        if !running && needs_database(args) {
+            return Err(format!(
+                "Database instance '{}' is not running. Start it with: burd start {}",
+                db.name, db.name
+            ));
+        }
+
+        let bin_dir = get_versioned_binary_dir(db.service_type, &db.version)?;
+        let mut path = vec![bin_dir.join("bin"), bin_dir.clone()];
+        if let Some(existing) = std::env::var_os("PATH") {
+            path.extend(std::env::split_paths(&existing));
+        }
+        let path = std::env::join_paths(path)
+            .map_err(|e| format!("Failed to build PATH for wp-cli: {}", e))?;
+
+        let lib_dir = bin_dir.join("lib");
+        cmd.env("PATH", path)
+            .env("DYLD_FALLBACK_LIBRARY_PATH", &lib_dir);
+    }
+
+    Ok(cmd)
+}
+
+/// Whether a wp-cli invocation touches the database
+///
+/// Everything but a few offline commands loads WordPress, which connects to
+/// the database.
+fn needs_database(args: &[String]) -> bool {
+    let command = args.iter().find(|a| !a.starts_with('-'));
+    !matches!(
+        command.map(String::as_str),
+        None | Some("help" | "cli" | "package" | "core" | "config")
+    )
+}
+
+/// Whether a wp-cli invocation drops or rewrites database content
+///
+/// `search-replace` (unless `--dry-run`) and `db import|reset|drop|clean`.
+pub fn overwrites_database(args: &[String]) -> bool {
+    let positional: Vec<&str> = args
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .map(String::as_str)
+        .collect();
+    match positional.as_slice() {
+        ["search-replace", ..] => !args.iter().any(|a| a == "--dry-run"),
+        ["db", "import" | "reset" | "drop" | "clean", ..] => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_needs_database() {
+        assert!(needs_database(&args(&["db", "import", "dump.sql"])));
+        assert!(needs_database(&args(&[
+            "--quiet",
+            "search-replace",
+            "a",
+            "b"
+        ])));
+        assert!(!needs_database(&args(&["help", "db"])));
+        assert!(!needs_database(&args(&["core", "download"])));
+        assert!(!needs_database(&args(&[])));
+    }
+
+    #[test]
+    fn test_overwrites_database() {
+        assert!(overwrites_database(&args(&[
+            "search-replace",
+            "old.test",
+            "new.burd"
+        ])));
+        assert!(!overwrites_database(&args(&[
+            "search-replace",
+            "old.test",
+            "new.burd",
+            "--dry-run"
+        ])));
+        assert!(overwrites_database(&args(&["db", "import", "dump.sql"])));
+        assert!(overwrites_database(&args(&["db", "reset", "--yes"])));
+        assert!(!overwrites_database(&args(&["db", "export", "dump.sql"])));
+        assert!(!overwrites_database(&args(&["plugin", "list"])));
+    }
+
+    #[test]
+    fn test_find_project_root_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("wp-config.php"), "").unwrap();
+        let nested = dir.path().join("wp-content/themes/site");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_project_root(&nested).as_deref(), Some(dir.path()));
+    }
+
+    #[test]
+    fn test_find_project_root_prefers_bedrock() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("config")).unwrap();
+        fs::write(dir.path().join("config/application.php"), "").unwrap();
+        let web = dir.path().join("web");
+        fs::create_dir_all(web.join("app/themes")).unwrap();
+        fs::write(web.join("wp-config.php"), "").unwrap();
+
+        assert_eq!(
+            find_project_root(&web.join("app/themes")).as_deref(),
+            Some(dir.path())
+        );
+    }
+
+    #[test]
+    fn test_site_url() {
+        assert_eq!(site_url("blog.test", true), "https://blog.test");
+        assert_eq!(site_url("blog.test", false), "http://blog.test");
+    }
+}
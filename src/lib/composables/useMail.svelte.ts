@@ -4,6 +4,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
 
 // ============================================================================
 // Types
@@ -77,6 +78,7 @@ export class MailState {
   searchQuery = $state("");
   selectedEmail = $state<MailMessageDetail | null>(null);
   selectedEmailLoading = $state(false);
+  htmlSource = $state<string | null>(null);
   smtpConfig = $state<SmtpConfig | null>(null);
   currentPage = $state(0);
   pageSize = 25;
@@ -156,6 +158,45 @@ export class MailState {
 
   closeEmail() {
     this.selectedEmail = null;
+    this.htmlSource = null;
+  }
+
+  async saveAttachment(messageId: string, attachment: MailAttachment) {
+    try {
+      const path = await save({ defaultPath: attachment.file_name });
+      if (path) {
+        await invoke("save_email_attachment", { messageId, partId: attachment.part_id, path });
+      }
+    } catch (e) {
+      this.error = e instanceof Error ? e.message : String(e);
+    }
+  }
+
+  async exportEml(messageId: string, subject: string) {
+    try {
+      const name = subject.replace(/[^\w\s-]+/g, "").trim().replace(/\s+/g, "-") || messageId;
+      const path = await save({
+        defaultPath: `${name}.eml`,
+        filters: [{ name: "Email Message", extensions: ["eml"] }],
+      });
+      if (path) {
+        await invoke("export_email_eml", { messageId, path });
+      }
+    } catch (e) {
+      this.error = e instanceof Error ? e.message : String(e);
+    }
+  }
+
+  async toggleHtmlSource(messageId: string) {
+    if (this.htmlSource !== null) {
+      this.htmlSource = null;
+      return;
+    }
+    try {
+      this.htmlSource = await invoke<string>("get_email_html_source", { messageId });
+    } catch (e) {
+      this.error = e instanceof Error ? e.message : String(e);
+    }
   }
 
   async deleteEmail(messageId: string) {
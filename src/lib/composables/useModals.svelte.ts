@@ -46,7 +46,10 @@ interface Instance {
   domain_enabled: boolean;
   process_manager: string;
   stack_id: string | null;
+  tags: string[];
   mapped_domains: string[];
+  auto_start: boolean;
+  extra_args: string[];
 }
 
 /**
@@ -183,12 +186,17 @@ export function createSettingsModal() {
     }
     try {
       saving = true;
-      await invoke("update_tld", { tld: tld.trim() });
-      show = false;
-      await message("TLD updated! Please restart the app for changes to take effect.", {
-        title: "TLD Updated",
-        kind: "info"
+      const migration = await invoke<{ new_tld: string; manual_steps: string[] }>("update_tld", {
+        tld: tld.trim()
       });
+      show = false;
+      const steps = migration.manual_steps.map((step) => `• ${step}`).join("\n");
+      await message(
+        steps
+          ? `Moved to .${migration.new_tld}. A few things still need attention:\n\n${steps}`
+          : `Moved to .${migration.new_tld}.`,
+        { title: "TLD Updated", kind: steps ? "warning" : "info" }
+      );
       return null;
     } catch (e) {
       return String(e);
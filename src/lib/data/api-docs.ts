@@ -1,6 +1,8 @@
 /**
  * API Documentation for The Burd Nest terminal
  * All endpoints available on localhost:19840
+ * In remote mode (`burd api remote`), every request needs `Authorization: Bearer <token>`
+ * Callers are rate limited (429 + Retry-After) and request bodies are capped at 1 MB
  */
 
 export interface ApiEndpoint {
@@ -38,6 +40,43 @@ export const apiCategories: ApiCategory[] = [
     "running_instances": 3
   }
 }`
+      },
+      {
+        method: 'GET',
+        path: '/health',
+        description: 'Health of every subsystem for uptime monitors. Responds 200 when all checks pass and 503 when any fails. Not wrapped in the usual success/data envelope',
+        response: `{
+  "status": "degraded",
+  "api": { "ok": true, "detail": "running" },
+  "dns": { "ok": true, "detail": "running" },
+  "proxy": { "ok": true, "detail": "healthy" },
+  "helper": { "ok": true, "detail": "not installed" },
+  "instances": {
+    "ok": false,
+    "total": 5,
+    "running": 3,
+    "unhealthy": ["search"]
+  }
+}`
+      }
+    ]
+  },
+  {
+    name: 'Events',
+    description: 'Live instance, config, download, health and log events over a WebSocket',
+    endpoints: [
+      {
+        method: 'GET',
+        path: '/events',
+        description: 'Upgrade to a WebSocket that streams one JSON event per message. Lagging clients receive {"type": "lagged", "skipped": n}. A config event follows every save of the config, by the app, the API or the CLI',
+        params: '?types=health,log,request - Event types (default: all), ?instance=<uuid> - One instance only, ?domain=shop.burd - Requests to one domain only',
+        response: `{"type": "instance", "instance_id": "uuid", "kind": "started", "detail": "7.2.4"}
+{"type": "health", "instance_id": "uuid", "running": true, "healthy": false}
+{"type": "log", "instance_id": "uuid", "line": "Ready to accept connections"}
+{"type": "request", "domain": "shop.burd", "at": "2025-01-01T12:00:00.250Z", "method": "POST", "path": "/cart?item=1", "status": 201, "duration_ms": 12.5, "remote_ip": "127.0.0.1"}
+{"type": "download", "service_type": "redis", "downloaded": 1048576, "total": 4194304, "percentage": 25.0, "phase": "downloading"}
+{"type": "download", "service_type": "php", "downloaded": 0, "total": 0, "percentage": 0.0, "phase": "extracting"}
+{"type": "config"}`
       }
     ]
   },
@@ -48,7 +87,7 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'GET',
         path: '/instances',
-        description: 'List all service instances with health status',
+        description: 'List all service instances with health status. Filter with ?tag=client:acme,env:test (instances must carry all tags)',
         response: `{
   "success": true,
   "data": [{
@@ -56,9 +95,13 @@ export const apiCategories: ApiCategory[] = [
     "name": "my-redis",
     "port": 6379,
     "service_type": "Redis",
+    "service_id": "redis",
     "version": "7.2.4",
     "running": true,
-    "healthy": true
+    "healthy": true,
+    "tags": ["client:acme"],
+    "auto_start": false,
+    "restart_on_failure": false
   }]
 }`
       },
@@ -71,12 +114,27 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'POST',
         path: '/instances',
-        description: 'Create a new service instance',
+        description: 'Create a new service instance (omit "version" to use the pinned or newest installed version). A port already used by another instance or process is rejected unless "auto_assign_port" is true, which picks a free port instead',
         body: `{
   "name": "my-redis",
   "port": 6379,
   "service_type": "redis",
-  "version": "7.2.4"
+  "version": "7.2.4",
+  "auto_assign_port": false
+}`
+      },
+      {
+        method: 'PUT',
+        path: '/instances/:id',
+        description: 'Update an instance. All fields are optional; auto_start instances are started when the app or the daemon launches, databases and other backing services first; restart_on_failure instances are restarted with backoff when they crash or fail their health check',
+        params: ':id - Instance UUID',
+        body: `{
+  "name": "cache",
+  "port": 6380,
+  "auto_start": true,
+  "restart_on_failure": true,
+  "tags": ["client:acme"],
+  "extra_args": ["--maxmemory", "256mb"]
 }`
       },
       {
@@ -88,7 +146,7 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'POST',
         path: '/instances/:id/start',
-        description: 'Start a stopped instance',
+        description: 'Start a stopped instance. With ?wait=true the response is sent only once the service passes its health check (?timeout=N seconds, default 30)',
         params: ':id - Instance UUID'
       },
       {
@@ -103,12 +161,50 @@ export const apiCategories: ApiCategory[] = [
         description: 'Restart an instance',
         params: ':id - Instance UUID'
       },
+      {
+        method: 'POST',
+        path: '/instances/bulk',
+        description: 'Start, stop, or restart every instance carrying all of the given tags and/or belonging to a stack (ID or name)',
+        body: `{
+  "action": "stop",
+  "tags": ["client:acme"],
+  "stack": "Client X"
+}`
+      },
       {
         method: 'GET',
         path: '/instances/:id/logs',
         description: 'Get recent logs from an instance',
         params: ':id - Instance UUID'
       },
+      {
+        method: 'GET',
+        path: '/instances/:id/activity',
+        description: 'Get lifecycle events (started, stopped, crashed, restarted, version_changed, config_edited), newest first',
+        params: ':id - Instance UUID, ?limit=50 - Number of events',
+        response: `{
+  "success": true,
+  "data": [
+    { "at": "2026-10-18T07:12:04Z", "kind": "restarted" },
+    { "at": "2026-10-18T07:11:40Z", "kind": "config_edited", "detail": "max_connections" }
+  ]
+}`
+      },
+      {
+        method: 'GET',
+        path: '/instances/:id/metrics',
+        description: 'Get CPU, memory and data directory usage of a running instance, sampled every 10 seconds, with the last 10 minutes of history (oldest first)',
+        params: ':id - Instance UUID',
+        response: `{
+  "success": true,
+  "data": {
+    "instance_id": "...",
+    "running": true,
+    "current": { "at": "2026-10-18T07:12:10Z", "cpu_percent": 3.5, "memory_bytes": 84934656, "disk_bytes": 52428800 },
+    "history": [ ... ]
+  }
+}`
+      },
       {
         method: 'GET',
         path: '/instances/:id/env',
@@ -118,6 +214,185 @@ export const apiCategories: ApiCategory[] = [
   "success": true,
   "data": "REDIS_HOST=127.0.0.1\\nREDIS_PORT=6379..."
 }`
+      },
+      {
+        method: 'POST',
+        path: '/instances/:id/centrifugo/setup',
+        description: 'Generate the Centrifugo config (API key, token secret, allowed origins from configured domains) and a Laravel broadcasting .env block. Applied on the next start.',
+        params: ':id - Centrifugo instance UUID',
+        response: `{
+  "success": true,
+  "data": {
+    "config": {
+      "api_key": "...",
+      "token_hmac_secret_key": "...",
+      "allowed_origins": ["https://shop.test"]
+    },
+    "env": "BROADCAST_CONNECTION=centrifugo\\nCENTRIFUGO_URL=http://127.0.0.1:8000..."
+  }
+}`
+      },
+      {
+        method: 'POST',
+        path: '/instances/:id/soketi/setup',
+        description: 'Generate Soketi app credentials (app ID, key, secret) and a Laravel broadcasting .env block with PUSHER_* and VITE_PUSHER_* variables. Applied on the next start.',
+        params: ':id - Soketi instance UUID',
+        response: `{
+  "success": true,
+  "data": {
+    "config": {
+      "host": "127.0.0.1",
+      "port": 6001,
+      "appManager.array.apps": [{ "id": "...", "key": "...", "secret": "...", "enableClientMessages": false }]
+    },
+    "env": "BROADCAST_CONNECTION=pusher\\nPUSHER_APP_ID=...\\nPUSHER_HOST=127.0.0.1..."
+  }
+}`
+      },
+      {
+        method: 'POST',
+        path: '/instances/:id/keycloak/provision',
+        description: 'Create the configured realm, OIDC client and test user on a running Keycloak instance, skipping whatever already exists. Returns the steps taken and a Laravel Socialite .env block.',
+        params: ':id - Keycloak instance UUID',
+        response: `{
+  "success": true,
+  "data": {
+    "steps": ["Created realm 'burd'", "Created client 'laravel'", "Created user 'test'"],
+    "env": "KEYCLOAK_BASE_URL=http://127.0.0.1:8180\\nKEYCLOAK_REALM=burd..."
+  }
+}`
+      },
+      {
+        method: 'PUT',
+        path: '/instances/:id/seed',
+        description: 'Set the database seed of a MariaDB or PostgreSQL instance. Send null to remove it. Needs an SQL file, a command (run in project_dir), or both; schedule is an optional cron expression.',
+        params: ':id - Database instance UUID',
+        body: `{
+  "database": "demo",
+  "sql_file": "~/Sites/shop/database/demo.sql",
+  "command": "php artisan migrate:fresh --seed",
+  "project_dir": "~/Sites/shop",
+  "schedule": "0 3 * * *"
+}`
+      },
+      {
+        method: 'POST',
+        path: '/instances/:id/seed/run',
+        description: 'Reset the database with its seed now: drop, recreate and import the SQL file, then run the command. The instance must be running.',
+        params: ':id - Database instance UUID',
+        response: `{
+  "success": true,
+  "data": {
+    "database": "demo",
+    "imported": true,
+    "output": "INFO  Seeding database.",
+    "duration_ms": 4210
+  }
+}`
+      },
+      {
+        method: 'GET',
+        path: '/instances/:id/xdebug',
+        description: 'Get the Xdebug mode of a PHP (FrankenPHP) instance',
+        params: ':id - PHP instance UUID',
+        response: `{
+  "success": true,
+  "data": {
+    "mode": "debug",
+    "client_port": 9003,
+    "output_dir": "/Users/dev/Library/Application Support/Burd/instances/3f2a.../xdebug",
+    "restarted": false
+  }
+}`
+      },
+      {
+        method: 'PUT',
+        path: '/instances/:id/xdebug',
+        description: 'Set the Xdebug mode of a PHP instance: off, debug (or on), or profile. Debug connects every request to the IDE on port 9003; profile writes cachegrind files for requests carrying XDEBUG_TRIGGER. A running instance is restarted to apply it.',
+        params: ':id - PHP instance UUID',
+        body: `{ "mode": "debug" }`,
+        response: `{
+  "success": true,
+  "data": {
+    "mode": "debug",
+    "client_port": 9003,
+    "output_dir": "/Users/dev/Library/Application Support/Burd/instances/3f2a.../xdebug",
+    "restarted": true
+  }
+}`
+      },
+      {
+        method: 'POST',
+        path: '/env/sync',
+        description: 'Apply .env updates suggested in the env_sync field of a PUT /instances/:id response (after a port or domain change)',
+        body: `{
+  "changes": [{
+    "project": "/Users/dev/Sites/shop",
+    "key": "DB_PORT",
+    "current": "3306",
+    "suggested": "3307"
+  }]
+}`
+      }
+    ]
+  },
+  {
+    name: 'Stacks',
+    description: 'Groups of instances with their combined resource usage',
+    endpoints: [
+      {
+        method: 'GET',
+        path: '/stacks',
+        description: 'List stacks with instance counts and the combined CPU, memory, and disk usage of their running instances',
+        response: `{
+  "success": true,
+  "data": [{
+    "id": "uuid",
+    "name": "Client X",
+    "description": null,
+    "instance_count": 4,
+    "running_count": 3,
+    "usage": {
+      "sampled": 3,
+      "cpu_percent": 12.5,
+      "memory_bytes": 3221225472,
+      "disk_bytes": 1073741824
+    },
+    "created_at": "2026-10-01T09:00:00+00:00",
+    "updated_at": "2026-10-01T09:00:00+00:00"
+  }]
+}`
+      },
+      {
+        method: 'GET',
+        path: '/stacks/:id',
+        description: 'Get a stack with its resource usage',
+        params: ':id - Stack UUID'
+      },
+      {
+        method: 'POST',
+        path: '/stacks/:id/start',
+        description: 'Start every instance of a stack: databases and other backing services first, each healthy before the apps that use them. Failures don\'t stop the others; every outcome is returned',
+        params: ':id - Stack UUID or name',
+        response: `{
+  "success": true,
+  "data": [
+    { "id": "uuid", "name": "db", "success": true, "skipped": false, "error": null },
+    { "id": "uuid", "name": "app", "success": false, "skipped": false, "error": "Version 8.4 is not installed for FrankenPHP" }
+  ]
+}`
+      },
+      {
+        method: 'POST',
+        path: '/stacks/:id/stop',
+        description: 'Stop every instance of a stack, apps before the services they depend on',
+        params: ':id - Stack UUID or name'
+      },
+      {
+        method: 'POST',
+        path: '/stacks/:id/restart',
+        description: 'Stop a stack, then start it again in dependency order',
+        params: ':id - Stack UUID or name'
       }
     ]
   },
@@ -155,12 +430,16 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'PUT',
         path: '/domains/:id',
-        description: 'Update a domain configuration',
+        description: 'Update a domain configuration (force_https, hsts and require_client_cert need SSL enabled; requiring a client cert issues one if needed). custom_directives are raw Caddyfile directives checked with `caddy validate` before they are applied; an empty string clears them',
         params: ':id - Domain UUID',
         body: `{
   "subdomain": "new-name",
   "target_type": "port",
-  "target_value": "3000"
+  "target_value": "3000",
+  "force_https": true,
+  "hsts": false,
+  "require_client_cert": false,
+  "custom_directives": "header X-Robots-Tag noindex"
 }`
       },
       {
@@ -175,6 +454,48 @@ export const apiCategories: ApiCategory[] = [
         description: 'Enable or disable SSL for a domain',
         params: ':id - Domain UUID',
         body: `{ "ssl_enabled": true }`
+      },
+      {
+        method: 'GET',
+        path: '/domains/stats',
+        description: 'Request counts and response times of every domain from its access log, for the last N minutes (recent) and the N minutes before (previous). slowdown is the recent median over the previous one when both windows saw at least 5 requests; slower is set from 1.5x',
+        params: '?minutes=15 - Window length',
+        response: `{
+  "success": true,
+  "data": [{
+    "domain": "shop.burd",
+    "window_minutes": 15,
+    "recent": { "requests": 120, "errors": 0, "avg_ms": 84.2, "p50_ms": 61.0, "p95_ms": 240.5, "max_ms": 812.3 },
+    "previous": { "requests": 98, "errors": 1, "avg_ms": 30.1, "p50_ms": 22.4, "p95_ms": 75.0, "max_ms": 190.2 },
+    "slowdown": 2.72,
+    "slower": true,
+    "last_request_at": "2025-01-01T12:00:00Z"
+  }]
+}`
+      },
+      {
+        method: 'GET',
+        path: '/domains/:id/stats',
+        description: 'Request counts and response times of one domain (same shape as /domains/stats)',
+        params: ':id - Domain UUID, ?minutes=15 - Window length'
+      },
+      {
+        method: 'GET',
+        path: '/domains/:id/requests',
+        description: 'Most recent requests served for a domain (up to 200 kept per domain), newest first. Stream new ones with /events?types=request&domain=<full domain>',
+        params: ':id - Domain UUID, ?limit=200 - Number of requests',
+        response: `{
+  "success": true,
+  "data": [{
+    "domain": "shop.burd",
+    "at": "2025-01-01T12:00:00.250Z",
+    "method": "GET",
+    "path": "/products?page=2",
+    "status": 200,
+    "duration_ms": 48.3,
+    "remote_ip": "127.0.0.1"
+  }]
+}`
       }
     ]
   },
@@ -185,7 +506,7 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'GET',
         path: '/databases',
-        description: 'List all databases across all DB instances',
+        description: 'List all databases across running MariaDB, PostgreSQL and MongoDB instances',
         response: `{
   "success": true,
   "data": [{
@@ -199,7 +520,7 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'POST',
         path: '/databases',
-        description: 'Create a new database',
+        description: 'Create a new database. Without instance_id, a running MariaDB or PostgreSQL instance is preferred over MongoDB',
         body: `{
   "name": "myapp_dev",
   "instance_id": "optional-specific-instance-uuid"
@@ -210,6 +531,219 @@ export const apiCategories: ApiCategory[] = [
         path: '/databases/:name',
         description: 'Drop a database',
         params: ':name - Database name'
+      },
+      {
+        method: 'GET',
+        path: '/databases/:name/users',
+        description: 'List users with access to a database (MariaDB/MySQL)',
+        params: ':name - Database name',
+        response: `{
+  "success": true,
+  "data": [{ "name": "myapp", "privileges": "all" }]
+}`
+      },
+      {
+        method: 'POST',
+        path: '/databases/:name/users',
+        description: 'Create a user if needed and grant it access; the password is generated and returned when omitted',
+        params: ':name - Database name',
+        body: `{
+  "username": "myapp",
+  "password": "optional",
+  "read_only": false
+}`,
+        response: `{
+  "success": true,
+  "data": {
+    "username": "myapp",
+    "database": "myapp",
+    "privileges": "all",
+    "password": "generated-password"
+  }
+}`
+      },
+      {
+        method: 'PUT',
+        path: '/databases/:name/users/:user',
+        description: 'Change the password and/or switch between full and read-only access',
+        params: ':name - Database name, :user - User name',
+        body: `{ "password": "optional", "read_only": true }`
+      },
+      {
+        method: 'DELETE',
+        path: '/databases/:name/users/:user',
+        description: 'Revoke the access of a user to the database',
+        params: ':name - Database name, :user - User name, ?drop_user=true - drop the user entirely'
+      }
+    ]
+  },
+  {
+    name: 'Object Storage',
+    description: 'MinIO bucket management',
+    endpoints: [
+      {
+        method: 'GET',
+        path: '/minio/:id/buckets',
+        description: 'List buckets',
+        params: ':id - MinIO instance UUID',
+        response: `{
+  "success": true,
+  "data": [{ "name": "media", "created": "2026-10-18T10:15:00.000Z" }]
+}`
+      },
+      {
+        method: 'POST',
+        path: '/minio/:id/buckets',
+        description: 'Create a bucket with an access preset (private, public-read, or public)',
+        params: ':id - MinIO instance UUID',
+        body: `{ "name": "media", "policy": "public-read" }`
+      },
+      {
+        method: 'DELETE',
+        path: '/minio/:id/buckets/:bucket',
+        description: 'Delete an empty bucket',
+        params: ':id - MinIO instance UUID, :bucket - Bucket name'
+      },
+      {
+        method: 'PUT',
+        path: '/minio/:id/buckets/:bucket/policy',
+        description: 'Apply an anonymous access preset to a bucket',
+        params: ':id - MinIO instance UUID, :bucket - Bucket name',
+        body: `{ "policy": "public" }`
+      },
+      {
+        method: 'POST',
+        path: '/minio/:id/keys',
+        description: 'Generate temporary access keys, optionally limited to one bucket (hours default to 24, at most 168)',
+        params: ':id - MinIO instance UUID',
+        body: `{ "bucket": "media", "hours": 24 }`,
+        response: `{
+  "success": true,
+  "data": {
+    "access_key": "Y4RJU1RNFGK48LGO9I2S",
+    "secret_key": "sYLRKS1Z7hSjluf6gEbb9066hnx315wHTiACPAjg",
+    "session_token": "eyJhbGciOiJIUzUxMiIs...",
+    "expires_at": "2026-10-19T10:15:00Z",
+    "bucket": "media"
+  }
+}`
+      }
+    ]
+  },
+  {
+    name: 'Queues',
+    description: 'Beanstalkd queue inspection',
+    endpoints: [
+      {
+        method: 'GET',
+        path: '/queues/:id/tubes',
+        description: 'List tubes with job counts',
+        params: ':id - Beanstalkd instance UUID',
+        response: `{
+  "success": true,
+  "data": [{
+    "name": "default",
+    "urgent": 0,
+    "ready": 3,
+    "reserved": 1,
+    "delayed": 0,
+    "buried": 2,
+    "total": 120,
+    "waiting": 1,
+    "paused": false
+  }]
+}`
+      },
+      {
+        method: 'GET',
+        path: '/queues/:id/tubes/:tube/peek',
+        description: 'Peek at the next job in a tube (?state=ready|delayed|buried, default ready)',
+        params: ':id - Beanstalkd instance UUID, :tube - Tube name',
+        response: `{
+  "success": true,
+  "data": {
+    "id": 42,
+    "tube": "default",
+    "state": "buried",
+    "priority": 1024,
+    "age": 360,
+    "ttr": 60,
+    "buries": 1,
+    "kicks": 0,
+    "body": "{...}"
+  }
+}`
+      },
+      {
+        method: 'POST',
+        path: '/queues/:id/tubes/:tube/kick',
+        description: 'Kick buried jobs (or delayed jobs if none are buried) back to ready',
+        params: ':id - Beanstalkd instance UUID, :tube - Tube name',
+        body: `{ "bound": 100 }`,
+        response: `{ "success": true, "data": { "kicked": 2 } }`
+      },
+      {
+        method: 'GET',
+        path: '/queues/:id/jobs/:job_id',
+        description: 'Get a job with its stats',
+        params: ':id - Beanstalkd instance UUID, :job_id - Job ID'
+      },
+      {
+        method: 'POST',
+        path: '/queues/:id/jobs/:job_id/bury',
+        description: 'Bury a ready or delayed job',
+        params: ':id - Beanstalkd instance UUID, :job_id - Job ID'
+      },
+      {
+        method: 'POST',
+        path: '/queues/:id/jobs/:job_id/kick',
+        description: 'Kick a buried or delayed job back to ready',
+        params: ':id - Beanstalkd instance UUID, :job_id - Job ID'
+      }
+    ]
+  },
+  {
+    name: 'Snapshots',
+    description: 'Snapshots of Meilisearch, MongoDB, Redis/Valkey, and MinIO instances',
+    endpoints: [
+      {
+        method: 'GET',
+        path: '/instances/:id/snapshots',
+        description: 'List snapshots of an instance, newest first',
+        params: ':id - Instance UUID',
+        response: `{
+  "success": true,
+  "data": [{
+    "id": "20261018-101500-120",
+    "instance_id": "uuid",
+    "instance_name": "cache",
+    "service_type": "redis",
+    "version": "8.4.0",
+    "kind": "redis_rdb",
+    "label": "seeded",
+    "size_bytes": 1363148,
+    "created_at": "2026-10-18T10:15:00.120Z"
+  }]
+}`
+      },
+      {
+        method: 'POST',
+        path: '/instances/:id/snapshots',
+        description: 'Create a snapshot (oldest snapshots beyond the retention are removed)',
+        params: ':id - Instance UUID',
+        body: `{ "label": "seeded" }`
+      },
+      {
+        method: 'POST',
+        path: '/instances/:id/snapshots/:snapshot_id/restore',
+        description: 'Restore a snapshot (MongoDB must be running, other services stopped)',
+        params: ':id - Instance UUID, :snapshot_id - Snapshot ID'
+      },
+      {
+        method: 'DELETE',
+        path: '/instances/:id/snapshots/:snapshot_id',
+        description: 'Delete a snapshot',
+        params: ':id - Instance UUID, :snapshot_id - Snapshot ID'
       }
     ]
   },
@@ -220,14 +754,15 @@ export const apiCategories: ApiCategory[] = [
       {
         method: 'GET',
         path: '/services',
-        description: 'List all available service types',
+        description: 'List all available service types, including user-defined ones',
         response: `{
   "success": true,
   "data": [{
     "id": "redis",
     "name": "Redis",
     "default_port": 6379,
-    "max_instances": null
+    "max_instances": null,
+    "user_defined": false
   }]
 }`
       },
@@ -243,6 +778,54 @@ export const apiCategories: ApiCategory[] = [
     "installed": ["7.2.4", "7.0.15"]
   }
 }`
+      },
+      {
+        method: 'GET',
+        path: '/services/:type/suggest-port',
+        description: 'Suggest a free port for a new instance, skipping ports used by instances and other processes',
+        params: ':type - Service type (redis, mariadb, etc.), ?preferred=<port> - Returned if free',
+        response: `{
+  "success": true,
+  "data": {
+    "service_type": "redis",
+    "port": 6379
+  }
+}`
+      },
+      {
+        method: 'POST',
+        path: '/services/:type/install',
+        description: 'Download and install a service binary (the newest on its release channel unless a version is given). Responds when the install finishes; progress is streamed as "download" events on /events',
+        params: ':type - Service type (redis, meilisearch, etc.)',
+        body: `{ "version": "1.11.3" }`,
+        response: `{
+  "success": true,
+  "data": {
+    "service_type": "meilisearch",
+    "version": "1.11.3",
+    "path": "~/Library/Application Support/Burd/bin/meilisearch/1.11.3/meilisearch"
+  }
+}`
+      }
+    ]
+  },
+  {
+    name: 'MCP',
+    description: 'Model Context Protocol over HTTP (SSE transport)',
+    endpoints: [
+      {
+        method: 'GET',
+        path: '/mcp/sse',
+        description: 'Open an MCP session; the first "endpoint" event names the URL to post messages to',
+        response: `event: endpoint
+data: /mcp/messages?sessionId=5d86ac05-...`
+      },
+      {
+        method: 'POST',
+        path: '/mcp/messages?sessionId=:id',
+        description: 'Send a JSON-RPC message; the response arrives on the session stream',
+        body: `{ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }`,
+        response: '202 Accepted'
       }
     ]
   }